@@ -8,6 +8,7 @@ mod run_options;
 mod tool_source_config;
 
 pub use memory_config::MemoryConfig;
+pub(crate) use run_config::default_thread_id;
 pub use run_config::{Error, RunConfig};
 pub use run_options::RunOptions;
 pub use tool_source_config::ToolSourceConfig;
@@ -4,13 +4,13 @@
 //! langgraph's `ToolChoiceMode`, `OpenAIEmbedder`.
 
 use super::{MemoryConfig, ToolSourceConfig};
-use langgraph::ToolChoiceMode;
+use langgraph::{HttpClientConfig, PromptProfile, SkillSet, ToolChoiceMode};
 
 /// Error type used for config loading.
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 
 /// Returns a default thread ID when none is set (unique per call, for CLI default memory).
-fn default_thread_id() -> String {
+pub(crate) fn default_thread_id() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
     let nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -32,6 +32,8 @@ pub struct RunConfig {
     pub temperature: Option<f32>,
     /// Tool choice mode: auto (model chooses), none (no tools), required (must use tools).
     pub tool_choice: Option<ToolChoiceMode>,
+    /// Built-in prompt profile (system prompt + tool_choice default). None means the default profile.
+    pub prompt_profile: Option<PromptProfile>,
     /// Embeddings API key. If not set, uses OPENAI_API_KEY.
     pub embedding_api_key: Option<String>,
     /// Embeddings API base URL. If not set, uses OPENAI_API_BASE.
@@ -50,10 +52,18 @@ pub struct RunConfig {
     pub mcp_remote_cmd: String,
     /// Args for mcp-remote, e.g. `-y mcp-remote`. Default: `-y mcp-remote`.
     pub mcp_remote_args: String,
+    /// Skills adding prompt sections, examples and tools. Default: none.
+    pub skills: SkillSet,
+    /// TOML or JSON file of named MCP servers to connect (env `MCP_SERVERS_FILE`). Default: none.
+    pub mcp_servers_file: Option<String>,
     /// When true, run with streaming: show Thinking... / Calling tool / LLM tokens on stdout.
     pub stream: bool,
     /// When true, show debug logs (node enter/exit, graph execution). Requires --verbose.
     pub verbose: bool,
+    /// JSON file to add an execution profile of the run to (`--profile`). Default: none.
+    pub profile: Option<String>,
+    /// Outbound HTTP tuning for LLM calls (pool, keep-alive, HTTP/2, proxy). Default: reqwest defaults.
+    pub http_client: HttpClientConfig,
 }
 
 impl RunConfig {
@@ -69,6 +79,9 @@ impl RunConfig {
         if let Some(tc) = options.tool_choice {
             self.tool_choice = Some(tc);
         }
+        if let Some(p) = options.prompt_profile {
+            self.prompt_profile = Some(p);
+        }
         if options.thread_id.is_some() || options.user_id.is_some() {
             self.memory = match (&options.thread_id, &options.user_id) {
                 (Some(tid), Some(uid)) => MemoryConfig::Both {
@@ -98,10 +111,19 @@ impl RunConfig {
         if let Some(url) = &options.mcp_exa_url {
             self.mcp_exa_url = url.clone();
         }
+        if !options.skills.is_empty() {
+            self.skills = options.skills.clone();
+        }
+        if options.mcp_servers_file.is_some() {
+            self.mcp_servers_file = options.mcp_servers_file.clone();
+        }
         if options.stream {
             self.stream = true;
         }
         self.verbose = options.verbose;
+        if options.profile.is_some() {
+            self.profile = options.profile.clone();
+        }
     }
 
     /// Enable short-term memory (checkpointer) for conversation history.
@@ -170,15 +192,45 @@ impl RunConfig {
             .unwrap_or("text-embedding-3-small")
     }
 
+    /// Tool choice to use: explicit `tool_choice`, else the prompt profile's default.
+    pub fn effective_tool_choice(&self) -> Option<ToolChoiceMode> {
+        self.tool_choice
+            .or_else(|| self.prompt_profile.and_then(|p| p.tool_choice()))
+    }
+
     /// Builds a langgraph [`ReactBuildConfig`](langgraph::ReactBuildConfig) for use with
     /// [`build_react_run_context`](langgraph::build_react_run_context). CLI-specific RunConfig
     /// is converted to the minimal config required by the builder.
     pub fn to_react_build_config(&self) -> langgraph::ReactBuildConfig {
         langgraph::ReactBuildConfig {
             db_path: self.db_path.clone(),
+            database_url: None,
+            redis_url: None,
+            redis_ttl_secs: None,
+            tenant_db_dir: None,
+            checkpoint_max_per_thread: None,
+            checkpoint_max_age_secs: None,
+            checkpoint_sync: None,
             thread_id: self.thread_id().map(ToString::to_string),
             user_id: self.user_id().map(ToString::to_string),
             system_prompt: None,
+            prompt_profile: self.prompt_profile,
+            max_turns: None,
+            interrupt_before: Vec::new(),
+            node_timeout_secs: None,
+            max_run_tokens: None,
+            max_run_cost_usd: None,
+            max_run_tool_calls: None,
+            daily_token_cap: None,
+            daily_user_token_cap: None,
+            history_tool_result_turns: None,
+            history_summary_turns: None,
+            history_pin_first_user: false,
+            memory_extraction: false,
+            memory_dedup_threshold: None,
+            skills: self.skills.clone(),
+            mcp_servers: Vec::new(),
+            mcp_servers_file: self.mcp_servers_file.clone(),
             exa_api_key: self.tool_source.exa_api_key.clone(),
             mcp_exa_url: self.mcp_exa_url.clone(),
             mcp_remote_cmd: self.mcp_remote_cmd.clone(),
@@ -186,14 +238,22 @@ impl RunConfig {
             mcp_verbose: self.verbose,
             openai_api_key: Some(self.api_key.clone()),
             openai_base_url: Some(self.api_base.clone()),
+            openai_fallback_base_urls: Vec::new(),
             model: Some(self.model.clone()),
+            azure_openai_endpoint: None,
+            azure_openai_api_version: None,
+            azure_openai_deployment: None,
+            azure_openai_api_key: None,
+            azure_openai_fallback_endpoints: Vec::new(),
             embedding_api_key: self.embedding_api_key.clone(),
             embedding_base_url: self.embedding_api_base.clone(),
             embedding_model: self.embedding_model.clone(),
+            embedding_batch_size: None,
+            embedding_cache: false,
+            http_client: self.http_client.clone(),
         }
     }
 
-    #[cfg(feature = "embedding")]
     /// Create an OpenAIEmbedder from this configuration.
     ///
     /// # Examples
@@ -207,7 +267,7 @@ impl RunConfig {
     /// ```
     pub fn create_embedder(&self) -> langgraph::OpenAIEmbedder {
         use async_openai::config::OpenAIConfig;
-        let openai_config = async_openai::config::OpenAIConfig::new()
+        let openai_config = OpenAIConfig::new()
             .with_api_key(self.embedding_api_key())
             .with_api_base(self.embedding_api_base());
         langgraph::OpenAIEmbedder::with_config(openai_config, self.embedding_model())
@@ -218,10 +278,11 @@ impl RunConfig {
     /// Fill config from env vars (and .env). Requires `dotenv::dotenv().ok()` or load inside `run()`.
     ///
     /// `OPENAI_API_KEY` required; `OPENAI_API_BASE`, `OPENAI_MODEL` have defaults.
-    /// `OPENAI_TEMPERATURE`, `OPENAI_TOOL_CHOICE` (auto|none|required) optional.
+    /// `OPENAI_TEMPERATURE`, `OPENAI_TOOL_CHOICE` (auto|none|required), `REACT_PROMPT_PROFILE` optional.
     /// For embeddings: `EMBEDDING_API_KEY`, `EMBEDDING_API_BASE`, `EMBEDDING_MODEL` optional.
     /// For memory: `THREAD_ID`, `USER_ID`, `DB_PATH` optional. When both `THREAD_ID` and `USER_ID` are unset, uses a generated thread_id and user_id "1" (memory mode both).
     /// For Exa MCP: `EXA_API_KEY`, `MCP_EXA_URL`, `MCP_REMOTE_CMD`, `MCP_REMOTE_ARGS` optional.
+    /// For the LLM HTTP client: `LLM_HTTP_*` optional (see [`HttpClientConfig::from_env`]).
     pub fn from_env() -> Result<Self, Error> {
         let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| {
             std::io::Error::new(
@@ -238,6 +299,9 @@ impl RunConfig {
         let tool_choice = std::env::var("OPENAI_TOOL_CHOICE")
             .ok()
             .and_then(|s| s.parse().ok());
+        let prompt_profile = std::env::var("REACT_PROMPT_PROFILE")
+            .ok()
+            .and_then(|s| s.parse().ok());
         let embedding_api_key = std::env::var("EMBEDDING_API_KEY").ok();
         let embedding_api_base = std::env::var("EMBEDDING_API_BASE").ok();
         let embedding_model = std::env::var("EMBEDDING_MODEL")
@@ -274,6 +338,7 @@ impl RunConfig {
             model,
             temperature,
             tool_choice,
+            prompt_profile,
             embedding_api_key,
             embedding_api_base,
             embedding_model,
@@ -283,8 +348,12 @@ impl RunConfig {
             mcp_exa_url,
             mcp_remote_cmd,
             mcp_remote_args,
+            skills: SkillSet::new(),
+            mcp_servers_file: std::env::var("MCP_SERVERS_FILE").ok(),
             stream: true,
             verbose: false,
+            profile: None,
+            http_client: HttpClientConfig::from_env(),
         })
     }
 }
@@ -4,9 +4,10 @@
 //! [`run_with_options`](crate::run_with_options). Callers (e.g. binary or tests) build
 //! a `RunOptions` and pass it to get env-based config with overrides applied.
 
-use langgraph::ToolChoiceMode;
+use langgraph::{PromptProfile, SkillSet, ToolChoiceMode};
 
-/// Optional overrides for a run: temperature, tool choice, memory, DB path, Exa MCP.
+/// Optional overrides for a run: temperature, tool choice, memory, DB path, Exa MCP, skills,
+/// MCP servers file.
 ///
 /// Used with [`run_with_options`](crate::run_with_options) or
 /// [`RunConfig::apply_options`](super::RunConfig::apply_options). All fields are optional;
@@ -17,6 +18,8 @@ pub struct RunOptions {
     pub temperature: Option<f32>,
     /// Override tool choice mode (auto, none, required).
     pub tool_choice: Option<ToolChoiceMode>,
+    /// Override prompt profile (e.g. research-agent); supplies system prompt and tool_choice default.
+    pub prompt_profile: Option<PromptProfile>,
     /// Thread ID for short-term memory (checkpointer). When set with `user_id`, enables both.
     pub thread_id: Option<String>,
     /// User ID for long-term memory (store). When set with `thread_id`, enables both.
@@ -29,10 +32,17 @@ pub struct RunOptions {
     pub exa_api_key: Option<String>,
     /// Exa MCP server URL override.
     pub mcp_exa_url: Option<String>,
+    /// Skills to load (e.g. from `--skill` manifests); replace the config's when not empty.
+    pub skills: SkillSet,
+    /// TOML or JSON file of named MCP servers (overrides env `MCP_SERVERS_FILE` when set).
+    pub mcp_servers_file: Option<String>,
     /// Enable streaming: show Thinking... / Calling tool / LLM tokens as they arrive (stdout). Default: true.
     pub stream: bool,
     /// When true, show debug logs (node enter/exit, graph execution). Default: false.
     pub verbose: bool,
+    /// JSON file to add an execution profile of the run to (wall/CPU time and allocations per
+    /// node and checkpoint operation).
+    pub profile: Option<String>,
 }
 
 impl Default for RunOptions {
@@ -40,14 +50,18 @@ impl Default for RunOptions {
         Self {
             temperature: None,
             tool_choice: None,
+            prompt_profile: None,
             thread_id: None,
             user_id: None,
             db_path: None,
             mcp_exa: false,
             exa_api_key: None,
             mcp_exa_url: None,
+            skills: SkillSet::new(),
+            mcp_servers_file: None,
             stream: true,
             verbose: false,
+            profile: None,
         }
     }
 }
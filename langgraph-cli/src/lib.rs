@@ -9,6 +9,16 @@
 //!   run configuration from env or programmatic overrides.
 //! - **Run**: [`run`], [`run_with_options`], [`run_with_config`] — execute the ReAct graph and
 //!   get back state; [`build_config_summary`] for human-readable config summary.
+//! - **Maintenance**: [`reembed`] — re-embed a long-term memory namespace after changing
+//!   `EMBEDDING_MODEL`; [`export_studio`] — export a thread as LangGraph Studio JSON;
+//!   [`analyze`] — report unreachable nodes, dead branches and unused tools of the agent;
+//!   [`graph_diagram`] — the agent's graph as a Mermaid, DOT or plain-text diagram;
+//!   [`backup`] — consistent copy of the SQLite checkpoint database, also while it is in use;
+//!   [`ingest`] — split and embed files into long-term memory for retrieval (RAG);
+//!   [`profile_report`] — summary table and flame graph export of recorded execution profiles;
+//!   [`tool_docs`] — markdown / HTML reference of the tools the agent exposes to the model.
+//! - **Chat**: [`ChatSession`] — interactive turns on one thread; `reload` re-reads `.env` and
+//!   rebuilds the agent between turns.
 //!
 //! ## Quick start
 //!
@@ -48,7 +58,10 @@ mod run;
 
 pub use config::{Error, MemoryConfig, RunConfig, RunOptions, ToolSourceConfig};
 pub use langgraph::{Message, ReActState};
-pub use run::{build_config_summary, run, run_with_config, run_with_options};
+pub use run::{
+    analyze, backup, build_config_summary, export_studio, graph_diagram, ingest, parse_namespace,
+    profile_report, reembed, run, run_with_config, run_with_options, tool_docs, ChatSession,
+};
 
 #[cfg(test)]
 mod tests;
@@ -1,9 +1,19 @@
 //! ReAct Agent binary: parses CLI message, invokes the library and prints the result.
 
-use clap::Parser;
-use langgraph_cli::{run_with_options, Message, RunOptions};
+use clap::{Parser, Subcommand};
+use langgraph::ingest::{Ingestor, TextSplitter};
+use langgraph::{CountingAllocator, GraphFormat, SkillSet, ToolDocsFormat};
+use langgraph_cli::{
+    analyze, backup, export_studio, graph_diagram, ingest, parse_namespace, profile_report,
+    reembed, run_with_options, tool_docs, ChatSession, Message, RunConfig, RunOptions,
+};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tracing_subscriber::EnvFilter;
 
+// Counts allocations for `--profile`; otherwise only two relaxed atomic adds per allocation.
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
 #[derive(Parser, Debug)]
 #[command(name = "langgraph")]
 #[command(about = "ReAct agent — input a message, run think → act → observe chain")]
@@ -24,6 +34,11 @@ struct Args {
     #[arg(long, value_name = "MODE")]
     tool_choice: Option<String>,
 
+    /// Prompt profile: default, strict-tools, conversational, coding-agent, research-agent
+    /// (short names such as `research` also work)
+    #[arg(long, value_name = "PROFILE")]
+    prompt_profile: Option<String>,
+
     /// Thread ID for short-term memory (checkpointer)
     #[arg(long, value_name = "ID")]
     thread_id: Option<String>,
@@ -48,6 +63,15 @@ struct Args {
     #[arg(long, value_name = "URL")]
     mcp_exa_url: Option<String>,
 
+    /// Load a skill manifest (TOML: prompt fragment, tools, few-shot examples); repeat to
+    /// combine skills
+    #[arg(long = "skill", value_name = "PATH")]
+    skills: Vec<String>,
+
+    /// TOML or JSON file of named MCP servers whose tools to add
+    #[arg(long, value_name = "PATH")]
+    mcp_servers_file: Option<String>,
+
     /// Stream output: show Thinking... / Calling tool / LLM tokens as they arrive (default: on)
     #[arg(long, default_value_t = true)]
     stream: bool,
@@ -59,6 +83,101 @@ struct Args {
     /// Show debug logs (node enter/exit, graph execution)
     #[arg(short, long)]
     verbose: bool,
+
+    /// Re-embed long-term memory NAMESPACE (segments separated by `/`, e.g. `1/memories`) in the
+    /// vector store at --db-path with EMBEDDING_MODEL, then exit. Run after changing the model.
+    #[arg(long, value_name = "NAMESPACE")]
+    reembed: Option<String>,
+
+    /// Print THREAD_ID from the checkpoints at --db-path as LangGraph Studio / langgraph-sdk
+    /// JSON (thread, state history, runs), then exit.
+    #[arg(long, value_name = "THREAD_ID")]
+    export_studio: Option<String>,
+
+    /// Report unreachable nodes, dead conditional branches and tools no prompt mentions for
+    /// the configured agent, then exit (status 2 when anything is found).
+    #[arg(long)]
+    analyze: bool,
+
+    /// Copy the SQLite database at --db-path to the new file PATH as one consistent snapshot
+    /// (SQLite online backup; safe while a server writes it), then exit.
+    #[arg(long, value_name = "PATH")]
+    backup: Option<String>,
+
+    /// Profile the run (wall time, CPU time and allocations per node and checkpoint operation),
+    /// add it to the JSON file PATH and print the summary table to stderr.
+    #[arg(long, value_name = "PATH")]
+    profile: Option<String>,
+
+    /// Print the summary table of the profile at PATH (written by --profile), then exit.
+    #[arg(long, value_name = "PATH")]
+    profile_report: Option<String>,
+
+    /// With --profile-report: also write the profile to OUT, as folded stacks for flame graphs
+    /// when OUT ends in .folded or .txt, as pprof protobuf otherwise.
+    #[arg(long, value_name = "OUT", requires = "profile_report")]
+    profile_export: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Chat interactively on one thread. Type /reload to re-read .env and the --skill manifests
+    /// and rebuild the agent (model, temperature, tools) between turns, /exit to quit.
+    Chat,
+    /// Inspect the tools the configured agent exposes to the model (built-in, MCP, skills)
+    Tools {
+        #[command(subcommand)]
+        command: ToolsCommand,
+    },
+    /// Print the compiled think → act → observe graph: nodes with their settings, edges and
+    /// conditional branches. Put flags such as --mcp-exa before `graph` to apply them.
+    Graph {
+        /// Output format: mermaid, dot or ascii
+        #[arg(long, value_name = "FORMAT", default_value = "mermaid")]
+        format: GraphFormat,
+    },
+    /// Split text files (or every text file under directories) into chunks and embed them into
+    /// long-term memory (--db-path, default memory.db) for retrieval. Ingesting a file again
+    /// replaces its chunks.
+    Ingest {
+        /// Files or directories to load
+        #[arg(required = true, value_name = "PATH")]
+        paths: Vec<std::path::PathBuf>,
+
+        /// Namespace to store the chunks in, as /-separated segments
+        #[arg(long, value_name = "NS", default_value = "documents")]
+        namespace: String,
+
+        /// Maximum characters per chunk
+        #[arg(long, value_name = "N", default_value_t = langgraph::ingest::DEFAULT_CHUNK_SIZE)]
+        chunk_size: usize,
+
+        /// Characters shared by consecutive chunks
+        #[arg(long, value_name = "N", default_value_t = langgraph::ingest::DEFAULT_CHUNK_OVERLAP)]
+        chunk_overlap: usize,
+
+        /// Embed at most N chunks per minute (the embedding API's rate limit)
+        #[arg(long, value_name = "N")]
+        max_chunks_per_minute: Option<u32>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ToolsCommand {
+    /// Print a reference of every tool: name, description, arguments, example call and input
+    /// schema. Put flags such as --mcp-exa before `tools` to include their tools.
+    Docs {
+        /// Output format: markdown or html
+        #[arg(long, value_name = "FORMAT", default_value = "markdown")]
+        format: ToolDocsFormat,
+
+        /// Write the reference to PATH instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<String>,
+    },
 }
 
 fn get_message(args: &Args) -> String {
@@ -76,21 +195,88 @@ fn args_to_run_options(args: &Args) -> Result<RunOptions, String> {
         None => None,
         Some(tc) => Some(tc.parse().map_err(|e: String| e)?),
     };
+    let prompt_profile = match &args.prompt_profile {
+        None => None,
+        Some(p) => Some(p.parse().map_err(|e: String| e)?),
+    };
+    let skills = SkillSet::load(&args.skills).map_err(|e| e.to_string())?;
     Ok(RunOptions {
         temperature: args.temperature,
         tool_choice,
+        prompt_profile,
         thread_id: args.thread_id.clone(),
         user_id: args.user_id.clone(),
         db_path: args.db_path.clone(),
         mcp_exa: args.mcp_exa,
         exa_api_key: args.exa_api_key.clone(),
         mcp_exa_url: args.mcp_exa_url.clone(),
+        skills,
+        mcp_servers_file: args.mcp_servers_file.clone(),
         stream: args.stream && !args.no_stream,
         verbose: args.verbose,
+        profile: args.profile.clone(),
         ..Default::default()
     })
 }
 
+/// Interactive chat on one thread: reads messages from stdin until EOF or `/exit`; `/reload`
+/// rebuilds the agent from `.env` and `args` while keeping the thread.
+async fn chat(args: &Args, options: &RunOptions) -> Result<(), langgraph_cli::Error> {
+    let mut config = RunConfig::from_env()?;
+    config.apply_options(options);
+    let mut session = ChatSession::new(config).await?;
+    println!(
+        "Chat on thread {} (/reload re-reads .env, /exit quits)",
+        session.config().thread_id().unwrap_or("-")
+    );
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        match line.trim() {
+            "" => {}
+            "/exit" | "/quit" => break,
+            "/reload" => {
+                let reloaded = match args_to_run_options(args) {
+                    Ok(options) => session.reload(&options).await,
+                    Err(e) => Err(e.into()),
+                };
+                match reloaded {
+                    Ok(()) => {
+                        let config = session.config();
+                        let temperature = config
+                            .temperature
+                            .map_or_else(|| "default".to_string(), |t| t.to_string());
+                        println!(
+                            "[Config reloaded: model {}, temperature {}]",
+                            config.model, temperature
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("error: reload failed, keeping the previous config: {}", e)
+                    }
+                }
+            }
+            message => match session.send(message).await {
+                Ok(state) => {
+                    if session.config().stream {
+                        println!();
+                    }
+                    match state.final_answer() {
+                        Some(answer) => println!("[Assistant] {}", answer),
+                        None => eprintln!("[No final answer; last turn may have been tool-only]"),
+                    }
+                }
+                Err(e) => eprintln!("error: {}", e),
+            },
+        }
+    }
+    Ok(())
+}
+
 /// Initializes tracing so that langgraph's debug/info/error logs are shown only when verbose.
 fn init_tracing(verbose: bool) {
     let level = if verbose { "debug" } else { "off" };
@@ -107,6 +293,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     init_tracing(args.verbose);
     let input = get_message(&args);
 
+    if let Some(ref path) = args.profile_report {
+        match profile_report(path, args.profile_export.as_deref()) {
+            Ok(table) => print!("{}", table),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     let options = match args_to_run_options(&args) {
         Ok(o) => o,
         Err(e) => {
@@ -115,6 +312,169 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    if let Some(ref namespace) = args.reembed {
+        let namespace = parse_namespace(namespace);
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                reembed(&config, &namespace).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(n) => println!("Re-embedded {} values in namespace {:?}", n, namespace),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(ref thread_id) = args.export_studio {
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                export_studio(&config, thread_id).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(ref dest) = args.backup {
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                backup(&config, dest).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(bytes) => println!("Backed up to {} ({} bytes)", dest, bytes),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Chat) = &args.command {
+        if let Err(e) = chat(&args, &options).await {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Tools {
+        command: ToolsCommand::Docs { format, output },
+    }) = &args.command
+    {
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                tool_docs(&config, *format).await
+            }
+            Err(e) => Err(e),
+        };
+        let result = result.and_then(|docs| match output {
+            Some(path) => std::fs::write(path, docs).map_err(Into::into),
+            None => {
+                print!("{}", docs);
+                Ok(())
+            }
+        });
+        if let Err(e) = result {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Graph { format }) = &args.command {
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                graph_diagram(&config, *format).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(diagram) => print!("{}", diagram),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Ingest {
+        paths,
+        namespace,
+        chunk_size,
+        chunk_overlap,
+        max_chunks_per_minute,
+    }) = &args.command
+    {
+        let namespace = parse_namespace(namespace);
+        let mut ingestor =
+            Ingestor::new().with_splitter(TextSplitter::characters(*chunk_size, *chunk_overlap));
+        if let Some(per_minute) = max_chunks_per_minute {
+            ingestor = ingestor.with_max_chunks_per_minute(*per_minute);
+        }
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                ingest(&config, paths, &namespace, &ingestor).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(report) => println!(
+                "Ingested {} chunks from {} documents into {:?} ({} stale chunks removed)",
+                report.chunks, report.documents, namespace, report.removed
+            ),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.analyze {
+        let result = match RunConfig::from_env() {
+            Ok(mut config) => {
+                config.apply_options(&options);
+                analyze(&config).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(analysis) => {
+                print!("{}", analysis);
+                if !analysis.is_clean() {
+                    std::process::exit(2);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     println!("User: {}", input);
     println!("---");
 
@@ -127,17 +487,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     if options.stream {
-        // Ensure the final reply is visible: use last non-empty Assistant (last one can be empty when the turn only had tool_calls).
+        // Ensure the final reply is visible after the streamed tokens.
         println!();
-        match state
-            .messages
-            .iter()
-            .rev()
-            .find(|m| matches!(m, Message::Assistant(c) if !c.is_empty()))
-        {
-            Some(Message::Assistant(content)) => println!("[Assistant] {}", content),
-            _ => eprintln!(
-                "[No assistant text in state ({} messages); last turn may have been tool-only]",
+        match state.final_answer() {
+            Some(answer) => println!("[Assistant] {}", answer),
+            None => eprintln!(
+                "[No final answer in state ({} messages); last turn may have been tool-only]",
                 state.messages.len()
             ),
         }
@@ -146,7 +501,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             match m {
                 Message::System(x) => println!("[System] {}", x),
                 Message::User(x) => println!("[User] {}", x),
-                Message::Assistant(x) => println!("[Assistant] {}", x),
+                Message::Assistant { content: x, .. } => println!("[Assistant] {}", x),
+                Message::Tool { name, content: x, .. } => {
+                    println!("[Tool {}] {}", name.as_deref().unwrap_or("?"), x)
+                }
             }
         }
     }
@@ -0,0 +1,39 @@
+//! Static analysis of the configured agent: its graph and the tools bound to it.
+//!
+//! **Interaction**: Backs the binary's `--analyze` flag. Builds the run context as
+//! [`run_with_config`](crate::run_with_config) does (tool source, persistence), compiles the
+//! ReAct graph with a placeholder LLM and calls langgraph's `analyze_graph`. Nothing is sent to
+//! the model.
+
+use langgraph::{analyze_graph, GraphAnalysis, MockLlm, PromptProfile, ReactRunner};
+
+use crate::config::{Error, RunConfig};
+
+/// Analyzes the agent `config` describes: unreachable nodes and dead conditional branches of
+/// its graph, and bound tools that neither a built-in prompt profile nor the configured system
+/// prompt mentions.
+pub async fn analyze(config: &RunConfig) -> Result<GraphAnalysis, Error> {
+    let build_config = config.to_react_build_config();
+    let ctx = langgraph::build_react_run_context(&build_config)
+        .await
+        .map_err(|e| Box::new(e) as Error)?;
+    let tools = ctx.tool_source.list_tools().await?;
+
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("")),
+        ctx.tool_source,
+        ctx.checkpointer,
+        ctx.store,
+        ctx.runnable_config,
+        None,
+        false,
+    )?;
+
+    let system_prompt = build_config.effective_system_prompt();
+    let mut prompts: Vec<&str> = PromptProfile::ALL
+        .iter()
+        .map(|p| p.system_prompt())
+        .collect();
+    prompts.push(&system_prompt);
+    Ok(analyze_graph(runner.graph()).with_tools(&tools, &prompts))
+}
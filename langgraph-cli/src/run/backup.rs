@@ -0,0 +1,17 @@
+//! Consistent backup of the SQLite checkpoint database.
+//!
+//! **Interaction**: Backs the binary's `--backup` flag. Calls langgraph's `backup_sqlite` (the
+//! SQLite online backup API behind `SqliteSaver::backup`) on
+//! [`RunConfig::db_path`](crate::RunConfig), so it is safe while a server writes the file.
+
+use langgraph::memory::backup_sqlite;
+
+use crate::config::{Error, RunConfig};
+
+/// Copies the database at `config.db_path` (default `memory.db`) to the new file `dest` as one
+/// consistent snapshot; returns the backup's size in bytes. Fails when the database does not
+/// exist or `dest` does.
+pub async fn backup(config: &RunConfig, dest: &str) -> Result<u64, Error> {
+    let db_path = config.db_path.as_deref().unwrap_or("memory.db");
+    Ok(backup_sqlite(db_path, dest).await?)
+}
@@ -0,0 +1,91 @@
+//! Interactive chat: many turns on one thread, with the config reloadable between turns.
+//!
+//! [`ChatSession`] builds the runner once and sends every message on the same thread, so the
+//! checkpointer carries the conversation. [`ChatSession::reload`] reads `.env` again (its values
+//! replace those loaded before; variables removed from the file keep their old value), applies
+//! the options and rebuilds the runner — model, temperature, tool choice, prompt profile,
+//! skills, Exa and MCP tools — keeping the thread, user and database, so the conversation goes
+//! on under the new config.
+//!
+//! **Interaction**: Backs the binary's `langgraph chat` and its `/reload` command; runs turns
+//! as [`run_with_config`](crate::run_with_config) does, without profiling.
+
+use std::path::{Path, PathBuf};
+
+use langgraph::{ReActState, ReactRunner};
+
+use super::run_with_config::{build_runner, run_turn};
+use crate::config::{default_thread_id, Error, MemoryConfig, RunConfig, RunOptions};
+
+/// A conversation on one thread whose config can be reloaded between turns.
+pub struct ChatSession {
+    config: RunConfig,
+    runner: ReactRunner,
+}
+
+impl ChatSession {
+    /// Session on `config`'s thread; a new thread when `config` has no short-term memory.
+    pub async fn new(mut config: RunConfig) -> Result<Self, Error> {
+        config.memory = match config.memory {
+            MemoryConfig::NoMemory => MemoryConfig::ShortTerm {
+                thread_id: default_thread_id(),
+            },
+            MemoryConfig::LongTerm { user_id } => MemoryConfig::Both {
+                thread_id: default_thread_id(),
+                user_id,
+            },
+            memory => memory,
+        };
+        let runner = build_runner(&config).await?;
+        Ok(Self { config, runner })
+    }
+
+    /// The config of the current runner.
+    pub fn config(&self) -> &RunConfig {
+        &self.config
+    }
+
+    /// Sends `message` as the next turn of the thread; returns the thread's state after it.
+    pub async fn send(&self, message: &str) -> Result<ReActState, Error> {
+        run_turn(&self.runner, &self.config, message).await
+    }
+
+    /// Reads `.env` and the environment again, applies `options` and rebuilds the runner on the
+    /// same thread. On error the session keeps its previous config.
+    pub async fn reload(&mut self, options: &RunOptions) -> Result<(), Error> {
+        reload_dotenv()?;
+        let mut config = RunConfig::from_env()?;
+        config.apply_options(options);
+        config.memory = self.config.memory.clone();
+        config.db_path = self.config.db_path.clone();
+        self.runner = build_runner(&config).await?;
+        self.config = config;
+        Ok(())
+    }
+}
+
+/// Sets the variables of the nearest `.env` (current directory or a parent), replacing the
+/// values already in the environment; returns the file, None when there is none.
+fn reload_dotenv() -> Result<Option<PathBuf>, dotenv::Error> {
+    let path = std::env::current_dir().ok().and_then(|cwd| {
+        cwd.ancestors()
+            .map(|dir| dir.join(".env"))
+            .find(|path| path.is_file())
+    });
+    match path {
+        Some(path) => {
+            set_env_from_file(&path)?;
+            Ok(Some(path))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Sets every variable of the env file at `path`, replacing existing values.
+fn set_env_from_file(path: &Path) -> Result<(), dotenv::Error> {
+    for item in dotenv::from_path_iter(path)? {
+        let (key, value) = item?;
+        std::env::set_var(key, value);
+    }
+    Ok(())
+}
@@ -106,6 +106,12 @@ fn tool_summary_fields(config: &RunConfig) -> (Vec<String>, Option<String>) {
     if has_exa {
         sources.push("exa".to_string());
     }
+    if config.mcp_servers_file.is_some() {
+        sources.push("mcp_servers".to_string());
+    }
+    for label in config.skills.labels() {
+        sources.push(format!("skill:{}", label));
+    }
     if sources.is_empty() {
         sources.push("".to_string()); // always show tools= for stable format
     }
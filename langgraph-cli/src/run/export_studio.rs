@@ -0,0 +1,29 @@
+//! Exports a persisted thread as LangGraph Studio / langgraph-sdk JSON.
+//!
+//! **Interaction**: Backs the binary's `--export-studio` flag. Opens langgraph's `SqliteSaver`
+//! at [`RunConfig::db_path`](crate::RunConfig) (the checkpointer runs with `--thread-id` write
+//! to) and calls `StudioExporter::export`.
+
+use std::sync::Arc;
+
+use langgraph::memory::{JsonSerializer, RunnableConfig, SqliteSaver};
+use langgraph::studio::StudioExporter;
+use langgraph::ReActState;
+
+use crate::config::{Error, RunConfig};
+
+/// Exports thread `thread_id` from the SQLite checkpointer at `config.db_path` (default
+/// `memory.db`) as pretty-printed JSON: the thread, its state history (newest first) and its
+/// runs, in the shape of langgraph-sdk's `Thread`, `ThreadState` and `Run`.
+pub async fn export_studio(config: &RunConfig, thread_id: &str) -> Result<String, Error> {
+    let db_path = config.db_path.as_deref().unwrap_or("memory.db");
+    let saver = SqliteSaver::<ReActState>::new(db_path, Arc::new(JsonSerializer))?;
+    let runnable_config = RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        ..Default::default()
+    };
+    let export = StudioExporter::new()
+        .export(&saver, &runnable_config)
+        .await?;
+    Ok(serde_json::to_string_pretty(&export)?)
+}
@@ -0,0 +1,28 @@
+//! Topology of the configured agent's graph as Mermaid, DOT or plain text.
+//!
+//! **Interaction**: Backs the binary's `langgraph graph --format mermaid|dot|ascii`. Compiles
+//! the ReAct graph as [`analyze`](crate::analyze) does (placeholder LLM, the configured tool
+//! source and persistence) and renders it with langgraph's `render_graph`. Nothing is sent to
+//! the model.
+
+use langgraph::{render_graph, GraphFormat, MockLlm, ReactRunner};
+
+use crate::config::{Error, RunConfig};
+
+/// The compiled think → act → observe graph of the agent `config` describes, in `format`.
+pub async fn graph_diagram(config: &RunConfig, format: GraphFormat) -> Result<String, Error> {
+    let build_config = config.to_react_build_config();
+    let ctx = langgraph::build_react_run_context(&build_config)
+        .await
+        .map_err(|e| Box::new(e) as Error)?;
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("")),
+        ctx.tool_source,
+        ctx.checkpointer,
+        ctx.store,
+        ctx.runnable_config,
+        None,
+        false,
+    )?;
+    Ok(render_graph(runner.graph(), format))
+}
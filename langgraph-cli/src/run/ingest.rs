@@ -0,0 +1,34 @@
+//! Loads files into long-term memory for retrieval (RAG).
+//!
+//! **Interaction**: Backs the binary's `ingest` subcommand. Reads files with langgraph's
+//! `load_documents`, splits and stores them with `Ingestor` into the `SqliteVecStore` at
+//! [`RunConfig::db_path`](crate::RunConfig) embedded with
+//! [`RunConfig::create_embedder`](crate::RunConfig::create_embedder), where a `Retriever` over
+//! the same namespace finds them.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use langgraph::ingest::{load_documents, IngestReport, Ingestor};
+use langgraph::memory::{Namespace, SqliteVecStore};
+
+use crate::config::{Error, RunConfig};
+
+/// Splits the text files at `paths` (files or directories, recursively) with `ingestor` and
+/// stores the chunks in `namespace` of the SQLite vector store at `config.db_path` (default
+/// `memory.db`), embedded with the configured embedding model (`EMBEDDING_MODEL`). Ingesting a
+/// file again replaces its chunks.
+pub async fn ingest(
+    config: &RunConfig,
+    paths: &[impl AsRef<Path>],
+    namespace: &Namespace,
+    ingestor: &Ingestor,
+) -> Result<IngestReport, Error> {
+    let mut documents = Vec::new();
+    for path in paths {
+        documents.extend(load_documents(path)?);
+    }
+    let db_path = config.db_path.as_deref().unwrap_or("memory.db");
+    let store = SqliteVecStore::new(db_path, Arc::new(config.create_embedder()))?;
+    Ok(ingestor.ingest(&store, namespace, &documents).await?)
+}
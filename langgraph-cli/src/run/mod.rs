@@ -1,20 +1,45 @@
 //! Run entry points: run with default config, run_with_config, or run_with_options.
 //!
-//! Re-exports [`run`], [`run_with_config`], [`run_with_options`] and [`Error`].
+//! Re-exports [`run`], [`run_with_config`], [`run_with_options`] and [`Error`]; [`reembed`]
+//! migrates long-term memory to a new embedding model; [`export_studio`] exports a thread for
+//! LangGraph Studio; [`analyze`] reports unreachable graph parts and unused tools;
+//! [`graph_diagram`] renders the agent's graph as Mermaid, DOT or text; [`backup`]
+//! copies the checkpoint database while it is in use; [`ingest`] loads files into long-term
+//! memory for retrieval; [`profile_report`] prints and exports execution profiles;
+//! [`tool_docs`] renders a reference of the agent's tools; [`ChatSession`] chats on one thread
+//! and reloads the config between turns.
 
 pub use crate::config::Error;
 
+mod analyze;
+mod backup;
+mod chat;
 mod config_summary;
+mod export_studio;
+mod graph_diagram;
+mod ingest;
+mod profile;
+mod reembed;
 mod run_with_config;
+mod tool_docs;
 
 use langgraph::ReActState;
 
 use crate::config::{RunConfig, RunOptions};
 
+pub use analyze::analyze;
+pub use backup::backup;
+pub use chat::ChatSession;
+pub use export_studio::export_studio;
+pub use graph_diagram::graph_diagram;
+pub use ingest::ingest;
 /// Re-exported from `langgraph` for convenience. Works with [`RunConfig`](crate::RunConfig)
 /// which implements [`RunConfigSummarySource`](langgraph::RunConfigSummarySource).
 pub use langgraph::build_config_summary;
+pub use profile::profile_report;
+pub use reembed::{parse_namespace, reembed};
 pub use run_with_config::run_with_config;
+pub use tool_docs::tool_docs;
 
 /// Run ReAct graph with default config (from .env), returns final state.
 ///
@@ -0,0 +1,43 @@
+//! Execution profiles of CLI runs, aggregated in one JSON file.
+//!
+//! **Interaction**: Backs the binary's `--profile` (via [`RunConfig::profile`](crate::RunConfig),
+//! read by [`run_with_config`](crate::run_with_config)) and `--profile-report` /
+//! `--profile-export`. The profile itself is langgraph's [`GraphProfile`].
+
+use std::path::Path;
+
+use langgraph::{GraphProfile, ProfileMetric, Profiler};
+
+use crate::config::Error;
+
+/// Adds the runs `profiler` recorded to the profile at `path` (created when missing) and
+/// returns the merged profile.
+pub(crate) fn save_profile(path: &str, profiler: &Profiler) -> Result<GraphProfile, Error> {
+    let mut profile = if Path::new(path).exists() {
+        GraphProfile::load(path)?
+    } else {
+        GraphProfile::new()
+    };
+    profile.merge(&profiler.snapshot());
+    profile.save(path)?;
+    Ok(profile)
+}
+
+/// Loads the profile at `path` and returns its summary table. With `export`, also writes the
+/// profile there: folded wall-time stacks for `.folded` and `.txt` files (flamegraph.pl,
+/// inferno, speedscope), pprof protobuf otherwise (`go tool pprof`).
+pub fn profile_report(path: &str, export: Option<&str>) -> Result<String, Error> {
+    let profile = GraphProfile::load(path)?;
+    if let Some(export) = export {
+        let folded = matches!(
+            Path::new(export).extension().and_then(|e| e.to_str()),
+            Some("folded" | "txt")
+        );
+        if folded {
+            std::fs::write(export, profile.to_folded(ProfileMetric::Wall))?;
+        } else {
+            std::fs::write(export, profile.to_pprof())?;
+        }
+    }
+    Ok(profile.to_string())
+}
@@ -0,0 +1,29 @@
+//! Re-embeds a long-term memory namespace after the embedding model changed.
+//!
+//! **Interaction**: Backs the binary's `--reembed` flag. Opens langgraph's `SqliteVecStore` at
+//! [`RunConfig::db_path`](crate::RunConfig) with [`RunConfig::create_embedder`](crate::RunConfig::create_embedder)
+//! and calls `Store::reembed`.
+
+use std::sync::Arc;
+
+use langgraph::memory::{Namespace, SqliteVecStore, Store};
+
+use crate::config::{Error, RunConfig};
+
+/// Parses a namespace given as `/`-separated segments, e.g. `1/memories`.
+pub fn parse_namespace(s: &str) -> Namespace {
+    s.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Re-embeds every value in `namespace` of the SQLite vector store at `config.db_path`
+/// (default `memory.db`) with the configured embedding model (`EMBEDDING_MODEL`) and pins the
+/// namespace to it. Returns how many values were re-embedded.
+pub async fn reembed(config: &RunConfig, namespace: &Namespace) -> Result<usize, Error> {
+    let db_path = config.db_path.as_deref().unwrap_or("memory.db");
+    let embedder = Arc::new(config.create_embedder());
+    let store = SqliteVecStore::new(db_path, embedder.clone())?;
+    Ok(store.reembed(namespace, embedder).await?)
+}
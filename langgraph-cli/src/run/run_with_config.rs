@@ -1,18 +1,20 @@
 //! Run ReAct graph with given config; does not read .env, returns final state.
 //!
 //! Uses [`langgraph::build_react_run_context`](langgraph::build_react_run_context) to build
-//! checkpointer, store, runnable_config and tool_source from config; then builds LLM and calls
-//! [`langgraph::run_react_graph`](langgraph::run_react_graph) or
-//! [`langgraph::run_react_graph_stream`](langgraph::run_react_graph_stream).
+//! checkpointer, store, runnable_config and tool_source from config; then builds LLM and a
+//! [`langgraph::ReactRunner`](langgraph::ReactRunner) with the prompt profile's system prompt,
+//! and calls `invoke` or `stream_with_callback`. With [`RunConfig::profile`], the run is
+//! profiled and added to that file, and the profile's summary table goes to stderr.
 //!
 //! See docs/rust-langgraph/tools-refactor/architecture/common-interface-mcp.md.
 
 use async_openai::config::OpenAIConfig;
-use langgraph::ChatOpenAI;
+use langgraph::{ChatOpenAI, Profiler, RefreshingToolSource, ToolCatalog};
 
 use crate::config::RunConfig;
 
 use langgraph::build_config_summary;
+use super::profile::save_profile;
 use super::Error;
 
 /// Run ReAct graph with given config; does not read .env, returns final state.
@@ -20,6 +22,25 @@ pub async fn run_with_config(
     config: &RunConfig,
     user_message: &str,
 ) -> Result<langgraph::ReActState, Error> {
+    let mut runner = build_runner(config).await?;
+    let profiler = config.profile.as_ref().map(|_| Profiler::new());
+    if let Some(profiler) = &profiler {
+        runner = runner.with_profiler(profiler);
+    }
+
+    let result = run_turn(&runner, config, user_message).await;
+
+    if let (Some(path), Some(profiler)) = (&config.profile, &profiler) {
+        profiler.finish_run();
+        let profile = save_profile(path, profiler)?;
+        eprintln!("\n[Profile] {}\n{}", path, profile);
+    }
+    result
+}
+
+/// Builds the runner for `config`: checkpointer, store and tools, the LLM and the prompt
+/// profile's system prompt. Prints the config summary to stderr when verbose.
+pub(crate) async fn build_runner(config: &RunConfig) -> Result<langgraph::ReactRunner, Error> {
     let build_config = config.to_react_build_config();
     let ctx = langgraph::build_react_run_context(&build_config)
         .await
@@ -33,30 +54,51 @@ pub async fn run_with_config(
         .with_api_base(&config.api_base)
         .with_api_key(config.api_key.clone());
 
-    let mut llm = ChatOpenAI::new_with_tool_source(
-        openai_config,
-        config.model.clone(),
-        ctx.tool_source.as_ref(),
-    )
-    .await?;
+    // Tools an MCP server changes during the run are re-listed into `catalog` for the LLM.
+    let catalog = ToolCatalog::new();
+    let tool_source: Box<dyn langgraph::ToolSource> =
+        Box::new(RefreshingToolSource::new(ctx.tool_source, catalog.clone()));
+    let mut llm =
+        ChatOpenAI::new_with_tool_source(openai_config, config.model.clone(), tool_source.as_ref())
+            .await?
+            .with_tool_catalog(catalog)
+            .with_http_client(
+                config
+                    .http_client
+                    .shared_client()
+                    .map_err(|e| Box::new(e) as Error)?,
+            );
     if let Some(t) = config.temperature {
         llm = llm.with_temperature(t);
     }
-    if let Some(tc) = config.tool_choice {
+    if let Some(tc) = config.effective_tool_choice() {
         llm = llm.with_tool_choice(tc);
     }
     let llm: Box<dyn langgraph::LlmClient> = Box::new(llm);
 
+    langgraph::ReactRunner::new(
+        llm,
+        tool_source,
+        ctx.checkpointer,
+        ctx.store,
+        ctx.runnable_config,
+        Some(build_config.effective_system_prompt()),
+        config.verbose,
+    )
+    .map_err(|e| Box::new(e) as Error)
+}
+
+/// Sends `user_message` to `runner`, streaming progress and tokens to stdout when
+/// `config.stream`; returns the final state.
+pub(crate) async fn run_turn(
+    runner: &langgraph::ReactRunner,
+    config: &RunConfig,
+    user_message: &str,
+) -> Result<langgraph::ReActState, Error> {
     if config.stream {
         let mut last_tool_calls: Vec<langgraph::ToolCall> = vec![];
-        langgraph::run_react_graph_stream(
+        runner.stream_with_callback(
             user_message,
-            llm,
-            ctx.tool_source,
-            ctx.checkpointer,
-            ctx.store,
-            ctx.runnable_config,
-            config.verbose,
             Some(|event: langgraph::StreamEvent<langgraph::ReActState>| {
                 use langgraph::StreamEvent;
                 use std::io::Write;
@@ -102,6 +144,18 @@ pub async fn run_with_config(
                             let _ = std::io::stderr().flush();
                         }
                     }
+                    StreamEvent::ResponseMetadata(metadata) => {
+                        if config.verbose {
+                            let _ = writeln!(
+                                std::io::stderr(),
+                                "[LLM response] finish_reason={} model={} system_fingerprint={}",
+                                metadata.finish_reason.as_deref().unwrap_or("-"),
+                                metadata.model.as_deref().unwrap_or("-"),
+                                metadata.system_fingerprint.as_deref().unwrap_or("-")
+                            );
+                            let _ = std::io::stderr().flush();
+                        }
+                    }
                     _ => {}
                 }
             }),
@@ -109,16 +163,9 @@ pub async fn run_with_config(
         .await
         .map_err(|e| Box::new(e) as Error)
     } else {
-        langgraph::run_react_graph(
-            user_message,
-            llm,
-            ctx.tool_source,
-            ctx.checkpointer,
-            ctx.store,
-            ctx.runnable_config,
-            config.verbose,
-        )
-        .await
-        .map_err(|e| Box::new(e) as Error)
+        runner
+            .invoke(user_message)
+            .await
+            .map_err(|e| Box::new(e) as Error)
     }
 }
@@ -0,0 +1,20 @@
+//! Tool reference of the configured agent.
+//!
+//! **Interaction**: Backs the binary's `langgraph tools docs`. Builds the tool source as
+//! [`run_with_config`](crate::run_with_config) does (built-in tools, Exa and other MCP servers,
+//! skills) and renders what it lists with langgraph's `render_tool_docs`. Nothing is sent to
+//! the model.
+
+use langgraph::{render_tool_docs, ToolDocsFormat};
+
+use crate::config::{Error, RunConfig};
+
+/// Reference of every tool the agent `config` describes exposes to the model, in `format`.
+pub async fn tool_docs(config: &RunConfig, format: ToolDocsFormat) -> Result<String, Error> {
+    let build_config = config.to_react_build_config();
+    let ctx = langgraph::build_react_run_context(&build_config)
+        .await
+        .map_err(|e| Box::new(e) as Error)?;
+    let tools = ctx.tool_source.list_tools().await?;
+    Ok(render_tool_docs(&tools, format))
+}
@@ -263,3 +263,74 @@ fn memory_config_default_is_no_memory() {
     let memory = MemoryConfig::default();
     assert!(matches!(memory, MemoryConfig::NoMemory));
 }
+
+/// **Scenario**: apply_options sets the prompt profile; its tool_choice is used unless overridden.
+///
+/// Given: a config from from_env and RunOptions with prompt_profile = conversational  
+/// When: apply_options is called, then tool_choice is set explicitly  
+/// Then: effective_tool_choice follows the profile first, then the explicit value; the build
+/// config carries the profile's system prompt
+#[test]
+fn apply_options_prompt_profile_sets_tool_choice_default_and_system_prompt() {
+    use langgraph::{PromptProfile, ToolChoiceMode};
+
+    let _guard = env_api_key_lock();
+    let saved_key = std::env::var("OPENAI_API_KEY").ok();
+    std::env::set_var("OPENAI_API_KEY", "key");
+    std::env::remove_var("OPENAI_TOOL_CHOICE");
+    let mut config = RunConfig::from_env().expect("need key");
+    if let Some(ref k) = saved_key {
+        std::env::set_var("OPENAI_API_KEY", k);
+    } else {
+        std::env::remove_var("OPENAI_API_KEY");
+    }
+
+    config.apply_options(&crate::RunOptions {
+        prompt_profile: Some(PromptProfile::Conversational),
+        ..Default::default()
+    });
+    assert_eq!(config.effective_tool_choice(), Some(ToolChoiceMode::None));
+    assert_eq!(
+        config.to_react_build_config().effective_system_prompt(),
+        PromptProfile::Conversational.system_prompt()
+    );
+
+    config.tool_choice = Some(ToolChoiceMode::Required);
+    assert_eq!(config.effective_tool_choice(), Some(ToolChoiceMode::Required));
+}
+
+/// **Scenario**: Skills given as options replace the config's; their prompt section follows
+/// the system prompt of the build config.
+///
+/// Given: a RunConfig from env and RunOptions with one skill  
+/// When: apply_options is called  
+/// Then: the build config carries the skill and its section ends the system prompt
+#[test]
+fn apply_options_skills_extend_system_prompt() {
+    use langgraph::{Skill, SkillSet};
+
+    let _guard = env_api_key_lock();
+    let saved_key = std::env::var("OPENAI_API_KEY").ok();
+    std::env::set_var("OPENAI_API_KEY", "key");
+    let mut config = RunConfig::from_env().expect("need key");
+    if let Some(ref k) = saved_key {
+        std::env::set_var("OPENAI_API_KEY", k);
+    } else {
+        std::env::remove_var("OPENAI_API_KEY");
+    }
+    assert!(config.skills.is_empty());
+
+    let mut skills = SkillSet::new();
+    skills
+        .push(Skill::from_toml("name = \"haiku\"\nprompt = \"Answer in haiku.\"").unwrap())
+        .unwrap();
+    config.apply_options(&crate::RunOptions {
+        skills,
+        ..Default::default()
+    });
+    let build_config = config.to_react_build_config();
+    assert_eq!(build_config.skills.labels(), ["haiku"]);
+    assert!(build_config
+        .effective_system_prompt()
+        .ends_with("## Skill: haiku\n\nAnswer in haiku."));
+}
@@ -93,3 +93,179 @@ fn main_with_verbose_prints_config_summary_to_stderr() {
         stderr
     );
 }
+
+/// **Scenario**: --analyze reports the default web_fetcher tool, which no prompt names, and
+/// exits with code 2 without running the agent.
+#[test]
+fn main_with_analyze_reports_unused_tools() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "langgraph-cli",
+            "--bin",
+            "langgraph",
+            "--",
+            "--analyze",
+        ])
+        .env("OPENAI_API_KEY", "test-key-for-analyze-test")
+        .output();
+    let output = output.expect("failed to run cargo");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(2), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("unused tool: web_fetcher"),
+        "stdout should report web_fetcher, got: {}",
+        stdout
+    );
+    assert!(!stdout.contains("User:"), "agent should not run: {}", stdout);
+}
+
+/// **Scenario**: `tools docs` prints a markdown reference of the default tools (web_fetcher
+/// among them) without running the agent; `--format html` prints an HTML page.
+#[test]
+fn main_tools_docs_prints_tool_reference() {
+    let run = |format: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "langgraph-cli",
+                "--bin",
+                "langgraph",
+                "--",
+                "tools",
+                "docs",
+                "--format",
+                format,
+            ])
+            .env("OPENAI_API_KEY", "test-key-for-tool-docs-test")
+            .output()
+            .expect("failed to run cargo")
+    };
+    let output = run("markdown");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.starts_with("# Tool reference"), "{}", stdout);
+    assert!(stdout.contains("## `web_fetcher`"), "{}", stdout);
+    assert!(!stdout.contains("User:"), "agent should not run: {}", stdout);
+
+    let output = run("html");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>"), "{}", stdout);
+}
+
+/// **Scenario**: `graph` prints the ReAct graph as Mermaid by default, with the branch from
+/// observe back to think, and as DOT with `--format dot`, without running the agent.
+#[test]
+fn main_graph_prints_react_topology() {
+    let run = |format: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "-p",
+                "langgraph-cli",
+                "--bin",
+                "langgraph",
+                "--",
+                "graph",
+                "--format",
+                format,
+            ])
+            .env("OPENAI_API_KEY", "test-key-for-graph-test")
+            .output()
+            .expect("failed to run cargo")
+    };
+    let output = run("mermaid");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.starts_with("flowchart TD"), "{}", stdout);
+    assert!(stdout.contains("__start__ --> think"), "{}", stdout);
+    assert!(stdout.contains("observe -.->|\"think\"| think"), "{}", stdout);
+    assert!(!stdout.contains("User:"), "agent should not run: {}", stdout);
+
+    let output = run("dot");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("digraph {"), "{}", stdout);
+}
+
+/// **Scenario**: `ingest` with a path that does not exist fails with status 1 and names the
+/// path, before any embedding request; without a path it is a usage error.
+#[test]
+fn main_ingest_reports_missing_path() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "langgraph-cli",
+            "--bin",
+            "langgraph",
+            "--",
+            "ingest",
+            "no-such-dir-for-ingest-test",
+        ])
+        .env("OPENAI_API_KEY", "test-key-for-ingest-test")
+        .output()
+        .expect("failed to run cargo");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("error: read no-such-dir-for-ingest-test"),
+        "{}",
+        stderr
+    );
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "langgraph-cli",
+            "--bin",
+            "langgraph",
+            "--",
+            "ingest",
+        ])
+        .output()
+        .expect("failed to run cargo");
+    assert!(!output.status.success());
+}
+
+/// **Scenario**: `chat` reads commands from stdin: `/reload` rebuilds the agent and reports
+/// the model, `/exit` quits with status 0, without sending anything to the model.
+#[test]
+fn main_chat_reloads_config_and_exits() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let db_path =
+        std::env::temp_dir().join(format!("langgraph-cli-chat-{}.db", std::process::id()));
+    let mut child = Command::new("cargo")
+        .args([
+            "run",
+            "-p",
+            "langgraph-cli",
+            "--bin",
+            "langgraph",
+            "--",
+            "chat",
+        ])
+        .env("OPENAI_API_KEY", "test-key-for-chat-test")
+        .env("OPENAI_MODEL", "gpt-4o-mini")
+        .env("DB_PATH", &db_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run cargo");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"/reload\n/exit\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(&db_path).ok();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Chat on thread"), "{}", stdout);
+    assert!(stdout.contains("[Config reloaded: model"), "{}", stdout);
+}
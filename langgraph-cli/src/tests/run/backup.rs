@@ -0,0 +1,58 @@
+//! Tests for [`backup`](crate::backup), used by `--backup`.
+
+use std::sync::Arc;
+
+use langgraph::memory::{
+    Checkpoint, CheckpointSource, Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver,
+};
+use langgraph::ReActState;
+
+use crate::backup;
+use crate::config::RunConfig;
+
+/// **Scenario**: The checkpoint database is copied to a new file; an existing destination is
+/// refused.
+///
+/// Given: a checkpoint of thread "backup-thread" in a temp SQLite file  
+/// When: backup is called twice with the same destination  
+/// Then: the first copy holds the checkpoint, the second call is Err
+#[tokio::test]
+async fn backup_copies_checkpoints_once() {
+    let saved = std::env::var("OPENAI_API_KEY").ok();
+    std::env::set_var("OPENAI_API_KEY", "test-key-for-test");
+    let config = RunConfig::from_env();
+    if let Some(k) = saved {
+        std::env::set_var("OPENAI_API_KEY", k);
+    }
+    let Ok(mut config) = config else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("langgraph-cli-backup-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db_path = dir.join("memory.db");
+    config.db_path = Some(db_path.display().to_string());
+
+    let saver = SqliteSaver::<ReActState>::new(&db_path, Arc::new(JsonSerializer)).unwrap();
+    let thread = RunnableConfig {
+        thread_id: Some("backup-thread".into()),
+        ..Default::default()
+    };
+    saver
+        .put(
+            &thread,
+            &Checkpoint::from_state(ReActState::default(), CheckpointSource::Input, -1),
+        )
+        .await
+        .unwrap();
+
+    let dest = dir.join("backup.db").display().to_string();
+    let bytes = backup(&config, &dest).await;
+    let again = backup(&config, &dest).await;
+    let copy = SqliteSaver::<ReActState>::new(&dest, Arc::new(JsonSerializer)).unwrap();
+    let restored = copy.get_tuple(&thread).await.unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(bytes.unwrap() > 0);
+    assert!(again.is_err());
+    assert!(restored.is_some());
+}
@@ -0,0 +1,49 @@
+//! Tests for [`ChatSession`](crate::ChatSession), used by `langgraph chat`.
+
+use crate::config::RunConfig;
+use crate::{ChatSession, RunOptions};
+
+/// **Scenario**: A session without short-term memory gets its own thread; a reload applies
+/// the new options and keeps the thread and the database.
+///
+/// Given: a config with only long-term memory and a temp SQLite file  
+/// When: a session starts, then reloads with temperature 0.3 and another db_path  
+/// Then: the thread is kept, the temperature applied and the db_path unchanged
+#[tokio::test]
+async fn chat_session_reload_keeps_thread() {
+    let saved = std::env::var("OPENAI_API_KEY").ok();
+    std::env::set_var("OPENAI_API_KEY", "test-key-for-test");
+    let config = RunConfig::from_env();
+    let Ok(config) = config else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("langgraph-cli-chat-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db_path = dir.join("memory.db").display().to_string();
+    let mut config = config.with_long_term_memory("chat-user");
+    config.db_path = Some(db_path.clone());
+
+    let mut session = ChatSession::new(config).await.unwrap();
+    let thread_id = session.config().thread_id().map(ToString::to_string);
+    assert!(thread_id.is_some());
+    assert_eq!(session.config().user_id(), Some("chat-user"));
+
+    let options = RunOptions {
+        temperature: Some(0.3),
+        db_path: Some(dir.join("other.db").display().to_string()),
+        ..Default::default()
+    };
+    let reloaded = session.reload(&options).await;
+    if let Some(k) = saved {
+        std::env::set_var("OPENAI_API_KEY", k);
+    }
+    std::fs::remove_dir_all(&dir).ok();
+
+    reloaded.unwrap();
+    assert_eq!(session.config().temperature, Some(0.3));
+    assert_eq!(
+        session.config().thread_id().map(ToString::to_string),
+        thread_id
+    );
+    assert_eq!(session.config().db_path.as_deref(), Some(db_path.as_str()));
+}
@@ -22,6 +22,7 @@ fn minimal_config(
         model: "gpt-4o-mini".to_string(),
         temperature: None,
         tool_choice: None,
+        prompt_profile: None,
         embedding_api_key,
         embedding_api_base: None,
         embedding_model: None,
@@ -33,8 +34,12 @@ fn minimal_config(
         mcp_exa_url: "https://mcp.exa.ai/mcp".to_string(),
         mcp_remote_cmd: "npx".to_string(),
         mcp_remote_args: "-y mcp-remote".to_string(),
+        skills: Default::default(),
+        mcp_servers_file: None,
         stream: true,
         verbose: false,
+        profile: None,
+        http_client: Default::default(),
     }
 }
 
@@ -0,0 +1,62 @@
+//! Tests for [`export_studio`](crate::export_studio), used by `--export-studio`.
+
+use std::sync::Arc;
+
+use langgraph::memory::{
+    Checkpoint, CheckpointSource, Checkpointer, JsonSerializer, RunnableConfig, SqliteSaver,
+};
+use langgraph::{Message, ReActState};
+
+use crate::config::RunConfig;
+use crate::export_studio;
+
+/// **Scenario**: A thread persisted in the SQLite checkpointer exports as Studio JSON; an
+/// unknown thread is an error.
+///
+/// Given: a checkpoint of thread "studio-thread" in a temp SQLite file  
+/// When: export_studio is called for that thread and for an unknown one  
+/// Then: the first returns JSON with the thread id and LangChain messages, the second Err
+#[tokio::test]
+async fn export_studio_reads_sqlite_checkpoints() {
+    let saved = std::env::var("OPENAI_API_KEY").ok();
+    std::env::set_var("OPENAI_API_KEY", "test-key-for-test");
+    let config = RunConfig::from_env();
+    if let Some(k) = saved {
+        std::env::set_var("OPENAI_API_KEY", k);
+    }
+    let Ok(mut config) = config else {
+        return;
+    };
+    let db_path = std::env::temp_dir().join(format!(
+        "langgraph-cli-export-studio-{}.db",
+        std::process::id()
+    ));
+    config.db_path = Some(db_path.display().to_string());
+
+    let saver = SqliteSaver::<ReActState>::new(&db_path, Arc::new(JsonSerializer)).unwrap();
+    let state = ReActState {
+        messages: vec![Message::user("hi"), Message::assistant("hello")],
+        ..Default::default()
+    };
+    let thread = RunnableConfig {
+        thread_id: Some("studio-thread".into()),
+        ..Default::default()
+    };
+    saver
+        .put(
+            &thread,
+            &Checkpoint::from_state(state, CheckpointSource::Input, -1),
+        )
+        .await
+        .unwrap();
+
+    let json = export_studio(&config, "studio-thread").await.unwrap();
+    let missing = export_studio(&config, "other-thread").await;
+    let _ = std::fs::remove_file(&db_path);
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["thread"]["thread_id"], "studio-thread");
+    assert_eq!(value["thread"]["values"]["messages"][0]["type"], "human");
+    assert_eq!(value["history"].as_array().unwrap().len(), 1);
+    assert!(missing.is_err());
+}
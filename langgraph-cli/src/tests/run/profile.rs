@@ -0,0 +1,43 @@
+//! Tests for [`profile_report`](crate::profile_report), used by `--profile-report`.
+
+use langgraph::{FrameStats, GraphProfile};
+
+use crate::profile_report;
+
+/// **Scenario**: A saved profile is printed as a table and exported as folded stacks or pprof
+/// depending on the file extension.
+///
+/// Given: a profile with one "think" frame saved in a temp directory  
+/// When: profile_report is called with a `.folded` and a `.pb` export path  
+/// Then: the table lists "think", the folded file holds its wall time, the pprof file is binary
+#[test]
+fn profile_report_prints_table_and_exports() {
+    let dir = std::env::temp_dir().join(format!("langgraph-cli-profile-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("profile.json").display().to_string();
+    let mut profile = GraphProfile::new();
+    profile.runs = 1;
+    profile.record(
+        "think",
+        FrameStats {
+            calls: 1,
+            wall_ns: 1_000,
+            ..Default::default()
+        },
+    );
+    profile.save(&path).unwrap();
+
+    let folded = dir.join("profile.folded").display().to_string();
+    let pprof = dir.join("profile.pb").display().to_string();
+    let table = profile_report(&path, Some(&folded)).unwrap();
+    profile_report(&path, Some(&pprof)).unwrap();
+    let folded = std::fs::read_to_string(&folded).unwrap();
+    let pprof = std::fs::read(&pprof).unwrap();
+    let missing = profile_report(&dir.join("missing.json").display().to_string(), None);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    assert!(table.lines().any(|l| l.starts_with("think")), "{}", table);
+    assert_eq!(folded, "think 1000\n");
+    assert!(!pprof.is_empty() && std::str::from_utf8(&pprof).is_err());
+    assert!(missing.is_err());
+}
@@ -0,0 +1,15 @@
+//! Tests for [`parse_namespace`](crate::parse_namespace), used by `--reembed`.
+
+use crate::parse_namespace;
+
+/// **Scenario**: A `/`-separated namespace becomes its segments.
+///
+/// Given: namespaces with one and two segments, and stray slashes  
+/// When: parse_namespace is called  
+/// Then: it returns the non-empty segments in order
+#[test]
+fn parse_namespace_splits_on_slash() {
+    assert_eq!(parse_namespace("1/memories"), vec!["1", "memories"]);
+    assert_eq!(parse_namespace("docs"), vec!["docs"]);
+    assert_eq!(parse_namespace("/1//memories/"), vec!["1", "memories"]);
+}
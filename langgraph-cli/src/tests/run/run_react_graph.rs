@@ -43,7 +43,7 @@ async fn run_react_graph_without_checkpointer_or_store_returns_ok_and_state_has_
     let has_assistant = state
         .messages
         .iter()
-        .any(|m| matches!(m, Message::Assistant(s) if s == "Hello from mock."));
+        .any(|m| matches!(m, Message::Assistant { content: s, .. } if s == "Hello from mock."));
     assert!(
         has_assistant,
         "state should contain assistant message from mock"
@@ -112,8 +112,8 @@ async fn run_react_graph_with_store_and_no_checkpointer_returns_ok() {
 ///
 /// Given: MockLlm::with_get_time_call(), MockToolSource::get_time_example(), no checkpointer/store  
 /// When: run_react_graph("What time is it?", ...) is called  
-/// Then: result is Ok; state.messages has at least 3 entries; at least one User message contains
-/// tool result (e.g. "Tool" and a date); tool_calls and tool_results are cleared.
+/// Then: result is Ok; state.messages has at least 3 entries; at least one Tool message contains
+/// the tool result (a date); tool_calls and tool_results are cleared.
 #[tokio::test]
 async fn run_react_graph_one_round_with_tool_call_returns_ok_and_tool_result_in_messages() {
     let llm = MockLlm::with_get_time_call();
@@ -126,16 +126,16 @@ async fn run_react_graph_one_round_with_tool_call_returns_ok_and_tool_result_in_
     let state = result.expect("run_react_graph with tool call should succeed");
     assert!(
         state.messages.len() >= 3,
-        "expected at least user + assistant + tool result user: {}",
+        "expected at least user + assistant + tool result: {}",
         state.messages.len()
     );
     let has_tool_result = state
         .messages
         .iter()
-        .any(|m| matches!(m, Message::User(s) if s.contains("Tool") && s.contains("2025")));
+        .any(|m| matches!(m, Message::Tool { content, .. } if content.contains("2025")));
     assert!(
         has_tool_result,
-        "state should contain a User message with tool result (Tool + date)"
+        "state should contain a Tool message with the tool result (date)"
     );
     assert!(state.tool_calls.is_empty(), "tool_calls should be cleared");
     assert!(
@@ -177,19 +177,23 @@ async fn run_react_graph_state_starts_with_system_prompt() {
 /// Given: MemorySaver with a checkpoint for thread "t1" containing messages [system, user("first"),
 /// assistant("Reply to first")]; MockLlm that returns no tool_calls with "Reply to second"
 /// When: run_react_graph("second", llm, tool_source, Some(checkpointer), None, Some(config with thread_id "t1")) is called
-/// Then: result is Ok; state.messages contains Message::User("first"), Message::Assistant("Reply to first"),
-/// Message::User("second"), and Message::Assistant("Reply to second").
+/// Then: result is Ok; state.messages contains Message::User("first"), Message::assistant("Reply to first"),
+/// Message::User("second"), and Message::assistant("Reply to second").
 #[tokio::test]
 async fn run_react_graph_with_checkpoint_loads_history_and_appends_new_turn() {
     let history_state = ReActState {
         messages: vec![
             Message::system(REACT_SYSTEM_PROMPT),
             Message::user("first".to_string()),
-            Message::Assistant("Reply to first".to_string()),
+            Message::assistant("Reply to first".to_string()),
         ],
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let checkpoint = Checkpoint::from_state(history_state, CheckpointSource::Update, 0);
     let saver: MemorySaver<ReActState> = MemorySaver::new();
@@ -198,6 +202,7 @@ async fn run_react_graph_with_checkpoint_loads_history_and_appends_new_turn() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     saver.put(&config, &checkpoint).await.unwrap();
 
@@ -227,7 +232,7 @@ async fn run_react_graph_with_checkpoint_loads_history_and_appends_new_turn() {
     let has_first_assistant = state
         .messages
         .iter()
-        .any(|m| matches!(m, Message::Assistant(s) if s == "Reply to first"));
+        .any(|m| matches!(m, Message::Assistant { content: s, .. } if s == "Reply to first"));
     assert!(
         has_first_assistant,
         "state should contain history assistant message 'Reply to first'"
@@ -240,7 +245,7 @@ async fn run_react_graph_with_checkpoint_loads_history_and_appends_new_turn() {
     let has_second_assistant = state
         .messages
         .iter()
-        .any(|m| matches!(m, Message::Assistant(s) if s == "Reply to second"));
+        .any(|m| matches!(m, Message::Assistant { content: s, .. } if s == "Reply to second"));
     assert!(
         has_second_assistant,
         "state should contain new assistant message 'Reply to second'"
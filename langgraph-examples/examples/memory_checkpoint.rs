@@ -35,7 +35,7 @@ impl Agent for EchoAgent {
             }
         });
         if let Some(content) = last {
-            messages.push(Message::Assistant(content));
+            messages.push(Message::assistant(content));
         }
         Ok(AgentState { messages })
     }
@@ -51,6 +51,7 @@ async fn main() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
 
     let mut graph = StateGraph::<AgentState>::new();
@@ -71,7 +72,7 @@ async fn main() {
         .await
         .expect("invoke");
 
-    if let Some(Message::Assistant(content)) = state.messages.last() {
+    if let Some(Message::Assistant { content, .. }) = state.messages.last() {
         println!("{content}");
     }
 
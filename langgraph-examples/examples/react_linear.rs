@@ -2,15 +2,16 @@
 //!
 //! Design: [docs/rust-langgraph/13-react-agent-design.md](https://github.com/.../13-react-agent-design.md) §5, §8.4.
 //! Builds StateGraph<ReActState> with ThinkNode, ActNode, ObserveNode; one User message,
-//! invoke once; MockLLM returns one get_time tool call, MockToolSource returns fixed time.
+//! invoke once; MockLLM returns one get_time tool call, TimeTool returns the current time.
 //!
 //! Run: `cargo run -p langgraph-examples --example react_linear -- "What time is it?"`
 
 use std::sync::Arc;
 
+use langgraph::tools::AggregateToolSource;
 use langgraph::{
-    ActNode, CompiledStateGraph, Message, MockLlm, MockToolSource, ObserveNode, ReActState,
-    StateGraph, ThinkNode, END, REACT_SYSTEM_PROMPT, START,
+    ActNode, CompiledStateGraph, Message, MockLlm, ObserveNode, ReActState, StateGraph, ThinkNode,
+    TimeTool, END, REACT_SYSTEM_PROMPT, START,
 };
 
 #[tokio::main]
@@ -19,16 +20,16 @@ async fn main() {
         .nth(1)
         .unwrap_or_else(|| "What time is it?".to_string());
 
+    let tools = AggregateToolSource::new();
+    tools.register_async(Box::new(TimeTool::new())).await;
+
     let mut graph = StateGraph::<ReActState>::new();
     graph
         .add_node(
             "think",
             Arc::new(ThinkNode::new(Box::new(MockLlm::with_get_time_call()))),
         )
-        .add_node(
-            "act",
-            Arc::new(ActNode::new(Box::new(MockToolSource::get_time_example()))),
-        )
+        .add_node("act", Arc::new(ActNode::new(Box::new(tools))))
         .add_node("observe", Arc::new(ObserveNode::new()))
         .add_edge(START, "think")
         .add_edge("think", "act")
@@ -42,6 +43,10 @@ async fn main() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     match compiled.invoke(state, None).await {
@@ -50,7 +55,10 @@ async fn main() {
                 match m {
                     Message::System(x) => println!("[System] {}", x),
                     Message::User(x) => println!("[User] {}", x),
-                    Message::Assistant(x) => println!("[Assistant] {}", x),
+                    Message::Assistant { content: x, .. } => println!("[Assistant] {}", x),
+                    Message::Tool { name, content: x, .. } => {
+                        println!("[Tool {}] {}", name.as_deref().unwrap_or("?"), x)
+                    }
                 }
             }
             if s.messages.is_empty() {
@@ -79,6 +79,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     let result = compiled.invoke(state, None).await?;
@@ -86,7 +90,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match m {
             Message::System(x) => println!("[System] {}", x),
             Message::User(x) => println!("[User] {}", x),
-            Message::Assistant(x) => println!("[Assistant] {}", x),
+            Message::Assistant { content: x, .. } => println!("[Assistant] {}", x),
+            Message::Tool { name, content: x, .. } => {
+                println!("[Tool {}] {}", name.as_deref().unwrap_or("?"), x)
+            }
         }
     }
     Ok(())
@@ -5,9 +5,9 @@ use serde::{Deserialize, Serialize};
 
 use langgraph::SqliteSaver;
 use langgraph::{
-    Checkpointer, Embedder, JsonSerializer, LanceStore, LlmClient, LlmResponse, Message, Namespace,
-    Next, Node, RunnableConfig, StateGraph, Store, StoreError, ToolCall as ReActToolCall,
-    ToolCallContent, ToolResult, ToolSource, ToolSpec, END, START,
+    Checkpointer, Embedder, JsonSerializer, LanceStore, LlmClient, LlmResponse, MemoryToolkit,
+    MemoryValueSchema, Message, Namespace, Next, Node, RunnableConfig, StateGraph, Store,
+    StoreError, ToolCall as ReActToolCall, ToolResult, ToolSource, END, START,
 };
 
 #[derive(Clone)]
@@ -62,16 +62,16 @@ impl LlmClient for MemoryMockLlm {
         let content = if let Some(Message::User(text)) = last_user_msg {
             let lower = text.to_lowercase();
             if lower.contains("remember") {
-                extract_and_format_tool_call("save_memory", text)
+                extract_and_format_tool_call("remember", text)
             } else if lower.contains("what's my name") || lower.contains("what is my name") {
-                extract_and_format_tool_call("retrieve_memory", "name")
+                extract_and_format_tool_call("search_memories", "name")
             } else if lower.contains("what do you know") || lower.contains("tell me about myself") {
                 extract_and_format_tool_call("list_memories", "")
             } else if lower.contains("hobbies")
                 || lower.contains("interests")
                 || lower.contains("hobby")
             {
-                extract_and_format_tool_call("retrieve_memory", "hobbies")
+                extract_and_format_tool_call("search_memories", "hobbies")
             } else if lower.contains("preferences")
                 || lower.contains("what do i like")
                 || lower.contains("what are my")
@@ -82,7 +82,7 @@ impl LlmClient for MemoryMockLlm {
                 || lower.contains("favorite")
                 || lower.contains("prefer")
             {
-                extract_and_format_tool_call("save_memory", text)
+                extract_and_format_tool_call("remember", text)
             } else {
                 format!("Based on our conversation, here's my response to: {}", text)
             }
@@ -100,22 +100,26 @@ impl LlmClient for MemoryMockLlm {
         Ok(LlmResponse {
             content,
             tool_calls,
+            usage: None,
+            truncated: false,
+            continuations: 0,
+            metadata: Default::default(),
         })
     }
 }
 
 fn extract_and_format_tool_call(tool_name: &str, text: &str) -> String {
     match tool_name {
-        "save_memory" => {
+        "remember" => {
             let info = text.replace("remember", "").trim().to_string();
-            let args = serde_json::json!({ "info": info });
+            let args = serde_json::json!({ "value": info });
             format!(
                 "tool_call:{{\"name\":\"{}\",\"arguments\":{}}}",
                 tool_name, args
             )
         }
-        "retrieve_memory" => {
-            let args = serde_json::json!({ "key": text });
+        "search_memories" => {
+            let args = serde_json::json!({ "query": text, "limit": 5 });
             format!(
                 "tool_call:{{\"name\":\"{}\",\"arguments\":{}}}",
                 tool_name, args
@@ -144,144 +148,6 @@ fn parse_tool_call(text: &str) -> ReActToolCall {
     }
 }
 
-struct MemoryToolSource {
-    store: Arc<dyn Store>,
-    namespace: Namespace,
-}
-
-impl MemoryToolSource {
-    fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
-        Self { store, namespace }
-    }
-}
-
-#[async_trait::async_trait]
-impl ToolSource for MemoryToolSource {
-    async fn list_tools(&self) -> Result<Vec<ToolSpec>, langgraph::tool_source::ToolSourceError> {
-        Ok(vec![
-            ToolSpec {
-                name: "save_memory".to_string(),
-                description: Some("Save information to long-term memory. Use when user says 'remember' or shares preferences.".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "info": {
-                            "type": "string",
-                            "description": "Information to remember (e.g., 'name is Alice', 'likes coffee')"
-                        }
-                    },
-                    "required": ["info"]
-                }),
-            },
-            ToolSpec {
-                name: "retrieve_memory".to_string(),
-                description: Some("Retrieve specific memory by key. Use for questions like 'what's my name'.".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        "key": {
-                            "type": "string",
-                            "description": "Key to retrieve (e.g., 'name', 'preferences')"
-                        }
-                    },
-                    "required": ["key"]
-                }),
-            },
-            ToolSpec {
-                name: "list_memories".to_string(),
-                description: Some("List all stored memories for the user. Use for 'what do you know about me'.".to_string()),
-                input_schema: serde_json::json!({
-                    "type": "object",
-                    "properties": {},
-                }),
-            },
-        ])
-    }
-
-    async fn call_tool(
-        &self,
-        name: &str,
-        arguments: serde_json::Value,
-    ) -> Result<ToolCallContent, langgraph::tool_source::ToolSourceError> {
-        match name {
-            "save_memory" => {
-                let info = arguments["info"].as_str().unwrap_or("").to_string();
-                let timestamp = chrono::Utc::now().to_rfc3339();
-                let key = format!(
-                    "memory_{}",
-                    chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
-                );
-                let value = serde_json::json!({
-                    "info": info,
-                    "timestamp": timestamp
-                });
-                self.store
-                    .put(&self.namespace, &key, &value)
-                    .await
-                    .map_err(|e| {
-                        langgraph::tool_source::ToolSourceError::Transport(e.to_string())
-                    })?;
-                Ok(ToolCallContent {
-                    text: format!("Saved to memory: {}", info),
-                })
-            }
-            "retrieve_memory" => {
-                let key = arguments["key"].as_str().unwrap_or("");
-                let hits = self
-                    .store
-                    .search(&self.namespace, Some(key), Some(5))
-                    .await
-                    .map_err(|e| {
-                        langgraph::tool_source::ToolSourceError::Transport(e.to_string())
-                    })?;
-                if hits.is_empty() {
-                    Ok(ToolCallContent {
-                        text: format!("No memories found for '{}'", key),
-                    })
-                } else {
-                    let memories: Vec<String> = hits
-                        .iter()
-                        .map(|h| h.value["info"].as_str().unwrap_or("").to_string())
-                        .collect();
-                    Ok(ToolCallContent {
-                        text: format!("Found memories: {}", memories.join(", ")),
-                    })
-                }
-            }
-            "list_memories" => {
-                let keys = self.store.list(&self.namespace).await.map_err(|e| {
-                    langgraph::tool_source::ToolSourceError::Transport(e.to_string())
-                })?;
-                let mut memories = Vec::new();
-                for key in keys {
-                    if let Some(value) =
-                        self.store.get(&self.namespace, &key).await.map_err(|e| {
-                            langgraph::tool_source::ToolSourceError::Transport(e.to_string())
-                        })?
-                    {
-                        if let Some(info) = value["info"].as_str() {
-                            memories.push(info.to_string());
-                        }
-                    }
-                }
-                if memories.is_empty() {
-                    Ok(ToolCallContent {
-                        text: "No memories stored yet. Tell me something to remember!".to_string(),
-                    })
-                } else {
-                    Ok(ToolCallContent {
-                        text: format!("I remember: {}", memories.join("; ")),
-                    })
-                }
-            }
-            _ => Err(langgraph::tool_source::ToolSourceError::NotFound(format!(
-                "Unknown tool: {}",
-                name
-            ))),
-        }
-    }
-}
-
 struct MemoryThinkNode {
     llm: Box<dyn LlmClient>,
 }
@@ -305,7 +171,7 @@ impl Node<MemoryReActState> for MemoryThinkNode {
         let response = self.llm.invoke(&state.messages).await?;
 
         let mut messages = state.messages;
-        messages.push(Message::Assistant(response.content));
+        messages.push(Message::assistant(response.content));
 
         Ok((
             MemoryReActState {
@@ -462,9 +328,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: Some(user_id.to_string()),
+        ..Default::default()
     };
 
-    let tools = Box::new(MemoryToolSource::new(store.clone(), namespace.clone()));
+    // Plain-text facts: remember takes {"value": "..."} and stores {"text", "saved_at"}.
+    let tools = Box::new(
+        MemoryToolkit::new(store.clone(), namespace.clone())
+            .with_value_schema(MemoryValueSchema::Text)
+            .into_tool_source()
+            .await,
+    );
     let llm = Box::new(MemoryMockLlm);
 
     let think = Arc::new(MemoryThinkNode::new(llm));
@@ -486,8 +359,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut current_state = MemoryReActState {
         messages: vec![Message::system(
             "You are a helpful assistant with both short-term and long-term memory. \
-             Use the save_memory tool when users share personal information. \
-             Use retrieve_memory or list_memories when they ask about what you know about them.",
+             Use the remember tool when users share personal information. \
+             Use search_memories or list_memories when they ask about what you know about them.",
         )],
         tool_calls: vec![],
         tool_results: vec![],
@@ -527,8 +400,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
         );
         let memory_value = serde_json::json!({
-            "info": memory,
-            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "text": memory,
+            "saved_at": chrono::Utc::now().timestamp(),
         });
         store.put(&namespace, &memory_key, &memory_value).await?;
     }
@@ -569,7 +442,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     match msg {
                         Message::System(s) => println!("[System] {}", s),
                         Message::User(s) => println!("[User] {}", s),
-                        Message::Assistant(s) => println!("[Assistant] {}", s),
+                        Message::Assistant { content: s, .. } => println!("[Assistant] {}", s),
+                        Message::Tool { name, content: s, .. } => {
+                            println!("[Tool {}] {}", name.as_deref().unwrap_or("?"), s)
+                        }
                     }
                 }
 
@@ -618,11 +494,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("2. Retrieve a specific memory by key:");
     if let Some(first_key) = keys.first() {
         if let Some(value) = store.get(&namespace, first_key).await? {
-            let info = value["info"].as_str().unwrap_or("N/A");
-            let timestamp = value["timestamp"].as_str().unwrap_or("N/A");
+            let info = value["text"].as_str().unwrap_or("N/A");
+            let saved_at = value["saved_at"].as_i64().unwrap_or(0);
             println!("   Key: {}", first_key);
             println!("   Info: {}", info);
-            println!("   Timestamp: {}\n", timestamp);
+            println!("   Saved at (unix secs): {}\n", saved_at);
         }
     }
 
@@ -632,7 +508,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let hits = store.search(&namespace, Some(term), Some(3)).await?;
         println!("   Search '{}':", term);
         for (idx, hit) in hits.iter().enumerate() {
-            let info = hit.value["info"].as_str().unwrap_or("N/A");
+            let info = hit.value["text"].as_str().unwrap_or("N/A");
             let score_display = hit.score.map_or("N/A".to_string(), |s| format!("{:.4}", s));
             println!("     [{}] {} (score: {})", idx + 1, info, score_display);
         }
@@ -647,7 +523,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     for (idx, key) in all_keys.iter().take(10).enumerate() {
         if let Some(value) = store.get(&namespace, key).await? {
-            let info = value["info"].as_str().unwrap_or("N/A");
+            let info = value["text"].as_str().unwrap_or("N/A");
             println!("     [{}] {}", idx + 1, info);
         }
     }
@@ -657,7 +533,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let recent_hits = store.search(&namespace, Some("swimming"), Some(2)).await?;
     println!("   Search 'swimming':");
     for (idx, hit) in recent_hits.iter().enumerate() {
-        let info = hit.value["info"].as_str().unwrap_or("N/A");
+        let info = hit.value["text"].as_str().unwrap_or("N/A");
         let score_display = hit.score.map_or("N/A".to_string(), |s| format!("{:.4}", s));
         println!("     [{}] {} (score: {})", idx + 1, info, score_display);
     }
@@ -0,0 +1,150 @@
+//! SSE throughput: CPU time per streamed token through [`StreamToSse`].
+//!
+//! Streams the same tokens three ways and prints nanoseconds per token:
+//! - `chunk + write_sse_line`: a `ChatCompletionChunk` and a `String` per token, as before
+//!   the adapter reused its buffer;
+//! - `StreamToSse, String sink`: the adapter sending `String` lines (`new_with_sink`);
+//! - `StreamToSse, Bytes sink`: the adapter sending `Bytes` lines (`new_with_bytes_sink`),
+//!   as langgraph-server does.
+//!
+//! Run: `cargo run --release -p langgraph-examples --example sse_throughput -- [tokens]`
+
+use std::time::{Duration, Instant};
+
+use langgraph::openai_sse::{ChunkChoice, Delta};
+use langgraph::stream::{MessageChunk, StreamMetadata};
+use langgraph::{write_sse_line, ChatCompletionChunk, ChunkMeta, StreamEvent, StreamToSse};
+use tokio::sync::mpsc;
+
+/// Tokens streamed per round; the channel is drained after each round.
+const ROUND: usize = 1024;
+
+fn meta() -> ChunkMeta {
+    ChunkMeta {
+        id: "chatcmpl-0190f2a4-7c1e-7b3a-9d2e-5f6a7b8c9d0e".to_string(),
+        model: "gpt-4o-mini".to_string(),
+        created: Some(1_700_000_000),
+    }
+}
+
+/// Token texts in the shape a model streams: short words, some needing escapes.
+const WORDS: [&str; 8] = [
+    " the",
+    " agent",
+    " called",
+    " get_time",
+    ",",
+    " said",
+    " \"ok\"",
+    ".\n",
+];
+
+fn tokens() -> Vec<String> {
+    (0..ROUND)
+        .map(|i| WORDS[i % WORDS.len()].to_string())
+        .collect()
+}
+
+fn message(text: &str) -> StreamEvent<langgraph::ReActState> {
+    StreamEvent::Messages {
+        chunk: MessageChunk::text(text),
+        metadata: StreamMetadata {
+            langgraph_node: "think".to_string(),
+        },
+    }
+}
+
+fn legacy(tokens: &[String], rounds: usize) -> Duration {
+    let meta = meta();
+    let (tx, mut rx) = mpsc::channel::<String>(ROUND);
+    let mut lines = Vec::new();
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for text in tokens {
+            let StreamEvent::Messages { chunk, .. } = message(text) else {
+                unreachable!()
+            };
+            // What the adapter did per token: clone the metadata, build a chunk, format a
+            // line, send a copy and keep it.
+            let (id, model) = (meta.id.clone(), meta.model.clone());
+            let chunk = ChatCompletionChunk {
+                id,
+                object: ChatCompletionChunk::OBJECT,
+                created: 1_700_000_000,
+                model,
+                choices: vec![ChunkChoice {
+                    index: 0,
+                    delta: Delta {
+                        content: Some(chunk.content),
+                        ..Default::default()
+                    },
+                    finish_reason: None,
+                }],
+                usage: None,
+                system_fingerprint: None,
+                citations: None,
+            };
+            let line = write_sse_line(&chunk);
+            let _ = tx.try_send(line.clone());
+            lines.push(line);
+        }
+        while rx.try_recv().is_ok() {}
+        lines.clear();
+    }
+    start.elapsed()
+}
+
+fn string_sink(tokens: &[String], rounds: usize) -> Duration {
+    let (tx, mut rx) = mpsc::channel::<String>(ROUND);
+    let mut adapter = StreamToSse::new_with_sink(meta(), false, tx);
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for text in tokens {
+            adapter.feed(message(text));
+        }
+        while rx.try_recv().is_ok() {}
+        adapter.take_lines();
+    }
+    start.elapsed()
+}
+
+fn bytes_sink(tokens: &[String], rounds: usize) -> Duration {
+    let (tx, mut rx) = mpsc::channel(ROUND);
+    let mut adapter = StreamToSse::new_with_bytes_sink(meta(), false, tx);
+    let start = Instant::now();
+    for _ in 0..rounds {
+        for text in tokens {
+            adapter.feed(message(text));
+        }
+        while rx.try_recv().is_ok() {}
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let total: usize = std::env::args()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1_000_000);
+    let rounds = total.div_ceil(ROUND).max(1);
+    let tokens = tokens();
+    let streamed = (rounds * ROUND) as f64;
+
+    // Warm up allocator and caches once before timing.
+    legacy(&tokens, 1);
+    string_sink(&tokens, 1);
+    bytes_sink(&tokens, 1);
+
+    println!("{} tokens per run", rounds * ROUND);
+    for (name, elapsed) in [
+        ("chunk + write_sse_line", legacy(&tokens, rounds)),
+        ("StreamToSse, String sink", string_sink(&tokens, rounds)),
+        ("StreamToSse, Bytes sink", bytes_sink(&tokens, rounds)),
+    ] {
+        println!(
+            "{:<26} {:>8.1} ns/token",
+            name,
+            elapsed.as_nanos() as f64 / streamed
+        );
+    }
+}
@@ -42,7 +42,7 @@ impl Agent for EchoAgent {
             }
         });
         if let Some(content) = last {
-            messages.push(Message::Assistant(content));
+            messages.push(Message::assistant(content));
         }
         Ok(AgentState { messages })
     }
@@ -67,7 +67,7 @@ async fn main() {
 
     match compiled.invoke(state, None).await {
         Ok(s) => {
-            if let Some(Message::Assistant(content)) = s.messages.last() {
+            if let Some(Message::Assistant { content, .. }) = s.messages.last() {
                 println!("{content}");
             } else {
                 eprintln!("no assistant reply");
@@ -0,0 +1,12 @@
+//! Compiles `proto/agent.proto` into the gRPC service when the `grpc` feature is on.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/agent.proto");
+        // A vendored protoc, so building the feature needs no system install.
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::compile_protos("proto/agent.proto").expect("compile proto/agent.proto");
+    }
+}
@@ -0,0 +1,110 @@
+//! Run analytics from checkpoint tags.
+//!
+//! GET /v1/analytics/outcomes counts the outcome labels (`answered`, `needed_tools`,
+//! `failed_tool`, `refused`, `hallucination_suspected`) the outcome classifier added to the
+//! checkpoints of every thread, or of one with `?thread_id=`. A run is one tagged checkpoint.
+//! Answers `{"threads", "runs", "outcomes": {label: count}}`; labels no run has count 0. When
+//! runs carry A/B experiment tags (`--experiment`), `"variants": {experiment: {variant: {"runs",
+//! "outcomes"}}}` breaks the labelled runs down by variant.
+//!
+//! Reads every checkpoint of every thread, so it suits periodic reports rather than dashboards
+//! polling each second.
+//!
+//! **Interaction**: `ReactRunner::list_threads` / `list_checkpoints`; the tags come from
+//! `ReactRunner::with_outcome_classifier` (`--outcome-classifier`) and
+//! `ReactRunner::with_experiment_variant`.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use langgraph::{ExperimentVariant, RunOutcome};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{AppState, ServerError};
+
+/// Query of GET /v1/analytics/outcomes.
+#[derive(Debug, Default, Deserialize)]
+pub struct OutcomesQuery {
+    /// Only this thread; all threads when absent.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+/// Counts of outcome labels over tagged runs.
+pub async fn outcomes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<OutcomesQuery>,
+) -> Result<Json<Value>, ServerError> {
+    let thread_ids = match query.thread_id.filter(|t| !t.is_empty()) {
+        Some(thread_id) => vec![thread_id],
+        None => state
+            .runner
+            .get()
+            .list_threads(None)
+            .await?
+            .into_iter()
+            .map(|t| t.thread_id)
+            .collect(),
+    };
+    let mut counts = outcome_counts();
+    let mut runs = 0u64;
+    let mut variants: BTreeMap<String, BTreeMap<&str, VariantRuns>> = BTreeMap::new();
+    for thread_id in &thread_ids {
+        for checkpoint in state.runner.get().list_checkpoints(thread_id).await? {
+            let labels: Vec<RunOutcome> = checkpoint
+                .metadata
+                .tags
+                .iter()
+                .filter_map(|t| RunOutcome::from_tag(t))
+                .collect();
+            if labels.is_empty() {
+                continue;
+            }
+            runs += 1;
+            for label in &labels {
+                *counts.entry(label.as_str()).or_default() += 1;
+            }
+            let tags = &checkpoint.metadata.tags;
+            for variant in tags.iter().filter_map(|t| ExperimentVariant::from_tag(t)) {
+                let on_variant = variants
+                    .entry(variant.experiment)
+                    .or_default()
+                    .entry(variant.variant.as_str())
+                    .or_insert_with(|| VariantRuns {
+                        runs: 0,
+                        outcomes: outcome_counts(),
+                    });
+                on_variant.runs += 1;
+                for label in &labels {
+                    *on_variant.outcomes.entry(label.as_str()).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut body = json!({
+        "threads": thread_ids.len(),
+        "runs": runs,
+        "outcomes": counts,
+    });
+    if !variants.is_empty() {
+        body["variants"] = json!(variants);
+    }
+    Ok(Json(body))
+}
+
+/// Labelled runs of one experiment variant.
+#[derive(Debug, Serialize)]
+struct VariantRuns {
+    runs: u64,
+    outcomes: BTreeMap<&'static str, u64>,
+}
+
+/// Every outcome label with count 0.
+fn outcome_counts() -> BTreeMap<&'static str, u64> {
+    RunOutcome::ALL.iter().map(|o| (o.as_str(), 0)).collect()
+}
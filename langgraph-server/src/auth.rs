@@ -0,0 +1,344 @@
+//! API key authentication with a token-bucket rate limit per key.
+//!
+//! Keys come from `LANGGRAPH_API_KEY` (one key), `LANGGRAPH_API_KEYS` (comma-separated) and the
+//! file named by `--api-keys-file` / `API_KEYS_FILE`: one key per line, optionally followed by
+//! its own requests-per-minute limit and burst (`sk-team-a 120 20`; the burst defaults to the
+//! rate); blank lines and `#` comments are skipped. With no key configured the server is open,
+//! as before.
+//!
+//! Every request must then send `Authorization: Bearer <key>`; unknown or missing keys answer
+//! 401. Each key has a bucket of `burst` requests refilled at its per-minute rate
+//! (`--key-rate-limit` / `API_KEY_RATE_LIMIT`, `--key-burst` / `API_KEY_BURST`); a request
+//! finding it empty answers 429 with `Retry-After`. Without a rate, keys are not limited.
+//!
+//! **Interaction**: [`require_auth`] is the outermost middleware of every route, reading
+//! `AppState::api_keys`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::AppState;
+
+/// Requests a key may make per minute, and how many it may make at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// `per_minute` requests a minute, all of which may come at once.
+    pub fn per_minute(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            burst: per_minute,
+        }
+    }
+
+    /// Sets how many requests may come at once.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+/// Tokens of one key; a request takes one.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    per_sec: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        let capacity = f64::from(limit.burst.max(1));
+        Self {
+            capacity,
+            per_sec: f64::from(limit.per_minute) / 60.0,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if self.per_sec <= 0.0 {
+            return Err(Duration::from_secs(60));
+        }
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec))
+    }
+}
+
+/// Why a request was refused.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthError {
+    /// Missing or unknown key.
+    Unauthorized,
+    /// The key's bucket is empty; retry after this long.
+    RateLimited(Duration),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::Unauthorized => {
+                let body = Json(serde_json::json!({
+                    "error": { "message": "Invalid or missing API key. Set Authorization: Bearer <key>." }
+                }));
+                (StatusCode::UNAUTHORIZED, body).into_response()
+            }
+            AuthError::RateLimited(wait) => {
+                let body = Json(serde_json::json!({
+                    "error": { "message": "Rate limit reached for this API key.", "type": "rate_limited" }
+                }));
+                let mut res = (StatusCode::TOO_MANY_REQUESTS, body).into_response();
+                // Whole seconds, rounded up so clients never retry early.
+                let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+                res.headers_mut().insert(header::RETRY_AFTER, secs.into());
+                res
+            }
+        }
+    }
+}
+
+/// Accepted API keys, each with its own rate limit bucket.
+#[derive(Debug, Default)]
+pub struct ApiKeys {
+    /// Limit of keys listed without one.
+    default_limit: Option<RateLimit>,
+    keys: HashMap<String, Option<Mutex<TokenBucket>>>,
+}
+
+impl ApiKeys {
+    /// No keys yet; keys added without a limit get `default_limit`.
+    pub fn new(default_limit: Option<RateLimit>) -> Self {
+        Self {
+            default_limit,
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Accepts `key`, limited by `limit` or else the default limit. Blank keys are ignored.
+    pub fn with_key(mut self, key: &str, limit: Option<RateLimit>) -> Self {
+        let key = key.trim();
+        if key.is_empty() {
+            return self;
+        }
+        let bucket = limit
+            .or(self.default_limit)
+            .map(|limit| Mutex::new(TokenBucket::new(limit, Instant::now())));
+        self.keys.insert(key.to_string(), bucket);
+        self
+    }
+
+    /// Adds the keys of a key file: `<key> [requests_per_minute [burst]]` per line. `#` starts a
+    /// comment at the start of a line or after whitespace, so keys may contain `#`.
+    pub fn with_key_file(mut self, contents: &str) -> Result<Self, String> {
+        for (n, line) in contents.lines().enumerate() {
+            let mut fields = line
+                .split_whitespace()
+                .take_while(|field| !field.starts_with('#'));
+            let Some(key) = fields.next() else {
+                continue;
+            };
+            let (rate, burst) = (fields.next(), fields.next());
+            if let Some(extra) = fields.next() {
+                return Err(format!("line {}: unexpected {:?}", n + 1, extra));
+            }
+            let parse = |what: &str, value: &str| {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| format!("line {}: invalid {} {:?}", n + 1, what, value))
+            };
+            let limit = match rate {
+                Some(rate) => {
+                    let limit = RateLimit::per_minute(parse("rate", rate)?);
+                    match burst {
+                        Some(burst) => Some(limit.with_burst(parse("burst", burst)?)),
+                        None => Some(limit),
+                    }
+                }
+                None => None,
+            };
+            self = self.with_key(key, limit);
+        }
+        Ok(self)
+    }
+
+    /// Keys from `LANGGRAPH_API_KEY`, `LANGGRAPH_API_KEYS` and `key_file`.
+    pub fn load(default_limit: Option<RateLimit>, key_file: Option<&Path>) -> Result<Self, String> {
+        let mut keys = Self::new(default_limit);
+        for var in ["LANGGRAPH_API_KEY", "LANGGRAPH_API_KEYS"] {
+            if let Ok(value) = std::env::var(var) {
+                for key in value.split(',') {
+                    keys = keys.with_key(key, None);
+                }
+            }
+        }
+        if let Some(path) = key_file {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| format!("API key file {}: {}", path.display(), e))?;
+            keys = keys
+                .with_key_file(&contents)
+                .map_err(|e| format!("API key file {}: {}", path.display(), e))?;
+        }
+        Ok(keys)
+    }
+
+    /// Number of accepted keys; 0 leaves the server open.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// True when no key is configured and every request is let through.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Checks the `Authorization` header value and takes a token from the key's bucket.
+    pub fn authorize(&self, authorization: Option<&str>) -> Result<(), AuthError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let token = authorization
+            .and_then(|s| s.strip_prefix("Bearer "))
+            .map(str::trim)
+            .ok_or(AuthError::Unauthorized)?;
+        let bucket = self.keys.get(token).ok_or(AuthError::Unauthorized)?;
+        match bucket {
+            Some(bucket) => bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take(Instant::now())
+                .map_err(AuthError::RateLimited),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Lets requests through when no key is configured; otherwise requires a known
+/// `Authorization: Bearer <key>` (401) with tokens left in its bucket (429).
+pub async fn require_auth(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if let Err(e) = state.api_keys.authorize(authorization) {
+        if let AuthError::RateLimited(wait) = &e {
+            tracing::info!(
+                retry_after_ms = wait.as_millis() as u64,
+                "API key rate limited"
+            );
+        }
+        return Err(e.into_response());
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Without keys every request passes; with keys, missing or unknown keys are
+    /// refused and file entries take their own rate.
+    #[test]
+    fn keys_from_env_list_and_file() {
+        assert!(ApiKeys::default().authorize(None).is_ok());
+
+        let keys = ApiKeys::new(Some(RateLimit::per_minute(60)))
+            .with_key("sk-a", None)
+            .with_key_file("# team keys\nsk-b 1\n\nsk-c  # default limit\n")
+            .unwrap();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys.authorize(None), Err(AuthError::Unauthorized));
+        assert_eq!(keys.authorize(Some("sk-a")), Err(AuthError::Unauthorized));
+        assert_eq!(
+            keys.authorize(Some("Bearer sk-x")),
+            Err(AuthError::Unauthorized)
+        );
+        assert!(keys.authorize(Some("Bearer sk-a")).is_ok());
+        assert!(keys.authorize(Some("Bearer sk-c")).is_ok());
+
+        assert!(ApiKeys::default().with_key_file("sk-d fast").is_err());
+        assert!(ApiKeys::default().with_key_file("sk-d 60 lots").is_err());
+        assert!(ApiKeys::default().with_key_file("sk-d 60 5 9").is_err());
+    }
+
+    /// **Scenario**: A `#` inside a key is part of it; only one at the start of a line or after
+    /// whitespace starts a comment.
+    #[test]
+    fn key_file_keys_may_contain_hash() {
+        let keys = ApiKeys::default()
+            .with_key_file("sk-a#b 60 #per team\n  # sk-c\nsk-d#\n")
+            .unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.authorize(Some("Bearer sk-a#b")).is_ok());
+        assert!(keys.authorize(Some("Bearer sk-d#")).is_ok());
+        assert_eq!(
+            keys.authorize(Some("Bearer sk-a")),
+            Err(AuthError::Unauthorized)
+        );
+    }
+
+    /// **Scenario**: A key file entry with a burst gets its own burst, not the global one.
+    #[test]
+    fn key_file_entry_sets_its_own_burst() {
+        let keys = ApiKeys::new(Some(RateLimit::per_minute(60).with_burst(1)))
+            .with_key_file("sk-a 60 3\nsk-b\n")
+            .unwrap();
+        for _ in 0..3 {
+            assert!(keys.authorize(Some("Bearer sk-a")).is_ok());
+        }
+        assert!(matches!(
+            keys.authorize(Some("Bearer sk-a")),
+            Err(AuthError::RateLimited(_))
+        ));
+        assert!(keys.authorize(Some("Bearer sk-b")).is_ok());
+        assert!(matches!(
+            keys.authorize(Some("Bearer sk-b")),
+            Err(AuthError::RateLimited(_))
+        ));
+    }
+
+    /// **Scenario**: A bucket allows `burst` requests at once, then refuses with the time until
+    /// the next token, and refills at its per-minute rate.
+    #[test]
+    fn token_bucket_refills_at_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(RateLimit::per_minute(60).with_burst(2), start);
+        assert!(bucket.take(start).is_ok());
+        assert!(bucket.take(start).is_ok());
+        let wait = bucket.take(start).unwrap_err();
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+
+        assert!(bucket.take(start + Duration::from_secs(1)).is_ok());
+        assert!(bucket.take(start + Duration::from_secs(1)).is_err());
+        assert!(bucket.take(start + Duration::from_secs(10)).is_ok());
+        assert!(bucket.take(start + Duration::from_secs(10)).is_ok());
+        assert!(bucket.take(start + Duration::from_secs(10)).is_err());
+    }
+}
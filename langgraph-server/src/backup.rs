@@ -0,0 +1,153 @@
+//! Consistent backups of the SQLite checkpoint files while the server runs: POST /v1/admin/backup.
+//!
+//! Copying `memory.db` while runs write to it can capture a torn file; the endpoint uses SQLite's
+//! online backup API (`langgraph::memory::backup_sqlite`) instead, so chat keeps working during
+//! the backup. The body names the destination on the server's filesystem, `{"path": "..."}`:
+//!
+//! - With `--db-path`, `path` is the backup file.
+//! - With `--tenant-db-dir`, `path` is a directory (created if missing) receiving one backup per
+//!   user file, under the same names.
+//!
+//! Existing files are never overwritten (409). Checkpoints in Postgres or Redis are backed up
+//! with those databases' own tools (400). Like every admin endpoint, it needs `X-Admin-Key`.
+//!
+//! **Interaction**: `main` derives [`SqliteFiles`] from the build config into
+//! `AppState::sqlite_files`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{extract::State, http::HeaderMap, Json};
+use langgraph::memory::{backup_sqlite, BackupError};
+use langgraph::ReactBuildConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::modes::require_admin;
+use crate::{AppState, ServerError};
+
+const NOT_SQLITE: &str =
+    "checkpoints are not stored in SQLite; back up Postgres or Redis with their own tools";
+
+/// SQLite files holding the server's checkpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqliteFiles {
+    /// `--db-path`: one database for every thread.
+    Single(PathBuf),
+    /// `--tenant-db-dir`: one `<user>.db` per user.
+    PerUser(PathBuf),
+}
+
+impl SqliteFiles {
+    /// Files the checkpointer built from `config` writes; None when it uses Postgres or Redis.
+    pub fn from_build_config(config: &ReactBuildConfig) -> Option<Self> {
+        if config.redis_url.is_some() || config.database_url.is_some() {
+            return None;
+        }
+        Some(match config.tenant_db_dir.as_deref() {
+            Some(dir) => Self::PerUser(dir.into()),
+            None => Self::Single(config.db_path.as_deref().unwrap_or("memory.db").into()),
+        })
+    }
+}
+
+/// Body of POST /v1/admin/backup.
+#[derive(Debug, Deserialize)]
+pub struct BackupRequest {
+    pub path: String,
+}
+
+/// One backed-up database.
+#[derive(Debug, Serialize)]
+pub struct BackupFile {
+    pub source: String,
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// Result of POST /v1/admin/backup.
+#[derive(Debug, Serialize)]
+pub struct BackupReport {
+    pub files: Vec<BackupFile>,
+}
+
+fn backup_error(e: BackupError) -> ServerError {
+    match e {
+        BackupError::DestinationExists(_) => ServerError::Conflict(e.to_string()),
+        BackupError::SourceNotFound(_) => ServerError::NotFound(e.to_string()),
+        e => ServerError::Internal(format!("backup failed: {}", e)),
+    }
+}
+
+async fn backup_file(source: &Path, dest: &Path) -> Result<BackupFile, ServerError> {
+    let bytes = backup_sqlite(source, dest).await.map_err(backup_error)?;
+    Ok(BackupFile {
+        source: source.display().to_string(),
+        path: dest.display().to_string(),
+        bytes,
+    })
+}
+
+/// Backs up every `*.db` in `dir` into `dest_dir`, in name order.
+async fn backup_dir(dir: &Path, dest_dir: &Path) -> Result<Vec<BackupFile>, ServerError> {
+    let io_error = |e: std::io::Error| ServerError::Internal(format!("backup failed: {}", e));
+    let mut sources: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(io_error)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "db"))
+        .collect();
+    sources.sort();
+    std::fs::create_dir_all(dest_dir).map_err(io_error)?;
+    let mut files = Vec::with_capacity(sources.len());
+    for source in sources {
+        let name = source.file_name().unwrap_or_default();
+        files.push(backup_file(&source, &dest_dir.join(name)).await?);
+    }
+    Ok(files)
+}
+
+/// POST /v1/admin/backup: returns the backed-up files with their sizes.
+pub async fn backup(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<BackupRequest>,
+) -> Result<Json<BackupReport>, ServerError> {
+    require_admin(&state, &headers)?;
+    if req.path.trim().is_empty() {
+        return Err(ServerError::BadRequest("path must not be empty".into()));
+    }
+    let dest = PathBuf::from(&req.path);
+    let files = match &state.sqlite_files {
+        Some(SqliteFiles::Single(db)) => vec![backup_file(db, &dest).await?],
+        Some(SqliteFiles::PerUser(dir)) => backup_dir(dir, &dest).await?,
+        None => return Err(ServerError::BadRequest(NOT_SQLITE.into())),
+    };
+    tracing::warn!(path = %req.path, files = files.len(), "database backup taken");
+    Ok(Json(BackupReport { files }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Postgres or Redis leave nothing to back up; otherwise the tenant directory
+    /// wins over the single database file.
+    #[test]
+    fn sqlite_files_follow_checkpointer_choice() {
+        let mut config = ReactBuildConfig::from_env();
+        config.redis_url = None;
+        config.database_url = None;
+        config.tenant_db_dir = None;
+        config.db_path = Some("/data/chat.db".into());
+        assert_eq!(
+            SqliteFiles::from_build_config(&config),
+            Some(SqliteFiles::Single("/data/chat.db".into()))
+        );
+        config.tenant_db_dir = Some("/data/tenants".into());
+        assert_eq!(
+            SqliteFiles::from_build_config(&config),
+            Some(SqliteFiles::PerUser("/data/tenants".into()))
+        );
+        config.database_url = Some("postgres://localhost/langgraph".into());
+        assert_eq!(SqliteFiles::from_build_config(&config), None);
+    }
+}
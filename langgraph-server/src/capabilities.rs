@@ -0,0 +1,50 @@
+//! What this build of the server can do: GET /v1/capabilities.
+//!
+//! Lists langgraph's optional Cargo features (`lance`, `postgres`, `redis`, ...) and the
+//! server's own (`grpc`), each true or false, so clients and operators can check a deployment
+//! before relying on e.g. the Postgres checkpointer or the gRPC API. Answers
+//! `{"object": "capabilities", "version", "features": {feature: bool}}`. The same set is logged
+//! at startup as the `[Features]` line of the config summary, and settings needing a missing
+//! feature (`DATABASE_URL`, `REDIS_URL`, `--grpc-listen`) stop the server at startup with a
+//! `FeatureMissing` error.
+//!
+//! **Interaction**: [`server_features`] extends `langgraph::FeatureSet::compiled`;
+//! `config::effective_config` adds it to the summary and `main` checks `--grpc-listen`
+//! against it.
+
+use axum::Json;
+use langgraph::FeatureSet;
+use serde_json::{json, Value};
+
+/// langgraph's features plus the server's, as compiled into this binary.
+pub fn server_features() -> FeatureSet {
+    FeatureSet::compiled().with_feature("grpc", cfg!(feature = "grpc"))
+}
+
+/// GET /v1/capabilities: server version and features.
+pub async fn capabilities() -> Json<Value> {
+    Json(json!({
+        "object": "capabilities",
+        "version": env!("CARGO_PKG_VERSION"),
+        "features": server_features().to_json(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Every feature is listed with whether this build has it, the server's
+    /// `grpc` included.
+    #[tokio::test]
+    async fn lists_features_of_this_build() {
+        let Json(body) = capabilities().await;
+        assert_eq!(body["object"], "capabilities");
+        assert_eq!(body["features"]["grpc"], cfg!(feature = "grpc"));
+        assert_eq!(body["features"]["postgres"], cfg!(feature = "postgres"));
+        assert!(body["features"]["lance"].is_boolean());
+
+        let missing = server_features().require("grpc", "--grpc-listen");
+        assert_eq!(missing.is_ok(), cfg!(feature = "grpc"));
+    }
+}
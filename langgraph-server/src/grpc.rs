@@ -0,0 +1,409 @@
+//! The agent over gRPC (`grpc` feature): the service in `proto/agent.proto` on `--grpc-listen`.
+//!
+//! `Chat` runs a message to the end; `StreamChat` streams text deltas, tool calls, tool results
+//! and usage, ending with `done` or `error` (the same error types as the HTTP stream's final
+//! event). `CreateThread` / `ListThreads` / `GetThread` / `DeleteThread` mirror /v1/threads.
+//! Blank `thread_id` / `user_id` fields mean none, as absent JSON fields do over HTTP.
+//!
+//! Requests follow the HTTP rules: API keys in the `authorization` metadata (`Bearer <key>`)
+//! with their rate limits, read-only and maintenance mode, the run rate limit and daily quotas,
+//! and shutdown draining. Refusals map to gRPC codes: UNAUTHENTICATED, RESOURCE_EXHAUSTED for
+//! rate limits, budgets and quotas, UNAVAILABLE for maintenance and provider errors,
+//! DEADLINE_EXCEEDED for provider timeouts.
+//!
+//! **Interaction**: [`service`] wraps [`AgentService`] in the API key check; `main` serves it
+//! with tonic next to the axum router, sharing its `AppState`.
+
+use std::sync::Arc;
+
+use langgraph::{AgentError, Message, ReActState, RunError, RunnableConfig, StreamEvent};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tracing::Instrument;
+
+use crate::auth::{ApiKeys, AuthError};
+use crate::shutdown::SERVER_SHUTDOWN;
+use crate::threads::unix_secs;
+use crate::{AppState, ServerError, BUDGET_EXCEEDED, QUOTA_EXCEEDED};
+
+/// Types and service traits generated from `proto/agent.proto`.
+pub mod proto {
+    tonic::include_proto!("langgraph.agent.v1");
+}
+
+use proto::agent_server::{Agent, AgentServer};
+use proto::stream_event::Event;
+
+/// Interceptor refusing requests without an accepted API key, like `require_auth` over HTTP.
+#[derive(Clone)]
+pub struct ApiKeyCheck(Arc<ApiKeys>);
+
+impl Interceptor for ApiKeyCheck {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        match self.0.authorize(authorization) {
+            Ok(()) => Ok(request),
+            Err(AuthError::Unauthorized) => Err(Status::unauthenticated(
+                "invalid or missing API key; send authorization: Bearer <key>",
+            )),
+            Err(AuthError::RateLimited(wait)) => Err(Status::resource_exhausted(format!(
+                "rate limit reached for this API key; retry in {}s",
+                wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+            ))),
+        }
+    }
+}
+
+/// The gRPC service over `state`, behind the same API keys as the HTTP routes.
+pub fn service(state: Arc<AppState>) -> InterceptedService<AgentServer<AgentService>, ApiKeyCheck> {
+    let check = ApiKeyCheck(Arc::clone(&state.api_keys));
+    AgentServer::with_interceptor(AgentService::new(state), check)
+}
+
+/// Implements the `Agent` service on the server's runner.
+pub struct AgentService {
+    state: Arc<AppState>,
+}
+
+impl AgentService {
+    /// Service over `state`, without the API key check; see [`service`].
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+}
+
+impl From<ServerError> for Status {
+    fn from(e: ServerError) -> Self {
+        let message = e.to_string();
+        match e {
+            ServerError::BadRequest(_) | ServerError::Parse(_) => Status::invalid_argument(message),
+            ServerError::NotFound(_) => Status::not_found(message),
+            ServerError::Conflict(_) => Status::already_exists(message),
+            ServerError::Forbidden(_) => Status::permission_denied(message),
+            ServerError::BudgetExceeded(_)
+            | ServerError::QuotaExceeded(_)
+            | ServerError::RateLimited(_) => Status::resource_exhausted(message),
+            ServerError::Llm(e) => match e.http_status() {
+                429 => Status::resource_exhausted(message),
+                504 => Status::deadline_exceeded(message),
+                _ => Status::unavailable(message),
+            },
+            ServerError::Upstream(_) | ServerError::Unavailable(_) => Status::unavailable(message),
+            ServerError::Internal(_) => Status::internal(message),
+        }
+    }
+}
+
+/// Blank strings are absent.
+fn non_blank(s: &str) -> Option<String> {
+    Some(s.trim()).filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+fn run_config(request: &proto::ChatRequest) -> Result<RunnableConfig, Status> {
+    if request.message.trim().is_empty() {
+        return Err(Status::invalid_argument("message is required"));
+    }
+    Ok(RunnableConfig {
+        thread_id: non_blank(&request.thread_id),
+        user_id: non_blank(&request.user_id),
+        ..Default::default()
+    })
+}
+
+/// Final answer, else the last assistant reply.
+fn reply(state: &ReActState) -> String {
+    state
+        .final_answer()
+        .map(str::to_string)
+        .or_else(|| state.last_assistant_reply())
+        .unwrap_or_default()
+}
+
+fn tool_calls(message: &Message) -> Vec<proto::ToolCall> {
+    match message {
+        Message::Assistant { tool_calls, .. } => tool_calls
+            .iter()
+            .map(|call| proto::ToolCall {
+                id: call.id.clone().unwrap_or_default(),
+                name: call.name.clone(),
+                arguments: call.arguments.clone(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// One message in the OpenAI chat shape.
+fn message_proto(message: &Message) -> proto::Message {
+    let (tool_call_id, name) = match message {
+        Message::Tool { call_id, name, .. } => (
+            call_id.clone().unwrap_or_default(),
+            name.clone().unwrap_or_default(),
+        ),
+        _ => (String::new(), String::new()),
+    };
+    proto::Message {
+        role: message.role().to_string(),
+        content: message.content().to_string(),
+        tool_calls: tool_calls(message),
+        tool_call_id,
+        name,
+    }
+}
+
+/// The stream events for one runner event: text deltas, tool calls and results, usage.
+fn stream_events(event: StreamEvent<ReActState>) -> Vec<Event> {
+    match event {
+        StreamEvent::Messages { chunk, .. } if !chunk.content.is_empty() => {
+            vec![Event::Text(proto::TextDelta {
+                content: chunk.content,
+            })]
+        }
+        StreamEvent::MessagesDelta { messages, .. } => messages
+            .iter()
+            .flat_map(|message| match message {
+                Message::Assistant { .. } => tool_calls(message)
+                    .into_iter()
+                    .map(Event::ToolCall)
+                    .collect(),
+                Message::Tool {
+                    call_id,
+                    name,
+                    content,
+                } => vec![Event::ToolResult(proto::ToolResult {
+                    call_id: call_id.clone().unwrap_or_default(),
+                    name: name.clone().unwrap_or_default(),
+                    content: content.clone(),
+                })],
+                _ => Vec::new(),
+            })
+            .collect(),
+        StreamEvent::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        } => vec![Event::Usage(proto::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        })],
+        _ => Vec::new(),
+    }
+}
+
+/// The `error` event ending a failed stream; `kind` as in the HTTP stream's error event.
+fn error_event(e: &RunError, shutting_down: bool) -> Event {
+    let kind = match e {
+        RunError::Execution(AgentError::Cancelled) if shutting_down => SERVER_SHUTDOWN.to_string(),
+        RunError::BudgetExceeded(_) => BUDGET_EXCEEDED.to_string(),
+        RunError::QuotaExceeded(_) => QUOTA_EXCEEDED.to_string(),
+        RunError::Execution(AgentError::Llm(llm)) => llm.kind.to_string(),
+        _ => String::new(),
+    };
+    let message = if kind == SERVER_SHUTDOWN {
+        "server shut down before the run finished; retry later".to_string()
+    } else {
+        e.to_string()
+    };
+    Event::Error(proto::Error { message, kind })
+}
+
+#[tonic::async_trait]
+impl Agent for AgentService {
+    type StreamChatStream = ReceiverStream<Result<proto::StreamEvent, Status>>;
+
+    async fn chat(
+        &self,
+        request: Request<proto::ChatRequest>,
+    ) -> Result<Response<proto::ChatResponse>, Status> {
+        let request = request.into_inner();
+        let config = run_config(&request)?;
+        let thread_id = config.thread_id.clone().unwrap_or_default();
+        let _run_guard = self.state.modes.begin_run()?;
+        self.state.runtime.check_rate()?;
+        tracing::debug!(thread_id = %thread_id, "grpc chat");
+        let request_id = langgraph::memory::uuid6();
+        let runner = self.state.runner.get_for(&config, &request_id);
+        let state = tokio::select! {
+            biased;
+            res = runner.invoke_with_config(&request.message, Some(config)) => {
+                res.map_err(ServerError::from)?
+            }
+            _ = self.state.modes.wait_runs_stopped() => return Err(crate::shutdown_error().into()),
+        };
+        Ok(Response::new(proto::ChatResponse {
+            reply: reply(&state),
+            thread_id,
+        }))
+    }
+
+    async fn stream_chat(
+        &self,
+        request: Request<proto::ChatRequest>,
+    ) -> Result<Response<Self::StreamChatStream>, Status> {
+        let request = request.into_inner();
+        let config = run_config(&request)?;
+        let thread_id = config.thread_id.clone().unwrap_or_default();
+        let run_guard = self.state.modes.begin_run()?;
+        self.state.runtime.check_rate()?;
+        let request_id = langgraph::memory::uuid6();
+        let runner = self.state.runner.get_for(&config, &request_id);
+        // Refused before the stream opens, like the HTTP stream.
+        runner
+            .check_quota(Some(&config))
+            .await
+            .map_err(ServerError::from)?;
+        tracing::debug!(thread_id = %thread_id, "grpc stream chat");
+
+        // Use a large buffer so text deltas are not dropped when the client reads slowly.
+        let (tx, rx) = mpsc::channel(2048);
+        let modes = Arc::clone(&self.state.modes);
+        let cancel = modes.run_token();
+        let run = async move {
+            let _run_guard = run_guard;
+            let events = tx.clone();
+            // The client hanging up closes the channel; stop the run instead of finishing it
+            // for nobody.
+            let closed = events.clone();
+            let stop_on_close = cancel.clone();
+            let watch = tokio::spawn(async move {
+                closed.closed().await;
+                stop_on_close.cancel();
+            });
+            let res = runner
+                .stream_with_cancellation(
+                    &request.message,
+                    Some(config),
+                    cancel,
+                    Some(|ev| {
+                        for event in stream_events(ev) {
+                            let _ = events.try_send(Ok(proto::StreamEvent { event: Some(event) }));
+                        }
+                    }),
+                )
+                .await;
+            watch.abort();
+            let last = match res {
+                Ok(state) => Event::Done(proto::Done {
+                    reply: reply(&state),
+                    thread_id,
+                }),
+                Err(RunError::Execution(AgentError::Cancelled)) if !modes.runs_stopped() => {
+                    tracing::info!("grpc client disconnected; run cancelled");
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!("grpc stream error: {}", e);
+                    error_event(&e, modes.runs_stopped())
+                }
+            };
+            let _ = tx.send(Ok(proto::StreamEvent { event: Some(last) })).await;
+        };
+        tokio::spawn(run.instrument(tracing::Span::current()));
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn create_thread(
+        &self,
+        request: Request<proto::CreateThreadRequest>,
+    ) -> Result<Response<proto::Thread>, Status> {
+        self.state.modes.check_writable()?;
+        let request = request.into_inner();
+        let thread_id =
+            non_blank(&request.thread_id).unwrap_or_else(|| langgraph::memory::uuid6().to_string());
+        let created = self
+            .state
+            .runner
+            .get()
+            .create_thread(&thread_id, non_blank(&request.user_id).as_deref())
+            .await
+            .map_err(ServerError::from)?;
+        if !created {
+            return Err(
+                ServerError::Conflict(format!("thread {} already exists", thread_id)).into(),
+            );
+        }
+        Ok(Response::new(proto::Thread {
+            thread_id,
+            last_activity_at: 0,
+        }))
+    }
+
+    async fn list_threads(
+        &self,
+        request: Request<proto::ListThreadsRequest>,
+    ) -> Result<Response<proto::ListThreadsResponse>, Status> {
+        let user_id = non_blank(&request.get_ref().user_id);
+        let threads = self
+            .state
+            .runner
+            .get()
+            .list_threads(user_id.as_deref())
+            .await
+            .map_err(ServerError::from)?;
+        Ok(Response::new(proto::ListThreadsResponse {
+            threads: threads
+                .into_iter()
+                .map(|thread| proto::Thread {
+                    last_activity_at: thread.last_activity.map(unix_secs).unwrap_or(0),
+                    thread_id: thread.thread_id,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_thread(
+        &self,
+        request: Request<proto::GetThreadRequest>,
+    ) -> Result<Response<proto::ThreadMessages>, Status> {
+        let request = request.into_inner();
+        let config = RunnableConfig {
+            thread_id: Some(request.thread_id.clone()),
+            user_id: non_blank(&request.user_id),
+            ..Default::default()
+        };
+        let Some(state) = self
+            .state
+            .runner
+            .get()
+            .get_state(Some(config))
+            .await
+            .map_err(ServerError::from)?
+        else {
+            return Err(ServerError::NotFound(format!("thread {}", request.thread_id)).into());
+        };
+        Ok(Response::new(proto::ThreadMessages {
+            thread_id: request.thread_id,
+            messages: state.messages.iter().map(message_proto).collect(),
+        }))
+    }
+
+    async fn delete_thread(
+        &self,
+        request: Request<proto::DeleteThreadRequest>,
+    ) -> Result<Response<proto::DeleteThreadResponse>, Status> {
+        self.state.modes.check_writable()?;
+        let request = request.into_inner();
+        let thread_id = request.thread_id;
+        let deleted = self
+            .state
+            .runner
+            .get()
+            .delete_thread(&thread_id, non_blank(&request.user_id).as_deref())
+            .await
+            .map_err(ServerError::from)?;
+        if !deleted {
+            return Err(ServerError::NotFound(format!("thread {}", thread_id)).into());
+        }
+        Ok(Response::new(proto::DeleteThreadResponse {
+            thread_id,
+            deleted,
+        }))
+    }
+}
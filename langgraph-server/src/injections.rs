@@ -0,0 +1,127 @@
+//! Steering a running chat stream: POST /v1/runs/{run_id}/messages.
+//!
+//! While a streaming chat completion runs, the client can send a follow-up user message
+//! ("actually, search in French") without stopping it. The message is queued on the run's
+//! `langgraph::Injector`; after the current ReAct round finishes (`observe`), it is appended to
+//! the conversation and the model thinks again, on the same stream. The run id is the `id` of
+//! the chat completion chunks (`chatcmpl-...`). Finished or unknown runs answer 404; a run that
+//! ends while the message is being queued answers 409.
+//!
+//! **Interaction**: `chat_completions` registers each stream's injector in
+//! `AppState::injectors` and runs it with `ReactRunner::stream_with_injector`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use langgraph::{InjectError, Injector, Message, ReActState};
+use serde::Deserialize;
+
+use crate::{AppState, ServerError};
+
+/// Injectors of the chat streams in flight, by run id.
+#[derive(Default)]
+pub struct RunInjectors {
+    runs: Mutex<HashMap<String, Injector<ReActState>>>,
+}
+
+impl RunInjectors {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Injector<ReActState>>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers a new injector for `run_id`; it is unregistered when the guard is dropped.
+    pub fn register(self: &Arc<Self>, run_id: &str) -> InjectorGuard {
+        let injector = Injector::new();
+        self.lock().insert(run_id.to_string(), injector.clone());
+        InjectorGuard {
+            runs: Arc::clone(self),
+            run_id: run_id.to_string(),
+            injector,
+        }
+    }
+
+    /// Injector of the run, None when it is unknown or finished.
+    pub fn get(&self, run_id: &str) -> Option<Injector<ReActState>> {
+        self.lock().get(run_id).cloned()
+    }
+}
+
+/// Keeps a run's injector registered; dropping it closes the injector and unregisters it.
+pub struct InjectorGuard {
+    runs: Arc<RunInjectors>,
+    run_id: String,
+    injector: Injector<ReActState>,
+}
+
+impl InjectorGuard {
+    pub fn injector(&self) -> Injector<ReActState> {
+        self.injector.clone()
+    }
+}
+
+impl Drop for InjectorGuard {
+    fn drop(&mut self) {
+        self.injector.close();
+        self.runs.lock().remove(&self.run_id);
+    }
+}
+
+/// Body of POST /v1/runs/{run_id}/messages.
+#[derive(Debug, Deserialize)]
+pub struct InjectMessageRequest {
+    pub content: String,
+}
+
+/// Queues a user message for the run. Returns 202 with `{"run_id", "queued": true}`; 400 for
+/// an empty message, 404 when the run is unknown or finished, 409 when it finished meanwhile.
+pub async fn inject_message(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+    Json(body): Json<InjectMessageRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ServerError> {
+    if body.content.trim().is_empty() {
+        return Err(ServerError::BadRequest("content must not be empty".into()));
+    }
+    let injector = state
+        .injectors
+        .get(&run_id)
+        .ok_or_else(|| ServerError::NotFound(format!("no running stream {}", run_id)))?;
+    let content = body.content;
+    injector
+        .push(move |s: &mut ReActState| s.messages.push(Message::user(content)))
+        .map_err(|e| match e {
+            InjectError::RunFinished => ServerError::Conflict(format!("run {}: {}", run_id, e)),
+        })?;
+    tracing::info!(run_id = %run_id, "user message queued for running stream");
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({ "run_id": run_id, "queued": true })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: A registered run is found until its guard drops; dropping closes the
+    /// injector, so a push through a handle taken earlier is refused.
+    #[test]
+    fn guard_unregisters_and_closes() {
+        let runs = Arc::new(RunInjectors::default());
+        let guard = runs.register("chatcmpl-1");
+        let injector = runs.get("chatcmpl-1").expect("registered");
+        assert!(runs.get("chatcmpl-2").is_none());
+
+        drop(guard);
+        assert!(runs.get("chatcmpl-1").is_none());
+        assert_eq!(
+            injector.push(|s: &mut ReActState| s.turn_count += 1),
+            Err(InjectError::RunFinished)
+        );
+    }
+}
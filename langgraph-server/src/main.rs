@@ -1,8 +1,77 @@
-//! HTTP server exposing POST /v1/chat/completions with OpenAI-compatible SSE streaming.
+//! HTTP server exposing POST /v1/chat/completions with OpenAI-compatible SSE streaming, or a
+//! single `chat.completion` JSON body for `stream: false`. Each request runs on its own thread:
+//! `X-Thread-Id` / `X-User-Id` headers, else the body's `thread_id` / `user` (or
+//! `metadata.thread_id` / `metadata.user_id`); requests naming no thread are not checkpointed.
 //!
-//! Configure via env: OPENAI_API_KEY, OPENAI_MODEL, OPENAI_BASE_URL, DB_PATH, THREAD_ID, etc.
-//! Optional LANGGRAPH_API_KEY: when set, requests must send Authorization: Bearer <key>.
+//! POST /v1/threads/{thread_id}/resume continues a thread paused by `REACT_INTERRUPT_BEFORE`
+//! with a human-provided value. POST /v1/threads/{thread_id}/title and .../suggestions generate
+//! a conversation title and follow-up questions with the title model (see `thread_meta`).
+//! GET /v1/runs/{run_id}/events replays the SSE events a finished chat stream sent, for
+//! debugging garbled streams (see `transcripts`). POST /v1/runs/{run_id}/messages sends a
+//! follow-up user message into a chat stream still running (see `injections`). GET/POST
+//! /v1/admin/mode report and toggle read-only and maintenance mode for backups and migrations
+//! (see `modes`). /v1/threads creates, lists, shows and deletes checkpointed threads (see
+//! `threads`); POST /v1/threads/{thread_id}/messages imports earlier messages into one.
+//! GET/POST /v1/admin/config change log level, run rate limit, enabled tools, model
+//! allowlist and run budget without a restart; GET /v1/admin/audit lists those changes (see
+//! `runtime_config`). POST /v1/admin/backup takes a consistent copy of the SQLite checkpoint
+//! files while the server runs (see `backup`). GET /metrics serves graph runtime metrics in the
+//! Prometheus text format (see `metrics`). POST /mcp serves the agent as an MCP tool,
+//! `ask_agent`, to MCP clients; `--mcp-stdio` serves it on stdin/stdout instead (see
+//! `mcp_server`). With the `grpc` feature, `--grpc-listen` also serves chat runs (unary and
+//! server-streaming) and thread management over gRPC (see `grpc` and `proto/agent.proto`).
+//! Finished runs are labelled (answered, needed_tools, failed_tool, refused,
+//! hallucination_suspected) in their checkpoint's tags per `--outcome-classifier`; GET
+//! /v1/analytics/outcomes counts the labels (see `analytics`). `--experiment` runs an A/B
+//! experiment: each thread (or request) runs on variant a, the configured agent, or b, the
+//! agent with the `--experiment-*` model, prompt or MCP servers, picked by a hash of its id;
+//! runs are tagged with their variant, usage is recorded per variant, and the outcome analytics
+//! break down by variant. GET /v1/capabilities lists the optional features this binary was
+//! built with (see `capabilities`).
+//!
+//! `--mock-llm` / `TEST_MODE` answers with a scripted mock LLM and mock tools and never calls
+//! an external API, for black-box HTTP tests and demos (see `test_mode`).
+//!
+//! SIGTERM / SIGINT stop accepting connections and let runs in flight finish within
+//! `--drain-timeout-secs` before stopping them (see `shutdown`). SIGHUP reads `.env` again and
+//! rebuilds the agent (model, prompt, tools) for the runs started afterwards (see `reload`).
+//!
+//! Classified LLM provider errors (rate limit, overload, timeout) are answered with 429 / 503 /
+//! 504 and a `Retry-After` header instead of 500; a streaming response, already sent as 200,
+//! reports them in a final `data: {"error": ...}` event.
+//!
+//! With `--daily-token-cap` / `--daily-user-token-cap`, tokens are counted per model and day
+//! (next to the checkpoints in `--db-path`); once the server or the request's user used the
+//! day's tokens, chat requests answer 429 with type `quota_exceeded`, a message for the user
+//! and `Retry-After` until midnight UTC.
+//!
+//! Configure via env: OPENAI_API_KEY, OPENAI_MODEL, OPENAI_BASE_URL, DB_PATH, THREAD_ID,
+//! REACT_PROMPT_PROFILE, REACT_MAX_TURNS, etc.
+//! Optional LANGGRAPH_API_KEY / LANGGRAPH_API_KEYS / API_KEYS_FILE: when any key is set, requests
+//! must send Authorization: Bearer <key>, rate-limited per key (see `auth`).
+//! Optional ADMIN_API_KEY: enables the admin endpoints for requests sending X-Admin-Key: <key>.
 //! See langgraph's ReactBuildConfig::from_env(). Load .env with dotenv.
+//! Command-line flags (`--model`, `--db-path`, `--listen`, ...; see `--help`)
+//! override the env; the effective config is logged at startup.
+
+mod analytics;
+mod auth;
+mod backup;
+mod capabilities;
+mod config;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod injections;
+mod mcp_server;
+mod metrics;
+mod modes;
+mod reload;
+mod runtime_config;
+mod shutdown;
+mod test_mode;
+mod thread_meta;
+mod threads;
+mod transcripts;
 
 use std::io::{self, Write};
 use std::sync::Arc;
@@ -10,60 +79,72 @@ use std::sync::Arc;
 use axum::{
     body::{to_bytes, Body},
     extract::{Path, State},
-    http::{Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use bytes::Bytes;
+use clap::Parser;
 use langgraph::{
-    build_react_run_context, parse_chat_request, ChunkMeta, ParseError, ReactBuildConfig,
-    ReactRunner, StreamToSse,
+    build_react_run_context_with_read_only, parse_chat_request, AgentError, AllowlistToolSource,
+    ChatCompletion, ChunkMeta, GraphInterrupt, LlmClient, LlmError, LlmOutcomeClassifier,
+    MemoryWriter, ParseError, ParsedChatRequest, PrometheusRecorder, QuotaExceeded,
+    ReactBuildConfig, ReactRunner, ReadOnlySwitch, RefreshingToolSource, RuleOutcomeClassifier,
+    RunError, RunnableConfig, StreamToSse, ToolAllowlist, ToolCatalog, ToolSource, UsageQuota,
 };
 use tokio::sync::mpsc;
 use tokio_stream::{StreamExt, wrappers::ReceiverStream};
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use tracing::{info, info_span};
+use tracing::{info, info_span, warn, Instrument};
+
+use auth::{require_auth, ApiKeys};
+use backup::{backup, SqliteFiles};
+use config::{effective_config, log_config, Args, OutcomeClassifierKind, DEFAULT_MODEL};
+use injections::{inject_message, RunInjectors};
+use modes::{get_mode, set_mode, ServerModes, MAINTENANCE_RETRY_AFTER_SECS};
+use reload::{Agent, RunnerSlot};
+use runtime_config::{
+    get_audit, get_config, set_config, BudgetSettings, LogReloadHandle, RuntimeBudget,
+    RuntimeConfig, RuntimeSettings, DEFAULT_LOG_FILTER,
+};
+use shutdown::{drain_on_signal, SERVER_SHUTDOWN};
+use thread_meta::{thread_suggestions, thread_title};
+use threads::{append_messages, create_thread, delete_thread, get_thread, list_threads};
+use transcripts::{run_events, TranscriptStore};
 
 /// Shared state for all routes: runner for chat completions, and config for /v1/models proxy.
 struct AppState {
-    runner: Arc<ReactRunner>,
+    /// The agent; replaced when the config is reloaded (SIGHUP, see `reload`).
+    runner: RunnerSlot,
+    /// Model without tools for thread titles and suggestions (`--title-model` / `TITLE_MODEL`).
+    title_llm: Arc<dyn LlmClient>,
     openai_base_url: Option<String>,
     openai_api_key: String,
     http_client: reqwest::Client,
-    /// When set, requests must include `Authorization: Bearer <this key>` (OpenAI-style). From env `LANGGRAPH_API_KEY`.
-    expected_api_key: Option<String>,
+    /// Compressed SSE transcripts of recent chat streams, for GET /v1/runs/{run_id}/events.
+    transcripts: Arc<TranscriptStore>,
+    /// Accepted `Authorization: Bearer <key>` keys and their rate limits; empty = open server.
+    api_keys: Arc<ApiKeys>,
+    /// Read-only / maintenance mode and runs in flight.
+    modes: Arc<ServerModes>,
+    /// Key for /v1/admin/* (`X-Admin-Key`); the endpoints are disabled when unset. From env `ADMIN_API_KEY`.
+    admin_api_key: Option<String>,
+    /// Settings changed at runtime through /v1/admin/config.
+    runtime: Arc<RuntimeConfig>,
+    /// Graph runtime metrics served at GET /metrics; langgraph's process-wide recorder.
+    metrics: Arc<PrometheusRecorder>,
+    /// Injectors of the chat streams in flight, for POST /v1/runs/{run_id}/messages.
+    injectors: Arc<RunInjectors>,
+    /// SQLite checkpoint files for POST /v1/admin/backup; None with Postgres or Redis.
+    sqlite_files: Option<SqliteFiles>,
 }
 
 /// Max request body size to buffer for logging (bytes). Requests larger than this return 413.
 const LOG_BODY_LIMIT: usize = 2 * 1024 * 1024;
 
-/// If `expected_api_key` is set, requires `Authorization: Bearer <key>`; otherwise returns 401.
-async fn require_auth(
-    State(state): State<Arc<AppState>>,
-    request: Request<Body>,
-    next: Next,
-) -> Result<Response, Response> {
-    let expected = match &state.expected_api_key {
-        None => return Ok(next.run(request).await),
-        Some(k) => k.as_str(),
-    };
-    let auth = request
-        .headers()
-        .get(axum::http::header::AUTHORIZATION)
-        .and_then(|v| v.to_str().ok());
-    let token = auth.and_then(|s| s.strip_prefix("Bearer ")).map(str::trim);
-    if token != Some(expected) {
-        let body = Json(serde_json::json!({
-            "error": { "message": "Invalid or missing API key. Set Authorization: Bearer <key>." }
-        }));
-        return Ok((StatusCode::UNAUTHORIZED, body).into_response());
-    }
-    Ok(next.run(request).await)
-}
-
 /// Middleware that logs method and URI at debug, then forwards the request.
 async fn log_request_body(request: Request<Body>, next: Next) -> Result<Response, Response> {
     let (parts, body) = request.into_parts();
@@ -78,6 +159,46 @@ async fn log_request_body(request: Request<Body>, next: Next) -> Result<Response
     Ok(next.run(request).await)
 }
 
+/// Span of one HTTP request; with the `otel` feature it continues the caller's trace when the
+/// request carries a W3C `traceparent` header.
+fn request_span(req: &Request<Body>) -> tracing::Span {
+    let span = info_span!("request", method = %req.method(), uri = %req.uri());
+    #[cfg(feature = "otel")]
+    if let Some(traceparent) = req
+        .headers()
+        .get("traceparent")
+        .and_then(|v| v.to_str().ok())
+    {
+        langgraph::otel::set_parent_from_traceparent(&span, traceparent);
+    }
+    span
+}
+
+/// CORS for browser clients on `origins` (`*` allows any origin); None for no origins, so
+/// browsers only call the API from its own origin.
+fn cors_layer(origins: &[String]) -> Result<Option<CorsLayer>, String> {
+    if origins.is_empty() {
+        return Ok(None);
+    }
+    if origins.iter().any(|o| o == "*") {
+        return Ok(Some(CorsLayer::permissive()));
+    }
+    let origins = origins
+        .iter()
+        .map(|o| {
+            o.parse::<axum::http::HeaderValue>()
+                .map_err(|_| format!("invalid CORS origin {:?}", o))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_methods(Any)
+            .allow_headers(Any)
+            .expose_headers(Any),
+    ))
+}
+
 /// Load .env from current directory; if not found, try parent (workspace root when run from crate dir).
 fn load_dotenv() {
     if dotenv::dotenv().is_ok() {
@@ -168,28 +289,50 @@ impl<W: Write> Write for StripAnsiWriter<W> {
     }
 }
 
-/// Initializes tracing: always to stdout; if env `LOG_FILE` is set, also to that file (append).
+/// Initializes tracing: always to stdout; if `log_file` is set, also to that file (append).
 /// File output is plain text (ANSI stripped) and uses a compact, readable format.
-fn init_tracing() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Returns the handle for changing the log filter at runtime (see `runtime_config`).
+fn init_tracing(
+    log_file: Option<&str>,
+    to_stderr: bool,
+) -> Result<LogReloadHandle, Box<dyn std::error::Error + Send + Sync>> {
     use tracing_subscriber::layer::SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
-    use tracing_subscriber::Layer;
 
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-        tracing_subscriber::EnvFilter::new("info,langgraph_server=debug")
-    });
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(DEFAULT_LOG_FILTER));
+    let (filter, log_handle) = tracing_subscriber::reload::Layer::new(filter);
 
-    let stdout_layer = tracing_subscriber::fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_filter(filter.clone());
+    // Over MCP stdio, stdout carries the protocol; logs go to stderr.
+    let console = if to_stderr {
+        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stderr)
+    } else {
+        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stdout)
+    };
+    let stdout_layer = tracing_subscriber::fmt::layer().with_writer(console);
+
+    // Spans of requests and runs go to an OTLP collector when OTEL_EXPORTER_OTLP_ENDPOINT is set.
+    #[cfg(feature = "otel")]
+    let otel_config = langgraph::otel::OtelConfig::from_env()
+        .map(|config| config.with_service_name(otel_service_name()));
+    #[cfg(feature = "otel")]
+    let otel_layer = match &otel_config {
+        Some(config) => Some(langgraph::otel::otlp_layer(config)?),
+        None => None,
+    };
+    #[cfg(not(feature = "otel"))]
+    let otel_layer = None::<tracing_subscriber::layer::Identity>;
 
-    let registry = tracing_subscriber::registry().with(stdout_layer);
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout_layer)
+        .with(otel_layer);
 
-    if let Ok(path) = std::env::var("LOG_FILE") {
+    if let Some(path) = log_file {
         let file = std::fs::OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&path)?;
+            .open(path)?;
         let plain_writer = std::sync::Mutex::new(StripAnsiWriter::new(file));
         let file_layer = tracing_subscriber::fmt::layer()
             .with_writer(plain_writer)
@@ -198,121 +341,491 @@ fn init_tracing() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             .with_level(true)
             .with_thread_ids(false)
             .with_file(false)
-            .with_line_number(false)
-            .with_filter(filter);
+            .with_line_number(false);
         registry.with(file_layer).init();
         tracing::info!(path = %path, "logging to file");
     } else {
         registry.init();
     }
-    Ok(())
+    #[cfg(feature = "otel")]
+    if let Some(config) = &otel_config {
+        tracing::info!(endpoint = %config.endpoint, "exporting traces over OTLP");
+    }
+    Ok(log_handle)
+}
+
+/// `OTEL_SERVICE_NAME`, else `langgraph-server`.
+#[cfg(feature = "otel")]
+fn otel_service_name() -> String {
+    std::env::var("OTEL_SERVICE_NAME")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| "langgraph-server".to_string())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     load_dotenv();
+    let args = Args::parse();
 
-    // Log file is only used when LOG_FILE is set (e.g. in .env). Use absolute path if relative path doesn't create file.
-    if std::env::var("LOG_FILE").is_err() {
-        eprintln!("langgraph-server: LOG_FILE not set, logs only to stdout. Set LOG_FILE=./langgraph-server.log in .env or env to also write to a file.");
+    // Log file is only used when --log-file or LOG_FILE is set (e.g. in .env). Use absolute path if relative path doesn't create file.
+    let log_file = args.log_file();
+    if log_file.is_none() {
+        eprintln!("langgraph-server: LOG_FILE not set, logs only to stdout. Pass --log-file or set LOG_FILE=./langgraph-server.log in .env or env to also write to a file.");
     }
 
-    init_tracing()?;
+    let log_handle = init_tracing(log_file.as_deref(), args.mcp_stdio())?;
 
-    let mut build_config = ReactBuildConfig::from_env();
-    // Prefer OPENAI_API_BASE (langgraph-cli / common .env) if OPENAI_BASE_URL not set.
-    if build_config.openai_base_url.is_none() {
-        if let Ok(base) = std::env::var("OPENAI_API_BASE") {
-            build_config.openai_base_url = Some(base);
-        }
-    }
-    if build_config.thread_id.is_none() {
-        build_config.thread_id = Some("default".to_string());
+    let build_config = load_build_config(&args)?;
+    // Settings needing a feature this binary lacks fail here, not when first used.
+    build_config.check_features()?;
+    if args.grpc_listen().is_some() {
+        capabilities::server_features().require("grpc", "--grpc-listen")?;
     }
-    if build_config.openai_api_key.is_none() || build_config.openai_api_key.as_deref() == Some("") {
-        return Err("OPENAI_API_KEY must be set".into());
+    let mock_llm = args.mock_llm();
+    if mock_llm {
+        warn!("test mode: answering with a scripted mock LLM and mock tools");
     }
 
     let model = build_config
         .model
         .clone()
-        .unwrap_or_else(|| "gpt-4o-mini".to_string());
-    let db_path = build_config
-        .db_path
-        .as_deref()
-        .unwrap_or("memory.db");
-    info!(
-        model = %model,
-        base_url = ?build_config.openai_base_url,
-        thread_id = ?build_config.thread_id,
-        user_id = ?build_config.user_id,
-        db_path = %db_path,
-        "LLM and runtime config loaded"
-    );
-
-    let ctx = build_react_run_context(&build_config).await.map_err(|e| e.to_string())?;
-    let mut openai_config = async_openai::config::OpenAIConfig::new()
-        .with_api_key(build_config.openai_api_key.clone().unwrap_or_default());
-    if let Some(ref base) = build_config.openai_base_url {
-        // Strip trailing slash so async_openai's url(base + "/chat/completions") does not become .../v4//chat/completions (some backends reject double slash).
-        let base = base.trim_end_matches('/');
-        openai_config = openai_config.with_api_base(base);
-    }
-    let llm = langgraph::ChatOpenAI::new_with_tool_source(
-        openai_config,
-        model.clone(),
-        ctx.tool_source.as_ref(),
-    )
-    .await?;
-    let llm: Box<dyn langgraph::LlmClient> = Box::new(llm);
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let title_model = args.title_model(&model);
+    let listen = args.listen();
+    let transcript_runs = args.transcript_runs();
+    let api_keys = ApiKeys::load(args.key_rate_limit(), args.api_keys_file().as_deref())?;
+    let cors_origins = args.cors_origins();
+    let cors = cors_layer(&cors_origins)?;
+    log_config(&effective_config(
+        &build_config,
+        &title_model,
+        &listen,
+        log_file.as_deref(),
+        !api_keys.is_empty(),
+        transcript_runs,
+    ));
 
-    let runner = ReactRunner::new(
-        llm,
-        ctx.tool_source,
-        ctx.checkpointer,
-        ctx.store,
-        None,
-        None,
-        false,
-    )?;
+    let read_only = ReadOnlySwitch::new(args.read_only());
+    let maintenance = args.maintenance();
+    let drain_timeout = args.drain_timeout();
+    if read_only.is_read_only() || maintenance {
+        warn!(
+            read_only = read_only.is_read_only(),
+            maintenance = maintenance,
+            "starting in restricted mode"
+        );
+    }
+    let admin_api_key = std::env::var("ADMIN_API_KEY")
+        .ok()
+        .filter(|s| !s.is_empty());
+    let title_llm: Arc<dyn LlmClient> = if mock_llm {
+        Arc::new(test_mode::llm())
+    } else {
+        Arc::new(
+            langgraph::ChatOpenAI::with_config(openai_config(&build_config), title_model)
+                .with_http_client(build_config.http_client.shared_client()?),
+        )
+    };
+    // Budget, rate limit, enabled tools, model allowlist and log level can change at runtime
+    // through /v1/admin/config; the runner reads them through `runtime`.
+    let budget_model = if build_config.uses_azure_openai() {
+        build_config.azure_openai_deployment.clone()
+    } else {
+        Some(model.clone())
+    };
+    let runtime = Arc::new(
+        RuntimeConfig::new(
+            RuntimeSettings {
+                log_level: log_handle
+                    .with_current(|filter| filter.to_string())
+                    .unwrap_or_else(|_| DEFAULT_LOG_FILTER.to_string()),
+                budget: BudgetSettings {
+                    max_tokens: build_config.max_run_tokens,
+                    max_cost_usd: build_config.max_run_cost_usd,
+                    max_tool_calls: build_config.max_run_tool_calls,
+                },
+                ..Default::default()
+            },
+            budget_model,
+            ToolAllowlist::new(),
+        )
+        .with_log_handle(log_handle),
+    );
+    let agent = build_agent(&args, &build_config, &read_only, &runtime, &title_llm).await?;
 
     let http_client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
         .build()
         .map_err(|e| e.to_string())?;
-    let expected_api_key = std::env::var("LANGGRAPH_API_KEY").ok().filter(|s| !s.is_empty());
-    if expected_api_key.is_some() {
-        info!("request auth enabled (LANGGRAPH_API_KEY set); require Authorization: Bearer <key>");
+    let metrics = Arc::new(PrometheusRecorder::new());
+    if langgraph::metrics::set_recorder(metrics.clone()).is_err() {
+        warn!("a metrics recorder is already installed; /metrics reports no graph metrics");
     }
+    if !api_keys.is_empty() {
+        info!(
+            keys = api_keys.len(),
+            rate_limit = ?args.key_rate_limit(),
+            "request auth enabled; require Authorization: Bearer <key>"
+        );
+    }
+    if args.mcp_stdio() {
+        info!("serving MCP on stdio");
+        mcp_server::serve_stdio(agent.runner(), tokio::io::stdin(), tokio::io::stdout()).await?;
+        return Ok(());
+    }
+
     let state = Arc::new(AppState {
-        runner: Arc::new(runner),
+        runner: RunnerSlot::new(agent),
+        title_llm,
         openai_base_url: build_config.openai_base_url.clone(),
         openai_api_key: build_config.openai_api_key.clone().unwrap_or_default(),
         http_client,
-        expected_api_key,
+        transcripts: Arc::new(TranscriptStore::new(transcript_runs)),
+        api_keys: Arc::new(api_keys),
+        modes: Arc::new(ServerModes::new(read_only.clone(), maintenance)),
+        admin_api_key,
+        runtime,
+        metrics,
+        injectors: Arc::new(RunInjectors::default()),
+        sqlite_files: SqliteFiles::from_build_config(&build_config),
     });
     let app = Router::new()
         .route("/v1/models", get(models_list))
         .route("/v1/models/:model_id", get(model_retrieve))
         .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/threads", get(list_threads).post(create_thread))
+        .route(
+            "/v1/threads/:thread_id",
+            get(get_thread).delete(delete_thread),
+        )
+        .route("/v1/threads/:thread_id/messages", post(append_messages))
+        .route("/v1/threads/:thread_id/resume", post(resume_thread))
+        .route("/v1/threads/:thread_id/title", post(thread_title))
+        .route("/v1/threads/:thread_id/suggestions", post(thread_suggestions))
+        .route("/v1/analytics/outcomes", get(analytics::outcomes))
+        .route("/v1/capabilities", get(capabilities::capabilities))
+        .route("/v1/runs/:run_id/events", get(run_events))
+        .route("/v1/runs/:run_id/messages", post(inject_message))
+        .route("/v1/admin/mode", get(get_mode).post(set_mode))
+        .route("/v1/admin/config", get(get_config).post(set_config))
+        .route("/v1/admin/audit", get(get_audit))
+        .route("/v1/admin/backup", post(backup))
+        .route("/metrics", get(metrics::metrics))
+        .route("/mcp", post(mcp_server::mcp_http))
         .layer(middleware::from_fn_with_state(state.clone(), require_auth))
         .layer(middleware::from_fn(log_request_body))
-        .layer(
-            TraceLayer::new_for_http()
-                .make_span_with(|req: &axum::http::Request<axum::body::Body>| {
-                    info_span!("request", method = %req.method(), uri = %req.uri())
-                }),
-        )
-        .layer(CorsLayer::permissive())
-        .with_state(state);
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .with_state(Arc::clone(&state));
+    let app = match cors {
+        Some(cors) => {
+            info!(origins = ?cors_origins, "CORS enabled");
+            app.layer(cors)
+        }
+        None => app,
+    };
 
-    let listen = std::env::var("LISTEN").unwrap_or_else(|_| "0.0.0.0:8123".to_string());
+    if let Some(grpc_listen) = args.grpc_listen() {
+        serve_grpc(&grpc_listen, Arc::clone(&state)).await?;
+    }
+    #[cfg(unix)]
+    tokio::spawn(reload::reload_on_sighup(
+        Arc::clone(&state),
+        args,
+        read_only,
+    ));
     info!("listening on http://{}", listen);
     let listener = tokio::net::TcpListener::bind(&listen).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(drain_on_signal(Arc::clone(&state.modes), drain_timeout))
+        .await?;
+    // Connections are closed; give stopped runs a moment to write their last checkpoint.
+    if tokio::time::timeout(std::time::Duration::from_secs(5), state.modes.wait_idle())
+        .await
+        .is_err()
+    {
+        warn!(
+            in_flight_runs = state.modes.status().in_flight_runs,
+            "runs still in flight at exit"
+        );
+    }
+    info!("shutdown complete");
+    #[cfg(feature = "otel")]
+    langgraph::otel::shutdown();
+    Ok(())
+}
+
+/// Serves the gRPC API on `addr` in the background; new runs are refused once the HTTP server
+/// starts draining, and the listener stops with the process.
+#[cfg(feature = "grpc")]
+async fn serve_grpc(
+    addr: &str,
+    state: Arc<AppState>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("gRPC listening on {}", addr);
+    let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let server = tonic::transport::Server::builder()
+        .add_service(grpc::service(state))
+        .serve_with_incoming(incoming);
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            warn!(error = %e, "gRPC server stopped");
+        }
+    });
     Ok(())
 }
 
+#[cfg(not(feature = "grpc"))]
+async fn serve_grpc(
+    _addr: &str,
+    _state: Arc<AppState>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    Err(langgraph::FeatureMissing::new("grpc", "--grpc-listen").into())
+}
+
+/// Build config from the environment (and `.env`) with `args` applied; errors when no API key
+/// is set outside test mode.
+fn load_build_config(
+    args: &Args,
+) -> Result<ReactBuildConfig, Box<dyn std::error::Error + Send + Sync>> {
+    let mut build_config = ReactBuildConfig::from_env();
+    // Prefer OPENAI_API_BASE (langgraph-cli / common .env) if OPENAI_BASE_URL not set.
+    if build_config.openai_base_url.is_none() {
+        if let Ok(base) = std::env::var("OPENAI_API_BASE") {
+            build_config.openai_base_url = Some(base);
+        }
+    }
+    args.apply(&mut build_config);
+    build_config.skills = args.skills()?;
+    if build_config.thread_id.is_none() {
+        build_config.thread_id = Some("default".to_string());
+    }
+    if args.mock_llm() {
+        test_mode::isolate(&mut build_config);
+    } else if build_config.openai_api_key.is_none()
+        || build_config.openai_api_key.as_deref() == Some("")
+    {
+        return Err("OPENAI_API_KEY must be set".into());
+    }
+    Ok(build_config)
+}
+
+/// OpenAI client config (API key and base URL) of `build_config`.
+fn openai_config(build_config: &ReactBuildConfig) -> async_openai::config::OpenAIConfig {
+    let openai_config = async_openai::config::OpenAIConfig::new()
+        .with_api_key(build_config.openai_api_key.clone().unwrap_or_default());
+    match build_config.openai_base_url {
+        // Strip trailing slash so async_openai's url(base + "/chat/completions") does not
+        // become .../v4//chat/completions (some backends reject double slash).
+        Some(ref base) => openai_config.with_api_base(base.trim_end_matches('/')),
+        None => openai_config,
+    }
+}
+
+/// The agent for `build_config`: one runner, or with `--experiment` the runners of variants a
+/// (`build_config`) and b (with the experiment's settings), sharing one usage quota that also
+/// records usage without a cap. Called at startup and on every config reload (see `reload`).
+async fn build_agent(
+    args: &Args,
+    build_config: &ReactBuildConfig,
+    read_only: &ReadOnlySwitch,
+    runtime: &Arc<RuntimeConfig>,
+    title_llm: &Arc<dyn LlmClient>,
+) -> Result<Agent, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(experiment) = args.experiment()? else {
+        let quota = build_config.usage_quota()?.map(Arc::new);
+        let runner = build_runner(args, build_config, quota, read_only, runtime, title_llm);
+        return Ok(runner.await?.into());
+    };
+    let quota = Arc::new(build_config.usage_stats()?);
+    let variant_b = experiment.variant_b(build_config);
+    let a = build_runner(
+        args,
+        build_config,
+        Some(Arc::clone(&quota)),
+        read_only,
+        runtime,
+        title_llm,
+    )
+    .await?;
+    let b = build_runner(args, &variant_b, Some(quota), read_only, runtime, title_llm).await?;
+    info!(
+        experiment = %experiment.name,
+        share_b = experiment.share_b,
+        assignment = ?experiment.assignment,
+        model_b = ?variant_b.model,
+        prompt_profile_b = ?variant_b.prompt_profile.map(|p| p.as_str()),
+        mcp_servers_file_b = ?variant_b.mcp_servers_file,
+        "A/B experiment"
+    );
+    let experiment = langgraph::Experiment::new(experiment.name, a, b)
+        .with_share_b(experiment.share_b)
+        .with_assignment(experiment.assignment);
+    Ok(experiment.into())
+}
+
+/// The agent runner for `build_config`: checkpointer and store (sharing `read_only`), tools
+/// filtered by the runtime allowlist, chat model, budget governor, `quota`, outcome classifier
+/// and history compressor.
+async fn build_runner(
+    args: &Args,
+    build_config: &ReactBuildConfig,
+    quota: Option<Arc<UsageQuota>>,
+    read_only: &ReadOnlySwitch,
+    runtime: &Arc<RuntimeConfig>,
+    title_llm: &Arc<dyn LlmClient>,
+) -> Result<ReactRunner, Box<dyn std::error::Error + Send + Sync>> {
+    let mock_llm = args.mock_llm();
+    let model = build_config
+        .model
+        .clone()
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    let ctx = build_react_run_context_with_read_only(build_config, read_only)
+        .await
+        .map_err(|e| e.to_string())?;
+    let store = ctx.store.clone();
+    let tools = runtime.tools().clone();
+    // Tools an MCP server registers or removes later (tools/list_changed) are re-listed into
+    // `catalog`, which the LLM offers from.
+    let catalog = ToolCatalog::new();
+    let tool_source: Box<dyn ToolSource> = if mock_llm {
+        Box::new(test_mode::tool_source())
+    } else {
+        Box::new(RefreshingToolSource::new(ctx.tool_source, catalog.clone()))
+    };
+    let tool_source: Box<dyn ToolSource> =
+        Box::new(AllowlistToolSource::new(tool_source, tools.clone()));
+    let llm: Box<dyn LlmClient> = if mock_llm {
+        Box::new(test_mode::llm())
+    } else {
+        Box::new(
+            openai_llm(
+                build_config,
+                openai_config(build_config),
+                &model,
+                tool_source.as_ref(),
+                tools,
+                catalog,
+            )
+            .await?,
+        )
+    };
+
+    let runner = ReactRunner::new_with_max_turns(
+        llm,
+        tool_source,
+        ctx.checkpointer,
+        ctx.store,
+        None,
+        Some(build_config.effective_system_prompt()),
+        false,
+        build_config.max_turns.unwrap_or(langgraph::MAX_REACT_TURNS),
+    )?
+    .with_interrupt_before(&build_config.interrupt_before)?;
+    let runner = match build_config.node_timeout_secs {
+        Some(secs) => runner.with_node_timeout(std::time::Duration::from_secs(secs)),
+        None => runner,
+    };
+    let runner = runner.with_governor(Arc::new(RuntimeBudget::new(Arc::clone(runtime))));
+    let runner = match quota {
+        Some(quota) => runner.with_usage_quota(quota),
+        None => runner,
+    };
+    let runner = match args.outcome_classifier()? {
+        OutcomeClassifierKind::Off => runner,
+        OutcomeClassifierKind::Rules => {
+            runner.with_outcome_classifier(Arc::new(RuleOutcomeClassifier))
+        }
+        OutcomeClassifierKind::Llm => {
+            let classifier = LlmOutcomeClassifier::new(Arc::clone(title_llm));
+            runner.with_outcome_classifier(Arc::new(classifier))
+        }
+    };
+    // Old turns are summarized by the title model, which is cheaper than the chat model.
+    let runner = match build_config.history_compressor(Some(Arc::clone(title_llm))) {
+        Some(compressor) => runner.with_history_compressor(compressor),
+        None => runner,
+    };
+    // Memories are extracted by the title model as well.
+    let runner = match store.filter(|_| build_config.memory_extraction) {
+        Some(store) => runner.with_memory_writer(MemoryWriter::new(Arc::clone(title_llm), store)),
+        None => runner,
+    };
+    Ok(runner)
+}
+
+/// The chat model: `ChatOpenAI` offering `tool_source`'s tools (filtered by `tools`, refreshed
+/// through `catalog`), tuned by the build config and the `LLM_*` env vars, after a health probe.
+async fn openai_llm(
+    build_config: &ReactBuildConfig,
+    openai_config: async_openai::config::OpenAIConfig,
+    model: &str,
+    tool_source: &dyn ToolSource,
+    tools: ToolAllowlist,
+    catalog: ToolCatalog,
+) -> Result<langgraph::ChatOpenAI, Box<dyn std::error::Error + Send + Sync>> {
+    let llm =
+        langgraph::ChatOpenAI::new_with_tool_source(openai_config.clone(), model, tool_source)
+            .await?
+            .with_tool_allowlist(tools)
+            .with_tool_catalog(catalog)
+            // Self-hosted backends (vLLM, llama.cpp) may need minutes for the first request
+            // while loading weights; adapt the timeout to observed latency and retry once while
+            // cold.
+            .with_adaptive_timeout(langgraph::AdaptiveTimeout::default())
+            .with_cold_start_retry(true)
+            // One pooled client (LLM_HTTP_* tuning) for every request the server makes.
+            .with_http_client(build_config.http_client.shared_client()?);
+    let llm = match build_config.prompt_profile.and_then(|p| p.tool_choice()) {
+        Some(mode) => llm.with_tool_choice(mode),
+        None => llm,
+    };
+    // OPENAI_FALLBACK_BASE_URLS: further API bases (e.g. other regions) requests fail over to.
+    let llm = build_config
+        .openai_fallback_base_urls
+        .iter()
+        .fold(llm, |llm, base| {
+            let base = base.trim_end_matches('/');
+            llm.with_region(openai_config.clone().with_api_base(base))
+        });
+    // LLM_STREAM_SANITIZE=standard|strict cleans up stuttering streamed deltas.
+    let llm = match std::env::var("LLM_STREAM_SANITIZE") {
+        Ok(preset) => match preset.parse::<langgraph::DeltaSanitizerConfig>() {
+            Ok(cfg) => llm.with_delta_sanitizer(cfg),
+            Err(e) => {
+                warn!(error = %e, "ignoring LLM_STREAM_SANITIZE");
+                llm
+            }
+        },
+        Err(_) => llm,
+    };
+    // LLM_MAX_CONTINUATIONS=n continues replies cut off at the token limit up to n times.
+    let llm = match std::env::var("LLM_MAX_CONTINUATIONS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+    {
+        Some(n) => llm.with_max_continuations(n),
+        None => llm,
+    };
+    // Streamed replies report real token usage for the final chunk; LLM_STREAM_USAGE=false
+    // turns it off for proxies that reject stream_options.
+    let stream_usage = !matches!(
+        std::env::var("LLM_STREAM_USAGE").as_deref(),
+        Ok("0") | Ok("false")
+    );
+    let llm = llm.with_stream_usage(stream_usage);
+    // Bounded by the probe timeout (10s per base), not the adaptive maximum meant for cold loads.
+    match llm.probe_health().await {
+        Ok(latency) => info!(
+            latency_ms = latency.as_millis() as u64,
+            "LLM provider health probe ok"
+        ),
+        Err(e) => warn!(error = %e, "LLM provider health probe failed; continuing"),
+    }
+    Ok(llm)
+}
+
 /// Proxies GET /v1/models to the configured OpenAI-compatible base URL.
 /// Returns 503 if OPENAI_BASE_URL (or OPENAI_API_BASE) is not set.
 async fn models_list(
@@ -379,46 +892,99 @@ async fn model_retrieve(
 
 async fn chat_completions(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<langgraph::ChatCompletionRequest>,
 ) -> Result<impl IntoResponse, ServerError> {
-    let runner = Arc::clone(&state.runner);
+    let mut parsed = parse_chat_request(&req).map_err(ServerError::from)?;
+    route_by_headers(&headers, &mut parsed.runnable_config);
+    // Also the run id for GET /v1/runs/{run_id}/events, so it must be unique per request.
+    let id = format!("chatcmpl-{}", langgraph::memory::uuid6());
+    let runner = state.runner.get_for(&parsed.runnable_config, &id);
+    state.runtime.check_model(&req.model)?;
+    let run_guard = state.modes.begin_run()?;
+    state.runtime.check_rate()?;
     if !req.stream {
-        return Err(ServerError::BadRequest("only stream: true is supported".into()));
+        let res = tokio::select! {
+            biased;
+            res = chat_completion(&runner, id, &req.model, parsed) => res,
+            _ = state.modes.wait_runs_stopped() => Err(shutdown_error()),
+        };
+        drop(run_guard);
+        return res;
     }
-
-    let parsed = parse_chat_request(&req).map_err(ServerError::from)?;
+    // The runner refuses over-quota runs itself, but a stream has answered 200 by then.
+    runner.check_quota(Some(&parsed.runnable_config)).await?;
 
     // Use a large buffer so content chunks are not dropped when client reads slowly.
-    let (tx, rx) = mpsc::channel::<String>(2048);
-    let id = format!(
-        "chatcmpl-{}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis())
-            .unwrap_or(0)
+    let (tx, rx) = mpsc::channel::<Bytes>(2048);
+    tracing::debug!(
+        request_id = %id,
+        model = %req.model,
+        thread_id = ?parsed.runnable_config.thread_id,
+        variant = ?runner.experiment_variant().map(|v| v.tag()),
+        "chat completions stream"
     );
-    tracing::debug!(request_id = %id, model = %req.model, "chat completions stream");
     let meta = ChunkMeta {
         id: id.clone(),
         model: req.model.clone(),
         created: None,
     };
-    let mut adapter = StreamToSse::new_with_sink(meta, parsed.include_usage, tx);
+    let error_tx = tx.clone();
+    let mut adapter = StreamToSse::new_with_bytes_sink(meta, parsed.include_usage, tx);
 
     let user_message = parsed.user_message.clone();
     let runnable_config = Some(parsed.runnable_config);
-    tokio::spawn(async move {
+    // The body stream owns the guard: when the client disconnects, axum drops the body and
+    // the run is cancelled instead of finishing (and calling tools) for nobody. Shutdown
+    // cancels it too, once the drain timeout is over.
+    let cancel = state.modes.run_token();
+    let disconnect_guard = cancel.clone().drop_guard();
+    let mut recorder = state.transcripts.recorder(&id);
+    let modes = Arc::clone(&state.modes);
+    let injector_guard = state.injectors.register(&id);
+    let run = async move {
+        let _run_guard = run_guard;
         let res = runner
-            .stream_with_config(&user_message, runnable_config, Some(|ev| adapter.feed(ev)))
+            .stream_with_injector(
+                &user_message,
+                runnable_config,
+                cancel,
+                injector_guard.injector(),
+                Some(|ev| adapter.feed(ev)),
+            )
             .await;
+        drop(injector_guard);
+        match &res {
+            Err(RunError::Execution(AgentError::Cancelled)) if modes.runs_stopped() => {
+                tracing::info!("server shutting down; run stopped");
+                let _ = error_tx.send(shutdown_error_event().into()).await;
+            }
+            Err(RunError::Execution(AgentError::Cancelled)) => {
+                tracing::info!("client disconnected; run cancelled");
+            }
+            Err(e) => {
+                tracing::error!("stream error: {}", e);
+                let _ = error_tx.send(stream_error_event(e).into()).await;
+            }
+            Ok(_) => {}
+        }
         adapter.finish();
         drop(adapter);
-        if let Err(e) = res {
-            tracing::error!("stream error: {}", e);
+        if modes.is_shutting_down() || modes.runs_stopped() {
+            // Tell clients the stream ended on purpose before the connection closes.
+            let _ = error_tx.send(Bytes::from_static(b"data: [DONE]\n\n")).await;
         }
-    });
+    };
+    // The run outlives this handler; keep it under the request span.
+    tokio::spawn(run.instrument(tracing::Span::current()));
 
-    let stream = ReceiverStream::new(rx).map(|s| Ok::<_, std::io::Error>(Bytes::from(s)));
+    let stream = ReceiverStream::new(rx).map(move |chunk| {
+        let _ = &disconnect_guard;
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.record(&chunk);
+        }
+        Ok::<_, std::io::Error>(chunk)
+    });
     let body = Body::from_stream(stream);
     let mut res = (axum::http::StatusCode::OK).into_response();
     res.headers_mut().insert(
@@ -433,6 +999,157 @@ async fn chat_completions(
     Ok(res)
 }
 
+/// Header naming the thread a chat request runs on; wins over the body's `thread_id`.
+const THREAD_ID_HEADER: &str = "x-thread-id";
+/// Header naming the user a chat request runs for; wins over the body's `user`.
+const USER_ID_HEADER: &str = "x-user-id";
+
+/// Sets `thread_id` / `user_id` from the routing headers, so a gateway that authenticates
+/// users can pin their threads regardless of the body. Blank headers are ignored.
+fn route_by_headers(headers: &HeaderMap, config: &mut RunnableConfig) {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    if let Some(thread_id) = header(THREAD_ID_HEADER) {
+        config.thread_id = Some(thread_id);
+    }
+    if let Some(user_id) = header(USER_ID_HEADER) {
+        config.user_id = Some(user_id);
+    }
+}
+
+/// Non-streaming chat completion: runs to the end, then answers with one `chat.completion`
+/// body. Errors keep their status codes (429 / 503 / 504 for LLM errors, 429 over budget or
+/// quota).
+/// Closing the connection drops this future and with it the run.
+async fn chat_completion(
+    runner: &ReactRunner,
+    id: String,
+    model: &str,
+    parsed: ParsedChatRequest,
+) -> Result<Response, ServerError> {
+    tracing::debug!(
+        request_id = %id,
+        model = %model,
+        thread_id = ?parsed.runnable_config.thread_id,
+        variant = ?runner.experiment_variant().map(|v| v.tag()),
+        "chat completions"
+    );
+    let final_state = runner
+        .invoke_with_config(&parsed.user_message, Some(parsed.runnable_config))
+        .await?;
+    let meta = ChunkMeta {
+        id,
+        model: model.to_string(),
+        created: None,
+    };
+    Ok(Json(ChatCompletion::from_state(meta, &final_state)).into_response())
+}
+
+/// SSE event reporting a failed run. Headers are already sent as 200 once streaming starts, so
+/// an LLM error carries its HTTP status as `code` and the provider's hint as `retry_after` (secs);
+/// a run over budget has type `budget_exceeded`.
+fn stream_error_event(e: &RunError) -> String {
+    let mut error = serde_json::json!({ "message": e.to_string() });
+    if let RunError::BudgetExceeded(_) = e {
+        error["type"] = BUDGET_EXCEEDED.into();
+    }
+    if let RunError::QuotaExceeded(_) = e {
+        error["type"] = QUOTA_EXCEEDED.into();
+    }
+    if let RunError::Execution(AgentError::Llm(llm)) = e {
+        error["type"] = llm.kind.to_string().into();
+        error["code"] = llm.http_status().into();
+        if let Some(wait) = llm.retry_after {
+            error["retry_after"] = wait.as_secs_f64().into();
+        }
+    }
+    format!("data: {}\n\n", serde_json::json!({ "error": error }))
+}
+
+/// 503 for a run stopped because the server shut down before it finished.
+fn shutdown_error() -> ServerError {
+    ServerError::Unavailable("server shut down before the run finished; retry later".to_string())
+}
+
+/// SSE event ending a stream whose run was stopped by shutdown.
+fn shutdown_error_event() -> String {
+    let error = serde_json::json!({
+        "message": "server shut down before the run finished; retry later",
+        "type": SERVER_SHUTDOWN,
+    });
+    format!("data: {}\n\n", serde_json::json!({ "error": error }))
+}
+
+/// Body of POST /v1/threads/{thread_id}/resume.
+#[derive(Debug, Default, serde::Deserialize)]
+struct ResumeRequest {
+    /// Human-provided value for the paused graph (e.g. a reply, or `{"approved": true}`).
+    /// A string for a thread paused before `think` is added as a user message.
+    #[serde(default)]
+    resume: Option<serde_json::Value>,
+    /// User id for long-term memory, as in chat requests.
+    #[serde(default)]
+    user_id: Option<String>,
+}
+
+/// Continues an interrupted thread. Returns `{"status": "completed", "reply", "final_answer"}`
+/// when the run finishes (`final_answer` is null when it ended without one),
+/// `{"status": "interrupted", "interrupt"}` when it pauses again, and 409 when the thread is
+/// not paused.
+async fn resume_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    Json(req): Json<ResumeRequest>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let _run_guard = state.modes.begin_run()?;
+    state.runtime.check_rate()?;
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.clone()),
+        user_id: req.user_id,
+        ..Default::default()
+    };
+    // The thread continues on its variant; resumes are not requests of their own.
+    let runner = state.runner.get_for(&config, &thread_id);
+    let pending = runner
+        .pending_node(Some(config.clone()))
+        .await
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+    let Some(pending_node) = pending else {
+        return Err(ServerError::Conflict(format!(
+            "thread {} is not interrupted",
+            thread_id
+        )));
+    };
+    tracing::debug!(thread_id = %thread_id, pending_node = %pending_node, "resume thread");
+    let res = tokio::select! {
+        biased;
+        res = runner.resume(req.resume, Some(config)) => res,
+        _ = state.modes.wait_runs_stopped() => return Err(shutdown_error()),
+    };
+    match res {
+        Ok(final_state) => Ok(Json(serde_json::json!({
+            "thread_id": thread_id,
+            "status": "completed",
+            "reply": final_state.last_assistant_reply(),
+            "final_answer": final_state.final_answer(),
+        }))),
+        Err(RunError::Execution(AgentError::Interrupted(GraphInterrupt(interrupt)))) => {
+            Ok(Json(serde_json::json!({
+                "thread_id": thread_id,
+                "status": "interrupted",
+                "interrupt": interrupt.value,
+            })))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Error when proxying /v1/models to upstream. Returns 503 if base URL is not set, 502 on upstream failure.
 #[derive(Debug, thiserror::Error)]
 pub enum ModelsProxyError {
@@ -469,6 +1186,42 @@ pub enum ServerError {
     Parse(#[from] ParseError),
     #[error("not found: {0}")]
     NotFound(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+    #[error("upstream error: {0}")]
+    Upstream(String),
+    #[error("{0}")]
+    Llm(LlmError),
+    #[error("budget exceeded: {0}")]
+    BudgetExceeded(String),
+    /// Daily token cap reached; the message is meant for the end user.
+    #[error("{0}")]
+    QuotaExceeded(QuotaExceeded),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    #[error("unavailable: {0}")]
+    Unavailable(String),
+    /// Over `max_runs_per_minute`; carries the seconds until a run is accepted again.
+    #[error("rate limited: retry in {0}s")]
+    RateLimited(u64),
+}
+
+/// Error type of requests stopped by the run budget (`--max-run-*`).
+const BUDGET_EXCEEDED: &str = "budget_exceeded";
+/// Error type of requests refused by the daily token caps (`--daily-*-token-cap`).
+const QUOTA_EXCEEDED: &str = "quota_exceeded";
+
+impl From<RunError> for ServerError {
+    fn from(e: RunError) -> Self {
+        match e {
+            RunError::Execution(AgentError::Llm(e)) => ServerError::Llm(e),
+            RunError::BudgetExceeded(reason) => ServerError::BudgetExceeded(reason),
+            RunError::QuotaExceeded(exceeded) => ServerError::QuotaExceeded(exceeded),
+            other => ServerError::Internal(other.to_string()),
+        }
+    }
 }
 
 impl IntoResponse for ServerError {
@@ -477,6 +1230,62 @@ impl IntoResponse for ServerError {
             ServerError::BadRequest(m) => (axum::http::StatusCode::BAD_REQUEST, m.clone()),
             ServerError::Parse(e) => (axum::http::StatusCode::BAD_REQUEST, e.to_string()),
             ServerError::NotFound(m) => (axum::http::StatusCode::NOT_FOUND, m.clone()),
+            ServerError::Conflict(m) => (axum::http::StatusCode::CONFLICT, m.clone()),
+            ServerError::Internal(m) => {
+                (axum::http::StatusCode::INTERNAL_SERVER_ERROR, m.clone())
+            }
+            ServerError::Upstream(m) => (axum::http::StatusCode::BAD_GATEWAY, m.clone()),
+            ServerError::Forbidden(m) => (StatusCode::FORBIDDEN, m.clone()),
+            ServerError::Unavailable(m) => {
+                let body = serde_json::json!({ "error": { "message": m } });
+                let mut res = (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+                res.headers_mut().insert(
+                    axum::http::header::RETRY_AFTER,
+                    MAINTENANCE_RETRY_AFTER_SECS.into(),
+                );
+                return res;
+            }
+            ServerError::Llm(e) => {
+                let status = StatusCode::from_u16(e.http_status())
+                    .unwrap_or(StatusCode::SERVICE_UNAVAILABLE);
+                let body = serde_json::json!({
+                    "error": { "message": e.to_string(), "type": e.kind.to_string() }
+                });
+                let mut res = (status, Json(body)).into_response();
+                if let Some(wait) = e.retry_after {
+                    // Retry-After takes whole seconds; round up so clients never retry early.
+                    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+                    res.headers_mut()
+                        .insert(axum::http::header::RETRY_AFTER, secs.into());
+                }
+                return res;
+            }
+            ServerError::RateLimited(secs) => {
+                let body = serde_json::json!({
+                    "error": { "message": self.to_string(), "type": "rate_limited" }
+                });
+                let mut res = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+                res.headers_mut()
+                    .insert(axum::http::header::RETRY_AFTER, (*secs).into());
+                return res;
+            }
+            ServerError::BudgetExceeded(_) => {
+                let body = serde_json::json!({
+                    "error": { "message": self.to_string(), "type": BUDGET_EXCEEDED }
+                });
+                return (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+            }
+            ServerError::QuotaExceeded(exceeded) => {
+                let body = serde_json::json!({
+                    "error": { "message": exceeded.to_string(), "type": QUOTA_EXCEEDED }
+                });
+                let mut res = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+                res.headers_mut().insert(
+                    axum::http::header::RETRY_AFTER,
+                    exceeded.resets_in_secs.into(),
+                );
+                return res;
+            }
         };
         (status, Json(serde_json::json!({ "error": { "message": msg } }))).into_response()
     }
@@ -487,15 +1296,92 @@ mod tests {
     use super::*;
     use axum::body::Body;
     use axum::http::Request;
-    use langgraph::{MockLlm, MockToolSource, ReactRunner};
+    use langgraph::{MemorySaver, MockLlm, MockToolSource, ReActState, ReactRunner};
     use tower::ServiceExt;
 
-    /// **Scenario**: When OPENAI_BASE_URL is not set, GET /v1/models returns 503.
+    fn app_state(runner: ReactRunner) -> Arc<AppState> {
+        Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::new(runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::default()),
+            admin_api_key: None,
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        })
+    }
+
+    async fn post_resume(
+        app: Router,
+        thread_id: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let res = app
+            .oneshot(
+                Request::post(format!("/v1/threads/{}/resume", thread_id))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = res.status();
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// **Scenario**: A thread paused before `act` is resumed over HTTP and completes; resuming
+    /// it again returns 409.
     #[tokio::test]
-    async fn models_list_returns_503_when_base_url_not_configured() {
+    async fn resume_thread_continues_paused_run() {
         let runner = ReactRunner::new(
             Box::new(MockLlm::with_no_tool_calls("ok")),
             Box::new(MockToolSource::get_time_example()),
+            Some(Arc::new(MemorySaver::<ReActState>::new())),
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+        .with_interrupt_before(&["act".to_string()])
+        .expect("act is a ReAct node");
+        let config = RunnableConfig {
+            thread_id: Some("paused".to_string()),
+            ..Default::default()
+        };
+        let err = runner
+            .invoke_with_config("Hello", Some(config))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RunError::Execution(AgentError::Interrupted(_))), "{:?}", err);
+        let app = Router::new()
+            .route("/v1/threads/:thread_id/resume", post(resume_thread))
+            .with_state(app_state(runner));
+
+        let (status, body) = post_resume(app.clone(), "paused", "{}").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["status"], "completed");
+        assert_eq!(body["reply"], "ok");
+        assert_eq!(body["final_answer"], "ok");
+
+        let (status, body) = post_resume(app, "paused", r#"{"resume": "again"}"#).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+    }
+
+    /// **Scenario**: POST /mcp answers a tools/call with the agent's reply as JSON and a
+    /// notification with 202.
+    #[tokio::test]
+    async fn mcp_endpoint_asks_agent() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("42")),
+            Box::new(MockToolSource::get_time_example()),
             None,
             None,
             None,
@@ -503,25 +1389,1361 @@ mod tests {
             false,
         )
         .expect("compile");
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(1))
-            .build()
-            .expect("client");
-        let state = Arc::new(AppState {
-            runner: Arc::new(runner),
-            openai_base_url: None,
-            openai_api_key: "sk-test".to_string(),
-            http_client,
-            expected_api_key: None,
-        });
         let app = Router::new()
-            .route("/v1/models", get(models_list))
-            .route("/v1/models/:model_id", get(model_retrieve))
-            .with_state(state);
+            .route("/mcp", post(mcp_server::mcp_http))
+            .with_state(app_state(runner));
+        let post_mcp = |body: serde_json::Value| {
+            Request::post("/mcp")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
         let res = app
-            .oneshot(Request::get("/v1/models").body(Body::empty()).unwrap())
+            .clone()
+            .oneshot(post_mcp(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "tools/call",
+                "params": { "name": "ask_agent", "arguments": { "question": "Answer?" } }
+            })))
             .await
             .unwrap();
-        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["result"]["content"][0]["text"], "42");
+
+        let res = app
+            .oneshot(post_mcp(serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/initialized"
+            })))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+    }
+
+    /// **Scenario**: StreamChat streams the tool call and its result and ends with `done`; the
+    /// run is checkpointed, so GetThread returns it and a second CreateThread is refused.
+    #[cfg(feature = "grpc")]
+    #[tokio::test]
+    async fn grpc_streams_run_and_manages_threads() {
+        use grpc::proto::{self, agent_server::Agent, stream_event::Event};
+
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::first_tools_then_end()),
+            Box::new(MockToolSource::get_time_example()),
+            Some(Arc::new(MemorySaver::<ReActState>::new())),
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let service = grpc::AgentService::new(app_state(runner));
+
+        let stream = service
+            .stream_chat(tonic::Request::new(proto::ChatRequest {
+                message: "What time is it?".to_string(),
+                thread_id: "grpc-1".to_string(),
+                user_id: String::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let events: Vec<Event> = stream.map(|ev| ev.unwrap().event.unwrap()).collect().await;
+        assert!(events
+            .iter()
+            .any(|ev| matches!(ev, Event::ToolCall(call) if call.name == "get_time")));
+        assert!(events
+            .iter()
+            .any(|ev| matches!(ev, Event::ToolResult(result) if result.call_id == "call-1")));
+        let Some(Event::Done(done)) = events.last() else {
+            panic!("stream did not end with done: {:?}", events);
+        };
+        assert_eq!(done.reply, "The time is as above.");
+        assert_eq!(done.thread_id, "grpc-1");
+
+        let thread = service
+            .get_thread(tonic::Request::new(proto::GetThreadRequest {
+                thread_id: "grpc-1".to_string(),
+                user_id: String::new(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let roles: Vec<&str> = thread.messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "assistant", "tool", "assistant"]);
+        let status = service
+            .create_thread(tonic::Request::new(proto::CreateThreadRequest {
+                thread_id: "grpc-1".to_string(),
+                user_id: String::new(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+    }
+
+    /// Router with the title and suggestions endpoints; the title model always replies `reply`.
+    fn thread_meta_app(runner: Arc<ReactRunner>, reply: &str) -> Router {
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(runner),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls(reply)),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::default()),
+            admin_api_key: None,
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        Router::new()
+            .route("/v1/threads/:thread_id/title", post(thread_title))
+            .route(
+                "/v1/threads/:thread_id/suggestions",
+                post(thread_suggestions),
+            )
+            .with_state(state)
+    }
+
+    async fn post_empty(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let res = app
+            .oneshot(Request::post(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = res.status();
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// **Scenario**: Title and suggestions are generated from a checkpointed thread; a thread
+    /// without history answers 404.
+    #[tokio::test]
+    async fn thread_title_and_suggestions_use_checkpointed_history() {
+        let runner = Arc::new(
+            ReactRunner::new(
+                Box::new(MockLlm::with_no_tool_calls("Rust is a systems language.")),
+                Box::new(MockToolSource::get_time_example()),
+                Some(Arc::new(MemorySaver::<ReActState>::new())),
+                None,
+                None,
+                None,
+                false,
+            )
+            .expect("compile"),
+        );
+        let config = RunnableConfig {
+            thread_id: Some("chat-1".to_string()),
+            ..Default::default()
+        };
+        runner
+            .invoke_with_config("What is Rust?", Some(config))
+            .await
+            .unwrap();
+
+        let app = thread_meta_app(runner.clone(), "\"About Rust.\"");
+        let (status, body) = post_empty(app.clone(), "/v1/threads/chat-1/title").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["title"], "About Rust");
+
+        let (status, body) = post_empty(app, "/v1/threads/unknown/title").await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", body);
+
+        let app = thread_meta_app(runner, "1. Is it fast?\n2. Is it safe?\n3. Where to start?");
+        let (status, body) = post_empty(app, "/v1/threads/chat-1/suggestions").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(
+            body["suggestions"],
+            serde_json::json!(["Is it fast?", "Is it safe?", "Where to start?"])
+        );
+    }
+
+    /// **Scenario**: GET /v1/runs/{id}/events replays byte for byte what a chat stream sent;
+    /// unknown runs answer 404.
+    #[tokio::test]
+    async fn run_events_replays_chat_stream() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello there")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/runs/:run_id/events", get(run_events))
+            .with_state(app_state(runner));
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::post("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"model":"m","stream":true,"messages":[{"role":"user","content":"Hi"}]}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let sent = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let sent = String::from_utf8(sent.to_vec()).unwrap();
+        let first: serde_json::Value = serde_json::from_str(
+            sent.lines()
+                .next()
+                .and_then(|l| l.strip_prefix("data: "))
+                .expect("first SSE event"),
+        )
+        .unwrap();
+        let run_id = first["id"].as_str().expect("chunk id").to_string();
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::get(format!("/v1/runs/{}/events", run_id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()[axum::http::header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let replayed = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(String::from_utf8(replayed.to_vec()).unwrap(), sent);
+
+        let res = app
+            .oneshot(
+                Request::get("/v1/runs/chatcmpl-unknown/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// **Scenario**: GET /v1/analytics/outcomes counts the outcome labels of runs on all
+    /// threads, or on the one named by `thread_id`.
+    #[tokio::test]
+    async fn analytics_counts_run_outcomes() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::first_tools_then_end()),
+            Box::new(MockToolSource::get_time_example()),
+            Some(Arc::new(MemorySaver::<ReActState>::new())),
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+        .with_outcome_classifier(Arc::new(RuleOutcomeClassifier));
+        let state = app_state(runner);
+        for thread_id in ["a-1", "a-2"] {
+            let config = RunnableConfig {
+                thread_id: Some(thread_id.to_string()),
+                ..Default::default()
+            };
+            state
+                .runner
+                .get()
+                .invoke_with_config("What time is it?", Some(config))
+                .await
+                .expect("run");
+        }
+        let app = Router::new()
+            .route("/v1/analytics/outcomes", get(analytics::outcomes))
+            .with_state(state);
+        let get_outcomes = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                assert_eq!(res.status(), StatusCode::OK);
+                let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+            }
+        };
+
+        // Runs are classified in the background.
+        let mut body = serde_json::Value::Null;
+        for _ in 0..200 {
+            body = get_outcomes("/v1/analytics/outcomes").await;
+            if body["runs"] == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(body["threads"], 2);
+        assert_eq!(body["runs"], 2, "{}", body);
+        assert_eq!(body["outcomes"]["answered"], 2);
+        assert_eq!(body["outcomes"]["needed_tools"], 2);
+        assert_eq!(body["outcomes"]["refused"], 0);
+
+        let body = get_outcomes("/v1/analytics/outcomes?thread_id=a-1").await;
+        assert_eq!(body["threads"], 1);
+        assert_eq!(body["runs"], 1);
+        assert!(body.get("variants").is_none(), "{}", body);
+    }
+
+    /// **Scenario**: During an A/B experiment, threads run on their assigned variant and
+    /// GET /v1/analytics/outcomes breaks the labelled runs down by variant.
+    #[tokio::test]
+    async fn analytics_breaks_outcomes_down_by_experiment_variant() {
+        let checkpointer = Arc::new(MemorySaver::<ReActState>::new());
+        let runner = |reply: &str| {
+            ReactRunner::new(
+                Box::new(MockLlm::with_no_tool_calls(reply)),
+                Box::new(MockToolSource::get_time_example()),
+                Some(checkpointer.clone()),
+                None,
+                None,
+                None,
+                false,
+            )
+            .expect("compile")
+            .with_outcome_classifier(Arc::new(RuleOutcomeClassifier))
+        };
+        let experiment = langgraph::Experiment::new("reply", runner("a"), runner("b"));
+        let thread_on = |variant| {
+            (0..)
+                .map(|i| format!("thread-{}", i))
+                .find(|t| experiment.variant_for(t) == variant)
+                .unwrap()
+        };
+        let threads = [
+            (thread_on(langgraph::Variant::A), "a"),
+            (thread_on(langgraph::Variant::B), "b"),
+        ];
+        let state = app_state(runner("unused"));
+        state.runner.replace(experiment);
+        for (thread_id, reply) in &threads {
+            let config = RunnableConfig {
+                thread_id: Some(thread_id.clone()),
+                ..Default::default()
+            };
+            let runner = state.runner.get_for(&config, "req");
+            let run = runner.invoke_with_config("hi", Some(config)).await.unwrap();
+            assert_eq!(run.last_assistant_reply().as_deref(), Some(*reply));
+        }
+        let app = Router::new()
+            .route("/v1/analytics/outcomes", get(analytics::outcomes))
+            .with_state(state);
+
+        // Runs are tagged in the background.
+        let mut body = serde_json::Value::Null;
+        for _ in 0..200 {
+            let request = Request::get("/v1/analytics/outcomes")
+                .body(Body::empty())
+                .unwrap();
+            let res = app.clone().oneshot(request).await.unwrap();
+            let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            body = serde_json::from_slice(&bytes).unwrap();
+            if body["runs"] == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(body["runs"], 2, "{}", body);
+        for variant in ["a", "b"] {
+            let runs = &body["variants"]["reply"][variant];
+            assert_eq!(runs["runs"], 1, "{}", body);
+            assert_eq!(runs["outcomes"]["answered"], 1, "{}", body);
+        }
+    }
+
+    /// **Scenario**: POST /v1/runs/{id}/messages queues a user message on a running stream
+    /// (202); empty messages answer 400, unknown runs 404, runs that just finished 409.
+    #[tokio::test]
+    async fn inject_message_queues_on_running_stream() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello there")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let state = app_state(runner);
+        let guard = state.injectors.register("chatcmpl-running");
+        let app = Router::new()
+            .route("/v1/runs/:run_id/messages", post(inject_message))
+            .with_state(Arc::clone(&state));
+        let post = |run_id: &str, body: &str| {
+            let req = Request::post(format!("/v1/runs/{}/messages", run_id))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let app = app.clone();
+            async move { app.oneshot(req).await.unwrap().status() }
+        };
+
+        let running = "chatcmpl-running";
+        let status = post(running, r#"{"content":"actually, in French"}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(guard.injector().pending(), 1);
+
+        let status = post(running, r#"{"content":"  "}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let status = post("chatcmpl-unknown", r#"{"content":"hi"}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        guard.injector().close();
+        let status = post(running, r#"{"content":"hi"}"#).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        drop(guard);
+        let status = post(running, r#"{"content":"hi"}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    /// **Scenario**: A stream whose run is stopped by shutdown ends with a `server_shutdown`
+    /// error event and `[DONE]`; once shutting down, new runs answer 503.
+    #[tokio::test]
+    async fn shutdown_stops_streams_with_done_and_refuses_new_runs() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello there")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let state = app_state(runner);
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(Arc::clone(&state));
+        let chat = || {
+            Request::post("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"model":"m","stream":true,"messages":[{"role":"user","content":"Hi"}]}"#,
+                ))
+                .unwrap()
+        };
+
+        state.modes.stop_runs();
+        let res = app.clone().oneshot(chat()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let sent = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let sent = String::from_utf8(sent.to_vec()).unwrap();
+        assert!(sent.contains(SERVER_SHUTDOWN), "{}", sent);
+        assert!(sent.ends_with("data: [DONE]\n\n"), "{}", sent);
+
+        state.modes.begin_shutdown();
+        let res = app.oneshot(chat()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    /// **Scenario**: `stream: false` answers with one `chat.completion` JSON body holding the
+    /// reply, finish reason and usage.
+    #[tokio::test]
+    async fn chat_completions_without_stream_returns_completion() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello there")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(app_state(runner));
+
+        let res = app
+            .oneshot(
+                Request::post("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"model":"m","stream":false,"messages":[{"role":"user","content":"Hi"}]}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()[axum::http::header::CONTENT_TYPE],
+            "application/json"
+        );
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["object"], "chat.completion");
+        assert_eq!(body["model"], "m");
+        assert!(body["id"].as_str().unwrap().starts_with("chatcmpl-"));
+        assert_eq!(body["choices"][0]["message"]["role"], "assistant");
+        assert_eq!(body["choices"][0]["message"]["content"], "Hello there");
+        assert_eq!(body["choices"][0]["finish_reason"], "stop");
+        assert!(body["usage"]["total_tokens"].is_u64());
+    }
+
+    /// **Scenario**: In test mode the server answers deterministically: asking for the time
+    /// runs `get_time` and reports its result, other messages are echoed, on every request.
+    #[tokio::test]
+    async fn test_mode_answers_deterministically() {
+        let runner = ReactRunner::new(
+            Box::new(test_mode::llm()),
+            Box::new(test_mode::tool_source()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(app_state(runner));
+        let ask = |text: &str| {
+            let body = serde_json::json!({
+                "model": "mock",
+                "stream": false,
+                "messages": [{ "role": "user", "content": text }]
+            });
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        Request::post("/v1/chat/completions")
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                body["choices"][0]["message"]["content"].clone()
+            }
+        };
+
+        for _ in 0..2 {
+            assert_eq!(
+                ask("What time is it?").await,
+                "Tool get_time returned: 2025-01-29 12:00:00"
+            );
+            assert_eq!(ask("Hi").await, "You said: Hi");
+        }
+    }
+
+    /// **Scenario**: Requests are checkpointed per thread: the `X-Thread-Id` header wins over
+    /// the body's `thread_id`, and other threads do not see the conversation.
+    #[tokio::test]
+    async fn chat_completions_isolate_threads() {
+        let runner = Arc::new(
+            ReactRunner::new(
+                Box::new(MockLlm::with_no_tool_calls("ok")),
+                Box::new(MockToolSource::get_time_example()),
+                Some(Arc::new(MemorySaver::<ReActState>::new())),
+                None,
+                None,
+                None,
+                false,
+            )
+            .expect("compile"),
+        );
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::clone(&runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::default()),
+            admin_api_key: None,
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(state);
+
+        let send = |thread_header: Option<&str>, body: &str| {
+            let mut req =
+                Request::post("/v1/chat/completions").header("content-type", "application/json");
+            if let Some(thread_id) = thread_header {
+                req = req.header(THREAD_ID_HEADER, thread_id);
+            }
+            app.clone()
+                .oneshot(req.body(Body::from(body.to_string())).unwrap())
+        };
+        let res = send(
+            Some("alice-1"),
+            r#"{"model":"m","stream":false,"thread_id":"ignored","messages":[{"role":"user","content":"One"}]}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = send(
+            None,
+            r#"{"model":"m","stream":false,"metadata":{"thread_id":"alice-1"},"messages":[{"role":"user","content":"Two"}]}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = send(
+            Some("bob-1"),
+            r#"{"model":"m","stream":false,"messages":[{"role":"user","content":"Three"}]}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let user_messages = |state: ReActState| {
+            state
+                .messages
+                .iter()
+                .filter(|m| matches!(m, langgraph::Message::User(_)))
+                .count()
+        };
+        let thread = |id: &str| RunnableConfig {
+            thread_id: Some(id.to_string()),
+            ..Default::default()
+        };
+        let alice = runner.get_state(Some(thread("alice-1"))).await.unwrap();
+        assert_eq!(alice.map(user_messages), Some(2));
+        let bob = runner.get_state(Some(thread("bob-1"))).await.unwrap();
+        assert_eq!(bob.map(user_messages), Some(1));
+        let ignored = runner.get_state(Some(thread("ignored"))).await.unwrap();
+        assert!(ignored.is_none());
+    }
+
+    /// **Scenario**: The admin endpoint needs X-Admin-Key; in read-only mode chats succeed
+    /// without checkpoints, and in maintenance new chats answer 503 with Retry-After.
+    #[tokio::test]
+    async fn admin_mode_toggles_read_only_and_maintenance() {
+        let switch = ReadOnlySwitch::new(false);
+        let saver = langgraph::memory::ReadOnlyCheckpointer::<ReActState>::new(
+            Arc::new(MemorySaver::<ReActState>::new()),
+            switch.clone(),
+        );
+        let runner = Arc::new(
+            ReactRunner::new(
+                Box::new(MockLlm::with_no_tool_calls("ok")),
+                Box::new(MockToolSource::get_time_example()),
+                Some(Arc::new(saver)),
+                None,
+                None,
+                None,
+                false,
+            )
+            .expect("compile"),
+        );
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::clone(&runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::new(switch, false)),
+            admin_api_key: Some("admin-secret".to_string()),
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/admin/mode", get(get_mode).post(set_mode))
+            .with_state(state);
+
+        let set = |key: &str, body: &str| {
+            app.clone().oneshot(
+                Request::post("/v1/admin/mode")
+                    .header("content-type", "application/json")
+                    .header(modes::ADMIN_KEY_HEADER, key)
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+        let chat = || {
+            app.clone().oneshot(
+                Request::post("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .header(THREAD_ID_HEADER, "t1")
+                    .body(Body::from(
+                        r#"{"model":"m","stream":false,"messages":[{"role":"user","content":"Hi"}]}"#,
+                    ))
+                    .unwrap(),
+            )
+        };
+
+        let res = app
+            .clone()
+            .oneshot(Request::get("/v1/admin/mode").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let res = set("wrong", r#"{"read_only":true}"#).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = set("admin-secret", r#"{"read_only":true}"#).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"read_only": true, "maintenance": false, "in_flight_runs": 0})
+        );
+        assert_eq!(chat().await.unwrap().status(), StatusCode::OK);
+        let thread = RunnableConfig {
+            thread_id: Some("t1".to_string()),
+            ..Default::default()
+        };
+        assert!(runner.get_state(Some(thread)).await.unwrap().is_none());
+
+        let res = set("admin-secret", r#"{"maintenance":true}"#)
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = chat().await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()[axum::http::header::RETRY_AFTER], "30");
+    }
+
+    /// **Scenario**: POST /v1/admin/backup copies the SQLite database for admins; a second
+    /// backup to the same path answers 409 and requests without the admin key 403.
+    #[tokio::test]
+    async fn admin_backup_copies_database_once() {
+        use langgraph::{SqliteStore, Store};
+
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("ok")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let dir =
+            std::env::temp_dir().join(format!("langgraph-server-backup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("memory.db");
+        let ns = vec!["u".to_string(), "memories".to_string()];
+        let store = SqliteStore::new(&db).unwrap();
+        store.put(&ns, "k", &serde_json::json!("v")).await.unwrap();
+        let mut state = app_state(runner);
+        let inner = Arc::get_mut(&mut state).unwrap();
+        inner.admin_api_key = Some("admin".into());
+        inner.sqlite_files = Some(SqliteFiles::Single(db));
+        let app = Router::new()
+            .route("/v1/admin/backup", post(backup))
+            .with_state(state);
+        let dest = dir.join("backup.db");
+        let post = |admin_key: &str| {
+            let body = serde_json::json!({ "path": dest }).to_string();
+            app.clone().oneshot(
+                Request::post("/v1/admin/backup")
+                    .header("content-type", "application/json")
+                    .header("x-admin-key", admin_key)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+
+        let res = post("admin").await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(report["files"][0]["bytes"].as_u64().unwrap() > 0);
+        let copy = SqliteStore::new(&dest).unwrap();
+        assert_eq!(
+            copy.get(&ns, "k").await.unwrap(),
+            Some(serde_json::json!("v"))
+        );
+
+        assert_eq!(post("admin").await.unwrap().status(), StatusCode::CONFLICT);
+        assert_eq!(post("wrong").await.unwrap().status(), StatusCode::FORBIDDEN);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// **Scenario**: With API keys configured, requests without a known Bearer key answer 401
+    /// and a key over its rate answers 429 with Retry-After, while other keys still pass.
+    #[tokio::test]
+    async fn api_keys_authenticate_and_rate_limit_per_key() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("ok")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let keys = ApiKeys::new(Some(auth::RateLimit::per_minute(1)))
+            .with_key("sk-a", None)
+            .with_key("sk-b", None);
+        let mut state = app_state(runner);
+        Arc::get_mut(&mut state).unwrap().api_keys = Arc::new(keys);
+        let app = Router::new()
+            .route("/ping", get(|| async { "pong" }))
+            .layer(middleware::from_fn_with_state(state, require_auth));
+        let ping = |key: Option<&str>| {
+            let mut request = Request::get("/ping");
+            if let Some(key) = key {
+                request = request.header("authorization", format!("Bearer {}", key));
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        assert_eq!(ping(None).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            ping(Some("sk-x")).await.unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(ping(Some("sk-a")).await.unwrap().status(), StatusCode::OK);
+        let res = ping(Some("sk-a")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()[axum::http::header::RETRY_AFTER], "60");
+        assert_eq!(ping(Some("sk-b")).await.unwrap().status(), StatusCode::OK);
+    }
+
+    /// **Scenario**: A model allowlist and run rate limit set through /v1/admin/config apply to
+    /// the next chats (403, then 429 with Retry-After), and the change shows in the audit log.
+    #[tokio::test]
+    async fn admin_config_applies_to_new_runs_and_is_audited() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("ok")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::new(runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::default()),
+            admin_api_key: Some("admin-secret".to_string()),
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/admin/config", get(get_config).post(set_config))
+            .route("/v1/admin/audit", get(get_audit))
+            .with_state(state);
+        let chat = |model: &str| {
+            let body = format!(
+                r#"{{"model":"{}","stream":false,"messages":[{{"role":"user","content":"Hi"}}]}}"#,
+                model
+            );
+            app.clone().oneshot(
+                Request::post("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::post("/v1/admin/config")
+                    .header("content-type", "application/json")
+                    .header(modes::ADMIN_KEY_HEADER, "admin-secret")
+                    .body(Body::from(
+                        r#"{"model_allowlist":["gpt-4o-mini"],"max_runs_per_minute":1}"#,
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        assert_eq!(
+            chat("gpt-4o").await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(chat("gpt-4o-mini").await.unwrap().status(), StatusCode::OK);
+        let res = chat("gpt-4o-mini").await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(res.headers().contains_key(axum::http::header::RETRY_AFTER));
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::get("/v1/admin/audit")
+                    .header(modes::ADMIN_KEY_HEADER, "admin-secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let changes = &body["entries"][0]["changes"];
+        assert_eq!(
+            changes["model_allowlist"]["to"],
+            serde_json::json!(["gpt-4o-mini"])
+        );
+        assert_eq!(
+            changes["max_runs_per_minute"]["from"],
+            serde_json::Value::Null
+        );
+    }
+
+    /// **Scenario**: A thread is created, listed, shown with its history after a chat, given
+    /// imported messages, and deleted; duplicates answer 409, unknown threads 404, bad imports
+    /// 400, and writes 503 in read-only mode.
+    #[tokio::test]
+    async fn threads_api_creates_lists_shows_and_deletes() {
+        let switch = ReadOnlySwitch::new(false);
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello back")),
+            Box::new(MockToolSource::get_time_example()),
+            Some(Arc::new(MemorySaver::<ReActState>::new())),
+            None,
+            None,
+            Some("Be brief.".to_string()),
+            false,
+        )
+        .expect("compile");
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::new(runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client: reqwest::Client::new(),
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::new(switch.clone(), false)),
+            admin_api_key: None,
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/threads", get(list_threads).post(create_thread))
+            .route(
+                "/v1/threads/:thread_id",
+                get(get_thread).delete(delete_thread),
+            )
+            .route("/v1/threads/:thread_id/messages", post(append_messages))
+            .with_state(state);
+        let send = |request: Request<Body>| {
+            let app = app.clone();
+            async move {
+                let res = app.oneshot(request).await.unwrap();
+                let status = res.status();
+                let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+                (status, body)
+            }
+        };
+        let empty = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let create = |body: &str| {
+            Request::post("/v1/threads")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let (status, body) = send(create(r#"{"thread_id":"t1"}"#)).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        assert_eq!(body["thread_id"], "t1");
+        let (status, _) = send(create(r#"{"thread_id":"t1"}"#)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, body) = send(create("{}")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(!body["thread_id"].as_str().unwrap().is_empty());
+
+        let (status, body) = send(empty("GET", "/v1/threads")).await;
+        assert_eq!(status, StatusCode::OK);
+        let threads = body["threads"].as_array().unwrap();
+        assert_eq!(threads.len(), 2);
+        assert!(threads.iter().all(|t| t["last_activity_at"].is_u64()));
+
+        let (status, _) = send(
+            Request::post("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .header(THREAD_ID_HEADER, "t1")
+                .body(Body::from(
+                    r#"{"model":"m","stream":false,"messages":[{"role":"user","content":"Hi"}]}"#,
+                ))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = send(empty("GET", "/v1/threads/t1")).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let roles: Vec<_> = body["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["role"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+        assert_eq!(body["messages"][2]["content"], "Hello back");
+
+        let append = |uri: &str, body: &str| {
+            Request::post(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let imported = r#"{"messages":[
+            {"role":"user","content":"What time is it?"},
+            {"role":"assistant","content":null,"tool_calls":[
+                {"id":"c1","type":"function","function":{"name":"get_time","arguments":"{}"}}]},
+            {"role":"tool","tool_call_id":"c1","name":"get_time","content":"12:00"},
+            {"role":"assistant","content":"It is noon."}]}"#;
+        let (status, body) = send(append("/v1/threads/t1/messages", imported)).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(
+            (body["appended"].as_u64(), body["message_count"].as_u64()),
+            (Some(4), Some(7))
+        );
+        let (_, body) = send(empty("GET", "/v1/threads/t1")).await;
+        assert_eq!(
+            body["messages"][4]["tool_calls"][0]["function"]["name"],
+            "get_time"
+        );
+        assert_eq!(body["messages"][5]["tool_call_id"], "c1");
+        let (status, body) = send(append(
+            "/v1/threads/new/messages",
+            r#"{"messages":[{"role":"user","content":"Hi"}]}"#,
+        ))
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["message_count"], 2);
+        let (status, _) = send(append("/v1/threads/t1/messages", r#"{"messages":[]}"#)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let bad_role = r#"{"messages":[{"role":"robot","content":"beep"}]}"#;
+        let (status, _) = send(append("/v1/threads/t1/messages", bad_role)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        switch.set(true);
+        let (status, _) = send(append("/v1/threads/t1/messages", imported)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let (status, _) = send(empty("DELETE", "/v1/threads/t1")).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        switch.set(false);
+        let (status, body) = send(empty("DELETE", "/v1/threads/t1")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["deleted"], true);
+        let (status, _) = send(empty("GET", "/v1/threads/t1")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(empty("DELETE", "/v1/threads/t1")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    /// **Scenario**: With per-user databases, a thread created for one `X-User-Id` is neither
+    /// listed nor deleted for another user with the same thread id.
+    #[tokio::test]
+    async fn threads_api_stays_in_the_users_database() {
+        let dir =
+            std::env::temp_dir().join(format!("langgraph-server-tenants-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let router = Arc::new(langgraph::DbRouter::<ReActState>::new(
+            langgraph::SqliteTenants::new(&dir, Arc::new(langgraph::JsonSerializer)),
+        ));
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello")),
+            Box::new(MockToolSource::get_time_example()),
+            Some(Arc::new(router.checkpointer())),
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let app = Router::new()
+            .route("/v1/threads", get(list_threads).post(create_thread))
+            .route(
+                "/v1/threads/:thread_id",
+                get(get_thread).delete(delete_thread),
+            )
+            .with_state(app_state(runner));
+        let send = |method: &str, uri: &str, user: &str, body: &str| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .header(USER_ID_HEADER, user)
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let res = app.oneshot(request).await.unwrap();
+                let status = res.status();
+                let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+                let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+                (status, body)
+            }
+        };
+
+        for user in ["alice", "bob"] {
+            let (status, _) = send("POST", "/v1/threads", user, r#"{"thread_id":"t1"}"#).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
+        let (status, _) = send("DELETE", "/v1/threads/t1", "alice", "").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send("DELETE", "/v1/threads/t1", "alice", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, body) = send("GET", "/v1/threads", "alice", "").await;
+        assert_eq!(body["threads"], serde_json::json!([]));
+        let (_, body) = send("GET", "/v1/threads", "bob", "").await;
+        assert_eq!(body["threads"][0]["thread_id"], "t1");
+        let (status, _) = send("GET", "/v1/threads/t1", "bob", "").await;
+        assert_eq!(status, StatusCode::OK);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// **Scenario**: A rate-limited LLM run answers 429 with Retry-After rounded up to whole
+    /// seconds; the streaming error event carries the same status and hint.
+    #[tokio::test]
+    async fn llm_error_maps_to_status_and_retry_after() {
+        let llm = LlmError::from_http(429, Some("2.5"), "Rate limit reached");
+        let err = RunError::Execution(AgentError::Llm(llm));
+
+        let event = stream_error_event(&err);
+        let payload: serde_json::Value =
+            serde_json::from_str(event.trim().trim_start_matches("data: ")).unwrap();
+        assert_eq!(payload["error"]["code"], 429);
+        assert_eq!(payload["error"]["retry_after"], 2.5);
+
+        let res = ServerError::from(err).into_response();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()[axum::http::header::RETRY_AFTER], "3");
+
+        let res = ServerError::from(RunError::Execution(AgentError::ExecutionFailed(
+            "boom".into(),
+        )))
+        .into_response();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(axum::http::header::RETRY_AFTER).is_none());
+    }
+
+    /// **Scenario**: A run stopped by its budget is a `budget_exceeded` error: 429 for plain
+    /// requests and the same type in the streaming error event.
+    #[tokio::test]
+    async fn budget_exceeded_maps_to_429_and_error_type() {
+        let err = RunError::from(AgentError::BudgetExceeded(
+            "token budget of 10 exceeded".into(),
+        ));
+
+        let event = stream_error_event(&err);
+        let payload: serde_json::Value =
+            serde_json::from_str(event.trim().trim_start_matches("data: ")).unwrap();
+        assert_eq!(payload["error"]["type"], "budget_exceeded");
+
+        let res = ServerError::from(err).into_response();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    /// **Scenario**: A user over the daily per-user cap is refused with 429 before the stream
+    /// starts, with the user-facing message and Retry-After; other users still chat.
+    #[tokio::test]
+    async fn user_over_daily_quota_gets_429_before_streaming() {
+        let store: Arc<dyn langgraph::memory::Store> =
+            Arc::new(langgraph::memory::InMemoryStore::new());
+        let used = langgraph::LlmUsage {
+            prompt_tokens: 80,
+            completion_tokens: 40,
+            total_tokens: 120,
+        };
+        langgraph::UsageQuota::new(Arc::clone(&store))
+            .record(Some("alice"), &[("gpt-4o".to_string(), used)].into())
+            .await
+            .unwrap();
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("Hello")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+        .with_usage_quota(langgraph::UsageQuota::new(store).with_daily_tokens_per_user(100));
+        let app = Router::new()
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(app_state(runner));
+        let chat = |user: &str| {
+            app.clone().oneshot(
+                Request::post("/v1/chat/completions")
+                    .header("content-type", "application/json")
+                    .header(USER_ID_HEADER, user)
+                    .body(Body::from(
+                        r#"{"model":"gpt-4o","stream":true,"messages":[{"role":"user","content":"Hi"}]}"#,
+                    ))
+                    .unwrap(),
+            )
+        };
+
+        let res = chat("alice").await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(res.headers().contains_key(axum::http::header::RETRY_AFTER));
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"]["type"], QUOTA_EXCEEDED);
+        let message = body["error"]["message"].as_str().unwrap();
+        assert!(message.contains("midnight UTC"), "{}", message);
+
+        assert_eq!(chat("bob").await.unwrap().status(), StatusCode::OK);
+    }
+
+    /// **Scenario**: When OPENAI_BASE_URL is not set, GET /v1/models returns 503.
+    #[tokio::test]
+    async fn models_list_returns_503_when_base_url_not_configured() {
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("ok")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(1))
+            .build()
+            .expect("client");
+        let state = Arc::new(AppState {
+            runner: RunnerSlot::new(Arc::new(runner)),
+            title_llm: Arc::new(MockLlm::with_no_tool_calls("Untitled")),
+            openai_base_url: None,
+            openai_api_key: "sk-test".to_string(),
+            http_client,
+            transcripts: Arc::new(TranscriptStore::new(8)),
+            api_keys: Arc::new(ApiKeys::default()),
+            modes: Arc::new(ServerModes::default()),
+            admin_api_key: None,
+            runtime: Arc::new(RuntimeConfig::default()),
+            metrics: Arc::new(PrometheusRecorder::new()),
+            injectors: Arc::new(RunInjectors::default()),
+            sqlite_files: None,
+        });
+        let app = Router::new()
+            .route("/v1/models", get(models_list))
+            .route("/v1/models/:model_id", get(model_retrieve))
+            .with_state(state);
+        let res = app
+            .oneshot(Request::get("/v1/models").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    /// **Scenario**: GET /metrics renders recorded series in the Prometheus text format, plus
+    /// the runs in flight.
+    #[tokio::test]
+    async fn metrics_endpoint_renders_recorded_series() {
+        use langgraph::MetricsRecorder;
+
+        let runner = ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls("ok")),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile");
+        let state = app_state(runner);
+        state
+            .metrics
+            .tool_call("get_time", std::time::Duration::from_millis(3), false);
+        let app = Router::new()
+            .route("/metrics", get(metrics::metrics))
+            .with_state(state);
+        let res = app
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain; version=0.0.4"));
+        let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        let series = "langgraph_tool_calls_total{tool=\"get_time\",status=\"error\"} 1\n";
+        assert!(body.contains(series), "{}", body);
+        assert!(body.contains("langgraph_server_in_flight_runs 0\n"));
+    }
+
+    /// **Scenario**: CORS answers only listed origins; without origins no CORS headers are sent,
+    /// and an invalid origin is a startup error.
+    #[tokio::test]
+    async fn cors_allows_only_configured_origins() {
+        assert!(cors_layer(&[]).unwrap().is_none());
+        assert!(cors_layer(&["bad\norigin".to_string()]).is_err());
+
+        let origins = vec!["https://app.example.com".to_string()];
+        let app = Router::new()
+            .route("/v1/models", get(|| async { "ok" }))
+            .layer(cors_layer(&origins).unwrap().unwrap());
+        let allowed = |origin: &'static str| {
+            let app = app.clone();
+            async move {
+                let res = app
+                    .oneshot(
+                        Request::get("/v1/models")
+                            .header("origin", origin)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                res.headers()
+                    .get("access-control-allow-origin")
+                    .map(|v| v.to_str().unwrap().to_string())
+            }
+        };
+        assert_eq!(
+            allowed("https://app.example.com").await.as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(allowed("https://evil.example.com").await, None);
     }
 }
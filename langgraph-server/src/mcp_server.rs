@@ -0,0 +1,306 @@
+//! The agent as an MCP server: one tool, `ask_agent`, over stdio or Streamable HTTP.
+//!
+//! MCP clients (desktop assistants, editors) call `ask_agent` with a `question` and optional
+//! `thread_id` / `user_id`; the question runs through the server's `ReactRunner` like a chat
+//! request and the final answer comes back as text content. Runs that fail are reported as a
+//! tool result with `isError: true`, so the client's model sees what went wrong.
+//!
+//! Transports: POST /mcp takes one JSON-RPC message (or a batch) and answers with JSON;
+//! notifications get 202. With `--mcp-stdio` the server reads newline-delimited JSON-RPC from
+//! stdin and answers on stdout instead of listening; logs then go to stderr. Sessions and
+//! server-initiated messages are not used, so GET /mcp is not served.
+//!
+//! **Interaction**: [`handle_message`] implements `initialize`, `ping`, `tools/list` and
+//! `tools/call` on top of `ReactRunner::invoke_with_config`; [`mcp_http`] and [`serve_stdio`]
+//! are its transports.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use langgraph::{ReactRunner, RunnableConfig};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{AppState, ServerError};
+
+/// Name of the tool that asks the agent.
+pub const ASK_AGENT: &str = "ask_agent";
+
+/// MCP protocol versions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn rpc_result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn rpc_error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() }
+    })
+}
+
+/// The tool list: `ask_agent` only.
+fn tools() -> Value {
+    json!([{
+        "name": ASK_AGENT,
+        "description": "Ask the agent a question. It may use its own tools (search, memory, \
+                        time, ...) before answering. Pass the same thread_id to continue a \
+                        conversation.",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "question": { "type": "string", "description": "The question or task" },
+                "thread_id": {
+                    "type": "string",
+                    "description": "Conversation to continue; omit for a one-off question"
+                },
+                "user_id": { "type": "string", "description": "User the question is asked for" }
+            },
+            "required": ["question"]
+        }
+    }])
+}
+
+/// Whether a message is a `tools/call` request, which starts an agent run.
+pub fn is_tool_call(message: &Value) -> bool {
+    message.get("method").and_then(Value::as_str) == Some("tools/call")
+}
+
+/// Handles one JSON-RPC message; `None` for notifications, which get no response.
+pub async fn handle_message(runner: &ReactRunner, message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let response = match message.get("method").and_then(Value::as_str) {
+        Some("initialize") => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str);
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|v| Some(**v) == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            rpc_result(
+                id,
+                json!({
+                    "protocolVersion": version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": "langgraph-server",
+                        "version": env!("CARGO_PKG_VERSION")
+                    }
+                }),
+            )
+        }
+        Some("ping") => rpc_result(id, json!({})),
+        Some("tools/list") => rpc_result(id, json!({ "tools": tools() })),
+        Some("tools/call") => call_tool(runner, id, &params).await,
+        Some(method) => rpc_error(id, METHOD_NOT_FOUND, format!("unknown method: {}", method)),
+        None => rpc_error(id, METHOD_NOT_FOUND, "missing method"),
+    };
+    Some(response)
+}
+
+/// Runs `ask_agent`; failed runs are a result with `isError: true`, bad arguments an error.
+async fn call_tool(runner: &ReactRunner, id: Value, params: &Value) -> Value {
+    let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+    if name != ASK_AGENT {
+        return rpc_error(id, INVALID_PARAMS, format!("unknown tool: {}", name));
+    }
+    let args = params.get("arguments").cloned().unwrap_or(Value::Null);
+    let arg = |key: &str| {
+        args.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let Some(question) = arg("question") else {
+        return rpc_error(id, INVALID_PARAMS, "question is required");
+    };
+    let config = RunnableConfig {
+        thread_id: arg("thread_id"),
+        user_id: arg("user_id"),
+        ..Default::default()
+    };
+    tracing::info!(thread_id = ?config.thread_id, "mcp ask_agent");
+    let (text, is_error) = match runner.invoke_with_config(&question, Some(config)).await {
+        Ok(state) => (
+            state
+                .final_answer()
+                .map(str::to_string)
+                .or_else(|| state.last_assistant_reply())
+                .unwrap_or_default(),
+            false,
+        ),
+        Err(e) => {
+            tracing::warn!(error = %e, "mcp ask_agent failed");
+            (e.to_string(), true)
+        }
+    };
+    rpc_result(
+        id,
+        json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error
+        }),
+    )
+}
+
+/// POST /mcp: Streamable HTTP transport. Answers a request (or batch) with JSON and
+/// notifications with 202; tool calls count as runs for maintenance mode and the rate limit.
+pub async fn mcp_http(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<Value>,
+) -> Result<Response, ServerError> {
+    let messages = match body {
+        Value::Array(messages) => messages,
+        message => vec![message],
+    };
+    let _run_guard = if messages.iter().any(is_tool_call) {
+        let guard = state.modes.begin_run()?;
+        state.runtime.check_rate()?;
+        Some(guard)
+    } else {
+        None
+    };
+    let mut responses = Vec::new();
+    for message in &messages {
+        responses.extend(handle_message(&state.runner.get(), message).await);
+    }
+    Ok(match responses.len() {
+        0 => StatusCode::ACCEPTED.into_response(),
+        1 if messages.len() == 1 => Json(responses.remove(0)).into_response(),
+        _ => Json(Value::Array(responses)).into_response(),
+    })
+}
+
+/// Stdio transport: reads one JSON-RPC message per line from `input` and writes each response
+/// as a line to `output`, until `input` ends. Messages are handled one at a time.
+pub async fn serve_stdio<R, W>(runner: &ReactRunner, input: R, mut output: W) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(input).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(runner, &message).await,
+            Err(e) => Some(rpc_error(Value::Null, PARSE_ERROR, e.to_string())),
+        };
+        if let Some(response) = response {
+            output.write_all(response.to_string().as_bytes()).await?;
+            output.write_all(b"\n").await?;
+            output.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use langgraph::{MockLlm, MockToolSource};
+
+    fn runner(reply: &str) -> ReactRunner {
+        ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls(reply)),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+    }
+
+    /// **Scenario**: initialize negotiates the client's version, tools/list offers ask_agent,
+    /// and notifications get no response.
+    #[tokio::test]
+    async fn initialize_and_list_tools() {
+        let runner = runner("ok");
+        let init = json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05", "capabilities": {} }
+        });
+        let res = handle_message(&runner, &init).await.unwrap();
+        assert_eq!(res["id"], 1);
+        assert_eq!(res["result"]["protocolVersion"], "2024-11-05");
+        assert!(res["result"]["capabilities"]["tools"].is_object());
+
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&runner, &initialized).await.is_none());
+
+        let list = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+        let res = handle_message(&runner, &list).await.unwrap();
+        assert_eq!(res["result"]["tools"][0]["name"], ASK_AGENT);
+    }
+
+    /// **Scenario**: ask_agent runs the agent and returns its answer; a missing question or
+    /// another tool name is invalid params.
+    #[tokio::test]
+    async fn ask_agent_returns_answer() {
+        let runner = runner("Paris");
+        let call = json!({
+            "jsonrpc": "2.0", "id": "c1", "method": "tools/call",
+            "params": { "name": ASK_AGENT, "arguments": { "question": "Capital of France?" } }
+        });
+        let res = handle_message(&runner, &call).await.unwrap();
+        assert_eq!(res["result"]["content"][0]["text"], "Paris");
+        assert_eq!(res["result"]["isError"], false);
+
+        for params in [
+            json!({ "name": ASK_AGENT, "arguments": {} }),
+            json!({ "name": "other", "arguments": { "question": "hi" } }),
+        ] {
+            let call =
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": params });
+            let res = handle_message(&runner, &call).await.unwrap();
+            assert_eq!(res["error"]["code"], INVALID_PARAMS, "{}", res);
+        }
+    }
+
+    /// **Scenario**: Over stdio each request line gets one response line, bad JSON a parse
+    /// error, and notifications nothing.
+    #[tokio::test]
+    async fn stdio_answers_line_by_line() {
+        let runner = runner("ok");
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            "not json\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        serve_stdio(&runner, input.as_bytes(), &mut output)
+            .await
+            .unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[0]["result"], json!({}));
+        assert_eq!(lines[1]["error"]["code"], PARSE_ERROR);
+        assert_eq!(lines[2]["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
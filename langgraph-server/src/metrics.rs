@@ -0,0 +1,38 @@
+//! Prometheus scrape endpoint: GET /metrics.
+//!
+//! Serves the graph runtime metrics langgraph records (node durations, LLM latency, tool calls,
+//! checkpoint latency, tokens; see `langgraph::metrics`) plus `langgraph_server_in_flight_runs`,
+//! in the Prometheus text format. Like every route, it requires an API key when keys are set.
+//!
+//! **Interaction**: `main` installs `AppState::metrics` as langgraph's process-wide recorder;
+//! `AppState::modes` counts the runs in flight.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+use crate::AppState;
+
+/// Content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// GET /metrics: all recorded series and the runs in flight.
+pub async fn metrics(State(state): State<Arc<AppState>>) -> Response {
+    let mut body = state.metrics.render();
+    let _ = writeln!(
+        body,
+        "# HELP langgraph_server_in_flight_runs Chat runs currently executing.\n\
+         # TYPE langgraph_server_in_flight_runs gauge\n\
+         langgraph_server_in_flight_runs {}",
+        state.modes.status().in_flight_runs
+    );
+    let mut res = body.into_response();
+    res.headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+    res
+}
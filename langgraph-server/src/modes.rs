@@ -0,0 +1,294 @@
+//! Read-only and maintenance modes, for backing up or migrating the SQLite files safely.
+//!
+//! - **Read-only** (`--read-only` / `READ_ONLY`): chat keeps working but nothing is persisted:
+//!   checkpoints are skipped and memory writes fail (see langgraph's `ReadOnlySwitch`).
+//! - **Maintenance** (`--maintenance` / `MAINTENANCE`): new runs (chat completions, resume) are
+//!   refused with 503 and `Retry-After`; runs already in flight finish. `in_flight_runs` in the
+//!   mode reaches 0 once the last one ended, after which the files are quiet.
+//!
+//! Both can be toggled at runtime: GET /v1/admin/mode reports them, POST /v1/admin/mode with
+//! `{"read_only": bool, "maintenance": bool}` (either optional) changes them. Admin requests must
+//! send `X-Admin-Key: <ADMIN_API_KEY>`; without `ADMIN_API_KEY` the admin endpoint is disabled.
+//!
+//! On shutdown (see `shutdown`) new runs are refused the same way, and runs still in flight when
+//! the drain timeout expires are stopped through the token of [`ServerModes::run_token`].
+//!
+//! **Interaction**: `chat_completions` and `resume_thread` hold a [`RunGuard`] from
+//! [`ServerModes::begin_run`] for the whole run (a streamed run hands it to its task).
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::{extract::State, http::HeaderMap, Json};
+use langgraph::{CancellationToken, ReadOnlySwitch};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::{AppState, ServerError};
+
+/// Header carrying the admin key.
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Seconds clients are told to wait before retrying a run refused during maintenance.
+pub const MAINTENANCE_RETRY_AFTER_SECS: u64 = 30;
+
+/// Current modes and the number of runs in flight.
+#[derive(Debug, Default)]
+pub struct ServerModes {
+    read_only: ReadOnlySwitch,
+    maintenance: AtomicBool,
+    shutting_down: AtomicBool,
+    in_flight: Arc<InFlight>,
+    stop_runs: CancellationToken,
+}
+
+/// Runs in flight, and a notification when the last one ends.
+#[derive(Debug, Default)]
+struct InFlight {
+    runs: AtomicUsize,
+    idle: Notify,
+}
+
+/// Body of GET /v1/admin/mode and of POST /v1/admin/mode's response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModeStatus {
+    pub read_only: bool,
+    pub maintenance: bool,
+    pub in_flight_runs: usize,
+}
+
+/// Body of POST /v1/admin/mode; absent fields keep their mode.
+#[derive(Debug, Default, Deserialize)]
+pub struct ModeUpdate {
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub maintenance: Option<bool>,
+}
+
+/// Counts one run in flight until dropped.
+#[derive(Debug)]
+pub struct RunGuard(Arc<InFlight>);
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        if self.0.runs.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl ServerModes {
+    /// Modes sharing `read_only` with the checkpointer and store built for the runner.
+    pub fn new(read_only: ReadOnlySwitch, maintenance: bool) -> Self {
+        Self {
+            read_only,
+            maintenance: AtomicBool::new(maintenance),
+            ..Default::default()
+        }
+    }
+
+    /// Registers a new run, or refuses it with 503 during maintenance or shutdown.
+    ///
+    /// The run is counted before the modes are checked, so once shutdown or maintenance is set
+    /// and [`wait_idle`](Self::wait_idle) returns, no run can still slip in.
+    pub fn begin_run(&self) -> Result<RunGuard, ServerError> {
+        self.in_flight.runs.fetch_add(1, Ordering::SeqCst);
+        let guard = RunGuard(Arc::clone(&self.in_flight));
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Err(ServerError::Unavailable(
+                "server is shutting down; retry later".to_string(),
+            ));
+        }
+        if self.maintenance.load(Ordering::SeqCst) {
+            return Err(ServerError::Unavailable(
+                "server is in maintenance mode; retry later".to_string(),
+            ));
+        }
+        Ok(guard)
+    }
+
+    /// Refuses new runs from now on; runs in flight go on.
+    pub fn begin_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`begin_shutdown`](Self::begin_shutdown) was called.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Cancellation token for one run, cancelled by [`stop_runs`](Self::stop_runs).
+    pub fn run_token(&self) -> CancellationToken {
+        self.stop_runs.child_token()
+    }
+
+    /// Cancels every run holding a [`run_token`](Self::run_token), past and future.
+    pub fn stop_runs(&self) {
+        self.stop_runs.cancel();
+    }
+
+    /// Whether [`stop_runs`](Self::stop_runs) was called.
+    pub fn runs_stopped(&self) -> bool {
+        self.stop_runs.is_cancelled()
+    }
+
+    /// Resolves once [`stop_runs`](Self::stop_runs) is called.
+    pub async fn wait_runs_stopped(&self) {
+        self.stop_runs.cancelled().await
+    }
+
+    /// Resolves once no run is in flight.
+    pub async fn wait_idle(&self) {
+        loop {
+            // Registered before the check, so a run ending in between still wakes us.
+            let idle = self.in_flight.idle.notified();
+            if self.in_flight.runs.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// 503 while read-only or in maintenance; for requests that only write (e.g. deleting a
+    /// thread).
+    pub fn check_writable(&self) -> Result<(), ServerError> {
+        let status = self.status();
+        if status.read_only || status.maintenance {
+            return Err(ServerError::Unavailable(
+                "server is read-only or in maintenance mode; retry later".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Applies the fields set in `update`.
+    pub fn apply(&self, update: &ModeUpdate) {
+        if let Some(read_only) = update.read_only {
+            self.read_only.set(read_only);
+        }
+        if let Some(maintenance) = update.maintenance {
+            self.maintenance.store(maintenance, Ordering::SeqCst);
+        }
+    }
+
+    /// Current modes and runs in flight.
+    pub fn status(&self) -> ModeStatus {
+        ModeStatus {
+            read_only: self.read_only.is_read_only(),
+            maintenance: self.maintenance.load(Ordering::SeqCst),
+            in_flight_runs: self.in_flight.runs.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// 403 unless `X-Admin-Key` matches `ADMIN_API_KEY`; always 403 when that is unset.
+pub fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), ServerError> {
+    let Some(expected) = state.admin_api_key.as_deref() else {
+        return Err(ServerError::Forbidden(
+            "admin endpoint disabled; set ADMIN_API_KEY".to_string(),
+        ));
+    };
+    let key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim);
+    if key != Some(expected) {
+        return Err(ServerError::Forbidden(
+            "invalid or missing X-Admin-Key".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /v1/admin/mode.
+pub async fn get_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ModeStatus>, ServerError> {
+    require_admin(&state, &headers)?;
+    Ok(Json(state.modes.status()))
+}
+
+/// POST /v1/admin/mode: applies the update and returns the new modes.
+pub async fn set_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(update): Json<ModeUpdate>,
+) -> Result<Json<ModeStatus>, ServerError> {
+    require_admin(&state, &headers)?;
+    state.modes.apply(&update);
+    let status = state.modes.status();
+    tracing::warn!(
+        read_only = status.read_only,
+        maintenance = status.maintenance,
+        in_flight_runs = status.in_flight_runs,
+        "server mode changed"
+    );
+    Ok(Json(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Maintenance refuses new runs while guards of running ones still count
+    /// until dropped; updates leave unset modes alone.
+    #[test]
+    fn maintenance_refuses_new_runs_and_counts_in_flight() {
+        let modes = ServerModes::new(ReadOnlySwitch::new(false), false);
+        let guard = modes.begin_run().expect("normal mode");
+        assert_eq!(modes.status().in_flight_runs, 1);
+
+        modes.apply(&ModeUpdate {
+            maintenance: Some(true),
+            ..Default::default()
+        });
+        assert!(matches!(
+            modes.begin_run(),
+            Err(ServerError::Unavailable(_))
+        ));
+        assert_eq!(modes.status().in_flight_runs, 1);
+        drop(guard);
+        assert_eq!(
+            modes.status(),
+            ModeStatus {
+                read_only: false,
+                maintenance: true,
+                in_flight_runs: 0
+            }
+        );
+
+        modes.apply(&ModeUpdate {
+            read_only: Some(true),
+            maintenance: Some(false),
+        });
+        assert!(modes.status().read_only);
+        assert!(modes.begin_run().is_ok());
+    }
+
+    /// **Scenario**: Runs started concurrently with shutdown either finish before the drain
+    /// sees no run in flight or are refused; none is admitted after the drain.
+    #[test]
+    fn no_run_starts_after_shutdown_drained() {
+        let modes = ServerModes::new(ReadOnlySwitch::new(false), false);
+        let drained = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    while let Ok(guard) = modes.begin_run() {
+                        assert!(!drained.load(Ordering::SeqCst), "run admitted after drain");
+                        drop(guard);
+                    }
+                });
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            modes.begin_shutdown();
+            while modes.status().in_flight_runs != 0 {
+                std::thread::yield_now();
+            }
+            drained.store(true, Ordering::SeqCst);
+        });
+        assert_eq!(modes.status().in_flight_runs, 0);
+    }
+}
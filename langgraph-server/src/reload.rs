@@ -0,0 +1,248 @@
+//! Config reload without a restart: SIGHUP rebuilds the agent from `.env` and the environment.
+//!
+//! On SIGHUP the server reads `.env` again, its values replacing those loaded before (variables
+//! removed from the file keep their old value), and rebuilds the runner as at startup: model,
+//! system prompt and profile, skills, MCP servers and tools, turn and timeout limits, quota,
+//! outcome classifier and history compression. Runs in flight finish on the runner they started
+//! with; threads live in the checkpointer, so they continue on the new runner. When the new
+//! config does not build, the error is logged and the old runner stays. The listen address,
+//! API keys, title model and the settings of `/v1/admin/config` are not reloaded. An A/B
+//! experiment (`EXPERIMENT_*`) is rebuilt too, so it can be started, changed or ended by reload.
+//!
+//! **Interaction**: `AppState::runner` is a [`RunnerSlot`]; `main` spawns
+//! [`reload_on_sighup`], which rebuilds with `load_build_config` and `build_agent`.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use langgraph::{Experiment, ReactRunner, ReadOnlySwitch, RunnableConfig};
+use tracing::{info, warn};
+
+use crate::config::Args;
+use crate::AppState;
+
+/// What serves runs: one runner, or the two runners of an A/B experiment.
+#[derive(Clone)]
+pub enum Agent {
+    Runner(Arc<ReactRunner>),
+    Experiment(Arc<Experiment>),
+}
+
+impl Agent {
+    /// The runner of runs not assigned by an experiment (variant a of one), e.g. for thread
+    /// management; every runner shares the checkpointer.
+    pub fn runner(&self) -> &Arc<ReactRunner> {
+        match self {
+            Self::Runner(runner) => runner,
+            Self::Experiment(experiment) => experiment.runner(langgraph::Variant::A),
+        }
+    }
+
+    /// The runner of a run with `config` in request `request_id`: the experiment's assigned
+    /// variant, else the only runner.
+    pub fn runner_for(&self, config: &RunnableConfig, request_id: &str) -> &Arc<ReactRunner> {
+        match self {
+            Self::Runner(runner) => runner,
+            Self::Experiment(experiment) => {
+                experiment.runner(experiment.assign(Some(config), request_id))
+            }
+        }
+    }
+}
+
+impl From<ReactRunner> for Agent {
+    fn from(runner: ReactRunner) -> Self {
+        Self::Runner(Arc::new(runner))
+    }
+}
+
+impl From<Arc<ReactRunner>> for Agent {
+    fn from(runner: Arc<ReactRunner>) -> Self {
+        Self::Runner(runner)
+    }
+}
+
+impl From<Experiment> for Agent {
+    fn from(experiment: Experiment) -> Self {
+        Self::Experiment(Arc::new(experiment))
+    }
+}
+
+/// The current agent; requests take a runner with [`get`](Self::get) or
+/// [`get_for`](Self::get_for) when they start.
+pub struct RunnerSlot(RwLock<Agent>);
+
+impl RunnerSlot {
+    pub fn new(agent: impl Into<Agent>) -> Self {
+        Self(RwLock::new(agent.into()))
+    }
+
+    fn agent(&self) -> Agent {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The current runner (variant a during an experiment); stays valid after a
+    /// [`replace`](Self::replace).
+    pub fn get(&self) -> Arc<ReactRunner> {
+        Arc::clone(self.agent().runner())
+    }
+
+    /// The runner for a run with `config` in request `request_id`: during an experiment, the
+    /// variant the thread or request is assigned to.
+    pub fn get_for(&self, config: &RunnableConfig, request_id: &str) -> Arc<ReactRunner> {
+        Arc::clone(self.agent().runner_for(config, request_id))
+    }
+
+    /// Makes `agent` the one later requests get.
+    pub fn replace(&self, agent: impl Into<Agent>) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = agent.into();
+    }
+}
+
+/// Rebuilds the runner on every SIGHUP until the process exits.
+#[cfg(unix)]
+pub async fn reload_on_sighup(state: Arc<AppState>, args: Args, read_only: ReadOnlySwitch) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(signal) => signal,
+        Err(e) => {
+            warn!(error = %e, "cannot listen for SIGHUP; config reload disabled");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        info!("SIGHUP: reloading config");
+        match reload(&state, &args, &read_only).await {
+            Ok(model) => info!(model = %model, "config reloaded"),
+            Err(e) => warn!(error = %e, "config reload failed; keeping the previous config"),
+        }
+    }
+}
+
+/// Reads `.env` again and swaps in a runner built from it; returns the new model.
+#[cfg(unix)]
+async fn reload(
+    state: &AppState,
+    args: &Args,
+    read_only: &ReadOnlySwitch,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    reload_dotenv()?;
+    let build_config = crate::load_build_config(args)?;
+    let agent = crate::build_agent(
+        args,
+        &build_config,
+        read_only,
+        &state.runtime,
+        &state.title_llm,
+    )
+    .await?;
+    state.runner.replace(agent);
+    Ok(build_config
+        .model
+        .unwrap_or_else(|| crate::config::DEFAULT_MODEL.to_string()))
+}
+
+/// Sets the variables of the nearest `.env` (current directory or a parent), replacing the
+/// values already in the environment; returns the file, None when there is none.
+#[cfg(unix)]
+pub fn reload_dotenv() -> Result<Option<PathBuf>, dotenv::Error> {
+    let path = std::env::current_dir().ok().and_then(|cwd| {
+        cwd.ancestors()
+            .map(|dir| dir.join(".env"))
+            .find(|path| path.is_file())
+    });
+    match path {
+        Some(path) => {
+            set_env_from_file(&path)?;
+            Ok(Some(path))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Sets every variable of the env file at `path`, replacing existing values.
+#[cfg(unix)]
+fn set_env_from_file(path: &Path) -> Result<(), dotenv::Error> {
+    for item in dotenv::from_path_iter(path)? {
+        let (key, value) = item?;
+        std::env::set_var(key, value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use langgraph::{MockLlm, MockToolSource};
+
+    fn runner(reply: &str) -> ReactRunner {
+        ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls(reply)),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+    }
+
+    /// **Scenario**: After a replace, new requests get the new runner while a run holding the
+    /// old one can still finish on it.
+    #[tokio::test]
+    async fn replace_keeps_runs_in_flight_on_the_old_runner() {
+        let slot = RunnerSlot::new(Arc::new(runner("old")));
+        let in_flight = slot.get();
+        slot.replace(runner("new"));
+
+        let old = in_flight.invoke("hi").await.unwrap();
+        assert_eq!(old.last_assistant_reply().as_deref(), Some("old"));
+        let new = slot.get().invoke("hi").await.unwrap();
+        assert_eq!(new.last_assistant_reply().as_deref(), Some("new"));
+    }
+
+    /// **Scenario**: During an experiment a thread always gets its assigned variant, while
+    /// `get` returns variant a; replacing the experiment with one runner ends it.
+    #[tokio::test]
+    async fn experiment_routes_threads_to_their_variant() {
+        let experiment = Experiment::new("reply", runner("a"), runner("b"));
+        let thread_on = |variant| {
+            (0..)
+                .map(|i| format!("thread-{}", i))
+                .find(|t| experiment.variant_for(t) == variant)
+                .unwrap()
+        };
+        let config = RunnableConfig {
+            thread_id: Some(thread_on(langgraph::Variant::B)),
+            ..Default::default()
+        };
+        let slot = RunnerSlot::new(experiment);
+
+        for request_id in ["req-1", "req-2"] {
+            let runner = slot.get_for(&config, request_id);
+            let state = runner.invoke("hi").await.unwrap();
+            assert_eq!(state.last_assistant_reply().as_deref(), Some("b"));
+        }
+        let control = slot.get().invoke("hi").await.unwrap();
+        assert_eq!(control.last_assistant_reply().as_deref(), Some("a"));
+
+        slot.replace(runner("single"));
+        let state = slot.get_for(&config, "req-3").invoke("hi").await.unwrap();
+        assert_eq!(state.last_assistant_reply().as_deref(), Some("single"));
+    }
+
+    /// **Scenario**: Values from the env file replace values already set.
+    #[cfg(unix)]
+    #[test]
+    fn env_file_values_replace_existing_ones() {
+        let path = std::env::temp_dir().join(format!("reload-test-{}.env", std::process::id()));
+        std::fs::write(&path, "RELOAD_TEST_MODEL=gpt-4o\nRELOAD_TEST_NEW=1\n").unwrap();
+        std::env::set_var("RELOAD_TEST_MODEL", "gpt-4o-mini");
+
+        set_env_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(std::env::var("RELOAD_TEST_MODEL").unwrap(), "gpt-4o");
+        assert_eq!(std::env::var("RELOAD_TEST_NEW").unwrap(), "1");
+    }
+}
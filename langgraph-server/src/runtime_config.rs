@@ -0,0 +1,459 @@
+//! Runtime configuration changed through the admin API, without a restart.
+//!
+//! GET /v1/admin/config reports the settings; POST /v1/admin/config changes the fields it
+//! sends (`null` removes a limit):
+//!
+//! - `log_level`: tracing filter in `RUST_LOG` syntax (e.g. `debug`, `info,langgraph=trace`).
+//! - `max_runs_per_minute`: runs (chat completions, resumes) accepted per rolling minute; more
+//!   answer 429 with `Retry-After`.
+//! - `enabled_tools`: tools offered to the model and callable (`null`: all of them).
+//! - `model_allowlist`: `model` values accepted by /v1/chat/completions (`null`: any); others
+//!   answer 403.
+//! - `budget`: `max_tokens`, `max_cost_usd` and `max_tool_calls` per run, as `--max-run-*`;
+//!   replaces all three.
+//!
+//! Changes apply to runs started afterwards; a run in flight sees new tool and budget settings
+//! from its next step. Every change is audited: logged at WARN with target `audit` and kept
+//! (the last [`AUDIT_LOG_LEN`]) for GET /v1/admin/audit. Admin requests need `X-Admin-Key` as
+//! for /v1/admin/mode.
+//!
+//! **Interaction**: The runner's tool source and LLM share the [`ToolAllowlist`]; the runner's
+//! governor is a [`RuntimeBudget`]; `init_tracing` hands over the [`LogReloadHandle`].
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use axum::{extract::State, http::HeaderMap, Json};
+use langgraph::{
+    BudgetPolicy, GovernorDecision, ModelPriceTable, ReActState, RunGovernor, RunProgress,
+    ToolAllowlist,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::modes::require_admin;
+use crate::{AppState, ServerError};
+
+/// Handle swapping the log filter of the running subscriber.
+pub type LogReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Log filter used when `RUST_LOG` is not set.
+pub const DEFAULT_LOG_FILTER: &str = "info,langgraph_server=debug";
+
+/// Number of audit entries kept for GET /v1/admin/audit.
+pub const AUDIT_LOG_LEN: usize = 100;
+
+/// Window of `max_runs_per_minute`.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Per-run limits; unset limits do not apply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetSettings {
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub max_tool_calls: Option<usize>,
+}
+
+/// Settings adjustable at runtime; the body of GET /v1/admin/config.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuntimeSettings {
+    pub log_level: String,
+    pub max_runs_per_minute: Option<u32>,
+    pub enabled_tools: Option<Vec<String>>,
+    pub model_allowlist: Option<Vec<String>>,
+    pub budget: BudgetSettings,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            log_level: DEFAULT_LOG_FILTER.to_string(),
+            max_runs_per_minute: None,
+            enabled_tools: None,
+            model_allowlist: None,
+            budget: BudgetSettings::default(),
+        }
+    }
+}
+
+/// Body of POST /v1/admin/config; absent fields keep their value, `null` clears a limit.
+#[derive(Debug, Default, Deserialize)]
+pub struct SettingsUpdate {
+    #[serde(default)]
+    pub log_level: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub max_runs_per_minute: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub enabled_tools: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub model_allowlist: Option<Option<Vec<String>>>,
+    #[serde(default)]
+    pub budget: Option<BudgetSettings>,
+}
+
+/// Tells a field sent as `null` (`Some(None)`) from an absent one (`None`, via `default`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// One audited change: the fields it changed, each as `{"from", "to"}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEntry {
+    /// Unix seconds of the change.
+    pub at: u64,
+    pub changes: BTreeMap<String, Value>,
+}
+
+/// Current runtime settings and the state enforcing them.
+pub struct RuntimeConfig {
+    settings: RwLock<RuntimeSettings>,
+    /// Budget built from `settings.budget`; None when it sets no limit.
+    budget: RwLock<Option<Arc<BudgetPolicy>>>,
+    /// Model priced by the budget until a reply names the model that served it.
+    budget_model: Option<String>,
+    tools: ToolAllowlist,
+    log: Option<LogReloadHandle>,
+    /// Start times of the runs accepted in the last [`RATE_WINDOW`].
+    recent_runs: Mutex<VecDeque<Instant>>,
+    audit: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::new(RuntimeSettings::default(), None, ToolAllowlist::new())
+    }
+}
+
+impl RuntimeConfig {
+    /// Config starting from `settings`; `tools` is the allowlist shared with the runner.
+    pub fn new(
+        settings: RuntimeSettings,
+        budget_model: Option<String>,
+        tools: ToolAllowlist,
+    ) -> Self {
+        tools.set(settings.enabled_tools.clone());
+        let budget = budget_policy(&settings.budget, budget_model.as_deref());
+        Self {
+            settings: RwLock::new(settings),
+            budget: RwLock::new(budget),
+            budget_model,
+            tools,
+            log: None,
+            recent_runs: Mutex::new(VecDeque::new()),
+            audit: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Applies `log_level` changes to the running subscriber through `handle`.
+    pub fn with_log_handle(mut self, handle: LogReloadHandle) -> Self {
+        self.log = Some(handle);
+        self
+    }
+
+    /// Current settings.
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 403 unless `model` is in the model allowlist (when one is set).
+    pub fn check_model(&self, model: &str) -> Result<(), ServerError> {
+        let settings = self.settings.read().unwrap_or_else(|e| e.into_inner());
+        match &settings.model_allowlist {
+            Some(models) if !models.iter().any(|m| m == model) => Err(ServerError::Forbidden(
+                format!("model {} is not allowed", model),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a new run, or refuses it with 429 when `max_runs_per_minute` runs started in the
+    /// last minute.
+    pub fn check_rate(&self) -> Result<(), ServerError> {
+        let Some(max) = self.settings().max_runs_per_minute else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let mut runs = self.recent_runs.lock().unwrap_or_else(|e| e.into_inner());
+        while runs
+            .front()
+            .is_some_and(|start| now.duration_since(*start) >= RATE_WINDOW)
+        {
+            runs.pop_front();
+        }
+        if runs.len() >= max as usize {
+            let oldest = runs.front().copied().unwrap_or(now);
+            let wait = RATE_WINDOW.saturating_sub(now.duration_since(oldest));
+            return Err(ServerError::RateLimited(wait.as_secs().max(1)));
+        }
+        runs.push_back(now);
+        Ok(())
+    }
+
+    /// The current budget, if it sets a limit.
+    pub fn budget(&self) -> Option<Arc<BudgetPolicy>> {
+        self.budget
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The enabled-tools allowlist, shared with the runner's tool source and LLM.
+    pub fn tools(&self) -> &ToolAllowlist {
+        &self.tools
+    }
+
+    /// Validates and applies `update`, audits what changed and returns the new settings.
+    pub fn apply(&self, update: SettingsUpdate) -> Result<RuntimeSettings, ServerError> {
+        let log_filter = match &update.log_level {
+            Some(level) => Some(
+                EnvFilter::try_new(level)
+                    .map_err(|e| ServerError::BadRequest(format!("invalid log_level: {}", e)))?,
+            ),
+            None => None,
+        };
+        if update.max_runs_per_minute == Some(Some(0)) {
+            return Err(ServerError::BadRequest(
+                "max_runs_per_minute must be at least 1; send null for no limit".to_string(),
+            ));
+        }
+        if let Some(cost) = update.budget.as_ref().and_then(|b| b.max_cost_usd) {
+            if cost.is_nan() || cost <= 0.0 {
+                return Err(ServerError::BadRequest(
+                    "budget.max_cost_usd must be positive".to_string(),
+                ));
+            }
+        }
+
+        let mut settings = self.settings.write().unwrap_or_else(|e| e.into_inner());
+        let before = settings.clone();
+        if let Some(filter) = log_filter {
+            if let Some(handle) = &self.log {
+                handle
+                    .reload(filter)
+                    .map_err(|e| ServerError::Internal(format!("log reload failed: {}", e)))?;
+            }
+        }
+        if let Some(level) = update.log_level {
+            settings.log_level = level;
+        }
+        if let Some(max) = update.max_runs_per_minute {
+            settings.max_runs_per_minute = max;
+        }
+        if let Some(tools) = update.enabled_tools {
+            self.tools.set(tools.clone());
+            settings.enabled_tools = tools;
+        }
+        if let Some(models) = update.model_allowlist {
+            settings.model_allowlist = models;
+        }
+        if let Some(budget) = update.budget {
+            *self.budget.write().unwrap_or_else(|e| e.into_inner()) =
+                budget_policy(&budget, self.budget_model.as_deref());
+            settings.budget = budget;
+        }
+        let after = settings.clone();
+        drop(settings);
+        self.audit(&before, &after);
+        Ok(after)
+    }
+
+    /// Audit entries, oldest first.
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        let audit = self.audit.lock().unwrap_or_else(|e| e.into_inner());
+        audit.iter().cloned().collect()
+    }
+
+    /// Records the fields that differ between `before` and `after`, if any.
+    fn audit(&self, before: &RuntimeSettings, after: &RuntimeSettings) {
+        let (Value::Object(before), Value::Object(after)) = (
+            serde_json::to_value(before).unwrap_or_default(),
+            serde_json::to_value(after).unwrap_or_default(),
+        ) else {
+            return;
+        };
+        let changes: BTreeMap<String, Value> = after
+            .into_iter()
+            .filter(|(field, to)| before.get(field) != Some(to))
+            .map(|(field, to)| {
+                let from = before.get(&field).cloned().unwrap_or(Value::Null);
+                (field, serde_json::json!({ "from": from, "to": to }))
+            })
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        tracing::warn!(
+            target: "audit",
+            changes = %serde_json::to_string(&changes).unwrap_or_default(),
+            "runtime config changed"
+        );
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut audit = self.audit.lock().unwrap_or_else(|e| e.into_inner());
+        if audit.len() == AUDIT_LOG_LEN {
+            audit.pop_front();
+        }
+        audit.push_back(AuditEntry { at, changes });
+    }
+}
+
+/// Budget enforcing `settings`, priced like `ReactBuildConfig::budget_policy`.
+fn budget_policy(settings: &BudgetSettings, model: Option<&str>) -> Option<Arc<BudgetPolicy>> {
+    let mut budget = BudgetPolicy::new();
+    if let Some(max) = settings.max_tokens {
+        budget = budget.with_max_tokens(max);
+    }
+    if let Some(max) = settings.max_cost_usd {
+        budget = budget.with_max_cost_usd(max, ModelPriceTable::openai_defaults());
+    }
+    if let Some(max) = settings.max_tool_calls {
+        budget = budget.with_max_tool_calls(max);
+    }
+    if let Some(model) = model {
+        budget = budget.with_model(model);
+    }
+    Some(budget).filter(|b| !b.is_unlimited()).map(Arc::new)
+}
+
+/// Runner governor enforcing the budget currently set in a [`RuntimeConfig`].
+pub struct RuntimeBudget(Arc<RuntimeConfig>);
+
+impl RuntimeBudget {
+    pub fn new(config: Arc<RuntimeConfig>) -> Self {
+        Self(config)
+    }
+}
+
+#[async_trait]
+impl RunGovernor<ReActState> for RuntimeBudget {
+    async fn decide(&self, state: &ReActState, progress: &RunProgress) -> GovernorDecision {
+        match self.0.budget() {
+            Some(budget) => budget.decide(state, progress).await,
+            None => GovernorDecision::Continue,
+        }
+    }
+}
+
+/// GET /v1/admin/config.
+pub async fn get_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<RuntimeSettings>, ServerError> {
+    require_admin(&state, &headers)?;
+    Ok(Json(state.runtime.settings()))
+}
+
+/// POST /v1/admin/config: applies the update and returns the new settings.
+pub async fn set_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(update): Json<SettingsUpdate>,
+) -> Result<Json<RuntimeSettings>, ServerError> {
+    require_admin(&state, &headers)?;
+    Ok(Json(state.runtime.apply(update)?))
+}
+
+/// GET /v1/admin/audit: `{"entries": [...]}`, oldest first.
+pub async fn get_audit(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ServerError> {
+    require_admin(&state, &headers)?;
+    Ok(Json(
+        serde_json::json!({ "entries": state.runtime.audit_log() }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(body: &str) -> SettingsUpdate {
+        serde_json::from_str(body).unwrap()
+    }
+
+    /// **Scenario**: An update changes only the fields it sends (`null` clears a limit),
+    /// reaches the shared tool allowlist and budget, and audits each changed field once;
+    /// invalid values are refused without changing anything.
+    #[test]
+    fn apply_changes_sent_fields_and_audits_them() {
+        let tools = ToolAllowlist::new();
+        let config = RuntimeConfig::new(
+            RuntimeSettings {
+                max_runs_per_minute: Some(10),
+                ..Default::default()
+            },
+            Some("gpt-4o-mini".to_string()),
+            tools.clone(),
+        );
+        assert!(config.budget().is_none());
+
+        let settings = config
+            .apply(update(
+                r#"{"max_runs_per_minute": null, "enabled_tools": ["get_time"],
+                    "budget": {"max_tokens": 1000}}"#,
+            ))
+            .unwrap();
+        assert_eq!(settings.max_runs_per_minute, None);
+        assert_eq!(settings.log_level, DEFAULT_LOG_FILTER);
+        assert!(tools.allows("get_time"));
+        assert!(!tools.allows("bash"));
+        assert!(config.budget().is_some());
+
+        let audit = config.audit_log();
+        assert_eq!(audit.len(), 1);
+        let fields: Vec<_> = audit[0].changes.keys().cloned().collect();
+        assert_eq!(fields, ["budget", "enabled_tools", "max_runs_per_minute"]);
+        assert_eq!(audit[0].changes["max_runs_per_minute"]["from"], 10);
+
+        assert!(matches!(
+            config.apply(update(r#"{"log_level": "[invalid"}"#)),
+            Err(ServerError::BadRequest(_))
+        ));
+        assert!(config
+            .apply(update(r#"{"max_runs_per_minute": 0}"#))
+            .is_err());
+        config.apply(update("{}")).unwrap();
+        assert_eq!(config.audit_log().len(), 1);
+    }
+
+    /// **Scenario**: The model allowlist refuses other models; the run rate limit refuses runs
+    /// over the limit with a retry hint.
+    #[test]
+    fn model_allowlist_and_rate_limit() {
+        let config = RuntimeConfig::default();
+        assert!(config.check_model("anything").is_ok());
+        config
+            .apply(update(
+                r#"{"model_allowlist": ["gpt-4o-mini"], "max_runs_per_minute": 2}"#,
+            ))
+            .unwrap();
+        assert!(config.check_model("gpt-4o-mini").is_ok());
+        assert!(matches!(
+            config.check_model("gpt-4o"),
+            Err(ServerError::Forbidden(_))
+        ));
+
+        assert!(config.check_rate().is_ok());
+        assert!(config.check_rate().is_ok());
+        match config.check_rate() {
+            Err(ServerError::RateLimited(secs)) => assert!((1..=60).contains(&secs)),
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Graceful shutdown: draining runs in flight on SIGTERM / SIGINT.
+//!
+//! On the first signal the server stops accepting connections and refuses new runs with 503
+//! (see `modes`); runs in flight, streamed or not, go on for up to `--drain-timeout-secs`
+//! (`DRAIN_TIMEOUT_SECS`, default [`DEFAULT_DRAIN_TIMEOUT_SECS`]). Runs still going then, or on a
+//! second signal, are stopped: a streamed one ends with a `server_shutdown` error event and
+//! `data: [DONE]`, a non-streamed one answers 503. Checkpoints are written after every step, so
+//! a stopped thread resumes from its last completed step.
+//!
+//! **Interaction**: [`drain_on_signal`] is the `with_graceful_shutdown` future of `main`;
+//! runs take their cancellation token from `ServerModes::run_token`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::modes::ServerModes;
+
+/// Seconds runs in flight may take to finish after a shutdown signal.
+pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 30;
+
+/// Error type of the SSE event ending a stream stopped by shutdown.
+pub const SERVER_SHUTDOWN: &str = "server_shutdown";
+
+/// Resolves on SIGTERM or SIGINT (Ctrl-C).
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!(error = %e, "cannot listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "cannot listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Waits for a shutdown signal, then starts draining; resolving tells axum to stop accepting
+/// connections and wait for open ones.
+pub async fn drain_on_signal(modes: Arc<ServerModes>, timeout: Duration) {
+    shutdown_signal().await;
+    start_drain(modes, timeout, shutdown_signal());
+}
+
+/// Refuses new runs and stops the ones still in flight after `timeout`, or as soon as `force`
+/// resolves (e.g. a second signal).
+pub fn start_drain(
+    modes: Arc<ServerModes>,
+    timeout: Duration,
+    force: impl std::future::Future<Output = ()> + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    info!(
+        in_flight_runs = modes.status().in_flight_runs,
+        drain_timeout_secs = timeout.as_secs(),
+        "shutdown requested; draining runs in flight"
+    );
+    modes.begin_shutdown();
+    tokio::spawn(async move {
+        let stop = tokio::select! {
+            _ = modes.wait_idle() => None,
+            _ = tokio::time::sleep(timeout) => Some("drain timeout reached"),
+            _ = force => Some("second shutdown signal"),
+        };
+        match stop {
+            None => info!("all runs finished"),
+            Some(reason) => {
+                warn!(
+                    in_flight_runs = modes.status().in_flight_runs,
+                    "{}; stopping remaining runs", reason
+                );
+                modes.stop_runs();
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ServerError;
+
+    /// **Scenario**: Draining refuses new runs and stops the ones still running at the timeout;
+    /// it ends once they are gone.
+    #[tokio::test]
+    async fn drain_stops_runs_left_at_timeout() {
+        let modes = Arc::new(ServerModes::default());
+        let guard = modes.begin_run().unwrap();
+        let token = modes.run_token();
+
+        let drain = start_drain(
+            Arc::clone(&modes),
+            Duration::from_millis(20),
+            std::future::pending(),
+        );
+        assert!(matches!(
+            modes.begin_run(),
+            Err(ServerError::Unavailable(_))
+        ));
+        assert!(!token.is_cancelled());
+
+        token.cancelled().await;
+        drain.await.unwrap();
+        assert!(modes.runs_stopped());
+        drop(guard);
+        modes.wait_idle().await;
+    }
+
+    /// **Scenario**: Runs finishing within the timeout are not stopped.
+    #[tokio::test]
+    async fn drain_ends_when_runs_finish() {
+        let modes = Arc::new(ServerModes::default());
+        let guard = modes.begin_run().unwrap();
+        let drain = start_drain(
+            Arc::clone(&modes),
+            Duration::from_secs(60),
+            std::future::pending(),
+        );
+        drop(guard);
+        drain.await.unwrap();
+        assert!(!modes.runs_stopped());
+    }
+}
@@ -0,0 +1,62 @@
+//! Test mode (`--mock-llm` / `TEST_MODE`): the server answers with a scripted [`MockLlm`] and
+//! [`MockToolSource`] instead of the OpenAI API and the configured tools, so black-box HTTP
+//! tests and demos get deterministic replies without API keys or network access.
+//!
+//! The mock answers from the request alone (see [`MockLlm::scripted`]): a message asking for
+//! the time calls `get_time` and reports its result, anything else is echoed as
+//! `You said: ...`. Checkpoints, auth, admin endpoints and the rest work as configured.
+//!
+//! **Interaction**: `main` calls [`isolate`] on the build config before building the run
+//! context, and uses [`llm`] and [`tool_source`] in place of `ChatOpenAI` and the MCP tools.
+
+use langgraph::{MockLlm, MockToolSource, ReactBuildConfig};
+
+/// Drops every setting that would reach an external service: API keys (so no embedder or
+/// Exa tools are built), API bases and MCP servers.
+pub fn isolate(config: &mut ReactBuildConfig) {
+    config.openai_api_key = None;
+    config.openai_base_url = None;
+    config.openai_fallback_base_urls.clear();
+    config.azure_openai_endpoint = None;
+    config.azure_openai_api_key = None;
+    config.azure_openai_fallback_endpoints.clear();
+    config.embedding_api_key = None;
+    config.exa_api_key = None;
+    config.mcp_servers.clear();
+    config.mcp_servers_file = None;
+}
+
+/// The scripted chat (and title) model.
+pub fn llm() -> MockLlm {
+    MockLlm::scripted()
+}
+
+/// `get_time` only; every call returns the same time.
+pub fn tool_source() -> MockToolSource {
+    MockToolSource::get_time_example()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: After isolation no key, API base or MCP server is left.
+    #[test]
+    fn isolate_drops_external_services() {
+        let mut config = ReactBuildConfig::from_env();
+        config.openai_api_key = Some("sk-live".into());
+        config.openai_base_url = Some("https://api.openai.com/v1".into());
+        config.embedding_api_key = Some("sk-embed".into());
+        config.exa_api_key = Some("exa".into());
+        config.mcp_servers_file = Some("mcp.toml".into());
+
+        isolate(&mut config);
+
+        assert!(config.openai_api_key.is_none());
+        assert!(config.openai_base_url.is_none());
+        assert!(config.embedding_api_key.is_none());
+        assert!(config.exa_api_key.is_none());
+        assert!(config.mcp_servers_file.is_none());
+        assert!(!config.uses_azure_openai());
+    }
+}
@@ -0,0 +1,242 @@
+//! Conversation title and suggested follow-ups for chat UIs.
+//!
+//! POST /v1/threads/{thread_id}/title and POST /v1/threads/{thread_id}/suggestions load the
+//! thread's checkpointed messages and ask the title model (`--title-model` / `TITLE_MODEL`,
+//! a cheaper model than the chat model) for a short title or [`SUGGESTION_COUNT`] follow-up
+//! questions. Only user and assistant text is sent; system prompts and tool output are left out.
+//!
+//! **Interaction**: Reads state via `ReactRunner::get_state`; calls `AppState::title_llm`
+//! (an `LlmClient` without tools).
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use langgraph::{LlmClient, Message, RunError, RunnableConfig};
+
+use crate::{AppState, ServerError};
+
+/// Number of follow-up questions returned by the suggestions endpoint.
+pub const SUGGESTION_COUNT: usize = 3;
+
+/// Transcript sent to the title model is cut to its last this many characters.
+const TRANSCRIPT_MAX_CHARS: usize = 8000;
+
+/// Titles longer than this (in characters) are cut.
+const TITLE_MAX_CHARS: usize = 80;
+
+const TITLE_PROMPT: &str = "Write a short title (at most 6 words) for the conversation below. \
+Reply with the title only, without quotes or trailing punctuation.";
+
+const SUGGESTIONS_PROMPT: &str = "Suggest exactly 3 short follow-up questions the user might ask \
+next in the conversation below, written from the user's point of view. Reply with one question \
+per line and nothing else.";
+
+/// `User: ...` / `Assistant: ...` lines of the user and assistant messages, keeping the end of
+/// the conversation when it is longer than [`TRANSCRIPT_MAX_CHARS`]. Empty when there is no text.
+fn transcript(messages: &[Message]) -> String {
+    let lines: Vec<String> = messages
+        .iter()
+        .filter_map(|m| match m {
+            Message::User(text) => Some(("User", text)),
+            Message::Assistant { content, .. } => Some(("Assistant", content)),
+            _ => None,
+        })
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(role, text)| format!("{}: {}", role, text.trim()))
+        .collect();
+    let text = lines.join("\n");
+    let chars = text.chars().count();
+    if chars <= TRANSCRIPT_MAX_CHARS {
+        return text;
+    }
+    text.chars().skip(chars - TRANSCRIPT_MAX_CHARS).collect()
+}
+
+/// Strips list markers (`-`, `*`, `1.`, `2)`) and surrounding quotes from one model line.
+fn clean_line(line: &str) -> &str {
+    let line = line.trim();
+    let line = line
+        .strip_prefix(|c: char| matches!(c, '-' | '*' | '•'))
+        .unwrap_or_else(|| {
+            let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            match line[digits..].strip_prefix(|c: char| matches!(c, '.' | ')')) {
+                Some(rest) if digits > 0 && rest.starts_with(char::is_whitespace) => rest,
+                _ => line,
+            }
+        })
+        .trim();
+    line.trim_matches(|c: char| c == '"' || c == '\'' || c == '`')
+        .trim()
+}
+
+/// First non-empty line of the reply, without quotes or a trailing period, at most
+/// [`TITLE_MAX_CHARS`] characters.
+fn parse_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(clean_line).find(|l| !l.is_empty())?;
+    let line = line.strip_prefix("Title:").map(str::trim).unwrap_or(line);
+    let title: String = line
+        .trim_end_matches('.')
+        .chars()
+        .take(TITLE_MAX_CHARS)
+        .collect();
+    let title = title.trim().to_string();
+    (!title.is_empty()).then_some(title)
+}
+
+/// Up to [`SUGGESTION_COUNT`] questions from the reply: one per line, or a JSON array of strings.
+fn parse_suggestions(reply: &str) -> Vec<String> {
+    if let Ok(list) = serde_json::from_str::<Vec<String>>(reply.trim()) {
+        return list
+            .iter()
+            .map(|s| clean_line(s).to_string())
+            .filter(|s| !s.is_empty())
+            .take(SUGGESTION_COUNT)
+            .collect();
+    }
+    reply
+        .lines()
+        .map(clean_line)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .take(SUGGESTION_COUNT)
+        .collect()
+}
+
+/// Transcript of the thread's checkpointed messages; 404 when the thread has no conversation.
+async fn thread_transcript(state: &AppState, thread_id: &str) -> Result<String, ServerError> {
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        ..Default::default()
+    };
+    let thread_state = state.runner.get().get_state(Some(config)).await?;
+    let text = thread_state
+        .map(|s| transcript(&s.messages))
+        .unwrap_or_default();
+    if text.is_empty() {
+        return Err(ServerError::NotFound(format!(
+            "thread {} has no messages",
+            thread_id
+        )));
+    }
+    Ok(text)
+}
+
+/// Asks the title model `instruction` about `transcript` and returns its reply text.
+async fn ask(
+    llm: &dyn LlmClient,
+    instruction: &str,
+    transcript: String,
+) -> Result<String, ServerError> {
+    let messages = [Message::system(instruction), Message::user(transcript)];
+    let response = llm.invoke(&messages).await.map_err(RunError::from)?;
+    Ok(response.content)
+}
+
+/// Generates a title for the thread. Returns `{"thread_id", "title"}`; 404 when the thread has
+/// no messages, 502 when the model replies with nothing usable.
+pub async fn thread_title(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let text = thread_transcript(&state, &thread_id).await?;
+    let reply = ask(state.title_llm.as_ref(), TITLE_PROMPT, text).await?;
+    let title = parse_title(&reply).ok_or_else(|| empty_reply("title"))?;
+    Ok(Json(serde_json::json!({
+        "thread_id": thread_id,
+        "title": title,
+    })))
+}
+
+/// Generates follow-up questions for the thread. Returns `{"thread_id", "suggestions": [...]}`
+/// with at most [`SUGGESTION_COUNT`] entries; 404 when the thread has no messages, 502 when the
+/// model replies with nothing usable.
+pub async fn thread_suggestions(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ServerError> {
+    let text = thread_transcript(&state, &thread_id).await?;
+    let reply = ask(state.title_llm.as_ref(), SUGGESTIONS_PROMPT, text).await?;
+    let suggestions = parse_suggestions(&reply);
+    if suggestions.is_empty() {
+        return Err(empty_reply("suggestions"));
+    }
+    Ok(Json(serde_json::json!({
+        "thread_id": thread_id,
+        "suggestions": suggestions,
+    })))
+}
+
+fn empty_reply(what: &str) -> ServerError {
+    ServerError::Upstream(format!("title model returned no {}", what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: The transcript keeps user and assistant text only, and the end of a long
+    /// conversation.
+    #[test]
+    fn transcript_skips_system_and_tool_messages() {
+        let messages = vec![
+            Message::system("You are helpful."),
+            Message::user("What time is it?"),
+            Message::Assistant {
+                content: String::new(),
+                tool_calls: vec![],
+            },
+            Message::Tool {
+                call_id: None,
+                name: Some("get_time".into()),
+                content: "12:00".into(),
+            },
+            Message::assistant("It is noon."),
+        ];
+        assert_eq!(
+            transcript(&messages),
+            "User: What time is it?\nAssistant: It is noon."
+        );
+
+        let long = vec![
+            Message::user("x".repeat(TRANSCRIPT_MAX_CHARS)),
+            Message::user("end"),
+        ];
+        let text = transcript(&long);
+        assert_eq!(text.chars().count(), TRANSCRIPT_MAX_CHARS);
+        assert!(text.ends_with("User: end"));
+    }
+
+    /// **Scenario**: Titles lose quotes, a "Title:" label and the trailing period; empty replies
+    /// give no title.
+    #[test]
+    fn parse_title_cleans_model_reply() {
+        assert_eq!(
+            parse_title("\n\"Planning a Rust Meetup.\"\n").as_deref(),
+            Some("Planning a Rust Meetup")
+        );
+        assert_eq!(
+            parse_title("Title: 2024 Tax Questions").as_deref(),
+            Some("2024 Tax Questions")
+        );
+        assert!(parse_title("  \n ").is_none());
+    }
+
+    /// **Scenario**: Suggestions come from numbered or bulleted lines or a JSON array, capped
+    /// at SUGGESTION_COUNT.
+    #[test]
+    fn parse_suggestions_accepts_lists_and_json() {
+        let reply = "1. How do I install it?\n2) Is it free?\n\n- What about Windows?\n4. Extra?";
+        assert_eq!(
+            parse_suggestions(reply),
+            vec!["How do I install it?", "Is it free?", "What about Windows?"]
+        );
+        assert_eq!(
+            parse_suggestions(r#"["Why?", "3.5 or 4?"]"#),
+            vec!["Why?", "3.5 or 4?"]
+        );
+        assert!(parse_suggestions("").is_empty());
+    }
+}
@@ -0,0 +1,298 @@
+//! Thread management backed by the checkpointer.
+//!
+//! - POST /v1/threads `{"thread_id"?}` creates a thread (a checkpoint holding only the system
+//!   prompt); without `thread_id` one is generated. 409 when the thread already exists.
+//! - GET /v1/threads lists threads, most recently active first, with `last_activity_at` (Unix
+//!   seconds of the latest checkpoint).
+//! - GET /v1/threads/{thread_id} returns the thread's messages from its latest checkpoint.
+//! - DELETE /v1/threads/{thread_id} removes all its checkpoints.
+//! - POST /v1/threads/{thread_id}/messages `{"messages": [...]}` appends earlier messages (e.g.
+//!   a conversation imported from another system) without running the agent; a new thread
+//!   starts with the system prompt unless the first message is a system message.
+//!
+//! Messages use the OpenAI chat shape (`role`, `content`, `tool_calls`, `tool_call_id`). 404 for
+//! threads without checkpoints. Creating, appending and deleting answer 503 in read-only or
+//! maintenance mode (see `modes`). With per-user databases (`--tenant-db-dir`) each request only
+//! sees the database of the `X-User-Id` user.
+//!
+//! **Interaction**: `ReactRunner::create_thread` / `list_threads` / `get_state` /
+//! `append_messages` / `delete_thread`, i.e. `Checkpointer::list_threads` and
+//! `Checkpointer::delete_thread`.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use langgraph::{Message, MessageContent, RunnableConfig, ThreadInfo, ToolCall};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{route_by_headers, AppState, ServerError};
+
+/// Body of POST /v1/threads.
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateThreadRequest {
+    /// Id of the new thread; generated when absent or blank.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+}
+
+/// Body of POST /v1/threads/{thread_id}/messages.
+#[derive(Debug, Deserialize)]
+pub struct AppendMessagesRequest {
+    /// Messages to append, oldest first, in the OpenAI chat shape.
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// One appended message: `role` is `system`, `user`, `assistant` or `tool`.
+#[derive(Debug, Deserialize)]
+pub struct ImportedMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<MessageContent>,
+    /// Tool calls of an assistant message.
+    #[serde(default)]
+    pub tool_calls: Vec<ImportedToolCall>,
+    /// Call a tool message answers.
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+    /// Tool name of a tool message.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// `{"id", "function": {"name", "arguments"}}` of an assistant message.
+#[derive(Debug, Deserialize)]
+pub struct ImportedToolCall {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub function: ImportedFunction,
+}
+
+/// Function name and JSON-encoded arguments of a tool call.
+#[derive(Debug, Deserialize)]
+pub struct ImportedFunction {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+impl ImportedMessage {
+    /// The message, or a description of what is wrong with it.
+    fn into_message(self) -> Result<Message, String> {
+        let content = self.content.map(|c| c.as_text()).unwrap_or_default();
+        match self.role.to_ascii_lowercase().as_str() {
+            "system" => Ok(Message::system(content)),
+            "user" => Ok(Message::user(content)),
+            "assistant" => Ok(Message::assistant_with_tool_calls(
+                content,
+                self.tool_calls
+                    .into_iter()
+                    .map(|call| ToolCall {
+                        name: call.function.name,
+                        arguments: call.function.arguments,
+                        id: call.id,
+                    })
+                    .collect(),
+            )),
+            "tool" => Ok(Message::tool(self.tool_call_id, self.name, content)),
+            other => Err(format!("unknown message role: {}", other)),
+        }
+    }
+}
+
+/// Unix seconds of `time`.
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn thread_json(thread: &ThreadInfo) -> Value {
+    json!({
+        "thread_id": thread.thread_id,
+        "last_activity_at": thread.last_activity.map(unix_secs),
+    })
+}
+
+/// One message in the OpenAI chat shape.
+fn message_json(message: &Message) -> Value {
+    match message {
+        Message::Assistant {
+            content,
+            tool_calls,
+        } if !tool_calls.is_empty() => json!({
+            "role": "assistant",
+            "content": content,
+            "tool_calls": tool_calls
+                .iter()
+                .map(|call| json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": { "name": call.name, "arguments": call.arguments },
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        Message::Tool {
+            call_id,
+            name,
+            content,
+        } => json!({
+            "role": "tool",
+            "tool_call_id": call_id,
+            "name": name,
+            "content": content,
+        }),
+        other => json!({ "role": other.role(), "content": other.content() }),
+    }
+}
+
+/// Creates a thread of the `X-User-Id` user. Returns 201 `{"thread_id"}`; 409 when it already
+/// exists.
+pub async fn create_thread(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Option<Json<CreateThreadRequest>>,
+) -> Result<(StatusCode, Json<Value>), ServerError> {
+    state.modes.check_writable()?;
+    let requested = body.and_then(|Json(req)| req.thread_id);
+    let thread_id = match requested.as_deref().map(str::trim) {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _ => langgraph::memory::uuid6().to_string(),
+    };
+    let mut config = RunnableConfig::default();
+    route_by_headers(&headers, &mut config);
+    let runner = state.runner.get();
+    if !runner
+        .create_thread(&thread_id, config.user_id.as_deref())
+        .await?
+    {
+        return Err(ServerError::Conflict(format!(
+            "thread {} already exists",
+            thread_id
+        )));
+    }
+    Ok((StatusCode::CREATED, Json(json!({ "thread_id": thread_id }))))
+}
+
+/// Lists the threads of the `X-User-Id` user: `{"threads": [{"thread_id", "last_activity_at"}]}`.
+pub async fn list_threads(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ServerError> {
+    let mut config = RunnableConfig::default();
+    route_by_headers(&headers, &mut config);
+    let threads = state
+        .runner
+        .get()
+        .list_threads(config.user_id.as_deref())
+        .await?;
+    Ok(Json(json!({
+        "threads": threads.iter().map(thread_json).collect::<Vec<_>>(),
+    })))
+}
+
+/// Message history of a thread: `{"thread_id", "messages": [...]}`; 404 when it has no
+/// checkpoint.
+pub async fn get_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ServerError> {
+    let mut config = RunnableConfig::default();
+    route_by_headers(&headers, &mut config);
+    config.thread_id = Some(thread_id.clone());
+    let Some(thread_state) = state.runner.get().get_state(Some(config)).await? else {
+        return Err(ServerError::NotFound(format!("thread {}", thread_id)));
+    };
+    Ok(Json(json!({
+        "thread_id": thread_id,
+        "messages": thread_state.messages.iter().map(message_json).collect::<Vec<_>>(),
+    })))
+}
+
+/// Appends messages to a thread without running the agent: `{"thread_id", "appended",
+/// "message_count"}`. 400 for an empty list or an unknown role; with per-user databases it
+/// writes the database of the `X-User-Id` user.
+pub async fn append_messages(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<AppendMessagesRequest>,
+) -> Result<Json<Value>, ServerError> {
+    state.modes.check_writable()?;
+    if req.messages.is_empty() {
+        return Err(ServerError::BadRequest("messages must not be empty".into()));
+    }
+    let messages = req
+        .messages
+        .into_iter()
+        .map(ImportedMessage::into_message)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ServerError::BadRequest)?;
+    let appended = messages.len();
+    let mut config = RunnableConfig::default();
+    route_by_headers(&headers, &mut config);
+    config.thread_id = Some(thread_id.clone());
+    let message_count = state.runner.get().append_messages(config, messages).await?;
+    Ok(Json(json!({
+        "thread_id": thread_id,
+        "appended": appended,
+        "message_count": message_count,
+    })))
+}
+
+/// Deletes the checkpoints of a thread of the `X-User-Id` user: `{"thread_id", "deleted": true}`;
+/// 404 when it had none.
+pub async fn delete_thread(
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ServerError> {
+    state.modes.check_writable()?;
+    let mut config = RunnableConfig::default();
+    route_by_headers(&headers, &mut config);
+    let runner = state.runner.get();
+    if !runner
+        .delete_thread(&thread_id, config.user_id.as_deref())
+        .await?
+    {
+        return Err(ServerError::NotFound(format!("thread {}", thread_id)));
+    }
+    Ok(Json(json!({ "thread_id": thread_id, "deleted": true })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use langgraph::ToolCall;
+
+    /// **Scenario**: Messages are rendered in the OpenAI chat shape, tool calls and results
+    /// included.
+    #[test]
+    fn messages_use_openai_shape() {
+        assert_eq!(
+            message_json(&Message::user("Hi")),
+            json!({"role": "user", "content": "Hi"})
+        );
+        let call = Message::assistant_with_tool_calls(
+            "",
+            vec![ToolCall {
+                name: "get_time".into(),
+                arguments: "{}".into(),
+                id: Some("c1".into()),
+            }],
+        );
+        assert_eq!(
+            message_json(&call)["tool_calls"][0],
+            json!({"id": "c1", "type": "function", "function": {"name": "get_time", "arguments": "{}"}})
+        );
+        let result = Message::tool(Some("c1".into()), Some("get_time".into()), "12:00");
+        assert_eq!(message_json(&result)["tool_call_id"], "c1");
+        assert_eq!(message_json(&result)["role"], "tool");
+    }
+}
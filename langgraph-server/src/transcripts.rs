@@ -0,0 +1,216 @@
+//! Replay of chat completion streams: GET /v1/runs/{run_id}/events.
+//!
+//! Every SSE chunk handed to the client is recorded while it is sent. When the response body is
+//! dropped (stream finished or client gone), the transcript is kept gzip-compressed in memory,
+//! so a replay returns exactly the bytes the client received. The run id is the `id` of the
+//! chat completion chunks (`chatcmpl-...`); a run that is still streaming is not available yet.
+//!
+//! Memory is bounded twice: only the last `--transcript-runs` / `TRANSCRIPT_RUNS` runs are kept
+//! (0 turns recording off), and a run stops recording after [`DEFAULT_MAX_BYTES`] of events; its
+//! replay then ends with a `: transcript truncated` SSE comment.
+//!
+//! **Interaction**: `chat_completions` feeds a [`TranscriptRecorder`] from its body stream;
+//! `AppState::transcripts` holds the [`TranscriptStore`].
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{AppState, ServerError};
+
+/// Runs kept when neither `--transcript-runs` nor `TRANSCRIPT_RUNS` is set.
+pub const DEFAULT_TRANSCRIPT_RUNS: usize = 256;
+
+/// Uncompressed event bytes recorded per run before the transcript is truncated.
+pub const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// SSE comment closing the replay of a truncated transcript; clients ignore comments.
+const TRUNCATED_MARKER: &str = ": transcript truncated\n\n";
+
+struct StoredTranscript {
+    gzip: Vec<u8>,
+    truncated: bool,
+}
+
+/// Compressed transcripts of the most recent runs, oldest evicted first.
+pub struct TranscriptStore {
+    max_runs: usize,
+    max_bytes: usize,
+    runs: Mutex<VecDeque<(String, StoredTranscript)>>,
+}
+
+impl TranscriptStore {
+    /// Keeps the last `max_runs` transcripts; 0 records nothing.
+    pub fn new(max_runs: usize) -> Self {
+        Self {
+            max_runs,
+            max_bytes: DEFAULT_MAX_BYTES,
+            runs: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Truncates each transcript after `max_bytes` of uncompressed events.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Recorder for one run, stored under `run_id` when dropped. `None` when recording is off.
+    pub fn recorder(self: &Arc<Self>, run_id: &str) -> Option<TranscriptRecorder> {
+        (self.max_runs > 0).then(|| TranscriptRecorder {
+            store: Arc::clone(self),
+            run_id: run_id.to_string(),
+            encoder: Some(GzEncoder::new(Vec::new(), Compression::fast())),
+            recorded: 0,
+            truncated: false,
+        })
+    }
+
+    fn insert(&self, run_id: String, transcript: StoredTranscript) {
+        let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+        runs.retain(|(id, _)| *id != run_id);
+        runs.push_back((run_id, transcript));
+        while runs.len() > self.max_runs {
+            runs.pop_front();
+        }
+    }
+
+    /// The recorded SSE text of `run_id` (ending with the truncation marker when cut), or
+    /// `None` when the run is unknown, still streaming, or already evicted.
+    pub fn replay(&self, run_id: &str) -> io::Result<Option<String>> {
+        let (gzip, truncated) = {
+            let runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+            match runs.iter().find(|(id, _)| id == run_id) {
+                Some((_, t)) => (t.gzip.clone(), t.truncated),
+                None => return Ok(None),
+            }
+        };
+        let mut events = String::new();
+        GzDecoder::new(gzip.as_slice()).read_to_string(&mut events)?;
+        if truncated {
+            events.push_str(TRUNCATED_MARKER);
+        }
+        Ok(Some(events))
+    }
+}
+
+/// Compresses the chunks of one run as they are sent; hands the transcript to the store on drop.
+pub struct TranscriptRecorder {
+    store: Arc<TranscriptStore>,
+    run_id: String,
+    encoder: Option<GzEncoder<Vec<u8>>>,
+    recorded: usize,
+    truncated: bool,
+}
+
+impl TranscriptRecorder {
+    /// Appends one chunk as sent to the client. Once the byte limit is hit, later chunks are
+    /// dropped so the transcript stays a prefix of what the client saw.
+    pub fn record(&mut self, chunk: impl AsRef<[u8]>) {
+        let chunk = chunk.as_ref();
+        if self.truncated {
+            return;
+        }
+        if self.recorded + chunk.len() > self.store.max_bytes {
+            self.truncated = true;
+            return;
+        }
+        self.recorded += chunk.len();
+        if let Some(encoder) = self.encoder.as_mut() {
+            if encoder.write_all(chunk).is_err() {
+                self.truncated = true;
+            }
+        }
+    }
+}
+
+impl Drop for TranscriptRecorder {
+    fn drop(&mut self) {
+        let Some(encoder) = self.encoder.take() else {
+            return;
+        };
+        match encoder.finish() {
+            Ok(gzip) => self.store.insert(
+                std::mem::take(&mut self.run_id),
+                StoredTranscript {
+                    gzip,
+                    truncated: self.truncated,
+                },
+            ),
+            Err(e) => tracing::warn!(run_id = %self.run_id, "transcript not stored: {}", e),
+        }
+    }
+}
+
+/// Replays the SSE events of a finished chat completion run as `text/event-stream`; 404 when the
+/// run is unknown, still streaming, or evicted.
+pub async fn run_events(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+) -> Result<Response, ServerError> {
+    let events = state
+        .transcripts
+        .replay(&run_id)
+        .map_err(|e| ServerError::Internal(format!("transcript of {}: {}", run_id, e)))?
+        .ok_or_else(|| ServerError::NotFound(format!("no transcript for run {}", run_id)))?;
+    let mut res = events.into_response();
+    res.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(store: &Arc<TranscriptStore>, run_id: &str, chunks: &[&str]) {
+        let mut recorder = store.recorder(run_id).expect("recording on");
+        for chunk in chunks {
+            recorder.record(chunk);
+        }
+    }
+
+    /// **Scenario**: Transcripts round-trip through compression; the oldest run is evicted once
+    /// more than max_runs are stored.
+    #[test]
+    fn store_keeps_most_recent_runs() {
+        let store = Arc::new(TranscriptStore::new(2));
+        record(&store, "a", &["data: 1\n\n", "data: [DONE]\n\n"]);
+        record(&store, "b", &["data: 2\n\n"]);
+        assert_eq!(
+            store.replay("a").unwrap().as_deref(),
+            Some("data: 1\n\ndata: [DONE]\n\n")
+        );
+
+        record(&store, "c", &["data: 3\n\n"]);
+        assert!(store.replay("a").unwrap().is_none());
+        assert_eq!(store.replay("c").unwrap().as_deref(), Some("data: 3\n\n"));
+    }
+
+    /// **Scenario**: Past the byte limit, later chunks are dropped and the replay ends with the
+    /// truncation marker; max_runs 0 records nothing.
+    #[test]
+    fn recorder_truncates_at_byte_limit() {
+        let store = Arc::new(TranscriptStore::new(1).with_max_bytes(12));
+        record(
+            &store,
+            "run",
+            &["data: 1\n\n", "data: 2\n\n", "data: 3\n\n"],
+        );
+        assert_eq!(
+            store.replay("run").unwrap().as_deref(),
+            Some("data: 1\n\n: transcript truncated\n\n")
+        );
+
+        assert!(Arc::new(TranscriptStore::new(0)).recorder("run").is_none());
+    }
+}
@@ -21,6 +21,8 @@ async fn stream_flow_produces_openai_sse_lines() {
         stream: true,
         stream_options: None,
         thread_id: None,
+        user: None,
+        metadata: None,
     };
     let parsed = parse_chat_request(&req).expect("parse");
 
@@ -0,0 +1,85 @@
+//! Assertions on [`ReActState`] messages with readable failure output.
+//!
+//! On failure each helper panics with the needle and a one-line-per-message dump of the
+//! conversation (`role: content`), so the failing test shows what the graph produced.
+
+use langgraph::{Message, ReActState};
+
+fn dump(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|m| format!("  {}: {}", m.role(), m.content()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Asserts that some assistant message contains `needle`.
+#[track_caller]
+pub fn assert_has_assistant_containing(state: &ReActState, needle: &str) {
+    let found = state
+        .messages
+        .iter()
+        .any(|m| matches!(m, Message::Assistant { content, .. } if content.contains(needle)));
+    assert!(
+        found,
+        "no assistant message contains {:?}; messages:\n{}",
+        needle,
+        dump(&state.messages)
+    );
+}
+
+/// Asserts that some tool result message contains `needle`.
+#[track_caller]
+pub fn assert_has_tool_message_containing(state: &ReActState, needle: &str) {
+    let found = state
+        .messages
+        .iter()
+        .any(|m| matches!(m, Message::Tool { content, .. } if content.contains(needle)));
+    assert!(
+        found,
+        "no tool message contains {:?}; messages:\n{}",
+        needle,
+        dump(&state.messages)
+    );
+}
+
+/// Asserts that the last assistant reply equals `expected`.
+#[track_caller]
+pub fn assert_last_assistant_eq(state: &ReActState, expected: &str) {
+    assert_eq!(
+        state.last_assistant_reply().as_deref(),
+        Some(expected),
+        "last assistant reply mismatch; messages:\n{}",
+        dump(&state.messages)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReActStateBuilder;
+
+    /// **Scenario**: Helpers pass on matching messages and only match their own role.
+    #[test]
+    fn assertions_pass_on_matching_messages() {
+        let state = ReActStateBuilder::new()
+            .user("hello there")
+            .assistant_calling("get_time", "{}")
+            .tool_message("get_time", "12:00")
+            .assistant("It is noon.")
+            .build();
+        assert_has_assistant_containing(&state, "noon");
+        assert_has_tool_message_containing(&state, "12:00");
+        assert_last_assistant_eq(&state, "It is noon.");
+        let user_only = std::panic::catch_unwind(|| assert_has_assistant_containing(&state, "hello"));
+        assert!(user_only.is_err());
+    }
+
+    /// **Scenario**: Failure message names the needle and dumps the conversation.
+    #[test]
+    #[should_panic(expected = "no assistant message contains \"bye\"")]
+    fn assert_has_assistant_containing_reports_needle() {
+        let state = ReActStateBuilder::new().user("hi").assistant("hello").build();
+        assert_has_assistant_containing(&state, "bye");
+    }
+}
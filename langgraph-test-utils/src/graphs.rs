@@ -0,0 +1,99 @@
+//! Prebuilt graphs for tests.
+//!
+//! [`two_node_graph`] and [`three_node_graph`] chain pass-through [`NameNode`]s and work for
+//! any state type; use them to test runners, middleware, streaming, or checkpointing without
+//! writing nodes. [`react_graph`] wires the ReAct think → act → observe chain from a given
+//! LLM and tool source; [`mock_react_graph`] uses `MockLlm` and `MockToolSource`.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use langgraph::{
+    ActNode, CompiledStateGraph, LlmClient, MockLlm, MockToolSource, NameNode, ObserveNode,
+    ReActState, StateGraph, ThinkNode, ToolSource, END, START,
+};
+
+/// Builds a linear pass-through graph over `ids`; panics only if the ids are invalid.
+fn linear_graph<S>(ids: &[&str]) -> CompiledStateGraph<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    let mut graph = StateGraph::<S>::new();
+    let mut prev: &str = START;
+    for &id in ids {
+        graph.add_node(id, Arc::new(NameNode::new(id)));
+        graph.add_edge(prev, id);
+        prev = id;
+    }
+    graph.add_edge(prev, END);
+    graph.compile().expect("prebuilt test graph is valid")
+}
+
+/// START → `first` → `second` → END; both nodes return the state unchanged.
+pub fn two_node_graph<S>() -> CompiledStateGraph<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    linear_graph(&["first", "second"])
+}
+
+/// START → `first` → `second` → `third` → END; all nodes return the state unchanged.
+pub fn three_node_graph<S>() -> CompiledStateGraph<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    linear_graph(&["first", "second", "third"])
+}
+
+/// START → `think` → `act` → `observe` → END with the given LLM and tools (one ReAct round).
+pub fn react_graph(
+    llm: Box<dyn LlmClient>,
+    tools: Box<dyn ToolSource>,
+) -> CompiledStateGraph<ReActState> {
+    let mut graph = StateGraph::<ReActState>::new();
+    graph
+        .add_node("think", Arc::new(ThinkNode::new(llm)))
+        .add_node("act", Arc::new(ActNode::new(tools)))
+        .add_node("observe", Arc::new(ObserveNode::new()))
+        .add_edge(START, "think")
+        .add_edge("think", "act")
+        .add_edge("act", "observe")
+        .add_edge("observe", END);
+    graph.compile().expect("prebuilt react graph is valid")
+}
+
+/// [`react_graph`] with `MockLlm::with_get_time_call` and `MockToolSource::get_time_example`.
+pub fn mock_react_graph() -> CompiledStateGraph<ReActState> {
+    react_graph(
+        Box::new(MockLlm::with_get_time_call()),
+        Box::new(MockToolSource::get_time_example()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_has_tool_message_containing, ReActStateBuilder};
+
+    /// **Scenario**: Pass-through graphs return the input state unchanged.
+    #[tokio::test]
+    async fn linear_graphs_pass_state_through() {
+        let out = two_node_graph::<i32>().invoke(7, None).await.unwrap();
+        assert_eq!(out, 7);
+        let state = ReActStateBuilder::new().user("hi").build();
+        let out = three_node_graph::<ReActState>()
+            .invoke(state, None)
+            .await
+            .unwrap();
+        assert_eq!(out.messages.len(), 1);
+    }
+
+    /// **Scenario**: Mock ReAct graph runs one round and merges the tool result into messages.
+    #[tokio::test]
+    async fn mock_react_graph_runs_one_round() {
+        let state = ReActStateBuilder::new().user("What time is it?").build();
+        let out = mock_react_graph().invoke(state, None).await.unwrap();
+        assert_has_tool_message_containing(&out, "2025-01-29");
+        assert_eq!(out.turn_count, 1);
+    }
+}
@@ -0,0 +1,40 @@
+//! Test fixtures for langgraph: state builders, prebuilt graphs, resilience tools, assertions.
+//!
+//! Cuts the boilerplate downstream tests repeat: building a [`ReActState`](langgraph::ReActState)
+//! by hand, wiring a small graph to exercise routing, and writing one-off tools to simulate
+//! failures or latency. Add as a dev-dependency and import what the test needs.
+//!
+//! # Main items
+//!
+//! - [`ReActStateBuilder`]: fluent builder for ReActState fixtures (messages, tool calls, turns).
+//! - [`two_node_graph`], [`three_node_graph`]: linear pass-through graphs for any state type;
+//!   [`react_graph`] / [`mock_react_graph`]: think → act → observe for ReActState.
+//! - [`FlakyTool`]: fails the first N calls, then succeeds (retry / error-handling tests).
+//! - [`SlowTool`]: sleeps before answering (timeout / cancellation tests).
+//! - [`assert_has_assistant_containing`] and friends: readable assertions on messages.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use langgraph_test_utils::{assert_has_assistant_containing, mock_react_graph, ReActStateBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let state = ReActStateBuilder::new().user("What time is it?").build();
+//! let out = mock_react_graph().invoke(state, None).await.unwrap();
+//! assert_has_assistant_containing(&out, "check the time");
+//! # }
+//! ```
+
+mod assertions;
+mod graphs;
+mod state;
+mod tools;
+
+pub use assertions::{
+    assert_has_assistant_containing, assert_has_tool_message_containing,
+    assert_last_assistant_eq,
+};
+pub use graphs::{mock_react_graph, react_graph, three_node_graph, two_node_graph};
+pub use state::ReActStateBuilder;
+pub use tools::{FlakyTool, SlowTool};
@@ -0,0 +1,149 @@
+//! Fluent builder for [`ReActState`] fixtures.
+//!
+//! **Interaction**: The built state is passed to `CompiledStateGraph::invoke`, node `run`
+//! methods, or the prebuilt graphs in [`crate::graphs`].
+
+use langgraph::{Message, ReActState, ToolCall, ToolResult};
+
+/// Builds a [`ReActState`] step by step.
+///
+/// Messages are appended in call order. [`tool_call`](Self::tool_call) and
+/// [`tool_result`](Self::tool_result) fill the per-round fields (as if Think or Act just
+/// ran); [`assistant_calling`](Self::assistant_calling) and [`tool_message`](Self::tool_message)
+/// add the equivalent history messages (as after Observe).
+#[derive(Debug, Clone, Default)]
+pub struct ReActStateBuilder {
+    state: ReActState,
+}
+
+impl ReActStateBuilder {
+    /// Creates a builder for an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends any message.
+    pub fn message(mut self, message: Message) -> Self {
+        self.state.messages.push(message);
+        self
+    }
+
+    /// Appends a system message.
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.message(Message::system(content))
+    }
+
+    /// Appends a user message.
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.message(Message::user(content))
+    }
+
+    /// Appends an assistant message without tool calls.
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.message(Message::assistant(content))
+    }
+
+    /// Appends an assistant message requesting one call of `tool` with `arguments` (JSON string);
+    /// the call id is `call-<n>` where n is the number of messages before it.
+    pub fn assistant_calling(self, tool: impl Into<String>, arguments: impl Into<String>) -> Self {
+        let id = format!("call-{}", self.state.messages.len());
+        let call = ToolCall {
+            name: tool.into(),
+            arguments: arguments.into(),
+            id: Some(id),
+        };
+        self.message(Message::assistant_with_tool_calls("", vec![call]))
+    }
+
+    /// Appends a tool result message answering the latest assistant tool call of `tool`.
+    pub fn tool_message(self, tool: impl Into<String>, content: impl Into<String>) -> Self {
+        let tool = tool.into();
+        let call_id = self.state.messages.iter().rev().find_map(|m| match m {
+            Message::Assistant { tool_calls, .. } => tool_calls
+                .iter()
+                .find(|c| c.name == tool)
+                .and_then(|c| c.id.clone()),
+            _ => None,
+        });
+        self.message(Message::tool(call_id, Some(tool), content))
+    }
+
+    /// Adds a pending tool call (the `tool_calls` field Act reads).
+    pub fn tool_call(mut self, tool: impl Into<String>, arguments: impl Into<String>) -> Self {
+        let id = format!("call-{}", self.state.tool_calls.len() + 1);
+        self.state.tool_calls.push(ToolCall {
+            name: tool.into(),
+            arguments: arguments.into(),
+            id: Some(id),
+        });
+        self
+    }
+
+    /// Adds a tool result for the pending call of `tool` (the `tool_results` field Observe reads).
+    pub fn tool_result(mut self, tool: impl Into<String>, content: impl Into<String>) -> Self {
+        let tool = tool.into();
+        let call_id = self
+            .state
+            .tool_calls
+            .iter()
+            .find(|c| c.name == tool)
+            .and_then(|c| c.id.clone());
+        self.state.tool_results.push(ToolResult {
+            call_id,
+            name: Some(tool),
+            content: content.into(),
+        });
+        self
+    }
+
+    /// Sets the number of completed observe rounds.
+    pub fn turn_count(mut self, turn_count: u32) -> Self {
+        self.state.turn_count = turn_count;
+        self
+    }
+
+    /// Returns the built state.
+    pub fn build(self) -> ReActState {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Builder appends messages in order and links tool messages to their call ids.
+    #[test]
+    fn builder_links_tool_message_to_assistant_call() {
+        let state = ReActStateBuilder::new()
+            .system("sys")
+            .user("What time is it?")
+            .assistant_calling("get_time", "{}")
+            .tool_message("get_time", "12:00")
+            .assistant("It is 12:00.")
+            .turn_count(1)
+            .build();
+        assert_eq!(state.messages.len(), 5);
+        assert_eq!(state.turn_count, 1);
+        match &state.messages[3] {
+            Message::Tool { call_id, name, content } => {
+                assert_eq!(call_id.as_deref(), Some("call-2"));
+                assert_eq!(name.as_deref(), Some("get_time"));
+                assert_eq!(content, "12:00");
+            }
+            other => panic!("expected tool message, got {:?}", other),
+        }
+    }
+
+    /// **Scenario**: Pending tool calls and results are correlated by id.
+    #[test]
+    fn builder_pending_tool_call_and_result() {
+        let state = ReActStateBuilder::new()
+            .tool_call("search", r#"{"q":"rust"}"#)
+            .tool_result("search", "found")
+            .build();
+        assert_eq!(state.tool_calls.len(), 1);
+        assert_eq!(state.tool_results[0].call_id, state.tool_calls[0].id);
+        assert_eq!(state.tool_results[0].content, "found");
+    }
+}
@@ -0,0 +1,164 @@
+//! Tools that misbehave on purpose, for resilience tests.
+//!
+//! **Interaction**: Both implement [`Tool`]; register them with
+//! [`AggregateToolSource`](langgraph::tools::AggregateToolSource) and hand the source to
+//! `ActNode`, or call them directly.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use langgraph::tools::Tool;
+use langgraph::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use serde_json::{json, Value};
+
+fn spec_for(name: &str, description: &str) -> ToolSpec {
+    ToolSpec {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        input_schema: json!({ "type": "object", "properties": {} }),
+    }
+}
+
+/// Fails the first `failures` calls with a transport error, then returns `output`.
+///
+/// Use to test retries and `HandleToolErrors`; [`calls`](Self::calls) reports how many
+/// times the tool was invoked.
+#[derive(Debug)]
+pub struct FlakyTool {
+    name: String,
+    failures: usize,
+    output: String,
+    calls: AtomicUsize,
+}
+
+impl FlakyTool {
+    /// Creates a tool named `name` that fails `failures` times before succeeding with "ok".
+    pub fn new(name: impl Into<String>, failures: usize) -> Self {
+        Self {
+            name: name.into(),
+            failures,
+            output: "ok".to_string(),
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets the text returned once the tool succeeds.
+    pub fn with_output(mut self, output: impl Into<String>) -> Self {
+        self.output = output.into();
+        self
+    }
+
+    /// Number of calls so far (failed and successful).
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Tool for FlakyTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn spec(&self) -> ToolSpec {
+        spec_for(&self.name, "Test tool that fails a fixed number of times, then succeeds.")
+    }
+
+    async fn call(
+        &self,
+        _args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let attempt = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if attempt <= self.failures {
+            return Err(ToolSourceError::Transport(format!(
+                "{} failed (attempt {} of {} planned failures)",
+                self.name, attempt, self.failures
+            )));
+        }
+        Ok(ToolCallContent {
+            text: self.output.clone(),
+        })
+    }
+}
+
+/// Sleeps for `delay` before returning `output`.
+///
+/// Use to test timeouts, cancellation, and parallel tool execution.
+#[derive(Debug)]
+pub struct SlowTool {
+    name: String,
+    delay: Duration,
+    output: String,
+}
+
+impl SlowTool {
+    /// Creates a tool named `name` that answers "done" after `delay`.
+    pub fn new(name: impl Into<String>, delay: Duration) -> Self {
+        Self {
+            name: name.into(),
+            delay,
+            output: "done".to_string(),
+        }
+    }
+
+    /// Sets the text returned after the delay.
+    pub fn with_output(mut self, output: impl Into<String>) -> Self {
+        self.output = output.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for SlowTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn spec(&self) -> ToolSpec {
+        spec_for(&self.name, "Test tool that answers after a fixed delay.")
+    }
+
+    async fn call(
+        &self,
+        _args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        tokio::time::sleep(self.delay).await;
+        Ok(ToolCallContent {
+            text: self.output.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: FlakyTool fails exactly `failures` times, then succeeds every time.
+    #[tokio::test]
+    async fn flaky_tool_fails_then_succeeds() {
+        let tool = FlakyTool::new("flaky", 2).with_output("fine");
+        for _ in 0..2 {
+            let err = tool.call(json!({}), None).await.unwrap_err();
+            assert!(matches!(err, ToolSourceError::Transport(_)), "{:?}", err);
+        }
+        assert_eq!(tool.call(json!({}), None).await.unwrap().text, "fine");
+        assert_eq!(tool.call(json!({}), None).await.unwrap().text, "fine");
+        assert_eq!(tool.calls(), 4);
+    }
+
+    /// **Scenario**: SlowTool answers no earlier than its delay; a shorter timeout elapses first.
+    #[tokio::test]
+    async fn slow_tool_waits_for_delay() {
+        let tool = SlowTool::new("slow", Duration::from_millis(50));
+        let timed_out =
+            tokio::time::timeout(Duration::from_millis(5), tool.call(json!({}), None)).await;
+        assert!(timed_out.is_err());
+        let start = std::time::Instant::now();
+        assert_eq!(tool.call(json!({}), None).await.unwrap().text, "done");
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(tool.spec().name, "slow");
+    }
+}
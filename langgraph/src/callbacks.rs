@@ -0,0 +1,142 @@
+//! Callback handlers: hooks into a graph run at node, LLM call and tool call granularity.
+//!
+//! A [`CallbackHandler`] sees the run start and end, every node, every LLM request `ThinkNode`
+//! makes and every tool call `ActNode` makes, for custom logging, analytics or tracing that
+//! [`NodeMiddleware`](crate::graph::NodeMiddleware) (which only wraps whole nodes) cannot do.
+//! Every hook defaults to doing nothing, so a handler implements only the ones it needs.
+//!
+//! Handlers are registered on the graph (`StateGraph::with_callback_handler`, for every run)
+//! or per run in [`RunnableConfig::callbacks`](crate::memory::RunnableConfig); the graph's run
+//! first. Hooks are called inline on the run's task and must not block; a handler that needs
+//! I/O should hand events off to a channel.
+//!
+//! **Interaction**: Carried on [`RunContext::callbacks`](crate::graph::RunContext); called from
+//! `CompiledStateGraph::run_loop_inner` (graph and node hooks), `ThinkNode` (LLM hooks) and
+//! `ActNode` (tool hooks). Every hook receives the run id of the `RunContext`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::AgentError;
+use crate::llm::LlmResponse;
+use crate::message::Message;
+use crate::state::ToolCall;
+use crate::tool_source::ToolSourceError;
+
+/// Hooks called during a graph run; every method defaults to doing nothing.
+pub trait CallbackHandler: Send + Sync {
+    /// The run started.
+    fn on_graph_start(&self, _run_id: &str) {}
+
+    /// The run ended; `error` is None on success (an interrupt is `AgentError::Interrupted`).
+    fn on_graph_end(&self, _run_id: &str, _error: Option<&AgentError>) {}
+
+    /// A node is about to run.
+    fn on_node_start(&self, _run_id: &str, _node_id: &str) {}
+
+    /// A node finished, after its retries; `error` is None on success.
+    fn on_node_end(&self, _run_id: &str, _node_id: &str, _error: Option<&AgentError>) {}
+
+    /// An LLM request is about to be sent with `messages`.
+    fn on_llm_start(&self, _run_id: &str, _messages: &[Message]) {}
+
+    /// An LLM request returned `response`.
+    fn on_llm_end(&self, _run_id: &str, _response: &LlmResponse) {}
+
+    /// An LLM request failed.
+    fn on_llm_error(&self, _run_id: &str, _error: &AgentError) {}
+
+    /// A tool call is about to run. Parallel calls of one step are told apart by `call.id`.
+    fn on_tool_start(&self, _run_id: &str, _call: &ToolCall) {}
+
+    /// A tool call returned `result`.
+    fn on_tool_end(&self, _run_id: &str, _call: &ToolCall, _result: &str) {}
+
+    /// A tool call failed; also called when `HandleToolErrors` turns the error into a result.
+    fn on_tool_error(&self, _run_id: &str, _call: &ToolCall, _error: &ToolSourceError) {}
+}
+
+/// Handlers called in registration order; cheap to clone.
+#[derive(Clone, Default)]
+pub struct Callbacks {
+    handlers: Vec<Arc<dyn CallbackHandler>>,
+}
+
+impl fmt::Debug for Callbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callbacks")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl Callbacks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `handler` after the ones already registered.
+    pub fn with_handler(mut self, handler: Arc<dyn CallbackHandler>) -> Self {
+        self.push(handler);
+        self
+    }
+
+    pub fn push(&mut self, handler: Arc<dyn CallbackHandler>) {
+        self.handlers.push(handler);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// These handlers followed by `other`'s.
+    pub(crate) fn chain(&self, other: &Callbacks) -> Callbacks {
+        let mut handlers = self.handlers.clone();
+        handlers.extend(other.handlers.iter().cloned());
+        Callbacks { handlers }
+    }
+
+    /// Calls `f` with every handler in order.
+    pub(crate) fn emit(&self, f: impl Fn(&dyn CallbackHandler)) {
+        for handler in &self.handlers {
+            f(handler.as_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct Named(&'static str, Arc<Mutex<Vec<String>>>);
+
+    impl CallbackHandler for Named {
+        fn on_node_start(&self, run_id: &str, node_id: &str) {
+            let event = format!("{}:{}:{}", self.0, run_id, node_id);
+            self.1.lock().unwrap().push(event);
+        }
+    }
+
+    /// **Scenario**: Chained callbacks call the first list's handlers, then the second's;
+    /// hooks a handler does not implement do nothing.
+    #[test]
+    fn chain_emits_in_registration_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let graph = Callbacks::new().with_handler(Arc::new(Named("graph", seen.clone())));
+        let config = Callbacks::new().with_handler(Arc::new(Named("config", seen.clone())));
+        let all = graph.chain(&config);
+        assert_eq!(all.len(), 2);
+
+        all.emit(|h| h.on_node_start("run-1", "think"));
+        all.emit(|h| h.on_graph_start("run-1"));
+        assert_eq!(
+            *seen.lock().unwrap(),
+            ["graph:run-1:think", "config:run-1:think"]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use super::{malformed_json, truncate_half, FaultInjector, FaultKind, INJECTED_FAULT};
+use crate::error::AgentError;
+use crate::llm::{LlmClient, LlmError, LlmErrorKind, LlmResponse, SamplingParams};
+use crate::message::Message;
+use crate::stream::MessageChunk;
+
+/// [`LlmClient`] that injects faults around another client.
+///
+/// Before the call: latency, then an error (`AgentError::Llm`, kind `Unavailable`). After a
+/// successful call: content cut in half and reported as `finish_reason: length`, and the
+/// arguments of every tool call replaced by invalid JSON. With `invoke_stream`, chunks already
+/// streamed are not altered; only the returned response is.
+pub struct FaultyLlm {
+    inner: Box<dyn LlmClient>,
+    injector: Arc<FaultInjector>,
+}
+
+impl FaultyLlm {
+    /// Wraps `inner`, injecting faults decided by `injector`.
+    pub fn new(inner: Box<dyn LlmClient>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+
+    async fn before_call(&self) -> Result<(), AgentError> {
+        self.injector.maybe_delay().await;
+        if self.injector.should_inject(FaultKind::Error) {
+            return Err(LlmError::new(LlmErrorKind::Unavailable, INJECTED_FAULT).into());
+        }
+        Ok(())
+    }
+
+    fn corrupt(&self, mut response: LlmResponse) -> LlmResponse {
+        if !response.content.is_empty() && self.injector.should_inject(FaultKind::Truncate) {
+            response.content = truncate_half(&response.content);
+            response.truncated = true;
+            response.metadata.finish_reason = Some("length".to_string());
+        }
+        if !response.tool_calls.is_empty() && self.injector.should_inject(FaultKind::MalformedArgs)
+        {
+            for call in &mut response.tool_calls {
+                call.arguments = malformed_json(&call.arguments);
+            }
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl LlmClient for FaultyLlm {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        self.before_call().await?;
+        let response = self.inner.invoke(messages).await?;
+        Ok(self.corrupt(response))
+    }
+
+    async fn invoke_stream(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+    ) -> Result<LlmResponse, AgentError> {
+        self.before_call().await?;
+        let response = self.inner.invoke_stream(messages, chunk_tx).await?;
+        Ok(self.corrupt(response))
+    }
+
+    async fn invoke_stream_with_sampling(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
+        self.before_call().await?;
+        let response = self
+            .inner
+            .invoke_stream_with_sampling(messages, chunk_tx, sampling)
+            .await?;
+        Ok(self.corrupt(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaos::FaultConfig;
+    use crate::llm::MockLlm;
+
+    fn faulty(config: FaultConfig) -> FaultyLlm {
+        FaultyLlm::new(
+            Box::new(MockLlm::with_get_time_call()),
+            Arc::new(FaultInjector::new(config)),
+        )
+    }
+
+    /// **Scenario**: An injected error is a retryable `AgentError::Llm` of kind Unavailable.
+    #[tokio::test]
+    async fn injected_errors_are_retryable_llm_errors() {
+        let llm = faulty(FaultConfig::new().with_error_rate(1.0));
+        let err = llm.invoke(&[Message::user("hi")]).await.err().unwrap();
+        assert!(
+            matches!(&err, AgentError::Llm(e) if e.kind == LlmErrorKind::Unavailable),
+            "{}",
+            err
+        );
+        assert!(err.is_retryable());
+    }
+
+    /// **Scenario**: Truncation halves the content and marks it cut off; malformed arguments
+    /// no longer parse; the injector counts both.
+    #[tokio::test]
+    async fn corrupts_content_and_tool_arguments() {
+        let injector = Arc::new(FaultInjector::new(
+            FaultConfig::new()
+                .with_truncate_rate(1.0)
+                .with_malformed_args_rate(1.0),
+        ));
+        let llm = FaultyLlm::new(Box::new(MockLlm::with_get_time_call()), injector.clone());
+        let response = llm
+            .invoke_stream(&[Message::user("what time is it?")], None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.content, "I'll check");
+        assert!(response.truncated);
+        assert_eq!(response.metadata.finish_reason.as_deref(), Some("length"));
+        let args = &response.tool_calls[0].arguments;
+        assert!(serde_json::from_str::<serde_json::Value>(args).is_err());
+        assert_eq!(injector.stats().total(), 2);
+    }
+}
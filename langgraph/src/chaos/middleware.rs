@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{FaultInjector, FaultKind, INJECTED_FAULT};
+use crate::error::AgentError;
+use crate::graph::{Next, NodeMiddleware};
+
+/// Node middleware that injects latency and errors before nodes run.
+///
+/// A node picked for an error fails with `AgentError::ExecutionFailed` without running, so
+/// graph retry policies, timeouts and checkpoint resume can be exercised. Truncation and
+/// malformed arguments do not apply to nodes; use [`FaultyLlm`](super::FaultyLlm) and
+/// [`FaultyToolSource`](super::FaultyToolSource) for those.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let injector = Arc::new(FaultInjector::with_seed(FaultConfig::new().with_error_rate(0.3), 1));
+/// let compiled = graph
+///     .with_retry_policy(RetryPolicy::fixed(5, Duration::from_millis(10)))
+///     .compile_with_middleware(Arc::new(
+///         FaultInjectionMiddleware::new(injector.clone()).with_nodes(["act"]),
+///     ))?;
+/// ```
+pub struct FaultInjectionMiddleware<S> {
+    injector: Arc<FaultInjector>,
+    /// When set, only these nodes get faults.
+    nodes: Option<HashSet<String>>,
+    _phantom: std::marker::PhantomData<fn(S)>,
+}
+
+impl<S> FaultInjectionMiddleware<S> {
+    /// Injects faults decided by `injector` into every node.
+    pub fn new(injector: Arc<FaultInjector>) -> Self {
+        Self {
+            injector,
+            nodes: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Limits fault injection to the nodes named in `nodes`.
+    pub fn with_nodes<I, N>(mut self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        self.nodes = Some(nodes.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for FaultInjectionMiddleware<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: Box<
+            dyn FnOnce(
+                    S,
+                ) -> Pin<
+                    Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<(S, Next), AgentError> {
+        let targeted = self
+            .nodes
+            .as_ref()
+            .is_none_or(|nodes| nodes.contains(node_id));
+        if targeted {
+            self.injector.maybe_delay().await;
+            if self.injector.should_inject(FaultKind::Error) {
+                return Err(AgentError::ExecutionFailed(format!(
+                    "{} in node {}",
+                    INJECTED_FAULT, node_id
+                )));
+            }
+        }
+        inner(state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::chaos::FaultConfig;
+    use crate::graph::{Node, RetryPolicy, StateGraph, END, START};
+
+    struct Increment;
+
+    #[async_trait]
+    impl Node<i32> for Increment {
+        fn id(&self) -> &str {
+            "inc"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            Ok((state + 1, Next::Continue))
+        }
+    }
+
+    fn graph() -> StateGraph<i32> {
+        let mut graph = StateGraph::<i32>::new();
+        graph
+            .add_node("inc", Arc::new(Increment))
+            .add_edge(START, "inc")
+            .add_edge("inc", END);
+        graph
+    }
+
+    /// **Scenario**: A targeted node fails with an injected error; other nodes are untouched.
+    #[tokio::test]
+    async fn fails_targeted_nodes_only() {
+        let injector = Arc::new(FaultInjector::new(FaultConfig::new().with_error_rate(1.0)));
+
+        let compiled = graph()
+            .compile_with_middleware(Arc::new(FaultInjectionMiddleware::new(injector.clone())))
+            .unwrap();
+        let err = compiled.invoke(1, None).await.unwrap_err();
+        assert!(
+            matches!(&err, AgentError::ExecutionFailed(m) if m == "injected fault in node inc"),
+            "{}",
+            err
+        );
+
+        let compiled = graph()
+            .compile_with_middleware(Arc::new(
+                FaultInjectionMiddleware::new(injector.clone()).with_nodes(["other"]),
+            ))
+            .unwrap();
+        assert_eq!(compiled.invoke(1, None).await.unwrap(), 2);
+        assert_eq!(injector.stats().errors, 1);
+    }
+
+    /// **Scenario**: With a seeded 50% error rate, the graph retry policy gets the node through.
+    #[tokio::test]
+    async fn retry_policy_recovers_from_injected_errors() {
+        let injector = Arc::new(FaultInjector::with_seed(
+            FaultConfig::new().with_error_rate(0.5),
+            3,
+        ));
+        let compiled = graph()
+            .with_retry_policy(RetryPolicy::fixed(20, Duration::from_millis(1)))
+            .compile_with_middleware(Arc::new(FaultInjectionMiddleware::new(injector.clone())))
+            .unwrap();
+        for _ in 0..5 {
+            assert_eq!(compiled.invoke(1, None).await.unwrap(), 2);
+        }
+        assert!(injector.stats().errors > 0);
+    }
+}
@@ -0,0 +1,298 @@
+//! Fault injection for resilience testing: latency, errors, truncated replies and malformed
+//! tool arguments, injected at configurable rates.
+//!
+//! A [`FaultInjector`] decides per call which faults to inject (rates from [`FaultConfig`],
+//! optionally from a fixed seed so CI runs are reproducible) and counts what it injected. It
+//! drives three wrappers: [`FaultyLlm`] around an [`LlmClient`](crate::LlmClient),
+//! [`FaultyToolSource`] around a [`ToolSource`](crate::ToolSource), and
+//! [`FaultInjectionMiddleware`] around the nodes of a graph. Share one injector between them to
+//! read combined [`FaultStats`].
+//!
+//! **Interaction**: Injected LLM errors are `AgentError::Llm` of kind `Unavailable`, which
+//! `RetryPolicy` retries; injected tool errors are `ToolSourceError::Transport`, which ActNode
+//! handles per `HandleToolErrors`; node faults are `AgentError::ExecutionFailed`.
+
+mod llm;
+mod middleware;
+mod tool_source;
+
+pub use llm::FaultyLlm;
+pub use middleware::FaultInjectionMiddleware;
+pub use tool_source::FaultyToolSource;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Message of every injected error.
+pub const INJECTED_FAULT: &str = "injected fault";
+
+/// How often each fault is injected; every rate is a probability in `0.0..=1.0` (default 0).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FaultConfig {
+    /// Probability that a call is delayed by [`latency`](Self::latency) first.
+    pub latency_rate: f64,
+    /// Delay added to a call picked for latency.
+    pub latency: Duration,
+    /// Probability that a call fails instead of reaching the wrapped client.
+    pub error_rate: f64,
+    /// Probability that a reply (LLM content or tool result) is cut in half.
+    pub truncate_rate: f64,
+    /// Probability that tool arguments are replaced by invalid JSON.
+    pub malformed_args_rate: f64,
+}
+
+impl FaultConfig {
+    /// No faults; enable them with the `with_*` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delays a fraction `rate` of calls by `latency`.
+    pub fn with_latency(mut self, rate: f64, latency: Duration) -> Self {
+        self.latency_rate = clamp_rate(rate);
+        self.latency = latency;
+        self
+    }
+
+    /// Fails a fraction `rate` of calls.
+    pub fn with_error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = clamp_rate(rate);
+        self
+    }
+
+    /// Truncates a fraction `rate` of replies.
+    pub fn with_truncate_rate(mut self, rate: f64) -> Self {
+        self.truncate_rate = clamp_rate(rate);
+        self
+    }
+
+    /// Corrupts the tool arguments of a fraction `rate` of calls.
+    pub fn with_malformed_args_rate(mut self, rate: f64) -> Self {
+        self.malformed_args_rate = clamp_rate(rate);
+        self
+    }
+}
+
+fn clamp_rate(rate: f64) -> f64 {
+    if rate.is_nan() {
+        0.0
+    } else {
+        rate.clamp(0.0, 1.0)
+    }
+}
+
+/// Kind of an injected fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// Extra delay before the call.
+    Latency,
+    /// The call fails.
+    Error,
+    /// The reply is cut in half.
+    Truncate,
+    /// Tool arguments are not valid JSON.
+    MalformedArgs,
+}
+
+/// Number of faults injected so far, per kind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    /// Delayed calls.
+    pub latency: u64,
+    /// Failed calls.
+    pub errors: u64,
+    /// Truncated replies.
+    pub truncations: u64,
+    /// Calls with corrupted tool arguments.
+    pub malformed_args: u64,
+}
+
+impl FaultStats {
+    /// Sum over all kinds.
+    pub fn total(&self) -> u64 {
+        self.latency + self.errors + self.truncations + self.malformed_args
+    }
+}
+
+/// Decides which faults to inject and counts them.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::chaos::{FaultConfig, FaultInjector, FaultyLlm, FaultyToolSource};
+/// use langgraph::{MockLlm, MockToolSource};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let injector = Arc::new(FaultInjector::with_seed(
+///     FaultConfig::new()
+///         .with_latency(0.2, Duration::from_millis(500))
+///         .with_error_rate(0.1)
+///         .with_malformed_args_rate(0.05),
+///     42,
+/// ));
+/// let llm = FaultyLlm::new(Box::new(MockLlm::with_get_time_call()), injector.clone());
+/// let tools = MockToolSource::get_time_example();
+/// let tools = FaultyToolSource::new(Box::new(tools), injector.clone());
+/// // ... run the agent, then check what was injected:
+/// println!("{:?}", injector.stats());
+/// ```
+#[derive(Debug)]
+pub struct FaultInjector {
+    config: FaultConfig,
+    rng: Mutex<u64>,
+    stats: Mutex<FaultStats>,
+}
+
+impl FaultInjector {
+    /// Injector with a random seed.
+    pub fn new(config: FaultConfig) -> Self {
+        Self::with_seed(config, random_seed())
+    }
+
+    /// Injector whose decisions repeat for the same `seed` and call order.
+    pub fn with_seed(config: FaultConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: Mutex::new(seed),
+            stats: Mutex::new(FaultStats::default()),
+        }
+    }
+
+    /// The configured rates.
+    pub fn config(&self) -> &FaultConfig {
+        &self.config
+    }
+
+    /// Faults injected so far.
+    pub fn stats(&self) -> FaultStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Rolls for `kind` at its configured rate; counts and returns true when it is injected.
+    pub fn should_inject(&self, kind: FaultKind) -> bool {
+        let rate = match kind {
+            FaultKind::Latency => self.config.latency_rate,
+            FaultKind::Error => self.config.error_rate,
+            FaultKind::Truncate => self.config.truncate_rate,
+            FaultKind::MalformedArgs => self.config.malformed_args_rate,
+        };
+        if rate <= 0.0 || self.next_unit() >= rate {
+            return false;
+        }
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        match kind {
+            FaultKind::Latency => stats.latency += 1,
+            FaultKind::Error => stats.errors += 1,
+            FaultKind::Truncate => stats.truncations += 1,
+            FaultKind::MalformedArgs => stats.malformed_args += 1,
+        }
+        true
+    }
+
+    /// Sleeps for the configured latency when a latency fault is injected.
+    pub async fn maybe_delay(&self) {
+        if self.should_inject(FaultKind::Latency) {
+            tokio::time::sleep(self.config.latency).await;
+        }
+    }
+
+    /// Next number in `[0, 1)` (splitmix64).
+    fn next_unit(&self) -> f64 {
+        let mut state = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Seed from std's randomly keyed hasher and the clock.
+fn random_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    hasher.finish()
+}
+
+/// First half of `text` (by characters).
+pub(crate) fn truncate_half(text: &str) -> String {
+    let keep = text.chars().count() / 2;
+    text.chars().take(keep).collect()
+}
+
+/// `arguments` cut in half so it no longer parses as JSON (`"{"` for empty input).
+pub(crate) fn malformed_json(arguments: &str) -> String {
+    let cut = truncate_half(arguments.trim());
+    if cut.is_empty() {
+        "{".to_string()
+    } else {
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Rate 0 never injects, rate 1 always does, and every injection is counted.
+    #[test]
+    fn rates_zero_and_one_are_exact() {
+        let injector = FaultInjector::new(
+            FaultConfig::new()
+                .with_error_rate(1.0)
+                .with_truncate_rate(0.0),
+        );
+        for _ in 0..100 {
+            assert!(injector.should_inject(FaultKind::Error));
+            assert!(!injector.should_inject(FaultKind::Truncate));
+            assert!(!injector.should_inject(FaultKind::Latency));
+        }
+        assert_eq!(
+            injector.stats(),
+            FaultStats {
+                errors: 100,
+                ..Default::default()
+            }
+        );
+        assert_eq!(FaultConfig::new().with_error_rate(7.0).error_rate, 1.0);
+    }
+
+    /// **Scenario**: The same seed gives the same decisions; the injected share follows the rate.
+    #[test]
+    fn seeded_injectors_repeat_decisions() {
+        let config = FaultConfig::new().with_error_rate(0.3);
+        let a = FaultInjector::with_seed(config.clone(), 7);
+        let b = FaultInjector::with_seed(config, 7);
+        let rolls_a: Vec<bool> = (0..1000)
+            .map(|_| a.should_inject(FaultKind::Error))
+            .collect();
+        let rolls_b: Vec<bool> = (0..1000)
+            .map(|_| b.should_inject(FaultKind::Error))
+            .collect();
+        assert_eq!(rolls_a, rolls_b);
+        let injected = a.stats().errors;
+        assert!((200..400).contains(&injected), "injected {}", injected);
+    }
+
+    /// **Scenario**: Malformed arguments never parse as JSON.
+    #[test]
+    fn malformed_json_does_not_parse() {
+        for args in ["", "{}", r#"{"q": "rust"}"#, r#"{"a": [1, 2, {"b": "ü"}]}"#] {
+            let bad = malformed_json(args);
+            assert!(
+                serde_json::from_str::<serde_json::Value>(&bad).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+}
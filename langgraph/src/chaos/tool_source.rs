@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{malformed_json, truncate_half, FaultInjector, FaultKind, INJECTED_FAULT};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+
+/// [`ToolSource`] that injects faults around another source.
+///
+/// Before a call: latency, then an error (`ToolSourceError::Transport`), then arguments
+/// replaced by a JSON string that is not valid JSON (as if the model produced it). After a
+/// successful call: result text cut in half. `list_tools` is passed through unchanged.
+pub struct FaultyToolSource {
+    inner: Box<dyn ToolSource>,
+    injector: Arc<FaultInjector>,
+}
+
+impl FaultyToolSource {
+    /// Wraps `inner`, injecting faults decided by `injector`.
+    pub fn new(inner: Box<dyn ToolSource>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+
+    /// Applies the pre-call faults; returns the (possibly corrupted) arguments.
+    async fn before_call(&self, name: &str, arguments: Value) -> Result<Value, ToolSourceError> {
+        self.injector.maybe_delay().await;
+        if self.injector.should_inject(FaultKind::Error) {
+            return Err(ToolSourceError::Transport(format!(
+                "{} calling {}",
+                INJECTED_FAULT, name
+            )));
+        }
+        if self.injector.should_inject(FaultKind::MalformedArgs) {
+            return Ok(Value::String(malformed_json(&arguments.to_string())));
+        }
+        Ok(arguments)
+    }
+
+    fn corrupt(&self, mut content: ToolCallContent) -> ToolCallContent {
+        if !content.text.is_empty() && self.injector.should_inject(FaultKind::Truncate) {
+            content.text = truncate_half(&content.text);
+        }
+        content
+    }
+}
+
+#[async_trait]
+impl ToolSource for FaultyToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.inner.list_tools().await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let arguments = self.before_call(name, arguments).await?;
+        let content = self.inner.call_tool(name, arguments).await?;
+        Ok(self.corrupt(content))
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let arguments = self.before_call(name, arguments).await?;
+        let content = self
+            .inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await?;
+        Ok(self.corrupt(content))
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx)
+    }
+
+    fn tools_changed(&self) -> bool {
+        self.inner.tools_changed()
+    }
+
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.inner.refresh_tools().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::chaos::FaultConfig;
+
+    /// Returns the arguments it was called with.
+    struct EchoSource;
+
+    #[async_trait]
+    impl ToolSource for EchoSource {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            Ok(vec![])
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            Ok(ToolCallContent {
+                text: arguments.to_string(),
+            })
+        }
+    }
+
+    fn faulty(config: FaultConfig) -> FaultyToolSource {
+        FaultyToolSource::new(Box::new(EchoSource), Arc::new(FaultInjector::new(config)))
+    }
+
+    /// **Scenario**: Errors are transport errors naming the tool; without faults the call
+    /// passes through untouched.
+    #[tokio::test]
+    async fn injects_errors_or_passes_through() {
+        let err = faulty(FaultConfig::new().with_error_rate(1.0))
+            .call_tool("search", json!({"q": "rust"}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolSourceError::Transport(m) if m == "injected fault calling search"),
+            "{}",
+            err
+        );
+
+        let ok = faulty(FaultConfig::new())
+            .call_tool_with_context("search", json!({"q": "rust"}), None)
+            .await
+            .unwrap();
+        assert_eq!(ok.text, r#"{"q":"rust"}"#);
+    }
+
+    /// **Scenario**: Malformed arguments reach the tool as an invalid JSON string; truncation
+    /// halves the result.
+    #[tokio::test]
+    async fn corrupts_arguments_and_results() {
+        let echoed = faulty(FaultConfig::new().with_malformed_args_rate(1.0))
+            .call_tool("search", json!({"q": "rust"}))
+            .await
+            .unwrap();
+        assert_eq!(echoed.text, r#""{\"q\":\"""#);
+
+        let cut = faulty(FaultConfig::new().with_truncate_rate(1.0))
+            .call_tool("search", json!({"q": "rust"}))
+            .await
+            .unwrap();
+        assert_eq!(cut.text, r#"{"q":""#);
+    }
+}
@@ -2,14 +2,18 @@
 //!
 //! Used by `Agent::run` and all agents that implement the minimal Agent trait.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
+use crate::features::FeatureMissing;
 use crate::graph::GraphInterrupt;
+use crate::llm::LlmError;
 
 /// Agent execution error.
 ///
 /// Returned by `Agent::run` when a step fails. Aligns with LangGraph-style
-/// single-node execution; no separate error types for tools or LLM in this minimal API.
+/// single-node execution; LLM provider failures carry a classified [`LlmError`].
 #[derive(Debug, Error)]
 pub enum AgentError {
     /// Execution failed with a message (e.g. LLM call failed, tool error).
@@ -23,6 +27,59 @@ pub enum AgentError {
     /// and later resume execution with user input.
     #[error("graph interrupted: {0}")]
     Interrupted(GraphInterrupt),
+
+    /// LLM provider call failed (rate limit, overload, OOM, ...); see [`LlmError`] for the
+    /// kind and retry hint.
+    #[error("LLM error: {0}")]
+    Llm(#[from] LlmError),
+
+    /// A node ran longer than its timeout (`StateGraph::with_node_timeout`) and was aborted.
+    #[error("node {node_id} timed out after {timeout:?}")]
+    NodeTimeout { node_id: String, timeout: Duration },
+
+    /// The run's cancellation token (`RunContext::with_cancellation`) was cancelled, e.g.
+    /// because the client went away. The node in progress was aborted.
+    #[error("run cancelled")]
+    Cancelled,
+
+    /// The graph's `RunGovernor` stopped the run before the next node (e.g. a budget ran out).
+    #[error("run aborted: {0}")]
+    Aborted(String),
+
+    /// The run hit a limit of its budget (tokens, cost or tool calls); see
+    /// `GovernorDecision::BudgetExceeded`.
+    #[error("budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    /// The config needs a Cargo feature this build does not have (e.g. `DATABASE_URL` without
+    /// `postgres`); raised when the run context is built, not during a run.
+    #[error(transparent)]
+    FeatureMissing(#[from] FeatureMissing),
+}
+
+impl AgentError {
+    /// Whether retrying the failed step may succeed: interrupts, cancellation, governor aborts,
+    /// exceeded budgets and permanent LLM errors (quota, bad request, authentication) are not retried; other failures
+    /// (including node timeouts) are.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AgentError::ExecutionFailed(_) | AgentError::NodeTimeout { .. } => true,
+            AgentError::Interrupted(_)
+            | AgentError::Cancelled
+            | AgentError::Aborted(_)
+            | AgentError::BudgetExceeded(_)
+            | AgentError::FeatureMissing(_) => false,
+            AgentError::Llm(e) => e.is_retryable(),
+        }
+    }
+
+    /// Minimum wait before retrying, when the provider asked for one.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            AgentError::Llm(e) => e.retry_after,
+            _ => None,
+        }
+    }
 }
 
 impl From<GraphInterrupt> for AgentError {
@@ -60,4 +117,36 @@ mod tests {
         );
         assert!(s.contains("test"), "Debug should contain message: {}", s);
     }
+
+    /// **Scenario**: Retry hints come from LLM errors; interrupts are never retried.
+    #[test]
+    fn agent_error_retry_hints() {
+        use crate::llm::LlmErrorKind;
+
+        let llm: AgentError = LlmError::new(LlmErrorKind::RateLimited, "slow down")
+            .with_retry_after(Duration::from_secs(3))
+            .into();
+        assert!(llm.is_retryable());
+        assert_eq!(llm.retry_after(), Some(Duration::from_secs(3)));
+        assert!(llm.to_string().contains("rate limited"), "{}", llm);
+
+        let quota: AgentError = LlmError::new(LlmErrorKind::QuotaExceeded, "no credits").into();
+        assert!(!quota.is_retryable());
+
+        assert!(AgentError::ExecutionFailed("x".into()).is_retryable());
+        let interrupt = GraphInterrupt(crate::graph::Interrupt::new(serde_json::json!("ask")));
+        assert!(!AgentError::Interrupted(interrupt).is_retryable());
+        assert!(!AgentError::Cancelled.is_retryable());
+        assert!(!AgentError::Aborted("budget".into()).is_retryable());
+        assert!(!AgentError::BudgetExceeded("tokens".into()).is_retryable());
+        let missing = FeatureMissing::new("postgres", "DATABASE_URL");
+        assert!(!AgentError::from(missing).is_retryable());
+
+        let timeout = AgentError::NodeTimeout {
+            node_id: "act".into(),
+            timeout: Duration::from_secs(30),
+        };
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.to_string(), "node act timed out after 30s");
+    }
 }
@@ -0,0 +1,192 @@
+//! Optional Cargo features of this build, as a capability set checked at construction time.
+//!
+//! Parts of langgraph only exist with a feature (`postgres` for `PostgresSaver`, `redis` for
+//! `RedisSaver`, `lance` for `LanceStore`, ...). [`FeatureSet::compiled`] lists them with
+//! whether this build has them. Config that needs a missing feature fails when the runner is
+//! built, with a [`FeatureMissing`] naming the feature and the setting that needs it, instead
+//! of a string error deep inside a run.
+//!
+//! **Interaction**: [`ReactBuildConfig::check_features`](crate::ReactBuildConfig::check_features)
+//! runs first in [`build_react_runner`](crate::build_react_runner) and
+//! [`build_react_run_context`](crate::build_react_run_context). The set is a
+//! [`ConfigSection`] ("Features"); langgraph-server adds its own features, logs the set with
+//! its config summary and serves it at `GET /v1/capabilities`.
+
+use crate::config::ConfigSection;
+
+/// Optional features and whether this build has them; see the [module docs](self).
+///
+/// # Examples
+///
+/// ```
+/// use langgraph::FeatureSet;
+///
+/// let features = FeatureSet::compiled().with_feature("grpc", false);
+/// assert!(!features.has("grpc"));
+/// assert_eq!(features.has("postgres"), cfg!(feature = "postgres"));
+/// assert!(features.require("grpc", "--grpc-listen").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FeatureSet {
+    features: Vec<(&'static str, bool)>,
+}
+
+impl FeatureSet {
+    /// An empty set; every feature counts as missing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The optional features of the langgraph crate, as compiled into this build.
+    pub fn compiled() -> Self {
+        Self::new()
+            .with_feature("lance", cfg!(feature = "lance"))
+            .with_feature("postgres", cfg!(feature = "postgres"))
+            .with_feature("redis", cfg!(feature = "redis"))
+            .with_feature("browser", cfg!(feature = "browser"))
+            .with_feature("html", cfg!(feature = "html"))
+            .with_feature("wasm-sandbox", cfg!(feature = "wasm-sandbox"))
+            .with_feature("sql-tool", cfg!(feature = "sql-tool"))
+            .with_feature("email", cfg!(feature = "email"))
+            .with_feature("otel", cfg!(feature = "otel"))
+    }
+
+    /// Adds feature `name` (or updates it), e.g. a feature of the application embedding
+    /// langgraph.
+    pub fn with_feature(mut self, name: &'static str, enabled: bool) -> Self {
+        match self.features.iter_mut().find(|(n, _)| *n == name) {
+            Some(entry) => entry.1 = enabled,
+            None => self.features.push((name, enabled)),
+        }
+        self
+    }
+
+    /// Whether this build has feature `name`; false for features the set does not list.
+    pub fn has(&self, name: &str) -> bool {
+        self.features
+            .iter()
+            .any(|(n, enabled)| *n == name && *enabled)
+    }
+
+    /// Names of the features this build has, in order.
+    pub fn enabled(&self) -> Vec<&'static str> {
+        self.iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// Every feature of the set with whether it is enabled, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.features.iter().copied()
+    }
+
+    /// Ok when this build has `feature`; otherwise [`FeatureMissing`] for `needed_by`, the
+    /// setting or flag that needs it.
+    pub fn require(
+        &self,
+        feature: &'static str,
+        needed_by: impl Into<String>,
+    ) -> Result<(), FeatureMissing> {
+        if self.has(feature) {
+            Ok(())
+        } else {
+            Err(FeatureMissing::new(feature, needed_by))
+        }
+    }
+
+    /// `{"<feature>": true | false, ...}`, for capability endpoints.
+    pub fn to_json(&self) -> serde_json::Value {
+        let map = self
+            .iter()
+            .map(|(name, enabled)| (name.to_string(), serde_json::Value::Bool(enabled)))
+            .collect();
+        serde_json::Value::Object(map)
+    }
+}
+
+impl ConfigSection for FeatureSet {
+    fn section_name(&self) -> &str {
+        "Features"
+    }
+
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        self.iter()
+            .map(|(name, enabled)| (name, enabled.to_string()))
+            .collect()
+    }
+}
+
+/// Config needs a feature this build was compiled without.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{needed_by} needs the `{feature}` feature, but this build does not have it")]
+pub struct FeatureMissing {
+    /// The Cargo feature, e.g. `postgres`.
+    pub feature: &'static str,
+    /// The setting or flag that needs it, e.g. `DATABASE_URL`.
+    pub needed_by: String,
+}
+
+impl FeatureMissing {
+    pub fn new(feature: &'static str, needed_by: impl Into<String>) -> Self {
+        Self {
+            feature,
+            needed_by: needed_by.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{build_react_runner, BuildRunnerError, MockLlm, ReactBuildConfig};
+
+    /// **Scenario**: The compiled set mirrors the crate's cfg flags; extra features can be
+    /// added, and requiring a missing one names it and the setting.
+    #[test]
+    fn lists_compiled_features() {
+        let features = FeatureSet::compiled().with_feature("grpc", true);
+        assert_eq!(features.has("redis"), cfg!(feature = "redis"));
+        assert!(features.has("grpc"));
+        assert!(features.enabled().contains(&"grpc"));
+        assert_eq!(features.to_json()["lance"], cfg!(feature = "lance"));
+        assert_eq!(features.entries().len(), 10);
+
+        let missing = features
+            .with_feature("grpc", false)
+            .require("grpc", "--grpc-listen");
+        let err = missing.unwrap_err();
+        assert_eq!(err, FeatureMissing::new("grpc", "--grpc-listen"));
+        assert_eq!(
+            err.to_string(),
+            "--grpc-listen needs the `grpc` feature, but this build does not have it"
+        );
+    }
+
+    /// **Scenario**: A config needing a feature the build lacks fails when the runner is
+    /// built, with a typed error, before anything is connected.
+    #[tokio::test]
+    async fn builder_fails_fast_without_feature() {
+        let mut config = ReactBuildConfig::from_env();
+        config.thread_id = Some("t1".into());
+        config.redis_url = Some("redis://localhost:6379".into());
+        let checked = config.check_features();
+        assert_eq!(checked.is_ok(), cfg!(feature = "redis"));
+        if cfg!(feature = "redis") {
+            return;
+        }
+
+        let llm = Box::new(MockLlm::with_no_tool_calls("ok"));
+        let err = build_react_runner(&config, Some(llm), false)
+            .await
+            .err()
+            .expect("missing feature");
+        assert!(
+            matches!(&err, BuildRunnerError::FeatureMissing(m) if m.feature == "redis"),
+            "{}",
+            err
+        );
+        config.thread_id = None;
+        assert!(config.check_features().is_ok());
+    }
+}
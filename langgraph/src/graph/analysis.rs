@@ -0,0 +1,268 @@
+//! Static analysis of a compiled graph and the tools bound to it.
+//!
+//! [`analyze_graph`] follows the graph's edges from START and reports what no run can reach:
+//! nodes no edge leads to (directly or through other reachable nodes), and the conditional
+//! branches of unreachable routers. [`GraphAnalysis::with_tools`] adds the bound tools no
+//! prompt mentions by name, which the model is unlikely to pick.
+//!
+//! The analysis sees edges only. A conditional router without a path map may return any node
+//! id, so it makes every node reachable; a node that is only reached through `Next::Node` is
+//! reported unreachable.
+//!
+//! **Interaction**: Backs `langgraph --analyze`; reads the same graph fields as
+//! [`generate_dot`](super::generate_dot).
+
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{CompiledStateGraph, NextEntry, END};
+use crate::tool_source::ToolSpec;
+
+/// A path map entry of a conditional edge that can never be taken.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DeadBranch {
+    /// Node whose router owns the branch.
+    pub source: String,
+    /// Routing key of the branch.
+    pub key: String,
+    /// Node (or END) the branch leads to.
+    pub target: String,
+}
+
+/// Findings of [`analyze_graph`]; every list is sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GraphAnalysis {
+    /// Nodes no edge path from START leads to.
+    pub unreachable_nodes: Vec<String>,
+    /// Conditional branches whose source node is unreachable.
+    pub dead_branches: Vec<DeadBranch>,
+    /// Bound tools no prompt mentions (set by [`with_tools`](Self::with_tools)).
+    pub unused_tools: Vec<String>,
+}
+
+impl GraphAnalysis {
+    /// Records the tools in `tools` whose name appears in none of `prompts`, as a whole word.
+    pub fn with_tools(mut self, tools: &[ToolSpec], prompts: &[&str]) -> Self {
+        let unused: BTreeSet<String> = tools
+            .iter()
+            .filter(|tool| !prompts.iter().any(|p| mentions(p, &tool.name)))
+            .map(|tool| tool.name.clone())
+            .collect();
+        self.unused_tools = unused.into_iter().collect();
+        self
+    }
+
+    /// True when nothing was found.
+    pub fn is_clean(&self) -> bool {
+        self.unreachable_nodes.is_empty()
+            && self.dead_branches.is_empty()
+            && self.unused_tools.is_empty()
+    }
+}
+
+impl fmt::Display for GraphAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "No issues found.");
+        }
+        for node in &self.unreachable_nodes {
+            writeln!(f, "unreachable node: {}", node)?;
+        }
+        for branch in &self.dead_branches {
+            writeln!(
+                f,
+                "dead branch: {} --{}--> {} (source is unreachable)",
+                branch.source, branch.key, branch.target
+            )?;
+        }
+        for tool in &self.unused_tools {
+            writeln!(f, "unused tool: {} (no prompt mentions it)", tool)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports the nodes and conditional branches of `graph` that no run can reach.
+pub fn analyze_graph<S>(graph: &CompiledStateGraph<S>) -> GraphAnalysis {
+    let reachable = reachable_nodes(graph);
+
+    let mut unreachable_nodes: Vec<String> = graph
+        .nodes
+        .keys()
+        .filter(|id| !reachable.contains(id.as_str()))
+        .cloned()
+        .collect();
+    unreachable_nodes.sort();
+
+    let mut dead_branches: Vec<DeadBranch> = graph
+        .next_map
+        .iter()
+        .filter(|(source, _)| !reachable.contains(source.as_str()))
+        .filter_map(|(source, entry)| match entry {
+            NextEntry::Conditional(router) => router.path_map.as_ref().map(|map| (source, map)),
+            NextEntry::Unconditional(_) => None,
+        })
+        .flat_map(|(source, map)| {
+            map.iter().map(move |(key, target)| DeadBranch {
+                source: source.clone(),
+                key: key.clone(),
+                target: target.clone(),
+            })
+        })
+        .collect();
+    dead_branches.sort();
+
+    GraphAnalysis {
+        unreachable_nodes,
+        dead_branches,
+        unused_tools: Vec::new(),
+    }
+}
+
+/// Nodes reachable from START through edges, path maps and the final answer node.
+fn reachable_nodes<S>(graph: &CompiledStateGraph<S>) -> HashSet<&str> {
+    let mut reachable: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    queue.push_back(graph.first_node_id.as_str());
+    if let Some(id) = &graph.final_answer_node {
+        queue.push_back(id.as_str());
+    }
+    while let Some(id) = queue.pop_front() {
+        if id == END || !reachable.insert(id) {
+            continue;
+        }
+        match graph.next_map.get(id) {
+            Some(NextEntry::Unconditional(to)) => queue.push_back(to.as_str()),
+            Some(NextEntry::Conditional(router)) => match &router.path_map {
+                Some(map) => queue.extend(map.values().map(String::as_str)),
+                // The router's key is a node id: any node may come next.
+                None => return graph.nodes.keys().map(String::as_str).collect(),
+            },
+            None => {
+                let pos = graph.edge_order.iter().position(|x| x == id);
+                if let Some(next) = pos.and_then(|p| graph.edge_order.get(p + 1)) {
+                    queue.push_back(next.as_str());
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// Whether `text` contains `name` not surrounded by identifier characters.
+fn mentions(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    use crate::error::AgentError;
+    use crate::graph::{Next, Node, StateGraph, START};
+
+    #[derive(Clone, Debug)]
+    struct DummyState;
+
+    struct DummyNode(&'static str);
+
+    #[async_trait]
+    impl Node<DummyState> for DummyNode {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        async fn run(&self, state: DummyState) -> Result<(DummyState, Next), AgentError> {
+            Ok((state, Next::Continue))
+        }
+    }
+
+    fn path_map(entries: &[(&str, &str)]) -> Option<HashMap<String, String>> {
+        Some(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    fn tool(name: &str) -> ToolSpec {
+        ToolSpec {
+            name: name.to_string(),
+            description: None,
+            input_schema: json!({}),
+        }
+    }
+
+    /// **Scenario**: Nodes off every path from START are unreachable, and so are the branches
+    /// of their routers; branches of reachable routers are kept.
+    #[test]
+    fn reports_unreachable_nodes_and_their_branches() {
+        let mut graph = StateGraph::<DummyState>::new();
+        for id in ["a", "b", "orphan", "dangling"] {
+            graph.add_node(id, Arc::new(DummyNode(id)));
+        }
+        graph.add_edge(START, "a");
+        graph.add_conditional_edges(
+            "a",
+            Arc::new(|_| "go".to_string()),
+            path_map(&[("go", "b"), ("stop", END)]),
+        );
+        graph.add_edge("b", END);
+        graph.add_conditional_edges(
+            "orphan",
+            Arc::new(|_| "x".to_string()),
+            path_map(&[("x", "dangling"), ("y", END)]),
+        );
+        let analysis = analyze_graph(&graph.compile().unwrap());
+
+        assert_eq!(analysis.unreachable_nodes, ["dangling", "orphan"]);
+        let branches: Vec<_> = analysis
+            .dead_branches
+            .iter()
+            .map(|b| (b.source.as_str(), b.key.as_str(), b.target.as_str()))
+            .collect();
+        assert_eq!(
+            branches,
+            [("orphan", "x", "dangling"), ("orphan", "y", END)]
+        );
+        assert!(analysis.to_string().contains("unreachable node: orphan"));
+    }
+
+    /// **Scenario**: A router without a path map may route anywhere, so no node is reported.
+    #[test]
+    fn router_without_path_map_reaches_every_node() {
+        let mut graph = StateGraph::<DummyState>::new();
+        for id in ["a", "b"] {
+            graph.add_node(id, Arc::new(DummyNode(id)));
+        }
+        graph.add_edge(START, "a");
+        graph.add_conditional_edges("a", Arc::new(|_| END.to_string()), None);
+        let analysis = analyze_graph(&graph.compile().unwrap());
+        assert!(analysis.is_clean(), "{}", analysis);
+    }
+
+    /// **Scenario**: Tools count as used only when a prompt names them as a whole word.
+    #[test]
+    fn tools_no_prompt_names_are_unused() {
+        let tools = [tool("get_time"), tool("web_fetcher"), tool("remember")];
+        let prompts = ["Call get_time_zone first.", "Fetch pages with web_fetcher."];
+        let analysis = GraphAnalysis::default().with_tools(&tools, &prompts);
+        assert_eq!(analysis.unused_tools, ["get_time", "remember"]);
+
+        let analysis = analysis.with_tools(&tools, &["`get_time`, web_fetcher or remember"]);
+        assert!(analysis.is_clean());
+    }
+}
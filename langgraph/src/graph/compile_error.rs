@@ -35,6 +35,10 @@ pub enum CompilationError {
     /// A value in a conditional path_map is not a valid node id or END.
     #[error("conditional path_map invalid target: {0}")]
     InvalidConditionalPathMap(String),
+
+    /// A node listed in interrupt_before / interrupt_after was not registered via `add_node`.
+    #[error("interrupt node not found: {0}")]
+    InterruptNodeNotFound(String),
 }
 
 #[cfg(test)]
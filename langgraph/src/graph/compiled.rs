@@ -2,30 +2,83 @@
 //!
 //! Built by `StateGraph::compile` or `compile_with_checkpointer`. Holds nodes and
 //! edge order (derived from explicit edges at compile time), optional checkpointer.
-//! When checkpointer is set and config.thread_id is provided, final state is saved after invoke. See docs/rust-langgraph/16-memory-design.md §4.1.
+//! When checkpointer is set and config.thread_id is provided, the input and the state after every
+//! node step are saved as a chain of checkpoints linked by `CheckpointMetadata::parent_checkpoint_id`
+//! (see [`CompiledStateGraph::get_state_history`]). See docs/rust-langgraph/16-memory-design.md §4.1.
+//! Interrupted runs (interrupt_before/after or a node returning `AgentError::Interrupted`) are
+//! saved with `Checkpoint::pending_node` and continued with [`CompiledStateGraph::resume`];
+//! `RunnableConfig::checkpoint_id` on invoke re-runs from an earlier checkpoint (time travel);
+//! [`CompiledStateGraph::update_state`] patches a thread's state between runs.
+//! [`CompiledStateGraph::batch`] runs many invocations concurrently with a limit.
+//! Node timeouts (`StateGraph::with_node_timeout`) and `RunContext::cancellation` abort a node
+//! in progress; the run then fails with `AgentError::NodeTimeout` or `AgentError::Cancelled`.
+//! A [`RunGovernor`] (`with_governor`) is asked before every node whether the run may go on.
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
 use tokio_stream::wrappers::ReceiverStream;
+use tracing::Instrument;
 
+use crate::callbacks::Callbacks;
 use crate::channels::BoxedStateUpdater;
 use crate::error::AgentError;
+use crate::managed::{IsLastStep, IS_LAST_STEP};
 use crate::memory::{Checkpoint, CheckpointSource, Checkpointer, RunnableConfig, Store};
-use crate::stream::{StreamEvent, StreamMode};
+use crate::metrics::{self, CheckpointOp};
+use crate::stream::{
+    CustomEventRegistry, MessagesAccessor, StreamEvent, StreamMode, UiEventsAccessor,
+    UiEventsRetention,
+};
 
-use super::interrupt::InterruptHandler;
+use super::governor::{GovernorDecision, RunGovernor, RunProgress};
+use super::interrupt::{Command, GraphInterrupt, Interrupt, InterruptHandler, ResumeHandler};
 use super::logging::{
     log_graph_complete, log_graph_error, log_graph_start, log_node_complete, log_node_start,
-    log_state_update,
+    log_state_update, node_span, run_span,
 };
 use super::node_middleware::NodeMiddleware;
 use super::retry::RetryPolicy;
 use super::state_graph::END;
 use super::{Next, NextEntry, Node, RunContext};
 
+/// Position of a run in its thread's checkpoint chain.
+#[derive(Debug)]
+struct CheckpointCursor {
+    /// Checkpoint the next save links to as parent.
+    parent_id: Option<String>,
+    /// Step number of the next save (-1 for a thread's first input checkpoint).
+    step: i64,
+    /// The parent already records the current state with the current node pending, so a pause
+    /// before that node reuses it instead of saving a duplicate.
+    at_current: bool,
+}
+
+impl Default for CheckpointCursor {
+    fn default() -> Self {
+        Self {
+            parent_id: None,
+            step: -1,
+            at_current: false,
+        }
+    }
+}
+
+impl CheckpointCursor {
+    /// Cursor continuing after `checkpoint` (resume or time travel from it).
+    fn after<S>(checkpoint: &Checkpoint<S>) -> Self {
+        Self {
+            parent_id: Some(checkpoint.id.clone()),
+            step: checkpoint.metadata.step + 1,
+            at_current: false,
+        }
+    }
+}
+
 /// Compiled graph: immutable structure, supports invoke only.
 ///
 /// Created by `StateGraph::compile()` or `compile_with_checkpointer()`. Runs from first node;
@@ -51,60 +104,121 @@ pub struct CompiledStateGraph<S> {
     pub(super) state_updater: BoxedStateUpdater<S>,
     /// Retry policy for node execution. Default is `RetryPolicy::None`.
     pub(super) retry_policy: RetryPolicy,
+    /// Per-node retry policies overriding `retry_policy` (`StateGraph::add_node_with_retry`).
+    pub(super) node_retry_policies: HashMap<String, RetryPolicy>,
     /// Optional interrupt handler for human-in-the-loop scenarios.
     pub(super) interrupt_handler: Option<Arc<dyn InterruptHandler>>,
+    /// Node ids before which the run pauses (`StateGraph::with_interrupt_before`).
+    pub(super) interrupt_before: HashSet<String>,
+    /// Node ids after which the run pauses (`StateGraph::with_interrupt_after`).
+    pub(super) interrupt_after: HashSet<String>,
+    /// Writes the resume value into state on `resume` (`StateGraph::with_resume_handler`).
+    pub(super) resume_handler: Option<Arc<dyn ResumeHandler<S>>>,
+    /// Reads the state's messages for `StreamMode::MessagesDelta` (`StateGraph::with_messages_accessor`).
+    pub(super) messages_accessor: Option<Arc<dyn MessagesAccessor<S>>>,
+    /// Reads the state's UI events for `StreamMode::UiEvents` and checkpoint pruning
+    /// (`StateGraph::with_ui_events_accessor`).
+    pub(super) ui_events_accessor: Option<Arc<dyn UiEventsAccessor<S>>>,
+    /// Which UI events checkpoints keep (`StateGraph::with_ui_events_retention`).
+    pub(super) ui_events_retention: UiEventsRetention,
+    /// Time limit for nodes without an entry in `node_timeouts` (`StateGraph::with_node_timeout`).
+    pub(super) node_timeout: Option<Duration>,
+    /// Per-node time limits (`StateGraph::add_node_with_timeout`).
+    pub(super) node_timeouts: HashMap<String, Duration>,
+    /// Run policy asked before each node (`StateGraph::with_governor`).
+    pub(super) governor: Option<Arc<dyn RunGovernor<S>>>,
+    /// Node run last when the governor wraps a run up (`StateGraph::with_final_answer_node`).
+    pub(super) final_answer_node: Option<String>,
+    /// Node after which injected updates are merged -> node run next
+    /// (`StateGraph::with_injection_point`).
+    pub(super) injection_points: HashMap<String, String>,
+    /// Handlers told about every run (`StateGraph::with_callback_handler`).
+    pub(super) callbacks: Callbacks,
+    /// Custom stream events nodes may emit (`StateGraph::with_custom_events`).
+    pub(super) custom_events: Option<Arc<CustomEventRegistry>>,
 }
 
 impl<S> CompiledStateGraph<S>
 where
     S: Clone + Send + Sync + Debug + 'static,
 {
+    /// Runs a node once, through the middleware when one is set.
+    async fn execute_node(
+        &self,
+        node: Arc<dyn Node<S>>,
+        state: S,
+        run_ctx: Option<&RunContext<S>>,
+    ) -> Result<(S, Next), AgentError> {
+        if let Some(middleware) = &self.middleware {
+            let node_id = node.id().to_string();
+            let run_ctx_owned = run_ctx.cloned();
+            let node_clone = node.clone();
+            middleware
+                .around_run(
+                    &node_id,
+                    state,
+                    Box::new(move |s| {
+                        let node = node_clone.clone();
+                        let run_ctx_inner = run_ctx_owned.clone();
+                        Box::pin(async move {
+                            if let Some(ctx) = run_ctx_inner.as_ref() {
+                                node.run_with_context(s, ctx).await
+                            } else {
+                                node.run(s).await
+                            }
+                        })
+                    }),
+                )
+                .await
+        } else if let Some(ctx) = run_ctx {
+            node.run_with_context(state, ctx).await
+        } else {
+            node.run(state).await
+        }
+    }
+
     /// Execute a node with retry logic.
     ///
-    /// Attempts to run the node, retrying according to the configured retry policy
-    /// if the execution fails.
+    /// Attempts to run the node, retrying according to the node's retry policy (its own from
+    /// `add_node_with_retry`, else the graph's) if the execution fails with a retryable error
+    /// ([`AgentError::is_retryable`]). Waits between attempts follow [`RetryPolicy::delay_for`].
+    /// Each attempt is aborted with `AgentError::NodeTimeout` after the node's time limit.
     async fn execute_node_with_retry(
         &self,
+        node_id: &str,
         node: Arc<dyn Node<S>>,
         state: S,
         run_ctx: Option<&RunContext<S>>,
     ) -> Result<(S, Next), AgentError> {
+        let timeout = self
+            .node_timeouts
+            .get(node_id)
+            .copied()
+            .or(self.node_timeout);
+        let policy = self
+            .node_retry_policies
+            .get(node_id)
+            .unwrap_or(&self.retry_policy);
         let mut attempt = 0;
         loop {
             let current_state = state.clone();
-            let result = if let Some(middleware) = &self.middleware {
-                let node_id = node.id().to_string();
-                let run_ctx_owned = run_ctx.cloned();
-                let node_clone = node.clone();
-                middleware
-                    .around_run(
-                        &node_id,
-                        current_state,
-                        Box::new(move |s| {
-                            let node = node_clone.clone();
-                            let run_ctx_inner = run_ctx_owned.clone();
-                            Box::pin(async move {
-                                if let Some(ctx) = run_ctx_inner.as_ref() {
-                                    node.run_with_context(s, ctx).await
-                                } else {
-                                    node.run(s).await
-                                }
-                            })
-                        }),
-                    )
-                    .await
-            } else if let Some(ctx) = run_ctx {
-                node.run_with_context(current_state, ctx).await
-            } else {
-                node.run(current_state).await
+            let run = self.execute_node(node.clone(), current_state, run_ctx);
+            let result = match timeout {
+                Some(limit) => tokio::time::timeout(limit, run).await.unwrap_or_else(|_| {
+                    Err(AgentError::NodeTimeout {
+                        node_id: node_id.to_string(),
+                        timeout: limit,
+                    })
+                }),
+                None => run.await,
             };
 
             match result {
                 Ok(output) => return Ok(output),
                 Err(e) => {
-                    // Check if we should retry
-                    if self.retry_policy.should_retry(attempt) {
-                        let delay = self.retry_policy.delay(attempt);
+                    // Retry transient errors, waiting at least as long as the provider asked
+                    if e.is_retryable() && policy.should_retry(attempt) {
+                        let delay = policy.delay_for(attempt, &e);
                         if delay > std::time::Duration::ZERO {
                             tokio::time::sleep(delay).await;
                         }
@@ -117,31 +231,375 @@ where
         }
     }
 
+    /// Saves `state` for `config.thread_id` when a checkpointer is set and emits a Checkpoint
+    /// event if enabled. `pending_node` is the node to run next (None when the run finished).
+    /// The checkpoint links to `cursor`'s parent; on success the cursor advances to it.
+    /// Returns the checkpoint id.
+    async fn save_checkpoint(
+        &self,
+        state: &S,
+        config: &Option<RunnableConfig>,
+        run_ctx: Option<&RunContext<S>>,
+        pending_node: Option<&str>,
+        source: CheckpointSource,
+        cursor: &mut CheckpointCursor,
+    ) -> Option<String> {
+        let (cp, cfg) = match (&self.checkpointer, config) {
+            (Some(cp), Some(cfg)) if cfg.thread_id.is_some() => (cp, cfg),
+            _ => return None,
+        };
+        let mut checkpoint =
+            Checkpoint::from_state(self.checkpoint_state(state.clone()), source, cursor.step);
+        checkpoint.metadata.parent_checkpoint_id = cursor.parent_id.clone();
+        if let Some(node) = pending_node {
+            checkpoint = checkpoint.with_pending_node(node);
+        }
+        let started = Instant::now();
+        let saved = cp.put(cfg, &checkpoint).await.ok();
+        metrics::record(|m| m.checkpoint(CheckpointOp::Write, started.elapsed(), saved.is_some()));
+        if let Some(ref id) = saved {
+            cursor.parent_id = Some(id.clone());
+            cursor.step += 1;
+            cursor.at_current = pending_node.is_some();
+        }
+        if let Some(ctx) = run_ctx {
+            if let Some(tx) = &ctx.stream_tx {
+                if ctx.stream_mode.contains(&StreamMode::Checkpoints)
+                    || ctx.stream_mode.contains(&StreamMode::Debug)
+                {
+                    let checkpoint_ns = if cfg.checkpoint_ns.is_empty() {
+                        None
+                    } else {
+                        Some(cfg.checkpoint_ns.clone())
+                    };
+                    let _ = tx
+                        .send(StreamEvent::Checkpoint(crate::stream::CheckpointEvent {
+                            checkpoint_id: checkpoint.id.clone(),
+                            timestamp: checkpoint.ts.clone(),
+                            step: checkpoint.metadata.step,
+                            state: checkpoint.channel_values.clone(),
+                            thread_id: cfg.thread_id.clone(),
+                            checkpoint_ns,
+                        }))
+                        .await;
+                }
+            }
+        }
+        saved
+    }
+
+    /// `state` as checkpoints hold it: UI events pruned by `ui_events_retention`.
+    fn checkpoint_state(&self, mut state: S) -> S {
+        if let Some(accessor) = &self.ui_events_accessor {
+            self.ui_events_retention
+                .prune(accessor.ui_events_mut(&mut state));
+        }
+        state
+    }
+
+    /// Pauses at a static interrupt point (`interrupt_before` / `interrupt_after` of `node_id`):
+    /// checkpoints with `pending_node` (reusing the step checkpoint when it already has it),
+    /// notifies the interrupt handler, and returns the error `invoke` reports. The interrupt id
+    /// is the checkpoint id when one was saved.
+    #[allow(clippy::too_many_arguments)]
+    async fn pause(
+        &self,
+        state: &S,
+        config: &Option<RunnableConfig>,
+        run_ctx: Option<&RunContext<S>>,
+        node_id: &str,
+        when: &str,
+        pending_node: &str,
+        cursor: &mut CheckpointCursor,
+    ) -> AgentError {
+        let checkpoint_id = if cursor.at_current {
+            cursor.parent_id.clone()
+        } else {
+            self.save_checkpoint(
+                state,
+                config,
+                run_ctx,
+                Some(pending_node),
+                CheckpointSource::Loop,
+                cursor,
+            )
+            .await
+        };
+        let value = serde_json::json!({
+            "node": node_id,
+            "when": when,
+            "pending_node": pending_node,
+        });
+        let interrupt = match checkpoint_id {
+            Some(id) => Interrupt::with_id(value, id),
+            None => Interrupt::new(value),
+        };
+        if let Some(handler) = &self.interrupt_handler {
+            let _ = handler.handle_interrupt(&interrupt);
+        }
+        let err = AgentError::Interrupted(GraphInterrupt(interrupt));
+        log_graph_error(&err);
+        err
+    }
+
+    /// Starts a fresh run's checkpoint chain and returns its cursor, or None when there is
+    /// nothing to run.
+    ///
+    /// With `config.checkpoint_id` (time travel) the state and first node are taken from that
+    /// checkpoint and new checkpoints branch off it; a checkpoint without `pending_node` (the
+    /// run had finished) leaves nothing to run. Otherwise the run links to the thread's latest
+    /// checkpoint and saves an input checkpoint.
+    async fn begin_run(
+        &self,
+        state: &mut S,
+        config: &Option<RunnableConfig>,
+        current_id: &mut String,
+        run_ctx: Option<&RunContext<S>>,
+    ) -> Result<Option<CheckpointCursor>, AgentError> {
+        let mut cursor = CheckpointCursor::default();
+        let (cp, cfg) = match (&self.checkpointer, config) {
+            (Some(cp), Some(cfg)) if cfg.thread_id.is_some() => (cp, cfg),
+            _ => return Ok(Some(cursor)),
+        };
+
+        if let Some(ref checkpoint_id) = cfg.checkpoint_id {
+            let started = Instant::now();
+            let loaded = cp.get_tuple(cfg).await;
+            metrics::record(|m| {
+                m.checkpoint(CheckpointOp::Read, started.elapsed(), loaded.is_ok())
+            });
+            let (checkpoint, _) = loaded
+                .map_err(|e| AgentError::ExecutionFailed(format!("load checkpoint: {}", e)))?
+                .ok_or_else(|| {
+                    AgentError::ExecutionFailed(format!("checkpoint not found: {}", checkpoint_id))
+                })?;
+            let cursor = CheckpointCursor::after(&checkpoint);
+            *state = checkpoint.channel_values;
+            return match checkpoint.pending_node {
+                None => Ok(None),
+                Some(node) if self.nodes.contains_key(&node) => {
+                    *current_id = node;
+                    Ok(Some(cursor))
+                }
+                Some(node) => Err(AgentError::ExecutionFailed(format!(
+                    "pending node not in graph: {}",
+                    node
+                ))),
+            };
+        }
+
+        let started = Instant::now();
+        let latest = cp.get_tuple(cfg).await;
+        metrics::record(|m| m.checkpoint(CheckpointOp::Read, started.elapsed(), latest.is_ok()));
+        if let Ok(Some((latest, _))) = latest {
+            cursor = CheckpointCursor::after(&latest);
+        }
+        self.save_checkpoint(
+            state,
+            config,
+            run_ctx,
+            Some(current_id.as_str()),
+            CheckpointSource::Input,
+            &mut cursor,
+        )
+        .await;
+        Ok(Some(cursor))
+    }
+
     /// Shared run loop used by invoke() and stream(): steps through nodes until completion.
     ///
     /// This method includes:
     /// - Structured logging for graph execution events
     /// - Retry mechanism for transient failures
-    /// - Interrupt handling support
+    /// - Interrupt handling support (node interrupts and interrupt_before/after)
+    /// - A checkpoint after every node step, linked to the previous one
+    ///
+    /// `resume_from` is None for a fresh run (see `begin_run`). When resuming it is the cursor
+    /// after the resumed checkpoint, and the `interrupt_before` check is skipped for the first
+    /// node, which is the node the previous run paused at.
+    ///
+    /// The run executes inside the run context's span ([`RunContext::span`]), or a `run` span
+    /// with a fresh run id when there is no context, so everything logged during the run
+    /// carries `run_id`, `thread_id` and `user_id`. Callback handlers (the graph's, then the
+    /// config's) are told when it starts and ends.
     async fn run_loop_inner(
         &self,
         state: &mut S,
         config: &Option<RunnableConfig>,
         current_id: &mut String,
         run_ctx: Option<&RunContext<S>>,
+        resume_from: Option<CheckpointCursor>,
+    ) -> Result<(), AgentError> {
+        // With a governor or callbacks, nodes always get a context (also on `invoke`): to
+        // report usage in, or to find the handlers on.
+        let has_callbacks =
+            !self.callbacks.is_empty() || config.as_ref().is_some_and(|c| !c.callbacks.is_empty());
+        let owned_ctx = (run_ctx.is_none() && (self.governor.is_some() || has_callbacks))
+            .then(|| RunContext::new(config.clone().unwrap_or_default()));
+        let run_ctx = run_ctx.or(owned_ctx.as_ref());
+        // The graph's handlers are called before the run's own; the graph's custom event
+        // registry applies unless the run brings its own.
+        let needs_graph_ctx = |ctx: &&RunContext<S>| {
+            !self.callbacks.is_empty()
+                || (ctx.custom_events.is_none() && self.custom_events.is_some())
+        };
+        let graph_callbacks_ctx = run_ctx.filter(needs_graph_ctx).map(|ctx| {
+            let mut ctx = ctx.clone();
+            ctx.callbacks = self.callbacks.chain(&ctx.callbacks);
+            if ctx.custom_events.is_none() {
+                ctx.custom_events = self.custom_events.clone();
+            }
+            ctx
+        });
+        let run_ctx = graph_callbacks_ctx.as_ref().or(run_ctx);
+        let span = match run_ctx {
+            Some(ctx) => ctx.span(),
+            None => run_span(config.as_ref(), &crate::memory::uuid6().to_string()),
+        };
+        if let Some(ctx) = run_ctx {
+            ctx.emit_callback(|h, run_id| h.on_graph_start(run_id));
+        }
+        let result = self
+            .run_steps(state, config, current_id, run_ctx, resume_from)
+            .instrument(span)
+            .await;
+        self.flush_checkpoints(config).await;
+        if let Some(ctx) = run_ctx {
+            ctx.emit_callback(|h, run_id| h.on_graph_end(run_id, result.as_ref().err()));
+        }
+        result
+    }
+
+    /// Writes the checkpoints of the run the checkpointer still buffers (see
+    /// [`Checkpointer::flush`]), also when the run was interrupted or failed. Like a failed
+    /// checkpoint put, a failed flush is logged and does not fail the run.
+    async fn flush_checkpoints(&self, config: &Option<RunnableConfig>) {
+        let Some(cp) = &self.checkpointer else {
+            return;
+        };
+        if config.as_ref().is_none_or(|c| c.thread_id.is_none()) {
+            return;
+        }
+        if let Err(e) = cp.flush().await {
+            tracing::warn!(error = %e, "flushing checkpoints failed");
+        }
+    }
+
+    /// Body of [`run_loop_inner`](Self::run_loop_inner), run inside the run's span.
+    async fn run_steps(
+        &self,
+        state: &mut S,
+        config: &Option<RunnableConfig>,
+        current_id: &mut String,
+        run_ctx: Option<&RunContext<S>>,
+        resume_from: Option<CheckpointCursor>,
     ) -> Result<(), AgentError> {
         log_graph_start();
 
+        let mut resuming = resume_from.is_some();
+        let mut cursor = match resume_from {
+            Some(cursor) => cursor,
+            None => match self.begin_run(state, config, current_id, run_ctx).await {
+                Ok(Some(cursor)) => cursor,
+                Ok(None) => {
+                    log_graph_complete();
+                    return Ok(());
+                }
+                Err(e) => {
+                    log_graph_error(&e);
+                    return Err(e);
+                }
+            },
+        };
+
+        let started = Instant::now();
+        let mut steps = 0;
+        // The governor wrapped the run up: the current node is the last one
+        let mut finishing = false;
+
         loop {
+            let skip_interrupt_before = std::mem::replace(&mut resuming, false);
+            if !skip_interrupt_before && self.interrupt_before.contains(current_id.as_str()) {
+                let node_id = current_id.as_str();
+                return Err(self
+                    .pause(
+                        state,
+                        config,
+                        run_ctx,
+                        node_id,
+                        "before",
+                        node_id,
+                        &mut cursor,
+                    )
+                    .await);
+            }
+            if let Some(governor) = self.governor.as_ref().filter(|_| !finishing) {
+                let progress = RunProgress {
+                    node_id: current_id.clone(),
+                    steps,
+                    elapsed: started.elapsed(),
+                    usage: run_ctx.map(|ctx| ctx.usage.total()).unwrap_or_default(),
+                };
+                match governor.decide(state, &progress).await {
+                    GovernorDecision::Continue => {}
+                    GovernorDecision::Abort(reason) => {
+                        let e = AgentError::Aborted(reason);
+                        log_graph_error(&e);
+                        return Err(e);
+                    }
+                    GovernorDecision::BudgetExceeded(reason) => {
+                        let e = AgentError::BudgetExceeded(reason);
+                        log_graph_error(&e);
+                        return Err(e);
+                    }
+                    GovernorDecision::SkipToFinalAnswer => match &self.final_answer_node {
+                        Some(final_id) => {
+                            finishing = true;
+                            if *final_id != *current_id {
+                                cursor.at_current = false;
+                                *current_id = final_id.clone();
+                            }
+                        }
+                        None => {
+                            self.save_checkpoint(
+                                state,
+                                config,
+                                run_ctx,
+                                None,
+                                CheckpointSource::Loop,
+                                &mut cursor,
+                            )
+                            .await;
+                            log_graph_complete();
+                            return Ok(());
+                        }
+                    },
+                }
+            }
+            // Whether the latest checkpoint already holds the pre-node state with this node
+            // pending; the node's update invalidates it.
+            let saved_before_node = std::mem::replace(&mut cursor.at_current, false);
+
             let node = self
                 .nodes
                 .get(current_id)
                 .expect("compiled graph has all nodes")
                 .clone();
             let current_state = state.clone();
+            let messages_before = self
+                .messages_accessor
+                .as_ref()
+                .map(|a| a.messages(state).len());
+            let ui_events_before = self
+                .ui_events_accessor
+                .as_ref()
+                .map(|a| a.ui_events(state).len());
 
             // Log node execution start
             log_node_start(current_id);
+            if let Some(ctx) = run_ctx {
+                ctx.emit_callback(|h, run_id| h.on_node_start(run_id, current_id.as_str()));
+            }
 
             // Emit TaskStart event if Tasks or Debug mode is enabled
             if let Some(ctx) = run_ctx {
@@ -158,50 +616,50 @@ where
                 }
             }
 
-            // Execute node with retry logic
-            let result = self
-                .execute_node_with_retry(node, current_state, run_ctx)
-                .await;
+            // The final answer node sees `IsLastStep` so it answers instead of going on
+            let last_step_ctx = run_ctx.filter(|_| finishing).map(|ctx| {
+                ctx.clone()
+                    .with_managed_value(IS_LAST_STEP, Arc::new(IsLastStep::new(true)))
+            });
+            let node_ctx = last_step_ctx.as_ref().or(run_ctx);
+
+            // Execute node with retry logic; a cancelled run aborts the node in progress
+            let cancellation = run_ctx.and_then(|ctx| ctx.cancellation.as_ref());
+            let started = Instant::now();
+            let execution = self
+                .execute_node_with_retry(current_id.as_str(), node, current_state, node_ctx)
+                .instrument(node_span(current_id));
+            let result = match cancellation {
+                Some(token) => tokio::select! {
+                    biased;
+                    _ = token.cancelled() => Err(AgentError::Cancelled),
+                    result = execution => result,
+                },
+                None => execution.await,
+            };
+            let ok = matches!(result, Ok(_) | Err(AgentError::Interrupted(_)));
+            metrics::record(|m| m.node_finished(current_id.as_str(), started.elapsed(), ok));
+            if let Some(ctx) = run_ctx {
+                ctx.emit_callback(|h, run_id| {
+                    h.on_node_end(run_id, current_id.as_str(), result.as_ref().err())
+                });
+            }
 
             // Handle errors (including interrupts)
             let (new_state, next) = match result {
                 Ok(output) => output,
                 Err(AgentError::Interrupted(ref interrupt)) => {
-                    // Handle interrupt: save checkpoint and optionally call handler
-                    if let (Some(cp), Some(cfg)) = (&self.checkpointer, config) {
-                        if cfg.thread_id.is_some() {
-                            // Save checkpoint before interrupt so we can resume later
-                            let checkpoint =
-                                Checkpoint::from_state(state.clone(), CheckpointSource::Update, 0);
-                            let _ = cp.put(cfg, &checkpoint).await;
-
-                            // Emit checkpoint event if enabled
-                            if let Some(ctx) = run_ctx {
-                                if let Some(tx) = &ctx.stream_tx {
-                                    if ctx.stream_mode.contains(&StreamMode::Checkpoints)
-                                        || ctx.stream_mode.contains(&StreamMode::Debug)
-                                    {
-                                        let checkpoint_ns = if cfg.checkpoint_ns.is_empty() {
-                                            None
-                                        } else {
-                                            Some(cfg.checkpoint_ns.clone())
-                                        };
-                                        let _ = tx
-                                            .send(StreamEvent::Checkpoint(
-                                                crate::stream::CheckpointEvent {
-                                                    checkpoint_id: checkpoint.id.clone(),
-                                                    timestamp: checkpoint.ts.clone(),
-                                                    step: checkpoint.metadata.step,
-                                                    state: state.clone(),
-                                                    thread_id: cfg.thread_id.clone(),
-                                                    checkpoint_ns,
-                                                },
-                                            ))
-                                            .await;
-                                    }
-                                }
-                            }
-                        }
+                    // Checkpoint the pre-node state so resume re-runs this node
+                    if !saved_before_node {
+                        self.save_checkpoint(
+                            state,
+                            config,
+                            run_ctx,
+                            Some(current_id.as_str()),
+                            CheckpointSource::Loop,
+                            &mut cursor,
+                        )
+                        .await;
                     }
 
                     // Call interrupt handler if configured
@@ -271,6 +729,7 @@ where
 
             // Log node completion
             log_node_complete(current_id, &next);
+            steps += 1;
 
             // Apply state update using the configured updater
             self.state_updater.apply_update(state, &new_state);
@@ -278,6 +737,19 @@ where
             // Log state update
             log_state_update(current_id);
 
+            let mut next_id = if finishing {
+                None
+            } else {
+                self.route_after(current_id, state, next)
+            };
+            // Updates injected while the node ran redirect the run to the point's target
+            if !finishing {
+                let ending = next_id.is_none() || next_id.as_deref() == Some(END);
+                if let Some(then) = self.merge_injections(state, current_id, run_ctx, ending) {
+                    next_id = Some(then);
+                }
+            }
+
             if let Some(ctx) = run_ctx {
                 if let Some(tx) = &ctx.stream_tx {
                     if ctx.stream_mode.contains(&StreamMode::Values) {
@@ -291,82 +763,136 @@ where
                             })
                             .await;
                     }
-                }
-            }
-
-            let next_id: Option<String> = if let Some(NextEntry::Conditional(router)) =
-                self.next_map.get(current_id)
-            {
-                Some(router.resolve_next(state))
-            } else {
-                match next {
-                    Next::End => None,
-                    Next::Node(id) => Some(id),
-                    Next::Continue => self
-                        .next_map
-                        .get(current_id)
-                        .and_then(|e| {
-                            if let NextEntry::Unconditional(id) = e {
-                                Some(id.clone())
-                            } else {
-                                None
+                    if ctx.stream_mode.contains(&StreamMode::MessagesDelta) {
+                        if let (Some(accessor), Some(before)) =
+                            (&self.messages_accessor, messages_before)
+                        {
+                            let messages = accessor.messages(state);
+                            let base = before.min(messages.len());
+                            if base < before || messages.len() > base {
+                                let _ = tx
+                                    .send(StreamEvent::MessagesDelta {
+                                        node_id: current_id.clone(),
+                                        base,
+                                        messages: messages[base..].to_vec(),
+                                    })
+                                    .await;
                             }
-                        })
-                        .or_else(|| {
-                            let pos = self.edge_order.iter().position(|x| x == current_id)?;
-                            self.edge_order.get(pos + 1).cloned()
-                        }),
-                }
-            };
-
-            let should_end = next_id.is_none() || next_id.as_deref() == Some(END);
-            if should_end {
-                if let (Some(cp), Some(cfg)) = (&self.checkpointer, config) {
-                    if cfg.thread_id.is_some() {
-                        let checkpoint =
-                            Checkpoint::from_state(state.clone(), CheckpointSource::Update, 0);
-                        let _ = cp.put(cfg, &checkpoint).await;
-                        if let Some(ctx) = run_ctx {
-                            if let Some(tx) = &ctx.stream_tx {
-                                if ctx.stream_mode.contains(&StreamMode::Checkpoints)
-                                    || ctx.stream_mode.contains(&StreamMode::Debug)
-                                {
-                                    let checkpoint_ns = if cfg.checkpoint_ns.is_empty() {
-                                        None
-                                    } else {
-                                        Some(cfg.checkpoint_ns.clone())
-                                    };
-                                    let _ = tx
-                                        .send(StreamEvent::Checkpoint(
-                                            crate::stream::CheckpointEvent {
-                                                checkpoint_id: checkpoint.id.clone(),
-                                                timestamp: checkpoint.ts.clone(),
-                                                step: checkpoint.metadata.step,
-                                                state: state.clone(),
-                                                thread_id: cfg.thread_id.clone(),
-                                                checkpoint_ns,
-                                            },
-                                        ))
-                                        .await;
-                                }
+                        }
+                    }
+                    if ctx.stream_mode.contains(&StreamMode::UiEvents) {
+                        if let (Some(accessor), Some(before)) =
+                            (&self.ui_events_accessor, ui_events_before)
+                        {
+                            let events = accessor.ui_events(state);
+                            if events.len() > before {
+                                let _ = tx
+                                    .send(StreamEvent::UiEvents {
+                                        node_id: current_id.clone(),
+                                        events: events[before..].to_vec(),
+                                    })
+                                    .await;
                             }
                         }
                     }
                 }
+            }
+
+            let should_end = next_id.is_none() || next_id.as_deref() == Some(END);
+            let pending_node = if should_end { None } else { next_id.as_deref() };
+            self.save_checkpoint(
+                state,
+                config,
+                run_ctx,
+                pending_node,
+                CheckpointSource::Loop,
+                &mut cursor,
+            )
+            .await;
+            if should_end {
                 log_graph_complete();
                 return Ok(());
             }
             if let Some(id) = next_id {
+                if self.interrupt_after.contains(current_id.as_str()) {
+                    return Err(self
+                        .pause(
+                            state,
+                            config,
+                            run_ctx,
+                            current_id,
+                            "after",
+                            &id,
+                            &mut cursor,
+                        )
+                        .await);
+                }
                 *current_id = id;
             }
         }
     }
 
+    /// Merges the updates queued on the run's injector into `state` when `node_id` is an
+    /// injection point, returning the node to continue at; None when nothing was queued.
+    /// When the run would end here (`ending`), an empty queue is closed in the same step.
+    fn merge_injections(
+        &self,
+        state: &mut S,
+        node_id: &str,
+        run_ctx: Option<&RunContext<S>>,
+        ending: bool,
+    ) -> Option<String> {
+        let then = self.injection_points.get(node_id)?;
+        let injector = run_ctx?.injector.as_ref()?;
+        let updates = injector.take(ending);
+        if updates.is_empty() {
+            return None;
+        }
+        tracing::debug!(node_id, count = updates.len(), next = %then, "merging injected updates");
+        for update in updates {
+            update(state);
+        }
+        Some(then.clone())
+    }
+
+    /// Node to run after `node_id` returned `next`, given the merged `state`: the node's
+    /// conditional router when it has one, otherwise `next`, where `Next::Continue` follows the
+    /// node's edge (or edge order). None or `END` means the run is over.
+    fn route_after(&self, node_id: &str, state: &S, next: Next) -> Option<String> {
+        if let Some(NextEntry::Conditional(router)) = self.next_map.get(node_id) {
+            return Some(router.resolve_next(state));
+        }
+        match next {
+            Next::End => None,
+            Next::Node(id) => Some(id),
+            Next::Continue => self
+                .next_map
+                .get(node_id)
+                .and_then(|e| {
+                    if let NextEntry::Unconditional(id) = e {
+                        Some(id.clone())
+                    } else {
+                        None
+                    }
+                })
+                .or_else(|| {
+                    let pos = self.edge_order.iter().position(|x| x == node_id)?;
+                    self.edge_order.get(pos + 1).cloned()
+                }),
+        }
+    }
+
     /// Runs the graph with the given state. Starts at the first node in edge order;
     /// after each node, uses returned `Next` to continue linear order, jump to a node, or end.
     ///
     /// When `config` has `thread_id` and the graph was compiled with a checkpointer,
-    /// the final state is saved after the run. Pass `None` for config to keep current behavior (no persistence).
+    /// the input and the state after every node are saved as checkpoints, the last one being
+    /// the final state. Pass `None` for config to keep current behavior (no persistence).
+    ///
+    /// When `config.checkpoint_id` is also set, `state` is ignored: the run re-starts from that
+    /// checkpoint's state and pending node, and its checkpoints branch off it (time travel).
+    /// A checkpoint of a finished run has nothing left to run and its state is returned as is.
+    /// Follow-up calls on the new branch should drop `checkpoint_id`.
     ///
     /// - `Next::Continue`: run the next node in edge_order, or end if last.
     /// - `Next::Node(id)`: run the node with that id next.
@@ -378,7 +904,7 @@ where
         let mut state = state;
         let mut current_id = self.first_node_id.clone();
 
-        self.run_loop_inner(&mut state, &config, &mut current_id, None)
+        self.run_loop_inner(&mut state, &config, &mut current_id, None, None)
             .await?;
 
         Ok(state)
@@ -417,12 +943,72 @@ where
         let mut current_id = self.first_node_id.clone();
 
         let config = Some(run_ctx.config.clone());
-        self.run_loop_inner(&mut state, &config, &mut current_id, Some(&run_ctx))
+        self.run_loop_inner(&mut state, &config, &mut current_id, Some(&run_ctx), None)
             .await?;
 
         Ok(state)
     }
 
+    /// Runs [`invoke`](Self::invoke) for every `(state, config)` pair, at most `concurrency` at a
+    /// time (0 is treated as 1), and returns one result per input in input order.
+    ///
+    /// Each run is a separate tokio task, so runs proceed in parallel on a multi-threaded
+    /// runtime; one failing (or panicking) run does not affect the others. Runs sharing a
+    /// `thread_id` are not serialized: give each input its own thread when checkpointing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let inputs = prompts.into_iter().map(|p| (MyState::from(p), None)).collect();
+    /// for result in graph.batch(inputs, 16).await {
+    ///     println!("{:?}", result?);
+    /// }
+    /// ```
+    pub async fn batch(
+        &self,
+        inputs: Vec<(S, Option<RunnableConfig>)>,
+        concurrency: usize,
+    ) -> Vec<Result<S, AgentError>> {
+        let graph = Arc::new(self.clone());
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut results: Vec<Option<Result<S, AgentError>>> = Vec::new();
+        results.resize_with(inputs.len(), || None);
+        let mut tasks = JoinSet::new();
+
+        for (index, (state, config)) in inputs.into_iter().enumerate() {
+            // Acquiring before spawning keeps at most `concurrency` tasks alive at once.
+            let permit = permits
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("batch semaphore is never closed");
+            let graph = graph.clone();
+            tasks.spawn(async move {
+                let result = graph.invoke(state, config).await;
+                drop(permit);
+                (index, result)
+            });
+        }
+
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((index, result)) = joined {
+                results[index] = Some(result);
+            }
+        }
+
+        // A run without a result panicked.
+        results
+            .into_iter()
+            .map(|r| {
+                r.unwrap_or_else(|| {
+                    Err(AgentError::ExecutionFailed(
+                        "batch run panicked".to_string(),
+                    ))
+                })
+            })
+            .collect()
+    }
+
     /// Streams graph execution, emitting events via channel-backed Stream.
     pub fn stream(
         &self,
@@ -433,6 +1019,8 @@ where
         let (tx, rx) = mpsc::channel(128);
         let graph = self.clone();
         let mode_set: HashSet<StreamMode> = stream_mode.into();
+        // The run executes on its own task; keep it in the caller's trace.
+        let parent_span = tracing::Span::current();
 
         tokio::spawn(async move {
             let mut state = state;
@@ -440,18 +1028,337 @@ where
                 Some(id) => id,
                 None => return,
             };
-            let mut run_ctx = RunContext::new(config.clone().unwrap_or_default());
+            let mut run_ctx =
+                RunContext::new(config.clone().unwrap_or_default()).with_parent_span(parent_span);
             run_ctx.stream_tx = Some(tx);
             run_ctx.stream_mode = mode_set;
+            run_ctx.store = graph.store.clone();
 
             let _ = graph
-                .run_loop_inner(&mut state, &config, &mut current_id, Some(&run_ctx))
+                .run_loop_inner(&mut state, &config, &mut current_id, Some(&run_ctx), None)
                 .await;
         });
 
         ReceiverStream::new(rx)
     }
 
+    /// Streams graph execution with a caller-built RunContext, e.g. one carrying a store,
+    /// runtime context or cancellation token (see [`RunContext::with_cancellation`]). The
+    /// context's stream sender and modes are replaced by the returned stream's; its store
+    /// defaults to the graph's, its parent span to the caller's current span.
+    pub fn stream_with_context(
+        &self,
+        state: S,
+        run_ctx: RunContext<S>,
+        stream_mode: impl Into<HashSet<StreamMode>>,
+    ) -> ReceiverStream<StreamEvent<S>> {
+        let (tx, rx) = mpsc::channel(128);
+        let graph = self.clone();
+        let mut run_ctx = run_ctx;
+        run_ctx.stream_tx = Some(tx);
+        run_ctx.stream_mode = stream_mode.into();
+        if run_ctx.store.is_none() {
+            run_ctx.store = graph.store.clone();
+        }
+        if run_ctx.parent_span.is_none() {
+            run_ctx.parent_span = Some(tracing::Span::current());
+        }
+
+        tokio::spawn(async move {
+            if !graph.nodes.contains_key(&graph.first_node_id) {
+                return;
+            }
+            let mut state = state;
+            let mut current_id = graph.first_node_id.clone();
+            let config = Some(run_ctx.config.clone());
+            let _ = graph
+                .run_loop_inner(&mut state, &config, &mut current_id, Some(&run_ctx), None)
+                .await;
+        });
+
+        ReceiverStream::new(rx)
+    }
+
+    /// Continues an interrupted run (aligns with LangGraph `invoke(Command(resume=...), config)`).
+    ///
+    /// Loads the latest checkpoint for `config.thread_id` (or `config.checkpoint_id`), merges
+    /// `command.update` into its state, and runs from the checkpoint's `pending_node`. The
+    /// graph's [`ResumeHandler`] (if any) then writes `command.resume` into the state; nodes
+    /// can also read it via [`RunContext::resume_value`]. The resumed node is not paused
+    /// again by its own `interrupt_before`; later interrupt points still apply.
+    ///
+    /// Fails with `ExecutionFailed` when the graph has no checkpointer, `thread_id` is missing,
+    /// or the thread has no interrupted checkpoint.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let graph = builder.with_interrupt_before(["act"]).compile_with_checkpointer(saver)?;
+    /// match graph.invoke(state, Some(config.clone())).await {
+    ///     Err(AgentError::Interrupted(_)) => {
+    ///         let out = graph.resume(Command::resume(json!({"approved": true})), config).await?;
+    ///     }
+    ///     other => { /* finished without pausing */ }
+    /// }
+    /// ```
+    pub async fn resume(
+        &self,
+        command: Command<S>,
+        config: RunnableConfig,
+    ) -> Result<S, AgentError> {
+        let checkpointer = self.checkpointer.as_ref().ok_or_else(|| {
+            AgentError::ExecutionFailed("resume requires a checkpointer".into())
+        })?;
+        if config.thread_id.is_none() {
+            return Err(AgentError::ExecutionFailed(
+                "resume requires config.thread_id".into(),
+            ));
+        }
+        let (checkpoint, _) = checkpointer
+            .get_tuple(&config)
+            .await
+            .map_err(|e| AgentError::ExecutionFailed(format!("load checkpoint: {}", e)))?
+            .ok_or_else(|| AgentError::ExecutionFailed("no checkpoint to resume".into()))?;
+        let cursor = CheckpointCursor::after(&checkpoint);
+        let mut current_id = checkpoint.pending_node.ok_or_else(|| {
+            AgentError::ExecutionFailed("nothing to resume: thread is not interrupted".into())
+        })?;
+        if !self.nodes.contains_key(&current_id) {
+            return Err(AgentError::ExecutionFailed(format!(
+                "pending node not in graph: {}",
+                current_id
+            )));
+        }
+
+        let mut state = checkpoint.channel_values;
+        if let Some(update) = command.update {
+            self.state_updater.apply_update(&mut state, &update);
+        }
+        if let (Some(handler), Some(value)) = (&self.resume_handler, &command.resume) {
+            handler.apply_resume(&mut state, &current_id, value)?;
+        }
+        let mut run_ctx = RunContext::new(config.clone());
+        run_ctx.store = self.store.clone();
+        run_ctx.resume = command.resume;
+        let config = Some(config);
+        self.run_loop_inner(
+            &mut state,
+            &config,
+            &mut current_id,
+            Some(&run_ctx),
+            Some(cursor),
+        )
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Resumes an interrupted thread with a human-provided value; shorthand for
+    /// `resume(Command::resume(value), config)`.
+    pub async fn resume_with_value(
+        &self,
+        config: RunnableConfig,
+        value: serde_json::Value,
+    ) -> Result<S, AgentError> {
+        self.resume(Command::resume(value), config).await
+    }
+
+    /// Node the thread is paused at (`Checkpoint::pending_node` of its latest checkpoint),
+    /// or None when it is not interrupted, has no checkpoint, or the graph has no checkpointer.
+    pub async fn pending_node(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<String>, AgentError> {
+        let Some(checkpointer) = self.checkpointer.as_ref() else {
+            return Ok(None);
+        };
+        if config.thread_id.is_none() {
+            return Ok(None);
+        }
+        let tuple = checkpointer
+            .get_tuple(config)
+            .await
+            .map_err(|e| AgentError::ExecutionFailed(format!("load checkpoint: {}", e)))?;
+        Ok(tuple.and_then(|(checkpoint, _)| checkpoint.pending_node))
+    }
+
+    /// Checkpoint chain of a thread, newest first: starts at the latest checkpoint (or
+    /// `config.checkpoint_id`) and follows `parent_checkpoint_id` back to the thread's first.
+    ///
+    /// Pass any returned checkpoint's id as `config.checkpoint_id` to `invoke` to re-run from
+    /// it. Returns an empty history when the graph has no checkpointer or `thread_id` is
+    /// missing.
+    pub async fn get_state_history(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<Checkpoint<S>>, AgentError> {
+        let Some(checkpointer) = self.checkpointer.as_ref() else {
+            return Ok(Vec::new());
+        };
+        if config.thread_id.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut history = Vec::new();
+        let mut cfg = config.clone();
+        loop {
+            let tuple = checkpointer
+                .get_tuple(&cfg)
+                .await
+                .map_err(|e| AgentError::ExecutionFailed(format!("load checkpoint: {}", e)))?;
+            let Some((checkpoint, metadata)) = tuple else {
+                break;
+            };
+            history.push(checkpoint);
+            match metadata.parent_checkpoint_id {
+                Some(parent) => cfg.checkpoint_id = Some(parent),
+                None => break,
+            }
+        }
+        Ok(history)
+    }
+
+    /// Patches the persisted state of a thread between runs (aligns with LangGraph
+    /// `update_state(config, values, as_node)`), e.g. to correct a tool result or inject a
+    /// human message before resuming.
+    ///
+    /// `values` is merged into the state of the latest checkpoint (or `config.checkpoint_id`)
+    /// with the graph's state updater, as if a node had returned it, and saved as a new
+    /// checkpoint with `CheckpointSource::Update` whose parent is the checkpoint it was based
+    /// on. With `as_node` the update counts as that node's output: the pending node becomes
+    /// the node routed to after it (None at END). Without it the pending node is kept, so a
+    /// paused thread stays paused at the same node. A thread with no checkpoint yet starts
+    /// from `values`.
+    ///
+    /// Returns `config` pointing at the new checkpoint; [`resume`](Self::resume) (or `invoke`
+    /// with that `checkpoint_id`) continues from it. Fails when the graph has no checkpointer,
+    /// `thread_id` is missing, `config.checkpoint_id` is not found, or `as_node` is not a node
+    /// of the graph.
+    pub async fn update_state(
+        &self,
+        config: &RunnableConfig,
+        values: S,
+        as_node: Option<&str>,
+    ) -> Result<RunnableConfig, AgentError> {
+        let checkpointer = self.checkpointer.as_ref().ok_or_else(|| {
+            AgentError::ExecutionFailed("update_state requires a checkpointer".into())
+        })?;
+        if config.thread_id.is_none() {
+            return Err(AgentError::ExecutionFailed(
+                "update_state requires config.thread_id".into(),
+            ));
+        }
+        if let Some(node) = as_node.filter(|node| !self.nodes.contains_key(*node)) {
+            return Err(AgentError::ExecutionFailed(format!(
+                "update_state: node not in graph: {}",
+                node
+            )));
+        }
+        let base = checkpointer
+            .get_tuple(config)
+            .await
+            .map_err(|e| AgentError::ExecutionFailed(format!("load checkpoint: {}", e)))?;
+        let (state, mut cursor, pending_node) = match base {
+            Some((checkpoint, _)) => {
+                let cursor = CheckpointCursor::after(&checkpoint);
+                let mut state = checkpoint.channel_values;
+                self.state_updater.apply_update(&mut state, &values);
+                (state, cursor, checkpoint.pending_node)
+            }
+            None => {
+                if let Some(ref checkpoint_id) = config.checkpoint_id {
+                    return Err(AgentError::ExecutionFailed(format!(
+                        "checkpoint not found: {}",
+                        checkpoint_id
+                    )));
+                }
+                (values, CheckpointCursor::default(), None)
+            }
+        };
+        let pending_node = match as_node {
+            Some(node) => self
+                .route_after(node, &state, Next::Continue)
+                .filter(|next| next != END),
+            None => pending_node,
+        };
+
+        let state = self.checkpoint_state(state);
+        let mut checkpoint = Checkpoint::from_state(state, CheckpointSource::Update, cursor.step);
+        checkpoint.metadata.parent_checkpoint_id = cursor.parent_id.take();
+        if let Some(node) = pending_node {
+            checkpoint = checkpoint.with_pending_node(node);
+        }
+        let checkpoint_id = checkpointer
+            .put(config, &checkpoint)
+            .await
+            .map_err(|e| AgentError::ExecutionFailed(format!("save checkpoint: {}", e)))?;
+        // Not part of a run, so nothing else flushes it.
+        checkpointer
+            .flush()
+            .await
+            .map_err(|e| AgentError::ExecutionFailed(format!("save checkpoint: {}", e)))?;
+        Ok(RunnableConfig {
+            checkpoint_id: Some(checkpoint_id),
+            ..config.clone()
+        })
+    }
+
+    /// Plugs `governor` into an already compiled graph, replacing the one set with
+    /// `StateGraph::with_governor`: it is asked before every node whether the run may go on.
+    /// `SkipToFinalAnswer` uses the node from `StateGraph::with_final_answer_node`, if any.
+    pub fn with_governor(mut self, governor: Arc<dyn RunGovernor<S>>) -> Self {
+        self.governor = Some(governor);
+        self
+    }
+
+    /// Sets which UI events checkpoints keep, like `StateGraph::with_ui_events_retention` at
+    /// build time. For runners that build and compile their graph internally.
+    pub(crate) fn set_ui_events_retention(&mut self, retention: UiEventsRetention) {
+        self.ui_events_retention = retention;
+    }
+
+    /// Sets the custom stream events nodes may emit, like `StateGraph::with_custom_events` at
+    /// build time. For runners that build and compile their graph internally.
+    pub(crate) fn set_custom_events(&mut self, registry: Option<Arc<CustomEventRegistry>>) {
+        self.custom_events = registry;
+    }
+
+    /// Sets the time limit of nodes without their own, like `StateGraph::with_node_timeout` at
+    /// build time. For runners that build and compile their graph internally.
+    pub(crate) fn set_node_timeout(&mut self, timeout: Option<Duration>) {
+        self.node_timeout = timeout;
+    }
+
+    /// Replaces the nodes before which the run pauses, like `StateGraph::with_interrupt_before`
+    /// at build time. For runners that build and compile their graph internally.
+    pub(crate) fn set_interrupt_before(
+        &mut self,
+        nodes: &[String],
+    ) -> Result<(), super::CompilationError> {
+        if let Some(missing) = nodes.iter().find(|id| !self.nodes.contains_key(*id)) {
+            return Err(super::CompilationError::InterruptNodeNotFound(missing.clone()));
+        }
+        self.interrupt_before = nodes.iter().cloned().collect();
+        Ok(())
+    }
+
+    /// Adds `middleware` around the current one (which then runs inside it), like passing both
+    /// to `StateGraph::with_middleware`. For runners that build and compile their graph
+    /// internally.
+    pub(crate) fn add_middleware(&mut self, middleware: Arc<dyn NodeMiddleware<S>>) {
+        self.middleware = Some(match self.middleware.take() {
+            Some(inner) => Arc::new(super::node_middleware::LayeredMiddleware::new(
+                middleware, inner,
+            )),
+            None => middleware,
+        });
+    }
+
+    /// Replaces the checkpointer, e.g. with a wrapper around the current one. For runners that
+    /// build and compile their graph internally.
+    pub(crate) fn set_checkpointer(&mut self, checkpointer: Option<Arc<dyn Checkpointer<S>>>) {
+        self.checkpointer = checkpointer;
+    }
+
     /// Returns the long-term store if the graph was compiled with `with_store(store)`.
     ///
     /// Nodes can use it for cross-thread memory (e.g. namespace from `config.user_id`). See docs/rust-langgraph/16-memory-design.md §5.
@@ -487,6 +1394,20 @@ mod tests {
             state_updater: Arc::new(crate::channels::ReplaceUpdater),
             retry_policy: RetryPolicy::None,
             interrupt_handler: None,
+            interrupt_before: HashSet::new(),
+            interrupt_after: HashSet::new(),
+            resume_handler: None,
+            messages_accessor: None,
+            ui_events_accessor: None,
+            ui_events_retention: UiEventsRetention::default(),
+            node_timeout: None,
+            node_timeouts: HashMap::new(),
+            node_retry_policies: HashMap::new(),
+            governor: None,
+            final_answer_node: None,
+            injection_points: HashMap::new(),
+            callbacks: Callbacks::default(),
+            custom_events: None,
         };
         let state = crate::state::ReActState::default();
         let result = graph.invoke(state, None).await;
@@ -657,6 +1578,7 @@ mod tests {
             checkpoint_id: None,
             checkpoint_ns: String::new(),
             user_id: None,
+            ..Default::default()
         };
         let out = compiled.invoke(0, Some(config)).await.unwrap();
         assert_eq!(out, 3);
@@ -772,6 +1694,115 @@ mod tests {
         assert_eq!(ids, vec!["first".to_string(), "second".to_string()]);
     }
 
+    #[derive(Clone, Debug)]
+    struct ChatState {
+        messages: Vec<crate::Message>,
+    }
+
+    /// Node that edits the message list with `edit` (append, remove, or nothing).
+    struct ChatNode {
+        id: &'static str,
+        edit: fn(&mut Vec<crate::Message>),
+    }
+
+    #[async_trait]
+    impl Node<ChatState> for ChatNode {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        async fn run(&self, mut state: ChatState) -> Result<(ChatState, Next), AgentError> {
+            (self.edit)(&mut state.messages);
+            Ok((state, Next::Continue))
+        }
+    }
+
+    fn chat_messages(state: &ChatState) -> &[crate::Message] {
+        &state.messages
+    }
+
+    /// **Scenario**: stream(messages delta) emits only the messages each node appended, skips
+    /// nodes that leave the list unchanged, and lowers `base` when a node removes messages.
+    #[tokio::test]
+    async fn stream_messages_delta_emits_appended_messages_per_node() {
+        let mut graph =
+            StateGraph::<ChatState>::new().with_messages_accessor(Arc::new(chat_messages));
+        graph
+            .add_node(
+                "reply",
+                Arc::new(ChatNode {
+                    id: "reply",
+                    edit: |m| {
+                        m.push(crate::Message::assistant("hi"));
+                        m.push(crate::Message::assistant("there"));
+                    },
+                }),
+            )
+            .add_node(
+                "noop",
+                Arc::new(ChatNode {
+                    id: "noop",
+                    edit: |_| {},
+                }),
+            )
+            .add_node(
+                "trim",
+                Arc::new(ChatNode {
+                    id: "trim",
+                    edit: |m| m.truncate(1),
+                }),
+            )
+            .add_edge(START, "reply")
+            .add_edge("reply", "noop")
+            .add_edge("noop", "trim")
+            .add_edge("trim", END);
+        let graph = graph.compile().unwrap();
+
+        let input = ChatState {
+            messages: vec![crate::Message::user("hello")],
+        };
+        let stream = graph.stream(input, None, HashSet::from_iter([StreamMode::MessagesDelta]));
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 2, "noop appends nothing: {:?}", events);
+        match &events[0] {
+            StreamEvent::MessagesDelta {
+                node_id,
+                base,
+                messages,
+            } => {
+                assert_eq!(node_id, "reply");
+                assert_eq!(*base, 1);
+                assert_eq!(messages.len(), 2);
+                assert!(matches!(
+                    &messages[1],
+                    crate::Message::Assistant { content, .. } if content == "there"
+                ));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        match &events[1] {
+            StreamEvent::MessagesDelta {
+                node_id,
+                base,
+                messages,
+            } => {
+                assert_eq!(node_id, "trim");
+                assert_eq!(*base, 1);
+                assert!(messages.is_empty());
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    /// **Scenario**: Without a messages accessor, MessagesDelta mode emits nothing.
+    #[tokio::test]
+    async fn stream_messages_delta_without_accessor_emits_nothing() {
+        let graph = build_two_step_graph();
+        let stream = graph.stream(0, None, HashSet::from_iter([StreamMode::MessagesDelta]));
+        let events: Vec<_> = stream.collect().await;
+        assert!(events.is_empty(), "{:?}", events);
+    }
+
     /// **Scenario**: Empty graph stream() does not panic and yields zero events.
     #[tokio::test]
     async fn stream_empty_graph_no_panic_zero_events() {
@@ -786,6 +1817,20 @@ mod tests {
             state_updater: Arc::new(crate::channels::ReplaceUpdater),
             retry_policy: RetryPolicy::None,
             interrupt_handler: None,
+            interrupt_before: HashSet::new(),
+            interrupt_after: HashSet::new(),
+            resume_handler: None,
+            messages_accessor: None,
+            ui_events_accessor: None,
+            ui_events_retention: UiEventsRetention::default(),
+            node_timeout: None,
+            node_timeouts: HashMap::new(),
+            node_retry_policies: HashMap::new(),
+            governor: None,
+            final_answer_node: None,
+            injection_points: HashMap::new(),
+            callbacks: Callbacks::default(),
+            custom_events: None,
         };
         let stream = graph.stream(0, None, HashSet::from_iter([StreamMode::Values]));
         let events: Vec<_> = stream.collect().await;
@@ -872,6 +1917,7 @@ mod tests {
             checkpoint_id: None,
             checkpoint_ns: String::new(),
             user_id: Some("u1".into()),
+            ..Default::default()
         };
         let stream = graph.stream(0, Some(config), HashSet::from_iter([StreamMode::Values]));
         let events: Vec<_> = stream.collect().await;
@@ -903,9 +1949,10 @@ mod tests {
                 | StreamEvent::Checkpoint(_)
                 | StreamEvent::TaskStart { .. }
                 | StreamEvent::TaskEnd { .. }
-                | StreamEvent::Usage { .. } => {
+                | StreamEvent::Usage { .. }
+                | StreamEvent::ResponseMetadata(_) => {
                     panic!(
-                        "run_loop does not emit Messages/Custom/Checkpoint/Task/Usage events in this test, got {:?}",
+                        "run_loop does not emit Messages/Custom/Checkpoint/Task/Usage/ResponseMetadata events in this test, got {:?}",
                         e
                     )
                 }
@@ -976,6 +2023,213 @@ mod tests {
         assert_eq!(result, 3);
     }
 
+    // === Batch Tests ===
+
+    /// Node that sleeps longer for smaller states (so later inputs finish first), records the
+    /// peak number of concurrent runs, and fails on negative states.
+    struct SlowNode {
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Node<i32> for SlowNode {
+        fn id(&self) -> &str {
+            "slow"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            let millis = 5 * (10 - state.clamp(0, 10)) as u64;
+            tokio::time::sleep(std::time::Duration::from_millis(millis)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if state < 0 {
+                return Err(AgentError::ExecutionFailed(format!("negative {}", state)));
+            }
+            Ok((state * 10, Next::Continue))
+        }
+    }
+
+    fn build_slow_graph() -> (CompiledStateGraph<i32>, Arc<std::sync::atomic::AtomicUsize>) {
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut graph = StateGraph::<i32>::new();
+        graph
+            .add_node(
+                "slow",
+                Arc::new(SlowNode {
+                    in_flight: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+                    peak: peak.clone(),
+                }),
+            )
+            .add_edge(START, "slow")
+            .add_edge("slow", END);
+        (graph.compile().expect("graph compiles"), peak)
+    }
+
+    /// **Scenario**: batch returns results in input order even when later inputs finish first,
+    /// and never runs more than `concurrency` invocations at once.
+    #[tokio::test]
+    async fn batch_returns_results_in_order_within_concurrency_limit() {
+        let (graph, peak) = build_slow_graph();
+        let inputs: Vec<(i32, Option<RunnableConfig>)> = (0..10).map(|i| (i, None)).collect();
+
+        let results = graph.batch(inputs, 3).await;
+
+        let values: Vec<i32> = results.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(values, (0..10).map(|i| i * 10).collect::<Vec<_>>());
+        let peak = peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(peak <= 3, "peak concurrency {} exceeds limit", peak);
+    }
+
+    /// **Scenario**: A failing input yields an Err in its slot without affecting the others;
+    /// concurrency 0 is treated as 1; an empty batch returns no results.
+    #[tokio::test]
+    async fn batch_keeps_per_input_errors_in_place() {
+        let (graph, peak) = build_slow_graph();
+        let config = RunnableConfig {
+            thread_id: Some("batch-1".into()),
+            ..Default::default()
+        };
+        let inputs = vec![(1, None), (-1, Some(config)), (2, None)];
+
+        let results = graph.batch(inputs, 0).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &10);
+        match &results[1] {
+            Err(AgentError::ExecutionFailed(msg)) => assert!(msg.contains("negative -1")),
+            other => panic!("expected ExecutionFailed, got {:?}", other),
+        }
+        assert_eq!(results[2].as_ref().unwrap(), &20);
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(graph.batch(Vec::new(), 4).await.is_empty());
+    }
+
+    // === Timeout and Cancellation Tests ===
+
+    /// Node that sleeps for `millis` and then adds 100.
+    struct SleepNode {
+        id: &'static str,
+        millis: u64,
+    }
+
+    #[async_trait]
+    impl Node<i32> for SleepNode {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            tokio::time::sleep(std::time::Duration::from_millis(self.millis)).await;
+            Ok((state + 100, Next::Continue))
+        }
+    }
+
+    /// **Scenario**: with_node_timeout aborts a node that runs too long with NodeTimeout;
+    /// add_node_with_timeout gives one node a longer limit.
+    #[tokio::test]
+    async fn node_timeout_aborts_slow_node_unless_overridden() {
+        let mut graph =
+            StateGraph::<i32>::new().with_node_timeout(std::time::Duration::from_millis(20));
+        graph
+            .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+            .add_node(
+                "slow",
+                Arc::new(SleepNode {
+                    id: "slow",
+                    millis: 500,
+                }),
+            )
+            .add_edge(START, "a")
+            .add_edge("a", "slow")
+            .add_edge("slow", END);
+        let compiled = graph.compile().expect("graph compiles");
+        match compiled.invoke(0, None).await {
+            Err(AgentError::NodeTimeout { node_id, timeout }) => {
+                assert_eq!(node_id, "slow");
+                assert_eq!(timeout, std::time::Duration::from_millis(20));
+            }
+            other => panic!("expected NodeTimeout, got {:?}", other),
+        }
+
+        let mut graph =
+            StateGraph::<i32>::new().with_node_timeout(std::time::Duration::from_millis(20));
+        graph
+            .add_node_with_timeout(
+                "slow",
+                Arc::new(SleepNode {
+                    id: "slow",
+                    millis: 50,
+                }),
+                std::time::Duration::from_secs(5),
+            )
+            .add_edge(START, "slow")
+            .add_edge("slow", END);
+        let compiled = graph.compile().expect("graph compiles");
+        assert_eq!(compiled.invoke(0, None).await.unwrap(), 100);
+    }
+
+    /// **Scenario**: Cancelling the token aborts the node in progress with Cancelled; the
+    /// checkpoint of the finished step is kept with the aborted node pending.
+    #[tokio::test]
+    async fn cancellation_aborts_run_and_keeps_finished_steps() {
+        let saver = Arc::new(MemorySaver::<i32>::new());
+        let mut graph = StateGraph::<i32>::new();
+        graph
+            .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+            .add_node(
+                "slow",
+                Arc::new(SleepNode {
+                    id: "slow",
+                    millis: 10_000,
+                }),
+            )
+            .add_edge(START, "a")
+            .add_edge("a", "slow")
+            .add_edge("slow", END);
+        let compiled = graph.compile_with_checkpointer(saver).expect("compiles");
+        let config = RunnableConfig {
+            thread_id: Some("cancel-1".into()),
+            ..Default::default()
+        };
+        let token = tokio_util::sync::CancellationToken::new();
+        let ctx =
+            crate::graph::RunContext::<i32>::new(config.clone()).with_cancellation(token.clone());
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+            canceller.cancel();
+        });
+
+        let started = std::time::Instant::now();
+        let result = compiled.invoke_with_context(0, ctx).await;
+        assert!(matches!(result, Err(AgentError::Cancelled)), "{:?}", result);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let history = compiled.get_state_history(&config).await.unwrap();
+        assert_eq!(history[0].channel_values, 1);
+        assert_eq!(history[0].pending_node.as_deref(), Some("slow"));
+    }
+
+    /// **Scenario**: stream_with_context with an already cancelled token runs no node and
+    /// emits no state.
+    #[tokio::test]
+    async fn stream_with_context_cancelled_token_emits_no_state() {
+        let graph = build_two_step_graph();
+        let token = tokio_util::sync::CancellationToken::new();
+        token.cancel();
+        let ctx = crate::graph::RunContext::<i32>::new(RunnableConfig::default())
+            .with_cancellation(token);
+
+        let events: Vec<_> = graph
+            .stream_with_context(0, ctx, HashSet::from_iter([StreamMode::Values]))
+            .collect()
+            .await;
+        assert!(events.is_empty(), "{:?}", events.len());
+    }
+
     // === StateUpdater Integration Tests ===
 
     #[derive(Clone, Debug, PartialEq)]
@@ -1216,6 +2470,201 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// **Scenario**: A per-node policy retries a flaky node under a graph without retries, and
+    /// RetryPolicy::None on a node opts it out of the graph's policy.
+    #[tokio::test]
+    async fn add_node_with_retry_overrides_graph_policy() {
+        let flaky_calls = Arc::new(AtomicUsize::new(0));
+        let mut graph = StateGraph::<i32>::new();
+        graph
+            .add_node_with_retry(
+                "flaky",
+                Arc::new(FailingNode {
+                    id: "flaky",
+                    fail_count: flaky_calls.clone(),
+                    max_failures: 2,
+                }),
+                RetryPolicy::exponential(
+                    3,
+                    std::time::Duration::from_millis(1),
+                    std::time::Duration::from_millis(5),
+                    2.0,
+                )
+                .with_jitter(0.5),
+            )
+            .add_edge(START, "flaky")
+            .add_edge("flaky", END);
+        let result = graph.compile().unwrap().invoke(0, None).await.unwrap();
+        assert_eq!(result, 10);
+        assert_eq!(flaky_calls.load(Ordering::SeqCst), 3);
+
+        let strict_calls = Arc::new(AtomicUsize::new(0));
+        let mut graph = StateGraph::<i32>::new()
+            .with_retry_policy(RetryPolicy::fixed(3, std::time::Duration::from_millis(1)));
+        graph
+            .add_node_with_retry(
+                "strict",
+                Arc::new(FailingNode {
+                    id: "strict",
+                    fail_count: strict_calls.clone(),
+                    max_failures: 1,
+                }),
+                RetryPolicy::None,
+            )
+            .add_edge(START, "strict")
+            .add_edge("strict", END);
+        assert!(graph.compile().unwrap().invoke(0, None).await.is_err());
+        assert_eq!(strict_calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// Node that adds 1 and reports 100 tokens of LLM usage per run.
+    struct UsageNode;
+
+    #[async_trait]
+    impl Node<i32> for UsageNode {
+        fn id(&self) -> &str {
+            "work"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            Ok((state + 1, Next::Continue))
+        }
+
+        async fn run_with_context(
+            &self,
+            state: i32,
+            ctx: &RunContext<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            ctx.record_usage(&crate::llm::LlmUsage {
+                prompt_tokens: 80,
+                completion_tokens: 20,
+                total_tokens: 100,
+            });
+            self.run(state).await
+        }
+    }
+
+    /// Node that adds 1000 when it runs as the last step (`IsLastStep`), else 1.
+    struct AnswerNode;
+
+    #[async_trait]
+    impl Node<i32> for AnswerNode {
+        fn id(&self) -> &str {
+            "answer"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            Ok((state + 1, Next::Continue))
+        }
+
+        async fn run_with_context(
+            &self,
+            state: i32,
+            ctx: &RunContext<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            let last = ctx.get_managed_value(IS_LAST_STEP) == Some(serde_json::json!(true));
+            Ok((state + if last { 1000 } else { 1 }, Next::Continue))
+        }
+    }
+
+    /// Graph looping on "work" forever; "answer" is only reachable through the governor.
+    fn governed_graph(
+        governor: crate::graph::BudgetGovernor,
+        final_answer: bool,
+    ) -> CompiledStateGraph<i32> {
+        let mut graph = StateGraph::<i32>::new().with_governor(Arc::new(governor));
+        if final_answer {
+            graph = graph.with_final_answer_node("answer");
+        }
+        graph
+            .add_node("work", Arc::new(UsageNode))
+            .add_node("answer", Arc::new(AnswerNode))
+            .add_edge(START, "work")
+            .add_conditional_edges("work", Arc::new(|_: &i32| "work".to_string()), None)
+            .add_edge("answer", END);
+        graph.compile().unwrap()
+    }
+
+    /// **Scenario**: Once the token budget is used up, the governor wraps the run up: the final
+    /// answer node runs once with IsLastStep and the run ends; without one the run just ends;
+    /// with abort_on_exceeded the run fails with AgentError::Aborted.
+    #[tokio::test]
+    async fn governor_stops_run_at_token_budget() {
+        let budget = || crate::graph::BudgetGovernor::new().with_max_tokens(300);
+
+        let graph = governed_graph(budget(), true);
+        assert_eq!(graph.invoke(0, None).await.unwrap(), 1003);
+
+        let graph = governed_graph(budget(), false);
+        assert_eq!(graph.invoke(0, None).await.unwrap(), 3);
+
+        let result = governed_graph(budget().abort_on_exceeded(), true)
+            .invoke(0, None)
+            .await;
+        match result {
+            Err(AgentError::Aborted(reason)) => {
+                assert_eq!(reason, "token budget of 300 exceeded")
+            }
+            other => panic!("expected Aborted, got {:?}", other),
+        }
+    }
+
+    /// Node that always fails with the given LLM error.
+    struct LlmFailingNode {
+        calls: Arc<AtomicUsize>,
+        error: crate::llm::LlmError,
+    }
+
+    #[async_trait]
+    impl Node<i32> for LlmFailingNode {
+        fn id(&self) -> &str {
+            "llm"
+        }
+
+        async fn run(&self, _state: i32) -> Result<(i32, Next), AgentError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(self.error.clone().into())
+        }
+    }
+
+    fn llm_failing_graph(
+        calls: Arc<AtomicUsize>,
+        error: crate::llm::LlmError,
+    ) -> CompiledStateGraph<i32> {
+        let mut graph = StateGraph::<i32>::new()
+            .with_retry_policy(RetryPolicy::fixed(2, std::time::Duration::from_millis(1)));
+        graph.add_node("llm", Arc::new(LlmFailingNode { calls, error }));
+        graph.add_edge(START, "llm");
+        graph.add_edge("llm", END);
+        graph.compile().expect("graph compiles")
+    }
+
+    /// **Scenario**: A permanent LLM error (quota) is not retried; a rate limit is retried after
+    /// its Retry-After hint.
+    #[tokio::test]
+    async fn invoke_retry_respects_llm_error_kind_and_retry_after() {
+        use crate::llm::{LlmError, LlmErrorKind};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let quota = LlmError::new(LlmErrorKind::QuotaExceeded, "no credits");
+        let result = llm_failing_graph(calls.clone(), quota)
+            .invoke(0, None)
+            .await;
+        assert!(matches!(result, Err(AgentError::Llm(_))), "{:?}", result);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let limited = LlmError::new(LlmErrorKind::RateLimited, "slow down")
+            .with_retry_after(std::time::Duration::from_millis(30));
+        let started = std::time::Instant::now();
+        let result = llm_failing_graph(calls.clone(), limited)
+            .invoke(0, None)
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(started.elapsed() >= std::time::Duration::from_millis(60));
+    }
+
     // === Checkpoints Streaming Tests ===
 
     /// **Scenario**: stream() emits checkpoint events when Checkpoints mode is enabled and checkpointer is present.
@@ -1735,4 +3184,146 @@ mod tests {
             serde_json::json!({"action": "approve", "item": "order_123"})
         );
     }
+
+    /// Node that logs one event while it runs.
+    struct LoggingNode;
+
+    #[async_trait]
+    impl Node<i32> for LoggingNode {
+        fn id(&self) -> &str {
+            "log"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            tracing::info!("inside node");
+            Ok((state, Next::Continue))
+        }
+    }
+
+    /// Collects formatted log output.
+    #[derive(Clone, Default)]
+    struct SharedLog(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedLog {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// **Scenario**: Events logged inside a node carry the run span (run_id, thread_id, user_id)
+    /// and the node span (node_id) without the node adding them.
+    #[tokio::test]
+    async fn node_logs_carry_run_and_node_span_fields() {
+        let log = SharedLog::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut graph = StateGraph::<i32>::new();
+        graph.add_node("log", Arc::new(LoggingNode));
+        graph.add_edge(START, "log");
+        graph.add_edge("log", END);
+        let compiled = graph.compile().expect("graph compiles");
+        let ctx = RunContext::new(RunnableConfig {
+            thread_id: Some("thread-7".into()),
+            user_id: Some("user-42".into()),
+            ..Default::default()
+        })
+        .with_run_id("run-1");
+        compiled.invoke_with_context(0, ctx).await.unwrap();
+
+        let output = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        let line = output
+            .lines()
+            .find(|l| l.contains("inside node"))
+            .expect("node event logged");
+        let fields = [
+            "run_id",
+            "run-1",
+            "thread_id",
+            "thread-7",
+            "user_id",
+            "user-42",
+        ];
+        for field in fields {
+            assert!(line.contains(field), "{} missing in {}", field, line);
+        }
+        assert!(line.contains("node{node_id"), "{}", line);
+    }
+
+    /// **Scenario**: A streamed run executes on its own task yet stays under the context's
+    /// parent span, so its events belong to the caller's trace.
+    #[tokio::test]
+    async fn streamed_run_span_is_child_of_parent_span() {
+        let log = SharedLog::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut graph = StateGraph::<i32>::new();
+        graph.add_node("log", Arc::new(LoggingNode));
+        graph.add_edge(START, "log");
+        graph.add_edge("log", END);
+        let compiled = graph.compile().expect("graph compiles");
+        let parent = tracing::info_span!("request", request_id = "req-9");
+        let ctx = RunContext::new(RunnableConfig::default())
+            .with_run_id("run-2")
+            .with_parent_span(parent);
+        let events: Vec<_> = compiled
+            .stream_with_context(0, ctx, [StreamMode::Values])
+            .collect()
+            .await;
+        assert!(!events.is_empty());
+
+        let output = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        let line = output
+            .lines()
+            .find(|l| l.contains("inside node"))
+            .expect("node event logged");
+        let request = line.find("request{request_id").expect("request span");
+        let run = line.find("run{run_id").expect("run span");
+        let node = line.find("node{node_id").expect("node span");
+        assert!(request < run && run < node, "{}", line);
+        assert!(line.contains("req-9") && line.contains("run-2"), "{}", line);
+    }
+
+    /// **Scenario**: An injected update is merged after the injection point and the run goes
+    /// on at its target, although the point routed to END; the injector is closed when the run
+    /// ends with nothing queued. Without an injector the point changes nothing.
+    #[tokio::test]
+    async fn injected_update_merges_after_injection_point_and_continues() {
+        let mut graph = StateGraph::<i32>::new();
+        for (id, delta) in [("first", 1), ("second", 10)] {
+            graph.add_node(id, Arc::new(AddNode { id, delta }));
+        }
+        graph.add_edge(START, "first");
+        graph.add_edge("first", "second");
+        graph.add_edge("second", END);
+        let compiled = graph
+            .with_injection_point("second", "first")
+            .compile()
+            .expect("graph compiles");
+
+        let injector = crate::graph::Injector::new();
+        injector.push(|s: &mut i32| *s *= 100).unwrap();
+        let ctx = RunContext::new(RunnableConfig::default()).with_injector(injector.clone());
+        let out = compiled.invoke_with_context(0, ctx).await.unwrap();
+        // 0 +1 +10 = 11, *100 = 1100, then first and second again.
+        assert_eq!(out, 1111);
+        assert!(injector.is_closed());
+
+        let out = compiled.invoke(0, None).await.unwrap();
+        assert_eq!(out, 11);
+    }
 }
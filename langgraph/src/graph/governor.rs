@@ -0,0 +1,282 @@
+//! Run governor: one place for run policies (time, step, token and cost budgets).
+//!
+//! Before each node the run loop asks the graph's [`RunGovernor`] whether to go on. It sees the
+//! state and a [`RunProgress`] (node about to run, steps so far, elapsed time, token usage) and
+//! answers with a [`GovernorDecision`]:
+//!
+//! - **Continue**: run the node.
+//! - **SkipToFinalAnswer**: run the graph's final answer node (`StateGraph::with_final_answer_node`)
+//!   once with `IsLastStep` set, then end the run; without one the run ends with the current state.
+//! - **Abort**: fail the run with `AgentError::Aborted` carrying the reason.
+//! - **BudgetExceeded**: fail the run with `AgentError::BudgetExceeded` carrying the limit hit,
+//!   so callers can tell a spent budget from other aborts.
+//!
+//! Token usage is what nodes report through `RunContext::record_usage` (ThinkNode reports every
+//! LLM call). [`BudgetGovernor`] covers the common limits; implement the trait for anything else,
+//! e.g. a per-tenant quota service.
+//!
+//! **Interaction**: Set with `StateGraph::with_governor` or `CompiledStateGraph::with_governor`;
+//! consulted in `CompiledStateGraph::run_loop_inner`.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::llm::LlmUsage;
+
+/// What the run loop does instead of (or before) the next node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GovernorDecision {
+    /// Run the node.
+    Continue,
+    /// Wrap up: run the final answer node as the last step, or end the run when none is set.
+    SkipToFinalAnswer,
+    /// Stop the run with `AgentError::Aborted(reason)`.
+    Abort(String),
+    /// Stop the run with `AgentError::BudgetExceeded(reason)`.
+    BudgetExceeded(String),
+}
+
+/// Where a run stands when the governor is asked about its next node.
+#[derive(Debug, Clone, Default)]
+pub struct RunProgress {
+    /// Node about to run.
+    pub node_id: String,
+    /// Nodes run so far in this invocation (a ReAct think → act → observe round is three).
+    pub steps: usize,
+    /// Time since this invocation started.
+    pub elapsed: Duration,
+    /// Tokens reported by nodes so far in this invocation.
+    pub usage: LlmUsage,
+}
+
+impl RunProgress {
+    /// Cost of the tokens used so far at `pricing`.
+    pub fn cost(&self, pricing: &TokenPricing) -> f64 {
+        pricing.cost(&self.usage)
+    }
+}
+
+/// Price of tokens, per million, in any currency.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenPricing {
+    /// Price of one million prompt (input) tokens.
+    pub prompt_per_million: f64,
+    /// Price of one million completion (output) tokens.
+    pub completion_per_million: f64,
+}
+
+impl TokenPricing {
+    /// Pricing with the given prices per million prompt and completion tokens.
+    pub fn new(prompt_per_million: f64, completion_per_million: f64) -> Self {
+        Self {
+            prompt_per_million,
+            completion_per_million,
+        }
+    }
+
+    /// Cost of `usage`.
+    pub fn cost(&self, usage: &LlmUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt_per_million
+            + usage.completion_tokens as f64 * self.completion_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Decides before each node whether a run goes on; see the [module docs](self).
+#[async_trait]
+pub trait RunGovernor<S>: Send + Sync
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    /// Called before `progress.node_id` runs, with the current state.
+    async fn decide(&self, state: &S, progress: &RunProgress) -> GovernorDecision;
+}
+
+/// Token usage of one run, shared by the clones of its `RunContext`.
+///
+/// Nodes add each LLM call with [`record`](Self::record) (usually via
+/// `RunContext::record_usage`); the run loop reads the [`total`](Self::total) for the governor.
+#[derive(Debug, Clone, Default)]
+pub struct UsageMeter(Arc<Mutex<LlmUsage>>);
+
+impl UsageMeter {
+    /// Adds one LLM call's usage.
+    pub fn record(&self, usage: &LlmUsage) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).add(usage);
+    }
+
+    /// Usage recorded so far.
+    pub fn total(&self) -> LlmUsage {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Governor enforcing fixed limits on elapsed time, steps, tokens and cost.
+///
+/// When a limit is reached the run wraps up with [`GovernorDecision::SkipToFinalAnswer`], or
+/// fails with [`GovernorDecision::Abort`] after [`abort_on_exceeded`](Self::abort_on_exceeded).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::graph::{BudgetGovernor, StateGraph, TokenPricing};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let governor = BudgetGovernor::new()
+///     .with_max_elapsed(Duration::from_secs(120))
+///     .with_max_tokens(50_000)
+///     .with_max_cost(0.50, TokenPricing::new(2.5, 10.0));
+/// let graph = StateGraph::<String>::new().with_governor(Arc::new(governor));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BudgetGovernor {
+    max_elapsed: Option<Duration>,
+    max_steps: Option<usize>,
+    max_tokens: Option<u32>,
+    max_cost: Option<(f64, TokenPricing)>,
+    abort: bool,
+}
+
+impl BudgetGovernor {
+    /// Governor without limits; add them with the `with_max_*` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps up once the run has taken `max` time.
+    pub fn with_max_elapsed(mut self, max: Duration) -> Self {
+        self.max_elapsed = Some(max);
+        self
+    }
+
+    /// Wraps up once `max` nodes have run.
+    pub fn with_max_steps(mut self, max: usize) -> Self {
+        self.max_steps = Some(max);
+        self
+    }
+
+    /// Wraps up once `max` total tokens have been used.
+    pub fn with_max_tokens(mut self, max: u32) -> Self {
+        self.max_tokens = Some(max);
+        self
+    }
+
+    /// Wraps up once the tokens used cost `max` at `pricing`.
+    pub fn with_max_cost(mut self, max: f64, pricing: TokenPricing) -> Self {
+        self.max_cost = Some((max, pricing));
+        self
+    }
+
+    /// Fails the run instead of wrapping up when a limit is reached.
+    pub fn abort_on_exceeded(mut self) -> Self {
+        self.abort = true;
+        self
+    }
+
+    /// The first limit `progress` has reached, as a reason.
+    fn exceeded(&self, progress: &RunProgress) -> Option<String> {
+        if let Some(max) = self.max_elapsed {
+            if progress.elapsed >= max {
+                return Some(format!("time budget of {:?} exceeded", max));
+            }
+        }
+        if let Some(max) = self.max_steps {
+            if progress.steps >= max {
+                return Some(format!("step budget of {} exceeded", max));
+            }
+        }
+        if let Some(max) = self.max_tokens {
+            if progress.usage.total_tokens >= max {
+                return Some(format!("token budget of {} exceeded", max));
+            }
+        }
+        if let Some((max, pricing)) = &self.max_cost {
+            if progress.cost(pricing) >= *max {
+                return Some(format!("cost budget of {} exceeded", max));
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl<S> RunGovernor<S> for BudgetGovernor
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn decide(&self, _state: &S, progress: &RunProgress) -> GovernorDecision {
+        match self.exceeded(progress) {
+            None => GovernorDecision::Continue,
+            Some(reason) if self.abort => GovernorDecision::Abort(reason),
+            Some(_) => GovernorDecision::SkipToFinalAnswer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt: u32, completion: u32) -> LlmUsage {
+        LlmUsage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+        }
+    }
+
+    /// **Scenario**: The meter sums usage across clones; pricing turns it into a cost.
+    #[test]
+    fn usage_meter_sums_and_prices_tokens() {
+        let meter = UsageMeter::default();
+        meter.clone().record(&usage(1_000, 200));
+        meter.record(&usage(500, 300));
+        let total = meter.total();
+        assert_eq!(total.prompt_tokens, 1_500);
+        assert_eq!(total.completion_tokens, 500);
+        assert_eq!(total.total_tokens, 2_000);
+
+        let cost = TokenPricing::new(2.0, 10.0).cost(&total);
+        assert!((cost - 0.008).abs() < 1e-12, "{}", cost);
+    }
+
+    /// **Scenario**: BudgetGovernor continues under its limits, wraps up at the first limit
+    /// reached, and aborts with the reason when configured to.
+    #[tokio::test]
+    async fn budget_governor_wraps_up_or_aborts_at_limits() {
+        async fn decide(governor: &BudgetGovernor, progress: &RunProgress) -> GovernorDecision {
+            RunGovernor::<()>::decide(governor, &(), progress).await
+        }
+
+        let governor = BudgetGovernor::new()
+            .with_max_steps(3)
+            .with_max_tokens(1_000);
+        let mut progress = RunProgress {
+            node_id: "think".into(),
+            steps: 2,
+            usage: usage(400, 100),
+            ..Default::default()
+        };
+        assert_eq!(
+            decide(&governor, &progress).await,
+            GovernorDecision::Continue
+        );
+
+        progress.usage = usage(900, 100);
+        assert_eq!(
+            decide(&governor, &progress).await,
+            GovernorDecision::SkipToFinalAnswer
+        );
+
+        progress.usage = usage(0, 0);
+        progress.steps = 3;
+        assert_eq!(
+            decide(&governor.abort_on_exceeded(), &progress).await,
+            GovernorDecision::Abort("step budget of 3 exceeded".into())
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Soft interrupts: state updates injected into a running graph.
+//!
+//! A caller steers a run in progress (e.g. a follow-up user message: "actually, search in
+//! French") by pushing updates onto the run's [`Injector`]. The run is not stopped: after the
+//! next node that is an injection point (`StateGraph::with_injection_point`) finishes, the run
+//! loop merges every queued update into the state and continues at that point's target node
+//! instead of the routed one, also when the run would otherwise have ended. Updates queued
+//! after the run ended are refused ([`InjectError::RunFinished`]).
+//!
+//! Injection points are where an update leaves the state consistent; for ReAct that is after
+//! `observe`, so a user message never lands between a tool call and its result.
+//!
+//! **Interaction**: Set with `RunContext::with_injector`; drained in
+//! `CompiledStateGraph::run_loop_inner`. `ReactRunner::stream_with_injector` and
+//! langgraph-server's POST /v1/runs/{run_id}/messages use it.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// An update merged into the state between two nodes.
+pub type StateInjection<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+/// Error pushing onto an [`Injector`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InjectError {
+    /// The run ended; nothing more is merged into its state.
+    #[error("run already finished")]
+    RunFinished,
+}
+
+struct Queue<S> {
+    updates: Vec<StateInjection<S>>,
+    closed: bool,
+}
+
+/// Queue of updates for one run; clones share the queue.
+pub struct Injector<S> {
+    queue: Arc<Mutex<Queue<S>>>,
+}
+
+impl<S> Clone for Injector<S> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: Arc::clone(&self.queue),
+        }
+    }
+}
+
+impl<S> Default for Injector<S> {
+    fn default() -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Queue {
+                updates: Vec::new(),
+                closed: false,
+            })),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Injector<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let queue = self.lock();
+        f.debug_struct("Injector")
+            .field("pending", &queue.updates.len())
+            .field("closed", &queue.closed)
+            .finish()
+    }
+}
+
+impl<S> Injector<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Queue<S>> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues `update` for the run's next injection point.
+    pub fn push(&self, update: impl FnOnce(&mut S) + Send + 'static) -> Result<(), InjectError> {
+        let mut queue = self.lock();
+        if queue.closed {
+            return Err(InjectError::RunFinished);
+        }
+        queue.updates.push(Box::new(update));
+        Ok(())
+    }
+
+    /// Number of updates not merged yet.
+    pub fn pending(&self) -> usize {
+        self.lock().updates.len()
+    }
+
+    /// Whether the run ended (see [`close`](Self::close)).
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Refuses further updates and drops the pending ones; returns how many were dropped.
+    /// Called when the run ends.
+    pub fn close(&self) -> usize {
+        let mut queue = self.lock();
+        queue.closed = true;
+        std::mem::take(&mut queue.updates).len()
+    }
+
+    /// Takes the pending updates. With `close_if_empty`, an empty queue is closed in the same
+    /// step, so no update slips in between the run's last check and its end.
+    pub(crate) fn take(&self, close_if_empty: bool) -> Vec<StateInjection<S>> {
+        let mut queue = self.lock();
+        if queue.updates.is_empty() {
+            queue.closed |= close_if_empty;
+        }
+        std::mem::take(&mut queue.updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Updates are taken in push order; once closed, pushes are refused.
+    #[test]
+    fn take_in_order_then_close() {
+        let injector = Injector::<Vec<u32>>::new();
+        injector.push(|s| s.push(1)).unwrap();
+        injector.clone().push(|s| s.push(2)).unwrap();
+        assert_eq!(injector.pending(), 2);
+
+        let mut state = Vec::new();
+        for update in injector.take(true) {
+            update(&mut state);
+        }
+        assert_eq!(state, [1, 2]);
+        assert!(!injector.is_closed(), "non-empty take keeps the queue open");
+
+        assert!(injector.take(true).is_empty());
+        assert!(injector.is_closed());
+        assert_eq!(injector.push(|s| s.push(3)), Err(InjectError::RunFinished));
+    }
+}
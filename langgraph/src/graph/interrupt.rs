@@ -2,6 +2,13 @@
 //!
 //! Provides support for interrupting graph execution, useful for human-in-the-loop
 //! scenarios where execution needs to pause for user input or approval.
+//!
+//! A run pauses when a node returns `AgentError::Interrupted` or when it reaches a node
+//! listed in `StateGraph::with_interrupt_before` / `with_interrupt_after`. With a
+//! checkpointer and `thread_id`, the state is saved with `Checkpoint::pending_node` and the
+//! run can be continued with `CompiledStateGraph::resume(Command::resume(value), config)`.
+//! A [`ResumeHandler`] (`StateGraph::with_resume_handler`) writes the human-provided value
+//! into the state before the pending node runs, so nodes need not read it from `RunContext`.
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -48,6 +55,74 @@ impl From<Interrupt> for GraphInterrupt {
     }
 }
 
+/// Input for continuing an interrupted run (aligns with LangGraph `Command(resume=...)`).
+///
+/// `resume` is handed to nodes via [`RunContext::resume`](super::RunContext); `update`,
+/// when set, is merged into the checkpointed state (with the graph's state updater) before
+/// the pending node runs, e.g. to apply a human edit.
+#[derive(Debug, Clone)]
+pub struct Command<S> {
+    /// Value answering the interrupt (e.g. `{"approved": true}`).
+    pub resume: Option<serde_json::Value>,
+    /// State update applied before resuming.
+    pub update: Option<S>,
+}
+
+impl<S> Command<S> {
+    /// Creates a command that resumes with `value`.
+    pub fn resume(value: serde_json::Value) -> Self {
+        Self {
+            resume: Some(value),
+            update: None,
+        }
+    }
+
+    /// Sets a state update to apply before resuming.
+    pub fn with_update(mut self, update: S) -> Self {
+        self.update = Some(update);
+        self
+    }
+}
+
+impl<S> Default for Command<S> {
+    /// Resumes without a value or update (e.g. after `interrupt_before` approval).
+    fn default() -> Self {
+        Self {
+            resume: None,
+            update: None,
+        }
+    }
+}
+
+/// Injects the value passed to `CompiledStateGraph::resume` into the checkpointed state.
+///
+/// Called once per resume, after `Command::update` is merged and before `pending_node` runs;
+/// not called when the command has no resume value. Closures
+/// `Fn(&mut S, &str, &Value) -> Result<(), AgentError>` implement it.
+pub trait ResumeHandler<S>: Send + Sync {
+    /// Writes `value` into `state`; `pending_node` is the node about to run.
+    fn apply_resume(
+        &self,
+        state: &mut S,
+        pending_node: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), AgentError>;
+}
+
+impl<S, F> ResumeHandler<S> for F
+where
+    F: Fn(&mut S, &str, &serde_json::Value) -> Result<(), AgentError> + Send + Sync,
+{
+    fn apply_resume(
+        &self,
+        state: &mut S,
+        pending_node: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), AgentError> {
+        self(state, pending_node, value)
+    }
+}
+
 /// Trait for handling interrupts during graph execution.
 ///
 /// Implement this trait to define custom interrupt handling logic.
@@ -101,6 +176,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_command_resume_with_update() {
+        let cmd = Command::resume(serde_json::json!({"approved": true})).with_update(3);
+        assert_eq!(cmd.resume, Some(serde_json::json!({"approved": true})));
+        assert_eq!(cmd.update, Some(3));
+        let empty = Command::<i32>::default();
+        assert!(empty.resume.is_none() && empty.update.is_none());
+    }
+
+    fn record(
+        state: &mut Vec<String>,
+        node: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), AgentError> {
+        state.push(format!("{}={}", node, value));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fn_resume_handler() {
+        let handler: &dyn ResumeHandler<Vec<String>> = &record;
+        let mut state = vec![];
+        handler
+            .apply_resume(&mut state, "review", &serde_json::json!("ok"))
+            .unwrap();
+        assert_eq!(state, vec!["review=\"ok\"".to_string()]);
+    }
+
     #[test]
     fn test_default_interrupt_handler() {
         let handler = DefaultInterruptHandler;
@@ -2,6 +2,40 @@
 //!
 //! Provides structured logging for graph execution events, node execution,
 //! state updates, and other important events.
+//!
+//! Every run executes inside a `run` span ([`run_span`]) carrying `run_id`, `thread_id` and
+//! `user_id`, and every node inside a `node` span ([`node_span`]); events logged by nodes,
+//! tools and LLM clients during the run inherit these fields, so logs can be filtered by
+//! conversation without adding the fields at each call site.
+//!
+//! Below the node span, `ActNode` opens a `tool` span per call (`tool`, `call_id`) and
+//! `ChatOpenAI` an `llm` span per request (`model`, `prompt_tokens`, `completion_tokens`).
+//! With the `otel` feature these spans are exported as an OpenTelemetry trace per run (see
+//! `crate::otel`).
+
+use crate::memory::RunnableConfig;
+
+/// Span of one graph run: `run_id` plus `thread_id` and `user_id` from `config` when set.
+pub fn run_span(config: Option<&RunnableConfig>, run_id: &str) -> tracing::Span {
+    let span = tracing::info_span!(
+        "run",
+        run_id = run_id,
+        thread_id = tracing::field::Empty,
+        user_id = tracing::field::Empty
+    );
+    if let Some(thread_id) = config.and_then(|c| c.thread_id.as_deref()) {
+        span.record("thread_id", thread_id);
+    }
+    if let Some(user_id) = config.and_then(|c| c.user_id.as_deref()) {
+        span.record("user_id", user_id);
+    }
+    span
+}
+
+/// Span of one node execution (all attempts when the node is retried).
+pub fn node_span(node_id: &str) -> tracing::Span {
+    tracing::info_span!("node", node_id = node_id)
+}
 
 /// Log node execution start.
 ///
@@ -55,4 +89,16 @@ mod tests {
             "test".to_string(),
         ));
     }
+
+    /// **Scenario**: Span helpers build spans with and without optional config fields.
+    #[test]
+    fn run_and_node_spans_accept_missing_fields() {
+        let config = RunnableConfig {
+            thread_id: Some("t1".into()),
+            ..Default::default()
+        };
+        let _ = run_span(Some(&config), "r1");
+        let _ = run_span(None, "r2");
+        let _ = node_span("think");
+    }
 }
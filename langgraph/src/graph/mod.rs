@@ -3,9 +3,12 @@
 //! Aligns with LangGraph `StateGraph`: add nodes and edges, compile, then
 //! invoke with state. Design: docs/rust-langgraph/11-state-graph-design.md.
 
+mod analysis;
 mod compile_error;
 mod compiled;
 mod conditional;
+mod governor;
+mod inject;
 mod interrupt;
 mod logging;
 mod logging_middleware;
@@ -13,27 +16,43 @@ mod name_node;
 mod next;
 mod node;
 mod node_middleware;
+mod profile;
 mod retry;
 mod run_context;
+mod run_stats;
 mod runtime;
 mod state_graph;
 mod visualization;
 
+pub use analysis::{analyze_graph, DeadBranch, GraphAnalysis};
 pub use compile_error::CompilationError;
 pub use compiled::CompiledStateGraph;
 pub use conditional::{ConditionalRouter, ConditionalRouterFn, NextEntry};
-pub use interrupt::{DefaultInterruptHandler, GraphInterrupt, Interrupt, InterruptHandler};
+pub use governor::{
+    BudgetGovernor, GovernorDecision, RunGovernor, RunProgress, TokenPricing, UsageMeter,
+};
+pub use inject::{InjectError, Injector, StateInjection};
+pub use interrupt::{
+    Command, DefaultInterruptHandler, GraphInterrupt, Interrupt, InterruptHandler, ResumeHandler,
+};
 pub use logging::{
     log_graph_complete, log_graph_error, log_graph_start, log_node_complete, log_node_start,
-    log_state_update,
+    log_state_update, node_span, run_span,
 };
 pub use logging_middleware::LoggingNodeMiddleware;
 pub use name_node::NameNode;
 pub use next::Next;
 pub use node::Node;
 pub use node_middleware::NodeMiddleware;
+pub use profile::{
+    CountingAllocator, FrameStats, GraphProfile, ProfileError, ProfileMetric, Profiler,
+};
 pub use retry::RetryPolicy;
 pub use run_context::RunContext;
+pub use run_stats::{GraphRunStats, NodeRunStats, RunStatsMiddleware};
 pub use runtime::Runtime;
 pub use state_graph::{StateGraph, END, START};
-pub use visualization::{generate_dot, generate_text};
+pub use visualization::{
+    generate_dot, generate_dot_with_stats, generate_mermaid, generate_text, render_graph,
+    GraphFormat,
+};
@@ -6,6 +6,7 @@
 use async_trait::async_trait;
 use std::fmt::Debug;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use crate::error::AgentError;
 
@@ -37,3 +38,49 @@ where
         >,
     ) -> Result<(S, Next), AgentError>;
 }
+
+/// Two middlewares as one: `outer` wraps `inner`, which wraps the node.
+pub(crate) struct LayeredMiddleware<S> {
+    outer: Arc<dyn NodeMiddleware<S>>,
+    inner: Arc<dyn NodeMiddleware<S>>,
+}
+
+impl<S> LayeredMiddleware<S> {
+    pub(crate) fn new(
+        outer: Arc<dyn NodeMiddleware<S>>,
+        inner: Arc<dyn NodeMiddleware<S>>,
+    ) -> Self {
+        Self { outer, inner }
+    }
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for LayeredMiddleware<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: Box<
+            dyn FnOnce(
+                    S,
+                ) -> Pin<
+                    Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<(S, Next), AgentError> {
+        let layer = Arc::clone(&self.inner);
+        let id = node_id.to_string();
+        self.outer
+            .around_run(
+                node_id,
+                state,
+                Box::new(move |state| {
+                    Box::pin(async move { layer.around_run(&id, state, inner).await })
+                }),
+            )
+            .await
+    }
+}
@@ -0,0 +1,780 @@
+//! Execution profiles: wall time, CPU time and allocations per node and checkpoint operation,
+//! aggregated across runs and exported for flame graphs.
+//!
+//! [`Profiler`] is opt-in. As node middleware it measures every node run;
+//! [`Profiler::checkpointer`] and [`Profiler::serializer`] wrap a checkpointer and a serializer
+//! to measure checkpoint reads and writes and the (de)serialization inside them. Measurements go
+//! into a shared [`GraphProfile`], which is plain serializable data: [`save`](GraphProfile::save)
+//! it as JSON, [`merge`](GraphProfile::merge) the profiles of many runs, print its summary table
+//! (`Display`), or export folded stacks ([`to_folded`](GraphProfile::to_folded), for
+//! flamegraph.pl, inferno or speedscope) and pprof protobuf ([`to_pprof`](GraphProfile::to_pprof),
+//! for `go tool pprof`).
+//!
+//! Frames are node ids and `checkpoint:put` / `checkpoint:get_tuple` / `checkpoint:list`;
+//! `serialize` and `deserialize` are children of the checkpoint frame that runs them. CPU time is
+//! the process's CPU time while the frame ran, and allocations are counted only when the binary
+//! installs [`CountingAllocator`] as its global allocator; both are exact only while one run
+//! executes at a time (e.g. profiling from the CLI).
+//!
+//! **Interaction**: `ReactRunner::with_profiler` layers the middleware on the ReAct graph and
+//! wraps its checkpointer; the CLI's `--profile` and `--profile-report` persist and print it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Debug};
+use std::path::Path;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AgentError;
+use crate::memory::{
+    Checkpoint, CheckpointError, CheckpointListItem, CheckpointMetadata, Checkpointer,
+    RunnableConfig, Serializer, ThreadInfo,
+};
+
+use super::{Next, NodeMiddleware};
+
+/// Error loading or saving a [`GraphProfile`].
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    #[error("profile file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("profile JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One measured quantity of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileMetric {
+    /// Wall-clock time, in nanoseconds.
+    Wall,
+    /// Process CPU time, in nanoseconds.
+    Cpu,
+    /// Number of allocations.
+    Allocations,
+    /// Bytes allocated.
+    AllocatedBytes,
+}
+
+impl ProfileMetric {
+    /// All metrics, in the order of pprof's sample types.
+    pub const ALL: [ProfileMetric; 4] = [
+        ProfileMetric::Wall,
+        ProfileMetric::Cpu,
+        ProfileMetric::Allocations,
+        ProfileMetric::AllocatedBytes,
+    ];
+
+    /// Name: `wall`, `cpu`, `allocs` or `alloc_bytes`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Wall => "wall",
+            Self::Cpu => "cpu",
+            Self::Allocations => "allocs",
+            Self::AllocatedBytes => "alloc_bytes",
+        }
+    }
+
+    /// pprof sample type and unit.
+    fn pprof_type(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Wall => ("wall", "nanoseconds"),
+            Self::Cpu => ("cpu", "nanoseconds"),
+            Self::Allocations => ("alloc_objects", "count"),
+            Self::AllocatedBytes => ("alloc_space", "bytes"),
+        }
+    }
+}
+
+impl FromStr for ProfileMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown profile metric {:?}; use wall, cpu, allocs or alloc_bytes",
+                    s
+                )
+            })
+    }
+}
+
+/// Totals of one frame (a node or a checkpoint operation) over all its calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameStats {
+    /// Number of calls.
+    pub calls: u64,
+    /// Wall-clock time, in nanoseconds.
+    pub wall_ns: u64,
+    /// Process CPU time while the frame ran, in nanoseconds.
+    pub cpu_ns: u64,
+    /// Allocations while the frame ran; 0 without [`CountingAllocator`].
+    pub allocations: u64,
+    /// Bytes allocated while the frame ran; 0 without [`CountingAllocator`].
+    pub allocated_bytes: u64,
+}
+
+impl FrameStats {
+    /// Adds another frame's totals into this one.
+    pub fn merge(&mut self, other: &FrameStats) {
+        self.calls += other.calls;
+        self.wall_ns += other.wall_ns;
+        self.cpu_ns += other.cpu_ns;
+        self.allocations += other.allocations;
+        self.allocated_bytes += other.allocated_bytes;
+    }
+
+    /// Total wall-clock time.
+    pub fn wall(&self) -> Duration {
+        Duration::from_nanos(self.wall_ns)
+    }
+
+    /// Total process CPU time.
+    pub fn cpu(&self) -> Duration {
+        Duration::from_nanos(self.cpu_ns)
+    }
+
+    /// The value of `metric`.
+    pub fn value(&self, metric: ProfileMetric) -> u64 {
+        match metric {
+            ProfileMetric::Wall => self.wall_ns,
+            ProfileMetric::Cpu => self.cpu_ns,
+            ProfileMetric::Allocations => self.allocations,
+            ProfileMetric::AllocatedBytes => self.allocated_bytes,
+        }
+    }
+}
+
+/// Frame totals of one or many runs, keyed by stack: frame names separated by `;`, outermost
+/// first (e.g. `checkpoint:put;serialize`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GraphProfile {
+    /// Runs the profile covers.
+    pub runs: u64,
+    /// Totals per stack.
+    pub frames: BTreeMap<String, FrameStats>,
+}
+
+impl GraphProfile {
+    /// Creates an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `stats` to the totals of `stack`.
+    pub fn record(&mut self, stack: &str, stats: FrameStats) {
+        self.frames
+            .entry(stack.to_string())
+            .or_default()
+            .merge(&stats);
+    }
+
+    /// Adds all runs and totals of `other` into this one.
+    pub fn merge(&mut self, other: &GraphProfile) {
+        self.runs += other.runs;
+        for (stack, stats) in &other.frames {
+            self.record(stack, *stats);
+        }
+    }
+
+    /// Reads a profile saved with [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// Writes the profile as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProfileError> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Stacks one frame below `parent`, or the outermost frames for `None`; most wall time
+    /// first.
+    fn children(&self, parent: Option<&str>) -> Vec<(&str, &FrameStats)> {
+        let mut children: Vec<(&str, &FrameStats)> = self
+            .frames
+            .iter()
+            .filter(|(stack, _)| match parent {
+                None => !stack.contains(';'),
+                Some(parent) => stack
+                    .strip_prefix(parent)
+                    .and_then(|rest| rest.strip_prefix(';'))
+                    .is_some_and(|leaf| !leaf.contains(';')),
+            })
+            .map(|(stack, stats)| (stack.as_str(), stats))
+            .collect();
+        children.sort_by(|a, b| b.1.wall_ns.cmp(&a.1.wall_ns).then(a.0.cmp(b.0)));
+        children
+    }
+
+    /// `metric` of `stack` minus that of its child frames, as flame graphs expect.
+    fn self_value(&self, stack: &str, metric: ProfileMetric) -> u64 {
+        let total = self.frames.get(stack).map_or(0, |s| s.value(metric));
+        let children: u64 = self
+            .children(Some(stack))
+            .iter()
+            .map(|(_, s)| s.value(metric))
+            .sum();
+        total.saturating_sub(children)
+    }
+
+    /// Folded stacks (`frame;frame value` per line) of `metric`, for flamegraph.pl, inferno
+    /// or speedscope. Values are the frames' own, without their children.
+    pub fn to_folded(&self, metric: ProfileMetric) -> String {
+        let mut out = String::new();
+        for stack in self.frames.keys() {
+            let value = self.self_value(stack, metric);
+            if value > 0 {
+                out.push_str(&format!("{} {}\n", stack, value));
+            }
+        }
+        out
+    }
+
+    /// The profile as an uncompressed pprof `Profile` protobuf with one sample per stack and
+    /// the sample types wall, cpu, alloc_objects and alloc_space.
+    pub fn to_pprof(&self) -> Vec<u8> {
+        let mut strings: Vec<String> = vec![String::new()];
+        let mut intern = |s: &str| -> u64 {
+            match strings.iter().position(|t| t == s) {
+                Some(i) => i as u64,
+                None => {
+                    strings.push(s.to_string());
+                    (strings.len() - 1) as u64
+                }
+            }
+        };
+        let mut profile = Proto::default();
+        for metric in ProfileMetric::ALL {
+            let (kind, unit) = metric.pprof_type();
+            let mut value_type = Proto::default();
+            value_type.uint(1, intern(kind));
+            value_type.uint(2, intern(unit));
+            profile.message(1, &value_type);
+        }
+        let names: BTreeSet<&str> = self.frames.keys().flat_map(|s| s.split(';')).collect();
+        let ids: BTreeMap<&str, u64> = names.iter().zip(1..).map(|(n, id)| (*n, id)).collect();
+        for stack in self.frames.keys() {
+            let mut sample = Proto::default();
+            let locations: Vec<u64> = stack.rsplit(';').map(|f| ids[f]).collect();
+            sample.packed(1, &locations);
+            let values: Vec<u64> = ProfileMetric::ALL
+                .iter()
+                .map(|m| self.self_value(stack, *m))
+                .collect();
+            sample.packed(2, &values);
+            profile.message(2, &sample);
+        }
+        for (name, id) in &ids {
+            let mut line = Proto::default();
+            line.uint(1, *id);
+            let mut location = Proto::default();
+            location.uint(1, *id);
+            location.message(4, &line);
+            profile.message(4, &location);
+
+            let name = intern(name);
+            let mut function = Proto::default();
+            function.uint(1, *id);
+            function.uint(2, name);
+            function.uint(3, name);
+            profile.message(5, &function);
+        }
+        for s in &strings {
+            profile.bytes(6, s.as_bytes());
+        }
+        profile.0
+    }
+}
+
+/// `1.23s`, `45.6ms` or `789µs`.
+fn format_duration(ns: u64) -> String {
+    let d = Duration::from_nanos(ns);
+    if d >= Duration::from_secs(1) {
+        format!("{:.2}s", d.as_secs_f64())
+    } else if d >= Duration::from_millis(1) {
+        format!("{:.1}ms", d.as_secs_f64() * 1e3)
+    } else {
+        format!("{}µs", d.as_micros())
+    }
+}
+
+/// `12.3 MiB`, `4.5 KiB` or `123 B`.
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b >= KIB * KIB {
+        format!("{:.1} MiB", b / (KIB * KIB))
+    } else if b >= KIB {
+        format!("{:.1} KiB", b / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Summary table: one row per frame, children indented under their parent, most wall time
+/// first; `wall %` is the share of the outermost frames' wall time. Allocation columns show `-`
+/// when nothing was counted.
+impl fmt::Display for GraphProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counted = self.frames.values().any(|s| s.allocations > 0);
+        let total: u64 = self.children(None).iter().map(|(_, s)| s.wall_ns).sum();
+        writeln!(f, "{} run(s)", self.runs)?;
+        writeln!(
+            f,
+            "{:<32} {:>8} {:>10} {:>10} {:>10} {:>10} {:>11} {:>7}",
+            "frame", "calls", "wall", "wall/call", "cpu", "allocs", "alloc", "wall %"
+        )?;
+        let mut pending: Vec<(&str, usize)> = self
+            .children(None)
+            .into_iter()
+            .rev()
+            .map(|(stack, _)| (stack, 0))
+            .collect();
+        while let Some((stack, depth)) = pending.pop() {
+            let stats = &self.frames[stack];
+            let name = stack.rsplit(';').next().unwrap_or(stack);
+            let per_call = stats.wall_ns / stats.calls.max(1);
+            let (allocs, bytes) = if counted {
+                (
+                    stats.allocations.to_string(),
+                    format_bytes(stats.allocated_bytes),
+                )
+            } else {
+                ("-".to_string(), "-".to_string())
+            };
+            let share = if total == 0 {
+                0.0
+            } else {
+                stats.wall_ns as f64 * 100.0 / total as f64
+            };
+            writeln!(
+                f,
+                "{:<32} {:>8} {:>10} {:>10} {:>10} {:>10} {:>11} {:>6.1}%",
+                format!("{}{}", "  ".repeat(depth), name),
+                stats.calls,
+                format_duration(stats.wall_ns),
+                format_duration(per_call),
+                format_duration(stats.cpu_ns),
+                allocs,
+                bytes,
+                share
+            )?;
+            for (child, _) in self.children(Some(stack)).into_iter().rev() {
+                pending.push((child, depth + 1));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Minimal protobuf writer for [`GraphProfile::to_pprof`].
+#[derive(Default)]
+struct Proto(Vec<u8>);
+
+impl Proto {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.0.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.0.push(v as u8);
+    }
+
+    fn uint(&mut self, field: u64, v: u64) {
+        self.varint(field << 3);
+        self.varint(v);
+    }
+
+    fn bytes(&mut self, field: u64, bytes: &[u8]) {
+        self.varint((field << 3) | 2);
+        self.varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn message(&mut self, field: u64, message: &Proto) {
+        self.bytes(field, &message.0);
+    }
+
+    fn packed(&mut self, field: u64, values: &[u64]) {
+        let mut packed = Proto::default();
+        for v in values {
+            packed.varint(*v);
+        }
+        self.message(field, &packed);
+    }
+}
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Global allocator that counts allocations for [`Profiler`], then defers to the system
+/// allocator.
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: langgraph::CountingAllocator = langgraph::CountingAllocator;
+/// ```
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn count(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is forwarded unchanged to `System`; counting has no effect on memory.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// CPU time the process used so far; zero where it cannot be read.
+fn process_cpu_time() -> Duration {
+    #[cfg(unix)]
+    {
+        let mut ts = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // SAFETY: `ts` is a valid, writable timespec.
+        if unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut ts) } == 0 {
+            return Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32);
+        }
+    }
+    Duration::ZERO
+}
+
+/// Counters at the start of a frame.
+struct Measure {
+    wall: Instant,
+    cpu: Duration,
+    allocations: u64,
+    allocated_bytes: u64,
+}
+
+impl Measure {
+    fn start() -> Self {
+        Self {
+            wall: Instant::now(),
+            cpu: process_cpu_time(),
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// One call with the counters' change since [`start`](Self::start).
+    fn finish(self) -> FrameStats {
+        FrameStats {
+            calls: 1,
+            wall_ns: self.wall.elapsed().as_nanos() as u64,
+            cpu_ns: process_cpu_time().saturating_sub(self.cpu).as_nanos() as u64,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - self.allocated_bytes,
+        }
+    }
+}
+
+/// Records frames into a shared [`GraphProfile`].
+///
+/// Clone is cheap (shared `Arc`): pass one handle to the graph (`StateGraph::with_middleware`
+/// or `ReactRunner::with_profiler`), call [`finish_run`](Self::finish_run) after each run and
+/// [`snapshot`](Self::snapshot) at the end.
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    profile: Arc<Mutex<GraphProfile>>,
+}
+
+impl Profiler {
+    /// Creates a profiler with an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts one finished run.
+    pub fn finish_run(&self) {
+        self.profile.lock().unwrap_or_else(|e| e.into_inner()).runs += 1;
+    }
+
+    /// A copy of the profile recorded so far.
+    pub fn snapshot(&self) -> GraphProfile {
+        self.profile
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Clears the profile.
+    pub fn reset(&self) {
+        *self.profile.lock().unwrap_or_else(|e| e.into_inner()) = GraphProfile::new();
+    }
+
+    fn record(&self, stack: &str, stats: FrameStats) {
+        self.profile
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(stack, stats);
+    }
+
+    /// Wraps `inner` so its puts, loads and listings are recorded as `checkpoint:*` frames.
+    pub fn checkpointer<S>(&self, inner: Arc<dyn Checkpointer<S>>) -> Arc<dyn Checkpointer<S>>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Arc::new(ProfiledCheckpointer {
+            inner,
+            profiler: self.clone(),
+        })
+    }
+
+    /// Wraps `inner` so (de)serialization is recorded under the checkpoint frame running it.
+    /// Pass the result to the saver (e.g. `SqliteSaver::new`).
+    pub fn serializer<S>(&self, inner: Arc<dyn Serializer<S>>) -> Arc<dyn Serializer<S>>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Arc::new(ProfiledSerializer {
+            inner,
+            profiler: self.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for Profiler
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: Box<
+            dyn FnOnce(
+                    S,
+                ) -> Pin<
+                    Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<(S, Next), AgentError> {
+        let measure = Measure::start();
+        let result = inner(state).await;
+        self.record(node_id, measure.finish());
+        result
+    }
+}
+
+const CHECKPOINT_PUT: &str = "checkpoint:put";
+const CHECKPOINT_GET: &str = "checkpoint:get_tuple";
+
+/// Checkpointer recording its calls; see [`Profiler::checkpointer`].
+struct ProfiledCheckpointer<S> {
+    inner: Arc<dyn Checkpointer<S>>,
+    profiler: Profiler,
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for ProfiledCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        let measure = Measure::start();
+        let result = self.inner.put(config, checkpoint).await;
+        self.profiler.record(CHECKPOINT_PUT, measure.finish());
+        result
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        let measure = Measure::start();
+        let result = self.inner.get_tuple(config).await;
+        self.profiler.record(CHECKPOINT_GET, measure.finish());
+        result
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        let measure = Measure::start();
+        let result = self.inner.list(config, limit, before, after).await;
+        self.profiler.record("checkpoint:list", measure.finish());
+        result
+    }
+
+    async fn list_threads(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        self.inner.list_threads(config).await
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        self.inner.delete_thread(config).await
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        self.inner.tag_checkpoint(config, tags).await
+    }
+
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        self.inner.flush().await
+    }
+}
+
+/// Serializer recording its calls; see [`Profiler::serializer`].
+struct ProfiledSerializer<S> {
+    inner: Arc<dyn Serializer<S>>,
+    profiler: Profiler,
+}
+
+impl<S> Serializer<S> for ProfiledSerializer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn serialize(&self, state: &S) -> Result<Vec<u8>, CheckpointError> {
+        let measure = Measure::start();
+        let result = self.inner.serialize(state);
+        let stack = format!("{};serialize", CHECKPOINT_PUT);
+        self.profiler.record(&stack, measure.finish());
+        result
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<S, CheckpointError> {
+        let measure = Measure::start();
+        let result = self.inner.deserialize(bytes);
+        let stack = format!("{};deserialize", CHECKPOINT_GET);
+        self.profiler.record(&stack, measure.finish());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(calls: u64, wall_ns: u64) -> FrameStats {
+        FrameStats {
+            calls,
+            wall_ns,
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Profiles of several runs merge; folded stacks and pprof samples carry each
+    /// frame's own value, without its children; the table lists children under their parent.
+    #[test]
+    fn merged_profile_exports_self_values() {
+        let mut first = GraphProfile::new();
+        first.runs = 1;
+        first.record("think", frame(2, 900));
+        first.record("checkpoint:put", frame(3, 300));
+        first.record("checkpoint:put;serialize", frame(3, 100));
+        let mut profile = GraphProfile::new();
+        profile.merge(&first);
+        profile.merge(&first);
+
+        assert_eq!(profile.runs, 2);
+        assert_eq!(profile.frames["think"], frame(4, 1800));
+        assert_eq!(
+            profile.to_folded(ProfileMetric::Wall),
+            "checkpoint:put 400\ncheckpoint:put;serialize 200\nthink 1800\n"
+        );
+
+        let table = profile.to_string();
+        let rows: Vec<&str> = table
+            .lines()
+            .skip(2)
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(rows, ["think", "checkpoint:put", "serialize"]);
+        assert!(
+            table.lines().nth(2).unwrap().ends_with("75.0%"),
+            "{}",
+            table
+        );
+
+        let pprof = profile.to_pprof();
+        let text = String::from_utf8_lossy(&pprof);
+        assert!(text.contains("alloc_space") && text.contains("serialize"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profile.json");
+        profile.save(&path).unwrap();
+        assert_eq!(GraphProfile::load(&path).unwrap(), profile);
+    }
+
+    /// **Scenario**: As middleware and checkpointer wrapper, the profiler records every node
+    /// run and checkpoint write of a graph run.
+    #[tokio::test]
+    async fn profiler_records_nodes_and_checkpoints() {
+        use crate::graph::StateGraph;
+        use crate::memory::MemorySaver;
+        use crate::{NameNode, END, START};
+
+        let profiler = Profiler::new();
+        let mut graph = StateGraph::<String>::new();
+        graph
+            .add_node("a", Arc::new(NameNode::new("a")))
+            .add_node("b", Arc::new(NameNode::new("b")))
+            .add_edge(START, "a")
+            .add_edge("a", "b")
+            .add_edge("b", END);
+        let checkpointer = profiler.checkpointer(Arc::new(MemorySaver::<String>::new()));
+        let compiled = graph
+            .compile_with_checkpointer_and_middleware(checkpointer, Arc::new(profiler.clone()))
+            .unwrap();
+        let config = RunnableConfig {
+            thread_id: Some("t".to_string()),
+            ..Default::default()
+        };
+        compiled.invoke(String::new(), Some(config)).await.unwrap();
+        profiler.finish_run();
+
+        let profile = profiler.snapshot();
+        assert_eq!(profile.runs, 1);
+        assert_eq!(profile.frames["a"].calls, 1);
+        assert_eq!(profile.frames["b"].calls, 1);
+        assert!(profile.frames[CHECKPOINT_PUT].calls >= 1);
+    }
+}
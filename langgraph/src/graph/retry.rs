@@ -1,13 +1,18 @@
 //! Retry mechanism for node execution.
 //!
 //! Provides retry policies for handling transient failures during graph execution.
+//! Only errors with [`AgentError::is_retryable`] are retried. A graph-wide policy is set with
+//! `StateGraph::with_retry_policy`; `StateGraph::add_node_with_retry` overrides it per node
+//! (e.g. `RetryPolicy::None` for deterministic nodes, a longer backoff for a flaky tool node).
 
 use std::time::Duration;
 
+use crate::error::AgentError;
+
 /// Retry policy for handling failures.
 ///
 /// Defines how many times and with what strategy to retry a failed operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RetryPolicy {
     /// No retry - fail immediately on error.
     None,
@@ -17,6 +22,8 @@ pub enum RetryPolicy {
         max_attempts: usize,
         /// Fixed interval between retries.
         interval: Duration,
+        /// Random spread of each delay, as a fraction (0.2 = ±20%). See [`RetryPolicy::with_jitter`].
+        jitter: f64,
     },
     /// Exponential backoff retry - retry with exponentially increasing delays.
     Exponential {
@@ -28,6 +35,8 @@ pub enum RetryPolicy {
         max_interval: Duration,
         /// Multiplier for exponential backoff (e.g., 2.0 doubles each time).
         multiplier: f64,
+        /// Random spread of each delay, as a fraction (0.2 = ±20%). See [`RetryPolicy::with_jitter`].
+        jitter: f64,
     },
 }
 
@@ -42,6 +51,7 @@ impl RetryPolicy {
         RetryPolicy::Fixed {
             max_attempts,
             interval,
+            jitter: 0.0,
         }
     }
 
@@ -57,7 +67,23 @@ impl RetryPolicy {
             initial_interval,
             max_interval,
             multiplier,
+            jitter: 0.0,
+        }
+    }
+
+    /// Spreads every delay randomly by up to `fraction` of itself (clamped to `0.0..=1.0`), so
+    /// many runs failing together (e.g. on a rate limit) do not retry in lockstep. No effect on
+    /// [`RetryPolicy::None`]; [`delay`](Self::delay) stays deterministic, the run loop uses
+    /// [`delay_for`](Self::delay_for).
+    pub fn with_jitter(mut self, fraction: f64) -> Self {
+        let fraction = fraction.clamp(0.0, 1.0);
+        match &mut self {
+            RetryPolicy::None => {}
+            RetryPolicy::Fixed { jitter, .. } | RetryPolicy::Exponential { jitter, .. } => {
+                *jitter = fraction
+            }
         }
+        self
     }
 
     /// Checks if a retry should be attempted for the given attempt number.
@@ -91,6 +117,26 @@ impl RetryPolicy {
         }
     }
 
+    /// Delay before retrying after `error`: the policy's [`delay`](Self::delay) with jitter
+    /// applied, raised to the error's retry hint (e.g. an LLM provider's `Retry-After`) when that
+    /// is longer.
+    pub fn delay_for(&self, attempt: usize, error: &AgentError) -> Duration {
+        let delay = match self {
+            RetryPolicy::Fixed { jitter, .. } | RetryPolicy::Exponential { jitter, .. }
+                if *jitter > 0.0 =>
+            {
+                // Uniform in [1 - jitter, 1 + jitter].
+                self.delay(attempt)
+                    .mul_f64(1.0 + *jitter * (2.0 * random_unit() - 1.0))
+            }
+            _ => self.delay(attempt),
+        };
+        match error.retry_after() {
+            Some(hint) => delay.max(hint),
+            None => delay,
+        }
+    }
+
     /// Gets the maximum number of attempts for this policy.
     pub fn max_attempts(&self) -> usize {
         match self {
@@ -101,6 +147,20 @@ impl RetryPolicy {
     }
 }
 
+/// Pseudo-random number in `[0, 1)` for jitter; seeded per call by std's randomly keyed hasher.
+fn random_unit() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
 impl Default for RetryPolicy {
     fn default() -> Self {
         RetryPolicy::None
@@ -153,4 +213,43 @@ mod tests {
         // delay(3) = 1 * 2^3 = 8, but capped at 5
         assert_eq!(policy.delay(3), Duration::from_secs(5));
     }
+
+    /// **Scenario**: An LLM retry hint longer than the policy delay wins; a shorter one does not.
+    #[test]
+    fn test_delay_for_honors_retry_after() {
+        use crate::llm::{LlmError, LlmErrorKind};
+
+        let policy = RetryPolicy::fixed(3, Duration::from_secs(1));
+        let slow: AgentError = LlmError::new(LlmErrorKind::RateLimited, "429")
+            .with_retry_after(Duration::from_secs(5))
+            .into();
+        assert_eq!(policy.delay_for(0, &slow), Duration::from_secs(5));
+        let fast: AgentError = LlmError::new(LlmErrorKind::RateLimited, "429")
+            .with_retry_after(Duration::from_millis(10))
+            .into();
+        assert_eq!(policy.delay_for(0, &fast), Duration::from_secs(1));
+        let plain = AgentError::ExecutionFailed("boom".into());
+        assert_eq!(policy.delay_for(0, &plain), Duration::from_secs(1));
+    }
+
+    /// **Scenario**: With jitter, delays spread within ±fraction of the policy delay while
+    /// delay() itself stays deterministic.
+    #[test]
+    fn test_jitter_spreads_delay_within_bounds() {
+        let policy =
+            RetryPolicy::exponential(3, Duration::from_secs(1), Duration::from_secs(10), 2.0)
+                .with_jitter(0.5);
+        assert_eq!(policy.delay(1), Duration::from_secs(2));
+        let error = AgentError::ExecutionFailed("boom".into());
+        let delays: Vec<Duration> = (0..50).map(|_| policy.delay_for(1, &error)).collect();
+        assert!(delays
+            .iter()
+            .all(|d| *d >= Duration::from_secs(1) && *d <= Duration::from_secs(3)));
+        assert!(delays.iter().any(|d| *d != delays[0]));
+
+        assert_eq!(
+            RetryPolicy::fixed(1, Duration::from_secs(1)).with_jitter(7.0),
+            RetryPolicy::fixed(1, Duration::from_secs(1)).with_jitter(1.0)
+        );
+    }
 }
@@ -28,10 +28,16 @@ use std::sync::Arc;
 
 use serde_json::Value;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
+use crate::callbacks::{CallbackHandler, Callbacks};
+use crate::llm::LlmUsage;
 use crate::managed::ManagedValue;
 use crate::memory::{RunnableConfig, Store};
-use crate::stream::{StreamEvent, StreamMode, StreamWriter};
+use crate::stream::{CustomEventRegistry, StreamEvent, StreamMode, StreamWriter};
+
+use super::governor::UsageMeter;
+use super::inject::Injector;
 
 /// Run context passed into nodes for streaming-aware execution.
 ///
@@ -45,6 +51,7 @@ use crate::stream::{StreamEvent, StreamMode, StreamWriter};
 /// - `store`: Long-term memory store for cross-thread data
 /// - `previous`: The previous state value (for functional API with checkpointer)
 /// - `runtime_context`: Custom context data (user_id, db_conn, etc.)
+/// - `cancellation`: Token that aborts the run (see [`with_cancellation`](Self::with_cancellation))
 ///
 /// # Example
 ///
@@ -70,6 +77,9 @@ where
 {
     /// Config for the current run (thread_id, checkpoint, user_id, etc.).
     pub config: RunnableConfig,
+    /// Id of this run, generated by [`new`](Self::new); logged on the run's span with
+    /// `thread_id` and `user_id` (see [`span`](Self::span)).
+    pub run_id: String,
     /// Optional sender for streaming events.
     pub stream_tx: Option<mpsc::Sender<StreamEvent<S>>>,
     /// Enabled stream modes (Values, Updates, Messages, Custom).
@@ -96,6 +106,37 @@ where
     /// This is a JSON value to support arbitrary context data without requiring
     /// additional type parameters.
     pub runtime_context: Option<serde_json::Value>,
+
+    /// Value passed with `Command::resume` when continuing an interrupted run.
+    ///
+    /// Set only for the run started by `CompiledStateGraph::resume`; a node that raised an
+    /// interrupt reads it on re-run to get the human's answer.
+    pub resume: Option<serde_json::Value>,
+
+    /// Cancels the run when triggered: the node in progress is aborted and the run fails
+    /// with `AgentError::Cancelled`. Nodes may also check it to stop long work early.
+    pub cancellation: Option<CancellationToken>,
+
+    /// Token usage of the run, read by the graph's `RunGovernor`. Clones of the context share
+    /// it; nodes add to it with [`record_usage`](Self::record_usage).
+    pub usage: UsageMeter,
+
+    /// Span the run's span is created under, e.g. the HTTP request that started the run, so
+    /// the run joins that trace although it executes on another task. None = the span current
+    /// where the run starts.
+    pub parent_span: Option<tracing::Span>,
+
+    /// Updates injected while the run is in progress, merged at the graph's injection points
+    /// (see [`Injector`]).
+    pub injector: Option<Injector<S>>,
+
+    /// Handlers told about the run, its nodes, LLM calls and tool calls. Starts as
+    /// `config.callbacks`; the graph's handlers are put first when the run starts.
+    pub callbacks: Callbacks,
+
+    /// Custom event types that [`stream_writer`](Self::stream_writer) and tool writers check
+    /// typed events against. None = the graph's (`StateGraph::with_custom_events`), if any.
+    pub custom_events: Option<Arc<CustomEventRegistry>>,
 }
 
 impl<S> RunContext<S>
@@ -104,14 +145,23 @@ where
 {
     /// Creates a new RunContext with default values.
     pub fn new(config: RunnableConfig) -> Self {
+        let callbacks = config.callbacks.clone();
         Self {
             config,
+            run_id: crate::memory::uuid6().to_string(),
             stream_tx: None,
             stream_mode: HashSet::new(),
             managed_values: HashMap::new(),
             store: None,
             previous: None,
             runtime_context: None,
+            resume: None,
+            cancellation: None,
+            usage: UsageMeter::default(),
+            parent_span: None,
+            injector: None,
+            callbacks,
+            custom_events: None,
         }
     }
 
@@ -167,6 +217,83 @@ where
         self
     }
 
+    /// Sets the resume value (see [`resume`](Self::resume)).
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_resume(mut self, value: serde_json::Value) -> Self {
+        self.resume = Some(value);
+        self
+    }
+
+    /// Sets the cancellation token; cancelling it (e.g. when the client disconnects) aborts
+    /// the run at the node in progress. State saved by earlier steps is kept.
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets the queue of updates injected into the run (see [`Injector`]); keep a clone to
+    /// push onto it while the run is in progress.
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_injector(mut self, injector: Injector<S>) -> Self {
+        self.injector = Some(injector);
+        self
+    }
+
+    /// Sets the run id, e.g. to the id of the request that started the run.
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_run_id(mut self, run_id: impl Into<String>) -> Self {
+        self.run_id = run_id.into();
+        self
+    }
+
+    /// Sets the span the run's span is created under (see [`parent_span`](Self::parent_span)).
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_parent_span(mut self, span: tracing::Span) -> Self {
+        self.parent_span = Some(span);
+        self
+    }
+
+    /// Adds a handler told about this run (see [`callbacks`](Self::callbacks)).
+    ///
+    /// Returns `Self` for method chaining.
+    pub fn with_callback_handler(mut self, handler: Arc<dyn CallbackHandler>) -> Self {
+        self.callbacks.push(handler);
+        self
+    }
+
+    /// Calls `f` with each of the run's handlers, passing the run id.
+    pub(crate) fn emit_callback(&self, f: impl Fn(&dyn CallbackHandler, &str)) {
+        self.callbacks.emit(|h| f(h, &self.run_id));
+    }
+
+    /// The `run` span (`run_id`, `thread_id`, `user_id`) the graph runs this context's run in,
+    /// a child of [`parent_span`](Self::parent_span) when set.
+    pub fn span(&self) -> tracing::Span {
+        let span = || super::logging::run_span(Some(&self.config), &self.run_id);
+        match &self.parent_span {
+            Some(parent) => parent.in_scope(span),
+            None => span(),
+        }
+    }
+
+    /// Adds the usage of one LLM call to the run's total (see [`usage`](Self::usage)).
+    pub fn record_usage(&self, usage: &LlmUsage) {
+        self.usage.record(usage);
+    }
+
+    /// Whether the run's cancellation token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
     /// Gets the store if available.
     pub fn store(&self) -> Option<&Arc<dyn Store>> {
         self.store.as_ref()
@@ -182,6 +309,11 @@ where
         self.runtime_context.as_ref()
     }
 
+    /// Gets the resume value when this run continues an interrupted one.
+    pub fn resume_value(&self) -> Option<&serde_json::Value> {
+        self.resume.as_ref()
+    }
+
     // === StreamWriter Integration ===
 
     /// Creates a StreamWriter from this context.
@@ -197,7 +329,11 @@ where
     /// writer.emit_message("Hello", "node_id").await;
     /// ```
     pub fn stream_writer(&self) -> StreamWriter<S> {
-        StreamWriter::new(self.stream_tx.clone(), self.stream_mode.clone())
+        let writer = StreamWriter::new(self.stream_tx.clone(), self.stream_mode.clone());
+        match &self.custom_events {
+            Some(registry) => writer.with_custom_events(registry.clone()),
+            None => writer,
+        }
     }
 
     /// Emits a custom JSON payload directly from the context.
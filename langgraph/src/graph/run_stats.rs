@@ -0,0 +1,246 @@
+//! Per-node runtime statistics: run count, error count, and durations.
+//!
+//! [`GraphRunStats`] is plain data collected from one run or merged across many;
+//! [`RunStatsMiddleware`] records into it around each `node.run`. Consumed by
+//! [`generate_dot_with_stats`](super::generate_dot_with_stats) to render a heat-map overlay.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::AgentError;
+use crate::graph::Next;
+
+use super::NodeMiddleware;
+
+/// Aggregated statistics for a single node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeRunStats {
+    /// Number of times the node ran (successful or not).
+    pub runs: u64,
+    /// Number of runs that returned an error.
+    pub errors: u64,
+    /// Sum of all run durations.
+    pub total_duration: Duration,
+    /// Longest single run.
+    pub max_duration: Duration,
+}
+
+impl NodeRunStats {
+    /// Records one run of the node.
+    pub fn record(&mut self, duration: Duration, ok: bool) {
+        self.runs += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_duration += duration;
+        if duration > self.max_duration {
+            self.max_duration = duration;
+        }
+    }
+
+    /// Average run duration; zero when the node never ran.
+    pub fn avg_duration(&self) -> Duration {
+        if self.runs == 0 {
+            Duration::ZERO
+        } else {
+            self.total_duration / self.runs as u32
+        }
+    }
+
+    /// Fraction of runs that failed, in `0.0..=1.0`; zero when the node never ran.
+    pub fn error_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.runs as f64
+        }
+    }
+
+    /// Adds another node's counters into this one.
+    pub fn merge(&mut self, other: &NodeRunStats) {
+        self.runs += other.runs;
+        self.errors += other.errors;
+        self.total_duration += other.total_duration;
+        self.max_duration = self.max_duration.max(other.max_duration);
+    }
+}
+
+/// Runtime statistics for all nodes of a graph, keyed by node id.
+///
+/// Build from one run via [`RunStatsMiddleware::snapshot`], or [`merge`](Self::merge)
+/// several snapshots to get aggregated metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphRunStats {
+    pub nodes: HashMap<String, NodeRunStats>,
+}
+
+impl GraphRunStats {
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one run of `node_id`.
+    pub fn record(&mut self, node_id: &str, duration: Duration, ok: bool) {
+        self.nodes
+            .entry(node_id.to_string())
+            .or_default()
+            .record(duration, ok);
+    }
+
+    /// Returns stats for `node_id`, if it ran at least once.
+    pub fn get(&self, node_id: &str) -> Option<&NodeRunStats> {
+        self.nodes.get(node_id)
+    }
+
+    /// Adds all counters from `other` into this one.
+    pub fn merge(&mut self, other: &GraphRunStats) {
+        for (id, stats) in &other.nodes {
+            self.nodes.entry(id.clone()).or_default().merge(stats);
+        }
+    }
+
+    /// Largest average duration across nodes; used to normalize heat-map colors.
+    pub fn max_avg_duration(&self) -> Duration {
+        self.nodes
+            .values()
+            .map(NodeRunStats::avg_duration)
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+}
+
+/// Middleware that records duration and outcome of every node run into shared [`GraphRunStats`].
+///
+/// Clone is cheap (shared `Arc`); keep one handle and pass another to
+/// `StateGraph::with_middleware`, then call [`snapshot`](Self::snapshot) after the run.
+pub struct RunStatsMiddleware<S> {
+    stats: Arc<Mutex<GraphRunStats>>,
+    _phantom: std::marker::PhantomData<S>,
+}
+
+impl<S> RunStatsMiddleware<S> {
+    /// Creates a middleware with empty statistics.
+    pub fn new() -> Self {
+        Self {
+            stats: Arc::new(Mutex::new(GraphRunStats::new())),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns a copy of the statistics recorded so far.
+    pub fn snapshot(&self) -> GraphRunStats {
+        self.stats.lock().map(|g| g.clone()).unwrap_or_default()
+    }
+
+    /// Clears all recorded statistics.
+    pub fn reset(&self) {
+        if let Ok(mut g) = self.stats.lock() {
+            *g = GraphRunStats::new();
+        }
+    }
+}
+
+impl<S> Default for RunStatsMiddleware<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for RunStatsMiddleware<S> {
+    fn clone(&self) -> Self {
+        Self {
+            stats: Arc::clone(&self.stats),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> NodeMiddleware<S> for RunStatsMiddleware<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    async fn around_run(
+        &self,
+        node_id: &str,
+        state: S,
+        inner: Box<
+            dyn FnOnce(S)
+                -> Pin<
+                    Box<dyn std::future::Future<Output = Result<(S, Next), AgentError>> + Send>,
+                > + Send,
+        >,
+    ) -> Result<(S, Next), AgentError> {
+        let start = Instant::now();
+        let result = inner(state).await;
+        if let Ok(mut g) = self.stats.lock() {
+            g.record(node_id, start.elapsed(), result.is_ok());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{NameNode, StateGraph, END, START};
+
+    /// **Scenario**: avg_duration and error_rate are computed from recorded runs.
+    #[test]
+    fn node_run_stats_avg_and_error_rate() {
+        let mut s = NodeRunStats::default();
+        assert_eq!(s.avg_duration(), Duration::ZERO);
+        assert_eq!(s.error_rate(), 0.0);
+        s.record(Duration::from_millis(10), true);
+        s.record(Duration::from_millis(30), false);
+        assert_eq!(s.runs, 2);
+        assert_eq!(s.errors, 1);
+        assert_eq!(s.avg_duration(), Duration::from_millis(20));
+        assert_eq!(s.max_duration, Duration::from_millis(30));
+        assert!((s.error_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    /// **Scenario**: merge sums counters per node and keeps the max duration.
+    #[test]
+    fn graph_run_stats_merge() {
+        let mut a = GraphRunStats::new();
+        a.record("n1", Duration::from_millis(5), true);
+        let mut b = GraphRunStats::new();
+        b.record("n1", Duration::from_millis(15), false);
+        b.record("n2", Duration::from_millis(1), true);
+        a.merge(&b);
+        let n1 = a.get("n1").unwrap();
+        assert_eq!(n1.runs, 2);
+        assert_eq!(n1.errors, 1);
+        assert_eq!(n1.max_duration, Duration::from_millis(15));
+        assert_eq!(a.get("n2").unwrap().runs, 1);
+        assert_eq!(a.max_avg_duration(), Duration::from_millis(10));
+    }
+
+    /// **Scenario**: RunStatsMiddleware records one run per node during invoke.
+    #[tokio::test]
+    async fn middleware_records_each_node_run() {
+        let stats = RunStatsMiddleware::<String>::new();
+        let mut graph = StateGraph::<String>::new();
+        graph.add_node("a", Arc::new(NameNode::new("a")));
+        graph.add_node("b", Arc::new(NameNode::new("b")));
+        graph.add_edge(START, "a");
+        graph.add_edge("a", "b");
+        graph.add_edge("b", END);
+        let compiled = graph
+            .with_middleware(Arc::new(stats.clone()))
+            .compile()
+            .unwrap();
+        compiled.invoke(String::new(), None).await.unwrap();
+        let snap = stats.snapshot();
+        assert_eq!(snap.get("a").map(|s| s.runs), Some(1));
+        assert_eq!(snap.get("b").map(|s| s.runs), Some(1));
+        stats.reset();
+        assert!(stats.snapshot().nodes.is_empty());
+    }
+}
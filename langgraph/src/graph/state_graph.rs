@@ -12,25 +12,64 @@
 //! used as the next node id, or looked up in an optional path map. A node must have
 //! either one outgoing `add_edge` or `add_conditional_edges`, not both.
 //!
+//! # Interrupts
+//!
+//! `with_interrupt_before` / `with_interrupt_after` name nodes where a run pauses for
+//! human-in-the-loop review. `invoke` then returns `AgentError::Interrupted`; with a
+//! checkpointer and `thread_id` the run continues via `CompiledStateGraph::resume`.
+//!
 //! # State Updates
 //!
 //! By default, nodes return a new state that completely replaces the previous state.
 //! To customize this behavior (e.g., append to lists, aggregate values), use
 //! `with_state_updater` to provide a custom `StateUpdater` implementation.
+//!
+//! # Timeouts and cancellation
+//!
+//! `with_node_timeout` (every node) and `add_node_with_timeout` (one node) abort a node that
+//! runs too long with `AgentError::NodeTimeout`. A run is cancelled from outside with a
+//! `CancellationToken` set via `RunContext::with_cancellation`.
+//!
+//! # Retries
+//!
+//! `with_retry_policy` retries every node on retryable errors (`AgentError::is_retryable`);
+//! `add_node_with_retry` gives one node its own policy, e.g. `RetryPolicy::None` for a
+//! deterministic node or a longer backoff for a flaky tool.
+//!
+//! # Run governor
+//!
+//! `with_governor` installs a `RunGovernor` asked before every node whether the run may go on
+//! (time, step, token or cost budgets). When it wraps a run up, the node named with
+//! `with_final_answer_node` runs once more as the last step.
+//!
+//! # Injection points
+//!
+//! `with_injection_point(after, then)` lets callers steer a running graph: updates queued on
+//! the run's `Injector` are merged after `after` runs, and the run continues at `then`.
+//!
+//! # Callbacks
+//!
+//! `with_callback_handler` registers a `CallbackHandler` told about every run: its start and
+//! end, each node, and the LLM and tool calls nodes make. Per-run handlers go in
+//! `RunnableConfig::callbacks`.
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::callbacks::{CallbackHandler, Callbacks};
 use crate::channels::{BoxedStateUpdater, ReplaceUpdater};
 use crate::graph::compile_error::CompilationError;
 use crate::graph::compiled::CompiledStateGraph;
 use crate::graph::conditional::{ConditionalRouter, ConditionalRouterFn, NextEntry};
-use crate::graph::interrupt::InterruptHandler;
+use crate::graph::governor::RunGovernor;
+use crate::graph::interrupt::{InterruptHandler, ResumeHandler};
 use crate::graph::node::Node;
 use crate::graph::node_middleware::NodeMiddleware;
 use crate::graph::retry::RetryPolicy;
 use crate::memory::{Checkpointer, Store};
+use crate::stream::{CustomEventRegistry, MessagesAccessor, UiEventsAccessor, UiEventsRetention};
 
 /// Sentinel for graph entry: use as `from_id` in `add_edge(START, first_node_id)`.
 pub const START: &str = "__start__";
@@ -66,8 +105,36 @@ pub struct StateGraph<S> {
     state_updater: Option<BoxedStateUpdater<S>>,
     /// Retry policy for node execution. Default is `RetryPolicy::None`.
     retry_policy: RetryPolicy,
+    /// Per-node retry policies (`add_node_with_retry`), overriding `retry_policy`.
+    node_retry_policies: HashMap<String, RetryPolicy>,
     /// Optional interrupt handler for human-in-the-loop scenarios.
     interrupt_handler: Option<Arc<dyn InterruptHandler>>,
+    /// Node ids before which execution pauses.
+    interrupt_before: Vec<String>,
+    /// Node ids after which execution pauses.
+    interrupt_after: Vec<String>,
+    /// Optional handler that writes the resume value into state on `resume`.
+    resume_handler: Option<Arc<dyn ResumeHandler<S>>>,
+    /// Optional message list reader for `StreamMode::MessagesDelta`.
+    messages_accessor: Option<Arc<dyn MessagesAccessor<S>>>,
+    /// Optional UI event list reader for `StreamMode::UiEvents` and checkpoint pruning.
+    ui_events_accessor: Option<Arc<dyn UiEventsAccessor<S>>>,
+    /// Which UI events checkpoints keep.
+    ui_events_retention: UiEventsRetention,
+    /// Time limit for every node without its own entry in `node_timeouts`.
+    node_timeout: Option<Duration>,
+    /// Per-node time limits (`add_node_with_timeout`).
+    node_timeouts: HashMap<String, Duration>,
+    /// Optional run policy consulted before each node.
+    governor: Option<Arc<dyn RunGovernor<S>>>,
+    /// Node run as the last step when the governor wraps a run up.
+    final_answer_node: Option<String>,
+    /// Injection points: node after which injected updates are merged -> node to run next.
+    injection_points: HashMap<String, String>,
+    /// Handlers told about every run of the compiled graph.
+    callbacks: Callbacks,
+    /// Declared custom stream events nodes may emit.
+    custom_events: Option<Arc<CustomEventRegistry>>,
 }
 
 impl<S> Default for StateGraph<S>
@@ -93,7 +160,21 @@ where
             middleware: None,
             state_updater: None,
             retry_policy: RetryPolicy::None,
+            node_retry_policies: HashMap::new(),
             interrupt_handler: None,
+            interrupt_before: Vec::new(),
+            interrupt_after: Vec::new(),
+            resume_handler: None,
+            messages_accessor: None,
+            ui_events_accessor: None,
+            ui_events_retention: UiEventsRetention::default(),
+            node_timeout: None,
+            node_timeouts: HashMap::new(),
+            governor: None,
+            final_answer_node: None,
+            injection_points: HashMap::new(),
+            callbacks: Callbacks::default(),
+            custom_events: None,
         }
     }
 
@@ -195,12 +276,258 @@ where
         }
     }
 
+    /// Pauses execution before each of the given nodes runs (human-in-the-loop approval).
+    ///
+    /// When the run reaches one of them, the state is checkpointed with `pending_node` set to
+    /// that node (requires a checkpointer and `config.thread_id`) and `invoke` returns
+    /// `AgentError::Interrupted`. `CompiledStateGraph::resume` then runs the node.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use langgraph::graph::StateGraph;
+    ///
+    /// let graph = StateGraph::<String>::new().with_interrupt_before(["act"]);
+    /// ```
+    pub fn with_interrupt_before<I, T>(self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            interrupt_before: nodes.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Pauses execution after each of the given nodes has run and its update was applied.
+    ///
+    /// The checkpoint's `pending_node` is the node that would run next; no pause happens when
+    /// the node is the last one (next is END). Resuming skips an `interrupt_before` on that
+    /// next node, so one pause covers both.
+    pub fn with_interrupt_after<I, T>(self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            interrupt_after: nodes.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Attaches a handler that injects the human-provided resume value into the state.
+    ///
+    /// On `CompiledStateGraph::resume`, the handler runs before the pending node with the
+    /// value from `Command::resume`; without it, nodes read the value via
+    /// `RunContext::resume_value`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use langgraph::graph::StateGraph;
+    /// use langgraph::AgentError;
+    /// use serde_json::Value;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Clone, Debug, Default)]
+    /// struct Review { approved: bool }
+    ///
+    /// fn apply_review(state: &mut Review, _node: &str, value: &Value) -> Result<(), AgentError> {
+    ///     state.approved = value["approved"].as_bool().unwrap_or(false);
+    ///     Ok(())
+    /// }
+    ///
+    /// let graph = StateGraph::<Review>::new().with_resume_handler(Arc::new(apply_review));
+    /// ```
+    pub fn with_resume_handler(self, handler: Arc<dyn ResumeHandler<S>>) -> Self {
+        Self {
+            resume_handler: Some(handler),
+            ..self
+        }
+    }
+
+    /// Tells the graph where the state keeps its messages, enabling `StreamMode::MessagesDelta`.
+    ///
+    /// After each node, streams with that mode get a `StreamEvent::MessagesDelta` holding only
+    /// the messages the node appended. Without an accessor the mode emits nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use langgraph::graph::StateGraph;
+    /// use langgraph::Message;
+    /// use std::sync::Arc;
+    ///
+    /// #[derive(Clone, Debug, Default)]
+    /// struct Chat { messages: Vec<Message> }
+    ///
+    /// fn messages(state: &Chat) -> &[Message] {
+    ///     &state.messages
+    /// }
+    ///
+    /// let graph = StateGraph::<Chat>::new().with_messages_accessor(Arc::new(messages));
+    /// ```
+    pub fn with_messages_accessor(self, accessor: Arc<dyn MessagesAccessor<S>>) -> Self {
+        Self {
+            messages_accessor: Some(accessor),
+            ..self
+        }
+    }
+
+    /// Tells the graph where the state keeps its UI events, enabling `StreamMode::UiEvents`.
+    ///
+    /// After each node, streams with that mode get a `StreamEvent::UiEvents` holding the events
+    /// the node appended; checkpoints keep them according to
+    /// [`with_ui_events_retention`](Self::with_ui_events_retention) (by default none).
+    pub fn with_ui_events_accessor(self, accessor: Arc<dyn UiEventsAccessor<S>>) -> Self {
+        Self {
+            ui_events_accessor: Some(accessor),
+            ..self
+        }
+    }
+
+    /// Sets which UI events checkpoints keep (default [`UiEventsRetention::Drop`]); needs
+    /// [`with_ui_events_accessor`](Self::with_ui_events_accessor).
+    pub fn with_ui_events_retention(self, retention: UiEventsRetention) -> Self {
+        Self {
+            ui_events_retention: retention,
+            ..self
+        }
+    }
+
+    /// Aborts any node that runs longer than `timeout`; the run fails with
+    /// `AgentError::NodeTimeout` for that node. The limit applies to each attempt, so with a
+    /// retry policy a timed-out node is retried like any other transient failure. Nodes added
+    /// with [`add_node_with_timeout`](Self::add_node_with_timeout) use their own limit.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use langgraph::graph::StateGraph;
+    /// use std::time::Duration;
+    ///
+    /// let graph = StateGraph::<String>::new().with_node_timeout(Duration::from_secs(60));
+    /// ```
+    pub fn with_node_timeout(self, timeout: Duration) -> Self {
+        Self {
+            node_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Consults `governor` before every node: it may let the node run, wrap the run up via the
+    /// [final answer node](Self::with_final_answer_node), or abort it with
+    /// `AgentError::Aborted`. See [`RunGovernor`] and `BudgetGovernor`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use langgraph::graph::{BudgetGovernor, StateGraph};
+    /// use std::sync::Arc;
+    ///
+    /// let graph = StateGraph::<String>::new()
+    ///     .with_governor(Arc::new(BudgetGovernor::new().with_max_tokens(20_000)))
+    ///     .with_final_answer_node("think");
+    /// ```
+    pub fn with_governor(self, governor: Arc<dyn RunGovernor<S>>) -> Self {
+        Self {
+            governor: Some(governor),
+            ..self
+        }
+    }
+
+    /// Node that produces the answer when the governor decides `SkipToFinalAnswer` (e.g. the
+    /// ReAct `think` node). It runs once with `IsLastStep` set and the run ends after it; without
+    /// one the run ends with the current state. Must be registered via `add_node`.
+    pub fn with_final_answer_node(self, id: impl Into<String>) -> Self {
+        Self {
+            final_answer_node: Some(id.into()),
+            ..self
+        }
+    }
+
+    /// Merges updates injected into a run (see [`Injector`](super::Injector)) after `after`
+    /// runs, then continues at `then`, whatever `after` routed to. Pick a node after which an
+    /// update leaves the state consistent, e.g. ReAct `observe` with `then` = `think`. Both
+    /// must be registered via `add_node`.
+    pub fn with_injection_point(
+        mut self,
+        after: impl Into<String>,
+        then: impl Into<String>,
+    ) -> Self {
+        self.injection_points.insert(after.into(), then.into());
+        self
+    }
+
+    /// Adds a handler called for every run of the compiled graph, before the run's own
+    /// handlers (`RunnableConfig::callbacks`). See [`CallbackHandler`].
+    pub fn with_callback_handler(mut self, handler: Arc<dyn CallbackHandler>) -> Self {
+        self.callbacks.push(handler);
+        self
+    }
+
+    /// Declares the custom stream events nodes of this graph emit: the stream writers of runs
+    /// (`RunContext::stream_writer`, tool writers) validate `emit_event` / `emit_typed` against
+    /// `registry` and reject undeclared types. Runs whose context has its own registry keep it.
+    pub fn with_custom_events(self, registry: Arc<CustomEventRegistry>) -> Self {
+        Self {
+            custom_events: Some(registry),
+            ..self
+        }
+    }
+
+    /// Like [`add_node`](Self::add_node), with a time limit for this node that overrides
+    /// [`with_node_timeout`](Self::with_node_timeout), e.g. a tool node calling slow services.
+    pub fn add_node_with_timeout(
+        &mut self,
+        id: impl Into<String>,
+        node: Arc<dyn Node<S>>,
+        timeout: Duration,
+    ) -> &mut Self {
+        let id = id.into();
+        self.add_node(id.clone(), node);
+        self.node_timeouts.insert(id, timeout);
+        self
+    }
+
+    /// Like [`add_node`](Self::add_node), with a retry policy for this node that overrides
+    /// [`with_retry_policy`](Self::with_retry_policy): retry a flaky LLM or tool node, or pass
+    /// `RetryPolicy::None` so a deterministic node fails on the first error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// graph
+    ///     .add_node_with_retry("search", search_node, RetryPolicy::exponential(
+    ///         4,
+    ///         Duration::from_millis(200),
+    ///         Duration::from_secs(5),
+    ///         2.0,
+    ///     ).with_jitter(0.2))
+    ///     .add_node_with_retry("format", format_node, RetryPolicy::None);
+    /// ```
+    pub fn add_node_with_retry(
+        &mut self,
+        id: impl Into<String>,
+        node: Arc<dyn Node<S>>,
+        policy: RetryPolicy,
+    ) -> &mut Self {
+        let id = id.into();
+        self.add_node(id.clone(), node);
+        self.node_retry_policies.insert(id, policy);
+        self
+    }
+
     /// Adds a node; id must be unique. Replaces if same id.
     ///
     /// Returns `&mut Self` for method chaining. The node is stored as
     /// `Arc<dyn Node<S>>`; use `add_edge` to include it in the chain.
     pub fn add_node(&mut self, id: impl Into<String>, node: Arc<dyn Node<S>>) -> &mut Self {
-        self.nodes.insert(id.into(), node);
+        let id = id.into();
+        self.node_timeouts.remove(&id);
+        self.node_retry_policies.remove(&id);
+        self.nodes.insert(id, node);
         self
     }
 
@@ -323,6 +650,22 @@ where
             }
         }
 
+        for id in self.interrupt_before.iter().chain(&self.interrupt_after) {
+            if !self.nodes.contains_key(id) {
+                return Err(CompilationError::InterruptNodeNotFound(id.clone()));
+            }
+        }
+        if let Some(id) = &self.final_answer_node {
+            if !self.nodes.contains_key(id) {
+                return Err(CompilationError::NodeNotFound(id.clone()));
+            }
+        }
+        for id in self.injection_points.iter().flat_map(|(a, t)| [a, t]) {
+            if !self.nodes.contains_key(id) {
+                return Err(CompilationError::NodeNotFound(id.clone()));
+            }
+        }
+
         let start_edges: Vec<_> = self
             .edges
             .iter()
@@ -424,6 +767,20 @@ where
             state_updater,
             retry_policy: self.retry_policy,
             interrupt_handler: self.interrupt_handler,
+            interrupt_before: self.interrupt_before.into_iter().collect(),
+            interrupt_after: self.interrupt_after.into_iter().collect(),
+            resume_handler: self.resume_handler,
+            messages_accessor: self.messages_accessor,
+            ui_events_accessor: self.ui_events_accessor,
+            ui_events_retention: self.ui_events_retention,
+            node_timeout: self.node_timeout,
+            node_timeouts: self.node_timeouts,
+            node_retry_policies: self.node_retry_policies,
+            governor: self.governor,
+            final_answer_node: self.final_answer_node,
+            injection_points: self.injection_points,
+            callbacks: self.callbacks,
+            custom_events: self.custom_events,
         })
     }
 }
@@ -476,6 +833,20 @@ mod tests {
         }
     }
 
+    /// **Scenario**: Compile fails when interrupt_before names a node that does not exist.
+    #[test]
+    fn compile_fails_when_interrupt_node_unknown() {
+        let mut graph = StateGraph::<DummyState>::new().with_interrupt_before(["missing"]);
+        graph.add_node("a", Arc::new(DummyNode("a")));
+        graph.add_edge(START, "a");
+        graph.add_edge("a", END);
+        match graph.compile() {
+            Err(CompilationError::InterruptNodeNotFound(id)) => assert_eq!(id, "missing"),
+            Err(e) => panic!("expected InterruptNodeNotFound(missing), got {:?}", e),
+            Ok(_) => panic!("expected compile error"),
+        }
+    }
+
     /// **Scenario**: Compile fails when conditional path_map references a non-existent node.
     #[test]
     fn compile_fails_when_conditional_path_map_has_invalid_target() {
@@ -1,17 +1,283 @@
 //! Graph visualization utilities.
 //!
-//! Provides functionality to export graph structure to Graphviz DOT format
-//! for visualization and debugging.
+//! Exports the structure of a compiled graph as Mermaid ([`generate_mermaid`]), Graphviz DOT
+//! ([`generate_dot`]) or plain text ([`generate_text`]) for docs and debugging; [`render_graph`]
+//! picks the format from a [`GraphFormat`]. All three show the edges from START, conditional
+//! branches (dashed, labeled with their routing key) and per-node settings: retry policy,
+//! interrupts, timeouts, the final answer node and injection points.
+//! [`generate_dot_with_stats`] overlays runtime statistics ([`GraphRunStats`]) as a heat map
+//! for performance analysis.
+//!
+//! A node is opaque to its graph, so a compiled graph run inside a node is drawn as that one
+//! node. A conditional router without a path map may return any node id and is drawn with a
+//! branch to every node.
+//!
+//! **Interaction**: Reads the same graph fields as [`analyze_graph`](super::analyze_graph);
+//! backs the CLI's `langgraph graph --format mermaid|dot|ascii`.
 
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Write;
+use std::str::FromStr;
 
 use super::CompiledStateGraph;
+use super::GraphRunStats;
+use super::{NextEntry, RetryPolicy};
 use super::{END, START};
 
+/// Output format of [`render_graph`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Mermaid flowchart, e.g. for markdown docs.
+    #[default]
+    Mermaid,
+    /// Graphviz DOT ([`generate_dot`]).
+    Dot,
+    /// Plain text ([`generate_text`]).
+    Ascii,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mermaid" => Ok(Self::Mermaid),
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "ascii" | "text" => Ok(Self::Ascii),
+            other => Err(format!(
+                "unknown graph format: {} (use mermaid, dot or ascii)",
+                other
+            )),
+        }
+    }
+}
+
+/// Renders `graph` in `format`.
+pub fn render_graph<S>(graph: &CompiledStateGraph<S>, format: GraphFormat) -> String
+where
+    S: std::fmt::Debug,
+{
+    match format {
+        GraphFormat::Mermaid => generate_mermaid(graph),
+        GraphFormat::Dot => generate_dot(graph),
+        GraphFormat::Ascii => generate_text(graph),
+    }
+}
+
+/// One edge of the rendered graph.
+struct Edge<'a> {
+    from: &'a str,
+    to: &'a str,
+    /// Set for the branches of a conditional router.
+    branch: Option<Branch<'a>>,
+}
+
+/// How a conditional edge is chosen.
+enum Branch<'a> {
+    /// Path map entry with this routing key.
+    Key(&'a str),
+    /// Router without a path map: its key is the node id.
+    Any,
+}
+
+/// Node ids in the order edges from START reach them, then the unreachable ones by name.
+fn node_order<S>(graph: &CompiledStateGraph<S>) -> Vec<&str> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::from([graph.first_node_id.as_str()]);
+    while let Some(id) = queue.pop_front() {
+        if !graph.nodes.contains_key(id) || !seen.insert(id) {
+            continue;
+        }
+        order.push(id);
+        queue.extend(node_edges(graph, id).iter().map(|e| e.to));
+    }
+    let mut rest: Vec<&str> = graph
+        .nodes
+        .keys()
+        .map(String::as_str)
+        .filter(|id| !seen.contains(id))
+        .collect();
+    rest.sort();
+    order.extend(rest);
+    order
+}
+
+/// Outgoing edges of node `id`: its edge or the branches of its router; in a graph without a
+/// next map, the linear edge order.
+fn node_edges<'a, S>(graph: &'a CompiledStateGraph<S>, id: &'a str) -> Vec<Edge<'a>> {
+    match graph.next_map.get(id) {
+        Some(NextEntry::Unconditional(to)) => vec![Edge {
+            from: id,
+            to,
+            branch: None,
+        }],
+        Some(NextEntry::Conditional(router)) => match &router.path_map {
+            Some(map) => {
+                let mut branches: Vec<(&String, &String)> = map.iter().collect();
+                branches.sort();
+                branches
+                    .into_iter()
+                    .map(|(key, to)| Edge {
+                        from: id,
+                        to,
+                        branch: Some(Branch::Key(key)),
+                    })
+                    .collect()
+            }
+            None => {
+                let mut targets: Vec<&str> = graph.nodes.keys().map(String::as_str).collect();
+                targets.sort();
+                targets.push(END);
+                targets
+                    .into_iter()
+                    .map(|to| Edge {
+                        from: id,
+                        to,
+                        branch: Some(Branch::Any),
+                    })
+                    .collect()
+            }
+        },
+        None => {
+            let Some(pos) = graph.edge_order.iter().position(|x| x == id) else {
+                return Vec::new();
+            };
+            let to = graph.edge_order.get(pos + 1).map_or(END, String::as_str);
+            vec![Edge {
+                from: id,
+                to,
+                branch: None,
+            }]
+        }
+    }
+}
+
+/// Every edge of `graph`, from START first, then per node in `order`.
+fn edges<'a, S>(graph: &'a CompiledStateGraph<S>, order: &[&'a str]) -> Vec<Edge<'a>> {
+    let mut edges = Vec::new();
+    if graph.nodes.contains_key(&graph.first_node_id) {
+        edges.push(Edge {
+            from: START,
+            to: &graph.first_node_id,
+            branch: None,
+        });
+    }
+    for id in order {
+        edges.extend(node_edges(graph, *id));
+    }
+    edges
+}
+
+/// Settings of node `id` worth showing next to it, e.g. `retry: 3x every 1s`.
+fn node_notes<S>(graph: &CompiledStateGraph<S>, id: &str) -> Vec<String> {
+    let mut notes = Vec::new();
+    let retry = graph
+        .node_retry_policies
+        .get(id)
+        .unwrap_or(&graph.retry_policy);
+    match retry {
+        RetryPolicy::None => {}
+        RetryPolicy::Fixed {
+            max_attempts,
+            interval,
+            ..
+        } => notes.push(format!("retry: {}x every {:?}", max_attempts, interval)),
+        RetryPolicy::Exponential {
+            max_attempts,
+            initial_interval,
+            max_interval,
+            ..
+        } => notes.push(format!(
+            "retry: {}x backoff {:?}..{:?}",
+            max_attempts, initial_interval, max_interval
+        )),
+    }
+    if graph.interrupt_before.contains(id) {
+        notes.push("interrupt before".to_string());
+    }
+    if graph.interrupt_after.contains(id) {
+        notes.push("interrupt after".to_string());
+    }
+    if let Some(timeout) = graph.node_timeouts.get(id).or(graph.node_timeout.as_ref()) {
+        notes.push(format!("timeout: {:?}", timeout));
+    }
+    if graph.final_answer_node.as_deref() == Some(id) {
+        notes.push("final answer".to_string());
+    }
+    if let Some(to) = graph.injection_points.get(id) {
+        notes.push(format!("injects -> {}", to));
+    }
+    notes
+}
+
 /// Generate Graphviz DOT format representation of the graph.
 ///
-/// Returns a string in DOT format that can be rendered using Graphviz tools.
+/// Returns a string in DOT format that can be rendered using Graphviz tools. Conditional
+/// branches are dashed and labeled with their routing key; nodes with settings (retry,
+/// interrupts, timeout) list them under their name.
 pub fn generate_dot<S>(graph: &CompiledStateGraph<S>) -> String
+where
+    S: std::fmt::Debug,
+{
+    render_dot(graph, None)
+}
+
+/// Generate DOT with nodes annotated by runtime statistics.
+///
+/// Each node that ran gets a label with run count, average duration and error
+/// rate, and a fill color from green (fastest) to red (slowest average duration,
+/// relative to the slowest node). Nodes with any errors get a red, thicker border.
+/// Nodes absent from `stats` are rendered as in [`generate_dot`].
+pub fn generate_dot_with_stats<S>(graph: &CompiledStateGraph<S>, stats: &GraphRunStats) -> String
+where
+    S: std::fmt::Debug,
+{
+    render_dot(graph, Some(stats))
+}
+
+/// DOT attribute list for a node with stats: label, heat-map fill and error border.
+fn stats_node_attrs(node_id: &str, notes: &[String], stats: &GraphRunStats) -> Option<String> {
+    let s = stats.get(node_id)?;
+    let max_avg = stats.max_avg_duration().as_secs_f64();
+    let avg = s.avg_duration();
+    let heat = if max_avg > 0.0 {
+        (avg.as_secs_f64() / max_avg).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    // HSV hue: 0.33 (green) for cold nodes down to 0.0 (red) for the hottest.
+    let hue = 0.33 * (1.0 - heat);
+    let mut attrs = format!(
+        "label=\"{}\\nruns={} avg={:.1}ms err={:.1}%{}\", style=filled, \
+         fillcolor=\"{:.3} 0.6 1.0\"",
+        dot_escape(node_id),
+        s.runs,
+        avg.as_secs_f64() * 1000.0,
+        s.error_rate() * 100.0,
+        dot_note_lines(notes),
+        hue
+    );
+    if s.errors > 0 {
+        attrs.push_str(", color=red, penwidth=2");
+    }
+    Some(attrs)
+}
+
+/// `notes` as extra DOT label lines.
+fn dot_note_lines(notes: &[String]) -> String {
+    notes
+        .iter()
+        .map(|n| format!("\\n{}", dot_escape(n)))
+        .collect()
+}
+
+/// `s` for a quoted DOT string.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_dot<S>(graph: &CompiledStateGraph<S>, stats: Option<&GraphRunStats>) -> String
 where
     S: std::fmt::Debug,
 {
@@ -29,41 +295,111 @@ where
         END
     ));
 
-    // Add regular nodes
-    for (node_id, _) in &graph.nodes {
-        dot.push_str(&format!("  \"{}\";\n", node_id));
+    // Add regular nodes, with their settings and stats
+    let order = node_order(graph);
+    for node_id in &order {
+        let notes = node_notes(graph, node_id);
+        let id = dot_escape(node_id);
+        match stats.and_then(|st| stats_node_attrs(node_id, &notes, st)) {
+            Some(attrs) => dot.push_str(&format!("  \"{}\" [{}];\n", id, attrs)),
+            None if !notes.is_empty() => dot.push_str(&format!(
+                "  \"{}\" [label=\"{}{}\"];\n",
+                id,
+                id,
+                dot_note_lines(&notes)
+            )),
+            None => dot.push_str(&format!("  \"{}\";\n", id)),
+        }
     }
 
-    dot.push_str("\n");
+    dot.push('\n');
 
-    // Add edges based on edge_order
-    if !graph.edge_order.is_empty() {
-        // Edge from START to first node
-        dot.push_str(&format!(
-            "  \"{}\" -> \"{}\";\n",
-            START, graph.edge_order[0]
-        ));
+    for edge in edges(graph, &order) {
+        let (from, to) = (dot_escape(edge.from), dot_escape(edge.to));
+        match edge.branch {
+            None => dot.push_str(&format!("  \"{}\" -> \"{}\";\n", from, to)),
+            Some(Branch::Key(key)) => dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [style=dashed, label=\"{}\"];\n",
+                from,
+                to,
+                dot_escape(key)
+            )),
+            Some(Branch::Any) => {
+                dot.push_str(&format!("  \"{}\" -> \"{}\" [style=dashed];\n", from, to))
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Generate a Mermaid flowchart of the graph, e.g. for a ```` ```mermaid ```` block in docs.
+///
+/// Like [`generate_dot`]: conditional branches are dotted and labeled with their routing key,
+/// and nodes list their settings (retry, interrupts, timeout) under their name.
+pub fn generate_mermaid<S>(graph: &CompiledStateGraph<S>) -> String
+where
+    S: std::fmt::Debug,
+{
+    let mut out = String::from("flowchart TD\n");
+    writeln!(out, "    {}([START])", mermaid_id(START)).unwrap();
+    writeln!(out, "    {}([END])", mermaid_id(END)).unwrap();
 
-        // Edges between nodes
-        for i in 1..graph.edge_order.len() {
-            dot.push_str(&format!(
-                "  \"{}\" -> \"{}\";\n",
-                graph.edge_order[i - 1],
-                graph.edge_order[i]
-            ));
+    let order = node_order(graph);
+    for node_id in &order {
+        let mut label = mermaid_text(node_id);
+        for note in node_notes(graph, node_id) {
+            label.push_str("<br/>");
+            label.push_str(&mermaid_text(&note));
         }
+        writeln!(out, "    {}[\"{}\"]", mermaid_id(node_id), label).unwrap();
+    }
 
-        // Edge from last node to END
-        if let Some(last_node) = graph.edge_order.last() {
-            dot.push_str(&format!("  \"{}\" -> \"{}\";\n", last_node, END));
+    for edge in edges(graph, &order) {
+        let (from, to) = (mermaid_id(edge.from), mermaid_id(edge.to));
+        match edge.branch {
+            None => writeln!(out, "    {} --> {}", from, to),
+            Some(Branch::Key(key)) => {
+                writeln!(out, "    {} -.->|\"{}\"| {}", from, mermaid_text(key), to)
+            }
+            Some(Branch::Any) => writeln!(out, "    {} -.-> {}", from, to),
         }
+        .unwrap();
     }
+    out
+}
 
-    dot.push_str("}\n");
-    dot
+/// Node id usable in Mermaid: other characters than ASCII letters, digits and `_` become `_`,
+/// and the keyword `end` gets a trailing `_`.
+fn mermaid_id(id: &str) -> String {
+    let mut out: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if out.eq_ignore_ascii_case("end") {
+        out.push('_');
+    }
+    out
+}
+
+/// Text for a quoted Mermaid label.
+fn mermaid_text(s: &str) -> String {
+    s.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
 }
 
 /// Generate a simple text representation of the graph structure.
+///
+/// Lists the nodes with their settings, then the edges; a conditional branch reads
+/// `source --key--> target`.
 pub fn generate_text<S>(graph: &CompiledStateGraph<S>) -> String
 where
     S: std::fmt::Debug,
@@ -71,15 +407,24 @@ where
     let mut text = String::new();
     writeln!(text, "Graph Structure:").unwrap();
     writeln!(text, "Nodes: {}", graph.nodes.len()).unwrap();
-
-    writeln!(text, "\nExecution Order:").unwrap();
-    writeln!(text, "  {} ->", START).unwrap();
-    for (i, node_id) in graph.edge_order.iter().enumerate() {
-        if i == graph.edge_order.len() - 1 {
-            writeln!(text, "  {} -> {}", node_id, END).unwrap();
+    let order = node_order(graph);
+    for node_id in &order {
+        let notes = node_notes(graph, node_id);
+        if notes.is_empty() {
+            writeln!(text, "  {}", node_id).unwrap();
         } else {
-            writeln!(text, "  {} ->", node_id).unwrap();
+            writeln!(text, "  {} ({})", node_id, notes.join(", ")).unwrap();
+        }
+    }
+
+    writeln!(text, "\nEdges:").unwrap();
+    for edge in edges(graph, &order) {
+        match edge.branch {
+            None => writeln!(text, "  {} -> {}", edge.from, edge.to),
+            Some(Branch::Key(key)) => writeln!(text, "  {} --{}--> {}", edge.from, key, edge.to),
+            Some(Branch::Any) => writeln!(text, "  {} --?--> {}", edge.from, edge.to),
         }
+        .unwrap();
     }
 
     text
@@ -109,6 +454,34 @@ mod tests {
         assert!(dot.contains("node2"));
     }
 
+    /// **Scenario**: generate_dot_with_stats labels nodes with stats and marks erroring nodes.
+    #[test]
+    fn test_generate_dot_with_stats() {
+        use std::time::Duration;
+
+        let mut graph = StateGraph::<String>::new();
+        graph.add_node("fast", std::sync::Arc::new(NameNode::new("fast")));
+        graph.add_node("slow", std::sync::Arc::new(NameNode::new("slow")));
+        graph.add_node("idle", std::sync::Arc::new(NameNode::new("idle")));
+        graph.add_edge(crate::graph::START, "fast");
+        graph.add_edge("fast", "slow");
+        graph.add_edge("slow", "idle");
+        graph.add_edge("idle", crate::graph::END);
+        let compiled = graph.compile().unwrap();
+
+        let mut stats = GraphRunStats::new();
+        stats.record("fast", Duration::from_millis(2), true);
+        stats.record("slow", Duration::from_millis(100), true);
+        stats.record("slow", Duration::from_millis(100), false);
+        let dot = generate_dot_with_stats(&compiled, &stats);
+
+        assert!(dot.contains("runs=1 avg=2.0ms err=0.0%"), "{}", dot);
+        assert!(dot.contains("runs=2 avg=100.0ms err=50.0%"), "{}", dot);
+        assert!(dot.contains("fillcolor=\"0.000 0.6 1.0\""), "{}", dot);
+        assert!(dot.contains("color=red, penwidth=2"), "{}", dot);
+        assert!(dot.contains("  \"idle\";\n"), "{}", dot);
+    }
+
     #[test]
     fn test_generate_text() {
         let mut graph = StateGraph::<String>::new();
@@ -124,4 +497,104 @@ mod tests {
         assert!(text.contains(END)); // Use the constant directly
         assert!(text.contains("node1"));
     }
+
+    /// Branching graph: `check` retries and pauses before it runs; its router goes back to
+    /// `work` or ends.
+    fn branching_graph() -> CompiledStateGraph<String> {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let mut graph = StateGraph::<String>::new().with_interrupt_before(["check"]);
+        graph.add_node("work", Arc::new(NameNode::new("work")));
+        graph.add_node_with_retry(
+            "check",
+            Arc::new(NameNode::new("check")),
+            RetryPolicy::fixed(3, Duration::from_secs(1)),
+        );
+        graph.add_edge(START, "work");
+        graph.add_edge("work", "check");
+        let path_map: HashMap<String, String> = [
+            ("again".to_string(), "work".to_string()),
+            ("done".to_string(), END.to_string()),
+        ]
+        .into();
+        graph.add_conditional_edges(
+            "check",
+            Arc::new(|_: &String| "done".to_string()),
+            Some(path_map),
+        );
+        graph.compile().unwrap()
+    }
+
+    /// **Scenario**: Mermaid output has START/END, solid edges, dotted branches labeled with
+    /// their key and node settings under the node name.
+    #[test]
+    fn test_generate_mermaid() {
+        let mermaid = generate_mermaid(&branching_graph());
+
+        assert!(mermaid.starts_with("flowchart TD\n"), "{}", mermaid);
+        assert!(mermaid.contains("__start__([START])"), "{}", mermaid);
+        assert!(mermaid.contains("__start__ --> work"), "{}", mermaid);
+        assert!(mermaid.contains("work --> check"), "{}", mermaid);
+        assert!(
+            mermaid.contains("check -.->|\"again\"| work"),
+            "{}",
+            mermaid
+        );
+        assert!(
+            mermaid.contains("check -.->|\"done\"| __end__"),
+            "{}",
+            mermaid
+        );
+        assert!(
+            mermaid.contains("check[\"check<br/>retry: 3x every 1s<br/>interrupt before\"]"),
+            "{}",
+            mermaid
+        );
+        assert!(mermaid.contains("work[\"work\"]"), "{}", mermaid);
+    }
+
+    /// **Scenario**: DOT and text output include the conditional branches and node settings,
+    /// which the linear edge order of a branching graph lacks.
+    #[test]
+    fn test_dot_and_text_show_branches_and_settings() {
+        let graph = branching_graph();
+
+        let dot = generate_dot(&graph);
+        assert!(
+            dot.contains("\"check\" -> \"work\" [style=dashed, label=\"again\"];"),
+            "{}",
+            dot
+        );
+        assert!(
+            dot.contains("\"check\" [label=\"check\\nretry: 3x every 1s\\ninterrupt before\"];"),
+            "{}",
+            dot
+        );
+
+        let text = generate_text(&graph);
+        assert!(
+            text.contains("  check (retry: 3x every 1s, interrupt before)\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("  check --done--> __end__\n"), "{}", text);
+    }
+
+    /// **Scenario**: Format names parse case-insensitively; render_graph dispatches on them.
+    #[test]
+    fn test_graph_format() {
+        assert_eq!("Mermaid".parse::<GraphFormat>(), Ok(GraphFormat::Mermaid));
+        assert_eq!("dot".parse::<GraphFormat>(), Ok(GraphFormat::Dot));
+        assert_eq!("ascii".parse::<GraphFormat>(), Ok(GraphFormat::Ascii));
+        assert!("svg".parse::<GraphFormat>().is_err());
+
+        let graph = branching_graph();
+        assert_eq!(render_graph(&graph, GraphFormat::Dot), generate_dot(&graph));
+        assert_eq!(
+            render_graph(&graph, GraphFormat::Ascii),
+            generate_text(&graph)
+        );
+    }
 }
@@ -0,0 +1,72 @@
+//! Document ingestion for RAG: files split into chunks and embedded into a [`Store`].
+//!
+//! [`load_documents`] reads a file or the text files of a directory, [`TextSplitter`] cuts text
+//! into overlapping chunks by characters or tokens, and [`Ingestor`] (or
+//! [`ingest_documents`] with defaults) stores the chunks in a namespace in batches — one
+//! embedding request per batch on vector stores — retrying failed batches and keeping under a
+//! rate limit.
+//!
+//! **Interaction**: Writes chunks as `{"text", "source", "chunk"}` values with
+//! [`Store::put_many`]; [`Retriever`](crate::react::Retriever) reads them back for the ReAct
+//! loop. Backs the CLI's `langgraph ingest`.
+//!
+//! [`Store`]: crate::memory::Store
+//! [`Store::put_many`]: crate::memory::Store::put_many
+
+mod loader;
+mod pipeline;
+mod splitter;
+
+pub use loader::load_documents;
+pub use pipeline::{ingest_documents, IngestReport, Ingestor, DEFAULT_INGEST_BATCH_SIZE};
+pub use splitter::{TextSplitter, DEFAULT_CHUNK_OVERLAP, DEFAULT_CHUNK_SIZE};
+
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::memory::StoreError;
+
+/// Errors loading or ingesting documents.
+#[derive(Debug, Error)]
+pub enum IngestError {
+    #[error("read {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{} is not a UTF-8 text file", .0.display())]
+    NotText(PathBuf),
+    #[error("store error: {0}")]
+    Store(#[from] StoreError),
+}
+
+/// A text to ingest and where it comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    /// Where the text comes from (e.g. a file path); prefixes the keys of its chunks.
+    pub source: String,
+    /// The full text.
+    pub text: String,
+    /// Fields added to every chunk's value (e.g. a title or URL).
+    pub metadata: Map<String, Value>,
+}
+
+impl Document {
+    /// Document `text` from `source`, without metadata.
+    pub fn new(source: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            text: text.into(),
+            metadata: Map::new(),
+        }
+    }
+
+    /// Adds `key: value` to the value of every chunk.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::time::Instant;
+
+use super::{Document, IngestError, TextSplitter};
+use crate::graph::RetryPolicy;
+use crate::memory::{Namespace, Store, StoreError};
+
+/// Chunks per `Store::put_many` call (one embedding batch) when none is given.
+pub const DEFAULT_INGEST_BATCH_SIZE: usize = 64;
+
+/// What [`Ingestor::ingest`] wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IngestReport {
+    /// Documents ingested.
+    pub documents: usize,
+    /// Chunks stored.
+    pub chunks: usize,
+    /// Chunks of earlier ingestions of the same sources that were deleted (the document got
+    /// shorter).
+    pub removed: usize,
+    /// Batches that failed and were retried.
+    pub retries: usize,
+}
+
+/// Splits documents into chunks and stores them in batches, retrying failed batches.
+///
+/// Each chunk is stored under key `<source>#<n>` as `{"text", "source", "chunk"}` plus the
+/// document's metadata, the shape [`Retriever`](crate::react::Retriever) reads. Ingesting a
+/// source again replaces its chunks. Vector stores embed each batch with one request
+/// ([`Store::put_many`]); a batch failing with an embedding or storage error (e.g. the
+/// embedding API's rate limit) is retried after the retry policy's delay, and
+/// [`with_max_chunks_per_minute`](Self::with_max_chunks_per_minute) keeps under a known limit.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use langgraph::ingest::{load_documents, Ingestor, TextSplitter};
+/// use langgraph::memory::Store;
+///
+/// # async fn example(store: &dyn Store) -> Result<(), Box<dyn std::error::Error>> {
+/// let documents = load_documents("docs/")?;
+/// let report = Ingestor::new()
+///     .with_splitter(TextSplitter::characters(800, 100))
+///     .with_max_chunks_per_minute(3000)
+///     .ingest(store, &vec!["docs".to_string()], &documents)
+///     .await?;
+/// println!("{} chunks from {} documents", report.chunks, report.documents);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Ingestor {
+    splitter: TextSplitter,
+    batch_size: usize,
+    retry: RetryPolicy,
+    max_chunks_per_minute: Option<u32>,
+}
+
+impl Ingestor {
+    /// Default splitter, batches of [`DEFAULT_INGEST_BATCH_SIZE`], up to 5 retries with
+    /// exponential backoff from 1s to 60s, no rate limit.
+    pub fn new() -> Self {
+        Self {
+            splitter: TextSplitter::default(),
+            batch_size: DEFAULT_INGEST_BATCH_SIZE,
+            retry: RetryPolicy::exponential(
+                5,
+                Duration::from_secs(1),
+                Duration::from_secs(60),
+                2.0,
+            ),
+            max_chunks_per_minute: None,
+        }
+    }
+
+    /// Splits documents with `splitter`.
+    pub fn with_splitter(mut self, splitter: TextSplitter) -> Self {
+        self.splitter = splitter;
+        self
+    }
+
+    /// Chunks per batch (at least 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Retries failed batches according to `retry`; `RetryPolicy::None` fails on the first
+    /// error.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Waits between batches so that at most `chunks` chunks are embedded per minute.
+    pub fn with_max_chunks_per_minute(mut self, chunks: u32) -> Self {
+        self.max_chunks_per_minute = Some(chunks.max(1));
+        self
+    }
+
+    /// Stores the chunks of `documents` in `namespace` of `store`.
+    ///
+    /// # Errors
+    ///
+    /// [`IngestError::Store`] when a batch still fails after the retries, or another store
+    /// error occurs; batches stored before stay.
+    pub async fn ingest(
+        &self,
+        store: &dyn Store,
+        namespace: &Namespace,
+        documents: &[Document],
+    ) -> Result<IngestReport, IngestError> {
+        let existing = store.list(namespace).await?;
+        let mut report = IngestReport::default();
+        let mut chunk_counts: HashMap<&str, usize> = HashMap::new();
+        let mut batch = Vec::with_capacity(self.batch_size);
+        let mut next_batch_at = Instant::now();
+
+        for document in documents {
+            let chunks = self.splitter.split(&document.text);
+            *chunk_counts.entry(document.source.as_str()).or_default() = chunks.len();
+            report.documents += 1;
+            for (index, text) in chunks.into_iter().enumerate() {
+                batch.push((
+                    format!("{}#{}", document.source, index),
+                    document.chunk_value(text, index),
+                ));
+                if batch.len() == self.batch_size {
+                    self.put_batch(store, namespace, &batch, &mut next_batch_at, &mut report)
+                        .await?;
+                    batch.clear();
+                }
+            }
+        }
+        if !batch.is_empty() {
+            self.put_batch(store, namespace, &batch, &mut next_batch_at, &mut report)
+                .await?;
+        }
+
+        for key in existing {
+            let Some((source, index)) = key.rsplit_once('#') else {
+                continue;
+            };
+            let (Some(count), Ok(index)) = (chunk_counts.get(source), index.parse::<usize>())
+            else {
+                continue;
+            };
+            if index >= *count {
+                store.delete(namespace, &key).await?;
+                report.removed += 1;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Stores one batch after the rate limit allows it, retrying transient errors.
+    async fn put_batch(
+        &self,
+        store: &dyn Store,
+        namespace: &Namespace,
+        batch: &[(String, Value)],
+        next_batch_at: &mut Instant,
+        report: &mut IngestReport,
+    ) -> Result<(), IngestError> {
+        tokio::time::sleep_until(*next_batch_at).await;
+        if let Some(per_minute) = self.max_chunks_per_minute {
+            let interval = Duration::from_secs(60).mul_f64(batch.len() as f64 / per_minute as f64);
+            *next_batch_at = Instant::now() + interval;
+        }
+        let mut attempt = 0;
+        loop {
+            match store.put_many(namespace, batch).await {
+                Ok(()) => {
+                    report.chunks += batch.len();
+                    return Ok(());
+                }
+                Err(e @ (StoreError::EmbeddingError(_) | StoreError::Storage(_)))
+                    if self.retry.should_retry(attempt) =>
+                {
+                    let delay = self.retry.delay(attempt);
+                    tracing::warn!(error = %e, attempt, ?delay, "ingest batch failed; retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                    report.retries += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Default for Ingestor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Document {
+    /// Stored value of chunk `index`: the metadata plus text, source and chunk number.
+    fn chunk_value(&self, text: String, index: usize) -> Value {
+        let mut value = self.metadata.clone();
+        value.insert("text".into(), json!(text));
+        value.insert("source".into(), json!(self.source));
+        value.insert("chunk".into(), json!(index));
+        Value::Object(value)
+    }
+}
+
+/// Stores the chunks of `documents` in `namespace` of `store` with the default [`Ingestor`].
+pub async fn ingest_documents(
+    store: &dyn Store,
+    namespace: &Namespace,
+    documents: &[Document],
+) -> Result<IngestReport, IngestError> {
+    Ingestor::new().ingest(store, namespace, documents).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// **Scenario**: Chunks are stored under `<source>#<n>` with text, source, chunk number
+    /// and metadata; ingesting a shorter version removes the chunks it no longer has.
+    #[tokio::test]
+    async fn stores_chunks_and_replaces_shorter_sources() {
+        let store = InMemoryStore::new();
+        let namespace = vec!["docs".to_string()];
+        let ingestor = Ingestor::new()
+            .with_splitter(TextSplitter::characters(10, 0))
+            .with_batch_size(2);
+        let long = Document::new("faq.md", "one two three four five six")
+            .with_metadata("lang", json!("en"));
+
+        let report = ingestor
+            .ingest(&store, &namespace, &[long.clone()])
+            .await
+            .unwrap();
+        assert_eq!((report.documents, report.chunks, report.removed), (1, 4, 0));
+        let first = store.get(&namespace, "faq.md#0").await.unwrap().unwrap();
+        assert_eq!(
+            first,
+            json!({"text": "one two", "source": "faq.md", "chunk": 0, "lang": "en"})
+        );
+
+        let short = Document::new("faq.md", "one two");
+        let report = ingest_documents(&store, &namespace, &[short])
+            .await
+            .unwrap();
+        assert_eq!((report.chunks, report.removed), (1, 3));
+        assert_eq!(store.list(&namespace).await.unwrap(), vec!["faq.md#0"]);
+    }
+
+    /// Store whose first `failures` put_many calls fail with an embedding error.
+    struct Flaky {
+        inner: InMemoryStore,
+        failures: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Store for Flaky {
+        async fn put(
+            &self,
+            namespace: &Namespace,
+            key: &str,
+            value: &Value,
+        ) -> Result<(), StoreError> {
+            self.inner.put(namespace, key, value).await
+        }
+
+        async fn put_many(
+            &self,
+            namespace: &Namespace,
+            items: &[(String, Value)],
+        ) -> Result<(), StoreError> {
+            let failed = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failed {
+                return Err(StoreError::EmbeddingError("429 rate limit".into()));
+            }
+            self.inner.put_many(namespace, items).await
+        }
+
+        async fn get(&self, namespace: &Namespace, key: &str) -> Result<Option<Value>, StoreError> {
+            self.inner.get(namespace, key).await
+        }
+
+        async fn get_item(
+            &self,
+            namespace: &Namespace,
+            key: &str,
+        ) -> Result<Option<crate::memory::Item>, StoreError> {
+            self.inner.get_item(namespace, key).await
+        }
+
+        async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
+            self.inner.delete(namespace, key).await
+        }
+
+        async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
+            self.inner.list(namespace).await
+        }
+
+        async fn search(
+            &self,
+            namespace_prefix: &Namespace,
+            options: crate::memory::SearchOptions,
+        ) -> Result<Vec<crate::memory::SearchItem>, StoreError> {
+            self.inner.search(namespace_prefix, options).await
+        }
+
+        async fn list_namespaces(
+            &self,
+            options: crate::memory::ListNamespacesOptions,
+        ) -> Result<Vec<Namespace>, StoreError> {
+            self.inner.list_namespaces(options).await
+        }
+
+        async fn batch(
+            &self,
+            ops: Vec<crate::memory::StoreOp>,
+        ) -> Result<Vec<crate::memory::StoreOpResult>, StoreError> {
+            self.inner.batch(ops).await
+        }
+    }
+
+    /// **Scenario**: A batch failing with an embedding error is retried; once retries run
+    /// out the error is returned.
+    #[tokio::test]
+    async fn retries_failed_batches() {
+        let store = Arc::new(Flaky {
+            inner: InMemoryStore::new(),
+            failures: AtomicUsize::new(2),
+        });
+        let namespace = vec!["docs".to_string()];
+        let documents = [Document::new("a.md", "alpha")];
+        let ingestor =
+            Ingestor::new().with_retry_policy(RetryPolicy::fixed(2, Duration::from_millis(1)));
+
+        let report = ingestor
+            .ingest(store.as_ref(), &namespace, &documents)
+            .await
+            .unwrap();
+        assert_eq!((report.chunks, report.retries), (1, 2));
+
+        store.failures.store(3, Ordering::SeqCst);
+        let err = ingestor
+            .ingest(store.as_ref(), &namespace, &documents)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, IngestError::Store(StoreError::EmbeddingError(_))),
+            "{}",
+            err
+        );
+    }
+}
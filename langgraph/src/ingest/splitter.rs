@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use crate::tokens::TokenCounter;
+
+/// Characters per chunk when none is given.
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// Characters shared by consecutive chunks when none is given.
+pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+/// Separators tried in order: paragraphs, lines, sentences, words, characters.
+const SEPARATORS: &[&str] = &["\n\n", "\n", ". ", " ", ""];
+
+/// Splits text into chunks of at most `chunk_size` characters or tokens, consecutive chunks
+/// sharing up to `chunk_overlap` of them.
+///
+/// Text is cut at the coarsest boundary that yields pieces small enough (paragraphs, then
+/// lines, sentences, words, characters), and neighbouring pieces are merged back up to the
+/// chunk size, so chunks end at natural boundaries where possible. In token mode the size of
+/// a chunk is the sum of its pieces' token counts, which can differ slightly from the count of
+/// the joined text.
+///
+/// # Examples
+///
+/// ```
+/// use langgraph::ingest::TextSplitter;
+///
+/// let splitter = TextSplitter::characters(20, 0);
+/// let chunks = splitter.split("First paragraph.\n\nSecond paragraph.");
+/// assert_eq!(chunks, vec!["First paragraph.", "Second paragraph."]);
+/// ```
+#[derive(Clone)]
+pub struct TextSplitter {
+    chunk_size: usize,
+    chunk_overlap: usize,
+    counter: Option<Arc<dyn TokenCounter>>,
+}
+
+impl TextSplitter {
+    /// Chunks of at most `chunk_size` characters (at least 1), `chunk_overlap` shared (less
+    /// than the chunk size).
+    pub fn characters(chunk_size: usize, chunk_overlap: usize) -> Self {
+        let chunk_size = chunk_size.max(1);
+        Self {
+            chunk_size,
+            chunk_overlap: chunk_overlap.min(chunk_size - 1),
+            counter: None,
+        }
+    }
+
+    /// Chunks of at most `chunk_size` tokens as counted by `counter` (e.g.
+    /// [`BpeTokenCounter`](crate::BpeTokenCounter) for the embedding model), `chunk_overlap`
+    /// shared.
+    pub fn tokens(counter: Arc<dyn TokenCounter>, chunk_size: usize, chunk_overlap: usize) -> Self {
+        Self {
+            counter: Some(counter),
+            ..Self::characters(chunk_size, chunk_overlap)
+        }
+    }
+
+    /// Maximum chunk size, in characters or tokens.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// The chunks of `text`, trimmed, without empty ones.
+    pub fn split(&self, text: &str) -> Vec<String> {
+        let mut chunks = Vec::new();
+        self.split_into(text, SEPARATORS, &mut chunks);
+        chunks
+    }
+
+    fn len(&self, text: &str) -> usize {
+        match &self.counter {
+            Some(counter) => counter.count(text),
+            None => text.chars().count(),
+        }
+    }
+
+    /// Cuts `text` at the first separator it contains, recursing into pieces still too large.
+    fn split_into(&self, text: &str, separators: &[&str], chunks: &mut Vec<String>) {
+        let index = separators
+            .iter()
+            .position(|s| s.is_empty() || text.contains(s))
+            .unwrap_or(separators.len() - 1);
+        let (separator, finer) = (separators[index], &separators[index + 1..]);
+        let pieces: Vec<&str> = if separator.is_empty() {
+            text.char_indices()
+                .map(|(i, c)| &text[i..i + c.len_utf8()])
+                .collect()
+        } else {
+            text.split_inclusive(separator).collect()
+        };
+
+        let mut small = Vec::new();
+        for piece in pieces {
+            let len = self.len(piece);
+            if len <= self.chunk_size || finer.is_empty() {
+                small.push((piece, len));
+            } else {
+                self.merge(std::mem::take(&mut small), chunks);
+                self.split_into(piece, finer, chunks);
+            }
+        }
+        self.merge(small, chunks);
+    }
+
+    /// Joins consecutive pieces into chunks up to the chunk size, starting each new chunk with
+    /// the last pieces of the previous one, up to the overlap.
+    fn merge(&self, pieces: Vec<(&str, usize)>, chunks: &mut Vec<String>) {
+        let mut window: VecDeque<(&str, usize)> = VecDeque::new();
+        let mut total = 0;
+        for (piece, len) in pieces {
+            if total + len > self.chunk_size && !window.is_empty() {
+                push_chunk(&window, chunks);
+                while total > self.chunk_overlap || (total > 0 && total + len > self.chunk_size) {
+                    let (_, dropped) = window.pop_front().expect("total > 0");
+                    total -= dropped;
+                }
+            }
+            window.push_back((piece, len));
+            total += len;
+        }
+        push_chunk(&window, chunks);
+    }
+}
+
+impl Default for TextSplitter {
+    /// [`DEFAULT_CHUNK_SIZE`] characters with [`DEFAULT_CHUNK_OVERLAP`] shared.
+    fn default() -> Self {
+        Self::characters(DEFAULT_CHUNK_SIZE, DEFAULT_CHUNK_OVERLAP)
+    }
+}
+
+fn push_chunk(window: &VecDeque<(&str, usize)>, chunks: &mut Vec<String>) {
+    let chunk: String = window.iter().map(|(piece, _)| *piece).collect();
+    let chunk = chunk.trim();
+    if !chunk.is_empty() {
+        chunks.push(chunk.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApproxTokenCounter;
+
+    /// **Scenario**: Chunks never exceed the size, end at word boundaries, and consecutive
+    /// chunks share words up to the overlap.
+    #[test]
+    fn splits_at_words_with_overlap() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = TextSplitter::characters(15, 7).split(text);
+        assert!(
+            chunks.iter().all(|c| c.chars().count() <= 15),
+            "{:?}",
+            chunks
+        );
+        assert_eq!(chunks.first().map(String::as_str), Some("one two three"));
+        assert!(chunks.last().unwrap().ends_with("ten"));
+        for pair in chunks.windows(2) {
+            let last_word = pair[0].rsplit(' ').next().unwrap();
+            assert!(pair[1].starts_with(last_word), "{:?}", pair);
+        }
+    }
+
+    /// **Scenario**: Words longer than a chunk are cut into characters; multi-byte text is
+    /// split on character boundaries.
+    #[test]
+    fn cuts_long_words_on_char_boundaries() {
+        let chunks = TextSplitter::characters(4, 0).split("ééééééé ab");
+        assert_eq!(chunks, vec!["éééé", "ééé", "ab"]);
+        assert!(TextSplitter::default().split("  \n\n ").is_empty());
+    }
+
+    /// **Scenario**: In token mode the chunk size counts tokens, not characters.
+    #[test]
+    fn token_mode_counts_tokens() {
+        let counter = Arc::new(ApproxTokenCounter::default());
+        let text = "alpha beta gamma delta ".repeat(50);
+        let splitter = TextSplitter::tokens(counter.clone(), 40, 0);
+        let chunks = splitter.split(&text);
+        assert!(chunks.len() > 1);
+        assert!(
+            chunks.iter().all(|c| counter.count(c) <= 40),
+            "{:?}",
+            chunks
+        );
+        assert!(chunks[0].chars().count() > 40, "{:?}", chunks);
+    }
+}
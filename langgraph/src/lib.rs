@@ -23,15 +23,41 @@
 //! - **Tool Integration**: Extensible tool system with MCP support ([`ToolSource`], [`McpToolSource`]).
 //! - **Persistence**: Optional SQLite and LanceDB backends for long-term memory.
 //! - **Middleware**: Wrap node execution with custom async logic ([`NodeMiddleware`]).
+//! - **Callbacks**: Hooks on run, node, LLM call and tool call start and end
+//!   ([`CallbackHandler`]), registered on the graph or per run.
 //! - **Streaming**: Stream per-step states or node updates via [`CompiledStateGraph::stream`] with [`StreamMode`].
 //! - **Channels**: State update strategies ([`LastValue`], [`EphemeralValue`], [`Topic`], [`BinaryOperatorAggregate`],
 //!   [`NamedBarrierValue`]); custom merge via [`StateUpdater`] and [`FieldBasedUpdater`].
 //! - **Runtime Context**: Custom runtime context, store access, and managed values ([`RunContext`], [`ManagedValue`]).
 //! - **Cache, Retry, Interrupts**: In-memory caching ([`InMemoryCache`]), retry policies ([`RetryPolicy`]),
 //!   human-in-the-loop ([`InterruptHandler`]).
-//! - **Graph Visualization**: [`generate_dot`], [`generate_text`].
+//! - **Structured Output**: JSON-schema replies ([`JsonSchema`]) parsed into typed values with
+//!   re-prompting ([`StructuredOutputNode`]).
+//! - **Graph Visualization**: [`generate_mermaid`], [`generate_dot`], [`generate_dot_with_stats`]
+//!   (runtime heat map via [`RunStatsMiddleware`]), [`generate_text`]; [`render_graph`] picks
+//!   one by [`GraphFormat`].
+//! - **Metrics**: Node durations, LLM latency, tool calls, checkpoint latency and tokens via
+//!   [`metrics::set_recorder`]; Prometheus text format with [`PrometheusRecorder`].
+//! - **Profiling**: Wall time, CPU time and allocations per node and checkpoint operation,
+//!   aggregated across runs ([`Profiler`], [`GraphProfile`]) and exported as folded stacks or
+//!   pprof.
+//! - **Graph Analysis**: [`analyze_graph`] reports unreachable nodes, dead conditional branches
+//!   and, with [`GraphAnalysis::with_tools`], tools no prompt mentions.
+//! - **Skills**: Prompt fragments, tool configuration and few-shot examples packaged as TOML
+//!   manifests and combined at runtime ([`SkillSet`]).
+//! - **Usage Quotas**: Tokens per model per day kept in a [`Store`], with daily caps per
+//!   deployment and per user ([`UsageQuota`]).
 //!
 //! Feature flag: `lance` — LanceDB vector store for long-term memory (optional; heavy dependency).
+//! Feature flag: `postgres` — `PostgresSaver` / `PostgresStore` for persistence shared by
+//! several processes.
+//! Feature flag: `redis` — `RedisSaver`, a checkpointer shared by several processes with
+//! optional TTL.
+//! Feature flag: `otel` — `otel::otlp_layer` exports run, node, LLM and tool spans to an
+//! OTLP collector, one trace per run.
+//!
+//! [`FeatureSet::compiled`] reports the features of a build at runtime; config needing a
+//! missing one fails when the runner is built, with [`FeatureMissing`].
 //!
 //! ## Main modules
 //!
@@ -41,15 +67,26 @@
 //! - [`state`]: [`ReActState`], [`ToolCall`], [`ToolResult`] — state and tool types for ReAct.
 //! - [`llm`]: [`LlmClient`] trait, [`MockLlm`], [`ChatOpenAI`].
 //! - [`memory`]: Checkpointing ([`Checkpointer`], [`MemorySaver`], [`SqliteSaver`]), [`Store`]; optional LanceDB.
-//! - [`tool_source`]: [`ToolSource`], [`ToolSpec`]; MCP ([`McpToolSource`]); [`WebToolsSource`], [`BashToolsSource`].
+//! - [`tool_source`]: [`ToolSource`], [`ToolSpec`]; MCP ([`McpToolSource`], [`McpServerConfig`]); [`WebToolsSource`], [`BashToolsSource`].
 //! - [`traits`]: Core [`Agent`] trait — implement for custom agents.
 //! - [`message`]: [`Message`] (System / User / Assistant).
 //! - [`stream`]: [`StreamWriter`], [`StreamEvent`], [`StreamMode`] for graph runs.
+//! - [`structured_output`]: [`StructuredOutputNode`], [`StructuredOutputState`] — typed extraction.
+//! - [`studio`]: [`studio::StudioExporter`] — threads as LangGraph Studio / langgraph-sdk JSON.
+//! - [`tokens`]: [`TokenCounter`], [`count_tokens`], [`count_messages_tokens`] — offline token counts.
+//! - [`ingest`]: [`ingest::TextSplitter`], [`ingest::Ingestor`] — documents chunked and embedded
+//!   into a [`Store`] for retrieval.
 //! - [`config`]: Config summaries ([`RunConfigSummary`], [`build_config_summary`]).
 //! - [`cache`]: [`Cache`], [`InMemoryCache`].
+//! - [`callbacks`]: [`CallbackHandler`], [`Callbacks`] — hooks for custom logging and analytics.
+//! - [`skill`]: [`Skill`], [`SkillSet`] — prompt fragments, tools and examples loaded from TOML.
+//! - [`chaos`]: Fault injection for resilience tests ([`chaos::FaultyLlm`],
+//!   [`chaos::FaultyToolSource`], [`chaos::FaultInjectionMiddleware`]).
 //! - [`channels`]: [`Channel`], [`LastValue`], [`Topic`], etc.; [`StateUpdater`], [`FieldBasedUpdater`].
 //! - [`managed`]: [`ManagedValue`], [`IsLastStep`].
-//! - [`tools`]: [`register_mcp_tools`], [`McpToolAdapter`].
+//! - [`tools`]: [`register_mcp_tools`], [`register_mcp_tools_with_prefix`], [`McpToolAdapter`].
+//! - [`metrics`]: [`MetricsRecorder`] for node, LLM, tool and checkpoint timings and tokens;
+//!   [`PrometheusRecorder`].
 //! - [`openai_sse`]: OpenAI-compatible SSE ([`StreamToSse`], [`ChatCompletionChunk`], [`parse_chat_request`]).
 //!
 //! Key types are re-exported at crate root: `use langgraph::{Agent, StateGraph, Message, ReActState};`.
@@ -78,7 +115,7 @@
 //!     async fn run(&self, state: Self::State) -> Result<Self::State, AgentError> {
 //!         let mut messages = state.messages;
 //!         if let Some(Message::User(s)) = messages.last() {
-//!             messages.push(Message::Assistant(s.clone()));
+//!             messages.push(Message::assistant(s.clone()));
 //!         }
 //!         Ok(MyState { messages })
 //!     }
@@ -92,7 +129,7 @@
 //! let agent = EchoAgent;
 //! match agent.run(state).await {
 //!     Ok(s) => {
-//!         if let Some(Message::Assistant(content)) = s.messages.last() {
+//!         if let Some(Message::Assistant { content, .. }) = s.messages.last() {
 //!             println!("{}", content);
 //!         }
 //!     }
@@ -109,19 +146,30 @@
 //! `memory_checkpoint`, `memory_persistence`, `openai_embedding`, `state_graph_echo`.
 
 pub mod cache;
+pub mod callbacks;
 pub mod channels;
+pub mod chaos;
 pub mod config;
 pub mod error;
+pub mod features;
 pub mod graph;
+pub mod ingest;
 pub mod llm;
 pub mod managed;
 pub mod memory;
 pub mod message;
+pub mod metrics;
 pub mod openai_sse;
+#[cfg(feature = "otel")]
+pub mod otel;
 pub mod react;
 pub mod react_builder;
+pub mod skill;
 pub mod state;
 pub mod stream;
+pub mod structured_output;
+pub mod studio;
+pub mod tokens;
 pub mod tool_source;
 pub mod tools;
 pub mod traits;
@@ -136,56 +184,97 @@ pub use channels::{
     NamedBarrierValue, StateUpdater, Topic,
 };
 pub use error::AgentError;
+pub use features::{FeatureMissing, FeatureSet};
 pub use graph::{
-    generate_dot, generate_text, log_graph_complete, log_graph_error, log_graph_start,
-    log_node_complete, log_node_start, log_state_update, CompilationError, CompiledStateGraph,
-    DefaultInterruptHandler, GraphInterrupt, Interrupt, InterruptHandler, LoggingNodeMiddleware,
-    NameNode, Next, Node, NodeMiddleware, RetryPolicy, RunContext, Runtime, StateGraph, END,
-    START,
+    analyze_graph, generate_dot, generate_dot_with_stats, generate_mermaid, generate_text,
+    log_graph_complete, log_graph_error, log_graph_start, log_node_complete, log_node_start,
+    log_state_update, render_graph, BudgetGovernor, Command, CompilationError, CompiledStateGraph,
+    CountingAllocator, DeadBranch, DefaultInterruptHandler, FrameStats, GovernorDecision,
+    GraphAnalysis, GraphFormat, GraphInterrupt, GraphProfile, GraphRunStats, InjectError, Injector,
+    Interrupt, InterruptHandler, LoggingNodeMiddleware, NameNode, Next, Node, NodeMiddleware,
+    NodeRunStats, ProfileError, ProfileMetric, Profiler, ResumeHandler, RetryPolicy, RunContext,
+    RunGovernor, RunProgress, RunStatsMiddleware, Runtime, StateGraph, TokenPricing, UsageMeter,
+    END, START,
 };
 pub use llm::ChatOpenAI;
-pub use llm::{LlmClient, LlmResponse, LlmUsage, MockLlm, ToolChoiceMode};
-pub use managed::{IsLastStep, ManagedValue};
+pub use llm::{
+    AdaptiveTimeout, DeltaSanitizer, DeltaSanitizerConfig, FailoverPolicy, HttpClientConfig,
+    HttpClientError, JsonSchema, LlmClient, LlmError, LlmErrorKind, LlmResponse,
+    LlmResponseMetadata, LlmUsage, MockLlm, SamplingParams, ToolChoiceMode,
+};
+pub use managed::{IsLastStep, ManagedValue, IS_LAST_STEP};
 pub use memory::OpenAIEmbedder;
 pub use memory::{
     Checkpoint, CheckpointError, CheckpointListItem, CheckpointMetadata, CheckpointSource,
-    Checkpointer, InMemoryStore, JsonSerializer, MemorySaver, Namespace, RunnableConfig, Store,
-    StoreError, StoreSearchHit,
+    CheckpointRetention, Checkpointer, InMemoryStore, JsonSerializer, MemorySaver, Namespace,
+    ReadOnlySwitch, RunnableConfig, Store, StoreError, StoreSearchHit, ThreadInfo,
 };
-pub use memory::Embedder;
+pub use memory::{Embedder, EmbedderPin, EmbeddingCache};
 #[cfg(feature = "lance")]
 pub use memory::LanceStore;
+#[cfg(feature = "postgres")]
+pub use memory::{PostgresSaver, PostgresStore};
+#[cfg(feature = "redis")]
+pub use memory::RedisSaver;
 pub use memory::{SqliteSaver, SqliteStore};
+pub use memory::{EncryptedCheckpointer, EncryptedStore, KeyService, LocalKeyService};
+pub use memory::{DbRouter, RoutedCheckpointer, RoutedStore, SqliteTenants, TenantOpener};
 pub use message::Message;
+pub use callbacks::{CallbackHandler, Callbacks};
+pub use skill::{Skill, SkillError, SkillSet};
+pub use metrics::{CheckpointOp, MetricsRecorder, PrometheusRecorder};
 pub use react::{
-    build_react_initial_state, run_react_graph, run_react_graph_stream, tools_condition, ActNode,
-    ErrorHandlerFn, HandleToolErrors, ObserveNode, ReactRunner, RunError, ThinkNode,
-    ToolsConditionResult, WithNodeLogging, DEFAULT_EXECUTION_ERROR_TEMPLATE,
-    DEFAULT_TOOL_ERROR_TEMPLATE, REACT_SYSTEM_PROMPT,
+    build_react_initial_state, render_error_template, run_react_graph, run_react_graph_stream,
+    tools_condition, ActNode, Assignment, BudgetPolicy, CompressorChain, DropOldToolResults,
+    ErrorHandlerFn, Experiment, ExperimentVariant, ExtractedMemory, HandleToolErrors,
+    HistoryCompressor, LlmOutcomeClassifier, LlmReranker, MemoryWriter, ModelPriceTable,
+    ObserveNode, OutcomeClassifier, PinnedMessages, PromptProfile, QuotaExceeded, QuotaScope,
+    ReactRunner, Reranker, RetrieveNode, Retriever, RuleOutcomeClassifier, RunError, RunOutcome,
+    SamplingSchedule, SummarizeOldTurns, ThinkNode, ToolAttachments, ToolsConditionResult,
+    UsageQuota, Variant, WithNodeLogging, DEFAULT_EXECUTION_ERROR_TEMPLATE,
+    DEFAULT_EXPERIMENT_SHARE_B, DEFAULT_MAX_PARALLEL_TOOL_CALLS, DEFAULT_TOOL_ERROR_TEMPLATE,
+    MAX_REACT_TURNS, NEED_MORE_STEPS_MESSAGE, OMITTED_TOOL_RESULT, REACT_SYSTEM_PROMPT,
+    SUMMARY_PREFIX,
 };
 pub use react_builder::{
-    build_react_run_context, build_react_runner, build_react_runner_with_openai, BuildRunnerError,
-    ReactBuildConfig, ReactRunContext,
+    build_react_run_context, build_react_run_context_with_read_only, build_react_runner,
+    build_react_runner_with_openai, BuildRunnerError, ReactBuildConfig, ReactRunContext,
 };
 pub use state::{ReActState, ToolCall, ToolResult};
 pub use stream::{
-    CheckpointEvent, MessageChunk, StreamEvent, StreamMetadata, StreamMode, StreamWriter,
-    ToolStreamWriter,
+    CheckpointEvent, CustomEvent, CustomEventError, CustomEventRegistry, MessageChunk,
+    MessagesAccessor, StreamEvent, StreamMetadata, StreamMode, StreamWriter, ToolCallChunk,
+    ToolStreamWriter, UiEventsAccessor, UiEventsRetention,
+};
+pub use structured_output::{StructuredOutputNode, StructuredOutputState};
+pub use tokens::{
+    count_messages_tokens, count_tokens, ApproxTokenCounter, BpeTokenCounter, TokenCounter,
 };
-pub use tool_source::McpToolSource;
+pub use tool_source::{McpConfigError, McpServerConfig, McpToolSource, McpTransport};
 pub use tool_source::{
-    BashToolsSource, MemoryToolsSource, MockToolSource, ShortTermMemoryToolSource, StoreToolSource,
-    ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec, TOOL_BASH,
-    TOOL_GET_RECENT_MESSAGES, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
-    TOOL_WEB_FETCHER, WebToolsSource,
+    render_tool_docs, AllowlistToolSource, RefreshingToolSource, ToolAllowlist, ToolCatalog,
+    ToolDocsFormat, UiEventSink,
+};
+#[allow(deprecated)]
+pub use tool_source::{
+    BashToolsSource, CachedToolSource, FsToolSource, MemoryToolkit, MemoryToolsSource,
+    MockToolSource, NotifyToolSource, ShortTermMemoryToolSource, StoreToolSource, ToolCallContent,
+    ToolCallContext, ToolSource, ToolSourceError, ToolSpec, TOOL_BASH, TOOL_GET_RECENT_MESSAGES,
+    TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES, TOOL_WEB_FETCHER,
+    ToolState, ToolStateStore, WebToolsSource,
 };
 pub use openai_sse::{
-    parse_chat_request, ChatCompletionChunk, ChatCompletionRequest, ChatMessage, ChunkMeta,
-    ChunkUsage, DeltaToolCall, MessageContent, ParseError, ParsedChatRequest, StreamOptions,
-    StreamToSse, write_sse_line,
+    parse_chat_request, ChatCompletion, ChatCompletionChunk, ChatCompletionRequest, ChatMessage,
+    ChunkMeta, ChunkUsage, Citation, DeltaToolCall, MessageContent, ParseError,
+    ParsedChatRequest, RequestMetadata, StreamOptions, StreamToSse, write_sse_line,
+};
+pub use tools::{
+    register_mcp_tools, register_mcp_tools_with_prefix, BashTool, DuplicateAction, McpToolAdapter,
+    MemoryDedup, MemoryValueSchema, RememberOutcome, TimeTool,
 };
-pub use tools::{register_mcp_tools, BashTool, McpToolAdapter};
 pub use traits::Agent;
+/// Token for cancelling a run (`RunContext::with_cancellation`), re-exported from tokio-util.
+pub use tokio_util::sync::CancellationToken;
 
 /// When running `cargo test -p langgraph`, initializes tracing from `RUST_LOG` so that
 /// unit tests in `src/**` (e.g. `openai.rs` `mod tests`) can print logs with `--nocapture`.
@@ -0,0 +1,214 @@
+//! Sanitizes streamed content deltas before they reach the stream channel.
+//!
+//! Some OpenAI-compatible backends duplicate chunks, echo the whole reply as a final delta,
+//! emit `\r\n` line endings, or interleave whitespace-only deltas; printed as-is this
+//! stutters in the CLI and SSE clients. [`DeltaSanitizer`] filters deltas according to a
+//! per-provider [`DeltaSanitizerConfig`].
+//!
+//! **Interaction**: Set on [`ChatOpenAI`](super::ChatOpenAI) via `with_delta_sanitizer`;
+//! applied in `invoke_stream` to every content delta, and the sanitized text is also what
+//! ends up in `LlmResponse::content`.
+
+/// Which sanitation steps to apply to streamed content deltas.
+///
+/// All steps are off by default. Use [`standard`](Self::standard) for safe fixes that never
+/// drop real text, and [`strict`](Self::strict) for backends known to duplicate chunks
+/// (dedupe can drop legitimately repeated tokens, so enable it only where needed).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeltaSanitizerConfig {
+    /// Drop empty deltas.
+    pub drop_empty: bool,
+    /// Convert `\r\n` and lone `\r` to `\n` (also across delta boundaries).
+    pub normalize_newlines: bool,
+    /// Drop a delta identical to the previous non-whitespace delta.
+    pub dedupe_consecutive: bool,
+    /// Drop a delta that repeats the entire text emitted so far (final "echo" of the reply).
+    pub suppress_echo: bool,
+    /// Buffer whitespace-only deltas, drop identical repeats of spaces/tabs, and attach the
+    /// buffered whitespace to the next text delta.
+    pub collapse_whitespace: bool,
+}
+
+impl DeltaSanitizerConfig {
+    /// Drops empty deltas and normalizes newlines.
+    pub fn standard() -> Self {
+        Self {
+            drop_empty: true,
+            normalize_newlines: true,
+            ..Self::default()
+        }
+    }
+
+    /// All steps enabled; for backends that duplicate or echo chunks.
+    pub fn strict() -> Self {
+        Self {
+            drop_empty: true,
+            normalize_newlines: true,
+            dedupe_consecutive: true,
+            suppress_echo: true,
+            collapse_whitespace: true,
+        }
+    }
+
+    /// True when no step is enabled.
+    pub fn is_noop(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::str::FromStr for DeltaSanitizerConfig {
+    type Err = String;
+
+    /// Parses a preset name: `off`, `standard`, or `strict`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "none" | "" => Ok(Self::default()),
+            "standard" | "on" => Ok(Self::standard()),
+            "strict" => Ok(Self::strict()),
+            _ => Err(format!(
+                "unknown delta sanitizer preset: {} (use off, standard, or strict)",
+                s
+            )),
+        }
+    }
+}
+
+/// Stateful filter for one streamed completion. Create one per `invoke_stream` call.
+#[derive(Debug)]
+pub struct DeltaSanitizer {
+    config: DeltaSanitizerConfig,
+    /// Last text delta passed through (before whitespace prefixing); used for dedupe.
+    last: Option<String>,
+    /// Everything emitted so far; used for echo suppression.
+    emitted: String,
+    /// Whitespace-only deltas waiting for the next text delta.
+    pending_ws: String,
+    /// Previous delta ended with `\r` (already emitted as `\n`); skip a leading `\n`.
+    pending_cr: bool,
+}
+
+impl DeltaSanitizer {
+    /// Creates a sanitizer for one stream.
+    pub fn new(config: DeltaSanitizerConfig) -> Self {
+        Self {
+            config,
+            last: None,
+            emitted: String::new(),
+            pending_ws: String::new(),
+            pending_cr: false,
+        }
+    }
+
+    /// Feeds one raw delta; returns the text to emit, or `None` when it was filtered out.
+    pub fn push(&mut self, delta: &str) -> Option<String> {
+        let mut text = delta.to_string();
+        if self.config.normalize_newlines {
+            text = self.normalize_newlines(&text);
+        }
+        if text.is_empty() {
+            return if self.config.drop_empty { None } else { Some(text) };
+        }
+        if self.config.collapse_whitespace && text.trim().is_empty() {
+            let repeated_blank = !text.contains('\n') && self.pending_ws.ends_with(&text);
+            if !repeated_blank {
+                self.pending_ws.push_str(&text);
+            }
+            return None;
+        }
+        if self.config.dedupe_consecutive && self.last.as_deref() == Some(text.as_str()) {
+            return None;
+        }
+        if self.config.suppress_echo && !self.emitted.is_empty() && text == self.emitted {
+            return None;
+        }
+        self.last = Some(text.clone());
+        let out = if self.pending_ws.is_empty() {
+            text
+        } else {
+            std::mem::take(&mut self.pending_ws) + &text
+        };
+        self.emitted.push_str(&out);
+        Some(out)
+    }
+
+    /// Flushes buffered whitespace at the end of the stream.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.pending_ws.is_empty() {
+            return None;
+        }
+        let out = std::mem::take(&mut self.pending_ws);
+        self.emitted.push_str(&out);
+        Some(out)
+    }
+
+    /// All text emitted so far (the sanitized reply).
+    pub fn content(&self) -> &str {
+        &self.emitted
+    }
+
+    fn normalize_newlines(&mut self, text: &str) -> String {
+        let text = if self.pending_cr {
+            text.strip_prefix('\n').unwrap_or(text)
+        } else {
+            text
+        };
+        self.pending_cr = text.ends_with('\r');
+        text.replace("\r\n", "\n").replace('\r', "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: DeltaSanitizerConfig, deltas: &[&str]) -> Vec<String> {
+        let mut s = DeltaSanitizer::new(config);
+        let mut out: Vec<String> = deltas.iter().filter_map(|d| s.push(d)).collect();
+        out.extend(s.finish());
+        out
+    }
+
+    /// **Scenario**: Default config passes every delta through unchanged.
+    #[test]
+    fn default_config_is_passthrough() {
+        let cfg = DeltaSanitizerConfig::default();
+        assert!(cfg.is_noop());
+        assert_eq!(run(cfg, &["a", "", "a", "\r\n"]), vec!["a", "", "a", "\r\n"]);
+    }
+
+    /// **Scenario**: Standard drops empty deltas and normalizes CRLF, including across deltas.
+    #[test]
+    fn standard_drops_empty_and_normalizes_newlines() {
+        let out = run(DeltaSanitizerConfig::standard(), &["a\r\nb", "", "c\r", "\nd", "e\rf"]);
+        assert_eq!(out.concat(), "a\nbc\nde\nf");
+    }
+
+    /// **Scenario**: Strict dedupes consecutive chunks, suppresses a final echo, collapses repeated spaces.
+    #[test]
+    fn strict_dedupes_echo_and_whitespace() {
+        let out = run(
+            DeltaSanitizerConfig::strict(),
+            &["Hello", "Hello", " ", " ", "world", "\n", "\n", "!", "Hello world\n\n!"],
+        );
+        assert_eq!(out, vec!["Hello", " world", "\n\n!"]);
+    }
+
+    /// **Scenario**: Trailing whitespace is flushed by finish().
+    #[test]
+    fn finish_flushes_pending_whitespace() {
+        let mut s = DeltaSanitizer::new(DeltaSanitizerConfig::strict());
+        assert_eq!(s.push("ok").as_deref(), Some("ok"));
+        assert_eq!(s.push("\n"), None);
+        assert_eq!(s.finish().as_deref(), Some("\n"));
+        assert_eq!(s.content(), "ok\n");
+    }
+
+    /// **Scenario**: Presets parse from strings; unknown names are rejected.
+    #[test]
+    fn config_from_str_presets() {
+        assert_eq!("off".parse::<DeltaSanitizerConfig>().unwrap(), DeltaSanitizerConfig::default());
+        assert_eq!("Standard".parse::<DeltaSanitizerConfig>().unwrap(), DeltaSanitizerConfig::standard());
+        assert_eq!("strict".parse::<DeltaSanitizerConfig>().unwrap(), DeltaSanitizerConfig::strict());
+        assert!("loud".parse::<DeltaSanitizerConfig>().is_err());
+    }
+}
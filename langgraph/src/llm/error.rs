@@ -0,0 +1,288 @@
+//! Provider-agnostic LLM error classification with retry hints.
+//!
+//! Providers fail in different shapes: OpenAI answers 429 with `Retry-After`, Azure throttles
+//! with "Please retry after N seconds", self-hosted servers report "CUDA out of memory" or
+//! "model is loading". [`LlmError`] maps all of them to one [`LlmErrorKind`] plus an optional
+//! `retry_after` hint, so callers can decide whether and when to retry without parsing text.
+//!
+//! **Interaction**: `ChatOpenAI` returns `AgentError::Llm`; the graph retry loop
+//! (`RetryPolicy`) skips non-retryable errors and waits at least `retry_after`; the server
+//! answers 429 / 503 with a `Retry-After` header.
+
+use std::fmt;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// What went wrong, independent of the provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LlmErrorKind {
+    /// Too many requests or tokens per minute (HTTP 429, Azure throttling).
+    RateLimited,
+    /// Billing quota used up; retrying does not help until the quota is raised.
+    QuotaExceeded,
+    /// Provider temporarily unavailable: overloaded, model loading, 502/503/504/529,
+    /// connection refused.
+    Unavailable,
+    /// Self-hosted backend ran out of (GPU) memory for the request.
+    ResourceExhausted,
+    /// Request timed out.
+    Timeout,
+    /// Anything else (bad request, authentication, unknown).
+    Other,
+}
+
+impl fmt::Display for LlmErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            LlmErrorKind::RateLimited => "rate limited",
+            LlmErrorKind::QuotaExceeded => "quota exceeded",
+            LlmErrorKind::Unavailable => "provider unavailable",
+            LlmErrorKind::ResourceExhausted => "out of memory",
+            LlmErrorKind::Timeout => "timed out",
+            LlmErrorKind::Other => "request failed",
+        };
+        f.write_str(s)
+    }
+}
+
+/// LLM call failure classified by [`LlmErrorKind`], with the provider's retry hint.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("{kind}: {message}")]
+pub struct LlmError {
+    /// Failure category.
+    pub kind: LlmErrorKind,
+    /// HTTP status of the provider response, when known.
+    pub status: Option<u16>,
+    /// How long the provider asked to wait before retrying (`Retry-After` or message hint).
+    pub retry_after: Option<Duration>,
+    /// Provider error message.
+    pub message: String,
+}
+
+impl LlmError {
+    /// Creates an error of `kind` with no status or retry hint.
+    pub fn new(kind: LlmErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            status: None,
+            retry_after: None,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the provider's retry hint.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Classifies a raw HTTP error response: `status`, the `Retry-After` header value (seconds)
+    /// if present, and the response body or message. A hint in the message (e.g. "Please
+    /// retry after 20 seconds") is used when the header is missing.
+    pub fn from_http(status: u16, retry_after_header: Option<&str>, body: &str) -> Self {
+        let mut err = Self::classify(Some(status), body);
+        if let Some(secs) = retry_after_header.and_then(|h| h.trim().parse::<f64>().ok()) {
+            if secs.is_finite() && secs >= 0.0 {
+                err.retry_after = Some(Duration::from_secs_f64(secs));
+            }
+        }
+        err
+    }
+
+    /// Classifies an error from its message and optional HTTP status.
+    pub fn classify(status: Option<u16>, message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+
+        let kind = if has(&["insufficient_quota", "exceeded your current quota"]) {
+            LlmErrorKind::QuotaExceeded
+        } else if status == Some(429)
+            || has(&[
+                "rate limit",
+                "rate_limit",
+                "ratelimit",
+                "too many requests",
+                "throttl",
+            ])
+        {
+            LlmErrorKind::RateLimited
+        } else if has(&[
+            "out of memory",
+            "outofmemory",
+            "insufficient memory",
+            "failed to allocate",
+            "kv cache is full",
+        ]) {
+            LlmErrorKind::ResourceExhausted
+        } else if matches!(status, Some(502 | 503 | 504 | 529))
+            || has(&[
+                "overloaded",
+                "service unavailable",
+                "temporarily unavailable",
+                "model is loading",
+                "loading model",
+                "bad gateway",
+                "connection refused",
+                "error sending request",
+            ])
+        {
+            LlmErrorKind::Unavailable
+        } else if status == Some(408) || has(&["timed out", "timeout"]) {
+            LlmErrorKind::Timeout
+        } else {
+            LlmErrorKind::Other
+        };
+
+        Self {
+            kind,
+            status,
+            retry_after: parse_retry_hint(&lower),
+            message: message.to_string(),
+        }
+    }
+
+    /// Classifies an async-openai error (OpenAI, Azure and OpenAI-compatible servers).
+    /// The HTTP status is taken from the underlying `reqwest::Error` when there is one.
+    pub fn from_openai(err: &async_openai::error::OpenAIError) -> Self {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+        let mut status = None;
+        let mut timed_out = false;
+        while let Some(e) = source {
+            if let Some(re) = e.downcast_ref::<reqwest::Error>() {
+                status = re.status().map(|s| s.as_u16());
+                timed_out = re.is_timeout();
+                break;
+            }
+            source = e.source();
+        }
+        let mut classified = Self::classify(status, &err.to_string());
+        if timed_out && classified.kind == LlmErrorKind::Other {
+            classified.kind = LlmErrorKind::Timeout;
+        }
+        classified
+    }
+
+    /// Whether retrying may succeed. Quota errors and 4xx client errors (bad request,
+    /// authentication) are permanent; unknown failures without a status are retried.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            LlmErrorKind::QuotaExceeded => false,
+            LlmErrorKind::Other => !matches!(self.status, Some(400..=499)),
+            _ => true,
+        }
+    }
+
+    /// HTTP status a server should answer with: 429 for rate limits and quota, 504 for
+    /// timeouts, 503 for other temporary failures, 502 for permanent provider errors.
+    pub fn http_status(&self) -> u16 {
+        match self.kind {
+            LlmErrorKind::RateLimited | LlmErrorKind::QuotaExceeded => 429,
+            LlmErrorKind::Unavailable | LlmErrorKind::ResourceExhausted => 503,
+            LlmErrorKind::Timeout => 504,
+            LlmErrorKind::Other if self.is_retryable() => 503,
+            LlmErrorKind::Other => 502,
+        }
+    }
+}
+
+/// Finds a wait hint in a lowercased provider message: "retry after 20 seconds" (Azure),
+/// "try again in 1.5s" / "try again in 250ms" (OpenAI).
+fn parse_retry_hint(lower: &str) -> Option<Duration> {
+    for marker in ["retry after ", "try again in "] {
+        let Some(pos) = lower.find(marker) else {
+            continue;
+        };
+        let rest = &lower[pos + marker.len()..];
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let Ok(value) = rest[..number_len].trim_end_matches('.').parse::<f64>() else {
+            continue;
+        };
+        let unit = rest[number_len..].trim_start();
+        let secs = if unit.starts_with("ms") {
+            value / 1000.0
+        } else if unit.starts_with('m') && !unit.starts_with("mi") {
+            value * 60.0
+        } else {
+            value
+        };
+        if secs.is_finite() && secs >= 0.0 {
+            return Some(Duration::from_secs_f64(secs));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: OpenAI 429 with Retry-After header is a retryable rate limit with the hint.
+    #[test]
+    fn from_http_429_uses_retry_after_header() {
+        let err = LlmError::from_http(429, Some("20"), r#"{"error":{"message":"slow down"}}"#);
+        assert_eq!(err.kind, LlmErrorKind::RateLimited);
+        assert_eq!(err.retry_after, Some(Duration::from_secs(20)));
+        assert!(err.is_retryable());
+        assert_eq!(err.http_status(), 429);
+    }
+
+    /// **Scenario**: Azure and OpenAI throttling messages carry their wait hint in the text.
+    #[test]
+    fn classify_reads_retry_hint_from_message() {
+        let azure = LlmError::classify(
+            None,
+            "Requests to the ChatCompletions_Create Operation have exceeded call rate limit. \
+             Please retry after 7 seconds.",
+        );
+        assert_eq!(azure.kind, LlmErrorKind::RateLimited);
+        assert_eq!(azure.retry_after, Some(Duration::from_secs(7)));
+
+        let openai = LlmError::classify(
+            None,
+            "Rate limit reached for gpt-4o on tokens per min. Please try again in 250ms.",
+        );
+        assert_eq!(openai.retry_after, Some(Duration::from_millis(250)));
+
+        let seconds = LlmError::classify(Some(429), "Please try again in 1.5s.");
+        assert_eq!(seconds.retry_after, Some(Duration::from_millis(1500)));
+    }
+
+    /// **Scenario**: Local OOM, overload and quota errors map to their kinds and statuses.
+    #[test]
+    fn classify_maps_provider_failures_to_kinds() {
+        let oom = LlmError::classify(Some(500), "CUDA out of memory. Tried to allocate 2.00 GiB");
+        assert_eq!(oom.kind, LlmErrorKind::ResourceExhausted);
+        assert_eq!(oom.http_status(), 503);
+
+        let overloaded = LlmError::classify(Some(529), "Overloaded");
+        assert_eq!(overloaded.kind, LlmErrorKind::Unavailable);
+        assert!(overloaded.is_retryable());
+
+        let quota = LlmError::classify(Some(429), "You exceeded your current quota");
+        assert_eq!(quota.kind, LlmErrorKind::QuotaExceeded);
+        assert!(!quota.is_retryable());
+        assert_eq!(quota.http_status(), 429);
+
+        let auth = LlmError::classify(Some(401), "Incorrect API key provided");
+        assert_eq!(auth.kind, LlmErrorKind::Other);
+        assert!(!auth.is_retryable());
+        assert_eq!(auth.http_status(), 502);
+
+        let unknown = LlmError::classify(None, "something odd");
+        assert!(unknown.is_retryable());
+        assert!(unknown.retry_after.is_none());
+    }
+
+    /// **Scenario**: Display names the kind and keeps the provider message.
+    #[test]
+    fn display_includes_kind_and_message() {
+        let err = LlmError::new(LlmErrorKind::Timeout, "OpenAI request timed out after 5s");
+        assert_eq!(
+            err.to_string(),
+            "timed out: OpenAI request timed out after 5s"
+        );
+    }
+}
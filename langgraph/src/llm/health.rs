@@ -0,0 +1,117 @@
+//! Latency-aware request timeouts for LLM providers (especially self-hosted ones).
+//!
+//! Self-hosted backends (vLLM, llama.cpp, Ollama behind an OpenAI-compatible base) can take
+//! minutes on the first request while weights load, then answer in seconds. A fixed timeout
+//! is either too short for the cold start or too long for detecting a hung server.
+//! [`AdaptiveTimeout`] starts at `max` and, once latencies are observed, shrinks to
+//! `ewma × multiplier` clamped to `[min, max]`.
+//!
+//! **Interaction**: Set on [`ChatOpenAI`](super::ChatOpenAI) via `with_adaptive_timeout`; it
+//! bounds a streamed reply as a whole. `ChatOpenAI::probe_health` has its own, shorter timeout.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Weight of the newest sample in the exponentially weighted moving average.
+const EWMA_ALPHA: f64 = 0.3;
+
+/// Request timeout that adapts to observed provider latency.
+///
+/// No observation yet ("cold"): [`current`](Self::current) returns `max`. After each
+/// successful call, [`observe`](Self::observe) updates an EWMA of latency and the timeout
+/// becomes `ewma × multiplier`, clamped to `[min, max]`.
+#[derive(Debug)]
+pub struct AdaptiveTimeout {
+    min: Duration,
+    max: Duration,
+    multiplier: f64,
+    ewma_secs: Mutex<Option<f64>>,
+}
+
+impl AdaptiveTimeout {
+    /// Creates an adaptive timeout bounded by `min` and `max` (multiplier 4).
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            multiplier: 4.0,
+            ewma_secs: Mutex::new(None),
+        }
+    }
+
+    /// Sets how many times the average latency a request may take before timing out.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Timeout to apply to the next request.
+    pub fn current(&self) -> Duration {
+        match self.ewma_secs.lock().ok().and_then(|g| *g) {
+            Some(avg) => Duration::from_secs_f64(avg * self.multiplier).clamp(self.min, self.max),
+            None => self.max,
+        }
+    }
+
+    /// Upper bound; used for cold starts.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// True until the first latency has been observed.
+    pub fn is_cold(&self) -> bool {
+        self.ewma_secs.lock().map(|g| g.is_none()).unwrap_or(true)
+    }
+
+    /// Records the latency of a successful request.
+    pub fn observe(&self, latency: Duration) {
+        if let Ok(mut g) = self.ewma_secs.lock() {
+            let sample = latency.as_secs_f64();
+            *g = Some(match *g {
+                Some(avg) => EWMA_ALPHA * sample + (1.0 - EWMA_ALPHA) * avg,
+                None => sample,
+            });
+        }
+    }
+}
+
+impl Default for AdaptiveTimeout {
+    /// 30s minimum, 300s maximum (enough for most cold model loads).
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30), Duration::from_secs(300))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Cold timeout is max; after observations it follows ewma × multiplier within bounds.
+    #[test]
+    fn adaptive_timeout_starts_at_max_then_adapts() {
+        let t = AdaptiveTimeout::new(Duration::from_secs(2), Duration::from_secs(60))
+            .with_multiplier(3.0);
+        assert!(t.is_cold());
+        assert_eq!(t.current(), Duration::from_secs(60));
+
+        t.observe(Duration::from_secs(4));
+        assert!(!t.is_cold());
+        assert_eq!(t.current(), Duration::from_secs(12));
+
+        // 0.3 * 1 + 0.7 * 4 = 3.1 → 9.3s
+        t.observe(Duration::from_secs(1));
+        let cur = t.current().as_secs_f64();
+        assert!((cur - 9.3).abs() < 1e-6, "{}", cur);
+    }
+
+    /// **Scenario**: Adapted timeout never drops below min or exceeds max.
+    #[test]
+    fn adaptive_timeout_is_clamped() {
+        let t = AdaptiveTimeout::new(Duration::from_secs(5), Duration::from_secs(20));
+        t.observe(Duration::from_millis(10));
+        assert_eq!(t.current(), Duration::from_secs(5));
+        let t = AdaptiveTimeout::new(Duration::from_secs(5), Duration::from_secs(20));
+        t.observe(Duration::from_secs(100));
+        assert_eq!(t.current(), Duration::from_secs(20));
+    }
+}
@@ -9,6 +9,7 @@
 //! `MockLlm` implements `invoke_stream()` with configurable streaming behavior:
 //! - Default: sends content as a single chunk (efficient for most tests)
 //! - Character-by-character: splits content into individual character chunks (for stream testing)
+//! - Tool calls (if any) are sent as one chunk with `tool_call_chunks`, after the content
 
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
@@ -16,10 +17,10 @@ use async_trait::async_trait;
 use tokio::sync::mpsc;
 
 use crate::error::AgentError;
-use crate::llm::{LlmClient, LlmResponse};
+use crate::llm::{LlmClient, LlmResponse, LlmResponseMetadata, LlmUsage};
 use crate::message::Message;
 use crate::state::ToolCall;
-use crate::stream::MessageChunk;
+use crate::stream::{MessageChunk, ToolCallChunk};
 
 /// Mock LLM: fixed assistant text and optional tool_calls.
 ///
@@ -45,6 +46,10 @@ pub struct MockLlm {
     second_content: Option<String>,
     /// When true, invoke_stream sends each character as a separate chunk.
     stream_by_char: AtomicBool,
+    /// Usage reported with every response.
+    usage: Option<LlmUsage>,
+    /// When true, replies are derived from the messages (see [`MockLlm::scripted`]).
+    scripted: bool,
 }
 
 impl MockLlm {
@@ -62,6 +67,8 @@ impl MockLlm {
             call_count: None,
             second_content: None,
             stream_by_char: AtomicBool::new(false),
+            usage: None,
+            scripted: false,
         }
     }
 
@@ -73,6 +80,8 @@ impl MockLlm {
             call_count: None,
             second_content: None,
             stream_by_char: AtomicBool::new(false),
+            usage: None,
+            scripted: false,
         }
     }
 
@@ -84,6 +93,8 @@ impl MockLlm {
             call_count: None,
             second_content: None,
             stream_by_char: AtomicBool::new(false),
+            usage: None,
+            scripted: false,
         }
     }
 
@@ -100,6 +111,45 @@ impl MockLlm {
             call_count: Some(AtomicUsize::new(0)),
             second_content: Some("The time is as above.".to_string()),
             stream_by_char: AtomicBool::new(false),
+            usage: None,
+            scripted: false,
+        }
+    }
+
+    /// Creates a mock that answers from the messages alone, so a request gets the same reply
+    /// however many ran before (e.g. the server's test mode).
+    ///
+    /// A latest user message mentioning "time" gets a `get_time` tool call; a tool result is
+    /// answered with `Tool {name} returned: {result}`; any other user message is echoed as
+    /// `You said: {message}`.
+    pub fn scripted() -> Self {
+        Self {
+            scripted: true,
+            ..Self::with_no_tool_calls("")
+        }
+    }
+
+    /// Reply of [`MockLlm::scripted`] to `messages`.
+    fn scripted_reply(messages: &[Message]) -> (String, Vec<ToolCall>) {
+        match messages.last() {
+            Some(Message::Tool { name, content, .. }) => (
+                format!(
+                    "Tool {} returned: {}",
+                    name.as_deref().unwrap_or("tool"),
+                    content
+                ),
+                vec![],
+            ),
+            Some(Message::User(text)) if text.to_lowercase().contains("time") => (
+                "I'll check the time.".to_string(),
+                vec![ToolCall {
+                    name: "get_time".to_string(),
+                    arguments: "{}".to_string(),
+                    id: Some("call-1".to_string()),
+                }],
+            ),
+            Some(Message::User(text)) => (format!("You said: {}", text), vec![]),
+            _ => ("OK".to_string(), vec![]),
         }
     }
 
@@ -115,6 +165,12 @@ impl MockLlm {
         self
     }
 
+    /// Report `usage` with every response (builder).
+    pub fn with_usage(mut self, usage: LlmUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     /// Enable character-by-character streaming for `invoke_stream()`.
     ///
     /// When enabled, each character of the content is sent as a separate `MessageChunk`.
@@ -127,8 +183,9 @@ impl MockLlm {
 
 #[async_trait]
 impl LlmClient for MockLlm {
-    async fn invoke(&self, _messages: &[Message]) -> Result<LlmResponse, AgentError> {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
         let (content, tool_calls) = match &self.call_count {
+            _ if self.scripted => Self::scripted_reply(messages),
             Some(c) => {
                 let n = c.fetch_add(1, Ordering::SeqCst);
                 if n == 0 {
@@ -145,10 +202,18 @@ impl LlmClient for MockLlm {
             }
             None => (self.content.clone(), self.tool_calls.clone()),
         };
+        let finish_reason = if tool_calls.is_empty() { "stop" } else { "tool_calls" };
         Ok(LlmResponse {
             content,
             tool_calls,
-            usage: None,
+            usage: self.usage.clone(),
+            truncated: false,
+            continuations: 0,
+            metadata: LlmResponseMetadata {
+                finish_reason: Some(finish_reason.to_string()),
+                model: Some("mock".to_string()),
+                system_fingerprint: None,
+            },
         })
     }
 
@@ -157,6 +222,8 @@ impl LlmClient for MockLlm {
     /// Behavior depends on `stream_by_char`:
     /// - false (default): sends entire content as one chunk
     /// - true: sends each character as a separate chunk (for testing)
+    ///
+    /// Tool calls are then sent as one chunk with empty content and `tool_call_chunks`.
     async fn invoke_stream(
         &self,
         messages: &[Message],
@@ -172,20 +239,35 @@ impl LlmClient for MockLlm {
                     // Character-by-character streaming
                     for c in response.content.chars() {
                         let _ = tx
-                            .send(MessageChunk {
-                                content: c.to_string(),
-                            })
+                            .send(MessageChunk::text(c.to_string()))
                             .await;
                     }
                 } else {
                     // Single chunk (default)
                     let _ = tx
-                        .send(MessageChunk {
-                            content: response.content.clone(),
-                        })
+                        .send(MessageChunk::text(response.content.clone()))
                         .await;
                 }
             }
+            if !response.tool_calls.is_empty() {
+                let tool_call_chunks = response
+                    .tool_calls
+                    .iter()
+                    .enumerate()
+                    .map(|(i, tc)| ToolCallChunk {
+                        index: i as u32,
+                        id: tc.id.clone(),
+                        name: Some(tc.name.clone()),
+                        arguments: Some(tc.arguments.clone()),
+                    })
+                    .collect();
+                let _ = tx
+                    .send(MessageChunk {
+                        content: String::new(),
+                        tool_call_chunks,
+                    })
+                    .await;
+            }
         }
 
         Ok(response)
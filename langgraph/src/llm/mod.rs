@@ -11,8 +11,19 @@
 //! Implementations that support streaming (like `ChatOpenAI`) will send chunks
 //! through the channel; others (like `MockLlm`) can use the default implementation
 //! that calls `invoke()` and optionally sends the full content as one chunk.
+//!
+//! # Sampling
+//!
+//! `invoke_stream_with_sampling()` overrides the client's sampling parameters
+//! ([`SamplingParams`]) for one call, e.g. a low temperature for tool selection and a higher
+//! one for the final answer (see `SamplingSchedule` in the react module).
 
+mod delta_sanitizer;
+mod error;
+mod health;
+mod http;
 mod mock;
+mod response_format;
 
 use tokio::sync::mpsc;
 
@@ -46,11 +57,19 @@ impl std::str::FromStr for ToolChoiceMode {
 }
 
 mod openai;
+mod regions;
 
+pub use delta_sanitizer::{DeltaSanitizer, DeltaSanitizerConfig};
+pub use error::{LlmError, LlmErrorKind};
+pub use health::AdaptiveTimeout;
+pub use http::{HttpClientConfig, HttpClientError};
 pub use mock::MockLlm;
-pub use openai::ChatOpenAI;
+pub use response_format::JsonSchema;
+pub use openai::{ChatOpenAI, CONTINUE_PROMPT, DEFAULT_PROBE_TIMEOUT};
+pub use regions::FailoverPolicy;
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
 use crate::error::AgentError;
 use crate::message::Message;
@@ -60,8 +79,9 @@ use crate::stream::MessageChunk;
 /// Token usage for one LLM call (prompt + completion).
 ///
 /// **Interaction**: Optional part of `LlmResponse`; emitted as `StreamEvent::Usage`
-/// when streaming so CLI can print usage when `--verbose`.
-#[derive(Clone, Debug, Default)]
+/// when streaming so CLI can print usage when `--verbose`. Summed per run in
+/// `ReActState::usage`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LlmUsage {
     /// Tokens in the prompt (input).
     pub prompt_tokens: u32,
@@ -71,6 +91,36 @@ pub struct LlmUsage {
     pub total_tokens: u32,
 }
 
+impl LlmUsage {
+    /// Adds another call's token counts (saturating).
+    pub fn add(&mut self, other: &LlmUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.total_tokens = self.total_tokens.saturating_add(other.total_tokens);
+    }
+}
+
+/// Provider metadata of one LLM completion (OpenAI `finish_reason`, `model`, `system_fingerprint`).
+///
+/// **Interaction**: Part of `LlmResponse`; ThinkNode stores it in
+/// `ReActState::response_metadata` and emits it as `StreamEvent::ResponseMetadata`;
+/// `StreamToSse` uses `finish_reason` and `system_fingerprint` for the final chunk.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmResponseMetadata {
+    /// Why generation stopped, as reported by the API: `stop`, `length`, `tool_calls`,
+    /// `content_filter`, or `function_call`. None when the provider did not say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+    /// Model that served the request (may be a dated snapshot of the requested model).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Backend configuration fingerprint (OpenAI `system_fingerprint`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+}
+
 /// Response from an LLM completion: assistant message text and optional tool calls.
 ///
 /// **Interaction**: Returned by `LlmClient::invoke()`; ThinkNode writes
@@ -82,6 +132,48 @@ pub struct LlmResponse {
     pub tool_calls: Vec<ToolCall>,
     /// Token usage for this call, when available (e.g. OpenAI returns this).
     pub usage: Option<LlmUsage>,
+    /// True when the provider cut the reply off at the token limit (`finish_reason: length`)
+    /// and it was not (fully) continued.
+    pub truncated: bool,
+    /// Number of automatic "continue" follow-up requests merged into `content`.
+    pub continuations: u32,
+    /// Finish reason, model id, and system fingerprint of the (last) completion.
+    pub metadata: LlmResponseMetadata,
+}
+
+/// Sampling parameters for one LLM call; unset fields keep the client's own setting.
+///
+/// **Interaction**: Passed to `LlmClient::invoke_stream_with_sampling()`; ThinkNode picks
+/// them per step from a `SamplingSchedule`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    /// Sampling temperature (0–2). Lower values are more deterministic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling: only tokens within the top `top_p` probability mass are considered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+}
+
+impl SamplingParams {
+    /// Parameters setting only the temperature.
+    pub fn temperature(temperature: f32) -> Self {
+        Self {
+            temperature: Some(temperature),
+            top_p: None,
+        }
+    }
+
+    /// Sets `top_p`.
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// True when no parameter is set (the call uses the client's settings).
+    pub fn is_empty(&self) -> bool {
+        self.temperature.is_none() && self.top_p.is_none()
+    }
 }
 
 /// LLM client: given messages, returns assistant text and optional tool_calls.
@@ -132,13 +224,25 @@ pub trait LlmClient: Send + Sync {
         if let Some(tx) = chunk_tx {
             if !response.content.is_empty() {
                 let _ = tx
-                    .send(MessageChunk {
-                        content: response.content.clone(),
-                    })
+                    .send(MessageChunk::text(response.content.clone()))
                     .await;
             }
         }
 
         Ok(response)
     }
+
+    /// Same as [`invoke_stream`](Self::invoke_stream) with `sampling` overriding the client's
+    /// sampling parameters for this call only.
+    ///
+    /// Default implementation ignores `sampling`, for clients without per-call sampling
+    /// (e.g. `MockLlm`); `ChatOpenAI` applies it.
+    async fn invoke_stream_with_sampling(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        _sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
+        self.invoke_stream(messages, chunk_tx).await
+    }
 }
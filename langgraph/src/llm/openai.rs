@@ -8,44 +8,101 @@
 //!
 //! Implements `invoke_stream()` for token-by-token streaming. Uses the OpenAI
 //! streaming API (`create_stream`) and sends `MessageChunk` through the provided
-//! channel as tokens arrive. Tool calls are accumulated from stream chunks.
+//! channel as tokens arrive. Tool-call deltas are forwarded as `MessageChunk::tool_call_chunks`
+//! and also accumulated into the final `LlmResponse::tool_calls`.
 //!
 //! Stream response format follows the [OpenAI Chat Completions Streaming] spec:
 //! each SSE chunk is a chat completion chunk object with `choices[]`, and we read
 //! `choices[0].delta.content` for incremental text and `choices[0].delta.tool_calls`
-//! for tool calls. When `stream_options.include_usage` is true, the last chunk may
-//! have empty `choices`; we omit `stream_options` so the request matches typical clients.
+//! for tool calls. `stream_options` is omitted by default so the request matches typical
+//! clients; `with_stream_usage(true)` sends `include_usage` so streamed replies report token
+//! usage (in a last chunk with empty `choices`).
 //!
 //! [OpenAI Chat Completions Streaming]: https://platform.openai.com/docs/api-reference/chat-streaming
 //!
+//! # Self-hosted backends
+//!
+//! `with_adaptive_timeout` bounds each call by a timeout learned from observed latency (a
+//! streamed reply as a whole, not only opening the stream), `with_cold_start_retry` retries
+//! once while the backend is still loading, and `probe_health` lists models so startup can
+//! report an unreachable base early, bounded by its own short timeout (`with_probe_timeout`).
+//! `with_delta_sanitizer` cleans up stuttering streams (duplicate, echoed or blank deltas).
+//! Provider failures are returned as `AgentError::Llm` with a [`LlmError`] kind (rate limited,
+//! unavailable, out of memory, ...) and the provider's retry hint.
+//! `with_http_client` swaps in a tuned `reqwest::Client` (pool, keep-alive, HTTP/2, proxy), e.g.
+//! the shared one from [`HttpClientConfig::shared_client`](super::HttpClientConfig::shared_client).
+//!
+//! # Truncated replies
+//!
+//! A reply with `finish_reason: length` is marked `LlmResponse::truncated`. With
+//! `with_max_continuations(n)`, up to `n` follow-up requests ([`CONTINUE_PROMPT`]) are sent
+//! and their text appended; `LlmResponse::continuations` records how many were made.
+//!
+//! # Structured output
+//!
+//! `with_response_format` sends a [`JsonSchema`] as `response_format` so replies are JSON
+//! matching it; parse them with `StructuredOutputNode`.
+//!
+//! # Azure OpenAI
+//!
+//! `with_azure_config` targets an Azure OpenAI deployment via [`AzureConfig`]; the client
+//! is stored as `Client<Box<dyn Config>>` so both providers share one code path.
+//!
+//! # Regions
+//!
+//! `with_region` adds more API bases of the same provider (e.g. Azure resources in two
+//! regions). Requests go to the fastest healthy base and move on to the next one when a base
+//! is unavailable, times out or is throttled; bases that keep failing are skipped for a while
+//! ([`FailoverPolicy`]). Streams fail over only while being opened. `probe_health` probes
+//! every base.
+//!
 //! **Interaction**: Implements `LlmClient`; used by ThinkNode like `MockLlm`.
 //! Depends on `async_openai` (feature `openai`).
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
 use async_trait::async_trait;
 use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn, Instrument};
 
 use crate::error::AgentError;
-use crate::llm::{LlmClient, LlmResponse, LlmUsage};
+use crate::llm::{LlmClient, LlmError, LlmErrorKind, LlmResponse, LlmResponseMetadata, LlmUsage};
 use crate::memory::uuid6;
 use crate::message::Message;
+use crate::metrics;
 use crate::state::ToolCall;
-use crate::stream::MessageChunk;
-use crate::tool_source::{ToolSource, ToolSourceError, ToolSpec};
+use crate::stream::{MessageChunk, ToolCallChunk};
+use crate::tool_source::{ToolAllowlist, ToolCatalog, ToolSource, ToolSourceError, ToolSpec};
 
 use async_openai::{
-    config::OpenAIConfig,
+    config::{AzureConfig, Config, OpenAIConfig},
+    error::OpenAIError,
     types::chat::{
-        ChatCompletionMessageToolCalls, ChatCompletionRequestMessage,
-        ChatCompletionRequestSystemMessage, ChatCompletionRequestUserMessage, ChatCompletionTool,
+        ChatCompletionMessageToolCall, ChatCompletionMessageToolCalls,
+        ChatCompletionRequestAssistantMessage, ChatCompletionRequestMessage,
+        ChatCompletionRequestSystemMessage, ChatCompletionRequestToolMessage,
+        ChatCompletionRequestUserMessage, ChatCompletionStreamOptions, ChatCompletionTool,
         ChatCompletionToolChoiceOption, ChatCompletionTools,
-        CreateChatCompletionRequestArgs, FunctionObject, ToolChoiceOptions,
+        CreateChatCompletionRequestArgs, FinishReason, FunctionCall, FunctionObject,
+        ResponseFormat, ResponseFormatJsonSchema, ToolChoiceOptions,
     },
     Client,
 };
 
-use super::ToolChoiceMode;
+use super::regions::{fails_over, Region, Regions};
+use super::{
+    AdaptiveTimeout, DeltaSanitizer, DeltaSanitizerConfig, FailoverPolicy, JsonSchema,
+    SamplingParams, ToolChoiceMode,
+};
+
+/// How long [`ChatOpenAI::probe_health`] waits for each API base, by default.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Follow-up user message asking the model to continue a reply cut off at the token limit.
+pub const CONTINUE_PROMPT: &str =
+    "Continue exactly where you left off. Do not repeat anything you already wrote.";
 
 /// OpenAI Chat Completions client implementing `LlmClient` (aligns with LangChain ChatOpenAI).
 ///
@@ -55,34 +112,107 @@ use super::ToolChoiceMode;
 ///
 /// **Interaction**: Implements `LlmClient`; used by ThinkNode.
 pub struct ChatOpenAI {
-    client: Client<OpenAIConfig>,
+    /// API bases requests go to; the first is the one the client was built with.
+    regions: Regions,
+    /// Shared HTTP client set with `with_http_client`; also used by regions added later.
+    http_client: Option<reqwest::Client>,
     model: String,
     tools: Option<Vec<ToolSpec>>,
+    /// When set and filled, its list replaces `tools` (tools changed at runtime).
+    tool_catalog: Option<ToolCatalog>,
+    /// When set, only the tools it enables are sent with each request.
+    tool_allowlist: Option<ToolAllowlist>,
     temperature: Option<f32>,
+    top_p: Option<f32>,
     tool_choice: Option<ToolChoiceMode>,
+    /// When set, replies must be JSON matching this schema.
+    response_format: Option<JsonSchema>,
+    /// When set, each API call is bounded by a latency-adaptive timeout.
+    adaptive_timeout: Option<AdaptiveTimeout>,
+    /// Time limit of `probe_health` per API base.
+    probe_timeout: Duration,
+    /// When true, a failed call is retried once until the first call has succeeded.
+    cold_start_retry: bool,
+    /// Set after the first successful API call; disables cold-start retry.
+    warmed_up: AtomicBool,
+    /// When set, streamed content deltas are filtered through a [`DeltaSanitizer`].
+    delta_sanitizer: Option<DeltaSanitizerConfig>,
+    /// Max automatic follow-up requests for replies cut off at the token limit (0 = off).
+    max_continuations: u32,
+    /// When true, streaming requests ask for usage (`stream_options.include_usage`).
+    stream_usage: bool,
 }
 
 impl ChatOpenAI {
     /// Build client with default config (API key from `OPENAI_API_KEY` env).
     pub fn new(model: impl Into<String>) -> Self {
-        Self {
-            client: Client::new(),
-            model: model.into(),
-            tools: None,
-            temperature: None,
-            tool_choice: None,
-        }
+        Self::with_config(OpenAIConfig::default(), model)
     }
 
     /// Build client with custom config (e.g. custom API key or base URL).
     pub fn with_config(config: OpenAIConfig, model: impl Into<String>) -> Self {
+        Self::with_boxed_config(Box::new(config), model)
+    }
+
+    /// Build client for an Azure OpenAI deployment. `deployment` is sent as the model name;
+    /// Azure routes by the deployment id in `config`, so pass the same name there.
+    pub fn with_azure_config(config: AzureConfig, deployment: impl Into<String>) -> Self {
+        Self::with_boxed_config(Box::new(config), deployment)
+    }
+
+    fn with_boxed_config(config: Box<dyn Config>, model: impl Into<String>) -> Self {
+        let name = config.api_base().to_string();
         Self {
-            client: Client::with_config(config),
+            regions: Regions::new(Region::new(name, Client::with_config(config))),
+            http_client: None,
             model: model.into(),
             tools: None,
+            tool_catalog: None,
+            tool_allowlist: None,
             temperature: None,
+            top_p: None,
             tool_choice: None,
+            response_format: None,
+            adaptive_timeout: None,
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+            cold_start_retry: false,
+            warmed_up: AtomicBool::new(false),
+            delta_sanitizer: None,
+            max_continuations: 0,
+            stream_usage: false,
+        }
+    }
+
+    /// Send requests through `http_client` instead of a default one. Pass a shared client
+    /// (see [`HttpClientConfig::shared_client`](super::HttpClientConfig::shared_client)) so
+    /// several runners reuse one connection pool.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.regions = self
+            .regions
+            .map_clients(|client| client.with_http_client(http_client.clone()));
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Adds another API base of the same provider, e.g. an [`AzureConfig`] for the deployment
+    /// in a second region, or an [`OpenAIConfig`] with another `api_base`. Requests fail over
+    /// between the bases by health and go to the fastest one (see [`FailoverPolicy`]).
+    pub fn with_region(mut self, config: impl Config + 'static) -> Self {
+        let config: Box<dyn Config> = Box::new(config);
+        let name = config.api_base().to_string();
+        let mut client = Client::with_config(config);
+        if let Some(ref http_client) = self.http_client {
+            client = client.with_http_client(http_client.clone());
         }
+        self.regions.push(Region::new(name, client));
+        self
+    }
+
+    /// Sets when a failing API base is skipped and for how long (default
+    /// [`FailoverPolicy::new`]). Only matters with more than one base (`with_region`).
+    pub fn with_failover_policy(mut self, policy: FailoverPolicy) -> Self {
+        self.regions.set_policy(policy);
+        self
     }
 
     /// Build client with tools from the given ToolSource.
@@ -108,18 +238,242 @@ impl ChatOpenAI {
         self
     }
 
+    /// Offer only the tools `allowlist` enables at the time of each request, so tools can be
+    /// switched off at runtime (see [`ToolAllowlist`]).
+    pub fn with_tool_allowlist(mut self, allowlist: ToolAllowlist) -> Self {
+        self.tool_allowlist = Some(allowlist);
+        self
+    }
+
+    /// Offer the tools in `catalog` once it has a list, so tools an MCP server adds or removes
+    /// at runtime reach the model (see [`RefreshingToolSource`]). Until then, the tools given
+    /// to [`with_tools`](Self::with_tools) are offered.
+    ///
+    /// [`RefreshingToolSource`]: crate::tool_source::RefreshingToolSource
+    pub fn with_tool_catalog(mut self, catalog: ToolCatalog) -> Self {
+        self.tool_catalog = Some(catalog);
+        self
+    }
+
+    /// Current tools: the catalog's list when set, else `tools`.
+    fn current_tools(&self) -> Option<Vec<ToolSpec>> {
+        self.tool_catalog
+            .as_ref()
+            .and_then(ToolCatalog::get)
+            .or_else(|| self.tools.clone())
+    }
+
+    /// Tools sent with a request: the current tools without those disabled by the allowlist;
+    /// None when no tool is left.
+    fn offered_tools(&self) -> Option<Vec<ToolSpec>> {
+        let tools = self.current_tools()?;
+        let tools = match &self.tool_allowlist {
+            Some(allowlist) => allowlist.filter(&tools),
+            None => tools,
+        };
+        Some(tools).filter(|t| !t.is_empty() || self.tool_allowlist.is_none())
+    }
+
     /// Set temperature (0–2). Lower values are more deterministic.
     pub fn with_temperature(mut self, temperature: f32) -> Self {
         self.temperature = Some(temperature);
         self
     }
 
+    /// Set nucleus sampling `top_p` (0–1).
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
     /// Set tool choice mode (auto, none, required). Overrides API default when tools are present.
     pub fn with_tool_choice(mut self, mode: ToolChoiceMode) -> Self {
         self.tool_choice = Some(mode);
         self
     }
 
+    /// Require replies to be JSON matching `schema` (OpenAI structured outputs). The reply is
+    /// still returned as text in `LlmResponse::content`; `StructuredOutputNode` parses it.
+    pub fn with_response_format(mut self, schema: JsonSchema) -> Self {
+        self.response_format = Some(schema);
+        self
+    }
+
+    /// Bound each API call by a timeout that adapts to observed latency (see [`AdaptiveTimeout`]).
+    /// A streamed reply must be complete within the timeout, not only opened.
+    pub fn with_adaptive_timeout(mut self, timeout: AdaptiveTimeout) -> Self {
+        self.adaptive_timeout = Some(timeout);
+        self
+    }
+
+    /// How long `probe_health` waits for each API base (default [`DEFAULT_PROBE_TIMEOUT`]).
+    /// Independent of the adaptive timeout, whose maximum allows for cold model loads.
+    pub fn with_probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = timeout;
+        self
+    }
+
+    /// Retry a failed or timed-out call once while the provider is still cold
+    /// (no call has succeeded yet). Useful for self-hosted backends that load weights lazily.
+    pub fn with_cold_start_retry(mut self, enabled: bool) -> Self {
+        self.cold_start_retry = enabled;
+        self
+    }
+
+    /// Filter streamed content deltas (dedupe, newline normalization, empty/whitespace
+    /// handling) for backends that stutter. A no-op config disables sanitation.
+    pub fn with_delta_sanitizer(mut self, config: DeltaSanitizerConfig) -> Self {
+        self.delta_sanitizer = if config.is_noop() { None } else { Some(config) };
+        self
+    }
+
+    /// Automatically continue replies cut off at the token limit (`finish_reason: length`)
+    /// with up to `max` follow-up requests; 0 (default) returns truncated replies as-is.
+    /// The result reports `truncated` and `continuations`.
+    pub fn with_max_continuations(mut self, max: u32) -> Self {
+        self.max_continuations = max;
+        self
+    }
+
+    /// Ask for token usage on streaming requests (`stream_options.include_usage`), so streamed
+    /// replies carry `LlmResponse::usage` like non-streaming ones. Off by default because some
+    /// proxies break the stream when `stream_options` is sent.
+    pub fn with_stream_usage(mut self, enabled: bool) -> Self {
+        self.stream_usage = enabled;
+        self
+    }
+
+    /// Probes the provider by listing models (`GET {base}/models`) and returns the latency.
+    ///
+    /// Bounded by the probe timeout per base (`with_probe_timeout`). Intended for startup checks
+    /// so a misconfigured or still-loading backend is reported before the first user request.
+    /// With several regions, each is probed and its latency recorded for region selection;
+    /// returns the latency of the first region that answered, or the last error when none did.
+    pub async fn probe_health(&self) -> Result<Duration, AgentError> {
+        let mut first_ok = None;
+        let mut last_err = None;
+        for i in 0..self.regions.len() {
+            let region = self.regions.get(i);
+            match self.probe_region(&region.client).await {
+                Ok(latency) => {
+                    self.regions.record_success(i, latency);
+                    first_ok.get_or_insert(latency);
+                }
+                Err(e) => {
+                    if self.regions.len() > 1 {
+                        warn!(region = %region.name, error = %e, "OpenAI region probe failed");
+                    }
+                    self.regions.record_failure(i);
+                    last_err = Some(e);
+                }
+            }
+        }
+        first_ok.ok_or_else(|| last_err.expect("at least one region"))
+    }
+
+    /// Health probe of one region's API base.
+    async fn probe_region(&self, client: &Client<Box<dyn Config>>) -> Result<Duration, AgentError> {
+        let started = Instant::now();
+        let probe = client.models().list();
+        let result = tokio::time::timeout(self.probe_timeout, probe)
+            .await
+            .map_err(|_| {
+                AgentError::ExecutionFailed(format!(
+                    "OpenAI health probe timed out after {:?}",
+                    self.probe_timeout
+                ))
+            })?;
+        result.map_err(|e| AgentError::ExecutionFailed(format!("OpenAI health probe failed: {}", e)))?;
+        let latency = started.elapsed();
+        debug!(latency_ms = latency.as_millis() as u64, "OpenAI health probe ok");
+        Ok(latency)
+    }
+
+    /// Runs one API call with the adaptive timeout, region failover and cold-start retry applied.
+    ///
+    /// `call` gets the client of the region to use; see [`Self::call_regions`]. The whole
+    /// failover is repeated once when cold-start retry is enabled and the first attempt fails
+    /// before any call has succeeded.
+    async fn call_with_policy<'s, T, F, Fut>(&'s self, what: &str, call: F) -> Result<T, AgentError>
+    where
+        F: Fn(&'s Client<Box<dyn Config>>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, AgentError>>,
+    {
+        let retry_allowed = self.cold_start_retry && !self.warmed_up.load(Ordering::SeqCst);
+        let attempts = if retry_allowed { 2 } else { 1 };
+        let mut last_err = None;
+        for attempt in 1..=attempts {
+            match self.call_regions(what, &call).await {
+                Ok(v) => {
+                    self.warmed_up.store(true, Ordering::SeqCst);
+                    return Ok(v);
+                }
+                Err(e) => {
+                    if attempt < attempts {
+                        warn!(error = %e, "OpenAI {} failed on cold start; retrying once", what);
+                    }
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.expect("at least one attempt"))
+    }
+
+    /// Tries the regions in order of preference until one succeeds.
+    ///
+    /// Moves on to the next region only on errors another region may not have (see
+    /// [`FailoverPolicy`]); other errors are returned at once. Successful latencies feed the
+    /// adaptive timeout and the region ranking.
+    async fn call_regions<'s, T, F, Fut>(&'s self, what: &str, call: &F) -> Result<T, AgentError>
+    where
+        F: Fn(&'s Client<Box<dyn Config>>) -> Fut,
+        Fut: std::future::Future<Output = Result<T, AgentError>>,
+    {
+        let order = self.regions.order();
+        let mut last_err = None;
+        for (n, &i) in order.iter().enumerate() {
+            let region = self.regions.get(i);
+            let started = Instant::now();
+            let result = match &self.adaptive_timeout {
+                Some(t) => {
+                    let limit = t.current();
+                    match tokio::time::timeout(limit, call(&region.client)).await {
+                        Ok(r) => r,
+                        Err(_) => Err(AgentError::Llm(LlmError::new(
+                            LlmErrorKind::Timeout,
+                            format!("OpenAI {} timed out after {:?}", what, limit),
+                        ))),
+                    }
+                }
+                None => call(&region.client).await,
+            };
+            match result {
+                Ok(v) => {
+                    let latency = started.elapsed();
+                    if let Some(t) = &self.adaptive_timeout {
+                        t.observe(latency);
+                    }
+                    self.regions.record_success(i, latency);
+                    return Ok(v);
+                }
+                Err(e) if fails_over(&e) => {
+                    self.regions.record_failure(i);
+                    if n + 1 < order.len() {
+                        warn!(
+                            region = %region.name,
+                            error = %e,
+                            "OpenAI {} failed; trying next region",
+                            what
+                        );
+                    }
+                    last_err = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_err.expect("at least one region"))
+    }
+
     /// Returns the chat completions URL used for logging (base from OPENAI_BASE_URL or
     /// OPENAI_API_BASE env, else default; path is /v1/chat/completions).
     /// Note: When using custom config via with_config(), the actual base may differ;
@@ -132,35 +486,117 @@ impl ChatOpenAI {
         format!("{}/v1/chat/completions", base)
     }
 
-    /// Convert our `Message` list to OpenAI request messages (system/user/assistant text only).
+    /// Convert our `Message` list to OpenAI request messages.
+    ///
+    /// Assistant tool calls become `tool_calls` and `Message::Tool` becomes a `tool` message
+    /// with `tool_call_id`. The API requires ids, so a call without one gets
+    /// `call_{message index}_{call index}`, stable across requests of one conversation. A tool
+    /// result without a call id answers the first unanswered call of the preceding assistant
+    /// message with the same tool name (else the first unanswered call); when no call is left
+    /// it is sent as a user message "Tool {name} returned: ...".
     fn messages_to_request(messages: &[Message]) -> Vec<ChatCompletionRequestMessage> {
+        // (id, tool name) of the calls of the last assistant message not answered yet.
+        let mut pending: Vec<(String, String)> = Vec::new();
         messages
             .iter()
-            .map(|m| match m {
+            .enumerate()
+            .map(|(i, m)| match m {
                 Message::System(s) => ChatCompletionRequestMessage::System(
                     ChatCompletionRequestSystemMessage::from(s.as_str()),
                 ),
                 Message::User(s) => ChatCompletionRequestMessage::User(
                     ChatCompletionRequestUserMessage::from(s.as_str()),
                 ),
-                Message::Assistant(s) => {
-                    ChatCompletionRequestMessage::Assistant((s.as_str()).into())
+                Message::Assistant {
+                    content,
+                    tool_calls,
+                } if tool_calls.is_empty() => {
+                    pending.clear();
+                    ChatCompletionRequestMessage::Assistant(content.as_str().into())
+                }
+                Message::Assistant {
+                    content,
+                    tool_calls,
+                } => {
+                    pending = tool_calls
+                        .iter()
+                        .enumerate()
+                        .map(|(j, tc)| {
+                            let id = tc.id.clone().unwrap_or_else(|| format!("call_{}_{}", i, j));
+                            (id, tc.name.clone())
+                        })
+                        .collect();
+                    ChatCompletionRequestMessage::Assistant(ChatCompletionRequestAssistantMessage {
+                        content: (!content.is_empty()).then(|| content.as_str().into()),
+                        tool_calls: Some(
+                            tool_calls
+                                .iter()
+                                .zip(&pending)
+                                .map(|(tc, (id, _))| {
+                                    ChatCompletionMessageToolCalls::Function(
+                                        ChatCompletionMessageToolCall {
+                                            id: id.clone(),
+                                            function: FunctionCall {
+                                                name: tc.name.clone(),
+                                                arguments: tc.arguments.clone(),
+                                            },
+                                        },
+                                    )
+                                })
+                                .collect(),
+                        ),
+                        ..Default::default()
+                    })
+                }
+                Message::Tool {
+                    call_id,
+                    name,
+                    content,
+                } => {
+                    let answered = match call_id {
+                        Some(id) => pending.iter().position(|(p, _)| p == id),
+                        None => pending
+                            .iter()
+                            .position(|(_, n)| Some(n) == name.as_ref())
+                            .or((!pending.is_empty()).then_some(0)),
+                    };
+                    let paired = answered.map(|k| pending.remove(k).0);
+                    match call_id.clone().or(paired) {
+                        Some(id) => {
+                            ChatCompletionRequestMessage::Tool(ChatCompletionRequestToolMessage {
+                                content: content.as_str().into(),
+                                tool_call_id: id,
+                            })
+                        }
+                        None => ChatCompletionRequestMessage::User(
+                            ChatCompletionRequestUserMessage::from(
+                                format!(
+                                    "Tool {} returned: {}",
+                                    name.as_deref().unwrap_or("tool"),
+                                    content
+                                )
+                                .as_str(),
+                            ),
+                        ),
+                    }
                 }
             })
             .collect()
     }
-}
 
-#[async_trait]
-impl LlmClient for ChatOpenAI {
-    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+    /// One non-streaming completion; `truncated` is set from `finish_reason: length`.
+    async fn complete(
+        &self,
+        messages: &[Message],
+        sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
         let trace_id = uuid6().to_string();
         let openai_messages = Self::messages_to_request(messages);
         let mut args = CreateChatCompletionRequestArgs::default();
         args.model(self.model.clone());
         args.messages(openai_messages);
 
-        if let Some(ref tools) = self.tools {
+        if let Some(tools) = self.offered_tools() {
             let chat_tools: Vec<ChatCompletionTools> = tools
                 .iter()
                 .map(|t| {
@@ -177,9 +613,13 @@ impl LlmClient for ChatOpenAI {
             args.tools(chat_tools);
         }
 
-        if let Some(t) = self.temperature {
+        let temperature = sampling.temperature.or(self.temperature);
+        if let Some(t) = temperature {
             args.temperature(t);
         }
+        if let Some(p) = sampling.top_p.or(self.top_p) {
+            args.top_p(p);
+        }
 
         if let Some(mode) = self.tool_choice {
             let opt = match mode {
@@ -190,11 +630,15 @@ impl LlmClient for ChatOpenAI {
             args.tool_choice(ChatCompletionToolChoiceOption::Mode(opt));
         }
 
+        if let Some(ref schema) = self.response_format {
+            args.response_format(response_format(schema));
+        }
+
         let request = args.build().map_err(|e| {
             AgentError::ExecutionFailed(format!("OpenAI request build failed: {}", e))
         })?;
 
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.current_tools().map(|t| t.len()).unwrap_or(0);
         let url = Self::chat_completions_url();
         debug!(
             trace_id = %trace_id,
@@ -202,7 +646,7 @@ impl LlmClient for ChatOpenAI {
             model = %self.model,
             message_count = messages.len(),
             tools_count = tools_count,
-            temperature = ?self.temperature,
+            temperature = ?temperature,
             tool_choice = ?self.tool_choice,
             "OpenAI chat create"
         );
@@ -212,12 +656,16 @@ impl LlmClient for ChatOpenAI {
             trace!(trace_id = %trace_id, url = %url, request = ?request, "OpenAI request body (debug)");
         }
 
+        let request = &request;
         let response = self
-            .client
-            .chat()
-            .create(request)
-            .await
-            .map_err(|e| AgentError::ExecutionFailed(format!("OpenAI API error: {}", e)))?;
+            .call_with_policy("request", |client| async move {
+                client
+                    .chat()
+                    .create(request.clone())
+                    .await
+                    .map_err(|e| provider_error("API error", &e))
+            })
+            .await?;
 
         if let Ok(js) = serde_json::to_string_pretty(&response) {
             trace!(trace_id = %trace_id, url = %url, response = %js, "OpenAI response body");
@@ -230,6 +678,12 @@ impl LlmClient for ChatOpenAI {
                 AgentError::ExecutionFailed("OpenAI returned no choices".to_string())
             })?;
 
+        let truncated = matches!(choice.finish_reason, Some(FinishReason::Length));
+        let metadata = LlmResponseMetadata {
+            finish_reason: choice.finish_reason.as_ref().and_then(finish_reason_name),
+            model: Some(response.model).filter(|m| !m.is_empty()),
+            system_fingerprint: response.system_fingerprint,
+        };
         let msg = choice.message;
         let content = msg.content.unwrap_or_default();
         let tool_calls: Vec<ToolCall> = msg
@@ -258,37 +712,37 @@ impl LlmClient for ChatOpenAI {
             content,
             tool_calls,
             usage,
+            truncated,
+            continuations: 0,
+            metadata,
         })
     }
 
-    /// Streaming variant: sends message chunks as they arrive from OpenAI.
-    ///
-    /// Uses OpenAI's streaming API to receive tokens incrementally. Each content
-    /// delta is sent through `chunk_tx` as a `MessageChunk`. Tool calls are
-    /// accumulated from stream chunks and returned in the final `LlmResponse`.
-    async fn invoke_stream(
+    /// One streaming completion: sends message chunks through `chunk_tx` as they arrive.
+    async fn complete_stream(
         &self,
         messages: &[Message],
-        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        chunk_tx: mpsc::Sender<MessageChunk>,
+        sampling: &SamplingParams,
     ) -> Result<LlmResponse, AgentError> {
-        // If no streaming requested, use non-streaming path
-        if chunk_tx.is_none() {
-            return self.invoke(messages).await;
-        }
-
         let trace_id = uuid6().to_string();
-        let chunk_tx = chunk_tx.unwrap();
         let openai_messages = Self::messages_to_request(messages);
         let mut args = CreateChatCompletionRequestArgs::default();
         args.model(self.model.clone());
         args.messages(openai_messages);
         args.stream(true);
-        // Do not set stream_options so the request matches typical OpenAI clients. When
-        // stream_options.include_usage is true, the last chunk may have empty choices and
-        // usage; we already handle empty choices. Some proxies (e.g. GPTProto) return
-        // broken streams when stream_options is sent, so omit it for compatibility.
+        // Only set stream_options when asked: some proxies (e.g. GPTProto) return broken
+        // streams when it is sent. With include_usage the last chunk has empty choices and
+        // usage; we already handle empty choices.
+        if self.stream_usage {
+            // Built from JSON so other (optional) stream options keep their defaults.
+            let options: ChatCompletionStreamOptions =
+                serde_json::from_value(serde_json::json!({ "include_usage": true }))
+                    .map_err(|e| AgentError::ExecutionFailed(e.to_string()))?;
+            args.stream_options(options);
+        }
 
-        if let Some(ref tools) = self.tools {
+        if let Some(tools) = self.offered_tools() {
             let chat_tools: Vec<ChatCompletionTools> = tools
                 .iter()
                 .map(|t| {
@@ -305,9 +759,13 @@ impl LlmClient for ChatOpenAI {
             args.tools(chat_tools);
         }
 
-        if let Some(t) = self.temperature {
+        let temperature = sampling.temperature.or(self.temperature);
+        if let Some(t) = temperature {
             args.temperature(t);
         }
+        if let Some(p) = sampling.top_p.or(self.top_p) {
+            args.top_p(p);
+        }
 
         if let Some(mode) = self.tool_choice {
             let opt = match mode {
@@ -318,11 +776,15 @@ impl LlmClient for ChatOpenAI {
             args.tool_choice(ChatCompletionToolChoiceOption::Mode(opt));
         }
 
+        if let Some(ref schema) = self.response_format {
+            args.response_format(response_format(schema));
+        }
+
         let request = args.build().map_err(|e| {
             AgentError::ExecutionFailed(format!("OpenAI request build failed: {}", e))
         })?;
 
-        let tools_count = self.tools.as_ref().map(|t| t.len()).unwrap_or(0);
+        let tools_count = self.current_tools().map(|t| t.len()).unwrap_or(0);
         let url = Self::chat_completions_url();
         debug!(
             trace_id = %trace_id,
@@ -331,7 +793,7 @@ impl LlmClient for ChatOpenAI {
             message_count = messages.len(),
             stream = true,
             tools_count = tools_count,
-            temperature = ?self.temperature,
+            temperature = ?temperature,
             tool_choice = ?self.tool_choice,
             "OpenAI chat create_stream"
         );
@@ -341,12 +803,23 @@ impl LlmClient for ChatOpenAI {
             trace!(trace_id = %trace_id, url = %url, request = ?request, "OpenAI stream request body (debug)");
         }
 
+        let request = &request;
+        // The adaptive timeout bounds the whole reply; opening the stream is bounded on its own
+        // in call_with_policy.
+        let started = Instant::now();
+        let deadline = self
+            .adaptive_timeout
+            .as_ref()
+            .map(|t| (t.current(), tokio::time::Instant::now() + t.current()));
         let mut stream = self
-            .client
-            .chat()
-            .create_stream(request)
-            .await
-            .map_err(|e| AgentError::ExecutionFailed(format!("OpenAI stream error: {}", e)))?;
+            .call_with_policy("stream request", |client| async move {
+                client
+                    .chat()
+                    .create_stream(request.clone())
+                    .await
+                    .map_err(|e| provider_error("stream error", &e))
+            })
+            .await?;
 
         // Accumulate content, tool calls, and usage from stream
         let mut full_content = String::new();
@@ -356,10 +829,28 @@ impl LlmClient for ChatOpenAI {
         let mut tool_call_map: std::collections::HashMap<u32, (String, String, String)> =
             std::collections::HashMap::new();
         let mut stream_usage: Option<LlmUsage> = None;
-
-        while let Some(result) = stream.next().await {
-            let response = result
-                .map_err(|e| AgentError::ExecutionFailed(format!("OpenAI stream error: {}", e)))?;
+        let mut sanitizer = self.delta_sanitizer.clone().map(DeltaSanitizer::new);
+        let mut truncated = false;
+        let mut metadata = LlmResponseMetadata::default();
+
+        loop {
+            let next = match deadline {
+                Some((limit, at)) => {
+                    tokio::time::timeout_at(at, stream.next())
+                        .await
+                        .map_err(|_| {
+                            AgentError::Llm(LlmError::new(
+                                LlmErrorKind::Timeout,
+                                format!("OpenAI stream timed out after {:?}", limit),
+                            ))
+                        })?
+                }
+                None => stream.next().await,
+            };
+            let Some(result) = next else {
+                break;
+            };
+            let response = result.map_err(|e| provider_error("stream error", &e))?;
 
             if let Some(ref u) = response.usage {
                 stream_usage = Some(LlmUsage {
@@ -368,26 +859,55 @@ impl LlmClient for ChatOpenAI {
                     total_tokens: u.total_tokens,
                 });
             }
+            if !response.model.is_empty() {
+                metadata.model = Some(response.model.clone());
+            }
+            if response.system_fingerprint.is_some() {
+                metadata.system_fingerprint = response.system_fingerprint.clone();
+            }
 
             for choice in response.choices {
+                if matches!(choice.finish_reason, Some(FinishReason::Length)) {
+                    truncated = true;
+                }
+                if let Some(reason) = choice.finish_reason.as_ref().and_then(finish_reason_name) {
+                    metadata.finish_reason = Some(reason);
+                }
                 let delta = &choice.delta;
 
-                // Handle content delta
+                // Handle content delta (optionally sanitized)
                 if let Some(ref content) = delta.content {
-                    if !content.is_empty() {
-                        full_content.push_str(content);
+                    let content = match sanitizer.as_mut() {
+                        Some(s) => s.push(content),
+                        None => Some(content.clone()),
+                    };
+                    if let Some(content) = content.filter(|c| !c.is_empty()) {
+                        full_content.push_str(&content);
                         sent_any_content = true;
                         // Send chunk to channel (ignore errors if receiver dropped)
+                        let _ = chunk_tx.send(MessageChunk::text(content)).await;
+                    }
+                }
+
+                // Handle tool calls delta (accumulated by index, forwarded as-is to the stream)
+                if let Some(ref tool_calls) = delta.tool_calls {
+                    let tool_call_chunks: Vec<ToolCallChunk> = tool_calls
+                        .iter()
+                        .map(|tc| ToolCallChunk {
+                            index: tc.index,
+                            id: tc.id.clone().filter(|id| !id.is_empty()),
+                            name: tc.function.as_ref().and_then(|f| f.name.clone()),
+                            arguments: tc.function.as_ref().and_then(|f| f.arguments.clone()),
+                        })
+                        .collect();
+                    if !tool_call_chunks.is_empty() {
                         let _ = chunk_tx
                             .send(MessageChunk {
-                                content: content.clone(),
+                                content: String::new(),
+                                tool_call_chunks,
                             })
                             .await;
                     }
-                }
-
-                // Handle tool calls delta (accumulated by index)
-                if let Some(ref tool_calls) = delta.tool_calls {
                     for tc in tool_calls {
                         let entry = tool_call_map.entry(tc.index).or_insert_with(|| {
                             (
@@ -418,25 +938,35 @@ impl LlmClient for ChatOpenAI {
             }
         }
 
+        if let Some(t) = &self.adaptive_timeout {
+            t.observe(started.elapsed());
+        }
+
+        if let Some(rest) = sanitizer.as_mut().and_then(DeltaSanitizer::finish) {
+            full_content.push_str(&rest);
+            sent_any_content = true;
+            let _ = chunk_tx.send(MessageChunk::text(rest)).await;
+        }
+
         // Some proxies (e.g. GPTProto) return stream chunks with empty choices[] but valid usage;
         // non-streaming with the same request returns content. Fall back to one non-streaming call
         // so the user gets the real reply instead of a generic fallback message.
         let completion_tokens = stream_usage.as_ref().map(|u| u.completion_tokens).unwrap_or(0);
         if full_content.is_empty() && tool_call_map.is_empty() && completion_tokens > 0 {
-            match self.invoke(messages).await {
+            match self.complete(messages, sampling).await {
                 Ok(fallback_resp) if !fallback_resp.content.is_empty() || !fallback_resp.tool_calls.is_empty() => {
                     full_content = fallback_resp.content.clone();
                     if !full_content.is_empty() {
                         sent_any_content = true;
                         let _ = chunk_tx
-                            .send(MessageChunk {
-                                content: full_content.clone(),
-                            })
+                            .send(MessageChunk::text(full_content.clone()))
                             .await;
                     }
                     if stream_usage.is_none() {
                         stream_usage = fallback_resp.usage;
                     }
+                    truncated = fallback_resp.truncated;
+                    metadata = fallback_resp.metadata;
                     // Use fallback tool_calls; we'll overwrite tool_call_map so the final collect below yields these.
                     tool_call_map = fallback_resp
                         .tool_calls
@@ -456,9 +986,7 @@ impl LlmClient for ChatOpenAI {
         // Send the full content as one chunk so the SSE stream still has assistant text.
         if !sent_any_content && !full_content.is_empty() {
             let _ = chunk_tx
-                .send(MessageChunk {
-                    content: full_content.clone(),
-                })
+                .send(MessageChunk::text(full_content.clone()))
                 .await;
         }
 
@@ -482,6 +1010,7 @@ impl LlmClient for ChatOpenAI {
             content = %full_content,
             tool_calls = ?tool_calls,
             usage = ?stream_usage,
+            metadata = ?metadata,
             "OpenAI stream response"
         );
 
@@ -489,8 +1018,167 @@ impl LlmClient for ChatOpenAI {
             content: full_content,
             tool_calls,
             usage: stream_usage,
+            truncated,
+            continuations: 0,
+            metadata,
         })
     }
+
+    /// Continues a reply cut off at the token limit, up to `max_continuations` follow-ups.
+    ///
+    /// Each follow-up sends the conversation plus the partial reply and [`CONTINUE_PROMPT`];
+    /// the continuation text is appended to `content` (and streamed when `chunk_tx` is set).
+    /// Replies with tool calls are never continued.
+    async fn continue_truncated(
+        &self,
+        messages: &[Message],
+        mut response: LlmResponse,
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
+        while response.truncated
+            && response.tool_calls.is_empty()
+            && response.continuations < self.max_continuations
+        {
+            let mut follow_up = messages.to_vec();
+            follow_up.push(Message::assistant(response.content.clone()));
+            follow_up.push(Message::User(CONTINUE_PROMPT.to_string()));
+            let more = match &chunk_tx {
+                Some(tx) => {
+                    self.complete_stream(&follow_up, tx.clone(), sampling)
+                        .await?
+                }
+                None => self.complete(&follow_up, sampling).await?,
+            };
+            debug!(
+                continuation = response.continuations + 1,
+                added_len = more.content.len(),
+                still_truncated = more.truncated,
+                "OpenAI continued truncated response"
+            );
+            response.content.push_str(&more.content);
+            response.tool_calls = more.tool_calls;
+            response.usage = merge_usage(response.usage, more.usage);
+            response.truncated = more.truncated;
+            response.metadata = more.metadata;
+            response.continuations += 1;
+        }
+        if response.truncated {
+            warn!(
+                continuations = response.continuations,
+                "OpenAI response truncated at token limit (finish_reason: length)"
+            );
+        }
+        Ok(response)
+    }
+}
+
+/// Classifies a provider error (rate limit, overload, OOM, ...) as `AgentError::Llm`, keeping
+/// the `OpenAI <what>: ...` message prefix.
+/// `response_format` of type `json_schema` for `schema`.
+fn response_format(schema: &JsonSchema) -> ResponseFormat {
+    ResponseFormat::JsonSchema {
+        json_schema: ResponseFormatJsonSchema {
+            name: schema.name.clone(),
+            description: schema.description.clone(),
+            schema: Some(schema.schema.clone()),
+            strict: Some(schema.strict),
+        },
+    }
+}
+
+fn provider_error(what: &str, e: &OpenAIError) -> AgentError {
+    let mut err = LlmError::from_openai(e);
+    err.message = format!("OpenAI {}: {}", what, err.message);
+    AgentError::Llm(err)
+}
+
+/// API name of a finish reason (`stop`, `length`, `tool_calls`, ...), as serialized by the API.
+fn finish_reason_name(reason: &FinishReason) -> Option<String> {
+    serde_json::to_value(reason)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+}
+
+/// Adds token counts of a follow-up call to the running total.
+fn merge_usage(a: Option<LlmUsage>, b: Option<LlmUsage>) -> Option<LlmUsage> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(LlmUsage {
+            prompt_tokens: a.prompt_tokens + b.prompt_tokens,
+            completion_tokens: a.completion_tokens + b.completion_tokens,
+            total_tokens: a.total_tokens + b.total_tokens,
+        }),
+        (a, b) => a.or(b),
+    }
+}
+
+#[async_trait]
+impl LlmClient for ChatOpenAI {
+    /// Non-streaming completion; continues truncated replies when `with_max_continuations` is set.
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        self.invoke_stream_with_sampling(messages, None, &SamplingParams::default())
+            .await
+    }
+
+    /// Streaming variant: sends message chunks as they arrive from OpenAI.
+    ///
+    /// Uses OpenAI's streaming API to receive tokens incrementally. Each content
+    /// delta is sent through `chunk_tx` as a `MessageChunk`. Tool-call deltas are sent
+    /// as chunks with empty `content` and non-empty `tool_call_chunks`, and are also
+    /// accumulated and returned in the final `LlmResponse`. Continuations of a truncated
+    /// reply are streamed through the same channel.
+    async fn invoke_stream(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+    ) -> Result<LlmResponse, AgentError> {
+        self.invoke_stream_with_sampling(messages, chunk_tx, &SamplingParams::default())
+            .await
+    }
+
+    /// Streaming or not (by `chunk_tx`), with `sampling` overriding the client's temperature
+    /// and `top_p` for this call and its continuations.
+    async fn invoke_stream_with_sampling(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
+        let span = tracing::info_span!(
+            "llm",
+            model = %self.model,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty
+        );
+        let started = Instant::now();
+        let result = async {
+            // If no streaming requested, use non-streaming path
+            let Some(chunk_tx) = chunk_tx else {
+                let response = self.complete(messages, sampling).await?;
+                return self
+                    .continue_truncated(messages, response, None, sampling)
+                    .await;
+            };
+            let response = self
+                .complete_stream(messages, chunk_tx.clone(), sampling)
+                .await?;
+            self.continue_truncated(messages, response, Some(chunk_tx), sampling)
+                .await
+        }
+        .instrument(span.clone())
+        .await;
+        if let Some(usage) = result.as_ref().ok().and_then(|r| r.usage.as_ref()) {
+            span.record("prompt_tokens", usage.prompt_tokens);
+            span.record("completion_tokens", usage.completion_tokens);
+        }
+        metrics::record(|m| {
+            m.llm_request(&self.model, started.elapsed(), result.is_ok());
+            if let Some(usage) = result.as_ref().ok().and_then(|r| r.usage.as_ref()) {
+                m.tokens(&self.model, usage.prompt_tokens, usage.completion_tokens);
+            }
+        });
+        result
+    }
 }
 
 #[cfg(test)]
@@ -513,6 +1201,129 @@ mod tests {
         let _ = ChatOpenAI::with_config(config, "gpt-4");
     }
 
+    /// **Scenario**: ChatOpenAI::with_azure_config builds a client for an Azure deployment.
+    #[test]
+    fn chat_openai_with_azure_config_creates_client() {
+        let config = AzureConfig::new()
+            .with_api_base("https://example.openai.azure.com")
+            .with_api_version("2024-10-21")
+            .with_deployment_id("gpt-4o-mini")
+            .with_api_key("test-key");
+        let client = ChatOpenAI::with_azure_config(config, "gpt-4o-mini");
+        assert_eq!(client.model, "gpt-4o-mini");
+    }
+
+    /// **Scenario**: Assistant tool calls and Tool results map to OpenAI `tool_calls` and `tool` messages;
+    /// a Tool result without call id falls back to a user message.
+    #[test]
+    fn messages_to_request_round_trips_tool_roles() {
+        let call = ToolCall {
+            name: "get_time".into(),
+            arguments: "{}".into(),
+            id: Some("call-1".into()),
+        };
+        let messages = vec![
+            Message::user("time?"),
+            Message::assistant_with_tool_calls("", vec![call]),
+            Message::tool(Some("call-1".into()), Some("get_time".into()), "12:00"),
+            Message::tool(None, Some("get_time".into()), "13:00"),
+        ];
+        let req = serde_json::to_value(ChatOpenAI::messages_to_request(&messages)).unwrap();
+        assert_eq!(req[1]["role"], "assistant");
+        assert_eq!(req[1]["tool_calls"][0]["id"], "call-1");
+        assert_eq!(req[1]["tool_calls"][0]["function"]["name"], "get_time");
+        assert_eq!(req[2]["role"], "tool");
+        assert_eq!(req[2]["tool_call_id"], "call-1");
+        assert_eq!(req[2]["content"], "12:00");
+        assert_eq!(req[3]["role"], "user");
+        assert!(req[3]["content"].as_str().unwrap().contains("13:00"));
+    }
+
+    /// **Scenario**: Tool calls without ids get stable generated ids, and tool results without
+    /// a call id answer them by tool name, so no empty `tool_call_id` is sent.
+    #[test]
+    fn messages_to_request_generates_missing_tool_call_ids() {
+        let call = |name: &str| ToolCall {
+            name: name.into(),
+            arguments: "{}".into(),
+            id: None,
+        };
+        let messages = vec![
+            Message::user("weather and time?"),
+            Message::assistant_with_tool_calls("", vec![call("get_weather"), call("get_time")]),
+            Message::tool(None, Some("get_time".into()), "12:00"),
+            Message::tool(None, Some("get_weather".into()), "sunny"),
+        ];
+        let req = serde_json::to_value(ChatOpenAI::messages_to_request(&messages)).unwrap();
+        assert_eq!(req[1]["tool_calls"][0]["id"], "call_1_0");
+        assert_eq!(req[1]["tool_calls"][1]["id"], "call_1_1");
+        assert_eq!(req[2]["role"], "tool");
+        assert_eq!(req[2]["tool_call_id"], "call_1_1");
+        assert_eq!(req[3]["role"], "tool");
+        assert_eq!(req[3]["tool_call_id"], "call_1_0");
+        let again = serde_json::to_value(ChatOpenAI::messages_to_request(&messages)).unwrap();
+        assert_eq!(req, again);
+    }
+
+    /// **Scenario**: merge_usage sums both sides and keeps whichever side is present.
+    #[test]
+    fn merge_usage_sums_token_counts() {
+        let u = |p, c| LlmUsage {
+            prompt_tokens: p,
+            completion_tokens: c,
+            total_tokens: p + c,
+        };
+        let merged = merge_usage(Some(u(10, 5)), Some(u(20, 7))).unwrap();
+        assert_eq!(merged.prompt_tokens, 30);
+        assert_eq!(merged.completion_tokens, 12);
+        assert_eq!(merged.total_tokens, 42);
+        assert_eq!(merge_usage(None, Some(u(1, 1))).unwrap().total_tokens, 2);
+        assert!(merge_usage(None, None).is_none());
+    }
+
+    /// **Scenario**: With continuations disabled (default), a truncated reply is returned as-is
+    /// and still marked truncated; no follow-up request is made.
+    #[tokio::test]
+    async fn continue_truncated_disabled_returns_response_unchanged() {
+        let config = OpenAIConfig::new().with_api_base("http://127.0.0.1:1");
+        let client = ChatOpenAI::with_config(config, "gpt-4o-mini");
+        let response = LlmResponse {
+            content: "partial".into(),
+            tool_calls: vec![],
+            usage: None,
+            truncated: true,
+            continuations: 0,
+            metadata: LlmResponseMetadata {
+                finish_reason: Some("length".into()),
+                ..Default::default()
+            },
+        };
+        let out = client
+            .continue_truncated(
+                &[Message::user("hi")],
+                response,
+                None,
+                &SamplingParams::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(out.content, "partial");
+        assert!(out.truncated);
+        assert_eq!(out.continuations, 0);
+        assert_eq!(out.metadata.finish_reason.as_deref(), Some("length"));
+    }
+
+    /// **Scenario**: Finish reasons map to their API names.
+    #[test]
+    fn finish_reason_name_uses_api_names() {
+        assert_eq!(finish_reason_name(&FinishReason::Stop).as_deref(), Some("stop"));
+        assert_eq!(finish_reason_name(&FinishReason::Length).as_deref(), Some("length"));
+        assert_eq!(
+            finish_reason_name(&FinishReason::ToolCalls).as_deref(),
+            Some("tool_calls")
+        );
+    }
+
     /// **Scenario**: Builder chain with_tools and with_temperature builds without panic.
     #[test]
     fn chat_openai_with_tools_and_temperature_builder() {
@@ -526,6 +1337,71 @@ mod tests {
             .with_temperature(0.5f32);
     }
 
+    /// **Scenario**: With an allowlist, requests offer only the enabled tools, following changes
+    /// made after the client was built; with every tool disabled none are sent.
+    #[test]
+    fn tool_allowlist_filters_offered_tools() {
+        let spec = |name: &str| ToolSpec {
+            name: name.into(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        };
+        let allowlist = ToolAllowlist::new();
+        let client = ChatOpenAI::new("gpt-4")
+            .with_tools(vec![spec("get_time"), spec("bash")])
+            .with_tool_allowlist(allowlist.clone());
+        assert_eq!(client.offered_tools().unwrap().len(), 2);
+
+        allowlist.set(Some(["get_time"]));
+        let offered = client.offered_tools().unwrap();
+        assert_eq!(offered.len(), 1);
+        assert_eq!(offered[0].name, "get_time");
+
+        allowlist.set(Some(Vec::<String>::new()));
+        assert!(client.offered_tools().is_none());
+    }
+
+    /// **Scenario**: With a catalog, requests offer the tools given at build time until the
+    /// catalog is filled, then the catalog's current list.
+    #[test]
+    fn tool_catalog_replaces_tools_once_set() {
+        let spec = |name: &str| ToolSpec {
+            name: name.into(),
+            description: None,
+            input_schema: serde_json::json!({}),
+        };
+        let catalog = ToolCatalog::new();
+        let client = ChatOpenAI::new("gpt-4")
+            .with_tools(vec![spec("get_time")])
+            .with_tool_catalog(catalog.clone());
+        assert_eq!(client.offered_tools().unwrap()[0].name, "get_time");
+
+        catalog.set(vec![spec("get_time"), spec("search")]);
+        let offered = client.offered_tools().unwrap();
+        assert_eq!(offered.len(), 2);
+        assert_eq!(offered[1].name, "search");
+    }
+
+    /// **Scenario**: with_response_format sends the schema as `response_format` of type
+    /// json_schema, with name, schema and strict flag.
+    #[test]
+    fn response_format_serializes_json_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "city": { "type": "string" } },
+            "required": ["city"],
+            "additionalProperties": false
+        });
+        let client = ChatOpenAI::new("gpt-4o-mini")
+            .with_response_format(JsonSchema::new("place", schema.clone()));
+        let format = response_format(client.response_format.as_ref().unwrap());
+        let value = serde_json::to_value(format).unwrap();
+        assert_eq!(value["type"], "json_schema");
+        assert_eq!(value["json_schema"]["name"], "place");
+        assert_eq!(value["json_schema"]["schema"], schema);
+        assert_eq!(value["json_schema"]["strict"], true);
+    }
+
     /// **Scenario**: invoke() against an unreachable API base returns an error (no real API key needed).
     /// Given a client configured with an invalid base URL, when we call invoke() with one user message,
     /// then the result is Err (e.g. connection refused or timeout).
@@ -542,6 +1418,26 @@ mod tests {
         assert!(result.is_err(), "invoke against unreachable base should return Err");
     }
 
+    /// **Scenario**: A client using a shared, tuned HTTP client sends requests through it.
+    /// Given with_http_client(shared client with connect timeout) and an unreachable base, invoke() is Err.
+    #[tokio::test]
+    async fn invoke_with_shared_http_client_uses_it() {
+        let http = crate::llm::HttpClientConfig {
+            connect_timeout: Some(Duration::from_secs(2)),
+            pool_max_idle_per_host: Some(2),
+            ..Default::default()
+        };
+        let config = OpenAIConfig::new()
+            .with_api_key("test-key")
+            .with_api_base("https://127.0.0.1:1");
+        let client = ChatOpenAI::with_config(config, "gpt-4o-mini")
+            .with_http_client(http.shared_client().unwrap());
+
+        let result = client.invoke(&[Message::user("Hello")]).await;
+
+        assert!(result.is_err(), "invoke against unreachable base should return Err");
+    }
+
     /// **Scenario**: invoke_stream() against an unreachable API base returns an error (no real API key needed).
     /// Given a client configured with an invalid base URL and a channel, when we call invoke_stream()
     /// with one user message, then the result is Err.
@@ -597,6 +1493,119 @@ mod tests {
         );
     }
 
+    /// **Scenario**: With cold-start retry and an adaptive timeout, an unreachable base still
+    /// returns Err (after one retry) and leaves the client cold.
+    #[tokio::test]
+    async fn invoke_with_cold_start_retry_and_unreachable_base_returns_error() {
+        let config = OpenAIConfig::new()
+            .with_api_key("test-key")
+            .with_api_base("https://127.0.0.1:1");
+        let client = ChatOpenAI::with_config(config, "gpt-4o-mini")
+            .with_adaptive_timeout(AdaptiveTimeout::new(
+                Duration::from_secs(1),
+                Duration::from_secs(5),
+            ))
+            .with_cold_start_retry(true);
+        let messages = [Message::user("Hello")];
+
+        let result = client.invoke(&messages).await;
+
+        assert!(result.is_err());
+        assert!(!client.warmed_up.load(Ordering::SeqCst));
+        assert!(client.adaptive_timeout.as_ref().unwrap().is_cold());
+    }
+
+    /// **Scenario**: A request to an unreachable API base fails over to the next region, which
+    /// is preferred afterwards.
+    #[tokio::test]
+    async fn invoke_fails_over_to_next_region() {
+        let reply = serde_json::json!({
+            "id": "c1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "hi" },
+                "finish_reason": "stop"
+            }]
+        });
+        let (base, server) =
+            crate::tools::notify::test_server::one_shot_json(200, reply.to_string()).await;
+        let config = |base: &str| {
+            OpenAIConfig::new()
+                .with_api_key("test-key")
+                .with_api_base(base)
+        };
+        let client = ChatOpenAI::with_config(config("http://127.0.0.1:1"), "gpt-4o-mini")
+            .with_region(config(&base));
+
+        let response = client.invoke(&[Message::user("Hello")]).await.unwrap();
+
+        assert_eq!(response.content, "hi");
+        assert!(server.await.unwrap().contains("Hello"));
+        assert_eq!(client.regions.order(), [1, 0]);
+    }
+
+    /// **Scenario**: With an adaptive timeout, a stream that opens and then stalls fails with a
+    /// timeout instead of waiting for the server.
+    #[tokio::test]
+    async fn invoke_stream_times_out_when_stream_stalls() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 8192];
+            let _ = socket.read(&mut buf).await.unwrap();
+            let chunk = serde_json::json!({
+                "id": "c1",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": "gpt-4o-mini",
+                "choices": [{ "index": 0, "delta": { "content": "hi" }, "finish_reason": null }]
+            });
+            let head = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\n";
+            socket.write_all(head.as_bytes()).await.unwrap();
+            let event = format!("data: {}\n\n", chunk);
+            socket.write_all(event.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+        let config = OpenAIConfig::new()
+            .with_api_key("test-key")
+            .with_api_base(base);
+        let client = ChatOpenAI::with_config(config, "gpt-4o-mini").with_adaptive_timeout(
+            AdaptiveTimeout::new(Duration::from_secs(1), Duration::from_secs(1)),
+        );
+        let (tx, _rx) = mpsc::channel(8);
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            client.invoke_stream(&[Message::user("Hello")], Some(tx)),
+        )
+        .await
+        .expect("stream should be cut off by the adaptive timeout");
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("stream timed out"), "{}", err);
+        server.abort();
+    }
+
+    /// **Scenario**: probe_health against an unreachable base returns Err mentioning the probe.
+    #[tokio::test]
+    async fn probe_health_with_unreachable_base_returns_error() {
+        let config = OpenAIConfig::new()
+            .with_api_key("test-key")
+            .with_api_base("https://127.0.0.1:1");
+        let client = ChatOpenAI::with_config(config, "gpt-4o-mini")
+            .with_probe_timeout(Duration::from_secs(5));
+
+        let err = client.probe_health().await.unwrap_err();
+
+        assert!(err.to_string().contains("health probe"), "{}", err);
+    }
+
     /// **Scenario**: invoke_stream() against real OpenAI API returns Ok and sends chunks when OPENAI_API_KEY is set.
     /// Given a client with default config and a channel, when we call invoke_stream() with one user message,
     /// then the result is Ok, the response content is non-empty or tool_calls present, and chunks were received.
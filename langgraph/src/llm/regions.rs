@@ -0,0 +1,256 @@
+//! Several API bases (regions) of one provider with health-based failover.
+//!
+//! A [`ChatOpenAI`](super::ChatOpenAI) built with `with_region` holds one client per API
+//! base, e.g. two Azure OpenAI resources in different regions. Each request goes to the
+//! fastest healthy region (by an EWMA of observed latency; regions not measured yet come after
+//! measured ones, in the order they were added). When a region fails with an error another
+//! region might not have (unavailable, timeout, rate limit, quota, connection failure), the
+//! request is retried on the next region. After [`FailoverPolicy::with_failure_threshold`]
+//! consecutive failures a region is skipped for [`FailoverPolicy::with_cooldown`]; then it gets
+//! requests again and one success makes it healthy. If every region is down, they are tried
+//! anyway, the one coming back soonest first.
+//!
+//! **Interaction**: Used by `ChatOpenAI::call_with_policy` and `probe_health`; the regions'
+//! state is internal, the policy is set with `ChatOpenAI::with_failover_policy`.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_openai::{config::Config, Client};
+
+use crate::error::AgentError;
+use crate::llm::LlmErrorKind;
+
+/// Weight of the newest sample in a region's latency average.
+const EWMA_ALPHA: f64 = 0.3;
+
+/// When a region counts as down and for how long.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FailoverPolicy {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl FailoverPolicy {
+    /// Two consecutive failures take a region out for 30 seconds.
+    pub fn new() -> Self {
+        Self {
+            failure_threshold: 2,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+
+    /// Consecutive failures after which a region is skipped (at least 1).
+    pub fn with_failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// How long a failing region is skipped before it is tried again.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `error` may not happen in another region, so the request should move on.
+pub(crate) fn fails_over(error: &AgentError) -> bool {
+    match error {
+        AgentError::Llm(e) => e.is_retryable() || e.kind == LlmErrorKind::QuotaExceeded,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Default)]
+struct RegionHealth {
+    ewma_secs: Option<f64>,
+    consecutive_failures: u32,
+    down_until: Option<Instant>,
+}
+
+/// One API base.
+pub(crate) struct Region {
+    pub(crate) name: String,
+    pub(crate) client: Client<Box<dyn Config>>,
+    health: Mutex<RegionHealth>,
+}
+
+impl Region {
+    pub(crate) fn new(name: impl Into<String>, client: Client<Box<dyn Config>>) -> Self {
+        Self {
+            name: name.into(),
+            client,
+            health: Mutex::new(RegionHealth::default()),
+        }
+    }
+}
+
+/// The regions of a client and the policy that marks them down.
+pub(crate) struct Regions {
+    regions: Vec<Region>,
+    policy: FailoverPolicy,
+}
+
+impl Regions {
+    /// A single region: the client's primary API base.
+    pub(crate) fn new(primary: Region) -> Self {
+        Self {
+            regions: vec![primary],
+            policy: FailoverPolicy::default(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, region: Region) {
+        self.regions.push(region);
+    }
+
+    pub(crate) fn set_policy(&mut self, policy: FailoverPolicy) {
+        self.policy = policy;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &Region {
+        &self.regions[index]
+    }
+
+    /// Applies `f` to every region's client (e.g. to swap the HTTP client).
+    pub(crate) fn map_clients(
+        self,
+        f: impl Fn(Client<Box<dyn Config>>) -> Client<Box<dyn Config>>,
+    ) -> Self {
+        let regions = self
+            .regions
+            .into_iter()
+            .map(|region| Region {
+                client: f(region.client),
+                ..region
+            })
+            .collect();
+        Self { regions, ..self }
+    }
+
+    /// Indexes of the regions in the order to try them: healthy ones by latency (measured
+    /// first), then those marked down, soonest back first.
+    pub(crate) fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut healthy = Vec::new();
+        let mut down = Vec::new();
+        for (i, region) in self.regions.iter().enumerate() {
+            let health = region.health.lock().unwrap_or_else(|e| e.into_inner());
+            match health.down_until.filter(|until| *until > now) {
+                Some(until) => down.push((until, i)),
+                None => healthy.push((health.ewma_secs, i)),
+            }
+        }
+        healthy.sort_by(|(a, i), (b, j)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b).then(i.cmp(j)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => i.cmp(j),
+        });
+        down.sort();
+        healthy
+            .into_iter()
+            .map(|(_, i)| i)
+            .chain(down.into_iter().map(|(_, i)| i))
+            .collect()
+    }
+
+    /// Records a successful request to region `index` and its latency.
+    pub(crate) fn record_success(&self, index: usize, latency: Duration) {
+        let mut health = self.regions[index]
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let sample = latency.as_secs_f64();
+        health.ewma_secs = Some(match health.ewma_secs {
+            Some(avg) => EWMA_ALPHA * sample + (1.0 - EWMA_ALPHA) * avg,
+            None => sample,
+        });
+        health.consecutive_failures = 0;
+        health.down_until = None;
+    }
+
+    /// Records a failed request to region `index`; marks it down at the failure threshold.
+    pub(crate) fn record_failure(&self, index: usize) {
+        let region = &self.regions[index];
+        let mut health = region.health.lock().unwrap_or_else(|e| e.into_inner());
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= self.policy.failure_threshold {
+            health.down_until = Some(Instant::now() + self.policy.cooldown);
+            if self.regions.len() > 1 {
+                tracing::warn!(
+                    region = %region.name,
+                    failures = health.consecutive_failures,
+                    cooldown_secs = self.policy.cooldown.as_secs(),
+                    "LLM region marked down"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_openai::config::OpenAIConfig;
+
+    use crate::llm::LlmError;
+
+    fn regions(names: &[&str]) -> Regions {
+        let region = |name: &str| {
+            let config: Box<dyn Config> = Box::new(OpenAIConfig::new().with_api_base(name));
+            Region::new(name, Client::with_config(config))
+        };
+        let mut regions = Regions::new(region(names[0]));
+        for name in &names[1..] {
+            regions.push(region(name));
+        }
+        regions
+    }
+
+    /// **Scenario**: Unmeasured regions keep their order; measured ones go first, fastest
+    /// first; a region marked down goes last until its cooldown ends.
+    #[test]
+    fn order_prefers_fast_healthy_regions() {
+        let mut regions = regions(&["http://eu", "http://us", "http://asia"]);
+        assert_eq!(regions.order(), [0, 1, 2]);
+
+        regions.record_success(2, Duration::from_millis(300));
+        regions.record_success(1, Duration::from_millis(100));
+        assert_eq!(regions.order(), [1, 2, 0]);
+
+        regions.set_policy(FailoverPolicy::new().with_failure_threshold(1));
+        regions.record_failure(1);
+        assert_eq!(regions.order(), [2, 0, 1]);
+
+        regions.set_policy(FailoverPolicy::new().with_cooldown(Duration::ZERO));
+        regions.record_failure(2);
+        regions.record_failure(2);
+        assert_eq!(regions.order()[0], 2);
+    }
+
+    /// **Scenario**: Outages, timeouts and quota errors fail over; bad requests do not.
+    #[test]
+    fn fails_over_on_regional_errors_only() {
+        let llm = |kind, status| {
+            let mut e = LlmError::new(kind, "x");
+            e.status = status;
+            AgentError::Llm(e)
+        };
+        assert!(fails_over(&llm(LlmErrorKind::Unavailable, Some(503))));
+        assert!(fails_over(&llm(LlmErrorKind::Timeout, None)));
+        assert!(fails_over(&llm(LlmErrorKind::QuotaExceeded, Some(429))));
+        assert!(!fails_over(&llm(LlmErrorKind::Other, Some(400))));
+        assert!(!fails_over(&AgentError::ExecutionFailed("x".into())));
+    }
+}
@@ -0,0 +1,64 @@
+//! JSON schema for structured output (OpenAI `response_format: json_schema`).
+//!
+//! **Interaction**: Set on [`ChatOpenAI::with_response_format`](super::ChatOpenAI::with_response_format)
+//! so the model replies with JSON matching the schema; `StructuredOutputNode` parses such replies
+//! into a typed value.
+
+use serde_json::Value;
+
+/// Schema the model's reply must match, sent as `response_format` with type `json_schema`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::llm::{ChatOpenAI, JsonSchema};
+/// use serde_json::json;
+///
+/// let schema = JsonSchema::new(
+///     "invoice",
+///     json!({
+///         "type": "object",
+///         "properties": { "number": { "type": "string" }, "total": { "type": "number" } },
+///         "required": ["number", "total"],
+///         "additionalProperties": false
+///     }),
+/// )
+/// .with_description("Fields extracted from an invoice");
+/// let llm = ChatOpenAI::new("gpt-4o-mini").with_response_format(schema);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchema {
+    /// Name of the format (letters, digits, `_` and `-`).
+    pub name: String,
+    /// What the format is for; helps the model fill it in.
+    pub description: Option<String>,
+    /// The JSON Schema of the reply.
+    pub schema: Value,
+    /// Whether the provider must follow the schema exactly (default `true`). Strict mode
+    /// requires every property in `required` and `additionalProperties: false`.
+    pub strict: bool,
+}
+
+impl JsonSchema {
+    /// Strict schema `schema` named `name`.
+    pub fn new(name: impl Into<String>, schema: Value) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            schema,
+            strict: true,
+        }
+    }
+
+    /// Sets the description sent with the schema.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets strict mode; see [`strict`](Self::strict).
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
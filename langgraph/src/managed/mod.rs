@@ -23,6 +23,9 @@ where
     fn get(&self, context: &RunContext<S>) -> T;
 }
 
+/// Key under which [`IsLastStep`] is registered in `RunContext::managed_values`.
+pub const IS_LAST_STEP: &str = "is_last_step";
+
 /// IsLastStep managed value: indicates whether the current step is the last one.
 ///
 /// This managed value can be used by nodes to determine if they are executing
@@ -0,0 +1,121 @@
+//! Keyword scoring (Okapi BM25) for [`SearchMode::Keyword`] and [`SearchMode::Hybrid`].
+//!
+//! Stores without a keyword index of their own score the candidates of a search at query time:
+//! text is split into lowercased alphanumeric terms, and term statistics (document frequency,
+//! average length) are taken over the candidates. Exact names and IDs the user recalls verbatim
+//! (`INV-2041`, `kubectl`) rank first, where cosine similarity of embeddings may miss them.
+//!
+//! **Interaction**: Used by [`InMemoryVectorStore`](super::InMemoryVectorStore) and `LanceStore`
+//! on the text they embed; [`hybrid_scores`] blends the result with vector similarity.
+
+use std::collections::{HashMap, HashSet};
+
+use super::store::SearchMode;
+
+/// Term frequency saturation.
+const K1: f64 = 1.2;
+/// Document length normalization.
+const B: f64 = 0.75;
+
+/// Lowercased alphanumeric runs of `text`: `INV-2041 shipped` gives `inv`, `2041`, `shipped`.
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// BM25 score of each of `documents` for `query`; 0.0 for documents without a query term.
+pub(crate) fn bm25_scores(query: &str, documents: &[String]) -> Vec<f64> {
+    let terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let documents: Vec<Vec<String>> = documents.iter().map(|d| tokenize(d)).collect();
+    if terms.is_empty() || documents.is_empty() {
+        return vec![0.0; documents.len()];
+    }
+    let count = documents.len() as f64;
+    let average_len = documents.iter().map(Vec::len).sum::<usize>() as f64 / count;
+    let mut frequencies: HashMap<&str, f64> = HashMap::new();
+    for document in &documents {
+        let distinct: HashSet<&str> = document.iter().map(String::as_str).collect();
+        for term in distinct.into_iter().filter(|t| terms.contains(*t)) {
+            *frequencies.entry(term).or_default() += 1.0;
+        }
+    }
+
+    documents
+        .iter()
+        .map(|document| {
+            let len_norm = 1.0 - B + B * document.len() as f64 / average_len.max(1.0);
+            frequencies
+                .iter()
+                .map(|(term, df)| {
+                    let tf = document.iter().filter(|t| t == term).count() as f64;
+                    let idf = (1.0 + (count - df + 0.5) / (df + 0.5)).ln();
+                    idf * tf * (K1 + 1.0) / (tf + K1 * len_norm)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Scores of a [`SearchMode::Hybrid`] search: `alpha * vector + (1 - alpha) * keyword`, each
+/// min-max normalized over the candidates first so that the two scales are comparable.
+pub(crate) fn hybrid_scores(alpha: f32, vector: &[f64], keyword: &[f64]) -> Vec<f64> {
+    let alpha = f64::from(SearchMode::Hybrid(alpha).alpha());
+    let vector = normalize(vector);
+    let keyword = normalize(keyword);
+    vector
+        .iter()
+        .zip(&keyword)
+        .map(|(v, k)| alpha * v + (1.0 - alpha) * k)
+        .collect()
+}
+
+/// Scores scaled to 0.0..=1.0; when all are equal, 1.0 if they are positive, else 0.0.
+fn normalize(scores: &[f64]) -> Vec<f64> {
+    let min = scores.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    scores
+        .iter()
+        .map(|s| {
+            if max > min {
+                (s - min) / (max - min)
+            } else if *s > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Only documents containing a query term score; rarer terms and shorter
+    /// documents weigh more, and hybrid scores blend both normalized rankings.
+    #[test]
+    fn scores_exact_terms() {
+        assert_eq!(
+            tokenize("Ticket INV-2041, shipped!"),
+            ["ticket", "inv", "2041", "shipped"]
+        );
+        let documents = [
+            "invoice INV-2041 was paid".to_string(),
+            "invoice INV-3000 was paid late".to_string(),
+            "the weather is nice".to_string(),
+        ];
+        let scores = bm25_scores("inv-2041", &documents);
+        assert!(scores[0] > scores[1], "{:?}", scores);
+        assert!(scores[1] > 0.0);
+        assert_eq!(scores[2], 0.0);
+        assert!(bm25_scores("  ", &documents).iter().all(|s| *s == 0.0));
+
+        let vector = [1.0, 3.0, 2.0];
+        assert_eq!(hybrid_scores(1.0, &vector, &scores), [0.0, 1.0, 0.5]);
+        let keyword_only = hybrid_scores(0.0, &vector, &scores);
+        assert_eq!(keyword_only[0], 1.0);
+        assert_eq!(keyword_only[2], 0.0);
+    }
+}
@@ -57,6 +57,23 @@ pub struct CheckpointMetadata {
     pub created_at: Option<std::time::SystemTime>,
     /// Parent checkpoint IDs (checkpoint_ns -> checkpoint_id).
     pub parents: HashMap<String, String>,
+    /// Checkpoint this one was created from in the same thread (previous step of the run,
+    /// or the checkpoint a resumed / time-travel run started at). None for a thread's first.
+    pub parent_checkpoint_id: Option<String>,
+    /// Labels added after the checkpoint was written, e.g. the outcome of the run it ends
+    /// (see [`Checkpointer::tag_checkpoint`](super::Checkpointer::tag_checkpoint)).
+    pub tags: Vec<String>,
+}
+
+impl CheckpointMetadata {
+    /// Appends the `tags` not present yet, in order.
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+    }
 }
 
 /// Source of the checkpoint (input, loop, update, fork).
@@ -94,6 +111,8 @@ mod tests {
             step: 0,
             created_at: None,
             parents: HashMap::new(),
+            parent_checkpoint_id: None,
+            tags: Vec::new(),
         };
     }
 
@@ -152,6 +171,16 @@ mod tests {
         assert_eq!(checkpoint.id, custom_id);
         assert_eq!(checkpoint.metadata.step, 5);
         assert_eq!(checkpoint.v, CHECKPOINT_VERSION);
+        assert!(checkpoint.pending_node.is_none());
+    }
+
+    /// **Scenario**: with_pending_node marks the checkpoint as interrupted; copy keeps it.
+    #[test]
+    fn checkpoint_with_pending_node() {
+        let checkpoint: Checkpoint<i32> =
+            Checkpoint::from_state(1, CheckpointSource::Loop, 0).with_pending_node("act");
+        assert_eq!(checkpoint.pending_node.as_deref(), Some("act"));
+        assert_eq!(checkpoint.copy().pending_node.as_deref(), Some("act"));
     }
 
     /// **Scenario**: Checkpoint copy creates a deep clone.
@@ -231,6 +260,7 @@ mod tests {
         assert_eq!(metadata.step, 0);
         assert!(metadata.created_at.is_none());
         assert!(metadata.parents.is_empty());
+        assert!(metadata.parent_checkpoint_id.is_none());
     }
 }
 
@@ -260,6 +290,9 @@ pub struct Checkpoint<S> {
     pub updated_channels: Option<Vec<String>>,
     /// Pending sends for message passing.
     pub pending_sends: Vec<PendingWrite>,
+    /// Node to run next: set on every checkpoint of an unfinished run (input, node steps,
+    /// interrupts); `None` when the run finished. Resume and time travel continue from here.
+    pub pending_node: Option<String>,
     /// Metadata for the checkpoint.
     pub metadata: CheckpointMetadata,
 }
@@ -317,11 +350,14 @@ impl<S> Checkpoint<S> {
             versions_seen: HashMap::new(),
             updated_channels: None,
             pending_sends: Vec::new(),
+            pending_node: None,
             metadata: CheckpointMetadata {
                 source,
                 step,
                 created_at: Some(now),
                 parents: HashMap::new(),
+                parent_checkpoint_id: None,
+                tags: Vec::new(),
             },
         }
     }
@@ -346,14 +382,23 @@ impl<S> Checkpoint<S> {
             versions_seen: HashMap::new(),
             updated_channels: None,
             pending_sends: Vec::new(),
+            pending_node: None,
             metadata: CheckpointMetadata {
                 source,
                 step,
                 created_at: Some(now),
                 parents: HashMap::new(),
+                parent_checkpoint_id: None,
+                tags: Vec::new(),
             },
         }
     }
+
+    /// Marks the checkpoint as interrupted: resuming the thread runs `node` next.
+    pub fn with_pending_node(mut self, node: impl Into<String>) -> Self {
+        self.pending_node = Some(node.into());
+        self
+    }
 }
 
 impl<S: Clone> Checkpoint<S> {
@@ -374,6 +419,7 @@ impl<S: Clone> Checkpoint<S> {
                 .collect(),
             updated_channels: self.updated_channels.clone(),
             pending_sends: self.pending_sends.clone(),
+            pending_node: self.pending_node.clone(),
             metadata: self.metadata.clone(),
         }
     }
@@ -399,6 +445,7 @@ impl<S: Default> Default for Checkpoint<S> {
             versions_seen: HashMap::new(),
             updated_channels: None,
             pending_sends: Vec::new(),
+            pending_node: None,
             metadata: CheckpointMetadata::default(),
         }
     }
@@ -3,6 +3,8 @@
 //! Saves and loads checkpoints by (thread_id, checkpoint_ns, checkpoint_id).
 //! Aligns with LangGraph BaseCheckpointSaver. See docs/rust-langgraph/16-memory-design.md §3.3.
 
+use std::time::SystemTime;
+
 use async_trait::async_trait;
 
 use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
@@ -49,9 +51,26 @@ mod tests {
     }
 }
 
+/// A thread with at least one checkpoint, as returned by [`Checkpointer::list_threads`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadInfo {
+    pub thread_id: String,
+    /// When the thread's latest checkpoint was created; None when its metadata has no time.
+    pub last_activity: Option<SystemTime>,
+}
+
+/// Orders threads most recently active first; threads without a time last, then by id.
+pub(crate) fn sort_threads(threads: &mut [ThreadInfo]) {
+    threads.sort_by(|a, b| {
+        b.last_activity
+            .cmp(&a.last_activity)
+            .then_with(|| a.thread_id.cmp(&b.thread_id))
+    });
+}
+
 /// Saves and loads checkpoints by (thread_id, checkpoint_ns, checkpoint_id).
 ///
-/// Aligns with LangGraph BaseCheckpointSaver (put, get_tuple, list).
+/// Aligns with LangGraph BaseCheckpointSaver (put, get_tuple, list, delete_thread).
 /// Implementations: MemorySaver (in-memory). Future: SqliteSaver, PostgresSaver.
 ///
 /// **Interaction**: Injected at compile via StateGraph::compile_with_checkpointer;
@@ -82,4 +101,50 @@ where
         before: Option<&str>,
         after: Option<&str>,
     ) -> Result<Vec<CheckpointListItem>, CheckpointError>;
+
+    /// Threads with checkpoints in any namespace, most recently active first. Only the
+    /// `user_id` of `config` is read, by checkpointers keeping one database per user.
+    ///
+    /// Checkpointers that cannot enumerate threads return `CheckpointError::Storage`.
+    async fn list_threads(
+        &self,
+        _config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        Err(CheckpointError::Storage(
+            "list_threads is not supported by this checkpointer".into(),
+        ))
+    }
+
+    /// Deletes every checkpoint of `config`'s thread in all namespaces; returns whether the
+    /// thread had any. Like [`list_threads`](Self::list_threads), reads only `thread_id` and
+    /// `user_id`.
+    ///
+    /// Checkpointers that cannot delete return `CheckpointError::Storage`.
+    async fn delete_thread(&self, _config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        Err(CheckpointError::Storage(
+            "delete_thread is not supported by this checkpointer".into(),
+        ))
+    }
+
+    /// Adds `tags` to the metadata of the thread's latest checkpoint, or of
+    /// `config.checkpoint_id`; returns false when there is no such checkpoint. Tags already
+    /// present are kept once.
+    ///
+    /// Checkpointers that cannot update metadata return `CheckpointError::Storage`.
+    async fn tag_checkpoint(
+        &self,
+        _config: &RunnableConfig,
+        _tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        Err(CheckpointError::Storage(
+            "tag_checkpoint is not supported by this checkpointer".into(),
+        ))
+    }
+
+    /// Writes checkpoints that were put but are still buffered (see
+    /// [`SqliteSaver::with_write_behind`](super::SqliteSaver::with_write_behind)). Called when a
+    /// run ends; checkpointers writing every put at once have nothing to do.
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        Ok(())
+    }
 }
@@ -1,8 +1,10 @@
-//! Invoke config: thread_id, checkpoint_id, checkpoint_ns, user_id.
+//! Invoke config: thread_id, checkpoint_id, checkpoint_ns, user_id, callbacks.
 //!
 //! Aligns with LangGraph's config["configurable"]. Used by CompiledStateGraph::invoke
 //! and Checkpointer. See docs/rust-langgraph/16-memory-design.md §3.1.
 
+use crate::callbacks::Callbacks;
+
 /// Config for a single invoke. Identifies the thread and optional checkpoint.
 ///
 /// Aligns with LangGraph's config["configurable"] (thread_id, checkpoint_id, checkpoint_ns).
@@ -20,6 +22,8 @@ pub struct RunnableConfig {
     pub checkpoint_ns: String,
     /// Optional user id; used by Store for cross-thread memory (namespace).
     pub user_id: Option<String>,
+    /// Handlers called for this run only, after the graph's (see [`crate::callbacks`]).
+    pub callbacks: Callbacks,
 }
 
 #[cfg(test)]
@@ -44,6 +48,7 @@ mod tests {
             checkpoint_id: Some("cp1".into()),
             checkpoint_ns: "ns".into(),
             user_id: Some("u1".into()),
+            callbacks: Callbacks::default(),
         };
         let c2 = c.clone();
         assert_eq!(c.thread_id, c2.thread_id);
@@ -0,0 +1,712 @@
+//! Routes checkpoints and store items to one database per tenant.
+//!
+//! A [`DbRouter`] maps a tenant — the `user_id` of a [`RunnableConfig`], or the first segment of
+//! a store [`Namespace`] (e.g. `[user_id, "memories"]`) — to its own database, opened on first
+//! use by a [`TenantOpener`]: [`SqliteTenants`] keeps one SQLite file per tenant in a directory,
+//! `PostgresTenants` (feature `postgres`) one schema per tenant in a database. Each tenant can
+//! then be backed up, moved or deleted on its own. Requests without a user go to
+//! [`DEFAULT_TENANT`].
+//!
+//! At most `capacity` tenants ([`DEFAULT_OPEN_TENANTS`] by default) are kept open; opening
+//! another drops the least recently used one, which closes once requests still using it finish.
+//!
+//! [`RoutedCheckpointer`] and [`RoutedStore`] are the `Checkpointer` and `Store` views of a
+//! router. Thread listing and deletion only see the tenant of the caller's `user_id`;
+//! `list_namespaces` names no user, so it visits every tenant.
+//!
+//! **Interaction**: Built by [`build_react_run_context`](crate::build_react_run_context) when
+//! `ReactBuildConfig::tenant_db_dir` is set; langgraph-server sets it with `--tenant-db-dir`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
+use crate::memory::checkpointer::{CheckpointError, Checkpointer, ThreadInfo};
+use crate::memory::config::RunnableConfig;
+use crate::memory::embedder::Embedder;
+use crate::memory::retention::CheckpointRetention;
+use crate::memory::serializer::Serializer;
+use crate::memory::sqlite_saver::SqliteSaver;
+use crate::memory::sqlite_store::SqliteStore;
+use crate::memory::sqlite_write_behind::CheckpointSync;
+use crate::memory::store::{
+    Item, ListNamespacesOptions, Namespace, SearchItem, SearchOptions, Store, StoreError, StoreOp,
+    StoreOpResult,
+};
+
+/// Tenant of requests without a `user_id` (or of store calls with an empty namespace).
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Tenants a [`DbRouter`] keeps open unless [`DbRouter::with_capacity`] says otherwise.
+pub const DEFAULT_OPEN_TENANTS: usize = 64;
+
+/// Checks that `tenant` is usable as a file or schema name: 1–63 ASCII letters, digits, `-`,
+/// `_`, `.` or `@`, not starting with `.`.
+fn check_tenant(tenant: &str) -> Result<(), CheckpointError> {
+    let valid = !tenant.is_empty()
+        && tenant.len() <= 63
+        && !tenant.starts_with('.')
+        && tenant
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@'));
+    if !valid {
+        return Err(CheckpointError::Storage(format!(
+            "invalid tenant {:?}",
+            tenant
+        )));
+    }
+    Ok(())
+}
+
+fn to_store_error(e: CheckpointError) -> StoreError {
+    StoreError::Storage(e.to_string())
+}
+
+/// The checkpointer and store of one tenant.
+pub struct TenantDb<S> {
+    pub checkpointer: Arc<dyn Checkpointer<S>>,
+    pub store: Arc<dyn Store>,
+}
+
+impl<S> Clone for TenantDb<S> {
+    fn clone(&self) -> Self {
+        Self {
+            checkpointer: self.checkpointer.clone(),
+            store: self.store.clone(),
+        }
+    }
+}
+
+/// Opens the database of a tenant, creating it when missing.
+#[async_trait]
+pub trait TenantOpener<S>: Send + Sync {
+    /// Opens (and creates if needed) the database of `tenant`; the name is already validated.
+    async fn open(&self, tenant: &str) -> Result<TenantDb<S>, CheckpointError>;
+
+    /// Tenants that have a database, open or not.
+    async fn tenants(&self) -> Result<Vec<String>, CheckpointError>;
+}
+
+/// One SQLite file per tenant: `<dir>/<tenant>.db`, holding its checkpoints and store items.
+pub struct SqliteTenants<S> {
+    dir: PathBuf,
+    serializer: Arc<dyn Serializer<S>>,
+    retention: CheckpointRetention,
+    sync: Option<CheckpointSync>,
+}
+
+impl<S> SqliteTenants<S> {
+    /// Tenant files live in `dir`, created on first open.
+    pub fn new(dir: impl Into<PathBuf>, serializer: Arc<dyn Serializer<S>>) -> Self {
+        Self {
+            dir: dir.into(),
+            serializer,
+            retention: CheckpointRetention::new(),
+            sync: None,
+        }
+    }
+
+    /// Applies `retention` to every tenant's checkpoints (see [`SqliteSaver::with_retention`]).
+    pub fn with_retention(mut self, retention: CheckpointRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Queues every tenant's checkpoint writes (see [`SqliteSaver::with_write_behind`]).
+    pub fn with_write_behind(mut self, sync: CheckpointSync) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+}
+
+#[async_trait]
+impl<S> TenantOpener<S> for SqliteTenants<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn open(&self, tenant: &str) -> Result<TenantDb<S>, CheckpointError> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            CheckpointError::Storage(format!("tenant dir {}: {}", self.dir.display(), e))
+        })?;
+        let path = self.dir.join(format!("{}.db", tenant));
+        let mut checkpointer =
+            SqliteSaver::new(&path, self.serializer.clone())?.with_retention(self.retention);
+        if let Some(sync) = self.sync {
+            checkpointer = checkpointer.with_write_behind(sync);
+        }
+        let store = SqliteStore::new(&path).map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        Ok(TenantDb {
+            checkpointer: Arc::new(checkpointer),
+            store: Arc::new(store),
+        })
+    }
+
+    async fn tenants(&self) -> Result<Vec<String>, CheckpointError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(CheckpointError::Storage(e.to_string())),
+        };
+        let mut tenants = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?
+                .path();
+            if path.extension().and_then(|e| e.to_str()) != Some("db") {
+                continue;
+            }
+            if let Some(tenant) = path.file_stem().and_then(|s| s.to_str()) {
+                if check_tenant(tenant).is_ok() {
+                    tenants.push(tenant.to_string());
+                }
+            }
+        }
+        Ok(tenants)
+    }
+}
+
+/// One Postgres schema per tenant, `<schema_prefix><tenant>`, each with its own small pool and
+/// the usual `langgraph_*` tables.
+#[cfg(feature = "postgres")]
+pub struct PostgresTenants<S> {
+    url: String,
+    schema_prefix: String,
+    max_connections: u32,
+    admin: sqlx::PgPool,
+    serializer: Arc<dyn Serializer<S>>,
+}
+
+#[cfg(feature = "postgres")]
+impl<S> PostgresTenants<S> {
+    /// Connects to `url`; tenant schemas are named `<schema_prefix><tenant>`.
+    pub async fn connect(
+        url: &str,
+        schema_prefix: impl Into<String>,
+        serializer: Arc<dyn Serializer<S>>,
+    ) -> Result<Self, CheckpointError> {
+        let admin = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(1)
+            .connect(url)
+            .await
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        Ok(Self {
+            url: url.to_string(),
+            schema_prefix: schema_prefix.into(),
+            max_connections: 2,
+            admin,
+            serializer,
+        })
+    }
+
+    /// Connections per open tenant (default 2).
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl<S> TenantOpener<S> for PostgresTenants<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn open(&self, tenant: &str) -> Result<TenantDb<S>, CheckpointError> {
+        use crate::memory::{PostgresSaver, PostgresStore};
+        use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+
+        let storage = |e: sqlx::Error| CheckpointError::Storage(e.to_string());
+        let schema = format!("\"{}{}\"", self.schema_prefix.replace('"', "\"\""), tenant);
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema))
+            .execute(&self.admin)
+            .await
+            .map_err(storage)?;
+        let options = self
+            .url
+            .parse::<PgConnectOptions>()
+            .map_err(storage)?
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .connect_with(options)
+            .await
+            .map_err(storage)?;
+        let checkpointer = PostgresSaver::from_pool(pool.clone(), self.serializer.clone()).await?;
+        let store = PostgresStore::from_pool(pool)
+            .await
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        Ok(TenantDb {
+            checkpointer: Arc::new(checkpointer),
+            store: Arc::new(store),
+        })
+    }
+
+    async fn tenants(&self) -> Result<Vec<String>, CheckpointError> {
+        let schemas: Vec<(String,)> = sqlx::query_as(
+            "SELECT schema_name::text FROM information_schema.schemata
+             WHERE left(schema_name, length($1)) = $1",
+        )
+        .bind(&self.schema_prefix)
+        .fetch_all(&self.admin)
+        .await
+        .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        Ok(schemas
+            .into_iter()
+            .filter_map(|(schema,)| schema.strip_prefix(&self.schema_prefix).map(String::from))
+            .filter(|tenant| check_tenant(tenant).is_ok())
+            .collect())
+    }
+}
+
+/// Open tenant databases with the tick of their last use.
+struct OpenTenants<S> {
+    dbs: HashMap<String, (TenantDb<S>, u64)>,
+    tick: u64,
+}
+
+/// Maps tenants to their databases, opening them lazily and keeping the most recently used open.
+pub struct DbRouter<S> {
+    opener: Box<dyn TenantOpener<S>>,
+    capacity: usize,
+    open: Mutex<OpenTenants<S>>,
+}
+
+impl<S> DbRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Router opening tenants with `opener`, keeping [`DEFAULT_OPEN_TENANTS`] open.
+    pub fn new(opener: impl TenantOpener<S> + 'static) -> Self {
+        Self {
+            opener: Box::new(opener),
+            capacity: DEFAULT_OPEN_TENANTS,
+            open: Mutex::new(OpenTenants {
+                dbs: HashMap::new(),
+                tick: 0,
+            }),
+        }
+    }
+
+    /// Keeps at most `capacity` (at least 1) tenants open.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Tenant of a run: its `user_id`, or [`DEFAULT_TENANT`].
+    pub fn tenant_of(config: &RunnableConfig) -> &str {
+        config
+            .user_id
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .unwrap_or(DEFAULT_TENANT)
+    }
+
+    /// Database of `tenant`, opened (and created) on first use.
+    pub async fn tenant(&self, tenant: &str) -> Result<TenantDb<S>, CheckpointError> {
+        check_tenant(tenant)?;
+        let mut open = self.open.lock().await;
+        open.tick += 1;
+        let tick = open.tick;
+        if let Some((db, used)) = open.dbs.get_mut(tenant) {
+            *used = tick;
+            return Ok(db.clone());
+        }
+        let db = self.opener.open(tenant).await?;
+        if open.dbs.len() >= self.capacity {
+            let lru = open
+                .dbs
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(name, _)| name.clone());
+            if let Some(lru) = lru {
+                open.dbs.remove(&lru);
+                tracing::debug!(tenant = %lru, "closed least recently used tenant database");
+            }
+        }
+        open.dbs.insert(tenant.to_string(), (db.clone(), tick));
+        Ok(db)
+    }
+
+    /// Tenants currently open, sorted.
+    pub async fn open_tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self.open.lock().await.dbs.keys().cloned().collect();
+        tenants.sort();
+        tenants
+    }
+
+    /// Every tenant with a database, sorted.
+    pub async fn tenants(&self) -> Result<Vec<String>, CheckpointError> {
+        let mut tenants = self.opener.tenants().await?;
+        tenants.extend(self.open_tenants().await);
+        tenants.sort();
+        tenants.dedup();
+        Ok(tenants)
+    }
+
+    /// `Checkpointer` routing by the run's `user_id`.
+    pub fn checkpointer(self: &Arc<Self>) -> RoutedCheckpointer<S> {
+        RoutedCheckpointer {
+            router: self.clone(),
+        }
+    }
+
+    /// `Store` routing by the first namespace segment.
+    pub fn store(self: &Arc<Self>) -> RoutedStore<S> {
+        RoutedStore {
+            router: self.clone(),
+        }
+    }
+}
+
+/// Checkpointer writing each run to the database of its `user_id`.
+pub struct RoutedCheckpointer<S> {
+    router: Arc<DbRouter<S>>,
+}
+
+impl<S> RoutedCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn inner(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Arc<dyn Checkpointer<S>>, CheckpointError> {
+        let tenant = DbRouter::<S>::tenant_of(config);
+        Ok(self.router.tenant(tenant).await?.checkpointer)
+    }
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for RoutedCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        self.inner(config).await?.put(config, checkpoint).await
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        self.inner(config).await?.get_tuple(config).await
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        self.inner(config)
+            .await?
+            .list(config, limit, before, after)
+            .await
+    }
+
+    /// Threads of the `user_id`'s tenant.
+    async fn list_threads(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        self.inner(config).await?.list_threads(config).await
+    }
+
+    /// Deletes the thread in the `user_id`'s tenant only; a thread with the same id in another
+    /// tenant is left alone.
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        self.inner(config).await?.delete_thread(config).await
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        self.inner(config).await?.tag_checkpoint(config, tags).await
+    }
+
+    /// Flushes the open tenants; closed ones wrote their queue when they were closed.
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        for tenant in self.router.open_tenants().await {
+            let db = self.router.tenant(&tenant).await?;
+            db.checkpointer.flush().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Store keeping each namespace in the database of its first segment.
+pub struct RoutedStore<S> {
+    router: Arc<DbRouter<S>>,
+}
+
+impl<S> RoutedStore<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn inner(&self, namespace: &Namespace) -> Result<Arc<dyn Store>, StoreError> {
+        let tenant = namespace
+            .first()
+            .map(String::as_str)
+            .filter(|t| !t.is_empty())
+            .unwrap_or(DEFAULT_TENANT);
+        Ok(self
+            .router
+            .tenant(tenant)
+            .await
+            .map_err(to_store_error)?
+            .store)
+    }
+}
+
+#[async_trait]
+impl<S> Store for RoutedStore<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        self.inner(namespace)
+            .await?
+            .put(namespace, key, value)
+            .await
+    }
+
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        self.inner(namespace)
+            .await?
+            .put_many(namespace, items)
+            .await
+    }
+
+    async fn get(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, StoreError> {
+        self.inner(namespace).await?.get(namespace, key).await
+    }
+
+    async fn get_item(&self, namespace: &Namespace, key: &str) -> Result<Option<Item>, StoreError> {
+        self.inner(namespace).await?.get_item(namespace, key).await
+    }
+
+    async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
+        self.inner(namespace).await?.delete(namespace, key).await
+    }
+
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        self.inner(namespace).await?.clear(namespace).await
+    }
+
+    async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
+        self.inner(namespace).await?.list(namespace).await
+    }
+
+    /// Searches the tenant of the prefix's first segment; an empty prefix searches
+    /// [`DEFAULT_TENANT`] only.
+    async fn search(
+        &self,
+        namespace_prefix: &Namespace,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchItem>, StoreError> {
+        self.inner(namespace_prefix)
+            .await?
+            .search(namespace_prefix, options)
+            .await
+    }
+
+    /// Namespaces of every tenant, sorted, then paginated.
+    async fn list_namespaces(
+        &self,
+        options: ListNamespacesOptions,
+    ) -> Result<Vec<Namespace>, StoreError> {
+        let (limit, offset) = (options.limit, options.offset);
+        let per_tenant = ListNamespacesOptions {
+            limit: limit.saturating_add(offset),
+            offset: 0,
+            ..options
+        };
+        let mut namespaces = Vec::new();
+        for tenant in self.router.tenants().await.map_err(to_store_error)? {
+            let db = self.router.tenant(&tenant).await.map_err(to_store_error)?;
+            namespaces.extend(db.store.list_namespaces(per_tenant.clone()).await?);
+        }
+        namespaces.sort();
+        namespaces.dedup();
+        Ok(namespaces.into_iter().skip(offset).take(limit).collect())
+    }
+
+    /// Runs each operation on its tenant, in order.
+    async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError> {
+        let mut results = Vec::with_capacity(ops.len());
+        for op in ops {
+            let result = match op {
+                StoreOp::ListNamespaces { options } => {
+                    StoreOpResult::ListNamespaces(self.list_namespaces(options).await?)
+                }
+                StoreOp::Get { ref namespace, .. }
+                | StoreOp::Put { ref namespace, .. }
+                | StoreOp::Search {
+                    namespace_prefix: ref namespace,
+                    ..
+                } => {
+                    let store = self.inner(namespace).await?;
+                    store.batch(vec![op]).await?.pop().ok_or_else(|| {
+                        StoreError::Storage("tenant store returned no batch result".into())
+                    })?
+                }
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    async fn reembed(
+        &self,
+        namespace: &Namespace,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        self.inner(namespace)
+            .await?
+            .reembed(namespace, embedder)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::JsonSerializer;
+    use serde_json::json;
+
+    fn router(dir: &std::path::Path) -> Arc<DbRouter<String>> {
+        Arc::new(DbRouter::new(SqliteTenants::new(
+            dir,
+            Arc::new(JsonSerializer),
+        )))
+    }
+
+    fn config(user_id: Option<&str>) -> RunnableConfig {
+        RunnableConfig {
+            thread_id: Some("t1".into()),
+            user_id: user_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Runs of different users with the same thread id land in separate tenant
+    /// files; store items follow their namespace's first segment.
+    #[tokio::test]
+    async fn tenants_get_their_own_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = router(dir.path());
+        let saver = router.checkpointer();
+        let store = router.store();
+
+        for (user, state) in [(Some("alice"), "a"), (Some("bob"), "b"), (None, "d")] {
+            saver
+                .put(
+                    &config(user),
+                    &Checkpoint::from_state(state.to_string(), Default::default(), 0),
+                )
+                .await
+                .unwrap();
+        }
+        let (latest, _) = saver
+            .get_tuple(&config(Some("bob")))
+            .await
+            .unwrap()
+            .expect("checkpoint");
+        assert_eq!(latest.channel_values, "b");
+        assert!(dir.path().join("alice.db").exists());
+        assert!(dir.path().join("default.db").exists());
+        let threads = saver.list_threads(&config(Some("bob"))).await.unwrap();
+        assert_eq!(threads.len(), 1);
+
+        let ns = vec!["alice".to_string(), "memories".to_string()];
+        store.put(&ns, "k", &json!("v")).await.unwrap();
+        let alice = SqliteStore::new(dir.path().join("alice.db")).unwrap();
+        assert_eq!(alice.get(&ns, "k").await.unwrap(), Some(json!("v")));
+        assert_eq!(
+            store
+                .list_namespaces(ListNamespacesOptions::new())
+                .await
+                .unwrap(),
+            vec![ns]
+        );
+    }
+
+    /// **Scenario**: Two tenants share a thread id; listing and deleting it for one leaves the
+    /// other's thread intact.
+    #[tokio::test]
+    async fn delete_thread_stays_in_its_tenant() {
+        let dir = tempfile::tempdir().unwrap();
+        let saver = router(dir.path()).checkpointer();
+        for user in ["alice", "bob"] {
+            saver
+                .put(
+                    &config(Some(user)),
+                    &Checkpoint::from_state(user.to_string(), Default::default(), 0),
+                )
+                .await
+                .unwrap();
+        }
+        assert!(saver.list_threads(&config(None)).await.unwrap().is_empty());
+
+        assert!(saver.delete_thread(&config(Some("alice"))).await.unwrap());
+        assert!(!saver.delete_thread(&config(Some("alice"))).await.unwrap());
+        assert!(saver
+            .get_tuple(&config(Some("alice")))
+            .await
+            .unwrap()
+            .is_none());
+        let (bob, _) = saver
+            .get_tuple(&config(Some("bob")))
+            .await
+            .unwrap()
+            .expect("bob's thread is kept");
+        assert_eq!(bob.channel_values, "bob");
+        let threads = saver.list_threads(&config(Some("bob"))).await.unwrap();
+        assert_eq!(threads.len(), 1);
+    }
+
+    /// **Scenario**: Past its capacity the router closes the least recently used tenant and
+    /// refuses tenant names that are not safe file or schema names.
+    #[tokio::test]
+    async fn least_recently_used_tenant_is_closed() {
+        let dir = tempfile::tempdir().unwrap();
+        let router = Arc::new(
+            DbRouter::<String>::new(SqliteTenants::new(dir.path(), Arc::new(JsonSerializer)))
+                .with_capacity(2),
+        );
+        for tenant in ["a", "b", "a", "c"] {
+            router.tenant(tenant).await.unwrap();
+        }
+        assert_eq!(router.open_tenants().await, vec!["a", "c"]);
+        assert_eq!(router.tenants().await.unwrap(), vec!["a", "b", "c"]);
+
+        assert!(router.tenant("../etc").await.is_err());
+        assert!(router.tenant(".hidden").await.is_err());
+        assert!(router.tenant("").await.is_err());
+    }
+}
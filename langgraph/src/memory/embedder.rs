@@ -1,15 +1,27 @@
 //! Embedder trait for LanceStore. Used to produce vectors from text for put and search.
 //!
 //! Implementations can wrap OpenAI, HuggingFace, or mock embedders for tests.
+//!
+//! Stores embed many texts (`put_many`, `reembed`) in batches of [`Embedder::batch_size`].
+//!
+//! Vector stores pin each namespace to the [`EmbedderPin`] (model and dimension) of its first
+//! write, since vectors from different models are not comparable; see `Store::reembed`.
+
+use std::fmt;
 
 use async_trait::async_trait;
 
 use crate::memory::store::StoreError;
 
+/// Default most texts per [`Embedder::embed`] call when a store embeds many.
+pub const DEFAULT_EMBED_BATCH_SIZE: usize = 64;
+
 /// Produces fixed-size float vectors from text. Used by [`crate::memory::LanceStore`]
 /// for embedding value text on put and query text on search.
 ///
-/// Implementations must be `Send + Sync` for use with async Store methods.
+/// `embed` is async so network embedders (e.g. [`OpenAIEmbedder`](crate::memory::OpenAIEmbedder))
+/// do not block the runtime. Implementations must be `Send + Sync` for use with async Store
+/// methods; retrying provider rate limits is up to the implementation.
 #[async_trait]
 pub trait Embedder: Send + Sync {
     /// Embeds each text into a vector of dimension [`Embedder::dimension`].
@@ -18,4 +30,71 @@ pub trait Embedder: Send + Sync {
 
     /// Vector dimension returned by [`Embedder::embed`].
     fn dimension(&self) -> usize;
+
+    /// Most texts the stores pass to one [`embed`](Embedder::embed) call (default
+    /// [`DEFAULT_EMBED_BATCH_SIZE`]); lower it for providers with small request limits.
+    fn batch_size(&self) -> usize {
+        DEFAULT_EMBED_BATCH_SIZE
+    }
+
+    /// Model that produces the vectors, e.g. `text-embedding-3-small`. Vector stores record it
+    /// per namespace; defaults to the implementing type's name, so override it when one type
+    /// can serve several models.
+    fn model(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Model and dimension a vector store namespace was embedded with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedderPin {
+    /// [`Embedder::model`] of the embedder.
+    pub model: String,
+    /// [`Embedder::dimension`] of the embedder.
+    pub dimension: usize,
+}
+
+impl EmbedderPin {
+    /// Pin of `embedder`.
+    pub fn of(embedder: &dyn Embedder) -> Self {
+        Self {
+            model: embedder.model().to_string(),
+            dimension: embedder.dimension(),
+        }
+    }
+}
+
+impl fmt::Display for EmbedderPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} dims)", self.model, self.dimension)
+    }
+}
+
+/// Embeds `texts` in batches of [`Embedder::batch_size`], checking that every text gets a
+/// vector of the embedder's dimension. Used by the vector stores' `put_many` and `reembed`.
+pub(crate) async fn embed_all(
+    embedder: &dyn Embedder,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, StoreError> {
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(embedder.batch_size().max(1)) {
+        let refs: Vec<&str> = batch.iter().map(String::as_str).collect();
+        let embedded = embedder.embed(&refs).await?;
+        if embedded.len() != batch.len() {
+            return Err(StoreError::EmbeddingError(format!(
+                "embedder returned {} vectors for {} texts",
+                embedded.len(),
+                batch.len()
+            )));
+        }
+        if let Some(v) = embedded.iter().find(|v| v.len() != embedder.dimension()) {
+            return Err(StoreError::EmbeddingError(format!(
+                "embedder returned a vector of dimension {}, expected {}",
+                v.len(),
+                embedder.dimension()
+            )));
+        }
+        vectors.extend(embedded);
+    }
+    Ok(vectors)
 }
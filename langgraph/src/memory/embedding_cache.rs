@@ -0,0 +1,330 @@
+//! Embedding cache: vectors keyed by model and a hash of the text, in memory or in SQLite.
+//!
+//! The same strings get embedded again and again: recurring user queries on every search,
+//! unchanged documents on re-ingest, the same memory on every `put`. [`EmbeddingCache`] wraps
+//! any [`Embedder`] and only sends the texts it has not seen to the inner one; the rest come
+//! from the cache. Entries are keyed by [`Embedder::model`] and a 128-bit FNV-1a hash of the
+//! text, stable across processes, so a SQLite cache stays valid between runs and a cache shared
+//! by several models never mixes their vectors.
+//!
+//! **Interaction**: Implements [`Embedder`], delegating `dimension`, `model` and `batch_size`
+//! to the wrapped embedder, so vector stores (`InMemoryVectorStore`, `SqliteVecStore`,
+//! `LanceStore`) pin and batch exactly as without it.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use rusqlite::OptionalExtension;
+
+use crate::memory::embedder::Embedder;
+use crate::memory::store::StoreError;
+
+/// Most vectors kept by an in-memory cache by default.
+pub const DEFAULT_EMBEDDING_CACHE_CAPACITY: usize = 10_000;
+
+/// Where the vectors are kept.
+enum Backend {
+    /// Bounded map; the oldest entry is dropped when full.
+    Memory {
+        entries: Mutex<MemoryEntries>,
+        capacity: usize,
+    },
+    /// Table `embedding_cache` in a SQLite file; unbounded.
+    Sqlite { db_path: PathBuf },
+}
+
+#[derive(Default)]
+struct MemoryEntries {
+    vectors: HashMap<String, Vec<f32>>,
+    /// Keys in insertion order, for eviction.
+    order: VecDeque<String>,
+}
+
+/// [`Embedder`] that caches the vectors of another one; see the [module docs](self).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::memory::{EmbeddingCache, InMemoryVectorStore, OpenAIEmbedder};
+/// use std::sync::Arc;
+///
+/// let embedder = Arc::new(OpenAIEmbedder::new("text-embedding-3-small"));
+/// let cached = EmbeddingCache::sqlite(embedder, "embeddings.db").unwrap();
+/// let store = InMemoryVectorStore::new(Arc::new(cached));
+/// ```
+pub struct EmbeddingCache {
+    inner: Arc<dyn Embedder>,
+    backend: Backend,
+}
+
+impl EmbeddingCache {
+    /// Caches the vectors of `inner` in memory, at most
+    /// [`DEFAULT_EMBEDDING_CACHE_CAPACITY`] of them.
+    pub fn in_memory(inner: Arc<dyn Embedder>) -> Self {
+        Self {
+            inner,
+            backend: Backend::Memory {
+                entries: Mutex::new(MemoryEntries::default()),
+                capacity: DEFAULT_EMBEDDING_CACHE_CAPACITY,
+            },
+        }
+    }
+
+    /// Caches the vectors of `inner` in the SQLite file at `path` (created when missing), so
+    /// they survive restarts. The file may be a store's database; the cache uses its own table.
+    pub fn sqlite(inner: Arc<dyn Embedder>, path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let db_path = path.as_ref().to_path_buf();
+        let conn =
+            rusqlite::Connection::open(&db_path).map_err(|e| StoreError::Storage(e.to_string()))?;
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS embedding_cache (
+                key TEXT PRIMARY KEY,
+                vector BLOB NOT NULL
+            )
+            "#,
+            [],
+        )
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+        Ok(Self {
+            inner,
+            backend: Backend::Sqlite { db_path },
+        })
+    }
+
+    /// Keeps at most `capacity` vectors in an in-memory cache (at least 1); no effect on a
+    /// SQLite cache.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        if let Backend::Memory { capacity: c, .. } = &mut self.backend {
+            *c = capacity.max(1);
+        }
+        self
+    }
+
+    /// Cache key of `text` for the inner embedder's model.
+    fn key(&self, text: &str) -> String {
+        format!("{:032x}", content_hash(self.inner.model(), text))
+    }
+
+    /// Cached vectors of `keys`, by key.
+    async fn lookup(&self, keys: &[String]) -> Result<HashMap<String, Vec<f32>>, StoreError> {
+        match &self.backend {
+            Backend::Memory { entries, .. } => {
+                let entries = entries.lock().expect("embedding cache lock poisoned");
+                Ok(keys
+                    .iter()
+                    .filter_map(|k| entries.vectors.get(k).map(|v| (k.clone(), v.clone())))
+                    .collect())
+            }
+            Backend::Sqlite { db_path } => {
+                let db_path = db_path.clone();
+                let keys = keys.to_vec();
+                tokio::task::spawn_blocking(move || {
+                    let conn = rusqlite::Connection::open(&db_path)?;
+                    let mut stmt =
+                        conn.prepare_cached("SELECT vector FROM embedding_cache WHERE key = ?1")?;
+                    let mut found = HashMap::new();
+                    for key in keys {
+                        let blob: Option<Vec<u8>> =
+                            stmt.query_row([&key], |row| row.get(0)).optional()?;
+                        if let Some(blob) = blob {
+                            found.insert(key, from_blob(&blob));
+                        }
+                    }
+                    Ok::<_, rusqlite::Error>(found)
+                })
+                .await
+                .map_err(|e| StoreError::Storage(e.to_string()))?
+                .map_err(|e| StoreError::Storage(e.to_string()))
+            }
+        }
+    }
+
+    /// Adds `vectors` to the cache.
+    async fn insert(&self, vectors: Vec<(String, Vec<f32>)>) -> Result<(), StoreError> {
+        match &self.backend {
+            Backend::Memory { entries, capacity } => {
+                let mut entries = entries.lock().expect("embedding cache lock poisoned");
+                for (key, vector) in vectors {
+                    if entries.vectors.insert(key.clone(), vector).is_none() {
+                        entries.order.push_back(key);
+                    }
+                    while entries.vectors.len() > *capacity {
+                        let Some(oldest) = entries.order.pop_front() else {
+                            break;
+                        };
+                        entries.vectors.remove(&oldest);
+                    }
+                }
+                Ok(())
+            }
+            Backend::Sqlite { db_path } => {
+                let db_path = db_path.clone();
+                tokio::task::spawn_blocking(move || {
+                    let mut conn = rusqlite::Connection::open(&db_path)?;
+                    let tx = conn.transaction()?;
+                    {
+                        let mut stmt = tx.prepare_cached(
+                            "INSERT OR REPLACE INTO embedding_cache (key, vector) VALUES (?1, ?2)",
+                        )?;
+                        for (key, vector) in &vectors {
+                            stmt.execute(rusqlite::params![key, to_blob(vector)])?;
+                        }
+                    }
+                    tx.commit()
+                })
+                .await
+                .map_err(|e| StoreError::Storage(e.to_string()))?
+                .map_err(|e| StoreError::Storage(e.to_string()))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for EmbeddingCache {
+    /// Cached vectors for known texts; the others (each distinct text once) are embedded by
+    /// the inner embedder in one call and cached.
+    async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
+        let keys: Vec<String> = texts.iter().map(|t| self.key(t)).collect();
+        let mut vectors = self.lookup(&keys).await?;
+
+        let mut seen = HashSet::new();
+        let missing: Vec<(String, &str)> = keys
+            .iter()
+            .zip(texts)
+            .filter(|(key, _)| !vectors.contains_key(*key) && seen.insert(*key))
+            .map(|(key, text)| (key.clone(), *text))
+            .collect();
+        if !missing.is_empty() {
+            let refs: Vec<&str> = missing.iter().map(|(_, t)| *t).collect();
+            let embedded = self.inner.embed(&refs).await?;
+            if embedded.len() != missing.len() {
+                return Err(StoreError::EmbeddingError(format!(
+                    "embedder returned {} vectors for {} texts",
+                    embedded.len(),
+                    missing.len()
+                )));
+            }
+            let fresh: Vec<(String, Vec<f32>)> = missing
+                .into_iter()
+                .map(|(key, _)| key)
+                .zip(embedded)
+                .collect();
+            if let Err(e) = self.insert(fresh.clone()).await {
+                tracing::warn!(error = %e, "embedding cache write failed");
+            }
+            vectors.extend(fresh);
+        }
+
+        Ok(keys
+            .iter()
+            .map(|k| vectors.get(k).cloned().unwrap_or_default())
+            .collect())
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn batch_size(&self) -> usize {
+        self.inner.batch_size()
+    }
+}
+
+/// 128-bit FNV-1a of `model`, a NUL separator and `text`; stable across processes and Rust
+/// versions (unlike `DefaultHasher`).
+fn content_hash(model: &str, text: &str) -> u128 {
+    const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+    let bytes = model.bytes().chain(std::iter::once(0)).chain(text.bytes());
+    bytes.fold(OFFSET, |hash, byte| {
+        (hash ^ u128::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Little-endian f32s.
+fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Embeds a text as `[len, 1]` and counts the texts it was asked for.
+    #[derive(Default)]
+    struct CountingEmbedder {
+        texts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Embedder for CountingEmbedder {
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
+            self.texts.fetch_add(texts.len(), Ordering::SeqCst);
+            Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn model(&self) -> &str {
+            "counting"
+        }
+    }
+
+    /// **Scenario**: Only texts not seen before reach the inner embedder, each once per call;
+    /// vectors come back in input order. A full in-memory cache drops its oldest entry.
+    #[tokio::test]
+    async fn in_memory_cache_embeds_each_text_once() {
+        let inner = Arc::new(CountingEmbedder::default());
+        let cache = EmbeddingCache::in_memory(inner.clone()).with_capacity(2);
+        assert_eq!(cache.model(), "counting");
+
+        let vectors = cache.embed(&["hello", "hi", "hello"]).await.unwrap();
+        assert_eq!(vectors, [[5.0, 1.0], [2.0, 1.0], [5.0, 1.0]]);
+        assert_eq!(inner.texts.load(Ordering::SeqCst), 2);
+
+        cache.embed(&["hi", "hello"]).await.unwrap();
+        assert_eq!(inner.texts.load(Ordering::SeqCst), 2);
+
+        cache.embed(&["hey"]).await.unwrap();
+        cache.embed(&["hi", "hello"]).await.unwrap();
+        assert_eq!(inner.texts.load(Ordering::SeqCst), 4);
+    }
+
+    /// **Scenario**: A SQLite cache serves vectors written by another instance on the same
+    /// file; keys include the model, so another model embeds again.
+    #[tokio::test]
+    async fn sqlite_cache_persists_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("embeddings.db");
+        let inner = Arc::new(CountingEmbedder::default());
+
+        let first = EmbeddingCache::sqlite(inner.clone(), &path).unwrap();
+        first.embed(&["recurring query"]).await.unwrap();
+        let second = EmbeddingCache::sqlite(inner.clone(), &path).unwrap();
+        let vectors = second.embed(&["recurring query"]).await.unwrap();
+        assert_eq!(vectors, [[15.0, 1.0]]);
+        assert_eq!(inner.texts.load(Ordering::SeqCst), 1);
+
+        assert_ne!(
+            content_hash("counting", "recurring query"),
+            content_hash("other-model", "recurring query")
+        );
+    }
+}
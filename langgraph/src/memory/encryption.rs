@@ -0,0 +1,850 @@
+//! Field-level encryption of conversation text in checkpoints and store items, keyed per user.
+//!
+//! Envelope encryption: every write gets a fresh AES-256-GCM data key that encrypts the text
+//! fields; the data key itself is wrapped by a [`KeyService`] holding one key per user and is
+//! stored next to the ciphertext. Reading a field asks the key service to unwrap its data key
+//! for the same user, so whoever can read the database but not use the key service only sees
+//! ciphertext. A sealed field is a string `enc:v1:<wrapped data key>:<nonce + ciphertext>`
+//! (base64); strings without that prefix are read as they are, so data written before
+//! encryption was turned on stays readable. Each field is bound (as AES-GCM associated data) to
+//! its user, the record holding it (thread and checkpoint id, or namespace and key) and its path
+//! in the value, so a ciphertext copied to another user, record or field does not decrypt.
+//!
+//! [`EncryptedCheckpointer`] encrypts the text of the checkpointed state for the config's
+//! `user_id`: for [`ReActState`] message contents, tool call arguments, tool results, the final
+//! answer and the strings of UI events. [`EncryptedStore`] encrypts every string in a stored
+//! value for the namespace's first segment (`[user_id, "memories"]`); object keys stay readable.
+//! Searches of an encrypted store only match keys: string filters and embeddings see ciphertext.
+//!
+//! **Interaction**: Wraps any [`Checkpointer`] or [`Store`], like the read-only wrappers;
+//! [`LocalKeyService`] keeps user keys in memory, a KMS-backed [`KeyService`] keeps them out of
+//! the process.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
+use crate::memory::checkpointer::{CheckpointError, Checkpointer, ThreadInfo};
+use crate::memory::config::RunnableConfig;
+use crate::memory::embedder::Embedder;
+use crate::memory::store::{
+    Item, ListNamespacesOptions, Namespace, SearchItem, SearchOptions, Store, StoreError, StoreOp,
+    StoreOpResult,
+};
+use crate::message::Message;
+use crate::state::{ReActState, ToolCall};
+
+/// Prefix of an encrypted field.
+const SEALED_PREFIX: &str = "enc:v1:";
+
+/// Length of an AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Error of encrypting or decrypting a field.
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    /// The data has no user to encrypt for (no `user_id` in the config, empty namespace).
+    #[error("user id required for encryption")]
+    MissingUser,
+    /// The key service has no key for the user.
+    #[error("no key for user: {0}")]
+    UnknownUser(String),
+    /// The key service failed (e.g. a KMS request error).
+    #[error("key service: {0}")]
+    KeyService(String),
+    /// A field could not be encrypted or decrypted: malformed, tampered with, or sealed for
+    /// another user.
+    #[error("crypto: {0}")]
+    Crypto(String),
+}
+
+/// Wraps and unwraps data keys with a per-user key encryption key.
+///
+/// Implement it over a KMS so user keys never enter the process; [`LocalKeyService`] is the
+/// in-memory implementation.
+#[async_trait]
+pub trait KeyService: Send + Sync {
+    /// Encrypts `data_key` with the key of `user_id`.
+    async fn wrap_key(&self, user_id: &str, data_key: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+
+    /// Decrypts a data key `wrap_key` returned for `user_id`.
+    async fn unwrap_key(&self, user_id: &str, wrapped: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+}
+
+/// Key service holding 256-bit user keys in memory; data keys are wrapped with AES-256-GCM.
+#[derive(Clone, Default)]
+pub struct LocalKeyService {
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl LocalKeyService {
+    /// Key service without user keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds (or replaces) the key of `user_id` (builder style).
+    pub fn with_user_key(mut self, user_id: impl Into<String>, key: [u8; 32]) -> Self {
+        self.keys
+            .insert(user_id.into(), Aes256Gcm::new(&key.into()));
+        self
+    }
+
+    /// A new random 256-bit key, e.g. for a new user.
+    pub fn generate_key() -> [u8; 32] {
+        Aes256Gcm::generate_key(OsRng).into()
+    }
+
+    fn key(&self, user_id: &str) -> Result<&Aes256Gcm, EncryptionError> {
+        self.keys
+            .get(user_id)
+            .ok_or_else(|| EncryptionError::UnknownUser(user_id.to_string()))
+    }
+}
+
+#[async_trait]
+impl KeyService for LocalKeyService {
+    async fn wrap_key(&self, user_id: &str, data_key: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        seal_bytes(self.key(user_id)?, user_id.as_bytes(), data_key)
+    }
+
+    async fn unwrap_key(&self, user_id: &str, wrapped: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        open_bytes(self.key(user_id)?, user_id.as_bytes(), wrapped)
+    }
+}
+
+/// Encrypts `plain` with a random nonce, bound to `aad`; returns nonce and ciphertext.
+fn seal_bytes(cipher: &Aes256Gcm, aad: &[u8], plain: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let payload = Payload { msg: plain, aad };
+    let ciphertext = cipher
+        .encrypt(&nonce, payload)
+        .map_err(|_| EncryptionError::Crypto("encryption failed".into()))?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts the output of [`seal_bytes`] for the same `aad`.
+fn open_bytes(cipher: &Aes256Gcm, aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < NONCE_LEN {
+        return Err(EncryptionError::Crypto("sealed data too short".into()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    cipher
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| EncryptionError::Crypto("decryption failed: wrong key or tampered".into()))
+}
+
+/// Associated data of a text field in a checkpoint: user, thread, checkpoint and the field's
+/// JSON pointer in the state.
+fn checkpoint_field_aad(
+    user_id: &str,
+    thread_id: &str,
+    checkpoint_id: &str,
+    path: &str,
+) -> Vec<u8> {
+    serde_json::json!(["checkpoint", user_id, thread_id, checkpoint_id, path])
+        .to_string()
+        .into_bytes()
+}
+
+/// Associated data of a string in a store value: namespace (its first segment is the user),
+/// key and the string's JSON pointer in the value.
+fn store_field_aad(namespace: &Namespace, key: &str, path: &str) -> Vec<u8> {
+    serde_json::json!(["store", namespace, key, path])
+        .to_string()
+        .into_bytes()
+}
+
+/// Encrypts the fields of one write under one fresh data key.
+struct Sealer {
+    wrapped: String,
+    cipher: Aes256Gcm,
+}
+
+impl Sealer {
+    /// Draws a data key and has `keys` wrap it for `user_id`.
+    async fn new(keys: &dyn KeyService, user_id: &str) -> Result<Self, EncryptionError> {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let wrapped = keys.wrap_key(user_id, data_key.as_slice()).await?;
+        Ok(Self {
+            wrapped: BASE64.encode(wrapped),
+            cipher: Aes256Gcm::new(&data_key),
+        })
+    }
+
+    /// Replaces `text` by its sealed form, bound to `aad`.
+    fn seal(&self, text: &mut String, aad: &[u8]) -> Result<(), EncryptionError> {
+        let sealed = seal_bytes(&self.cipher, aad, text.as_bytes())?;
+        *text = format!(
+            "{}{}:{}",
+            SEALED_PREFIX,
+            self.wrapped,
+            BASE64.encode(sealed)
+        );
+        Ok(())
+    }
+}
+
+/// Decrypts fields of one user, unwrapping each data key once.
+struct Opener<'a> {
+    keys: &'a dyn KeyService,
+    user_id: &'a str,
+    data_keys: HashMap<String, Aes256Gcm>,
+}
+
+impl<'a> Opener<'a> {
+    fn new(keys: &'a dyn KeyService, user_id: &'a str) -> Self {
+        Self {
+            keys,
+            user_id,
+            data_keys: HashMap::new(),
+        }
+    }
+
+    /// Replaces a sealed `text` by its plaintext, checking it was sealed for `aad`; other text
+    /// is left as it is.
+    async fn open(&mut self, text: &mut String, aad: &[u8]) -> Result<(), EncryptionError> {
+        let Some(rest) = text.strip_prefix(SEALED_PREFIX) else {
+            return Ok(());
+        };
+        let malformed = || EncryptionError::Crypto("malformed sealed field".into());
+        let (wrapped, sealed) = rest.split_once(':').ok_or_else(malformed)?;
+        if !self.data_keys.contains_key(wrapped) {
+            let raw = BASE64.decode(wrapped).map_err(|_| malformed())?;
+            let data_key = self.keys.unwrap_key(self.user_id, &raw).await?;
+            let cipher = Aes256Gcm::new_from_slice(&data_key)
+                .map_err(|_| EncryptionError::Crypto("data key has the wrong length".into()))?;
+            self.data_keys.insert(wrapped.to_string(), cipher);
+        }
+        let sealed = BASE64.decode(sealed).map_err(|_| malformed())?;
+        let plain = open_bytes(&self.data_keys[wrapped], aad, &sealed)?;
+        *text = String::from_utf8(plain).map_err(|_| malformed())?;
+        Ok(())
+    }
+}
+
+/// Collects every string in `value` with its JSON pointer (`/tags/0`), object keys excluded.
+fn strings_mut<'a>(value: &'a mut Value, path: String, out: &mut Vec<(String, &'a mut String)>) {
+    match value {
+        Value::String(s) => out.push((path, s)),
+        Value::Array(items) => {
+            for (i, v) in items.iter_mut().enumerate() {
+                strings_mut(v, format!("{}/{}", path, i), out);
+            }
+        }
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                let k = k.replace('~', "~0").replace('/', "~1");
+                strings_mut(v, format!("{}/{}", path, k), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Gives [`EncryptedCheckpointer`] the text fields of a state, each with its JSON pointer in
+/// the state (`/messages/0/content`).
+///
+/// Implemented for `Fn(&mut S) -> &mut Vec<Message>`, so a plain function returning the
+/// messages works; their contents and tool call arguments are the fields.
+pub trait TextFieldsAccessor<S>: Send + Sync {
+    /// Returns the state's text fields with their paths.
+    fn text_fields_mut<'a>(&self, state: &'a mut S) -> Vec<(String, &'a mut String)>;
+}
+
+impl<S, F> TextFieldsAccessor<S> for F
+where
+    F: Fn(&mut S) -> &mut Vec<Message> + Send + Sync,
+{
+    fn text_fields_mut<'a>(&self, state: &'a mut S) -> Vec<(String, &'a mut String)> {
+        let mut out = Vec::new();
+        message_fields_mut(self(state), &mut out);
+        out
+    }
+}
+
+/// Collects the contents and tool call arguments of `messages` (`/messages/{i}/...`).
+fn message_fields_mut<'a>(messages: &'a mut [Message], out: &mut Vec<(String, &'a mut String)>) {
+    for (i, message) in messages.iter_mut().enumerate() {
+        let path = format!("/messages/{}", i);
+        match message {
+            Message::Assistant {
+                content,
+                tool_calls,
+            } => {
+                out.push((format!("{}/content", path), content));
+                tool_call_fields_mut(tool_calls, &format!("{}/tool_calls", path), out);
+            }
+            other => out.push((format!("{}/content", path), other.content_mut())),
+        }
+    }
+}
+
+/// Collects the arguments of `calls` (`{path}/{i}/arguments`).
+fn tool_call_fields_mut<'a>(
+    calls: &'a mut [ToolCall],
+    path: &str,
+    out: &mut Vec<(String, &'a mut String)>,
+) {
+    for (i, call) in calls.iter_mut().enumerate() {
+        out.push((format!("{}/{}/arguments", path, i), &mut call.arguments));
+    }
+}
+
+/// The text of a [`ReActState`]: messages, pending tool calls and results, the final answer and
+/// the strings of UI events.
+struct ReactTextFields;
+
+impl TextFieldsAccessor<ReActState> for ReactTextFields {
+    fn text_fields_mut<'a>(&self, state: &'a mut ReActState) -> Vec<(String, &'a mut String)> {
+        let mut out = Vec::new();
+        message_fields_mut(&mut state.messages, &mut out);
+        tool_call_fields_mut(&mut state.tool_calls, "/tool_calls", &mut out);
+        for (i, result) in state.tool_results.iter_mut().enumerate() {
+            out.push((format!("/tool_results/{}/content", i), &mut result.content));
+        }
+        if let Some(answer) = state.final_answer.as_mut() {
+            out.push(("/final_answer".to_string(), answer));
+        }
+        for (i, event) in state.ui_events.iter_mut().enumerate() {
+            strings_mut(event, format!("/ui_events/{}", i), &mut out);
+        }
+        out
+    }
+}
+
+/// Checkpointer that stores the text of a state encrypted for the config's `user_id`.
+///
+/// `put` fails with [`CheckpointError::Storage`] when the config has no `user_id` or the key
+/// service refuses the user; so does `get_tuple` when the checkpoint holds sealed fields.
+/// Everything but the text fields (metadata, counters, other channels) is stored as it is.
+pub struct EncryptedCheckpointer<S> {
+    inner: Arc<dyn Checkpointer<S>>,
+    keys: Arc<dyn KeyService>,
+    fields: Arc<dyn TextFieldsAccessor<S>>,
+}
+
+impl<S> EncryptedCheckpointer<S> {
+    /// Wraps `inner`; `fields` finds the text fields of a state.
+    pub fn new(
+        inner: Arc<dyn Checkpointer<S>>,
+        keys: Arc<dyn KeyService>,
+        fields: Arc<dyn TextFieldsAccessor<S>>,
+    ) -> Self {
+        Self {
+            inner,
+            keys,
+            fields,
+        }
+    }
+}
+
+impl EncryptedCheckpointer<ReActState> {
+    /// Wraps a checkpointer of [`ReActState`], encrypting all of its text (see the module docs).
+    pub fn for_react(inner: Arc<dyn Checkpointer<ReActState>>, keys: Arc<dyn KeyService>) -> Self {
+        Self::new(inner, keys, Arc::new(ReactTextFields))
+    }
+}
+
+fn checkpoint_user(config: &RunnableConfig) -> Result<&str, CheckpointError> {
+    config
+        .user_id
+        .as_deref()
+        .ok_or_else(|| CheckpointError::Storage(EncryptionError::MissingUser.to_string()))
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for EncryptedCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        let user_id = checkpoint_user(config)?;
+        let thread_id = config.thread_id.as_deref().unwrap_or_default();
+        let sealer = Sealer::new(self.keys.as_ref(), user_id)
+            .await
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        let mut sealed = checkpoint.clone();
+        let checkpoint_id = sealed.id.clone();
+        for (path, field) in self.fields.text_fields_mut(&mut sealed.channel_values) {
+            let aad = checkpoint_field_aad(user_id, thread_id, &checkpoint_id, &path);
+            sealer
+                .seal(field, &aad)
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        }
+        self.inner.put(config, &sealed).await
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        let Some((mut checkpoint, metadata)) = self.inner.get_tuple(config).await? else {
+            return Ok(None);
+        };
+        let checkpoint_id = checkpoint.id.clone();
+        let fields = self.fields.text_fields_mut(&mut checkpoint.channel_values);
+        if fields.iter().all(|(_, f)| !f.starts_with(SEALED_PREFIX)) {
+            return Ok(Some((checkpoint, metadata)));
+        }
+        let user_id = checkpoint_user(config)?;
+        let thread_id = config.thread_id.as_deref().unwrap_or_default();
+        let mut opener = Opener::new(self.keys.as_ref(), user_id);
+        for (path, field) in fields {
+            let aad = checkpoint_field_aad(user_id, thread_id, &checkpoint_id, &path);
+            opener
+                .open(field, &aad)
+                .await
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        }
+        Ok(Some((checkpoint, metadata)))
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        self.inner.list(config, limit, before, after).await
+    }
+
+    async fn list_threads(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        self.inner.list_threads(config).await
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        self.inner.delete_thread(config).await
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        self.inner.tag_checkpoint(config, tags).await
+    }
+
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        self.inner.flush().await
+    }
+}
+
+/// Store that keeps the strings of its values encrypted for the namespace's user (its first
+/// segment).
+///
+/// Writes to an empty namespace and reads the key service refuses fail with
+/// [`StoreError::Storage`].
+pub struct EncryptedStore {
+    inner: Arc<dyn Store>,
+    keys: Arc<dyn KeyService>,
+}
+
+impl EncryptedStore {
+    /// Wraps `inner`; user keys come from `keys`.
+    pub fn new(inner: Arc<dyn Store>, keys: Arc<dyn KeyService>) -> Self {
+        Self { inner, keys }
+    }
+
+    async fn seal(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<serde_json::Value, StoreError> {
+        let user_id = namespace
+            .first()
+            .ok_or_else(|| StoreError::Storage(EncryptionError::MissingUser.to_string()))?;
+        let sealer = Sealer::new(self.keys.as_ref(), user_id)
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        let mut sealed = value.clone();
+        let mut fields = Vec::new();
+        strings_mut(&mut sealed, String::new(), &mut fields);
+        for (path, field) in fields {
+            sealer
+                .seal(field, &store_field_aad(namespace, key, &path))
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+        }
+        Ok(sealed)
+    }
+
+    async fn open(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &mut serde_json::Value,
+    ) -> Result<(), StoreError> {
+        let mut fields = Vec::new();
+        strings_mut(value, String::new(), &mut fields);
+        if fields.iter().all(|(_, f)| !f.starts_with(SEALED_PREFIX)) {
+            return Ok(());
+        }
+        let user_id = namespace
+            .first()
+            .ok_or_else(|| StoreError::Storage(EncryptionError::MissingUser.to_string()))?;
+        let mut opener = Opener::new(self.keys.as_ref(), user_id);
+        for (path, field) in fields {
+            opener
+                .open(field, &store_field_aad(namespace, key, &path))
+                .await
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn open_item(&self, item: &mut Item) -> Result<(), StoreError> {
+        self.open(&item.namespace, &item.key, &mut item.value).await
+    }
+}
+
+#[async_trait]
+impl Store for EncryptedStore {
+    async fn put(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        let sealed = self.seal(namespace, key, value).await?;
+        self.inner.put(namespace, key, &sealed).await
+    }
+
+    async fn get(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, StoreError> {
+        let Some(mut value) = self.inner.get(namespace, key).await? else {
+            return Ok(None);
+        };
+        self.open(namespace, key, &mut value).await?;
+        Ok(Some(value))
+    }
+
+    async fn get_item(&self, namespace: &Namespace, key: &str) -> Result<Option<Item>, StoreError> {
+        let Some(mut item) = self.inner.get_item(namespace, key).await? else {
+            return Ok(None);
+        };
+        self.open_item(&mut item).await?;
+        Ok(Some(item))
+    }
+
+    async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
+        self.inner.delete(namespace, key).await
+    }
+
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        self.inner.clear(namespace).await
+    }
+
+    async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
+        self.inner.list(namespace).await
+    }
+
+    async fn search(
+        &self,
+        namespace_prefix: &Namespace,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchItem>, StoreError> {
+        let mut hits = self.inner.search(namespace_prefix, options).await?;
+        for hit in &mut hits {
+            self.open_item(&mut hit.item).await?;
+        }
+        Ok(hits)
+    }
+
+    async fn list_namespaces(
+        &self,
+        options: ListNamespacesOptions,
+    ) -> Result<Vec<Namespace>, StoreError> {
+        self.inner.list_namespaces(options).await
+    }
+
+    async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError> {
+        let mut sealed_ops = Vec::with_capacity(ops.len());
+        for op in ops {
+            sealed_ops.push(match op {
+                StoreOp::Put {
+                    namespace,
+                    key,
+                    value: Some(value),
+                } => {
+                    let value = Some(self.seal(&namespace, &key, &value).await?);
+                    StoreOp::Put {
+                        namespace,
+                        key,
+                        value,
+                    }
+                }
+                op => op,
+            });
+        }
+        let mut results = self.inner.batch(sealed_ops).await?;
+        for result in &mut results {
+            match result {
+                StoreOpResult::Get(Some(item)) => self.open_item(item).await?,
+                StoreOpResult::Search(hits) => {
+                    for hit in hits {
+                        self.open_item(&mut hit.item).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(results)
+    }
+
+    async fn reembed(
+        &self,
+        namespace: &Namespace,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        self.inner.reembed(namespace, embedder).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{InMemoryStore, MemorySaver};
+    use serde_json::json;
+
+    fn keys() -> Arc<dyn KeyService> {
+        Arc::new(
+            LocalKeyService::new()
+                .with_user_key("alice", LocalKeyService::generate_key())
+                .with_user_key("bob", LocalKeyService::generate_key()),
+        )
+    }
+
+    fn config(user_id: Option<&str>) -> RunnableConfig {
+        RunnableConfig {
+            thread_id: Some("t1".into()),
+            user_id: user_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Message contents reach the wrapped checkpointer encrypted and come back
+    /// decrypted for the same user; another user's key cannot read them.
+    #[tokio::test]
+    async fn checkpoint_messages_are_sealed_per_user() {
+        let inner = Arc::new(MemorySaver::<ReActState>::new());
+        let saver = EncryptedCheckpointer::for_react(inner.clone(), keys());
+        let state = ReActState {
+            messages: vec![
+                Message::user("my card number is 4242"),
+                Message::assistant("noted"),
+            ],
+            ..Default::default()
+        };
+        saver
+            .put(
+                &config(Some("alice")),
+                &Checkpoint::from_state(state, Default::default(), 0),
+            )
+            .await
+            .unwrap();
+
+        let (stored, _) = inner
+            .get_tuple(&config(None))
+            .await
+            .unwrap()
+            .expect("checkpoint");
+        assert!(stored.channel_values.messages[0]
+            .content()
+            .starts_with(SEALED_PREFIX));
+        assert!(!format!("{:?}", stored.channel_values).contains("4242"));
+
+        let (read, _) = saver
+            .get_tuple(&config(Some("alice")))
+            .await
+            .unwrap()
+            .expect("checkpoint");
+        let contents: Vec<_> = read
+            .channel_values
+            .messages
+            .iter()
+            .map(Message::content)
+            .collect();
+        assert_eq!(contents, ["my card number is 4242", "noted"]);
+
+        assert!(saver.get_tuple(&config(Some("bob"))).await.is_err());
+        assert!(saver.get_tuple(&config(None)).await.is_err());
+    }
+
+    /// **Scenario**: Tool call arguments, tool results, the final answer and the strings of UI
+    /// events are sealed like message contents: the stored checkpoint holds none of their
+    /// plaintexts and reading it back restores all of them.
+    #[tokio::test]
+    async fn checkpoint_react_text_is_sealed() {
+        let inner = Arc::new(MemorySaver::<ReActState>::new());
+        let saver = EncryptedCheckpointer::for_react(inner.clone(), keys());
+        let call = ToolCall {
+            name: "lookup".into(),
+            arguments: r#"{"iban":"DE00-1111"}"#.into(),
+            id: Some("c1".into()),
+        };
+        let state = ReActState {
+            messages: vec![
+                Message::user("what do I owe?"),
+                Message::assistant_with_tool_calls("", vec![call.clone()]),
+            ],
+            tool_calls: vec![call],
+            tool_results: vec![crate::state::ToolResult {
+                call_id: Some("c1".into()),
+                name: Some("lookup".into()),
+                content: "debt 2222".into(),
+            }],
+            final_answer: Some("owe 3333".into()),
+            ui_events: vec![json!({"type": "table", "rows": [["row-4444"]]})],
+            ..Default::default()
+        };
+        saver
+            .put(
+                &config(Some("alice")),
+                &Checkpoint::from_state(state.clone(), Default::default(), 0),
+            )
+            .await
+            .unwrap();
+
+        let (stored, _) = inner.get_tuple(&config(None)).await.unwrap().unwrap();
+        let raw = serde_json::to_string(&stored.channel_values).unwrap();
+        for plain in ["I owe", "DE00-1111", "debt 2222", "owe 3333", "row-4444"] {
+            assert!(!raw.contains(plain), "{:?} in {}", plain, raw);
+        }
+
+        let (read, _) = saver
+            .get_tuple(&config(Some("alice")))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&read.channel_values).unwrap(),
+            serde_json::to_value(&state).unwrap()
+        );
+    }
+
+    /// **Scenario**: Every string of a stored value is encrypted for the namespace's user,
+    /// object keys stay readable, and get, search and batch return plaintext.
+    #[tokio::test]
+    async fn store_values_are_sealed_for_namespace_user() {
+        let inner = Arc::new(InMemoryStore::new());
+        let store = EncryptedStore::new(inner.clone(), keys());
+        let ns = vec!["alice".to_string(), "memories".to_string()];
+        let value = json!({"text": "likes coffee", "tags": ["food"], "weight": 2});
+
+        store.put(&ns, "pref", &value).await.unwrap();
+        let stored = inner.get(&ns, "pref").await.unwrap().unwrap();
+        assert!(stored["text"].as_str().unwrap().starts_with(SEALED_PREFIX));
+        assert!(!stored.to_string().contains("coffee"));
+        assert_eq!(stored["weight"], 2);
+
+        assert_eq!(store.get(&ns, "pref").await.unwrap(), Some(value.clone()));
+        let hits = store.search(&ns, SearchOptions::new()).await.unwrap();
+        assert_eq!(hits[0].item.value, value);
+        let results = store
+            .batch(vec![StoreOp::Get {
+                namespace: ns.clone(),
+                key: "pref".into(),
+            }])
+            .await
+            .unwrap();
+        assert!(matches!(&results[0], StoreOpResult::Get(Some(item)) if item.value == value));
+    }
+
+    /// **Scenario**: Plaintext written before encryption stays readable; users the key
+    /// service does not know cannot write.
+    #[tokio::test]
+    async fn plaintext_passes_and_unknown_users_fail() {
+        let inner = Arc::new(InMemoryStore::new());
+        let store = EncryptedStore::new(inner.clone(), keys());
+        let ns = vec!["alice".to_string(), "memories".to_string()];
+        inner.put(&ns, "old", &json!("plain")).await.unwrap();
+        assert_eq!(store.get(&ns, "old").await.unwrap(), Some(json!("plain")));
+
+        let carol = vec!["carol".to_string(), "memories".to_string()];
+        let err = store.put(&carol, "k", &json!("v")).await.unwrap_err();
+        assert!(err.to_string().contains("carol"), "{}", err);
+    }
+
+    /// **Scenario**: A sealed string copied to another key or another field of the same user
+    /// does not decrypt.
+    #[tokio::test]
+    async fn store_fields_are_bound_to_key_and_path() {
+        let inner = Arc::new(InMemoryStore::new());
+        let store = EncryptedStore::new(inner.clone(), keys());
+        let ns = vec!["alice".to_string(), "memories".to_string()];
+        store
+            .put(&ns, "a", &json!({"text": "secret", "note": "other"}))
+            .await
+            .unwrap();
+        let stored = inner.get(&ns, "a").await.unwrap().unwrap();
+
+        inner.put(&ns, "b", &stored).await.unwrap();
+        assert!(store.get(&ns, "b").await.is_err());
+
+        let swapped = json!({"text": stored["note"], "note": stored["text"]});
+        inner.put(&ns, "a", &swapped).await.unwrap();
+        assert!(store.get(&ns, "a").await.is_err());
+    }
+
+    /// **Scenario**: A checkpoint without sealed messages is read without a user; a sealed
+    /// message copied into another thread's checkpoint does not decrypt.
+    #[tokio::test]
+    async fn checkpoint_user_only_needed_for_sealed_messages() {
+        let inner = Arc::new(MemorySaver::<ReActState>::new());
+        let saver = EncryptedCheckpointer::for_react(inner.clone(), keys());
+        let state = ReActState {
+            messages: vec![Message::user("hello")],
+            ..Default::default()
+        };
+        inner
+            .put(
+                &config(None),
+                &Checkpoint::from_state(state.clone(), Default::default(), 0),
+            )
+            .await
+            .unwrap();
+        let (read, _) = saver.get_tuple(&config(None)).await.unwrap().unwrap();
+        assert_eq!(read.channel_values.messages[0].content(), "hello");
+
+        saver
+            .put(
+                &config(Some("alice")),
+                &Checkpoint::from_state(state, Default::default(), 1),
+            )
+            .await
+            .unwrap();
+        let (sealed, _) = inner.get_tuple(&config(None)).await.unwrap().unwrap();
+        let other = RunnableConfig {
+            thread_id: Some("t2".into()),
+            user_id: Some("alice".into()),
+            ..Default::default()
+        };
+        inner.put(&other, &sealed).await.unwrap();
+        assert!(saver.get_tuple(&other).await.is_err());
+    }
+}
@@ -11,8 +11,8 @@ use async_trait::async_trait;
 use tokio::sync::RwLock;
 
 use crate::memory::store::{
-    Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType, SearchItem,
-    SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
+    matches_filter, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
+    SearchItem, SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
 };
 
 /// Stored entry with value and metadata.
@@ -169,6 +169,13 @@ impl Store for InMemoryStore {
         Ok(())
     }
 
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let mut guard = self.inner.write().await;
+        let before = guard.len();
+        guard.retain(|_, item| item.namespace != *namespace);
+        Ok(before - guard.len())
+    }
+
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let prefix = Self::namespace_prefix(namespace);
         let guard = self.inner.read().await;
@@ -211,36 +218,7 @@ impl Store for InMemoryStore {
         }
 
         // Apply filter operators if provided
-        if let Some(ref filter) = options.filter {
-            for (field, op) in filter {
-                hits.retain(|h| {
-                    let field_value = h.item.value.get(field);
-                    match (field_value, op) {
-                        (Some(v), crate::memory::store::FilterOp::Eq(expected)) => v == expected,
-                        (Some(v), crate::memory::store::FilterOp::Ne(expected)) => v != expected,
-                        (Some(v), crate::memory::store::FilterOp::Gt(expected)) => {
-                            compare_json(v, expected) == Some(std::cmp::Ordering::Greater)
-                        }
-                        (Some(v), crate::memory::store::FilterOp::Gte(expected)) => {
-                            matches!(
-                                compare_json(v, expected),
-                                Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
-                            )
-                        }
-                        (Some(v), crate::memory::store::FilterOp::Lt(expected)) => {
-                            compare_json(v, expected) == Some(std::cmp::Ordering::Less)
-                        }
-                        (Some(v), crate::memory::store::FilterOp::Lte(expected)) => {
-                            matches!(
-                                compare_json(v, expected),
-                                Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
-                            )
-                        }
-                        _ => false,
-                    }
-                });
-            }
-        }
+        hits.retain(|h| matches_filter(&h.item.value, options.filter.as_ref()));
 
         // Apply offset and limit
         let offset = options.offset;
@@ -360,7 +338,7 @@ impl Store for InMemoryStore {
             query: query.map(String::from),
             filter: None,
             limit: limit.unwrap_or(10),
-            offset: 0,
+            ..SearchOptions::new()
         };
         let results = self.search(namespace, options).await?;
         Ok(results
@@ -374,23 +352,10 @@ impl Store for InMemoryStore {
     }
 }
 
-/// Compare two JSON values for ordering (only works for numbers and strings).
-fn compare_json(a: &serde_json::Value, b: &serde_json::Value) -> Option<std::cmp::Ordering> {
-    use serde_json::Value;
-    match (a, b) {
-        (Value::Number(a), Value::Number(b)) => {
-            let a = a.as_f64()?;
-            let b = b.as_f64()?;
-            a.partial_cmp(&b)
-        }
-        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
-        _ => None,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::store::FilterOp;
     use serde_json::json;
 
     /// **Scenario**: Put and get a value returns the stored value.
@@ -456,6 +421,57 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// **Scenario**: clear deletes every item of the namespace and returns the count; nested
+    /// and other namespaces keep theirs.
+    #[tokio::test]
+    async fn clear_removes_namespace_items() {
+        let store = InMemoryStore::new();
+        let ns: Namespace = vec!["u1".into()];
+        let nested: Namespace = vec!["u1".into(), "notes".into()];
+        store.put(&ns, "a", &json!(1)).await.unwrap();
+        store.put(&ns, "b", &json!(2)).await.unwrap();
+        store.put(&nested, "c", &json!(3)).await.unwrap();
+
+        assert_eq!(store.clear(&ns).await.unwrap(), 2);
+        assert!(store.get(&ns, "a").await.unwrap().is_none());
+        assert_eq!(store.get(&nested, "c").await.unwrap(), Some(json!(3)));
+        assert_eq!(store.clear(&ns).await.unwrap(), 0);
+    }
+
+    /// **Scenario**: search_with_filter keeps values matching the tag and date range, and the
+    /// limit applies to the matches.
+    #[tokio::test]
+    async fn search_with_filter_scopes_by_tag_and_date() {
+        let store = InMemoryStore::new();
+        let ns: Namespace = vec!["memories".into()];
+        for (key, tag, date) in [
+            ("m1", "work", "2024-01-10"),
+            ("m2", "home", "2024-01-12"),
+            ("m3", "work", "2024-03-01"),
+        ] {
+            store
+                .put(&ns, key, &json!({"text": key, "tag": tag, "date": date}))
+                .await
+                .unwrap();
+        }
+
+        let filter = json!({"tag": "work", "date": {"$gte": "2024-01-01", "$lt": "2024-02-01"}});
+        let hits = store.search_with_filter(&ns, None, &filter).await.unwrap();
+        let keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+        assert_eq!(keys, vec!["m1"]);
+
+        let options = SearchOptions::new()
+            .with_filter("tag", FilterOp::Eq(json!("work")))
+            .with_limit(1);
+        assert_eq!(store.search(&ns, options).await.unwrap().len(), 1);
+
+        let err = store
+            .search_with_filter(&ns, None, &json!({"date": {"$before": "2024"}}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StoreError::Serialization(_)), "{}", err);
+    }
+
     /// **Scenario**: List returns all keys in namespace.
     #[tokio::test]
     async fn list_returns_all_keys() {
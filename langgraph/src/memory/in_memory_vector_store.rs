@@ -1,18 +1,25 @@
 //! In-memory vector store for semantic search.
 //!
-//! Uses embeddings for semantic similarity search. Not persistent.
+//! Uses embeddings for semantic similarity search. Not persistent. [`SearchMode::Keyword`]
+//! ranks by BM25 over the same text instead, and [`SearchMode::Hybrid`] blends both.
+//!
+//! Each namespace is pinned to the embedder of its first write; [`Store::reembed`] switches a
+//! namespace to another embedder and re-embeds its values.
 
 use async_trait::async_trait;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use serde_json::Value as JsonValue;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::SystemTime;
 
-use crate::memory::embedder::Embedder;
+use crate::memory::bm25::{bm25_scores, hybrid_scores};
+use crate::memory::embedder::{embed_all, Embedder, EmbedderPin};
 use crate::memory::store::{
-    Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType, SearchItem,
-    SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
+    matches_filter, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
+    SearchItem, SearchMode, SearchOptions, Store, StoreError, StoreOp, StoreOpResult,
+    StoreSearchHit,
 };
 
 /// Pure in-memory vector store for semantic search.
@@ -24,6 +31,10 @@ use crate::memory::store::{
 pub struct InMemoryVectorStore {
     data: DashMap<String, VectorEntry>,
     embedder: Arc<dyn Embedder>,
+    /// Embedder each namespace was first written with.
+    pins: DashMap<Namespace, EmbedderPin>,
+    /// Embedders set by `reembed`, replacing `embedder` for their namespace.
+    namespace_embedders: DashMap<Namespace, Arc<dyn Embedder>>,
 }
 
 /// Entry in the vector store.
@@ -84,6 +95,42 @@ impl InMemoryVectorStore {
         Self {
             data: DashMap::new(),
             embedder,
+            pins: DashMap::new(),
+            namespace_embedders: DashMap::new(),
+        }
+    }
+
+    /// Embedder used for `namespace`: the one set by `reembed`, else the store's.
+    fn embedder_for(&self, namespace: &Namespace) -> Arc<dyn Embedder> {
+        self.namespace_embedders
+            .get(namespace)
+            .map(|e| e.value().clone())
+            .unwrap_or_else(|| self.embedder.clone())
+    }
+
+    /// Fails with `EmbedderMismatch` when `namespace` is pinned to another embedder; pins it to
+    /// `current` when `pin_if_new` and it has no pin yet.
+    fn check_pin(
+        &self,
+        namespace: &Namespace,
+        current: EmbedderPin,
+        pin_if_new: bool,
+    ) -> Result<(), StoreError> {
+        match self.pins.entry(namespace.clone()) {
+            Entry::Occupied(pinned) if *pinned.get() != current => {
+                Err(StoreError::EmbedderMismatch {
+                    namespace: namespace.clone(),
+                    pinned: pinned.get().clone(),
+                    current,
+                })
+            }
+            Entry::Occupied(_) => Ok(()),
+            Entry::Vacant(vacant) => {
+                if pin_if_new {
+                    vacant.insert(current);
+                }
+                Ok(())
+            }
         }
     }
 
@@ -165,9 +212,11 @@ impl Store for InMemoryVectorStore {
         key: &str,
         value: &JsonValue,
     ) -> Result<(), StoreError> {
+        let embedder = self.embedder_for(namespace);
+        self.check_pin(namespace, EmbedderPin::of(embedder.as_ref()), true)?;
         let text = Self::text_from_value(value);
 
-        let vectors = self.embedder.embed(&[&text]).await?;
+        let vectors = embedder.embed(&[&text]).await?;
         let vector = vectors
             .into_iter()
             .next()
@@ -185,6 +234,33 @@ impl Store for InMemoryVectorStore {
         Ok(())
     }
 
+    /// Embeds the values in batches of the embedder's `batch_size` (see [`embed_all`]), then
+    /// stores them.
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, JsonValue)],
+    ) -> Result<(), StoreError> {
+        let embedder = self.embedder_for(namespace);
+        self.check_pin(namespace, EmbedderPin::of(embedder.as_ref()), true)?;
+        let texts: Vec<String> = items
+            .iter()
+            .map(|(_, value)| Self::text_from_value(value))
+            .collect();
+        let vectors = embed_all(embedder.as_ref(), &texts).await?;
+
+        for ((key, value), vector) in items.iter().zip(vectors) {
+            let compound_key = Self::make_key(namespace, key);
+            if let Some(mut existing) = self.data.get_mut(&compound_key) {
+                existing.update(value.clone(), vector);
+            } else {
+                let entry = VectorEntry::new(namespace.clone(), key.clone(), value.clone(), vector);
+                self.data.insert(compound_key, entry);
+            }
+        }
+        Ok(())
+    }
+
     async fn get(&self, namespace: &Namespace, key: &str) -> Result<Option<JsonValue>, StoreError> {
         let compound_key = Self::make_key(namespace, key);
 
@@ -206,6 +282,14 @@ impl Store for InMemoryVectorStore {
         Ok(())
     }
 
+    /// Removes the namespace's entries; its embedder pin stays.
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let ns_prefix = Self::namespace_prefix(namespace);
+        let before = self.data.len();
+        self.data.retain(|key, _| !key.starts_with(&ns_prefix));
+        Ok(before - self.data.len())
+    }
+
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let ns_prefix = Self::namespace_prefix(namespace);
 
@@ -227,39 +311,66 @@ impl Store for InMemoryVectorStore {
         let limit = options.limit.min(1000);
         let ns_prefix = Self::namespace_prefix(namespace_prefix);
 
-        // Semantic search with query
-        if let Some(ref q) = options.query {
-            if !q.is_empty() {
-                let vectors = self.embedder.embed(&[q]).await?;
+        // Ranked search with query: vector, keyword or hybrid
+        if let Some(q) = options.query.as_deref().filter(|q| !q.is_empty()) {
+            let query_vec = if options.mode == SearchMode::Keyword {
+                None
+            } else {
+                let embedder = self.embedder_for(namespace_prefix);
+                self.check_pin(namespace_prefix, EmbedderPin::of(embedder.as_ref()), false)?;
+                let vectors = embedder.embed(&[q]).await?;
                 let query_vec = vectors
                     .into_iter()
                     .next()
                     .ok_or_else(|| StoreError::EmbeddingError("No vector returned".into()))?;
-
-                let mut scores: Vec<(String, f32)> = Vec::new();
-
-                for entry in self.data.iter() {
-                    if entry.key().starts_with(&ns_prefix) {
-                        let score = Self::cosine_similarity(&query_vec, &entry.vector);
-                        scores.push((entry.key().clone(), score));
+                Some(query_vec)
+            };
+            let keyword = options.mode != SearchMode::Vector;
+
+            let mut keys = Vec::new();
+            let mut vector_scores = Vec::new();
+            let mut texts = Vec::new();
+            for entry in self.data.iter() {
+                if entry.key().starts_with(&ns_prefix)
+                    && matches_filter(&entry.value, options.filter.as_ref())
+                {
+                    keys.push(entry.key().clone());
+                    if let Some(query_vec) = &query_vec {
+                        let score = Self::cosine_similarity(query_vec, &entry.vector);
+                        vector_scores.push(f64::from(score));
+                    }
+                    if keyword {
+                        texts.push(Self::text_from_value(&entry.value));
                     }
                 }
+            }
 
-                scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            let scores = match options.mode {
+                SearchMode::Vector => vector_scores,
+                SearchMode::Keyword => bm25_scores(q, &texts),
+                SearchMode::Hybrid(alpha) => {
+                    hybrid_scores(alpha, &vector_scores, &bm25_scores(q, &texts))
+                }
+            };
+            let mut scores: Vec<(String, f64)> = keys
+                .into_iter()
+                .zip(scores)
+                .filter(|(_, score)| options.mode != SearchMode::Keyword || *score > 0.0)
+                .collect();
+            scores.sort_by(|a, b| b.1.total_cmp(&a.1));
 
-                let hits: Vec<SearchItem> = scores
-                    .into_iter()
-                    .skip(options.offset)
-                    .take(limit)
-                    .filter_map(|(key, score)| {
-                        self.data
-                            .get(&key)
-                            .map(|e| SearchItem::with_score(e.to_item(), score as f64))
-                    })
-                    .collect();
-
-                return Ok(hits);
-            }
+            let hits: Vec<SearchItem> = scores
+                .into_iter()
+                .skip(options.offset)
+                .take(limit)
+                .filter_map(|(key, score)| {
+                    self.data
+                        .get(&key)
+                        .map(|e| SearchItem::with_score(e.to_item(), score))
+                })
+                .collect();
+
+            return Ok(hits);
         }
 
         // Non-semantic search (no query): return items up to limit
@@ -267,6 +378,7 @@ impl Store for InMemoryVectorStore {
             .data
             .iter()
             .filter(|e| e.key().starts_with(&ns_prefix))
+            .filter(|e| matches_filter(&e.value, options.filter.as_ref()))
             .skip(options.offset)
             .take(limit)
             .map(|e| SearchItem::from_item(e.to_item()))
@@ -369,6 +481,31 @@ impl Store for InMemoryVectorStore {
         Ok(results)
     }
 
+    async fn reembed(
+        &self,
+        namespace: &Namespace,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        let ns_prefix = Self::namespace_prefix(namespace);
+        let (keys, texts): (Vec<String>, Vec<String>) = self
+            .data
+            .iter()
+            .filter(|e| e.key().starts_with(&ns_prefix))
+            .map(|e| (e.key().clone(), Self::text_from_value(&e.value)))
+            .unzip();
+
+        let vectors = embed_all(embedder.as_ref(), &texts).await?;
+        for (key, vector) in keys.iter().zip(vectors) {
+            if let Some(mut entry) = self.data.get_mut(key) {
+                entry.vector = vector;
+            }
+        }
+        self.pins
+            .insert(namespace.clone(), EmbedderPin::of(embedder.as_ref()));
+        self.namespace_embedders.insert(namespace.clone(), embedder);
+        Ok(keys.len())
+    }
+
     async fn search_simple(
         &self,
         namespace: &Namespace,
@@ -379,7 +516,7 @@ impl Store for InMemoryVectorStore {
             query: query.map(String::from),
             filter: None,
             limit: limit.unwrap_or(10),
-            offset: 0,
+            ..SearchOptions::new()
         };
         let results = self.search(namespace, options).await?;
         Ok(results
@@ -568,6 +705,97 @@ mod tests {
         assert_eq!(v2, Some(serde_json::json!("v2")));
     }
 
+    /// Embeds every text as `[len, 1.0, ...]` under the model name `model`.
+    struct NamedEmbedder {
+        model: &'static str,
+        dimension: usize,
+    }
+
+    #[async_trait]
+    impl Embedder for NamedEmbedder {
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let mut v = vec![1f32; self.dimension];
+                    v[0] = t.len() as f32;
+                    v
+                })
+                .collect())
+        }
+
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        fn model(&self) -> &str {
+            self.model
+        }
+    }
+
+    /// **Scenario**: reembed re-embeds only the given namespace, pins it to the new embedder and
+    /// uses that embedder for later writes there; a namespace pinned to another embedder refuses
+    /// writes.
+    #[tokio::test]
+    async fn reembed_migrates_namespace_to_new_embedder() {
+        let store = InMemoryVectorStore::new(Arc::new(MockEmbedder::new(8)));
+        let ns: Namespace = vec!["u1".into()];
+        let other: Namespace = vec!["u2".into()];
+        store
+            .put(&ns, "a", &serde_json::json!({"text": "tea"}))
+            .await
+            .unwrap();
+        store
+            .put(&ns, "b", &serde_json::json!({"text": "coffee"}))
+            .await
+            .unwrap();
+        store
+            .put(&other, "c", &serde_json::json!("x"))
+            .await
+            .unwrap();
+
+        let small = Arc::new(NamedEmbedder {
+            model: "small",
+            dimension: 3,
+        });
+        assert_eq!(store.reembed(&ns, small).await.unwrap(), 2);
+        let b = store
+            .data
+            .get(&InMemoryVectorStore::make_key(&ns, "b"))
+            .unwrap();
+        assert_eq!(b.vector, vec![6.0, 1.0, 1.0]);
+        drop(b);
+        let c = store
+            .data
+            .get(&InMemoryVectorStore::make_key(&other, "c"))
+            .unwrap();
+        assert_eq!(c.vector.len(), 8);
+        drop(c);
+
+        store.put(&ns, "d", &serde_json::json!("y")).await.unwrap();
+        let hits = store
+            .search(&ns, SearchOptions::new().with_query("tea"))
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 3);
+
+        let err = store
+            .check_pin(
+                &other,
+                EmbedderPin::of(&NamedEmbedder {
+                    model: "small",
+                    dimension: 3,
+                }),
+                true,
+            )
+            .unwrap_err();
+        assert!(
+            matches!(&err, StoreError::EmbedderMismatch { pinned, .. } if pinned.dimension == 8),
+            "{}",
+            err
+        );
+    }
+
     /// **Scenario**: Cosine similarity returns 0.0 for zero vectors.
     #[test]
     fn test_cosine_similarity_zero_vectors() {
@@ -627,6 +855,74 @@ mod tests {
         assert_eq!(hits.len(), 1);
     }
 
+    /// **Scenario**: Semantic search only ranks entries matching the filter; clear empties the
+    /// namespace and returns the count.
+    #[tokio::test]
+    async fn test_search_with_filter_and_clear() {
+        let embedder = Arc::new(MockEmbedder::new(64));
+        let store = InMemoryVectorStore::new(embedder);
+
+        let ns = vec!["test".into()];
+        for (key, text, tag) in [
+            ("a", "rust ownership", "lang"),
+            ("b", "rust traits", "lang"),
+            ("c", "rust belt", "places"),
+        ] {
+            store
+                .put(&ns, key, &serde_json::json!({"text": text, "tag": tag}))
+                .await
+                .unwrap();
+        }
+
+        let hits = store
+            .search_with_filter(&ns, Some("rust"), &serde_json::json!({"tag": "lang"}))
+            .await
+            .unwrap();
+        let mut keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b"]);
+        assert!(hits.iter().all(|h| h.score.is_some()));
+
+        assert_eq!(store.clear(&ns).await.unwrap(), 3);
+        assert!(store.list(&ns).await.unwrap().is_empty());
+    }
+
+    /// **Scenario**: An exact ID that vector similarity ranks last is found first by keyword
+    /// search, which skips entries without a query term, and by a keyword-leaning hybrid.
+    #[tokio::test]
+    async fn test_search_keyword_and_hybrid() {
+        let embedder = Arc::new(MockEmbedder::new(1536));
+        let store = InMemoryVectorStore::new(embedder);
+
+        let ns = vec!["test".into()];
+        for (key, text) in [
+            ("typo", "INV-2014"),
+            ("refund", "Refund for INV-2041 approved"),
+            ("weather", "weather is nice"),
+        ] {
+            store
+                .put(&ns, key, &serde_json::json!({ "text": text }))
+                .await
+                .unwrap();
+        }
+        let keys = |hits: Vec<SearchItem>| -> Vec<String> {
+            hits.into_iter().map(|h| h.item.key).collect()
+        };
+
+        let query = SearchOptions::new().with_query("INV-2041");
+        let vector = store.search(&ns, query.clone()).await.unwrap();
+        assert_eq!(keys(vector), ["typo", "weather", "refund"]);
+
+        let keyword = query.clone().with_mode(SearchMode::Keyword);
+        let hits = store.search(&ns, keyword).await.unwrap();
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(keys(hits), ["refund", "typo"]);
+
+        let hybrid = query.with_mode(SearchMode::Hybrid(0.3));
+        let hits = store.search(&ns, hybrid).await.unwrap();
+        assert_eq!(keys(hits), ["refund", "typo", "weather"]);
+    }
+
     /// **Scenario**: list_namespaces returns unique namespaces.
     #[tokio::test]
     async fn test_list_namespaces() {
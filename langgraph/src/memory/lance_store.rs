@@ -1,7 +1,11 @@
 //! LanceDB-backed Store (LanceStore). Persistent with vector search.
 //!
-//! Requires feature `lance`. put/get/list; put embeds value text; search with query uses vector similarity.
+//! Requires feature `lance`. put/get/list/delete/clear; put embeds value text; search with query
+//! uses vector similarity (score: distance, lower is closer) and filters values with
+//! `SearchOptions::filter`. [`SearchMode::Keyword`] ranks the namespace's rows by BM25 over the
+//! same text and [`SearchMode::Hybrid`] blends both (scores: higher is better).
 
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -13,10 +17,14 @@ use async_trait::async_trait;
 use futures::TryStreamExt;
 use lancedb::connection::Connection;
 use lancedb::query::ExecutableQuery;
-use lancedb::query::QueryBase;
+use lancedb::query::{QueryBase, Select};
 
-use crate::memory::embedder::Embedder;
-use crate::memory::store::{Namespace, Store, StoreError, StoreSearchHit};
+use crate::memory::bm25::{bm25_scores, hybrid_scores};
+use crate::memory::embedder::{embed_all, Embedder};
+use crate::memory::store::{
+    matches_filter, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
+    SearchItem, SearchMode, SearchOptions, Store, StoreError, StoreOp, StoreOpResult,
+};
 
 const TABLE_NAME: &str = "store";
 
@@ -24,6 +32,10 @@ fn ns_to_key(ns: &Namespace) -> String {
     serde_json::to_string(ns).unwrap_or_else(|_| "[]".to_string())
 }
 
+fn key_to_ns(key: &str) -> Namespace {
+    serde_json::from_str(key).unwrap_or_default()
+}
+
 /// Escape single quotes for use in LanceDB SQL predicate (e.g. only_if).
 fn escape_sql(s: &str) -> String {
     s.replace('\'', "''")
@@ -97,44 +109,19 @@ impl LanceStore {
         })
     }
 
-    async fn open_table(&self) -> Result<lancedb::Table, StoreError> {
-        self.conn
-            .open_table(&self.table_name)
-            .execute()
-            .await
-            .map_err(|e| StoreError::Storage(e.to_string()))
-    }
-}
-
-#[async_trait]
-impl Store for LanceStore {
-    async fn put(
+    /// Replaces the rows of namespace `ns` with the given `(key, value JSON, vector)` rows.
+    async fn write_rows(
         &self,
-        namespace: &Namespace,
-        key: &str,
-        value: &serde_json::Value,
+        ns: &str,
+        rows: Vec<(String, String, Vec<f32>)>,
     ) -> Result<(), StoreError> {
-        let ns = ns_to_key(namespace);
-        let key = key.to_string();
-        let value_str = serde_json::to_string(value)?;
-        let text = text_from_value(value);
-        let vectors = self.embedder.embed(&[text.as_str()]).await?;
-        let vector = vectors
-            .into_iter()
-            .next()
-            .ok_or_else(|| StoreError::Storage("embedder returned no vector".into()))?;
-        if vector.len() != self.dimension {
-            return Err(StoreError::Storage(format!(
-                "embedder dimension {} != expected {}",
-                vector.len(),
-                self.dimension
-            )));
-        }
-
         let table = self.open_table().await?;
-        let pred_ns = escape_sql(&ns);
-        let pred_key = escape_sql(&key);
-        let predicate = format!("ns = '{}' AND key = '{}'", pred_ns, pred_key);
+        let keys = rows
+            .iter()
+            .map(|(key, _, _)| format!("'{}'", escape_sql(key)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let predicate = format!("ns = '{}' AND key IN ({})", escape_sql(ns), keys);
         table
             .delete(&predicate)
             .await
@@ -156,12 +143,18 @@ impl Store for LanceStore {
         let batch = RecordBatch::try_new(
             schema.clone(),
             vec![
-                Arc::new(StringArray::from(vec![ns.as_str()])),
-                Arc::new(StringArray::from(vec![key.as_str()])),
-                Arc::new(StringArray::from(vec![value_str.as_str()])),
+                Arc::new(StringArray::from(vec![ns; rows.len()])),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|(key, _, _)| key.as_str()),
+                )),
+                Arc::new(StringArray::from_iter_values(
+                    rows.iter().map(|(_, value, _)| value.as_str()),
+                )),
                 Arc::new(
                     FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
-                        std::iter::once(Some(vector.into_iter().map(Some).collect::<Vec<_>>())),
+                        rows.into_iter().map(|(_, _, vector)| {
+                            Some(vector.into_iter().map(Some).collect::<Vec<_>>())
+                        }),
                         self.dimension as i32,
                     ),
                 ),
@@ -179,6 +172,74 @@ impl Store for LanceStore {
         Ok(())
     }
 
+    async fn open_table(&self) -> Result<lancedb::Table, StoreError> {
+        self.conn
+            .open_table(&self.table_name)
+            .execute()
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Store for LanceStore {
+    async fn put(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        let ns = ns_to_key(namespace);
+        let key = key.to_string();
+        let value_str = serde_json::to_string(value)?;
+        let text = text_from_value(value);
+        let vectors = self.embedder.embed(&[text.as_str()]).await?;
+        let vector = vectors
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::Storage("embedder returned no vector".into()))?;
+        if vector.len() != self.dimension {
+            return Err(StoreError::Storage(format!(
+                "embedder dimension {} != expected {}",
+                vector.len(),
+                self.dimension
+            )));
+        }
+
+        self.write_rows(&ns, vec![(key, value_str, vector)]).await
+    }
+
+    /// Embeds the values in batches of the embedder's `batch_size` (see [`embed_all`]) and
+    /// adds them in one write.
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let ns = ns_to_key(namespace);
+        let texts: Vec<String> = items
+            .iter()
+            .map(|(_, value)| text_from_value(value))
+            .collect();
+        let vectors = embed_all(self.embedder.as_ref(), &texts).await?;
+        if let Some(v) = vectors.iter().find(|v| v.len() != self.dimension) {
+            return Err(StoreError::Storage(format!(
+                "embedder dimension {} != expected {}",
+                v.len(),
+                self.dimension
+            )));
+        }
+        let rows = items
+            .iter()
+            .zip(vectors)
+            .map(|((key, value), vector)| Ok((key.clone(), serde_json::to_string(value)?, vector)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        self.write_rows(&ns, rows).await
+    }
+
     async fn get(
         &self,
         namespace: &Namespace,
@@ -247,20 +308,66 @@ impl Store for LanceStore {
         Ok(keys)
     }
 
+    async fn get_item(&self, namespace: &Namespace, key: &str) -> Result<Option<Item>, StoreError> {
+        let value = self.get(namespace, key).await?;
+        Ok(value.map(|value| Item::new(namespace.clone(), key.to_string(), value)))
+    }
+
+    async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
+        let predicate = format!(
+            "ns = '{}' AND key = '{}'",
+            escape_sql(&ns_to_key(namespace)),
+            escape_sql(key)
+        );
+        let table = self.open_table().await?;
+        table
+            .delete(&predicate)
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let predicate = format!("ns = '{}'", escape_sql(&ns_to_key(namespace)));
+        let table = self.open_table().await?;
+        let count = table
+            .count_rows(Some(predicate.clone()))
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+        if count > 0 {
+            table
+                .delete(&predicate)
+                .await
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+        }
+        Ok(count)
+    }
+
+    /// Items carry no stored timestamps: `created_at` and `updated_at` are the time of the
+    /// search. With a filter, the matches are filtered before the limit and offset apply.
     async fn search(
         &self,
-        namespace: &Namespace,
-        query: Option<&str>,
-        limit: Option<usize>,
-    ) -> Result<Vec<StoreSearchHit>, StoreError> {
-        let ns = ns_to_key(namespace);
-        let pred_ns = escape_sql(&ns);
-        let predicate = format!("ns = '{}'", pred_ns);
-        let limit = limit.unwrap_or(100).min(1000);
+        namespace_prefix: &Namespace,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchItem>, StoreError> {
+        let ns = ns_to_key(namespace_prefix);
+        let predicate = format!("ns LIKE '{}%'", escape_sql(ns.trim_end_matches(']')));
+        let limit = options.limit.min(1000);
         let table = self.open_table().await?;
+        let query = options.query.as_deref().filter(|q| !q.is_empty());
+        // Keyword and hybrid scores are computed here, over every row of the namespace.
+        let keyword = query.is_some() && options.mode != SearchMode::Vector;
+        let fetch = if options.filter.is_some() || keyword {
+            table
+                .count_rows(Some(predicate.clone()))
+                .await
+                .map_err(|e| StoreError::Storage(e.to_string()))?
+        } else {
+            limit + options.offset
+        };
 
-        if let Some(q) = query {
-            if !q.is_empty() {
+        let query_vec = match query {
+            Some(q) if options.mode != SearchMode::Keyword => {
                 let vectors = self.embedder.embed(&[q]).await?;
                 let query_vec = vectors
                     .into_iter()
@@ -273,56 +380,69 @@ impl Store for LanceStore {
                         self.dimension
                     )));
                 }
-                let stream = table
+                Some(query_vec)
+            }
+            _ => None,
+        };
+        let stream = match &query_vec {
+            Some(query_vec) => {
+                table
                     .query()
                     .nearest_to(query_vec.as_slice())
                     .map_err(|e| StoreError::Storage(e.to_string()))?
                     .only_if(predicate)
-                    .limit(limit)
+                    .limit(fetch.max(1))
                     .execute()
                     .await
-                    .map_err(|e| StoreError::Storage(e.to_string()))?;
-                let batches: Vec<RecordBatch> = stream
-                    .try_collect()
+            }
+            None => {
+                table
+                    .query()
+                    .only_if(predicate)
+                    .limit(fetch.max(1))
+                    .execute()
                     .await
-                    .map_err(|e| StoreError::Storage(e.to_string()))?;
-                let mut hits = Vec::new();
-                for batch in batches {
-                    let key_col = batch
-                        .column_by_name("key")
-                        .ok_or_else(|| StoreError::Storage("missing key column".into()))?;
-                    let value_col = batch
-                        .column_by_name("value")
-                        .ok_or_else(|| StoreError::Storage("missing value column".into()))?;
-                    let key_arr = key_col
-                        .as_any()
-                        .downcast_ref::<StringArray>()
-                        .ok_or_else(|| StoreError::Storage("key column not string".into()))?;
-                    let value_arr = value_col
-                        .as_any()
-                        .downcast_ref::<StringArray>()
-                        .ok_or_else(|| StoreError::Storage("value column not string".into()))?;
-                    let score_col = batch.column_by_name("_distance");
-                    for i in 0..batch.num_rows() {
-                        let key = key_arr.value(i).to_string();
-                        let value =
-                            serde_json::from_str(value_arr.value(i)).map_err(StoreError::from)?;
-                        let score = score_col.and_then(|col| {
-                            col.as_any()
-                                .downcast_ref::<Float32Array>()
-                                .map(|arr| arr.value(i) as f64)
-                        });
-                        hits.push(StoreSearchHit { key, value, score });
-                    }
+            }
+        }
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+        let batches: Vec<RecordBatch> = stream
+            .try_collect()
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+
+        let mut hits = Vec::new();
+        for batch in &batches {
+            for (namespace, key, value, distance) in rows_of(batch)? {
+                // LIKE also matches sibling namespaces sharing a name prefix.
+                if !namespace.starts_with(namespace_prefix)
+                    || !matches_filter(&value, options.filter.as_ref())
+                {
+                    continue;
                 }
-                return Ok(hits);
+                hits.push((Item::new(namespace, key, value), distance));
             }
         }
+        let hits = match query {
+            Some(q) if keyword => rank_by_keyword(q, options.mode, hits),
+            _ => hits
+                .into_iter()
+                .map(|(item, distance)| match distance {
+                    Some(distance) => SearchItem::with_score(item, distance),
+                    None => SearchItem::from_item(item),
+                })
+                .collect(),
+        };
+        Ok(hits.into_iter().skip(options.offset).take(limit).collect())
+    }
 
+    async fn list_namespaces(
+        &self,
+        options: ListNamespacesOptions,
+    ) -> Result<Vec<Namespace>, StoreError> {
+        let table = self.open_table().await?;
         let stream = table
             .query()
-            .only_if(predicate)
-            .limit(limit)
+            .select(Select::columns(&["ns"]))
             .execute()
             .await
             .map_err(|e| StoreError::Storage(e.to_string()))?;
@@ -330,32 +450,139 @@ impl Store for LanceStore {
             .try_collect()
             .await
             .map_err(|e| StoreError::Storage(e.to_string()))?;
-        let mut hits = Vec::new();
-        for batch in batches {
-            let key_col = batch
-                .column_by_name("key")
-                .ok_or_else(|| StoreError::Storage("missing key column".into()))?;
-            let value_col = batch
-                .column_by_name("value")
-                .ok_or_else(|| StoreError::Storage("missing value column".into()))?;
-            let key_arr = key_col
-                .as_any()
-                .downcast_ref::<StringArray>()
-                .ok_or_else(|| StoreError::Storage("key column not string".into()))?;
-            let value_arr = value_col
-                .as_any()
-                .downcast_ref::<StringArray>()
-                .ok_or_else(|| StoreError::Storage("value column not string".into()))?;
-            for i in 0..batch.num_rows() {
-                let key = key_arr.value(i).to_string();
-                let value = serde_json::from_str(value_arr.value(i)).map_err(StoreError::from)?;
-                hits.push(StoreSearchHit {
+        let mut namespaces = HashSet::new();
+        for batch in &batches {
+            let ns_arr = string_column(batch, "ns")?;
+            for i in 0..ns_arr.len() {
+                let ns = key_to_ns(ns_arr.value(i));
+                if options
+                    .match_conditions
+                    .iter()
+                    .all(|cond| matches_condition(&ns, cond))
+                {
+                    namespaces.insert(match options.max_depth {
+                        Some(depth) => ns.into_iter().take(depth).collect(),
+                        None => ns,
+                    });
+                }
+            }
+        }
+        let mut result: Vec<Namespace> = namespaces.into_iter().collect();
+        result.sort();
+        Ok(result
+            .into_iter()
+            .skip(options.offset)
+            .take(options.limit)
+            .collect())
+    }
+
+    async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError> {
+        let mut results = Vec::with_capacity(ops.len());
+        for op in ops {
+            let result = match op {
+                StoreOp::Get { namespace, key } => {
+                    StoreOpResult::Get(self.get_item(&namespace, &key).await?)
+                }
+                StoreOp::Put {
+                    namespace,
                     key,
                     value,
-                    score: None,
-                });
-            }
+                } => {
+                    match value {
+                        Some(v) => self.put(&namespace, &key, &v).await?,
+                        None => self.delete(&namespace, &key).await?,
+                    }
+                    StoreOpResult::Put
+                }
+                StoreOp::Search {
+                    namespace_prefix,
+                    options,
+                } => StoreOpResult::Search(self.search(&namespace_prefix, options).await?),
+                StoreOp::ListNamespaces { options } => {
+                    StoreOpResult::ListNamespaces(self.list_namespaces(options).await?)
+                }
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+fn string_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a StringArray, StoreError> {
+    batch
+        .column_by_name(name)
+        .ok_or_else(|| StoreError::Storage(format!("missing {} column", name)))?
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| StoreError::Storage(format!("{} column not string", name)))
+}
+
+/// A row as `(namespace, key, value, distance)`; the distance only for vector queries.
+type Row = (Namespace, String, serde_json::Value, Option<f64>);
+
+fn rows_of(batch: &RecordBatch) -> Result<Vec<Row>, StoreError> {
+    let ns_arr = string_column(batch, "ns")?;
+    let key_arr = string_column(batch, "key")?;
+    let value_arr = string_column(batch, "value")?;
+    let distances = batch
+        .column_by_name("_distance")
+        .and_then(|col| col.as_any().downcast_ref::<Float32Array>());
+    (0..batch.num_rows())
+        .map(|i| {
+            let value = serde_json::from_str(value_arr.value(i))?;
+            Ok((
+                key_to_ns(ns_arr.value(i)),
+                key_arr.value(i).to_string(),
+                value,
+                distances.map(|arr| arr.value(i) as f64),
+            ))
+        })
+        .collect()
+}
+
+/// Ranks the rows of a keyword or hybrid search by BM25 over their text, blended with the
+/// negated vector distance for [`SearchMode::Hybrid`]; keyword search drops rows without a
+/// query term.
+fn rank_by_keyword(
+    query: &str,
+    mode: SearchMode,
+    rows: Vec<(Item, Option<f64>)>,
+) -> Vec<SearchItem> {
+    let texts: Vec<String> = rows
+        .iter()
+        .map(|(item, _)| text_from_value(&item.value))
+        .collect();
+    let keyword = bm25_scores(query, &texts);
+    let scores = match mode {
+        SearchMode::Hybrid(alpha) => {
+            let vector: Vec<f64> = rows.iter().map(|(_, d)| -d.unwrap_or_default()).collect();
+            hybrid_scores(alpha, &vector, &keyword)
         }
-        Ok(hits)
+        _ => keyword,
+    };
+    let mut ranked: Vec<(Item, f64)> = rows
+        .into_iter()
+        .map(|(item, _)| item)
+        .zip(scores)
+        .filter(|(_, score)| mode != SearchMode::Keyword || *score > 0.0)
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+        .into_iter()
+        .map(|(item, score)| SearchItem::with_score(item, score))
+        .collect()
+}
+
+fn matches_condition(namespace: &Namespace, condition: &MatchCondition) -> bool {
+    let path = &condition.path;
+    if namespace.len() < path.len() {
+        return false;
     }
+    let start = match condition.match_type {
+        NamespaceMatchType::Prefix => 0,
+        NamespaceMatchType::Suffix => namespace.len() - path.len(),
+    };
+    path.iter()
+        .enumerate()
+        .all(|(i, p)| p == "*" || namespace.get(start + i) == Some(p))
 }
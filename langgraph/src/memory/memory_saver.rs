@@ -10,8 +10,9 @@ use async_trait::async_trait;
 use tokio::sync::RwLock;
 
 use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
-use crate::memory::checkpointer::{CheckpointError, Checkpointer};
+use crate::memory::checkpointer::{sort_threads, CheckpointError, Checkpointer, ThreadInfo};
 use crate::memory::config::RunnableConfig;
+use crate::memory::retention::CheckpointRetention;
 
 /// In-memory checkpointer. Key: (thread_id, checkpoint_ns); each thread has a list of checkpoints.
 ///
@@ -20,11 +21,12 @@ use crate::memory::config::RunnableConfig;
 /// **Interaction**: Used as `Arc<dyn Checkpointer<S>>` in StateGraph::compile_with_checkpointer.
 pub struct MemorySaver<S> {
     inner: Arc<RwLock<MemorySaverInner<S>>>,
+    retention: CheckpointRetention,
 }
 
 struct MemorySaverInner<S> {
-    /// Key: format!("{}:{}", thread_id, checkpoint_ns). Value: list of (checkpoint_id, checkpoint) newest last.
-    by_thread: HashMap<String, Vec<(String, Checkpoint<S>)>>,
+    /// Key: (thread_id, checkpoint_ns). Value: list of (checkpoint_id, checkpoint) newest last.
+    by_thread: HashMap<(String, String), Vec<(String, Checkpoint<S>)>>,
     next_id: u64,
 }
 
@@ -39,15 +41,22 @@ where
                 by_thread: HashMap::new(),
                 next_id: 0,
             })),
+            retention: CheckpointRetention::new(),
         }
     }
 
-    fn thread_key(config: &RunnableConfig) -> Result<String, CheckpointError> {
+    /// Drops checkpoints beyond `retention` from a thread whenever one is put.
+    pub fn with_retention(mut self, retention: CheckpointRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    fn thread_key(config: &RunnableConfig) -> Result<(String, String), CheckpointError> {
         let thread_id = config
             .thread_id
             .as_deref()
             .ok_or(CheckpointError::ThreadIdRequired)?;
-        Ok(format!("{}:{}", thread_id, config.checkpoint_ns))
+        Ok((thread_id.to_string(), config.checkpoint_ns.clone()))
     }
 }
 
@@ -76,11 +85,17 @@ where
         let mut guard = self.inner.write().await;
         let next_id = guard.next_id;
         guard.next_id = next_id.wrapping_add(1);
-        guard
-            .by_thread
-            .entry(key)
-            .or_default()
-            .push((id.clone(), cp));
+        let list = guard.by_thread.entry(key).or_default();
+        list.push((id.clone(), cp));
+        if !self.retention.is_unbounded() {
+            let cutoff = self.retention.cutoff(std::time::SystemTime::now());
+            let mut from_newest = list.len();
+            list.retain(|(_, cp)| {
+                from_newest -= 1;
+                self.retention
+                    .keeps(from_newest, cp.metadata.created_at, cutoff)
+            });
+        }
         Ok(id)
     }
 
@@ -142,4 +157,60 @@ where
         }
         Ok(items)
     }
+
+    async fn list_threads(
+        &self,
+        _config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        let guard = self.inner.read().await;
+        let mut latest: HashMap<&str, Option<std::time::SystemTime>> = HashMap::new();
+        for ((thread_id, _), list) in &guard.by_thread {
+            let Some((_, cp)) = list.last() else {
+                continue;
+            };
+            let entry = latest.entry(thread_id.as_str()).or_default();
+            *entry = (*entry).max(cp.metadata.created_at);
+        }
+        let mut threads: Vec<ThreadInfo> = latest
+            .into_iter()
+            .map(|(thread_id, last_activity)| ThreadInfo {
+                thread_id: thread_id.to_string(),
+                last_activity,
+            })
+            .collect();
+        sort_threads(&mut threads);
+        Ok(threads)
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        let thread_id = config
+            .thread_id
+            .as_deref()
+            .ok_or(CheckpointError::ThreadIdRequired)?;
+        let mut guard = self.inner.write().await;
+        let before = guard.by_thread.len();
+        guard.by_thread.retain(|(id, _), _| id != thread_id);
+        Ok(guard.by_thread.len() < before)
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        let key = Self::thread_key(config)?;
+        let mut guard = self.inner.write().await;
+        let Some(list) = guard.by_thread.get_mut(&key) else {
+            return Ok(false);
+        };
+        let found = match &config.checkpoint_id {
+            Some(cid) => list.iter_mut().find(|(id, _)| id == cid),
+            None => list.last_mut(),
+        };
+        let Some((_, cp)) = found else {
+            return Ok(false);
+        };
+        cp.metadata.add_tags(tags);
+        Ok(true)
+    }
 }
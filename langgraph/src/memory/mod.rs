@@ -25,6 +25,8 @@
 //! |--------------|-------------|-----------------------------|----------|
 //! | [`MemorySaver`]  | In-memory   | Dev, tests                  | —        |
 //! | [`SqliteSaver`]  | SQLite file | Single-node, production     | — |
+//! | `PostgresSaver`  | Postgres    | Multi-instance, production  | `postgres` |
+//! | `RedisSaver`     | Redis       | Multi-instance, low latency, TTL | `redis` |
 //!
 //! Use with [`StateGraph::compile_with_checkpointer`](crate::graph::StateGraph::compile_with_checkpointer).
 //! [`JsonSerializer`] is required for `SqliteSaver` (state must be `Serialize + DeserializeOwned`).
@@ -35,53 +37,132 @@
 //! |------------------|-------------|-----------------------------|----------|
 //! | [`InMemoryStore`] | In-memory   | String filter (key/value)   | —        |
 //! | [`SqliteStore`]   | SQLite file | String filter               | — |
+//! | `PostgresStore`   | Postgres    | String filter               | `postgres` |
 //! | [`SqliteVecStore`] | SQLite file | Vector similarity (semantic) | — |
 //! | [`LanceStore`]      | LanceDB     | Vector similarity (semantic)| `lance`  |
 //! | [`InMemoryVectorStore`] | In-memory | Vector similarity (semantic) | — |
 //!
+//! `PostgresSaver` and `PostgresStore` can share one `PgPool` (`from_pool`); both migrate the
+//! schema on construction.
+//!
 //! `SqliteVecStore`, `LanceStore`, and `InMemoryVectorStore` require an `Embedder` for vector indexing; search with `query` uses semantic similarity.
+//! Embedding is async; `put_many` and `reembed` send the texts in batches of
+//! [`Embedder::batch_size`], and [`OpenAIEmbedder`] retries rate-limited requests.
+//! [`EmbeddingCache`] wraps any embedder to reuse the vectors of texts seen before.
+//! [`SearchMode`] picks how `InMemoryVectorStore` and `LanceStore` rank a query: vector
+//! similarity, BM25 keyword scores for exact names and IDs, or a weighted hybrid of both.
+//! `SqliteVecStore` and `InMemoryVectorStore` pin each namespace to the [`EmbedderPin`] of its
+//! first write and refuse another embedder; `Store::reembed` migrates a namespace to a new one.
+//!
+//! Every store deletes single items (`Store::delete`) and whole namespaces (`Store::clear`), and
+//! filters search results on value fields ([`SearchOptions::filter`],
+//! `Store::search_with_filter`).
+//!
+//! [`CheckpointRetention`] bounds the checkpoints a thread keeps (newest N, max age);
+//! [`MemorySaver`] and [`SqliteSaver`] enforce it on every put (`with_retention`).
+//!
+//! `SqliteSaver::with_write_behind` queues checkpoint puts and writes them in batches, one
+//! transaction each, per a [`CheckpointSync`] policy (always, every interval, or when the run
+//! ends via `Checkpointer::flush`); reads see queued checkpoints at once.
+//!
+//! [`ReadOnlyCheckpointer`] and [`ReadOnlyStore`] wrap any backend and stop its writes while a
+//! shared [`ReadOnlySwitch`] is on, e.g. during a backup.
+//!
+//! [`EncryptedCheckpointer`] and [`EncryptedStore`] keep message contents and memory values
+//! encrypted per user (envelope encryption): data keys are wrapped by a [`KeyService`], so
+//! reading the database alone does not reveal the conversation.
+//!
+//! `SqliteSaver::backup` and `SqliteStore::backup` ([`backup_sqlite`]) take a consistent copy
+//! of a SQLite database while it is in use, through SQLite's online backup API.
+//!
+//! ## Per-tenant databases
+//!
+//! [`DbRouter`] gives each tenant (`user_id`) its own database, opened lazily with an LRU of
+//! open handles: one SQLite file per tenant ([`SqliteTenants`]) or one Postgres schema per
+//! tenant (`PostgresTenants`, feature `postgres`). [`RoutedCheckpointer`] and [`RoutedStore`]
+//! plug it in wherever a checkpointer or store is expected.
 
+mod bm25;
 mod checkpoint;
 mod checkpointer;
 mod config;
+mod db_router;
 mod embedder;
+mod embedding_cache;
+mod encryption;
 mod in_memory_store;
 mod in_memory_vector_store;
 mod memory_saver;
 mod openai_embedder;
+mod read_only;
+mod retention;
 mod serializer;
+mod sqlite_backup;
 mod store;
 mod uuid6;
 
 #[cfg(feature = "lance")]
 mod lance_store;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+mod postgres_saver;
+#[cfg(feature = "postgres")]
+mod postgres_store;
+#[cfg(feature = "redis")]
+mod redis_saver;
 mod sqlite_saver;
 mod sqlite_store;
 mod sqlite_vec_store;
+mod sqlite_write_behind;
 
 pub use checkpoint::{
     writes_idx_map, ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata,
     CheckpointSource, CheckpointTuple, PendingWrite, CHECKPOINT_VERSION, ERROR, INTERRUPT, RESUME,
     SCHEDULED,
 };
-pub use checkpointer::{CheckpointError, Checkpointer};
+pub use checkpointer::{CheckpointError, Checkpointer, ThreadInfo};
 pub use config::RunnableConfig;
+#[cfg(feature = "postgres")]
+pub use db_router::PostgresTenants;
+pub use db_router::{
+    DbRouter, RoutedCheckpointer, RoutedStore, SqliteTenants, TenantDb, TenantOpener,
+    DEFAULT_OPEN_TENANTS, DEFAULT_TENANT,
+};
+pub use encryption::{
+    EncryptedCheckpointer, EncryptedStore, EncryptionError, KeyService, LocalKeyService,
+    TextFieldsAccessor,
+};
 pub use in_memory_store::InMemoryStore;
 pub use memory_saver::MemorySaver;
+pub use read_only::{ReadOnlyCheckpointer, ReadOnlyStore, ReadOnlySwitch};
+pub use retention::CheckpointRetention;
 pub use serializer::{
     JsonSerializer, Serializer, TypedData, TypedSerializer, TYPE_BYTES, TYPE_JSON, TYPE_NULL,
 };
 pub use store::{
     FilterOp, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
-    SearchItem, SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
+    SearchItem, SearchMode, SearchOptions, Store, StoreError, StoreOp, StoreOpResult,
+    StoreSearchHit,
 };
 pub use uuid6::{uuid6, uuid6_with_params, Uuid6};
 
-pub use embedder::Embedder;
+pub use embedder::{Embedder, EmbedderPin, DEFAULT_EMBED_BATCH_SIZE};
+pub use embedding_cache::{EmbeddingCache, DEFAULT_EMBEDDING_CACHE_CAPACITY};
 pub use in_memory_vector_store::InMemoryVectorStore;
 #[cfg(feature = "lance")]
 pub use lance_store::LanceStore;
 pub use openai_embedder::OpenAIEmbedder;
+#[cfg(feature = "postgres")]
+pub use postgres_saver::PostgresSaver;
+#[cfg(feature = "postgres")]
+pub use postgres_store::PostgresStore;
+#[cfg(feature = "postgres")]
+pub use sqlx::postgres::{PgPool, PgPoolOptions};
+#[cfg(feature = "redis")]
+pub use redis_saver::RedisSaver;
+pub use sqlite_backup::{backup_sqlite, BackupError};
 pub use sqlite_saver::SqliteSaver;
 pub use sqlite_store::SqliteStore;
 pub use sqlite_vec_store::SqliteVecStore;
+pub use sqlite_write_behind::CheckpointSync;
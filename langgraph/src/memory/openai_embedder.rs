@@ -5,7 +5,16 @@
 //!
 //! **Interaction**: Implements [`Embedder`]; used by [`LanceStore`](crate::memory::LanceStore) for vector search.
 //!
-//! Requires `OPENAI_API_KEY` environment variable (or custom config).
+//! Requires `OPENAI_API_KEY` environment variable (or custom config). `with_http_client` routes
+//! requests through a configured client (proxy, custom CA), e.g. from
+//! [`HttpClientConfig::shared_client`](crate::llm::HttpClientConfig::shared_client).
+//!
+//! Texts are sent in requests of at most `with_batch_size` inputs; a request answered with a
+//! rate limit (HTTP 429) is retried per `with_retry_policy`, waiting at least the provider's
+//! `Retry-After`.
+
+use std::future::Future;
+use std::time::Duration;
 
 use async_openai::{
     config::OpenAIConfig,
@@ -13,6 +22,10 @@ use async_openai::{
     Client,
 };
 
+use crate::error::AgentError;
+use crate::graph::RetryPolicy;
+use crate::llm::{LlmError, LlmErrorKind};
+use crate::memory::embedder::{Embedder, DEFAULT_EMBED_BATCH_SIZE};
 use crate::memory::store::StoreError;
 
 /// OpenAI Embeddings client implementing [`Embedder`].
@@ -38,6 +51,12 @@ pub struct OpenAIEmbedder {
     config: OpenAIConfig,
     model: String,
     dimensions: usize,
+    /// HTTP client for requests; None uses async-openai's default client.
+    http_client: Option<reqwest::Client>,
+    /// Most inputs per request.
+    batch_size: usize,
+    /// Retries of rate-limited requests.
+    retry: RetryPolicy,
 }
 
 impl OpenAIEmbedder {
@@ -67,6 +86,9 @@ impl OpenAIEmbedder {
             config: OpenAIConfig::new(),
             model,
             dimensions,
+            http_client: None,
+            batch_size: DEFAULT_EMBED_BATCH_SIZE,
+            retry: Self::default_retry_policy(),
         }
     }
 
@@ -95,6 +117,45 @@ impl OpenAIEmbedder {
             config,
             model,
             dimensions,
+            http_client: None,
+            batch_size: DEFAULT_EMBED_BATCH_SIZE,
+            retry: Self::default_retry_policy(),
+        }
+    }
+
+    /// Sends requests through `http_client` (e.g. one with a proxy or custom CA) instead of a
+    /// default client.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sends at most `batch_size` texts per request (default
+    /// [`DEFAULT_EMBED_BATCH_SIZE`](crate::memory::DEFAULT_EMBED_BATCH_SIZE)); at least 1.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Retries requests answered with a rate limit (HTTP 429) according to `retry` (default:
+    /// 4 retries, exponential from 1s to 30s with jitter); `RetryPolicy::None` fails at once.
+    /// Other errors are not retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    fn default_retry_policy() -> RetryPolicy {
+        RetryPolicy::exponential(4, Duration::from_secs(1), Duration::from_secs(30), 2.0)
+            .with_jitter(0.2)
+    }
+
+    /// API client for one request, using the configured HTTP client when set.
+    fn client(&self) -> Client<OpenAIConfig> {
+        let client = Client::with_config(self.config.clone());
+        match &self.http_client {
+            Some(http) => client.with_http_client(http.clone()),
+            None => client,
         }
     }
 
@@ -133,62 +194,80 @@ impl OpenAIEmbedder {
     /// let vector = embedder.embed_one("Hello, world!").await?;
     /// ```
     pub async fn embed_one(&self, text: &str) -> Result<Vec<f32>, StoreError> {
-        let client = Client::with_config(self.config.clone());
+        self.embed(&[text])
+            .await?
+            .pop()
+            .ok_or_else(|| StoreError::EmbeddingError("No embedding returned".to_string()))
+    }
+
+    /// One request embedding `texts`.
+    async fn request(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let input = match texts {
+            [text] => EmbeddingInput::String(text.to_string()),
+            _ => EmbeddingInput::StringArray(texts.iter().map(|&s| s.to_string()).collect()),
+        };
         let request = CreateEmbeddingRequest {
-            input: EmbeddingInput::String(text.to_string()),
+            input,
             model: self.model.clone(),
             ..Default::default()
         };
 
-        let response = client
+        let response = self
+            .client()
             .embeddings()
             .create(request)
             .await
-            .map_err(|e| StoreError::EmbeddingError(format!("OpenAI API error: {}", e)))?;
+            .map_err(|e| LlmError::from_openai(&e))?;
+        Ok(response.data.into_iter().map(|e| e.embedding).collect())
+    }
+}
 
-        if response.data.is_empty() {
-            return Err(StoreError::EmbeddingError(
-                "No embedding returned".to_string(),
-            ));
+/// Runs `call` until it succeeds, fails with anything but a rate limit, or `retry` gives up;
+/// waits per [`RetryPolicy::delay_for`] (at least the provider's retry hint) in between.
+async fn retry_rate_limited<T, F, Fut>(retry: &RetryPolicy, mut call: F) -> Result<T, LlmError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, LlmError>>,
+{
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Err(e) if e.kind == LlmErrorKind::RateLimited && retry.should_retry(attempt) => {
+                let error = AgentError::Llm(e);
+                let delay = retry.delay_for(attempt, &error);
+                tracing::warn!(error = %error, attempt, ?delay, "embedding rate limited; retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
         }
-
-        Ok(response.data[0].embedding.clone())
     }
 }
 
 #[async_trait::async_trait]
-impl crate::memory::Embedder for OpenAIEmbedder {
+impl Embedder for OpenAIEmbedder {
     async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
-        let inputs: Vec<String> = texts.iter().map(|&s| s.to_string()).collect();
-        let input = if inputs.len() == 1 {
-            EmbeddingInput::String(inputs[0].clone())
-        } else {
-            EmbeddingInput::StringArray(inputs)
-        };
-
-        let request = CreateEmbeddingRequest {
-            input,
-            model: self.model.clone(),
-            ..Default::default()
-        };
-
-        let client = Client::with_config(self.config.clone());
-        let response = client
-            .embeddings()
-            .create(request)
-            .await
-            .map_err(|e| StoreError::EmbeddingError(format!("OpenAI API error: {}", e)))?;
-
-        Ok(response
-            .data
-            .into_iter()
-            .map(|e| e.embedding)
-            .collect())
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let embedded = retry_rate_limited(&self.retry, || self.request(batch))
+                .await
+                .map_err(|e| StoreError::EmbeddingError(format!("OpenAI API error: {}", e)))?;
+            vectors.extend(embedded);
+        }
+        Ok(vectors)
     }
 
     fn dimension(&self) -> usize {
         self.dimensions
     }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
 }
 
 #[cfg(test)]
@@ -218,6 +297,7 @@ mod tests {
         let embedder = OpenAIEmbedder::new("text-embedding-3-small");
         assert_eq!(embedder.model, "text-embedding-3-small");
         assert_eq!(embedder.dimension(), 1536);
+        assert_eq!(embedder.model(), "text-embedding-3-small");
 
         let embedder = OpenAIEmbedder::new("text-embedding-3-large");
         assert_eq!(embedder.dimension(), 3072);
@@ -233,6 +313,64 @@ mod tests {
         assert_eq!(embedder.dimension(), 1536);
     }
 
+    /// **Scenario**: Rate-limited calls are retried until they succeed or the policy gives up;
+    /// other errors (quota) fail at once. The batch size is what the stores batch by.
+    #[tokio::test]
+    async fn retries_rate_limited_requests_only() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let retry = RetryPolicy::fixed(2, Duration::from_millis(1));
+        let counter = AtomicUsize::new(0);
+        let calls = &counter;
+        let result = retry_rate_limited(&retry, || async move {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(LlmError::from_http(429, Some("0"), "Rate limit reached")),
+                _ => Ok(vec![vec![1.0]]),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), [[1.0]]);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = retry_rate_limited(&retry, || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(LlmError::classify(
+                Some(429),
+                "You exceeded your current quota",
+            ))
+        })
+        .await;
+        assert_eq!(result.unwrap_err().kind, LlmErrorKind::QuotaExceeded);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let embedder = OpenAIEmbedder::new("text-embedding-3-small").with_batch_size(0);
+        assert_eq!(embedder.batch_size(), 1);
+        assert_eq!(
+            OpenAIEmbedder::new("text-embedding-3-small").batch_size(),
+            DEFAULT_EMBED_BATCH_SIZE
+        );
+    }
+
+    /// **Scenario**: Requests go through the configured HTTP client; an unreachable base is
+    /// reported as an embedding error.
+    #[tokio::test]
+    async fn test_embed_with_http_client_unreachable_base_errors() {
+        use async_openai::config::OpenAIConfig;
+
+        let config = OpenAIConfig::new()
+            .with_api_key("test-key")
+            .with_api_base("http://127.0.0.1:1");
+        let http = crate::llm::HttpClientConfig::default()
+            .shared_client()
+            .unwrap();
+        let embedder =
+            OpenAIEmbedder::with_config(config, "text-embedding-3-small").with_http_client(http);
+        assert!(embedder.http_client.is_some());
+        let err = embedder.embed(&["hello"]).await.unwrap_err();
+        assert!(matches!(err, StoreError::EmbeddingError(_)), "{:?}", err);
+    }
+
     #[tokio::test]
     #[ignore = "Requires OPENAI_API_KEY"]
     async fn test_openai_embed() {
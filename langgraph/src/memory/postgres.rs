@@ -0,0 +1,107 @@
+//! Shared Postgres plumbing for [`PostgresSaver`](super::PostgresSaver) and
+//! [`PostgresStore`](super::PostgresStore): connection pool and schema migrations.
+//!
+//! Migrations are versioned SQL applied once per database and recorded in
+//! `langgraph_migrations`. They run in one transaction under an advisory lock, so several
+//! server replicas starting at once apply them exactly once. Tables are prefixed `langgraph_`
+//! so they can live in an application's existing database.
+
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+/// Max pool connections used by `connect` (`PostgresSaver::connect`, `PostgresStore::connect`).
+pub(crate) const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+
+/// Key of the advisory lock held while migrating ("langgraph" in ASCII, truncated).
+const MIGRATION_LOCK_KEY: i64 = 0x6c61_6e67_6772_6170;
+
+/// (version, name, SQL). Append only; never edit an applied migration.
+const MIGRATIONS: &[(i64, &str, &str)] = &[
+    (
+        1,
+        "checkpoints",
+        r#"
+        CREATE TABLE IF NOT EXISTS langgraph_checkpoints (
+            thread_id TEXT NOT NULL,
+            checkpoint_ns TEXT NOT NULL DEFAULT '',
+            checkpoint_id TEXT NOT NULL,
+            seq BIGSERIAL NOT NULL,
+            ts TEXT NOT NULL,
+            payload BYTEA NOT NULL,
+            channel_versions JSONB NOT NULL,
+            metadata_source TEXT NOT NULL,
+            metadata_step BIGINT NOT NULL,
+            metadata_created_at BIGINT,
+            pending_node TEXT,
+            parent_checkpoint_id TEXT,
+            PRIMARY KEY (thread_id, checkpoint_ns, checkpoint_id)
+        );
+        CREATE INDEX IF NOT EXISTS langgraph_checkpoints_thread_seq_idx
+            ON langgraph_checkpoints (thread_id, checkpoint_ns, seq DESC);
+        "#,
+    ),
+    (
+        2,
+        "store",
+        r#"
+        CREATE TABLE IF NOT EXISTS langgraph_store (
+            ns TEXT[] NOT NULL,
+            key TEXT NOT NULL,
+            value JSONB NOT NULL,
+            created_at BIGINT NOT NULL,
+            updated_at BIGINT NOT NULL,
+            PRIMARY KEY (ns, key)
+        );
+        CREATE INDEX IF NOT EXISTS langgraph_store_ns_idx
+            ON langgraph_store USING GIN (ns);
+        "#,
+    ),
+    (
+        3,
+        "checkpoint_tags",
+        r#"
+        ALTER TABLE langgraph_checkpoints
+            ADD COLUMN IF NOT EXISTS tags JSONB NOT NULL DEFAULT '[]';
+        "#,
+    ),
+];
+
+/// Opens a pool of up to [`DEFAULT_MAX_CONNECTIONS`] connections to `url`.
+pub(crate) async fn connect(url: &str) -> Result<PgPool, sqlx::Error> {
+    PgPoolOptions::new()
+        .max_connections(DEFAULT_MAX_CONNECTIONS)
+        .connect(url)
+        .await
+}
+
+/// Applies pending migrations. Safe to call from every instance on startup.
+pub(crate) async fn migrate(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::raw_sql(
+        "CREATE TABLE IF NOT EXISTS langgraph_migrations (
+            version BIGINT PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )",
+    )
+    .execute(&mut *tx)
+    .await?;
+    let applied: Vec<i64> = sqlx::query_scalar("SELECT version FROM langgraph_migrations")
+        .fetch_all(&mut *tx)
+        .await?;
+    for (version, name, sql) in MIGRATIONS {
+        if applied.contains(version) {
+            continue;
+        }
+        sqlx::raw_sql(sql).execute(&mut *tx).await?;
+        sqlx::query("INSERT INTO langgraph_migrations (version, name) VALUES ($1, $2)")
+            .bind(*version)
+            .bind(*name)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
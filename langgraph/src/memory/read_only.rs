@@ -0,0 +1,284 @@
+//! Read-only switch for a checkpointer and a store, toggled at runtime.
+//!
+//! While the [`ReadOnlySwitch`] is on, [`ReadOnlyCheckpointer`] drops checkpoint writes (runs
+//! still work, they just are not persisted) and [`ReadOnlyStore`] refuses writes with
+//! [`StoreError::Storage`] (memory tools report the error to the model). Reads always go to the
+//! wrapped backend. Turning the switch on lets the SQLite files be copied or migrated while the
+//! server keeps answering.
+//!
+//! **Interaction**: Wrapped around the built backends by
+//! [`build_react_run_context_with_read_only`](crate::build_react_run_context_with_read_only);
+//! langgraph-server flips the switch with `--read-only` or its admin endpoint.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::memory::checkpoint::{Checkpoint, CheckpointListItem, CheckpointMetadata};
+use crate::memory::checkpointer::{CheckpointError, Checkpointer, ThreadInfo};
+use crate::memory::config::RunnableConfig;
+use crate::memory::embedder::Embedder;
+use crate::memory::store::{
+    Item, ListNamespacesOptions, Namespace, SearchItem, SearchOptions, Store, StoreError, StoreOp,
+    StoreOpResult,
+};
+
+/// Shared on/off flag; clones see the same state.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlySwitch(Arc<AtomicBool>);
+
+impl ReadOnlySwitch {
+    /// Switch starting in the given state.
+    pub fn new(read_only: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(read_only)))
+    }
+
+    /// Turns read-only mode on or off.
+    pub fn set(&self, read_only: bool) {
+        self.0.store(read_only, Ordering::SeqCst);
+    }
+
+    /// Whether writes are currently refused.
+    pub fn is_read_only(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Checkpointer that skips `put` and `tag_checkpoint` and refuses `delete_thread` while its
+/// switch is on.
+///
+/// A skipped put returns the checkpoint's own id, so the run goes on as if it was saved; the
+/// thread resumes from its last checkpoint written before the switch was turned on.
+pub struct ReadOnlyCheckpointer<S> {
+    inner: Arc<dyn Checkpointer<S>>,
+    switch: ReadOnlySwitch,
+}
+
+impl<S> ReadOnlyCheckpointer<S> {
+    /// Wraps `inner`; writes are skipped while `switch` is on.
+    pub fn new(inner: Arc<dyn Checkpointer<S>>, switch: ReadOnlySwitch) -> Self {
+        Self { inner, switch }
+    }
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for ReadOnlyCheckpointer<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        if self.switch.is_read_only() {
+            tracing::debug!(thread_id = ?config.thread_id, "read-only: checkpoint not written");
+            return Ok(checkpoint.id.clone());
+        }
+        self.inner.put(config, checkpoint).await
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        self.inner.get_tuple(config).await
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        self.inner.list(config, limit, before, after).await
+    }
+
+    async fn list_threads(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        self.inner.list_threads(config).await
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        if self.switch.is_read_only() {
+            return Err(CheckpointError::Storage("checkpointer is read-only".into()));
+        }
+        self.inner.delete_thread(config).await
+    }
+
+    /// Skipped like `put` while the switch is on; returns false then.
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        if self.switch.is_read_only() {
+            tracing::debug!(thread_id = ?config.thread_id, "read-only: checkpoint not tagged");
+            return Ok(false);
+        }
+        self.inner.tag_checkpoint(config, tags).await
+    }
+
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        self.inner.flush().await
+    }
+}
+
+/// Store that refuses `put`, `put_many`, `delete`, `reembed` and batches with a put while its
+/// switch is on.
+pub struct ReadOnlyStore {
+    inner: Arc<dyn Store>,
+    switch: ReadOnlySwitch,
+}
+
+impl ReadOnlyStore {
+    /// Wraps `inner`; writes fail while `switch` is on.
+    pub fn new(inner: Arc<dyn Store>, switch: ReadOnlySwitch) -> Self {
+        Self { inner, switch }
+    }
+
+    fn check_writable(&self) -> Result<(), StoreError> {
+        if self.switch.is_read_only() {
+            return Err(StoreError::Storage("store is read-only".into()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for ReadOnlyStore {
+    async fn put(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        self.check_writable()?;
+        self.inner.put(namespace, key, value).await
+    }
+
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        self.check_writable()?;
+        self.inner.put_many(namespace, items).await
+    }
+
+    async fn get(
+        &self,
+        namespace: &Namespace,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, StoreError> {
+        self.inner.get(namespace, key).await
+    }
+
+    async fn get_item(&self, namespace: &Namespace, key: &str) -> Result<Option<Item>, StoreError> {
+        self.inner.get_item(namespace, key).await
+    }
+
+    async fn delete(&self, namespace: &Namespace, key: &str) -> Result<(), StoreError> {
+        self.check_writable()?;
+        self.inner.delete(namespace, key).await
+    }
+
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        self.check_writable()?;
+        self.inner.clear(namespace).await
+    }
+
+    async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
+        self.inner.list(namespace).await
+    }
+
+    async fn search(
+        &self,
+        namespace_prefix: &Namespace,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchItem>, StoreError> {
+        self.inner.search(namespace_prefix, options).await
+    }
+
+    async fn list_namespaces(
+        &self,
+        options: ListNamespacesOptions,
+    ) -> Result<Vec<Namespace>, StoreError> {
+        self.inner.list_namespaces(options).await
+    }
+
+    async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError> {
+        if ops.iter().any(|op| matches!(op, StoreOp::Put { .. })) {
+            self.check_writable()?;
+        }
+        self.inner.batch(ops).await
+    }
+
+    async fn reembed(
+        &self,
+        namespace: &Namespace,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        self.check_writable()?;
+        self.inner.reembed(namespace, embedder).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{InMemoryStore, MemorySaver};
+    use serde_json::json;
+
+    /// **Scenario**: While the switch is on, checkpoints are not written and store writes fail;
+    /// reads still work and turning it off restores writes.
+    #[tokio::test]
+    async fn switch_blocks_writes_only() {
+        let switch = ReadOnlySwitch::new(false);
+        let saver = ReadOnlyCheckpointer::new(
+            Arc::new(MemorySaver::<String>::new()) as Arc<dyn Checkpointer<String>>,
+            switch.clone(),
+        );
+        let store = ReadOnlyStore::new(Arc::new(InMemoryStore::new()), switch.clone());
+        let config = RunnableConfig {
+            thread_id: Some("t1".into()),
+            ..Default::default()
+        };
+        let ns = vec!["u1".to_string(), "memories".to_string()];
+
+        saver
+            .put(
+                &config,
+                &Checkpoint::from_state("one".to_string(), Default::default(), 0),
+            )
+            .await
+            .unwrap();
+        store.put(&ns, "k", &json!("v1")).await.unwrap();
+
+        switch.set(true);
+        assert!(switch.is_read_only());
+        saver
+            .put(
+                &config,
+                &Checkpoint::from_state("two".to_string(), Default::default(), 1),
+            )
+            .await
+            .unwrap();
+        let (latest, _) = saver.get_tuple(&config).await.unwrap().expect("checkpoint");
+        assert_eq!(latest.channel_values, "one");
+        assert!(matches!(
+            store.put(&ns, "k", &json!("v2")).await,
+            Err(StoreError::Storage(_))
+        ));
+        assert!(store.delete(&ns, "k").await.is_err());
+        assert_eq!(store.get(&ns, "k").await.unwrap(), Some(json!("v1")));
+
+        switch.set(false);
+        store.put(&ns, "k", &json!("v2")).await.unwrap();
+        assert_eq!(store.get(&ns, "k").await.unwrap(), Some(json!("v2")));
+    }
+}
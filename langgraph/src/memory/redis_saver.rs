@@ -0,0 +1,476 @@
+//! Redis-backed checkpointer (RedisSaver). Low-latency short-term memory shared by server replicas.
+//!
+//! Aligns with LangGraph langgraph-checkpoint-redis. Each (thread_id, checkpoint_ns) owns four
+//! keys with one hash tag, so they land on the same Redis Cluster slot:
+//!
+//! | Key | Type | Content |
+//! |-----|------|---------|
+//! | `<prefix>:{<thread_id>:<ns>}:index` | sorted set | checkpoint ids scored by write sequence |
+//! | `<prefix>:{<thread_id>:<ns>}:payload` | hash | checkpoint id → serialized state |
+//! | `<prefix>:{<thread_id>:<ns>}:meta` | hash | checkpoint id → JSON (ts, versions, metadata, pending node) |
+//! | `<prefix>:{<thread_id>:<ns>}:seq` | string | write sequence counter |
+//!
+//! The sequence comes from `INCR`, so "latest" agrees across instances whatever their clocks say.
+//! With [`RedisSaver::with_ttl`], every write resets the expiry of all four keys: a thread
+//! expires as a whole once it has been idle that long.
+//!
+//! `list_threads` and `delete_thread` find threads by `SCAN`ning the index keys, reading the
+//! thread id up to the last `:` inside the braces; checkpoint namespaces must not contain `:`
+//! for those two.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::memory::checkpoint::{
+    ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata, CHECKPOINT_VERSION,
+};
+use crate::memory::checkpointer::{sort_threads, CheckpointError, Checkpointer, ThreadInfo};
+use crate::memory::config::RunnableConfig;
+use crate::memory::serializer::Serializer;
+use crate::memory::sqlite_saver::{
+    created_at_to_i64, i64_to_created_at, source_to_str, str_to_source,
+};
+
+/// Key prefix used unless [`RedisSaver::with_key_prefix`] sets another.
+const DEFAULT_KEY_PREFIX: &str = "langgraph";
+
+fn storage(e: redis::RedisError) -> CheckpointError {
+    CheckpointError::Storage(e.to_string())
+}
+
+fn serialization(e: serde_json::Error) -> CheckpointError {
+    CheckpointError::Serialization(e.to_string())
+}
+
+/// Everything of a checkpoint except the state, stored as JSON in the `meta` hash.
+#[derive(Serialize, Deserialize)]
+struct StoredMeta {
+    ts: String,
+    channel_versions: ChannelVersions,
+    source: String,
+    step: i64,
+    created_at: Option<i64>,
+    pending_node: Option<String>,
+    parent_checkpoint_id: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl StoredMeta {
+    fn metadata(&self) -> CheckpointMetadata {
+        CheckpointMetadata {
+            source: str_to_source(&self.source),
+            step: self.step,
+            created_at: i64_to_created_at(self.created_at),
+            parents: HashMap::new(),
+            parent_checkpoint_id: self.parent_checkpoint_id.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+}
+
+/// Escapes the glob characters of `s` for `SCAN MATCH`.
+fn escape_glob(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Keys of one (thread_id, checkpoint_ns); see the module docs for the layout.
+struct ThreadKeys {
+    index: String,
+    payload: String,
+    meta: String,
+    seq: String,
+}
+
+impl ThreadKeys {
+    /// `(thread_id, checkpoint_ns)` of an index key written with `prefix`.
+    fn parse_index(prefix: &str, key: &str) -> Option<(String, String)> {
+        let inner = key
+            .strip_prefix(prefix)?
+            .strip_prefix(":{")?
+            .strip_suffix("}:index")?;
+        let (thread_id, checkpoint_ns) = inner.rsplit_once(':')?;
+        Some((thread_id.to_string(), checkpoint_ns.to_string()))
+    }
+
+    fn new(prefix: &str, thread_id: &str, checkpoint_ns: &str) -> Self {
+        let base = format!("{}:{{{}:{}}}", prefix, thread_id, checkpoint_ns);
+        Self {
+            index: format!("{}:index", base),
+            payload: format!("{}:payload", base),
+            meta: format!("{}:meta", base),
+            seq: format!("{}:seq", base),
+        }
+    }
+}
+
+/// Redis-backed checkpointer. Key: (thread_id, checkpoint_ns, checkpoint_id).
+///
+/// For deployments where several processes serve the same threads and a SQL round trip per
+/// turn is too slow. Holds a reconnecting [`ConnectionManager`]; cloning it is cheap.
+///
+/// **Interaction**: Used as `Arc<dyn Checkpointer<S>>` in StateGraph::compile_with_checkpointer;
+/// built by `build_react_run_context` when `ReactBuildConfig::redis_url` is set.
+pub struct RedisSaver<S> {
+    conn: ConnectionManager,
+    serializer: Arc<dyn Serializer<S>>,
+    key_prefix: String,
+    ttl: Option<Duration>,
+}
+
+impl<S> RedisSaver<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Connects to `url` (e.g. `redis://:pass@host:6379/0`). Checkpoints never expire until
+    /// [`with_ttl`](Self::with_ttl) is set.
+    pub async fn connect(
+        url: &str,
+        serializer: Arc<dyn Serializer<S>>,
+    ) -> Result<Self, CheckpointError> {
+        let client = redis::Client::open(url).map_err(storage)?;
+        let conn = ConnectionManager::new(client).await.map_err(storage)?;
+        Ok(Self::from_connection(conn, serializer))
+    }
+
+    /// Uses an existing connection manager (e.g. shared with the rest of the application).
+    pub fn from_connection(conn: ConnectionManager, serializer: Arc<dyn Serializer<S>>) -> Self {
+        Self {
+            conn,
+            serializer,
+            key_prefix: DEFAULT_KEY_PREFIX.to_string(),
+            ttl: None,
+        }
+    }
+
+    /// Expires a thread's checkpoints once it has not been written for `ttl` (rounded down to
+    /// whole seconds, at least one).
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    /// Prefixes every key with `prefix` instead of `"langgraph"`, e.g. to separate
+    /// environments sharing one Redis.
+    pub fn with_key_prefix(self, prefix: impl Into<String>) -> Self {
+        Self {
+            key_prefix: prefix.into(),
+            ..self
+        }
+    }
+
+    /// `(thread_id, checkpoint_ns)` of every index key matching `pattern` (inside the braces).
+    async fn scan_threads(&self, pattern: &str) -> Result<Vec<(String, String)>, CheckpointError> {
+        let mut conn = self.conn.clone();
+        let pattern = format!("{}:{{{}}}:index", escape_glob(&self.key_prefix), pattern);
+        let mut found = Vec::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(500)
+                .query_async(&mut conn)
+                .await
+                .map_err(storage)?;
+            found.extend(
+                keys.iter()
+                    .filter_map(|key| ThreadKeys::parse_index(&self.key_prefix, key)),
+            );
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+        Ok(found)
+    }
+
+    fn keys(&self, config: &RunnableConfig) -> Result<ThreadKeys, CheckpointError> {
+        let thread_id = config
+            .thread_id
+            .as_deref()
+            .ok_or(CheckpointError::ThreadIdRequired)?;
+        Ok(ThreadKeys::new(
+            &self.key_prefix,
+            thread_id,
+            &config.checkpoint_ns,
+        ))
+    }
+}
+
+#[async_trait]
+impl<S> Checkpointer<S> for RedisSaver<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn put(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<String, CheckpointError> {
+        let keys = self.keys(config)?;
+        let payload = self.serializer.serialize(&checkpoint.channel_values)?;
+        let meta = serde_json::to_string(&StoredMeta {
+            ts: checkpoint.ts.clone(),
+            channel_versions: checkpoint.channel_versions.clone(),
+            source: source_to_str(&checkpoint.metadata.source).to_string(),
+            step: checkpoint.metadata.step,
+            created_at: created_at_to_i64(&checkpoint.metadata.created_at),
+            pending_node: checkpoint.pending_node.clone(),
+            parent_checkpoint_id: checkpoint.metadata.parent_checkpoint_id.clone(),
+            tags: checkpoint.metadata.tags.clone(),
+        })
+        .map_err(serialization)?;
+
+        let mut conn = self.conn.clone();
+        let seq: i64 = conn.incr(&keys.seq, 1).await.map_err(storage)?;
+        // Re-putting a checkpoint id replaces it and moves it to the end, as in SqliteSaver.
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hset(&keys.payload, &checkpoint.id, payload)
+            .ignore()
+            .hset(&keys.meta, &checkpoint.id, meta)
+            .ignore()
+            .zadd(&keys.index, &checkpoint.id, seq)
+            .ignore();
+        if let Some(ttl) = self.ttl {
+            let secs = ttl.as_secs().max(1) as i64;
+            for key in [&keys.index, &keys.payload, &keys.meta, &keys.seq] {
+                pipe.expire(key, secs).ignore();
+            }
+        }
+        let _: () = pipe.query_async(&mut conn).await.map_err(storage)?;
+        Ok(checkpoint.id.clone())
+    }
+
+    async fn get_tuple(
+        &self,
+        config: &RunnableConfig,
+    ) -> Result<Option<(Checkpoint<S>, CheckpointMetadata)>, CheckpointError> {
+        let keys = self.keys(config)?;
+        let mut conn = self.conn.clone();
+        let id = match &config.checkpoint_id {
+            Some(id) => id.clone(),
+            None => {
+                let latest: Vec<String> =
+                    conn.zrevrange(&keys.index, 0, 0).await.map_err(storage)?;
+                match latest.into_iter().next() {
+                    Some(id) => id,
+                    None => return Ok(None),
+                }
+            }
+        };
+        let (payload, meta): (Option<Vec<u8>>, Option<String>) = redis::pipe()
+            .hget(&keys.payload, &id)
+            .hget(&keys.meta, &id)
+            .query_async(&mut conn)
+            .await
+            .map_err(storage)?;
+        let (Some(payload), Some(meta)) = (payload, meta) else {
+            return Ok(None);
+        };
+        let meta: StoredMeta = serde_json::from_str(&meta).map_err(serialization)?;
+        let metadata = meta.metadata();
+        let checkpoint = Checkpoint {
+            v: CHECKPOINT_VERSION,
+            id,
+            ts: meta.ts,
+            channel_values: self.serializer.deserialize(&payload)?,
+            channel_versions: meta.channel_versions,
+            versions_seen: HashMap::new(),
+            updated_channels: None,
+            pending_sends: Vec::new(),
+            pending_node: meta.pending_node,
+            metadata: metadata.clone(),
+        };
+        Ok(Some((checkpoint, metadata)))
+    }
+
+    async fn list(
+        &self,
+        config: &RunnableConfig,
+        limit: Option<usize>,
+        before: Option<&str>,
+        after: Option<&str>,
+    ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
+        let keys = self.keys(config)?;
+        let mut conn = self.conn.clone();
+        let ids: Vec<String> = conn.zrange(&keys.index, 0, -1).await.map_err(storage)?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let metas: Vec<Option<String>> = redis::cmd("HMGET")
+            .arg(&keys.meta)
+            .arg(&ids)
+            .query_async(&mut conn)
+            .await
+            .map_err(storage)?;
+        let mut list = Vec::with_capacity(ids.len());
+        for (checkpoint_id, meta) in ids.into_iter().zip(metas) {
+            let Some(meta) = meta else {
+                continue;
+            };
+            let meta: StoredMeta = serde_json::from_str(&meta).map_err(serialization)?;
+            list.push(CheckpointListItem {
+                checkpoint_id,
+                metadata: meta.metadata(),
+            });
+        }
+        if let Some(a) = after {
+            if let Some(pos) = list.iter().position(|i| i.checkpoint_id == a) {
+                list = list.split_off(pos + 1);
+            }
+        }
+        if let Some(b) = before {
+            if let Some(pos) = list.iter().position(|i| i.checkpoint_id == b) {
+                list.truncate(pos);
+            }
+        }
+        if let Some(n) = limit {
+            if list.len() > n {
+                list = list.split_off(list.len() - n);
+            }
+        }
+        Ok(list)
+    }
+
+    async fn list_threads(
+        &self,
+        _config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        let mut conn = self.conn.clone();
+        let mut latest: HashMap<String, Option<std::time::SystemTime>> = HashMap::new();
+        for (thread_id, checkpoint_ns) in self.scan_threads("*").await? {
+            let keys = ThreadKeys::new(&self.key_prefix, &thread_id, &checkpoint_ns);
+            let newest: Vec<String> = conn.zrevrange(&keys.index, 0, 0).await.map_err(storage)?;
+            let Some(id) = newest.into_iter().next() else {
+                continue;
+            };
+            let meta: Option<String> = conn.hget(&keys.meta, &id).await.map_err(storage)?;
+            let created_at = match meta {
+                Some(meta) => {
+                    let meta: StoredMeta = serde_json::from_str(&meta).map_err(serialization)?;
+                    i64_to_created_at(meta.created_at)
+                }
+                None => None,
+            };
+            let entry = latest.entry(thread_id).or_default();
+            *entry = (*entry).max(created_at);
+        }
+        let mut threads: Vec<ThreadInfo> = latest
+            .into_iter()
+            .map(|(thread_id, last_activity)| ThreadInfo {
+                thread_id,
+                last_activity,
+            })
+            .collect();
+        sort_threads(&mut threads);
+        Ok(threads)
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        let thread_id = config
+            .thread_id
+            .as_deref()
+            .ok_or(CheckpointError::ThreadIdRequired)?;
+        let pattern = format!("{}:*", escape_glob(thread_id));
+        let mut deleted = false;
+        let mut conn = self.conn.clone();
+        for (found, checkpoint_ns) in self.scan_threads(&pattern).await? {
+            // `t:*` also matches thread `t:x`; keep exact matches only.
+            if found != thread_id {
+                continue;
+            }
+            let keys = ThreadKeys::new(&self.key_prefix, &found, &checkpoint_ns);
+            let removed: usize = conn
+                .del(vec![keys.index, keys.payload, keys.meta, keys.seq])
+                .await
+                .map_err(storage)?;
+            deleted |= removed > 0;
+        }
+        Ok(deleted)
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        let keys = self.keys(config)?;
+        let mut conn = self.conn.clone();
+        let id = match &config.checkpoint_id {
+            Some(id) => id.clone(),
+            None => {
+                let latest: Vec<String> =
+                    conn.zrevrange(&keys.index, 0, 0).await.map_err(storage)?;
+                match latest.into_iter().next() {
+                    Some(id) => id,
+                    None => return Ok(false),
+                }
+            }
+        };
+        let meta: Option<String> = conn.hget(&keys.meta, &id).await.map_err(storage)?;
+        let Some(meta) = meta else {
+            return Ok(false);
+        };
+        let mut meta: StoredMeta = serde_json::from_str(&meta).map_err(serialization)?;
+        let mut metadata = meta.metadata();
+        metadata.add_tags(tags);
+        meta.tags = metadata.tags;
+        let meta = serde_json::to_string(&meta).map_err(serialization)?;
+        let _: () = conn.hset(&keys.meta, &id, meta).await.map_err(storage)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: All keys of a thread share one hash tag (same Cluster slot) and differ
+    /// between namespaces of the same thread.
+    #[test]
+    fn thread_keys_share_hash_tag_per_namespace() {
+        let keys = ThreadKeys::new("lg", "t1", "");
+        assert_eq!(keys.index, "lg:{t1:}:index");
+        assert_eq!(keys.payload, "lg:{t1:}:payload");
+        assert_eq!(keys.meta, "lg:{t1:}:meta");
+        assert_eq!(keys.seq, "lg:{t1:}:seq");
+        let sub = ThreadKeys::new("lg", "t1", "child");
+        assert_eq!(sub.index, "lg:{t1:child}:index");
+    }
+
+    /// **Scenario**: Index keys parse back to thread and namespace, thread ids with `:`
+    /// included; glob characters are escaped for SCAN.
+    #[test]
+    fn index_keys_parse_back_to_threads() {
+        assert_eq!(
+            ThreadKeys::parse_index("lg", "lg:{t1:}:index"),
+            Some(("t1".to_string(), String::new()))
+        );
+        assert_eq!(
+            ThreadKeys::parse_index("lg", &ThreadKeys::new("lg", "user:7", "child").index),
+            Some(("user:7".to_string(), "child".to_string()))
+        );
+        assert_eq!(ThreadKeys::parse_index("lg", "lg:{t1:}:meta"), None);
+        assert_eq!(ThreadKeys::parse_index("other", "lg:{t1:}:index"), None);
+        assert_eq!(escape_glob("a*b[c]"), "a\\*b\\[c\\]");
+    }
+}
@@ -0,0 +1,112 @@
+//! Checkpoint retention: how many and how old checkpoints a thread keeps.
+//!
+//! With a checkpoint written after every step, a long thread would otherwise grow the database
+//! without bound. A [`CheckpointRetention`] keeps the newest `max_checkpoints` of each thread
+//! (and checkpoint namespace) and drops those older than `max_age`; the newest checkpoint is
+//! always kept so the thread can continue.
+//!
+//! **Interaction**: Enforced on `put` by [`MemorySaver`](super::MemorySaver) and
+//! [`SqliteSaver`](super::SqliteSaver) (`with_retention`, also through
+//! [`SqliteTenants`](super::SqliteTenants)); configured from `ReactBuildConfig`'s
+//! `checkpoint_max_per_thread` / `checkpoint_max_age_secs`.
+
+use std::time::{Duration, SystemTime};
+
+/// Limits on the checkpoints kept per thread; unbounded by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckpointRetention {
+    max_checkpoints: Option<usize>,
+    max_age: Option<Duration>,
+}
+
+impl CheckpointRetention {
+    /// No limits: every checkpoint is kept.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps the newest `max` checkpoints of a thread (at least 1).
+    pub fn with_max_checkpoints(mut self, max: usize) -> Self {
+        self.max_checkpoints = Some(max.max(1));
+        self
+    }
+
+    /// Drops checkpoints created more than `max_age` ago, except the thread's newest.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Checkpoints kept per thread at most, if limited.
+    pub fn max_checkpoints(&self) -> Option<usize> {
+        self.max_checkpoints
+    }
+
+    /// Age after which checkpoints are dropped, if limited.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// True when neither limit is set.
+    pub fn is_unbounded(&self) -> bool {
+        self.max_checkpoints.is_none() && self.max_age.is_none()
+    }
+
+    /// Oldest creation time kept at `now`; checkpoints without a creation time are kept.
+    pub(crate) fn cutoff(&self, now: SystemTime) -> Option<SystemTime> {
+        self.max_age.and_then(|age| now.checked_sub(age))
+    }
+
+    /// Whether to keep the checkpoint at `index_from_newest` (0 = newest) created at
+    /// `created_at`.
+    pub(crate) fn keeps(
+        &self,
+        index_from_newest: usize,
+        created_at: Option<SystemTime>,
+        cutoff: Option<SystemTime>,
+    ) -> bool {
+        if index_from_newest == 0 {
+            return true;
+        }
+        if self
+            .max_checkpoints
+            .is_some_and(|max| index_from_newest >= max)
+        {
+            return false;
+        }
+        match (created_at, cutoff) {
+            (Some(created_at), Some(cutoff)) => created_at >= cutoff,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: The newest checkpoint is always kept; older ones past the count or age
+    /// limit are not; checkpoints without a creation time only count against the count limit.
+    #[test]
+    fn keeps_newest_within_limits() {
+        let now = SystemTime::now();
+        let old = now - Duration::from_secs(7200);
+        let retention = CheckpointRetention::new()
+            .with_max_checkpoints(3)
+            .with_max_age(Duration::from_secs(3600));
+        let cutoff = retention.cutoff(now);
+
+        assert!(retention.keeps(0, Some(old), cutoff));
+        assert!(retention.keeps(1, Some(now), cutoff));
+        assert!(!retention.keeps(1, Some(old), cutoff));
+        assert!(retention.keeps(2, None, cutoff));
+        assert!(!retention.keeps(3, Some(now), cutoff));
+        assert!(CheckpointRetention::new().is_unbounded());
+        assert_eq!(
+            CheckpointRetention::new()
+                .with_max_checkpoints(0)
+                .max_checkpoints(),
+            Some(1)
+        );
+    }
+}
@@ -6,18 +6,21 @@ use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 use crate::memory::checkpoint::{
     ChannelVersions, Checkpoint, CheckpointListItem, CheckpointMetadata, CheckpointSource,
     CHECKPOINT_VERSION,
 };
-use crate::memory::checkpointer::{CheckpointError, Checkpointer};
+use crate::memory::checkpointer::{sort_threads, CheckpointError, Checkpointer, ThreadInfo};
 use crate::memory::config::RunnableConfig;
+use crate::memory::retention::CheckpointRetention;
 use crate::memory::serializer::Serializer;
+use crate::memory::sqlite_backup::{backup_sqlite, BackupError};
+use crate::memory::sqlite_write_behind::{write_rows, CheckpointRow, CheckpointSync, WriteQueue};
 use std::collections::HashMap;
 
-fn source_to_str(s: &CheckpointSource) -> &'static str {
+pub(super) fn source_to_str(s: &CheckpointSource) -> &'static str {
     match s {
         CheckpointSource::Input => "Input",
         CheckpointSource::Loop => "Loop",
@@ -26,7 +29,7 @@ fn source_to_str(s: &CheckpointSource) -> &'static str {
     }
 }
 
-fn str_to_source(s: &str) -> CheckpointSource {
+pub(super) fn str_to_source(s: &str) -> CheckpointSource {
     match s {
         "Input" => CheckpointSource::Input,
         "Loop" => CheckpointSource::Loop,
@@ -36,7 +39,7 @@ fn str_to_source(s: &str) -> CheckpointSource {
     }
 }
 
-fn created_at_to_i64(t: &Option<std::time::SystemTime>) -> Option<i64> {
+pub(super) fn created_at_to_i64(t: &Option<std::time::SystemTime>) -> Option<i64> {
     t.as_ref().and_then(|t| {
         t.duration_since(std::time::UNIX_EPOCH)
             .ok()
@@ -44,18 +47,31 @@ fn created_at_to_i64(t: &Option<std::time::SystemTime>) -> Option<i64> {
     })
 }
 
-fn i64_to_created_at(v: Option<i64>) -> Option<std::time::SystemTime> {
+pub(super) fn i64_to_created_at(v: Option<i64>) -> Option<std::time::SystemTime> {
     v.and_then(|ms| std::time::UNIX_EPOCH.checked_add(std::time::Duration::from_millis(ms as u64)))
 }
 
+pub(super) fn tags_to_json(tags: &[String]) -> Result<String, CheckpointError> {
+    serde_json::to_string(tags).map_err(|e| CheckpointError::Serialization(e.to_string()))
+}
+
+/// Tags of a `tags` column; rows written before tags existed have none.
+pub(super) fn tags_from_json(json: Option<&str>) -> Vec<String> {
+    json.and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
 /// SQLite-backed checkpointer. Key: (thread_id, checkpoint_ns, checkpoint_id).
 ///
-/// Persistent; for single-node and dev. Uses spawn_blocking for async.
+/// Persistent; for single-node and dev. Uses spawn_blocking for async. Each put is written
+/// before it returns, unless [`with_write_behind`](Self::with_write_behind) queues them.
 ///
 /// **Interaction**: Used as `Arc<dyn Checkpointer<S>>` in StateGraph::compile_with_checkpointer.
 pub struct SqliteSaver<S> {
     db_path: std::path::PathBuf,
     serializer: Arc<dyn Serializer<S>>,
+    retention: CheckpointRetention,
+    queue: Option<Arc<WriteQueue>>,
 }
 
 impl<S> SqliteSaver<S>
@@ -82,18 +98,66 @@ where
                 metadata_source TEXT NOT NULL,
                 metadata_step INTEGER NOT NULL,
                 metadata_created_at INTEGER,
+                pending_node TEXT,
+                parent_checkpoint_id TEXT,
+                tags TEXT,
                 PRIMARY KEY (thread_id, checkpoint_ns, checkpoint_id)
             )
             "#,
             [],
         )
         .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+        // Databases created before interrupt support / checkpoint history / tags lack these
+        // columns.
+        for column in ["pending_node", "parent_checkpoint_id", "tags"] {
+            let exists = conn
+                .prepare("SELECT 1 FROM pragma_table_info('checkpoints') WHERE name = ?1")
+                .and_then(|mut stmt| stmt.exists([column]))
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            if !exists {
+                conn.execute(
+                    &format!("ALTER TABLE checkpoints ADD COLUMN {} TEXT", column),
+                    [],
+                )
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            }
+        }
         Ok(Self {
             db_path,
             serializer,
+            retention: CheckpointRetention::new(),
+            queue: None,
         })
     }
 
+    /// Deletes checkpoints beyond `retention` from a thread whenever one is put.
+    pub fn with_retention(mut self, retention: CheckpointRetention) -> Self {
+        self.retention = retention;
+        let sync = self.queue.as_ref().map(|q| q.sync());
+        if let Some(sync) = sync {
+            self = self.with_write_behind(sync);
+        }
+        self
+    }
+
+    /// Queues puts and writes them in batches as `sync` says (see [`CheckpointSync`]), instead
+    /// of one transaction per put. Reads see queued checkpoints at once.
+    pub fn with_write_behind(mut self, sync: CheckpointSync) -> Self {
+        self.queue = Some(Arc::new(WriteQueue::new(
+            self.db_path.clone(),
+            self.retention,
+            sync,
+        )));
+        self
+    }
+
+    /// Copies the database to the new file `path` as one consistent snapshot, also while runs
+    /// keep writing checkpoints; returns the backup's size in bytes. See [`backup_sqlite`].
+    /// Queued checkpoints are not in it; [`flush`](Checkpointer::flush) first to include them.
+    pub async fn backup(&self, path: impl AsRef<Path>) -> Result<u64, BackupError> {
+        backup_sqlite(&self.db_path, path).await
+    }
+
     fn thread_id_required(config: &RunnableConfig) -> Result<String, CheckpointError> {
         config
             .thread_id
@@ -101,6 +165,75 @@ where
             .ok_or(CheckpointError::ThreadIdRequired)
             .map(String::from)
     }
+
+    /// The table row of `checkpoint` in the thread of `config`.
+    fn encode_row(
+        &self,
+        config: &RunnableConfig,
+        checkpoint: &Checkpoint<S>,
+    ) -> Result<CheckpointRow, CheckpointError> {
+        Ok(CheckpointRow {
+            thread_id: Self::thread_id_required(config)?,
+            checkpoint_ns: config.checkpoint_ns.clone(),
+            checkpoint_id: checkpoint.id.clone(),
+            ts: checkpoint.ts.clone(),
+            payload: self.serializer.serialize(&checkpoint.channel_values)?,
+            channel_versions: serde_json::to_string(&checkpoint.channel_versions)
+                .map_err(|e| CheckpointError::Serialization(e.to_string()))?,
+            metadata_source: source_to_str(&checkpoint.metadata.source).to_string(),
+            metadata_step: checkpoint.metadata.step as i64,
+            metadata_created_at: created_at_to_i64(&checkpoint.metadata.created_at),
+            pending_node: checkpoint.pending_node.clone(),
+            parent_checkpoint_id: checkpoint.metadata.parent_checkpoint_id.clone(),
+            tags: Some(tags_to_json(&checkpoint.metadata.tags)?),
+        })
+    }
+
+    /// The checkpoint stored in `row`.
+    fn decode_row(
+        &self,
+        row: CheckpointRow,
+    ) -> Result<(Checkpoint<S>, CheckpointMetadata), CheckpointError> {
+        let channel_values = self.serializer.deserialize(&row.payload)?;
+        let channel_versions: ChannelVersions = serde_json::from_str(&row.channel_versions)
+            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
+        let metadata = CheckpointMetadata {
+            source: str_to_source(&row.metadata_source),
+            step: row.metadata_step,
+            created_at: i64_to_created_at(row.metadata_created_at),
+            parents: HashMap::new(),
+            parent_checkpoint_id: row.parent_checkpoint_id,
+            tags: tags_from_json(row.tags.as_deref()),
+        };
+        let checkpoint = Checkpoint {
+            v: CHECKPOINT_VERSION,
+            id: row.checkpoint_id,
+            ts: row.ts,
+            channel_values,
+            channel_versions,
+            versions_seen: HashMap::new(),
+            updated_channels: None,
+            pending_sends: Vec::new(),
+            pending_node: row.pending_node,
+            metadata: metadata.clone(),
+        };
+        Ok((checkpoint, metadata))
+    }
+}
+
+/// The list entry of a table row.
+fn list_item(row: &CheckpointRow) -> CheckpointListItem {
+    CheckpointListItem {
+        checkpoint_id: row.checkpoint_id.clone(),
+        metadata: CheckpointMetadata {
+            source: str_to_source(&row.metadata_source),
+            step: row.metadata_step,
+            created_at: i64_to_created_at(row.metadata_created_at),
+            parents: HashMap::new(),
+            parent_checkpoint_id: row.parent_checkpoint_id.clone(),
+            tags: tags_from_json(row.tags.as_deref()),
+        },
+    }
 }
 
 #[async_trait]
@@ -113,45 +246,18 @@ where
         config: &RunnableConfig,
         checkpoint: &Checkpoint<S>,
     ) -> Result<String, CheckpointError> {
-        let thread_id = Self::thread_id_required(config)?;
-        let checkpoint_ns = config.checkpoint_ns.clone();
-        let payload = self.serializer.serialize(&checkpoint.channel_values)?;
-        let channel_versions = serde_json::to_string(&checkpoint.channel_versions)
-            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
-        let metadata_source = source_to_str(&checkpoint.metadata.source).to_string();
-        let metadata_step = checkpoint.metadata.step as i64;
-        let metadata_created_at = created_at_to_i64(&checkpoint.metadata.created_at);
-        let id = checkpoint.id.clone();
-        let ts = checkpoint.ts.clone();
-
+        let row = self.encode_row(config, checkpoint)?;
+        let id = row.checkpoint_id.clone();
+        if let Some(queue) = &self.queue {
+            queue.push(row).await?;
+            return Ok(id);
+        }
         let db_path = self.db_path.clone();
-        tokio::task::spawn_blocking(move || {
-            let conn = rusqlite::Connection::open(&db_path)
-                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            conn.execute(
-                r#"
-                INSERT OR REPLACE INTO checkpoints
-                (thread_id, checkpoint_ns, checkpoint_id, ts, payload, channel_versions,
-                 metadata_source, metadata_step, metadata_created_at)
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
-                "#,
-                params![
-                    thread_id,
-                    checkpoint_ns,
-                    id.clone(),
-                    ts,
-                    payload,
-                    channel_versions,
-                    metadata_source,
-                    metadata_step,
-                    metadata_created_at,
-                ],
-            )
-            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            Ok::<String, CheckpointError>(id)
-        })
-        .await
-        .map_err(|e| CheckpointError::Storage(e.to_string()))?
+        let retention = self.retention;
+        tokio::task::spawn_blocking(move || write_rows(&db_path, &[row], retention))
+            .await
+            .map_err(|e| CheckpointError::Storage(e.to_string()))??;
+        Ok(id)
     }
 
     async fn get_tuple(
@@ -161,85 +267,64 @@ where
         let thread_id = Self::thread_id_required(config)?;
         let checkpoint_ns = config.checkpoint_ns.clone();
         let want_id = config.checkpoint_id.clone();
+        // Queued checkpoints are newer than every written one.
+        if let Some(row) = self
+            .queue
+            .as_ref()
+            .and_then(|q| q.get(&thread_id, &checkpoint_ns, want_id.as_deref()))
+        {
+            return self.decode_row(row).map(Some);
+        }
         let db_path = self.db_path.clone();
 
-        type RowData = (String, String, Vec<u8>, String, String, i64, Option<i64>);
-        let row: Option<RowData> = tokio::task::spawn_blocking(move || -> Result<Option<RowData>, CheckpointError> {
+        let row = tokio::task::spawn_blocking(move || {
             let conn = rusqlite::Connection::open(&db_path)
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let sql = if want_id.is_some() {
-                "SELECT checkpoint_id, ts, payload, channel_versions, metadata_source, metadata_step, metadata_created_at
-                 FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2 AND checkpoint_id = ?3"
-            } else {
-                "SELECT checkpoint_id, ts, payload, channel_versions, metadata_source, metadata_step, metadata_created_at
-                 FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
-                 ORDER BY metadata_created_at DESC LIMIT 1"
+            let columns = "checkpoint_id, ts, payload, channel_versions, metadata_source,
+                 metadata_step, metadata_created_at, pending_node, parent_checkpoint_id, tags";
+            let to_row = |row: &rusqlite::Row<'_>| {
+                Ok(CheckpointRow {
+                    thread_id: thread_id.clone(),
+                    checkpoint_ns: checkpoint_ns.clone(),
+                    checkpoint_id: row.get(0)?,
+                    ts: row.get(1)?,
+                    payload: row.get(2)?,
+                    channel_versions: row.get(3)?,
+                    metadata_source: row.get(4)?,
+                    metadata_step: row.get(5)?,
+                    metadata_created_at: row.get(6)?,
+                    pending_node: row.get(7)?,
+                    parent_checkpoint_id: row.get(8)?,
+                    tags: row.get(9)?,
+                })
             };
-            let mut stmt = conn.prepare(sql).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let mut rows = if let Some(ref cid) = want_id {
-                stmt.query(params![thread_id, checkpoint_ns, cid])
-            } else {
-                stmt.query(params![thread_id, checkpoint_ns])
+            match &want_id {
+                Some(cid) => conn.query_row(
+                    &format!(
+                        "SELECT {} FROM checkpoints
+                         WHERE thread_id = ?1 AND checkpoint_ns = ?2 AND checkpoint_id = ?3",
+                        columns
+                    ),
+                    params![thread_id, checkpoint_ns, cid],
+                    to_row,
+                ),
+                None => conn.query_row(
+                    &format!(
+                        "SELECT {} FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
+                         ORDER BY metadata_created_at DESC, rowid DESC LIMIT 1",
+                        columns
+                    ),
+                    params![thread_id, checkpoint_ns],
+                    to_row,
+                ),
             }
-            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let row = match rows.next().map_err(|e| CheckpointError::Storage(e.to_string()))? {
-                Some(r) => r,
-                None => return Ok::<_, CheckpointError>(None),
-            };
-            let checkpoint_id: String = row.get(0).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let ts: String = row.get(1).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let payload: Vec<u8> = row.get(2).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let channel_versions_json: String = row.get(3).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let metadata_source: String = row.get(4).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let metadata_step: i64 = row.get(5).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let metadata_created_at: Option<i64> = row.get(6).map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            Ok(Some((
-                checkpoint_id,
-                ts,
-                payload,
-                channel_versions_json,
-                metadata_source,
-                metadata_step,
-                metadata_created_at,
-            )))
+            .optional()
+            .map_err(|e| CheckpointError::Storage(e.to_string()))
         })
         .await
         .map_err(|e| CheckpointError::Storage(e.to_string()))??;
 
-        let (
-            checkpoint_id,
-            ts,
-            payload,
-            channel_versions_json,
-            metadata_source,
-            metadata_step,
-            metadata_created_at,
-        ): RowData = match row {
-            Some(r) => r,
-            None => return Ok(None),
-        };
-
-        let channel_values = self.serializer.deserialize(&payload)?;
-        let channel_versions: ChannelVersions = serde_json::from_str(&channel_versions_json)
-            .map_err(|e| CheckpointError::Serialization(e.to_string()))?;
-        let metadata = CheckpointMetadata {
-            source: str_to_source(&metadata_source),
-            step: metadata_step,
-            created_at: i64_to_created_at(metadata_created_at),
-            parents: HashMap::new(),
-        };
-        let checkpoint = Checkpoint {
-            v: CHECKPOINT_VERSION,
-            id: checkpoint_id.clone(),
-            ts,
-            channel_values,
-            channel_versions,
-            versions_seen: HashMap::new(),
-            updated_channels: None,
-            pending_sends: Vec::new(),
-            metadata: metadata.clone(),
-        };
-        Ok(Some((checkpoint, metadata)))
+        row.map(|row| self.decode_row(row)).transpose()
     }
 
     async fn list(
@@ -251,18 +336,25 @@ where
     ) -> Result<Vec<CheckpointListItem>, CheckpointError> {
         let thread_id = Self::thread_id_required(config)?;
         let checkpoint_ns = config.checkpoint_ns.clone();
+        let queued: Vec<CheckpointListItem> = self
+            .queue
+            .as_ref()
+            .map(|q| q.rows(&thread_id, &checkpoint_ns))
+            .unwrap_or_default()
+            .iter()
+            .map(list_item)
+            .collect();
         let db_path = self.db_path.clone();
-        let before = before.map(String::from);
-        let after = after.map(String::from);
 
-        let items = tokio::task::spawn_blocking(move || {
+        let mut list = tokio::task::spawn_blocking(move || {
             let conn = rusqlite::Connection::open(&db_path)
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
             let mut stmt = conn
                 .prepare(
-                    "SELECT checkpoint_id, metadata_source, metadata_step, metadata_created_at
+                    "SELECT checkpoint_id, metadata_source, metadata_step, metadata_created_at,
+                            parent_checkpoint_id, tags
                      FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
-                     ORDER BY metadata_created_at ASC",
+                     ORDER BY metadata_created_at ASC, rowid ASC",
                 )
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
             let rows = stmt
@@ -274,34 +366,176 @@ where
                             step: row.get::<_, i64>(2)?,
                             created_at: i64_to_created_at(row.get(3)?),
                             parents: HashMap::new(),
+                            parent_checkpoint_id: row.get(4)?,
+                            tags: tags_from_json(row.get::<_, Option<String>>(5)?.as_deref()),
                         },
                     })
                 })
                 .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            let mut list: Vec<CheckpointListItem> = rows
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
-            if let Some(a) = &after {
-                if let Some(pos) = list.iter().position(|i| i.checkpoint_id.as_str() == a) {
-                    list = list[pos + 1..].to_vec();
-                }
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| CheckpointError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| CheckpointError::Storage(e.to_string()))??;
+
+        if !queued.is_empty() {
+            // Queued checkpoints come after the written ones, replacing those with their id;
+            // retention applies as if they were written.
+            list.retain(|i| !queued.iter().any(|q| q.checkpoint_id == i.checkpoint_id));
+            list.extend(queued);
+            if let Some(max) = self.retention.max_checkpoints() {
+                let excess = list.len().saturating_sub(max);
+                list = list.split_off(excess);
+            }
+        }
+        if let Some(a) = after {
+            if let Some(pos) = list.iter().position(|i| i.checkpoint_id.as_str() == a) {
+                list = list[pos + 1..].to_vec();
             }
-            if let Some(b) = &before {
-                if let Some(pos) = list.iter().position(|i| i.checkpoint_id.as_str() == b) {
-                    list = list[..pos].to_vec();
-                }
+        }
+        if let Some(b) = before {
+            if let Some(pos) = list.iter().position(|i| i.checkpoint_id.as_str() == b) {
+                list = list[..pos].to_vec();
+            }
+        }
+        if let Some(n) = limit {
+            let len = list.len();
+            if len > n {
+                list = list[len - n..].to_vec();
+            }
+        }
+        Ok(list)
+    }
+
+    async fn list_threads(
+        &self,
+        _config: &RunnableConfig,
+    ) -> Result<Vec<ThreadInfo>, CheckpointError> {
+        let queued = self.queue.as_ref().map(|q| q.threads()).unwrap_or_default();
+        let db_path = self.db_path.clone();
+        let mut threads: Vec<ThreadInfo> = tokio::task::spawn_blocking(move || {
+            let conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT thread_id, MAX(metadata_created_at) FROM checkpoints
+                     GROUP BY thread_id",
+                )
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok(ThreadInfo {
+                        thread_id: row.get(0)?,
+                        last_activity: i64_to_created_at(row.get(1)?),
+                    })
+                })
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| CheckpointError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| CheckpointError::Storage(e.to_string()))??;
+        for info in queued {
+            match threads.iter_mut().find(|t| t.thread_id == info.thread_id) {
+                Some(thread) => thread.last_activity = thread.last_activity.max(info.last_activity),
+                None => threads.push(info),
             }
-            if let Some(n) = limit {
-                let len = list.len();
-                if len > n {
-                    list = list[len - n..].to_vec();
-                }
+        }
+        sort_threads(&mut threads);
+        Ok(threads)
+    }
+
+    async fn delete_thread(&self, config: &RunnableConfig) -> Result<bool, CheckpointError> {
+        let thread_id = Self::thread_id_required(config)?;
+        // Holds off queued writes until the thread is gone from the table too.
+        let (_writing, queued) = match &self.queue {
+            Some(queue) => {
+                let (writing, removed) = queue.remove_thread(&thread_id).await;
+                (Some(writing), removed)
             }
-            Ok::<Vec<CheckpointListItem>, CheckpointError>(list)
+            None => (None, false),
+        };
+        let db_path = self.db_path.clone();
+        let deleted = tokio::task::spawn_blocking(move || {
+            let conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let deleted = conn
+                .execute(
+                    "DELETE FROM checkpoints WHERE thread_id = ?1",
+                    params![thread_id],
+                )
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            Ok::<bool, CheckpointError>(deleted > 0)
         })
         .await
         .map_err(|e| CheckpointError::Storage(e.to_string()))??;
+        Ok(deleted || queued)
+    }
+
+    async fn tag_checkpoint(
+        &self,
+        config: &RunnableConfig,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        let thread_id = Self::thread_id_required(config)?;
+        let checkpoint_ns = config.checkpoint_ns.clone();
+        let want_id = config.checkpoint_id.clone();
+        if let Some(queue) = &self.queue {
+            if queue.tag(&thread_id, &checkpoint_ns, want_id.as_deref(), tags)? {
+                return Ok(true);
+            }
+        }
+        let tags = tags.to_vec();
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let row: Option<(String, Option<String>)> = match &want_id {
+                Some(cid) => conn.query_row(
+                    "SELECT checkpoint_id, tags FROM checkpoints
+                     WHERE thread_id = ?1 AND checkpoint_ns = ?2 AND checkpoint_id = ?3",
+                    params![thread_id, checkpoint_ns, cid],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                ),
+                None => conn.query_row(
+                    "SELECT checkpoint_id, tags FROM checkpoints
+                     WHERE thread_id = ?1 AND checkpoint_ns = ?2
+                     ORDER BY metadata_created_at DESC, rowid DESC LIMIT 1",
+                    params![thread_id, checkpoint_ns],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                ),
+            }
+            .optional()
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            let Some((checkpoint_id, existing)) = row else {
+                return Ok(false);
+            };
+            let mut metadata = CheckpointMetadata {
+                tags: tags_from_json(existing.as_deref()),
+                ..Default::default()
+            };
+            metadata.add_tags(&tags);
+            conn.execute(
+                "UPDATE checkpoints SET tags = ?4
+                 WHERE thread_id = ?1 AND checkpoint_ns = ?2 AND checkpoint_id = ?3",
+                params![
+                    thread_id,
+                    checkpoint_ns,
+                    checkpoint_id,
+                    tags_to_json(&metadata.tags)?
+                ],
+            )
+            .map_err(|e| CheckpointError::Storage(e.to_string()))?;
+            Ok(true)
+        })
+        .await
+        .map_err(|e| CheckpointError::Storage(e.to_string()))?
+    }
 
-        Ok(items)
+    async fn flush(&self) -> Result<(), CheckpointError> {
+        match &self.queue {
+            Some(queue) => queue.flush().await,
+            None => Ok(()),
+        }
     }
 }
@@ -9,9 +9,10 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use async_trait::async_trait;
 use rusqlite::params;
 
+use crate::memory::sqlite_backup::{backup_sqlite, BackupError};
 use crate::memory::store::{
-    Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType, SearchItem,
-    SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
+    matches_filter, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
+    SearchItem, SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
 };
 
 fn ns_to_key(ns: &Namespace) -> String {
@@ -64,6 +65,12 @@ impl SqliteStore {
         Ok(Self { db_path })
     }
 
+    /// Copies the database to the new file `path` as one consistent snapshot, also while
+    /// memories keep being written; returns the backup's size in bytes. See [`backup_sqlite`].
+    pub async fn backup(&self, path: impl AsRef<Path>) -> Result<u64, BackupError> {
+        backup_sqlite(&self.db_path, path).await
+    }
+
     /// Checks if a namespace matches a condition.
     fn matches_condition(namespace: &Namespace, condition: &MatchCondition) -> bool {
         let path = &condition.path;
@@ -235,6 +242,20 @@ impl Store for SqliteStore {
         .map_err(|e| StoreError::Storage(e.to_string()))?
     }
 
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let ns = ns_to_key(namespace);
+        let db_path = self.db_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            conn.execute("DELETE FROM store_kv WHERE ns = ?1", params![ns])
+                .map_err(|e| StoreError::Storage(e.to_string()))
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let ns = ns_to_key(namespace);
         let db_path = self.db_path.clone();
@@ -320,6 +341,7 @@ impl Store for SqliteStore {
                 });
             }
         }
+        hits.retain(|h| matches_filter(&h.item.value, options.filter.as_ref()));
 
         // Apply offset and limit
         if options.offset > 0 {
@@ -452,7 +474,7 @@ impl Store for SqliteStore {
             query: query.map(String::from),
             filter: None,
             limit: limit.unwrap_or(10),
-            offset: 0,
+            ..SearchOptions::new()
         };
         let results = self.search(namespace, options).await?;
         Ok(results
@@ -2,23 +2,32 @@
 //!
 //! Uses dual-table design: store_vec_meta for metadata (ns, key, value),
 //! vec0 virtual table for embeddings. Search with query uses KNN vector similarity.
+//!
+//! store_vec_models pins each namespace to the embedder (model, dimension) of its first write;
+//! puts and queries with another embedder fail with `StoreError::EmbedderMismatch` until
+//! [`Store::reembed`] migrates the namespace. Namespaces written before pinning existed are pinned
+//! on their next write.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Once;
+use std::sync::{Arc, Mutex, Once};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use rusqlite::params;
 
-use crate::memory::embedder::Embedder;
+use crate::memory::embedder::{embed_all, Embedder, EmbedderPin};
 use crate::memory::store::{
-    Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType, SearchItem,
-    SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
+    matches_filter, Item, ListNamespacesOptions, MatchCondition, Namespace, NamespaceMatchType,
+    SearchItem, SearchOptions, Store, StoreError, StoreOp, StoreOpResult, StoreSearchHit,
 };
 
 static SQLITE_VEC_INIT: Once = Once::new();
 
+/// Nearest neighbours fetched when a search has a filter (sqlite-vec's maximum `k`), so that
+/// filtering after the KNN query still finds enough matches.
+const FILTERED_KNN_LIMIT: usize = 4096;
+
 fn ns_to_key(ns: &Namespace) -> String {
     serde_json::to_string(ns).unwrap_or_else(|_| "[]".to_string())
 }
@@ -52,15 +61,173 @@ fn text_from_value(value: &serde_json::Value) -> String {
         .unwrap_or_else(|| value.to_string())
 }
 
+/// Dimension of the vec0 table `table`, parsed from the `float[N]` of its declaration.
+fn vec_table_dimension(conn: &rusqlite::Connection, table: &str) -> Option<usize> {
+    let sql: String = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master WHERE name = ?1",
+            params![table],
+            |row| row.get(0),
+        )
+        .ok()?;
+    let (_, rest) = sql.split_once("float[")?;
+    let (n, _) = rest.split_once(']')?;
+    n.trim().parse().ok()
+}
+
+/// Returns the pin of namespace `ns` (as stored, see [`ns_to_key`]), if any.
+fn pinned(conn: &rusqlite::Connection, ns: &str) -> Result<Option<EmbedderPin>, StoreError> {
+    let mut stmt = conn
+        .prepare("SELECT model, dimension FROM store_vec_models WHERE ns = ?1")
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    let mut rows = stmt
+        .query(params![ns])
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    match rows
+        .next()
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    {
+        Some(row) => {
+            let model: String = row.get(0).map_err(|e| StoreError::Storage(e.to_string()))?;
+            let dimension: i64 = row.get(1).map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(Some(EmbedderPin {
+                model,
+                dimension: dimension as usize,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Fails with `EmbedderMismatch` when namespace `ns` is pinned to another embedder than
+/// `current`; pins it to `current` when it has no pin yet.
+fn pin_namespace(
+    conn: &rusqlite::Connection,
+    ns: &str,
+    current: &EmbedderPin,
+) -> Result<(), StoreError> {
+    match pinned(conn, ns)? {
+        Some(pinned) if pinned != *current => Err(StoreError::EmbedderMismatch {
+            namespace: key_to_ns(ns),
+            pinned,
+            current: current.clone(),
+        }),
+        Some(_) => Ok(()),
+        None => {
+            conn.execute(
+                "INSERT INTO store_vec_models (ns, model, dimension) VALUES (?1, ?2, ?3)",
+                params![ns, current.model, current.dimension as i64],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(())
+        }
+    }
+}
+
+/// Inserts or updates the value of `(ns, key)` and replaces its embedding.
+fn write_row(
+    conn: &rusqlite::Connection,
+    vec_table: &str,
+    ns: &str,
+    key: &str,
+    value_str: &str,
+    vec_json: &str,
+    now: i64,
+) -> Result<(), StoreError> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM store_vec_meta WHERE ns = ?1 AND key = ?2",
+            params![ns, key],
+            |row| row.get(0),
+        )
+        .ok();
+
+    let id = match existing {
+        Some(id) => {
+            conn.execute(
+                "DELETE FROM store_vec_embeddings WHERE rowid = ?1",
+                params![id],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            conn.execute(
+                "UPDATE store_vec_meta SET value = ?1, updated_at = ?2 WHERE id = ?3",
+                params![value_str, now, id],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO store_vec_meta (ns, key, value, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![ns, key, value_str, now, now],
+            )
+            .map_err(|e| StoreError::Storage(e.to_string()))?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (rowid, embedding) VALUES (?1, ?2)",
+            vec_table
+        ),
+        params![id, vec_json],
+    )
+    .map_err(|e| StoreError::Storage(e.to_string()))?;
+    Ok(())
+}
+
+/// Fails with `EmbedderMismatch` when a namespace matching `like_pattern` is pinned to another
+/// embedder than `current`.
+fn check_pins(
+    conn: &rusqlite::Connection,
+    like_pattern: &str,
+    current: &EmbedderPin,
+) -> Result<(), StoreError> {
+    let mut stmt = conn
+        .prepare("SELECT ns, model, dimension FROM store_vec_models WHERE ns LIKE ?1")
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    let rows = stmt
+        .query_map(params![like_pattern], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+    for row in rows {
+        let (ns, model, dimension) = row.map_err(|e| StoreError::Storage(e.to_string()))?;
+        let pinned = EmbedderPin {
+            model,
+            dimension: dimension as usize,
+        };
+        if pinned != *current {
+            return Err(StoreError::EmbedderMismatch {
+                namespace: key_to_ns(&ns),
+                pinned,
+                current: current.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
 /// SQLite-backed Store with vector search. Key: (namespace, key). Value stored as JSON; embeddings in vec0.
 ///
 /// **Interaction**: Used as `Arc<dyn Store>`; nodes use it for cross-thread memory with semantic search.
 /// Put embeds value text via [`Embedder`]; search with query uses KNN vector similarity.
+///
+/// The vec0 table has the dimension of the embedder the file was created with, so
+/// [`reembed`](Store::reembed) only migrates to embedders of that dimension; for another
+/// dimension, copy the values into a new store file.
 pub struct SqliteVecStore {
     db_path: std::path::PathBuf,
     embedder: std::sync::Arc<dyn Embedder>,
     dimension: usize,
     vec_table: String,
+    /// Embedders set by `reembed`, replacing `embedder` for their namespace (key: [`ns_to_key`]).
+    namespace_embedders: Mutex<HashMap<String, Arc<dyn Embedder>>>,
 }
 
 impl SqliteVecStore {
@@ -96,21 +263,47 @@ impl SqliteVecStore {
         )
         .map_err(|e| StoreError::Storage(e.to_string()))?;
 
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS store_vec_models (
+                ns TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                dimension INTEGER NOT NULL
+            )
+            "#,
+            [],
+        )
+        .map_err(|e| StoreError::Storage(e.to_string()))?;
+
         let create_vec_sql = format!(
             "CREATE VIRTUAL TABLE IF NOT EXISTS {} USING vec0(embedding float[{}])",
             vec_table, dimension
         );
         conn.execute(&create_vec_sql, [])
             .map_err(|e| StoreError::Storage(e.to_string()))?;
+        // An existing file keeps the dimension it was created with.
+        let dimension = vec_table_dimension(&conn, &vec_table).unwrap_or(dimension);
 
         Ok(Self {
             db_path,
             embedder,
             dimension,
             vec_table,
+            namespace_embedders: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Embedder used for namespace `ns` (see [`ns_to_key`]): the one set by `reembed`, else the
+    /// store's.
+    fn embedder_for(&self, ns: &str) -> Arc<dyn Embedder> {
+        self.namespace_embedders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(ns)
+            .cloned()
+            .unwrap_or_else(|| self.embedder.clone())
+    }
+
     fn matches_condition(namespace: &Namespace, condition: &MatchCondition) -> bool {
         let path = &condition.path;
         match condition.match_type {
@@ -153,7 +346,9 @@ impl Store for SqliteVecStore {
         let key = key.to_string();
         let value_str = serde_json::to_string(value)?;
         let text = text_from_value(value);
-        let vectors = self.embedder.embed(&[&text]).await?;
+        let embedder = self.embedder_for(&ns);
+        let pin = EmbedderPin::of(embedder.as_ref());
+        let vectors = embedder.embed(&[&text]).await?;
         let vector = vectors
             .into_iter()
             .next()
@@ -173,44 +368,60 @@ impl Store for SqliteVecStore {
         tokio::task::spawn_blocking(move || {
             let conn = rusqlite::Connection::open(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
+            pin_namespace(&conn, &ns, &pin)?;
+            write_row(&conn, &vec_table, &ns, &key, &value_str, &vec_json, now)
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
 
-            let existing: Option<(i64, i64)> = conn
-                .query_row(
-                    "SELECT id, created_at FROM store_vec_meta WHERE ns = ?1 AND key = ?2",
-                    params![ns, key],
-                    |row| Ok((row.get(0)?, row.get(1)?)),
-                )
-                .ok();
-
-            let (id, _created_at) = match existing {
-                Some((id, created)) => {
-                    conn.execute("DELETE FROM store_vec_embeddings WHERE rowid = ?1", params![id])
-                        .map_err(|e| StoreError::Storage(e.to_string()))?;
-                    conn.execute(
-                        "UPDATE store_vec_meta SET value = ?1, updated_at = ?2 WHERE id = ?3",
-                        params![value_str, now, id],
-                    )
-                    .map_err(|e| StoreError::Storage(e.to_string()))?;
-                    (id, created)
-                }
-                None => {
-                    conn.execute(
-                        "INSERT INTO store_vec_meta (ns, key, value, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-                        params![ns, key, value_str, now, now],
-                    )
-                    .map_err(|e| StoreError::Storage(e.to_string()))?;
-                    let id = conn.last_insert_rowid();
-                    (id, now)
-                }
-            };
-
-            conn.execute(
-                &format!("INSERT INTO {} (rowid, embedding) VALUES (?1, ?2)", vec_table),
-                params![id, vec_json],
-            )
-            .map_err(|e| StoreError::Storage(e.to_string()))?;
+    /// Embeds the values in batches (see [`embed_all`]) and writes them in one transaction.
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        let ns = ns_to_key(namespace);
+        let embedder = self.embedder_for(&ns);
+        let pin = EmbedderPin::of(embedder.as_ref());
+        let texts: Vec<String> = items
+            .iter()
+            .map(|(_, value)| text_from_value(value))
+            .collect();
+        let vectors = embed_all(embedder.as_ref(), &texts).await?;
+        if let Some(v) = vectors.iter().find(|v| v.len() != self.dimension) {
+            return Err(StoreError::Storage(format!(
+                "embedder dimension {} != expected {}",
+                v.len(),
+                self.dimension
+            )));
+        }
+        let rows = items
+            .iter()
+            .zip(&vectors)
+            .map(|((key, value), vector)| {
+                Ok((
+                    key.clone(),
+                    serde_json::to_string(value)?,
+                    vector_to_json(vector),
+                ))
+            })
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let db_path = self.db_path.clone();
+        let vec_table = self.vec_table.clone();
+        let now = system_time_to_millis(SystemTime::now());
 
-            Ok::<(), StoreError>(())
+        tokio::task::spawn_blocking(move || {
+            let mut conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            pin_namespace(&tx, &ns, &pin)?;
+            for (key, value_str, vec_json) in &rows {
+                write_row(&tx, &vec_table, &ns, key, value_str, vec_json, now)?;
+            }
+            tx.commit().map_err(|e| StoreError::Storage(e.to_string()))
         })
         .await
         .map_err(|e| StoreError::Storage(e.to_string()))?
@@ -320,6 +531,46 @@ impl Store for SqliteVecStore {
         .map_err(|e| StoreError::Storage(e.to_string()))?
     }
 
+    /// Deletes the namespace's rows and vectors in one transaction; its embedder pin stays.
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let ns = ns_to_key(namespace);
+        let db_path = self.db_path.clone();
+        let vec_table = self.vec_table.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = rusqlite::Connection::open(&db_path)
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            let ids: Vec<i64> = {
+                let mut stmt = tx
+                    .prepare("SELECT id FROM store_vec_meta WHERE ns = ?1")
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                let rows = stmt
+                    .query_map(params![ns], |row| row.get(0))
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                rows.collect::<Result<_, _>>()
+                    .map_err(|e| StoreError::Storage(e.to_string()))?
+            };
+            for id in ids {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE rowid = ?1", vec_table),
+                    params![id],
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            }
+            let removed = tx
+                .execute("DELETE FROM store_vec_meta WHERE ns = ?1", params![ns])
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            tx.commit()
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+            Ok(removed)
+        })
+        .await
+        .map_err(|e| StoreError::Storage(e.to_string()))?
+    }
+
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError> {
         let ns = ns_to_key(namespace);
         let db_path = self.db_path.clone();
@@ -353,9 +604,11 @@ impl Store for SqliteVecStore {
         let ns_prefix = ns_to_key(namespace_prefix);
         let like_pattern = format!("{}%", ns_prefix.trim_end_matches(']'));
         let query = options.query.clone();
+        let filter = options.filter.clone();
         let db_path = self.db_path.clone();
         let vec_table = self.vec_table.clone();
-        let embedder = self.embedder.clone();
+        let embedder = self.embedder_for(&ns_prefix);
+        let pin = EmbedderPin::of(embedder.as_ref());
         let dimension = self.dimension;
 
         if let Some(ref q) = query {
@@ -373,11 +626,15 @@ impl Store for SqliteVecStore {
                     )));
                 }
                 let vec_json = vector_to_json(&query_vec);
-                let knn_limit = (limit + options.offset).max(50) * 3;
+                let mut knn_limit = (limit + options.offset).max(50) * 3;
+                if filter.is_some() {
+                    knn_limit = knn_limit.max(FILTERED_KNN_LIMIT);
+                }
 
                 let hits = tokio::task::spawn_blocking(move || {
                     let conn = rusqlite::Connection::open(&db_path)
                         .map_err(|e| StoreError::Storage(e.to_string()))?;
+                    check_pins(&conn, &like_pattern, &pin)?;
 
                     let knn_sql = format!(
                         "SELECT rowid, distance FROM {} WHERE embedding MATCH ?1 AND k = ?2",
@@ -439,6 +696,9 @@ impl Store for SqliteVecStore {
                             let dist = dist_map.get(&id).copied()?;
                             let value: serde_json::Value =
                                 serde_json::from_str(&value_str).ok()?;
+                            if !matches_filter(&value, filter.as_ref()) {
+                                return None;
+                            }
                             let score = 1.0 / (1.0 + dist);
                             let item = Item::with_timestamps(
                                 key_to_ns(&ns_str),
@@ -470,6 +730,11 @@ impl Store for SqliteVecStore {
             }
         }
 
+        // With a filter, rows are paged after filtering them in Rust.
+        let (sql_limit, sql_offset) = match filter {
+            Some(_) => (-1, 0),
+            None => ((limit + options.offset) as i64, options.offset as i64),
+        };
         let hits = tokio::task::spawn_blocking(move || {
             let conn = rusqlite::Connection::open(&db_path)
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
@@ -480,7 +745,7 @@ impl Store for SqliteVecStore {
                 .map_err(|e| StoreError::Storage(e.to_string()))?;
             let rows = stmt
                 .query_map(
-                    params![like_pattern, (limit + options.offset) as i64, options.offset as i64],
+                    params![like_pattern, sql_limit, sql_offset],
                     |row| {
                         Ok((
                             row.get::<_, String>(0)?,
@@ -497,6 +762,9 @@ impl Store for SqliteVecStore {
                 let (ns_str, key, value_str, created_at, updated_at) =
                     row.map_err(|e| StoreError::Storage(e.to_string()))?;
                 let value: serde_json::Value = serde_json::from_str(&value_str)?;
+                if !matches_filter(&value, filter.as_ref()) {
+                    continue;
+                }
                 let item = Item::with_timestamps(
                     key_to_ns(&ns_str),
                     key,
@@ -506,6 +774,13 @@ impl Store for SqliteVecStore {
                 );
                 hits.push(SearchItem::from_item(item));
             }
+            if filter.is_some() {
+                hits = hits
+                    .into_iter()
+                    .skip(options.offset)
+                    .take(limit)
+                    .collect();
+            }
             Ok::<Vec<SearchItem>, StoreError>(hits)
         })
         .await
@@ -613,6 +888,94 @@ impl Store for SqliteVecStore {
         Ok(results)
     }
 
+    async fn reembed(
+        &self,
+        namespace: &Namespace,
+        embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        let pin = EmbedderPin::of(embedder.as_ref());
+        if pin.dimension != self.dimension {
+            return Err(StoreError::Storage(format!(
+                "reembed: {} holds vectors of dimension {}, embedder {} does not fit; copy the values into a new store file instead",
+                self.vec_table, self.dimension, pin
+            )));
+        }
+        let ns = ns_to_key(namespace);
+        let db_path = self.db_path.clone();
+
+        let rows = {
+            let ns = ns.clone();
+            let db_path = db_path.clone();
+            tokio::task::spawn_blocking(move || {
+                let conn = rusqlite::Connection::open(&db_path)
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                let mut stmt = conn
+                    .prepare("SELECT id, value FROM store_vec_meta WHERE ns = ?1 ORDER BY id")
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                let rows = stmt
+                    .query_map(params![ns], |row| {
+                        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                    })
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                let rows: Vec<(i64, String)> = rows
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                Ok::<Vec<(i64, String)>, StoreError>(rows)
+            })
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))??
+        };
+
+        let mut ids = Vec::with_capacity(rows.len());
+        let mut texts = Vec::with_capacity(rows.len());
+        for (id, value_str) in rows {
+            let value: serde_json::Value = serde_json::from_str(&value_str)?;
+            ids.push(id);
+            texts.push(text_from_value(&value));
+        }
+        let vectors = embed_all(embedder.as_ref(), &texts).await?;
+        let count = ids.len();
+        let vec_table = self.vec_table.clone();
+
+        {
+            let ns = ns.clone();
+            let pin = pin.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut conn = rusqlite::Connection::open(&db_path)
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                let tx = conn
+                    .transaction()
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                for (id, vector) in ids.into_iter().zip(vectors) {
+                    tx.execute(&format!("DELETE FROM {} WHERE rowid = ?1", vec_table), params![id])
+                        .map_err(|e| StoreError::Storage(e.to_string()))?;
+                    tx.execute(
+                        &format!("INSERT INTO {} (rowid, embedding) VALUES (?1, ?2)", vec_table),
+                        params![id, vector_to_json(&vector)],
+                    )
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                }
+                tx.execute(
+                    "INSERT INTO store_vec_models (ns, model, dimension) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(ns) DO UPDATE SET model = excluded.model, dimension = excluded.dimension",
+                    params![ns, pin.model, pin.dimension as i64],
+                )
+                .map_err(|e| StoreError::Storage(e.to_string()))?;
+                tx.commit()
+                    .map_err(|e| StoreError::Storage(e.to_string()))?;
+                Ok::<(), StoreError>(())
+            })
+            .await
+            .map_err(|e| StoreError::Storage(e.to_string()))??;
+        }
+
+        self.namespace_embedders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(ns, embedder);
+        Ok(count)
+    }
+
     async fn search_simple(
         &self,
         namespace: &Namespace,
@@ -623,7 +986,7 @@ impl Store for SqliteVecStore {
             query: query.map(String::from),
             filter: None,
             limit: limit.unwrap_or(10),
-            offset: 0,
+            ..SearchOptions::new()
         };
         let results = self.search(namespace, options).await?;
         Ok(results
@@ -0,0 +1,450 @@
+//! Write-behind queue of [`SqliteSaver`](super::SqliteSaver): coalesced checkpoint writes.
+//!
+//! A run puts a checkpoint after every step; written one at a time, each put opens the
+//! database and commits (one fsync) inside the run loop. With
+//! `SqliteSaver::with_write_behind` puts are queued instead and written in batches, one
+//! transaction per batch, when the [`CheckpointSync`] policy says so. Queued checkpoints
+//! overlay the table until they are written: `get_tuple`, `list`, `list_threads` and
+//! `tag_checkpoint` see every put at once. A put replacing a queued checkpoint of the same id
+//! replaces it in the queue, and checkpoints past the retention count are dropped from the
+//! queue without being written.
+//!
+//! **Interaction**: `CompiledStateGraph` calls [`Checkpointer::flush`](super::Checkpointer::flush)
+//! when a run ends, which writes the queue under [`CheckpointSync::EndOfRun`]. Whatever is still
+//! queued when the saver is dropped is written then, off the async worker thread; flush before
+//! shutdown to avoid that blocking write. Configured from `ReactBuildConfig`'s
+//! `checkpoint_sync` (`CHECKPOINT_SYNC`).
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rusqlite::params;
+
+use super::checkpoint::CheckpointMetadata;
+use super::checkpointer::{CheckpointError, ThreadInfo};
+use super::retention::CheckpointRetention;
+use super::sqlite_saver::{created_at_to_i64, i64_to_created_at};
+
+/// Checkpoints queued at most under [`CheckpointSync::EndOfRun`]; a put reaching it writes
+/// the queue, so that a long run does not hold its whole history in memory.
+pub(super) const MAX_PENDING: usize = 256;
+
+/// When queued checkpoints are written to SQLite (and fsynced).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointSync {
+    /// Every put is written before it returns; puts of concurrent runs waiting for the same
+    /// write share one transaction.
+    Always,
+    /// Puts return at once and are written in the background every interval. A crash loses
+    /// at most the last interval's checkpoints.
+    Interval(Duration),
+    /// Puts are written by `Checkpointer::flush`, which runs call when they end (also when
+    /// interrupted or failed), or once 256 are queued.
+    EndOfRun,
+}
+
+impl fmt::Display for CheckpointSync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => f.write_str("always"),
+            Self::Interval(interval) => write!(f, "{}ms", interval.as_millis()),
+            Self::EndOfRun => f.write_str("end-of-run"),
+        }
+    }
+}
+
+impl std::str::FromStr for CheckpointSync {
+    type Err = String;
+
+    /// `always`, `end-of-run`, or an interval such as `500ms` or `2s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase().replace('_', "-");
+        let interval = if let Some(ms) = s.strip_suffix("ms") {
+            ms.parse().ok().map(Duration::from_millis)
+        } else if let Some(secs) = s.strip_suffix('s') {
+            secs.parse().ok().map(Duration::from_secs)
+        } else {
+            None
+        };
+        match (s.as_str(), interval) {
+            ("always", _) => Ok(Self::Always),
+            ("end-of-run" | "endofrun", _) => Ok(Self::EndOfRun),
+            (_, Some(interval)) if !interval.is_zero() => Ok(Self::Interval(interval)),
+            _ => Err(format!(
+                "unknown checkpoint sync: {} (use always, end-of-run, or an interval like 500ms)",
+                s
+            )),
+        }
+    }
+}
+
+/// One row of the `checkpoints` table.
+#[derive(Debug, Clone)]
+pub(super) struct CheckpointRow {
+    pub thread_id: String,
+    pub checkpoint_ns: String,
+    pub checkpoint_id: String,
+    pub ts: String,
+    pub payload: Vec<u8>,
+    pub channel_versions: String,
+    pub metadata_source: String,
+    pub metadata_step: i64,
+    pub metadata_created_at: Option<i64>,
+    pub pending_node: Option<String>,
+    pub parent_checkpoint_id: Option<String>,
+    pub tags: Option<String>,
+}
+
+impl CheckpointRow {
+    fn is_of(&self, thread_id: &str, checkpoint_ns: &str) -> bool {
+        self.thread_id == thread_id && self.checkpoint_ns == checkpoint_ns
+    }
+
+    fn same_key(&self, other: &CheckpointRow) -> bool {
+        self.is_of(&other.thread_id, &other.checkpoint_ns)
+            && self.checkpoint_id == other.checkpoint_id
+    }
+}
+
+/// Writes `rows` in order in one transaction, then deletes what `retention` no longer keeps
+/// from each thread written (its last row in `rows` counts as the thread's newest).
+pub(super) fn write_rows(
+    db_path: &Path,
+    rows: &[CheckpointRow],
+    retention: CheckpointRetention,
+) -> Result<(), CheckpointError> {
+    let storage = |e: rusqlite::Error| CheckpointError::Storage(e.to_string());
+    let mut conn = rusqlite::Connection::open(db_path).map_err(storage)?;
+    let tx = conn.transaction().map_err(storage)?;
+    for row in rows {
+        tx.execute(
+            r#"
+            INSERT OR REPLACE INTO checkpoints
+            (thread_id, checkpoint_ns, checkpoint_id, ts, payload, channel_versions,
+             metadata_source, metadata_step, metadata_created_at, pending_node,
+             parent_checkpoint_id, tags)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#,
+            params![
+                row.thread_id,
+                row.checkpoint_ns,
+                row.checkpoint_id,
+                row.ts,
+                row.payload,
+                row.channel_versions,
+                row.metadata_source,
+                row.metadata_step,
+                row.metadata_created_at,
+                row.pending_node,
+                row.parent_checkpoint_id,
+                row.tags,
+            ],
+        )
+        .map_err(storage)?;
+    }
+    let cutoff = retention
+        .cutoff(std::time::SystemTime::now())
+        .and_then(|t| created_at_to_i64(&Some(t)));
+    let newest = rows
+        .iter()
+        .enumerate()
+        .filter(|(i, row)| {
+            !rows[i + 1..]
+                .iter()
+                .any(|r| r.is_of(&row.thread_id, &row.checkpoint_ns))
+        })
+        .map(|(_, row)| row);
+    for row in newest {
+        if let Some(max) = retention.max_checkpoints() {
+            tx.execute(
+                "DELETE FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
+                 AND rowid NOT IN (
+                     SELECT rowid FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
+                     ORDER BY metadata_created_at DESC, rowid DESC LIMIT ?3)",
+                params![row.thread_id, row.checkpoint_ns, max as i64],
+            )
+            .map_err(storage)?;
+        }
+        if let Some(cutoff) = cutoff {
+            tx.execute(
+                "DELETE FROM checkpoints WHERE thread_id = ?1 AND checkpoint_ns = ?2
+                 AND metadata_created_at < ?3 AND checkpoint_id != ?4",
+                params![row.thread_id, row.checkpoint_ns, cutoff, row.checkpoint_id],
+            )
+            .map_err(storage)?;
+        }
+    }
+    tx.commit().map_err(storage)
+}
+
+/// A queued row; `version` changes whenever the row does, so a batch written meanwhile does
+/// not dequeue the newer content.
+struct Pending {
+    row: CheckpointRow,
+    version: u64,
+}
+
+/// Checkpoints put but not yet written, in put order, and the policy writing them.
+pub(super) struct WriteQueue {
+    db_path: PathBuf,
+    retention: CheckpointRetention,
+    sync: CheckpointSync,
+    pending: Mutex<Vec<Pending>>,
+    next_version: AtomicU64,
+    /// Held while a batch is written (and while a thread is deleted), so batches are written
+    /// in order and a deleted thread is not written back by a batch taken before.
+    writing: tokio::sync::Mutex<()>,
+    timer_started: AtomicBool,
+}
+
+impl WriteQueue {
+    pub(super) fn new(
+        db_path: PathBuf,
+        retention: CheckpointRetention,
+        sync: CheckpointSync,
+    ) -> Self {
+        Self {
+            db_path,
+            retention,
+            sync,
+            pending: Mutex::new(Vec::new()),
+            next_version: AtomicU64::new(0),
+            writing: tokio::sync::Mutex::new(()),
+            timer_started: AtomicBool::new(false),
+        }
+    }
+
+    pub(super) fn sync(&self) -> CheckpointSync {
+        self.sync
+    }
+
+    fn version(&self) -> u64 {
+        self.next_version.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, Vec<Pending>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues `row` (replacing a queued row with the same id) and writes the queue when the
+    /// policy says so.
+    pub(super) async fn push(self: &Arc<Self>, row: CheckpointRow) -> Result<(), CheckpointError> {
+        let queued = {
+            let mut pending = self.pending();
+            pending.retain(|p| !p.row.same_key(&row));
+            let (thread_id, checkpoint_ns) = (row.thread_id.clone(), row.checkpoint_ns.clone());
+            pending.push(Pending {
+                row,
+                version: self.version(),
+            });
+            if let Some(max) = self.retention.max_checkpoints() {
+                let mut kept = 0;
+                for i in (0..pending.len()).rev() {
+                    if pending[i].row.is_of(&thread_id, &checkpoint_ns) {
+                        kept += 1;
+                        if kept > max {
+                            pending.remove(i);
+                        }
+                    }
+                }
+            }
+            pending.len()
+        };
+        match self.sync {
+            CheckpointSync::Always => self.flush().await,
+            CheckpointSync::Interval(interval) => {
+                self.start_timer(interval);
+                Ok(())
+            }
+            CheckpointSync::EndOfRun if queued >= MAX_PENDING => self.flush().await,
+            CheckpointSync::EndOfRun => Ok(()),
+        }
+    }
+
+    /// Writes the queued rows in one transaction. On failure they stay queued for the next
+    /// write.
+    pub(super) async fn flush(&self) -> Result<(), CheckpointError> {
+        let _writing = self.writing.lock().await;
+        let batch: Vec<(CheckpointRow, u64)> = self
+            .pending()
+            .iter()
+            .map(|p| (p.row.clone(), p.version))
+            .collect();
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let db_path = self.db_path.clone();
+        let retention = self.retention;
+        let rows: Vec<CheckpointRow> = batch.iter().map(|(row, _)| row.clone()).collect();
+        tokio::task::spawn_blocking(move || write_rows(&db_path, &rows, retention))
+            .await
+            .map_err(|e| CheckpointError::Storage(e.to_string()))??;
+        self.pending().retain(|p| {
+            !batch
+                .iter()
+                .any(|(row, version)| p.version == *version && p.row.same_key(row))
+        });
+        Ok(())
+    }
+
+    /// Starts the task writing the queue every `interval`, once. It ends with the queue.
+    fn start_timer(self: &Arc<Self>, interval: Duration) {
+        if self.timer_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let queue = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(queue) = queue.upgrade() else {
+                    break;
+                };
+                if let Err(e) = queue.flush().await {
+                    tracing::warn!(error = %e, "writing queued checkpoints failed; will retry");
+                }
+            }
+        });
+    }
+
+    /// The queued row of the thread's checkpoint `checkpoint_id`, or its newest queued row.
+    pub(super) fn get(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: &str,
+        checkpoint_id: Option<&str>,
+    ) -> Option<CheckpointRow> {
+        self.pending()
+            .iter()
+            .rev()
+            .map(|p| &p.row)
+            .filter(|row| row.is_of(thread_id, checkpoint_ns))
+            .find(|row| checkpoint_id.is_none_or(|id| row.checkpoint_id == id))
+            .cloned()
+    }
+
+    /// The thread's queued rows, oldest first.
+    pub(super) fn rows(&self, thread_id: &str, checkpoint_ns: &str) -> Vec<CheckpointRow> {
+        self.pending()
+            .iter()
+            .map(|p| &p.row)
+            .filter(|row| row.is_of(thread_id, checkpoint_ns))
+            .cloned()
+            .collect()
+    }
+
+    /// Threads with queued rows and the newest creation time among them.
+    pub(super) fn threads(&self) -> Vec<ThreadInfo> {
+        let mut threads: Vec<ThreadInfo> = Vec::new();
+        for p in self.pending().iter() {
+            let created_at = i64_to_created_at(p.row.metadata_created_at);
+            match threads.iter_mut().find(|t| t.thread_id == p.row.thread_id) {
+                Some(thread) => thread.last_activity = thread.last_activity.max(created_at),
+                None => threads.push(ThreadInfo {
+                    thread_id: p.row.thread_id.clone(),
+                    last_activity: created_at,
+                }),
+            }
+        }
+        threads
+    }
+
+    /// Adds `tags` to a queued row as `Checkpointer::tag_checkpoint` does: the row of
+    /// `checkpoint_id`, else the thread's newest. False when no queued row matches.
+    pub(super) fn tag(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: &str,
+        checkpoint_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<bool, CheckpointError> {
+        let version = self.version();
+        let mut pending = self.pending();
+        let Some(p) = pending
+            .iter_mut()
+            .rev()
+            .filter(|p| p.row.is_of(thread_id, checkpoint_ns))
+            .find(|p| checkpoint_id.is_none_or(|id| p.row.checkpoint_id == id))
+        else {
+            return Ok(false);
+        };
+        let mut metadata = CheckpointMetadata {
+            tags: super::sqlite_saver::tags_from_json(p.row.tags.as_deref()),
+            ..Default::default()
+        };
+        metadata.add_tags(tags);
+        p.row.tags = Some(super::sqlite_saver::tags_to_json(&metadata.tags)?);
+        p.version = version;
+        Ok(true)
+    }
+
+    /// Waits for a batch being written, then drops the thread's queued rows; returns whether
+    /// it had any. Batches are held off until the guard is dropped.
+    pub(super) async fn remove_thread(
+        &self,
+        thread_id: &str,
+    ) -> (tokio::sync::MutexGuard<'_, ()>, bool) {
+        let writing = self.writing.lock().await;
+        let mut pending = self.pending();
+        let before = pending.len();
+        pending.retain(|p| p.row.thread_id != thread_id);
+        let removed = pending.len() < before;
+        drop(pending);
+        (writing, removed)
+    }
+}
+
+impl Drop for WriteQueue {
+    /// Writes what is still queued, blocking. On a runtime worker the write runs through
+    /// `block_in_place` (multi-thread runtime) or on a dedicated thread (current-thread
+    /// runtime), so the worker's other tasks are not stalled on SQLite IO. Call
+    /// [`flush`](Self::flush) before dropping to avoid the blocking write.
+    fn drop(&mut self) {
+        let pending = std::mem::take(self.pending.get_mut().unwrap_or_else(|e| e.into_inner()));
+        if pending.is_empty() {
+            return;
+        }
+        let rows: Vec<CheckpointRow> = pending.into_iter().map(|p| p.row).collect();
+        let (db_path, retention) = (self.db_path.clone(), self.retention);
+        let write = move || {
+            if let Err(e) = write_rows(&db_path, &rows, retention) {
+                tracing::warn!(error = %e, count = rows.len(), "queued checkpoints were lost");
+            }
+        };
+        match tokio::runtime::Handle::try_current().map(|h| h.runtime_flavor()) {
+            Ok(tokio::runtime::RuntimeFlavor::MultiThread) => tokio::task::block_in_place(write),
+            Ok(_) => {
+                if std::thread::spawn(write).join().is_err() {
+                    tracing::warn!("writing queued checkpoints panicked");
+                }
+            }
+            Err(_) => write(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Sync policies parse from config strings and print back the same way.
+    #[test]
+    fn parses_sync_policy() {
+        assert_eq!("always".parse(), Ok(CheckpointSync::Always));
+        assert_eq!("End_Of_Run".parse(), Ok(CheckpointSync::EndOfRun));
+        let interval: CheckpointSync = "500ms".parse().unwrap();
+        assert_eq!(
+            interval,
+            CheckpointSync::Interval(Duration::from_millis(500))
+        );
+        assert_eq!(interval.to_string(), "500ms");
+        assert_eq!(
+            "2s".parse(),
+            Ok(CheckpointSync::Interval(Duration::from_secs(2)))
+        );
+        assert!("0ms".parse::<CheckpointSync>().is_err());
+        assert!("sometimes".parse::<CheckpointSync>().is_err());
+    }
+}
@@ -26,8 +26,11 @@
 //! ```
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use std::time::SystemTime;
 
+use crate::memory::embedder::{Embedder, EmbedderPin};
+
 /// Namespace for Store: e.g. (user_id, "memories") or (user_id, "preferences").
 ///
 /// Aligns with LangGraph namespace tuple. Each element in the vector represents
@@ -63,6 +66,18 @@ pub enum StoreError {
     /// Embedding generation error (e.g. OpenAI API error).
     #[error("embedding: {0}")]
     EmbeddingError(String),
+
+    /// The namespace holds vectors from another embedder (model or dimension changed).
+    #[error(
+        "namespace {namespace:?} was embedded with {pinned}, not {current}; migrate it with reembed"
+    )]
+    EmbedderMismatch {
+        namespace: Namespace,
+        /// Embedder the namespace's vectors come from.
+        pinned: EmbedderPin,
+        /// Embedder of the refused write or query.
+        current: EmbedderPin,
+    },
 }
 
 impl From<serde_json::Error> for StoreError {
@@ -170,6 +185,111 @@ pub enum FilterOp {
     Lt(serde_json::Value),
     /// Less than or equal to.
     Lte(serde_json::Value),
+    /// Every operator matches, e.g. a date range from `{"$gte": .., "$lt": ..}`.
+    All(Vec<FilterOp>),
+}
+
+impl FilterOp {
+    /// Whether a value whose filtered field is `field` (None when missing) matches. A missing
+    /// field never matches; ordering compares numbers with numbers and strings with strings
+    /// (byte order, so ISO 8601 dates compare chronologically).
+    pub fn matches(&self, field: Option<&serde_json::Value>) -> bool {
+        use std::cmp::Ordering;
+        let Some(v) = field else {
+            return false;
+        };
+        match self {
+            FilterOp::Eq(expected) => v == expected,
+            FilterOp::Ne(expected) => v != expected,
+            FilterOp::Gt(expected) => compare_json(v, expected) == Some(Ordering::Greater),
+            FilterOp::Gte(expected) => matches!(
+                compare_json(v, expected),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            FilterOp::Lt(expected) => compare_json(v, expected) == Some(Ordering::Less),
+            FilterOp::Lte(expected) => matches!(
+                compare_json(v, expected),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            FilterOp::All(ops) => ops.iter().all(|op| op.matches(field)),
+        }
+    }
+
+    /// Parses a LangGraph-style filter object into [`SearchOptions::filter`]: `{"tag": "work"}`
+    /// matches equal values, and an object of operators such as
+    /// `{"date": {"$gte": "2024-01-01", "$lt": "2024-02-01"}}` compares (`$eq`, `$ne`, `$gt`,
+    /// `$gte`, `$lt`, `$lte`).
+    ///
+    /// # Errors
+    ///
+    /// [`StoreError::Serialization`] when `filter` is not an object or names an unknown
+    /// operator.
+    pub fn parse_filter(
+        filter: &serde_json::Value,
+    ) -> Result<std::collections::HashMap<String, FilterOp>, StoreError> {
+        let fields = filter.as_object().ok_or_else(|| {
+            StoreError::Serialization(format!("filter must be an object, got {}", filter))
+        })?;
+        let mut parsed = std::collections::HashMap::with_capacity(fields.len());
+        for (field, condition) in fields {
+            let op = match condition.as_object() {
+                Some(ops) if !ops.is_empty() && ops.keys().all(|k| k.starts_with('$')) => {
+                    let mut all = ops
+                        .iter()
+                        .map(|(name, operand)| Self::operator(name, operand.clone()))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if all.len() == 1 {
+                        all.remove(0)
+                    } else {
+                        FilterOp::All(all)
+                    }
+                }
+                _ => FilterOp::Eq(condition.clone()),
+            };
+            parsed.insert(field.clone(), op);
+        }
+        Ok(parsed)
+    }
+
+    fn operator(name: &str, operand: serde_json::Value) -> Result<FilterOp, StoreError> {
+        Ok(match name {
+            "$eq" => FilterOp::Eq(operand),
+            "$ne" => FilterOp::Ne(operand),
+            "$gt" => FilterOp::Gt(operand),
+            "$gte" => FilterOp::Gte(operand),
+            "$lt" => FilterOp::Lt(operand),
+            "$lte" => FilterOp::Lte(operand),
+            other => {
+                return Err(StoreError::Serialization(format!(
+                    "unknown filter operator: {}",
+                    other
+                )))
+            }
+        })
+    }
+}
+
+/// Orders two JSON values of the same kind (numbers or strings); None otherwise.
+fn compare_json(a: &serde_json::Value, b: &serde_json::Value) -> Option<std::cmp::Ordering> {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Whether `value` matches every entry of `filter` (true without a filter); for stores that
+/// filter search results themselves.
+pub(crate) fn matches_filter(
+    value: &serde_json::Value,
+    filter: Option<&std::collections::HashMap<String, FilterOp>>,
+) -> bool {
+    filter.map_or(true, |filter| {
+        filter
+            .iter()
+            .all(|(field, op)| op.matches(value.get(field)))
+    })
 }
 
 /// Options for search operations.
@@ -185,6 +305,38 @@ pub struct SearchOptions {
     pub limit: usize,
     /// Number of matching items to skip for pagination. Default: 0.
     pub offset: usize,
+    /// How `query` ranks items. Default: [`SearchMode::Vector`].
+    pub mode: SearchMode,
+}
+
+/// How a search `query` ranks items; see [`SearchOptions::with_mode`].
+///
+/// Vector stores (`InMemoryVectorStore`, `LanceStore`) implement all modes; other stores have
+/// one way of matching a query and ignore the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SearchMode {
+    /// Cosine similarity of the query's embedding (semantic search).
+    #[default]
+    Vector,
+    /// BM25 over the terms of the stored text, for exact names and IDs; items without a query
+    /// term are not returned, and the query is not embedded.
+    Keyword,
+    /// Both scores, min-max normalized over the candidates and blended as
+    /// `alpha * vector + (1 - alpha) * keyword`; `alpha` is clamped to 0.0..=1.0.
+    Hybrid(f32),
+}
+
+impl SearchMode {
+    /// Weight of the vector score: 1.0 for `Vector`, 0.0 for `Keyword`, the clamped `alpha` of
+    /// `Hybrid` (0.0 when NaN).
+    pub fn alpha(&self) -> f32 {
+        match self {
+            Self::Vector => 1.0,
+            Self::Keyword => 0.0,
+            Self::Hybrid(alpha) if alpha.is_nan() => 0.0,
+            Self::Hybrid(alpha) => alpha.clamp(0.0, 1.0),
+        }
+    }
 }
 
 impl Default for SearchOptions {
@@ -201,6 +353,7 @@ impl SearchOptions {
             filter: None,
             limit: 10,
             offset: 0,
+            mode: SearchMode::Vector,
         }
     }
 
@@ -221,6 +374,20 @@ impl SearchOptions {
         self.offset = offset;
         self
     }
+
+    /// Ranks query results by `mode`: vector, keyword (BM25) or a hybrid of both.
+    pub fn with_mode(mut self, mode: SearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Adds a condition on the top-level `field` of values; results must match all conditions.
+    pub fn with_filter(mut self, field: impl Into<String>, op: FilterOp) -> Self {
+        self.filter
+            .get_or_insert_with(Default::default)
+            .insert(field.into(), op);
+        self
+    }
 }
 
 /// Match type for namespace filtering in list operations.
@@ -373,6 +540,23 @@ mod tests {
         assert!(s.to_lowercase().contains("not found"), "{}", s);
         let s = StoreError::EmbeddingError("api".into()).to_string();
         assert!(s.to_lowercase().contains("embedding"), "{}", s);
+        let s = StoreError::EmbedderMismatch {
+            namespace: vec!["u1".into()],
+            pinned: EmbedderPin {
+                model: "text-embedding-ada-002".into(),
+                dimension: 1536,
+            },
+            current: EmbedderPin {
+                model: "text-embedding-3-large".into(),
+                dimension: 3072,
+            },
+        }
+        .to_string();
+        assert!(
+            s.contains("text-embedding-ada-002 (1536 dims)") && s.contains("reembed"),
+            "{}",
+            s
+        );
     }
 
     /// **Scenario**: StoreSearchHit key/value/score can be constructed and accessed.
@@ -558,6 +742,41 @@ mod tests {
         assert_ne!(gt, gte);
         assert_ne!(lt, lte);
     }
+
+    /// **Scenario**: parse_filter reads plain values as equality and operator objects as
+    /// comparisons (several become a range); unknown operators and non-objects are errors.
+    #[test]
+    fn parse_filter_operators_and_ranges() {
+        let filter = FilterOp::parse_filter(&serde_json::json!({
+            "tag": "work",
+            "meta": {"source": "chat"},
+            "date": {"$gte": "2024-01-01", "$lt": "2024-02-01"},
+            "rank": {"$gt": 3}
+        }))
+        .unwrap();
+        assert_eq!(filter["tag"], FilterOp::Eq(serde_json::json!("work")));
+        assert_eq!(
+            filter["meta"],
+            FilterOp::Eq(serde_json::json!({"source": "chat"}))
+        );
+        assert_eq!(filter["rank"], FilterOp::Gt(serde_json::json!(3)));
+
+        let jan = serde_json::json!({"tag": "work", "meta": {"source": "chat"},
+            "date": "2024-01-15", "rank": 4});
+        let feb = serde_json::json!({"tag": "work", "meta": {"source": "chat"},
+            "date": "2024-02-03", "rank": 4});
+        assert!(matches_filter(&jan, Some(&filter)));
+        assert!(!matches_filter(&feb, Some(&filter)));
+        let no_date = serde_json::json!({"tag": "work"});
+        assert!(!matches_filter(&no_date, Some(&filter)));
+        assert!(matches_filter(&feb, None));
+
+        assert!(matches!(
+            FilterOp::parse_filter(&serde_json::json!({"n": {"$in": [1]}})),
+            Err(StoreError::Serialization(_))
+        ));
+        assert!(FilterOp::parse_filter(&serde_json::json!(["tag"])).is_err());
+    }
 }
 
 /// A single hit returned by [`Store::search_simple`] (legacy API).
@@ -621,6 +840,20 @@ pub trait Store: Send + Sync {
         value: &serde_json::Value,
     ) -> Result<(), StoreError>;
 
+    /// Stores every `(key, value)` of `items` under `namespace`, e.g. the chunks of an ingested
+    /// document. Vector stores embed the values in batches instead of one request per value;
+    /// the default puts them one by one. On error, some items may already be stored.
+    async fn put_many(
+        &self,
+        namespace: &Namespace,
+        items: &[(String, serde_json::Value)],
+    ) -> Result<(), StoreError> {
+        for (key, value) in items {
+            self.put(namespace, key, value).await?;
+        }
+        Ok(())
+    }
+
     /// Returns the value for `(namespace, key)`, or `None` if not found.
     ///
     /// This is the simple API that returns only the value. Use [`get_item`] for full item metadata.
@@ -643,11 +876,24 @@ pub trait Store: Send + Sync {
     /// Returns all keys in the given namespace (order is implementation-defined).
     async fn list(&self, namespace: &Namespace) -> Result<Vec<String>, StoreError>;
 
+    /// Deletes every item of `namespace` (not of namespaces below it) and returns how many
+    /// there were, e.g. to forget all memories of a user. The default deletes the listed keys
+    /// one by one; on error, some items may already be deleted.
+    async fn clear(&self, namespace: &Namespace) -> Result<usize, StoreError> {
+        let keys = self.list(namespace).await?;
+        for key in &keys {
+            self.delete(namespace, key).await?;
+        }
+        Ok(keys.len())
+    }
+
     /// Searches within the namespace prefix with the given options.
     ///
     /// - If `options.query` is `None`, returns items up to `options.limit`.
     /// - If `options.query` is set, filters by string match or semantic similarity
     ///   (implementation-defined).
+    /// - Only values matching every `options.filter` condition are returned; the limit and
+    ///   offset apply to the matches.
     /// - Results include optional relevance scores for ranked search.
     async fn search(
         &self,
@@ -671,6 +917,44 @@ pub trait Store: Send + Sync {
     /// More efficient than calling individual operations for bulk data manipulation.
     async fn batch(&self, ops: Vec<StoreOp>) -> Result<Vec<StoreOpResult>, StoreError>;
 
+    /// Re-embeds every value in `namespace` with `embedder` and pins the namespace to it;
+    /// returns how many items were re-embedded.
+    ///
+    /// Vector stores pin each namespace to the embedder of its first write and refuse writes and
+    /// queries from another one with [`StoreError::EmbedderMismatch`]; this migrates a namespace
+    /// after the embedding model changed. Later puts and searches on this store use `embedder`
+    /// for the namespace. Stores without vectors return `StoreError::Storage`.
+    async fn reembed(
+        &self,
+        _namespace: &Namespace,
+        _embedder: Arc<dyn Embedder>,
+    ) -> Result<usize, StoreError> {
+        Err(StoreError::Storage(
+            "reembed is not supported by this store".into(),
+        ))
+    }
+
+    /// Searches like [`search`](Self::search) with a LangGraph-style JSON `filter` on value
+    /// fields (see [`FilterOp::parse_filter`]), e.g. `{"tag": "work", "date": {"$gte":
+    /// "2024-01-01"}}` to scope retrieval by tag and date. Returns up to 10 items.
+    ///
+    /// # Errors
+    ///
+    /// [`StoreError::Serialization`] for an invalid filter, else the errors of `search`.
+    async fn search_with_filter(
+        &self,
+        namespace_prefix: &Namespace,
+        query: Option<&str>,
+        filter: &serde_json::Value,
+    ) -> Result<Vec<SearchItem>, StoreError> {
+        let options = SearchOptions {
+            query: query.map(String::from),
+            filter: Some(FilterOp::parse_filter(filter)?),
+            ..SearchOptions::new()
+        };
+        self.search(namespace_prefix, options).await
+    }
+
     // --- Legacy API for backward compatibility ---
 
     /// Searches within the namespace (legacy API).
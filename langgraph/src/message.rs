@@ -1,20 +1,39 @@
 //! Minimal message types for agent state.
 //!
-//! Aligns with LangGraph/LangChain: System (usually first in the list), User, Assistant.
+//! Aligns with LangGraph/LangChain: System (usually first in the list), User, Assistant
+//! (with optional tool calls), and Tool (result of one tool call).
 //! Used by `AgentState::messages` and by agents that read/append messages in `Agent::run`.
+//!
+//! **Serde**: `Assistant` used to be a plain string (`{"Assistant":"text"}`); that form still
+//! deserializes (as an assistant message without tool calls), so old checkpoints keep loading.
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::ToolCall;
 
 /// A single message in the conversation.
 ///
-/// Roles match LangGraph: system prompt, user input, assistant reply.
-/// No separate Tool role in this minimal design; extend in later Sprints.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Roles match LangGraph / OpenAI chat: system prompt, user input, assistant reply (text and
+/// optional tool calls), and tool result (answering one assistant tool call by `call_id`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "MessageRepr")]
 pub enum Message {
     /// System prompt; typically placed first in the message list.
     System(String),
     /// User input.
     User(String),
-    /// Model/agent reply.
-    Assistant(String),
+    /// Model/agent reply: text plus the tool calls requested in this turn (may be empty).
+    Assistant {
+        content: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_calls: Vec<ToolCall>,
+    },
+    /// Result of one tool call; `call_id` matches `ToolCall::id` of the preceding assistant message.
+    Tool {
+        call_id: Option<String>,
+        name: Option<String>,
+        content: String,
+    },
 }
 
 impl Message {
@@ -28,9 +47,111 @@ impl Message {
         Self::User(content.into())
     }
 
-    /// Creates an assistant message.
+    /// Creates an assistant message without tool calls.
     pub fn assistant(content: impl Into<String>) -> Self {
-        Self::Assistant(content.into())
+        Self::Assistant {
+            content: content.into(),
+            tool_calls: vec![],
+        }
+    }
+
+    /// Creates an assistant message that requests tool calls.
+    pub fn assistant_with_tool_calls(content: impl Into<String>, tool_calls: Vec<ToolCall>) -> Self {
+        Self::Assistant {
+            content: content.into(),
+            tool_calls,
+        }
+    }
+
+    /// Creates a tool result message.
+    pub fn tool(
+        call_id: Option<String>,
+        name: Option<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        Self::Tool {
+            call_id,
+            name,
+            content: content.into(),
+        }
+    }
+
+    /// Text content of the message, whatever its role.
+    pub fn content(&self) -> &str {
+        match self {
+            Self::System(s) | Self::User(s) => s,
+            Self::Assistant { content, .. } | Self::Tool { content, .. } => content,
+        }
+    }
+
+    /// Mutable text content of the message, whatever its role.
+    pub fn content_mut(&mut self) -> &mut String {
+        match self {
+            Self::System(s) | Self::User(s) => s,
+            Self::Assistant { content, .. } | Self::Tool { content, .. } => content,
+        }
+    }
+
+    /// Role name as used by OpenAI chat: `system`, `user`, `assistant`, or `tool`.
+    pub fn role(&self) -> &'static str {
+        match self {
+            Self::System(_) => "system",
+            Self::User(_) => "user",
+            Self::Assistant { .. } => "assistant",
+            Self::Tool { .. } => "tool",
+        }
+    }
+}
+
+/// Deserialization shape of [`Message`]; accepts the legacy string form of `Assistant`.
+#[derive(Deserialize)]
+enum MessageRepr {
+    System(String),
+    User(String),
+    Assistant(AssistantRepr),
+    Tool {
+        #[serde(default)]
+        call_id: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+        content: String,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AssistantRepr {
+    Text(String),
+    Full {
+        content: String,
+        #[serde(default)]
+        tool_calls: Vec<ToolCall>,
+    },
+}
+
+impl From<MessageRepr> for Message {
+    fn from(repr: MessageRepr) -> Self {
+        match repr {
+            MessageRepr::System(s) => Self::System(s),
+            MessageRepr::User(s) => Self::User(s),
+            MessageRepr::Assistant(AssistantRepr::Text(content)) => Self::assistant(content),
+            MessageRepr::Assistant(AssistantRepr::Full {
+                content,
+                tool_calls,
+            }) => Self::Assistant {
+                content,
+                tool_calls,
+            },
+            MessageRepr::Tool {
+                call_id,
+                name,
+                content,
+            } => Self::Tool {
+                call_id,
+                name,
+                content,
+            },
+        }
     }
 }
 
@@ -46,25 +167,74 @@ mod tests {
         let usr = Message::user("u");
         assert!(matches!(&usr, Message::User(c) if c == "u"));
         let ast = Message::assistant("a");
-        assert!(matches!(&ast, Message::Assistant(c) if c == "a"));
+        assert!(
+            matches!(&ast, Message::Assistant { content, tool_calls } if content == "a" && tool_calls.is_empty())
+        );
+        let tool = Message::tool(Some("c1".into()), Some("get_time".into()), "12:00");
+        assert_eq!(tool.content(), "12:00");
+        assert_eq!(tool.role(), "tool");
     }
 
     /// **Scenario**: Each Message variant round-trips through serde.
     #[test]
     fn message_serialize_deserialize_roundtrip() {
+        let call = ToolCall {
+            name: "get_time".into(),
+            arguments: "{}".into(),
+            id: Some("c1".into()),
+        };
         for msg in [
             Message::system("sys"),
             Message::user("usr"),
             Message::assistant("ast"),
+            Message::assistant_with_tool_calls("", vec![call]),
+            Message::tool(Some("c1".into()), Some("get_time".into()), "12:00"),
         ] {
             let json = serde_json::to_string(&msg).expect("serialize");
             let back: Message = serde_json::from_str(&json).expect("deserialize");
             match (&msg, &back) {
                 (Message::System(a), Message::System(b)) => assert_eq!(a, b),
                 (Message::User(a), Message::User(b)) => assert_eq!(a, b),
-                (Message::Assistant(a), Message::Assistant(b)) => assert_eq!(a, b),
+                (
+                    Message::Assistant {
+                        content: a,
+                        tool_calls: ta,
+                    },
+                    Message::Assistant {
+                        content: b,
+                        tool_calls: tb,
+                    },
+                ) => {
+                    assert_eq!(a, b);
+                    assert_eq!(ta.len(), tb.len());
+                    assert_eq!(ta.first().map(|t| &t.id), tb.first().map(|t| &t.id));
+                }
+                (
+                    Message::Tool {
+                        call_id: a,
+                        content: ca,
+                        ..
+                    },
+                    Message::Tool {
+                        call_id: b,
+                        content: cb,
+                        ..
+                    },
+                ) => {
+                    assert_eq!(a, b);
+                    assert_eq!(ca, cb);
+                }
                 _ => panic!("variant mismatch: {:?} vs {:?}", msg, back),
             }
         }
     }
+
+    /// **Scenario**: Legacy `{"Assistant":"text"}` JSON still deserializes as an assistant message.
+    #[test]
+    fn message_deserializes_legacy_assistant_string() {
+        let back: Message = serde_json::from_str(r#"{"Assistant":"hello"}"#).unwrap();
+        assert!(
+            matches!(&back, Message::Assistant { content, tool_calls } if content == "hello" && tool_calls.is_empty())
+        );
+    }
 }
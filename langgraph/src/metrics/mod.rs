@@ -0,0 +1,78 @@
+//! Runtime metrics: node durations, LLM latency, tool calls, checkpoint latency and tokens.
+//!
+//! The graph run loop, `ActNode` and `ChatOpenAI` report to the process-wide
+//! [`MetricsRecorder`] installed with [`set_recorder`]; without one, nothing is recorded.
+//! [`PrometheusRecorder`] keeps counters and histograms and renders them in the Prometheus
+//! text format (langgraph-server serves it at `/metrics`); other exporters implement
+//! [`MetricsRecorder`] themselves.
+//!
+//! Like a tracing subscriber, the recorder is installed once per process.
+//!
+//! **Interaction**: Called from `CompiledStateGraph::run_loop_inner` (node durations,
+//! checkpoint reads and writes of a run), `ActNode` (tool calls) and `ChatOpenAI` (LLM
+//! latency and tokens).
+
+mod prometheus;
+
+pub use prometheus::{PrometheusRecorder, DURATION_BUCKETS};
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Checkpoint operation timed by [`MetricsRecorder::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointOp {
+    /// Loading a checkpoint (`get_tuple`).
+    Read,
+    /// Saving a checkpoint (`put`).
+    Write,
+}
+
+impl CheckpointOp {
+    /// Label value: `read` or `write`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+}
+
+/// Receives runtime measurements; every method defaults to doing nothing.
+///
+/// `ok` is false when the measured operation failed.
+pub trait MetricsRecorder: Send + Sync {
+    /// A graph node finished (an interrupt counts as ok).
+    fn node_finished(&self, _node_id: &str, _duration: Duration, _ok: bool) {}
+
+    /// An LLM call finished, continuations of a truncated reply included.
+    fn llm_request(&self, _model: &str, _duration: Duration, _ok: bool) {}
+
+    /// A tool call finished; a failure caught by `HandleToolErrors` still counts as failed.
+    fn tool_call(&self, _tool: &str, _duration: Duration, _ok: bool) {}
+
+    /// A checkpoint was read or written.
+    fn checkpoint(&self, _op: CheckpointOp, _duration: Duration, _ok: bool) {}
+
+    /// Tokens an LLM call consumed, as reported by the provider.
+    fn tokens(&self, _model: &str, _prompt_tokens: u32, _completion_tokens: u32) {}
+}
+
+static RECORDER: OnceLock<Arc<dyn MetricsRecorder>> = OnceLock::new();
+
+/// Installs the process-wide recorder. Fails (returning it) when one is already installed.
+pub fn set_recorder(recorder: Arc<dyn MetricsRecorder>) -> Result<(), Arc<dyn MetricsRecorder>> {
+    RECORDER.set(recorder)
+}
+
+/// The installed recorder, if any.
+pub fn recorder() -> Option<&'static dyn MetricsRecorder> {
+    RECORDER.get().map(|r| r.as_ref())
+}
+
+/// Runs `f` with the installed recorder; does nothing without one.
+pub(crate) fn record(f: impl FnOnce(&dyn MetricsRecorder)) {
+    if let Some(recorder) = recorder() {
+        f(recorder);
+    }
+}
@@ -0,0 +1,277 @@
+//! [`MetricsRecorder`] keeping counters and histograms for a Prometheus scrape.
+//!
+//! Metrics (all durations in seconds):
+//!
+//! - `langgraph_node_duration_seconds{node,status}`: histogram of node runs.
+//! - `langgraph_llm_request_duration_seconds{model,status}`: histogram of LLM calls.
+//! - `langgraph_tool_calls_total{tool,status}`: counter of tool calls; `status="error"` counts
+//!   failures.
+//! - `langgraph_tool_call_duration_seconds{tool}`: histogram of tool calls.
+//! - `langgraph_checkpoint_duration_seconds{op,status}`: histogram of checkpoint reads and
+//!   writes.
+//! - `langgraph_tokens_total{model,kind}`: counter of prompt and completion tokens.
+//!
+//! `status` is `ok` or `error`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::{CheckpointOp, MetricsRecorder};
+
+/// Upper bounds (seconds) of the duration histogram buckets.
+pub const DURATION_BUCKETS: [f64; 14] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+const NODE_DURATION: &str = "langgraph_node_duration_seconds";
+const LLM_DURATION: &str = "langgraph_llm_request_duration_seconds";
+const TOOL_CALLS: &str = "langgraph_tool_calls_total";
+const TOOL_DURATION: &str = "langgraph_tool_call_duration_seconds";
+const CHECKPOINT_DURATION: &str = "langgraph_checkpoint_duration_seconds";
+const TOKENS: &str = "langgraph_tokens_total";
+
+/// Name, help text and whether the family is a histogram (else a counter), in render order.
+const FAMILIES: [(&str, &str, bool); 6] = [
+    (NODE_DURATION, "Duration of graph node runs.", true),
+    (LLM_DURATION, "Duration of LLM calls.", true),
+    (TOOL_CALLS, "Tool calls by outcome.", false),
+    (TOOL_DURATION, "Duration of tool calls.", true),
+    (
+        CHECKPOINT_DURATION,
+        "Duration of checkpoint reads and writes.",
+        true,
+    ),
+    (TOKENS, "Tokens consumed by LLM calls.", false),
+];
+
+/// Label pairs of one series, in declaration order.
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket of [`DURATION_BUCKETS`] (not cumulative).
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if let Some(i) = DURATION_BUCKETS.iter().position(|le| value <= *le) {
+            self.buckets[i] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Series {
+    counters: BTreeMap<(&'static str, Labels), u64>,
+    histograms: BTreeMap<(&'static str, Labels), Histogram>,
+}
+
+/// Recorder keeping every series in memory; [`render`](Self::render) writes them in the
+/// Prometheus text exposition format.
+#[derive(Debug, Default)]
+pub struct PrometheusRecorder {
+    series: Mutex<Series>,
+}
+
+fn status(ok: bool) -> String {
+    if ok { "ok" } else { "error" }.to_string()
+}
+
+impl PrometheusRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn count(&self, name: &'static str, labels: Labels, n: u64) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        *series.counters.entry((name, labels)).or_default() += n;
+    }
+
+    fn observe(&self, name: &'static str, labels: Labels, duration: Duration) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series
+            .histograms
+            .entry((name, labels))
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Value of a counter, e.g. `counter("langgraph_tool_calls_total", &[("tool", "bash"),
+    /// ("status", "error")])`; 0 when never incremented.
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series
+            .counters
+            .iter()
+            .find(|((n, l), _)| *n == name && same_labels(l, labels))
+            .map_or(0, |(_, v)| *v)
+    }
+
+    /// Number of observations of a histogram; 0 when never observed.
+    pub fn observations(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series
+            .histograms
+            .iter()
+            .find(|((n, l), _)| *n == name && same_labels(l, labels))
+            .map_or(0, |(_, h)| h.count)
+    }
+
+    /// All series in the Prometheus text format (version 0.0.4).
+    pub fn render(&self) -> String {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (family, help, histogram) in FAMILIES {
+            let _ = writeln!(out, "# HELP {} {}", family, help);
+            let kind = if histogram { "histogram" } else { "counter" };
+            let _ = writeln!(out, "# TYPE {} {}", family, kind);
+            if !histogram {
+                let counters = series.counters.iter().filter(|((n, _), _)| *n == family);
+                for ((_, labels), value) in counters {
+                    let _ = writeln!(out, "{}{} {}", family, label_set(labels, None), value);
+                }
+                continue;
+            }
+            let histograms = series.histograms.iter().filter(|((n, _), _)| *n == family);
+            for ((_, labels), h) in histograms {
+                let mut cumulative = 0;
+                for (le, n) in DURATION_BUCKETS.iter().zip(h.buckets) {
+                    cumulative += n;
+                    let le = le.to_string();
+                    let labels = label_set(labels, Some(&le));
+                    let _ = writeln!(out, "{}_bucket{} {}", family, labels, cumulative);
+                }
+                let inf = label_set(labels, Some("+Inf"));
+                let _ = writeln!(out, "{}_bucket{} {}", family, inf, h.count);
+                let labels = label_set(labels, None);
+                let _ = writeln!(out, "{}_sum{} {}", family, labels, h.sum);
+                let _ = writeln!(out, "{}_count{} {}", family, labels, h.count);
+            }
+        }
+        out
+    }
+}
+
+fn same_labels(labels: &Labels, wanted: &[(&str, &str)]) -> bool {
+    labels.len() == wanted.len()
+        && labels
+            .iter()
+            .zip(wanted)
+            .all(|((k, v), (wk, wv))| k == wk && v == wv)
+}
+
+/// `{k="v",...}` with `le` last when given; empty when there are no labels.
+fn label_set(labels: &Labels, le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl MetricsRecorder for PrometheusRecorder {
+    fn node_finished(&self, node_id: &str, duration: Duration, ok: bool) {
+        let labels = vec![("node", node_id.to_string()), ("status", status(ok))];
+        self.observe(NODE_DURATION, labels, duration);
+    }
+
+    fn llm_request(&self, model: &str, duration: Duration, ok: bool) {
+        let labels = vec![("model", model.to_string()), ("status", status(ok))];
+        self.observe(LLM_DURATION, labels, duration);
+    }
+
+    fn tool_call(&self, tool: &str, duration: Duration, ok: bool) {
+        let labels = vec![("tool", tool.to_string()), ("status", status(ok))];
+        self.count(TOOL_CALLS, labels, 1);
+        self.observe(TOOL_DURATION, vec![("tool", tool.to_string())], duration);
+    }
+
+    fn checkpoint(&self, op: CheckpointOp, duration: Duration, ok: bool) {
+        let labels = vec![("op", op.as_str().to_string()), ("status", status(ok))];
+        self.observe(CHECKPOINT_DURATION, labels, duration);
+    }
+
+    fn tokens(&self, model: &str, prompt_tokens: u32, completion_tokens: u32) {
+        for (kind, n) in [("prompt", prompt_tokens), ("completion", completion_tokens)] {
+            let labels = vec![("model", model.to_string()), ("kind", kind.to_string())];
+            self.count(TOKENS, labels, u64::from(n));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Counters add up per label set; histograms render cumulative buckets,
+    /// `+Inf`, sum and count; label values are escaped.
+    #[test]
+    fn renders_counters_and_histograms() {
+        let recorder = PrometheusRecorder::new();
+        recorder.tool_call("bash", Duration::from_millis(20), true);
+        recorder.tool_call("bash", Duration::from_millis(30), false);
+        recorder.tool_call("bash", Duration::from_millis(40), false);
+        recorder.tokens("gpt-4o", 10, 5);
+        recorder.tokens("gpt-4o", 1, 2);
+        recorder.node_finished("say \"hi\"", Duration::from_millis(200), true);
+        recorder.node_finished("say \"hi\"", Duration::from_secs(500), true);
+
+        assert_eq!(
+            recorder.counter(TOOL_CALLS, &[("tool", "bash"), ("status", "error")]),
+            2
+        );
+        assert_eq!(
+            recorder.counter(TOKENS, &[("model", "gpt-4o"), ("kind", "prompt")]),
+            11
+        );
+        assert_eq!(recorder.observations(TOOL_DURATION, &[("tool", "bash")]), 3);
+
+        let text = recorder.render();
+        assert!(text.contains("# TYPE langgraph_tool_calls_total counter\n"));
+        assert!(text.contains("langgraph_tool_calls_total{tool=\"bash\",status=\"ok\"} 1\n"));
+        assert!(text.contains("langgraph_tokens_total{model=\"gpt-4o\",kind=\"completion\"} 7\n"));
+        let node = r#"node="say \"hi\"",status="ok""#;
+        assert!(text.contains(&format!(
+            "langgraph_node_duration_seconds_bucket{{{},le=\"0.1\"}} 0\n",
+            node
+        )));
+        assert!(text.contains(&format!(
+            "langgraph_node_duration_seconds_bucket{{{},le=\"0.25\"}} 1\n",
+            node
+        )));
+        assert!(text.contains(&format!(
+            "langgraph_node_duration_seconds_bucket{{{},le=\"120\"}} 1\n",
+            node
+        )));
+        assert!(text.contains(&format!(
+            "langgraph_node_duration_seconds_bucket{{{},le=\"+Inf\"}} 2\n",
+            node
+        )));
+        assert!(text.contains(&format!(
+            "langgraph_node_duration_seconds_count{{{}}} 2\n",
+            node
+        )));
+        assert!(text.contains("# TYPE langgraph_checkpoint_duration_seconds histogram\n"));
+    }
+}
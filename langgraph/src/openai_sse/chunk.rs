@@ -5,6 +5,8 @@
 
 use serde::Serialize;
 
+use super::Annotation;
+
 /// A single streamed chunk of a chat completion (object: "chat.completion.chunk").
 ///
 /// Serialized as the JSON value in SSE `data:` lines. Consumed by OpenAI-compatible
@@ -25,6 +27,13 @@ pub struct ChatCompletionChunk {
     /// Usage statistics; present only in the final chunk when include_usage was requested.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<ChunkUsage>,
+    /// Backend fingerprint reported by the upstream provider; set on the final chunk when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+    /// URLs of the sources tools returned (Perplexity-style); set on the final chunk when
+    /// there are any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citations: Option<Vec<String>>,
 }
 
 /// One choice in a streamed chunk.
@@ -57,6 +66,9 @@ pub struct Delta {
     /// Tool calls delta; when present, choice typically has finish_reason "tool_calls".
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<DeltaToolCall>>,
+    /// `url_citation`s of the sources tools returned; only in the final chunk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
 }
 
 /// One tool call in a streamed delta (OpenAI streaming tool_calls format).
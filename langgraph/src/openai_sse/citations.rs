@@ -0,0 +1,210 @@
+//! Sources cited by tool results, as OpenAI `url_citation` annotations.
+//!
+//! Search tools (Exa, web_fetcher, MCP search servers) return their sources in the tool result
+//! text: JSON with `url` / `title` fields, `Title: …` / `URL: …` lines, or bare links. The
+//! sources of the last turn are collected into [`Citation`]s and sent the way chat UIs already
+//! render OpenAI's web search: `annotations` of type `url_citation` on the assistant message
+//! (or the final delta), plus a Perplexity-style `citations` list of URLs.
+//!
+//! **Interaction**: [`StreamToSse`](super::StreamToSse) collects them from each
+//! `StreamEvent::Values` and sends them with the final chunk;
+//! [`ChatCompletion::from_state`](super::ChatCompletion::from_state) puts them on the message.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::message::Message;
+use crate::state::ReActState;
+
+/// Most sources reported for one answer.
+pub const MAX_CITATIONS: usize = 20;
+
+/// A source a tool returned in the last turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    /// The source URL.
+    pub url: String,
+    /// Its title, when the tool gave one.
+    pub title: Option<String>,
+}
+
+/// One annotation of an assistant message (OpenAI `annotations[]`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Annotation {
+    /// Always `url_citation`.
+    pub r#type: &'static str,
+    /// The cited source and where the answer cites it.
+    pub url_citation: UrlCitation,
+}
+
+/// A cited URL; `start_index..end_index` is where the answer mentions it, in characters, or
+/// an empty range at the end of the answer when it does not.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UrlCitation {
+    pub start_index: usize,
+    pub end_index: usize,
+    pub url: String,
+    /// The source's title, or its URL when the tool gave none.
+    pub title: String,
+}
+
+impl Citation {
+    /// The sources in the tool results of the last turn of `state` (the messages after the
+    /// latest user message), in order, without duplicates, at most [`MAX_CITATIONS`].
+    pub fn from_state(state: &ReActState) -> Vec<Citation> {
+        let start = state
+            .messages
+            .iter()
+            .rposition(|m| matches!(m, Message::User(_)))
+            .map_or(0, |i| i + 1);
+        let mut citations = Vec::new();
+        for message in &state.messages[start..] {
+            if let Message::Tool { content, .. } = message {
+                extract(content, &mut citations);
+            }
+        }
+        citations.truncate(MAX_CITATIONS);
+        citations
+    }
+
+    /// The `url_citation` annotation of this source for `answer`.
+    pub fn annotation(&self, answer: &str) -> Annotation {
+        let (start_index, end_index) = match answer.find(&self.url) {
+            Some(at) => {
+                let start = answer[..at].chars().count();
+                (start, start + self.url.chars().count())
+            }
+            None => {
+                let end = answer.chars().count();
+                (end, end)
+            }
+        };
+        Annotation {
+            r#type: "url_citation",
+            url_citation: UrlCitation {
+                start_index,
+                end_index,
+                url: self.url.clone(),
+                title: self.title.clone().unwrap_or_else(|| self.url.clone()),
+            },
+        }
+    }
+}
+
+/// Annotations of `citations` for `answer`; None when there are none.
+pub(crate) fn annotations(citations: &[Citation], answer: &str) -> Option<Vec<Annotation>> {
+    (!citations.is_empty()).then(|| citations.iter().map(|c| c.annotation(answer)).collect())
+}
+
+/// Adds the sources in one tool result to `out`, skipping URLs already there.
+fn extract(text: &str, out: &mut Vec<Citation>) {
+    let mut push = |url: &str, title: Option<String>| {
+        if !out.iter().any(|c| c.url == url) {
+            out.push(Citation {
+                url: url.to_string(),
+                title: title.filter(|t| !t.is_empty()),
+            });
+        }
+    };
+    if let Ok(json) = serde_json::from_str::<Value>(text.trim()) {
+        json_sources(&json, &mut push);
+        return;
+    }
+    // `Title: …` / `URL: …` lines (Exa), else any link; a title belongs to the next link.
+    let mut title = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(t) = line.strip_prefix("Title:") {
+            title = Some(t.trim().to_string());
+            continue;
+        }
+        for url in urls(line) {
+            push(url, title.take());
+        }
+    }
+}
+
+/// Objects with a string `url` (or `link`) anywhere in `json`, with their `title`.
+fn json_sources(json: &Value, push: &mut impl FnMut(&str, Option<String>)) {
+    match json {
+        Value::Object(object) => {
+            let url = object
+                .get("url")
+                .or_else(|| object.get("link"))
+                .and_then(Value::as_str)
+                .filter(|u| u.starts_with("http://") || u.starts_with("https://"));
+            if let Some(url) = url {
+                let title = object.get("title").and_then(Value::as_str);
+                push(url, title.map(|t| t.trim().to_string()));
+            }
+            object.values().for_each(|v| json_sources(v, push));
+        }
+        Value::Array(items) => items.iter().for_each(|v| json_sources(v, push)),
+        _ => {}
+    }
+}
+
+/// http(s) links in `text`, without trailing punctuation.
+fn urls(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            word.find("http://")
+                .or_else(|| word.find("https://"))
+                .map(|i| &word[i..])
+        })
+        .map(|url| url.trim_end_matches(|c: char| ".,;:!?)]}>\"'".contains(c)))
+        .filter(|url| url.len() > "https://".len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(content: &str) -> Message {
+        Message::tool(Some("c1".into()), Some("search".into()), content)
+    }
+
+    /// **Scenario**: Sources come from Exa-style lines, JSON results and bare links of the last
+    /// turn only, deduplicated; annotations point at the URL in the answer or at its end.
+    #[test]
+    fn collects_sources_of_last_turn() {
+        let state = ReActState {
+            messages: vec![
+                Message::user("Old question"),
+                tool("See https://old.example.com"),
+                Message::assistant("Old answer"),
+                Message::user("What is new in tokio?"),
+                tool(
+                    "Title: Tokio 1.40 released\nURL: https://tokio.rs/blog/1.40\nText: ...\n\n\
+                     Title: Changelog\nURL: https://github.com/tokio-rs/tokio/releases.",
+                ),
+                tool(
+                    r#"{"results": [{"title": "Docs", "url": "https://docs.rs/tokio"},
+                        {"url": "https://tokio.rs/blog/1.40"}]}"#,
+                ),
+                Message::assistant("Read https://docs.rs/tokio."),
+            ],
+            ..Default::default()
+        };
+
+        let citations = Citation::from_state(&state);
+        let urls: Vec<&str> = citations.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://tokio.rs/blog/1.40",
+                "https://github.com/tokio-rs/tokio/releases",
+                "https://docs.rs/tokio"
+            ]
+        );
+        assert_eq!(citations[0].title.as_deref(), Some("Tokio 1.40 released"));
+        assert_eq!(citations[2].title.as_deref(), Some("Docs"));
+
+        let answer = "Read https://docs.rs/tokio.";
+        let cited = citations[2].annotation(answer).url_citation;
+        assert_eq!((cited.start_index, cited.end_index), (5, 26));
+        let uncited = citations[0].annotation(answer).url_citation;
+        assert_eq!((uncited.start_index, uncited.end_index), (27, 27));
+        assert!(annotations(&[], answer).is_none());
+    }
+}
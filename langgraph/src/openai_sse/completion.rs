@@ -0,0 +1,104 @@
+//! OpenAI-compatible chat completion (non-streaming response) DTOs.
+//!
+//! Matches the [OpenAI chat completion object](https://platform.openai.com/docs/api-reference/chat/object);
+//! built from the final state of a run with [`ChatCompletion::from_state`].
+
+use serde::Serialize;
+
+use super::citations::annotations;
+use super::{final_finish_reason, Annotation, ChunkMeta, ChunkUsage, Citation};
+use crate::state::ReActState;
+
+/// A complete chat completion (object: "chat.completion"), the body of a `stream: false`
+/// response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ChatCompletion {
+    /// Unique id for this completion.
+    pub id: String,
+    /// Always "chat.completion".
+    pub object: &'static str,
+    /// Unix timestamp (seconds) when the completion was created.
+    pub created: u64,
+    /// Model name (echoed from request or server config).
+    pub model: String,
+    /// One choice (index 0).
+    pub choices: Vec<CompletionChoice>,
+    /// Tokens of all LLM calls of the run.
+    pub usage: ChunkUsage,
+    /// Backend fingerprint reported by the upstream provider, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+    /// URLs of the sources tools returned in the run's last turn (Perplexity-style).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citations: Option<Vec<String>>,
+}
+
+/// One choice of a chat completion.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CompletionChoice {
+    /// Index of the choice (0 when n=1).
+    pub index: u32,
+    /// The assistant reply.
+    pub message: CompletionMessage,
+    /// "stop", or the reason reported for the last completion (e.g. "length").
+    pub finish_reason: String,
+}
+
+/// Assistant message of a chat completion.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CompletionMessage {
+    /// Always "assistant".
+    pub role: String,
+    /// Reply text; null when the run produced none.
+    pub content: Option<String>,
+    /// `url_citation`s of the sources tools returned in the run's last turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<Annotation>>,
+}
+
+impl ChatCompletion {
+    /// Object type string of a complete (non-streamed) completion.
+    pub const OBJECT: &'static str = "chat.completion";
+
+    /// Completion for a finished run: the run's answer (`final_answer`, else the last
+    /// assistant reply), the finish reason of its last LLM call and its token usage. Tool rounds
+    /// already ran on the server, so a last `tool_calls` reason is reported as `stop`, as in the
+    /// final SSE chunk. Sources the tools of the last turn returned are attached as
+    /// annotations and citations (see [`Citation`]).
+    pub fn from_state(mut meta: ChunkMeta, state: &ReActState) -> Self {
+        let created = meta.created_secs();
+        let metadata = state.response_metadata.as_ref();
+        let content = state
+            .final_answer()
+            .map(str::to_string)
+            .or_else(|| state.last_assistant_reply());
+        let sources = Citation::from_state(state);
+        Self {
+            id: meta.id,
+            object: Self::OBJECT,
+            created,
+            model: meta.model,
+            choices: vec![CompletionChoice {
+                index: 0,
+                message: CompletionMessage {
+                    role: "assistant".to_string(),
+                    annotations: annotations(&sources, content.as_deref().unwrap_or_default()),
+                    content,
+                },
+                finish_reason: final_finish_reason(
+                    metadata.and_then(|m| m.finish_reason.as_deref()),
+                ),
+            }],
+            usage: ChunkUsage {
+                prompt_tokens: state.usage.prompt_tokens,
+                completion_tokens: state.usage.completion_tokens,
+                total_tokens: state.usage.total_tokens,
+            },
+            system_fingerprint: metadata.and_then(|m| m.system_fingerprint.clone()),
+            citations: (!sources.is_empty()).then(|| sources.into_iter().map(|c| c.url).collect()),
+        }
+    }
+}
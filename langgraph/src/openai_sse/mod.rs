@@ -4,10 +4,17 @@
 //! in the format of [OpenAI streaming](https://platform.openai.com/docs/api-reference/chat-streaming).
 //! No HTTP dependency: callers feed events and consume SSE lines (or bytes).
 //!
+//! Streaming is the hot path: one chunk per token. [`StreamToSse`] serializes into one reused
+//! buffer, and content deltas (the common chunk) copy a pre-serialized
+//! `data: {"id":…,"choices":[{"index":0,"delta":` prefix and only escape the token text. With
+//! [`new_with_bytes_sink`](StreamToSse::new_with_bytes_sink), each line goes out as [`Bytes`]
+//! split off that buffer, with no `String` per token.
+//!
 //! # Types
 //!
 //! - **[`ChatCompletionRequest`]**: Request body DTO (messages, model, stream, stream_options, thread_id).
 //! - **[`ChatCompletionChunk`]**: Response chunk DTO (id, object, created, model, choices, usage).
+//! - **[`ChatCompletion`]**: Non-streaming response DTO, built from a run's final state.
 //! - **[`StreamToSse`]**: Stateful adapter that turns `StreamEvent<ReActState>` into SSE lines.
 //! - **[`parse_chat_request`]**: Parses request into `user_message`, `system_prompt`, `RunnableConfig`.
 //!
@@ -21,17 +28,24 @@
 //! ```
 
 mod chunk;
+mod citations;
+mod completion;
 mod parse;
 mod request;
 
 pub use chunk::{
     ChatCompletionChunk, ChunkChoice, ChunkUsage, Delta, DeltaToolCall, DeltaToolCallFunction,
 };
+pub use citations::{Annotation, Citation, UrlCitation, MAX_CITATIONS};
+pub use completion::{ChatCompletion, CompletionChoice, CompletionMessage};
 pub use parse::{parse_chat_request, ParseError, ParsedChatRequest};
-pub use request::{ChatCompletionRequest, ChatMessage, MessageContent, StreamOptions};
+pub use request::{
+    ChatCompletionRequest, ChatMessage, MessageContent, RequestMetadata, StreamOptions,
+};
 
 use crate::state::ReActState;
 use crate::stream::StreamEvent;
+use bytes::{BufMut, Bytes, BytesMut};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use chunk::ChatCompletionChunk as Chunk;
@@ -68,17 +82,46 @@ impl ChunkMeta {
 /// Feed events via [`feed`](StreamToSse::feed); then call [`finish`](StreamToSse::finish) and
 /// [`take_lines`](StreamToSse::take_lines) to get `data: <JSON>\n\n` strings. When constructed
 /// with [`new_with_sink`](StreamToSse::new_with_sink), each line is also sent to the channel
-/// as it is produced (for HTTP streaming). Holds optional pending usage for the final chunk.
+/// as it is produced (for HTTP streaming);
+/// [`new_with_bytes_sink`](StreamToSse::new_with_bytes_sink) sends [`Bytes`] instead and keeps
+/// nothing. Holds optional pending usage, finish_reason and
+/// system_fingerprint for the final chunk.
 pub struct StreamToSse {
     meta: ChunkMeta,
     include_usage: bool,
+    /// Usage summed over every LLM call of the run (one `StreamEvent::Usage` per call).
     usage: Option<ChunkUsage>,
+    /// finish_reason of the last completion (from `StreamEvent::ResponseMetadata`).
+    finish_reason: Option<String>,
+    /// system_fingerprint of the last completion that reported one.
+    system_fingerprint: Option<String>,
     lines: Vec<String>,
     sent_initial: bool,
+    /// True when the current think turn streamed tool-call deltas via `Messages`.
+    streamed_tool_calls: bool,
     /// When set, each produced line is also sent here (e.g. for SSE response body).
-    sink: Option<mpsc::Sender<String>>,
+    sink: Option<Sink>,
+    /// Lines are serialized here and split off; the allocation is reused once they are sent.
+    buf: BytesMut,
+    /// `data: {"id":…,"object":…,"created":…,"model":…,"choices":[{"index":0,"delta":`,
+    /// serialized on the first content delta.
+    content_prefix: Vec<u8>,
+    /// Sources of the last turn's tool results, from the latest `StreamEvent::Values`.
+    citations: Vec<Citation>,
+    /// Answer of the latest `StreamEvent::Values`, for the citations' positions.
+    answer: String,
+}
+
+/// Where produced lines go besides `lines`.
+enum Sink {
+    Lines(mpsc::Sender<String>),
+    /// Lines are sent as bytes and not kept.
+    Bytes(mpsc::Sender<Bytes>),
 }
 
+/// Initial capacity of the line buffer; grows to the largest chunk when needed.
+const LINE_BUF_CAPACITY: usize = 512;
+
 impl StreamToSse {
     /// Builds a new adapter with the given chunk metadata and options.
     pub fn new(meta: ChunkMeta, include_usage: bool) -> Self {
@@ -86,9 +129,16 @@ impl StreamToSse {
             meta,
             include_usage,
             usage: None,
+            finish_reason: None,
+            system_fingerprint: None,
             lines: Vec::new(),
             sent_initial: false,
+            streamed_tool_calls: false,
             sink: None,
+            buf: BytesMut::with_capacity(LINE_BUF_CAPACITY),
+            content_prefix: Vec::new(),
+            citations: Vec::new(),
+            answer: String::new(),
         }
     }
 
@@ -100,17 +150,57 @@ impl StreamToSse {
         sink: mpsc::Sender<String>,
     ) -> Self {
         Self {
-            meta,
-            include_usage,
-            usage: None,
-            lines: Vec::new(),
-            sent_initial: false,
-            sink: Some(sink),
+            sink: Some(Sink::Lines(sink)),
+            ..Self::new(meta, include_usage)
         }
     }
 
-    fn push_line(&mut self, line: String) {
-        if let Some(ref tx) = self.sink {
+    /// Builds a new adapter that sends each SSE line to `sink` as [`Bytes`] and keeps none
+    /// ([`take_lines`](StreamToSse::take_lines) stays empty). The cheapest way to stream: no
+    /// per-line `String`, and the line buffer is reused once the receiver drops each line.
+    pub fn new_with_bytes_sink(
+        meta: ChunkMeta,
+        include_usage: bool,
+        sink: mpsc::Sender<Bytes>,
+    ) -> Self {
+        Self {
+            sink: Some(Sink::Bytes(sink)),
+            ..Self::new(meta, include_usage)
+        }
+    }
+
+    /// Serializes `chunk` as one SSE line and emits it.
+    fn push_chunk(&mut self, chunk: &Chunk) {
+        self.buf.extend_from_slice(b"data: ");
+        serde_json::to_writer((&mut self.buf).writer(), chunk)
+            .expect("chunk serialization is infallible");
+        self.buf.extend_from_slice(b"\n\n");
+        self.emit();
+    }
+
+    /// Emits a content delta: the same line [`push_chunk`](Self::push_chunk) would for a chunk
+    /// with only `delta.content` set, written from the pre-serialized prefix.
+    fn push_content(&mut self, created: u64, content: &str) {
+        if self.content_prefix.is_empty() {
+            self.content_prefix = content_prefix(&self.meta, created);
+        }
+        self.buf.extend_from_slice(&self.content_prefix);
+        self.buf.extend_from_slice(br#"{"content":"#);
+        serde_json::to_writer((&mut self.buf).writer(), content)
+            .expect("string serialization is infallible");
+        self.buf.extend_from_slice(b"}}]}\n\n");
+        self.emit();
+    }
+
+    /// Splits the line written to `buf` off and hands it to the sink and/or `lines`.
+    fn emit(&mut self) {
+        let line = self.buf.split();
+        if let Some(Sink::Bytes(tx)) = &self.sink {
+            let _ = tx.try_send(line.freeze());
+            return;
+        }
+        let line = String::from_utf8(Vec::from(line)).expect("serialized JSON is UTF-8");
+        if let Some(Sink::Lines(tx)) = &self.sink {
             let _ = tx.try_send(line.clone());
         }
         self.lines.push(line);
@@ -121,48 +211,97 @@ impl StreamToSse {
     /// Call [`take_lines`](StreamToSse::take_lines) after the stream ends to retrieve them.
     pub fn feed(&mut self, event: StreamEvent<ReActState>) {
         let created = self.meta.created_secs();
-        let id = self.meta.id.clone();
-        let model = self.meta.model.clone();
 
         match event {
             StreamEvent::TaskStart { node_id } if node_id == "think" && !self.sent_initial => {
                 self.sent_initial = true;
                 let chunk = Chunk {
-                    id: id.clone(),
+                    id: self.meta.id.clone(),
                     object: Chunk::OBJECT,
                     created,
-                    model: model.clone(),
+                    model: self.meta.model.clone(),
                     choices: vec![ChunkChoice {
                         index: 0,
                         delta: Delta {
                             role: Some("assistant".to_string()),
                             content: Some(String::new()),
                             tool_calls: None,
+                            annotations: None,
                         },
                         finish_reason: None,
                     }],
                     usage: None,
+                    system_fingerprint: None,
+                    citations: None,
                 };
-                self.push_line(write_sse_line(&chunk));
+                self.push_chunk(&chunk);
+            }
+            StreamEvent::TaskStart { node_id } if node_id == "think" => {
+                // New think turn: tool calls (if any) have not been streamed yet.
+                self.streamed_tool_calls = false;
+            }
+            StreamEvent::Messages { chunk, .. } if chunk.tool_call_chunks.is_empty() => {
+                self.push_content(created, &chunk.content);
             }
             StreamEvent::Messages { chunk, .. } => {
+                self.streamed_tool_calls = true;
+                let tool_calls = chunk
+                    .tool_call_chunks
+                    .into_iter()
+                    .map(|tc| DeltaToolCall {
+                        index: tc.index,
+                        r#type: tc.id.as_ref().map(|_| "function".to_string()),
+                        id: tc.id,
+                        function: Some(DeltaToolCallFunction {
+                            name: tc.name,
+                            arguments: tc.arguments,
+                        }),
+                    })
+                    .collect();
+                let content = Some(chunk.content).filter(|c| !c.is_empty());
                 let chunk = Chunk {
-                    id: id.clone(),
+                    id: self.meta.id.clone(),
                     object: Chunk::OBJECT,
                     created,
-                    model: model.clone(),
+                    model: self.meta.model.clone(),
                     choices: vec![ChunkChoice {
                         index: 0,
                         delta: Delta {
                             role: None,
-                            content: Some(chunk.content),
-                            tool_calls: None,
+                            content,
+                            tool_calls: Some(tool_calls),
+                            annotations: None,
                         },
                         finish_reason: None,
                     }],
                     usage: None,
+                    system_fingerprint: None,
+                    citations: None,
                 };
-                self.push_line(write_sse_line(&chunk));
+                self.push_chunk(&chunk);
+            }
+            StreamEvent::Updates { node_id, state }
+                if !state.tool_calls.is_empty() && self.streamed_tool_calls =>
+            {
+                // Tool calls already went out as deltas; only close the think turn.
+                if node_id != "think" {
+                    return;
+                }
+                let chunk = Chunk {
+                    id: self.meta.id.clone(),
+                    object: Chunk::OBJECT,
+                    created,
+                    model: self.meta.model.clone(),
+                    choices: vec![ChunkChoice {
+                        index: 0,
+                        delta: Delta::default(),
+                        finish_reason: Some("tool_calls".to_string()),
+                    }],
+                    usage: None,
+                    system_fingerprint: None,
+                    citations: None,
+                };
+                self.push_chunk(&chunk);
             }
             StreamEvent::Updates { state, .. } if !state.tool_calls.is_empty() => {
                 let tool_calls: Vec<DeltaToolCall> = state
@@ -180,45 +319,75 @@ impl StreamToSse {
                     })
                     .collect();
                 let chunk = Chunk {
-                    id: id.clone(),
+                    id: self.meta.id.clone(),
                     object: Chunk::OBJECT,
                     created,
-                    model: model.clone(),
+                    model: self.meta.model.clone(),
                     choices: vec![ChunkChoice {
                         index: 0,
                         delta: Delta {
                             role: None,
                             content: None,
                             tool_calls: Some(tool_calls),
+                            annotations: None,
                         },
                         finish_reason: Some("tool_calls".to_string()),
                     }],
                     usage: None,
+                    system_fingerprint: None,
+                    citations: None,
                 };
-                self.push_line(write_sse_line(&chunk));
+                self.push_chunk(&chunk);
             }
             StreamEvent::Usage {
                 prompt_tokens,
                 completion_tokens,
                 total_tokens,
             } => {
-                self.usage = Some(ChunkUsage {
-                    prompt_tokens,
-                    completion_tokens,
-                    total_tokens,
+                let usage = self.usage.get_or_insert(ChunkUsage {
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    total_tokens: 0,
                 });
+                usage.prompt_tokens = usage.prompt_tokens.saturating_add(prompt_tokens);
+                usage.completion_tokens = usage.completion_tokens.saturating_add(completion_tokens);
+                usage.total_tokens = usage.total_tokens.saturating_add(total_tokens);
             }
-            StreamEvent::Values(_) => {
+            StreamEvent::ResponseMetadata(metadata) => {
+                self.finish_reason = metadata.finish_reason;
+                if metadata.system_fingerprint.is_some() {
+                    self.system_fingerprint = metadata.system_fingerprint;
+                }
+            }
+            StreamEvent::Values(state) => {
                 // Do not emit here: we emit the final chunk only in finish() after stream ends.
+                self.citations = Citation::from_state(&state);
+                self.answer = state
+                    .final_answer()
+                    .map(str::to_string)
+                    .or_else(|| state.last_assistant_reply())
+                    .unwrap_or_default();
             }
             _ => {}
         }
     }
 
-    /// Emits the final chunk (delta: {}, finish_reason: "stop", optional usage).
+    /// Emits the final chunk (delta: {}, finish_reason, optional usage and system_fingerprint).
     /// Call this once after the stream has ended (e.g. after the last event was fed).
+    ///
+    /// `usage` is the total of all LLM calls in the run, as reported by the provider; it is
+    /// omitted when `include_usage` was not requested or no call reported usage.
+    ///
+    /// `finish_reason` is the one reported for the last completion (e.g. `length` or
+    /// `content_filter`), or `stop` when none was reported. `tool_calls` is reported as
+    /// `stop` because tool rounds were already closed and the run has ended.
+    ///
+    /// Sources the tools of the last turn returned (see [`Citation`]) go out with this chunk:
+    /// as `url_citation`s in `delta.annotations` and as URLs in `citations`.
     pub fn finish(&mut self) {
         let created = self.meta.created_secs();
+        let finish_reason = final_finish_reason(self.finish_reason.as_deref());
+        let sources = std::mem::take(&mut self.citations);
         let chunk = Chunk {
             id: self.meta.id.clone(),
             object: Chunk::OBJECT,
@@ -226,16 +395,21 @@ impl StreamToSse {
             model: self.meta.model.clone(),
             choices: vec![ChunkChoice {
                 index: 0,
-                delta: Delta::default(),
-                finish_reason: Some("stop".to_string()),
+                delta: Delta {
+                    annotations: citations::annotations(&sources, &self.answer),
+                    ..Delta::default()
+                },
+                finish_reason: Some(finish_reason),
             }],
             usage: if self.include_usage {
                 self.usage.clone()
             } else {
                 None
             },
+            system_fingerprint: self.system_fingerprint.clone(),
+            citations: (!sources.is_empty()).then(|| sources.into_iter().map(|c| c.url).collect()),
         };
-        self.push_line(write_sse_line(&chunk));
+        self.push_chunk(&chunk);
     }
 
     /// Returns and clears the collected SSE lines.
@@ -244,6 +418,30 @@ impl StreamToSse {
     }
 }
 
+/// `data: ` and a content chunk's JSON up to its `delta` value, as serde writes it.
+fn content_prefix(meta: &ChunkMeta, created: u64) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(96 + meta.id.len() + meta.model.len());
+    prefix.extend_from_slice(br#"data: {"id":"#);
+    serde_json::to_writer(&mut prefix, &meta.id).expect("string serialization is infallible");
+    prefix.extend_from_slice(br#","object":""#);
+    prefix.extend_from_slice(Chunk::OBJECT.as_bytes());
+    prefix.extend_from_slice(br#"","created":"#);
+    prefix.extend_from_slice(created.to_string().as_bytes());
+    prefix.extend_from_slice(br#","model":"#);
+    serde_json::to_writer(&mut prefix, &meta.model).expect("string serialization is infallible");
+    prefix.extend_from_slice(br#","choices":[{"index":0,"delta":"#);
+    prefix
+}
+
+/// finish_reason reported when a run ends: the last completion's, or `stop` when none was
+/// reported or it was a tool round.
+fn final_finish_reason(reason: Option<&str>) -> String {
+    match reason {
+        None | Some("tool_calls") | Some("function_call") => "stop".to_string(),
+        Some(reason) => reason.to_string(),
+    }
+}
+
 /// Serializes a [`ChatCompletionChunk`] to a single SSE line: `data: <JSON>\n\n`.
 ///
 /// Used by [`StreamToSse`] and by HTTP handlers that write the response body.
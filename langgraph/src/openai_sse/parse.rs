@@ -32,7 +32,8 @@ pub enum ParseError {
 ///
 /// - **user_message**: Last message with `role == "user"`; its `content` (or empty string if null).
 /// - **system_prompt**: First message with `role == "system"` content, or [`REACT_SYSTEM_PROMPT`].
-/// - **runnable_config**: `thread_id` from request if present; otherwise default.
+/// - **runnable_config**: `thread_id` from the request, else `metadata.thread_id`; `user_id`
+///   from `user`, else `metadata.user_id`. Blank values count as unset.
 /// - **include_usage**: From `stream_options.include_usage` (default false).
 ///
 /// # Errors
@@ -59,11 +60,15 @@ pub fn parse_chat_request(req: &ChatCompletionRequest) -> Result<ParsedChatReque
         .and_then(|m| m.content.as_ref().map(|c| c.as_text()))
         .unwrap_or_else(|| REACT_SYSTEM_PROMPT.to_string());
 
+    let metadata = req.metadata.as_ref();
     let runnable_config = RunnableConfig {
-        thread_id: req.thread_id.clone(),
+        thread_id: non_blank(req.thread_id.as_deref())
+            .or_else(|| non_blank(metadata.and_then(|m| m.thread_id.as_deref()))),
         checkpoint_id: None,
         checkpoint_ns: String::new(),
-        user_id: None,
+        user_id: non_blank(req.user.as_deref())
+            .or_else(|| non_blank(metadata.and_then(|m| m.user_id.as_deref()))),
+        ..Default::default()
     };
 
     let include_usage = req
@@ -79,3 +84,11 @@ pub fn parse_chat_request(req: &ChatCompletionRequest) -> Result<ParsedChatReque
         include_usage,
     })
 }
+
+/// Trimmed `value`, or None when missing or blank.
+fn non_blank(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
@@ -27,6 +27,24 @@ pub struct ChatCompletionRequest {
     /// Optional thread id for checkpointing multi-turn conversations (extension).
     #[serde(default)]
     pub thread_id: Option<String>,
+    /// End-user identifier (OpenAI `user`); used as `user_id` for long-term memory.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Request metadata; `thread_id` / `user_id` keys are used when the fields above are unset.
+    #[serde(default)]
+    pub metadata: Option<RequestMetadata>,
+}
+
+/// Routing keys read from the request `metadata` object; other keys are ignored.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct RequestMetadata {
+    /// Thread to checkpoint the conversation under.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// User owning the conversation.
+    #[serde(default)]
+    pub user_id: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -0,0 +1,148 @@
+//! OpenTelemetry export of graph runs (feature `otel`).
+//!
+//! The graph already runs inside tracing spans: `run` per run, `node` per node, and below them
+//! `llm` per ChatOpenAI request and `tool` per tool call (see `graph::logging`).
+//! [`otlp_layer`] turns those spans into OpenTelemetry spans sent to an OTLP collector, so each
+//! run becomes a trace with the span fields (`node_id`, `model`, `tool`, token counts) as
+//! attributes. Runs started from a [`RunContext`](crate::graph::RunContext) with a parent span
+//! join the parent's trace; [`set_parent_from_traceparent`] continues a trace begun in another
+//! process (W3C `traceparent` header).
+//!
+//! **Interaction**: langgraph-server adds the layer to its subscriber when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set and parents each request span on the request's
+//! `traceparent`.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Service name reported when `OTEL_SERVICE_NAME` is not set.
+pub const DEFAULT_SERVICE_NAME: &str = "langgraph";
+
+/// Where and as which service spans are exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtelConfig {
+    /// OTLP/gRPC endpoint of the collector, e.g. `http://localhost:4317`.
+    pub endpoint: String,
+    /// `service.name` resource attribute.
+    pub service_name: String,
+}
+
+impl OtelConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+        }
+    }
+
+    pub fn with_service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    /// From the standard `OTEL_EXPORTER_OTLP_ENDPOINT` and `OTEL_SERVICE_NAME`; None when no
+    /// endpoint is set (export off).
+    pub fn from_env() -> Option<Self> {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .ok()
+            .filter(|s| !s.trim().is_empty())?;
+        let config = Self::new(endpoint);
+        Some(match std::env::var("OTEL_SERVICE_NAME") {
+            Ok(name) if !name.trim().is_empty() => config.with_service_name(name),
+            _ => config,
+        })
+    }
+}
+
+/// Error setting up the OTLP export.
+#[derive(Debug, thiserror::Error)]
+pub enum OtelError {
+    #[error("OTLP exporter: {0}")]
+    Exporter(String),
+}
+
+/// Tracing layer exporting spans to the collector in `config`, batched on the tokio runtime.
+///
+/// Also installs the tracer provider and the W3C trace context propagator globally; call
+/// [`shutdown`] before exiting to flush spans still buffered.
+pub fn otlp_layer<S>(config: &OtelConfig) -> Result<OpenTelemetryLayer<S, Tracer>, OtelError>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(config.endpoint.clone())
+        .build()
+        .map_err(|e| OtelError::Exporter(e.to_string()))?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]))
+        .build();
+    let tracer = provider.tracer("langgraph");
+    opentelemetry::global::set_tracer_provider(provider);
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Makes `span` a child of the remote span in a W3C `traceparent` header value
+/// (`00-<trace id>-<span id>-<flags>`). An invalid value leaves `span` a root.
+pub fn set_parent_from_traceparent(span: &tracing::Span, traceparent: &str) {
+    let carrier = HashMap::from([("traceparent".to_string(), traceparent.to_string())]);
+    span.set_parent(TraceContextPropagator::new().extract(&carrier));
+}
+
+/// Flushes buffered spans and stops the exporter installed by [`otlp_layer`].
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// **Scenario**: A span parented on a `traceparent` belongs to that trace; a malformed
+    /// value starts a new trace.
+    #[test]
+    fn traceparent_sets_remote_parent() {
+        let tracer = TracerProvider::builder().build().tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            set_parent_from_traceparent(
+                &span,
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            );
+            let trace_id = span.context().span().span_context().trace_id();
+            assert_eq!(trace_id.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+
+            let span = tracing::info_span!("request");
+            set_parent_from_traceparent(&span, "not-a-traceparent");
+            let trace_id = span.context().span().span_context().trace_id();
+            assert_ne!(trace_id.to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        });
+    }
+
+    /// **Scenario**: The service name defaults to `langgraph` and can be overridden.
+    #[test]
+    fn config_defaults() {
+        let config = OtelConfig::new("http://localhost:4317");
+        assert_eq!(config.service_name, DEFAULT_SERVICE_NAME);
+        let config = config.with_service_name("agent");
+        assert_eq!(config.service_name, "agent");
+    }
+}
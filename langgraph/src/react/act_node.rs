@@ -4,6 +4,12 @@
 //! ActNode holds a ToolSource (e.g. `Box<dyn ToolSource>`), implements `Node<ReActState>`;
 //! run reads state.tool_calls, calls call_tool(name, args) for each, writes state.tool_results.
 //!
+//! # Parallel Tool Calls
+//!
+//! Tool calls of one step run concurrently, at most `with_max_parallel_tool_calls` at a time
+//! (default [`DEFAULT_MAX_PARALLEL_TOOL_CALLS`]); `tool_results` keep the order of `tool_calls`.
+//! Set the limit to 1 for tools that must run one after another.
+//!
 //! # Error Handling
 //!
 //! By default, tool errors propagate and short-circuit the graph. Use `with_handle_tool_errors`
@@ -11,25 +17,65 @@
 //!
 //! - `HandleToolErrors::Never` - Errors propagate (default)
 //! - `HandleToolErrors::Always` - Errors are caught and returned as error messages
+//! - `HandleToolErrors::PerTool` - Like `Always`, with a message template per tool
 //! - `HandleToolErrors::Custom(handler)` - Custom error handler function
 //!
+//! Templates are filled by [`render_error_template`] with `{error}`, `{tool_name}`,
+//! `{tool_kwargs}`, `{call_id}` and `{attempt}` (1 + earlier results of the same tool in the
+//! conversation); `{{` and `}}` are literal braces.
+//!
+//! # Argument Validation
+//!
+//! Before a tool runs, its arguments are parsed and checked against the tool's
+//! `ToolSpec::input_schema` (JSON Schema). Malformed JSON or arguments the schema rejects fail
+//! the call with `ToolSourceError::InvalidInput` naming each problem (e.g.
+//! `/city: 5 is not of type "string"`), which goes through `HandleToolErrors` like any tool
+//! error, so the model can fix its call. Schemas are listed once and cached; tools missing
+//! from the list are called unchecked. Disable with `with_argument_validation(false)`.
+//!
 //! # Streaming Support
 //!
 //! `ActNode` supports custom streaming through `run_with_context`. When called with
 //! a `RunContext` that has `StreamMode::Custom` enabled, it creates a `ToolStreamWriter`
 //! and passes it to tools via `ToolCallContext`. Tools can then emit progress updates
 //! or intermediate results during execution.
+//!
+//! # Tool State
+//!
+//! Each call of a run with a thread_id receives a [`ToolState`](crate::tool_source::ToolState)
+//! in `ToolCallContext::tool_state`, scoped to the run's user_id, thread_id and the tool name,
+//! so tools can keep state across turns. The state lives in an in-memory key-value store by
+//! default; `with_tool_state_store` sets a durable one. It never goes to the run's long-term
+//! memory store, which may embed every put.
+//!
+//! # UI Events
+//!
+//! Each call gets a [`UiEventSink`] in `ToolCallContext::ui_events`; events a tool emits are
+//! appended to `state.ui_events` in call order once the step's calls finished, also when the
+//! tool failed and the error was handled.
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use jsonschema::Validator;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{debug, trace, warn};
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tracing::{debug, trace, warn, Instrument};
 
+use crate::callbacks::CallbackHandler;
 use crate::error::AgentError;
 use crate::graph::{Next, Node, RunContext};
-use crate::state::{ReActState, ToolResult};
+use crate::message::Message;
+use crate::metrics;
+use crate::state::{ReActState, ToolCall, ToolResult};
 use crate::stream::{StreamEvent, StreamMode, ToolStreamWriter};
-use crate::tool_source::{ToolCallContext, ToolSource, ToolSourceError};
+use crate::tool_source::{
+    ToolCallContext, ToolSource, ToolSourceError, ToolSpec, ToolState, ToolStateStore, UiEventSink,
+};
+
+use super::render_error_template;
 
 /// Truncates a string for logging, appending "..." if longer than max_len.
 /// Used for tool result preview in tracing to avoid huge log lines.
@@ -41,10 +87,14 @@ fn truncate_for_log(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Tool calls run at the same time when `with_max_parallel_tool_calls` is not called.
+pub const DEFAULT_MAX_PARALLEL_TOOL_CALLS: usize = 8;
+
 /// Default error message template for tool errors.
 pub const DEFAULT_TOOL_ERROR_TEMPLATE: &str = "Error: {error}\n Please fix your mistakes.";
 
-/// Default execution error message template with tool name and kwargs.
+/// Default execution error message template with tool name and kwargs; see
+/// [`render_error_template`] for the placeholders.
 pub const DEFAULT_EXECUTION_ERROR_TEMPLATE: &str =
     "Error executing tool '{tool_name}' with kwargs {tool_kwargs} with error:\n {error}\n Please fix the error and try again.";
 
@@ -62,8 +112,14 @@ pub enum HandleToolErrors {
     /// Errors propagate and short-circuit the graph (default behavior).
     Never,
     /// Errors are caught and returned as ToolResult with error message.
-    /// Uses the default error template if None.
+    /// The message is a template; uses [`DEFAULT_EXECUTION_ERROR_TEMPLATE`] if None.
     Always(Option<String>),
+    /// Errors are caught like `Always`, with the template registered for the failing tool in
+    /// `templates`, else `default` ([`DEFAULT_EXECUTION_ERROR_TEMPLATE`] if None).
+    PerTool {
+        templates: HashMap<String, String>,
+        default: Option<String>,
+    },
     /// Custom error handler function.
     Custom(ErrorHandlerFn),
 }
@@ -79,6 +135,11 @@ impl std::fmt::Debug for HandleToolErrors {
         match self {
             Self::Never => write!(f, "HandleToolErrors::Never"),
             Self::Always(msg) => write!(f, "HandleToolErrors::Always({:?})", msg),
+            Self::PerTool { templates, default } => write!(
+                f,
+                "HandleToolErrors::PerTool {{ templates: {:?}, default: {:?} }}",
+                templates, default
+            ),
             Self::Custom(_) => write!(f, "HandleToolErrors::Custom(<fn>)"),
         }
     }
@@ -87,10 +148,13 @@ impl std::fmt::Debug for HandleToolErrors {
 /// Act node: one ReAct step that executes tool_calls and produces tool_results.
 ///
 /// Reads `state.tool_calls`, calls `ToolSource::call_tool(name, arguments)` for each
-/// (parsing arguments from JSON string); appends one ToolResult per call.
+/// (parsing arguments from JSON string and checking them against the tool's input schema);
+/// appends one ToolResult per call.
 ///
 /// # Error Handling
 ///
+/// Calls run concurrently up to `with_max_parallel_tool_calls`; results are in call order.
+///
 /// By default (HandleToolErrors::Never), a single call failure returns Err and
 /// short-circuits the graph. Use `with_handle_tool_errors` to configure error handling:
 ///
@@ -107,16 +171,41 @@ pub struct ActNode {
     tools: Box<dyn ToolSource>,
     /// Error handling configuration.
     handle_tool_errors: HandleToolErrors,
+    /// Per-thread tool state, separate from the run's long-term memory store.
+    tool_state_store: ToolStateStore,
+    /// Tool calls of one step in flight at the same time.
+    max_parallel_tool_calls: usize,
+    /// When true, arguments are checked against the tool's input schema before the call.
+    validate_arguments: bool,
+    /// Compiled input schemas by tool name; None for tools without a usable schema.
+    schemas: Mutex<HashMap<String, Option<Arc<Validator>>>>,
+}
+
+/// Compiles the input schema of `spec`; None (logged) when it is not a valid JSON Schema.
+fn compile_schema(spec: &ToolSpec) -> Option<Arc<Validator>> {
+    match jsonschema::validator_for(&spec.input_schema) {
+        Ok(validator) => Some(Arc::new(validator)),
+        Err(e) => {
+            warn!(tool = %spec.name, error = %e, "Tool input schema is invalid; not validating");
+            None
+        }
+    }
 }
 
 impl ActNode {
     /// Creates an Act node with the given tool source.
     ///
-    /// By default, tool errors propagate (HandleToolErrors::Never).
+    /// By default, tool errors propagate (HandleToolErrors::Never) and tool state lives in
+    /// memory: it is lost on restart even when the graph has a persistent checkpointer. Set a
+    /// durable store with [`with_tool_state_store`](Self::with_tool_state_store).
     pub fn new(tools: Box<dyn ToolSource>) -> Self {
         Self {
             tools,
             handle_tool_errors: HandleToolErrors::Never,
+            tool_state_store: ToolStateStore::in_memory(),
+            max_parallel_tool_calls: DEFAULT_MAX_PARALLEL_TOOL_CALLS,
+            validate_arguments: true,
+            schemas: Mutex::new(HashMap::new()),
         }
     }
 
@@ -127,7 +216,8 @@ impl ActNode {
     /// * `handle_tool_errors` - How to handle tool errors:
     ///   - `Never` - Errors propagate (default)
     ///   - `Always(None)` - Catch errors with default message
-    ///   - `Always(Some(msg))` - Catch errors with custom message
+    ///   - `Always(Some(msg))` - Catch errors with custom message (a template)
+    ///   - `PerTool { templates, default }` - Catch errors with a template per tool name
     ///   - `Custom(handler)` - Use custom error handler
     ///
     /// # Example
@@ -141,6 +231,16 @@ impl ActNode {
     /// let act = ActNode::new(tools)
     ///     .with_handle_tool_errors(HandleToolErrors::Always(Some("Custom error".into())));
     ///
+    /// // Own message for one tool, the default template for the others
+    /// let act = ActNode::new(tools).with_handle_tool_errors(HandleToolErrors::PerTool {
+    ///     templates: [(
+    ///         "run_sql".to_string(),
+    ///         "SQL failed (attempt {attempt}): {error}".to_string(),
+    ///     )]
+    ///     .into(),
+    ///     default: None,
+    /// });
+    ///
     /// // Custom error handler
     /// let act = ActNode::new(tools)
     ///     .with_handle_tool_errors(HandleToolErrors::Custom(Arc::new(|e, name, _args| {
@@ -152,29 +252,235 @@ impl ActNode {
         self
     }
 
+    /// Sets the store for per-thread tool state passed to tools as `ToolCallContext::tool_state`.
+    /// The default is [`ToolStateStore::in_memory`], which does not survive a restart; back it
+    /// with a durable key-value store such as `SqliteStore` (not a vector store) when threads
+    /// outlive the process.
+    ///
+    /// Its default thread id is used by `run` (no `RunContext`); `run_with_context` uses the
+    /// run config's thread_id when present.
+    pub fn with_tool_state_store(mut self, store: ToolStateStore) -> Self {
+        self.tool_state_store = store;
+        self
+    }
+
+    /// Sets how many tool calls of one step run at the same time (default
+    /// [`DEFAULT_MAX_PARALLEL_TOOL_CALLS`]); 1 runs them one after another. 0 is treated as 1.
+    pub fn with_max_parallel_tool_calls(mut self, max: usize) -> Self {
+        self.max_parallel_tool_calls = max.max(1);
+        self
+    }
+
+    /// Sets whether arguments are checked against the tool's input schema before the call
+    /// (default true). When false, malformed JSON is still rejected.
+    pub fn with_argument_validation(mut self, enabled: bool) -> Self {
+        self.validate_arguments = enabled;
+        self
+    }
+
+    /// Validator for `tool_name`'s input schema. Tools not seen yet are looked up by listing
+    /// the tools once more; None when validation is off or the tool has no usable schema.
+    async fn validator(&self, tool_name: &str) -> Option<Arc<Validator>> {
+        if !self.validate_arguments {
+            return None;
+        }
+        let mut schemas = self.schemas.lock().await;
+        if !schemas.contains_key(tool_name) {
+            match self.tools.list_tools().await {
+                Ok(specs) => {
+                    for spec in &specs {
+                        schemas.insert(spec.name.clone(), compile_schema(spec));
+                    }
+                }
+                Err(e) => {
+                    debug!(tool = %tool_name, error = %e, "Listing tools failed; not validating");
+                    return None;
+                }
+            }
+            schemas.entry(tool_name.to_string()).or_insert(None);
+        }
+        schemas.get(tool_name).cloned().flatten()
+    }
+
+    /// Parses the call's JSON arguments and checks them against the tool's input schema. On
+    /// failure, returns the `InvalidInput` error with the arguments as far as they could be
+    /// read (the raw string when they are not JSON).
+    async fn checked_arguments(&self, tc: &ToolCall) -> Result<Value, (ToolSourceError, Value)> {
+        let args: Value = if tc.arguments.trim().is_empty() {
+            serde_json::json!({})
+        } else {
+            serde_json::from_str(&tc.arguments).map_err(|e| {
+                let error =
+                    ToolSourceError::InvalidInput(format!("arguments are not valid JSON: {}", e));
+                (error, Value::String(tc.arguments.clone()))
+            })?
+        };
+        let Some(validator) = self.validator(&tc.name).await else {
+            return Ok(args);
+        };
+        let problems: Vec<String> = validator
+            .iter_errors(&args)
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                if path.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{}: {}", path, e)
+                }
+            })
+            .collect();
+        if problems.is_empty() {
+            Ok(args)
+        } else {
+            Err((ToolSourceError::InvalidInput(problems.join("; ")), args))
+        }
+    }
+
+    /// Tool state for `tool_name` of the run's user and thread.
+    fn tool_state(
+        &self,
+        run_ctx: Option<&RunContext<ReActState>>,
+        tool_name: &str,
+    ) -> Option<ToolState> {
+        let user_id = run_ctx.and_then(|c| c.config.user_id.as_deref());
+        let thread_id = run_ctx.and_then(|c| c.config.thread_id.as_deref());
+        self.tool_state_store
+            .for_tool(user_id, thread_id, tool_name)
+    }
+
     /// Handles a tool error according to the configured error handling mode.
     ///
     /// Returns Some(error_message) if the error should be caught and returned as a result,
-    /// or None if the error should propagate.
+    /// or None if the error should propagate. `messages` is the conversation so far, from
+    /// which the `{attempt}` of the call is counted.
     fn handle_error(
         &self,
         error: &ToolSourceError,
-        tool_name: &str,
+        tc: &ToolCall,
         tool_args: &Value,
+        messages: &[Message],
     ) -> Option<String> {
-        match &self.handle_tool_errors {
-            HandleToolErrors::Never => None,
-            HandleToolErrors::Always(custom_msg) => {
-                let msg = custom_msg.clone().unwrap_or_else(|| {
-                    DEFAULT_EXECUTION_ERROR_TEMPLATE
-                        .replace("{tool_name}", tool_name)
-                        .replace("{tool_kwargs}", &tool_args.to_string())
-                        .replace("{error}", &error.to_string())
-                });
-                Some(msg)
+        let template = match &self.handle_tool_errors {
+            HandleToolErrors::Never => return None,
+            HandleToolErrors::Custom(handler) => return Some(handler(error, &tc.name, tool_args)),
+            HandleToolErrors::Always(template) => template.as_deref(),
+            HandleToolErrors::PerTool { templates, default } => templates
+                .get(&tc.name)
+                .map(String::as_str)
+                .or(default.as_deref()),
+        };
+        let attempt = 1 + messages
+            .iter()
+            .filter(|m| matches!(m, Message::Tool { name: Some(name), .. } if *name == tc.name))
+            .count();
+        Some(render_error_template(
+            template.unwrap_or(DEFAULT_EXECUTION_ERROR_TEMPLATE),
+            &[
+                ("error", &error.to_string()),
+                ("tool_name", &tc.name),
+                ("tool_kwargs", &tool_args.to_string()),
+                ("call_id", tc.id.as_deref().unwrap_or_default()),
+                ("attempt", &attempt.to_string()),
+            ],
+        ))
+    }
+
+    /// Runs `tool_calls` with at most `max_parallel_tool_calls` in flight and returns their
+    /// results, and the UI events they emitted, in call order. A failure that
+    /// `handle_tool_errors` does not catch is returned once the calls before it have finished;
+    /// calls still running are dropped.
+    async fn execute_tool_calls(
+        &self,
+        tool_calls: &[ToolCall],
+        ctx: &ToolCallContext,
+        run_ctx: Option<&RunContext<ReActState>>,
+    ) -> Result<(Vec<ToolResult>, Vec<Value>), AgentError> {
+        let outputs: Vec<(ToolResult, Vec<Value>)> = stream::iter(tool_calls)
+            .map(|tc| self.execute_tool_call(tc, ctx, run_ctx))
+            .buffered(self.max_parallel_tool_calls)
+            .try_collect()
+            .await?;
+        let mut results = Vec::with_capacity(outputs.len());
+        let mut ui_events = Vec::new();
+        for (result, events) in outputs {
+            results.push(result);
+            ui_events.extend(events);
+        }
+        Ok((results, ui_events))
+    }
+
+    /// Runs one tool call with its own tool state and UI event sink; caught errors, including
+    /// rejected arguments, become the result content.
+    async fn execute_tool_call(
+        &self,
+        tc: &ToolCall,
+        ctx: &ToolCallContext,
+        run_ctx: Option<&RunContext<ReActState>>,
+    ) -> Result<(ToolResult, Vec<Value>), AgentError> {
+        let emit = |f: &dyn Fn(&dyn CallbackHandler, &str)| {
+            if let Some(run_ctx) = run_ctx {
+                run_ctx.emit_callback(f);
+            }
+        };
+        let args = match self.checked_arguments(tc).await {
+            Ok(args) => args,
+            Err((e, args)) => {
+                warn!(tool = %tc.name, error = %e, "Tool call arguments rejected");
+                emit(&|h, run_id| h.on_tool_start(run_id, tc));
+                emit(&|h, run_id| h.on_tool_error(run_id, tc, &e));
+                let content = self
+                    .handle_error(&e, tc, &args, &ctx.recent_messages)
+                    .ok_or_else(|| AgentError::ExecutionFailed(e.to_string()))?;
+                let result = ToolResult {
+                    call_id: tc.id.clone(),
+                    name: Some(tc.name.clone()),
+                    content,
+                };
+                return Ok((result, Vec::new()));
             }
-            HandleToolErrors::Custom(handler) => Some(handler(error, tool_name, tool_args)),
+        };
+
+        debug!(tool = %tc.name, args = ?args, "Calling tool");
+        let mut ctx = ctx.clone();
+        ctx.tool_state = self.tool_state(run_ctx, &tc.name);
+        let ui_events = UiEventSink::new();
+        ctx.ui_events = Some(ui_events.clone());
+
+        let span = tracing::info_span!("tool", tool = %tc.name, call_id = ?tc.id);
+        emit(&|h, run_id| h.on_tool_start(run_id, tc));
+        let started = Instant::now();
+        let result = self
+            .tools
+            .call_tool_with_context(&tc.name, args.clone(), Some(&ctx))
+            .instrument(span)
+            .await;
+        metrics::record(|m| m.tool_call(&tc.name, started.elapsed(), result.is_ok()));
+        match &result {
+            Ok(content) => emit(&|h, run_id| h.on_tool_end(run_id, tc, &content.text)),
+            Err(e) => emit(&|h, run_id| h.on_tool_error(run_id, tc, e)),
         }
+        let content = match result {
+            Ok(content) => {
+                trace!(
+                    tool = %tc.name,
+                    result_len = content.text.len(),
+                    result_preview = %truncate_for_log(&content.text, 200),
+                    "Tool returned"
+                );
+                content.text
+            }
+            Err(e) => {
+                warn!(tool = %tc.name, error = %e, "Tool call failed");
+                self.handle_error(&e, tc, &args, &ctx.recent_messages)
+                    .ok_or_else(|| AgentError::ExecutionFailed(e.to_string()))?
+            }
+        };
+        let result = ToolResult {
+            call_id: tc.id.clone(),
+            name: Some(tc.name.clone()),
+            content,
+        };
+        Ok((result, ui_events.take()))
     }
 }
 
@@ -186,8 +492,9 @@ impl Node<ReActState> for ActNode {
 
     /// Reads state.tool_calls, calls call_tool_with_context for each, writes tool_results.
     /// Passes ToolCallContext (recent_messages) explicitly so tools like get_recent_messages
-    /// receive current conversation without internal state. Also calls set_call_context for
-    /// backward compatibility. Returns Next::Continue.
+    /// receive current conversation without internal state. Also calls set_call_context once
+    /// per step with the shared context (no tool state or UI sink, which are per call and only
+    /// passed to call_tool_with_context) for backward compatibility. Returns Next::Continue.
     ///
     /// # Error Handling
     ///
@@ -201,60 +508,21 @@ impl Node<ReActState> for ActNode {
     async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
         let ctx = ToolCallContext::new(state.messages.clone());
         self.tools.set_call_context(Some(ctx.clone()));
-        let mut tool_results = Vec::with_capacity(state.tool_calls.len());
-
-        for tc in &state.tool_calls {
-            let args: Value = if tc.arguments.trim().is_empty() {
-                serde_json::json!({})
-            } else {
-                serde_json::from_str(&tc.arguments).unwrap_or(serde_json::json!({}))
-            };
-
-            debug!(tool = %tc.name, args = ?args, "Calling tool");
-
-            let result = self
-                .tools
-                .call_tool_with_context(&tc.name, args.clone(), Some(&ctx))
-                .await;
-
-            match result {
-                Ok(content) => {
-                    trace!(
-                        tool = %tc.name,
-                        result_len = content.text.len(),
-                        result_preview = %truncate_for_log(&content.text, 200),
-                        "Tool returned"
-                    );
-                    tool_results.push(ToolResult {
-                        call_id: tc.id.clone(),
-                        name: Some(tc.name.clone()),
-                        content: content.text,
-                    });
-                }
-                Err(e) => {
-                    warn!(tool = %tc.name, error = %e, "Tool call failed");
-                    if let Some(error_msg) = self.handle_error(&e, &tc.name, &args) {
-                        // Error is handled - add as error result
-                        tool_results.push(ToolResult {
-                            call_id: tc.id.clone(),
-                            name: Some(tc.name.clone()),
-                            content: error_msg,
-                        });
-                    } else {
-                        // Error propagates
-                        self.tools.set_call_context(None);
-                        return Err(AgentError::ExecutionFailed(e.to_string()));
-                    }
-                }
-            }
-        }
-
+        let outputs = self.execute_tool_calls(&state.tool_calls, &ctx, None).await;
         self.tools.set_call_context(None);
+        let (tool_results, new_ui_events) = outputs?;
+
+        let mut ui_events = state.ui_events;
+        ui_events.extend(new_ui_events);
         let new_state = ReActState {
             messages: state.messages,
             tool_calls: state.tool_calls,
             tool_results,
             turn_count: state.turn_count,
+            response_metadata: state.response_metadata,
+            usage: state.usage,
+            final_answer: state.final_answer,
+            ui_events,
         };
         Ok((new_state, Next::Continue))
     }
@@ -305,64 +573,31 @@ impl Node<ReActState> for ActNode {
         } else {
             ToolStreamWriter::noop()
         };
+        let tool_writer = match &run_ctx.custom_events {
+            Some(registry) => tool_writer.with_custom_events(registry.clone()),
+            None => tool_writer,
+        };
 
         // Create ToolCallContext with stream writer
         let ctx = ToolCallContext::with_stream_writer(state.messages.clone(), tool_writer);
         self.tools.set_call_context(Some(ctx.clone()));
-
-        let mut tool_results = Vec::with_capacity(state.tool_calls.len());
-
-        for tc in &state.tool_calls {
-            let args: Value = if tc.arguments.trim().is_empty() {
-                serde_json::json!({})
-            } else {
-                serde_json::from_str(&tc.arguments).unwrap_or(serde_json::json!({}))
-            };
-
-            debug!(tool = %tc.name, args = ?args, "Calling tool");
-
-            let result = self
-                .tools
-                .call_tool_with_context(&tc.name, args.clone(), Some(&ctx))
-                .await;
-
-            match result {
-                Ok(content) => {
-                    trace!(
-                        tool = %tc.name,
-                        result_len = content.text.len(),
-                        result_preview = %truncate_for_log(&content.text, 200),
-                        "Tool returned"
-                    );
-                    tool_results.push(ToolResult {
-                        call_id: tc.id.clone(),
-                        name: Some(tc.name.clone()),
-                        content: content.text,
-                    });
-                }
-                Err(e) => {
-                    warn!(tool = %tc.name, error = %e, "Tool call failed");
-                    if let Some(error_msg) = self.handle_error(&e, &tc.name, &args) {
-                        tool_results.push(ToolResult {
-                            call_id: tc.id.clone(),
-                            name: Some(tc.name.clone()),
-                            content: error_msg,
-                        });
-                    } else {
-                        self.tools.set_call_context(None);
-                        return Err(AgentError::ExecutionFailed(e.to_string()));
-                    }
-                }
-            }
-        }
-
+        let outputs = self
+            .execute_tool_calls(&state.tool_calls, &ctx, Some(run_ctx))
+            .await;
         self.tools.set_call_context(None);
+        let (tool_results, new_ui_events) = outputs?;
 
+        let mut ui_events = state.ui_events;
+        ui_events.extend(new_ui_events);
         let new_state = ReActState {
             messages: state.messages,
             tool_calls: state.tool_calls,
             tool_results,
             turn_count: state.turn_count,
+            response_metadata: state.response_metadata,
+            usage: state.usage,
+            final_answer: state.final_answer,
+            ui_events,
         };
         Ok((new_state, Next::Continue))
     }
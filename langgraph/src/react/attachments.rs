@@ -0,0 +1,161 @@
+//! Large tool outputs as named attachments: stored in full, replayed as a preview.
+//!
+//! With [`ToolAttachments`] set, ObserveNode stores every tool output longer than the threshold
+//! in the Store under an attachment id named after the tool (`web_fetcher-<uuid>`) and puts only
+//! a preview and that id into the Tool message. The model reads the rest on demand with the
+//! `fetch_attachment` tool ([`ToolAttachments::fetch_tool`]), so long outputs neither bloat every
+//! later prompt nor get cut off.
+//!
+//! **Interaction**: Used by [`ObserveNode::with_attachments`](super::ObserveNode::with_attachments);
+//! register [`ToolAttachments::fetch_tool`] on the ActNode's tool source (e.g. an
+//! `AggregateToolSource`) with the same store and namespace.
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::memory::{uuid6, Namespace, Store, StoreError};
+use crate::state::ToolResult;
+use crate::tools::{FetchAttachmentTool, TOOL_FETCH_ATTACHMENT};
+
+/// Namespace attachments are stored under by default.
+pub const DEFAULT_ATTACHMENT_NAMESPACE: &str = "attachments";
+/// Outputs longer than this many characters become attachments by default.
+pub const DEFAULT_ATTACHMENT_THRESHOLD: usize = 4000;
+/// Characters of an attachment kept in the message by default.
+pub const DEFAULT_ATTACHMENT_PREVIEW: usize = 1000;
+
+/// Where and from which size tool outputs are stored as attachments.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::memory::InMemoryStore;
+/// use langgraph::react::ToolAttachments;
+/// use langgraph::tools::AggregateToolSource;
+/// use langgraph::ObserveNode;
+/// use std::sync::Arc;
+///
+/// # async fn example(tools: AggregateToolSource) {
+/// let attachments = ToolAttachments::new(Arc::new(InMemoryStore::new())).with_threshold(2000);
+/// tools.register_async(Box::new(attachments.fetch_tool())).await;
+/// let observe = ObserveNode::with_loop().with_attachments(attachments);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ToolAttachments {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+    threshold: usize,
+    preview: usize,
+}
+
+impl ToolAttachments {
+    /// Attachments in namespace [`DEFAULT_ATTACHMENT_NAMESPACE`] of `store`.
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            namespace: vec![DEFAULT_ATTACHMENT_NAMESPACE.to_string()],
+            threshold: DEFAULT_ATTACHMENT_THRESHOLD,
+            preview: DEFAULT_ATTACHMENT_PREVIEW,
+        }
+    }
+
+    /// Stores attachments under `namespace` instead, e.g. `[user_id, "attachments"]`.
+    pub fn with_namespace(mut self, namespace: Namespace) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    /// Outputs longer than `chars` characters become attachments.
+    pub fn with_threshold(mut self, chars: usize) -> Self {
+        self.threshold = chars;
+        self
+    }
+
+    /// Keeps the first `chars` characters of an attachment in the message (capped at the
+    /// threshold).
+    pub fn with_preview(mut self, chars: usize) -> Self {
+        self.preview = chars;
+        self
+    }
+
+    /// The namespace attachments are stored under.
+    pub fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    /// `fetch_attachment` over the same store and namespace.
+    pub fn fetch_tool(&self) -> FetchAttachmentTool {
+        FetchAttachmentTool::new(self.store.clone(), self.namespace.clone())
+    }
+
+    /// Content for the Tool message of `result`: unchanged when short, else the output is
+    /// stored and a preview naming the attachment is returned.
+    pub async fn offload(&self, result: &ToolResult) -> Result<String, StoreError> {
+        let total = result.content.chars().count();
+        if total <= self.threshold {
+            return Ok(result.content.clone());
+        }
+        let tool = result.name.as_deref().unwrap_or("tool");
+        let id = format!("{}-{}", tool, uuid6());
+        let value = json!({
+            "tool": result.name,
+            "call_id": result.call_id,
+            "content": result.content,
+        });
+        self.store.put(&self.namespace, &id, &value).await?;
+
+        let shown = self.preview.min(self.threshold);
+        let preview: String = result.content.chars().take(shown).collect();
+        Ok(format!(
+            "{}\n\n[Output of {} shortened: {} of {} characters shown. The full output is \
+             attachment \"{}\"; call {} with {{\"id\": \"{}\", \"offset\": {}}} to read more.]",
+            preview, tool, shown, total, id, TOOL_FETCH_ATTACHMENT, id, shown
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use crate::tools::Tool;
+
+    /// **Scenario**: Short outputs stay inline; long ones are stored under an id named after
+    /// the tool, and the preview tells the model how to fetch the rest.
+    #[tokio::test]
+    async fn offloads_long_outputs() {
+        let store = Arc::new(InMemoryStore::new());
+        let attachments = ToolAttachments::new(store.clone())
+            .with_threshold(10)
+            .with_preview(4);
+        let result = |content: &str| ToolResult {
+            call_id: Some("c1".into()),
+            name: Some("web_fetcher".into()),
+            content: content.into(),
+        };
+
+        assert_eq!(
+            attachments.offload(&result("short")).await.unwrap(),
+            "short"
+        );
+
+        let text = attachments
+            .offload(&result("0123456789abcdef"))
+            .await
+            .unwrap();
+        assert!(text.starts_with("0123\n\n[Output of web_fetcher shortened: 4 of 16"));
+        let keys = store.list(attachments.namespace()).await.unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].starts_with("web_fetcher-"));
+        assert!(text.contains(&keys[0]));
+
+        let rest = attachments
+            .fetch_tool()
+            .call(json!({"id": keys[0], "offset": 4}), None)
+            .await
+            .unwrap();
+        assert_eq!(rest.text, "456789abcdef");
+    }
+}
@@ -0,0 +1,279 @@
+//! Per-run budgets for ReAct runs: tokens, cost in USD and tool calls.
+//!
+//! A [`BudgetPolicy`] is a [`RunGovernor`] over [`ReActState`]: before each think / act /
+//! observe step it compares the run's usage with its limits and, when one is hit, fails the run
+//! with `AgentError::BudgetExceeded` (surfaced by [`ReactRunner`](super::ReactRunner) as
+//! `RunError::BudgetExceeded`). Cost is the run's token usage priced with a
+//! [`ModelPriceTable`] for the model that served it.
+//!
+//! **Interaction**: Reads `ReActState::usage` (summed per run by ThinkNode) and
+//! `ReActState::response_metadata`; set with `ReactRunner::with_budget`, or from
+//! `REACT_MAX_RUN_TOKENS` / `REACT_MAX_RUN_COST_USD` / `REACT_MAX_RUN_TOOL_CALLS` via
+//! [`ReactBuildConfig::budget_policy`](crate::ReactBuildConfig::budget_policy).
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::graph::{GovernorDecision, RunGovernor, RunProgress, TokenPricing};
+use crate::message::Message;
+use crate::state::ReActState;
+
+/// Prices in USD per million tokens, by model name.
+///
+/// [`lookup`](Self::lookup) matches the model exactly, else the longest name the model starts
+/// with, so dated snapshots (`gpt-4o-2024-08-06`) use the price of their family (`gpt-4o`).
+#[derive(Debug, Clone, Default)]
+pub struct ModelPriceTable {
+    prices: HashMap<String, TokenPricing>,
+}
+
+impl ModelPriceTable {
+    /// Empty table; add prices with [`with_price`](Self::with_price).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List prices of common OpenAI chat models (USD per million prompt / completion tokens).
+    pub fn openai_defaults() -> Self {
+        Self::new()
+            .with_price("gpt-4o", TokenPricing::new(2.5, 10.0))
+            .with_price("gpt-4o-mini", TokenPricing::new(0.15, 0.6))
+            .with_price("gpt-4.1", TokenPricing::new(2.0, 8.0))
+            .with_price("gpt-4.1-mini", TokenPricing::new(0.4, 1.6))
+            .with_price("gpt-4.1-nano", TokenPricing::new(0.1, 0.4))
+    }
+
+    /// Sets the price of `model`, replacing any previous one.
+    pub fn with_price(mut self, model: impl Into<String>, pricing: TokenPricing) -> Self {
+        self.prices.insert(model.into(), pricing);
+        self
+    }
+
+    /// Price of `model`: exact match, else the longest table entry `model` starts with.
+    pub fn lookup(&self, model: &str) -> Option<TokenPricing> {
+        if let Some(pricing) = self.prices.get(model) {
+            return Some(*pricing);
+        }
+        self.prices
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, pricing)| *pricing)
+    }
+}
+
+/// Limits on one ReAct run; the first limit hit fails the run.
+///
+/// - **Tokens**: total tokens of the run's LLM calls reached `max`.
+/// - **Cost**: those tokens, priced for the model of the last reply (or
+///   [`with_model`](Self::with_model) before the first one), cost `max` USD or more. Runs on a
+///   model missing from the table are not limited by cost.
+/// - **Tool calls**: running the pending tool calls would take the run past `max`. Calls are
+///   counted from the last user message, so each invoke on a thread starts from zero.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::react::{BudgetPolicy, ModelPriceTable};
+///
+/// let budget = BudgetPolicy::new()
+///     .with_max_tokens(50_000)
+///     .with_max_cost_usd(0.25, ModelPriceTable::openai_defaults())
+///     .with_max_tool_calls(20)
+///     .with_model("gpt-4o-mini");
+/// // runner.with_budget(budget)
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BudgetPolicy {
+    max_tokens: Option<u32>,
+    max_cost_usd: Option<(f64, ModelPriceTable)>,
+    max_tool_calls: Option<usize>,
+    model: Option<String>,
+}
+
+impl BudgetPolicy {
+    /// Policy without limits; add them with the `with_max_*` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the run once it has used `max` total tokens.
+    pub fn with_max_tokens(mut self, max: u32) -> Self {
+        self.max_tokens = Some(max);
+        self
+    }
+
+    /// Stops the run once its tokens cost `max` USD at the prices in `prices`.
+    pub fn with_max_cost_usd(mut self, max: f64, prices: ModelPriceTable) -> Self {
+        self.max_cost_usd = Some((max, prices));
+        self
+    }
+
+    /// Stops the run before a tool call would be its `max + 1`-th.
+    pub fn with_max_tool_calls(mut self, max: usize) -> Self {
+        self.max_tool_calls = Some(max);
+        self
+    }
+
+    /// Model priced when the state does not name one yet (no reply carried metadata).
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// True when no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_tokens.is_none() && self.max_cost_usd.is_none() && self.max_tool_calls.is_none()
+    }
+
+    /// Cost in USD of the run so far; None when its model has no price.
+    pub fn cost_usd(&self, state: &ReActState, prices: &ModelPriceTable) -> Option<f64> {
+        let model = state
+            .response_metadata
+            .as_ref()
+            .and_then(|m| m.model.as_deref())
+            .or(self.model.as_deref())?;
+        prices
+            .lookup(model)
+            .map(|pricing| pricing.cost(&state.usage))
+    }
+
+    /// The first limit the run reaches before `node_id` runs, as a reason.
+    pub fn check(&self, state: &ReActState, node_id: &str) -> Option<String> {
+        if let Some(max) = self.max_tokens {
+            if state.usage.total_tokens >= max {
+                return Some(format!(
+                    "token budget of {} exceeded ({} used)",
+                    max, state.usage.total_tokens
+                ));
+            }
+        }
+        if let Some((max, prices)) = &self.max_cost_usd {
+            if let Some(cost) = self.cost_usd(state, prices) {
+                if cost >= *max {
+                    return Some(format!(
+                        "cost budget of ${} exceeded (${:.4} spent)",
+                        max, cost
+                    ));
+                }
+            }
+        }
+        if let Some(max) = self.max_tool_calls {
+            let pending = if node_id == "act" {
+                state.tool_calls.len()
+            } else {
+                0
+            };
+            if run_tool_calls(state) + pending > max {
+                return Some(format!("tool call budget of {} exceeded", max));
+            }
+        }
+        None
+    }
+}
+
+/// Tool results since the last user message.
+fn run_tool_calls(state: &ReActState) -> usize {
+    state
+        .messages
+        .iter()
+        .rev()
+        .take_while(|m| !matches!(m, Message::User(_)))
+        .filter(|m| matches!(m, Message::Tool { .. }))
+        .count()
+}
+
+#[async_trait]
+impl RunGovernor<ReActState> for BudgetPolicy {
+    async fn decide(&self, state: &ReActState, progress: &RunProgress) -> GovernorDecision {
+        match self.check(state, &progress.node_id) {
+            Some(reason) => GovernorDecision::BudgetExceeded(reason),
+            None => GovernorDecision::Continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmResponseMetadata, LlmUsage};
+    use crate::state::ToolCall;
+
+    fn state(total_tokens: u32, model: Option<&str>) -> ReActState {
+        ReActState {
+            messages: vec![Message::system("sys"), Message::user("hi")],
+            usage: LlmUsage {
+                prompt_tokens: total_tokens / 2,
+                completion_tokens: total_tokens - total_tokens / 2,
+                total_tokens,
+            },
+            response_metadata: model.map(|m| LlmResponseMetadata {
+                model: Some(m.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Exact names win; dated snapshots fall back to the longest matching family.
+    #[test]
+    fn price_table_matches_longest_prefix() {
+        let prices = ModelPriceTable::openai_defaults();
+        assert_eq!(prices.lookup("gpt-4o"), Some(TokenPricing::new(2.5, 10.0)));
+        assert_eq!(
+            prices.lookup("gpt-4o-mini-2024-07-18"),
+            Some(TokenPricing::new(0.15, 0.6))
+        );
+        assert_eq!(
+            prices.lookup("gpt-4o-2024-08-06"),
+            Some(TokenPricing::new(2.5, 10.0))
+        );
+        assert_eq!(prices.lookup("claude-3"), None);
+    }
+
+    /// **Scenario**: Token and cost limits trip at their thresholds; the cost uses the model of
+    /// the last reply, or the fallback model, and unknown models are not limited.
+    #[test]
+    fn token_and_cost_limits() {
+        let tokens = BudgetPolicy::new().with_max_tokens(1_000);
+        assert_eq!(tokens.check(&state(999, None), "think"), None);
+        assert_eq!(
+            tokens.check(&state(1_000, None), "think").as_deref(),
+            Some("token budget of 1000 exceeded (1000 used)")
+        );
+
+        // 1M tokens of gpt-4o: 0.5M prompt at $2.5 + 0.5M completion at $10 = $6.25.
+        let cost = BudgetPolicy::new()
+            .with_max_cost_usd(5.0, ModelPriceTable::openai_defaults())
+            .with_model("gpt-4o-mini");
+        assert!(cost
+            .check(&state(1_000_000, Some("gpt-4o-2024-08-06")), "think")
+            .is_some_and(|r| r.starts_with("cost budget of $5 exceeded")));
+        assert_eq!(cost.check(&state(1_000_000, None), "think"), None);
+        assert_eq!(
+            cost.check(&state(100_000_000, Some("local-llama")), "think"),
+            None
+        );
+    }
+
+    /// **Scenario**: Tool calls count from the last user message; pending calls count before act.
+    #[test]
+    fn tool_call_limit_counts_run_and_pending_calls() {
+        let policy = BudgetPolicy::new().with_max_tool_calls(2);
+        let mut s = state(0, None);
+        s.messages.push(Message::tool(None, None, "earlier run"));
+        s.messages.push(Message::user("again"));
+        s.messages.push(Message::tool(None, None, "1"));
+        assert_eq!(policy.check(&s, "think"), None);
+
+        s.tool_calls = vec![ToolCall::default(), ToolCall::default()];
+        assert_eq!(policy.check(&s, "think"), None);
+        assert_eq!(
+            policy.check(&s, "act").as_deref(),
+            Some("tool call budget of 2 exceeded")
+        );
+        assert!(BudgetPolicy::new().is_unlimited());
+        assert!(!policy.is_unlimited());
+    }
+}
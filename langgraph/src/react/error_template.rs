@@ -0,0 +1,76 @@
+//! Error message templates for [`ActNode`](super::ActNode): `{name}` placeholders filled in one
+//! pass.
+//!
+//! Values are inserted as they are and never scanned again, so braces in tool arguments or
+//! error texts (JSON, code) cannot be mistaken for placeholders. `{{` and `}}` stand for
+//! literal braces; placeholders without a value are kept as written.
+//!
+//! **Interaction**: Used by `ActNode` for `HandleToolErrors::Always` and
+//! `HandleToolErrors::PerTool` templates, with the variables `error`, `tool_name`,
+//! `tool_kwargs`, `call_id` and `attempt`.
+
+/// Fills the `{name}` placeholders of `template` from `vars`.
+///
+/// # Examples
+///
+/// ```
+/// use langgraph::render_error_template;
+///
+/// let text = render_error_template(
+///     "{tool_name} failed on {tool_kwargs}: {error} {{attempt {attempt}}}",
+///     &[
+///         ("tool_name", "search"),
+///         ("tool_kwargs", r#"{"q": "{error}"}"#),
+///         ("error", "timeout"),
+///         ("attempt", "2"),
+///     ],
+/// );
+/// assert_eq!(text, r#"search failed on {"q": "{error}"}: timeout {attempt 2}"#);
+/// ```
+pub fn render_error_template(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if let Some(end) = tail.strip_prefix('{').and_then(|t| t.find('}')) {
+            let name = &tail[1..=end];
+            if let Some((_, value)) = vars.iter().find(|(key, _)| *key == name) {
+                out.push_str(value);
+                rest = &tail[end + 2..];
+                continue;
+            }
+        }
+        out.push_str(&tail[..1]);
+        rest = &tail[1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Values containing placeholders are not expanded again; escaped and unknown
+    /// placeholders and stray braces are kept literally.
+    #[test]
+    fn fills_placeholders_once() {
+        let vars = [("error", "bad {tool_name}"), ("tool_name", "search")];
+        assert_eq!(
+            render_error_template("{tool_name}: {error}", &vars),
+            "search: bad {tool_name}"
+        );
+        assert_eq!(
+            render_error_template("{{error}} {unknown} {error", &vars),
+            "{error} {unknown} {error"
+        );
+        assert_eq!(render_error_template("a } b {} c", &vars), "a } b {} c");
+        assert_eq!(render_error_template("", &vars), "");
+    }
+}
@@ -0,0 +1,320 @@
+//! A/B experiments: two agent configurations side by side, each thread or request assigned to
+//! one by a deterministic hash.
+//!
+//! An [`Experiment`] holds two runners built from different configurations (prompt profile,
+//! model, tool set, ...) as variants `a` and `b`. [`Experiment::assign`] picks the variant of a
+//! run from a hash of the experiment name and the thread id ([`Assignment::PerThread`], so a
+//! conversation stays on one variant) or the request id ([`Assignment::PerRequest`]); the same
+//! key always gets the same variant, across restarts and replicas. Each runner knows its
+//! [`ExperimentVariant`]: it adds the variant's tag (`experiment:<name>=<variant>`) to the
+//! thread's latest checkpoint after every run and records the run's tokens per variant in its
+//! usage quota, so outcomes and usage of the two variants can be compared.
+//!
+//! **Interaction**: Labels runners with [`ReactRunner::with_experiment_variant`]; tags are
+//! written with [`Checkpointer::tag_checkpoint`](crate::memory::Checkpointer::tag_checkpoint)
+//! next to the [`RunOutcome`](super::RunOutcome) labels, usage with
+//! [`UsageQuota::record_experiment`](super::UsageQuota::record_experiment). langgraph-server
+//! builds one from its `EXPERIMENT_*` settings.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::memory::RunnableConfig;
+
+use super::ReactRunner;
+
+/// Prefix of the checkpoint tags naming a run's experiment variant.
+pub const EXPERIMENT_TAG_PREFIX: &str = "experiment:";
+
+/// Share of runs assigned to variant `b` when none is given.
+pub const DEFAULT_EXPERIMENT_SHARE_B: f64 = 0.5;
+
+/// One of the two configurations of an experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Variant {
+    /// The first configuration, usually the current one (control).
+    A,
+    /// The second configuration, the change under test.
+    B,
+}
+
+impl Variant {
+    /// Name in tags and usage namespaces: `a` or `b`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::A => "a",
+            Self::B => "b",
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" => Ok(Self::A),
+            "b" => Ok(Self::B),
+            other => Err(format!("unknown variant {:?}; expected a or b", other)),
+        }
+    }
+}
+
+/// What a run's variant is derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Assignment {
+    /// The thread id, so every turn of a conversation runs on the same variant; runs without a
+    /// thread id fall back to the request id.
+    #[default]
+    PerThread,
+    /// The request id, so turns of one conversation may run on different variants.
+    PerRequest,
+}
+
+impl FromStr for Assignment {
+    type Err = String;
+
+    /// `thread` or `request`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "thread" => Ok(Self::PerThread),
+            "request" => Ok(Self::PerRequest),
+            other => Err(format!(
+                "unknown experiment assignment {:?}; expected thread or request",
+                other
+            )),
+        }
+    }
+}
+
+/// The experiment and variant a runner serves; see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExperimentVariant {
+    /// Experiment name, e.g. `coding-profile`.
+    pub experiment: String,
+    pub variant: Variant,
+}
+
+impl ExperimentVariant {
+    pub fn new(experiment: impl Into<String>, variant: Variant) -> Self {
+        Self {
+            experiment: experiment.into(),
+            variant,
+        }
+    }
+
+    /// Checkpoint tag of runs on this variant: `experiment:<name>=<variant>`.
+    pub fn tag(&self) -> String {
+        format!(
+            "{}{}={}",
+            EXPERIMENT_TAG_PREFIX, self.experiment, self.variant
+        )
+    }
+
+    /// The variant a checkpoint tag names; None for other tags.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let (experiment, variant) = tag.strip_prefix(EXPERIMENT_TAG_PREFIX)?.rsplit_once('=')?;
+        Some(Self::new(experiment, variant.parse().ok()?))
+    }
+}
+
+/// Two runners compared on live traffic; see the [module docs](self).
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use langgraph::{Assignment, Experiment, Variant};
+///
+/// let experiment = Experiment::new("research-profile", current_runner, research_runner)
+///     .with_share_b(0.2)
+///     .with_assignment(Assignment::PerThread);
+/// let runner = experiment.runner(experiment.assign(Some(&config), &request_id));
+/// let state = runner.invoke_with_config("Hello", Some(config)).await?;
+/// ```
+pub struct Experiment {
+    name: String,
+    a: Arc<ReactRunner>,
+    b: Arc<ReactRunner>,
+    share_b: f64,
+    assignment: Assignment,
+}
+
+impl Experiment {
+    /// Experiment `name` between runners `a` and `b`, each labelled with its variant; half of
+    /// the threads go to each.
+    pub fn new(name: impl Into<String>, a: ReactRunner, b: ReactRunner) -> Self {
+        let name = name.into();
+        Self {
+            a: Arc::new(a.with_experiment_variant(ExperimentVariant::new(&name, Variant::A))),
+            b: Arc::new(b.with_experiment_variant(ExperimentVariant::new(&name, Variant::B))),
+            name,
+            share_b: DEFAULT_EXPERIMENT_SHARE_B,
+            assignment: Assignment::default(),
+        }
+    }
+
+    /// Sends `share` of the threads or requests (0.0 to 1.0, clamped) to variant `b`.
+    pub fn with_share_b(mut self, share: f64) -> Self {
+        self.share_b = if share.is_nan() {
+            0.0
+        } else {
+            share.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /// Assigns variants per thread (default) or per request.
+    pub fn with_assignment(mut self, assignment: Assignment) -> Self {
+        self.assignment = assignment;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Share of threads or requests assigned to variant `b`.
+    pub fn share_b(&self) -> f64 {
+        self.share_b
+    }
+
+    pub fn assignment(&self) -> Assignment {
+        self.assignment
+    }
+
+    /// The runner of `variant`.
+    pub fn runner(&self, variant: Variant) -> &Arc<ReactRunner> {
+        match variant {
+            Variant::A => &self.a,
+            Variant::B => &self.b,
+        }
+    }
+
+    /// Variant of a run with `config` in request `request_id`; see [`Assignment`].
+    pub fn assign(&self, config: Option<&RunnableConfig>, request_id: &str) -> Variant {
+        let thread_id = config.and_then(|c| c.thread_id.as_deref());
+        let key = match (self.assignment, thread_id) {
+            (Assignment::PerThread, Some(thread_id)) => thread_id,
+            _ => request_id,
+        };
+        self.variant_for(key)
+    }
+
+    /// Variant of `key`: `b` when the key's hash falls in the first `share_b` of the range.
+    pub fn variant_for(&self, key: &str) -> Variant {
+        if bucket(&self.name, key) < self.share_b {
+            Variant::B
+        } else {
+            Variant::A
+        }
+    }
+}
+
+impl fmt::Debug for Experiment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Experiment")
+            .field("name", &self.name)
+            .field("share_b", &self.share_b)
+            .field("assignment", &self.assignment)
+            .finish()
+    }
+}
+
+/// Position of `key` in [0, 1) for experiment `name`: FNV-1a of `name/key`, stable across
+/// processes and Rust versions (unlike `DefaultHasher`). Including the name keeps the splits of
+/// different experiments independent.
+fn bucket(name: &str, key: &str) -> f64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let bytes = name.bytes().chain(std::iter::once(b'/')).chain(key.bytes());
+    let hash = bytes.fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    // The top 53 bits fit an f64 exactly.
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockLlm, MockToolSource};
+
+    fn runner(reply: &str) -> ReactRunner {
+        ReactRunner::new(
+            Box::new(MockLlm::with_no_tool_calls(reply)),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+    }
+
+    fn thread(thread_id: &str) -> RunnableConfig {
+        RunnableConfig {
+            thread_id: Some(thread_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Assignment is deterministic, splits keys close to the configured share,
+    /// and keeps a thread on one variant across requests; per request it varies.
+    #[test]
+    fn assigns_by_stable_hash() {
+        let experiment = Experiment::new("profile", runner("a"), runner("b")).with_share_b(0.2);
+        let b = (0..10_000)
+            .filter(|i| experiment.variant_for(&format!("thread-{}", i)) == Variant::B)
+            .count();
+        assert!((1_800..2_200).contains(&b), "{} of 10000 on b", b);
+
+        let config = thread("thread-7");
+        let first = experiment.assign(Some(&config), "req-1");
+        assert!((2..50).all(|i| experiment.assign(Some(&config), &format!("req-{}", i)) == first));
+        assert_eq!(
+            experiment.assign(None, "req-1"),
+            experiment.variant_for("req-1")
+        );
+
+        let per_request = Experiment::new("profile", runner("a"), runner("b"))
+            .with_assignment(Assignment::PerRequest);
+        let variants: std::collections::HashSet<Variant> = (0..50)
+            .map(|i| per_request.assign(Some(&config), &format!("req-{}", i)))
+            .collect();
+        assert_eq!(variants.len(), 2);
+
+        let all_b = Experiment::new("profile", runner("a"), runner("b")).with_share_b(7.0);
+        assert_eq!(all_b.variant_for("anything"), Variant::B);
+    }
+
+    /// **Scenario**: Each variant's runner answers with its own configuration and carries its
+    /// label; tags round-trip and other tags are not variants.
+    #[tokio::test]
+    async fn runners_carry_their_variant() {
+        let experiment = Experiment::new("profile", runner("from a"), runner("from b"));
+        let state = experiment.runner(Variant::B).invoke("hi").await.unwrap();
+        assert_eq!(state.last_assistant_reply().as_deref(), Some("from b"));
+        let label = experiment.runner(Variant::A).experiment_variant().unwrap();
+        assert_eq!(label.tag(), "experiment:profile=a");
+
+        assert_eq!(
+            ExperimentVariant::from_tag(&label.tag()).as_ref(),
+            Some(label)
+        );
+        assert_eq!(
+            ExperimentVariant::from_tag("experiment:a=b=b"),
+            Some(ExperimentVariant::new("a=b", Variant::B))
+        );
+        assert_eq!(ExperimentVariant::from_tag("answered"), None);
+        assert_eq!(ExperimentVariant::from_tag("experiment:profile=c"), None);
+        assert_eq!("request".parse(), Ok(Assignment::PerRequest));
+    }
+}
@@ -0,0 +1,411 @@
+//! Compression of long message histories before they are sent to the LLM.
+//!
+//! A thread's checkpoints keep its full history; a [`HistoryCompressor`] only rewrites the
+//! messages of one LLM request, so long threads stay within the model's context window and
+//! cost less. A turn starts at a user message; the latest turns are always sent as they are.
+//!
+//! - [`DropOldToolResults`]: tool results older than N turns are replaced by
+//!   [`OMITTED_TOOL_RESULT`]. The messages themselves stay, so every tool call keeps its result.
+//! - [`SummarizeOldTurns`]: turns older than N are replaced by one system message with a
+//!   summary written by an LLM; summaries are cached, so a thread pays for each once.
+//! - [`PinnedMessages`]: messages [`SummarizeOldTurns`] keeps verbatim: system messages, and
+//!   optionally the first user message or messages containing a marker.
+//! - [`CompressorChain`]: several compressors applied in order.
+//!
+//! **Interaction**: Set with [`ThinkNode::with_history_compressor`](super::ThinkNode::with_history_compressor)
+//! or [`ReactRunner::with_history_compressor`](super::ReactRunner::with_history_compressor);
+//! deployments configure it through `ReactBuildConfig::history_compressor`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+
+use crate::message::Message;
+use crate::LlmClient;
+
+/// Content that replaces tool results dropped by [`DropOldToolResults`].
+pub const OMITTED_TOOL_RESULT: &str = "[Tool result omitted from earlier in the conversation.]";
+
+/// Start of the system message that carries a summary of [`SummarizeOldTurns`].
+pub const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:\n";
+
+/// Rewrites the messages of one LLM request.
+///
+/// Compressors do not fail: when a strategy cannot be applied (e.g. the summarizer errored),
+/// it logs and returns the messages unchanged.
+#[async_trait]
+pub trait HistoryCompressor: Send + Sync {
+    /// The messages to send instead of `messages`.
+    async fn compress(&self, messages: Vec<Message>) -> Vec<Message>;
+}
+
+/// Index of the first message of the last `keep_turns` turns (at least one); messages before
+/// it belong to older turns.
+fn recent_start(messages: &[Message], keep_turns: usize) -> usize {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| matches!(m, Message::User(_)))
+        .map(|(i, _)| i)
+        .rev()
+        .nth(keep_turns.max(1) - 1)
+        .unwrap_or(0)
+}
+
+/// Messages kept verbatim by compressors that remove messages.
+///
+/// System messages are always pinned. Tool messages and assistant messages with tool calls
+/// never are, so a call is never separated from its result.
+#[derive(Debug, Clone, Default)]
+pub struct PinnedMessages {
+    first_user_message: bool,
+    markers: Vec<String>,
+}
+
+impl PinnedMessages {
+    /// Pins system messages only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also pins the thread's first user message, e.g. when it states the task.
+    pub fn with_first_user_message(mut self) -> Self {
+        self.first_user_message = true;
+        self
+    }
+
+    /// Also pins user and assistant messages whose content contains `marker`.
+    pub fn with_marker(mut self, marker: impl Into<String>) -> Self {
+        self.markers.push(marker.into());
+        self
+    }
+
+    /// Whether `messages[index]` is kept verbatim.
+    pub fn is_pinned(&self, messages: &[Message], index: usize) -> bool {
+        let message = &messages[index];
+        match message {
+            Message::System(_) => true,
+            Message::Tool { .. } => false,
+            Message::Assistant { tool_calls, .. } if !tool_calls.is_empty() => false,
+            _ => {
+                let first_user = self.first_user_message
+                    && matches!(message, Message::User(_))
+                    && !messages[..index]
+                        .iter()
+                        .any(|m| matches!(m, Message::User(_)));
+                first_user
+                    || self
+                        .markers
+                        .iter()
+                        .any(|marker| message.content().contains(marker.as_str()))
+            }
+        }
+    }
+}
+
+/// Replaces the content of tool results older than `keep_turns` turns with
+/// [`OMITTED_TOOL_RESULT`].
+#[derive(Debug, Clone, Copy)]
+pub struct DropOldToolResults {
+    keep_turns: usize,
+}
+
+impl DropOldToolResults {
+    /// Keeps the tool results of the last `keep_turns` turns (at least one).
+    pub fn new(keep_turns: usize) -> Self {
+        Self {
+            keep_turns: keep_turns.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl HistoryCompressor for DropOldToolResults {
+    async fn compress(&self, mut messages: Vec<Message>) -> Vec<Message> {
+        let start = recent_start(&messages, self.keep_turns);
+        for message in &mut messages[..start] {
+            if matches!(message, Message::Tool { .. })
+                && message.content().len() > OMITTED_TOOL_RESULT.len()
+            {
+                *message.content_mut() = OMITTED_TOOL_RESULT.to_string();
+            }
+        }
+        messages
+    }
+}
+
+/// Instruction for the summarizer of [`SummarizeOldTurns`].
+const SUMMARY_PROMPT: &str = "Summarize the following conversation between a user and an \
+assistant in a few sentences. Keep facts, decisions, names, numbers and open questions the \
+assistant may need later; leave out greetings and repetition. Reply with the summary only.";
+
+/// Longest message content sent to the summarizer, in characters.
+const MAX_MESSAGE_CHARS: usize = 4000;
+
+/// Summaries kept by one [`SummarizeOldTurns`] before the cache is cleared.
+const MAX_CACHED_SUMMARIES: usize = 256;
+
+/// Replaces turns older than `keep_turns` with one system message holding an LLM-written
+/// summary ([`SUMMARY_PREFIX`]), placed where the first replaced message was. Pinned messages
+/// of those turns stay.
+///
+/// Summaries are cached by the replaced messages, so the summarizer runs again only when
+/// another turn gets old. When it fails, the messages are sent unchanged.
+pub struct SummarizeOldTurns {
+    llm: Arc<dyn LlmClient>,
+    keep_turns: usize,
+    pinned: PinnedMessages,
+    cache: Mutex<HashMap<u64, String>>,
+}
+
+impl SummarizeOldTurns {
+    /// Keeps the last `keep_turns` turns (at least one) and summarizes older ones with `llm`.
+    pub fn new(llm: Arc<dyn LlmClient>, keep_turns: usize) -> Self {
+        Self {
+            llm,
+            keep_turns: keep_turns.max(1),
+            pinned: PinnedMessages::new(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the messages kept verbatim (default: system messages).
+    pub fn with_pinned(mut self, pinned: PinnedMessages) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Cache key of the replaced messages.
+    fn key(messages: &[&Message]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for message in messages {
+            message.role().hash(&mut hasher);
+            message.content().hash(&mut hasher);
+            if let Message::Assistant { tool_calls, .. } = message {
+                for call in tool_calls {
+                    call.name.hash(&mut hasher);
+                    call.arguments.hash(&mut hasher);
+                }
+            }
+        }
+        hasher.finish()
+    }
+
+    /// The replaced messages as `role: content` lines; tool calls as `name(arguments)`.
+    fn transcript(messages: &[&Message]) -> String {
+        messages
+            .iter()
+            .map(|m| {
+                let mut content: String = m.content().chars().take(MAX_MESSAGE_CHARS).collect();
+                if let Message::Assistant { tool_calls, .. } = m {
+                    for call in tool_calls {
+                        content.push_str(&format!(" [called {}({})]", call.name, call.arguments));
+                    }
+                }
+                format!("{}: {}", m.role(), content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Summary of `removed`, from the cache or the summarizer; None when the summarizer failed.
+    async fn summary(&self, removed: &[&Message]) -> Option<String> {
+        let key = Self::key(removed);
+        let cached = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned();
+        if cached.is_some() {
+            return cached;
+        }
+        let request = [
+            Message::system(SUMMARY_PROMPT),
+            Message::user(Self::transcript(removed)),
+        ];
+        match self.llm.invoke(&request).await {
+            Ok(response) => {
+                let summary = response.content.trim().to_string();
+                let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+                if cache.len() >= MAX_CACHED_SUMMARIES {
+                    cache.clear();
+                }
+                cache.insert(key, summary.clone());
+                Some(summary)
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "history summary failed; sending full history");
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HistoryCompressor for SummarizeOldTurns {
+    async fn compress(&self, messages: Vec<Message>) -> Vec<Message> {
+        let start = recent_start(&messages, self.keep_turns);
+        let (removed_at, removed): (Vec<usize>, Vec<&Message>) = (0..start)
+            .filter(|&i| !self.pinned.is_pinned(&messages, i))
+            .map(|i| (i, &messages[i]))
+            .unzip();
+        let Some(&first_removed) = removed_at.first() else {
+            return messages;
+        };
+        let Some(summary) = self.summary(&removed).await else {
+            return messages;
+        };
+        let mut compressed = Vec::with_capacity(messages.len() - removed_at.len() + 1);
+        for (i, message) in messages.into_iter().enumerate() {
+            if i == first_removed {
+                compressed.push(Message::system(format!("{}{}", SUMMARY_PREFIX, summary)));
+            }
+            if removed_at.binary_search(&i).is_err() {
+                compressed.push(message);
+            }
+        }
+        compressed
+    }
+}
+
+/// Compressors applied one after the other, e.g. dropping old tool results first and then
+/// summarizing turns older still.
+#[derive(Clone, Default)]
+pub struct CompressorChain {
+    compressors: Vec<Arc<dyn HistoryCompressor>>,
+}
+
+impl CompressorChain {
+    /// Empty chain; returns messages unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `compressor`.
+    pub fn with(mut self, compressor: Arc<dyn HistoryCompressor>) -> Self {
+        self.compressors.push(compressor);
+        self
+    }
+
+    /// True when no compressor was added.
+    pub fn is_empty(&self) -> bool {
+        self.compressors.is_empty()
+    }
+}
+
+#[async_trait]
+impl HistoryCompressor for CompressorChain {
+    async fn compress(&self, mut messages: Vec<Message>) -> Vec<Message> {
+        for compressor in &self.compressors {
+            messages = compressor.compress(messages).await;
+        }
+        messages
+    }
+}
+
+/// Compressor of a ThinkNode, shared with the runner that built the node so it can be set
+/// after the graph was compiled.
+#[derive(Clone, Default)]
+pub(crate) struct CompressorSlot(Arc<RwLock<Option<Arc<dyn HistoryCompressor>>>>);
+
+impl CompressorSlot {
+    /// Replaces the compressor.
+    pub(crate) fn set(&self, compressor: Arc<dyn HistoryCompressor>) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(compressor);
+    }
+
+    /// `messages` compressed for the LLM; None when no compressor is set.
+    pub(crate) async fn compress(&self, messages: &[Message]) -> Option<Vec<Message>> {
+        let compressor = self.0.read().unwrap_or_else(|e| e.into_inner()).clone()?;
+        Some(compressor.compress(messages.to_vec()).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ToolCall;
+    use crate::MockLlm;
+
+    /// Two finished turns with a tool call each, and a new question.
+    fn history() -> Vec<Message> {
+        let turn = |n: u32| {
+            let call = ToolCall {
+                name: "search".into(),
+                arguments: format!("{{\"q\":\"{}\"}}", n),
+                id: Some(format!("c{}", n)),
+            };
+            vec![
+                Message::user(format!("question {}", n)),
+                Message::assistant_with_tool_calls("", vec![call]),
+                Message::tool(
+                    Some(format!("c{}", n)),
+                    Some("search".into()),
+                    format!("results for question {}: {}", n, "lorem ipsum ".repeat(10)),
+                ),
+                Message::assistant(format!("answer {}", n)),
+            ]
+        };
+        [
+            vec![Message::system("You are helpful.")],
+            turn(1),
+            turn(2),
+            vec![Message::user("question 3")],
+        ]
+        .concat()
+    }
+
+    /// **Scenario**: Tool results older than the kept turns are replaced by the placeholder;
+    /// every message, and so every call/result pair, stays.
+    #[tokio::test]
+    async fn drops_old_tool_results() {
+        let messages = DropOldToolResults::new(2).compress(history()).await;
+        assert_eq!(messages.len(), history().len());
+        assert_eq!(messages[3].content(), OMITTED_TOOL_RESULT);
+        assert_eq!(messages[7].content(), history()[7].content());
+    }
+
+    /// **Scenario**: Old turns become one summary after the system prompt; pinned messages
+    /// and the kept turns stay, and a second request reuses the cached summary.
+    #[tokio::test]
+    async fn summarizes_old_turns_and_keeps_pins() {
+        let llm = Arc::new(MockLlm::with_no_tool_calls("User asked two questions."));
+        let compressor = SummarizeOldTurns::new(llm, 1)
+            .with_pinned(PinnedMessages::new().with_first_user_message());
+        let messages = compressor.compress(history()).await;
+        let roles: Vec<&str> = messages.iter().map(Message::role).collect();
+        assert_eq!(roles, ["system", "user", "system", "user"]);
+        assert_eq!(messages[1].content(), "question 1");
+        assert_eq!(
+            messages[2].content(),
+            format!("{}User asked two questions.", SUMMARY_PREFIX)
+        );
+        assert_eq!(messages[3].content(), "question 3");
+
+        let again = compressor.compress(history()).await;
+        assert_eq!(again[2].content(), messages[2].content());
+        assert_eq!(compressor.cache.lock().unwrap().len(), 1);
+    }
+
+    /// **Scenario**: A chain drops tool results of the last but one turn and summarizes nothing
+    /// when every turn is recent; a single-turn history is left alone.
+    #[tokio::test]
+    async fn chain_applies_in_order() {
+        let llm = Arc::new(MockLlm::with_no_tool_calls("summary"));
+        let chain = CompressorChain::new()
+            .with(Arc::new(DropOldToolResults::new(1)))
+            .with(Arc::new(SummarizeOldTurns::new(llm, 3)));
+        let messages = chain.compress(history()).await;
+        assert_eq!(messages.len(), history().len());
+        assert_eq!(messages[3].content(), OMITTED_TOOL_RESULT);
+        assert_eq!(messages[7].content(), OMITTED_TOOL_RESULT);
+
+        let single = chain.compress(vec![Message::user("hi")]).await;
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].content(), "hi");
+    }
+}
@@ -0,0 +1,303 @@
+//! Automatic long-term memory: facts and preferences extracted from each finished turn.
+//!
+//! The memory tools only save what the model decides to `remember`, and models often forget.
+//! A [`MemoryWriter`] looks at the last turn of a run (the latest user message and the replies
+//! to it), asks a (cheap) model for durable facts about the user (preferences, names, ongoing
+//! projects) and writes them to the Store under the user's namespace `[user_id, "memories"]`,
+//! the one the memory tools read. The model sees the memories already stored and reuses their
+//! keys to update them, so a changed preference replaces the old one instead of piling up.
+//!
+//! Entries are stored like `remember` with [`MemoryValueSchema::Text`]:
+//! `{"text": ..., "saved_at": <unix secs>}`.
+//!
+//! **Interaction**: Set on [`ReactRunner::with_memory_writer`](super::ReactRunner::with_memory_writer)
+//! (or `REACT_MEMORY_EXTRACTION` in `ReactBuildConfig`); after each run it writes in the
+//! background, next to outcome tagging. Recalled with `recall` / `search_memories` of
+//! [`MemoryToolkit`](crate::tool_source::MemoryToolkit).
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::memory::{Namespace, SearchOptions, Store, StoreError};
+use crate::message::Message;
+use crate::state::ReActState;
+use crate::tools::memory::MemoryValueSchema;
+use crate::LlmClient;
+
+use super::outcome::last_turn;
+
+/// First namespace segment for runs without a `user_id`.
+pub const DEFAULT_MEMORY_USER: &str = "default";
+/// Second namespace segment of a user's memories: `[user_id, "memories"]`.
+pub const MEMORIES_NAMESPACE: &str = "memories";
+/// Default cap on the facts written per turn.
+pub const DEFAULT_MAX_MEMORIES_PER_TURN: usize = 5;
+
+/// Stored memories shown to the extraction model, so it can update them.
+const MAX_EXISTING_MEMORIES: usize = 50;
+/// Longest message content sent to the extraction model, in characters.
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Instruction for the extraction model.
+const EXTRACTION_PROMPT: &str = "You maintain long-term memory about a user. From the \
+conversation turn below, extract durable facts worth remembering in later conversations: \
+preferences, personal details, ongoing projects, decisions. Skip small talk, one-off requests \
+and anything only relevant to this turn. Reply with a JSON array and nothing else, each entry \
+{\"key\": \"<short_snake_case_key>\", \"text\": \"<the fact, one sentence>\"}. To update a \
+stored memory, reuse its key. Reply [] when there is nothing new.";
+
+/// A fact extracted from a turn, stored under `key` in the user's namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExtractedMemory {
+    /// Store key, snake_case; an existing key is overwritten.
+    pub key: String,
+    /// The fact in plain text.
+    pub text: String,
+}
+
+/// Extracts durable facts from finished turns and writes them to a Store; see the
+/// [module docs](self).
+pub struct MemoryWriter {
+    llm: Arc<dyn LlmClient>,
+    store: Arc<dyn Store>,
+    namespace: Option<Namespace>,
+    max_per_turn: usize,
+}
+
+impl MemoryWriter {
+    /// Writer asking `llm` (typically a small, cheap model) and writing to `store`.
+    pub fn new(llm: Arc<dyn LlmClient>, store: Arc<dyn Store>) -> Self {
+        Self {
+            llm,
+            store,
+            namespace: None,
+            max_per_turn: DEFAULT_MAX_MEMORIES_PER_TURN,
+        }
+    }
+
+    /// Writes every user's memories to `namespace` instead of `[user_id, "memories"]`.
+    pub fn with_namespace(mut self, namespace: Namespace) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// Writes at most `max` facts per turn (default [`DEFAULT_MAX_MEMORIES_PER_TURN`]).
+    pub fn with_max_per_turn(mut self, max: usize) -> Self {
+        self.max_per_turn = max;
+        self
+    }
+
+    /// Namespace of the memories of `user_id`: `[user_id, "memories"]`, or
+    /// `["default", "memories"]` without one.
+    pub fn namespace(&self, user_id: Option<&str>) -> Namespace {
+        self.namespace.clone().unwrap_or_else(|| {
+            vec![
+                user_id.unwrap_or(DEFAULT_MEMORY_USER).to_string(),
+                MEMORIES_NAMESPACE.to_string(),
+            ]
+        })
+    }
+
+    /// Extracts the facts of the last turn of `state` and writes them to the namespace of
+    /// `user_id`; returns what was written. Nothing is written for a run paused before its
+    /// tool calls, and a failing or unusable model reply is logged and writes nothing.
+    pub async fn write(
+        &self,
+        user_id: Option<&str>,
+        state: &ReActState,
+    ) -> Result<Vec<ExtractedMemory>, StoreError> {
+        let Some(transcript) = Self::transcript(state) else {
+            return Ok(Vec::new());
+        };
+        let namespace = self.namespace(user_id);
+        let existing = self
+            .store
+            .search(
+                &namespace,
+                SearchOptions::new().with_limit(MAX_EXISTING_MEMORIES),
+            )
+            .await?;
+        let stored: Vec<String> = existing
+            .iter()
+            .map(|hit| {
+                let text = MemoryValueSchema::Text.decode(hit.item.value.clone());
+                let text = text
+                    .as_str()
+                    .map_or_else(|| text.to_string(), str::to_string);
+                format!("- {}: {}", hit.item.key, text)
+            })
+            .collect();
+
+        let memories = self.extract(&transcript, &stored).await;
+        let items: Vec<(String, Value)> = memories
+            .iter()
+            .filter_map(|m| {
+                let value = MemoryValueSchema::Text.encode(Value::String(m.text.clone()));
+                value.ok().map(|v| (m.key.clone(), v))
+            })
+            .collect();
+        if !items.is_empty() {
+            self.store.put_many(&namespace, &items).await?;
+        }
+        Ok(memories)
+    }
+
+    /// Asks the model for the facts of `transcript`, given the `stored` memories.
+    async fn extract(&self, transcript: &str, stored: &[String]) -> Vec<ExtractedMemory> {
+        let stored = if stored.is_empty() {
+            "(none)".to_string()
+        } else {
+            stored.join("\n")
+        };
+        let messages = [
+            Message::system(EXTRACTION_PROMPT),
+            Message::user(format!(
+                "Stored memories:\n{}\n\nConversation turn:\n{}",
+                stored, transcript
+            )),
+        ];
+        match self.llm.invoke(&messages).await {
+            Ok(response) => match Self::parse(&response.content) {
+                Some(mut memories) => {
+                    memories.truncate(self.max_per_turn);
+                    memories
+                }
+                None => {
+                    tracing::warn!("memory extraction reply has no JSON array; nothing saved");
+                    Vec::new()
+                }
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "memory extraction failed; nothing saved");
+                Vec::new()
+            }
+        }
+    }
+
+    /// The user's request and the replies to it as `role: content` lines; tool calls and
+    /// results are left out. None when the run is paused or there is no user message.
+    fn transcript(state: &ReActState) -> Option<String> {
+        if !state.tool_calls.is_empty() {
+            return None;
+        }
+        let turn = last_turn(state);
+        let request = state.messages.len().checked_sub(turn.len() + 1)?;
+        let lines: Vec<String> = state.messages[request..]
+            .iter()
+            .filter(|m| matches!(m, Message::User(_) | Message::Assistant { .. }))
+            .filter(|m| !m.content().trim().is_empty())
+            .map(|m| {
+                let content: String = m.content().chars().take(MAX_MESSAGE_CHARS).collect();
+                format!("{}: {}", m.role(), content)
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Entries of the first JSON array of `reply`, keys normalized to snake_case; entries
+    /// without a key or text are dropped. None when there is no array.
+    fn parse(reply: &str) -> Option<Vec<ExtractedMemory>> {
+        let start = reply.find('[')?;
+        let end = reply.rfind(']')?;
+        let entries: Vec<Value> = serde_json::from_str(reply.get(start..=end)?).ok()?;
+        Some(
+            entries
+                .into_iter()
+                .filter_map(|e| serde_json::from_value::<ExtractedMemory>(e).ok())
+                .filter_map(|m| {
+                    let key = normalize_key(&m.key);
+                    let text = m.text.trim().to_string();
+                    (!key.is_empty() && !text.is_empty()).then_some(ExtractedMemory { key, text })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// `Favorite Color!` gives `favorite_color`.
+fn normalize_key(key: &str) -> String {
+    key.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use crate::MockLlm;
+
+    fn turn(user: &str, answer: &str) -> ReActState {
+        ReActState {
+            messages: vec![
+                Message::system("You are helpful."),
+                Message::user(user),
+                Message::assistant(answer),
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Facts from the model's reply are stored as text memories under the user's
+    /// namespace, keys normalized; the same key updates the stored fact.
+    #[tokio::test]
+    async fn writes_extracted_facts_to_user_namespace() {
+        let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+        let llm = MockLlm::with_no_tool_calls(
+            "```json\n[{\"key\": \"Favorite Drink\", \"text\": \"Prefers tea over coffee.\"}, \
+             {\"key\": \"\", \"text\": \"dropped\"}]\n```",
+        );
+        let writer = MemoryWriter::new(Arc::new(llm), store.clone());
+        let state = turn("I switched to tea, coffee keeps me up.", "Tea it is!");
+
+        let written = writer.write(Some("u1"), &state).await.unwrap();
+        assert_eq!(
+            written,
+            [ExtractedMemory {
+                key: "favorite_drink".into(),
+                text: "Prefers tea over coffee.".into()
+            }]
+        );
+        let namespace = vec!["u1".to_string(), "memories".to_string()];
+        let stored = store.get(&namespace, "favorite_drink").await.unwrap();
+        assert_eq!(stored.unwrap()["text"], "Prefers tea over coffee.");
+        assert_eq!(writer.namespace(None), ["default", "memories"]);
+    }
+
+    /// **Scenario**: An unusable reply or a paused run writes nothing; a cap limits facts per
+    /// turn.
+    #[tokio::test]
+    async fn skips_unusable_replies_and_paused_runs() {
+        let store: Arc<dyn Store> = Arc::new(InMemoryStore::new());
+        let namespace = vec!["u1".to_string(), "memories".to_string()];
+        let writer = MemoryWriter::new(
+            Arc::new(MockLlm::with_no_tool_calls("Nothing to remember.")),
+            store.clone(),
+        );
+        let state = turn("Hi", "Hello!");
+        assert!(writer.write(Some("u1"), &state).await.unwrap().is_empty());
+
+        let writer = MemoryWriter::new(
+            Arc::new(MockLlm::with_no_tool_calls(
+                r#"[{"key": "a", "text": "A."}, {"key": "b", "text": "B."}]"#,
+            )),
+            store.clone(),
+        )
+        .with_max_per_turn(1);
+        let mut paused = state.clone();
+        paused.tool_calls = vec![crate::state::ToolCall {
+            name: "get_time".into(),
+            arguments: "{}".into(),
+            id: None,
+        }];
+        assert!(writer.write(Some("u1"), &paused).await.unwrap().is_empty());
+        assert!(store.list(&namespace).await.unwrap().is_empty());
+
+        assert_eq!(writer.write(Some("u1"), &state).await.unwrap().len(), 1);
+        assert_eq!(store.list(&namespace).await.unwrap(), ["a"]);
+    }
+}
@@ -13,9 +13,36 @@
 //!   [`HandleToolErrors`] to customize error handling.
 //! - **[`ObserveNode`]**: Merges tool results into messages and clears `tool_calls`/`tool_results`;
 //!   increments turn count. Typically the last node before looping back to think or ending.
-//! - **[`ReactRunner`]**: Holds compiled graph, checkpointer, store, LLM, and tool source. Use
+//!   With [`ToolAttachments`], long tool outputs are stored in the Store and replayed as a
+//!   preview the model can expand with `fetch_attachment`.
+//! - **[`ReactRunner`]**: Holds compiled graph, checkpointer, store, LLM, and tool source; loops
+//!   observe → think while tools are called, up to `max_turns` rounds ([`MAX_REACT_TURNS`]). Use
 //!   [`run_react_graph`] or [`run_react_graph_stream`] to run; build state with
 //!   [`build_react_initial_state`].
+//! - **[`SamplingSchedule`]**: Temperature / `top_p` per phase (tool selection, final answer)
+//!   for [`ReactRunner::with_sampling_schedule`].
+//! - **[`BudgetPolicy`]**: Per-run limits on tokens, cost ([`ModelPriceTable`]) and tool calls
+//!   for [`ReactRunner::with_budget`]; runs over budget fail with [`RunError::BudgetExceeded`].
+//! - **[`UsageQuota`]**: Tokens per model per day in a Store, with daily caps per deployment
+//!   and per user for [`ReactRunner::with_usage_quota`]; refused runs fail with
+//!   [`RunError::QuotaExceeded`].
+//! - **[`OutcomeClassifier`]**: Labels finished runs ([`RunOutcome`]: answered, needed_tools,
+//!   failed_tool, refused, hallucination_suspected) by rules ([`RuleOutcomeClassifier`]) or a
+//!   cheap model ([`LlmOutcomeClassifier`]) for [`ReactRunner::with_outcome_classifier`].
+//! - **[`Experiment`]**: Two runners (variants `a` and `b`) compared on live traffic, each
+//!   thread or request assigned by a deterministic hash; runs are tagged with their
+//!   [`ExperimentVariant`] and their usage recorded per variant.
+//! - **[`HistoryCompressor`]**: Shrinks what ThinkNode sends to the LLM on long threads
+//!   ([`DropOldToolResults`], [`SummarizeOldTurns`] with [`PinnedMessages`], [`CompressorChain`])
+//!   for [`ReactRunner::with_history_compressor`]; checkpoints keep the full history.
+//! - **[`MemoryWriter`]**: Extracts durable facts and preferences from each finished turn with
+//!   a model and writes them to the user's memory namespace in the Store, for
+//!   [`ReactRunner::with_memory_writer`].
+//! - **[`Retriever`]**: Top-k documents from a vector store for the user's question, put before
+//!   think by [`RetrieveNode`] or [`ReactRunner::with_retriever`]; [`Retriever::tool`] lets the
+//!   model search again. A [`Reranker`] ([`LlmReranker`]) reorders the candidates by relevance.
+//! - **[`PromptProfile`]**: Built-in system prompts (strict-tools, conversational, coding-agent,
+//!   research-agent) with matching tool_choice defaults; [`REACT_SYSTEM_PROMPT`] is the default.
 //! - **[`tools_condition`]**: Conditional routing: if there are tool calls, go to act; else end.
 //!   Returns [`ToolsConditionResult`]; use [`.as_str()`](ToolsConditionResult::as_str) for node IDs.
 //!
@@ -39,18 +66,59 @@
 //! ```
 
 mod act_node;
+mod attachments;
+mod budget;
+mod error_template;
+mod experiment;
+mod history;
+mod memory_writer;
 mod observe_node;
+mod outcome;
+mod prompt_profile;
+mod quota;
+mod rerank;
+mod retrieve;
 mod runner;
+mod sampling;
 mod think_node;
 mod with_node_logging;
 
 pub use act_node::{
     ActNode, ErrorHandlerFn, HandleToolErrors, DEFAULT_EXECUTION_ERROR_TEMPLATE,
-    DEFAULT_TOOL_ERROR_TEMPLATE,
+    DEFAULT_MAX_PARALLEL_TOOL_CALLS, DEFAULT_TOOL_ERROR_TEMPLATE,
+};
+pub use attachments::{
+    ToolAttachments, DEFAULT_ATTACHMENT_NAMESPACE, DEFAULT_ATTACHMENT_PREVIEW,
+    DEFAULT_ATTACHMENT_THRESHOLD,
+};
+pub use budget::{BudgetPolicy, ModelPriceTable};
+pub use error_template::render_error_template;
+pub use experiment::{
+    Assignment, Experiment, ExperimentVariant, Variant, DEFAULT_EXPERIMENT_SHARE_B,
+    EXPERIMENT_TAG_PREFIX,
+};
+pub use history::{
+    CompressorChain, DropOldToolResults, HistoryCompressor, PinnedMessages, SummarizeOldTurns,
+    OMITTED_TOOL_RESULT, SUMMARY_PREFIX,
+};
+pub use memory_writer::{
+    ExtractedMemory, MemoryWriter, DEFAULT_MAX_MEMORIES_PER_TURN, DEFAULT_MEMORY_USER,
+    MEMORIES_NAMESPACE,
+};
+pub use observe_node::{ObserveNode, MAX_REACT_TURNS};
+pub use outcome::{LlmOutcomeClassifier, OutcomeClassifier, RuleOutcomeClassifier, RunOutcome};
+pub use prompt_profile::{
+    PromptProfile, CODING_AGENT_SYSTEM_PROMPT, CONVERSATIONAL_SYSTEM_PROMPT,
+    RESEARCH_AGENT_SYSTEM_PROMPT, STRICT_TOOLS_SYSTEM_PROMPT,
+};
+pub use quota::{QuotaExceeded, QuotaMeter, QuotaScope, UsageQuota, USAGE_NAMESPACE};
+pub use rerank::{LlmReranker, Reranker, DEFAULT_RERANK_CANDIDATES};
+pub use retrieve::{
+    RetrieveNode, RetrievedDocument, Retriever, DEFAULT_RETRIEVE_TOP_K, RETRIEVED_CONTEXT_PREFIX,
 };
-pub use observe_node::ObserveNode;
 pub use runner::{build_react_initial_state, run_react_graph, run_react_graph_stream, ReactRunner, RunError};
-pub use think_node::ThinkNode;
+pub use sampling::{SamplingSchedule, ThinkPhase, SAMPLING_SCHEDULE};
+pub use think_node::{ThinkNode, NEED_MORE_STEPS_MESSAGE};
 pub use with_node_logging::WithNodeLogging;
 
 use crate::state::ReActState;
@@ -145,6 +213,10 @@ mod tests {
             tool_calls: vec![],
             tool_results: vec![],
             turn_count: 0,
+            response_metadata: None,
+            usage: Default::default(),
+            final_answer: None,
+            ui_events: vec![],
         };
 
         let result = tools_condition(&state);
@@ -164,6 +236,10 @@ mod tests {
             }],
             tool_results: vec![],
             turn_count: 0,
+            response_metadata: None,
+            usage: Default::default(),
+            final_answer: None,
+            ui_events: vec![],
         };
 
         let result = tools_condition(&state);
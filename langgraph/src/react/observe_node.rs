@@ -2,46 +2,77 @@
 //!
 //! Design: docs/rust-langgraph/13-react-agent-design.md §8.3 stage 3.5–3.6.
 //! ObserveNode has no external dependencies, implements `Node<ReActState>`; run reads
-//! state.tool_results, appends them to state (as User messages so next Think sees context),
-//! then clears tool_calls and tool_results. Linear-chain phase does not return next-hop.
+//! state.tool_results, appends them to state (as Tool messages so next Think sees context),
+//! then clears tool_calls and tool_results. A round without tool calls ends the run, so its
+//! reply becomes `state.final_answer`. Linear-chain phase does not return next-hop.
+//! With [`ToolAttachments`] set, long tool outputs are stored as attachments and the Tool
+//! message carries a preview instead.
 
 use async_trait::async_trait;
 
+use super::ToolAttachments;
 use crate::error::AgentError;
 use crate::graph::Next;
 use crate::message::Message;
 use crate::state::ReActState;
 use crate::Node;
 
+/// Default maximum number of ReAct loop rounds (observe passes) before forcing End.
+pub const MAX_REACT_TURNS: u32 = 10;
+
 /// Observe node: one ReAct step that merges tool results into state and clears tool_*.
 ///
-/// Reads `state.tool_results`, appends each result to messages as a User message
-/// (e.g. "Tool get_time returned: 12:00") so the next Think round has context;
+/// Reads `state.tool_results`, appends each result to messages as a `Message::Tool`
+/// (matched to the assistant's tool call by `call_id`) so the next Think round has context;
 /// then clears tool_calls and tool_results. When `enable_loop` is false (linear chain),
 /// returns `Next::Continue` so the runner stops after this node if it is last. When
 /// `enable_loop` is true, returns `Next::Node("think")` when this round had tool_calls
-/// (ReAct loop), else `Next::End`.
+/// (ReAct loop), else `Next::End`; after `max_turns` rounds it returns `Next::End`.
 ///
-/// Maximum number of ReAct loop rounds (observe passes) before forcing End.
-pub const MAX_REACT_TURNS: u32 = 10;
-
-/// **Interaction**: Implements `Node<ReActState>`; used by StateGraph. No external
-/// deps; reads ReActState.tool_results, writes ReActState.messages and clears
-/// tool_calls/tool_results.
+/// **Interaction**: Implements `Node<ReActState>`; used by StateGraph. Reads
+/// ReActState.tool_results, writes ReActState.messages and clears tool_calls/tool_results;
+/// writes long outputs to the Store only with [`with_attachments`](Self::with_attachments).
 pub struct ObserveNode {
     /// When true, return Node("think") to loop; when false, return Continue (linear chain).
     enable_loop: bool,
+    /// Rounds after which the loop ends even if tools were called.
+    max_turns: u32,
+    /// Where long tool outputs go instead of the messages; None keeps them inline.
+    attachments: Option<ToolAttachments>,
 }
 
 impl ObserveNode {
     /// Creates an Observe node for linear chain (one round): returns Next::Continue.
     pub fn new() -> Self {
-        Self { enable_loop: false }
+        Self {
+            enable_loop: false,
+            max_turns: MAX_REACT_TURNS,
+            attachments: None,
+        }
     }
 
     /// Creates an Observe node for multi-round ReAct: returns Node("think") or End.
     pub fn with_loop() -> Self {
-        Self { enable_loop: true }
+        Self {
+            enable_loop: true,
+            max_turns: MAX_REACT_TURNS,
+            attachments: None,
+        }
+    }
+
+    /// Sets the maximum number of loop rounds (default [`MAX_REACT_TURNS`]); at least 1.
+    pub fn with_max_turns(mut self, max_turns: u32) -> Self {
+        self.max_turns = max_turns.max(1);
+        self
+    }
+
+    /// Stores tool outputs longer than the attachments' threshold in the Store and appends a
+    /// preview naming the attachment instead. Register
+    /// [`ToolAttachments::fetch_tool`] with the act node so the model can read the rest. When
+    /// storing fails, the full output is kept in the message.
+    pub fn with_attachments(mut self, attachments: ToolAttachments) -> Self {
+        self.attachments = Some(attachments);
+        self
     }
 }
 
@@ -51,36 +82,56 @@ impl Default for ObserveNode {
     }
 }
 
+/// Content of the last non-empty assistant message after the last user message.
+fn answer_of_run(messages: &[Message]) -> Option<String> {
+    messages
+        .iter()
+        .rev()
+        .take_while(|m| !matches!(m, Message::User(_)))
+        .find_map(|m| match m {
+            Message::Assistant { content, .. } if !content.is_empty() => Some(content.clone()),
+            _ => None,
+        })
+}
+
 #[async_trait]
 impl Node<ReActState> for ObserveNode {
     fn id(&self) -> &str {
         "observe"
     }
 
-    /// Merges tool_results into messages (one User message per result), clears tool_*.
+    /// Merges tool_results into messages (one Tool message per result), clears tool_*.
     /// Returns Next::Node("think") when this round had tool_calls (ReAct loop), else Next::End.
     async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
         let had_tool_calls = !state.tool_calls.is_empty();
         let mut messages = state.messages;
         for tr in &state.tool_results {
-            let name = tr
-                .name
-                .as_deref()
-                .or(tr.call_id.as_deref())
-                .unwrap_or("tool");
-            messages.push(Message::User(format!(
-                "Tool {} returned: {}",
-                name, tr.content
-            )));
+            let content = match &self.attachments {
+                Some(attachments) => attachments.offload(tr).await.unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "failed to store tool output as attachment");
+                    tr.content.clone()
+                }),
+                None => tr.content.clone(),
+            };
+            messages.push(Message::tool(tr.call_id.clone(), tr.name.clone(), content));
         }
+        let final_answer = if had_tool_calls {
+            None
+        } else {
+            answer_of_run(&messages)
+        };
         let next_turn = state.turn_count.saturating_add(1);
         let new_state = ReActState {
             messages,
             tool_calls: vec![],
             tool_results: vec![],
             turn_count: next_turn,
+            response_metadata: state.response_metadata,
+            usage: state.usage,
+            final_answer,
+            ui_events: state.ui_events,
         };
-        let next = if self.enable_loop && next_turn >= MAX_REACT_TURNS {
+        let next = if self.enable_loop && next_turn >= self.max_turns {
             Next::End
         } else if self.enable_loop && had_tool_calls {
             Next::Node("think".to_string())
@@ -0,0 +1,349 @@
+//! Outcome labels for finished runs: answered, needed tools, failed tool, refused, or suspected
+//! of hallucination.
+//!
+//! An [`OutcomeClassifier`] looks at the last turn of a run (the messages after the latest user
+//! message) and returns the [`RunOutcome`]s that apply; a run can have several.
+//! [`RuleOutcomeClassifier`] uses fixed rules and costs nothing; [`LlmOutcomeClassifier`] asks
+//! a (cheap) model and falls back to the rules when the reply is unusable.
+//!
+//! **Interaction**: Set on [`ReactRunner::with_outcome_classifier`](super::ReactRunner::with_outcome_classifier);
+//! after each run the labels are added to the thread's latest checkpoint with
+//! [`Checkpointer::tag_checkpoint`](crate::memory::Checkpointer::tag_checkpoint) and read back
+//! from [`CheckpointMetadata::tags`](crate::memory::CheckpointMetadata::tags).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::message::Message;
+use crate::state::ReActState;
+use crate::LlmClient;
+
+/// Outcome label of a run, stored as a checkpoint tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunOutcome {
+    /// The run ended with an answer that is not a refusal.
+    Answered,
+    /// The run called at least one tool.
+    NeededTools,
+    /// At least one tool call returned an error.
+    FailedTool,
+    /// The answer declines the request.
+    Refused,
+    /// The answer states things (e.g. links) found neither in the conversation nor in tool
+    /// results.
+    HallucinationSuspected,
+}
+
+impl RunOutcome {
+    /// Every label, in the order classifiers report them.
+    pub const ALL: [RunOutcome; 5] = [
+        Self::Answered,
+        Self::NeededTools,
+        Self::FailedTool,
+        Self::Refused,
+        Self::HallucinationSuspected,
+    ];
+
+    /// Tag stored in checkpoint metadata, e.g. `needed_tools`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Answered => "answered",
+            Self::NeededTools => "needed_tools",
+            Self::FailedTool => "failed_tool",
+            Self::Refused => "refused",
+            Self::HallucinationSuspected => "hallucination_suspected",
+        }
+    }
+
+    /// The label for a tag; None for tags that are not outcome labels.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|o| o.as_str() == tag)
+    }
+}
+
+/// Labels a finished run.
+#[async_trait]
+pub trait OutcomeClassifier: Send + Sync {
+    /// Outcomes of the run that produced `state`; empty when nothing can be said (e.g. the run
+    /// is paused before its tool calls).
+    async fn classify(&self, state: &ReActState) -> Vec<RunOutcome>;
+}
+
+/// Phrases that start or make up a refusal, lowercase.
+const REFUSAL_PHRASES: &[&str] = &[
+    "i can't help",
+    "i cannot help",
+    "i can't assist",
+    "i cannot assist",
+    "i won't be able to",
+    "i'm unable to",
+    "i am unable to",
+    "i'm not able to",
+    "i am not able to",
+    "i must decline",
+    "i can't provide",
+    "i cannot provide",
+];
+
+/// Messages of the last turn: everything after the latest user message.
+pub(super) fn last_turn(state: &ReActState) -> &[Message] {
+    let start = state
+        .messages
+        .iter()
+        .rposition(|m| matches!(m, Message::User(_)))
+        .map_or(0, |i| i + 1);
+    &state.messages[start..]
+}
+
+/// URLs in `text`, without trailing punctuation.
+fn urls(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            word.find("http://")
+                .or_else(|| word.find("https://"))
+                .map(|i| &word[i..])
+        })
+        .map(|url| url.trim_end_matches(|c: char| ".,;:!?)]}>\"'".contains(c)))
+}
+
+/// Classifier based on fixed rules.
+///
+/// - `needed_tools`: the turn has tool messages;
+/// - `failed_tool`: one of them starts with `Error` (ActNode's error templates, see
+///   [`HandleToolErrors`](super::HandleToolErrors));
+/// - `refused`: the answer contains a refusal phrase ("I can't help", "I'm unable to", ...);
+/// - `hallucination_suspected`: the answer has a URL that no message of the thread contains;
+/// - `answered`: the answer is not empty and not a refusal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleOutcomeClassifier;
+
+impl RuleOutcomeClassifier {
+    /// Outcomes of `state` by the rules; synchronous so other classifiers can fall back to it.
+    pub fn classify_state(state: &ReActState) -> Vec<RunOutcome> {
+        if !state.tool_calls.is_empty() {
+            return Vec::new();
+        }
+        let turn = last_turn(state);
+        let tool_outputs: Vec<&str> = turn
+            .iter()
+            .filter(|m| matches!(m, Message::Tool { .. }))
+            .map(Message::content)
+            .collect();
+        let answer = state
+            .final_answer()
+            .map(str::to_string)
+            .or_else(|| state.last_assistant_reply())
+            .unwrap_or_default();
+        let lower = answer.to_lowercase();
+        let refused = REFUSAL_PHRASES.iter().any(|p| lower.contains(p));
+        let known = |url: &str| {
+            state
+                .messages
+                .iter()
+                .filter(|m| !matches!(m, Message::Assistant { .. }))
+                .any(|m| m.content().contains(url))
+        };
+
+        let mut outcomes = Vec::new();
+        if !answer.trim().is_empty() && !refused {
+            outcomes.push(RunOutcome::Answered);
+        }
+        if !tool_outputs.is_empty() {
+            outcomes.push(RunOutcome::NeededTools);
+        }
+        if tool_outputs
+            .iter()
+            .any(|t| t.trim_start().starts_with("Error"))
+        {
+            outcomes.push(RunOutcome::FailedTool);
+        }
+        if refused {
+            outcomes.push(RunOutcome::Refused);
+        }
+        if urls(&answer).any(|url| !known(url)) {
+            outcomes.push(RunOutcome::HallucinationSuspected);
+        }
+        outcomes
+    }
+}
+
+#[async_trait]
+impl OutcomeClassifier for RuleOutcomeClassifier {
+    async fn classify(&self, state: &ReActState) -> Vec<RunOutcome> {
+        Self::classify_state(state)
+    }
+}
+
+/// Instruction for [`LlmOutcomeClassifier`].
+const CLASSIFIER_PROMPT: &str = "You label the outcome of an assistant's turn. Reply with a \
+JSON array of the labels that apply and nothing else. Labels: \"answered\" (the assistant \
+answered the request), \"needed_tools\" (tools were called), \"failed_tool\" (a tool call \
+returned an error), \"refused\" (the assistant declined the request), \
+\"hallucination_suspected\" (the answer states facts, numbers or links not supported by the \
+conversation or tool results).";
+
+/// Longest message content sent to the classifier model, in characters.
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Classifier that asks a model, typically a small and cheap one.
+///
+/// The model gets the user's request and the turn's messages and replies with a JSON array of
+/// labels; unknown labels are dropped. When the call fails or the reply has no array, the
+/// run is classified by [`RuleOutcomeClassifier`] instead.
+pub struct LlmOutcomeClassifier {
+    llm: Arc<dyn LlmClient>,
+}
+
+impl LlmOutcomeClassifier {
+    /// Classifier asking `llm`.
+    pub fn new(llm: Arc<dyn LlmClient>) -> Self {
+        Self { llm }
+    }
+
+    /// The request and the turn as `role: content` lines.
+    fn transcript(state: &ReActState) -> String {
+        let turn = last_turn(state);
+        let request = state.messages.len() - turn.len();
+        state.messages[request.saturating_sub(1)..]
+            .iter()
+            .filter(|m| !matches!(m, Message::System(_)))
+            .map(|m| {
+                let content: String = m.content().chars().take(MAX_MESSAGE_CHARS).collect();
+                format!("{}: {}", m.role(), content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Labels in the first JSON array of `reply`; None when there is none.
+    fn parse(reply: &str) -> Option<Vec<RunOutcome>> {
+        let start = reply.find('[')?;
+        let end = reply.rfind(']')?;
+        let tags: Vec<String> = serde_json::from_str(reply.get(start..=end)?).ok()?;
+        Some(
+            tags.iter()
+                .filter_map(|t| RunOutcome::from_tag(t.trim()))
+                .collect(),
+        )
+    }
+}
+
+#[async_trait]
+impl OutcomeClassifier for LlmOutcomeClassifier {
+    async fn classify(&self, state: &ReActState) -> Vec<RunOutcome> {
+        if !state.tool_calls.is_empty() {
+            return Vec::new();
+        }
+        let messages = [
+            Message::system(CLASSIFIER_PROMPT),
+            Message::user(Self::transcript(state)),
+        ];
+        match self.llm.invoke(&messages).await {
+            Ok(response) => match Self::parse(&response.content) {
+                Some(outcomes) => outcomes,
+                None => {
+                    tracing::warn!("outcome classifier reply has no labels; using rules");
+                    RuleOutcomeClassifier::classify_state(state)
+                }
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "outcome classifier failed; using rules");
+                RuleOutcomeClassifier::classify_state(state)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockLlm;
+
+    fn state(messages: Vec<Message>, answer: &str) -> ReActState {
+        ReActState {
+            messages,
+            final_answer: Some(answer.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn tool(content: &str) -> Message {
+        Message::tool(Some("c1".into()), Some("search".into()), content)
+    }
+
+    /// **Scenario**: The rules label tool use, tool errors, refusals and unknown links, and
+    /// only look at tools of the last turn.
+    #[test]
+    fn rules_label_last_turn() {
+        let earlier = vec![
+            Message::user("What is new?"),
+            tool("Error: timeout"),
+            Message::assistant("Nothing."),
+        ];
+        let answered = state(
+            [
+                earlier.clone(),
+                vec![
+                    Message::user("Docs?"),
+                    tool("See https://docs.rs/tokio"),
+                    Message::assistant("Here: https://docs.rs/tokio."),
+                ],
+            ]
+            .concat(),
+            "Here: https://docs.rs/tokio.",
+        );
+        assert_eq!(
+            RuleOutcomeClassifier::classify_state(&answered),
+            [RunOutcome::Answered, RunOutcome::NeededTools]
+        );
+
+        let failed = state(
+            vec![
+                Message::user("Docs?"),
+                tool("Error executing tool 'search' with kwargs {} with error:\n down"),
+                Message::assistant("See https://example.com/made-up"),
+            ],
+            "See https://example.com/made-up",
+        );
+        assert_eq!(
+            RuleOutcomeClassifier::classify_state(&failed),
+            [
+                RunOutcome::Answered,
+                RunOutcome::NeededTools,
+                RunOutcome::FailedTool,
+                RunOutcome::HallucinationSuspected
+            ]
+        );
+
+        let refused = state(
+            [earlier, vec![Message::user("Hack it")]].concat(),
+            "Sorry, I can't help with that.",
+        );
+        assert_eq!(
+            RuleOutcomeClassifier::classify_state(&refused),
+            [RunOutcome::Refused]
+        );
+    }
+
+    /// **Scenario**: The model's JSON labels are used, unknown ones dropped; a reply without
+    /// an array falls back to the rules.
+    #[tokio::test]
+    async fn llm_labels_with_rule_fallback() {
+        let run = state(
+            vec![Message::user("Hi"), Message::assistant("Hello!")],
+            "Hello!",
+        );
+        let classifier = LlmOutcomeClassifier::new(Arc::new(MockLlm::with_no_tool_calls(
+            "```json\n[\"answered\", \"hallucination_suspected\", \"polite\"]\n```",
+        )));
+        assert_eq!(
+            classifier.classify(&run).await,
+            [RunOutcome::Answered, RunOutcome::HallucinationSuspected]
+        );
+
+        let classifier =
+            LlmOutcomeClassifier::new(Arc::new(MockLlm::with_no_tool_calls("It answered.")));
+        assert_eq!(classifier.classify(&run).await, [RunOutcome::Answered]);
+    }
+}
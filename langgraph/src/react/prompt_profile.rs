@@ -0,0 +1,176 @@
+//! Built-in ReAct prompt profiles: system prompt plus tool_choice default.
+//!
+//! [`PromptProfile::Default`] is [`REACT_SYSTEM_PROMPT`]; the others tune the agent for a
+//! use case. Selected via `REACT_PROMPT_PROFILE` ([`ReactBuildConfig`](crate::ReactBuildConfig))
+//! or the CLI `--prompt-profile` flag. An explicit system prompt always wins over the profile.
+//! See docs/rust-langgraph/17-react-prompt-practices.md.
+
+use crate::llm::ToolChoiceMode;
+
+use super::REACT_SYSTEM_PROMPT;
+
+/// System prompt for [`PromptProfile::StrictTools`]: answer only from tool output.
+pub const STRICT_TOOLS_SYSTEM_PROMPT: &str = r#"You are a tool-grounded agent following the ReAct pattern.
+
+RULES:
+0. LANGUAGE: Reply in the same language the user used.
+1. Never state facts from memory. Every factual claim in your answer must come from a tool result in this conversation.
+2. If no available tool can provide the needed information, say so plainly instead of guessing.
+3. Call one tool at a time, read its OBSERVATION, then decide whether another call is needed.
+4. In the final answer, state which tool each fact came from.
+
+Only reasoning, arithmetic on tool results, and formatting may be done without tools."#;
+
+/// System prompt for [`PromptProfile::Conversational`]: plain chat assistant.
+pub const CONVERSATIONAL_SYSTEM_PROMPT: &str = r#"You are a friendly, concise conversational assistant.
+
+- Reply in the same language the user used.
+- Answer directly from your own knowledge; keep replies short unless the user asks for detail.
+- Ask a clarifying question when the request is ambiguous.
+- Be honest about uncertainty; do not invent facts, links, or quotes."#;
+
+/// System prompt for [`PromptProfile::CodingAgent`]: software engineering tasks.
+pub const CODING_AGENT_SYSTEM_PROMPT: &str = r#"You are a careful software engineering agent following the ReAct pattern.
+
+RULES:
+0. LANGUAGE: Reply in the same language the user used; keep code, identifiers and commands in their original form.
+1. THOUGHT first: restate the goal, then decide whether you need to inspect files, run commands, or look up documentation.
+2. Prefer inspecting real code and command output over assumptions. Use tools to verify before you claim something works.
+3. Make the smallest change that solves the problem and matches the surrounding code style.
+4. When you show code, give complete, compilable snippets and say where they go.
+5. After each OBSERVATION, check for errors or failing output and adjust.
+
+FINAL_ANSWER: summarize what you changed or found, and list any follow-up the user must do."#;
+
+/// System prompt for [`PromptProfile::ResearchAgent`]: multi-step search and synthesis.
+pub const RESEARCH_AGENT_SYSTEM_PROMPT: &str = r#"You are a research agent following the ReAct pattern.
+
+RULES:
+0. LANGUAGE: Reply in the same language the user used.
+1. THOUGHT first: break the question into sub-questions and decide which need fresh or external information.
+2. Use search and fetch tools for anything time-sensitive, niche, or disputed. Prefer primary sources.
+3. Cross-check important claims against at least two independent sources when possible.
+4. After each OBSERVATION, note what you learned, what is still missing, and whether sources disagree.
+5. Stop searching when additional calls are unlikely to change the answer.
+
+FINAL_ANSWER: a structured summary with key findings first, then details. Cite sources (title or URL) for each claim and flag remaining uncertainty."#;
+
+/// Built-in prompt profile: a system prompt with a matching tool_choice default.
+///
+/// Parse from config strings with [`FromStr`](std::str::FromStr) (e.g. `"research"`,
+/// `"strict-tools"`); [`PromptProfile::as_str`] returns the canonical name.
+///
+/// **Interaction**: Read by `ReactBuildConfig::effective_system_prompt` and
+/// `build_default_llm`; the CLI maps `--prompt-profile` to it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PromptProfile {
+    /// General ReAct agent ([`REACT_SYSTEM_PROMPT`]); tool_choice left to the API default.
+    #[default]
+    Default,
+    /// Answers only from tool output; tool_choice `auto`.
+    StrictTools,
+    /// Plain chat without tools; tool_choice `none`.
+    Conversational,
+    /// Software engineering tasks; tool_choice `auto`.
+    CodingAgent,
+    /// Multi-step search and synthesis with citations; tool_choice `auto`.
+    ResearchAgent,
+}
+
+impl PromptProfile {
+    /// All built-in profiles, in display order.
+    pub const ALL: [PromptProfile; 5] = [
+        PromptProfile::Default,
+        PromptProfile::StrictTools,
+        PromptProfile::Conversational,
+        PromptProfile::CodingAgent,
+        PromptProfile::ResearchAgent,
+    ];
+
+    /// Canonical name, as accepted by `FromStr` and shown in config summaries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::StrictTools => "strict-tools",
+            Self::Conversational => "conversational",
+            Self::CodingAgent => "coding-agent",
+            Self::ResearchAgent => "research-agent",
+        }
+    }
+
+    /// System prompt for this profile.
+    pub fn system_prompt(&self) -> &'static str {
+        match self {
+            Self::Default => REACT_SYSTEM_PROMPT,
+            Self::StrictTools => STRICT_TOOLS_SYSTEM_PROMPT,
+            Self::Conversational => CONVERSATIONAL_SYSTEM_PROMPT,
+            Self::CodingAgent => CODING_AGENT_SYSTEM_PROMPT,
+            Self::ResearchAgent => RESEARCH_AGENT_SYSTEM_PROMPT,
+        }
+    }
+
+    /// Default tool_choice for this profile; `None` leaves the API default.
+    /// An explicit tool_choice from config or CLI overrides it.
+    pub fn tool_choice(&self) -> Option<ToolChoiceMode> {
+        match self {
+            Self::Default => None,
+            Self::Conversational => Some(ToolChoiceMode::None),
+            Self::StrictTools | Self::CodingAgent | Self::ResearchAgent => {
+                Some(ToolChoiceMode::Auto)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PromptProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PromptProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "default" | "react" => Ok(Self::Default),
+            "strict-tools" | "strict" => Ok(Self::StrictTools),
+            "conversational" | "chat" => Ok(Self::Conversational),
+            "coding-agent" | "coding" => Ok(Self::CodingAgent),
+            "research-agent" | "research" => Ok(Self::ResearchAgent),
+            _ => Err(format!(
+                "unknown prompt profile: {} (use default, strict-tools, conversational, coding-agent, or research-agent)",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Canonical names and short aliases parse; as_str round-trips.
+    #[test]
+    fn prompt_profile_from_str_accepts_names_and_aliases() {
+        for p in PromptProfile::ALL {
+            assert_eq!(p.as_str().parse::<PromptProfile>().unwrap(), p);
+        }
+        assert_eq!("research".parse::<PromptProfile>().unwrap(), PromptProfile::ResearchAgent);
+        assert_eq!("Coding_Agent".parse::<PromptProfile>().unwrap(), PromptProfile::CodingAgent);
+        assert_eq!("strict".parse::<PromptProfile>().unwrap(), PromptProfile::StrictTools);
+        assert!("pirate".parse::<PromptProfile>().unwrap_err().contains("unknown prompt profile"));
+    }
+
+    /// **Scenario**: Default profile keeps REACT_SYSTEM_PROMPT and API tool_choice; conversational disables tools.
+    #[test]
+    fn prompt_profile_prompts_and_tool_choice() {
+        assert_eq!(PromptProfile::default().system_prompt(), REACT_SYSTEM_PROMPT);
+        assert_eq!(PromptProfile::Default.tool_choice(), None);
+        assert_eq!(PromptProfile::Conversational.tool_choice(), Some(ToolChoiceMode::None));
+        assert_eq!(PromptProfile::ResearchAgent.tool_choice(), Some(ToolChoiceMode::Auto));
+        for p in PromptProfile::ALL {
+            assert!(!p.system_prompt().is_empty());
+        }
+    }
+}
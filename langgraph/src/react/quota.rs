@@ -0,0 +1,430 @@
+//! Daily usage quotas: tokens per model per day, kept in a [`Store`], with daily caps.
+//!
+//! A [`UsageQuota`] adds up the tokens every run uses, per model and UTC day, for the whole
+//! deployment and for each `user_id`. With a cap set ([`with_daily_tokens`] for the deployment,
+//! [`with_daily_tokens_per_user`] per user), a run whose deployment or user already used its
+//! day's tokens is refused before it starts with [`QuotaExceeded`], whose message is a friendly
+//! reply for the user. Runs in progress are not stopped, so a day's total can end slightly
+//! above the cap. Totals live in the store under `["usage", "<YYYY-MM-DD>"]` (deployment) and
+//! `["usage", "<YYYY-MM-DD>", "users", "<user_id>"]`, one key per model, so they survive
+//! restarts and are easy to inspect. Runners of an A/B [`Experiment`] also record under
+//! `["usage", "<YYYY-MM-DD>", "experiments", "<experiment>", "<variant>"]`
+//! ([`experiment_usage`]); those totals count towards no cap.
+//!
+//! Store errors never block a run: the quota is then not enforced or the usage not recorded,
+//! with a warning. Updates are serialized within one process; processes sharing a store may
+//! lose concurrent updates.
+//!
+//! **Interaction**: Set with `ReactRunner::with_usage_quota`, or from `REACT_DAILY_TOKEN_CAP` /
+//! `REACT_DAILY_USER_TOKEN_CAP` via `ReactBuildConfig::usage_quota`. Each run's [`QuotaMeter`]
+//! is a [`CallbackHandler`] counting `on_llm_end` usage; langgraph-server answers 429 with
+//! the message.
+//!
+//! [`with_daily_tokens`]: UsageQuota::with_daily_tokens
+//! [`with_daily_tokens_per_user`]: UsageQuota::with_daily_tokens_per_user
+//! [`experiment_usage`]: UsageQuota::experiment_usage
+//! [`Experiment`]: super::Experiment
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::callbacks::CallbackHandler;
+use crate::llm::{LlmResponse, LlmUsage};
+use crate::memory::{Namespace, Store, StoreError};
+
+use super::ExperimentVariant;
+
+/// First namespace segment of the usage totals.
+pub const USAGE_NAMESPACE: &str = "usage";
+
+/// Model name recorded for replies that name no model when the quota has none configured.
+const UNKNOWN_MODEL: &str = "unknown";
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Whose cap a run hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaScope {
+    /// The cap of the whole deployment.
+    Deployment,
+    /// The cap of the run's `user_id`.
+    User,
+}
+
+/// A run refused because today's tokens are used up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaExceeded {
+    pub scope: QuotaScope,
+    /// Daily cap in tokens.
+    pub limit: u64,
+    /// Tokens used today.
+    pub used: u64,
+    /// Seconds until the quota resets at midnight UTC.
+    pub resets_in_secs: u64,
+}
+
+impl fmt::Display for QuotaExceeded {
+    /// The reply shown to the user.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let who = match self.scope {
+            QuotaScope::User => "You've used up your",
+            QuotaScope::Deployment => "This assistant has used up its",
+        };
+        let minutes = self.resets_in_secs.div_ceil(60);
+        write!(
+            f,
+            "{} usage allowance for today. It resets at midnight UTC, in about {}h {:02}m; \
+             please try again then.",
+            who,
+            minutes / 60,
+            minutes % 60
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+/// Tracks tokens per model per day and enforces daily caps; see the [module docs](self).
+pub struct UsageQuota {
+    store: Arc<dyn Store>,
+    daily_tokens: Option<u64>,
+    daily_tokens_per_user: Option<u64>,
+    model: Option<String>,
+    /// Serializes the read-modify-write of totals.
+    write: tokio::sync::Mutex<()>,
+}
+
+impl fmt::Debug for UsageQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageQuota")
+            .field("daily_tokens", &self.daily_tokens)
+            .field("daily_tokens_per_user", &self.daily_tokens_per_user)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl UsageQuota {
+    /// Records usage in `store` without caps; add them with the `with_daily_*` methods.
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            daily_tokens: None,
+            daily_tokens_per_user: None,
+            model: None,
+            write: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Refuses runs once the deployment used `max` tokens today.
+    pub fn with_daily_tokens(mut self, max: u64) -> Self {
+        self.daily_tokens = Some(max);
+        self
+    }
+
+    /// Refuses runs of a `user_id` once that user used `max` tokens today.
+    pub fn with_daily_tokens_per_user(mut self, max: u64) -> Self {
+        self.daily_tokens_per_user = Some(max);
+        self
+    }
+
+    /// Model recorded for replies that do not name the model that served them.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    fn namespace(day: &str, user_id: Option<&str>) -> Namespace {
+        let mut ns = vec![USAGE_NAMESPACE.to_string(), day.to_string()];
+        if let Some(user_id) = user_id {
+            ns.extend(["users".to_string(), user_id.to_string()]);
+        }
+        ns
+    }
+
+    fn experiment_namespace(day: &str, variant: &ExperimentVariant) -> Namespace {
+        let mut ns = Self::namespace(day, None);
+        ns.extend([
+            "experiments".to_string(),
+            variant.experiment.clone(),
+            variant.variant.to_string(),
+        ]);
+        ns
+    }
+
+    /// Tokens per model on `day` (`YYYY-MM-DD`, UTC), of the deployment or of `user_id`.
+    pub async fn usage(
+        &self,
+        day: &str,
+        user_id: Option<&str>,
+    ) -> Result<BTreeMap<String, LlmUsage>, StoreError> {
+        self.read(&Self::namespace(day, user_id)).await
+    }
+
+    /// Tokens per model on `day` (`YYYY-MM-DD`, UTC) of the runs of an experiment variant.
+    pub async fn experiment_usage(
+        &self,
+        day: &str,
+        variant: &ExperimentVariant,
+    ) -> Result<BTreeMap<String, LlmUsage>, StoreError> {
+        self.read(&Self::experiment_namespace(day, variant)).await
+    }
+
+    async fn read(&self, ns: &Namespace) -> Result<BTreeMap<String, LlmUsage>, StoreError> {
+        let mut usage = BTreeMap::new();
+        for model in self.store.list(ns).await? {
+            if let Some(value) = self.store.get(ns, &model).await? {
+                usage.insert(model, serde_json::from_value(value)?);
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Whether a run of `user_id` may start now; the first cap already reached otherwise.
+    pub async fn check(&self, user_id: Option<&str>) -> Result<Option<QuotaExceeded>, StoreError> {
+        let now = now_secs();
+        let day = utc_date(now);
+        let resets_in_secs = SECS_PER_DAY - now % SECS_PER_DAY;
+        let caps = [
+            (QuotaScope::Deployment, self.daily_tokens, None),
+            (QuotaScope::User, self.daily_tokens_per_user, user_id),
+        ];
+        for (scope, limit, user) in caps {
+            let Some(limit) = limit else { continue };
+            if scope == QuotaScope::User && user.is_none() {
+                continue;
+            }
+            let used = total_tokens(&self.usage(&day, user).await?);
+            if used >= limit {
+                return Ok(Some(QuotaExceeded {
+                    scope,
+                    limit,
+                    used,
+                    resets_in_secs,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Counter for one run; pass its usage to [`record`](Self::record) when the run ends.
+    pub fn meter(&self) -> Arc<QuotaMeter> {
+        Arc::new(QuotaMeter {
+            model: self.model.clone(),
+            usage: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Adds `usage` (tokens per model) to today's totals of the deployment and of `user_id`.
+    pub async fn record(
+        &self,
+        user_id: Option<&str>,
+        usage: &BTreeMap<String, LlmUsage>,
+    ) -> Result<(), StoreError> {
+        if usage.is_empty() {
+            return Ok(());
+        }
+        let day = utc_date(now_secs());
+        let _write = self.write.lock().await;
+        let scopes = [None].into_iter().chain(user_id.map(Some));
+        for user in scopes {
+            self.add(&Self::namespace(&day, user), usage).await?;
+        }
+        Ok(())
+    }
+
+    /// Adds `usage` (tokens per model) to today's totals of an experiment variant.
+    pub async fn record_experiment(
+        &self,
+        variant: &ExperimentVariant,
+        usage: &BTreeMap<String, LlmUsage>,
+    ) -> Result<(), StoreError> {
+        if usage.is_empty() {
+            return Ok(());
+        }
+        let day = utc_date(now_secs());
+        let _write = self.write.lock().await;
+        self.add(&Self::experiment_namespace(&day, variant), usage)
+            .await
+    }
+
+    /// Adds `usage` to the totals in `ns`; callers hold `write`.
+    async fn add(
+        &self,
+        ns: &Namespace,
+        usage: &BTreeMap<String, LlmUsage>,
+    ) -> Result<(), StoreError> {
+        for (model, run_usage) in usage {
+            let mut total: LlmUsage = match self.store.get(ns, model).await? {
+                Some(value) => serde_json::from_value(value)?,
+                None => LlmUsage::default(),
+            };
+            total.add(run_usage);
+            self.store
+                .put(ns, model, &serde_json::to_value(&total)?)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Tokens one run used per model, counted from `on_llm_end`.
+#[derive(Debug)]
+pub struct QuotaMeter {
+    model: Option<String>,
+    usage: Mutex<BTreeMap<String, LlmUsage>>,
+}
+
+impl QuotaMeter {
+    /// Tokens counted so far, per model.
+    pub fn usage(&self) -> BTreeMap<String, LlmUsage> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl CallbackHandler for QuotaMeter {
+    fn on_llm_end(&self, _run_id: &str, response: &LlmResponse) {
+        let Some(usage) = &response.usage else { return };
+        let model = response
+            .metadata
+            .model
+            .as_deref()
+            .or(self.model.as_deref())
+            .unwrap_or(UNKNOWN_MODEL);
+        let mut totals = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        totals.entry(model.to_string()).or_default().add(usage);
+    }
+}
+
+fn total_tokens(usage: &BTreeMap<String, LlmUsage>) -> u64 {
+    usage.values().map(|u| u64::from(u.total_tokens)).sum()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// `YYYY-MM-DD` of the UTC day containing `secs` after the Unix epoch.
+fn utc_date(secs: u64) -> String {
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = (secs / SECS_PER_DAY) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::LlmResponseMetadata;
+    use crate::memory::InMemoryStore;
+    use crate::react::Variant;
+
+    fn response(model: Option<&str>, total_tokens: u32) -> LlmResponse {
+        LlmResponse {
+            content: String::new(),
+            tool_calls: vec![],
+            usage: Some(LlmUsage {
+                prompt_tokens: total_tokens / 2,
+                completion_tokens: total_tokens - total_tokens / 2,
+                total_tokens,
+            }),
+            truncated: false,
+            continuations: 0,
+            metadata: LlmResponseMetadata {
+                model: model.map(String::from),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// **Scenario**: UTC dates around epoch, leap days and year ends.
+    #[test]
+    fn utc_date_formats_civil_days() {
+        assert_eq!(utc_date(0), "1970-01-01");
+        assert_eq!(utc_date(SECS_PER_DAY - 1), "1970-01-01");
+        assert_eq!(utc_date(951_782_400), "2000-02-29");
+        assert_eq!(utc_date(1_735_689_599), "2024-12-31");
+        assert_eq!(utc_date(1_735_689_600), "2025-01-01");
+    }
+
+    /// **Scenario**: A meter counts usage per served model, falling back to the configured one.
+    #[test]
+    fn meter_counts_per_model() {
+        let quota = UsageQuota::new(Arc::new(InMemoryStore::new())).with_model("gpt-4o-mini");
+        let meter = quota.meter();
+        meter.on_llm_end("run", &response(Some("gpt-4o-2024-08-06"), 100));
+        meter.on_llm_end("run", &response(None, 30));
+        meter.on_llm_end("run", &response(Some("gpt-4o-2024-08-06"), 20));
+        let usage = meter.usage();
+        assert_eq!(usage["gpt-4o-2024-08-06"].total_tokens, 120);
+        assert_eq!(usage["gpt-4o-mini"].total_tokens, 30);
+    }
+
+    /// **Scenario**: Recorded usage adds up per day for the deployment and the user; the user
+    /// cap refuses only that user, the deployment cap everyone.
+    #[tokio::test]
+    async fn caps_refuse_runs_once_reached() {
+        let quota = UsageQuota::new(Arc::new(InMemoryStore::new()))
+            .with_daily_tokens(1_000)
+            .with_daily_tokens_per_user(300);
+        let run = BTreeMap::from([("gpt-4o".to_string(), response(None, 200).usage.unwrap())]);
+
+        quota.record(Some("alice"), &run).await.unwrap();
+        assert_eq!(quota.check(Some("alice")).await.unwrap(), None);
+        quota.record(Some("alice"), &run).await.unwrap();
+        let refused = quota.check(Some("alice")).await.unwrap().expect("user cap");
+        assert_eq!(refused.scope, QuotaScope::User);
+        assert_eq!((refused.limit, refused.used), (300, 400));
+        assert!(refused.resets_in_secs <= SECS_PER_DAY);
+        assert!(refused.to_string().contains("midnight UTC"));
+        assert_eq!(quota.check(Some("bob")).await.unwrap(), None);
+
+        let today = utc_date(now_secs());
+        let alice = quota.usage(&today, Some("alice")).await.unwrap();
+        assert_eq!(alice["gpt-4o"].total_tokens, 400);
+
+        for _ in 0..3 {
+            quota.record(None, &run).await.unwrap();
+        }
+        let refused = quota
+            .check(Some("bob"))
+            .await
+            .unwrap()
+            .expect("deployment cap");
+        assert_eq!(refused.scope, QuotaScope::Deployment);
+        assert_eq!(refused.used, 1_000);
+        let anonymous = quota.check(None).await.unwrap().expect("deployment cap");
+        assert_eq!(anonymous.scope, QuotaScope::Deployment);
+    }
+
+    /// **Scenario**: Usage recorded for an experiment variant adds up per variant and does not
+    /// count towards the deployment's cap.
+    #[tokio::test]
+    async fn experiment_usage_is_kept_per_variant() {
+        let quota = UsageQuota::new(Arc::new(InMemoryStore::new())).with_daily_tokens(100);
+        let run = BTreeMap::from([("gpt-4o".to_string(), response(None, 80).usage.unwrap())]);
+        let a = ExperimentVariant::new("profile", Variant::A);
+        let b = ExperimentVariant::new("profile", Variant::B);
+
+        quota.record_experiment(&b, &run).await.unwrap();
+        quota.record_experiment(&b, &run).await.unwrap();
+        assert_eq!(quota.check(None).await.unwrap(), None);
+
+        let today = utc_date(now_secs());
+        let on_b = quota.experiment_usage(&today, &b).await.unwrap();
+        assert_eq!(on_b["gpt-4o"].total_tokens, 160);
+        assert!(quota.experiment_usage(&today, &a).await.unwrap().is_empty());
+    }
+}
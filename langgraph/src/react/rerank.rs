@@ -0,0 +1,118 @@
+//! Reranking of retrieved documents by a model that reads the query and each passage.
+//!
+//! Embedding similarity ranks well enough for a handful of documents but gets crude over long
+//! collections and memory namespaces: passages sharing the query's words outrank the one that
+//! answers it. A [`Reranker`] scores each (query, passage) pair directly; the retriever then
+//! fetches more candidates than it returns, reorders them by that score and keeps the top k.
+//!
+//! [`LlmReranker`] asks a (cheap) chat model for the scores; a local cross-encoder implements
+//! [`Reranker::score`] the same way.
+//!
+//! **Interaction**: Set with [`Retriever::with_reranker`](super::Retriever::with_reranker);
+//! applies to [`RetrieveNode`](super::RetrieveNode),
+//! [`ReactRunner::with_retriever`](super::ReactRunner::with_retriever) and the `retrieve` tool
+//! alike, since they all retrieve through the [`Retriever`](super::Retriever).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::AgentError;
+use crate::message::Message;
+use crate::LlmClient;
+
+/// Candidates fetched from the store per query for reranking, by default.
+pub const DEFAULT_RERANK_CANDIDATES: usize = 20;
+
+/// Longest passage sent to the reranking model, in characters.
+const MAX_PASSAGE_CHARS: usize = 1000;
+
+/// Instruction for the reranking model.
+const RERANK_PROMPT: &str = "You rate how well passages answer a search query. For each \
+passage give a score from 0 (unrelated) to 10 (answers the query directly). Reply with a JSON \
+array of the scores, one per passage in the given order, and nothing else, e.g. [7, 0, 3].";
+
+/// Scores passages against a query; see the [module docs](self).
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Relevance of each of `passages` to `query`, in order; higher is more relevant. Returns
+    /// one score per passage.
+    async fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f64>, AgentError>;
+}
+
+/// Reranker that asks a model, typically a small and cheap one, in one call per query.
+///
+/// The model gets the query and the numbered passages and replies with a JSON array of scores
+/// from 0 to 10, returned scaled to 0..=1. A reply without one score per passage is an error,
+/// and the retriever keeps the store's order.
+pub struct LlmReranker {
+    llm: Arc<dyn LlmClient>,
+}
+
+impl LlmReranker {
+    /// Reranker asking `llm`.
+    pub fn new(llm: Arc<dyn LlmClient>) -> Self {
+        Self { llm }
+    }
+
+    /// Scores in the first JSON array of `reply`, scaled to 0..=1; None when there is none.
+    fn parse(reply: &str) -> Option<Vec<f64>> {
+        let start = reply.find('[')?;
+        let end = reply.rfind(']')?;
+        let scores: Vec<f64> = serde_json::from_str(reply.get(start..=end)?).ok()?;
+        Some(scores.iter().map(|s| (s / 10.0).clamp(0.0, 1.0)).collect())
+    }
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    async fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f64>, AgentError> {
+        let numbered: Vec<String> = passages
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let passage: String = p.chars().take(MAX_PASSAGE_CHARS).collect();
+                format!("[{}] {}", i + 1, passage)
+            })
+            .collect();
+        let messages = [
+            Message::system(RERANK_PROMPT),
+            Message::user(format!(
+                "Query: {}\n\nPassages:\n{}",
+                query,
+                numbered.join("\n\n")
+            )),
+        ];
+        let response = self.llm.invoke(&messages).await?;
+        match Self::parse(&response.content) {
+            Some(scores) if scores.len() == passages.len() => Ok(scores),
+            _ => Err(AgentError::ExecutionFailed(format!(
+                "rerank reply has no {} scores: {}",
+                passages.len(),
+                response.content
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockLlm;
+
+    /// **Scenario**: Scores from the model's reply are scaled to 0..=1; a reply with the wrong
+    /// number of scores is an error.
+    #[tokio::test]
+    async fn llm_reranker_scales_scores_and_rejects_short_replies() {
+        let llm = MockLlm::with_no_tool_calls("```\n[2, 9]\n```");
+        let reranker = LlmReranker::new(Arc::new(llm));
+        let scores = reranker
+            .score("refunds", &["Shipping is free.", "Refunds take 5 days."])
+            .await
+            .unwrap();
+        assert_eq!(scores, [0.2, 0.9]);
+
+        let err = reranker.score("refunds", &["only one"]).await.unwrap_err();
+        assert!(err.to_string().contains("no 1 scores"), "{}", err);
+    }
+}
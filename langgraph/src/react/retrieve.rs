@@ -0,0 +1,435 @@
+//! Retrieval-augmented generation: top-k documents from a vector store put before `think`.
+//!
+//! A [`Retriever`] searches one namespace of a [`Store`] (e.g. `InMemoryVectorStore`,
+//! `LanceStore`) with the user's question and formats the hits as numbered passages with their
+//! source and score; with a [`Reranker`] it fetches more candidates and keeps the k a model
+//! ranks best. [`RetrieveNode`] runs it before `think` and inserts the passages as a
+//! System message right before the latest user message, so the model answers from them without
+//! a tool round trip; [`Retriever::tool`] lets the model search again with its own query.
+//!
+//! Documents are values with the passage in a text field (default `"text"`) and an optional
+//! source field (default `"source"`; the key when missing); plain string values are the passage.
+//!
+//! **Interaction**: Add [`RetrieveNode`] before `think` in a custom graph, or call
+//! [`ReactRunner::with_retriever`](super::ReactRunner::with_retriever); register
+//! [`Retriever::tool`] on the ActNode's tool source.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::error::AgentError;
+use crate::graph::Next;
+use crate::memory::{Namespace, SearchOptions, Store, StoreError};
+use crate::message::Message;
+use crate::state::ReActState;
+use crate::tools::RetrieverTool;
+use crate::Node;
+
+use super::rerank::{Reranker, DEFAULT_RERANK_CANDIDATES};
+
+/// Documents retrieved per question by default.
+pub const DEFAULT_RETRIEVE_TOP_K: usize = 4;
+
+/// First line of the System message [`RetrieveNode`] inserts.
+pub const RETRIEVED_CONTEXT_PREFIX: &str = "Retrieved context for the user's question";
+
+/// One retrieved document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetrievedDocument {
+    /// Key of the document in the store.
+    pub key: String,
+    /// The passage.
+    pub text: String,
+    /// Where the passage comes from: the value's source field, else the key.
+    pub source: String,
+    /// Similarity score, or the reranker's score when reranked; None for stores without
+    /// ranking.
+    pub score: Option<f64>,
+}
+
+/// Searches a namespace of a store and formats the hits for the model.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::memory::{Embedder, InMemoryVectorStore};
+/// use langgraph::react::{RetrieveNode, Retriever};
+/// use langgraph::tools::AggregateToolSource;
+/// use std::sync::Arc;
+///
+/// # async fn example(embedder: Arc<dyn Embedder>, tools: AggregateToolSource) {
+/// let store = Arc::new(InMemoryVectorStore::new(embedder));
+/// let retriever = Retriever::new(store, vec!["docs".to_string()]).with_top_k(3);
+/// tools.register_async(Box::new(retriever.tool())).await;
+/// let retrieve = RetrieveNode::new(retriever);
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Retriever {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+    top_k: usize,
+    min_score: Option<f64>,
+    text_field: String,
+    source_field: String,
+    reranker: Option<Arc<dyn Reranker>>,
+    rerank_candidates: usize,
+}
+
+impl Retriever {
+    /// Retriever over the documents in `namespace` of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self {
+            store,
+            namespace,
+            top_k: DEFAULT_RETRIEVE_TOP_K,
+            min_score: None,
+            text_field: "text".to_string(),
+            source_field: "source".to_string(),
+            reranker: None,
+            rerank_candidates: DEFAULT_RERANK_CANDIDATES,
+        }
+    }
+
+    /// Documents per question (default [`DEFAULT_RETRIEVE_TOP_K`]); at least 1.
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k.max(1);
+        self
+    }
+
+    /// Reranks the top [`rerank_candidates`](Self::with_rerank_candidates) hits with
+    /// `reranker` and keeps the k it scores highest. When it fails, the store's order is kept.
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// Hits fetched for the reranker per query (default [`DEFAULT_RERANK_CANDIDATES`]); never
+    /// fewer than the documents returned.
+    pub fn with_rerank_candidates(mut self, candidates: usize) -> Self {
+        self.rerank_candidates = candidates.max(1);
+        self
+    }
+
+    /// Drops hits scored below `min_score` by the store (before reranking); hits without a
+    /// score are kept.
+    pub fn with_min_score(mut self, min_score: f64) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /// Field of the value holding the passage (default `"text"`).
+    pub fn with_text_field(mut self, field: impl Into<String>) -> Self {
+        self.text_field = field.into();
+        self
+    }
+
+    /// Field of the value naming its source (default `"source"`).
+    pub fn with_source_field(mut self, field: impl Into<String>) -> Self {
+        self.source_field = field.into();
+        self
+    }
+
+    /// Documents per question.
+    pub fn top_k(&self) -> usize {
+        self.top_k
+    }
+
+    /// `retrieve` over the same store and namespace, for the model to search with its own
+    /// query.
+    pub fn tool(&self) -> RetrieverTool {
+        RetrieverTool::new(self.clone())
+    }
+
+    /// The `k` documents most relevant to `query` (`top_k` when None), best first.
+    pub async fn retrieve(
+        &self,
+        query: &str,
+        k: Option<usize>,
+    ) -> Result<Vec<RetrievedDocument>, StoreError> {
+        let limit = k.map(|k| k.max(1)).unwrap_or(self.top_k);
+        let candidates = match self.reranker {
+            Some(_) => self.rerank_candidates.max(limit),
+            None => limit,
+        };
+        let options = SearchOptions::new()
+            .with_query(query)
+            .with_limit(candidates);
+        let hits = self.store.search(&self.namespace, options).await?;
+        let documents = hits
+            .into_iter()
+            .filter(|hit| match (self.min_score, hit.score) {
+                (Some(min), Some(score)) => score >= min,
+                _ => true,
+            })
+            .map(|hit| {
+                let value = &hit.item.value;
+                let text = match value {
+                    Value::String(s) => s.clone(),
+                    _ => match value.get(&self.text_field) {
+                        Some(Value::String(s)) => s.clone(),
+                        _ => value.to_string(),
+                    },
+                };
+                let source = value
+                    .get(&self.source_field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(&hit.item.key)
+                    .to_string();
+                RetrievedDocument {
+                    key: hit.item.key,
+                    text,
+                    source,
+                    score: hit.score,
+                }
+            })
+            .collect();
+        Ok(self.rerank(query, documents, limit).await)
+    }
+
+    /// The `limit` best of `documents` by the reranker's score, which replaces theirs; the
+    /// first `limit` as they are without a reranker or when it fails.
+    async fn rerank(
+        &self,
+        query: &str,
+        mut documents: Vec<RetrievedDocument>,
+        limit: usize,
+    ) -> Vec<RetrievedDocument> {
+        if let Some(reranker) = self.reranker.as_ref().filter(|_| documents.len() > 1) {
+            let passages: Vec<&str> = documents.iter().map(|d| d.text.as_str()).collect();
+            match reranker.score(query, &passages).await {
+                Ok(scores) if scores.len() == documents.len() => {
+                    for (doc, score) in documents.iter_mut().zip(scores) {
+                        doc.score = Some(score);
+                    }
+                    // Stable, so ties keep the store's order.
+                    let score = |d: &RetrievedDocument| d.score.unwrap_or(0.0);
+                    documents.sort_by(|a, b| score(b).total_cmp(&score(a)));
+                }
+                Ok(scores) => tracing::warn!(
+                    expected = documents.len(),
+                    got = scores.len(),
+                    "reranker returned the wrong number of scores; keeping store order"
+                ),
+                Err(e) => tracing::warn!(error = %e, "reranking failed; keeping store order"),
+            }
+        }
+        documents.truncate(limit);
+        documents
+    }
+
+    /// Numbered passages, each headed by its source and score.
+    pub fn format(documents: &[RetrievedDocument]) -> String {
+        documents
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| match doc.score {
+                Some(score) => format!(
+                    "[{}] {} (score {:.3})\n{}",
+                    i + 1,
+                    doc.source,
+                    score,
+                    doc.text
+                ),
+                None => format!("[{}] {}\n{}", i + 1, doc.source, doc.text),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Retrieves documents for the latest user message and inserts them as a System message
+    /// right before it; returns how many were inserted. Nothing is inserted when the last
+    /// message is not from the user (e.g. a run continued after tool calls) or nothing matched.
+    pub async fn inject(&self, state: &mut ReActState) -> Result<usize, StoreError> {
+        let Some(Message::User(question)) = state.messages.last() else {
+            return Ok(0);
+        };
+        if question.trim().is_empty() {
+            return Ok(0);
+        }
+        let documents = self.retrieve(question, None).await?;
+        if documents.is_empty() {
+            return Ok(0);
+        }
+        let context = format!(
+            "{}; cite the sources you use:\n\n{}",
+            RETRIEVED_CONTEXT_PREFIX,
+            Self::format(&documents)
+        );
+        let at = state.messages.len() - 1;
+        state.messages.insert(at, Message::system(context));
+        Ok(documents.len())
+    }
+}
+
+/// Node that puts the documents retrieved for the user's question before `think`.
+///
+/// Runs [`Retriever::inject`] and continues; when the store fails the error is logged and the
+/// run goes on without context.
+///
+/// **Interaction**: Implements `Node<ReActState>` with id `"retrieve"`; add an edge from
+/// `retrieve` to `think` (e.g. START → retrieve → think → act → observe).
+pub struct RetrieveNode {
+    retriever: Retriever,
+}
+
+impl RetrieveNode {
+    /// Node retrieving with `retriever`.
+    pub fn new(retriever: Retriever) -> Self {
+        Self { retriever }
+    }
+}
+
+#[async_trait]
+impl Node<ReActState> for RetrieveNode {
+    fn id(&self) -> &str {
+        "retrieve"
+    }
+
+    async fn run(&self, mut state: ReActState) -> Result<(ReActState, Next), AgentError> {
+        if let Err(e) = self.retriever.inject(&mut state).await {
+            tracing::warn!(error = %e, "retrieval failed; continuing without context");
+        }
+        Ok((state, Next::Continue))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use serde_json::json;
+
+    async fn docs() -> Retriever {
+        let store = Arc::new(InMemoryStore::new());
+        let namespace = vec!["docs".to_string()];
+        store
+            .put(
+                &namespace,
+                "refunds",
+                &json!({"text": "Refunds take 5 days.", "source": "faq.md"}),
+            )
+            .await
+            .unwrap();
+        store
+            .put(&namespace, "shipping", &json!("Shipping is free."))
+            .await
+            .unwrap();
+        Retriever::new(store, namespace)
+    }
+
+    /// **Scenario**: The node inserts the passages with their source right before the latest
+    /// user message and leaves states not ending in a user message alone.
+    #[tokio::test]
+    async fn retrieve_node_inserts_context_before_question() {
+        let node = RetrieveNode::new(docs().await);
+        let state = ReActState {
+            messages: vec![Message::system("You help."), Message::user("refunds")],
+            ..Default::default()
+        };
+
+        let (state, next) = node.run(state).await.unwrap();
+        assert_eq!(next, Next::Continue);
+        assert_eq!(state.messages.len(), 3);
+        let Message::System(context) = &state.messages[1] else {
+            panic!("expected context, got {:?}", state.messages[1]);
+        };
+        assert!(context.starts_with(RETRIEVED_CONTEXT_PREFIX), "{}", context);
+        assert!(
+            context.ends_with("[1] faq.md\nRefunds take 5 days."),
+            "{}",
+            context
+        );
+        assert!(matches!(&state.messages[2], Message::User(q) if q == "refunds"));
+
+        let mut state = state;
+        state.messages.push(Message::assistant("Five days."));
+        let (unchanged, _) = node.run(state).await.unwrap();
+        assert_eq!(unchanged.messages.len(), 4);
+    }
+
+    /// **Scenario**: Passages are numbered and headed by their source, with the score when
+    /// the store ranks.
+    #[test]
+    fn formats_sources_and_scores() {
+        let documents = vec![
+            RetrievedDocument {
+                key: "shipping".into(),
+                text: "Shipping is free.".into(),
+                source: "shipping".into(),
+                score: Some(0.91234),
+            },
+            RetrievedDocument {
+                key: "refunds".into(),
+                text: "Refunds take 5 days.".into(),
+                source: "faq.md".into(),
+                score: None,
+            },
+        ];
+        assert_eq!(
+            Retriever::format(&documents),
+            "[1] shipping (score 0.912)\nShipping is free.\n\n[2] faq.md\nRefunds take 5 days."
+        );
+    }
+
+    /// Scores passages mentioning refunds 1, others 0; fails for the query "fail".
+    struct RefundReranker;
+
+    #[async_trait]
+    impl Reranker for RefundReranker {
+        async fn score(&self, query: &str, passages: &[&str]) -> Result<Vec<f64>, AgentError> {
+            if query == "fail" {
+                return Err(AgentError::ExecutionFailed("reranker down".into()));
+            }
+            Ok(passages
+                .iter()
+                .map(|p| if p.contains("Refund") { 1.0 } else { 0.0 })
+                .collect())
+        }
+    }
+
+    /// **Scenario**: With a reranker, all candidates are scored and the best k are returned
+    /// with the reranker's score; when it fails, the first k hits of the store are returned.
+    #[tokio::test]
+    async fn reranker_picks_top_k_of_candidates() {
+        let store = Arc::new(InMemoryStore::new());
+        let namespace = vec!["docs".to_string()];
+        for (key, text) in [
+            ("privacy", "Privacy policy: we keep no logs. fail"),
+            ("refunds", "Refund policy: 5 days. fail"),
+            ("shipping", "Shipping policy: free. fail"),
+        ] {
+            store.put(&namespace, key, &json!(text)).await.unwrap();
+        }
+        let retriever = Retriever::new(store, namespace)
+            .with_top_k(1)
+            .with_reranker(Arc::new(RefundReranker))
+            .with_rerank_candidates(3);
+
+        let documents = retriever.retrieve("policy", None).await.unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].key, "refunds");
+        assert_eq!(documents[0].score, Some(1.0));
+
+        let documents = retriever.retrieve("fail", Some(2)).await.unwrap();
+        assert_eq!(documents.len(), 2);
+        assert!(documents.iter().all(|d| d.score.is_none()));
+    }
+
+    /// **Scenario**: Plain string values are passages sourced by their key.
+    #[tokio::test]
+    async fn string_values_use_key_as_source() {
+        let documents = docs().await.retrieve("shipping", None).await.unwrap();
+        assert_eq!(
+            documents,
+            vec![RetrievedDocument {
+                key: "shipping".into(),
+                text: "Shipping is free.".into(),
+                source: "shipping".into(),
+                score: None,
+            }]
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! ReAct graph runner: encapsulates graph build, initial state, invoke and stream.
 //!
 //! Used by langgraph-cli, langgraph-bot, and other callers that need to run the
-//! ReAct graph without manually building Think → Act → Observe. Interacts with
+//! ReAct graph without manually building Think → Act → Observe. The graph loops
+//! observe → think (conditional edge) while the last round produced tool results, for at
+//! most `max_turns` rounds; the last think step drops further tool calls. Interacts with
 //! [`StateGraph`](crate::graph::StateGraph), [`ThinkNode`](super::ThinkNode),
 //! [`ActNode`](super::ActNode), [`ObserveNode`](super::ObserveNode), and
 //! [`build_react_initial_state`](super::build_react_initial_state).
@@ -11,7 +13,8 @@
 //! Use [`run_react_graph_stream`] with an `on_event` callback to drive "Thinking...",
 //! "Calling tool", or token-by-token UX. You receive [`StreamEvent`](crate::stream::StreamEvent)
 //! variants: `TaskStart` / `TaskEnd` (node enter/exit), `Messages` (LLM chunks),
-//! `Updates` (per-node state), `Values` (full state). Example:
+//! `Updates` (per-node state), `MessagesDelta` (messages each node appended), `UiEvents` (UI
+//! hints tools appended to `ui_events`), `Values` (full state). Example:
 //!
 //! ```ignore
 //! run_react_graph_stream(
@@ -22,22 +25,65 @@
 //!     }),
 //! ).await?;
 //! ```
+//!
+//! [`ReactRunner::stream_with_cancellation`] stops a run when its token is cancelled (e.g. the
+//! client went away); [`ReactRunner::with_node_timeout`] bounds each think / act / observe step.
+//! [`ReactRunner::stream_with_injector`] lets the caller inject follow-up user messages into a
+//! run in progress; they are merged after `observe` and the loop goes back to `think`.
+//! [`ReactRunner::with_governor`] applies run policies such as token or cost budgets; when one
+//! wraps a run up, `think` answers once more without tools. [`ReactRunner::with_budget`] caps
+//! tokens, cost and tool calls per run and fails runs over budget with
+//! [`RunError::BudgetExceeded`]. [`ReactRunner::with_usage_quota`] records tokens per model per
+//! day and refuses runs over a daily cap with [`RunError::QuotaExceeded`].
+//! [`ReactRunner::with_sampling_schedule`] varies temperature and `top_p` between
+//! tool-selection steps and the final answer. [`ReactRunner::with_ui_events_retention`] keeps
+//! UI events in checkpoints, which by default hold none.
+//! [`ReactRunner::with_outcome_classifier`] labels each finished run and adds the labels to the
+//! tags of the thread's latest checkpoint. [`ReactRunner::with_history_compressor`] shortens
+//! what `think` sends to the LLM on long threads; checkpoints keep the full history.
+//! [`ReactRunner::with_retriever`] puts documents from a vector store for each user message
+//! before `think`. [`ReactRunner::append_messages`] imports earlier messages into a thread
+//! without running the graph. [`ReactRunner::with_experiment_variant`] tags every run with the
+//! A/B experiment variant the runner serves (see [`Experiment`](super::Experiment)).
+//! [`ReactRunner::with_memory_writer`] saves facts from each finished turn to the user's
+//! long-term memory.
+//! [`ReactRunner::with_profiler`] records time and allocations per step and checkpoint access.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio_stream::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use async_trait::async_trait;
 
 use crate::error::AgentError;
-use crate::graph::{CompilationError, CompiledStateGraph, LoggingNodeMiddleware};
-use crate::memory::{CheckpointError, Checkpointer, RunnableConfig, Store};
+use crate::graph::{
+    Command, CompilationError, CompiledStateGraph, GovernorDecision, Injector,
+    LoggingNodeMiddleware, Profiler, RunContext, RunGovernor, RunProgress,
+};
+use crate::memory::{
+    Checkpoint, CheckpointError, CheckpointListItem, CheckpointSource, Checkpointer,
+    RunnableConfig, Store, ThreadInfo,
+};
 use crate::message::Message;
 use crate::state::ReActState;
-use crate::stream::{StreamEvent, StreamMode};
+use crate::stream::{
+    CustomEventRegistry, StreamEvent, StreamMode, UiEventsAccessor, UiEventsRetention,
+};
 use crate::tool_source::ToolSource;
-use crate::LlmClient;
+use crate::{LlmClient, LlmUsage};
 use crate::{ActNode, ObserveNode, ThinkNode, StateGraph, END, REACT_SYSTEM_PROMPT, START};
 
+use super::{
+    BudgetPolicy, ExperimentVariant, HistoryCompressor, MemoryWriter, OutcomeClassifier,
+    QuotaExceeded, QuotaMeter, Retriever, SamplingSchedule, UsageQuota, MAX_REACT_TURNS,
+    SAMPLING_SCHEDULE,
+};
+
+use super::history::CompressorSlot;
+
 use super::with_node_logging::WithNodeLogging;
 
 /// Builds the initial ReActState for a run: either from the latest checkpoint for the thread
@@ -69,6 +115,10 @@ pub async fn build_react_initial_state(
             state.messages.push(Message::user(user_message.to_string()));
             state.tool_calls = vec![];
             state.tool_results = vec![];
+            // max_turns caps rounds per invoke, not per thread; usage is counted per run too.
+            state.turn_count = 0;
+            state.usage = LlmUsage::default();
+            state.final_answer = None;
             return Ok(state);
         }
     }
@@ -82,6 +132,10 @@ pub async fn build_react_initial_state(
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     })
 }
 
@@ -154,6 +208,15 @@ where
     runner.stream_with_callback(user_message, on_event).await
 }
 
+/// Config naming `thread_id` of `user_id`, for the thread management methods of [`ReactRunner`].
+fn thread_config(thread_id: &str, user_id: Option<&str>) -> RunnableConfig {
+    RunnableConfig {
+        thread_id: Some(thread_id.to_string()),
+        user_id: user_id.map(str::to_string),
+        ..Default::default()
+    }
+}
+
 /// Error type for ReactRunner invoke/stream operations.
 #[derive(Debug, thiserror::Error)]
 pub enum RunError {
@@ -162,11 +225,26 @@ pub enum RunError {
     #[error("checkpoint error: {0}")]
     Checkpoint(#[from] CheckpointError),
     #[error("execution failed: {0}")]
-    Execution(#[from] AgentError),
+    Execution(AgentError),
+    /// The run's [`BudgetPolicy`] stopped it; carries the limit that was hit.
+    #[error("budget exceeded: {0}")]
+    BudgetExceeded(String),
+    /// The runner's [`UsageQuota`] refused the run; the message is a reply for the user.
+    #[error("{0}")]
+    QuotaExceeded(QuotaExceeded),
     #[error("stream ended without final state")]
     StreamEndedWithoutState,
 }
 
+impl From<AgentError> for RunError {
+    fn from(e: AgentError) -> Self {
+        match e {
+            AgentError::BudgetExceeded(reason) => RunError::BudgetExceeded(reason),
+            other => RunError::Execution(other),
+        }
+    }
+}
+
 impl From<std::io::Error> for RunError {
     fn from(e: std::io::Error) -> Self {
         RunError::Execution(AgentError::ExecutionFailed(e.to_string()))
@@ -178,7 +256,8 @@ impl From<std::io::Error> for RunError {
 /// Built from LLM, tool source, and optional checkpointer/store/config.
 /// Supports `invoke` (non-streaming) and `stream` (streaming with StreamEvent).
 /// Optional `system_prompt` is used when building initial state; when `None`,
-/// [`REACT_SYSTEM_PROMPT`](crate::REACT_SYSTEM_PROMPT) is used.
+/// [`REACT_SYSTEM_PROMPT`](crate::REACT_SYSTEM_PROMPT) is used. Each invoke runs at most
+/// `max_turns` think → act → observe rounds ([`MAX_REACT_TURNS`] with [`new`](Self::new)).
 ///
 /// # Example
 ///
@@ -192,6 +271,91 @@ pub struct ReactRunner {
     runnable_config: Option<RunnableConfig>,
     /// When set, used as system prompt in initial state; otherwise REACT_SYSTEM_PROMPT.
     system_prompt: Option<String>,
+    /// Maximum think → act → observe rounds per invoke.
+    max_turns: u32,
+    /// Governor set with `with_governor`; consulted after the budget.
+    governor: Option<Arc<dyn RunGovernor<ReActState>>>,
+    /// Per-run limits set with `with_budget`.
+    budget: Option<BudgetPolicy>,
+    /// Sampling per think phase set with `with_sampling_schedule`.
+    sampling: Option<SamplingSchedule>,
+    /// Daily usage totals and caps set with `with_usage_quota`.
+    quota: Option<Arc<UsageQuota>>,
+    /// Labels finished runs; set with `with_outcome_classifier`.
+    outcome_classifier: Option<Arc<dyn OutcomeClassifier>>,
+    /// Saves facts from finished turns to long-term memory; set with `with_memory_writer`.
+    memory_writer: Option<Arc<MemoryWriter>>,
+    /// Compressor slot of the think node; set with `with_history_compressor`.
+    history: CompressorSlot,
+    /// Puts documents for the user's message before think; set with `with_retriever`.
+    retriever: Option<Retriever>,
+    /// Experiment variant runs are tagged with; set with `with_experiment_variant`.
+    experiment: Option<ExperimentVariant>,
+}
+
+/// Governor of a runner: its budget first, then the caller's governor.
+struct RunnerGovernor {
+    budget: Option<BudgetPolicy>,
+    inner: Option<Arc<dyn RunGovernor<ReActState>>>,
+}
+
+#[async_trait]
+impl RunGovernor<ReActState> for RunnerGovernor {
+    async fn decide(&self, state: &ReActState, progress: &RunProgress) -> GovernorDecision {
+        if let Some(budget) = &self.budget {
+            let decision = budget.decide(state, progress).await;
+            if decision != GovernorDecision::Continue {
+                return decision;
+            }
+        }
+        match &self.inner {
+            Some(governor) => governor.decide(state, progress).await,
+            None => GovernorDecision::Continue,
+        }
+    }
+}
+
+/// Resume handler of the ReAct graph: a string resume value for a run paused before `think`
+/// is appended as a user message, so the model sees the human reply. Other values are left
+/// to nodes via `RunContext::resume_value`.
+fn inject_human_reply(
+    state: &mut ReActState,
+    pending_node: &str,
+    value: &serde_json::Value,
+) -> Result<(), AgentError> {
+    if let (Some(text), "think") = (value.as_str(), pending_node) {
+        state.messages.push(Message::user(text));
+    }
+    Ok(())
+}
+
+/// Message list of the ReAct state, for `StreamEvent::MessagesDelta`.
+fn react_messages(state: &ReActState) -> &[Message] {
+    &state.messages
+}
+
+/// UI event list of the ReAct state, for `StreamEvent::UiEvents` and checkpoint pruning.
+struct ReactUiEvents;
+
+impl UiEventsAccessor<ReActState> for ReactUiEvents {
+    fn ui_events<'a>(&self, state: &'a ReActState) -> &'a [serde_json::Value] {
+        &state.ui_events
+    }
+
+    fn ui_events_mut<'a>(&self, state: &'a mut ReActState) -> &'a mut Vec<serde_json::Value> {
+        &mut state.ui_events
+    }
+}
+
+/// Routes after observe: back to think when the round produced tool results (the last
+/// message is a tool result) and fewer than `max_turns` rounds ran; otherwise END.
+fn route_after_observe(state: &ReActState, max_turns: u32) -> String {
+    let had_tool_results = matches!(state.messages.last(), Some(Message::Tool { .. }));
+    if had_tool_results && state.turn_count < max_turns {
+        "think".to_string()
+    } else {
+        END.to_string()
+    }
 }
 
 impl ReactRunner {
@@ -200,6 +364,7 @@ impl ReactRunner {
     /// When `verbose` is true, attaches node logging middleware. When both
     /// checkpointer and verbose are set, compiles with both.
     /// `system_prompt`: when `Some`, used for initial state; when `None`, uses [`REACT_SYSTEM_PROMPT`](crate::REACT_SYSTEM_PROMPT).
+    /// Loops at most [`MAX_REACT_TURNS`] rounds; use [`new_with_max_turns`](Self::new_with_max_turns) to change it.
     pub fn new(
         llm: Box<dyn LlmClient>,
         tool_source: Box<dyn ToolSource>,
@@ -209,11 +374,45 @@ impl ReactRunner {
         system_prompt: Option<String>,
         verbose: bool,
     ) -> Result<Self, CompilationError> {
-        let think = ThinkNode::new(llm);
+        Self::new_with_max_turns(
+            llm,
+            tool_source,
+            checkpointer,
+            store,
+            runnable_config,
+            system_prompt,
+            verbose,
+            MAX_REACT_TURNS,
+        )
+    }
+
+    /// Same as [`new`](Self::new) with an explicit cap on think → act → observe rounds per
+    /// invoke (at least 1). The think step of the last round sees `IsLastStep` and answers
+    /// instead of calling more tools. The act node keeps tool state in memory
+    /// ([`ActNode::new`]), so it does not survive a restart even with a persistent checkpointer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_max_turns(
+        llm: Box<dyn LlmClient>,
+        tool_source: Box<dyn ToolSource>,
+        checkpointer: Option<Arc<dyn Checkpointer<ReActState>>>,
+        store: Option<Arc<dyn Store>>,
+        runnable_config: Option<RunnableConfig>,
+        system_prompt: Option<String>,
+        verbose: bool,
+        max_turns: u32,
+    ) -> Result<Self, CompilationError> {
+        let max_turns = max_turns.max(1);
+        let think = ThinkNode::new(llm).with_max_turns(max_turns);
+        let history = think.history_slot();
         let act = ActNode::new(tool_source);
-        let observe = ObserveNode::with_loop();
+        let observe = ObserveNode::with_loop().with_max_turns(max_turns);
 
-        let mut graph = StateGraph::<ReActState>::new();
+        let mut graph = StateGraph::<ReActState>::new()
+            .with_resume_handler(Arc::new(inject_human_reply))
+            .with_messages_accessor(Arc::new(react_messages))
+            .with_ui_events_accessor(Arc::new(ReactUiEvents))
+            .with_final_answer_node("think")
+            .with_injection_point("observe", "think");
         if let Some(s) = store {
             graph = graph.with_store(s);
         }
@@ -224,7 +423,14 @@ impl ReactRunner {
             .add_edge(START, "think")
             .add_edge("think", "act")
             .add_edge("act", "observe")
-            .add_edge("observe", END);
+            .add_conditional_edges(
+                "observe",
+                Arc::new(move |s: &ReActState| route_after_observe(s, max_turns)),
+                Some(HashMap::from([
+                    ("think".to_string(), "think".to_string()),
+                    (END.to_string(), END.to_string()),
+                ])),
+            );
 
         let graph = if verbose {
             graph.with_node_logging()
@@ -246,9 +452,470 @@ impl ReactRunner {
             checkpointer,
             runnable_config,
             system_prompt,
+            max_turns,
+            governor: None,
+            budget: None,
+            sampling: None,
+            quota: None,
+            outcome_classifier: None,
+            memory_writer: None,
+            history,
+            retriever: None,
+            experiment: None,
         })
     }
 
+    /// Maximum think → act → observe rounds per invoke.
+    pub fn max_turns(&self) -> u32 {
+        self.max_turns
+    }
+
+    /// The compiled think → act → observe graph, e.g. for [`analyze_graph`](crate::analyze_graph)
+    /// or [`generate_dot`](crate::generate_dot).
+    pub fn graph(&self) -> &CompiledStateGraph<ReActState> {
+        &self.compiled
+    }
+
+    /// Pauses runs before the given nodes (`think`, `act`, `observe`), e.g. `["act"]` to
+    /// approve tool calls. Needs a checkpointer and a `thread_id`; continue with
+    /// [`resume`](Self::resume). `act` runs every round, so the round in which the model
+    /// answers without tool calls pauses too. Fails when a name is not a node of the ReAct graph.
+    pub fn with_interrupt_before(mut self, nodes: &[String]) -> Result<Self, CompilationError> {
+        self.compiled.set_interrupt_before(nodes)?;
+        Ok(self)
+    }
+
+    /// Aborts a think / act / observe step that runs longer than `timeout` (e.g. a hung MCP
+    /// tool) with `AgentError::NodeTimeout`, like `StateGraph::with_node_timeout`.
+    pub fn with_node_timeout(mut self, timeout: Duration) -> Self {
+        self.compiled.set_node_timeout(Some(timeout));
+        self
+    }
+
+    /// Keeps UI events (`ReActState::ui_events`) in checkpoints according to `retention`; by
+    /// default checkpoints hold none, so they only reach live streams.
+    pub fn with_ui_events_retention(mut self, retention: UiEventsRetention) -> Self {
+        self.compiled.set_ui_events_retention(retention);
+        self
+    }
+
+    /// Declares the custom stream events tools emit (`ToolCallContext::emit_event`), like
+    /// `StateGraph::with_custom_events`; undeclared types or invalid payloads are rejected.
+    pub fn with_custom_events(mut self, registry: Arc<CustomEventRegistry>) -> Self {
+        self.compiled.set_custom_events(Some(registry));
+        self
+    }
+
+    /// Records every think / act / observe step and checkpoint read or write into `profiler`
+    /// (see [`Profiler`]); call [`Profiler::finish_run`] after each run.
+    pub fn with_profiler(mut self, profiler: &Profiler) -> Self {
+        self.checkpointer = self
+            .checkpointer
+            .map(|checkpointer| profiler.checkpointer(checkpointer));
+        self.compiled.set_checkpointer(self.checkpointer.clone());
+        self.compiled.add_middleware(Arc::new(profiler.clone()));
+        self
+    }
+
+    /// Consults `governor` before every think / act / observe step (see
+    /// [`RunGovernor`]). `SkipToFinalAnswer` runs `think` as the last step, so the model answers
+    /// without further tool calls; `Abort` fails the run with `AgentError::Aborted`.
+    pub fn with_governor(mut self, governor: Arc<dyn RunGovernor<ReActState>>) -> Self {
+        self.governor = Some(governor);
+        self.apply_governors()
+    }
+
+    /// Limits tokens, cost and tool calls of every run (see [`BudgetPolicy`]). A run over
+    /// budget fails with [`RunError::BudgetExceeded`]; the budget is checked before any
+    /// governor set with [`with_governor`](Self::with_governor).
+    pub fn with_budget(mut self, budget: BudgetPolicy) -> Self {
+        self.budget = Some(budget);
+        self.apply_governors()
+    }
+
+    /// Calls the LLM with `schedule`'s parameters per think step: `tool_selection` while the
+    /// model may call tools, `final_answer` on the last step (the `max_turns` round, or when a
+    /// governor wraps the run up). Needs an LLM client that applies per-call sampling, such as
+    /// `ChatOpenAI`; others ignore it. Runs continued with [`resume`](Self::resume) use the
+    /// client's settings.
+    pub fn with_sampling_schedule(mut self, schedule: SamplingSchedule) -> Self {
+        self.sampling = Some(schedule);
+        self
+    }
+
+    /// Records the tokens of every run per model and day in `quota`'s store and refuses runs
+    /// once the deployment or the run's `user_id` reached a daily cap, with
+    /// [`RunError::QuotaExceeded`] (see [`UsageQuota`]). Runners sharing one `Arc` add up
+    /// their usage without losing concurrent updates.
+    pub fn with_usage_quota(mut self, quota: impl Into<Arc<UsageQuota>>) -> Self {
+        self.quota = Some(quota.into());
+        self
+    }
+
+    /// Labels every finished run with `classifier` and adds the labels to the tags of the
+    /// thread's latest checkpoint (see [`Checkpointer::tag_checkpoint`]). Classification runs
+    /// in the background after the run returned; runs without a checkpointer or thread_id are
+    /// not labelled, and a failed tagging is logged.
+    pub fn with_outcome_classifier(mut self, classifier: Arc<dyn OutcomeClassifier>) -> Self {
+        self.outcome_classifier = Some(classifier);
+        self
+    }
+
+    /// Saves durable facts and preferences of every finished turn to the Store with `writer`,
+    /// under the run's `user_id` (see [`MemoryWriter`]), so the model need not call `remember`.
+    /// Extraction runs in the background after the run returned; a failure is logged.
+    pub fn with_memory_writer(mut self, writer: MemoryWriter) -> Self {
+        self.memory_writer = Some(Arc::new(writer));
+        self
+    }
+
+    /// Sends the LLM the messages `compressor` makes of the thread's history, e.g.
+    /// [`DropOldToolResults`](super::DropOldToolResults) or
+    /// [`SummarizeOldTurns`](super::SummarizeOldTurns). Checkpoints keep the full history.
+    pub fn with_history_compressor(self, compressor: Arc<dyn HistoryCompressor>) -> Self {
+        self.history.set(compressor);
+        self
+    }
+
+    /// Puts the documents `retriever` finds for each new user message into the state before
+    /// `think` (see [`Retriever::inject`]); they stay in the thread's history. Runs continued
+    /// with [`resume`](Self::resume) retrieve nothing, and a failing store is logged.
+    pub fn with_retriever(mut self, retriever: Retriever) -> Self {
+        self.retriever = Some(retriever);
+        self
+    }
+
+    /// Marks this runner as `variant` of an A/B experiment: the tag
+    /// [`ExperimentVariant::tag`] is added to the thread's latest checkpoint after every run,
+    /// next to the outcome labels, and the usage quota, if any, also records the run's tokens
+    /// under the variant (see [`UsageQuota::record_experiment`]). Usually set by
+    /// [`Experiment::new`](super::Experiment::new).
+    pub fn with_experiment_variant(mut self, variant: ExperimentVariant) -> Self {
+        self.experiment = Some(variant);
+        self
+    }
+
+    /// Experiment variant set with [`with_experiment_variant`](Self::with_experiment_variant).
+    pub fn experiment_variant(&self) -> Option<&ExperimentVariant> {
+        self.experiment.as_ref()
+    }
+
+    /// Runs the retriever, if any, on the initial state of a run.
+    async fn retrieve(&self, state: &mut ReActState) {
+        if let Some(retriever) = &self.retriever {
+            if let Err(e) = retriever.inject(state).await {
+                tracing::warn!(error = %e, "retrieval failed; continuing without context");
+            }
+        }
+    }
+
+    /// Classifies the run that produced `state` and tags the latest checkpoint of the thread
+    /// in `config` with the labels and the experiment variant, in a background task.
+    fn tag_outcome(&self, config: Option<&RunnableConfig>, state: &ReActState) {
+        let Some(checkpointer) = &self.checkpointer else {
+            return;
+        };
+        if self.outcome_classifier.is_none() && self.experiment.is_none() {
+            return;
+        }
+        let Some(config) = config.filter(|c| c.thread_id.is_some()) else {
+            return;
+        };
+        let config = RunnableConfig {
+            checkpoint_id: None,
+            ..config.clone()
+        };
+        let classifier = self.outcome_classifier.clone();
+        let variant_tag = self.experiment.as_ref().map(ExperimentVariant::tag);
+        let checkpointer = Arc::clone(checkpointer);
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut tags: Vec<String> = variant_tag.into_iter().collect();
+            if let Some(classifier) = classifier {
+                let outcomes = classifier.classify(&state).await;
+                tags.extend(outcomes.iter().map(|o| o.as_str().to_string()));
+            }
+            if tags.is_empty() {
+                return;
+            }
+            match checkpointer.tag_checkpoint(&config, &tags).await {
+                Ok(_) => tracing::debug!(thread_id = ?config.thread_id, ?tags, "run outcome"),
+                Err(e) => tracing::warn!(error = %e, "failed to tag run outcome"),
+            }
+        });
+    }
+
+    /// Extracts memories from the run that produced `state` for the `user_id` of `config`, in
+    /// a background task.
+    fn write_memories(&self, config: Option<&RunnableConfig>, state: &ReActState) {
+        let Some(writer) = &self.memory_writer else {
+            return;
+        };
+        let writer = Arc::clone(writer);
+        let user_id = config.and_then(|c| c.user_id.clone());
+        let state = state.clone();
+        tokio::spawn(async move {
+            match writer.write(user_id.as_deref(), &state).await {
+                Ok(memories) if !memories.is_empty() => {
+                    let keys: Vec<&str> = memories.iter().map(|m| m.key.as_str()).collect();
+                    tracing::debug!(?user_id, ?keys, "memories extracted");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "failed to save extracted memories"),
+            }
+        });
+    }
+
+    /// Fails with [`RunError::QuotaExceeded`] when a run with `config` (or the runner's config)
+    /// would be refused by the usage quota, e.g. to answer before a response is streamed. A
+    /// quota that cannot be read lets the run through.
+    pub async fn check_quota(&self, config: Option<&RunnableConfig>) -> Result<(), RunError> {
+        let Some(quota) = &self.quota else {
+            return Ok(());
+        };
+        let user_id = config
+            .or(self.runnable_config.as_ref())
+            .and_then(|c| c.user_id.as_deref());
+        match quota.check(user_id).await {
+            Ok(None) => Ok(()),
+            Ok(Some(exceeded)) => Err(RunError::QuotaExceeded(exceeded)),
+            Err(e) => {
+                tracing::warn!(error = %e, "daily quota unavailable; run not limited");
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks the quota for a run with `config` and adds a meter counting its tokens to the
+    /// config's callbacks; None without a quota.
+    async fn start_quota(
+        &self,
+        config: &mut Option<RunnableConfig>,
+    ) -> Result<Option<Arc<QuotaMeter>>, RunError> {
+        let Some(quota) = &self.quota else {
+            return Ok(None);
+        };
+        let config = config.get_or_insert_with(Default::default);
+        self.check_quota(Some(config)).await?;
+        let meter = quota.meter();
+        config.callbacks.push(meter.clone());
+        Ok(Some(meter))
+    }
+
+    /// Adds the tokens `meter` counted to today's totals, also when the run failed.
+    async fn finish_quota(&self, user_id: Option<&str>, meter: Option<Arc<QuotaMeter>>) {
+        let (Some(quota), Some(meter)) = (&self.quota, meter) else {
+            return;
+        };
+        let usage = meter.usage();
+        if let Err(e) = quota.record(user_id, &usage).await {
+            tracing::warn!(error = %e, "failed to record usage for the daily quota");
+        }
+        if let Some(variant) = &self.experiment {
+            if let Err(e) = quota.record_experiment(variant, &usage).await {
+                tracing::warn!(error = %e, "failed to record usage of the experiment variant");
+            }
+        }
+    }
+
+    /// RunContext of one run: `config`, the graph's store and the sampling schedule.
+    fn run_context(&self, config: RunnableConfig) -> RunContext<ReActState> {
+        let mut run_ctx = RunContext::new(config);
+        run_ctx.store = self.compiled.store().cloned();
+        match self.sampling {
+            Some(schedule) => run_ctx.with_managed_value(SAMPLING_SCHEDULE, Arc::new(schedule)),
+            None => run_ctx,
+        }
+    }
+
+    fn apply_governors(mut self) -> Self {
+        let governor: Arc<dyn RunGovernor<ReActState>> = match (&self.budget, &self.governor) {
+            (None, Some(governor)) => Arc::clone(governor),
+            (None, None) => return self,
+            (budget, inner) => Arc::new(RunnerGovernor {
+                budget: budget.clone(),
+                inner: inner.clone(),
+            }),
+        };
+        self.compiled = self.compiled.with_governor(governor);
+        self
+    }
+
+    /// Node the thread in `config` (or the runner's config) is paused at; None when the
+    /// thread is not interrupted.
+    pub async fn pending_node(
+        &self,
+        config: Option<RunnableConfig>,
+    ) -> Result<Option<String>, RunError> {
+        let run_config = config.or_else(|| self.runnable_config.clone()).unwrap_or_default();
+        Ok(self.compiled.pending_node(&run_config).await?)
+    }
+
+    /// Persisted state of the thread in `config` (or the runner's config): its latest
+    /// checkpoint, or `config.checkpoint_id`. None when the runner has no checkpointer, the
+    /// config has no `thread_id`, or the thread has no checkpoint yet.
+    pub async fn get_state(
+        &self,
+        config: Option<RunnableConfig>,
+    ) -> Result<Option<ReActState>, RunError> {
+        let run_config = config.or_else(|| self.runnable_config.clone()).unwrap_or_default();
+        let Some(checkpointer) = self.checkpointer.as_ref() else {
+            return Ok(None);
+        };
+        if run_config.thread_id.is_none() {
+            return Ok(None);
+        }
+        let tuple = checkpointer.get_tuple(&run_config).await?;
+        Ok(tuple.map(|(checkpoint, _)| checkpoint.channel_values))
+    }
+
+    /// Starts `thread_id` of `user_id` with a checkpoint holding only the system prompt, so the
+    /// thread exists (and is listed) before its first message. Returns false, writing nothing,
+    /// when the thread already has a checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// `RunError::Checkpoint` when the runner has no checkpointer or the write fails.
+    pub async fn create_thread(
+        &self,
+        thread_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<bool, RunError> {
+        let checkpointer = self.require_checkpointer()?;
+        let config = thread_config(thread_id, user_id);
+        if checkpointer.get_tuple(&config).await?.is_some() {
+            return Ok(false);
+        }
+        let prompt = self.system_prompt.as_deref().unwrap_or(REACT_SYSTEM_PROMPT);
+        let state = ReActState {
+            messages: vec![Message::system(prompt)],
+            ..Default::default()
+        };
+        let checkpoint = Checkpoint::from_state(state, CheckpointSource::Input, -1);
+        checkpointer.put(&config, &checkpoint).await?;
+        checkpointer.flush().await?;
+        Ok(true)
+    }
+
+    /// Appends `messages` to the history of the thread in `config` without running the graph,
+    /// e.g. to import a conversation from another system. A thread without checkpoints is
+    /// started, with the system prompt first unless `messages` begins with a system message.
+    /// All messages are saved in one checkpoint (`CheckpointSource::Update`) after the latest,
+    /// so a thread paused at an interrupt stays paused. Returns the thread's message count.
+    ///
+    /// # Errors
+    ///
+    /// `RunError::Checkpoint` when the runner has no checkpointer or the thread cannot be
+    /// read; `RunError::Execution` when `config` has no `thread_id` or saving fails.
+    pub async fn append_messages(
+        &self,
+        config: RunnableConfig,
+        messages: Vec<Message>,
+    ) -> Result<usize, RunError> {
+        self.require_checkpointer()?;
+        if config.thread_id.is_none() {
+            return Err(RunError::Execution(AgentError::ExecutionFailed(
+                "append_messages requires config.thread_id".into(),
+            )));
+        }
+        let config = RunnableConfig {
+            checkpoint_id: None,
+            ..config
+        };
+        let mut state = match self.get_state(Some(config.clone())).await? {
+            Some(state) => state,
+            None if matches!(messages.first(), Some(Message::System(_))) => ReActState::default(),
+            None => {
+                let prompt = self.system_prompt.as_deref().unwrap_or(REACT_SYSTEM_PROMPT);
+                ReActState {
+                    messages: vec![Message::system(prompt)],
+                    ..Default::default()
+                }
+            }
+        };
+        state.messages.extend(messages);
+        let count = state.messages.len();
+        self.compiled.update_state(&config, state, None).await?;
+        Ok(count)
+    }
+
+    /// Threads of `user_id` with checkpoints, most recently active first (see
+    /// [`Checkpointer::list_threads`]). Empty when the runner has no checkpointer.
+    pub async fn list_threads(&self, user_id: Option<&str>) -> Result<Vec<ThreadInfo>, RunError> {
+        let config = RunnableConfig {
+            user_id: user_id.map(str::to_string),
+            ..Default::default()
+        };
+        match self.checkpointer.as_ref() {
+            Some(checkpointer) => Ok(checkpointer.list_threads(&config).await?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Deletes every checkpoint of `thread_id` of `user_id`; returns whether it had any. False
+    /// when the runner has no checkpointer.
+    pub async fn delete_thread(
+        &self,
+        thread_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<bool, RunError> {
+        match self.checkpointer.as_ref() {
+            Some(checkpointer) => Ok(checkpointer
+                .delete_thread(&thread_config(thread_id, user_id))
+                .await?),
+            None => Ok(false),
+        }
+    }
+
+    /// Checkpoints of `thread_id`, oldest first, with their metadata and tags (see
+    /// [`Checkpointer::list`]). Empty when the runner has no checkpointer.
+    pub async fn list_checkpoints(
+        &self,
+        thread_id: &str,
+    ) -> Result<Vec<CheckpointListItem>, RunError> {
+        let Some(checkpointer) = self.checkpointer.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let config = RunnableConfig {
+            thread_id: Some(thread_id.to_string()),
+            ..Default::default()
+        };
+        Ok(checkpointer.list(&config, None, None, None).await?)
+    }
+
+    fn require_checkpointer(&self) -> Result<&Arc<dyn Checkpointer<ReActState>>, RunError> {
+        self.checkpointer.as_ref().ok_or_else(|| {
+            RunError::Checkpoint(CheckpointError::Storage(
+                "runner has no checkpointer".to_string(),
+            ))
+        })
+    }
+
+    /// Continues an interrupted thread from its pending node with the human-provided
+    /// `resume_value` (see [`CompiledStateGraph::resume`]).
+    ///
+    /// A string value for a thread paused before `think` is added as a user message; other
+    /// values reach nodes via `RunContext::resume_value`. `None` simply continues (e.g. approves
+    /// the pending tool calls).
+    pub async fn resume(
+        &self,
+        resume_value: Option<serde_json::Value>,
+        config: Option<RunnableConfig>,
+    ) -> Result<ReActState, RunError> {
+        let mut run_config = config.or_else(|| self.runnable_config.clone());
+        let meter = self.start_quota(&mut run_config).await?;
+        let run_config = run_config.unwrap_or_default();
+        let user_id = run_config.user_id.clone();
+        let tag_config = run_config.clone();
+        let command = resume_value.map(Command::resume).unwrap_or_default();
+        let result = self.compiled.resume(command, run_config).await;
+        self.finish_quota(user_id.as_deref(), meter).await;
+        let state = result?;
+        self.tag_outcome(Some(&tag_config), &state);
+        self.write_memories(Some(&tag_config), &state);
+        Ok(state)
+    }
+
     /// Invokes the graph with the given user message.
     ///
     /// Uses the runner's built-in `runnable_config` (if any). For per-invoke config
@@ -268,16 +935,31 @@ impl ReactRunner {
         user_message: &str,
         config: Option<RunnableConfig>,
     ) -> Result<ReActState, RunError> {
-        let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_react_initial_state(
+        let mut run_config = config.or_else(|| self.runnable_config.clone());
+        let meter = self.start_quota(&mut run_config).await?;
+        let mut state = build_react_initial_state(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
             self.system_prompt.as_deref(),
         )
         .await?;
-        let final_state = self.compiled.invoke(state, run_config).await?;
-        Ok(final_state)
+        self.retrieve(&mut state).await;
+        // checkpoint_id was honored when loading the state above; the graph must run from
+        // that state plus the new message, not time-travel back to the bare checkpoint.
+        if let Some(cfg) = run_config.as_mut() {
+            cfg.checkpoint_id = None;
+        }
+        let user_id = run_config.as_ref().and_then(|c| c.user_id.clone());
+        let tag_config = run_config.clone();
+        // Run with a RunContext so nodes see the store and thread_id (e.g. ActNode tool state).
+        let run_ctx = self.run_context(run_config.unwrap_or_default());
+        let result = self.compiled.invoke_with_context(state, run_ctx).await;
+        self.finish_quota(user_id.as_deref(), meter).await;
+        let state = result?;
+        self.tag_outcome(tag_config.as_ref(), &state);
+        self.write_memories(tag_config.as_ref(), &state);
+        Ok(state)
     }
 
     /// Streams the graph execution; returns the final state from the last StreamEvent::Values.
@@ -299,32 +981,106 @@ impl ReactRunner {
     ///
     /// When `config` is `Some`, it is used for this run; when `None`, the runner's
     /// `runnable_config` is used. Emits `StreamEvent` for TaskStart, TaskEnd, Messages,
-    /// Updates, Values. When `on_event` is provided, invokes it for each event.
+    /// Updates, MessagesDelta, UiEvents, Values. When `on_event` is provided, invokes it for each
+    /// event.
     pub async fn stream_with_config<F>(
         &self,
         user_message: &str,
         config: Option<RunnableConfig>,
+        on_event: Option<F>,
+    ) -> Result<ReActState, RunError>
+    where
+        F: FnMut(StreamEvent<ReActState>),
+    {
+        self.stream_inner(user_message, config, None, None, on_event)
+            .await
+    }
+
+    /// Same as [`stream_with_config`](Self::stream_with_config), stopped when `cancel` is
+    /// cancelled (e.g. the SSE client disconnected): the step in progress is aborted, steps
+    /// already checkpointed are kept, and the call returns `AgentError::Cancelled`.
+    pub async fn stream_with_cancellation<F>(
+        &self,
+        user_message: &str,
+        config: Option<RunnableConfig>,
+        cancel: CancellationToken,
+        on_event: Option<F>,
+    ) -> Result<ReActState, RunError>
+    where
+        F: FnMut(StreamEvent<ReActState>),
+    {
+        self.stream_inner(user_message, config, Some(cancel), None, on_event)
+            .await
+    }
+
+    /// Same as [`stream_with_cancellation`](Self::stream_with_cancellation), steerable while
+    /// it runs: updates pushed onto `injector` (e.g. a follow-up user message) are merged after
+    /// the current round's `observe`, and the loop continues with `think`, so the model sees
+    /// them even when it had already answered. The injector is closed when the run ends.
+    pub async fn stream_with_injector<F>(
+        &self,
+        user_message: &str,
+        config: Option<RunnableConfig>,
+        cancel: CancellationToken,
+        injector: Injector<ReActState>,
+        on_event: Option<F>,
+    ) -> Result<ReActState, RunError>
+    where
+        F: FnMut(StreamEvent<ReActState>),
+    {
+        self.stream_inner(user_message, config, Some(cancel), Some(injector), on_event)
+            .await
+    }
+
+    async fn stream_inner<F>(
+        &self,
+        user_message: &str,
+        config: Option<RunnableConfig>,
+        cancel: Option<CancellationToken>,
+        injector: Option<Injector<ReActState>>,
         mut on_event: Option<F>,
     ) -> Result<ReActState, RunError>
     where
         F: FnMut(StreamEvent<ReActState>),
     {
-        let run_config = config.or_else(|| self.runnable_config.clone());
-        let state = build_react_initial_state(
+        let mut run_config = config.or_else(|| self.runnable_config.clone());
+        let meter = self.start_quota(&mut run_config).await?;
+        let mut state = build_react_initial_state(
             user_message,
             self.checkpointer.as_deref(),
             run_config.as_ref(),
             self.system_prompt.as_deref(),
         )
         .await?;
+        self.retrieve(&mut state).await;
+        // checkpoint_id was honored when loading the state above; the graph must run from
+        // that state plus the new message, not time-travel back to the bare checkpoint.
+        if let Some(cfg) = run_config.as_mut() {
+            cfg.checkpoint_id = None;
+        }
+        let user_id = run_config.as_ref().and_then(|c| c.user_id.clone());
+        let tag_config = run_config.clone();
 
         let modes = HashSet::from([
             StreamMode::Messages,
+            StreamMode::MessagesDelta,
             StreamMode::Tasks,
+            StreamMode::UiEvents,
             StreamMode::Updates,
             StreamMode::Values,
         ]);
-        let mut stream = self.compiled.stream(state, run_config, modes);
+        let mut stream = if cancel.is_some() || self.sampling.is_some() || injector.is_some() {
+            let mut run_ctx = self.run_context(run_config.unwrap_or_default());
+            if let Some(ref token) = cancel {
+                run_ctx = run_ctx.with_cancellation(token.clone());
+            }
+            if let Some(ref injector) = injector {
+                run_ctx = run_ctx.with_injector(injector.clone());
+            }
+            self.compiled.stream_with_context(state, run_ctx, modes)
+        } else {
+            self.compiled.stream(state, run_config, modes)
+        };
 
         let mut final_state: Option<ReActState> = None;
         while let Some(event) = stream.next().await {
@@ -335,7 +1091,17 @@ impl ReactRunner {
                 final_state = Some(s);
             }
         }
+        self.finish_quota(user_id.as_deref(), meter).await;
+        if let Some(dropped) = injector.map(|i| i.close()).filter(|n| *n > 0) {
+            tracing::warn!(dropped, "run ended before injected updates were merged");
+        }
 
-        final_state.ok_or(RunError::StreamEndedWithoutState)
+        if cancel.is_some_and(|token| token.is_cancelled()) {
+            return Err(AgentError::Cancelled.into());
+        }
+        let state = final_state.ok_or(RunError::StreamEndedWithoutState)?;
+        self.tag_outcome(tag_config.as_ref(), &state);
+        self.write_memories(tag_config.as_ref(), &state);
+        Ok(state)
     }
 }
@@ -0,0 +1,130 @@
+//! Sampling schedule: different temperature / `top_p` per phase of the ReAct loop.
+//!
+//! One global temperature compromises both ends: tool selection wants it low (exact tool
+//! names and JSON arguments), the final answer often wants it higher. A [`SamplingSchedule`]
+//! gives ThinkNode one [`SamplingParams`] for steps that may still call tools and one for the
+//! final-answer step, the last step of a run (its `max_turns` round, or a governor wrapping
+//! the run up), where tool calls are dropped and the reply is the answer.
+//!
+//! **Interaction**: Set with [`ReactRunner::with_sampling_schedule`](super::ReactRunner::with_sampling_schedule),
+//! which registers it in the run's `RunContext` under [`SAMPLING_SCHEDULE`]; ThinkNode reads it
+//! from there and calls `LlmClient::invoke_stream_with_sampling`.
+
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::RunContext;
+use crate::llm::SamplingParams;
+use crate::managed::ManagedValue;
+
+/// Key under which a [`SamplingSchedule`] is registered in `RunContext::managed_values`.
+pub const SAMPLING_SCHEDULE: &str = "sampling_schedule";
+
+/// Phase of a think step, deciding its sampling parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThinkPhase {
+    /// A step that may call tools.
+    ToolSelection,
+    /// The last step of the run: its reply is the final answer.
+    FinalAnswer,
+}
+
+/// Sampling parameters per [`ThinkPhase`]; unset parameters keep the LLM client's own.
+///
+/// # Example
+///
+/// ```rust
+/// use langgraph::react::{SamplingSchedule, ThinkPhase};
+/// use langgraph::SamplingParams;
+///
+/// let schedule = SamplingSchedule::new()
+///     .with_tool_selection(SamplingParams::temperature(0.0))
+///     .with_final_answer(SamplingParams::temperature(0.8).with_top_p(0.95));
+/// assert_eq!(schedule.for_phase(ThinkPhase::ToolSelection).temperature, Some(0.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingSchedule {
+    /// Used by steps that may call tools.
+    #[serde(default)]
+    pub tool_selection: SamplingParams,
+    /// Used by the final-answer step.
+    #[serde(default)]
+    pub final_answer: SamplingParams,
+}
+
+impl SamplingSchedule {
+    /// Schedule leaving every step at the client's settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the parameters of steps that may call tools.
+    pub fn with_tool_selection(mut self, params: SamplingParams) -> Self {
+        self.tool_selection = params;
+        self
+    }
+
+    /// Sets the parameters of the final-answer step.
+    pub fn with_final_answer(mut self, params: SamplingParams) -> Self {
+        self.final_answer = params;
+        self
+    }
+
+    /// Parameters of a step in `phase`.
+    pub fn for_phase(&self, phase: ThinkPhase) -> &SamplingParams {
+        match phase {
+            ThinkPhase::ToolSelection => &self.tool_selection,
+            ThinkPhase::FinalAnswer => &self.final_answer,
+        }
+    }
+
+    /// The schedule registered in `ctx` under [`SAMPLING_SCHEDULE`], if any.
+    pub fn from_context<S>(ctx: &RunContext<S>) -> Option<Self>
+    where
+        S: Clone + Send + Sync + Debug + 'static,
+    {
+        ctx.get_managed_value(SAMPLING_SCHEDULE)
+            .and_then(|v| serde_json::from_value(v).ok())
+    }
+}
+
+impl<S> ManagedValue<serde_json::Value, S> for SamplingSchedule
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    fn get(&self, _context: &RunContext<S>) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::RunnableConfig;
+    use crate::state::ReActState;
+    use std::sync::Arc;
+
+    /// **Scenario**: A schedule registered in the run context is read back per phase; without
+    /// one there is none.
+    #[test]
+    fn schedule_round_trips_through_run_context() {
+        let schedule = SamplingSchedule::new()
+            .with_tool_selection(SamplingParams::temperature(0.0))
+            .with_final_answer(SamplingParams::temperature(0.9).with_top_p(0.5));
+        let ctx = RunContext::<ReActState>::new(RunnableConfig::default());
+        assert!(SamplingSchedule::from_context(&ctx).is_none());
+
+        let ctx = ctx.with_managed_value(SAMPLING_SCHEDULE, Arc::new(schedule));
+        let read = SamplingSchedule::from_context(&ctx).expect("registered");
+        assert_eq!(read, schedule);
+        assert_eq!(
+            read.for_phase(ThinkPhase::ToolSelection),
+            &SamplingParams::temperature(0.0)
+        );
+        assert_eq!(read.for_phase(ThinkPhase::FinalAnswer).top_p, Some(0.5));
+        assert!(SamplingSchedule::new()
+            .for_phase(ThinkPhase::FinalAnswer)
+            .is_empty());
+    }
+}
@@ -10,6 +10,34 @@
 //! ThinkNode implements `run_with_context` to support Messages streaming. When
 //! `stream_mode` contains `StreamMode::Messages`, it uses `LlmClient::invoke_stream()`
 //! and forwards `MessageChunk` tokens to the stream channel as `StreamEvent::Messages`.
+//! Tool-call deltas arrive in the same events via `MessageChunk::tool_call_chunks`.
+//! Whenever a stream channel is present, `StreamEvent::Usage` follows the LLM call (if
+//! reported); with `StreamMode::Updates`, so does `StreamEvent::ResponseMetadata`. The
+//! metadata is also stored in `ReActState::response_metadata`. Reported usage is added to
+//! `ReActState::usage` (the run's total) and to `RunContext::usage` for the graph's
+//! `RunGovernor`.
+//!
+//! # Last step
+//!
+//! With [`ThinkNode::with_max_turns`], or when the run context carries [`IsLastStep`](crate::IsLastStep)
+//! `true` under [`IS_LAST_STEP`], the node is on its last step: if the model still requests
+//! tools, the calls are dropped and [`NEED_MORE_STEPS_MESSAGE`] becomes the reply, so the run
+//! ends with an answer instead of unexecuted tool calls. The reply of the last step is also
+//! stored in `ReActState::final_answer`, since a governor may end the run before ObserveNode.
+//!
+//! # Sampling
+//!
+//! When the run context carries a [`SamplingSchedule`] under
+//! [`SAMPLING_SCHEDULE`](super::SAMPLING_SCHEDULE), the LLM is called with the schedule's
+//! parameters for the step's phase: final answer on the last step, tool selection otherwise.
+//!
+//! # History compression
+//!
+//! With [`ThinkNode::with_history_compressor`], the messages sent to the LLM (and reported to
+//! `on_llm_start`) are the compressor's output; `state.messages`, and so the checkpoints,
+//! keep the full history.
+
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use tokio::sync::mpsc;
@@ -17,11 +45,18 @@ use tokio::sync::mpsc;
 use crate::error::AgentError;
 use crate::graph::{Next, RunContext};
 use crate::llm::LlmClient;
+use crate::managed::IS_LAST_STEP;
 use crate::message::Message;
-use crate::state::ReActState;
+use crate::state::{ReActState, ToolCall};
 use crate::stream::{MessageChunk, StreamEvent, StreamMetadata, StreamMode};
 use crate::Node;
 
+use super::history::{CompressorSlot, HistoryCompressor};
+use super::sampling::{SamplingSchedule, ThinkPhase};
+
+/// Reply used when the model still requests tools on the last allowed step.
+pub const NEED_MORE_STEPS_MESSAGE: &str = "Sorry, need more steps to process this request.";
+
 /// Think node: one ReAct step that produces assistant message and optional tool_calls.
 ///
 /// Reads `state.messages`, calls the LLM, appends one assistant message and sets
@@ -34,12 +69,64 @@ use crate::Node;
 pub struct ThinkNode {
     /// LLM client used to produce assistant message and optional tool_calls.
     llm: Box<dyn LlmClient>,
+    /// When set, the think step with `turn_count + 1 >= max_turns` is the last step.
+    max_turns: Option<u32>,
+    /// Rewrites the messages sent to the LLM; shared with the runner that built the node.
+    history: CompressorSlot,
 }
 
 impl ThinkNode {
     /// Creates a Think node with the given LLM client.
     pub fn new(llm: Box<dyn LlmClient>) -> Self {
-        Self { llm }
+        Self {
+            llm,
+            max_turns: None,
+            history: CompressorSlot::default(),
+        }
+    }
+
+    /// Sets the loop limit used to detect the last step (should match the observe router).
+    pub fn with_max_turns(mut self, max_turns: u32) -> Self {
+        self.max_turns = Some(max_turns.max(1));
+        self
+    }
+
+    /// Sends the LLM the messages `compressor` makes of `state.messages` (see
+    /// [`HistoryCompressor`]); the state keeps the full history.
+    pub fn with_history_compressor(self, compressor: Arc<dyn HistoryCompressor>) -> Self {
+        self.history.set(compressor);
+        self
+    }
+
+    /// The node's compressor slot, for setting the compressor after the graph was compiled.
+    pub(crate) fn history_slot(&self) -> CompressorSlot {
+        self.history.clone()
+    }
+
+    /// True when this think step is the last one: from `max_turns` or the context's `IsLastStep`.
+    fn is_last_step(&self, state: &ReActState, ctx: Option<&RunContext<ReActState>>) -> bool {
+        let managed = ctx
+            .and_then(|c| c.get_managed_value(IS_LAST_STEP))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        managed
+            || self
+                .max_turns
+                .is_some_and(|max| state.turn_count.saturating_add(1) >= max)
+    }
+}
+
+/// On the last step, replaces a tool-calling reply with [`NEED_MORE_STEPS_MESSAGE`].
+/// Returns the (content, tool_calls) to store and whether the reply was replaced.
+fn finish_last_step(
+    is_last: bool,
+    content: String,
+    tool_calls: Vec<ToolCall>,
+) -> (String, Vec<ToolCall>, bool) {
+    if is_last && !tool_calls.is_empty() {
+        (NEED_MORE_STEPS_MESSAGE.to_string(), vec![], true)
+    } else {
+        (content, tool_calls, false)
     }
 }
 
@@ -52,14 +139,28 @@ impl Node<ReActState> for ThinkNode {
     /// Reads state.messages, calls LLM, appends assistant message and sets tool_calls.
     /// Returns Next::Continue to follow linear edge order (e.g. think → act).
     async fn run(&self, state: ReActState) -> Result<(ReActState, Next), AgentError> {
-        let response = self.llm.invoke(&state.messages).await?;
+        let compressed = self.history.compress(&state.messages).await;
+        let prompt = compressed.as_deref().unwrap_or(&state.messages);
+        let response = self.llm.invoke(prompt).await?;
+        let is_last = self.is_last_step(&state, None);
+        let (content, tool_calls, _) =
+            finish_last_step(is_last, response.content, response.tool_calls);
+        let final_answer = is_last.then(|| content.clone());
+        let mut usage = state.usage;
+        if let Some(u) = response.usage.as_ref() {
+            usage.add(u);
+        }
         let mut messages = state.messages;
-        messages.push(Message::Assistant(response.content));
+        messages.push(Message::assistant_with_tool_calls(content, tool_calls.clone()));
         let new_state = ReActState {
             messages,
-            tool_calls: response.tool_calls,
+            tool_calls,
             tool_results: state.tool_results,
             turn_count: state.turn_count,
+            response_metadata: Some(response.metadata),
+            usage,
+            final_answer,
+            ui_events: state.ui_events,
         };
         Ok((new_state, Next::Continue))
     }
@@ -69,6 +170,7 @@ impl Node<ReActState> for ThinkNode {
     ///
     /// Token chunks are sent as `StreamEvent::Messages` with metadata containing
     /// the node id ("think"). This enables real-time LLM output display (typewriter effect).
+    /// The context's callback handlers are told about the LLM request.
     async fn run_with_context(
         &self,
         state: ReActState,
@@ -76,8 +178,20 @@ impl Node<ReActState> for ThinkNode {
     ) -> Result<(ReActState, Next), AgentError> {
         let should_stream =
             ctx.stream_mode.contains(&StreamMode::Messages) && ctx.stream_tx.is_some();
+        let is_last = self.is_last_step(&state, Some(ctx));
+        let phase = if is_last {
+            ThinkPhase::FinalAnswer
+        } else {
+            ThinkPhase::ToolSelection
+        };
+        let sampling = SamplingSchedule::from_context(ctx)
+            .map(|schedule| *schedule.for_phase(phase))
+            .unwrap_or_default();
 
-        let response = if should_stream {
+        let compressed = self.history.compress(&state.messages).await;
+        let prompt = compressed.as_deref().unwrap_or(&state.messages);
+        ctx.emit_callback(|h, run_id| h.on_llm_start(run_id, prompt));
+        let result = if should_stream {
             // Create internal channel for message chunks
             let (chunk_tx, mut chunk_rx) = mpsc::channel::<MessageChunk>(128);
 
@@ -100,34 +214,48 @@ impl Node<ReActState> for ThinkNode {
             });
 
             // Call LLM with streaming
-            let result = self
-                .llm
-                .invoke_stream(&state.messages, Some(chunk_tx))
-                .await;
+            let result = if sampling.is_empty() {
+                self.llm.invoke_stream(prompt, Some(chunk_tx)).await
+            } else {
+                self.llm
+                    .invoke_stream_with_sampling(prompt, Some(chunk_tx), &sampling)
+                    .await
+            };
 
             // Wait for forwarding task to complete (chunk_tx is dropped after invoke_stream)
             let _ = forward_task.await;
 
-            result?
-        } else {
+            result
+        } else if sampling.is_empty() {
             // Non-streaming path: use regular invoke
-            self.llm.invoke(&state.messages).await?
+            self.llm.invoke(prompt).await
+        } else {
+            self.llm
+                .invoke_stream_with_sampling(prompt, None, &sampling)
+                .await
         };
+        match &result {
+            Ok(response) => ctx.emit_callback(|h, run_id| h.on_llm_end(run_id, response)),
+            Err(e) => ctx.emit_callback(|h, run_id| h.on_llm_error(run_id, e)),
+        }
+        let response = result?;
+
+        let (content, tool_calls, stopped) =
+            finish_last_step(is_last, response.content, response.tool_calls);
 
         // When the model returns no content and no tool calls, still push a fallback reply
         // so the user sees a response (e.g. some APIs return empty content in stream).
-        let used_fallback = response.content.is_empty() && response.tool_calls.is_empty();
+        let used_fallback = content.is_empty() && tool_calls.is_empty();
         let content = if used_fallback {
             "No text response from the model. Please try again or check the API.".to_string()
         } else {
-            response.content
+            content
         };
 
-        // So that streaming clients see the fallback, emit it as a Messages event when streaming.
-        if used_fallback && ctx.stream_tx.is_some() {
-            let fallback_chunk = MessageChunk {
-                content: content.clone(),
-            };
+        // So that streaming clients see the fallback (or last-step reply), emit it as a
+        // Messages event when streaming.
+        if (used_fallback || stopped) && ctx.stream_tx.is_some() {
+            let fallback_chunk = MessageChunk::text(content.clone());
             let _ = ctx.stream_tx.as_ref().unwrap()
                 .send(StreamEvent::Messages {
                     chunk: fallback_chunk,
@@ -138,16 +266,28 @@ impl Node<ReActState> for ThinkNode {
                 .await;
         }
 
+        let mut usage = state.usage;
+        if let Some(u) = response.usage.as_ref() {
+            usage.add(u);
+        }
+        let final_answer = is_last.then(|| content.clone());
         let mut messages = state.messages;
-        messages.push(Message::Assistant(content));
+        messages.push(Message::assistant_with_tool_calls(content, tool_calls.clone()));
         let new_state = ReActState {
             messages,
-            tool_calls: response.tool_calls,
+            tool_calls,
             tool_results: state.tool_results,
             turn_count: state.turn_count,
+            response_metadata: Some(response.metadata.clone()),
+            usage,
+            final_answer,
+            ui_events: state.ui_events,
         };
 
-        // Emit token usage when available so CLI can print when --verbose
+        // Count usage for the run's governor; emit it so CLI can print when --verbose
+        if let Some(u) = response.usage.as_ref() {
+            ctx.record_usage(u);
+        }
         if let (Some(ref tx), Some(ref u)) = (ctx.stream_tx.as_ref(), response.usage.as_ref()) {
             let _ = tx
                 .send(StreamEvent::Usage {
@@ -158,6 +298,16 @@ impl Node<ReActState> for ThinkNode {
                 .await;
         }
 
+        // Emit finish_reason / model / fingerprint with Updates so SSE adapters can close the
+        // stream correctly.
+        if ctx.stream_mode.contains(&StreamMode::Updates) {
+            if let Some(ref tx) = ctx.stream_tx {
+                let _ = tx
+                    .send(StreamEvent::ResponseMetadata(response.metadata))
+                    .await;
+            }
+        }
+
         Ok((new_state, Next::Continue))
     }
 }
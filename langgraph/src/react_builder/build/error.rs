@@ -1,6 +1,7 @@
 //! Error type when building a [`ReactRunner`](crate::react::ReactRunner) from config.
 
 use crate::error::AgentError;
+use crate::features::FeatureMissing;
 use crate::graph::CompilationError;
 
 /// Error when building a [`ReactRunner`](crate::react::ReactRunner) from config.
@@ -12,4 +13,8 @@ pub enum BuildRunnerError {
     Compilation(#[from] CompilationError),
     #[error("no LLM provided and config has no openai_api_key/model; pass Some(llm) or set OPENAI_API_KEY and OPENAI_MODEL")]
     NoLlm,
+    /// The config needs a Cargo feature this build does not have; see
+    /// [`ReactBuildConfig::check_features`](crate::ReactBuildConfig::check_features).
+    #[error(transparent)]
+    FeatureMissing(#[from] FeatureMissing),
 }
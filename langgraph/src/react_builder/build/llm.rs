@@ -4,6 +4,7 @@
 //! passes `llm: None` and expects the library to construct an LLM from config (e.g. env or
 //! CLI). It reads `openai_api_key`, `model`, and optionally `openai_base_url` from the config
 //! and returns a [`LlmClient`](crate::LlmClient) implemented by [`ChatOpenAI`](crate::llm::ChatOpenAI).
+//! When the Azure endpoint and deployment are set, the client targets Azure OpenAI instead.
 
 use crate::llm::ChatOpenAI;
 use crate::LlmClient;
@@ -37,9 +38,20 @@ use super::super::config::ReactBuildConfig;
 /// * **Base URL**: If `config.openai_base_url` is set and non-empty, it is used (trailing slash
 ///   trimmed); otherwise the default OpenAI API base is used via
 ///   [`OpenAIConfig`](async_openai::config::OpenAIConfig).
+/// * **Azure**: When [`ReactBuildConfig::uses_azure_openai`] is true, builds with
+///   [`AzureConfig`](async_openai::config::AzureConfig) from the `azure_openai_*` fields; the
+///   key is `azure_openai_api_key`, falling back to `openai_api_key`.
+/// * **Failover**: `openai_fallback_base_urls` (or `azure_openai_fallback_endpoints` for Azure)
+///   are added as regions with [`ChatOpenAI::with_region`]; requests fail over to them.
+/// * **Tool choice**: Taken from `config.prompt_profile` when the profile has a default.
+/// * **HTTP client**: Requests go through `config.http_client`'s shared client, so runners built
+///   from equal configs reuse one connection pool.
 pub(crate) fn build_default_llm(config: &ReactBuildConfig) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
     use async_openai::config::OpenAIConfig;
 
+    if config.uses_azure_openai() {
+        return build_azure_llm(config);
+    }
     let api_key = config
         .openai_api_key
         .as_deref()
@@ -57,6 +69,62 @@ pub(crate) fn build_default_llm(config: &ReactBuildConfig) -> Result<Box<dyn Llm
             openai_config = openai_config.with_api_base(base);
         }
     }
-    let client = ChatOpenAI::with_config(openai_config, model);
-    Ok(Box::new(client))
+    let mut client = ChatOpenAI::with_config(openai_config.clone(), model);
+    for base in &config.openai_fallback_base_urls {
+        let base = base.trim_end_matches('/');
+        if base.is_empty() {
+            continue;
+        }
+        client = client.with_region(openai_config.clone().with_api_base(base));
+    }
+    Ok(Box::new(apply_profile(config, client)?))
+}
+
+/// Builds the default LLM against an Azure OpenAI deployment.
+fn build_azure_llm(config: &ReactBuildConfig) -> Result<Box<dyn LlmClient>, BuildRunnerError> {
+    use async_openai::config::AzureConfig;
+
+    let api_key = config
+        .azure_openai_api_key
+        .as_deref()
+        .or(config.openai_api_key.as_deref())
+        .filter(|s| !s.is_empty())
+        .ok_or(BuildRunnerError::NoLlm)?;
+    let endpoint = config
+        .azure_openai_endpoint
+        .as_deref()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let deployment = config.azure_openai_deployment.as_deref().unwrap_or_default();
+    let api_version = config
+        .azure_openai_api_version
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(ReactBuildConfig::DEFAULT_AZURE_OPENAI_API_VERSION);
+    let azure_config = AzureConfig::new()
+        .with_api_base(endpoint)
+        .with_api_version(api_version)
+        .with_deployment_id(deployment)
+        .with_api_key(api_key);
+    let mut client = ChatOpenAI::with_azure_config(azure_config.clone(), deployment);
+    for endpoint in &config.azure_openai_fallback_endpoints {
+        let endpoint = endpoint.trim_end_matches('/');
+        if endpoint.is_empty() {
+            continue;
+        }
+        client = client.with_region(azure_config.clone().with_api_base(endpoint));
+    }
+    Ok(Box::new(apply_profile(config, client)?))
+}
+
+/// Applies the shared HTTP client and the profile's tool choice.
+fn apply_profile(
+    config: &ReactBuildConfig,
+    client: ChatOpenAI,
+) -> Result<ChatOpenAI, BuildRunnerError> {
+    let client = client.with_http_client(config.http_client.shared_client()?);
+    Ok(match config.prompt_profile.and_then(|p| p.tool_choice()) {
+        Some(mode) => client.with_tool_choice(mode),
+        None => client,
+    })
 }
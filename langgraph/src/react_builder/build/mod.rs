@@ -12,8 +12,13 @@ mod tool_source;
 use std::sync::Arc;
 
 use crate::error::AgentError;
-use crate::memory::{JsonSerializer, RunnableConfig, SqliteSaver};
-use crate::react::ReactRunner;
+#[cfg(not(all(feature = "postgres", feature = "redis")))]
+use crate::features::FeatureMissing;
+use crate::memory::{
+    DbRouter, JsonSerializer, ReadOnlyCheckpointer, ReadOnlyStore, ReadOnlySwitch, RunnableConfig,
+    SqliteSaver, SqliteTenants,
+};
+use crate::react::{MemoryWriter, ReactRunner, MAX_REACT_TURNS};
 use crate::state::ReActState;
 use crate::LlmClient;
 
@@ -30,7 +35,10 @@ fn to_agent_error(e: impl std::fmt::Display) -> AgentError {
 }
 
 /// Builds checkpointer when thread_id is set; otherwise returns None.
-fn build_checkpointer(
+/// Uses Redis when `redis_url` is set, else Postgres when `database_url` is set, else one SQLite
+/// file per user in `tenant_db_dir` when set, else SQLite at `db_path`. SQLite checkpointers
+/// apply [`ReactBuildConfig::checkpoint_retention`] and, when set, `checkpoint_sync`.
+async fn build_checkpointer(
     config: &ReactBuildConfig,
     db_path: &str,
 ) -> Result<Option<Arc<dyn crate::memory::Checkpointer<ReActState>>>, AgentError> {
@@ -38,12 +46,80 @@ fn build_checkpointer(
         return Ok(None);
     }
     let serializer = Arc::new(JsonSerializer);
-    let saver = SqliteSaver::new(db_path, serializer).map_err(to_agent_error)?;
+    let retention = config.checkpoint_retention();
+    if !retention.is_unbounded() && (config.redis_url.is_some() || config.database_url.is_some()) {
+        tracing::warn!("checkpoint retention is only enforced for SQLite checkpoints; ignored");
+    }
+    if let Some(url) = config.redis_url.as_deref() {
+        return build_redis_checkpointer(url, config.redis_ttl_secs, serializer)
+            .await
+            .map(Some);
+    }
+    if let Some(url) = config.database_url.as_deref() {
+        return build_postgres_checkpointer(url, serializer).await.map(Some);
+    }
+    if let Some(dir) = config.tenant_db_dir.as_deref() {
+        let mut tenants = SqliteTenants::new(dir, serializer).with_retention(retention);
+        if let Some(sync) = config.checkpoint_sync {
+            tenants = tenants.with_write_behind(sync);
+        }
+        let router = Arc::new(DbRouter::<ReActState>::new(tenants));
+        return Ok(Some(Arc::new(router.checkpointer())));
+    }
+    let mut saver = SqliteSaver::new(db_path, serializer)
+        .map_err(to_agent_error)?
+        .with_retention(retention);
+    if let Some(sync) = config.checkpoint_sync {
+        saver = saver.with_write_behind(sync);
+    }
     Ok(Some(
         Arc::new(saver) as Arc<dyn crate::memory::Checkpointer<ReActState>>
     ))
 }
 
+#[cfg(feature = "postgres")]
+async fn build_postgres_checkpointer(
+    url: &str,
+    serializer: Arc<JsonSerializer>,
+) -> Result<Arc<dyn crate::memory::Checkpointer<ReActState>>, AgentError> {
+    let saver = crate::memory::PostgresSaver::<ReActState>::connect(url, serializer)
+        .await
+        .map_err(to_agent_error)?;
+    Ok(Arc::new(saver))
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn build_postgres_checkpointer(
+    _url: &str,
+    _serializer: Arc<JsonSerializer>,
+) -> Result<Arc<dyn crate::memory::Checkpointer<ReActState>>, AgentError> {
+    Err(FeatureMissing::new("postgres", "DATABASE_URL").into())
+}
+
+#[cfg(feature = "redis")]
+async fn build_redis_checkpointer(
+    url: &str,
+    ttl_secs: Option<u64>,
+    serializer: Arc<JsonSerializer>,
+) -> Result<Arc<dyn crate::memory::Checkpointer<ReActState>>, AgentError> {
+    let mut saver = crate::memory::RedisSaver::<ReActState>::connect(url, serializer)
+        .await
+        .map_err(to_agent_error)?;
+    if let Some(secs) = ttl_secs {
+        saver = saver.with_ttl(std::time::Duration::from_secs(secs));
+    }
+    Ok(Arc::new(saver))
+}
+
+#[cfg(not(feature = "redis"))]
+async fn build_redis_checkpointer(
+    _url: &str,
+    _ttl_secs: Option<u64>,
+    _serializer: Arc<JsonSerializer>,
+) -> Result<Arc<dyn crate::memory::Checkpointer<ReActState>>, AgentError> {
+    Err(FeatureMissing::new("redis", "REDIS_URL").into())
+}
+
 /// Builds runnable_config when thread_id or user_id is set; otherwise returns None.
 fn build_runnable_config(config: &ReactBuildConfig) -> Option<RunnableConfig> {
     if config.thread_id.is_none() && config.user_id.is_none() {
@@ -54,6 +130,7 @@ fn build_runnable_config(config: &ReactBuildConfig) -> Option<RunnableConfig> {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: config.user_id.clone(),
+        ..Default::default()
     })
 }
 
@@ -64,10 +141,37 @@ fn build_runnable_config(config: &ReactBuildConfig) -> Option<RunnableConfig> {
 pub async fn build_react_run_context(
     config: &ReactBuildConfig,
 ) -> Result<ReactRunContext, AgentError> {
+    build_run_context(config, None).await
+}
+
+/// Like [`build_react_run_context`], with the checkpointer and store wrapped in
+/// [`ReadOnlyCheckpointer`] / [`ReadOnlyStore`]: while `switch` is on, checkpoints are not
+/// written and store writes (including memory tools) fail. Reads are unaffected.
+pub async fn build_react_run_context_with_read_only(
+    config: &ReactBuildConfig,
+    switch: &ReadOnlySwitch,
+) -> Result<ReactRunContext, AgentError> {
+    build_run_context(config, Some(switch)).await
+}
+
+async fn build_run_context(
+    config: &ReactBuildConfig,
+    read_only: Option<&ReadOnlySwitch>,
+) -> Result<ReactRunContext, AgentError> {
+    config.check_features()?;
     let db_path = config.db_path.as_deref().unwrap_or("memory.db");
 
-    let checkpointer = build_checkpointer(config, db_path)?;
-    let store = build_store(config, db_path)?;
+    let mut checkpointer = build_checkpointer(config, db_path).await?;
+    let mut store = build_store(config, db_path)?;
+    if let Some(switch) = read_only {
+        checkpointer = checkpointer.map(|inner| {
+            Arc::new(ReadOnlyCheckpointer::new(inner, switch.clone()))
+                as Arc<dyn crate::memory::Checkpointer<ReActState>>
+        });
+        store = store.map(|inner| {
+            Arc::new(ReadOnlyStore::new(inner, switch.clone())) as Arc<dyn crate::memory::Store>
+        });
+    }
     let runnable_config = build_runnable_config(config);
     let tool_source = build_tool_source(config, &store).await?;
 
@@ -83,30 +187,79 @@ pub async fn build_react_run_context(
 ///
 /// When `llm` is `Some`, that client is used. When `llm` is `None`, the library builds a default
 /// LLM from config if `openai_api_key` and `model` (or env) are set (requires `openai` feature);
-/// otherwise returns [`BuildRunnerError::NoLlm`].
+/// otherwise returns [`BuildRunnerError::NoLlm`]. A config needing a Cargo feature this build
+/// lacks fails first with [`BuildRunnerError::FeatureMissing`], before anything is connected.
 ///
 /// Uses [`build_react_run_context`](build_react_run_context) for persistence and tool source,
-/// then compiles the ReAct graph with optional checkpointer and passes `config.system_prompt`
-/// into the runner for initial state.
+/// then compiles the ReAct graph with optional checkpointer and passes
+/// [`ReactBuildConfig::effective_system_prompt`] into the runner for initial state. The loop
+/// is capped at `config.max_turns` rounds (default [`MAX_REACT_TURNS`]), pauses before
+/// the nodes in `config.interrupt_before`, aborts steps longer than
+/// `config.node_timeout_secs`, enforces [`ReactBuildConfig::budget_policy`] and
+/// [`ReactBuildConfig::usage_quota`], and compresses long histories with
+/// [`ReactBuildConfig::history_compressor`] (summaries by a second default LLM). With
+/// `config.memory_extraction` and a store, a [`MemoryWriter`] on another default LLM saves
+/// facts from each turn to the user's memory.
 pub async fn build_react_runner(
     config: &ReactBuildConfig,
     llm: Option<Box<dyn LlmClient>>,
     verbose: bool,
 ) -> Result<ReactRunner, BuildRunnerError> {
+    config.check_features()?;
     let ctx = build_react_run_context(config).await?;
+    let store = ctx.store.clone();
     let llm = match llm {
         Some(l) => l,
         None => build_default_llm(config)?,
     };
-    let runner = ReactRunner::new(
+    let runner = ReactRunner::new_with_max_turns(
         llm,
         ctx.tool_source,
         ctx.checkpointer,
         ctx.store,
         ctx.runnable_config,
-        config.system_prompt.clone(),
+        Some(config.effective_system_prompt()),
         verbose,
-    )?;
+        config.max_turns.unwrap_or(MAX_REACT_TURNS),
+    )?
+    .with_interrupt_before(&config.interrupt_before)?;
+    let runner = match config.node_timeout_secs {
+        Some(secs) => runner.with_node_timeout(std::time::Duration::from_secs(secs)),
+        None => runner,
+    };
+    let runner = match config.budget_policy() {
+        Some(budget) => runner.with_budget(budget),
+        None => runner,
+    };
+    let quota = config
+        .usage_quota()
+        .map_err(|e| AgentError::ExecutionFailed(format!("usage quota: {}", e)))?;
+    let runner = match quota {
+        Some(quota) => runner.with_usage_quota(quota),
+        None => runner,
+    };
+    let summarizer = config
+        .history_summary_turns
+        .and_then(|_| build_default_llm(config).ok())
+        .map(Arc::from);
+    let runner = match config.history_compressor(summarizer) {
+        Some(compressor) => runner.with_history_compressor(compressor),
+        None => runner,
+    };
+    let runner = match (config.memory_extraction, store) {
+        (true, Some(store)) => match build_default_llm(config) {
+            Ok(llm) => runner.with_memory_writer(MemoryWriter::new(Arc::from(llm), store)),
+            Err(e) => {
+                tracing::warn!(error = %e, "no LLM for memory extraction; not extracting");
+                runner
+            }
+        },
+        (true, None) => {
+            tracing::warn!("memory extraction needs a store (user_id); not extracting");
+            runner
+        }
+        (false, _) => runner,
+    };
     Ok(runner)
 }
 
@@ -120,6 +273,7 @@ pub async fn build_react_runner_with_openai(
     verbose: bool,
 ) -> Result<ReactRunner, BuildRunnerError> {
     use crate::llm::ChatOpenAI;
-    let client = ChatOpenAI::with_config(openai_config, model);
+    let client = ChatOpenAI::with_config(openai_config, model)
+        .with_http_client(config.http_client.shared_client()?);
     build_react_runner(config, Some(Box::new(client)), verbose).await
 }
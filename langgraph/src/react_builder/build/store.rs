@@ -28,7 +28,7 @@ fn build_vector_store(
     config: &ReactBuildConfig,
 ) -> Result<Arc<dyn crate::memory::Store>, AgentError> {
     use async_openai::config::OpenAIConfig;
-    use crate::memory::{InMemoryVectorStore, OpenAIEmbedder};
+    use crate::memory::{Embedder, EmbeddingCache, InMemoryVectorStore, OpenAIEmbedder};
 
     let api_key = config
         .embedding_api_key
@@ -51,7 +51,16 @@ fn build_vector_store(
         let b = b.trim_end_matches('/');
         openai_config = openai_config.with_api_base(b);
     }
-    let embedder = OpenAIEmbedder::with_config(openai_config, model);
-    let store = InMemoryVectorStore::new(Arc::new(embedder));
+    let mut embedder = OpenAIEmbedder::with_config(openai_config, model)
+        .with_http_client(config.http_client.shared_client()?);
+    if let Some(batch_size) = config.embedding_batch_size {
+        embedder = embedder.with_batch_size(batch_size);
+    }
+    let embedder: Arc<dyn Embedder> = if config.embedding_cache {
+        Arc::new(EmbeddingCache::in_memory(Arc::new(embedder)))
+    } else {
+        Arc::new(embedder)
+    };
+    let store = InMemoryVectorStore::new(embedder);
     Ok(Arc::new(store) as Arc<dyn crate::memory::Store>)
 }
@@ -1,14 +1,23 @@
 //! Builds tool source from [`ReactBuildConfig`](super::super::config::ReactBuildConfig).
 //!
-//! Always includes web_fetcher (WebToolsSource). When no memory and no Exa, returns
-//! an `AggregateToolSource` with only web_fetcher; otherwise `AggregateToolSource`
-//! with optional `MemoryToolsSource`, optional MCP Exa, and web_fetcher.
+//! Returns an `AggregateToolSource` that always includes web_fetcher and get_time
+//! (`TimeTool`), with optional memory tools (`MemoryToolkit`) and optional MCP Exa.
+//! web_fetcher and MCP over HTTP use `config.http_client` (proxy, custom CA).
+//! Every MCP server of `config.all_mcp_servers()` (the servers file, `mcp_servers` and skills)
+//! is connected and its tools registered, under the server's `tool_prefix` when set. When skills
+//! list `builtin` tools, the other built-in tools are hidden behind an `AllowlistToolSource`.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::error::AgentError;
-use crate::tool_source::{MemoryToolsSource, ToolSource, WebToolsSource};
-use crate::tools::{register_mcp_tools, AggregateToolSource, WebFetcherTool};
+use crate::tool_source::{
+    AllowlistToolSource, McpServerConfig, McpTransport, MemoryToolkit, ToolAllowlist, ToolSource,
+};
+use crate::tools::{
+    register_mcp_tools, register_mcp_tools_with_prefix, AggregateToolSource, MemoryDedup, TimeTool,
+    WebFetcherTool,
+};
 
 use crate::tool_source::McpToolSource;
 
@@ -35,9 +44,13 @@ async fn register_exa_mcp(
     let use_http = url.starts_with("http://") || url.starts_with("https://");
 
     let mcp = if use_http {
-        McpToolSource::new_http(url, [("EXA_API_KEY", key.as_str())])
-            .await
-            .map_err(to_agent_error)?
+        McpToolSource::new_http_with_config(
+            url,
+            [("EXA_API_KEY", key.as_str())],
+            &config.http_client,
+        )
+        .await
+        .map_err(to_agent_error)?
     } else {
         let args: Vec<String> = config
             .mcp_remote_args
@@ -69,35 +82,98 @@ async fn register_exa_mcp(
     Ok(())
 }
 
-/// Builds tool source: MockToolSource when no memory and no Exa; otherwise AggregateToolSource
-/// with optional MemoryToolsSource and optional MCP Exa.
+/// Connects each of `servers` and registers its tools on the aggregate.
+async fn register_mcp_servers(
+    config: &ReactBuildConfig,
+    servers: &[McpServerConfig],
+    aggregate: &AggregateToolSource,
+) -> Result<(), AgentError> {
+    for server in servers {
+        let mcp = match server.transport().map_err(to_agent_error)? {
+            McpTransport::Http { url, headers } => {
+                McpToolSource::new_http_with_config(url, headers, &config.http_client)
+                    .await
+                    .map_err(to_agent_error)?
+            }
+            McpTransport::Stdio { command, args, env } => {
+                McpToolSource::new_with_env(command, args, env, config.mcp_verbose)
+                    .map_err(to_agent_error)?
+            }
+        };
+        register_mcp_tools_with_prefix(aggregate, Arc::new(mcp), server.tool_prefix.as_deref())
+            .await
+            .map_err(|e| to_agent_error(format!("MCP server {}: {}", server.name, e)))?;
+    }
+    Ok(())
+}
+
+/// Wraps `aggregate` so only the built-in tools `config.skills` ask for (and every MCP tool)
+/// are offered; `builtin` names the built-in tools registered on it.
+async fn restrict_builtin_tools(
+    config: &ReactBuildConfig,
+    aggregate: AggregateToolSource,
+    builtin: HashSet<String>,
+) -> Result<Box<dyn ToolSource>, AgentError> {
+    let Some(wanted) = config.skills.builtin_tools() else {
+        return Ok(Box::new(aggregate));
+    };
+    for name in wanted.iter().filter(|n| !builtin.contains(*n)) {
+        tracing::warn!(tool = %name, "skill asks for a built-in tool that is not available");
+    }
+    let enabled: Vec<String> = aggregate
+        .list_tools()
+        .await
+        .map_err(to_agent_error)?
+        .into_iter()
+        .map(|t| t.name)
+        .filter(|n| !builtin.contains(n) || wanted.contains(n))
+        .collect();
+    let allowlist = ToolAllowlist::new();
+    allowlist.set(Some(enabled));
+    Ok(Box::new(AllowlistToolSource::new(
+        Box::new(aggregate),
+        allowlist,
+    )))
+}
+
+/// Builds tool source: AggregateToolSource with web_fetcher and get_time, optional memory tools
+/// ([`MemoryToolkit`] plus get_recent_messages), optional MCP Exa and the configured MCP servers.
 /// Long-term memory is enabled by default when store is available; namespace is
 /// `[user_id, "memories"]` when config.user_id is set, else `["default", "memories"]`.
 pub(crate) async fn build_tool_source(
     config: &ReactBuildConfig,
     store: &Option<Arc<dyn crate::memory::Store>>,
 ) -> Result<Box<dyn ToolSource>, AgentError> {
-    let has_memory = store.is_some();
-    let has_exa = config.exa_api_key.is_some();
-
-    if !has_memory && !has_exa {
-        return Ok(Box::new(WebToolsSource::new().await));
-    }
+    let http_client = config.http_client.shared_client()?;
+    let mcp_servers = config.all_mcp_servers().map_err(to_agent_error)?;
 
-    let aggregate = if has_memory {
-        let s = store.as_ref().unwrap();
+    let aggregate = AggregateToolSource::new();
+    if let Some(s) = store {
         let namespace: Vec<String> = config
             .user_id
             .as_ref()
             .map(|u| vec![u.clone(), "memories".to_string()])
             .unwrap_or_else(|| DEFAULT_MEMORY_NAMESPACE.iter().map(|s| (*s).to_string()).collect());
-        MemoryToolsSource::new(s.clone(), namespace).await
-    } else {
-        AggregateToolSource::new()
-    };
+        let mut toolkit = MemoryToolkit::new(s.clone(), namespace).with_recent_messages(true);
+        if let Some(threshold) = config.memory_dedup_threshold {
+            toolkit = toolkit.with_dedup(MemoryDedup::new(threshold));
+        }
+        toolkit.register(&aggregate).await;
+    }
 
-    aggregate.register_async(Box::new(WebFetcherTool::new())).await;
+    aggregate
+        .register_async(Box::new(WebFetcherTool::with_client(http_client)))
+        .await;
+    aggregate.register_async(Box::new(TimeTool::new())).await;
+    let builtin: HashSet<String> = aggregate
+        .list_tools()
+        .await
+        .map_err(to_agent_error)?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
     register_exa_mcp(config, &aggregate).await?;
+    register_mcp_servers(config, &mcp_servers, &aggregate).await?;
 
-    Ok(Box::new(aggregate))
+    restrict_builtin_tools(config, aggregate, builtin).await
 }
@@ -43,6 +43,7 @@
 //! | Function | Description |
 //! |----------|-------------|
 //! | [`build_react_run_context`] | Builds checkpointer, store, runnable_config and tool_source from config. Returns [`ReactRunContext`]. |
+//! | [`build_react_run_context_with_read_only`] | Same, with checkpointer and store wrapped to stop writing while a [`ReadOnlySwitch`](crate::memory::ReadOnlySwitch) is on. |
 //! | [`build_react_runner`] | Builds a [`ReactRunner`](crate::ReactRunner) from config and optional LLM. When `llm: None`, constructs default OpenAI LLM from config. |
 //! | [`build_react_runner_with_openai`] | Convenience when you already have an [`OpenAIConfig`](async_openai::config::OpenAIConfig). Wraps [`build_react_runner`] with a pre-built OpenAI client. |
 //!
@@ -53,9 +54,18 @@
 //! | Variable | Description | Default |
 //! |----------|-------------|---------|
 //! | `DB_PATH` | SQLite database path for checkpointer/store | None (uses "memory.db" at build time) |
+//! | `DATABASE_URL` | Postgres URL; the checkpointer becomes a `PostgresSaver` (feature `postgres`) | None |
+//! | `REDIS_URL` | Redis URL; the checkpointer becomes a `RedisSaver` (feature `redis`), overriding `DATABASE_URL` | None |
+//! | `REDIS_TTL_SECS` | Seconds after the last write a thread's Redis checkpoints expire | None (never) |
+//! | `CHECKPOINT_MAX_PER_THREAD` | Checkpoints each thread keeps in SQLite; older ones are deleted on write | None (all) |
+//! | `CHECKPOINT_MAX_AGE_SECS` | Seconds after which SQLite checkpoints are deleted, except a thread's newest | None (never) |
 //! | `THREAD_ID` | Thread ID for short-term memory; enables checkpointer when set | None |
 //! | `USER_ID` | User ID for long-term memory; enables store when set | None |
 //! | `REACT_SYSTEM_PROMPT` | System prompt for the agent | None (library default) |
+//! | `REACT_PROMPT_PROFILE` | Built-in prompt profile (default, strict-tools, conversational, coding-agent, research-agent) | `default` |
+//! | `REACT_MAX_TURNS` | Maximum think → act → observe rounds per invoke | `10` |
+//! | `REACT_INTERRUPT_BEFORE` | Comma-separated ReAct nodes to pause before (e.g. `act`) | None |
+//! | `REACT_NODE_TIMEOUT_SECS` | Abort a think / act / observe step after this many seconds | None |
 //! | `EXA_API_KEY` | Exa API key; enables MCP Exa when set | None |
 //! | `MCP_EXA_URL` | Exa MCP server URL | `"https://mcp.exa.ai/mcp"` |
 //! | `MCP_REMOTE_CMD` | Command for mcp-remote (stdio→HTTP bridge) | `"npx"` |
@@ -64,13 +74,26 @@
 //! | `OPENAI_API_KEY` | OpenAI API key for default LLM | None |
 //! | `OPENAI_BASE_URL` | OpenAI API base URL | None |
 //! | `OPENAI_MODEL` | Model name (e.g. gpt-4o-mini) | None |
+//! | `AZURE_OPENAI_ENDPOINT` | Azure OpenAI endpoint; with `AZURE_OPENAI_DEPLOYMENT`, the default LLM uses Azure | None |
+//! | `AZURE_OPENAI_API_VERSION` | Azure OpenAI API version | `"2024-10-21"` |
+//! | `AZURE_OPENAI_DEPLOYMENT` | Azure OpenAI deployment name (used as model) | None |
+//! | `AZURE_OPENAI_API_KEY` | Azure OpenAI API key | `OPENAI_API_KEY` |
 //! | `EMBEDDING_API_KEY` | Embedding API key for long-term memory | None |
 //! | `EMBEDDING_API_BASE` | Embedding API base URL | None |
 //! | `EMBEDDING_MODEL` | Embedding model (e.g. text-embedding-3-small) | None |
+//! | `LLM_HTTP_POOL_MAX_IDLE` | Max idle LLM connections kept per host | reqwest default |
+//! | `LLM_HTTP_POOL_IDLE_TIMEOUT_SECS` | Seconds an idle LLM connection is kept | reqwest default (90) |
+//! | `LLM_HTTP_TCP_KEEPALIVE_SECS` | TCP keep-alive interval for LLM connections | off |
+//! | `LLM_HTTP2_PRIOR_KNOWLEDGE` | Use HTTP/2 without negotiation (h2-only backends) | `false` |
+//! | `LLM_HTTP_PROXY` | HTTP or SOCKS5 proxy URL for all outbound requests (LLM, embeddings, web_fetcher, MCP over HTTP) | None (system proxy env) |
+//! | `LLM_HTTP_CONNECT_TIMEOUT_SECS` | Connect timeout for LLM requests | None |
+//! | `LLM_HTTP_CA_BUNDLE` | PEM file of extra root CAs trusted by all outbound requests | None |
 //!
 //! # Feature requirements
 //!
 //! - **sqlite**: Required for `SqliteSaver` (checkpointer) and `SqliteStore`. Without it, checkpointer/store building will fail when `thread_id`/`user_id` are set.
+//! - **postgres**: Required when `DATABASE_URL` is set (`PostgresSaver`); without it, building fails with [`FeatureMissing`](crate::FeatureMissing).
+//! - **redis**: Required when `REDIS_URL` is set (`RedisSaver`); without it, building fails with [`FeatureMissing`](crate::FeatureMissing).
 //! - **mcp**: Required for MCP Exa tool source. Without it, Exa search tools will not be available even when `EXA_API_KEY` is set.
 //! - **openai**: Required when using `build_react_runner(config, None, _)` to construct the default LLM from config.
 //!
@@ -111,7 +134,7 @@ mod build;
 mod config;
 
 pub use build::{
-    build_react_run_context, build_react_runner, build_react_runner_with_openai, BuildRunnerError,
-    ReactRunContext,
+    build_react_run_context, build_react_run_context_with_read_only, build_react_runner,
+    build_react_runner_with_openai, BuildRunnerError, ReactRunContext,
 };
 pub use config::ReactBuildConfig;
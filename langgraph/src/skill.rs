@@ -0,0 +1,387 @@
+//! Skills: agent capabilities packaged as TOML manifests outside the binary.
+//!
+//! A skill bundles a system prompt fragment, the tools it needs (built-in tools by name and MCP
+//! servers) and optional few-shot examples, so a capability such as "research" can be shared,
+//! versioned and loaded at runtime (`--skill ./skills/research.toml`):
+//!
+//! ```toml
+//! name = "research"
+//! version = "1.2.0"
+//! description = "Searches the web and cites its sources."
+//! prompt = "Search before answering and cite every source you used."
+//!
+//! [tools]
+//! builtin = ["web_fetcher"]
+//!
+//! [[tools.mcp]]
+//! name = "exa"
+//! url = "https://mcp.exa.ai/mcp"
+//! headers = { EXA_API_KEY = "${EXA_API_KEY}" }
+//!
+//! [[examples]]
+//! user = "Who won the 2018 World Cup?"
+//! assistant = "France won the 2018 World Cup [1].\n\n[1] https://www.fifa.com/..."
+//! ```
+//!
+//! Skills combine in a [`SkillSet`]: prompt fragments and examples are appended to the system
+//! prompt in load order, MCP servers of every skill are connected, and skills that list
+//! `builtin` tools restrict the built-in tools to the union of their lists (skills without the
+//! key leave them alone). MCP servers are [`McpServerConfig`]s: a `command` (stdio) or a `url`
+//! (Streamable HTTP), whose `${VAR}` in args, env and headers is read from the environment when
+//! they connect, so manifests need not contain secrets.
+//!
+//! **Interaction**: Carried on [`ReactBuildConfig::skills`](crate::ReactBuildConfig::skills):
+//! `effective_system_prompt` appends [`SkillSet::prompt`], `build_tool_source` connects
+//! [`SkillSet::mcp_servers`] and applies [`SkillSet::builtin_tools`]. The CLI and langgraph-server
+//! load manifests given with `--skill`.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::tool_source::{McpConfigError, McpServerConfig};
+
+/// Error loading, combining or configuring skills.
+#[derive(Debug, thiserror::Error)]
+pub enum SkillError {
+    #[error("read skill {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("parse skill {}: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+    /// The manifest parsed but describes no usable skill.
+    #[error("invalid skill {skill}: {message}")]
+    Invalid { skill: String, message: String },
+    #[error("skill {0} loaded twice")]
+    Duplicate(String),
+    /// Two skills declare MCP servers of the same name with different settings.
+    #[error("MCP server {0} is declared differently by two skills")]
+    McpConflict(String),
+    /// An MCP server of a skill is misconfigured.
+    #[error(transparent)]
+    Mcp(#[from] McpConfigError),
+}
+
+/// One skill manifest.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Skill {
+    /// Unique name, e.g. `research`.
+    pub name: String,
+    /// Version of the package, e.g. `1.2.0`; only reported.
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Fragment appended to the system prompt.
+    #[serde(default)]
+    pub prompt: String,
+    #[serde(default)]
+    pub tools: SkillTools,
+    /// Few-shot examples, shown to the model after the prompt fragment.
+    #[serde(default)]
+    pub examples: Vec<SkillExample>,
+}
+
+/// Tools a skill needs.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillTools {
+    /// Built-in tools by name (e.g. `web_fetcher`, `remember`); None leaves them all enabled.
+    #[serde(default)]
+    pub builtin: Option<Vec<String>>,
+    #[serde(default)]
+    pub mcp: Vec<McpServerConfig>,
+}
+
+/// Question and answer shown to the model as an example.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillExample {
+    pub user: String,
+    pub assistant: String,
+}
+
+impl Skill {
+    /// Reads and validates the manifest at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SkillError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| SkillError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml(&text).map_err(|e| match e {
+            SkillError::Parse { message, .. } => SkillError::Parse {
+                path: path.to_path_buf(),
+                message,
+            },
+            e => e,
+        })
+    }
+
+    /// Parses and validates a manifest.
+    pub fn from_toml(text: &str) -> Result<Self, SkillError> {
+        let skill: Skill = toml::from_str(text).map_err(|e| SkillError::Parse {
+            path: PathBuf::new(),
+            message: e.to_string(),
+        })?;
+        skill.validate()?;
+        Ok(skill)
+    }
+
+    fn validate(&self) -> Result<(), SkillError> {
+        let invalid = |message: String| SkillError::Invalid {
+            skill: self.name.clone(),
+            message,
+        };
+        if self.name.trim().is_empty() {
+            return Err(invalid("name must not be empty".into()));
+        }
+        if self.prompt.trim().is_empty()
+            && self.examples.is_empty()
+            && self.tools == SkillTools::default()
+        {
+            return Err(invalid("needs a prompt, examples or tools".into()));
+        }
+        for server in &self.tools.mcp {
+            server.validate()?;
+        }
+        Ok(())
+    }
+
+    /// `name` with its version, e.g. `research@1.2.0`.
+    pub fn label(&self) -> String {
+        match &self.version {
+            Some(v) => format!("{}@{}", self.name, v),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Prompt fragment and examples as one system prompt section.
+    fn prompt_section(&self) -> String {
+        let mut section = format!("## Skill: {}", self.name);
+        if !self.prompt.trim().is_empty() {
+            section.push_str("\n\n");
+            section.push_str(self.prompt.trim());
+        }
+        if !self.examples.is_empty() {
+            section.push_str("\n\nExamples:");
+            for example in &self.examples {
+                section.push_str(&format!(
+                    "\n\nUser: {}\nAssistant: {}",
+                    example.user.trim(),
+                    example.assistant.trim()
+                ));
+            }
+        }
+        section
+    }
+}
+
+/// Skills in load order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillSet {
+    skills: Vec<Skill>,
+}
+
+impl SkillSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the manifests at `paths`, in order.
+    pub fn load<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Result<Self, SkillError> {
+        let mut set = Self::new();
+        for path in paths {
+            set.push(Skill::load(path)?)?;
+        }
+        Ok(set)
+    }
+
+    /// Adds `skill` after the loaded ones. Fails when a skill of the same name is loaded or an
+    /// MCP server of the same name is declared with other settings.
+    pub fn push(&mut self, skill: Skill) -> Result<(), SkillError> {
+        if self.skills.iter().any(|s| s.name == skill.name) {
+            return Err(SkillError::Duplicate(skill.name));
+        }
+        for server in &skill.tools.mcp {
+            let declared = self.mcp_servers().find(|s| s.name == server.name);
+            if declared.is_some_and(|s| s != server) {
+                return Err(SkillError::McpConflict(server.name.clone()));
+            }
+        }
+        self.skills.push(skill);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    pub fn skills(&self) -> &[Skill] {
+        &self.skills
+    }
+
+    /// Skill labels (`name@version`), in load order.
+    pub fn labels(&self) -> Vec<String> {
+        self.skills.iter().map(Skill::label).collect()
+    }
+
+    /// System prompt sections of every skill; None without skills.
+    pub fn prompt(&self) -> Option<String> {
+        if self.skills.is_empty() {
+            return None;
+        }
+        let sections: Vec<String> = self.skills.iter().map(Skill::prompt_section).collect();
+        Some(sections.join("\n\n"))
+    }
+
+    /// Built-in tools the skills ask for; None when no skill lists any, so all stay enabled.
+    pub fn builtin_tools(&self) -> Option<BTreeSet<String>> {
+        let lists: Vec<&Vec<String>> = self
+            .skills
+            .iter()
+            .filter_map(|s| s.tools.builtin.as_ref())
+            .collect();
+        if lists.is_empty() {
+            return None;
+        }
+        Some(lists.into_iter().flatten().cloned().collect())
+    }
+
+    /// MCP servers of every skill, each name once.
+    pub fn mcp_servers(&self) -> impl Iterator<Item = &McpServerConfig> {
+        let mut seen = BTreeSet::new();
+        self.skills
+            .iter()
+            .flat_map(|s| &s.tools.mcp)
+            .filter(move |server| seen.insert(server.name.as_str()))
+    }
+
+    /// Whether the skills change the tool set.
+    pub fn has_tools(&self) -> bool {
+        self.builtin_tools().is_some() || self.mcp_servers().next().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::McpTransport;
+
+    const RESEARCH: &str = r#"
+name = "research"
+version = "1.2.0"
+prompt = "Cite your sources."
+
+[tools]
+builtin = ["web_fetcher"]
+
+[[tools.mcp]]
+name = "exa"
+url = "https://mcp.exa.ai/mcp"
+headers = { EXA_API_KEY = "${SKILL_TEST_EXA_KEY}" }
+
+[[examples]]
+user = "Who won in 2018?"
+assistant = "France [1]."
+"#;
+
+    const NOTES: &str = r#"
+name = "notes"
+prompt = "Remember what the user asks you to."
+
+[tools]
+builtin = ["remember", "recall"]
+"#;
+
+    /// **Scenario**: Combined skills append their prompt sections with examples in load order,
+    /// union their built-in tools and keep each MCP server once.
+    #[test]
+    fn skill_set_combines_prompts_and_tools() {
+        let mut set = SkillSet::new();
+        set.push(Skill::from_toml(RESEARCH).unwrap()).unwrap();
+        set.push(Skill::from_toml(NOTES).unwrap()).unwrap();
+        assert_eq!(set.labels(), ["research@1.2.0", "notes"]);
+        assert_eq!(
+            set.prompt().unwrap(),
+            "## Skill: research\n\nCite your sources.\n\nExamples:\n\n\
+             User: Who won in 2018?\nAssistant: France [1].\n\n\
+             ## Skill: notes\n\nRemember what the user asks you to."
+        );
+        let builtin: Vec<String> = set.builtin_tools().unwrap().into_iter().collect();
+        assert_eq!(builtin, ["recall", "remember", "web_fetcher"]);
+        let servers: Vec<&str> = set.mcp_servers().map(|s| s.name.as_str()).collect();
+        assert_eq!(servers, ["exa"]);
+        assert!(SkillSet::new().prompt().is_none());
+        assert!(!SkillSet::new().has_tools());
+    }
+
+    /// **Scenario**: A skill loaded twice, an MCP server redeclared differently, a server with
+    /// neither command nor url and unknown keys are refused.
+    #[test]
+    fn conflicting_or_invalid_skills_are_refused() {
+        let mut set = SkillSet::new();
+        set.push(Skill::from_toml(RESEARCH).unwrap()).unwrap();
+        assert!(matches!(
+            set.push(Skill::from_toml(RESEARCH).unwrap()),
+            Err(SkillError::Duplicate(name)) if name == "research"
+        ));
+        let other_exa = Skill::from_toml(
+            "name = \"search\"\n[[tools.mcp]]\nname = \"exa\"\nurl = \"http://localhost:9000\"",
+        )
+        .unwrap();
+        assert!(matches!(
+            set.push(other_exa),
+            Err(SkillError::McpConflict(name)) if name == "exa"
+        ));
+
+        let no_transport = "name = \"broken\"\n[[tools.mcp]]\nname = \"x\"";
+        assert!(matches!(
+            Skill::from_toml(no_transport),
+            Err(SkillError::Mcp(McpConfigError::Invalid { server, .. })) if server == "x"
+        ));
+        assert!(matches!(
+            Skill::from_toml("name = \"typo\"\npromt = \"hi\""),
+            Err(SkillError::Parse { .. })
+        ));
+    }
+
+    /// **Scenario**: `${VAR}` in a skill's MCP settings is read from the environment when the
+    /// transport is built; an unset variable is reported with its server.
+    #[test]
+    fn mcp_transport_expands_env_vars() {
+        let skill = Skill::from_toml(RESEARCH).unwrap();
+        let exa = &skill.tools.mcp[0];
+        std::env::remove_var("SKILL_TEST_EXA_KEY");
+        assert!(matches!(
+            exa.transport(),
+            Err(McpConfigError::MissingEnv { server, var })
+                if server == "exa" && var == "SKILL_TEST_EXA_KEY"
+        ));
+        std::env::set_var("SKILL_TEST_EXA_KEY", "k-123");
+        assert_eq!(
+            exa.transport().unwrap(),
+            McpTransport::Http {
+                url: "https://mcp.exa.ai/mcp".into(),
+                headers: vec![("EXA_API_KEY".into(), "k-123".into())],
+            }
+        );
+    }
+
+    /// **Scenario**: A manifest file loads; a missing file reports its path.
+    #[test]
+    fn load_reads_manifest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.toml");
+        std::fs::write(&path, NOTES).unwrap();
+        let set = SkillSet::load([&path]).unwrap();
+        assert_eq!(set.skills()[0].name, "notes");
+        let err = SkillSet::load([dir.path().join("missing.toml")]).unwrap_err();
+        assert!(err.to_string().contains("missing.toml"), "{}", err);
+    }
+}
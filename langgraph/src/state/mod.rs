@@ -10,7 +10,8 @@
 //! # Main types
 //!
 //! - [`ReActState`]: Conversation messages plus per-round `tool_calls` and `tool_results`;
-//!   use [`ReActState::last_assistant_reply`] for the final assistant message.
+//!   use [`ReActState::final_answer`] for the run's answer, or
+//!   [`ReActState::last_assistant_reply`] for the last assistant message.
 //! - [`ToolCall`]: A single tool invocation from the LLM; consumed by Act to call
 //!   [`ToolSource::call_tool`](crate::tool_source::ToolSource::call_tool).
 //! - [`ToolResult`]: Result of one tool execution; written by Act, merged in Observe.
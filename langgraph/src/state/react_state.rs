@@ -5,8 +5,10 @@
 //! nodes read and write these fields. ToolCall and ToolResult align with MCP `tools/call`
 //! and result content.
 
+use crate::llm::{LlmResponseMetadata, LlmUsage};
 use crate::message::Message;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// A single tool invocation produced by the LLM (Think node) and consumed by Act.
 ///
@@ -62,13 +64,32 @@ pub struct ReActState {
     /// Number of observe rounds completed; incremented in ObserveNode, used to enforce max turns.
     #[serde(default)]
     pub turn_count: u32,
+    /// Finish reason, model id, and system fingerprint of the latest LLM response (set by
+    /// ThinkNode); None before the first think step or when the client reports none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_metadata: Option<LlmResponseMetadata>,
+    /// Token usage summed over the LLM calls of the current run (added by ThinkNode, reset
+    /// when a run continues a checkpointed thread). Zero when the client reports no usage.
+    #[serde(default)]
+    pub usage: LlmUsage,
+    /// The run's answer: set by ObserveNode when a round ends without tool calls (and by
+    /// ThinkNode on its last step); None while the run is still calling tools or when it ended
+    /// without an answer. Read it with [`final_answer`](Self::final_answer).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_answer: Option<String>,
+    /// Structured hints for streaming UIs (tables, charts, progress) appended by nodes and by
+    /// tools through `ToolCallContext::emit_ui_event`. Never part of the LLM prompt; streamed as
+    /// `StreamEvent::UiEvents` and kept in checkpoints per
+    /// [`UiEventsRetention`](crate::stream::UiEventsRetention) (by default not at all).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ui_events: Vec<Value>,
 }
 
 impl ReActState {
     /// Returns the content of the chronologically last Assistant message, if any.
     ///
     /// Used by callers (e.g. bot, CLI) to get the final reply without scanning `messages`.
-    /// Semantics: last message in `messages` that is `Message::Assistant(content)`; empty
+    /// Semantics: last message in `messages` that is `Message::Assistant { content, .. }`; empty
     /// content (e.g. assistant turn with only tool_calls) returns `Some("")`. Returns
     /// `None` only when there is no Assistant message at all.
     pub fn last_assistant_reply(&self) -> Option<String> {
@@ -76,10 +97,18 @@ impl ReActState {
             .iter()
             .rev()
             .find_map(|m| match m {
-                Message::Assistant(s) => Some(s.clone()),
+                Message::Assistant { content, .. } => Some(content.clone()),
                 _ => None,
             })
     }
+
+    /// The run's final answer, if it ended with one; see the `final_answer` field.
+    ///
+    /// Unlike [`last_assistant_reply`](Self::last_assistant_reply) this is never the empty
+    /// content of a tool-calling turn nor a reply from an earlier run on the thread.
+    pub fn final_answer(&self) -> Option<&str> {
+        self.final_answer.as_deref()
+    }
 }
 
 // ReActState, ToolCall, ToolResult: fields are standard types (String, Vec<Message>, Option<String>, etc.),
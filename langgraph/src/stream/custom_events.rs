@@ -0,0 +1,339 @@
+//! Typed custom stream events: a registry of event types and their JSON Schemas.
+//!
+//! `StreamEvent::Custom` carries free-form JSON, so a consumer cannot tell a progress update
+//! from a search hit without guessing at fields. Tools and nodes declare the custom events they
+//! emit in a [`CustomEventRegistry`] (a type name and a JSON Schema of its data) and emit them
+//! with `emit_event` / `emit_typed` on [`ToolStreamWriter`](super::ToolStreamWriter) or
+//! [`StreamWriter`](super::StreamWriter). The payload is wrapped in an envelope
+//! `{"type": "<event type>", "data": <payload>}` and checked against the schema before it is
+//! sent. Consumers match on the envelope's type with [`CustomEvent::decode`] or
+//! `StreamEvent::decode_custom`.
+//!
+//! ```
+//! use langgraph::stream::{CustomEvent, CustomEventRegistry};
+//! use serde::{Deserialize, Serialize};
+//! use serde_json::json;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Progress {
+//!     percent: u8,
+//! }
+//!
+//! impl CustomEvent for Progress {
+//!     const EVENT_TYPE: &'static str = "progress";
+//!
+//!     fn schema() -> serde_json::Value {
+//!         json!({
+//!             "type": "object",
+//!             "properties": {"percent": {"type": "integer", "maximum": 100}},
+//!             "required": ["percent"]
+//!         })
+//!     }
+//! }
+//!
+//! let registry = CustomEventRegistry::new().with_typed::<Progress>().unwrap();
+//! let event = registry.envelope("progress", json!({"percent": 50})).unwrap();
+//! assert_eq!(event, json!({"type": "progress", "data": {"percent": 50}}));
+//! assert_eq!(Progress::decode(&event).unwrap(), Some(Progress { percent: 50 }));
+//! assert!(registry.envelope("progress", json!({"percent": 150})).is_err());
+//! ```
+//!
+//! **Interaction**: Set on a graph with `StateGraph::with_custom_events` (or
+//! `ReactRunner::with_custom_events`); runs put it in `RunContext::custom_events`, from where
+//! `RunContext::stream_writer` and `ActNode`'s tool writers pick it up. `emit_custom` keeps
+//! sending free-form JSON as before.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use jsonschema::Validator;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Envelope field holding the event type.
+pub const CUSTOM_EVENT_TYPE: &str = "type";
+/// Envelope field holding the event's data.
+pub const CUSTOM_EVENT_DATA: &str = "data";
+
+/// Why a custom event could not be declared, emitted or decoded.
+#[derive(Debug, thiserror::Error)]
+pub enum CustomEventError {
+    /// A registry is set and does not declare the type.
+    #[error("custom event type `{0}` is not registered")]
+    UnknownType(String),
+    /// The declared schema is not a valid JSON Schema.
+    #[error("schema of custom event `{event_type}` is invalid: {message}")]
+    InvalidSchema { event_type: String, message: String },
+    /// The data does not match the type's schema.
+    #[error("custom event `{event_type}` does not match its schema: {message}")]
+    Invalid { event_type: String, message: String },
+    /// The event could not be converted to or from JSON.
+    #[error("custom event `{event_type}`: {message}")]
+    Serialization { event_type: String, message: String },
+}
+
+/// A custom event type with a fixed name and schema, for emitting and decoding it as a Rust
+/// type; see the [module docs](self).
+pub trait CustomEvent: Serialize + DeserializeOwned {
+    /// The envelope's `type`.
+    const EVENT_TYPE: &'static str;
+
+    /// JSON Schema of the event's data.
+    fn schema() -> Value;
+
+    /// The event in `value` when it is an envelope of this type; None for other types and
+    /// free-form payloads.
+    fn decode(value: &Value) -> Result<Option<Self>, CustomEventError> {
+        if event_type(value) != Some(Self::EVENT_TYPE) {
+            return Ok(None);
+        }
+        let data = value.get(CUSTOM_EVENT_DATA).cloned().unwrap_or(Value::Null);
+        serde_json::from_value(data)
+            .map(Some)
+            .map_err(|e| CustomEventError::Serialization {
+                event_type: Self::EVENT_TYPE.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+/// The `type` of a custom event envelope; None for free-form payloads.
+pub fn event_type(value: &Value) -> Option<&str> {
+    value
+        .as_object()
+        .filter(|o| o.contains_key(CUSTOM_EVENT_DATA))?
+        .get(CUSTOM_EVENT_TYPE)?
+        .as_str()
+}
+
+/// Custom event types and their schemas; clones share the compiled schemas.
+#[derive(Clone, Default)]
+pub struct CustomEventRegistry {
+    events: BTreeMap<String, (Value, Arc<Validator>)>,
+}
+
+impl CustomEventRegistry {
+    /// A registry declaring no types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares `event_type` with the JSON Schema of its data, replacing an earlier
+    /// declaration.
+    pub fn with_event(
+        mut self,
+        event_type: impl Into<String>,
+        schema: Value,
+    ) -> Result<Self, CustomEventError> {
+        let event_type = event_type.into();
+        let validator =
+            jsonschema::validator_for(&schema).map_err(|e| CustomEventError::InvalidSchema {
+                event_type: event_type.clone(),
+                message: e.to_string(),
+            })?;
+        self.events
+            .insert(event_type, (schema, Arc::new(validator)));
+        Ok(self)
+    }
+
+    /// Declares the type and schema of `T`.
+    pub fn with_typed<T: CustomEvent>(self) -> Result<Self, CustomEventError> {
+        self.with_event(T::EVENT_TYPE, T::schema())
+    }
+
+    /// Whether `event_type` is declared.
+    pub fn contains(&self, event_type: &str) -> bool {
+        self.events.contains_key(event_type)
+    }
+
+    /// The declared schema of `event_type`.
+    pub fn schema(&self, event_type: &str) -> Option<&Value> {
+        self.events.get(event_type).map(|(schema, _)| schema)
+    }
+
+    /// Declared types, sorted.
+    pub fn event_types(&self) -> Vec<&str> {
+        self.events.keys().map(String::as_str).collect()
+    }
+
+    /// Checks `data` against the schema of `event_type`.
+    pub fn validate(&self, event_type: &str, data: &Value) -> Result<(), CustomEventError> {
+        let (_, validator) = self
+            .events
+            .get(event_type)
+            .ok_or_else(|| CustomEventError::UnknownType(event_type.to_string()))?;
+        let errors: Vec<String> = validator.iter_errors(data).map(|e| e.to_string()).collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(CustomEventError::Invalid {
+                event_type: event_type.to_string(),
+                message: errors.join("; "),
+            })
+        }
+    }
+
+    /// The envelope of `data` as `event_type`, after checking it against the type's schema.
+    pub fn envelope(&self, event_type: &str, data: Value) -> Result<Value, CustomEventError> {
+        self.validate(event_type, &data)?;
+        Ok(envelope(event_type, data))
+    }
+
+    /// `{"<event type>": <schema>, ...}`, e.g. for clients generating their decoders.
+    pub fn to_json(&self) -> Value {
+        let map = self
+            .events
+            .iter()
+            .map(|(event_type, (schema, _))| (event_type.clone(), schema.clone()))
+            .collect();
+        Value::Object(map)
+    }
+}
+
+impl Debug for CustomEventRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomEventRegistry")
+            .field("event_types", &self.event_types())
+            .finish()
+    }
+}
+
+/// `{"type": event_type, "data": data}`, unchecked.
+pub(crate) fn envelope(event_type: &str, data: Value) -> Value {
+    json!({ CUSTOM_EVENT_TYPE: event_type, CUSTOM_EVENT_DATA: data })
+}
+
+/// The envelope `event_type` / `data` is sent as: checked against `registry` when there is one.
+pub(crate) fn checked_envelope(
+    registry: Option<&CustomEventRegistry>,
+    event_type: &str,
+    data: Value,
+) -> Result<Value, CustomEventError> {
+    match registry {
+        Some(registry) => registry.envelope(event_type, data),
+        None => Ok(envelope(event_type, data)),
+    }
+}
+
+/// The data of `event` as JSON, for sending it as `T::EVENT_TYPE`.
+pub(crate) fn typed_data<T: CustomEvent>(event: &T) -> Result<Value, CustomEventError> {
+    serde_json::to_value(event).map_err(|e| CustomEventError::Serialization {
+        event_type: T::EVENT_TYPE.to_string(),
+        message: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde::Deserialize;
+    use tokio::sync::mpsc;
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::graph::{Next, Node, RunContext, StateGraph, END, START};
+    use crate::stream::{StreamEvent, StreamMode, StreamWriter, ToolStreamWriter};
+    use crate::AgentError;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Progress {
+        percent: u8,
+    }
+
+    impl CustomEvent for Progress {
+        const EVENT_TYPE: &'static str = "progress";
+
+        fn schema() -> Value {
+            json!({
+                "type": "object",
+                "properties": {"percent": {"type": "integer", "maximum": 100}},
+                "required": ["percent"]
+            })
+        }
+    }
+
+    fn registry() -> Arc<CustomEventRegistry> {
+        Arc::new(CustomEventRegistry::new().with_typed::<Progress>().unwrap())
+    }
+
+    /// **Scenario**: A tool writer with a registry wraps events in a typed envelope and refuses
+    /// undeclared types and data off-schema; without one it only wraps them.
+    #[test]
+    fn tool_writer_sends_checked_envelopes() {
+        let (tx, mut rx) = mpsc::channel::<Value>(8);
+        let writer =
+            ToolStreamWriter::new(move |v| tx.try_send(v).is_ok()).with_custom_events(registry());
+
+        assert!(writer.emit_typed(&Progress { percent: 40 }).unwrap());
+        let sent = rx.try_recv().unwrap();
+        assert_eq!(sent, json!({"type": "progress", "data": {"percent": 40}}));
+        assert_eq!(
+            Progress::decode(&sent).unwrap(),
+            Some(Progress { percent: 40 })
+        );
+
+        let off_schema = writer.emit_event("progress", json!({"percent": "half"}));
+        assert!(matches!(off_schema, Err(CustomEventError::Invalid { .. })));
+        let unknown = writer.emit_event("search_hit", json!({}));
+        assert!(matches!(unknown, Err(CustomEventError::UnknownType(t)) if t == "search_hit"));
+        assert!(rx.try_recv().is_err(), "nothing sent for refused events");
+
+        let (tx, mut rx) = mpsc::channel::<Value>(8);
+        let unchecked = ToolStreamWriter::new(move |v| tx.try_send(v).is_ok());
+        assert!(unchecked.emit_event("search_hit", json!({"n": 1})).unwrap());
+        let sent = rx.try_recv().unwrap();
+        assert_eq!(event_type(&sent), Some("search_hit"));
+        assert_eq!(Progress::decode(&sent).unwrap(), None);
+        assert_eq!(event_type(&json!({"type": "free-form"})), None);
+        assert_eq!(registry().to_json()["progress"], Progress::schema());
+    }
+
+    #[derive(Clone)]
+    struct ProgressNode;
+
+    #[async_trait::async_trait]
+    impl Node<i32> for ProgressNode {
+        fn id(&self) -> &str {
+            "work"
+        }
+
+        async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+            Ok((state, Next::Continue))
+        }
+
+        async fn run_with_context(
+            &self,
+            state: i32,
+            ctx: &RunContext<i32>,
+        ) -> Result<(i32, Next), AgentError> {
+            let writer: StreamWriter<i32> = ctx.stream_writer();
+            let sent = writer.emit_typed(&Progress { percent: 100 }).await;
+            assert!(sent.expect("declared type"));
+            assert!(writer.emit_event("other", json!(1)).await.is_err());
+            Ok((state + 1, Next::Continue))
+        }
+    }
+
+    /// **Scenario**: Nodes of a graph with a registry emit typed events through their context's
+    /// writer; the consumer decodes them from `StreamEvent::Custom`.
+    #[tokio::test]
+    async fn graph_nodes_emit_typed_events() {
+        let mut graph = StateGraph::<i32>::new();
+        graph.add_node("work", Arc::new(ProgressNode));
+        graph.add_edge(START, "work");
+        graph.add_edge("work", END);
+        let compiled = graph.with_custom_events(registry()).compile().unwrap();
+
+        let modes = HashSet::from_iter([StreamMode::Custom]);
+        let events: Vec<StreamEvent<i32>> = compiled.stream(0, None, modes).collect().await;
+        let decoded: Vec<Progress> = events
+            .iter()
+            .filter_map(|e| e.decode_custom::<Progress>().unwrap())
+            .collect();
+        assert_eq!(decoded, [Progress { percent: 100 }]);
+        assert_eq!(events[0].custom_event_type(), Some("progress"));
+    }
+}
@@ -4,6 +4,28 @@
 //! streaming. Used by `CompiledStateGraph::stream` and nodes that emit
 //! incremental results.
 //!
+//! # Messages delta
+//!
+//! With `StreamMode::MessagesDelta`, the run emits `StreamEvent::MessagesDelta` after each node
+//! with only the messages that node appended, so chat UIs can append instead of diffing full
+//! `Values` snapshots. The graph finds the message list through a [`MessagesAccessor`] set with
+//! `StateGraph::with_messages_accessor`; `ReactRunner` sets one for `ReActState`.
+//!
+//! # UI events
+//!
+//! States can keep structured hints for streaming UIs (tables, charts, progress) apart from
+//! the conversation, e.g. `ReActState::ui_events`. With a [`UiEventsAccessor`] set through
+//! `StateGraph::with_ui_events_accessor`, `StreamMode::UiEvents` emits `StreamEvent::UiEvents`
+//! after each node with the events it appended, and checkpoints keep them according to
+//! [`UiEventsRetention`].
+//!
+//! # Typed custom events
+//!
+//! Custom payloads can be typed: a [`CustomEventRegistry`] declares event types with JSON
+//! Schemas, `emit_event` / `emit_typed` send `{"type", "data"}` envelopes checked against them,
+//! and consumers decode with [`CustomEvent::decode`] or [`StreamEvent::decode_custom`]. See
+//! [`custom_events`].
+//!
 //! # StreamWriter
 //!
 //! The `StreamWriter` struct provides a convenient API for nodes and tools to emit
@@ -32,6 +54,17 @@ use std::fmt::Debug;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+use crate::llm::LlmResponseMetadata;
+use crate::message::Message;
+
+pub mod custom_events;
+
+use custom_events::{checked_envelope, typed_data};
+pub use custom_events::{
+    event_type, CustomEvent, CustomEventError, CustomEventRegistry, CUSTOM_EVENT_DATA,
+    CUSTOM_EVENT_TYPE,
+};
+
 // ============================================================================
 // ToolStreamWriter - Type-erased writer for tools
 // ============================================================================
@@ -68,6 +101,8 @@ use tokio::sync::mpsc;
 pub struct ToolStreamWriter {
     /// Function that emits a custom event. Returns true if sent successfully.
     emit_fn: Arc<dyn Fn(Value) -> bool + Send + Sync>,
+    /// Declared event types that `emit_event` checks against.
+    custom_events: Option<Arc<CustomEventRegistry>>,
 }
 
 impl ToolStreamWriter {
@@ -82,6 +117,7 @@ impl ToolStreamWriter {
     pub fn new(emit_fn: impl Fn(Value) -> bool + Send + Sync + 'static) -> Self {
         Self {
             emit_fn: Arc::new(emit_fn),
+            custom_events: None,
         }
     }
 
@@ -91,9 +127,16 @@ impl ToolStreamWriter {
     pub fn noop() -> Self {
         Self {
             emit_fn: Arc::new(|_| false),
+            custom_events: None,
         }
     }
 
+    /// Checks events sent with [`emit_event`](Self::emit_event) against `registry`.
+    pub fn with_custom_events(mut self, registry: Arc<CustomEventRegistry>) -> Self {
+        self.custom_events = Some(registry);
+        self
+    }
+
     /// Emits a custom JSON payload.
     ///
     /// Returns `true` if the event was sent successfully, `false` otherwise.
@@ -117,6 +160,21 @@ impl ToolStreamWriter {
         (self.emit_fn)(value)
     }
 
+    /// Emits `data` as a custom event of type `event_type`, wrapped in a `{"type", "data"}`
+    /// envelope (see [`custom_events`]).
+    ///
+    /// With a registry the type must be declared and `data` match its schema; otherwise
+    /// nothing is sent and the error says why. Returns whether the event was sent.
+    pub fn emit_event(&self, event_type: &str, data: Value) -> Result<bool, CustomEventError> {
+        let event = checked_envelope(self.custom_events.as_deref(), event_type, data)?;
+        Ok(self.emit_custom(event))
+    }
+
+    /// Emits `event` as its [`CustomEvent::EVENT_TYPE`], like [`emit_event`](Self::emit_event).
+    pub fn emit_typed<T: CustomEvent>(&self, event: &T) -> Result<bool, CustomEventError> {
+        self.emit_event(T::EVENT_TYPE, typed_data(event)?)
+    }
+
     /// Checks if this writer is a no-op (always returns false).
     ///
     /// This can be used to skip expensive computations when streaming
@@ -134,6 +192,7 @@ impl Debug for ToolStreamWriter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ToolStreamWriter")
             .field("emit_fn", &"<fn>")
+            .field("custom_events", &self.custom_events)
             .finish()
     }
 }
@@ -161,6 +220,84 @@ pub enum StreamMode {
     Tasks,
     /// Emit both checkpoints and tasks events (debug mode).
     Debug,
+    /// Emit the messages each node appended (requires a [`MessagesAccessor`] on the graph).
+    MessagesDelta,
+    /// Emit the UI events each node appended (requires a [`UiEventsAccessor`] on the graph).
+    UiEvents,
+}
+
+/// Reads the conversation messages out of a graph state, for `StreamMode::MessagesDelta`.
+///
+/// Implemented for `Fn(&S) -> &[Message]`, so a plain function works:
+///
+/// ```rust,ignore
+/// fn messages(state: &ReActState) -> &[Message] {
+///     &state.messages
+/// }
+/// let graph = StateGraph::<ReActState>::new().with_messages_accessor(Arc::new(messages));
+/// ```
+pub trait MessagesAccessor<S>: Send + Sync {
+    /// Returns the state's messages, oldest first.
+    fn messages<'a>(&self, state: &'a S) -> &'a [Message];
+}
+
+impl<S, F> MessagesAccessor<S> for F
+where
+    F: Fn(&S) -> &[Message] + Send + Sync,
+{
+    fn messages<'a>(&self, state: &'a S) -> &'a [Message] {
+        self(state)
+    }
+}
+
+/// Reads and edits the UI events of a graph state, for `StreamMode::UiEvents` and
+/// [`UiEventsRetention`].
+///
+/// ```rust,ignore
+/// struct ChatUiEvents;
+///
+/// impl UiEventsAccessor<Chat> for ChatUiEvents {
+///     fn ui_events<'a>(&self, state: &'a Chat) -> &'a [Value] {
+///         &state.ui_events
+///     }
+///     fn ui_events_mut<'a>(&self, state: &'a mut Chat) -> &'a mut Vec<Value> {
+///         &mut state.ui_events
+///     }
+/// }
+/// let graph = StateGraph::<Chat>::new().with_ui_events_accessor(Arc::new(ChatUiEvents));
+/// ```
+pub trait UiEventsAccessor<S>: Send + Sync {
+    /// Returns the state's UI events, oldest first.
+    fn ui_events<'a>(&self, state: &'a S) -> &'a [Value];
+
+    /// Returns the state's UI events for pruning before a checkpoint is saved.
+    fn ui_events_mut<'a>(&self, state: &'a mut S) -> &'a mut Vec<Value>;
+}
+
+/// Which UI events checkpoints keep; the running state always keeps them all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UiEventsRetention {
+    /// Checkpoints hold no UI events: they are for live streams only.
+    #[default]
+    Drop,
+    /// Checkpoints hold the last `n` UI events, e.g. for UIs that redraw a thread.
+    Last(usize),
+    /// Checkpoints hold every UI event.
+    Keep,
+}
+
+impl UiEventsRetention {
+    /// Removes the events a checkpoint should not hold.
+    pub fn prune(&self, events: &mut Vec<Value>) {
+        match *self {
+            UiEventsRetention::Drop => events.clear(),
+            UiEventsRetention::Last(n) => {
+                let excess = events.len().saturating_sub(n);
+                events.drain(..excess);
+            }
+            UiEventsRetention::Keep => {}
+        }
+    }
 }
 
 /// Metadata attached to streamed messages.
@@ -194,9 +331,41 @@ where
 }
 
 /// One chunk of streamed message content.
-#[derive(Clone, Debug)]
+///
+/// Carries a text delta (`content`, may be empty) and any tool-call deltas that
+/// arrived in the same provider chunk. Consumers that only display text can ignore
+/// `tool_call_chunks`; `StreamToSse` forwards them as `delta.tool_calls`.
+#[derive(Clone, Debug, Default)]
 pub struct MessageChunk {
     pub content: String,
+    /// Partial tool calls streamed by the model; concatenate by `index` to rebuild each call.
+    pub tool_call_chunks: Vec<ToolCallChunk>,
+}
+
+impl MessageChunk {
+    /// Creates a text-only chunk.
+    pub fn text(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            tool_call_chunks: Vec::new(),
+        }
+    }
+}
+
+/// Partial tool call streamed by the model (aligns with OpenAI `delta.tool_calls[]`).
+///
+/// `id` and `name` usually arrive only in the first delta for an `index`;
+/// `arguments` arrives as JSON fragments to be concatenated in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ToolCallChunk {
+    /// Position of the tool call within the assistant message.
+    pub index: u32,
+    /// Tool call id, when present in this delta.
+    pub id: Option<String>,
+    /// Function name fragment, when present in this delta.
+    pub name: Option<String>,
+    /// Arguments JSON fragment, when present in this delta.
+    pub arguments: Option<String>,
 }
 
 /// Streamed event emitted while running a graph.
@@ -240,6 +409,52 @@ where
         /// Total tokens (prompt + completion).
         total_tokens: u32,
     },
+    /// Finish reason, model id, and system fingerprint of the last completion (after think node;
+    /// emitted when Updates mode is enabled).
+    ResponseMetadata(LlmResponseMetadata),
+    /// Messages appended by a node, emitted after its update is applied (MessagesDelta mode).
+    ///
+    /// Keep the first `base` messages already shown and append `messages`. `base` is the
+    /// message count before the node ran, or the new count when the node removed messages;
+    /// nodes that leave the list unchanged emit nothing. Changes are detected by length, so a
+    /// node that rewrites messages in place is not reported.
+    MessagesDelta {
+        /// Node that appended the messages.
+        node_id: String,
+        /// Number of earlier messages the delta follows.
+        base: usize,
+        /// The appended messages, oldest first.
+        messages: Vec<Message>,
+    },
+    /// UI events appended by a node, emitted after its update is applied (UiEvents mode).
+    UiEvents {
+        /// Node that appended the events.
+        node_id: String,
+        /// The appended events, oldest first.
+        events: Vec<Value>,
+    },
+}
+
+impl<S> StreamEvent<S>
+where
+    S: Clone + Send + Sync + Debug + 'static,
+{
+    /// The envelope type of a typed `Custom` event (see [`custom_events`]); None for other
+    /// events and free-form payloads.
+    pub fn custom_event_type(&self) -> Option<&str> {
+        match self {
+            Self::Custom(value) => event_type(value),
+            _ => None,
+        }
+    }
+
+    /// The `T` in a `Custom` event of type `T::EVENT_TYPE`; None for any other event.
+    pub fn decode_custom<T: CustomEvent>(&self) -> Result<Option<T>, CustomEventError> {
+        match self {
+            Self::Custom(value) => T::decode(value),
+            _ => Ok(None),
+        }
+    }
 }
 
 /// A writer for emitting streaming events from nodes and tools.
@@ -282,6 +497,8 @@ where
     tx: Option<mpsc::Sender<StreamEvent<S>>>,
     /// The enabled stream modes.
     modes: Arc<HashSet<StreamMode>>,
+    /// Declared event types that `emit_event` checks against.
+    custom_events: Option<Arc<CustomEventRegistry>>,
 }
 
 impl<S> StreamWriter<S>
@@ -298,6 +515,7 @@ where
         Self {
             tx,
             modes: Arc::new(modes),
+            custom_events: None,
         }
     }
 
@@ -308,9 +526,16 @@ where
         Self {
             tx: None,
             modes: Arc::new(HashSet::new()),
+            custom_events: None,
         }
     }
 
+    /// Checks events sent with [`emit_event`](Self::emit_event) against `registry`.
+    pub fn with_custom_events(mut self, registry: Arc<CustomEventRegistry>) -> Self {
+        self.custom_events = Some(registry);
+        self
+    }
+
     /// Checks if a specific stream mode is enabled.
     pub fn is_mode_enabled(&self, mode: StreamMode) -> bool {
         self.modes.contains(&mode)
@@ -352,6 +577,25 @@ where
         }
     }
 
+    /// Emits `data` as a custom event of type `event_type`, wrapped in a `{"type", "data"}`
+    /// envelope (see [`custom_events`]).
+    ///
+    /// With a registry the type must be declared and `data` match its schema, also when
+    /// `StreamMode::Custom` is off. Returns whether the event was sent.
+    pub async fn emit_event(
+        &self,
+        event_type: &str,
+        data: Value,
+    ) -> Result<bool, CustomEventError> {
+        let event = checked_envelope(self.custom_events.as_deref(), event_type, data)?;
+        Ok(self.emit_custom(event).await)
+    }
+
+    /// Emits `event` as its [`CustomEvent::EVENT_TYPE`], like [`emit_event`](Self::emit_event).
+    pub async fn emit_typed<T: CustomEvent>(&self, event: &T) -> Result<bool, CustomEventError> {
+        self.emit_event(T::EVENT_TYPE, typed_data(event)?).await
+    }
+
     /// Emits a message chunk (LLM token).
     ///
     /// Only sends if `StreamMode::Messages` is enabled and a sender is available.
@@ -371,9 +615,7 @@ where
         }
         if let Some(tx) = &self.tx {
             let event = StreamEvent::Messages {
-                chunk: MessageChunk {
-                    content: content.into(),
-                },
+                chunk: MessageChunk::text(content.into()),
                 metadata: StreamMetadata {
                     langgraph_node: node_id.into(),
                 },
@@ -393,9 +635,7 @@ where
         }
         if let Some(tx) = &self.tx {
             let event = StreamEvent::Messages {
-                chunk: MessageChunk {
-                    content: content.into(),
-                },
+                chunk: MessageChunk::text(content.into()),
                 metadata: StreamMetadata {
                     langgraph_node: node_id.into(),
                 },
@@ -563,6 +803,7 @@ where
         f.debug_struct("StreamWriter")
             .field("has_sender", &self.tx.is_some())
             .field("modes", &self.modes)
+            .field("custom_events", &self.custom_events)
             .finish()
     }
 }
@@ -629,9 +870,7 @@ mod tests {
         }
 
         let messages: StreamEvent<DummyState> = StreamEvent::Messages {
-            chunk: MessageChunk {
-                content: "chunk".into(),
-            },
+            chunk: MessageChunk::text("chunk".into()),
             metadata: StreamMetadata {
                 langgraph_node: "think".into(),
             },
@@ -1099,4 +1338,25 @@ mod tests {
         let sent = writer.emit_custom(serde_json::json!({}));
         assert!(!sent, "default writer should be noop");
     }
+
+    /// **Scenario**: UiEventsRetention drops all events by default, keeps the newest with
+    /// `Last` and leaves them alone with `Keep`.
+    #[test]
+    fn ui_events_retention_prunes_oldest() {
+        let events: Vec<Value> = (0..4).map(|i| serde_json::json!({"step": i})).collect();
+
+        let mut dropped = events.clone();
+        UiEventsRetention::default().prune(&mut dropped);
+        assert!(dropped.is_empty());
+
+        let mut last = events.clone();
+        UiEventsRetention::Last(2).prune(&mut last);
+        assert_eq!(last, events[2..]);
+        UiEventsRetention::Last(10).prune(&mut last);
+        assert_eq!(last.len(), 2);
+
+        let mut kept = events.clone();
+        UiEventsRetention::Keep.prune(&mut kept);
+        assert_eq!(kept, events);
+    }
 }
@@ -0,0 +1,308 @@
+//! Structured output: parse the model's reply into a typed value, re-prompting on parse failure.
+//!
+//! [`StructuredOutputNode<T>`] calls an [`LlmClient`] with the state's messages and parses the
+//! reply as JSON into `T` (a Markdown code fence around the JSON is accepted). When parsing
+//! fails, the reply and a correction request ([`REPROMPT_TEMPLATE`] with the parse error) are
+//! added to the conversation and the model is asked again, up to `max_retries` times.
+//!
+//! Pair it with [`ChatOpenAI::with_response_format`](crate::llm::ChatOpenAI::with_response_format)
+//! so the provider already constrains the reply to the schema; the re-prompt covers providers
+//! and models without structured outputs.
+//!
+//! **Interaction**: Implements `Node<StructuredOutputState<T>>` for extraction graphs; nodes
+//! with other state types call [`StructuredOutputNode::extract`].
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+
+use crate::error::AgentError;
+use crate::graph::{Next, Node};
+use crate::llm::LlmClient;
+use crate::message::Message;
+
+/// Correction request sent after a reply that did not parse; `{error}` is the parse error.
+pub const REPROMPT_TEMPLATE: &str = "Your reply could not be parsed: {error}. Reply again with only the JSON value, without any other text.";
+
+/// Re-prompts after a failed parse when `with_max_retries` is not called.
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// State of an extraction graph: the conversation and the typed result.
+#[derive(Debug, Clone)]
+pub struct StructuredOutputState<T> {
+    /// Conversation sent to the model; the accepted reply is appended as an assistant message.
+    pub messages: Vec<Message>,
+    /// The parsed reply, once [`StructuredOutputNode`] has run.
+    pub output: Option<T>,
+}
+
+impl<T> StructuredOutputState<T> {
+    /// State asking the model with `messages`.
+    pub fn new(messages: Vec<Message>) -> Self {
+        Self {
+            messages,
+            output: None,
+        }
+    }
+}
+
+impl<T> Default for StructuredOutputState<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+/// Node that asks the model for a reply and parses it into `T`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::graph::{StateGraph, END, START};
+/// use langgraph::llm::{ChatOpenAI, JsonSchema};
+/// use langgraph::structured_output::{StructuredOutputNode, StructuredOutputState};
+/// use langgraph::Message;
+/// use serde::Deserialize;
+/// use serde_json::json;
+/// use std::sync::Arc;
+///
+/// #[derive(Debug, Clone, Deserialize)]
+/// struct Contact { name: String, email: String }
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let schema = json!({
+///     "type": "object",
+///     "properties": { "name": { "type": "string" }, "email": { "type": "string" } },
+///     "required": ["name", "email"],
+///     "additionalProperties": false
+/// });
+/// let llm = ChatOpenAI::new("gpt-4o-mini").with_response_format(JsonSchema::new("contact", schema));
+///
+/// let mut graph = StateGraph::<StructuredOutputState<Contact>>::new();
+/// graph
+///     .add_node("extract", Arc::new(StructuredOutputNode::<Contact>::new(Box::new(llm))))
+///     .add_edge(START, "extract")
+///     .add_edge("extract", END);
+/// let state = graph
+///     .compile()?
+///     .invoke(
+///         StructuredOutputState::new(vec![Message::user("Reach Ada at ada@example.com")]),
+///         None,
+///     )
+///     .await?;
+/// println!("{:?}", state.output);
+/// # Ok(())
+/// # }
+/// ```
+pub struct StructuredOutputNode<T> {
+    id: String,
+    llm: Box<dyn LlmClient>,
+    max_retries: u32,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> StructuredOutputNode<T>
+where
+    T: DeserializeOwned,
+{
+    /// Node with id `"structured_output"` asking `llm`, re-prompting up to
+    /// [`DEFAULT_MAX_RETRIES`] times.
+    pub fn new(llm: Box<dyn LlmClient>) -> Self {
+        Self {
+            id: "structured_output".to_string(),
+            llm,
+            max_retries: DEFAULT_MAX_RETRIES,
+            _output: PhantomData,
+        }
+    }
+
+    /// Sets the node id (default `"structured_output"`).
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Sets how often the model is asked again after a reply that does not parse; 0 fails on
+    /// the first bad reply.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Asks the model with `messages` and returns the parsed value with the reply text it came
+    /// from. Fails with `AgentError::ExecutionFailed` when no reply parses.
+    pub async fn extract(&self, messages: &[Message]) -> Result<(T, String), AgentError> {
+        let mut conversation = messages.to_vec();
+        let mut retries = 0;
+        loop {
+            let reply = self.llm.invoke(&conversation).await?.content;
+            let error = match serde_json::from_str::<T>(strip_code_fence(&reply)) {
+                Ok(value) => return Ok((value, reply)),
+                Err(e) => e,
+            };
+            if retries == self.max_retries {
+                return Err(AgentError::ExecutionFailed(format!(
+                    "structured output: no valid reply after {} attempts: {}",
+                    retries + 1,
+                    error
+                )));
+            }
+            retries += 1;
+            tracing::debug!(node = %self.id, retry = retries, "reply did not parse: {}", error);
+            conversation.push(Message::assistant(reply));
+            conversation.push(Message::user(
+                REPROMPT_TEMPLATE.replace("{error}", &error.to_string()),
+            ));
+        }
+    }
+}
+
+/// The JSON inside a reply, without a surrounding Markdown code fence (```` ```json ... ``` ````).
+fn strip_code_fence(reply: &str) -> &str {
+    let trimmed = reply.trim();
+    let Some(fenced) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let fenced = fenced.strip_suffix("```").unwrap_or(fenced);
+    match fenced.split_once('\n') {
+        // The first line holds only the language tag, e.g. `json`
+        Some((tag, body)) if !tag.trim().starts_with(['{', '[', '"']) => body.trim(),
+        _ => fenced.trim(),
+    }
+}
+
+#[async_trait]
+impl<T> Node<StructuredOutputState<T>> for StructuredOutputNode<T>
+where
+    T: DeserializeOwned + Clone + Send + Sync + Debug + 'static,
+{
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn run(
+        &self,
+        state: StructuredOutputState<T>,
+    ) -> Result<(StructuredOutputState<T>, Next), AgentError> {
+        let (output, reply) = self.extract(&state.messages).await?;
+        let mut messages = state.messages;
+        messages.push(Message::assistant(reply));
+        Ok((
+            StructuredOutputState {
+                messages,
+                output: Some(output),
+            },
+            Next::Continue,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use serde::Deserialize;
+
+    use crate::llm::{LlmResponse, LlmResponseMetadata};
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct Contact {
+        name: String,
+        email: String,
+    }
+
+    /// Returns the scripted replies in order and records the conversation of every call.
+    struct ScriptedLlm {
+        replies: Mutex<Vec<&'static str>>,
+        calls: Arc<Mutex<Vec<Vec<Message>>>>,
+    }
+
+    impl ScriptedLlm {
+        fn new(replies: &[&'static str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                calls: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LlmClient for ScriptedLlm {
+        async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+            self.calls.lock().unwrap().push(messages.to_vec());
+            let content = self.replies.lock().unwrap().pop().unwrap_or("").to_string();
+            Ok(LlmResponse {
+                content,
+                tool_calls: vec![],
+                usage: None,
+                truncated: false,
+                continuations: 0,
+                metadata: LlmResponseMetadata::default(),
+            })
+        }
+    }
+
+    /// **Scenario**: A reply that does not parse is sent back with the parse error; the next
+    /// reply (inside a ```json fence) becomes the typed output and the last assistant message.
+    #[tokio::test]
+    async fn node_reprompts_until_reply_parses() {
+        let llm = Box::new(ScriptedLlm::new(&[
+            "Sure! The contact is Ada.",
+            "```json\n{\"name\": \"Ada\", \"email\": \"ada@example.com\"}\n```",
+        ]));
+        let node = StructuredOutputNode::<Contact>::new(llm);
+        let state = StructuredOutputState::new(vec![Message::user("Ada, ada@example.com")]);
+
+        let (state, _) = node.run(state).await.unwrap();
+        assert_eq!(
+            state.output,
+            Some(Contact {
+                name: "Ada".into(),
+                email: "ada@example.com".into()
+            })
+        );
+        assert_eq!(state.messages.len(), 2);
+        assert!(
+            matches!(&state.messages[1], Message::Assistant { content, .. } if content.contains("\"Ada\"")),
+            "{:?}",
+            state.messages
+        );
+    }
+
+    /// **Scenario**: The re-prompt carries the bad reply and the parse error; after max_retries
+    /// bad replies the node fails.
+    #[tokio::test]
+    async fn extract_fails_after_max_retries() {
+        let llm = ScriptedLlm::new(&["nope", "still nope"]);
+        let calls = llm.calls.clone();
+        let node = StructuredOutputNode::<Contact>::new(Box::new(llm)).with_max_retries(1);
+        let err = node.extract(&[Message::user("hi")]).await.unwrap_err();
+        assert!(
+            err.to_string().contains("no valid reply after 2 attempts"),
+            "{}",
+            err
+        );
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        let retry = &calls[1];
+        assert_eq!(retry.len(), 3);
+        assert!(matches!(&retry[1], Message::Assistant { content, .. } if content == "nope"));
+        assert!(
+            matches!(&retry[2], Message::User(text) if text.starts_with("Your reply could not be parsed: ")),
+            "{:?}",
+            retry[2]
+        );
+    }
+
+    /// **Scenario**: Code fences with or without a language tag are removed; bare JSON is kept.
+    #[test]
+    fn strip_code_fence_variants() {
+        assert_eq!(strip_code_fence(" {\"a\":1} "), "{\"a\":1}");
+        assert_eq!(strip_code_fence("```json\n{\"a\":1}\n```"), "{\"a\":1}");
+        assert_eq!(strip_code_fence("```\n[1, 2]\n```"), "[1, 2]");
+        assert_eq!(strip_code_fence("```{\"a\":1}```"), "{\"a\":1}");
+    }
+}
@@ -0,0 +1,122 @@
+use serde_json::{json, Value};
+
+use crate::message::Message;
+use crate::state::ToolCall;
+
+/// `message` as a LangChain message dict (`type` human / ai / system / tool), the shape
+/// LangGraph Studio renders as chat. `id` becomes the message id.
+///
+/// Tool call arguments that are not valid JSON go to `invalid_tool_calls` with the raw string,
+/// as LangChain does.
+pub fn to_langchain_message(message: &Message, id: impl Into<String>) -> Value {
+    let id = id.into();
+    match message {
+        Message::System(content) => base("system", content, id),
+        Message::User(content) => base("human", content, id),
+        Message::Assistant {
+            content,
+            tool_calls,
+        } => {
+            let mut valid = Vec::new();
+            let mut invalid = Vec::new();
+            for call in tool_calls {
+                match tool_call(call) {
+                    Ok(call) => valid.push(call),
+                    Err(call) => invalid.push(call),
+                }
+            }
+            let mut value = base("ai", content, id);
+            value["tool_calls"] = Value::Array(valid);
+            value["invalid_tool_calls"] = Value::Array(invalid);
+            value
+        }
+        Message::Tool {
+            call_id,
+            name,
+            content,
+        } => {
+            let mut value = base("tool", content, id);
+            value["tool_call_id"] = call_id.clone().unwrap_or_default().into();
+            value["name"] = name.clone().into();
+            value["status"] = "success".into();
+            value
+        }
+    }
+}
+
+fn base(kind: &str, content: &str, id: String) -> Value {
+    json!({
+        "type": kind,
+        "content": content,
+        "id": id,
+        "name": null,
+        "additional_kwargs": {},
+        "response_metadata": {},
+    })
+}
+
+/// LangChain `ToolCall` dict, or an `InvalidToolCall` dict when the arguments do not parse.
+fn tool_call(call: &ToolCall) -> Result<Value, Value> {
+    let arguments = if call.arguments.trim().is_empty() {
+        "{}"
+    } else {
+        call.arguments.as_str()
+    };
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(args) if args.is_object() => Ok(json!({
+            "name": call.name,
+            "args": args,
+            "id": call.id,
+            "type": "tool_call",
+        })),
+        Ok(_) | Err(_) => Err(json!({
+            "name": call.name,
+            "args": call.arguments,
+            "id": call.id,
+            "error": "arguments are not a JSON object",
+            "type": "invalid_tool_call",
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Roles map to LangChain types; tool calls carry parsed args and bad
+    /// arguments become invalid tool calls.
+    #[test]
+    fn maps_roles_and_tool_calls() {
+        assert_eq!(
+            to_langchain_message(&Message::user("hi"), "m0")["type"],
+            "human"
+        );
+
+        let ai = Message::assistant_with_tool_calls(
+            "",
+            vec![
+                ToolCall {
+                    name: "get_time".into(),
+                    arguments: r#"{"tz":"UTC"}"#.into(),
+                    id: Some("c1".into()),
+                },
+                ToolCall {
+                    name: "search".into(),
+                    arguments: r#"{"q":"#.into(),
+                    id: Some("c2".into()),
+                },
+            ],
+        );
+        let value = to_langchain_message(&ai, "m1");
+        assert_eq!(value["type"], "ai");
+        assert_eq!(value["tool_calls"][0]["args"], json!({"tz": "UTC"}));
+        assert_eq!(value["tool_calls"][0]["type"], "tool_call");
+        assert_eq!(value["invalid_tool_calls"][0]["args"], r#"{"q":"#);
+
+        let tool = Message::tool(Some("c1".into()), Some("get_time".into()), "12:00");
+        let value = to_langchain_message(&tool, "m2");
+        assert_eq!(value["type"], "tool");
+        assert_eq!(value["tool_call_id"], "c1");
+        assert_eq!(value["name"], "get_time");
+    }
+}
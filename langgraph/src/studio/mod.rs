@@ -0,0 +1,463 @@
+//! Export of threads in the JSON understood by LangGraph Studio and langgraph-sdk.
+//!
+//! [`StudioExporter::export`] reads the checkpoints of one thread from any [`Checkpointer`] and
+//! returns a [`StudioExport`]: the thread (SDK `Thread`), its state history newest first (SDK
+//! `ThreadState`, as returned by `client.threads.get_history`) and its runs (SDK `Run`). Field
+//! names follow the Python SDK, and message lists become LangChain message dicts
+//! ([`to_langchain_message`]) so Studio renders them as chat.
+//!
+//! Runs are rebuilt from the checkpoints: every input checkpoint starts a run, and the
+//! checkpoints up to the next one (including those of resumes) belong to it. A run whose last
+//! checkpoint still has a pending node is `interrupted`, otherwise `success`.
+//!
+//! **Interaction**: Reads `Checkpointer::list` and `Checkpointer::get_tuple`; state values come
+//! from [`StudioValues`] (implemented for `ReActState` and `serde_json::Value`). Backs the CLI's
+//! `--export-studio`.
+
+mod message;
+
+pub use message::to_langchain_message;
+
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::memory::{Checkpoint, CheckpointError, CheckpointSource, Checkpointer, RunnableConfig};
+use crate::state::ReActState;
+
+/// Graph (and assistant) id the export claims when none is set; LangGraph templates use `agent`.
+pub const DEFAULT_GRAPH_ID: &str = "agent";
+
+/// State as the `values` of a Studio thread state.
+///
+/// Implement it for custom states; `serde_json::to_value(self)` is enough unless the state holds
+/// messages, which Studio only renders as LangChain message dicts.
+pub trait StudioValues {
+    /// JSON object of the state's channels.
+    fn studio_values(&self) -> Value;
+}
+
+impl StudioValues for ReActState {
+    /// All fields, with `messages` as LangChain message dicts (ids `msg-<index>`).
+    fn studio_values(&self) -> Value {
+        let mut values = serde_json::to_value(self).unwrap_or_else(|_| json!({}));
+        values["messages"] = self
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| to_langchain_message(m, format!("msg-{}", i)))
+            .collect();
+        values
+    }
+}
+
+impl StudioValues for Value {
+    fn studio_values(&self) -> Value {
+        self.clone()
+    }
+}
+
+/// SDK `Checkpoint`: where a thread state is stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StudioCheckpoint {
+    pub thread_id: String,
+    pub checkpoint_ns: String,
+    pub checkpoint_id: String,
+}
+
+/// SDK `ThreadState`: one checkpoint of the thread.
+#[derive(Debug, Clone, Serialize)]
+pub struct StudioThreadState {
+    /// State values ([`StudioValues`]).
+    pub values: Value,
+    /// Nodes to run next; empty when the run finished.
+    pub next: Vec<String>,
+    /// One task per node in `next`.
+    pub tasks: Vec<Value>,
+    pub checkpoint: StudioCheckpoint,
+    /// `source` (input, loop, update, fork), `step`, `parents`, `graph_id` and `thread_id`.
+    pub metadata: Value,
+    /// ISO 8601 creation time.
+    pub created_at: Option<String>,
+    /// Checkpoint this one was created from.
+    pub parent_checkpoint: Option<StudioCheckpoint>,
+}
+
+/// SDK `Thread`: the thread with its latest values.
+#[derive(Debug, Clone, Serialize)]
+pub struct StudioThread {
+    pub thread_id: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// `graph_id`, which Studio uses to list the threads of a graph.
+    pub metadata: Value,
+    /// `interrupted` when the latest checkpoint has a pending node, else `idle`.
+    pub status: String,
+    pub values: Value,
+}
+
+/// SDK `Run`: one run of the thread, rebuilt from its checkpoints.
+#[derive(Debug, Clone, Serialize)]
+pub struct StudioRun {
+    /// Id of the run's input checkpoint.
+    pub run_id: String,
+    pub thread_id: String,
+    pub assistant_id: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// `success` or `interrupted`.
+    pub status: String,
+    /// `checkpoint_ids`: the run's checkpoints, oldest first.
+    pub metadata: Value,
+    pub multitask_strategy: String,
+}
+
+/// A thread exported for LangGraph Studio / langgraph-sdk.
+#[derive(Debug, Clone, Serialize)]
+pub struct StudioExport {
+    pub thread: StudioThread,
+    /// Thread states, newest first.
+    pub history: Vec<StudioThreadState>,
+    /// Runs, oldest first.
+    pub runs: Vec<StudioRun>,
+}
+
+/// Builds [`StudioExport`]s from a checkpointer.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::memory::{MemorySaver, RunnableConfig};
+/// use langgraph::studio::StudioExporter;
+/// use langgraph::ReActState;
+///
+/// # async fn example(saver: MemorySaver<ReActState>) -> Result<(), Box<dyn std::error::Error>> {
+/// let config = RunnableConfig {
+///     thread_id: Some("thread-1".into()),
+///     ..Default::default()
+/// };
+/// let export = StudioExporter::new().export(&saver, &config).await?;
+/// println!("{}", serde_json::to_string_pretty(&export)?);
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StudioExporter {
+    graph_id: String,
+}
+
+impl Default for StudioExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StudioExporter {
+    /// Exporter for graph [`DEFAULT_GRAPH_ID`].
+    pub fn new() -> Self {
+        Self {
+            graph_id: DEFAULT_GRAPH_ID.to_string(),
+        }
+    }
+
+    /// Sets the graph id written to thread metadata and used as the runs' assistant id; use the
+    /// graph's name in `langgraph.json` when importing next to Python graphs.
+    pub fn with_graph_id(mut self, graph_id: impl Into<String>) -> Self {
+        self.graph_id = graph_id.into();
+        self
+    }
+
+    /// Exports the thread of `config.thread_id` (namespace `config.checkpoint_ns`).
+    ///
+    /// # Errors
+    ///
+    /// `ThreadIdRequired` without a thread id, `NotFound` when the thread has no checkpoint,
+    /// and the checkpointer's errors.
+    pub async fn export<S>(
+        &self,
+        checkpointer: &dyn Checkpointer<S>,
+        config: &RunnableConfig,
+    ) -> Result<StudioExport, CheckpointError>
+    where
+        S: StudioValues + Clone + Send + Sync + 'static,
+    {
+        let thread_id = config
+            .thread_id
+            .clone()
+            .ok_or(CheckpointError::ThreadIdRequired)?;
+        let mut thread_config = config.clone();
+        thread_config.checkpoint_id = None;
+
+        // Checkpointers list oldest first.
+        let mut checkpoints = Vec::new();
+        for item in checkpointer.list(&thread_config, None, None, None).await? {
+            let mut cfg = thread_config.clone();
+            cfg.checkpoint_id = Some(item.checkpoint_id);
+            if let Some((checkpoint, _)) = checkpointer.get_tuple(&cfg).await? {
+                checkpoints.push(checkpoint);
+            }
+        }
+        let (Some(first), Some(latest)) = (checkpoints.first(), checkpoints.last()) else {
+            return Err(CheckpointError::NotFound(thread_id));
+        };
+
+        let thread = StudioThread {
+            thread_id: thread_id.clone(),
+            created_at: created_at(first),
+            updated_at: created_at(latest),
+            metadata: json!({ "graph_id": self.graph_id }),
+            status: if latest.pending_node.is_some() {
+                "interrupted"
+            } else {
+                "idle"
+            }
+            .to_string(),
+            values: latest.channel_values.studio_values(),
+        };
+        let runs = self.runs(&thread_id, &checkpoints);
+        let history = checkpoints
+            .iter()
+            .rev()
+            .map(|cp| self.thread_state(&thread_id, &config.checkpoint_ns, cp))
+            .collect();
+        Ok(StudioExport {
+            thread,
+            history,
+            runs,
+        })
+    }
+
+    fn thread_state<S: StudioValues>(
+        &self,
+        thread_id: &str,
+        checkpoint_ns: &str,
+        checkpoint: &Checkpoint<S>,
+    ) -> StudioThreadState {
+        let at = |checkpoint_id: &str| StudioCheckpoint {
+            thread_id: thread_id.to_string(),
+            checkpoint_ns: checkpoint_ns.to_string(),
+            checkpoint_id: checkpoint_id.to_string(),
+        };
+        let metadata = &checkpoint.metadata;
+        let next: Vec<String> = checkpoint.pending_node.iter().cloned().collect();
+        let tasks = next
+            .iter()
+            .map(|node| {
+                json!({
+                    "id": format!("{}:{}", checkpoint.id, node),
+                    "name": node,
+                    "error": null,
+                    "interrupts": [],
+                    "checkpoint": null,
+                    "state": null,
+                })
+            })
+            .collect();
+        StudioThreadState {
+            values: checkpoint.channel_values.studio_values(),
+            next,
+            tasks,
+            checkpoint: at(&checkpoint.id),
+            metadata: json!({
+                "source": source_name(&metadata.source),
+                "step": metadata.step,
+                "parents": metadata.parents,
+                "graph_id": self.graph_id,
+                "thread_id": thread_id,
+            }),
+            created_at: created_at(checkpoint),
+            parent_checkpoint: metadata.parent_checkpoint_id.as_deref().map(at),
+        }
+    }
+
+    /// Splits `checkpoints` (oldest first) into runs at every input checkpoint.
+    fn runs<S>(&self, thread_id: &str, checkpoints: &[Checkpoint<S>]) -> Vec<StudioRun> {
+        let mut groups: Vec<Vec<&Checkpoint<S>>> = Vec::new();
+        for checkpoint in checkpoints {
+            match groups.last_mut() {
+                Some(group) if checkpoint.metadata.source != CheckpointSource::Input => {
+                    group.push(checkpoint)
+                }
+                _ => groups.push(vec![checkpoint]),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|group| {
+                let (first, last) = (group[0], group[group.len() - 1]);
+                let ids: Vec<&str> = group.iter().map(|cp| cp.id.as_str()).collect();
+                StudioRun {
+                    run_id: first.id.clone(),
+                    thread_id: thread_id.to_string(),
+                    assistant_id: self.graph_id.clone(),
+                    created_at: created_at(first),
+                    updated_at: created_at(last),
+                    status: if last.pending_node.is_some() {
+                        "interrupted"
+                    } else {
+                        "success"
+                    }
+                    .to_string(),
+                    metadata: json!({ "checkpoint_ids": ids }),
+                    multitask_strategy: "reject".to_string(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn source_name(source: &CheckpointSource) -> &'static str {
+    match source {
+        CheckpointSource::Input => "input",
+        CheckpointSource::Loop => "loop",
+        CheckpointSource::Update => "update",
+        CheckpointSource::Fork => "fork",
+    }
+}
+
+/// Creation time of `checkpoint` in ISO 8601: metadata time, else `ts` (milliseconds).
+fn created_at<S>(checkpoint: &Checkpoint<S>) -> Option<String> {
+    let millis = match checkpoint.metadata.created_at {
+        Some(t) => t.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64,
+        None => checkpoint.ts.parse().ok()?,
+    };
+    Some(iso8601(millis))
+}
+
+/// `millis` since the Unix epoch as `YYYY-MM-DDTHH:MM:SS.mmm+00:00`.
+fn iso8601(millis: i64) -> String {
+    let secs = millis.div_euclid(1000);
+    let days = secs.div_euclid(86_400);
+    let secs_of_day = secs.rem_euclid(86_400);
+    // Civil date from days since 1970-01-01 (H. Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}+00:00",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60,
+        millis.rem_euclid(1000)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemorySaver;
+    use crate::message::Message;
+
+    fn config(thread_id: &str) -> RunnableConfig {
+        RunnableConfig {
+            thread_id: Some(thread_id.into()),
+            ..Default::default()
+        }
+    }
+
+    fn state(messages: Vec<Message>) -> ReActState {
+        ReActState {
+            messages,
+            ..Default::default()
+        }
+    }
+
+    /// **Scenario**: Timestamps are ISO 8601 UTC with milliseconds.
+    #[test]
+    fn iso8601_formats_utc_millis() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00.000+00:00");
+        assert_eq!(iso8601(1_709_210_096_789), "2024-02-29T12:34:56.789+00:00");
+    }
+
+    /// **Scenario**: Two runs, the second interrupted: history is newest first with parents,
+    /// runs split at input checkpoints, and messages are LangChain dicts.
+    #[tokio::test]
+    async fn exports_history_and_runs() {
+        let saver = MemorySaver::<ReActState>::new();
+        let cfg = config("t1");
+        let hi = vec![Message::user("hi")];
+        let answered = vec![Message::user("hi"), Message::assistant("hello")];
+        let mut again = answered.clone();
+        again.push(Message::user("again"));
+
+        let mut parent: Option<String> = None;
+        let steps = [
+            (hi, CheckpointSource::Input, None),
+            (answered, CheckpointSource::Loop, None),
+            (again.clone(), CheckpointSource::Input, None),
+            (again, CheckpointSource::Loop, Some("act")),
+        ];
+        for (i, (messages, source, pending)) in steps.into_iter().enumerate() {
+            let mut cp = Checkpoint::from_state(state(messages), source, i as i64);
+            cp.metadata.parent_checkpoint_id = parent.clone();
+            if let Some(node) = pending {
+                cp = cp.with_pending_node(node);
+            }
+            parent = Some(saver.put(&cfg, &cp).await.unwrap());
+        }
+
+        let export = StudioExporter::new()
+            .with_graph_id("react")
+            .export(&saver, &cfg)
+            .await
+            .unwrap();
+
+        assert_eq!(export.thread.status, "interrupted");
+        assert_eq!(export.thread.metadata["graph_id"], "react");
+        assert_eq!(export.thread.values["messages"][1]["type"], "ai");
+        assert_eq!(export.thread.values["messages"][2]["content"], "again");
+
+        assert_eq!(export.history.len(), 4);
+        let latest = &export.history[0];
+        assert_eq!(latest.next, vec!["act".to_string()]);
+        assert_eq!(latest.tasks[0]["name"], "act");
+        assert_eq!(latest.metadata["source"], "loop");
+        assert_eq!(latest.metadata["step"], 3);
+        assert_eq!(
+            latest.parent_checkpoint.as_ref().unwrap().checkpoint_id,
+            export.history[1].checkpoint.checkpoint_id
+        );
+        assert!(export.history[3].parent_checkpoint.is_none());
+        assert!(latest.created_at.as_deref().unwrap().ends_with("+00:00"));
+
+        assert_eq!(export.runs.len(), 2);
+        assert_eq!(export.runs[0].status, "success");
+        assert_eq!(export.runs[1].status, "interrupted");
+        assert_eq!(export.runs[0].assistant_id, "react");
+        assert_eq!(
+            export.runs[1].run_id,
+            export.history[1].checkpoint.checkpoint_id
+        );
+        assert_eq!(
+            export.runs[0].metadata["checkpoint_ids"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    /// **Scenario**: A missing thread id or an unknown thread is an error.
+    #[tokio::test]
+    async fn missing_thread_is_an_error() {
+        let saver = MemorySaver::<ReActState>::new();
+        let exporter = StudioExporter::new();
+        assert!(matches!(
+            exporter.export(&saver, &RunnableConfig::default()).await,
+            Err(CheckpointError::ThreadIdRequired)
+        ));
+        assert!(matches!(
+            exporter.export(&saver, &config("nope")).await,
+            Err(CheckpointError::NotFound(_))
+        ));
+    }
+}
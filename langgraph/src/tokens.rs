@@ -0,0 +1,257 @@
+//! Offline token counting: how many tokens a text or a message list costs a model.
+//!
+//! [`TokenCounter`] counts text and messages; [`BpeTokenCounter`] is exact for OpenAI models (the
+//! `cl100k_base` / `o200k_base` BPE encodings, bundled, no API call), and [`ApproxTokenCounter`]
+//! estimates about four characters per token for other models. [`count_tokens`] and
+//! [`count_messages_tokens`] pick the encoding from the model name.
+//!
+//! Message lists are counted the way OpenAI bills chat requests: a few tokens of framing per
+//! message plus its role and content, and three tokens priming the reply. Tool calls add their
+//! name and arguments; tool schemas sent with the request are not counted.
+//!
+//! **Interaction**: Counts [`Message`]s as sent by `ChatOpenAI`; use it to trim histories or to
+//! estimate usage before a call, e.g. against a `BudgetPolicy`.
+
+use std::sync::OnceLock;
+
+use tiktoken_rs::CoreBPE;
+
+use crate::message::Message;
+
+/// Tokens of framing per chat message (`<|start|>{role}\n{content}<|end|>\n`).
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Tokens priming the assistant reply (`<|start|>assistant<|message|>`).
+const TOKENS_PER_REPLY: usize = 3;
+/// Tokens a tool message's name adds.
+const TOKENS_PER_NAME: usize = 1;
+
+/// Counts tokens of text and messages for one model.
+pub trait TokenCounter: Send + Sync {
+    /// Tokens of `text`.
+    fn count(&self, text: &str) -> usize;
+
+    /// Tokens of a chat request with `messages`, including per-message framing and the reply
+    /// priming.
+    fn count_messages(&self, messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .map(|m| self.count_message(m))
+            .sum::<usize>()
+            + TOKENS_PER_REPLY
+    }
+
+    /// Tokens of one message in a chat request, framing included.
+    fn count_message(&self, message: &Message) -> usize {
+        let mut tokens =
+            TOKENS_PER_MESSAGE + self.count(message.role()) + self.count(message.content());
+        match message {
+            Message::Assistant { tool_calls, .. } => {
+                for call in tool_calls {
+                    tokens +=
+                        TOKENS_PER_MESSAGE + self.count(&call.name) + self.count(&call.arguments);
+                }
+            }
+            Message::Tool {
+                name: Some(name), ..
+            } => {
+                tokens += TOKENS_PER_NAME + self.count(name);
+            }
+            _ => {}
+        }
+        tokens
+    }
+}
+
+/// BPE encoding of an OpenAI model family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// GPT-4, GPT-3.5 and the `text-embedding-*` models.
+    Cl100kBase,
+    /// GPT-4o, GPT-4.1 and the o-series reasoning models.
+    O200kBase,
+}
+
+impl Encoding {
+    /// Encoding of `model`; names not recognised as `o200k_base` models use `cl100k_base`.
+    pub fn for_model(model: &str) -> Self {
+        let model = model.rsplit('/').next().unwrap_or(model);
+        let o200k = [
+            "gpt-4o",
+            "gpt-4.1",
+            "gpt-4.5",
+            "gpt-5",
+            "chatgpt-4o",
+            "o1",
+            "o3",
+            "o4",
+        ];
+        if o200k.iter().any(|prefix| model.starts_with(prefix)) {
+            Self::O200kBase
+        } else {
+            Self::Cl100kBase
+        }
+    }
+
+    /// Encoding name as used by tiktoken, e.g. `cl100k_base`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cl100kBase => "cl100k_base",
+            Self::O200kBase => "o200k_base",
+        }
+    }
+
+    /// The encoder, built once per process.
+    fn bpe(&self) -> &'static CoreBPE {
+        static CL100K: OnceLock<CoreBPE> = OnceLock::new();
+        static O200K: OnceLock<CoreBPE> = OnceLock::new();
+        match self {
+            Self::Cl100kBase => CL100K
+                .get_or_init(|| tiktoken_rs::cl100k_base().expect("bundled cl100k_base ranks")),
+            Self::O200kBase => {
+                O200K.get_or_init(|| tiktoken_rs::o200k_base().expect("bundled o200k_base ranks"))
+            }
+        }
+    }
+}
+
+/// Exact token counts for OpenAI models, from the model's BPE encoding.
+///
+/// Special tokens in the text (e.g. `<|endoftext|>`) are counted as plain text, as the API
+/// does for message content.
+#[derive(Debug, Clone, Copy)]
+pub struct BpeTokenCounter {
+    encoding: Encoding,
+}
+
+impl BpeTokenCounter {
+    /// Counter for `encoding`.
+    pub fn new(encoding: Encoding) -> Self {
+        Self { encoding }
+    }
+
+    /// Counter for the encoding of `model` ([`Encoding::for_model`]).
+    pub fn for_model(model: &str) -> Self {
+        Self::new(Encoding::for_model(model))
+    }
+
+    /// The encoding counted with.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+}
+
+impl TokenCounter for BpeTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+        self.encoding.bpe().encode_ordinary(text).len()
+    }
+}
+
+/// Estimate of one token per `chars_per_token` characters (default 4), rounded up; for models
+/// without a known encoding.
+#[derive(Debug, Clone, Copy)]
+pub struct ApproxTokenCounter {
+    chars_per_token: usize,
+}
+
+impl Default for ApproxTokenCounter {
+    fn default() -> Self {
+        Self { chars_per_token: 4 }
+    }
+}
+
+impl ApproxTokenCounter {
+    /// Estimate of four characters per token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the characters per token (at least 1).
+    pub fn with_chars_per_token(mut self, chars_per_token: usize) -> Self {
+        self.chars_per_token = chars_per_token.max(1);
+        self
+    }
+}
+
+impl TokenCounter for ApproxTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(self.chars_per_token)
+    }
+}
+
+/// Tokens of `text` for `model`.
+pub fn count_tokens(text: &str, model: &str) -> usize {
+    BpeTokenCounter::for_model(model).count(text)
+}
+
+/// Tokens of a chat request with `messages` for `model` (see [`TokenCounter::count_messages`]).
+pub fn count_messages_tokens(messages: &[Message], model: &str) -> usize {
+    BpeTokenCounter::for_model(model).count_messages(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ToolCall;
+
+    /// **Scenario**: Models map to their encoding family, provider prefixes included.
+    #[test]
+    fn encoding_for_model() {
+        assert_eq!(Encoding::for_model("gpt-4o-mini"), Encoding::O200kBase);
+        assert_eq!(Encoding::for_model("openai/gpt-4.1"), Encoding::O200kBase);
+        assert_eq!(Encoding::for_model("o3-mini"), Encoding::O200kBase);
+        assert_eq!(Encoding::for_model("gpt-4-turbo"), Encoding::Cl100kBase);
+        assert_eq!(Encoding::for_model("gpt-3.5-turbo"), Encoding::Cl100kBase);
+        assert_eq!(Encoding::for_model("local-llama"), Encoding::Cl100kBase);
+        assert_eq!(Encoding::O200kBase.name(), "o200k_base");
+    }
+
+    /// **Scenario**: Text counts match tiktoken; special tokens count as text.
+    #[test]
+    fn counts_text_like_tiktoken() {
+        assert_eq!(count_tokens("tiktoken is great!", "gpt-4"), 6);
+        assert_eq!(count_tokens("hello world", "gpt-4o"), 2);
+        assert_eq!(count_tokens("", "gpt-4o"), 0);
+        assert!(count_tokens("<|endoftext|>", "gpt-4") > 1);
+    }
+
+    /// **Scenario**: Messages add framing, role and reply priming; tool calls add name and
+    /// arguments.
+    #[test]
+    fn counts_messages_with_framing() {
+        // 3 framing + 1 role ("user") + 2 content + 3 reply priming.
+        assert_eq!(
+            count_messages_tokens(&[Message::user("hello world")], "gpt-4o"),
+            9
+        );
+
+        let counter = BpeTokenCounter::for_model("gpt-4o");
+        let plain = Message::assistant("");
+        let with_call = Message::assistant_with_tool_calls(
+            "",
+            vec![ToolCall {
+                name: "get_time".into(),
+                arguments: r#"{"tz":"UTC"}"#.into(),
+                id: Some("c1".into()),
+            }],
+        );
+        assert!(counter.count_message(&with_call) > counter.count_message(&plain) + 3);
+    }
+
+    /// **Scenario**: The estimate rounds characters per token up.
+    #[test]
+    fn approx_counter_rounds_up() {
+        let counter = ApproxTokenCounter::new();
+        assert_eq!(counter.count(""), 0);
+        assert_eq!(counter.count("abcd"), 1);
+        assert_eq!(counter.count("abcde"), 2);
+        assert_eq!(
+            ApproxTokenCounter::new()
+                .with_chars_per_token(0)
+                .count("abc"),
+            3
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Tool allowlist that can be changed at runtime.
+//!
+//! A [`ToolAllowlist`] is a shared set of enabled tool names (or "all tools"); clones see the
+//! same set. [`AllowlistToolSource`] hides disabled tools from `list_tools` and refuses calls
+//! to them with [`ToolSourceError::NotFound`], so a tool switched off mid-conversation is
+//! reported to the model instead of run.
+//!
+//! **Interaction**: Wraps the tool source given to ActNode; pass the same allowlist to
+//! `ChatOpenAI::with_tool_allowlist` so the model is only offered enabled tools.
+
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+
+/// Shared set of enabled tools; `None` (the default) enables every tool.
+#[derive(Debug, Clone, Default)]
+pub struct ToolAllowlist(Arc<RwLock<Option<HashSet<String>>>>);
+
+impl ToolAllowlist {
+    /// Allowlist enabling every tool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables only the tools named in `names`, or every tool when `None`.
+    pub fn set<I, S>(&self, names: Option<I>)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names = names.map(|n| n.into_iter().map(Into::into).collect());
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = names;
+    }
+
+    /// Enabled tool names, sorted; `None` when every tool is enabled.
+    pub fn get(&self) -> Option<Vec<String>> {
+        let guard = self.0.read().unwrap_or_else(|e| e.into_inner());
+        guard.as_ref().map(|names| {
+            let mut names: Vec<String> = names.iter().cloned().collect();
+            names.sort();
+            names
+        })
+    }
+
+    /// Whether `name` is enabled.
+    pub fn allows(&self, name: &str) -> bool {
+        let guard = self.0.read().unwrap_or_else(|e| e.into_inner());
+        guard.as_ref().map_or(true, |names| names.contains(name))
+    }
+
+    /// `tools` without the disabled ones.
+    pub fn filter(&self, tools: &[ToolSpec]) -> Vec<ToolSpec> {
+        tools
+            .iter()
+            .filter(|t| self.allows(&t.name))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Tool source exposing only the tools enabled in its [`ToolAllowlist`].
+pub struct AllowlistToolSource {
+    inner: Box<dyn ToolSource>,
+    allowlist: ToolAllowlist,
+}
+
+impl AllowlistToolSource {
+    /// Wraps `inner`; tools are enabled as `allowlist` says at the time of each call.
+    pub fn new(inner: Box<dyn ToolSource>, allowlist: ToolAllowlist) -> Self {
+        Self { inner, allowlist }
+    }
+
+    fn check(&self, name: &str) -> Result<(), ToolSourceError> {
+        if !self.allowlist.allows(name) {
+            return Err(ToolSourceError::NotFound(format!("{} (disabled)", name)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ToolSource for AllowlistToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(self.allowlist.filter(&self.inner.list_tools().await?))
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.check(name)?;
+        self.inner.call_tool(name, arguments).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.check(name)?;
+        self.inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx)
+    }
+
+    fn tools_changed(&self) -> bool {
+        self.inner.tools_changed()
+    }
+
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(self.allowlist.filter(&self.inner.refresh_tools().await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::MockToolSource;
+    use serde_json::json;
+
+    /// **Scenario**: Disabling a tool hides it from the list and refuses its calls; enabling
+    /// all tools again restores it.
+    #[tokio::test]
+    async fn disabled_tools_are_hidden_and_refused() {
+        let allowlist = ToolAllowlist::new();
+        let source = AllowlistToolSource::new(
+            Box::new(MockToolSource::get_time_example()),
+            allowlist.clone(),
+        );
+        assert_eq!(source.list_tools().await.unwrap().len(), 1);
+
+        allowlist.set(Some(["web_fetcher"]));
+        assert_eq!(allowlist.get(), Some(vec!["web_fetcher".to_string()]));
+        assert!(source.list_tools().await.unwrap().is_empty());
+        assert!(matches!(
+            source.call_tool("get_time", json!({})).await,
+            Err(ToolSourceError::NotFound(_))
+        ));
+
+        allowlist.set(None::<Vec<String>>);
+        assert!(allowlist.allows("get_time"));
+        assert!(source.call_tool("get_time", json!({})).await.is_ok());
+    }
+}
@@ -0,0 +1,337 @@
+//! Tool result caching: reuse `call_tool(name, args)` results for identical calls.
+//!
+//! In multi-turn loops the model often repeats a web fetch or search with the same arguments;
+//! [`CachedToolSource`] answers those from a [`Cache`] until the entry expires. Only tools named
+//! with `with_cached_tools` are cached. The key is the tool name plus the arguments serialized
+//! with object keys sorted, so `{"a":1,"b":2}` and `{"b":2,"a":1}` share an entry; calls with a
+//! [`ToolCallContext`] are also keyed by its user and thread. Errors are never cached.
+//!
+//! **Interaction**: Wraps any [`ToolSource`] and is itself one; pass it to
+//! `ActNode::new(Box::new(...))`. Uses [`InMemoryCache`] unless another cache is set.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::cache::{Cache, InMemoryCache};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+
+/// Tool source that caches the results of another one.
+///
+/// Nothing is cached until [`with_cached_tools`](Self::with_cached_tools) names the tools; leave
+/// out tools with side effects (`remember`, `bash`, notifications) or with context-dependent
+/// results (`get_recent_messages`).
+///
+/// A call with a context is cached per user and thread, taken from its
+/// [`tool_state`](ToolCallContext::tool_state); when the context has no tool state (no thread
+/// id known) the call bypasses the cache.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tool_source::{CachedToolSource, WebToolsSource, TOOL_WEB_FETCHER};
+/// use std::time::Duration;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let web = WebToolsSource::new().await;
+/// let tools = CachedToolSource::new(Box::new(web), Some(Duration::from_secs(300)))
+///     .with_cached_tools([TOOL_WEB_FETCHER]);
+/// # }
+/// ```
+pub struct CachedToolSource {
+    inner: Box<dyn ToolSource>,
+    cache: Arc<dyn Cache<String, String>>,
+    ttl: Option<Duration>,
+    cached_tools: HashSet<String>,
+}
+
+impl CachedToolSource {
+    /// Caches the results of `inner` in memory for `ttl` (`None`: until the source is dropped).
+    pub fn new(inner: Box<dyn ToolSource>, ttl: Option<Duration>) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(InMemoryCache::new()),
+            ttl,
+            cached_tools: HashSet::new(),
+        }
+    }
+
+    /// Stores results in `cache` instead of a private in-memory cache, e.g. to share it between
+    /// agents.
+    pub fn with_cache(mut self, cache: Arc<dyn Cache<String, String>>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Caches the tools named in `names`; other calls always reach the inner source.
+    pub fn with_cached_tools<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.cached_tools = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Drops every cached result.
+    pub async fn clear(&self) {
+        if let Err(e) = self.cache.clear().await {
+            tracing::warn!("tool cache clear failed: {}", e);
+        }
+    }
+
+    /// Cache key for the call, or `None` when `name` is not cached or `ctx` has no tool state.
+    fn key(&self, name: &str, arguments: &Value, ctx: Option<&ToolCallContext>) -> Option<String> {
+        if !self.cached_tools.contains(name) {
+            return None;
+        }
+        let scope = match ctx {
+            // The namespace is `["tool_state", user_id, thread_id, tool_name]`.
+            Some(ctx) => json!(ctx.tool_state.as_ref()?.namespace().get(1..3)),
+            None => Value::Null,
+        };
+        Some(canonical_json(&json!([name, scope, arguments])))
+    }
+
+    /// Answers from the cache when possible, otherwise runs `call` and caches its success.
+    async fn cached<F>(
+        &self,
+        name: &str,
+        arguments: &Value,
+        ctx: Option<&ToolCallContext>,
+        call: F,
+    ) -> Result<ToolCallContent, ToolSourceError>
+    where
+        F: std::future::Future<Output = Result<ToolCallContent, ToolSourceError>> + Send,
+    {
+        let Some(key) = self.key(name, arguments, ctx) else {
+            return call.await;
+        };
+        if let Some(text) = self.cache.get(&key).await {
+            tracing::debug!(tool = %name, "tool result from cache");
+            return Ok(ToolCallContent { text });
+        }
+        let content = call.await?;
+        if let Err(e) = self.cache.set(key, content.text.clone(), self.ttl).await {
+            tracing::warn!(tool = %name, "tool cache write failed: {}", e);
+        }
+        Ok(content)
+    }
+}
+
+/// `value` serialized with object keys sorted at every level.
+fn canonical_json(value: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| (k.clone(), sorted(v)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    sorted(value).to_string()
+}
+
+#[async_trait]
+impl ToolSource for CachedToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.inner.list_tools().await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let call = self.inner.call_tool(name, arguments.clone());
+        self.cached(name, &arguments, None, call).await
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let call = self
+            .inner
+            .call_tool_with_context(name, arguments.clone(), ctx);
+        self.cached(name, &arguments, ctx, call).await
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx)
+    }
+
+    fn tools_changed(&self) -> bool {
+        self.inner.tools_changed()
+    }
+
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.inner.refresh_tools().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::tool_source::ToolStateStore;
+
+    /// Counts calls and answers `"{name} #{n}"`; the tool `fail` always errors.
+    #[derive(Default)]
+    struct CountingSource {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ToolSource for CountingSource {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            Ok(vec![])
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            _arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if name == "fail" {
+                return Err(ToolSourceError::Transport("down".into()));
+            }
+            Ok(ToolCallContent {
+                text: format!("{} #{}", name, n),
+            })
+        }
+    }
+
+    /// **Scenario**: Calls with the same name and arguments (in any key order) hit the cache;
+    /// other arguments, uncached tools and errors reach the inner source every time.
+    #[tokio::test]
+    async fn identical_calls_are_served_from_cache() {
+        let inner = CountingSource::default();
+        let calls = inner.calls.clone();
+        let source =
+            CachedToolSource::new(Box::new(inner), None).with_cached_tools(["search", "fail"]);
+
+        let first = source
+            .call_tool("search", json!({"q": "rust", "opts": {"a": 1, "b": 2}}))
+            .await
+            .unwrap();
+        let again = source
+            .call_tool_with_context(
+                "search",
+                json!({"opts": {"b": 2, "a": 1}, "q": "rust"}),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(first.text, "search #1");
+        assert_eq!(again.text, "search #1");
+
+        let other = source
+            .call_tool("search", json!({"q": "go"}))
+            .await
+            .unwrap();
+        assert_eq!(other.text, "search #2");
+
+        source.call_tool("remember", json!({})).await.unwrap();
+        let remember = source.call_tool("remember", json!({})).await.unwrap();
+        assert_eq!(remember.text, "remember #4");
+
+        assert!(source.call_tool("fail", json!({})).await.is_err());
+        assert!(source.call_tool("fail", json!({})).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+        source.clear().await;
+        let fresh = source
+            .call_tool("search", json!({"q": "rust", "opts": {"a": 1, "b": 2}}))
+            .await
+            .unwrap();
+        assert_eq!(fresh.text, "search #7");
+    }
+
+    /// **Scenario**: An entry expires after the TTL and the tool runs again.
+    #[tokio::test]
+    async fn entries_expire_after_ttl() {
+        let source = CachedToolSource::new(
+            Box::new(CountingSource::default()),
+            Some(Duration::from_millis(20)),
+        )
+        .with_cached_tools(["fetch"]);
+        let args = json!({"url": "https://example.com"});
+        assert_eq!(
+            source.call_tool("fetch", args.clone()).await.unwrap().text,
+            "fetch #1"
+        );
+        assert_eq!(
+            source.call_tool("fetch", args.clone()).await.unwrap().text,
+            "fetch #1"
+        );
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(
+            source.call_tool("fetch", args).await.unwrap().text,
+            "fetch #2"
+        );
+    }
+
+    /// **Scenario**: Without `with_cached_tools` nothing is cached.
+    #[tokio::test]
+    async fn tools_are_not_cached_by_default() {
+        let source = CachedToolSource::new(Box::new(CountingSource::default()), None);
+        let args = json!({"url": "https://example.com"});
+        source.call_tool("fetch", args.clone()).await.unwrap();
+        assert_eq!(
+            source.call_tool("fetch", args).await.unwrap().text,
+            "fetch #2"
+        );
+    }
+
+    /// **Scenario**: Calls with a context share an entry only within the same user and thread; a
+    /// context without tool state bypasses the cache.
+    #[tokio::test]
+    async fn context_calls_are_cached_per_user_and_thread() {
+        let source = CachedToolSource::new(Box::new(CountingSource::default()), None)
+            .with_cached_tools(["search"]);
+        let store = ToolStateStore::in_memory();
+        let scoped = |user: &str, thread: &str| ToolCallContext {
+            tool_state: store.for_tool(Some(user), Some(thread), "search"),
+            ..Default::default()
+        };
+        let args = json!({"q": "rust"});
+        let call = |ctx: ToolCallContext| {
+            let source = &source;
+            let args = args.clone();
+            async move {
+                source
+                    .call_tool_with_context("search", args, Some(&ctx))
+                    .await
+                    .unwrap()
+                    .text
+            }
+        };
+
+        assert_eq!(call(scoped("alice", "t1")).await, "search #1");
+        assert_eq!(call(scoped("alice", "t1")).await, "search #1");
+        assert_eq!(call(scoped("bob", "t1")).await, "search #2");
+        assert_eq!(call(scoped("alice", "t2")).await, "search #3");
+        assert_eq!(call(ToolCallContext::default()).await, "search #4");
+        assert_eq!(call(ToolCallContext::default()).await, "search #5");
+        assert_eq!(
+            source.call_tool("search", args.clone()).await.unwrap().text,
+            "search #6"
+        );
+    }
+}
@@ -24,9 +24,21 @@
 //!     "Result".to_string()
 //! }
 //! ```
+//!
+//! # UI Events
+//!
+//! Tools can attach structured hints for streaming UIs (a table, a chart, progress) with
+//! [`ToolCallContext::emit_ui_event`]. `ActNode` appends them to `ReActState::ui_events`, which
+//! is never sent to the LLM; clients see them as `StreamEvent::UiEvents`.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
 
 use crate::message::Message;
-use crate::stream::ToolStreamWriter;
+use crate::stream::{CustomEvent, CustomEventError, ToolStreamWriter};
+
+use super::ToolState;
 
 /// Per-step context available to tools during execution.
 ///
@@ -38,6 +50,8 @@ use crate::stream::ToolStreamWriter;
 ///
 /// - `recent_messages`: Current conversation messages from state
 /// - `stream_writer`: Optional writer for emitting custom streaming events
+/// - `tool_state`: Optional per-thread state of the called tool
+/// - `ui_events`: Optional sink for UI events of the call
 ///
 /// # Streaming
 ///
@@ -67,6 +81,38 @@ pub struct ToolCallContext {
     /// }
     /// ```
     pub stream_writer: Option<ToolStreamWriter>,
+
+    /// Durable state of the called tool in the current thread.
+    ///
+    /// Set by `ActNode` per call when a thread id is known, and only passed to
+    /// `call_tool_with_context`. Scoped to `(user_id, thread_id, tool name)`.
+    pub tool_state: Option<ToolState>,
+
+    /// Collects the UI events of the current call ([`emit_ui_event`](Self::emit_ui_event)).
+    ///
+    /// Set by `ActNode` per call; the events end up in `ReActState::ui_events` in call order.
+    pub ui_events: Option<UiEventSink>,
+}
+
+/// UI events collected during one tool call; clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct UiEventSink(Arc<Mutex<Vec<Value>>>);
+
+impl UiEventSink {
+    /// Creates an empty sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `event`; ActNode moves it to `state.ui_events` once the step's calls finish.
+    pub fn push(&self, event: Value) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+    }
+
+    /// Removes and returns the collected events, oldest first.
+    pub fn take(&self) -> Vec<Value> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
 }
 
 impl ToolCallContext {
@@ -75,6 +121,8 @@ impl ToolCallContext {
         Self {
             recent_messages,
             stream_writer: None,
+            tool_state: None,
+            ui_events: None,
         }
     }
 
@@ -86,6 +134,8 @@ impl ToolCallContext {
         Self {
             recent_messages,
             stream_writer: Some(stream_writer),
+            tool_state: None,
+            ui_events: None,
         }
     }
 
@@ -106,4 +156,35 @@ impl ToolCallContext {
             .map(|w| w.emit_custom(value))
             .unwrap_or(false)
     }
+
+    /// Emits a custom event of a declared type (see `ToolStreamWriter::emit_event`);
+    /// `Ok(false)` when no writer is available or sending failed.
+    pub fn emit_event(&self, event_type: &str, data: Value) -> Result<bool, CustomEventError> {
+        match &self.stream_writer {
+            Some(writer) => writer.emit_event(event_type, data),
+            None => Ok(false),
+        }
+    }
+
+    /// Emits a typed custom event (see `ToolStreamWriter::emit_typed`).
+    pub fn emit_typed<T: CustomEvent>(&self, event: &T) -> Result<bool, CustomEventError> {
+        match &self.stream_writer {
+            Some(writer) => writer.emit_typed(event),
+            None => Ok(false),
+        }
+    }
+
+    /// Appends a UI hint (e.g. `{"type": "table", "rows": [...]}`) to the run's UI events.
+    ///
+    /// Returns `false` when the caller collects no UI events (e.g. the tool runs outside
+    /// `ActNode`), in which case the event is dropped.
+    pub fn emit_ui_event(&self, event: Value) -> bool {
+        match &self.ui_events {
+            Some(sink) => {
+                sink.push(event);
+                true
+            }
+            None => false,
+        }
+    }
 }
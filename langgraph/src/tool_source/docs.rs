@@ -0,0 +1,380 @@
+//! Tool reference: renders a tool set as markdown or HTML documentation.
+//!
+//! For every tool the reference shows its name, description, an argument table (type,
+//! required, description) derived from the input schema, an example call and the schema
+//! itself, so a team can review exactly what an agent lets the model do.
+//!
+//! **Interaction**: Takes the [`ToolSpec`]s of any [`ToolSource`](super::ToolSource)
+//! (`list_tools`), e.g. the built-in and MCP tools of a configured agent; used by the CLI's
+//! `langgraph tools docs`.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+use super::ToolSpec;
+
+/// Output format of [`render_tool_docs`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolDocsFormat {
+    /// GitHub-flavored markdown.
+    #[default]
+    Markdown,
+    /// A standalone HTML page.
+    Html,
+}
+
+impl FromStr for ToolDocsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            other => Err(format!(
+                "unknown tool docs format: {} (use markdown or html)",
+                other
+            )),
+        }
+    }
+}
+
+/// One row of a tool's argument table.
+struct Argument {
+    name: String,
+    ty: String,
+    required: bool,
+    description: String,
+}
+
+/// The top-level properties of `schema`, in schema order.
+fn arguments(schema: &Value) -> Vec<Argument> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    properties
+        .iter()
+        .map(|(name, property)| Argument {
+            name: name.clone(),
+            ty: type_name(property),
+            required: required.contains(&name.as_str()),
+            description: property
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect()
+}
+
+/// Short type of a schema, e.g. `string`, `array of integer`, `"GET" | "POST"`.
+fn type_name(schema: &Value) -> String {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.clone(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "any".to_string(),
+    };
+    match (ty.as_str(), schema.get("items")) {
+        ("array", Some(items)) => format!("array of {}", type_name(items)),
+        _ => ty,
+    }
+}
+
+/// Example arguments for a tool with input schema `schema`: its `examples` when given,
+/// otherwise the required properties filled from their defaults, enums or placeholders.
+pub fn example_arguments(schema: &Value) -> Value {
+    example_value("value", schema)
+}
+
+fn example_value(name: &str, schema: &Value) -> Value {
+    if let Some(example) = schema
+        .get("examples")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return example.clone();
+    }
+    if let Some(default) = schema.get("default") {
+        return default.clone();
+    }
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return first.clone();
+    }
+    let ty = match schema.get("type") {
+        Some(Value::Array(types)) => types.iter().find_map(Value::as_str),
+        Some(ty) => ty.as_str(),
+        None if schema.get("properties").is_some() => Some("object"),
+        None => None,
+    };
+    match ty {
+        Some("object") => {
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|r| r.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut object = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (key, property) in properties {
+                    if required.contains(&key.as_str()) {
+                        object.insert(key.clone(), example_value(key, property));
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        Some("array") => match schema.get("items") {
+            Some(items) => Value::Array(vec![example_value(name, items)]),
+            None => Value::Array(Vec::new()),
+        },
+        Some("integer") | Some("number") => Value::from(1),
+        Some("boolean") => Value::Bool(true),
+        Some("null") => Value::Null,
+        _ => Value::String(format!("<{}>", name)),
+    }
+}
+
+/// Text on one line, with `|` escaped, for a markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Renders `tools`, in the given order, as a reference document.
+pub fn render_tool_docs(tools: &[ToolSpec], format: ToolDocsFormat) -> String {
+    match format {
+        ToolDocsFormat::Markdown => render_markdown(tools),
+        ToolDocsFormat::Html => render_html(tools),
+    }
+}
+
+fn render_markdown(tools: &[ToolSpec]) -> String {
+    let mut out = String::from("# Tool reference\n\n");
+    let _ = writeln!(out, "{} tools are exposed to the model.\n", tools.len());
+    for tool in tools {
+        let _ = writeln!(out, "- [`{}`](#{})", tool.name, anchor(&tool.name));
+    }
+    for tool in tools {
+        let _ = writeln!(out, "\n## `{}`\n", tool.name);
+        if let Some(description) = &tool.description {
+            let _ = writeln!(out, "{}\n", description.trim());
+        }
+        let arguments = arguments(&tool.input_schema);
+        if arguments.is_empty() {
+            out.push_str("No arguments.\n\n");
+        } else {
+            out.push_str("| Argument | Type | Required | Description |\n");
+            out.push_str("|---|---|---|---|\n");
+            for arg in arguments {
+                let _ = writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    arg.name,
+                    table_cell(&arg.ty),
+                    if arg.required { "yes" } else { "no" },
+                    table_cell(&arg.description)
+                );
+            }
+            out.push('\n');
+        }
+        let _ = writeln!(
+            out,
+            "Example arguments:\n\n```json\n{}\n```\n",
+            pretty(&example_arguments(&tool.input_schema))
+        );
+        let _ = writeln!(
+            out,
+            "<details><summary>Input schema</summary>\n\n```json\n{}\n```\n\n</details>",
+            pretty(&tool.input_schema)
+        );
+    }
+    out
+}
+
+/// Heading anchor GitHub generates for a tool name in backticks.
+fn anchor(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ')
+        .map(|c| {
+            if c == ' ' {
+                '-'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+fn render_html(tools: &[ToolSpec]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Tool reference</title>\n\
+         <style>body{font-family:sans-serif;max-width:60em;margin:auto}\
+         table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.3em .6em}\
+         pre{background:#f6f8fa;padding:.6em;overflow:auto}</style>\n</head>\n<body>\n\
+         <h1>Tool reference</h1>\n",
+    );
+    let _ = writeln!(
+        out,
+        "<p>{} tools are exposed to the model.</p>\n<ul>",
+        tools.len()
+    );
+    for tool in tools {
+        let name = escape_html(&tool.name);
+        let _ = writeln!(
+            out,
+            "<li><a href=\"#{}\"><code>{}</code></a></li>",
+            name, name
+        );
+    }
+    out.push_str("</ul>\n");
+    for tool in tools {
+        let name = escape_html(&tool.name);
+        let _ = writeln!(out, "<h2 id=\"{}\"><code>{}</code></h2>", name, name);
+        if let Some(description) = &tool.description {
+            let _ = writeln!(out, "<p>{}</p>", escape_html(description.trim()));
+        }
+        let arguments = arguments(&tool.input_schema);
+        if arguments.is_empty() {
+            out.push_str("<p>No arguments.</p>\n");
+        } else {
+            out.push_str(
+                "<table>\n<tr><th>Argument</th><th>Type</th><th>Required</th>\
+                 <th>Description</th></tr>\n",
+            );
+            for arg in arguments {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(&arg.name),
+                    escape_html(&arg.ty),
+                    if arg.required { "yes" } else { "no" },
+                    escape_html(&arg.description)
+                );
+            }
+            out.push_str("</table>\n");
+        }
+        let _ = writeln!(
+            out,
+            "<p>Example arguments:</p>\n<pre><code>{}</code></pre>",
+            escape_html(&pretty(&example_arguments(&tool.input_schema)))
+        );
+        let _ = writeln!(
+            out,
+            "<details><summary>Input schema</summary>\n<pre><code>{}</code></pre>\n</details>",
+            escape_html(&pretty(&tool.input_schema))
+        );
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fetcher() -> ToolSpec {
+        ToolSpec {
+            name: "web_fetcher".into(),
+            description: Some("Fetch a URL.".into()),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "URL to fetch" },
+                    "method": { "type": "string", "enum": ["GET", "POST"] },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    /// **Scenario**: Example arguments hold the required properties only, from examples,
+    /// defaults, enums or placeholders.
+    #[test]
+    fn example_arguments_fill_required_properties() {
+        assert_eq!(
+            example_arguments(&fetcher().input_schema),
+            json!({"url": "<url>"})
+        );
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "n": { "type": "integer", "default": 5 },
+                "mode": { "enum": ["a", "b"] },
+                "ids": { "type": "array", "items": { "type": "integer" } }
+            },
+            "required": ["n", "mode", "ids"]
+        });
+        assert_eq!(
+            example_arguments(&schema),
+            json!({"n": 5, "mode": "a", "ids": [1]})
+        );
+    }
+
+    /// **Scenario**: Markdown lists every tool with its argument table, example and schema;
+    /// HTML escapes the same content.
+    #[test]
+    fn renders_markdown_and_html() {
+        let tools = [
+            fetcher(),
+            ToolSpec {
+                name: "get_time".into(),
+                description: None,
+                input_schema: json!({"type": "object", "properties": {}}),
+            },
+        ];
+        let markdown = render_tool_docs(&tools, ToolDocsFormat::Markdown);
+        assert!(markdown.contains("2 tools are exposed"), "{}", markdown);
+        assert!(markdown.contains("- [`web_fetcher`](#web_fetcher)"));
+        assert!(markdown.contains("| `url` | string | yes | URL to fetch |"));
+        assert!(markdown.contains(r#"| `method` | "GET" \| "POST" | no |  |"#));
+        assert!(markdown.contains("| `tags` | array of string | no |  |"));
+        assert!(markdown.contains("## `get_time`\n\nNo arguments."));
+
+        let html = render_tool_docs(&tools, ToolDocsFormat::Html);
+        assert!(html.contains("<h2 id=\"web_fetcher\"><code>web_fetcher</code></h2>"));
+        assert!(
+            html.contains("&quot;GET&quot; | &quot;POST&quot;"),
+            "{}",
+            html
+        );
+        assert!(html.ends_with("</html>\n"));
+        assert_eq!("HTML".parse::<ToolDocsFormat>(), Ok(ToolDocsFormat::Html));
+        assert!("pdf".parse::<ToolDocsFormat>().is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Filesystem tools source: `read_file`, `write_file`, `list_dir` and `glob` over one root
+//! directory.
+//!
+//! Gives coding and RAG agents a workspace without a shell: every path is confined to the
+//! configured root (see [`FsJail`]), reads and writes are size-limited, and a read-only source
+//! leaves out `write_file`.
+//!
+//! **Interaction**: Builds the tools in [`crate::tools::fs`] and registers them on an
+//! [`AggregateToolSource`].
+
+use std::path::PathBuf;
+
+use crate::tools::fs::{FsJail, GlobTool, ListDirTool, ReadFileTool, WriteFileTool};
+use crate::tools::{AggregateToolSource, Tool};
+
+/// Filesystem tools confined to one root directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tool_source::FsToolSource;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let tools = FsToolSource::new("./workspace")
+///     .with_read_only(true)
+///     .into_tool_source()
+///     .await;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FsToolSource {
+    jail: FsJail,
+    read_only: bool,
+}
+
+impl FsToolSource {
+    /// Tools over `root` with the default size limits, writable.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            jail: FsJail::new(root),
+            read_only: false,
+        }
+    }
+
+    /// Leaves out `write_file` when `read_only` is true.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Bytes `read_file` returns at most (default
+    /// [`DEFAULT_MAX_READ_BYTES`](crate::tools::fs::DEFAULT_MAX_READ_BYTES)).
+    pub fn with_max_read_bytes(mut self, bytes: usize) -> Self {
+        self.jail = self.jail.with_max_read_bytes(bytes);
+        self
+    }
+
+    /// Bytes `write_file` accepts at most (default
+    /// [`DEFAULT_MAX_WRITE_BYTES`](crate::tools::fs::DEFAULT_MAX_WRITE_BYTES)).
+    pub fn with_max_write_bytes(mut self, bytes: usize) -> Self {
+        self.jail = self.jail.with_max_write_bytes(bytes);
+        self
+    }
+
+    /// The tools, in the order read_file, write_file (unless read-only), list_dir, glob.
+    pub fn tools(&self) -> Vec<Box<dyn Tool>> {
+        let mut tools: Vec<Box<dyn Tool>> = vec![Box::new(ReadFileTool::new(self.jail.clone()))];
+        if !self.read_only {
+            tools.push(Box::new(WriteFileTool::new(self.jail.clone())));
+        }
+        tools.push(Box::new(ListDirTool::new(self.jail.clone())));
+        tools.push(Box::new(GlobTool::new(self.jail.clone())));
+        tools
+    }
+
+    /// Registers the tools on `aggregate`, next to its other tools.
+    pub async fn register(&self, aggregate: &AggregateToolSource) {
+        for tool in self.tools() {
+            aggregate.register_async(tool).await;
+        }
+    }
+
+    /// A tool source with only the filesystem tools; pass to `ActNode::new(Box::new(...))`.
+    pub async fn into_tool_source(self) -> AggregateToolSource {
+        let aggregate = AggregateToolSource::new();
+        self.register(&aggregate).await;
+        aggregate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::ToolSource;
+    use serde_json::json;
+
+    /// **Scenario**: A read-only source has no write_file; a writable one writes files that
+    /// read_file then returns.
+    #[tokio::test]
+    async fn read_only_leaves_out_write_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = |source: &FsToolSource| -> Vec<String> {
+            source
+                .tools()
+                .iter()
+                .map(|t| t.name().to_string())
+                .collect()
+        };
+        let source = FsToolSource::new(dir.path());
+        assert_eq!(
+            names(&source.clone().with_read_only(true)),
+            ["read_file", "list_dir", "glob"]
+        );
+        assert_eq!(
+            names(&source),
+            ["read_file", "write_file", "list_dir", "glob"]
+        );
+
+        let source = source.into_tool_source().await;
+        source
+            .call_tool("write_file", json!({"path": "a.txt", "content": "hi"}))
+            .await
+            .unwrap();
+        let read = source
+            .call_tool("read_file", json!({"path": "a.txt"}))
+            .await
+            .unwrap();
+        assert_eq!(read.text, "hi");
+    }
+}
@@ -0,0 +1,387 @@
+//! Named MCP server settings: how to start or reach a server and how its tools are named.
+//!
+//! A [`McpServerConfig`] is a `command` run over stdio or a Streamable HTTP `url`, with env vars
+//! or headers. `${VAR}` in args, env, url and headers is read from the environment by
+//! [`McpServerConfig::transport`], so files holding the settings need no secrets. With
+//! `tool_prefix`, the server's tools are offered as `<prefix>_<tool>`, so two servers exporting
+//! the same tool name can be used together.
+//!
+//! Servers are listed in a TOML or JSON file (chosen by extension) under `servers`:
+//!
+//! ```toml
+//! [[servers]]
+//! name = "github"
+//! command = "npx"
+//! args = ["-y", "@modelcontextprotocol/server-github"]
+//! env = { GITHUB_TOKEN = "${GITHUB_TOKEN}" }
+//! tool_prefix = "gh"
+//!
+//! [[servers]]
+//! name = "exa"
+//! url = "https://mcp.exa.ai/mcp"
+//! headers = { EXA_API_KEY = "${EXA_API_KEY}" }
+//! ```
+//!
+//! **Interaction**: [`ReactBuildConfig::mcp_servers`](crate::ReactBuildConfig::mcp_servers) and
+//! `mcp_servers_file` (`MCP_SERVERS_FILE`), and skill manifests (`[[tools.mcp]]`); connected by
+//! `build_tool_source` and registered with
+//! [`register_mcp_tools_with_prefix`](crate::tools::register_mcp_tools_with_prefix).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// Error reading or resolving MCP server settings.
+#[derive(Debug, thiserror::Error)]
+pub enum McpConfigError {
+    #[error("read MCP servers {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("parse MCP servers {}: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+    #[error("MCP server {server}: {message}")]
+    Invalid { server: String, message: String },
+    /// Two servers of the same name with different settings.
+    #[error("MCP server {0} is configured twice")]
+    Duplicate(String),
+    /// `${VAR}` in a setting names an unset environment variable.
+    #[error("MCP server {server}: environment variable {var} is not set")]
+    MissingEnv { server: String, var: String },
+}
+
+/// Transport named in the settings; inferred from `command` / `url` when omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransportKind {
+    Stdio,
+    Http,
+}
+
+/// One MCP server: a `command` run over stdio or a Streamable HTTP `url`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct McpServerConfig {
+    /// Unique name, used in logs and errors.
+    pub name: String,
+    #[serde(default)]
+    pub transport: Option<McpTransportKind>,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment of the `command`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Headers sent to the `url` on every request.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// When set, the server's tools are offered as `<tool_prefix>_<tool>`.
+    #[serde(default)]
+    pub tool_prefix: Option<String>,
+}
+
+/// How to reach an MCP server, with `${VAR}` replaced from the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpTransport {
+    Stdio {
+        command: String,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+    },
+    Http {
+        url: String,
+        headers: Vec<(String, String)>,
+    },
+}
+
+/// Contents of an MCP servers file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct McpServersFile {
+    #[serde(default)]
+    servers: Vec<McpServerConfig>,
+}
+
+impl McpServerConfig {
+    /// Stdio server running `command` with `args`.
+    pub fn stdio(name: impl Into<String>, command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            command: Some(command.into()),
+            args,
+            ..Self::empty(name.into())
+        }
+    }
+
+    /// Streamable HTTP server at `url`.
+    pub fn http(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            url: Some(url.into()),
+            ..Self::empty(name.into())
+        }
+    }
+
+    fn empty(name: String) -> Self {
+        Self {
+            name,
+            transport: None,
+            command: None,
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            url: None,
+            headers: BTreeMap::new(),
+            tool_prefix: None,
+        }
+    }
+
+    /// Adds an environment variable of the `command`.
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    /// Adds a header sent to the `url`.
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    /// Offers the server's tools as `<prefix>_<tool>`.
+    pub fn with_tool_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.tool_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Checks that the settings name one transport and a usable tool prefix.
+    pub fn validate(&self) -> Result<(), McpConfigError> {
+        let invalid = |message: &str| McpConfigError::Invalid {
+            server: self.name.clone(),
+            message: message.to_string(),
+        };
+        if self.name.trim().is_empty() {
+            return Err(invalid("name must not be empty"));
+        }
+        match (self.transport, &self.command, &self.url) {
+            (None | Some(McpTransportKind::Stdio), Some(_), None) => {}
+            (None | Some(McpTransportKind::Http), None, Some(_)) => {}
+            (Some(McpTransportKind::Stdio), _, _) => {
+                return Err(invalid("stdio needs a command and no url"))
+            }
+            (Some(McpTransportKind::Http), _, _) => {
+                return Err(invalid("http needs a url and no command"))
+            }
+            (None, _, _) => return Err(invalid("needs either command or url")),
+        }
+        if let Some(prefix) = &self.tool_prefix {
+            let valid = !prefix.is_empty()
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid {
+                return Err(invalid("tool_prefix may only use letters, digits, _ and -"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Transport with `${VAR}` in args, env, url and headers read from the environment.
+    pub fn transport(&self) -> Result<McpTransport, McpConfigError> {
+        self.validate()?;
+        let expand = |value: &str| expand_env(&self.name, value);
+        let pairs = |map: &BTreeMap<String, String>| {
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), expand(v)?)))
+                .collect::<Result<Vec<_>, McpConfigError>>()
+        };
+        match (&self.command, &self.url) {
+            (Some(command), _) => Ok(McpTransport::Stdio {
+                command: command.clone(),
+                args: self
+                    .args
+                    .iter()
+                    .map(|a| expand(a))
+                    .collect::<Result<_, _>>()?,
+                env: pairs(&self.env)?,
+            }),
+            (None, url) => Ok(McpTransport::Http {
+                url: expand(url.as_deref().unwrap_or_default())?,
+                headers: pairs(&self.headers)?,
+            }),
+        }
+    }
+
+    /// Reads the `servers` of a `.json` file, or of a TOML file for any other extension.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<Self>, McpConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| McpConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| McpConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        };
+        let file: McpServersFile = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&text).map_err(|e| parse_error(e.to_string()))?
+        } else {
+            toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?
+        };
+        let mut servers = Vec::with_capacity(file.servers.len());
+        for server in file.servers {
+            server.validate()?;
+            merge_server(&mut servers, server)?;
+        }
+        Ok(servers)
+    }
+}
+
+/// Adds `server` to `servers` unless an identical one is there; fails when one of the same
+/// name differs.
+pub(crate) fn merge_server(
+    servers: &mut Vec<McpServerConfig>,
+    server: McpServerConfig,
+) -> Result<(), McpConfigError> {
+    match servers.iter().find(|s| s.name == server.name) {
+        Some(existing) if *existing == server => Ok(()),
+        Some(_) => Err(McpConfigError::Duplicate(server.name)),
+        None => {
+            servers.push(server);
+            Ok(())
+        }
+    }
+}
+
+/// Replaces every `${VAR}` in `value` with the environment variable.
+fn expand_env(server: &str, value: &str) -> Result<String, McpConfigError> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let var = &rest[start + 2..start + 2 + len];
+        let resolved = std::env::var(var).map_err(|_| McpConfigError::MissingEnv {
+            server: server.to_string(),
+            var: var.to_string(),
+        })?;
+        out.push_str(&rest[..start]);
+        out.push_str(&resolved);
+        rest = &rest[start + 3 + len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: TOML and JSON files list the same servers; identical entries are kept once
+    /// and a name configured differently is refused.
+    #[test]
+    fn load_file_reads_toml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("mcp.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+[[servers]]
+name = "github"
+command = "npx"
+args = ["-y", "@modelcontextprotocol/server-github"]
+tool_prefix = "gh"
+
+[[servers]]
+name = "exa"
+transport = "http"
+url = "https://mcp.exa.ai/mcp"
+"#,
+        )
+        .unwrap();
+        let json_path = dir.path().join("mcp.json");
+        std::fs::write(
+            &json_path,
+            r#"{"servers": [
+                {"name": "github", "command": "npx",
+                 "args": ["-y", "@modelcontextprotocol/server-github"], "tool_prefix": "gh"},
+                {"name": "exa", "transport": "http", "url": "https://mcp.exa.ai/mcp"},
+                {"name": "exa", "transport": "http", "url": "https://mcp.exa.ai/mcp"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let from_toml = McpServerConfig::load_file(&toml_path).unwrap();
+        assert_eq!(from_toml, McpServerConfig::load_file(&json_path).unwrap());
+        assert_eq!(
+            from_toml[0],
+            McpServerConfig::stdio(
+                "github",
+                "npx",
+                vec!["-y".into(), "@modelcontextprotocol/server-github".into()]
+            )
+            .with_tool_prefix("gh")
+        );
+
+        std::fs::write(
+            &json_path,
+            r#"{"servers": [
+                {"name": "exa", "url": "https://mcp.exa.ai/mcp"},
+                {"name": "exa", "url": "http://localhost:9000/mcp"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            McpServerConfig::load_file(&json_path),
+            Err(McpConfigError::Duplicate(name)) if name == "exa"
+        ));
+    }
+
+    /// **Scenario**: A transport that contradicts command / url, a server with neither and a
+    /// prefix that would make invalid tool names are refused.
+    #[test]
+    fn validate_refuses_inconsistent_servers() {
+        let mut stdio_url = McpServerConfig::http("x", "http://localhost");
+        stdio_url.transport = Some(McpTransportKind::Stdio);
+        assert!(stdio_url.validate().is_err());
+        let both = McpServerConfig {
+            url: Some("http://localhost".into()),
+            ..McpServerConfig::stdio("x", "srv", vec![])
+        };
+        assert!(both.validate().is_err());
+        assert!(McpServerConfig::empty("x".into()).validate().is_err());
+        let prefixed = McpServerConfig::stdio("x", "srv", vec![]);
+        assert!(prefixed.clone().with_tool_prefix("gh-2").validate().is_ok());
+        assert!(prefixed.with_tool_prefix("g h").validate().is_err());
+    }
+
+    /// **Scenario**: `${VAR}` in MCP settings is read from the environment when the transport
+    /// is built; an unset variable is reported with its server.
+    #[test]
+    fn transport_expands_env_vars() {
+        let exa = McpServerConfig::http("exa", "https://mcp.exa.ai/mcp")
+            .with_header("EXA_API_KEY", "${MCP_CONFIG_TEST_KEY}");
+        std::env::remove_var("MCP_CONFIG_TEST_KEY");
+        assert!(matches!(
+            exa.transport(),
+            Err(McpConfigError::MissingEnv { server, var })
+                if server == "exa" && var == "MCP_CONFIG_TEST_KEY"
+        ));
+        std::env::set_var("MCP_CONFIG_TEST_KEY", "k-123");
+        assert_eq!(
+            exa.transport().unwrap(),
+            McpTransport::Http {
+                url: "https://mcp.exa.ai/mcp".into(),
+                headers: vec![("EXA_API_KEY".into(), "k-123".into())],
+            }
+        );
+        assert_eq!(
+            expand_env("s", "a-${MCP_CONFIG_TEST_KEY}-b-${").unwrap(),
+            "a-k-123-b-${"
+        );
+    }
+}
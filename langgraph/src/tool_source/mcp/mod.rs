@@ -3,8 +3,11 @@
 //! Design: docs/rust-langgraph/mcp-integration/mcp-tool-devplan.md.
 //! Uses `McpSession` (stdio) or `McpHttpSession` (HTTP); maps MCP tools/list and
 //! tools/call to `ToolSpec` and `ToolCallContent`. For Exa, HTTP is preferred when
-//! the server URL is http(s).
+//! the server URL is http(s). Servers that add or remove tools at runtime announce it with
+//! `notifications/tools/list_changed`; both sessions record it for `ToolSource::tools_changed`,
+//! and `ToolSource::refresh_tools` re-lists.
 
+mod config;
 mod session;
 mod session_http;
 
@@ -16,11 +19,17 @@ use tokio::task;
 
 use mcp_core::ResultMessage;
 
+use crate::llm::HttpClientConfig;
 use crate::tool_source::{ToolCallContent, ToolSource, ToolSourceError, ToolSpec};
 
+pub(crate) use config::merge_server;
+pub use config::{McpConfigError, McpServerConfig, McpTransport, McpTransportKind};
 pub use session::{McpSession, McpSessionError};
 pub use session_http::McpHttpSession;
 
+/// Notification an MCP server sends when its tool list changed.
+pub(crate) const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
+
 /// Transport kind: stdio (spawn process) or HTTP (POST to URL).
 /// HTTP variant uses `Arc` so we can release the mutex before awaiting.
 enum McpSessionKind {
@@ -88,7 +97,19 @@ impl McpToolSource {
         url: impl Into<String>,
         headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
     ) -> Result<Self, ToolSourceError> {
-        let session = McpHttpSession::new(url, headers).await?;
+        Self::new_http_with_config(url, headers, &HttpClientConfig::default()).await
+    }
+
+    /// Like `new_http`, but requests go through a client built from `http_config`, so a
+    /// corporate proxy or custom CA applies to the MCP server too.
+    ///
+    /// **Interaction**: `register_exa_mcp` passes `ReactBuildConfig::http_client`.
+    pub async fn new_http_with_config(
+        url: impl Into<String>,
+        headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+        http_config: &HttpClientConfig,
+    ) -> Result<Self, ToolSourceError> {
+        let session = McpHttpSession::new_with_http_config(url, headers, http_config).await?;
         Ok(Self {
             session: Mutex::new(McpSessionKind::Http(Arc::new(session))),
         })
@@ -270,6 +291,30 @@ impl ToolSource for McpToolSource {
         let result = arc.request(&id, "tools/call", params).await?;
         parse_call_tool_result(result)
     }
+
+    fn tools_changed(&self) -> bool {
+        match self.session.lock() {
+            Ok(guard) => match &*guard {
+                McpSessionKind::Stdio(s) => s.tools_changed(),
+                McpSessionKind::Http(h) => h.tools_changed(),
+            },
+            Err(_) => false,
+        }
+    }
+
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        {
+            let guard = self
+                .session
+                .lock()
+                .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+            match &*guard {
+                McpSessionKind::Stdio(s) => s.clear_tools_changed(),
+                McpSessionKind::Http(h) => h.clear_tools_changed(),
+            }
+        }
+        self.list_tools().await
+    }
 }
 
 #[cfg(test)]
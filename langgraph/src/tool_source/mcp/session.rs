@@ -2,9 +2,13 @@
 //!
 //! Design: docs/rust-langgraph/mcp-integration/mcp-tool-devplan.md.
 //! Wraps `StdioClientTransport` from mcp_client; used by `McpToolSource` for
-//! `tools/list` and `tools/call`. Does not handle resources or prompts.
+//! `tools/list` and `tools/call`. Does not handle resources or prompts. A
+//! `notifications/tools/list_changed` from the server, whenever it arrives, is recorded
+//! for [`McpSession::tools_changed`].
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use mcp_client::stdio::{
@@ -14,6 +18,8 @@ use mcp_client::stdio::{
 use mcp_core::{MessageId, NotificationMessage, RequestMessage, ResultMessage};
 use serde_json::{json, Value};
 
+use super::TOOLS_LIST_CHANGED;
+
 /// Protocol version for MCP initialize.
 const PROTOCOL_VERSION: &str = "2025-11-25";
 /// Request id for initialize.
@@ -28,6 +34,8 @@ const INITIALIZE_REQUEST_ID: &str = "langgraph-mcp-initialize";
 pub struct McpSession {
     transport: StdioClientTransport,
     receiver: mpsc::Receiver<JsonRpcMessage>,
+    /// Set by the transport's message callback on `notifications/tools/list_changed`.
+    tools_changed: Arc<AtomicBool>,
 }
 
 impl McpSession {
@@ -61,8 +69,13 @@ impl McpSession {
             params = params.env(env_iter);
         }
 
+        let tools_changed = Arc::new(AtomicBool::new(false));
+        let changed = Arc::clone(&tools_changed);
         let mut transport = StdioClientTransport::new(params);
         transport.on_message(move |msg| {
+            if matches!(&msg, JsonRpcMessage::Notification(n) if n.method == TOOLS_LIST_CHANGED) {
+                changed.store(true, Ordering::SeqCst);
+            }
             let _ = tx.send(msg);
         });
         transport.on_error(|e| {
@@ -74,6 +87,7 @@ impl McpSession {
         let mut session = Self {
             transport,
             receiver: rx,
+            tools_changed,
         };
         session.initialize()?;
         Ok(session)
@@ -120,6 +134,16 @@ impl McpSession {
         Ok(())
     }
 
+    /// Whether the server announced a change of its tools since the flag was last cleared.
+    pub fn tools_changed(&self) -> bool {
+        self.tools_changed.load(Ordering::SeqCst)
+    }
+
+    /// Clears the flag [`tools_changed`](Self::tools_changed) reports; call before re-listing.
+    pub fn clear_tools_changed(&self) {
+        self.tools_changed.store(false, Ordering::SeqCst);
+    }
+
     /// Sends a JSON-RPC request. Does not wait for the response.
     pub fn send_request(
         &mut self,
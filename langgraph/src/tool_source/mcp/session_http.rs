@@ -5,10 +5,14 @@
 //! POST single JSON-RPC message, Accept: application/json and text/event-stream,
 //! optional MCP-Session-Id and MCP-Protocol-Version headers.
 //!
-//! **Interaction**: Created by `McpToolSource::new_http`; used for `initialize`,
-//! `tools/list`, and `tools/call` when the server URL is http(s).
-//! Uses async reqwest; safe to create and use from async/tokio context.
+//! **Interaction**: Created by `McpToolSource::new_http` / `new_http_with_config`; used for
+//! `initialize`, `tools/list`, and `tools/call` when the server URL is http(s). Proxy and CA
+//! settings come from [`HttpClientConfig`].
+//! Uses async reqwest; safe to create and use from async/tokio context. A
+//! `notifications/tools/list_changed` the server streams before a response is recorded for
+//! [`McpHttpSession::tools_changed`].
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
 use mcp_core::{ErrorObject, MessageId, NotificationMessage, RequestMessage, ResultMessage};
@@ -16,12 +20,17 @@ use reqwest::Client;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use crate::llm::HttpClientConfig;
 use crate::tool_source::ToolSourceError;
 
+use super::TOOLS_LIST_CHANGED;
+
 /// MCP protocol version for HTTP header.
 const MCP_PROTOCOL_VERSION: &str = "2025-11-25";
 /// Request id for initialize.
 const INITIALIZE_REQUEST_ID: &str = "langgraph-mcp-initialize";
+/// Per-request timeout for MCP calls.
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// JSON-RPC error object in response body.
 #[derive(Debug, Deserialize)]
@@ -30,20 +39,40 @@ struct JsonRpcErrorBody {
     message: String,
 }
 
-/// JSON-RPC response body (id + result or error).
+/// JSON-RPC response body (id + result or error); `method` is set on server notifications.
 #[derive(Debug, Deserialize)]
 struct JsonRpcResponse {
     id: Option<MessageId>,
     result: Option<Value>,
     error: Option<JsonRpcErrorBody>,
+    #[serde(default)]
+    method: Option<String>,
+}
+
+/// Parses one SSE message: returns it when it is a response (has result or error); when it
+/// is a notification, adds its method to `notifications` (once).
+fn response_or_notification(
+    data: &str,
+    notifications: &mut Vec<String>,
+) -> Option<JsonRpcResponse> {
+    let r = serde_json::from_str::<JsonRpcResponse>(data).ok()?;
+    if r.result.is_some() || r.error.is_some() {
+        return Some(r);
+    }
+    if let Some(method) = r.method.filter(|m| !notifications.contains(m)) {
+        notifications.push(method);
+    }
+    None
 }
 
 /// Parses JSON-RPC response from HTTP body. Supports both application/json (single
 /// JSON object) and text/event-stream (SSE: data lines with JSON-RPC messages).
-/// Returns the first JSON-RPC response (has result or error) found in the body.
+/// Returns the first JSON-RPC response (has result or error) found in the body; methods of
+/// notifications streamed before it are pushed to `notifications`.
 fn parse_json_rpc_from_body(
     body: &str,
     content_type: Option<&reqwest::header::HeaderValue>,
+    notifications: &mut Vec<String>,
 ) -> Result<JsonRpcResponse, ToolSourceError> {
     let is_sse = content_type
         .and_then(|v| v.to_str().ok())
@@ -56,10 +85,8 @@ fn parse_json_rpc_from_body(
             if let Some(data) = line.strip_prefix("data: ") {
                 if data == "[DONE]" || data.is_empty() {
                     if !data_buffer.is_empty() {
-                        if let Ok(r) = serde_json::from_str::<JsonRpcResponse>(&data_buffer) {
-                            if r.result.is_some() || r.error.is_some() {
-                                return Ok(r);
-                            }
+                        if let Some(r) = response_or_notification(&data_buffer, notifications) {
+                            return Ok(r);
                         }
                         data_buffer.clear();
                     }
@@ -71,27 +98,21 @@ fn parse_json_rpc_from_body(
                     data_buffer.push('\n');
                     data_buffer.push_str(data);
                 }
-                if let Ok(r) = serde_json::from_str::<JsonRpcResponse>(&data_buffer) {
-                    if r.result.is_some() || r.error.is_some() {
-                        return Ok(r);
-                    }
+                if let Some(r) = response_or_notification(&data_buffer, notifications) {
+                    return Ok(r);
                 }
             } else if line.trim().is_empty() {
                 if !data_buffer.is_empty() {
-                    if let Ok(r) = serde_json::from_str::<JsonRpcResponse>(&data_buffer) {
-                        if r.result.is_some() || r.error.is_some() {
-                            return Ok(r);
-                        }
+                    if let Some(r) = response_or_notification(&data_buffer, notifications) {
+                        return Ok(r);
                     }
                     data_buffer.clear();
                 }
             }
         }
         if !data_buffer.is_empty() {
-            if let Ok(r) = serde_json::from_str::<JsonRpcResponse>(&data_buffer) {
-                if r.result.is_some() || r.error.is_some() {
-                    return Ok(r);
-                }
+            if let Some(r) = response_or_notification(&data_buffer, notifications) {
+                return Ok(r);
             }
         }
         Err(ToolSourceError::Transport(
@@ -115,6 +136,8 @@ pub struct McpHttpSession {
     headers: Vec<(String, String)>,
     /// Session id from server MCP-Session-Id header; sent on subsequent requests.
     session_id: Mutex<Option<String>>,
+    /// Set when a response stream carried `notifications/tools/list_changed`.
+    tools_changed: AtomicBool,
 }
 
 impl McpHttpSession {
@@ -125,14 +148,26 @@ impl McpHttpSession {
     pub async fn new(
         url: impl Into<String>,
         headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Result<Self, ToolSourceError> {
+        Self::new_with_http_config(url, headers, &HttpClientConfig::default()).await
+    }
+
+    /// Like `new`, but builds the HTTP client from `http_config` (proxy, custom CA, pool
+    /// tuning). Requests keep the session's own 60s timeout.
+    pub async fn new_with_http_config(
+        url: impl Into<String>,
+        headers: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+        http_config: &HttpClientConfig,
     ) -> Result<Self, ToolSourceError> {
         let url = url.into();
         let headers: Vec<(String, String)> = headers
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
             .collect();
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
+        let client = http_config
+            .client_builder()
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?
+            .timeout(REQUEST_TIMEOUT)
             .build()
             .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
         let session_id = Mutex::new(None);
@@ -141,6 +176,7 @@ impl McpHttpSession {
             url: url.clone(),
             headers,
             session_id,
+            tools_changed: AtomicBool::new(false),
         };
         s.initialize().await?;
         Ok(s)
@@ -198,7 +234,7 @@ impl McpHttpSession {
             ToolSourceError::Transport(format!("initialize response body: {}", e))
         })?;
         let _: JsonRpcResponse =
-            parse_json_rpc_from_body(&text, content_type.as_ref())
+            parse_json_rpc_from_body(&text, content_type.as_ref(), &mut vec![])
                 .map_err(|e| ToolSourceError::Transport(format!("initialize {}", e)))?;
 
         let notification = NotificationMessage::new("notifications/initialized", Some(json!({})));
@@ -229,6 +265,16 @@ impl McpHttpSession {
         Ok(())
     }
 
+    /// Whether the server announced a change of its tools since the flag was last cleared.
+    pub fn tools_changed(&self) -> bool {
+        self.tools_changed.load(Ordering::SeqCst)
+    }
+
+    /// Clears the flag [`tools_changed`](Self::tools_changed) reports; call before re-listing.
+    pub fn clear_tools_changed(&self) {
+        self.tools_changed.store(false, Ordering::SeqCst);
+    }
+
     /// Sends a JSON-RPC request and returns the parsed result (one POST, one response).
     ///
     /// Used by McpToolSource for tools/list and tools/call. Response must be
@@ -272,9 +318,13 @@ impl McpHttpSession {
             .text()
             .await
             .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        let mut notifications = Vec::new();
         let json: JsonRpcResponse =
-            parse_json_rpc_from_body(&text, content_type.as_ref())
+            parse_json_rpc_from_body(&text, content_type.as_ref(), &mut notifications)
                 .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        if notifications.iter().any(|m| m == TOOLS_LIST_CHANGED) {
+            self.tools_changed.store(true, Ordering::SeqCst);
+        }
         let msg_id = json.id.unwrap_or_else(|| MessageId::from(id));
         if let Some(err) = json.error {
             let err_obj = ErrorObject::new(err.code as i32, err.message, None);
@@ -283,3 +333,26 @@ impl McpHttpSession {
         Ok(ResultMessage::success(msg_id, json.result.unwrap_or(Value::Null)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: A `tools/list_changed` notification streamed before the response is
+    /// reported, and the response is still returned.
+    #[test]
+    fn sse_body_reports_notifications_before_response() {
+        let body = concat!(
+            r#"data: {"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#,
+            "\n\n",
+            r#"data: {"jsonrpc":"2.0","id":"c1","result":{"content":[]}}"#,
+            "\n\n",
+        );
+        let content_type = reqwest::header::HeaderValue::from_static("text/event-stream");
+        let mut notifications = Vec::new();
+        let response =
+            parse_json_rpc_from_body(body, Some(&content_type), &mut notifications).unwrap();
+        assert!(response.result.is_some());
+        assert_eq!(notifications, [TOOLS_LIST_CHANGED]);
+    }
+}
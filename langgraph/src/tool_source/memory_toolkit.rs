@@ -0,0 +1,255 @@
+//! Configurable memory tools: one place to choose tool names, value shape and which tools exist.
+//!
+//! Replaces `StoreToolSource` (long-term tools only) and `MemoryToolsSource` (plus
+//! `get_recent_messages`), which differed only in the tool set. See
+//! `docs/rust-langgraph/tools-refactor/overview.md` §2.
+//!
+//! **Interaction**: Builds `RememberTool`, `RecallTool`, `SearchMemoriesTool`, `ListMemoriesTool`
+//! and `GetRecentMessagesTool` and registers them on an [`AggregateToolSource`]. Used by the ReAct
+//! builder (`build_tool_source`) when a store is configured.
+
+use std::sync::Arc;
+
+use crate::memory::{Namespace, Store};
+use crate::tools::{
+    AggregateToolSource, GetRecentMessagesTool, ListMemoriesTool, MemoryDedup, MemoryValueSchema,
+    RecallTool, RememberTool, SearchMemoriesTool, Tool, TOOL_LIST_MEMORIES, TOOL_RECALL,
+    TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
+};
+
+/// Memory tools over one store namespace.
+///
+/// Defaults match `StoreToolSource`: `remember`, `recall`, `search_memories` and `list_memories`
+/// with JSON values. Options:
+///
+/// - [`with_tool_prefix`](Self::with_tool_prefix): name the store tools `{prefix}remember`, ...
+///   so they do not clash with other tools (e.g. `"memory_"`).
+/// - [`with_value_schema`](Self::with_value_schema): any JSON (default) or plain-text facts.
+/// - [`with_dedup`](Self::with_dedup): update or skip facts similar to a stored one instead of
+///   adding near-duplicates.
+/// - [`with_semantic_search`](Self::with_semantic_search): set `false` to leave out
+///   `search_memories`, e.g. for stores without a vector index where search only filters.
+/// - [`with_recent_messages`](Self::with_recent_messages): also expose `get_recent_messages`
+///   (what `MemoryToolsSource` added).
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::memory::InMemoryStore;
+/// use langgraph::tool_source::MemoryToolkit;
+/// use langgraph::tools::MemoryValueSchema;
+/// use std::sync::Arc;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let store = Arc::new(InMemoryStore::new());
+/// let tools = MemoryToolkit::new(store, vec!["user-123".into(), "memories".into()])
+///     .with_tool_prefix("memory_")
+///     .with_value_schema(MemoryValueSchema::Text)
+///     .into_tool_source()
+///     .await;
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MemoryToolkit {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+    tool_prefix: String,
+    value_schema: MemoryValueSchema,
+    dedup: Option<MemoryDedup>,
+    semantic_search: bool,
+    recent_messages: bool,
+}
+
+impl MemoryToolkit {
+    /// Long-term memory tools over `namespace` (e.g. `[user_id, "memories"]`) of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self {
+            store,
+            namespace,
+            tool_prefix: String::new(),
+            value_schema: MemoryValueSchema::default(),
+            dedup: None,
+            semantic_search: true,
+            recent_messages: false,
+        }
+    }
+
+    /// Prepends `prefix` to the names of the store tools; `get_recent_messages` keeps its name.
+    pub fn with_tool_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.tool_prefix = prefix.into();
+        self
+    }
+
+    /// Sets the shape of stored values; see [`MemoryValueSchema`].
+    pub fn with_value_schema(mut self, value_schema: MemoryValueSchema) -> Self {
+        self.value_schema = value_schema;
+        self
+    }
+
+    /// Deduplicates `remember` writes by similarity; see [`MemoryDedup`].
+    pub fn with_dedup(mut self, dedup: MemoryDedup) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Whether to expose `search_memories` (default `true`).
+    pub fn with_semantic_search(mut self, enabled: bool) -> Self {
+        self.semantic_search = enabled;
+        self
+    }
+
+    /// Whether to expose `get_recent_messages` over the current conversation (default `false`).
+    pub fn with_recent_messages(mut self, enabled: bool) -> Self {
+        self.recent_messages = enabled;
+        self
+    }
+
+    /// Name under which the tool `base` (e.g. [`TOOL_REMEMBER`]) is exposed.
+    pub fn tool_name(&self, base: &str) -> String {
+        format!("{}{}", self.tool_prefix, base)
+    }
+
+    /// The configured tools, in the order remember, recall, search, list, recent messages.
+    pub fn tools(&self) -> Vec<Box<dyn Tool>> {
+        let store = &self.store;
+        let namespace = &self.namespace;
+        let mut remember = RememberTool::new(store.clone(), namespace.clone())
+            .with_name(self.tool_name(TOOL_REMEMBER))
+            .with_value_schema(self.value_schema);
+        if let Some(dedup) = self.dedup {
+            remember = remember.with_dedup(dedup);
+        }
+        let mut tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(remember),
+            Box::new(
+                RecallTool::new(store.clone(), namespace.clone())
+                    .with_name(self.tool_name(TOOL_RECALL))
+                    .with_value_schema(self.value_schema),
+            ),
+        ];
+        if self.semantic_search {
+            tools.push(Box::new(
+                SearchMemoriesTool::new(store.clone(), namespace.clone())
+                    .with_name(self.tool_name(TOOL_SEARCH_MEMORIES))
+                    .with_value_schema(self.value_schema),
+            ));
+        }
+        tools.push(Box::new(
+            ListMemoriesTool::new(store.clone(), namespace.clone())
+                .with_name(self.tool_name(TOOL_LIST_MEMORIES)),
+        ));
+        if self.recent_messages {
+            tools.push(Box::new(GetRecentMessagesTool::new()));
+        }
+        tools
+    }
+
+    /// Registers the tools on `aggregate`, next to its other tools.
+    pub async fn register(&self, aggregate: &AggregateToolSource) {
+        for tool in self.tools() {
+            aggregate.register_async(tool).await;
+        }
+    }
+
+    /// A tool source with only the memory tools; pass to `ActNode::new(Box::new(...))`.
+    pub async fn into_tool_source(self) -> AggregateToolSource {
+        let aggregate = AggregateToolSource::new();
+        self.register(&aggregate).await;
+        aggregate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use crate::tool_source::ToolSource;
+    use serde_json::json;
+
+    fn toolkit() -> MemoryToolkit {
+        MemoryToolkit::new(Arc::new(InMemoryStore::new()), vec!["u1".into()])
+    }
+
+    async fn tool_names(source: &AggregateToolSource) -> Vec<String> {
+        let mut names: Vec<String> = source
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// **Scenario**: The default toolkit exposes the four long-term tools; options add
+    /// get_recent_messages, drop search_memories and prefix the store tool names.
+    #[tokio::test]
+    async fn toolkit_options_select_and_name_tools() {
+        let source = toolkit().into_tool_source().await;
+        assert_eq!(
+            tool_names(&source).await,
+            vec!["list_memories", "recall", "remember", "search_memories"]
+        );
+
+        let source = toolkit()
+            .with_tool_prefix("memory_")
+            .with_semantic_search(false)
+            .with_recent_messages(true)
+            .into_tool_source()
+            .await;
+        assert_eq!(
+            tool_names(&source).await,
+            vec![
+                "get_recent_messages",
+                "memory_list_memories",
+                "memory_recall",
+                "memory_remember"
+            ]
+        );
+        let text = source
+            .call_tool("memory_remember", json!({"key": "k", "value": 1}))
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "ok");
+        assert!(source.call_tool("remember", json!({})).await.is_err());
+    }
+
+    /// **Scenario**: With the Text schema, remember generates a key and stores
+    /// {"text", "saved_at"}; recall returns the plain text and non-string values are rejected.
+    #[tokio::test]
+    async fn text_schema_stores_and_returns_plain_text() {
+        let store = Arc::new(InMemoryStore::new());
+        let namespace = vec!["u1".to_string()];
+        let source = MemoryToolkit::new(store.clone(), namespace.clone())
+            .with_value_schema(MemoryValueSchema::Text)
+            .into_tool_source()
+            .await;
+
+        let reply = source
+            .call_tool(TOOL_REMEMBER, json!({"value": "likes coffee"}))
+            .await
+            .unwrap()
+            .text;
+        let key = reply
+            .strip_prefix("ok (key: ")
+            .and_then(|r| r.strip_suffix(')'))
+            .expect("generated key reported");
+        let stored = store.get(&namespace, key).await.unwrap().unwrap();
+        assert_eq!(stored["text"], "likes coffee");
+        assert!(stored["saved_at"].is_u64());
+
+        let recalled = source
+            .call_tool(TOOL_RECALL, json!({"key": key}))
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(recalled, "likes coffee");
+
+        assert!(source
+            .call_tool(TOOL_REMEMBER, json!({"key": "n", "value": 3}))
+            .await
+            .is_err());
+    }
+}
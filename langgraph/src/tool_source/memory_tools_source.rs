@@ -1,7 +1,6 @@
 //! Composite tool source: long-term (Store) + short-term (get_recent_messages) in one.
 //!
-//! Uses AggregateToolSource internally to combine memory and conversation tools.
-//! Forwards `set_call_context` to the underlying source for get_recent_messages.
+//! Deprecated: same as [`MemoryToolkit`](super::MemoryToolkit) with `with_recent_messages(true)`.
 //! See `docs/rust-langgraph/tools-refactor/overview.md` §7.5.
 
 use std::sync::Arc;
@@ -9,11 +8,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::memory::{Namespace, Store};
-use crate::tool_source::{ToolSource, ToolSourceError};
-use crate::tools::{
-    AggregateToolSource, GetRecentMessagesTool, ListMemoriesTool, RecallTool, RememberTool,
-    SearchMemoriesTool,
-};
+use crate::tool_source::{MemoryToolkit, ToolSource, ToolSourceError};
+use crate::tools::AggregateToolSource;
 
 /// Composite tool source that exposes both long-term (Store) and short-term (recent messages) memory tools.
 ///
@@ -23,10 +19,15 @@ use crate::tools::{
 ///
 /// **Interaction**: Use with `ActNode::new(Box::new(MemoryToolsSource::new(store, namespace)))`
 /// when you want both remember/recall/search_memories/list_memories and get_recent_messages.
+#[deprecated(
+    since = "0.1.0",
+    note = "use `MemoryToolkit::new(store, namespace).with_recent_messages(true).into_tool_source()`"
+)]
 pub struct MemoryToolsSource {
     _source: AggregateToolSource,
 }
 
+#[allow(deprecated)]
 impl MemoryToolsSource {
     /// Creates a composite with both long-term (store + namespace) and short-term memory tools.
     ///
@@ -41,6 +42,7 @@ impl MemoryToolsSource {
     /// # Examples
     ///
     /// ```no_run
+    /// # #![allow(deprecated)]
     /// use langgraph::tool_source::MemoryToolsSource;
     /// use langgraph::memory::{InMemoryStore, Namespace};
     /// use std::sync::Arc;
@@ -53,24 +55,14 @@ impl MemoryToolsSource {
     /// ```
     #[allow(clippy::new_ret_no_self)]
     pub async fn new(store: Arc<dyn Store>, namespace: Namespace) -> AggregateToolSource {
-        let source = AggregateToolSource::new();
-
-        let remember = RememberTool::new(store.clone(), namespace.clone());
-        let recall = RecallTool::new(store.clone(), namespace.clone());
-        let search = SearchMemoriesTool::new(store.clone(), namespace.clone());
-        let list = ListMemoriesTool::new(store, namespace);
-        let get_recent = GetRecentMessagesTool::new();
-
-        source.register_async(Box::new(remember)).await;
-        source.register_async(Box::new(recall)).await;
-        source.register_async(Box::new(search)).await;
-        source.register_async(Box::new(list)).await;
-        source.register_async(Box::new(get_recent)).await;
-
-        source
+        MemoryToolkit::new(store, namespace)
+            .with_recent_messages(true)
+            .into_tool_source()
+            .await
     }
 }
 
+#[allow(deprecated)]
 #[async_trait]
 impl ToolSource for MemoryToolsSource {
     async fn list_tools(&self) -> Result<Vec<crate::tool_source::ToolSpec>, ToolSourceError> {
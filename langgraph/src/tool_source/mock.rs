@@ -10,6 +10,9 @@ use super::{ToolCallContent, ToolSource, ToolSourceError, ToolSpec};
 
 /// Mock tool source: fixed tool list and fixed call result.
 ///
+/// For a real `get_time` tool, register [`TimeTool`](crate::tools::TimeTool) (with a
+/// [`FixedClock`](crate::tools::FixedClock) when results must be deterministic).
+///
 /// `list_tools()` returns a configurable list; `call_tool(name, _)` returns
 /// a configurable text (same for all tools by default). Used by ActNode tests
 /// and ReAct linear-chain example.
@@ -2,16 +2,25 @@
 //!
 //! Design: docs/rust-langgraph/mcp-integration/implementation.md.
 //! ReAct/Agent depends on `ToolSource` instead of a concrete tool registry;
-//! implementations include `MockToolSource` (tests), `StoreToolSource`, `ShortTermMemoryToolSource`, `WebToolsSource`, and `McpToolSource` (feature mcp).
+//! implementations include `MockToolSource` (tests), `MemoryToolkit`, `ShortTermMemoryToolSource`, `WebToolsSource`, and `McpToolSource` (feature mcp).
 //!
 //! ## Memory tools (docs/rust-langgraph/tools-refactor/overview.md)
 //!
-//! - **StoreToolSource**: long-term memory as tools (`remember`, `recall`, `search_memories`, `list_memories`).
-//!   Use with `Arc<dyn Store>` and a fixed namespace; pass to `ActNode::new(Box::new(store_tools))`.
+//! - **MemoryToolkit**: long-term memory as tools (`remember`, `recall`, `search_memories`, `list_memories`)
+//!   over `Arc<dyn Store>` and a fixed namespace, with optional tool-name prefix, plain-text values,
+//!   no `search_memories`, or an extra `get_recent_messages`. Build with
+//!   `MemoryToolkit::new(store, namespace).into_tool_source().await` and pass to `ActNode::new(Box::new(...))`.
 //! - **ShortTermMemoryToolSource**: one optional tool `get_recent_messages` (current conversation).
 //!   Use only when you need to explicitly re-read or summarize last N messages; most flows can omit it.
 //!   ActNode passes `ToolCallContext` via `call_tool_with_context` so this tool receives `state.messages`.
-//! - **MemoryToolsSource**: composite of both. Use `MemoryToolsSource::new(store, namespace)` and pass to `ActNode::new(Box::new(memory_tools))` for one-line setup.
+//! - **StoreToolSource**, **MemoryToolsSource** (deprecated): the default toolkit, and the toolkit
+//!   `with_recent_messages(true)`.
+//!
+//! ## Tool state
+//!
+//! - **ToolStateStore**: durable per-thread scratch state for stateful tools, scoped by
+//!   (user_id, thread_id, tool name). ActNode passes a [`ToolState`] handle in
+//!   `ToolCallContext::tool_state`.
 //!
 //! ## Web tools
 //!
@@ -19,28 +28,72 @@
 //!   Use `WebToolsSource::new()` to enable HTTP GET/POST capabilities; pass to `ActNode::new(Box::new(web_tools))`.
 //! - **BashToolsSource**: shell command execution as tool (`bash`).
 //!   Use `BashToolsSource::new()` to enable running shell commands; pass to `ActNode::new(Box::new(bash_tools))`.
+//! - **FsToolSource**: `read_file`, `write_file`, `list_dir` and `glob` confined to one root
+//!   directory, with size limits and an optional read-only mode.
+//! - **NotifyToolSource**: out-of-band delivery (`send_webhook`, `slack_message`, `send_email` with
+//!   feature `email`) to destinations configured by the deployment.
+//!
+//! ## Runtime tool switches
+//!
+//! - **AllowlistToolSource**: wraps any tool source and exposes only the tools enabled in a shared
+//!   [`ToolAllowlist`], which can be changed while the agent is running.
+//! - **RefreshingToolSource**: wraps any tool source and, when an MCP server announces changed
+//!   tools, re-lists them into a shared [`ToolCatalog`] the LLM client offers from.
+//!
+//! ## Documentation
+//!
+//! - **render_tool_docs**: a markdown or HTML reference of a tool set (arguments, example call,
+//!   input schema), e.g. of everything `list_tools` returns for a configured agent.
+//!
+//! ## Caching
+//!
+//! - **CachedToolSource**: wraps any tool source and reuses results of identical calls (same tool,
+//!   same arguments, same user and thread) to the tools it is told to cache until a TTL expires,
+//!   e.g. for `web_fetcher` in multi-turn loops.
 
+mod allowlist_tool_source;
 mod bash_tools_source;
+mod cached_tool_source;
 mod context;
+mod docs;
+mod fs_tool_source;
+mod memory_toolkit;
 mod memory_tools_source;
 mod mock;
+mod notify_tool_source;
+mod refreshing_tool_source;
 mod short_term_memory_tool_source;
 mod store_tool_source;
+mod tool_state;
 mod web_tools_source;
 
 mod mcp;
 
+pub use allowlist_tool_source::{AllowlistToolSource, ToolAllowlist};
 pub use bash_tools_source::{BashToolsSource, TOOL_BASH};
-pub use context::ToolCallContext;
+pub use cached_tool_source::CachedToolSource;
+pub use context::{ToolCallContext, UiEventSink};
+pub use docs::{example_arguments, render_tool_docs, ToolDocsFormat};
+pub use fs_tool_source::FsToolSource;
+pub use memory_toolkit::MemoryToolkit;
+#[allow(deprecated)]
 pub use memory_tools_source::MemoryToolsSource;
 pub use mock::MockToolSource;
+pub use notify_tool_source::NotifyToolSource;
+pub use refreshing_tool_source::{RefreshingToolSource, ToolCatalog};
 pub use short_term_memory_tool_source::{ShortTermMemoryToolSource, TOOL_GET_RECENT_MESSAGES};
+#[allow(deprecated)]
 pub use store_tool_source::{
     StoreToolSource, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
 };
+pub use tool_state::{ToolState, ToolStateStore, DEFAULT_TOOL_STATE_USER, TOOL_STATE_NAMESPACE};
 pub use web_tools_source::{WebToolsSource, TOOL_WEB_FETCHER};
 
-pub use mcp::{McpSession, McpSessionError, McpToolSource};
+pub(crate) use mcp::merge_server;
+pub use mcp::{
+    McpConfigError, McpServerConfig, McpSession, McpSessionError, McpToolSource, McpTransport,
+    McpTransportKind,
+};
 
 use async_trait::async_trait;
 use serde_json::Value;
@@ -173,4 +226,16 @@ pub trait ToolSource: Send + Sync {
     /// ActNode calls this before executing tool_calls; implementations that need
     /// context (e.g. ShortTermMemoryToolSource) override; others use this default no-op.
     fn set_call_context(&self, _ctx: Option<ToolCallContext>) {}
+
+    /// Whether the tools this source offers changed since they were last listed (e.g. an MCP
+    /// server sent `notifications/tools/list_changed`). Default: never.
+    fn tools_changed(&self) -> bool {
+        false
+    }
+
+    /// Re-lists tools on demand, picking up tools a server added or removed at runtime, and
+    /// clears [`tools_changed`](Self::tools_changed). Default: [`list_tools`](Self::list_tools).
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.list_tools().await
+    }
 }
@@ -0,0 +1,149 @@
+//! Notification tools source: `send_webhook`, `slack_message` and `send_email` (feature `email`).
+//!
+//! Lets assistant agents deliver results out-of-band. The deployment decides where messages can
+//! go (named webhooks, Slack channels, email recipient allowlist); a tool is only exposed when
+//! at least one destination is configured for it.
+//!
+//! **Interaction**: Builds the tools in [`crate::tools::notify`] and registers them on an
+//! [`AggregateToolSource`].
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "email")]
+use crate::tools::notify::{SendEmailTool, SmtpConfig};
+use crate::tools::notify::{SendWebhookTool, SlackMessageTool};
+use crate::tools::{AggregateToolSource, Tool};
+
+/// Notification tools over the destinations configured for this deployment.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tool_source::NotifyToolSource;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let tools = NotifyToolSource::new()
+///     .with_webhook("crm", "https://crm.example.com/hooks/agent")
+///     .with_slack_channel("#alerts", "https://hooks.slack.com/services/T000/B000/XXXX")
+///     .into_tool_source()
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct NotifyToolSource {
+    client: reqwest::Client,
+    webhooks: BTreeMap<String, String>,
+    slack_channels: BTreeMap<String, String>,
+    #[cfg(feature = "email")]
+    email: Option<(SmtpConfig, Vec<String>)>,
+}
+
+impl NotifyToolSource {
+    /// No destinations; add them with the `with_*` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the HTTP client used for webhooks and Slack (timeouts, proxies).
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Lets `send_webhook` post to `url` under `name`.
+    pub fn with_webhook(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.webhooks.insert(name.into(), url.into());
+        self
+    }
+
+    /// Lets `slack_message` post to `channel` through its incoming webhook `webhook_url`.
+    pub fn with_slack_channel(
+        mut self,
+        channel: impl Into<String>,
+        webhook_url: impl Into<String>,
+    ) -> Self {
+        self.slack_channels
+            .insert(channel.into(), webhook_url.into());
+        self
+    }
+
+    /// Exposes `send_email` through `smtp`, to `allowed_recipients` only (addresses or
+    /// `@domain` entries).
+    #[cfg(feature = "email")]
+    pub fn with_email<I, S>(mut self, smtp: SmtpConfig, allowed_recipients: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let recipients = allowed_recipients.into_iter().map(Into::into).collect();
+        self.email = Some((smtp, recipients));
+        self
+    }
+
+    /// The tools with at least one destination, in the order webhook, Slack, email.
+    pub fn tools(&self) -> Vec<Box<dyn Tool>> {
+        let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+        if !self.webhooks.is_empty() {
+            tools.push(Box::new(SendWebhookTool::new(
+                self.client.clone(),
+                self.webhooks.clone(),
+            )));
+        }
+        if !self.slack_channels.is_empty() {
+            tools.push(Box::new(SlackMessageTool::new(
+                self.client.clone(),
+                self.slack_channels.clone(),
+            )));
+        }
+        #[cfg(feature = "email")]
+        if let Some((smtp, recipients)) = &self.email {
+            if !recipients.is_empty() {
+                tools.push(Box::new(SendEmailTool::new(
+                    smtp.clone(),
+                    recipients.clone(),
+                )));
+            }
+        }
+        tools
+    }
+
+    /// Registers the tools on `aggregate`, next to its other tools.
+    pub async fn register(&self, aggregate: &AggregateToolSource) {
+        for tool in self.tools() {
+            aggregate.register_async(tool).await;
+        }
+    }
+
+    /// A tool source with only the notification tools; pass to `ActNode::new(Box::new(...))`.
+    pub async fn into_tool_source(self) -> AggregateToolSource {
+        let aggregate = AggregateToolSource::new();
+        self.register(&aggregate).await;
+        aggregate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::ToolSource;
+
+    /// **Scenario**: Only tools with a configured destination are exposed.
+    #[tokio::test]
+    async fn exposes_tools_with_destinations_only() {
+        let source = NotifyToolSource::new().into_tool_source().await;
+        assert!(source.list_tools().await.unwrap().is_empty());
+
+        let source = NotifyToolSource::new()
+            .with_slack_channel("#alerts", "http://127.0.0.1:9/hook")
+            .into_tool_source()
+            .await;
+        let names: Vec<String> = source
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["slack_message"]);
+    }
+}
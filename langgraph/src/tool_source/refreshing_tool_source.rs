@@ -0,0 +1,206 @@
+//! Tool list that follows tools added or removed at runtime.
+//!
+//! A [`ToolCatalog`] is a shared, replaceable list of tool specs; clones see the same list.
+//! [`RefreshingToolSource`] checks [`ToolSource::tools_changed`] of the source it wraps after
+//! every tool call and listing; when an MCP server announced new tools, it re-lists them with
+//! [`ToolSource::refresh_tools`] and puts the new list into its catalog.
+//!
+//! **Interaction**: Wraps the tool source given to ActNode; pass the same catalog to
+//! `ChatOpenAI::with_tool_catalog` so the model is offered the tools a server registered after
+//! startup.
+
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError, ToolSpec};
+
+/// Shared list of tool specs; empty (`None`) until first set.
+#[derive(Debug, Clone, Default)]
+pub struct ToolCatalog(Arc<RwLock<Option<Vec<ToolSpec>>>>);
+
+impl ToolCatalog {
+    /// Catalog with no list yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the list.
+    pub fn set(&self, tools: Vec<ToolSpec>) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(tools);
+    }
+
+    /// The current list; `None` when it was never set.
+    pub fn get(&self) -> Option<Vec<ToolSpec>> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Tool source that re-lists its tools when they changed and publishes them to a
+/// [`ToolCatalog`].
+pub struct RefreshingToolSource {
+    inner: Box<dyn ToolSource>,
+    catalog: ToolCatalog,
+}
+
+impl RefreshingToolSource {
+    /// Wraps `inner`; refreshed tool lists go to `catalog`.
+    pub fn new(inner: Box<dyn ToolSource>, catalog: ToolCatalog) -> Self {
+        Self { inner, catalog }
+    }
+
+    /// Re-lists when the inner source reports a change; a failure is logged.
+    async fn refresh_if_changed(&self) {
+        if !self.inner.tools_changed() {
+            return;
+        }
+        match self.inner.refresh_tools().await {
+            Ok(tools) => {
+                tracing::info!(tools = tools.len(), "tool list changed");
+                self.catalog.set(tools);
+            }
+            Err(e) => tracing::warn!("refreshing changed tools failed: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolSource for RefreshingToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        self.refresh_if_changed().await;
+        self.inner.list_tools().await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let result = self.inner.call_tool(name, arguments).await;
+        self.refresh_if_changed().await;
+        result
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let result = self
+            .inner
+            .call_tool_with_context(name, arguments, ctx)
+            .await;
+        self.refresh_if_changed().await;
+        result
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        self.inner.set_call_context(ctx)
+    }
+
+    fn tools_changed(&self) -> bool {
+        self.inner.tools_changed()
+    }
+
+    async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        let tools = self.inner.refresh_tools().await?;
+        self.catalog.set(tools.clone());
+        Ok(tools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    /// Source whose tool list can grow, flagging the change like an MCP server's
+    /// `tools/list_changed`.
+    #[derive(Default)]
+    struct GrowingSource {
+        names: Mutex<Vec<&'static str>>,
+        changed: AtomicBool,
+    }
+
+    impl GrowingSource {
+        fn add(&self, name: &'static str) {
+            self.names.lock().unwrap().push(name);
+            self.changed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl ToolSource for Arc<GrowingSource> {
+        async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            let names = self.names.lock().unwrap().clone();
+            Ok(names
+                .into_iter()
+                .map(|name| ToolSpec {
+                    name: name.to_string(),
+                    description: None,
+                    input_schema: json!({}),
+                })
+                .collect())
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            _arguments: Value,
+        ) -> Result<ToolCallContent, ToolSourceError> {
+            if name == "register" {
+                self.add("search");
+            }
+            Ok(ToolCallContent {
+                text: "ok".to_string(),
+            })
+        }
+
+        fn tools_changed(&self) -> bool {
+            self.changed.load(Ordering::SeqCst)
+        }
+
+        async fn refresh_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+            self.changed.store(false, Ordering::SeqCst);
+            self.list_tools().await
+        }
+    }
+
+    fn names(tools: &[ToolSpec]) -> Vec<&str> {
+        tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    /// **Scenario**: A call after which the server announces a new tool updates the catalog;
+    /// calls without a change leave it alone.
+    #[tokio::test]
+    async fn changed_tools_are_published_after_a_call() {
+        let inner = Arc::new(GrowingSource::default());
+        inner.names.lock().unwrap().push("register");
+        let catalog = ToolCatalog::new();
+        let source = RefreshingToolSource::new(Box::new(Arc::clone(&inner)), catalog.clone());
+
+        source.call_tool("noop", json!({})).await.unwrap();
+        assert!(catalog.get().is_none());
+
+        source.call_tool("register", json!({})).await.unwrap();
+        assert_eq!(names(&catalog.get().unwrap()), ["register", "search"]);
+        assert!(!source.tools_changed());
+    }
+
+    /// **Scenario**: refresh_tools re-lists on demand and publishes the list.
+    #[tokio::test]
+    async fn refresh_on_demand_publishes_list() {
+        let inner = Arc::new(GrowingSource::default());
+        let catalog = ToolCatalog::new();
+        let source = RefreshingToolSource::new(Box::new(Arc::clone(&inner)), catalog.clone());
+        inner.names.lock().unwrap().push("get_time");
+
+        let tools = source.refresh_tools().await.unwrap();
+        assert_eq!(names(&tools), ["get_time"]);
+        assert_eq!(names(&catalog.get().unwrap()), ["get_time"]);
+    }
+}
@@ -1,17 +1,15 @@
 //! Store-backed tool source: long-term memory as tools (remember, recall, search_memories, list_memories).
 //!
-//! Wraps `Store` with a fixed namespace and exposes put/get/list/search as tools for the LLM.
-//! Uses AggregateToolSource internally to register memory tools. See `docs/rust-langgraph/tools-refactor/overview.md` §2.
+//! Deprecated: [`MemoryToolkit`](super::MemoryToolkit) builds the same tools and adds naming and
+//! value-shape options. The `TOOL_*` name constants stay here.
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
 
 use crate::memory::{Namespace, Store};
-use crate::tool_source::{ToolSource, ToolSourceError};
-use crate::tools::{
-    AggregateToolSource, ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool,
-};
+use crate::tool_source::{MemoryToolkit, ToolSource, ToolSourceError};
+use crate::tools::AggregateToolSource;
 
 /// Tool name: write a key-value pair to long-term memory.
 pub const TOOL_REMEMBER: &str = "remember";
@@ -27,10 +25,15 @@ pub const TOOL_LIST_MEMORIES: &str = "list_memories";
 /// Holds `Arc<dyn Store>` and a fixed namespace (e.g. `[user_id, "memories"]`). Uses AggregateToolSource
 /// internally to register memory tools. Use with ActNode or composite ToolSource for long-term memory.
 /// See `docs/rust-langgraph/tools-refactor/overview.md` §2.
+#[deprecated(
+    since = "0.1.0",
+    note = "use `MemoryToolkit::new(store, namespace).into_tool_source()`"
+)]
 pub struct StoreToolSource {
     _source: AggregateToolSource,
 }
 
+#[allow(deprecated)]
 impl StoreToolSource {
     /// Creates a store tool source with the given store and namespace.
     ///
@@ -45,6 +48,7 @@ impl StoreToolSource {
     /// # Examples
     ///
     /// ```no_run
+    /// # #![allow(deprecated)]
     /// use langgraph::tool_source::StoreToolSource;
     /// use langgraph::memory::{InMemoryStore, Namespace};
     /// use std::sync::Arc;
@@ -57,22 +61,13 @@ impl StoreToolSource {
     /// ```
     #[allow(clippy::new_ret_no_self)]
     pub async fn new(store: Arc<dyn Store>, namespace: Namespace) -> AggregateToolSource {
-        let source = AggregateToolSource::new();
-
-        let remember = RememberTool::new(store.clone(), namespace.clone());
-        let recall = RecallTool::new(store.clone(), namespace.clone());
-        let search = SearchMemoriesTool::new(store.clone(), namespace.clone());
-        let list = ListMemoriesTool::new(store, namespace);
-
-        source.register_sync(Box::new(remember));
-        source.register_sync(Box::new(recall));
-        source.register_sync(Box::new(search));
-        source.register_sync(Box::new(list));
-
-        source
+        MemoryToolkit::new(store, namespace)
+            .into_tool_source()
+            .await
     }
 }
 
+#[allow(deprecated)]
 #[async_trait]
 impl ToolSource for StoreToolSource {
     async fn list_tools(&self) -> Result<Vec<crate::tool_source::ToolSpec>, ToolSourceError> {
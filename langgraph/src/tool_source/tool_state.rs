@@ -0,0 +1,185 @@
+//! Per-thread tool state: durable scratch data scoped by (user_id, thread_id, tool name).
+//!
+//! Some tools must remember things across turns of one conversation (a browser session id,
+//! a pagination cursor). [`ToolStateStore`] wraps a [`Store`] and hands out [`ToolState`]
+//! handles whose namespace is `["tool_state", user_id, thread_id, tool_name]`, so a tool only
+//! sees its own keys for the current user and thread, also when users reuse a thread id.
+//!
+//! The backing store should be a plain key-value store (`InMemoryStore`, `SqliteStore`), kept
+//! apart from the long-term memory store: a vector store would embed every put.
+//!
+//! **Interaction**: ActNode holds a `ToolStateStore` (in-memory by default) and sets
+//! [`ToolCallContext::tool_state`](super::ToolCallContext) for each call; tools read it via
+//! `ctx.tool_state`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::memory::{InMemoryStore, Namespace, Store, StoreError};
+
+/// First namespace segment for all tool state items.
+pub const TOOL_STATE_NAMESPACE: &str = "tool_state";
+/// User namespace segment for runs without a `user_id`.
+pub const DEFAULT_TOOL_STATE_USER: &str = "default";
+
+/// Factory for [`ToolState`] handles backed by a [`Store`].
+///
+/// `thread_id` is the default thread used when the run provides none (e.g. `invoke` without
+/// a `RunContext`); without any thread id no state is handed out.
+#[derive(Clone)]
+pub struct ToolStateStore {
+    store: Arc<dyn Store>,
+    thread_id: Option<String>,
+}
+
+impl ToolStateStore {
+    /// Creates a tool state store on top of `store`.
+    pub fn new(store: Arc<dyn Store>) -> Self {
+        Self {
+            store,
+            thread_id: None,
+        }
+    }
+
+    /// Tool state kept in a fresh [`InMemoryStore`]; lost when the process exits.
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(InMemoryStore::new()))
+    }
+
+    /// Sets the thread used when the run does not supply one.
+    pub fn with_thread_id(mut self, thread_id: Option<String>) -> Self {
+        self.thread_id = thread_id;
+        self
+    }
+
+    /// Returns the state handle for `tool_name` of `user_id` in `thread_id` (or the default
+    /// thread). `None` when no thread id is known.
+    pub fn for_tool(
+        &self,
+        user_id: Option<&str>,
+        thread_id: Option<&str>,
+        tool_name: &str,
+    ) -> Option<ToolState> {
+        let thread_id = thread_id.or(self.thread_id.as_deref())?;
+        Some(ToolState {
+            store: Arc::clone(&self.store),
+            namespace: vec![
+                TOOL_STATE_NAMESPACE.to_string(),
+                user_id.unwrap_or(DEFAULT_TOOL_STATE_USER).to_string(),
+                thread_id.to_string(),
+                tool_name.to_string(),
+            ],
+        })
+    }
+}
+
+impl Default for ToolStateStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl fmt::Debug for ToolStateStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolStateStore")
+            .field("thread_id", &self.thread_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Key-value state of one tool in one thread.
+#[derive(Clone)]
+pub struct ToolState {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl ToolState {
+    /// Namespace used for this tool's keys: `["tool_state", user_id, thread_id, tool_name]`.
+    pub fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    /// Returns the value stored under `key`, if any.
+    pub async fn get(&self, key: &str) -> Result<Option<serde_json::Value>, StoreError> {
+        self.store.get(&self.namespace, key).await
+    }
+
+    /// Stores `value` under `key`, replacing any previous value.
+    pub async fn put(&self, key: &str, value: &serde_json::Value) -> Result<(), StoreError> {
+        self.store.put(&self.namespace, key, value).await
+    }
+
+    /// Removes `key`; succeeds when it does not exist.
+    pub async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.store.delete(&self.namespace, key).await
+    }
+
+    /// Lists all keys of this tool in this thread.
+    pub async fn keys(&self) -> Result<Vec<String>, StoreError> {
+        self.store.list(&self.namespace).await
+    }
+
+    /// Removes all keys of this tool in this thread.
+    pub async fn clear(&self) -> Result<(), StoreError> {
+        for key in self.keys().await? {
+            self.delete(&key).await?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ToolState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolState")
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use serde_json::json;
+
+    /// **Scenario**: State is isolated per user, per thread and per tool.
+    #[tokio::test]
+    async fn tool_state_is_scoped_by_user_thread_and_tool() {
+        let store = ToolStateStore::in_memory();
+        let a = store.for_tool(Some("u1"), Some("t1"), "browser").unwrap();
+        let b = store.for_tool(Some("u1"), Some("t2"), "browser").unwrap();
+        let c = store.for_tool(Some("u1"), Some("t1"), "search").unwrap();
+        let d = store.for_tool(Some("u2"), Some("t1"), "browser").unwrap();
+        a.put("session", &json!("s-1")).await.unwrap();
+        assert_eq!(a.get("session").await.unwrap(), Some(json!("s-1")));
+        assert_eq!(b.get("session").await.unwrap(), None);
+        assert_eq!(c.get("session").await.unwrap(), None);
+        assert_eq!(d.get("session").await.unwrap(), None);
+        assert_eq!(
+            a.namespace(),
+            &vec![
+                "tool_state".to_string(),
+                "u1".into(),
+                "t1".into(),
+                "browser".into()
+            ]
+        );
+        let anonymous = store.for_tool(None, Some("t1"), "browser").unwrap();
+        assert_eq!(anonymous.namespace()[1], DEFAULT_TOOL_STATE_USER);
+    }
+
+    /// **Scenario**: Default thread is used when the run has none; no thread means no state.
+    #[tokio::test]
+    async fn tool_state_default_thread_and_clear() {
+        let store = ToolStateStore::new(Arc::new(InMemoryStore::new()));
+        assert!(store.for_tool(None, None, "browser").is_none());
+        let store = store.with_thread_id(Some("t1".into()));
+        let s = store.for_tool(None, None, "browser").unwrap();
+        s.put("cursor", &json!(2)).await.unwrap();
+        s.put("page", &json!(1)).await.unwrap();
+        assert_eq!(s.keys().await.unwrap().len(), 2);
+        s.clear().await.unwrap();
+        assert!(s.keys().await.unwrap().is_empty());
+    }
+}
@@ -3,6 +3,8 @@ use async_trait::async_trait;
 use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSource, ToolSourceError};
 use crate::tools::{Tool, ToolRegistryLocked};
 
+use super::mcp_adapter::{refresh_mcp_registration, McpRegistration};
+
 /// Aggregates multiple tools and implements ToolSource trait via ToolRegistry.
 ///
 /// This is the bridge between the new Tool-based architecture and the existing
@@ -41,9 +43,13 @@ use crate::tools::{Tool, ToolRegistryLocked};
 /// - **Tool**: Individual tools are registered here
 /// - **ToolSource**: Implements this trait for use with ActNode and ThinkNode
 /// - **ToolCallContext**: Context is passed through call_tool_with_context
+/// - **McpToolSource**: Servers added with `register_mcp_tools` are re-listed by
+///   `refresh_tools`, which replaces their adapters
 pub struct AggregateToolSource {
     registry: ToolRegistryLocked,
     context: std::sync::Arc<std::sync::RwLock<Option<crate::tool_source::ToolCallContext>>>,
+    /// MCP servers whose tools are registered here, for `refresh_tools`.
+    mcp: tokio::sync::Mutex<Vec<McpRegistration>>,
 }
 
 impl AggregateToolSource {
@@ -66,6 +72,7 @@ impl AggregateToolSource {
         Self {
             registry: ToolRegistryLocked::new(),
             context: std::sync::Arc::new(std::sync::RwLock::new(None)),
+            mcp: tokio::sync::Mutex::new(Vec::new()),
         }
     }
 
@@ -112,6 +119,16 @@ impl AggregateToolSource {
     pub fn register_sync(&self, tool: Box<dyn Tool>) {
         self.registry.register_sync(tool);
     }
+
+    /// Removes the tool named `name`; returns whether it was registered.
+    pub async fn unregister_async(&self, name: &str) -> bool {
+        self.registry.unregister_async(name).await
+    }
+
+    /// Remembers an MCP server whose tools were registered, so `refresh_tools` re-lists it.
+    pub(crate) async fn track_mcp(&self, registration: McpRegistration) {
+        self.mcp.lock().await.push(registration);
+    }
 }
 
 impl Default for AggregateToolSource {
@@ -198,7 +215,8 @@ impl ToolSource for AggregateToolSource {
     /// # Interaction
     ///
     /// - Called by ActNode with ToolCallContext before executing tool calls
-    /// - Context is set via set_call_context() before each round
+    /// - Context is set via set_call_context() before each round; an explicit `ctx` is only
+    ///   passed to the tool, never stored, since calls of one round run concurrently
     /// - Delegates to ToolRegistryLocked::call() with context
     async fn call_tool_with_context(
         &self,
@@ -206,10 +224,7 @@ impl ToolSource for AggregateToolSource {
         arguments: serde_json::Value,
         ctx: Option<&ToolCallContext>,
     ) -> Result<ToolCallContent, ToolSourceError> {
-        if let Some(c) = ctx {
-            if let Ok(mut g) = self.context.write() {
-                *g = Some(c.clone());
-            }
+        if ctx.is_some() {
             self.registry.call(name, arguments, ctx).await
         } else {
             let effective_ctx: Option<ToolCallContext> =
@@ -224,4 +239,23 @@ impl ToolSource for AggregateToolSource {
             *g = ctx;
         }
     }
+
+    /// Whether one of the registered MCP servers announced a change of its tools.
+    fn tools_changed(&self) -> bool {
+        self.mcp
+            .try_lock()
+            .map(|servers| servers.iter().any(|s| s.source.tools_changed()))
+            .unwrap_or(false)
+    }
+
+    /// Re-lists the tools of every registered MCP server, registering new tools and removing
+    /// the ones a server dropped, then lists all tools.
+    async fn refresh_tools(&self) -> Result<Vec<crate::tool_source::ToolSpec>, ToolSourceError> {
+        let mut servers = self.mcp.lock().await;
+        for server in servers.iter_mut() {
+            refresh_mcp_registration(self, server).await?;
+        }
+        drop(servers);
+        self.list_tools().await
+    }
 }
@@ -0,0 +1,143 @@
+//! `fetch_attachment`: reads a tool output stored as an attachment, in slices.
+//!
+//! **Interaction**: Reads values written by `ToolAttachments` (ObserveNode stores large tool
+//! outputs in the Store and leaves a preview naming the attachment in the Tool message).
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::memory::{Namespace, Store};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::Tool;
+
+/// Tool name for reading attachments.
+pub const TOOL_FETCH_ATTACHMENT: &str = "fetch_attachment";
+
+/// Characters returned per call when the model gives no `limit`.
+pub const DEFAULT_FETCH_LIMIT: usize = 4000;
+
+/// Tool returning part of an attachment: `limit` characters from `offset` (default the first
+/// [`DEFAULT_FETCH_LIMIT`]), with a note on where to continue when more remains.
+///
+/// Attachments are values `{"tool", "call_id", "content"}` under `namespace`, keyed by
+/// attachment id.
+pub struct FetchAttachmentTool {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl FetchAttachmentTool {
+    /// Tool over the attachments in `namespace` of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self { store, namespace }
+    }
+}
+
+#[async_trait]
+impl Tool for FetchAttachmentTool {
+    fn name(&self) -> &str {
+        TOOL_FETCH_ATTACHMENT
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_FETCH_ATTACHMENT.to_string(),
+            description: Some(
+                "Read a tool output that was too long to show in full. Call with the attachment \
+                 id named in the shortened output; use offset to continue reading."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Attachment id" },
+                    "offset": { "type": "integer", "description": "First character to return (default 0)" },
+                    "limit": { "type": "integer", "description": "Characters to return (default 4000)" }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing id".to_string()))?;
+        let offset = args.get("offset").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|l| (l as usize).max(1))
+            .unwrap_or(DEFAULT_FETCH_LIMIT);
+
+        let value = self
+            .store
+            .get(&self.namespace, id)
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?
+            .ok_or_else(|| ToolSourceError::NotFound(format!("attachment {} not found", id)))?;
+        let content = value
+            .get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+
+        let total = content.chars().count();
+        let end = offset.saturating_add(limit).min(total);
+        let mut text: String = content
+            .chars()
+            .skip(offset)
+            .take(end.saturating_sub(offset))
+            .collect();
+        if end < total {
+            text.push_str(&format!(
+                "\n\n[characters {}-{} of {}; call {} with offset {} for more]",
+                offset, end, total, TOOL_FETCH_ATTACHMENT, end
+            ));
+        }
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+
+    /// **Scenario**: Slices of an attachment are returned with a continuation note; unknown
+    /// ids are NotFound.
+    #[tokio::test]
+    async fn fetches_slices_and_reports_missing() {
+        let store = Arc::new(InMemoryStore::new());
+        let namespace = vec!["attachments".to_string()];
+        store
+            .put(
+                &namespace,
+                "web_fetcher-1",
+                &json!({"tool": "web_fetcher", "call_id": "c1", "content": "abcdefghij"}),
+            )
+            .await
+            .unwrap();
+        let tool = FetchAttachmentTool::new(store, namespace);
+
+        let first = tool
+            .call(json!({"id": "web_fetcher-1", "limit": 4}), None)
+            .await
+            .unwrap();
+        assert!(first.text.starts_with("abcd\n\n[characters 0-4 of 10;"));
+        let rest = tool
+            .call(json!({"id": "web_fetcher-1", "offset": 4}), None)
+            .await
+            .unwrap();
+        assert_eq!(rest.text, "efghij");
+
+        let missing = tool.call(json!({"id": "nope"}), None).await;
+        assert!(matches!(missing, Err(ToolSourceError::NotFound(_))));
+    }
+}
@@ -0,0 +1,444 @@
+//! Browser tool: drive a headless Chromium for pages that need JavaScript (feature `browser`).
+//!
+//! [`BrowserTool`] exposes one tool, `browser`, whose `action` argument is `navigate`, `click`,
+//! `extract_text` or `screenshot`. The browser is launched on the first call and keeps one page
+//! open, so consecutive calls act on the same page (navigate, then click, then extract text).
+//!
+//! Sandboxing: every URL the page navigates to must pass the [`DomainPolicy`] (checked before
+//! `navigate` and on the page URL before and after every action, which catches redirects and
+//! link clicks), the tool refuses further actions after `max_steps`, and each action is bounded
+//! by a timeout. Extracted text is cut at `max_text_chars`.
+//!
+//! **Interaction**: Implements [`Tool`]; register it on an
+//! [`AggregateToolSource`](crate::tools::AggregateToolSource) next to
+//! [`WebFetcherTool`](crate::tools::WebFetcherTool), which stays the cheaper choice for static
+//! pages and APIs.
+
+mod policy;
+
+pub use policy::DomainPolicy;
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::page::ScreenshotParams;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for the browser automation operation.
+pub const TOOL_BROWSER: &str = "browser";
+
+/// Actions allowed per tool instance when `with_max_steps` is not called.
+pub const DEFAULT_MAX_STEPS: usize = 20;
+
+/// Time allowed per action when `with_action_timeout` is not called.
+pub const DEFAULT_ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Characters returned by `extract_text` when `with_max_text_chars` is not called.
+pub const DEFAULT_MAX_TEXT_CHARS: usize = 20_000;
+
+/// One call of the tool, from its `action` argument.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum BrowserAction {
+    Navigate { url: String },
+    Click { selector: String },
+    ExtractText { selector: Option<String> },
+    Screenshot,
+}
+
+/// The running browser with its one page.
+struct Session {
+    browser: Browser,
+    page: Page,
+    /// Drives the DevTools connection; the browser stops responding when it ends.
+    handler: JoinHandle<()>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.handler.abort();
+    }
+}
+
+/// Tool that drives a headless Chromium: navigate, click, extract text, take screenshots.
+///
+/// Requires a Chrome or Chromium install (found on `PATH` or set with
+/// [`with_chrome_executable`](Self::with_chrome_executable)). See the [module docs](self) for
+/// the sandboxing rules.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tools::browser::{BrowserTool, DomainPolicy};
+/// use langgraph::tools::Tool;
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let tool = BrowserTool::new(DomainPolicy::allow_only(["example.com"])).with_max_steps(10);
+/// tool.call(json!({ "action": "navigate", "url": "https://example.com" }), None)
+///     .await
+///     .unwrap();
+/// let text = tool
+///     .call(json!({ "action": "extract_text", "selector": "h1" }), None)
+///     .await
+///     .unwrap()
+///     .text;
+/// println!("{}", text);
+/// tool.close().await;
+/// # }
+/// ```
+///
+/// # Interaction
+///
+/// - **Tool**: Implements this trait for registration with
+///   [`AggregateToolSource`](crate::tools::AggregateToolSource).
+/// - **ToolSourceError**: `InvalidInput` for bad arguments, refused URLs and the step limit;
+///   `Transport` for browser failures and timeouts.
+/// - **ToolCallContext**: Not used by this tool.
+pub struct BrowserTool {
+    policy: DomainPolicy,
+    max_steps: usize,
+    action_timeout: Duration,
+    max_text_chars: usize,
+    screenshot_dir: PathBuf,
+    chrome_executable: Option<PathBuf>,
+    steps: AtomicUsize,
+    session: Mutex<Option<Session>>,
+}
+
+impl BrowserTool {
+    /// Browser tool that may only open URLs allowed by `policy`.
+    pub fn new(policy: DomainPolicy) -> Self {
+        Self {
+            policy,
+            max_steps: DEFAULT_MAX_STEPS,
+            action_timeout: DEFAULT_ACTION_TIMEOUT,
+            max_text_chars: DEFAULT_MAX_TEXT_CHARS,
+            screenshot_dir: std::env::temp_dir(),
+            chrome_executable: None,
+            steps: AtomicUsize::new(0),
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Sets how many actions the tool performs before refusing further calls (default
+    /// [`DEFAULT_MAX_STEPS`]). Refused calls count as well.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Sets the time allowed per action, including the browser launch on the first call
+    /// (default [`DEFAULT_ACTION_TIMEOUT`]).
+    pub fn with_action_timeout(mut self, timeout: Duration) -> Self {
+        self.action_timeout = timeout;
+        self
+    }
+
+    /// Sets how many characters `extract_text` returns (default [`DEFAULT_MAX_TEXT_CHARS`]).
+    pub fn with_max_text_chars(mut self, max_chars: usize) -> Self {
+        self.max_text_chars = max_chars;
+        self
+    }
+
+    /// Sets the directory screenshots are written to (default: the system temp directory).
+    pub fn with_screenshot_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.screenshot_dir = dir.into();
+        self
+    }
+
+    /// Sets the Chrome/Chromium binary instead of searching for one.
+    pub fn with_chrome_executable(mut self, path: impl Into<PathBuf>) -> Self {
+        self.chrome_executable = Some(path.into());
+        self
+    }
+
+    /// Closes the browser. The next call launches a new one; the step count is kept.
+    pub async fn close(&self) {
+        if let Some(mut session) = self.session.lock().await.take() {
+            if let Err(e) = session.browser.close().await {
+                tracing::debug!("browser close failed: {}", e);
+            }
+        }
+    }
+
+    /// Counts one step and fails once `max_steps` have been taken.
+    fn take_step(&self) -> Result<(), ToolSourceError> {
+        let step = self.steps.fetch_add(1, Ordering::SeqCst) + 1;
+        if step > self.max_steps {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "browser step limit of {} reached",
+                self.max_steps
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fails when `url` is not allowed by the domain policy.
+    fn check_url(&self, url: &str) -> Result<(), ToolSourceError> {
+        if self.policy.allows(url) {
+            Ok(())
+        } else {
+            Err(ToolSourceError::InvalidInput(format!(
+                "URL not allowed by the browser domain policy: {}",
+                url
+            )))
+        }
+    }
+
+    /// Fails when the page has moved to a URL the policy refuses, leaving it for `about:blank`.
+    async fn check_page_url(&self, page: &Page) -> Result<(), ToolSourceError> {
+        let url = page.url().await.map_err(transport)?.unwrap_or_default();
+        if url.is_empty() || self.policy.allows(&url) {
+            return Ok(());
+        }
+        if let Err(e) = page.goto("about:blank").await {
+            tracing::debug!("leaving refused page failed: {}", e);
+        }
+        self.check_url(&url)
+    }
+
+    /// Launches the browser and opens its page.
+    async fn launch(&self) -> Result<Session, ToolSourceError> {
+        let mut config = BrowserConfig::builder();
+        if let Some(path) = &self.chrome_executable {
+            config = config.chrome_executable(path);
+        }
+        let config = config.build().map_err(ToolSourceError::Transport)?;
+        let (browser, mut handler) = Browser::launch(config).await.map_err(transport)?;
+        let handler = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if event.is_err() {
+                    break;
+                }
+            }
+        });
+        let page = browser.new_page("about:blank").await.map_err(transport)?;
+        Ok(Session {
+            browser,
+            page,
+            handler,
+        })
+    }
+
+    /// Runs `action` on the page, launching the browser when needed.
+    async fn run(&self, action: BrowserAction) -> Result<String, ToolSourceError> {
+        let mut session = self.session.lock().await;
+        if session.is_none() {
+            *session = Some(self.launch().await?);
+        }
+        let page = &session.as_ref().expect("session launched").page;
+        self.check_page_url(page).await?;
+
+        let text = match action {
+            BrowserAction::Navigate { url } => {
+                page.goto(url.as_str()).await.map_err(transport)?;
+                self.check_page_url(page).await?;
+                let title = page.get_title().await.map_err(transport)?;
+                let current = page.url().await.map_err(transport)?.unwrap_or(url);
+                format!(
+                    "navigated to {} (title: {})",
+                    current,
+                    title.unwrap_or_default()
+                )
+            }
+            BrowserAction::Click { selector } => {
+                page.find_element(selector.as_str())
+                    .await
+                    .map_err(transport)?
+                    .click()
+                    .await
+                    .map_err(transport)?;
+                self.check_page_url(page).await?;
+                format!("clicked {}", selector)
+            }
+            BrowserAction::ExtractText { selector } => {
+                let text = match selector {
+                    Some(selector) => page
+                        .find_element(selector.as_str())
+                        .await
+                        .map_err(transport)?
+                        .inner_text()
+                        .await
+                        .map_err(transport)?
+                        .unwrap_or_default(),
+                    None => page
+                        .evaluate("document.body ? document.body.innerText : ''")
+                        .await
+                        .map_err(transport)?
+                        .into_value::<String>()
+                        .map_err(|e| ToolSourceError::Transport(e.to_string()))?,
+                };
+                truncate_chars(text, self.max_text_chars)
+            }
+            BrowserAction::Screenshot => {
+                let png = page
+                    .screenshot(ScreenshotParams::builder().full_page(true).build())
+                    .await
+                    .map_err(transport)?;
+                let path = self
+                    .screenshot_dir
+                    .join(format!("screenshot-{}.png", crate::memory::uuid6()));
+                tokio::fs::write(&path, png)
+                    .await
+                    .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+                format!("screenshot saved to {}", path.display())
+            }
+        };
+        Ok(text)
+    }
+}
+
+/// Browser errors are reported as transport failures.
+fn transport(e: impl std::fmt::Display) -> ToolSourceError {
+    ToolSourceError::Transport(format!("browser: {}", e))
+}
+
+/// `text` cut to `max_chars` characters, with a marker when anything was cut.
+fn truncate_chars(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}\n[truncated]", &text[..end]),
+        None => text,
+    }
+}
+
+/// Parses the tool arguments into an action.
+fn parse_action(args: Value) -> Result<BrowserAction, ToolSourceError> {
+    serde_json::from_value(args).map_err(|e| ToolSourceError::InvalidInput(e.to_string()))
+}
+
+#[async_trait]
+impl Tool for BrowserTool {
+    fn name(&self) -> &str {
+        TOOL_BROWSER
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_BROWSER.to_string(),
+            description: Some(
+                "Control a headless web browser for pages that need JavaScript. The page stays \
+                 open between calls. Actions: navigate (url), click (CSS selector), extract_text \
+                 (optional CSS selector; whole page when omitted), screenshot (saves a PNG and \
+                 returns its path). Only some domains may be opened."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["navigate", "click", "extract_text", "screenshot"],
+                        "description": "What to do on the page"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "URL to open (navigate)"
+                    },
+                    "selector": {
+                        "type": "string",
+                        "description": "CSS selector of the element (click, extract_text)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        self.take_step()?;
+        let action = parse_action(args)?;
+        if let BrowserAction::Navigate { url } = &action {
+            self.check_url(url)?;
+        }
+        let text = tokio::time::timeout(self.action_timeout, self.run(action))
+            .await
+            .map_err(|_| {
+                ToolSourceError::Transport(format!(
+                    "browser action timed out after {:?}",
+                    self.action_timeout
+                ))
+            })??;
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Each action parses from its `action` tag; unknown actions and missing
+    /// arguments are invalid input.
+    #[test]
+    fn parse_action_reads_tagged_arguments() {
+        assert_eq!(
+            parse_action(json!({"action": "navigate", "url": "https://example.com"})).unwrap(),
+            BrowserAction::Navigate {
+                url: "https://example.com".into()
+            }
+        );
+        assert_eq!(
+            parse_action(json!({"action": "extract_text"})).unwrap(),
+            BrowserAction::ExtractText { selector: None }
+        );
+        assert_eq!(
+            parse_action(json!({"action": "screenshot"})).unwrap(),
+            BrowserAction::Screenshot
+        );
+        assert!(matches!(
+            parse_action(json!({"action": "click"})),
+            Err(ToolSourceError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            parse_action(json!({"action": "type", "text": "x"})),
+            Err(ToolSourceError::InvalidInput(_))
+        ));
+    }
+
+    /// **Scenario**: A refused URL fails before any browser is launched, and calls past
+    /// max_steps are refused, including the refused ones.
+    #[tokio::test]
+    async fn refuses_denied_urls_and_calls_past_step_limit() {
+        let tool = BrowserTool::new(DomainPolicy::allow_only(["example.com"])).with_max_steps(2);
+        let navigate = json!({"action": "navigate", "url": "https://evil.io/"});
+
+        let err = tool.call(navigate.clone(), None).await.unwrap_err();
+        assert!(err.to_string().contains("domain policy"), "{}", err);
+        assert!(tool.session.lock().await.is_none());
+
+        let err = tool
+            .call(
+                json!({"action": "navigate", "url": "file:///etc/passwd"}),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("domain policy"), "{}", err);
+
+        let err = tool.call(navigate, None).await.unwrap_err();
+        assert!(err.to_string().contains("step limit of 2"), "{}", err);
+    }
+
+    /// **Scenario**: Text is cut on a character boundary with a marker; short text is unchanged.
+    #[test]
+    fn truncate_chars_marks_cut_text() {
+        assert_eq!(truncate_chars("héllo".into(), 2), "hé\n[truncated]");
+        assert_eq!(truncate_chars("héllo".into(), 5), "héllo");
+    }
+}
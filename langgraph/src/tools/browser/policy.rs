@@ -0,0 +1,138 @@
+use reqwest::Url;
+
+/// Which sites [`BrowserTool`](super::BrowserTool) may open.
+///
+/// Only `http` and `https` URLs are considered; `file:`, `data:`, `javascript:` and browser
+/// internal pages are always refused (except the `about:blank` start page). A domain entry
+/// matches the host itself and its subdomains: `"example.com"` covers `example.com` and
+/// `docs.example.com`. Denied domains win over allowed ones.
+///
+/// # Examples
+///
+/// ```
+/// use langgraph::tools::browser::DomainPolicy;
+///
+/// let policy = DomainPolicy::allow_only(["example.com"]).with_denied(["admin.example.com"]);
+/// assert!(policy.allows("https://docs.example.com/start"));
+/// assert!(!policy.allows("https://admin.example.com/"));
+/// assert!(!policy.allows("https://other.org/"));
+/// assert!(!policy.allows("file:///etc/passwd"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainPolicy {
+    /// `None` allows every domain not denied.
+    allowed: Option<Vec<String>>,
+    denied: Vec<String>,
+}
+
+impl DomainPolicy {
+    /// Allows only `domains` and their subdomains.
+    pub fn allow_only<I, S>(domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            allowed: Some(normalize(domains)),
+            denied: Vec::new(),
+        }
+    }
+
+    /// Allows every domain; narrow it with [`with_denied`](Self::with_denied).
+    pub fn allow_any() -> Self {
+        Self {
+            allowed: None,
+            denied: Vec::new(),
+        }
+    }
+
+    /// Refuses `domains` and their subdomains, even when they are allowed.
+    pub fn with_denied<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.denied.extend(normalize(domains));
+        self
+    }
+
+    /// Whether the browser may open `url`.
+    pub fn allows(&self, url: &str) -> bool {
+        if url == "about:blank" {
+            return true;
+        }
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if self.denied.iter().any(|d| covers(d, &host)) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => allowed.iter().any(|d| covers(d, &host)),
+            None => true,
+        }
+    }
+}
+
+/// Lowercased domains without a leading `*.` or `.`.
+fn normalize<I, S>(domains: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    domains
+        .into_iter()
+        .map(|d| {
+            let d = d.as_ref().trim();
+            let d = d.strip_prefix("*.").unwrap_or(d);
+            d.trim_start_matches('.')
+                .trim_end_matches('.')
+                .to_ascii_lowercase()
+        })
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// Whether `domain` is `host` or one of its parent domains.
+fn covers(domain: &str, host: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|rest| rest.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Allowed domains cover their subdomains but not look-alike hosts; denied
+    /// domains win; `allow_any` still refuses non-http schemes.
+    #[test]
+    fn domain_policy_matches_hosts_and_schemes() {
+        let policy = DomainPolicy::allow_only(["*.Example.com", "rust-lang.org"])
+            .with_denied(["admin.example.com"]);
+        assert!(policy.allows("https://example.com/"));
+        assert!(policy.allows("http://a.b.example.com/path?q=1"));
+        assert!(policy.allows("https://rust-lang.org"));
+        assert!(policy.allows("about:blank"));
+        assert!(!policy.allows("https://notexample.com/"));
+        assert!(!policy.allows("https://example.com.evil.io/"));
+        assert!(!policy.allows("https://admin.example.com/login"));
+        assert!(!policy.allows("https://x.admin.example.com/"));
+        assert!(!policy.allows("not a url"));
+
+        let any = DomainPolicy::allow_any().with_denied(["evil.io"]);
+        assert!(any.allows("https://anything.dev/"));
+        assert!(!any.allows("https://evil.io/"));
+        assert!(!any.allows("file:///etc/passwd"));
+        assert!(!any.allows("chrome://settings"));
+        assert!(!any.allows("javascript:alert(1)"));
+    }
+}
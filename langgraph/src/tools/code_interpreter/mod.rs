@@ -0,0 +1,559 @@
+//! Code interpreter tool: run model-written Python or JavaScript in a WASM sandbox (feature
+//! `wasm-sandbox`).
+//!
+//! [`CodeInterpreterTool`] runs a WASI build of an interpreter (CPython, QuickJS) under wasmtime
+//! and returns what the program printed. Nothing runs on the host: the guest sees no network,
+//! no environment and no files except the read-only directories its [`WasmInterpreter`] maps
+//! (typically the Python standard library). Each run gets a fresh instance bounded by a CPU
+//! budget (wasmtime fuel), a memory limit and a wall-clock timeout.
+//!
+//! The interpreter modules are not bundled; point the tool at them with
+//! [`with_python`](CodeInterpreterTool::with_python) /
+//! [`with_javascript`](CodeInterpreterTool::with_javascript). Each is compiled once, on first
+//! use.
+//!
+//! **Interaction**: Implements [`Tool`]; register it on an
+//! [`AggregateToolSource`](crate::tools::AggregateToolSource) for data-analysis agents in place
+//! of [`BashTool`](crate::tools::BashTool), which runs on the host.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use wasmtime::{
+    Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap, UpdateDeadline,
+};
+use wasmtime_wasi::pipe::MemoryOutputPipe;
+use wasmtime_wasi::preview1::WasiP1Ctx;
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for the code interpreter operation.
+pub const TOOL_CODE_INTERPRETER: &str = "code_interpreter";
+
+/// Fuel (roughly, wasm instructions) per run when `with_max_fuel` is not called.
+pub const DEFAULT_MAX_FUEL: u64 = 20_000_000_000;
+
+/// Linear memory per run when `with_max_memory_bytes` is not called.
+pub const DEFAULT_MAX_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Wall-clock time per run when `with_timeout` is not called.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Bytes of stdout and of stderr kept per run when `with_max_output_bytes` is not called.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// How often the engine's epoch advances, i.e. how late a timeout may be noticed.
+const EPOCH_TICK: Duration = Duration::from_millis(50);
+
+/// Language of a `code_interpreter` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Language {
+    Python,
+    JavaScript,
+}
+
+impl Language {
+    fn parse(language: &str) -> Result<Self, ToolSourceError> {
+        match language.to_ascii_lowercase().as_str() {
+            "python" | "py" => Ok(Self::Python),
+            "javascript" | "js" => Ok(Self::JavaScript),
+            other => Err(ToolSourceError::InvalidInput(format!(
+                "unsupported language: {} (use python or javascript)",
+                other
+            ))),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+        }
+    }
+}
+
+/// A WASI interpreter module and how to hand it code.
+///
+/// The guest's command line is `args` followed by the code, e.g. `python -c <code>`.
+#[derive(Debug, Clone)]
+pub struct WasmInterpreter {
+    module: PathBuf,
+    args: Vec<String>,
+    dirs: Vec<(PathBuf, String)>,
+    env: Vec<(String, String)>,
+}
+
+impl WasmInterpreter {
+    /// Interpreter at `module` (`.wasm`, or `.wat` text) run as `args... <code>`.
+    pub fn new(
+        module: impl Into<PathBuf>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self {
+            module: module.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            dirs: Vec::new(),
+            env: Vec::new(),
+        }
+    }
+
+    /// A CPython WASI build, run as `python -c <code>`. Map its standard library with
+    /// [`with_dir`](Self::with_dir) unless the module embeds it.
+    pub fn python(module: impl Into<PathBuf>) -> Self {
+        Self::new(module, ["python", "-c"])
+    }
+
+    /// A QuickJS WASI build, run as `qjs --std -e <code>`.
+    pub fn quickjs(module: impl Into<PathBuf>) -> Self {
+        Self::new(module, ["qjs", "--std", "-e"])
+    }
+
+    /// Maps the host directory `host` read-only at `guest` (e.g. the Python standard library
+    /// at `/usr/local/lib`).
+    pub fn with_dir(mut self, host: impl Into<PathBuf>, guest: impl Into<String>) -> Self {
+        self.dirs.push((host.into(), guest.into()));
+        self
+    }
+
+    /// Sets an environment variable in the guest (e.g. `PYTHONHOME`); it sees no others.
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Limits applied to every run.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_fuel: u64,
+    max_memory_bytes: usize,
+    timeout: Duration,
+    max_output_bytes: usize,
+}
+
+/// Per-run store data.
+struct RunState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// Returned by the epoch callback once the run's deadline has passed.
+#[derive(Debug)]
+struct TimedOut;
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("run timed out")
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// What one run produced.
+struct Outcome {
+    exit_code: i32,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    /// Why the run was stopped before the program exited, if it was.
+    stopped: Option<String>,
+}
+
+impl Outcome {
+    /// stdout, then stderr and how the run ended when there is something to say.
+    fn into_text(self) -> String {
+        let mut text = String::from_utf8_lossy(&self.stdout).into_owned();
+        let mut note = |section: String| {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&section);
+        };
+        if !self.stderr.is_empty() {
+            note(format!(
+                "[stderr]\n{}",
+                String::from_utf8_lossy(&self.stderr)
+            ));
+        }
+        if let Some(reason) = self.stopped {
+            note(format!("[stopped: {}]", reason));
+        } else if self.exit_code != 0 {
+            note(format!("[exit code {}]", self.exit_code));
+        }
+        text
+    }
+}
+
+/// Tool that runs Python or JavaScript in a wasmtime sandbox and returns its output.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tools::code_interpreter::{CodeInterpreterTool, WasmInterpreter};
+/// use langgraph::tools::Tool;
+/// use serde_json::json;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let python = WasmInterpreter::python("wasm/python.wasm").with_dir("wasm/lib", "/usr/local/lib");
+/// let tool = CodeInterpreterTool::new().with_python(python);
+/// let out = tool
+///     .call(json!({ "language": "python", "code": "print(sum(range(10)))" }), None)
+///     .await
+///     .unwrap();
+/// assert_eq!(out.text.trim(), "45");
+/// # }
+/// ```
+///
+/// # Interaction
+///
+/// - **Tool**: Implements this trait for registration with
+///   [`AggregateToolSource`](crate::tools::AggregateToolSource).
+/// - **ToolSourceError**: `InvalidInput` for bad arguments and languages with no interpreter;
+///   `Transport` when an interpreter module cannot be loaded or started. A program that fails,
+///   runs out of fuel or times out is a successful call whose text says so.
+/// - **ToolCallContext**: Not used by this tool.
+pub struct CodeInterpreterTool {
+    engine: Engine,
+    interpreters: HashMap<Language, WasmInterpreter>,
+    /// Compiled interpreters, shared with the blocking tasks that run them.
+    modules: Arc<Mutex<HashMap<Language, Module>>>,
+    limits: Limits,
+}
+
+impl CodeInterpreterTool {
+    /// Tool with the default limits and no interpreters yet.
+    pub fn new() -> Self {
+        let mut config = Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        Self {
+            engine: Engine::new(&config).expect("fuel and epoch interruption are always supported"),
+            interpreters: HashMap::new(),
+            modules: Arc::new(Mutex::new(HashMap::new())),
+            limits: Limits {
+                max_fuel: DEFAULT_MAX_FUEL,
+                max_memory_bytes: DEFAULT_MAX_MEMORY_BYTES,
+                timeout: DEFAULT_TIMEOUT,
+                max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            },
+        }
+    }
+
+    /// Runs `language: "python"` with `interpreter`.
+    pub fn with_python(mut self, interpreter: WasmInterpreter) -> Self {
+        self.interpreters.insert(Language::Python, interpreter);
+        self
+    }
+
+    /// Runs `language: "javascript"` with `interpreter`.
+    pub fn with_javascript(mut self, interpreter: WasmInterpreter) -> Self {
+        self.interpreters.insert(Language::JavaScript, interpreter);
+        self
+    }
+
+    /// Sets the CPU budget per run in wasmtime fuel (default [`DEFAULT_MAX_FUEL`]).
+    pub fn with_max_fuel(mut self, fuel: u64) -> Self {
+        self.limits.max_fuel = fuel;
+        self
+    }
+
+    /// Sets the linear memory a run may grow to (default [`DEFAULT_MAX_MEMORY_BYTES`]).
+    pub fn with_max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.limits.max_memory_bytes = bytes;
+        self
+    }
+
+    /// Sets the wall-clock time per run (default [`DEFAULT_TIMEOUT`]).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.limits.timeout = timeout;
+        self
+    }
+
+    /// Sets the bytes of stdout and of stderr kept per run; writes past it fail in the guest
+    /// (default [`DEFAULT_MAX_OUTPUT_BYTES`]).
+    pub fn with_max_output_bytes(mut self, bytes: usize) -> Self {
+        self.limits.max_output_bytes = bytes;
+        self
+    }
+
+    /// Runs `code` in a fresh instance of the interpreter for `language`.
+    async fn run(&self, language: Language, code: String) -> Result<Outcome, ToolSourceError> {
+        let interpreter = self.interpreters.get(&language).cloned().ok_or_else(|| {
+            ToolSourceError::InvalidInput(format!(
+                "no {} interpreter is configured",
+                language.as_str()
+            ))
+        })?;
+        let (engine, modules, limits) = (self.engine.clone(), self.modules.clone(), self.limits);
+        // Compiling an interpreter takes seconds and a run may spin until its limits; both
+        // block, so neither runs on the async runtime.
+        tokio::task::spawn_blocking(move || {
+            let module = load_module(&engine, &modules, language, &interpreter)?;
+            execute(&engine, &module, &interpreter, code, limits)
+        })
+        .await
+        .map_err(|e| ToolSourceError::Transport(format!("code interpreter task: {}", e)))?
+    }
+}
+
+impl Default for CodeInterpreterTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The compiled module for `language`, compiling it on first use.
+fn load_module(
+    engine: &Engine,
+    modules: &Mutex<HashMap<Language, Module>>,
+    language: Language,
+    interpreter: &WasmInterpreter,
+) -> Result<Module, ToolSourceError> {
+    let mut modules = modules.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(module) = modules.get(&language) {
+        return Ok(module.clone());
+    }
+    let module = Module::from_file(engine, &interpreter.module).map_err(|e| {
+        ToolSourceError::Transport(format!(
+            "failed to load {}: {}",
+            interpreter.module.display(),
+            e
+        ))
+    })?;
+    modules.insert(language, module.clone());
+    Ok(module)
+}
+
+/// Instantiates `module` with the sandbox's WASI context and runs it to completion or a limit.
+fn execute(
+    engine: &Engine,
+    module: &Module,
+    interpreter: &WasmInterpreter,
+    code: String,
+    limits: Limits,
+) -> Result<Outcome, ToolSourceError> {
+    let stdout = MemoryOutputPipe::new(limits.max_output_bytes);
+    let stderr = MemoryOutputPipe::new(limits.max_output_bytes);
+    let mut wasi = WasiCtxBuilder::new();
+    wasi.args(interpreter.args.as_slice())
+        .arg(code)
+        .envs(interpreter.env.as_slice())
+        .stdout(stdout.clone())
+        .stderr(stderr.clone());
+    for (host, guest) in &interpreter.dirs {
+        wasi.preopened_dir(host, guest, DirPerms::READ, FilePerms::READ)
+            .map_err(|e| {
+                ToolSourceError::Transport(format!("failed to map {}: {}", host.display(), e))
+            })?;
+    }
+
+    let mut store = Store::new(
+        engine,
+        RunState {
+            wasi: wasi.build_p1(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(limits.max_memory_bytes)
+                .build(),
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(limits.max_fuel).map_err(transport)?;
+    let deadline = Instant::now() + limits.timeout;
+    store.set_epoch_deadline(1);
+    store.epoch_deadline_callback(move |_| {
+        if Instant::now() >= deadline {
+            Err(wasmtime::Error::new(TimedOut))
+        } else {
+            Ok(UpdateDeadline::Continue(1))
+        }
+    });
+
+    let mut linker: Linker<RunState> = Linker::new(engine);
+    wasmtime_wasi::preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi)
+        .map_err(transport)?;
+    let instance = linker.instantiate(&mut store, module).map_err(transport)?;
+    let start = instance
+        .get_typed_func::<(), ()>(&mut store, "_start")
+        .map_err(transport)?;
+
+    // Advances the epoch so the deadline callback above gets to check the clock.
+    let done = Arc::new(AtomicBool::new(false));
+    let ticker = {
+        let (engine, done) = (engine.clone(), done.clone());
+        std::thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                std::thread::sleep(EPOCH_TICK);
+                engine.increment_epoch();
+            }
+        })
+    };
+    let result = start.call(&mut store, ());
+    done.store(true, Ordering::Relaxed);
+    let _ = ticker.join();
+
+    let (exit_code, stopped) = match result {
+        Ok(()) => (0, None),
+        Err(e) => {
+            if let Some(exit) = e.downcast_ref::<I32Exit>() {
+                (exit.0, None)
+            } else if e.downcast_ref::<TimedOut>().is_some() {
+                (1, Some(format!("timed out after {:?}", limits.timeout)))
+            } else if matches!(e.downcast_ref::<Trap>(), Some(Trap::OutOfFuel)) {
+                (1, Some("CPU limit exceeded".to_string()))
+            } else {
+                (1, Some(format!("trapped: {}", e)))
+            }
+        }
+    };
+    Ok(Outcome {
+        exit_code,
+        stdout: stdout.contents().to_vec(),
+        stderr: stderr.contents().to_vec(),
+        stopped,
+    })
+}
+
+/// wasmtime setup errors are reported as transport failures.
+fn transport(e: impl std::fmt::Display) -> ToolSourceError {
+    ToolSourceError::Transport(format!("code interpreter: {}", e))
+}
+
+#[async_trait]
+impl Tool for CodeInterpreterTool {
+    fn name(&self) -> &str {
+        TOOL_CODE_INTERPRETER
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        let mut languages: Vec<&str> = self.interpreters.keys().map(|l| l.as_str()).collect();
+        languages.sort_unstable();
+        crate::tool_source::ToolSpec {
+            name: TOOL_CODE_INTERPRETER.to_string(),
+            description: Some(
+                "Run a short Python or JavaScript program in an isolated sandbox and get what it \
+                 printed (stdout, then stderr). Print the results you need. There is no network \
+                 or file access, and CPU time and memory are limited."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "language": {
+                        "type": "string",
+                        "enum": languages,
+                        "description": "Language of the code"
+                    },
+                    "code": {
+                        "type": "string",
+                        "description": "Complete program to run"
+                    }
+                },
+                "required": ["language", "code"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let language = args
+            .get("language")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing language".to_string()))?;
+        let language = Language::parse(language)?;
+        let code = args
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing code".to_string()))?;
+        let outcome = self.run(language, code.to_string()).await?;
+        Ok(ToolCallContent {
+            text: outcome.into_text(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prints its argv[2] (the code) via fd_write, then exits with status 3.
+    const ECHO_WAT: &str = r#"(module
+      (import "wasi_snapshot_preview1" "args_sizes_get"
+        (func $args_sizes_get (param i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "args_get" (func $args_get (param i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+      (memory (export "memory") 1)
+      (func (export "_start")
+        (drop (call $args_sizes_get (i32.const 0) (i32.const 4)))
+        (drop (call $args_get (i32.const 16) (i32.const 64)))
+        ;; iovec at 32: argv[2] and its length (up to the end of the args buffer, minus NUL)
+        (i32.store (i32.const 32) (i32.load (i32.const 24)))
+        (i32.store (i32.const 36)
+          (i32.sub (i32.add (i32.const 63) (i32.load (i32.const 4))) (i32.load (i32.const 24))))
+        (drop (call $fd_write (i32.const 1) (i32.const 32) (i32.const 1) (i32.const 40)))
+        (call $proc_exit (i32.const 3))))"#;
+
+    /// Loops forever.
+    const SPIN_WAT: &str = r#"(module (func (export "_start") (loop $l (br $l))))"#;
+
+    fn interpreter(dir: &tempfile::TempDir, name: &str, wat: &str) -> WasmInterpreter {
+        let path = dir.path().join(name);
+        std::fs::write(&path, wat).unwrap();
+        WasmInterpreter::new(path, ["interp", "-c"])
+    }
+
+    /// **Scenario**: The guest gets the code as its last argument; stdout and a non-zero exit
+    /// code come back as text.
+    #[tokio::test]
+    async fn returns_stdout_and_exit_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = CodeInterpreterTool::new().with_python(interpreter(&dir, "echo.wat", ECHO_WAT));
+        let out = tool
+            .call(json!({"language": "py", "code": "print(1)"}), None)
+            .await
+            .unwrap();
+        assert_eq!(out.text, "print(1)\n[exit code 3]");
+        assert_eq!(
+            tool.spec().input_schema["properties"]["language"]["enum"],
+            json!(["python"])
+        );
+    }
+
+    /// **Scenario**: A program that never ends is stopped by the fuel budget, or by the timeout
+    /// when fuel is plentiful; languages without an interpreter are invalid input.
+    #[tokio::test]
+    async fn stops_runaway_programs() {
+        let dir = tempfile::tempdir().unwrap();
+        let spin = interpreter(&dir, "spin.wat", SPIN_WAT);
+        let args = json!({"language": "python", "code": "while True: pass"});
+
+        let tool = CodeInterpreterTool::new()
+            .with_python(spin.clone())
+            .with_max_fuel(1_000_000);
+        let out = tool.call(args.clone(), None).await.unwrap();
+        assert_eq!(out.text, "[stopped: CPU limit exceeded]");
+
+        let tool = CodeInterpreterTool::new()
+            .with_python(spin)
+            .with_max_fuel(u64::MAX)
+            .with_timeout(Duration::from_millis(200));
+        let out = tool.call(args, None).await.unwrap();
+        assert!(out.text.starts_with("[stopped: timed out"), "{}", out.text);
+
+        let err = tool
+            .call(json!({"language": "javascript", "code": "1"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+    }
+}
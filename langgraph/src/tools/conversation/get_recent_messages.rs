@@ -28,7 +28,7 @@ pub const TOOL_GET_RECENT_MESSAGES: &str = "get_recent_messages";
 ///
 /// let context = ToolCallContext::new(vec![
 ///     Message::User("hello".to_string()),
-///     Message::Assistant("hi there!".to_string()),
+///     Message::assistant("hi there!"),
 /// ]);
 ///
 /// let args = json!({"limit": 2});
@@ -63,12 +63,7 @@ impl GetRecentMessagesTool {
 
     /// Converts a Message to a JSON value with role and content.
     fn message_to_json(m: &Message) -> Value {
-        let (role, content) = match m {
-            Message::System(s) => ("system", s.as_str()),
-            Message::User(s) => ("user", s.as_str()),
-            Message::Assistant(s) => ("assistant", s.as_str()),
-        };
-        json!({ "role": role, "content": content })
+        json!({ "role": m.role(), "content": m.content() })
     }
 }
 
@@ -0,0 +1,206 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{FsJail, MAX_ENTRIES};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::notify::required_str;
+use crate::tools::Tool;
+
+/// Tool name for finding files by pattern under the root.
+pub const TOOL_GLOB: &str = "glob";
+
+/// Most directory entries one search visits, so a pattern like `**` stays cheap on big trees.
+const MAX_VISITED: usize = 50_000;
+
+/// Tool that finds paths under the jail's root matching a glob pattern.
+///
+/// Patterns are relative to the root and use `/`: `*` and `?` match within one path segment,
+/// `**` matches any number of segments (e.g. `src/**/*.rs`). Symlinked directories are not
+/// followed. Matches are sorted; at most [`MAX_ENTRIES`] are returned.
+///
+/// # Interaction
+///
+/// - **FsToolSource**: Always registered.
+/// - **ToolSourceError**: `InvalidInput` for a missing pattern or one leaving the root;
+///   `Transport` when the search fails.
+pub struct GlobTool {
+    jail: FsJail,
+}
+
+impl GlobTool {
+    /// Tool searching inside `jail`.
+    pub fn new(jail: FsJail) -> Self {
+        Self { jail }
+    }
+}
+
+/// Whether `name` matches the one-segment `pattern` (`*`, `?` and literal characters).
+fn segment_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| segment_matches(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && segment_matches(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && segment_matches(rest, &name[1..]),
+    }
+}
+
+/// Whether the path segments `path` match the pattern segments `pattern`.
+fn path_matches(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((segment, rest)) if *segment == ['*', '*'] => {
+            (0..=path.len()).any(|i| path_matches(rest, &path[i..]))
+        }
+        Some((segment, rest)) => {
+            !path.is_empty() && segment_matches(segment, &path[0]) && path_matches(rest, &path[1..])
+        }
+    }
+}
+
+/// Relative paths (with `/`) under `dir` that match `pattern`, walking at most
+/// [`MAX_VISITED`] entries; `prefix` is `dir` relative to the root.
+fn find(dir: &Path, prefix: &str, pattern: &[Vec<char>]) -> Vec<String> {
+    let mut matches = Vec::new();
+    let mut visited = 0;
+    let mut pending = vec![(dir.to_path_buf(), prefix.to_string())];
+    while let Some((dir, prefix)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > MAX_VISITED {
+                return matches;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let segments: Vec<Vec<char>> =
+                relative.split('/').map(|s| s.chars().collect()).collect();
+            if path_matches(pattern, &segments) {
+                matches.push(relative.clone());
+            }
+            // `file_type` does not follow symlinks, so links out of the root are not walked.
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                pending.push((entry.path(), relative));
+            }
+        }
+    }
+    matches
+}
+
+#[async_trait]
+impl Tool for GlobTool {
+    fn name(&self) -> &str {
+        TOOL_GLOB
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_GLOB.to_string(),
+            description: Some(
+                "Find files in the workspace by glob pattern, e.g. `src/**/*.rs` or `*.md`. \
+                 `*` and `?` match within one path segment, `**` any number of directories."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Glob pattern relative to the workspace root"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let pattern = required_str(&args, "pattern")?;
+        let segments: Vec<&str> = pattern
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .collect();
+        // Walk from the longest prefix without wildcards; it also goes through the jail.
+        let literal = segments
+            .iter()
+            .take_while(|s| !s.contains(['*', '?']))
+            .count()
+            .min(segments.len().saturating_sub(1));
+        let base = segments[..literal].join("/");
+        let dir = self
+            .jail
+            .resolve(if base.is_empty() { "." } else { &base })?;
+        let prefix = self.jail.display(&dir);
+        let prefix = if prefix == "." { String::new() } else { prefix };
+        let compiled: Vec<Vec<char>> = prefix
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .chain(segments[literal..].iter().copied())
+            .map(|s| s.chars().collect())
+            .collect();
+        let mut matches = tokio::task::spawn_blocking(move || find(&dir, &prefix, &compiled))
+            .await
+            .map_err(|e| ToolSourceError::Transport(format!("glob failed: {}", e)))?;
+        matches.sort();
+        let total = matches.len();
+        matches.truncate(MAX_ENTRIES);
+        if total > MAX_ENTRIES {
+            matches.push(format!("[{} more matches not shown]", total - MAX_ENTRIES));
+        }
+        let text = if matches.is_empty() {
+            format!("no files match {}", pattern)
+        } else {
+            matches.join("\n")
+        };
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: `*` stays within a segment, `**` crosses directories, and patterns cannot
+    /// walk out of the root.
+    #[tokio::test]
+    async fn matches_segments_and_recursive_wildcards() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/tools")).unwrap();
+        for file in [
+            "README.md",
+            "src/lib.rs",
+            "src/tools/fs.rs",
+            "src/tools/fs.txt",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+        let tool = GlobTool::new(FsJail::new(dir.path()));
+        let glob = |pattern: &str| tool.call(json!({ "pattern": pattern }), None);
+
+        assert_eq!(glob("src/*.rs").await.unwrap().text, "src/lib.rs");
+        assert_eq!(
+            glob("**/*.rs").await.unwrap().text,
+            "src/lib.rs\nsrc/tools/fs.rs"
+        );
+        assert_eq!(
+            glob("src/tools/fs.?s").await.unwrap().text,
+            "src/tools/fs.rs"
+        );
+        assert_eq!(glob("*.toml").await.unwrap().text, "no files match *.toml");
+        assert!(matches!(
+            glob("../*").await,
+            Err(ToolSourceError::InvalidInput(_))
+        ));
+    }
+}
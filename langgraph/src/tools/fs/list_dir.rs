@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{io_error, FsJail, MAX_ENTRIES};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::Tool;
+
+/// Tool name for listing a directory under the root.
+pub const TOOL_LIST_DIR: &str = "list_dir";
+
+/// Tool that lists a directory under the jail's root, one entry per line.
+///
+/// Entries are sorted by name; directories end with `/`, files show their size. At most
+/// [`MAX_ENTRIES`] entries are listed.
+///
+/// # Interaction
+///
+/// - **FsToolSource**: Always registered.
+/// - **ToolSourceError**: `InvalidInput` for paths outside the root and files; `NotFound` for
+///   missing directories; `Transport` for other IO errors.
+pub struct ListDirTool {
+    jail: FsJail,
+}
+
+impl ListDirTool {
+    /// Tool listing inside `jail`.
+    pub fn new(jail: FsJail) -> Self {
+        Self { jail }
+    }
+}
+
+#[async_trait]
+impl Tool for ListDirTool {
+    fn name(&self) -> &str {
+        TOOL_LIST_DIR
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_LIST_DIR.to_string(),
+            description: Some(
+                "List the files and directories in a directory of the workspace.".to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory relative to the workspace root (default: the root)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let path = args.get("path").and_then(Value::as_str).unwrap_or(".");
+        let full = self.jail.resolve(path)?;
+        let metadata = tokio::fs::metadata(&full)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        if !metadata.is_dir() {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "{} is not a directory",
+                path
+            )));
+        }
+        let mut dir = tokio::fs::read_dir(&full)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        let mut entries = Vec::new();
+        while let Some(entry) = dir.next_entry().await.map_err(|e| io_error(path, e))? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let line = match entry.metadata().await {
+                Ok(m) if m.is_dir() => format!("{}/", name),
+                Ok(m) => format!("{} ({} bytes)", name, m.len()),
+                Err(_) => name,
+            };
+            entries.push(line);
+        }
+        entries.sort();
+        let total = entries.len();
+        entries.truncate(MAX_ENTRIES);
+        if total > MAX_ENTRIES {
+            entries.push(format!("[{} more entries not shown]", total - MAX_ENTRIES));
+        }
+        let text = if entries.is_empty() {
+            format!("{} is empty", self.jail.display(&full))
+        } else {
+            entries.join("\n")
+        };
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Entries are listed sorted, directories marked with `/`, files with size.
+    #[tokio::test]
+    async fn lists_sorted_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("README.md"), "# hi").unwrap();
+        let tool = ListDirTool::new(FsJail::new(dir.path()));
+
+        let text = tool.call(json!({}), None).await.unwrap().text;
+        assert_eq!(text, "README.md (4 bytes)\nsrc/");
+        let text = tool.call(json!({"path": "src"}), None).await.unwrap().text;
+        assert_eq!(text, "src is empty");
+    }
+}
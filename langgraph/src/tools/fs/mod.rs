@@ -0,0 +1,221 @@
+//! Filesystem tools: `read_file`, `write_file`, `list_dir` and `glob` inside one root directory.
+//!
+//! Every path the model passes is relative to the root of an [`FsJail`]. Absolute paths and
+//! `..` that would leave the root are refused, and so are paths through a symlink (also a
+//! dangling one, which a write would follow out of the root). Reads
+//! and writes are bounded by the jail's size limits; listings and glob matches are capped at
+//! [`MAX_ENTRIES`].
+//!
+//! **Interaction**: Built and registered by
+//! [`FsToolSource`](crate::tool_source::FsToolSource), which leaves out `write_file` in
+//! read-only mode.
+
+mod glob;
+mod list_dir;
+mod read_file;
+mod write_file;
+
+pub use glob::{GlobTool, TOOL_GLOB};
+pub use list_dir::{ListDirTool, TOOL_LIST_DIR};
+pub use read_file::{ReadFileTool, TOOL_READ_FILE};
+pub use write_file::{WriteFileTool, TOOL_WRITE_FILE};
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::tool_source::ToolSourceError;
+
+/// Most entries `list_dir` and `glob` return.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// Default for [`FsJail::with_max_read_bytes`]: 256 KiB.
+pub const DEFAULT_MAX_READ_BYTES: usize = 256 * 1024;
+
+/// Default for [`FsJail::with_max_write_bytes`]: 1 MiB.
+pub const DEFAULT_MAX_WRITE_BYTES: usize = 1024 * 1024;
+
+/// Root directory the filesystem tools are confined to, with their size limits.
+#[derive(Debug, Clone)]
+pub struct FsJail {
+    root: PathBuf,
+    max_read_bytes: usize,
+    max_write_bytes: usize,
+}
+
+impl FsJail {
+    /// Jail at `root`, which must exist when the tools are called.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_read_bytes: DEFAULT_MAX_READ_BYTES,
+            max_write_bytes: DEFAULT_MAX_WRITE_BYTES,
+        }
+    }
+
+    /// Bytes `read_file` returns at most; longer files are cut off with a note.
+    pub fn with_max_read_bytes(mut self, bytes: usize) -> Self {
+        self.max_read_bytes = bytes;
+        self
+    }
+
+    /// Bytes `write_file` accepts at most; larger contents are refused.
+    pub fn with_max_write_bytes(mut self, bytes: usize) -> Self {
+        self.max_write_bytes = bytes;
+        self
+    }
+
+    /// The root directory as configured.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub(crate) fn max_read_bytes(&self) -> usize {
+        self.max_read_bytes
+    }
+
+    pub(crate) fn max_write_bytes(&self) -> usize {
+        self.max_write_bytes
+    }
+
+    /// The canonical root directory.
+    fn canonical_root(&self) -> Result<PathBuf, ToolSourceError> {
+        self.root
+            .canonicalize()
+            .map_err(|e| ToolSourceError::Transport(format!("root {}: {}", self.root.display(), e)))
+    }
+
+    /// Resolves `path` (relative to the root) to a path inside the root.
+    ///
+    /// `.` and `..` are applied lexically and may not climb above the root. No component below
+    /// the root may be a symlink, dangling or not; the returned path may not exist yet (e.g. a
+    /// file to write).
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, ToolSourceError> {
+        let outside =
+            || ToolSourceError::InvalidInput(format!("path {:?} is outside the root", path));
+        let mut relative = PathBuf::new();
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(part) => relative.push(part),
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    if !relative.pop() {
+                        return Err(outside());
+                    }
+                }
+                Component::RootDir | Component::Prefix(_) => {
+                    return Err(ToolSourceError::InvalidInput(format!(
+                        "path {:?} must be relative to the root",
+                        path
+                    )))
+                }
+            }
+        }
+        let root = self.canonical_root()?;
+        let mut full = root.clone();
+        for part in relative.iter() {
+            full.push(part);
+            match std::fs::symlink_metadata(&full) {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    return Err(ToolSourceError::InvalidInput(format!(
+                        "path {:?} goes through a symlink",
+                        path
+                    )))
+                }
+                Ok(_) => {}
+                // Nothing below a missing component exists, so no link can follow.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+                Err(e) => return Err(io_error(path, e)),
+            }
+        }
+        Ok(root.join(relative))
+    }
+
+    /// `path` (as returned by [`resolve`](Self::resolve)) relative to the root, with `/`
+    /// separators; `.` for the root itself.
+    pub(crate) fn display(&self, path: &Path) -> String {
+        let relative = self
+            .canonical_root()
+            .ok()
+            .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| path.to_path_buf());
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if parts.is_empty() {
+            ".".to_string()
+        } else {
+            parts.join("/")
+        }
+    }
+}
+
+/// Maps an IO error on `path` to a tool error; a missing file is `NotFound`.
+pub(crate) fn io_error(path: &str, e: std::io::Error) -> ToolSourceError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            ToolSourceError::NotFound(format!("{}: no such file", path))
+        }
+        _ => ToolSourceError::Transport(format!("{}: {}", path, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Paths inside the root resolve, also ones that do not exist yet; absolute
+    /// paths, `..` above the root and symlinks leading out are refused.
+    #[test]
+    fn resolve_keeps_paths_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let jail = FsJail::new(dir.path());
+
+        let root = dir.path().canonicalize().unwrap();
+        assert_eq!(jail.resolve("src/../a.txt").unwrap(), root.join("a.txt"));
+        assert_eq!(
+            jail.resolve("new/dir/b.txt").unwrap(),
+            root.join("new/dir/b.txt")
+        );
+        assert_eq!(jail.display(&jail.resolve(".").unwrap()), ".");
+
+        for path in ["../etc/passwd", "src/../../x", "/etc/passwd"] {
+            assert!(
+                matches!(jail.resolve(path), Err(ToolSourceError::InvalidInput(_))),
+                "{}",
+                path
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            let outside = tempfile::tempdir().unwrap();
+            std::os::unix::fs::symlink(outside.path(), dir.path().join("escape")).unwrap();
+            assert!(jail.resolve("escape/secret").is_err());
+            std::os::unix::fs::symlink(dir.path().join("src"), dir.path().join("inside")).unwrap();
+            assert!(jail.resolve("inside/a.txt").is_err());
+        }
+    }
+
+    /// **Scenario**: A dangling symlink to a path outside the root is refused, so writing
+    /// through it does not create the file outside.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn write_through_dangling_symlink_is_refused() {
+        use crate::tools::Tool;
+        use serde_json::json;
+
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("new.txt");
+        std::os::unix::fs::symlink(&target, dir.path().join("out")).unwrap();
+        let tool = WriteFileTool::new(FsJail::new(dir.path()));
+
+        let err = tool
+            .call(json!({"path": "out", "content": "pwned"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::io::AsyncReadExt;
+
+use super::{io_error, FsJail};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::notify::required_str;
+use crate::tools::Tool;
+
+/// Tool name for reading a text file under the root.
+pub const TOOL_READ_FILE: &str = "read_file";
+
+/// Tool that returns the text of a file under the jail's root.
+///
+/// Files longer than the jail's read limit are cut off at the limit, with a note giving the
+/// full size. Files that are not UTF-8 text are refused.
+///
+/// # Interaction
+///
+/// - **FsToolSource**: Always registered.
+/// - **ToolSourceError**: `InvalidInput` for paths outside the root, directories and binary
+///   files; `NotFound` for missing files; `Transport` for other IO errors.
+pub struct ReadFileTool {
+    jail: FsJail,
+}
+
+impl ReadFileTool {
+    /// Tool reading inside `jail`.
+    pub fn new(jail: FsJail) -> Self {
+        Self { jail }
+    }
+}
+
+#[async_trait]
+impl Tool for ReadFileTool {
+    fn name(&self) -> &str {
+        TOOL_READ_FILE
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_READ_FILE.to_string(),
+            description: Some(
+                "Read a text file. The path is relative to the workspace root.".to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File path relative to the workspace root"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let path = required_str(&args, "path")?;
+        let full = self.jail.resolve(path)?;
+        let metadata = tokio::fs::metadata(&full)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        if metadata.is_dir() {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "{} is a directory; use list_dir",
+                path
+            )));
+        }
+        // Read at most one byte past the limit, so large files are never loaded whole.
+        let limit = self.jail.max_read_bytes();
+        let file = tokio::fs::File::open(&full)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        let mut bytes = Vec::with_capacity((metadata.len() as usize).min(limit + 1));
+        file.take(limit as u64 + 1)
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        let shown = &bytes[..bytes.len().min(limit)];
+        let text = match std::str::from_utf8(shown) {
+            Ok(text) => text,
+            // The limit may split a character; drop the partial one.
+            Err(e) if e.error_len().is_none() => {
+                std::str::from_utf8(&shown[..e.valid_up_to()]).unwrap_or_default()
+            }
+            Err(_) => {
+                return Err(ToolSourceError::InvalidInput(format!(
+                    "{} is not a UTF-8 text file",
+                    path
+                )))
+            }
+        };
+        let mut text = text.to_string();
+        if bytes.len() > limit {
+            text.push_str(&format!(
+                "\n[truncated: {} has {} bytes, showing the first {}]",
+                path,
+                metadata.len().max(bytes.len() as u64),
+                limit
+            ));
+        }
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: A file is read whole, or cut at the read limit with a note; directories
+    /// and binary files are refused.
+    #[tokio::test]
+    async fn reads_text_within_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "hello world").unwrap();
+        std::fs::write(dir.path().join("bin"), [0xff, 0xfe, 0x00]).unwrap();
+        let tool = ReadFileTool::new(FsJail::new(dir.path()).with_max_read_bytes(5));
+
+        let text = tool
+            .call(json!({"path": "a.txt"}), None)
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(
+            text,
+            "hello\n[truncated: a.txt has 11 bytes, showing the first 5]"
+        );
+
+        for path in [".", "bin"] {
+            let err = tool.call(json!({ "path": path }), None).await.unwrap_err();
+            assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+        }
+        let err = tool
+            .call(json!({"path": "missing"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::NotFound(_)), "{}", err);
+    }
+}
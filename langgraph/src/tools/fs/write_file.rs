@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{io_error, FsJail};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::notify::required_str;
+use crate::tools::Tool;
+
+/// Tool name for writing a text file under the root.
+pub const TOOL_WRITE_FILE: &str = "write_file";
+
+/// Tool that creates or replaces a text file under the jail's root.
+///
+/// Missing parent directories are created. Contents larger than the jail's write limit are
+/// refused.
+///
+/// # Interaction
+///
+/// - **FsToolSource**: Registered unless the source is read-only.
+/// - **ToolSourceError**: `InvalidInput` for paths outside the root, directories, a missing
+///   `content` or one over the limit; `Transport` for IO errors.
+pub struct WriteFileTool {
+    jail: FsJail,
+}
+
+impl WriteFileTool {
+    /// Tool writing inside `jail`.
+    pub fn new(jail: FsJail) -> Self {
+        Self { jail }
+    }
+}
+
+#[async_trait]
+impl Tool for WriteFileTool {
+    fn name(&self) -> &str {
+        TOOL_WRITE_FILE
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_WRITE_FILE.to_string(),
+            description: Some(
+                "Create or overwrite a text file with the given content. The path is relative \
+                 to the workspace root; missing directories are created."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File path relative to the workspace root"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Full new content of the file"
+                    }
+                },
+                "required": ["path", "content"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let path = required_str(&args, "path")?;
+        let content = required_str(&args, "content")?;
+        let limit = self.jail.max_write_bytes();
+        if content.len() > limit {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "content has {} bytes; at most {} may be written",
+                content.len(),
+                limit
+            )));
+        }
+        let full = self.jail.resolve(path)?;
+        if full.is_dir() {
+            return Err(ToolSourceError::InvalidInput(format!(
+                "{} is a directory",
+                path
+            )));
+        }
+        if let Some(parent) = full.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error(path, e))?;
+        }
+        tokio::fs::write(&full, content)
+            .await
+            .map_err(|e| io_error(path, e))?;
+        Ok(ToolCallContent {
+            text: format!(
+                "wrote {} bytes to {}",
+                content.len(),
+                self.jail.display(&full)
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: A file is written with its directories; contents over the limit and paths
+    /// outside the root are refused without writing.
+    #[tokio::test]
+    async fn writes_inside_root_within_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = WriteFileTool::new(FsJail::new(dir.path()).with_max_write_bytes(8));
+
+        let text = tool
+            .call(json!({"path": "notes/todo.md", "content": "- ship"}), None)
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "wrote 6 bytes to notes/todo.md");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("notes/todo.md")).unwrap(),
+            "- ship"
+        );
+
+        let err = tool
+            .call(json!({"path": "big.txt", "content": "123456789"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+        assert!(!dir.path().join("big.txt").exists());
+
+        let err = tool
+            .call(json!({"path": "../x.txt", "content": "x"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+    }
+}
@@ -3,7 +3,10 @@
 //! See `docs/rust-langgraph/tools-refactor/architecture/common-interface-mcp.md`.
 //! Each MCP tool is represented by an `McpToolAdapter` that implements `Tool`;
 //! `call` delegates to the shared `McpToolSource`. Use `register_mcp_tools`
-//! to list MCP tools and register one adapter per tool into an `AggregateToolSource`.
+//! to list MCP tools and register one adapter per tool into an `AggregateToolSource`,
+//! or `register_mcp_tools_with_prefix` to offer them as `<prefix>_<tool>` when
+//! several servers export the same tool name. The aggregate remembers each server, so
+//! `ToolSource::refresh_tools` on it picks up tools the server added or removed later.
 
 use std::sync::Arc;
 
@@ -24,6 +27,8 @@ use crate::tools::Tool;
 /// `ToolCallContext` and forwards to MCP.
 pub struct McpToolAdapter {
     name: String,
+    /// Name of the tool on the MCP server; differs from `name` when prefixed.
+    remote: String,
     spec: ToolSpec,
     source: Arc<McpToolSource>,
 }
@@ -33,7 +38,19 @@ impl McpToolAdapter {
     ///
     /// **Interaction**: Used by `register_mcp_tools`; not typically called directly.
     pub fn new(name: String, spec: ToolSpec, source: Arc<McpToolSource>) -> Self {
-        Self { name, spec, source }
+        Self {
+            remote: name.clone(),
+            name,
+            spec,
+            source,
+        }
+    }
+
+    /// Offers the tool as `<prefix>_<name>`; calls still use the server's name.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.name = format!("{}_{}", prefix, self.remote);
+        self.spec.name = self.name.clone();
+        self
     }
 }
 
@@ -52,8 +69,57 @@ impl Tool for McpToolAdapter {
         args: serde_json::Value,
         _ctx: Option<&ToolCallContext>,
     ) -> Result<ToolCallContent, ToolSourceError> {
-        self.source.call_tool(self.name.as_str(), args).await
+        self.source.call_tool(self.remote.as_str(), args).await
+    }
+}
+
+/// An MCP server whose tools are registered on an `AggregateToolSource`, and the names they
+/// were registered under.
+pub(crate) struct McpRegistration {
+    pub(crate) source: Arc<McpToolSource>,
+    prefix: Option<String>,
+    names: Vec<String>,
+}
+
+/// Registers one adapter per spec on `aggregate`; returns the names registered.
+async fn register_specs(
+    aggregate: &super::AggregateToolSource,
+    mcp: &Arc<McpToolSource>,
+    specs: Vec<ToolSpec>,
+    prefix: Option<&str>,
+) -> Vec<String> {
+    let mut names = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let name = spec.name.clone();
+        let mut adapter = McpToolAdapter::new(name, spec, Arc::clone(mcp));
+        if let Some(prefix) = prefix {
+            adapter = adapter.with_prefix(prefix);
+        }
+        names.push(adapter.name.clone());
+        aggregate.register_async(Box::new(adapter)).await;
     }
+    names
+}
+
+/// Re-lists the server's tools: registers (or replaces) an adapter per tool, then removes
+/// the adapters of tools the server no longer offers.
+pub(crate) async fn refresh_mcp_registration(
+    aggregate: &super::AggregateToolSource,
+    registration: &mut McpRegistration,
+) -> Result<(), ToolSourceError> {
+    let specs = registration.source.refresh_tools().await?;
+    let names = register_specs(
+        aggregate,
+        &registration.source,
+        specs,
+        registration.prefix.as_deref(),
+    )
+    .await;
+    for stale in registration.names.iter().filter(|n| !names.contains(n)) {
+        aggregate.unregister_async(stale).await;
+    }
+    registration.names = names;
+    Ok(())
 }
 
 /// Registers all tools from the MCP server into the given aggregate.
@@ -68,12 +134,29 @@ impl Tool for McpToolAdapter {
 pub async fn register_mcp_tools(
     aggregate: &super::AggregateToolSource,
     mcp: Arc<McpToolSource>,
+) -> Result<(), ToolSourceError> {
+    register_mcp_tools_with_prefix(aggregate, mcp, None).await
+}
+
+/// Like [`register_mcp_tools`], but with `prefix` each tool is registered as
+/// `<prefix>_<tool>`, so tools of the same name from two servers do not replace
+/// each other.
+///
+/// **Interaction**: Used by `build_tool_source` for every configured MCP server,
+/// with its `McpServerConfig::tool_prefix`.
+pub async fn register_mcp_tools_with_prefix(
+    aggregate: &super::AggregateToolSource,
+    mcp: Arc<McpToolSource>,
+    prefix: Option<&str>,
 ) -> Result<(), ToolSourceError> {
     let specs = mcp.list_tools().await?;
-    for spec in specs {
-        let name = spec.name.clone();
-        let adapter = McpToolAdapter::new(name, spec, Arc::clone(&mcp));
-        aggregate.register_async(Box::new(adapter)).await;
-    }
+    let names = register_specs(aggregate, &mcp, specs, prefix).await;
+    aggregate
+        .track_mcp(McpRegistration {
+            source: mcp,
+            prefix: prefix.map(String::from),
+            names,
+        })
+        .await;
     Ok(())
 }
@@ -0,0 +1,111 @@
+use serde_json::Value;
+
+use crate::memory::{Namespace, SearchOptions, Store, StoreError};
+
+/// Default similarity above which `remember` treats a fact as already stored.
+pub const DEFAULT_DEDUP_THRESHOLD: f64 = 0.9;
+
+/// What `remember` does with a fact similar to a stored one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateAction {
+    /// Overwrite the similar entry with the new value (e.g. "likes tea now").
+    #[default]
+    Update,
+    /// Keep the similar entry and write nothing.
+    Skip,
+}
+
+/// Semantic deduplication for [`RememberTool`](super::RememberTool).
+///
+/// Before a write, the store is searched with the new value; when the best match in the
+/// namespace (under another key) scores at least `threshold`, the fact counts as a duplicate
+/// and is handled per `on_duplicate` instead of being added under a new key. Scores are the
+/// store's: cosine similarity for vector stores. Stores that return no scores (no vector index)
+/// never report duplicates.
+///
+/// **Interaction**: Set with [`RememberTool::with_dedup`](super::RememberTool::with_dedup) or
+/// [`MemoryToolkit::with_dedup`](crate::tool_source::MemoryToolkit::with_dedup); the tool
+/// reports the path taken as a [`RememberOutcome`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryDedup {
+    /// Lowest score of a duplicate.
+    pub threshold: f64,
+    /// What happens to a duplicate.
+    pub on_duplicate: DuplicateAction,
+}
+
+impl Default for MemoryDedup {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_THRESHOLD)
+    }
+}
+
+impl MemoryDedup {
+    /// Duplicates scoring at least `threshold` update the similar entry.
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            on_duplicate: DuplicateAction::Update,
+        }
+    }
+
+    /// Sets what happens to a duplicate (default [`DuplicateAction::Update`]).
+    pub fn with_action(mut self, on_duplicate: DuplicateAction) -> Self {
+        self.on_duplicate = on_duplicate;
+        self
+    }
+
+    /// The stored entry of `namespace` most similar to `value`, other than `key`, with its
+    /// score, when it scores at least the threshold.
+    pub(crate) async fn find_duplicate(
+        &self,
+        store: &dyn Store,
+        namespace: &Namespace,
+        key: &str,
+        value: &Value,
+    ) -> Result<Option<(String, f64)>, StoreError> {
+        // The same text the vector stores embed for a value.
+        let query = value
+            .get("text")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| value.to_string());
+        let options = SearchOptions::new().with_query(query).with_limit(5);
+        let hits = store.search(namespace, options).await?;
+        Ok(hits
+            .into_iter()
+            .filter(|hit| hit.item.namespace == *namespace && hit.item.key != key)
+            .filter_map(|hit| hit.score.map(|score| (hit.item.key, score)))
+            .filter(|(_, score)| *score >= self.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1)))
+    }
+}
+
+/// The path `remember` took for a fact.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RememberOutcome {
+    /// Written under its own key.
+    Saved { key: String },
+    /// A similar entry was overwritten with the new value.
+    Updated { key: String, score: f64 },
+    /// A similar entry exists; nothing was written.
+    Skipped { key: String, score: f64 },
+}
+
+impl RememberOutcome {
+    /// `saved`, `updated` or `skipped`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Saved { .. } => "saved",
+            Self::Updated { .. } => "updated",
+            Self::Skipped { .. } => "skipped",
+        }
+    }
+
+    /// Key the fact is stored under.
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Saved { key } | Self::Updated { key, .. } | Self::Skipped { key, .. } => key,
+        }
+    }
+}
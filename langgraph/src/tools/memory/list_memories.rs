@@ -43,10 +43,11 @@ pub const TOOL_LIST_MEMORIES: &str = "list_memories";
 /// - **Store**: Lists keys via Store::list()
 /// - **Namespace**: Isolates storage per user/context
 /// - **ToolRegistry**: Registers this tool by name "list_memories"
-/// - **StoreToolSource**: Uses this tool via AggregateToolSource
+/// - **MemoryToolkit**: Registers this tool on an AggregateToolSource
 pub struct ListMemoriesTool {
     store: std::sync::Arc<dyn Store>,
     namespace: Namespace,
+    name: String,
 }
 
 impl ListMemoriesTool {
@@ -69,19 +70,29 @@ impl ListMemoriesTool {
     /// let tool = ListMemoriesTool::new(store, namespace);
     /// ```
     pub fn new(store: std::sync::Arc<dyn Store>, namespace: Namespace) -> Self {
-        Self { store, namespace }
+        Self {
+            store,
+            namespace,
+            name: TOOL_LIST_MEMORIES.to_string(),
+        }
+    }
+
+    /// Exposes the tool under `name` instead of `"list_memories"` (e.g. a prefixed name).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
     }
 }
 
 #[async_trait]
 impl Tool for ListMemoriesTool {
     fn name(&self) -> &str {
-        TOOL_LIST_MEMORIES
+        &self.name
     }
 
     fn spec(&self) -> crate::tool_source::ToolSpec {
         crate::tool_source::ToolSpec {
-            name: TOOL_LIST_MEMORIES.to_string(),
+            name: self.name.clone(),
             description: Some(
                 "List all memory keys in the current namespace. Call when you need to see what \
                  has been stored before recalling or searching."
@@ -110,6 +121,9 @@ impl Tool for ListMemoriesTool {
                 crate::memory::StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
                 crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
                 crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
+                e @ crate::memory::StoreError::EmbedderMismatch { .. } => {
+                    ToolSourceError::Transport(e.to_string())
+                }
             })?;
 
         Ok(ToolCallContent {
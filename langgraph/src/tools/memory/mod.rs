@@ -1,9 +1,13 @@
+mod dedup;
 mod list_memories;
 mod recall;
 mod remember;
 mod search_memories;
+mod value_schema;
 
+pub use dedup::{DuplicateAction, MemoryDedup, RememberOutcome, DEFAULT_DEDUP_THRESHOLD};
 pub use list_memories::{ListMemoriesTool, TOOL_LIST_MEMORIES};
 pub use recall::{RecallTool, TOOL_RECALL};
 pub use remember::{RememberTool, TOOL_REMEMBER};
 pub use search_memories::{SearchMemoriesTool, TOOL_SEARCH_MEMORIES};
+pub use value_schema::MemoryValueSchema;
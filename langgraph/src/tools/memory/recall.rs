@@ -6,6 +6,8 @@ use crate::memory::{Namespace, Store};
 use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
 use crate::tools::Tool;
 
+use super::MemoryValueSchema;
+
 /// Tool name for the recall operation.
 pub const TOOL_RECALL: &str = "recall";
 
@@ -41,10 +43,12 @@ pub const TOOL_RECALL: &str = "recall";
 /// - **Store**: Retrieves values via Store::get()
 /// - **Namespace**: Isolates storage per user/context
 /// - **ToolRegistry**: Registers this tool by name "recall"
-/// - **StoreToolSource**: Uses this tool via AggregateToolSource
+/// - **MemoryToolkit**: Registers this tool on an AggregateToolSource
 pub struct RecallTool {
     store: std::sync::Arc<dyn Store>,
     namespace: Namespace,
+    name: String,
+    value_schema: MemoryValueSchema,
 }
 
 impl RecallTool {
@@ -67,19 +71,36 @@ impl RecallTool {
     /// let tool = RecallTool::new(store, namespace);
     /// ```
     pub fn new(store: std::sync::Arc<dyn Store>, namespace: Namespace) -> Self {
-        Self { store, namespace }
+        Self {
+            store,
+            namespace,
+            name: TOOL_RECALL.to_string(),
+            value_schema: MemoryValueSchema::default(),
+        }
+    }
+
+    /// Exposes the tool under `name` instead of `"recall"` (e.g. a prefixed name).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the shape of stored values; see [`MemoryValueSchema`].
+    pub fn with_value_schema(mut self, value_schema: MemoryValueSchema) -> Self {
+        self.value_schema = value_schema;
+        self
     }
 }
 
 #[async_trait]
 impl Tool for RecallTool {
     fn name(&self) -> &str {
-        TOOL_RECALL
+        &self.name
     }
 
     fn spec(&self) -> crate::tool_source::ToolSpec {
         crate::tool_source::ToolSpec {
-            name: TOOL_RECALL.to_string(),
+            name: self.name.clone(),
             description: Some(
                 "Read a value by key from long-term memory. Call when you need to retrieve something \
                  previously stored with remember.".to_string(),
@@ -115,10 +136,16 @@ impl Tool for RecallTool {
                 crate::memory::StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
                 crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
                 crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
+                e @ crate::memory::StoreError::EmbedderMismatch { .. } => {
+                    ToolSourceError::Transport(e.to_string())
+                }
             })?;
 
         let text = match opt {
-            Some(v) => v.to_string(),
+            Some(v) => match (self.value_schema, self.value_schema.decode(v)) {
+                (MemoryValueSchema::Text, serde_json::Value::String(text)) => text,
+                (_, v) => v.to_string(),
+            },
             None => return Err(ToolSourceError::NotFound("key not found".to_string())),
         };
 
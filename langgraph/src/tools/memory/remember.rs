@@ -2,10 +2,12 @@ use async_trait::async_trait;
 
 use serde_json::json;
 
-use crate::memory::{Namespace, Store};
+use crate::memory::{Namespace, Store, StoreError};
 use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
 use crate::tools::Tool;
 
+use super::{DuplicateAction, MemoryDedup, MemoryValueSchema, RememberOutcome};
+
 /// Tool name for the remember operation.
 pub const TOOL_REMEMBER: &str = "remember";
 
@@ -42,10 +44,14 @@ pub const TOOL_REMEMBER: &str = "remember";
 /// - **Store**: Stores key-value pairs via Store::put()
 /// - **Namespace**: Isolates storage per user/context
 /// - **ToolRegistry**: Registers this tool by name "remember"
-/// - **StoreToolSource**: Uses this tool via AggregateToolSource
+/// - **MemoryToolkit**: Registers this tool on an AggregateToolSource
+/// - **MemoryDedup**: Optionally updates or skips facts similar to a stored one
 pub struct RememberTool {
     store: std::sync::Arc<dyn Store>,
     namespace: Namespace,
+    name: String,
+    value_schema: MemoryValueSchema,
+    dedup: Option<MemoryDedup>,
 }
 
 impl RememberTool {
@@ -68,19 +74,82 @@ impl RememberTool {
     /// let tool = RememberTool::new(store, namespace);
     /// ```
     pub fn new(store: std::sync::Arc<dyn Store>, namespace: Namespace) -> Self {
-        Self { store, namespace }
+        Self {
+            store,
+            namespace,
+            name: TOOL_REMEMBER.to_string(),
+            value_schema: MemoryValueSchema::default(),
+            dedup: None,
+        }
+    }
+
+    /// Exposes the tool under `name` instead of `"remember"` (e.g. a prefixed name).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the shape of stored values; see [`MemoryValueSchema`].
+    pub fn with_value_schema(mut self, value_schema: MemoryValueSchema) -> Self {
+        self.value_schema = value_schema;
+        self
+    }
+
+    /// Searches for a similar stored fact before each write and updates or skips it instead
+    /// of adding a near-duplicate; see [`MemoryDedup`].
+    pub fn with_dedup(mut self, dedup: MemoryDedup) -> Self {
+        self.dedup = Some(dedup);
+        self
+    }
+
+    /// Writes `value` under `key`, or per the dedup setting when a similar fact is stored.
+    pub async fn remember(
+        &self,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<RememberOutcome, StoreError> {
+        let duplicate = match &self.dedup {
+            Some(dedup) => dedup
+                .find_duplicate(self.store.as_ref(), &self.namespace, key, value)
+                .await?
+                .map(|(key, score)| (dedup.on_duplicate, key, score)),
+            None => None,
+        };
+        match duplicate {
+            Some((DuplicateAction::Skip, key, score)) => {
+                Ok(RememberOutcome::Skipped { key, score })
+            }
+            Some((DuplicateAction::Update, key, score)) => {
+                self.store.put(&self.namespace, &key, value).await?;
+                Ok(RememberOutcome::Updated { key, score })
+            }
+            None => {
+                self.store.put(&self.namespace, key, value).await?;
+                Ok(RememberOutcome::Saved {
+                    key: key.to_string(),
+                })
+            }
+        }
     }
 }
 
 #[async_trait]
 impl Tool for RememberTool {
     fn name(&self) -> &str {
-        TOOL_REMEMBER
+        &self.name
     }
 
     fn spec(&self) -> crate::tool_source::ToolSpec {
+        let (key_description, required) = if self.value_schema.requires_key() {
+            ("Memory key", json!(["key", "value"]))
+        } else {
+            (
+                "Memory key (optional; generated when missing)",
+                json!(["value"]),
+            )
+        };
         crate::tool_source::ToolSpec {
-            name: TOOL_REMEMBER.to_string(),
+            name: self.name.clone(),
             description: Some(
                 "Write a key-value pair to long-term memory. Call when: the user expresses a preference, \
                  the user explicitly asks to remember something, or existing memory should be updated.".to_string(),
@@ -88,10 +157,10 @@ impl Tool for RememberTool {
             input_schema: json!({
                 "type": "object",
                 "properties": {
-                    "key": { "type": "string", "description": "Memory key" },
-                    "value": { "description": "Value (any JSON)" }
+                    "key": { "type": "string", "description": key_description },
+                    "value": self.value_schema.value_property()
                 },
-                "required": ["key", "value"]
+                "required": required
             }),
         }
     }
@@ -101,29 +170,37 @@ impl Tool for RememberTool {
         args: serde_json::Value,
         _ctx: Option<&ToolCallContext>,
     ) -> Result<ToolCallContent, ToolSourceError> {
-        let key = args
-            .get("key")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolSourceError::InvalidInput("missing key".to_string()))?;
-        let value = args
-            .get("value")
-            .cloned()
-            .unwrap_or(serde_json::Value::Null);
+        let (key, generated) = match args.get("key").and_then(|v| v.as_str()) {
+            Some(key) => (key.to_string(), false),
+            None if !self.value_schema.requires_key() => (MemoryValueSchema::generate_key(), true),
+            None => return Err(ToolSourceError::InvalidInput("missing key".to_string())),
+        };
+        let value = self.value_schema.encode(
+            args.get("value")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+        )?;
 
-        self.store
-            .put(&self.namespace, key, &value)
-            .await
-            .map_err(|e| match e {
-                crate::memory::StoreError::NotFound => {
-                    ToolSourceError::NotFound("key not found".to_string())
-                }
-                crate::memory::StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
-                crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
-                crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
-            })?;
+        let outcome = self.remember(&key, &value).await.map_err(|e| match e {
+            StoreError::NotFound => ToolSourceError::NotFound("key not found".to_string()),
+            StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
+            StoreError::Storage(s) => ToolSourceError::Transport(s),
+            StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
+            e @ StoreError::EmbedderMismatch { .. } => ToolSourceError::Transport(e.to_string()),
+        })?;
 
-        Ok(ToolCallContent {
-            text: "ok".to_string(),
-        })
+        // A generated key is the only handle for a later recall, so report it; so is the key
+        // of the similar memory a deduplicated fact went to.
+        let text = match outcome {
+            RememberOutcome::Saved { key } if generated => format!("ok (key: {})", key),
+            RememberOutcome::Saved { .. } => "ok".to_string(),
+            RememberOutcome::Updated { key, .. } => {
+                format!("updated similar memory (key: {})", key)
+            }
+            RememberOutcome::Skipped { key, .. } => {
+                format!("skipped: similar memory exists (key: {})", key)
+            }
+        };
+        Ok(ToolCallContent { text })
     }
 }
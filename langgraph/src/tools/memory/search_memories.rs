@@ -6,6 +6,8 @@ use crate::memory::{Namespace, Store};
 use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
 use crate::tools::Tool;
 
+use super::MemoryValueSchema;
+
 /// Tool name for the search_memories operation.
 pub const TOOL_SEARCH_MEMORIES: &str = "search_memories";
 
@@ -42,10 +44,12 @@ pub const TOOL_SEARCH_MEMORIES: &str = "search_memories";
 /// - **Store**: Performs semantic search via Store::search()
 /// - **Namespace**: Isolates storage per user/context
 /// - **ToolRegistry**: Registers this tool by name "search_memories"
-/// - **StoreToolSource**: Uses this tool via AggregateToolSource
+/// - **MemoryToolkit**: Registers this tool on an AggregateToolSource
 pub struct SearchMemoriesTool {
     store: std::sync::Arc<dyn Store>,
     namespace: Namespace,
+    name: String,
+    value_schema: MemoryValueSchema,
 }
 
 impl SearchMemoriesTool {
@@ -68,19 +72,36 @@ impl SearchMemoriesTool {
     /// let tool = SearchMemoriesTool::new(store, namespace);
     /// ```
     pub fn new(store: std::sync::Arc<dyn Store>, namespace: Namespace) -> Self {
-        Self { store, namespace }
+        Self {
+            store,
+            namespace,
+            name: TOOL_SEARCH_MEMORIES.to_string(),
+            value_schema: MemoryValueSchema::default(),
+        }
+    }
+
+    /// Exposes the tool under `name` instead of `"search_memories"` (e.g. a prefixed name).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the shape of stored values; see [`MemoryValueSchema`].
+    pub fn with_value_schema(mut self, value_schema: MemoryValueSchema) -> Self {
+        self.value_schema = value_schema;
+        self
     }
 }
 
 #[async_trait]
 impl Tool for SearchMemoriesTool {
     fn name(&self) -> &str {
-        TOOL_SEARCH_MEMORIES
+        &self.name
     }
 
     fn spec(&self) -> crate::tool_source::ToolSpec {
         crate::tool_source::ToolSpec {
-            name: TOOL_SEARCH_MEMORIES.to_string(),
+            name: self.name.clone(),
             description: Some(
                 "Search long-term memories by query (optional) and limit (optional). Call when you need \
                  to find relevant past information before answering or acting.".to_string(),
@@ -118,6 +139,9 @@ impl Tool for SearchMemoriesTool {
                 crate::memory::StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
                 crate::memory::StoreError::Storage(s) => ToolSourceError::Transport(s),
                 crate::memory::StoreError::EmbeddingError(s) => ToolSourceError::Transport(s),
+                e @ crate::memory::StoreError::EmbedderMismatch { .. } => {
+                    ToolSourceError::Transport(e.to_string())
+                }
             })?;
 
         let arr: Vec<serde_json::Value> = hits
@@ -125,7 +149,7 @@ impl Tool for SearchMemoriesTool {
             .map(|h| {
                 json!({
                     "key": h.key,
-                    "value": h.value,
+                    "value": self.value_schema.decode(h.value),
                     "score": h.score
                 })
             })
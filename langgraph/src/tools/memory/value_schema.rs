@@ -0,0 +1,73 @@
+use serde_json::{json, Value};
+
+use crate::memory::uuid6;
+use crate::tool_source::ToolSourceError;
+
+/// Shape of the values the memory tools write to and read from the store.
+///
+/// - **Json** (default): `remember` takes any JSON `value` and stores it as is; `recall` and
+///   `search_memories` return it unchanged.
+/// - **Text**: `remember` takes a string `value` (and an optional `key`, generated when missing)
+///   and stores `{"text": ..., "saved_at": <unix secs>}`; reads return the text only. Suits
+///   free-form facts such as "likes coffee" and keeps every entry embeddable the same way.
+///
+/// **Interaction**: Set on [`RememberTool`](super::RememberTool), [`RecallTool`](super::RecallTool)
+/// and [`SearchMemoriesTool`](super::SearchMemoriesTool), usually through
+/// [`MemoryToolkit::with_value_schema`](crate::tool_source::MemoryToolkit::with_value_schema).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoryValueSchema {
+    #[default]
+    Json,
+    Text,
+}
+
+impl MemoryValueSchema {
+    /// Input schema of the `value` argument of `remember`.
+    pub(crate) fn value_property(self) -> Value {
+        match self {
+            Self::Json => json!({ "description": "Value (any JSON)" }),
+            Self::Text => {
+                json!({ "type": "string", "description": "Fact to remember, in plain text" })
+            }
+        }
+    }
+
+    /// Whether `remember` requires a `key` (Text generates one when missing).
+    pub(crate) fn requires_key(self) -> bool {
+        matches!(self, Self::Json)
+    }
+
+    /// Key for a `remember` call without one.
+    pub(crate) fn generate_key() -> String {
+        format!("memory_{}", uuid6())
+    }
+
+    /// Value to store for the `value` argument of `remember`.
+    pub(crate) fn encode(self, value: Value) -> Result<Value, ToolSourceError> {
+        match self {
+            Self::Json => Ok(value),
+            Self::Text => {
+                let text = value.as_str().ok_or_else(|| {
+                    ToolSourceError::InvalidInput("value must be a string".to_string())
+                })?;
+                let saved_at = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                Ok(json!({ "text": text, "saved_at": saved_at }))
+            }
+        }
+    }
+
+    /// Value shown to the model for a stored entry. Text entries written some other way are
+    /// returned unchanged.
+    pub(crate) fn decode(self, stored: Value) -> Value {
+        match self {
+            Self::Text => match stored.get("text") {
+                Some(Value::String(text)) => Value::String(text.clone()),
+                _ => stored,
+            },
+            Self::Json => stored,
+        }
+    }
+}
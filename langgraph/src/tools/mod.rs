@@ -1,21 +1,54 @@
 mod aggregate_source;
+mod attachments;
 pub mod bash;
+#[cfg(feature = "browser")]
+pub mod browser;
+#[cfg(feature = "wasm-sandbox")]
+pub mod code_interpreter;
 mod conversation;
+pub mod fs;
 mod mcp_adapter;
 pub mod memory;
+pub mod notify;
 mod registry;
+pub mod reminders;
+mod retriever;
+#[cfg(feature = "sql-tool")]
+pub mod sql;
+mod time;
 mod r#trait;
 pub mod web;
 
 pub use aggregate_source::AggregateToolSource;
+pub use attachments::{FetchAttachmentTool, DEFAULT_FETCH_LIMIT, TOOL_FETCH_ATTACHMENT};
 pub use bash::{BashTool, TOOL_BASH};
+#[cfg(feature = "browser")]
+pub use browser::{BrowserTool, DomainPolicy, TOOL_BROWSER};
+#[cfg(feature = "wasm-sandbox")]
+pub use code_interpreter::{CodeInterpreterTool, WasmInterpreter, TOOL_CODE_INTERPRETER};
 pub use conversation::{GetRecentMessagesTool, TOOL_GET_RECENT_MESSAGES};
+pub use fs::{
+    FsJail, GlobTool, ListDirTool, ReadFileTool, WriteFileTool, TOOL_GLOB, TOOL_LIST_DIR,
+    TOOL_READ_FILE, TOOL_WRITE_FILE,
+};
 pub use memory::{
-    ListMemoriesTool, RecallTool, RememberTool, SearchMemoriesTool, TOOL_LIST_MEMORIES,
-    TOOL_RECALL, TOOL_REMEMBER, TOOL_SEARCH_MEMORIES,
+    DuplicateAction, ListMemoriesTool, MemoryDedup, MemoryValueSchema, RecallTool, RememberOutcome,
+    RememberTool, SearchMemoriesTool, TOOL_LIST_MEMORIES, TOOL_RECALL, TOOL_REMEMBER,
+    TOOL_SEARCH_MEMORIES,
 };
+#[cfg(feature = "email")]
+pub use notify::{SendEmailTool, SmtpConfig, TOOL_SEND_EMAIL};
+pub use notify::{SendWebhookTool, SlackMessageTool, TOOL_SEND_WEBHOOK, TOOL_SLACK_MESSAGE};
 pub use r#trait::Tool;
 pub use registry::{ToolRegistry, ToolRegistryLocked};
+pub use reminders::{
+    register_reminder_tools, CancelReminderTool, CreateReminderTool, ListRemindersTool,
+    TOOL_CANCEL_REMINDER, TOOL_CREATE_REMINDER, TOOL_LIST_REMINDERS,
+};
+pub use retriever::{RetrieverTool, TOOL_RETRIEVE};
+#[cfg(feature = "sql-tool")]
+pub use sql::{SqlTool, SqlToolError, TOOL_DESCRIBE_SCHEMA, TOOL_RUN_SQL};
+pub use time::{Clock, FixedClock, SystemClock, TimeTool, TOOL_GET_TIME};
 pub use web::{WebFetcherTool, TOOL_WEB_FETCHER};
 
-pub use mcp_adapter::{register_mcp_tools, McpToolAdapter};
+pub use mcp_adapter::{register_mcp_tools, register_mcp_tools_with_prefix, McpToolAdapter};
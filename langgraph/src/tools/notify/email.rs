@@ -0,0 +1,250 @@
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::{json, Value};
+
+use super::required_str;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for sending an email over SMTP.
+pub const TOOL_SEND_EMAIL: &str = "send_email";
+
+/// SMTP server and sender used by [`SendEmailTool`].
+///
+/// Connects with STARTTLS (port 587 unless [`with_port`](Self::with_port) is called).
+#[derive(Clone)]
+pub struct SmtpConfig {
+    /// SMTP server host name.
+    pub host: String,
+    /// SMTP port; `None` uses the STARTTLS default (587).
+    pub port: Option<u16>,
+    /// Login user and password, when the server needs them.
+    pub credentials: Option<(String, String)>,
+    /// Sender address, e.g. `"Agent <agent@example.com>"`.
+    pub from: String,
+}
+
+impl std::fmt::Debug for SmtpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("user", &self.credentials.as_ref().map(|(user, _)| user))
+            .field("from", &self.from)
+            .finish()
+    }
+}
+
+impl SmtpConfig {
+    /// Server `host` sending as `from`, without login.
+    pub fn new(host: impl Into<String>, from: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port: None,
+            credentials: None,
+            from: from.into(),
+        }
+    }
+
+    /// Sets the SMTP port.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the login user and password.
+    pub fn with_credentials(
+        mut self,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((user.into(), password.into()));
+        self
+    }
+}
+
+/// Tool that sends a plain-text email to allowlisted recipients.
+///
+/// Allowlist entries are addresses (`ops@example.com`) or whole domains (`@example.com`),
+/// compared case-insensitively. A message with any recipient outside the list is refused.
+///
+/// # Interaction
+///
+/// - **NotifyToolSource**: Built by `with_email`.
+/// - **ToolSourceError**: `InvalidInput` for bad arguments and refused recipients; `Transport`
+///   for SMTP failures.
+pub struct SendEmailTool {
+    config: SmtpConfig,
+    allowed_recipients: Vec<String>,
+}
+
+impl SendEmailTool {
+    /// Tool sending through `config` to `allowed_recipients` only.
+    pub fn new(config: SmtpConfig, allowed_recipients: Vec<String>) -> Self {
+        Self {
+            config,
+            allowed_recipients: allowed_recipients
+                .into_iter()
+                .map(|r| r.trim().to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Whether `address` matches the allowlist.
+    fn allows(&self, address: &str) -> bool {
+        let address = address.to_ascii_lowercase();
+        self.allowed_recipients.iter().any(|entry| {
+            if entry.starts_with('@') {
+                address.ends_with(entry.as_str())
+            } else {
+                address == *entry
+            }
+        })
+    }
+
+    /// Recipients from the `to` argument (one address or a list), all checked.
+    fn recipients(&self, args: &Value) -> Result<Vec<Mailbox>, ToolSourceError> {
+        let to: Vec<&str> = match args.get("to") {
+            Some(Value::String(one)) => vec![one.as_str()],
+            Some(Value::Array(many)) => many.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        if to.is_empty() {
+            return Err(ToolSourceError::InvalidInput("missing to".to_string()));
+        }
+        to.into_iter()
+            .map(|raw| {
+                let mailbox: Mailbox = raw.parse().map_err(|e| {
+                    ToolSourceError::InvalidInput(format!("invalid address {:?}: {}", raw, e))
+                })?;
+                if !self.allows(&mailbox.email.to_string()) {
+                    return Err(ToolSourceError::InvalidInput(format!(
+                        "recipient not allowed: {}",
+                        mailbox.email
+                    )));
+                }
+                Ok(mailbox)
+            })
+            .collect()
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, ToolSourceError> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.config.host)
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        if let Some(port) = self.config.port {
+            builder = builder.port(port);
+        }
+        if let Some((user, password)) = &self.config.credentials {
+            builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[async_trait]
+impl Tool for SendEmailTool {
+    fn name(&self) -> &str {
+        TOOL_SEND_EMAIL
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_SEND_EMAIL.to_string(),
+            description: Some(format!(
+                "Send a plain-text email. Allowed recipients: {}.",
+                self.allowed_recipients.join(", ")
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "to": {
+                        "description": "Recipient address, or a list of addresses",
+                        "anyOf": [
+                            { "type": "string" },
+                            { "type": "array", "items": { "type": "string" } }
+                        ]
+                    },
+                    "subject": { "type": "string", "description": "Subject line" },
+                    "body": { "type": "string", "description": "Message text" }
+                },
+                "required": ["to", "subject", "body"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let recipients = self.recipients(&args)?;
+        let subject = required_str(&args, "subject")?;
+        let body = required_str(&args, "body")?;
+        let from: Mailbox = self
+            .config
+            .from
+            .parse()
+            .map_err(|e| ToolSourceError::Transport(format!("invalid sender: {}", e)))?;
+
+        let mut message = Message::builder().from(from).subject(subject);
+        for to in &recipients {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .map_err(|e| ToolSourceError::InvalidInput(e.to_string()))?;
+        self.transport()?
+            .send(message)
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+
+        let sent: Vec<String> = recipients.iter().map(|r| r.email.to_string()).collect();
+        Ok(ToolCallContent {
+            text: format!("email sent to {}", sent.join(", ")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Addresses and @domain entries are matched case-insensitively; a message
+    /// with one recipient outside the list is refused before connecting.
+    #[tokio::test]
+    async fn refuses_recipients_outside_allowlist() {
+        let tool = SendEmailTool::new(
+            SmtpConfig::new("smtp.invalid", "agent@example.com"),
+            vec!["Ops@Example.com".into(), "@team.example.com".into()],
+        );
+        assert!(tool.allows("ops@example.com"));
+        assert!(tool.allows("ada@team.example.com"));
+        assert!(!tool.allows("ada@example.com"));
+        assert!(!tool.allows("ada@evilteam.example.com.io"));
+
+        let err = tool
+            .call(
+                json!({
+                    "to": ["ops@example.com", "Mallory <mallory@evil.io>"],
+                    "subject": "report",
+                    "body": "done"
+                }),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolSourceError::InvalidInput(m) if m == "recipient not allowed: mallory@evil.io"),
+            "{}",
+            err
+        );
+        assert!(matches!(
+            tool.call(json!({"subject": "s", "body": "b"}), None).await,
+            Err(ToolSourceError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,124 @@
+//! Notification tools: deliver results out-of-band by webhook, Slack or email.
+//!
+//! Every destination is configured by the deployment, never chosen freely by the model:
+//! webhooks and Slack channels are registered by name and the model only picks a name;
+//! email recipients must match an allowlist. `send_email` needs the `email` feature (SMTP via
+//! lettre); the webhook and Slack tools use the HTTP client already used by `web_fetcher`.
+//!
+//! **Interaction**: Built and registered by
+//! [`NotifyToolSource`](crate::tool_source::NotifyToolSource).
+
+#[cfg(feature = "email")]
+mod email;
+mod slack;
+mod webhook;
+
+#[cfg(feature = "email")]
+pub use email::{SendEmailTool, SmtpConfig, TOOL_SEND_EMAIL};
+pub use slack::{SlackMessageTool, TOOL_SLACK_MESSAGE};
+pub use webhook::{SendWebhookTool, TOOL_SEND_WEBHOOK};
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::tool_source::ToolSourceError;
+
+/// Posts `body` as JSON to `url`; non-2xx responses are transport errors.
+pub(crate) async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    body: &Value,
+) -> Result<(), ToolSourceError> {
+    let response = client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(ToolSourceError::Transport(format!(
+            "HTTP {}: {}",
+            status.as_u16(),
+            text
+        )));
+    }
+    Ok(())
+}
+
+/// Looks up the destination `name` among the configured ones.
+pub(crate) fn destination<'a>(
+    destinations: &'a BTreeMap<String, String>,
+    kind: &str,
+    name: &str,
+) -> Result<&'a str, ToolSourceError> {
+    destinations.get(name).map(String::as_str).ok_or_else(|| {
+        let known: Vec<&str> = destinations.keys().map(String::as_str).collect();
+        ToolSourceError::InvalidInput(format!(
+            "unknown {} {:?}; configured: {}",
+            kind,
+            name,
+            known.join(", ")
+        ))
+    })
+}
+
+/// Required string argument `key` of `args`.
+pub(crate) fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str, ToolSourceError> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ToolSourceError::InvalidInput(format!("missing {}", key)))
+}
+
+#[cfg(test)]
+pub(crate) mod test_server {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Accepts one HTTP request on a local port, answers with `status` and returns the body.
+    pub async fn one_shot(status: u16) -> (String, JoinHandle<String>) {
+        one_shot_json(status, String::new()).await
+    }
+
+    /// Like [`one_shot`], answering with the JSON `reply`.
+    pub async fn one_shot_json(status: u16, reply: String) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|l| {
+                            let (name, value) = l.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length || n == 0 {
+                        break body.to_string();
+                    }
+                }
+            };
+            let reply = format!(
+                "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\
+                 connection: close\r\n\r\n{}",
+                status,
+                reply.len(),
+                reply
+            );
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            body
+        });
+        (url, handle)
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{destination, post_json, required_str};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for posting a message to a configured Slack channel.
+pub const TOOL_SLACK_MESSAGE: &str = "slack_message";
+
+/// Tool that posts a text message to one of the configured Slack channels.
+///
+/// Each channel is a Slack incoming webhook URL registered under the channel name; the model
+/// picks the `channel` and writes the `text` (Slack mrkdwn).
+///
+/// # Interaction
+///
+/// - **NotifyToolSource**: Built by `with_slack_channel`.
+/// - **ToolSourceError**: `InvalidInput` for an unknown channel or missing text; `Transport`
+///   for network errors and non-2xx responses.
+pub struct SlackMessageTool {
+    client: reqwest::Client,
+    channels: BTreeMap<String, String>,
+}
+
+impl SlackMessageTool {
+    /// Tool posting to `channels` (channel name → incoming webhook URL) with `client`.
+    pub fn new(client: reqwest::Client, channels: BTreeMap<String, String>) -> Self {
+        Self { client, channels }
+    }
+}
+
+#[async_trait]
+impl Tool for SlackMessageTool {
+    fn name(&self) -> &str {
+        TOOL_SLACK_MESSAGE
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        let channels: Vec<&String> = self.channels.keys().collect();
+        crate::tool_source::ToolSpec {
+            name: TOOL_SLACK_MESSAGE.to_string(),
+            description: Some(
+                "Post a message to a Slack channel. Only the listed channels can be used."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "channel": {
+                        "type": "string",
+                        "enum": channels,
+                        "description": "Slack channel"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "Message text (Slack mrkdwn)"
+                    }
+                },
+                "required": ["channel", "text"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let channel = required_str(&args, "channel")?;
+        let url = destination(&self.channels, "Slack channel", channel)?;
+        let text = required_str(&args, "text")?;
+        post_json(&self.client, url, &json!({ "text": text })).await?;
+        Ok(ToolCallContent {
+            text: format!("posted to {}", channel),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::notify::test_server;
+
+    /// **Scenario**: The message is posted as {"text"} to the channel's webhook; the spec lists
+    /// the configured channels.
+    #[tokio::test]
+    async fn posts_text_to_configured_channel() {
+        let (url, server) = test_server::one_shot(200).await;
+        let tool = SlackMessageTool::new(
+            reqwest::Client::new(),
+            BTreeMap::from([("#alerts".to_string(), url)]),
+        );
+        assert_eq!(
+            tool.spec().input_schema["properties"]["channel"]["enum"],
+            json!(["#alerts"])
+        );
+
+        let text = tool
+            .call(json!({"channel": "#alerts", "text": "build *green*"}), None)
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "posted to #alerts");
+        let body: Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(body, json!({"text": "build *green*"}));
+
+        assert!(matches!(
+            tool.call(json!({"channel": "#general", "text": "hi"}), None)
+                .await,
+            Err(ToolSourceError::InvalidInput(_))
+        ));
+    }
+}
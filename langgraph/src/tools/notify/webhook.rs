@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{destination, post_json, required_str};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::Tool;
+
+/// Tool name for posting a JSON payload to a configured webhook.
+pub const TOOL_SEND_WEBHOOK: &str = "send_webhook";
+
+/// Tool that posts a JSON payload to one of the configured webhooks.
+///
+/// The model picks a webhook by name (`webhook`) and passes the `payload`; URLs are never
+/// taken from the model.
+///
+/// # Interaction
+///
+/// - **NotifyToolSource**: Built by `with_webhook`.
+/// - **ToolSourceError**: `InvalidInput` for an unknown name or missing payload; `Transport`
+///   for network errors and non-2xx responses.
+pub struct SendWebhookTool {
+    client: reqwest::Client,
+    webhooks: BTreeMap<String, String>,
+}
+
+impl SendWebhookTool {
+    /// Tool posting to `webhooks` (name → URL) with `client`.
+    pub fn new(client: reqwest::Client, webhooks: BTreeMap<String, String>) -> Self {
+        Self { client, webhooks }
+    }
+}
+
+#[async_trait]
+impl Tool for SendWebhookTool {
+    fn name(&self) -> &str {
+        TOOL_SEND_WEBHOOK
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        let names: Vec<&String> = self.webhooks.keys().collect();
+        crate::tool_source::ToolSpec {
+            name: TOOL_SEND_WEBHOOK.to_string(),
+            description: Some(
+                "Send a JSON payload to a configured webhook, e.g. to hand results to another \
+                 system. Pick the webhook by name."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "webhook": {
+                        "type": "string",
+                        "enum": names,
+                        "description": "Name of the webhook"
+                    },
+                    "payload": {
+                        "description": "JSON payload to send"
+                    }
+                },
+                "required": ["webhook", "payload"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let name = required_str(&args, "webhook")?;
+        let url = destination(&self.webhooks, "webhook", name)?;
+        let payload = args
+            .get("payload")
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing payload".to_string()))?;
+        post_json(&self.client, url, payload).await?;
+        Ok(ToolCallContent {
+            text: format!("sent to webhook {}", name),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::notify::test_server;
+
+    /// **Scenario**: The payload is posted to the URL registered under the chosen name; unknown
+    /// names fail without any request and list the configured ones.
+    #[tokio::test]
+    async fn posts_payload_to_named_webhook_only() {
+        let (url, server) = test_server::one_shot(200).await;
+        let tool = SendWebhookTool::new(
+            reqwest::Client::new(),
+            BTreeMap::from([("ops".to_string(), url)]),
+        );
+
+        let err = tool
+            .call(json!({"webhook": "https://evil.io", "payload": {}}), None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolSourceError::InvalidInput(m) if m.contains("configured: ops")),
+            "{}",
+            err
+        );
+
+        let text = tool
+            .call(json!({"webhook": "ops", "payload": {"done": true}}), None)
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "sent to webhook ops");
+        let body: Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(body, json!({"done": true}));
+    }
+
+    /// **Scenario**: A non-2xx response is a transport error carrying the status.
+    #[tokio::test]
+    async fn error_status_is_transport_error() {
+        let (url, _server) = test_server::one_shot(500).await;
+        let tool = SendWebhookTool::new(
+            reqwest::Client::new(),
+            BTreeMap::from([("ops".to_string(), url)]),
+        );
+        let err = tool
+            .call(json!({"webhook": "ops", "payload": "x"}), None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, ToolSourceError::Transport(m) if m.starts_with("HTTP 500")),
+            "{}",
+            err
+        );
+    }
+}
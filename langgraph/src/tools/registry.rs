@@ -86,6 +86,11 @@ impl ToolRegistry {
         self.tools.insert(name, tool);
     }
 
+    /// Removes the tool named `name`; returns whether it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.tools.remove(name).is_some()
+    }
+
     /// Lists all registered tools as ToolSpec objects.
     ///
     /// Returns a vector of tool specifications that can be sent to the LLM.
@@ -246,6 +251,11 @@ impl ToolRegistryLocked {
         inner.register(tool);
     }
 
+    /// Removes the tool named `name`; returns whether it was registered.
+    pub async fn unregister_async(&self, name: &str) -> bool {
+        self.inner.write().await.unregister(name)
+    }
+
     /// Registers a tool in the registry synchronously.
     ///
     /// This method spawns a new thread with its own tokio runtime to avoid conflicts.
@@ -0,0 +1,414 @@
+//! Reminder tools: `create_reminder`, `list_reminders` and `cancel_reminder` over a Store namespace.
+//!
+//! Each reminder is one item `{"text", "due_at", "created_at"}` (unix seconds) keyed by a
+//! generated id, in a namespace dedicated to reminders, by convention
+//! `[user_id, REMINDERS_NAMESPACE]`. [`ReminderScheduler`] polls such namespaces and hands
+//! reminders that are due to a [`ReminderHandler`] (a webhook, or a follow-up agent run), then
+//! removes them.
+//!
+//! **Interaction**: Register the tools with [`register_reminder_tools`] (or one by one on an
+//! [`AggregateToolSource`]); run a [`ReminderScheduler`] over the same store.
+
+mod scheduler;
+
+pub use scheduler::{ReminderHandler, ReminderScheduler, WebhookReminderHandler};
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::memory::{uuid6, Namespace, Store, StoreError};
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError};
+use crate::tools::{AggregateToolSource, Tool};
+
+/// Tool name for creating a reminder.
+pub const TOOL_CREATE_REMINDER: &str = "create_reminder";
+/// Tool name for listing pending reminders.
+pub const TOOL_LIST_REMINDERS: &str = "list_reminders";
+/// Tool name for cancelling a reminder.
+pub const TOOL_CANCEL_REMINDER: &str = "cancel_reminder";
+
+/// Last namespace segment of reminder namespaces; [`ReminderScheduler`] scans namespaces
+/// ending with it.
+pub const REMINDERS_NAMESPACE: &str = "reminders";
+
+/// A pending reminder as stored and as listed to the model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reminder {
+    /// Store key of the reminder.
+    #[serde(skip)]
+    pub id: String,
+    /// What to remind about.
+    pub text: String,
+    /// When the reminder is due (unix seconds).
+    pub due_at: u64,
+    /// When it was created (unix seconds).
+    pub created_at: u64,
+}
+
+impl Reminder {
+    /// Reads the stored value of reminder `id`.
+    pub fn from_value(id: impl Into<String>, value: Value) -> Result<Self, StoreError> {
+        let mut reminder: Reminder = serde_json::from_value(value)?;
+        reminder.id = id.into();
+        Ok(reminder)
+    }
+
+    fn to_listing(&self, now: u64) -> Value {
+        json!({
+            "id": self.id,
+            "text": self.text,
+            "due_at": self.due_at,
+            "due_in_seconds": self.due_at.saturating_sub(now),
+        })
+    }
+}
+
+/// Current time in unix seconds.
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn store_error(e: StoreError) -> ToolSourceError {
+    match e {
+        StoreError::NotFound => ToolSourceError::NotFound("reminder not found".to_string()),
+        StoreError::Serialization(s) => ToolSourceError::InvalidInput(s),
+        other => ToolSourceError::Transport(other.to_string()),
+    }
+}
+
+/// Pending reminders in `namespace`, soonest first; entries that are not reminders are skipped.
+pub async fn list_reminders(
+    store: &dyn Store,
+    namespace: &Namespace,
+) -> Result<Vec<Reminder>, StoreError> {
+    let mut reminders = Vec::new();
+    for key in store.list(namespace).await? {
+        let Some(value) = store.get(namespace, &key).await? else {
+            continue;
+        };
+        match Reminder::from_value(key.clone(), value) {
+            Ok(reminder) => reminders.push(reminder),
+            Err(e) => tracing::debug!(key = %key, "skipping non-reminder entry: {}", e),
+        }
+    }
+    reminders.sort_by(|a, b| a.due_at.cmp(&b.due_at).then_with(|| a.id.cmp(&b.id)));
+    Ok(reminders)
+}
+
+/// Tool that stores a reminder due at a time or after a delay.
+///
+/// # Interaction
+///
+/// - **Store**: Writes the reminder with `Store::put` under a generated `reminder_...` key.
+/// - **ReminderScheduler**: Fires it once `due_at` has passed.
+pub struct CreateReminderTool {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl CreateReminderTool {
+    /// Tool writing reminders into `namespace` (e.g. `[user_id, "reminders"]`) of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self { store, namespace }
+    }
+}
+
+#[async_trait]
+impl Tool for CreateReminderTool {
+    fn name(&self) -> &str {
+        TOOL_CREATE_REMINDER
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_CREATE_REMINDER.to_string(),
+            description: Some(
+                "Create a reminder that fires later, e.g. to follow up with the user. Give either \
+                 delay_seconds (from now) or due_at (unix seconds). Returns the reminder id."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string", "description": "What to remind about" },
+                    "delay_seconds": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Seconds from now until the reminder is due"
+                    },
+                    "due_at": {
+                        "type": "integer",
+                        "description": "When the reminder is due, in unix seconds"
+                    }
+                },
+                "required": ["text"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let text = args
+            .get("text")
+            .and_then(Value::as_str)
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing text".to_string()))?;
+        let now = now_secs();
+        let due_at = match (
+            args.get("due_at").and_then(Value::as_u64),
+            args.get("delay_seconds").and_then(Value::as_u64),
+        ) {
+            (Some(due_at), None) => due_at,
+            (None, Some(delay)) => now.saturating_add(delay),
+            _ => {
+                return Err(ToolSourceError::InvalidInput(
+                    "give exactly one of delay_seconds or due_at".to_string(),
+                ))
+            }
+        };
+        let reminder = Reminder {
+            id: format!("reminder_{}", uuid6()),
+            text: text.to_string(),
+            due_at,
+            created_at: now,
+        };
+        let value = serde_json::to_value(&reminder)
+            .map_err(|e| ToolSourceError::InvalidInput(e.to_string()))?;
+        self.store
+            .put(&self.namespace, &reminder.id, &value)
+            .await
+            .map_err(store_error)?;
+        Ok(ToolCallContent {
+            text: json!({ "id": reminder.id, "due_at": due_at }).to_string(),
+        })
+    }
+}
+
+/// Tool that lists pending reminders, soonest first.
+///
+/// # Interaction
+///
+/// - **Store**: Reads the namespace with `Store::list` and `Store::get`.
+pub struct ListRemindersTool {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl ListRemindersTool {
+    /// Tool listing the reminders in `namespace` of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self { store, namespace }
+    }
+}
+
+#[async_trait]
+impl Tool for ListRemindersTool {
+    fn name(&self) -> &str {
+        TOOL_LIST_REMINDERS
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_LIST_REMINDERS.to_string(),
+            description: Some(
+                "List pending reminders (id, text, due_at, due_in_seconds), soonest first."
+                    .to_string(),
+            ),
+            input_schema: json!({ "type": "object", "properties": {} }),
+        }
+    }
+
+    async fn call(
+        &self,
+        _args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let now = now_secs();
+        let reminders = list_reminders(self.store.as_ref(), &self.namespace)
+            .await
+            .map_err(store_error)?;
+        let listing: Vec<Value> = reminders.iter().map(|r| r.to_listing(now)).collect();
+        Ok(ToolCallContent {
+            text: Value::Array(listing).to_string(),
+        })
+    }
+}
+
+/// Tool that cancels a pending reminder by id.
+///
+/// # Interaction
+///
+/// - **Store**: Deletes the reminder with `Store::delete`.
+pub struct CancelReminderTool {
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+}
+
+impl CancelReminderTool {
+    /// Tool cancelling reminders in `namespace` of `store`.
+    pub fn new(store: Arc<dyn Store>, namespace: Namespace) -> Self {
+        Self { store, namespace }
+    }
+}
+
+#[async_trait]
+impl Tool for CancelReminderTool {
+    fn name(&self) -> &str {
+        TOOL_CANCEL_REMINDER
+    }
+
+    fn spec(&self) -> crate::tool_source::ToolSpec {
+        crate::tool_source::ToolSpec {
+            name: TOOL_CANCEL_REMINDER.to_string(),
+            description: Some(
+                "Cancel a pending reminder by the id from create_reminder or list_reminders."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Reminder id" }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let id = args
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ToolSourceError::InvalidInput("missing id".to_string()))?;
+        let exists = self
+            .store
+            .get(&self.namespace, id)
+            .await
+            .map_err(store_error)?
+            .is_some();
+        if !exists {
+            return Err(ToolSourceError::NotFound(format!("reminder {}", id)));
+        }
+        self.store
+            .delete(&self.namespace, id)
+            .await
+            .map_err(store_error)?;
+        Ok(ToolCallContent {
+            text: "cancelled".to_string(),
+        })
+    }
+}
+
+/// Registers `create_reminder`, `list_reminders` and `cancel_reminder` over `namespace` of
+/// `store` on `aggregate`.
+pub async fn register_reminder_tools(
+    aggregate: &AggregateToolSource,
+    store: Arc<dyn Store>,
+    namespace: Namespace,
+) {
+    aggregate
+        .register_async(Box::new(CreateReminderTool::new(
+            store.clone(),
+            namespace.clone(),
+        )))
+        .await;
+    aggregate
+        .register_async(Box::new(ListRemindersTool::new(
+            store.clone(),
+            namespace.clone(),
+        )))
+        .await;
+    aggregate
+        .register_async(Box::new(CancelReminderTool::new(store, namespace)))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryStore;
+    use crate::tool_source::ToolSource;
+
+    fn namespace() -> Namespace {
+        vec!["u1".into(), REMINDERS_NAMESPACE.into()]
+    }
+
+    /// **Scenario**: Reminders created with a delay or a due time are listed soonest first
+    /// and can be cancelled by id; cancelling twice is NotFound.
+    #[tokio::test]
+    async fn create_list_and_cancel_reminders() {
+        let store = Arc::new(InMemoryStore::new());
+        let tools = AggregateToolSource::new();
+        register_reminder_tools(&tools, store.clone(), namespace()).await;
+
+        let later: Value = serde_json::from_str(
+            &tools
+                .call_tool(
+                    TOOL_CREATE_REMINDER,
+                    json!({"text": "call Ada", "delay_seconds": 3600}),
+                )
+                .await
+                .unwrap()
+                .text,
+        )
+        .unwrap();
+        tools
+            .call_tool(
+                TOOL_CREATE_REMINDER,
+                json!({"text": "standup", "due_at": 1_000}),
+            )
+            .await
+            .unwrap();
+        assert!(tools
+            .call_tool(
+                TOOL_CREATE_REMINDER,
+                json!({"text": "both", "due_at": 1, "delay_seconds": 1}),
+            )
+            .await
+            .is_err());
+
+        let listed: Vec<Value> = serde_json::from_str(
+            &tools
+                .call_tool(TOOL_LIST_REMINDERS, json!({}))
+                .await
+                .unwrap()
+                .text,
+        )
+        .unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0]["text"], "standup");
+        assert_eq!(listed[0]["due_in_seconds"], 0);
+        assert_eq!(listed[1]["id"], later["id"]);
+        assert!(listed[1]["due_in_seconds"].as_u64().unwrap() > 3500);
+
+        let id = later["id"].as_str().unwrap();
+        let text = tools
+            .call_tool(TOOL_CANCEL_REMINDER, json!({"id": id}))
+            .await
+            .unwrap()
+            .text;
+        assert_eq!(text, "cancelled");
+        assert!(matches!(
+            tools
+                .call_tool(TOOL_CANCEL_REMINDER, json!({"id": id}))
+                .await,
+            Err(ToolSourceError::NotFound(_))
+        ));
+        let left = list_reminders(store.as_ref(), &namespace()).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].text, "standup");
+    }
+}
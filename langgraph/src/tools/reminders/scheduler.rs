@@ -0,0 +1,241 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+use super::{list_reminders, now_secs, Reminder, REMINDERS_NAMESPACE};
+use crate::memory::{ListNamespacesOptions, Namespace, Store, StoreError};
+
+/// Namespaces read per `list_namespaces` page while scanning.
+const NAMESPACE_PAGE: usize = 100;
+
+/// What happens when a reminder is due.
+///
+/// Return `Err` to keep the reminder and retry on the next poll.
+///
+/// # Example
+///
+/// A follow-up agent run per reminder (the namespace is `[user_id, "reminders"]`):
+///
+/// ```rust,ignore
+/// struct FollowUp { agent: Arc<CompiledStateGraph<ReActState>> }
+///
+/// #[async_trait]
+/// impl ReminderHandler for FollowUp {
+///     async fn fire(&self, namespace: &Namespace, reminder: &Reminder) -> Result<(), String> {
+///         let state = ReActState::from_user(format!("Reminder for {}: {}", namespace[0], reminder.text));
+///         self.agent.invoke(state, None).await.map(|_| ()).map_err(|e| e.to_string())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait ReminderHandler: Send + Sync {
+    /// Delivers `reminder` from `namespace`.
+    async fn fire(&self, namespace: &Namespace, reminder: &Reminder) -> Result<(), String>;
+}
+
+/// Posts each due reminder as JSON `{"namespace", "id", "text", "due_at"}` to a webhook.
+pub struct WebhookReminderHandler {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookReminderHandler {
+    /// Handler posting to `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+
+    /// Sets the HTTP client (timeouts, proxies).
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[async_trait]
+impl ReminderHandler for WebhookReminderHandler {
+    async fn fire(&self, namespace: &Namespace, reminder: &Reminder) -> Result<(), String> {
+        let body = json!({
+            "namespace": namespace,
+            "id": reminder.id,
+            "text": reminder.text,
+            "due_at": reminder.due_at,
+        });
+        crate::tools::notify::post_json(&self.client, &self.url, &body)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Polls the store for due reminders and fires them.
+///
+/// Scans every namespace ending with [`REMINDERS_NAMESPACE`]; a reminder whose `due_at` has
+/// passed goes to the handler and is deleted once the handler succeeds. Reminders survive
+/// restarts because they live in the store.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use langgraph::memory::InMemoryStore;
+/// use langgraph::tools::reminders::{ReminderScheduler, WebhookReminderHandler};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let store = Arc::new(InMemoryStore::new());
+/// let handler = Arc::new(WebhookReminderHandler::new("https://example.com/hooks/reminder"));
+/// let scheduler = ReminderScheduler::new(store, handler).with_poll_interval(Duration::from_secs(30));
+/// let task = scheduler.spawn();
+/// # task.abort();
+/// # }
+/// ```
+pub struct ReminderScheduler {
+    store: Arc<dyn Store>,
+    handler: Arc<dyn ReminderHandler>,
+    poll_interval: Duration,
+}
+
+impl ReminderScheduler {
+    /// Scheduler over `store` delivering to `handler`, polling every 60 seconds.
+    pub fn new(store: Arc<dyn Store>, handler: Arc<dyn ReminderHandler>) -> Self {
+        Self {
+            store,
+            handler,
+            poll_interval: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets how often the store is polled.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Fires the reminders due at `now` (unix seconds) and returns how many were delivered.
+    pub async fn fire_due(&self, now: u64) -> Result<usize, StoreError> {
+        let mut fired = 0;
+        for namespace in self.reminder_namespaces().await? {
+            for reminder in list_reminders(self.store.as_ref(), &namespace).await? {
+                if reminder.due_at > now {
+                    break;
+                }
+                match self.handler.fire(&namespace, &reminder).await {
+                    Ok(()) => {
+                        self.store.delete(&namespace, &reminder.id).await?;
+                        fired += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!(id = %reminder.id, "reminder delivery failed: {}", e)
+                    }
+                }
+            }
+        }
+        Ok(fired)
+    }
+
+    /// Polls every interval until the task is aborted.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.fire_due(now_secs()).await {
+                    tracing::warn!("reminder poll failed: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn reminder_namespaces(&self) -> Result<Vec<Namespace>, StoreError> {
+        let mut namespaces = Vec::new();
+        loop {
+            let mut options = ListNamespacesOptions::new()
+                .with_suffix(vec![REMINDERS_NAMESPACE.to_string()])
+                .with_limit(NAMESPACE_PAGE);
+            options.offset = namespaces.len();
+            let page = self.store.list_namespaces(options).await?;
+            let done = page.len() < NAMESPACE_PAGE;
+            namespaces.extend(page);
+            if done {
+                return Ok(namespaces);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::memory::InMemoryStore;
+
+    /// Records fired reminder texts; fails for texts starting with "fail".
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl ReminderHandler for Recorder {
+        async fn fire(&self, namespace: &Namespace, reminder: &Reminder) -> Result<(), String> {
+            if reminder.text.starts_with("fail") {
+                return Err("unreachable".into());
+            }
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", namespace[0], reminder.text));
+            Ok(())
+        }
+    }
+
+    async fn put(store: &InMemoryStore, user: &str, key: &str, text: &str, due_at: u64) {
+        let ns = vec![user.to_string(), REMINDERS_NAMESPACE.to_string()];
+        store
+            .put(
+                &ns,
+                key,
+                &json!({"text": text, "due_at": due_at, "created_at": 0}),
+            )
+            .await
+            .unwrap();
+    }
+
+    /// **Scenario**: Due reminders of every user are fired and deleted; future ones and failed
+    /// deliveries stay for a later poll.
+    #[tokio::test]
+    async fn fire_due_delivers_and_removes_due_reminders() {
+        let store = Arc::new(InMemoryStore::new());
+        put(&store, "u1", "a", "standup", 100).await;
+        put(&store, "u1", "b", "later", 500).await;
+        put(&store, "u2", "c", "pay rent", 200).await;
+        put(&store, "u2", "d", "fail to deliver", 150).await;
+        store
+            .put(
+                &vec!["u1".into(), "memories".into()],
+                "m",
+                &json!({"due_at": 1}),
+            )
+            .await
+            .unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        let scheduler = ReminderScheduler::new(store.clone(), recorder.clone());
+        assert_eq!(scheduler.fire_due(300).await.unwrap(), 2);
+
+        let mut fired = recorder.0.lock().unwrap().clone();
+        fired.sort();
+        assert_eq!(fired, vec!["u1: standup", "u2: pay rent"]);
+
+        let u1 = vec!["u1".to_string(), REMINDERS_NAMESPACE.to_string()];
+        let u2 = vec!["u2".to_string(), REMINDERS_NAMESPACE.to_string()];
+        assert_eq!(store.list(&u1).await.unwrap(), vec!["b".to_string()]);
+        assert_eq!(store.list(&u2).await.unwrap(), vec!["d".to_string()]);
+    }
+}
@@ -0,0 +1,130 @@
+//! `retrieve`: searches the documents of a vector store with the model's own query.
+//!
+//! **Interaction**: Built by `Retriever::tool` over the store and namespace that
+//! `RetrieveNode` reads before `think`.
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::react::Retriever;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::notify::required_str;
+use crate::tools::Tool;
+
+/// Tool name for searching documents.
+pub const TOOL_RETRIEVE: &str = "retrieve";
+
+/// Tool returning the documents most relevant to a query as numbered passages with their
+/// source and score (see [`Retriever::format`]), or a note when nothing matched.
+///
+/// # Interaction
+///
+/// - **Retriever**: Searches its store and namespace; `k` defaults to its `top_k`.
+/// - **ToolSourceError**: `Transport` when the store or its embedder fails.
+pub struct RetrieverTool {
+    retriever: Retriever,
+    name: String,
+}
+
+impl RetrieverTool {
+    /// Tool searching with `retriever`.
+    pub fn new(retriever: Retriever) -> Self {
+        Self {
+            retriever,
+            name: TOOL_RETRIEVE.to_string(),
+        }
+    }
+
+    /// Exposes the tool under `name` instead of `"retrieve"`, e.g. one per collection.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for RetrieverTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: self.name.clone(),
+            description: Some(format!(
+                "Search the document collection and get the most relevant passages with their \
+                 sources. Call when the context you have does not answer the question; cite the \
+                 sources you use. Returns {} passages unless k is given.",
+                self.retriever.top_k()
+            )),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "What to search for" },
+                    "k": { "type": "integer", "description": "Number of passages (optional)" }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: serde_json::Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let query = required_str(&args, "query")?;
+        let k = args.get("k").and_then(|v| v.as_u64()).map(|k| k as usize);
+        let documents = self
+            .retriever
+            .retrieve(query, k)
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        let text = if documents.is_empty() {
+            format!("No documents match {:?}.", query)
+        } else {
+            Retriever::format(&documents)
+        };
+        Ok(ToolCallContent { text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{InMemoryStore, Store};
+    use std::sync::Arc;
+
+    /// **Scenario**: The tool returns numbered passages with sources, at most k of them, and
+    /// says so when nothing matches; a missing query is invalid input.
+    #[tokio::test]
+    async fn retrieves_passages_with_sources() {
+        let store = Arc::new(InMemoryStore::new());
+        let namespace = vec!["docs".to_string()];
+        for (key, text) in [("a", "rust ownership"), ("b", "rust traits")] {
+            store
+                .put(
+                    &namespace,
+                    key,
+                    &json!({"text": text, "source": format!("{}.md", key)}),
+                )
+                .await
+                .unwrap();
+        }
+        let tool = Retriever::new(store, namespace).tool();
+
+        let out = tool
+            .call(json!({"query": "rust", "k": 1}), None)
+            .await
+            .unwrap();
+        assert_eq!(out.text.matches("\n\n").count(), 0, "{}", out.text);
+        assert!(out.text.starts_with("[1] "), "{}", out.text);
+        assert!(out.text.contains(".md\nrust "), "{}", out.text);
+
+        let out = tool.call(json!({"query": "python"}), None).await.unwrap();
+        assert_eq!(out.text, "No documents match \"python\".");
+
+        let err = tool.call(json!({}), None).await.unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+    }
+}
@@ -0,0 +1,380 @@
+//! Database connections behind the SQL tools: SQLite through rusqlite, Postgres and MySQL
+//! through sqlx.
+//!
+//! Read-only mode is enforced by the database, not by parsing SQL: SQLite opens the file
+//! read-only (so `ATTACH` cannot create files either) and refuses statements that are not
+//! `sqlite3_stmt_readonly`, Postgres and MySQL run every query in a read-only transaction.
+//! Only one statement runs per call (prepared statements), and at most `max_rows + 1` rows are
+//! fetched to tell whether the result was cut.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use futures::TryStreamExt;
+use rusqlite::types::ValueRef;
+use serde_json::{json, Value};
+use sqlx::mysql::{MySqlPool, MySqlRow};
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
+
+use super::{SqlToolError, TableSchema};
+
+/// Rows (or the affected-row count) returned by one statement.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct QueryOutput {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// More than `max_rows` rows matched; `rows` holds the first `max_rows`.
+    pub truncated: bool,
+    /// Set for statements that return no columns (writes).
+    pub rows_affected: Option<u64>,
+}
+
+/// An open database.
+#[derive(Clone)]
+pub(super) enum Database {
+    /// SQLite file, opened per call like [`SqliteSaver`](crate::memory::SqliteSaver) does.
+    Sqlite(PathBuf),
+    Postgres(PgPool),
+    MySql(MySqlPool),
+}
+
+impl Database {
+    /// Opens the database at `url`: `sqlite:PATH` / `sqlite://PATH`, `postgres://…`
+    /// (`postgresql://…`) or `mysql://…` (`mariadb://…`).
+    pub async fn connect(url: &str) -> Result<Self, SqlToolError> {
+        if let Some(path) = url
+            .strip_prefix("sqlite://")
+            .or_else(|| url.strip_prefix("sqlite:"))
+        {
+            let path = PathBuf::from(path);
+            let check = path.clone();
+            tokio::task::spawn_blocking(move || open_sqlite(&check, true, None).map(drop))
+                .await
+                .map_err(|e| SqlToolError::Connection(e.to_string()))??;
+            return Ok(Self::Sqlite(path));
+        }
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            let pool = PgPool::connect(url).await.map_err(connection_error)?;
+            return Ok(Self::Postgres(pool));
+        }
+        if url.starts_with("mysql://") || url.starts_with("mariadb://") {
+            let url = url.replacen("mariadb://", "mysql://", 1);
+            let pool = MySqlPool::connect(&url).await.map_err(connection_error)?;
+            return Ok(Self::MySql(pool));
+        }
+        let scheme = url.split(':').next().unwrap_or_default();
+        Err(SqlToolError::UnsupportedUrl(scheme.to_string()))
+    }
+
+    /// Name of the SQL dialect, for the tool description.
+    pub fn dialect(&self) -> &'static str {
+        match self {
+            Self::Sqlite(_) => "SQLite",
+            Self::Postgres(_) => "PostgreSQL",
+            Self::MySql(_) => "MySQL",
+        }
+    }
+
+    /// Runs one statement and returns at most `max_rows` rows.
+    pub async fn query(
+        &self,
+        sql: &str,
+        read_only: bool,
+        max_rows: usize,
+        timeout: Duration,
+    ) -> Result<QueryOutput, SqlToolError> {
+        let run = async {
+            match self {
+                Self::Sqlite(path) => {
+                    let (path, sql) = (path.clone(), sql.to_string());
+                    let deadline = Instant::now() + timeout;
+                    tokio::task::spawn_blocking(move || {
+                        let conn = open_sqlite(&path, read_only, Some(deadline))?;
+                        sqlite_query(&conn, &sql, read_only, max_rows)
+                    })
+                    .await
+                    .map_err(|e| SqlToolError::Connection(e.to_string()))?
+                }
+                Self::Postgres(pool) => postgres_query(pool, sql, read_only, max_rows).await,
+                Self::MySql(pool) => mysql_query(pool, sql, read_only, max_rows).await,
+            }
+        };
+        tokio::time::timeout(timeout, run)
+            .await
+            .map_err(|_| SqlToolError::Timeout(timeout))?
+    }
+
+    /// Tables and views with their columns, sorted by name.
+    pub async fn schema(&self) -> Result<Vec<TableSchema>, SqlToolError> {
+        let columns: Vec<(String, String, String)> = match self {
+            Self::Sqlite(path) => {
+                let path = path.clone();
+                tokio::task::spawn_blocking(move || sqlite_schema(&open_sqlite(&path, true, None)?))
+                    .await
+                    .map_err(|e| SqlToolError::Connection(e.to_string()))??
+            }
+            // information_schema columns are domain types sqlx does not decode as String.
+            Self::Postgres(pool) => sqlx::query_as(
+                "SELECT CASE WHEN table_schema = 'public' THEN table_name::text \
+                 ELSE table_schema::text || '.' || table_name::text END, \
+                 column_name::text, data_type::text \
+                 FROM information_schema.columns \
+                 WHERE table_schema NOT IN ('pg_catalog', 'information_schema') \
+                 ORDER BY 1, ordinal_position",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(query_error)?,
+            Self::MySql(pool) => sqlx::query_as(
+                "SELECT CAST(table_name AS CHAR), CAST(column_name AS CHAR), \
+                 CAST(column_type AS CHAR) \
+                 FROM information_schema.columns WHERE table_schema = DATABASE() \
+                 ORDER BY table_name, ordinal_position",
+            )
+            .fetch_all(pool)
+            .await
+            .map_err(query_error)?,
+        };
+        let mut tables: Vec<TableSchema> = Vec::new();
+        for (table, column, ty) in columns {
+            match tables.last_mut() {
+                Some(last) if last.name == table => last.columns.push((column, ty)),
+                _ => tables.push(TableSchema {
+                    name: table,
+                    columns: vec![(column, ty)],
+                }),
+            }
+        }
+        Ok(tables)
+    }
+}
+
+fn connection_error(e: sqlx::Error) -> SqlToolError {
+    SqlToolError::Connection(e.to_string())
+}
+
+/// Errors the model can fix by changing its SQL are query errors, the rest connection errors.
+fn query_error(e: sqlx::Error) -> SqlToolError {
+    match e {
+        sqlx::Error::Database(_)
+        | sqlx::Error::ColumnDecode { .. }
+        | sqlx::Error::ColumnNotFound(_)
+        | sqlx::Error::TypeNotFound { .. } => SqlToolError::Query(e.to_string()),
+        e => SqlToolError::Connection(e.to_string()),
+    }
+}
+
+fn sqlite_error(e: rusqlite::Error) -> SqlToolError {
+    SqlToolError::Query(e.to_string())
+}
+
+/// Opens an existing SQLite file, read-only when `read_only` (attached databases then open
+/// read-only too); with a `deadline`, statements are interrupted once it passes.
+fn open_sqlite(
+    path: &std::path::Path,
+    read_only: bool,
+    deadline: Option<Instant>,
+) -> Result<rusqlite::Connection, SqlToolError> {
+    use rusqlite::OpenFlags;
+    let mode = if read_only {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_WRITE
+    };
+    let conn = rusqlite::Connection::open_with_flags(path, mode)
+        .map_err(|e| SqlToolError::Connection(format!("{}: {}", path.display(), e)))?;
+    if let Some(deadline) = deadline {
+        conn.progress_handler(10_000, Some(move || Instant::now() >= deadline));
+    }
+    Ok(conn)
+}
+
+fn sqlite_query(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    read_only: bool,
+    max_rows: usize,
+) -> Result<QueryOutput, SqlToolError> {
+    let mut stmt = conn.prepare(sql).map_err(sqlite_error)?;
+    if read_only && !stmt.readonly() {
+        return Err(SqlToolError::ReadOnly);
+    }
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    if columns.is_empty() {
+        let affected = stmt.execute([]).map_err(sqlite_error)?;
+        return Ok(QueryOutput {
+            rows_affected: Some(affected as u64),
+            ..Default::default()
+        });
+    }
+    let mut output = QueryOutput {
+        columns,
+        ..Default::default()
+    };
+    let mut rows = stmt.query([]).map_err(sqlite_error)?;
+    while let Some(row) = rows.next().map_err(sqlite_error)? {
+        if output.rows.len() == max_rows {
+            output.truncated = true;
+            break;
+        }
+        let values = (0..output.columns.len())
+            .map(|i| row.get_ref(i).map(sqlite_value))
+            .collect::<Result<_, _>>()
+            .map_err(sqlite_error)?;
+        output.rows.push(values);
+    }
+    Ok(output)
+}
+
+fn sqlite_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(n) => json!(n),
+        ValueRef::Real(x) => json!(x),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::String(format!("<{} bytes>", bytes.len())),
+    }
+}
+
+fn sqlite_schema(
+    conn: &rusqlite::Connection,
+) -> Result<Vec<(String, String, String)>, SqlToolError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.name, c.name, c.type FROM sqlite_master m, pragma_table_info(m.name) c \
+             WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%' \
+             ORDER BY m.name, c.cid",
+        )
+        .map_err(sqlite_error)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(sqlite_error)?;
+    rows.collect::<Result<_, _>>().map_err(sqlite_error)
+}
+
+/// Decodes column `i` of `row` as the first of the listed types it is compatible with.
+macro_rules! decode_column {
+    ($row:expr, $i:expr, $($ty:ty),+) => {{
+        $(
+            if let Ok(value) = $row.try_get::<Option<$ty>, _>($i) {
+                return json!(value);
+            }
+        )+
+        if let Ok(bytes) = $row.try_get::<Option<Vec<u8>>, _>($i) {
+            return bytes.map_or(Value::Null, |b| Value::String(format!("<{} bytes>", b.len())));
+        }
+        // Numeric, date and other types: the model can cast them to text.
+        Value::String(format!("<{}>", $row.column($i).type_info().name()))
+    }};
+}
+
+fn postgres_value(row: &PgRow, i: usize) -> Value {
+    decode_column!(row, i, bool, i16, i32, i64, f32, f64, String, Value)
+}
+
+fn mysql_value(row: &MySqlRow, i: usize) -> Value {
+    decode_column!(row, i, i64, u64, f64, f32, String, Value)
+}
+
+async fn postgres_query(
+    pool: &PgPool,
+    sql: &str,
+    read_only: bool,
+    max_rows: usize,
+) -> Result<QueryOutput, SqlToolError> {
+    let mut tx = pool.begin().await.map_err(connection_error)?;
+    if read_only {
+        (&mut *tx)
+            .execute("SET TRANSACTION READ ONLY")
+            .await
+            .map_err(query_error)?;
+    }
+    let statement = (&mut *tx).prepare(sql).await.map_err(query_error)?;
+    let columns: Vec<String> = statement
+        .columns()
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+    let mut output = QueryOutput {
+        columns,
+        ..Default::default()
+    };
+    if output.columns.is_empty() {
+        let result = statement
+            .query()
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        output.rows_affected = Some(result.rows_affected());
+    } else {
+        let mut rows = statement.query().fetch(&mut *tx);
+        while let Some(row) = rows.try_next().await.map_err(query_error)? {
+            if output.rows.len() == max_rows {
+                output.truncated = true;
+                break;
+            }
+            output
+                .rows
+                .push((0..row.len()).map(|i| postgres_value(&row, i)).collect());
+        }
+    }
+    if read_only {
+        tx.rollback().await.map_err(connection_error)?;
+    } else {
+        tx.commit().await.map_err(connection_error)?;
+    }
+    Ok(output)
+}
+
+async fn mysql_query(
+    pool: &MySqlPool,
+    sql: &str,
+    read_only: bool,
+    max_rows: usize,
+) -> Result<QueryOutput, SqlToolError> {
+    let mut conn = pool.acquire().await.map_err(connection_error)?;
+    if read_only {
+        // Applies to the next transaction only.
+        (&mut *conn)
+            .execute("SET TRANSACTION READ ONLY")
+            .await
+            .map_err(query_error)?;
+    }
+    let mut tx = conn.begin().await.map_err(connection_error)?;
+    let statement = (&mut *tx).prepare(sql).await.map_err(query_error)?;
+    let columns: Vec<String> = statement
+        .columns()
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+    let mut output = QueryOutput {
+        columns,
+        ..Default::default()
+    };
+    if output.columns.is_empty() {
+        let result = statement
+            .query()
+            .execute(&mut *tx)
+            .await
+            .map_err(query_error)?;
+        output.rows_affected = Some(result.rows_affected());
+    } else {
+        let mut rows = statement.query().fetch(&mut *tx);
+        while let Some(row) = rows.try_next().await.map_err(query_error)? {
+            if output.rows.len() == max_rows {
+                output.truncated = true;
+                break;
+            }
+            output
+                .rows
+                .push((0..row.len()).map(|i| mysql_value(&row, i)).collect());
+        }
+    }
+    if read_only {
+        tx.rollback().await.map_err(connection_error)?;
+    } else {
+        tx.commit().await.map_err(connection_error)?;
+    }
+    Ok(output)
+}
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::SqlTool;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::Tool;
+
+/// Tool name for listing tables and their columns.
+pub const TOOL_DESCRIBE_SCHEMA: &str = "describe_schema";
+
+/// Tool that lists the database's tables with their columns, read live from the database.
+///
+/// Returns one `table(column TYPE, ...)` line per table, or only the table named by the
+/// optional `table` argument.
+///
+/// # Interaction
+///
+/// - **SqlTool**: Always registered.
+/// - **ToolSourceError**: `NotFound` for an unknown `table`; `Transport` for connection
+///   failures.
+pub struct DescribeSchemaTool {
+    sql: SqlTool,
+}
+
+impl DescribeSchemaTool {
+    /// Tool describing `sql`'s database.
+    pub fn new(sql: SqlTool) -> Self {
+        Self { sql }
+    }
+}
+
+#[async_trait]
+impl Tool for DescribeSchemaTool {
+    fn name(&self) -> &str {
+        TOOL_DESCRIBE_SCHEMA
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_DESCRIBE_SCHEMA.to_string(),
+            description: Some(
+                "List the database tables with their columns and types, or one table.".to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "table": {
+                        "type": "string",
+                        "description": "Only this table (all tables when omitted)"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let schema = self.sql.database.schema().await?;
+        let text = match args.get("table").and_then(Value::as_str) {
+            Some(name) => schema
+                .iter()
+                .find(|t| t.name.eq_ignore_ascii_case(name))
+                .map(ToString::to_string)
+                .ok_or_else(|| ToolSourceError::NotFound(format!("table {}", name)))?,
+            None => schema
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        Ok(ToolCallContent { text })
+    }
+}
@@ -0,0 +1,308 @@
+//! SQL tools: `run_sql` and `describe_schema` over one configured database (feature
+//! `sql-tool`).
+//!
+//! [`SqlTool`] connects to SQLite, Postgres or MySQL, reads the schema once and writes the
+//! tables and their columns into the `run_sql` description, so the model can query without a
+//! discovery round trip. Queries are read-only unless
+//! [`with_read_only(false)`](SqlTool::with_read_only), which the database itself enforces
+//! (statement check on SQLite, read-only transactions on Postgres and MySQL); they return at
+//! most `max_rows` rows as JSON and time out after `timeout`.
+//!
+//! **Interaction**: Register the tools on an
+//! [`AggregateToolSource`] with [`SqlTool::register`], or use [`SqlTool::into_tool_source`]
+//! and pass it to `ActNode::new(Box::new(...))`.
+
+mod database;
+mod describe_schema;
+mod run_sql;
+
+pub use describe_schema::{DescribeSchemaTool, TOOL_DESCRIBE_SCHEMA};
+pub use run_sql::{RunSqlTool, TOOL_RUN_SQL};
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::tool_source::ToolSourceError;
+use crate::tools::{AggregateToolSource, Tool};
+
+use database::Database;
+
+/// Rows `run_sql` returns when `with_max_rows` is not called.
+pub const DEFAULT_MAX_ROWS: usize = 100;
+
+/// Time per query when `with_timeout` is not called.
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Characters of schema written into the `run_sql` description when `with_max_schema_chars`
+/// is not called; the rest is left to `describe_schema`.
+pub const DEFAULT_MAX_SCHEMA_CHARS: usize = 4000;
+
+/// Errors connecting to the database or running a statement.
+///
+/// **Interaction**: Converted to [`ToolSourceError`] by the tools: `InvalidInput` for what
+/// the model can fix (SQL errors, writes in read-only mode, timeouts), `Transport` otherwise.
+#[derive(Debug, Error)]
+pub enum SqlToolError {
+    #[error("unsupported database URL scheme {0:?} (use sqlite:, postgres:// or mysql://)")]
+    UnsupportedUrl(String),
+    #[error("database connection failed: {0}")]
+    Connection(String),
+    #[error("SQL error: {0}")]
+    Query(String),
+    #[error("the database is read-only: only queries that do not modify data are allowed")]
+    ReadOnly,
+    #[error("query timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+impl From<SqlToolError> for ToolSourceError {
+    fn from(e: SqlToolError) -> Self {
+        match e {
+            SqlToolError::Connection(_) => ToolSourceError::Transport(e.to_string()),
+            _ => ToolSourceError::InvalidInput(e.to_string()),
+        }
+    }
+}
+
+/// A table or view and its columns (name, declared type).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    /// Table name; qualified with its schema outside Postgres' `public`.
+    pub name: String,
+    /// Columns in declaration order.
+    pub columns: Vec<(String, String)>,
+}
+
+impl fmt::Display for TableSchema {
+    /// `name(column TYPE, ...)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, (column, ty)) in self.columns.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {}", column, ty)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// The SQL tools over one database connection.
+///
+/// # Examples
+///
+/// ```no_run
+/// use langgraph::tools::sql::SqlTool;
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let tools = SqlTool::connect("postgres://readonly@localhost/shop")
+///     .await?
+///     .with_max_rows(50)
+///     .into_tool_source()
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SqlTool {
+    database: Database,
+    schema: Arc<Vec<TableSchema>>,
+    read_only: bool,
+    max_rows: usize,
+    timeout: Duration,
+    max_schema_chars: usize,
+}
+
+impl SqlTool {
+    /// Connects to `url` (`sqlite:PATH`, `postgres://…`, `mysql://…`) and reads its schema.
+    /// The tools are read-only until [`with_read_only(false)`](Self::with_read_only).
+    pub async fn connect(url: &str) -> Result<Self, SqlToolError> {
+        let database = Database::connect(url).await?;
+        let schema = database.schema().await?;
+        Ok(Self {
+            database,
+            schema: Arc::new(schema),
+            read_only: true,
+            max_rows: DEFAULT_MAX_ROWS,
+            timeout: DEFAULT_QUERY_TIMEOUT,
+            max_schema_chars: DEFAULT_MAX_SCHEMA_CHARS,
+        })
+    }
+
+    /// Allows statements that modify data when `read_only` is false (default: read-only).
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Rows `run_sql` returns at most; longer results say they were cut (default
+    /// [`DEFAULT_MAX_ROWS`]).
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows.max(1);
+        self
+    }
+
+    /// Time allowed per statement (default [`DEFAULT_QUERY_TIMEOUT`]).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Characters of schema in the `run_sql` description (default
+    /// [`DEFAULT_MAX_SCHEMA_CHARS`]); 0 leaves the schema to `describe_schema`.
+    pub fn with_max_schema_chars(mut self, chars: usize) -> Self {
+        self.max_schema_chars = chars;
+        self
+    }
+
+    /// The schema read at connect time (or by the last [`refresh_schema`](Self::refresh_schema)).
+    pub fn schema(&self) -> &[TableSchema] {
+        &self.schema
+    }
+
+    /// Reads the schema again, e.g. after migrations; tools built afterwards describe it.
+    pub async fn refresh_schema(&mut self) -> Result<(), SqlToolError> {
+        self.schema = Arc::new(self.database.schema().await?);
+        Ok(())
+    }
+
+    /// The tools: run_sql, describe_schema.
+    pub fn tools(&self) -> Vec<Box<dyn Tool>> {
+        vec![
+            Box::new(RunSqlTool::new(self.clone())),
+            Box::new(DescribeSchemaTool::new(self.clone())),
+        ]
+    }
+
+    /// Registers the tools on `aggregate`, next to its other tools.
+    pub async fn register(&self, aggregate: &AggregateToolSource) {
+        for tool in self.tools() {
+            aggregate.register_async(tool).await;
+        }
+    }
+
+    /// A tool source with only the SQL tools; pass to `ActNode::new(Box::new(...))`.
+    pub async fn into_tool_source(self) -> AggregateToolSource {
+        let aggregate = AggregateToolSource::new();
+        self.register(&aggregate).await;
+        aggregate
+    }
+
+    /// The schema as one `table(column TYPE, ...)` line per table, cut after `max_chars`.
+    fn schema_summary(&self, max_chars: usize) -> String {
+        let mut summary = String::new();
+        if max_chars == 0 {
+            return summary;
+        }
+        for table in self.schema.iter() {
+            let line = format!("{}\n", table);
+            if summary.len() + line.len() > max_chars {
+                summary.push_str("... (call describe_schema for the remaining tables)\n");
+                break;
+            }
+            summary.push_str(&line);
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_source::ToolSource;
+    use serde_json::json;
+
+    async fn shop() -> (tempfile::TempDir, SqlTool) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.db");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO users (name) VALUES ('ada'), ('bob'), ('cy');",
+        )
+        .unwrap();
+        let url = format!("sqlite:{}", path.display());
+        (dir, SqlTool::connect(&url).await.unwrap())
+    }
+
+    /// **Scenario**: The schema read at connect time is in the run_sql description; rows come
+    /// back as JSON, cut at max_rows with a flag.
+    #[tokio::test]
+    async fn run_sql_returns_rows_and_describes_schema() {
+        let (_dir, sql) = shop().await;
+        let source = sql.with_max_rows(2).into_tool_source().await;
+        let tools = source.list_tools().await.unwrap();
+        let run_sql = tools.iter().find(|t| t.name == TOOL_RUN_SQL).unwrap();
+        let description = run_sql.description.as_deref().unwrap();
+        assert!(
+            description.contains("users(id INTEGER, name TEXT)"),
+            "{}",
+            description
+        );
+
+        let out = source
+            .call_tool(
+                TOOL_RUN_SQL,
+                json!({"query": "SELECT id, name FROM users ORDER BY id"}),
+            )
+            .await
+            .unwrap();
+        let out: serde_json::Value = serde_json::from_str(&out.text).unwrap();
+        assert_eq!(out["columns"], json!(["id", "name"]));
+        assert_eq!(out["rows"], json!([[1, "ada"], [2, "bob"]]));
+        assert_eq!(out["truncated"], json!(true));
+
+        let schema = source
+            .call_tool(TOOL_DESCRIBE_SCHEMA, json!({"table": "users"}))
+            .await
+            .unwrap();
+        assert_eq!(schema.text, "users(id INTEGER, name TEXT)");
+    }
+
+    /// **Scenario**: Writes are refused in read-only mode and run with the affected-row count
+    /// otherwise; SQL errors and unknown URL schemes are invalid input.
+    #[tokio::test]
+    async fn read_only_refuses_writes() {
+        let (_dir, sql) = shop().await;
+        let delete = json!({"query": "DELETE FROM users WHERE name = 'cy'"});
+
+        let err = RunSqlTool::new(sql.clone())
+            .call(delete.clone(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{}", err);
+
+        let out = RunSqlTool::new(sql.clone().with_read_only(false))
+            .call(delete, None)
+            .await
+            .unwrap();
+        assert_eq!(out.text, r#"{"rows_affected":1}"#);
+
+        let err = RunSqlTool::new(sql)
+            .call(json!({"query": "SELECT nope FROM users"}), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolSourceError::InvalidInput(_)), "{}", err);
+        assert!(matches!(
+            SqlTool::connect("oracle://db").await,
+            Err(SqlToolError::UnsupportedUrl(_))
+        ));
+    }
+
+    /// **Scenario**: In read-only mode ATTACH cannot create a database file.
+    #[tokio::test]
+    async fn read_only_refuses_attach() {
+        let (dir, sql) = shop().await;
+        let target = dir.path().join("created.db");
+        let attach = format!("ATTACH DATABASE '{}' AS x", target.display());
+
+        let result = RunSqlTool::new(sql)
+            .call(json!({ "query": attach }), None)
+            .await;
+        assert!(result.is_err(), "{:?}", result.map(|c| c.text));
+        assert!(!target.exists());
+    }
+}
@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+
+use super::SqlTool;
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::notify::required_str;
+use crate::tools::Tool;
+
+/// Tool name for running one SQL statement.
+pub const TOOL_RUN_SQL: &str = "run_sql";
+
+/// Tool that runs one SQL statement and returns its rows as JSON.
+///
+/// The result is `{"columns": [...], "rows": [[...], ...]}`, with `"truncated": true` when
+/// more than `max_rows` rows matched; statements without a result set return
+/// `{"rows_affected": n}`. The description lists the tables read at connect time.
+///
+/// # Interaction
+///
+/// - **SqlTool**: Always registered.
+/// - **ToolSourceError**: `InvalidInput` for SQL errors, writes in read-only mode and
+///   timeouts; `Transport` for connection failures.
+pub struct RunSqlTool {
+    sql: SqlTool,
+}
+
+impl RunSqlTool {
+    /// Tool running statements on `sql`'s database.
+    pub fn new(sql: SqlTool) -> Self {
+        Self { sql }
+    }
+}
+
+#[async_trait]
+impl Tool for RunSqlTool {
+    fn name(&self) -> &str {
+        TOOL_RUN_SQL
+    }
+
+    fn spec(&self) -> ToolSpec {
+        let mut description = format!(
+            "Run one {} statement and get the result as JSON (at most {} rows; use LIMIT, \
+             WHERE or aggregates for large tables).",
+            self.sql.database.dialect(),
+            self.sql.max_rows
+        );
+        if self.sql.read_only {
+            description.push_str(" The database is read-only: only queries are allowed.");
+        }
+        let tables = self.sql.schema_summary(self.sql.max_schema_chars);
+        if !tables.is_empty() {
+            description.push_str("\n\nTables:\n");
+            description.push_str(tables.trim_end());
+        }
+        ToolSpec {
+            name: TOOL_RUN_SQL.to_string(),
+            description: Some(description),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "One SQL statement"
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let query = required_str(&args, "query")?;
+        let output = self
+            .sql
+            .database
+            .query(
+                query,
+                self.sql.read_only,
+                self.sql.max_rows,
+                self.sql.timeout,
+            )
+            .await?;
+        let mut result = Map::new();
+        match output.rows_affected {
+            Some(affected) => {
+                result.insert("rows_affected".into(), json!(affected));
+            }
+            None => {
+                result.insert("columns".into(), json!(output.columns));
+                result.insert("rows".into(), json!(output.rows));
+                if output.truncated {
+                    result.insert("truncated".into(), json!(true));
+                }
+            }
+        }
+        Ok(ToolCallContent {
+            text: Value::Object(result).to_string(),
+        })
+    }
+}
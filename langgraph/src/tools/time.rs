@@ -0,0 +1,268 @@
+//! Time tool: `get_time` returns the current time in a requested IANA timezone.
+//!
+//! The time comes from a [`Clock`]: [`SystemClock`] by default, a [`FixedClock`] in tests so
+//! results are deterministic. The result is formatted as RFC 3339 unless the model asks for
+//! `rfc2822`, `unix` or a strftime pattern; with a `locale` (e.g. `de_DE`), day and month
+//! names are localized and the default format is the locale's own (`%c`).
+//!
+//! **Interaction**: Registered by default in the tool source `build_react_runner` builds; can be
+//! registered on any [`AggregateToolSource`](crate::tools::AggregateToolSource).
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Locale, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::{json, Value};
+
+use crate::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use crate::tools::Tool;
+
+/// Tool name for getting the current time.
+pub const TOOL_GET_TIME: &str = "get_time";
+
+/// Source of the current time for [`TimeTool`].
+pub trait Clock: Send + Sync {
+    /// The current instant, in UTC.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system's wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant; for tests and reproducible runs.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl FixedClock {
+    /// Clock stopped at `secs` unix seconds (the epoch when out of range).
+    pub fn at_unix(secs: i64) -> Self {
+        Self(Utc.timestamp_opt(secs, 0).single().unwrap_or_default())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Tool that returns the current time in a timezone, optionally formatted and localized.
+///
+/// Arguments (all optional): `timezone` (IANA name, default `UTC`), `format` (`rfc3339`,
+/// `rfc2822`, `unix` or a strftime pattern) and `locale` (e.g. `fr_FR`). Returns
+/// `{"time", "timezone", "utc_offset", "unix"}`.
+///
+/// # Interaction
+///
+/// - **Clock**: Reads the time from the clock given to [`with_clock`](Self::with_clock).
+/// - **ToolRegistry**: Registers this tool by name "get_time".
+pub struct TimeTool {
+    clock: Arc<dyn Clock>,
+}
+
+impl TimeTool {
+    /// Time tool over the system clock.
+    pub fn new() -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Reads the time from `clock` instead (builder style).
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Formats `now` per the tool arguments.
+    fn format(now: DateTime<Utc>, args: &Value) -> Result<Value, ToolSourceError> {
+        let arg = |key: &str| {
+            args.get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        let tz: Tz = match arg("timezone") {
+            Some(name) => name.parse().map_err(|_| {
+                ToolSourceError::InvalidInput(format!("unknown IANA timezone: {}", name))
+            })?,
+            None => Tz::UTC,
+        };
+        let locale = arg("locale")
+            .map(|name| {
+                Locale::try_from(name)
+                    .map_err(|_| ToolSourceError::InvalidInput(format!("unknown locale: {}", name)))
+            })
+            .transpose()?;
+        let local = now.with_timezone(&tz);
+
+        let time = match (arg("format"), locale) {
+            (Some("rfc3339"), _) | (None, None) => local.to_rfc3339(),
+            (Some("rfc2822"), _) => local.to_rfc2822(),
+            (Some("unix"), _) => local.timestamp().to_string(),
+            (pattern, locale) => {
+                let pattern = pattern.unwrap_or("%c");
+                if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+                    return Err(ToolSourceError::InvalidInput(format!(
+                        "invalid strftime format: {}",
+                        pattern
+                    )));
+                }
+                let mut out = String::new();
+                let written = match locale {
+                    Some(locale) => write!(out, "{}", local.format_localized(pattern, locale)),
+                    None => write!(out, "{}", local.format(pattern)),
+                };
+                written.map_err(|_| {
+                    ToolSourceError::InvalidInput(format!("cannot format time as {}", pattern))
+                })?;
+                out
+            }
+        };
+        Ok(json!({
+            "time": time,
+            "timezone": tz.name(),
+            "utc_offset": local.format("%:z").to_string(),
+            "unix": local.timestamp(),
+        }))
+    }
+}
+
+impl Default for TimeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for TimeTool {
+    fn name(&self) -> &str {
+        TOOL_GET_TIME
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: TOOL_GET_TIME.to_string(),
+            description: Some(
+                "Get the current date and time. Use ONLY when the user asks for the current date \
+                 or time, or needs it (e.g. 'what time is it in Tokyo'). Do NOT use for math or \
+                 general knowledge."
+                    .to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "timezone": {
+                        "type": "string",
+                        "description": "IANA timezone, e.g. Europe/Paris or America/New_York (default UTC)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "description": "rfc3339 (default), rfc2822, unix, or a strftime pattern such as %Y-%m-%d %H:%M"
+                    },
+                    "locale": {
+                        "type": "string",
+                        "description": "Locale for day and month names, e.g. fr_FR or ja_JP"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Value,
+        _ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let result = Self::format(self.clock.now(), &args)?;
+        Ok(ToolCallContent {
+            text: result.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-01-29T12:00:00Z, a Wednesday.
+    const NOON: i64 = 1_738_152_000;
+
+    async fn get_time(args: Value) -> Result<Value, ToolSourceError> {
+        let tool = TimeTool::new().with_clock(FixedClock::at_unix(NOON));
+        let out = tool.call(args, None).await?;
+        Ok(serde_json::from_str(&out.text).unwrap())
+    }
+
+    /// **Scenario**: Without arguments the tool returns the clock's time in UTC as RFC 3339.
+    #[tokio::test]
+    async fn defaults_to_utc_rfc3339() {
+        let out = get_time(json!({})).await.unwrap();
+        assert_eq!(out["time"], "2025-01-29T12:00:00+00:00");
+        assert_eq!(out["timezone"], "UTC");
+        assert_eq!(out["utc_offset"], "+00:00");
+        assert_eq!(out["unix"], NOON);
+    }
+
+    /// **Scenario**: The time is converted to the requested IANA timezone, with its offset.
+    #[tokio::test]
+    async fn converts_to_requested_timezone() {
+        let out = get_time(json!({"timezone": "America/New_York"}))
+            .await
+            .unwrap();
+        assert_eq!(out["time"], "2025-01-29T07:00:00-05:00");
+        assert_eq!(out["utc_offset"], "-05:00");
+
+        let out = get_time(json!({"timezone": "Asia/Tokyo", "format": "%Y-%m-%d %H:%M"}))
+            .await
+            .unwrap();
+        assert_eq!(out["time"], "2025-01-29 21:00");
+        assert_eq!(out["unix"], NOON);
+    }
+
+    /// **Scenario**: A locale localizes day and month names of a strftime pattern.
+    #[tokio::test]
+    async fn locale_localizes_names() {
+        let out = get_time(json!({
+            "timezone": "Europe/Berlin",
+            "format": "%A, %d. %B %Y %H:%M",
+            "locale": "de_DE"
+        }))
+        .await
+        .unwrap();
+        assert_eq!(out["time"], "Mittwoch, 29. Januar 2025 13:00");
+
+        let out = get_time(json!({"format": "rfc2822", "locale": "de_DE"}))
+            .await
+            .unwrap();
+        assert_eq!(out["time"], "Wed, 29 Jan 2025 12:00:00 +0000");
+    }
+
+    /// **Scenario**: Unknown timezones, locales and format specifiers are invalid input.
+    #[tokio::test]
+    async fn rejects_invalid_arguments() {
+        for args in [
+            json!({"timezone": "Mars/Olympus_Mons"}),
+            json!({"locale": "xx_YY"}),
+            json!({"format": "%Q"}),
+        ] {
+            let err = get_time(args.clone()).await.unwrap_err();
+            assert!(
+                matches!(err, ToolSourceError::InvalidInput(_)),
+                "{}: {:?}",
+                args,
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Content extraction for `web_fetcher`: the main content of an HTML page as markdown or text.
+//!
+//! A light readability pass picks the page's main content (the first `<article>`, `<main>` or
+//! `role="main"` element, else `<body>`) and drops markup that is never content: scripts,
+//! styles, navigation, page headers and footers, sidebars and forms. What remains is converted
+//! to markdown (headings, links, lists and tables survive) or reduced to plain text.
+//!
+//! **Interaction**: Used by [`WebFetcherTool`](super::WebFetcherTool) for the `markdown` and
+//! `text` formats; feature `html`.
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements whose content is dropped.
+const SKIPPED_TAGS: [&str; 12] = [
+    "script", "style", "noscript", "template", "head", "nav", "header", "footer", "aside", "form",
+    "iframe", "svg",
+];
+
+/// Elements that start a new line in text output.
+const BLOCK_TAGS: [&str; 22] = [
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "table",
+    "tr",
+    "blockquote",
+    "pre",
+    "br",
+    "hr",
+    "dt",
+    "dd",
+];
+
+/// The element holding the page's main content.
+fn main_element(doc: &Html) -> ElementRef<'_> {
+    for selector in ["article", "main", "[role=main]", "body"] {
+        let selector = Selector::parse(selector).expect("static selector");
+        if let Some(element) = doc.select(&selector).next() {
+            return element;
+        }
+    }
+    doc.root_element()
+}
+
+/// Collapses runs of blank lines to one and trims the ends.
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push('\n');
+            blank = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Main content of `html` as markdown.
+pub(super) fn to_markdown(html: &str) -> String {
+    let doc = Html::parse_document(html);
+    let content = main_element(&doc).html();
+    let converter = htmd::HtmlToMarkdown::builder()
+        .skip_tags(SKIPPED_TAGS.to_vec())
+        .build();
+    match converter.convert(&content) {
+        Ok(markdown) => collapse_blank_lines(&markdown),
+        Err(_) => to_text(html),
+    }
+}
+
+/// Main content of `html` as plain text, one line per block element.
+pub(super) fn to_text(html: &str) -> String {
+    fn end_line(out: &mut String) {
+        out.truncate(out.trim_end_matches(' ').len());
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    fn walk(element: ElementRef<'_>, out: &mut String) {
+        for child in element.children() {
+            match child.value() {
+                // Source line breaks are plain spaces; lines come from block elements.
+                Node::Text(text) => {
+                    out.extend(
+                        text.chars()
+                            .map(|c| if c.is_whitespace() { ' ' } else { c }),
+                    )
+                }
+                Node::Element(e) if SKIPPED_TAGS.contains(&e.name()) => {}
+                Node::Element(e) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    let block = BLOCK_TAGS.contains(&e.name());
+                    if block {
+                        end_line(out);
+                    }
+                    walk(child, out);
+                    if block {
+                        end_line(out);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let doc = Html::parse_document(html);
+    let mut out = String::new();
+    walk(main_element(&doc), &mut out);
+    let lines: Vec<String> = out
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    collapse_blank_lines(&lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title>T</title><style>p { color: red }</style></head>
+        <body><nav><a href="/">Home</a></nav>
+        <article><h1>Release notes</h1><p>Version <b>2.0</b> adds
+        <a href="https://example.com/docs">docs</a>.</p><script>track()</script>
+        <ul><li>Faster</li><li>Smaller</li></ul></article>
+        <footer>Copyright</footer></body></html>"#;
+
+    /// **Scenario**: Markdown keeps the article's headings, links and lists and drops
+    /// navigation, scripts, styles and the footer.
+    #[test]
+    fn markdown_keeps_main_content() {
+        let markdown = to_markdown(PAGE);
+        assert!(markdown.starts_with("# Release notes"), "{}", markdown);
+        assert!(
+            markdown.contains("[docs](https://example.com/docs)"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("Faster") && markdown.contains("Smaller"));
+        for dropped in ["Home", "track()", "color", "Copyright"] {
+            assert!(!markdown.contains(dropped), "{}", markdown);
+        }
+    }
+
+    /// **Scenario**: Text output has one line per block and no markup.
+    #[test]
+    fn text_has_one_line_per_block() {
+        assert_eq!(
+            to_text(PAGE),
+            "Release notes\nVersion 2.0 adds docs.\nFaster\nSmaller"
+        );
+    }
+}
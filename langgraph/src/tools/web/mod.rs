@@ -1,3 +1,8 @@
+//! `web_fetcher`: HTTP GET / POST for the LLM, with HTML pages reduced to their main content.
+
+#[cfg(feature = "html")]
+mod extract;
+
 use async_trait::async_trait;
 
 use serde_json::json;
@@ -8,11 +13,84 @@ use crate::tools::Tool;
 /// Tool name for the web fetcher operation.
 pub const TOOL_WEB_FETCHER: &str = "web_fetcher";
 
+/// Characters of content `web_fetcher` returns by default; see
+/// [`WebFetcherTool::with_max_length`].
+pub const DEFAULT_MAX_LENGTH: usize = 50_000;
+
+/// How `web_fetcher` returns a response body (`format` argument).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// HTML pages as the markdown of their main content (feature `html`).
+    Markdown,
+    /// HTML pages as the plain text of their main content (feature `html`).
+    Text,
+    /// The body as received.
+    Raw,
+}
+
+impl Format {
+    fn parse(format: Option<&str>) -> Result<Self, ToolSourceError> {
+        let format = match format.map(str::to_ascii_lowercase).as_deref() {
+            None if cfg!(feature = "html") => Self::Markdown,
+            None | Some("raw") => Self::Raw,
+            Some("markdown") => Self::Markdown,
+            Some("text") => Self::Text,
+            Some(other) => {
+                return Err(ToolSourceError::InvalidInput(format!(
+                    "unsupported format: {} (use markdown, text or raw)",
+                    other
+                )))
+            }
+        };
+        if format != Self::Raw && !cfg!(feature = "html") {
+            return Err(ToolSourceError::InvalidInput(
+                "markdown and text formats need langgraph's `html` feature; use raw".to_string(),
+            ));
+        }
+        Ok(format)
+    }
+
+    /// Converts an HTML `body`; other content types are returned as received.
+    #[cfg(feature = "html")]
+    fn apply(self, body: String, is_html: bool) -> String {
+        match self {
+            Self::Markdown if is_html => extract::to_markdown(&body),
+            Self::Text if is_html => extract::to_text(&body),
+            _ => body,
+        }
+    }
+
+    #[cfg(not(feature = "html"))]
+    fn apply(self, body: String, _is_html: bool) -> String {
+        body
+    }
+}
+
+/// Cuts `text` after `max` characters and says so.
+fn truncate(text: String, max: usize) -> String {
+    let Some((cut, _)) = text.char_indices().nth(max) else {
+        return text;
+    };
+    let total = text.chars().count();
+    let mut shown = text[..cut].to_string();
+    shown.push_str(&format!(
+        "\n\n[content truncated: showing the first {} of {} characters]",
+        max, total
+    ));
+    shown
+}
+
 /// Tool for HTTP requests to URLs (GET or POST).
 ///
 /// Wraps reqwest::Client and exposes it as a tool for the LLM.
 /// Supports GET (default) and POST with optional body and headers.
 ///
+/// With the `html` feature, HTML pages come back as the markdown of their main content
+/// (`format: "markdown"`, the default) or its plain text (`"text"`); `"raw"` returns the body
+/// as received, which is also what other content types and builds without the feature get.
+/// Content longer than [`DEFAULT_MAX_LENGTH`] characters (or the call's `max_length`) is cut
+/// off with a "content truncated" note.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -46,6 +124,7 @@ pub const TOOL_WEB_FETCHER: &str = "web_fetcher";
 /// - **ToolSourceError**: Maps HTTP errors to tool error types
 pub struct WebFetcherTool {
     client: reqwest::Client,
+    max_length: usize,
 }
 
 impl Default for WebFetcherTool {
@@ -67,9 +146,7 @@ impl WebFetcherTool {
     /// let tool = WebFetcherTool::new();
     /// ```
     pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
-        }
+        Self::with_client(reqwest::Client::new())
     }
 
     /// Creates a new WebFetcherTool with a custom HTTP client.
@@ -91,7 +168,17 @@ impl WebFetcherTool {
     /// let tool = WebFetcherTool::with_client(client);
     /// ```
     pub fn with_client(client: reqwest::Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            max_length: DEFAULT_MAX_LENGTH,
+        }
+    }
+
+    /// Characters of content returned at most, unless a call asks for fewer (default
+    /// [`DEFAULT_MAX_LENGTH`]).
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
     }
 }
 
@@ -119,7 +206,9 @@ impl Tool for WebFetcherTool {
             description: Some(
                 "Fetch or send content to a URL. Use this tool to retrieve web pages (GET), call \
                  APIs with a body (POST), or other HTTP-accessible content. Optional: method (default \
-                 GET), body (string or JSON object), headers (object). Returns the response body as text.".to_string(),
+                 GET), body (string or JSON object), headers (object), format (markdown, text or \
+                 raw), max_length (characters). Returns the response body as text; HTML pages \
+                 as the markdown of their main content by default.".to_string(),
             ),
             input_schema: json!({
                 "type": "object",
@@ -140,6 +229,16 @@ impl Tool for WebFetcherTool {
                         "type": "object",
                         "description": "Optional HTTP headers as key-value pairs (string keys and values).",
                         "additionalProperties": { "type": "string" }
+                    },
+                    "format": {
+                        "type": "string",
+                        "description": "HTML pages as main-content markdown (default), plain text, or raw HTML.",
+                        "enum": ["markdown", "text", "raw"]
+                    },
+                    "max_length": {
+                        "type": "integer",
+                        "description": "Maximum characters to return; longer content is truncated.",
+                        "minimum": 1
                     }
                 },
                 "required": ["url"]
@@ -151,18 +250,20 @@ impl Tool for WebFetcherTool {
     ///
     /// # Parameters
     ///
-    /// - `args`: JSON with required "url"; optional "method" (GET|POST), "body", "headers"
+    /// - `args`: JSON with required "url"; optional "method" (GET|POST), "body", "headers",
+    ///   "format" (markdown|text|raw), "max_length"
     /// - `_ctx`: Optional per-call context (not used by this tool)
     ///
     /// # Returns
     ///
-    /// The HTTP response body as text content.
+    /// The HTTP response body as text content, converted per "format" and truncated to the
+    /// maximum length.
     ///
     /// # Errors
     ///
     /// Returns ToolSourceError for:
     /// - Missing or invalid "url" (InvalidInput)
-    /// - Unsupported "method" (InvalidInput)
+    /// - Unsupported "method" or "format" (InvalidInput)
     /// - HTTP request failures (Transport)
     /// - Non-success HTTP status codes (Transport)
     /// - Response read failures (Transport)
@@ -191,6 +292,11 @@ impl Tool for WebFetcherTool {
                 method
             )));
         }
+        let format = Format::parse(args.get("format").and_then(|v| v.as_str()))?;
+        let max_length = args
+            .get("max_length")
+            .and_then(|v| v.as_u64())
+            .map_or(self.max_length, |n| (n as usize).clamp(1, self.max_length));
 
         let mut request = match method.as_str() {
             "GET" => self.client.get(url),
@@ -231,11 +337,47 @@ impl Tool for WebFetcherTool {
             )));
         }
 
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("html"));
         let content = response
             .text()
             .await
             .map_err(|e| ToolSourceError::Transport(format!("failed to read response: {}", e)))?;
 
-        Ok(ToolCallContent { text: content })
+        Ok(ToolCallContent {
+            text: truncate(format.apply(content, is_html), max_length),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// **Scenario**: Content over the limit is cut at a character boundary with a note; shorter
+    /// content is unchanged.
+    #[test]
+    fn truncate_marks_cut_content() {
+        assert_eq!(truncate("short".to_string(), 5), "short");
+        assert_eq!(
+            truncate("héllo world".to_string(), 2),
+            "hé\n\n[content truncated: showing the first 2 of 11 characters]"
+        );
+    }
+
+    /// **Scenario**: Formats parse case-insensitively; unknown ones are refused.
+    #[test]
+    fn format_parses_known_values() {
+        assert_eq!(Format::parse(Some("RAW")).unwrap(), Format::Raw);
+        assert!(Format::parse(Some("pdf")).is_err());
+        let default = if cfg!(feature = "html") {
+            Format::Markdown
+        } else {
+            Format::Raw
+        };
+        assert_eq!(Format::parse(None).unwrap(), default);
     }
 }
@@ -0,0 +1,204 @@
+//! Integration test: callback handlers see graph, node, LLM and tool events of a ReAct run.
+
+mod init_logging;
+
+use std::sync::{Arc, Mutex};
+
+use langgraph::{
+    ActNode, AgentError, CallbackHandler, Callbacks, LlmResponse, Message, MockLlm, MockToolSource,
+    ObserveNode, ReActState, ReactRunner, RunnableConfig, StateGraph, ThinkNode, ToolCall,
+    ToolSourceError, END, START,
+};
+
+/// Records every event as `"<name>:<event>"`, plus the run ids it was given.
+struct Recorder {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+    run_ids: Mutex<Vec<String>>,
+}
+
+impl Recorder {
+    fn new(name: &'static str, events: Arc<Mutex<Vec<String>>>) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            events,
+            run_ids: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn record(&self, run_id: &str, event: String) {
+        self.run_ids.lock().unwrap().push(run_id.to_string());
+        let event = format!("{}:{}", self.name, event);
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+fn outcome(error: Option<&AgentError>) -> &'static str {
+    if error.is_some() {
+        "err"
+    } else {
+        "ok"
+    }
+}
+
+impl CallbackHandler for Recorder {
+    fn on_graph_start(&self, run_id: &str) {
+        self.record(run_id, "graph_start".into());
+    }
+
+    fn on_graph_end(&self, run_id: &str, error: Option<&AgentError>) {
+        self.record(run_id, format!("graph_end:{}", outcome(error)));
+    }
+
+    fn on_node_start(&self, run_id: &str, node_id: &str) {
+        self.record(run_id, format!("node_start:{}", node_id));
+    }
+
+    fn on_node_end(&self, run_id: &str, node_id: &str, error: Option<&AgentError>) {
+        self.record(run_id, format!("node_end:{}:{}", node_id, outcome(error)));
+    }
+
+    fn on_llm_start(&self, run_id: &str, messages: &[Message]) {
+        let last_is_user = matches!(messages.last(), Some(Message::User(_)));
+        self.record(run_id, format!("llm_start:{}", last_is_user));
+    }
+
+    fn on_llm_end(&self, run_id: &str, response: &LlmResponse) {
+        let calls = response.tool_calls.len();
+        self.record(run_id, format!("llm_end:{}", calls));
+    }
+
+    fn on_tool_start(&self, run_id: &str, call: &ToolCall) {
+        self.record(run_id, format!("tool_start:{}", call.name));
+    }
+
+    fn on_tool_end(&self, run_id: &str, call: &ToolCall, result: &str) {
+        let dated = result.contains("2025-01-29");
+        self.record(run_id, format!("tool_end:{}:{}", call.name, dated));
+    }
+
+    fn on_tool_error(&self, run_id: &str, call: &ToolCall, _error: &ToolSourceError) {
+        self.record(run_id, format!("tool_error:{}", call.name));
+    }
+}
+
+/// **Scenario**: Handlers passed in `RunnableConfig::callbacks` see the LLM requests and the
+/// tool call of a ReAct run, between the run's start and end, all under one run id.
+#[tokio::test]
+async fn config_callbacks_see_llm_and_tool_events() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Recorder::new("run", events.clone());
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    let config = Some(RunnableConfig {
+        callbacks: Callbacks::new().with_handler(recorder.clone()),
+        ..Default::default()
+    });
+    runner
+        .invoke_with_config("What time is it?", config)
+        .await
+        .expect("run");
+
+    let events = events.lock().unwrap().clone();
+    assert_eq!(events.first().map(String::as_str), Some("run:graph_start"));
+    assert_eq!(events.last().map(String::as_str), Some("run:graph_end:ok"));
+    let calls: Vec<&str> = events
+        .iter()
+        .map(String::as_str)
+        .filter(|e| e.starts_with("run:llm_") || e.starts_with("run:tool_"))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            "run:llm_start:true",
+            "run:llm_end:1",
+            "run:tool_start:get_time",
+            "run:tool_end:get_time:true",
+            "run:llm_start:false",
+            "run:llm_end:0",
+        ]
+    );
+    assert!(events.contains(&"run:node_end:act:ok".to_string()));
+
+    let run_ids = recorder.run_ids.lock().unwrap();
+    assert!(!run_ids[0].is_empty());
+    assert!(run_ids.iter().all(|id| *id == run_ids[0]));
+}
+
+/// **Scenario**: Handlers registered on the graph run for every invoke, each event before the
+/// per-run handlers; node hooks wrap the LLM and tool hooks of their node.
+#[tokio::test]
+async fn graph_callbacks_run_before_config_callbacks() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut graph = StateGraph::<ReActState>::new()
+        .with_callback_handler(Recorder::new("graph", events.clone()));
+    graph
+        .add_node(
+            "think",
+            Arc::new(ThinkNode::new(Box::new(MockLlm::with_get_time_call()))),
+        )
+        .add_node(
+            "act",
+            Arc::new(ActNode::new(Box::new(MockToolSource::get_time_example()))),
+        )
+        .add_node("observe", Arc::new(ObserveNode::new()))
+        .add_edge(START, "think")
+        .add_edge("think", "act")
+        .add_edge("act", "observe")
+        .add_edge("observe", END);
+    let compiled = graph.compile().expect("valid graph");
+
+    let state = ReActState {
+        messages: vec![Message::user("What time is it?")],
+        ..Default::default()
+    };
+    let config = RunnableConfig {
+        callbacks: Callbacks::new().with_handler(Recorder::new("config", events.clone())),
+        ..Default::default()
+    };
+    compiled.invoke(state, Some(config)).await.unwrap();
+
+    let seen = events.lock().unwrap().clone();
+    let (graph_events, config_events): (Vec<_>, Vec<_>) =
+        seen.chunks(2).map(|pair| (&pair[0], &pair[1])).unzip();
+    assert_eq!(graph_events.len() * 2, seen.len());
+    for (g, c) in graph_events.iter().zip(&config_events) {
+        assert_eq!(g.strip_prefix("graph:"), c.strip_prefix("config:"));
+    }
+    let graph_events: Vec<&str> = graph_events.iter().map(|e| e.as_str()).collect();
+    assert_eq!(
+        graph_events,
+        [
+            "graph:graph_start",
+            "graph:node_start:think",
+            "graph:llm_start:true",
+            "graph:llm_end:1",
+            "graph:node_end:think:ok",
+            "graph:node_start:act",
+            "graph:tool_start:get_time",
+            "graph:tool_end:get_time:true",
+            "graph:node_end:act:ok",
+            "graph:node_start:observe",
+            "graph:node_end:observe:ok",
+            "graph:graph_end:ok",
+        ]
+    );
+
+    // Without per-run handlers, only the graph's run.
+    let state = ReActState {
+        messages: vec![Message::user("Again?")],
+        ..Default::default()
+    };
+    compiled.invoke(state, None).await.unwrap();
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), seen.len() + graph_events.len());
+    assert!(events[seen.len()..].iter().all(|e| e.starts_with("graph:")));
+}
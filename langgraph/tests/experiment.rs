@@ -0,0 +1,98 @@
+//! Integration test: runners of an A/B experiment tag their runs and record usage per variant.
+
+mod init_logging;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use langgraph::memory::{InMemoryStore, ListNamespacesOptions, MemorySaver, RunnableConfig, Store};
+use langgraph::{
+    Experiment, ExperimentVariant, LlmUsage, MockLlm, MockToolSource, ReActState, ReactRunner,
+    RuleOutcomeClassifier, UsageQuota, Variant,
+};
+
+/// Runner answering `reply` and reporting `tokens` per call, on the shared checkpointer and quota.
+fn runner(
+    reply: &str,
+    tokens: u32,
+    checkpointer: &Arc<MemorySaver<ReActState>>,
+    quota: &Arc<UsageQuota>,
+) -> ReactRunner {
+    let llm = MockLlm::with_no_tool_calls(reply).with_usage(LlmUsage {
+        prompt_tokens: tokens,
+        completion_tokens: 0,
+        total_tokens: tokens,
+    });
+    ReactRunner::new(
+        Box::new(llm),
+        Box::new(MockToolSource::get_time_example()),
+        Some(checkpointer.clone()),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile")
+    .with_usage_quota(Arc::clone(quota))
+    .with_outcome_classifier(Arc::new(RuleOutcomeClassifier))
+}
+
+/// Tags of the latest checkpoint of `thread_id`, once the background tagging added some.
+async fn latest_tags(runner: &ReactRunner, thread_id: &str) -> Vec<String> {
+    for _ in 0..200 {
+        let checkpoints = runner.list_checkpoints(thread_id).await.unwrap();
+        let tags = checkpoints.last().map(|c| c.metadata.tags.clone());
+        if let Some(tags) = tags.filter(|t| !t.is_empty()) {
+            return tags;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    Vec::new()
+}
+
+/// **Scenario**: A thread runs on the variant it is assigned; its checkpoint is tagged with the
+/// variant next to the outcome, and the run's tokens are recorded under the variant only.
+#[tokio::test]
+async fn runs_are_tagged_and_metered_per_variant() {
+    let checkpointer = Arc::new(MemorySaver::<ReActState>::new());
+    let store = Arc::new(InMemoryStore::new());
+    let quota = Arc::new(UsageQuota::new(store.clone()));
+    let experiment = Experiment::new(
+        "profile",
+        runner("from a", 10, &checkpointer, &quota),
+        runner("from b", 30, &checkpointer, &quota),
+    );
+    let thread_id = (0..)
+        .map(|i| format!("thread-{}", i))
+        .find(|t| experiment.variant_for(t) == Variant::B)
+        .unwrap();
+    let config = RunnableConfig {
+        thread_id: Some(thread_id.clone()),
+        ..Default::default()
+    };
+
+    let variant = experiment.assign(Some(&config), "req-1");
+    let runner = experiment.runner(variant);
+    let state = runner
+        .invoke_with_config("hi", Some(config))
+        .await
+        .expect("run");
+    assert_eq!(state.last_assistant_reply().as_deref(), Some("from b"));
+    assert_eq!(
+        latest_tags(runner, &thread_id).await,
+        ["experiment:profile=b", "answered"]
+    );
+
+    let namespaces = store
+        .list_namespaces(ListNamespacesOptions::new())
+        .await
+        .unwrap();
+    let day = &namespaces[0][1];
+    let on_b = ExperimentVariant::new("profile", Variant::B);
+    let on_a = ExperimentVariant::new("profile", Variant::A);
+    let usage = quota.experiment_usage(day, &on_b).await.unwrap();
+    assert_eq!(usage.values().map(|u| u.total_tokens).sum::<u32>(), 30);
+    assert!(quota.experiment_usage(day, &on_a).await.unwrap().is_empty());
+    let total = quota.usage(day, None).await.unwrap();
+    assert_eq!(total.values().map(|u| u.total_tokens).sum::<u32>(), 30);
+}
@@ -0,0 +1,64 @@
+//! Integration test: a runner's history compressor shapes what the LLM is sent while the
+//! checkpointed state keeps the full history.
+
+mod init_logging;
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use langgraph::memory::{MemorySaver, RunnableConfig};
+use langgraph::{HistoryCompressor, Message, MockLlm, MockToolSource, ReActState, ReactRunner};
+
+/// Records how many messages it was given and keeps only the system prompt and the latest one.
+#[derive(Default)]
+struct KeepLast {
+    seen: Mutex<Vec<usize>>,
+}
+
+#[async_trait]
+impl HistoryCompressor for KeepLast {
+    async fn compress(&self, messages: Vec<Message>) -> Vec<Message> {
+        self.seen.lock().unwrap().push(messages.len());
+        let last = messages.last().cloned();
+        messages
+            .into_iter()
+            .filter(|m| matches!(m, Message::System(_)))
+            .chain(last)
+            .collect()
+    }
+}
+
+/// **Scenario**: On the second run of a thread the compressor sees the whole history, and
+/// the run's state still holds every message of both runs.
+#[tokio::test]
+async fn compressor_sees_history_and_state_keeps_it() {
+    let compressor = Arc::new(KeepLast::default());
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("ok")),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile")
+    .with_history_compressor(compressor.clone());
+    let config = RunnableConfig {
+        thread_id: Some("long".into()),
+        ..Default::default()
+    };
+
+    runner
+        .invoke_with_config("first", Some(config.clone()))
+        .await
+        .expect("first run");
+    let state = runner
+        .invoke_with_config("second", Some(config))
+        .await
+        .expect("second run");
+
+    // system, first, ok, second, ok
+    assert_eq!(state.messages.len(), 5);
+    assert_eq!(*compressor.seen.lock().unwrap(), [2, 4]);
+}
@@ -4,8 +4,8 @@
 mod init_logging;
 
 use langgraph::memory::{
-    Checkpoint, CheckpointMetadata, CheckpointSource, MemorySaver, RunnableConfig,
-    CHECKPOINT_VERSION,
+    Checkpoint, CheckpointMetadata, CheckpointRetention, CheckpointSource, MemorySaver,
+    RunnableConfig, CHECKPOINT_VERSION,
 };
 use langgraph::Checkpointer;
 use std::collections::HashMap;
@@ -23,6 +23,7 @@ async fn memory_saver_put_and_get_tuple() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     let checkpoint = Checkpoint {
         v: CHECKPOINT_VERSION,
@@ -35,11 +36,14 @@ async fn memory_saver_put_and_get_tuple() {
         versions_seen: HashMap::new(),
         updated_channels: None,
         pending_sends: Vec::new(),
+        pending_node: None,
         metadata: CheckpointMetadata {
             source: CheckpointSource::Update,
             step: 0,
             created_at: None,
             parents: HashMap::new(),
+            parent_checkpoint_id: None,
+            tags: Vec::new(),
         },
     };
     let id = saver.put(&config, &checkpoint).await.unwrap();
@@ -59,6 +63,7 @@ async fn memory_saver_get_tuple_empty_returns_none() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     let tuple = saver.get_tuple(&config).await.unwrap();
     assert!(tuple.is_none());
@@ -72,6 +77,7 @@ async fn memory_saver_list_returns_empty_when_no_checkpoints() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     let list = saver.list(&config, None, None, None).await.unwrap();
     assert!(list.is_empty());
@@ -89,3 +95,98 @@ async fn checkpoint_from_state() {
     assert_eq!(cp.metadata.step, 1);
     assert!(cp.channel_versions.is_empty());
 }
+
+fn checkpoint_at(id: &str, millis: u64) -> Checkpoint<TestState> {
+    let mut cp = Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 0);
+    cp.id = id.into();
+    cp.metadata.created_at = Some(std::time::UNIX_EPOCH + std::time::Duration::from_millis(millis));
+    cp
+}
+
+/// **Scenario**: Threads are listed once (across namespaces) with their latest checkpoint
+/// time, newest first; deleting a thread removes all its namespaces and only reports true once.
+#[tokio::test]
+async fn memory_saver_list_and_delete_threads() {
+    let saver: MemorySaver<TestState> = MemorySaver::new();
+    let config = |thread: &str, ns: &str| RunnableConfig {
+        thread_id: Some(thread.into()),
+        checkpoint_ns: ns.into(),
+        ..Default::default()
+    };
+    saver
+        .put(&config("old", ""), &checkpoint_at("a", 1_000))
+        .await
+        .unwrap();
+    saver
+        .put(&config("new", ""), &checkpoint_at("b", 2_000))
+        .await
+        .unwrap();
+    saver
+        .put(&config("new", "sub"), &checkpoint_at("c", 3_000))
+        .await
+        .unwrap();
+
+    let threads = saver
+        .list_threads(&RunnableConfig::default())
+        .await
+        .unwrap();
+    let ids: Vec<&str> = threads.iter().map(|t| t.thread_id.as_str()).collect();
+    assert_eq!(ids, ["new", "old"]);
+    assert_eq!(
+        threads[0].last_activity,
+        Some(std::time::UNIX_EPOCH + std::time::Duration::from_millis(3_000))
+    );
+
+    assert!(saver.delete_thread(&config("new", "")).await.unwrap());
+    assert!(!saver.delete_thread(&config("new", "")).await.unwrap());
+    assert!(saver
+        .get_tuple(&config("new", "sub"))
+        .await
+        .unwrap()
+        .is_none());
+    let threads = saver
+        .list_threads(&RunnableConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+    assert_eq!(threads[0].thread_id, "old");
+}
+
+/// **Scenario**: A MemorySaver with a retention policy keeps only the newest checkpoints of a
+/// thread; the newest stays even when older than the age limit.
+#[tokio::test]
+async fn memory_saver_retention_keeps_newest() {
+    let saver: MemorySaver<TestState> = MemorySaver::new().with_retention(
+        CheckpointRetention::new()
+            .with_max_checkpoints(2)
+            .with_max_age(std::time::Duration::from_secs(3600)),
+    );
+    let config = RunnableConfig {
+        thread_id: Some("t".into()),
+        ..Default::default()
+    };
+    for (id, millis) in [("a", 1_000), ("b", 2_000)] {
+        saver
+            .put(&config, &checkpoint_at(id, millis))
+            .await
+            .unwrap();
+    }
+    let ids = |list: Vec<langgraph::memory::CheckpointListItem>| -> Vec<String> {
+        list.into_iter().map(|i| i.checkpoint_id).collect()
+    };
+    assert_eq!(
+        ids(saver.list(&config, None, None, None).await.unwrap()),
+        ["b"]
+    );
+
+    let mut recent = checkpoint_at("c", 0);
+    for id in ["c", "d", "e"] {
+        recent.id = id.into();
+        recent.metadata.created_at = Some(std::time::SystemTime::now());
+        saver.put(&config, &recent).await.unwrap();
+    }
+    assert_eq!(
+        ids(saver.list(&config, None, None, None).await.unwrap()),
+        ["d", "e"]
+    );
+}
@@ -0,0 +1,123 @@
+//! Integration tests for semantic deduplication of `remember` writes (MemoryDedup).
+//!
+//! Uses InMemoryVectorStore with a bag-of-words embedder so similarity is predictable:
+//! "likes coffee" vs "really likes coffee" scores 0.82, vs "likes tea" 0.5.
+
+mod init_logging;
+
+use async_trait::async_trait;
+use langgraph::memory::{Embedder, InMemoryVectorStore, Store, StoreError};
+use langgraph::tool_source::MemoryToolkit;
+use langgraph::tools::{
+    DuplicateAction, MemoryDedup, MemoryValueSchema, RememberTool, Tool, TOOL_REMEMBER,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+const VOCABULARY: [&str; 6] = ["coffee", "tea", "likes", "hates", "really", "dog"];
+
+/// Embeds a text as the counts of the vocabulary words in it.
+struct WordEmbedder;
+
+#[async_trait]
+impl Embedder for WordEmbedder {
+    async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, StoreError> {
+        Ok(texts
+            .iter()
+            .map(|t| {
+                let mut v = vec![0f32; VOCABULARY.len()];
+                for word in t.split(|c: char| !c.is_alphabetic()) {
+                    if let Some(i) = VOCABULARY.iter().position(|w| w.eq_ignore_ascii_case(word)) {
+                        v[i] += 1.0;
+                    }
+                }
+                v
+            })
+            .collect())
+    }
+
+    fn dimension(&self) -> usize {
+        VOCABULARY.len()
+    }
+}
+
+fn namespace() -> Vec<String> {
+    vec!["u1".to_string(), "memories".to_string()]
+}
+
+/// **Scenario**: With Update, a fact similar to a stored one overwrites it under the stored key
+/// and the tool says so; a distinct fact and a rewrite of the same key are saved as usual.
+#[tokio::test]
+async fn similar_fact_updates_existing_memory() {
+    let store: Arc<dyn Store> = Arc::new(InMemoryVectorStore::new(Arc::new(WordEmbedder)));
+    let toolkit = MemoryToolkit::new(store.clone(), namespace())
+        .with_value_schema(MemoryValueSchema::Text)
+        .with_dedup(MemoryDedup::new(0.8));
+    let tools = toolkit.tools();
+    let remember = tools.iter().find(|t| t.name() == TOOL_REMEMBER).unwrap();
+
+    let r = remember
+        .call(json!({ "key": "drink", "value": "likes coffee" }), None)
+        .await
+        .unwrap();
+    assert_eq!(r.text, "ok");
+
+    let r = remember
+        .call(
+            json!({ "key": "coffee", "value": "really likes coffee" }),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(r.text, "updated similar memory (key: drink)");
+    let stored = store.get(&namespace(), "drink").await.unwrap().unwrap();
+    assert_eq!(stored["text"], "really likes coffee");
+    assert!(store.get(&namespace(), "coffee").await.unwrap().is_none());
+
+    let r = remember
+        .call(json!({ "key": "tea", "value": "likes tea" }), None)
+        .await
+        .unwrap();
+    assert_eq!(r.text, "ok");
+    let r = remember
+        .call(
+            json!({ "key": "drink", "value": "really likes coffee" }),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(r.text, "ok");
+
+    let mut keys = store.list(&namespace()).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, ["drink", "tea"]);
+}
+
+/// **Scenario**: With Skip, a fact similar to a stored one writes nothing and reports the key of
+/// the stored memory.
+#[tokio::test]
+async fn similar_fact_is_skipped() {
+    let store: Arc<dyn Store> = Arc::new(InMemoryVectorStore::new(Arc::new(WordEmbedder)));
+    let remember = RememberTool::new(store.clone(), namespace())
+        .with_value_schema(MemoryValueSchema::Text)
+        .with_dedup(MemoryDedup::new(0.8).with_action(DuplicateAction::Skip));
+
+    let r = remember
+        .call(json!({ "value": "likes coffee" }), None)
+        .await
+        .unwrap();
+    let key = store.list(&namespace()).await.unwrap().remove(0);
+    assert_eq!(r.text, format!("ok (key: {})", key));
+
+    let r = remember
+        .call(json!({ "value": "really likes coffee" }), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        r.text,
+        format!("skipped: similar memory exists (key: {})", key)
+    );
+    let stored = store.get(&namespace(), &key).await.unwrap().unwrap();
+    assert_eq!(stored["text"], "likes coffee");
+    assert_eq!(store.list(&namespace()).await.unwrap().len(), 1);
+}
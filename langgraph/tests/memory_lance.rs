@@ -5,7 +5,7 @@
 mod init_logging;
 
 use async_trait::async_trait;
-use langgraph::memory::{Embedder, LanceStore, Store};
+use langgraph::memory::{Embedder, LanceStore, SearchMode, SearchOptions, Store};
 use std::sync::Arc;
 
 /// Mock embedder: returns a fixed vector per text (hash-based) for deterministic tests.
@@ -105,7 +105,10 @@ async fn lance_store_search_with_query() {
         .await
         .unwrap();
 
-    let hits = store.search(&ns, Some("rust"), Some(10)).await.unwrap();
+    let hits = store
+        .search_simple(&ns, Some("rust"), Some(10))
+        .await
+        .unwrap();
     assert!(!hits.is_empty());
     assert!(hits.iter().any(|h| h.key == "a" || h.key == "c"));
     for h in &hits {
@@ -130,7 +133,7 @@ async fn lance_store_search_no_query() {
         .await
         .unwrap();
 
-    let hits = store.search(&ns, None, Some(5)).await.unwrap();
+    let hits = store.search_simple(&ns, None, Some(5)).await.unwrap();
     assert_eq!(hits.len(), 2);
     for h in &hits {
         assert!(h.score.is_none());
@@ -181,3 +184,65 @@ async fn lance_store_namespace_isolation() {
     assert_eq!(v1, Some(serde_json::json!("v1")));
     assert_eq!(v2, Some(serde_json::json!("v2")));
 }
+
+/// **Scenario**: Filtered search keeps only matching values; delete and clear remove rows and
+/// clear returns how many the namespace held.
+#[tokio::test]
+async fn lance_store_filter_delete_clear() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lance-store");
+    let embedder = Arc::new(MockEmbedder::new(4));
+    let store = LanceStore::new(&path, embedder).await.unwrap();
+    let ns = vec!["user1".into(), "mem".into()];
+    for (key, tag) in [("a", "work"), ("b", "home"), ("c", "work")] {
+        store
+            .put(&ns, key, &serde_json::json!({"text": key, "tag": tag}))
+            .await
+            .unwrap();
+    }
+
+    let hits = store
+        .search_with_filter(&ns, Some("a"), &serde_json::json!({"tag": "work"}))
+        .await
+        .unwrap();
+    let mut keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "c"]);
+
+    store.delete(&ns, "a").await.unwrap();
+    assert!(store.get(&ns, "a").await.unwrap().is_none());
+    assert_eq!(store.clear(&ns).await.unwrap(), 2);
+    assert!(store.list(&ns).await.unwrap().is_empty());
+}
+
+/// **Scenario**: Keyword search ranks rows by the exact ID in their text and skips rows without
+/// a query term; a hybrid search weighted to keywords ranks the same row first.
+#[tokio::test]
+async fn lance_store_keyword_and_hybrid_search() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lance-store");
+    let embedder = Arc::new(MockEmbedder::new(4));
+    let store = LanceStore::new(&path, embedder).await.unwrap();
+    let ns = vec!["user1".into(), "mem".into()];
+    for (key, text) in [
+        ("typo", "INV-2014"),
+        ("refund", "Refund for INV-2041 approved"),
+        ("weather", "weather is nice"),
+    ] {
+        store
+            .put(&ns, key, &serde_json::json!({ "text": text }))
+            .await
+            .unwrap();
+    }
+
+    let query = SearchOptions::new().with_query("INV-2041");
+    let keyword = query.clone().with_mode(SearchMode::Keyword);
+    let hits = store.search(&ns, keyword).await.unwrap();
+    let keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+    assert_eq!(keys, vec!["refund", "typo"]);
+
+    let hybrid = query.with_mode(SearchMode::Hybrid(0.0));
+    let hits = store.search(&ns, hybrid).await.unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[0].item.key, "refund");
+}
@@ -0,0 +1,179 @@
+//! Integration tests for PostgresSaver and PostgresStore. Run with:
+//! `LANGGRAPH_TEST_POSTGRES_URL=postgres://... cargo test -p langgraph --features postgres --test memory_postgres`
+//!
+//! Tests are skipped when `LANGGRAPH_TEST_POSTGRES_URL` is unset. Thread ids and namespaces are
+//! unique per run, so the database does not need to be empty.
+
+#![cfg(feature = "postgres")]
+
+mod init_logging;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use langgraph::memory::{
+    uuid6, Checkpointer, FilterOp, JsonSerializer, ListNamespacesOptions, MatchCondition,
+    NamespaceMatchType, PgPool, PostgresSaver, PostgresStore, RunnableConfig, SearchOptions, Store,
+};
+use langgraph::{AgentError, Next, Node, StateGraph, END, START};
+use serde_json::json;
+
+async fn pool() -> Option<PgPool> {
+    let url = std::env::var("LANGGRAPH_TEST_POSTGRES_URL").ok()?;
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("connect to test database"),
+    )
+}
+
+struct AddNode {
+    id: &'static str,
+    delta: i32,
+}
+
+#[async_trait]
+impl Node<i32> for AddNode {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+        Ok((state + self.delta, Next::Continue))
+    }
+}
+
+/// **Scenario**: A graph on PostgresSaver checkpoints every step; a second saver on the same
+/// database (another replica) sees the latest checkpoint and the full history, and can tag it.
+#[tokio::test]
+async fn postgres_saver_shares_checkpoints_between_instances() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let saver = Arc::new(
+        PostgresSaver::<i32>::from_pool(pool.clone(), Arc::new(JsonSerializer))
+            .await
+            .unwrap(),
+    );
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_node("b", Arc::new(AddNode { id: "b", delta: 10 }))
+        .add_edge(START, "a")
+        .add_edge("a", "b")
+        .add_edge("b", END);
+    let graph = graph.compile_with_checkpointer(saver).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some(format!("pg-{}", uuid6())),
+        ..Default::default()
+    };
+
+    assert_eq!(graph.invoke(0, Some(config.clone())).await.unwrap(), 11);
+
+    let replica = PostgresSaver::<i32>::from_pool(pool, Arc::new(JsonSerializer))
+        .await
+        .unwrap();
+    let (latest, metadata) = replica.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(latest.channel_values, 11);
+    assert!(latest.pending_node.is_none());
+    assert!(metadata.parent_checkpoint_id.is_some());
+
+    let items = replica.list(&config, None, None, None).await.unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[2].checkpoint_id, latest.id);
+    let limited = replica.list(&config, Some(1), None, None).await.unwrap();
+    assert_eq!(limited[0].checkpoint_id, latest.id);
+
+    let tags = vec!["answered".to_string(), "needed_tools".to_string()];
+    assert!(replica.tag_checkpoint(&config, &tags).await.unwrap());
+    assert!(replica.tag_checkpoint(&config, &tags[1..]).await.unwrap());
+    let (_, metadata) = replica.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(metadata.tags, tags);
+
+    let first = RunnableConfig {
+        checkpoint_id: Some(items[0].checkpoint_id.clone()),
+        ..config
+    };
+    let (input, _) = replica.get_tuple(&first).await.unwrap().unwrap();
+    assert_eq!(input.channel_values, 0);
+    assert_eq!(input.pending_node.as_deref(), Some("a"));
+}
+
+/// **Scenario**: PostgresStore put/get/list/delete keep created_at on update; search honors the
+/// namespace prefix, query text and filters; list_namespaces applies prefix and depth.
+#[tokio::test]
+async fn postgres_store_put_search_and_namespaces() {
+    let Some(pool) = pool().await else {
+        return;
+    };
+    let store = PostgresStore::from_pool(pool).await.unwrap();
+    let root = format!("pg-{}", uuid6());
+    let prefs = vec![root.clone(), "u1".to_string(), "prefs".to_string()];
+    let notes = vec![root.clone(), "u1".to_string(), "notes".to_string()];
+    let other = vec![format!("{}-other", root), "u1".to_string()];
+
+    store
+        .put(&prefs, "lang", &json!({"value": "rust", "rank": 1}))
+        .await
+        .unwrap();
+    store
+        .put(&prefs, "editor", &json!({"value": "helix", "rank": 5}))
+        .await
+        .unwrap();
+    store
+        .put(&notes, "n1", &json!({"text": "Rust is fast"}))
+        .await
+        .unwrap();
+    store
+        .put(&other, "x", &json!({"text": "rust"}))
+        .await
+        .unwrap();
+
+    let created = store.get_item(&prefs, "lang").await.unwrap().unwrap();
+    store
+        .put(&prefs, "lang", &json!({"value": "rust", "rank": 2}))
+        .await
+        .unwrap();
+    let updated = store.get_item(&prefs, "lang").await.unwrap().unwrap();
+    assert_eq!(updated.created_at, created.created_at);
+    assert_eq!(updated.value["rank"], 2);
+    assert_eq!(store.list(&prefs).await.unwrap(), vec!["editor", "lang"]);
+
+    let prefix = vec![root.clone()];
+    let all = store.search(&prefix, SearchOptions::new()).await.unwrap();
+    assert_eq!(all.len(), 3, "the -other namespace is not under the prefix");
+
+    let hits = store
+        .search(&prefix, SearchOptions::new().with_query("RUST"))
+        .await
+        .unwrap();
+    let keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+    assert_eq!(keys, vec!["n1", "lang"]);
+
+    let mut filter = HashMap::new();
+    filter.insert("rank".to_string(), FilterOp::Gt(json!(3)));
+    let options = SearchOptions {
+        filter: Some(filter),
+        ..SearchOptions::new()
+    };
+    let hits = store.search(&prefix, options).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].item.key, "editor");
+
+    let namespaces = store
+        .list_namespaces(ListNamespacesOptions {
+            match_conditions: vec![MatchCondition {
+                match_type: NamespaceMatchType::Prefix,
+                path: prefix.clone(),
+            }],
+            max_depth: Some(2),
+            ..ListNamespacesOptions::new()
+        })
+        .await
+        .unwrap();
+    assert_eq!(namespaces, vec![vec![root.clone(), "u1".to_string()]]);
+
+    store.delete(&prefs, "lang").await.unwrap();
+    assert!(store.get(&prefs, "lang").await.unwrap().is_none());
+}
@@ -0,0 +1,123 @@
+//! Integration tests for RedisSaver. Run with:
+//! `LANGGRAPH_TEST_REDIS_URL=redis://localhost:6379 cargo test -p langgraph --features redis --test memory_redis`
+//!
+//! Tests are skipped when `LANGGRAPH_TEST_REDIS_URL` is unset. Thread ids are unique per run, so
+//! the database does not need to be empty.
+
+#![cfg(feature = "redis")]
+
+mod init_logging;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use langgraph::memory::{uuid6, Checkpointer, JsonSerializer, RedisSaver, RunnableConfig};
+use langgraph::{AgentError, Next, Node, StateGraph, END, START};
+
+async fn saver() -> Option<RedisSaver<i32>> {
+    let url = std::env::var("LANGGRAPH_TEST_REDIS_URL").ok()?;
+    Some(
+        RedisSaver::connect(&url, Arc::new(JsonSerializer))
+            .await
+            .expect("connect to test redis"),
+    )
+}
+
+struct AddNode {
+    id: &'static str,
+    delta: i32,
+}
+
+#[async_trait]
+impl Node<i32> for AddNode {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+        Ok((state + self.delta, Next::Continue))
+    }
+}
+
+/// **Scenario**: A graph on RedisSaver checkpoints every step; a second saver on the same Redis
+/// (another replica) sees the latest checkpoint and the full history, and can tag it.
+#[tokio::test]
+async fn redis_saver_shares_checkpoints_between_instances() {
+    let Some(saver) = saver().await else {
+        return;
+    };
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_node("b", Arc::new(AddNode { id: "b", delta: 10 }))
+        .add_edge(START, "a")
+        .add_edge("a", "b")
+        .add_edge("b", END);
+    let graph = graph.compile_with_checkpointer(Arc::new(saver)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some(format!("redis-{}", uuid6())),
+        ..Default::default()
+    };
+
+    assert_eq!(graph.invoke(0, Some(config.clone())).await.unwrap(), 11);
+
+    let replica = saver().await.unwrap();
+    let (latest, metadata) = replica.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(latest.channel_values, 11);
+    assert!(latest.pending_node.is_none());
+    assert!(metadata.parent_checkpoint_id.is_some());
+
+    let items = replica.list(&config, None, None, None).await.unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[2].checkpoint_id, latest.id);
+    let limited = replica.list(&config, Some(1), None, None).await.unwrap();
+    assert_eq!(limited[0].checkpoint_id, latest.id);
+
+    let tags = vec!["answered".to_string(), "needed_tools".to_string()];
+    assert!(replica.tag_checkpoint(&config, &tags).await.unwrap());
+    assert!(replica.tag_checkpoint(&config, &tags[1..]).await.unwrap());
+    let (_, metadata) = replica.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(metadata.tags, tags);
+
+    let first = RunnableConfig {
+        checkpoint_id: Some(items[0].checkpoint_id.clone()),
+        ..config.clone()
+    };
+    let (input, _) = replica.get_tuple(&first).await.unwrap().unwrap();
+    assert_eq!(input.channel_values, 0);
+    assert_eq!(input.pending_node.as_deref(), Some("a"));
+
+    let other_ns = RunnableConfig {
+        checkpoint_ns: "child".into(),
+        ..config
+    };
+    assert!(replica.get_tuple(&other_ns).await.unwrap().is_none());
+}
+
+/// **Scenario**: With a TTL, a thread's checkpoints disappear once it has been idle that long.
+#[tokio::test]
+async fn redis_saver_ttl_expires_idle_threads() {
+    let Some(saver) = saver().await else {
+        return;
+    };
+    let saver = saver.with_ttl(Duration::from_secs(1));
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_edge(START, "a")
+        .add_edge("a", END);
+    let saver = Arc::new(saver);
+    let graph = graph.compile_with_checkpointer(saver.clone()).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some(format!("redis-ttl-{}", uuid6())),
+        ..Default::default()
+    };
+
+    graph.invoke(0, Some(config.clone())).await.unwrap();
+    assert!(saver.get_tuple(&config).await.unwrap().is_some());
+
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    assert!(saver.get_tuple(&config).await.unwrap().is_none());
+    assert!(saver.list(&config, None, None, None).await.unwrap().is_empty());
+}
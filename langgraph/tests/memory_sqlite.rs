@@ -3,12 +3,15 @@
 mod init_logging;
 
 use langgraph::memory::{
-    Checkpoint, CheckpointMetadata, CheckpointSource, Checkpointer, JsonSerializer, RunnableConfig,
-    SearchOptions, SqliteSaver, SqliteStore, Store, CHECKPOINT_VERSION,
+    Checkpoint, CheckpointMetadata, CheckpointRetention, CheckpointSource, CheckpointSync,
+    Checkpointer, JsonSerializer, RunnableConfig, SearchOptions, SqliteSaver, SqliteStore, Store,
+    CHECKPOINT_VERSION,
 };
+use langgraph::{MockLlm, MockToolSource, ReActState, ReactRunner};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct TestState {
@@ -26,6 +29,7 @@ async fn sqlite_saver_put_and_get_tuple() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     let checkpoint = Checkpoint {
         v: CHECKPOINT_VERSION,
@@ -38,11 +42,14 @@ async fn sqlite_saver_put_and_get_tuple() {
         versions_seen: HashMap::new(),
         updated_channels: None,
         pending_sends: Vec::new(),
+        pending_node: None,
         metadata: CheckpointMetadata {
             source: CheckpointSource::Update,
             step: 0,
             created_at: None,
             parents: HashMap::new(),
+            parent_checkpoint_id: None,
+            tags: Vec::new(),
         },
     };
     let id = saver.put(&config, &checkpoint).await.unwrap();
@@ -65,11 +72,124 @@ async fn sqlite_saver_get_tuple_empty_returns_none() {
         checkpoint_id: None,
         checkpoint_ns: String::new(),
         user_id: None,
+        ..Default::default()
     };
     let tuple = saver.get_tuple(&config).await.unwrap();
     assert!(tuple.is_none());
 }
 
+/// **Scenario**: pending_node of an interrupted checkpoint survives put/get_tuple.
+#[tokio::test]
+async fn sqlite_saver_persists_pending_node() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("t-int".into()),
+        ..Default::default()
+    };
+    let checkpoint = Checkpoint::from_state(TestState::default(), CheckpointSource::Update, 0)
+        .with_pending_node("act");
+    saver.put(&config, &checkpoint).await.unwrap();
+    let (cp, _) = saver.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(cp.pending_node.as_deref(), Some("act"));
+}
+
+/// **Scenario**: parent_checkpoint_id survives put/get_tuple/list; the newest of two
+/// checkpoints written in the same millisecond is returned as latest.
+#[tokio::test]
+async fn sqlite_saver_persists_parent_checkpoint_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("t-chain".into()),
+        ..Default::default()
+    };
+    let first = Checkpoint::from_state(TestState::default(), CheckpointSource::Input, 0);
+    let mut second = Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 1);
+    second.metadata.created_at = first.metadata.created_at;
+    second.metadata.parent_checkpoint_id = Some(first.id.clone());
+    saver.put(&config, &first).await.unwrap();
+    saver.put(&config, &second).await.unwrap();
+
+    let (cp, meta) = saver.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(cp.id, second.id);
+    assert_eq!(meta.parent_checkpoint_id.as_deref(), Some(first.id.as_str()));
+    let list = saver.list(&config, None, None, None).await.unwrap();
+    assert_eq!(list.len(), 2);
+    assert!(list[0].metadata.parent_checkpoint_id.is_none());
+    assert_eq!(list[1].metadata.parent_checkpoint_id, Some(first.id));
+}
+
+/// **Scenario**: tag_checkpoint adds tags to the latest checkpoint (or the one in the config)
+/// without duplicates; they are read back by get_tuple and list.
+#[tokio::test]
+async fn sqlite_saver_tags_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("t-tags".into()),
+        ..Default::default()
+    };
+    let first = Checkpoint::from_state(TestState::default(), CheckpointSource::Input, 0);
+    let second = Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 1);
+    saver.put(&config, &first).await.unwrap();
+    saver.put(&config, &second).await.unwrap();
+
+    let tags = vec!["answered".to_string(), "needed_tools".to_string()];
+    assert!(saver.tag_checkpoint(&config, &tags).await.unwrap());
+    assert!(saver.tag_checkpoint(&config, &tags[..1]).await.unwrap());
+    let (_, meta) = saver.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(meta.tags, tags);
+
+    let first_config = RunnableConfig {
+        checkpoint_id: Some(first.id.clone()),
+        ..config.clone()
+    };
+    let refused = vec!["refused".to_string()];
+    assert!(saver.tag_checkpoint(&first_config, &refused).await.unwrap());
+    let list = saver.list(&config, None, None, None).await.unwrap();
+    assert_eq!(list[0].metadata.tags, refused);
+    assert_eq!(list[1].metadata.tags, tags);
+
+    let missing = RunnableConfig {
+        thread_id: Some("t-none".into()),
+        ..Default::default()
+    };
+    assert!(!saver.tag_checkpoint(&missing, &tags).await.unwrap());
+}
+
+/// **Scenario**: A database created before pending_node existed is migrated on open.
+#[tokio::test]
+async fn sqlite_saver_migrates_table_without_pending_node() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute(
+            "CREATE TABLE checkpoints (
+                thread_id TEXT NOT NULL, checkpoint_ns TEXT NOT NULL, checkpoint_id TEXT NOT NULL,
+                ts TEXT NOT NULL, payload BLOB NOT NULL, channel_versions TEXT NOT NULL,
+                metadata_source TEXT NOT NULL, metadata_step INTEGER NOT NULL,
+                metadata_created_at INTEGER,
+                PRIMARY KEY (thread_id, checkpoint_ns, checkpoint_id))",
+            [],
+        )
+        .unwrap();
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("t-old".into()),
+        ..Default::default()
+    };
+    let checkpoint = Checkpoint::from_state(TestState::default(), CheckpointSource::Update, 0);
+    saver.put(&config, &checkpoint).await.unwrap();
+    let (cp, meta) = saver.get_tuple(&config).await.unwrap().unwrap();
+    assert!(cp.pending_node.is_none());
+    assert!(meta.parent_checkpoint_id.is_none());
+}
+
 #[tokio::test]
 async fn sqlite_saver_list() {
     let dir = tempfile::tempdir().unwrap();
@@ -81,6 +201,7 @@ async fn sqlite_saver_list() {
         checkpoint_id: None,
         checkpoint_ns: "ns".into(),
         user_id: None,
+        ..Default::default()
     };
     let list = saver.list(&config, None, None, None).await.unwrap();
     assert!(list.is_empty());
@@ -96,11 +217,14 @@ async fn sqlite_saver_list() {
         versions_seen: HashMap::new(),
         updated_channels: None,
         pending_sends: Vec::new(),
+        pending_node: None,
         metadata: CheckpointMetadata {
             source: CheckpointSource::Input,
             step: 1,
             created_at: None,
             parents: HashMap::new(),
+            parent_checkpoint_id: None,
+            tags: Vec::new(),
         },
     };
     saver.put(&config, &checkpoint).await.unwrap();
@@ -109,6 +233,302 @@ async fn sqlite_saver_list() {
     assert_eq!(list[0].checkpoint_id, "c3");
 }
 
+/// **Scenario**: Threads are listed newest first with their latest checkpoint time; deleting
+/// one removes its checkpoints in every namespace.
+#[tokio::test]
+async fn sqlite_saver_list_and_delete_threads() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = |thread: &str, ns: &str| RunnableConfig {
+        thread_id: Some(thread.into()),
+        checkpoint_ns: ns.into(),
+        ..Default::default()
+    };
+    let at = |id: &str, millis: u64| {
+        let mut cp = Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 0);
+        cp.id = id.into();
+        cp.metadata.created_at = Some(UNIX_EPOCH + Duration::from_millis(millis));
+        cp
+    };
+    saver
+        .put(&config("old", ""), &at("a", 1_000))
+        .await
+        .unwrap();
+    saver
+        .put(&config("new", ""), &at("b", 2_000))
+        .await
+        .unwrap();
+    saver
+        .put(&config("new", "sub"), &at("c", 3_000))
+        .await
+        .unwrap();
+
+    let threads = saver
+        .list_threads(&RunnableConfig::default())
+        .await
+        .unwrap();
+    let ids: Vec<&str> = threads.iter().map(|t| t.thread_id.as_str()).collect();
+    assert_eq!(ids, ["new", "old"]);
+    assert_eq!(
+        threads[0].last_activity,
+        Some(UNIX_EPOCH + Duration::from_millis(3_000))
+    );
+
+    assert!(saver.delete_thread(&config("new", "")).await.unwrap());
+    assert!(!saver.delete_thread(&config("new", "")).await.unwrap());
+    assert!(saver
+        .get_tuple(&config("new", "sub"))
+        .await
+        .unwrap()
+        .is_none());
+    let threads = saver
+        .list_threads(&RunnableConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+}
+
+/// **Scenario**: With a retention policy, each put deletes the thread's checkpoints past the
+/// count and age limits; the newest is kept even when old, and other threads are untouched.
+#[tokio::test]
+async fn sqlite_saver_retention_deletes_old_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer))
+        .unwrap()
+        .with_retention(
+            CheckpointRetention::new()
+                .with_max_checkpoints(3)
+                .with_max_age(Duration::from_secs(3600)),
+        );
+    let config = |thread: &str| RunnableConfig {
+        thread_id: Some(thread.into()),
+        ..Default::default()
+    };
+    let now = std::time::SystemTime::now();
+    let at = |id: &str, created_at: std::time::SystemTime| {
+        let mut cp = Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 0);
+        cp.id = id.into();
+        cp.metadata.created_at = Some(created_at);
+        cp
+    };
+    let ids = |list: Vec<langgraph::memory::CheckpointListItem>| -> Vec<String> {
+        list.into_iter().map(|i| i.checkpoint_id).collect()
+    };
+    let old = now - Duration::from_secs(7200);
+
+    saver.put(&config("other"), &at("x", old)).await.unwrap();
+    saver.put(&config("t"), &at("a", old)).await.unwrap();
+    let list = saver.list(&config("t"), None, None, None).await.unwrap();
+    assert_eq!(ids(list), ["a"]);
+
+    for (i, id) in ["b", "c", "d", "e"].into_iter().enumerate() {
+        let created_at = now + Duration::from_millis(i as u64);
+        saver.put(&config("t"), &at(id, created_at)).await.unwrap();
+    }
+    let list = saver.list(&config("t"), None, None, None).await.unwrap();
+    assert_eq!(ids(list), ["c", "d", "e"]);
+    let list = saver
+        .list(&config("other"), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(ids(list), ["x"]);
+}
+
+/// **Scenario**: A backup taken while checkpoints keep being written opens as a complete
+/// database holding every checkpoint committed before it started.
+#[tokio::test]
+async fn sqlite_saver_backup_while_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = Arc::new(SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap());
+    let config = |thread: &str| RunnableConfig {
+        thread_id: Some(thread.into()),
+        ..Default::default()
+    };
+    let checkpoint = || Checkpoint::from_state(TestState::default(), CheckpointSource::Loop, 0);
+    saver.put(&config("before"), &checkpoint()).await.unwrap();
+
+    let writer = {
+        let saver = Arc::clone(&saver);
+        tokio::spawn(async move {
+            for i in 0..20 {
+                let thread = format!("during-{}", i);
+                saver.put(&config(&thread), &checkpoint()).await.unwrap();
+            }
+        })
+    };
+    let backup = dir.path().join("backup.db");
+    assert!(saver.backup(&backup).await.unwrap() > 0);
+    writer.await.unwrap();
+
+    let copy = SqliteSaver::<TestState>::new(&backup, Arc::new(JsonSerializer)).unwrap();
+    assert!(copy.get_tuple(&config("before")).await.unwrap().is_some());
+    assert!(saver.backup(&backup).await.is_err(), "never overwrites");
+}
+
+/// **Scenario**: With write-behind until the end of the run, puts stay queued: the saver reads,
+/// lists and tags them at once while the file has none yet. flush writes them with retention
+/// applied; a deleted thread is never written, and what is queued at drop is written then.
+#[tokio::test]
+async fn sqlite_saver_write_behind_until_flush() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer))
+        .unwrap()
+        .with_write_behind(CheckpointSync::EndOfRun)
+        .with_retention(CheckpointRetention::new().with_max_checkpoints(2));
+    let file = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = |thread: &str| RunnableConfig {
+        thread_id: Some(thread.into()),
+        ..Default::default()
+    };
+    let checkpoint = |value: &str| {
+        let state = TestState {
+            value: value.into(),
+        };
+        Checkpoint::from_state(state, CheckpointSource::Loop, 0)
+    };
+
+    let mut ids = Vec::new();
+    for value in ["a", "b", "c"] {
+        ids.push(saver.put(&config("t"), &checkpoint(value)).await.unwrap());
+    }
+    let (latest, _) = saver.get_tuple(&config("t")).await.unwrap().unwrap();
+    assert_eq!(latest.channel_values.value, "c");
+    let listed: Vec<String> = saver
+        .list(&config("t"), None, None, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|i| i.checkpoint_id)
+        .collect();
+    assert_eq!(listed, ids[1..]);
+    let tags = vec!["answered".to_string()];
+    assert!(saver.tag_checkpoint(&config("t"), &tags).await.unwrap());
+    let threads = saver
+        .list_threads(&RunnableConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(threads.len(), 1);
+    assert!(file.get_tuple(&config("t")).await.unwrap().is_none());
+
+    saver.flush().await.unwrap();
+    let (written, meta) = file.get_tuple(&config("t")).await.unwrap().unwrap();
+    assert_eq!(written.id, ids[2]);
+    assert_eq!(meta.tags, tags);
+    let list = file.list(&config("t"), None, None, None).await.unwrap();
+    assert_eq!(list.len(), 2);
+
+    saver.put(&config("gone"), &checkpoint("x")).await.unwrap();
+    assert!(saver.delete_thread(&config("gone")).await.unwrap());
+    saver.flush().await.unwrap();
+    assert!(file.get_tuple(&config("gone")).await.unwrap().is_none());
+
+    saver.put(&config("late"), &checkpoint("y")).await.unwrap();
+    drop(saver);
+    assert!(file.get_tuple(&config("late")).await.unwrap().is_some());
+}
+
+/// **Scenario**: With an interval, queued checkpoints reach the file in the background without
+/// a flush.
+#[tokio::test]
+async fn sqlite_saver_write_behind_on_interval() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer))
+        .unwrap()
+        .with_write_behind(CheckpointSync::Interval(Duration::from_millis(20)));
+    let file = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let config = RunnableConfig {
+        thread_id: Some("t".into()),
+        ..Default::default()
+    };
+    let checkpoint = Checkpoint::from_state(TestState::default(), CheckpointSource::Input, 0);
+    saver.put(&config, &checkpoint).await.unwrap();
+
+    let mut written = false;
+    for _ in 0..100 {
+        if file.get_tuple(&config).await.unwrap().is_some() {
+            written = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(written, "interval flush did not write the checkpoint");
+}
+
+/// **Scenario**: A run on a saver writing at the end of the run leaves its checkpoints in the
+/// file once it returns: the graph flushes the queue.
+#[tokio::test]
+async fn sqlite_saver_write_behind_flushed_when_run_ends() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<ReActState>::new(&path, Arc::new(JsonSerializer))
+        .unwrap()
+        .with_write_behind(CheckpointSync::EndOfRun);
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("done")),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(saver)),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    let config = RunnableConfig {
+        thread_id: Some("run".into()),
+        ..Default::default()
+    };
+    runner
+        .invoke_with_config("hi", Some(config.clone()))
+        .await
+        .expect("run");
+
+    let file = SqliteSaver::<ReActState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    let (checkpoint, _) = file.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(
+        checkpoint.channel_values.last_assistant_reply().as_deref(),
+        Some("done")
+    );
+}
+
+/// Puts one checkpoint on an end-of-run saver, drops it without flushing and returns whether
+/// the checkpoint reached the file.
+async fn dropped_write_behind_saver_writes_queue() -> bool {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoints.db");
+    let saver = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer))
+        .unwrap()
+        .with_write_behind(CheckpointSync::EndOfRun);
+    let config = RunnableConfig {
+        thread_id: Some("dropped".into()),
+        ..Default::default()
+    };
+    let checkpoint = Checkpoint::from_state(TestState::default(), CheckpointSource::Update, 0);
+    saver.put(&config, &checkpoint).await.unwrap();
+    drop(saver);
+
+    let file = SqliteSaver::<TestState>::new(&path, Arc::new(JsonSerializer)).unwrap();
+    file.get_tuple(&config).await.unwrap().is_some()
+}
+
+/// **Scenario**: Dropping a write-behind saver on a current-thread runtime writes its queue
+/// (from a dedicated thread).
+#[tokio::test]
+async fn sqlite_saver_write_behind_written_on_drop_current_thread() {
+    assert!(dropped_write_behind_saver_writes_queue().await);
+}
+
+/// **Scenario**: Dropping a write-behind saver on a multi-thread runtime writes its queue
+/// (through block_in_place).
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sqlite_saver_write_behind_written_on_drop_multi_thread() {
+    assert!(dropped_write_behind_saver_writes_queue().await);
+}
+
 #[tokio::test]
 async fn sqlite_store_put_get_list_search() {
     let dir = tempfile::tempdir().unwrap();
@@ -165,6 +585,26 @@ async fn sqlite_store_persistence() {
     assert_eq!(v, Some(serde_json::json!("survives")));
 }
 
+/// **Scenario**: A store backup holds the items written before it.
+#[tokio::test]
+async fn sqlite_store_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SqliteStore::new(dir.path().join("store.db")).unwrap();
+    let ns = vec!["user1".into(), "memories".into()];
+    store
+        .put(&ns, "k", &serde_json::json!("backed up"))
+        .await
+        .unwrap();
+
+    let backup = dir.path().join("store-backup.db");
+    store.backup(&backup).await.unwrap();
+    let copy = SqliteStore::new(&backup).unwrap();
+    assert_eq!(
+        copy.get(&ns, "k").await.unwrap(),
+        Some(serde_json::json!("backed up"))
+    );
+}
+
 #[tokio::test]
 async fn sqlite_store_namespace_isolation() {
     let dir = tempfile::tempdir().unwrap();
@@ -192,3 +632,36 @@ async fn sqlite_store_namespace_isolation() {
     assert_eq!(keys1, vec!["key"]);
     assert_eq!(keys2, vec!["key"]);
 }
+
+/// **Scenario**: search_with_filter returns the values matching the filter; clear forgets a
+/// namespace and returns how many items it held.
+#[tokio::test]
+async fn sqlite_store_search_with_filter_and_clear() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SqliteStore::new(dir.path().join("store.db")).unwrap();
+    let ns = vec!["user1".into(), "memories".into()];
+    let other = vec!["user2".into(), "memories".into()];
+    for (key, tag, date) in [
+        ("m1", "work", "2024-01-10"),
+        ("m2", "home", "2024-01-12"),
+        ("m3", "work", "2024-03-01"),
+    ] {
+        store
+            .put(&ns, key, &serde_json::json!({"tag": tag, "date": date}))
+            .await
+            .unwrap();
+    }
+    store
+        .put(&other, "m1", &serde_json::json!({"tag": "work"}))
+        .await
+        .unwrap();
+
+    let filter = serde_json::json!({"tag": "work", "date": {"$lt": "2024-02-01"}});
+    let hits = store.search_with_filter(&ns, None, &filter).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].item.key, "m1");
+
+    assert_eq!(store.clear(&ns).await.unwrap(), 3);
+    assert!(store.list(&ns).await.unwrap().is_empty());
+    assert_eq!(store.list(&other).await.unwrap(), vec!["m1"]);
+}
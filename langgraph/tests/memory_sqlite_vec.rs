@@ -4,17 +4,26 @@ mod init_logging;
 
 use async_trait::async_trait;
 use langgraph::memory::{
-    Embedder, SearchOptions, SqliteVecStore, Store, StoreError, StoreOp, StoreOpResult,
+    Embedder, FilterOp, SearchOptions, SqliteVecStore, Store, StoreError, StoreOp, StoreOpResult,
 };
 use std::sync::Arc;
 
 struct MockEmbedder {
+    model: &'static str,
     dimension: usize,
 }
 
 impl MockEmbedder {
     fn new(dimension: usize) -> Self {
-        Self { dimension }
+        Self {
+            model: "mock",
+            dimension,
+        }
+    }
+
+    fn with_model(mut self, model: &'static str) -> Self {
+        self.model = model;
+        self
     }
 }
 
@@ -36,6 +45,10 @@ impl Embedder for MockEmbedder {
     fn dimension(&self) -> usize {
         self.dimension
     }
+
+    fn model(&self) -> &str {
+        self.model
+    }
 }
 
 #[tokio::test]
@@ -150,6 +163,84 @@ async fn sqlite_vec_store_delete() {
     assert!(store.get(&ns, "key1").await.unwrap().is_none());
 }
 
+/// **Scenario**: clear deletes the namespace's rows and vectors and returns the count; other
+/// namespaces keep theirs and the namespace can be written again.
+#[tokio::test]
+async fn sqlite_vec_store_clear() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store_vec.db");
+    let store = SqliteVecStore::new(&path, Arc::new(MockEmbedder::new(8))).unwrap();
+    let ns = vec!["u1".into()];
+    let other = vec!["u2".into()];
+    for key in ["a", "b"] {
+        store
+            .put(&ns, key, &serde_json::json!({"text": key}))
+            .await
+            .unwrap();
+    }
+    store
+        .put(&other, "a", &serde_json::json!({"text": "a"}))
+        .await
+        .unwrap();
+
+    assert_eq!(store.clear(&ns).await.unwrap(), 2);
+    assert!(store.list(&ns).await.unwrap().is_empty());
+    let hits = store
+        .search(&ns, SearchOptions::new().with_query("a"))
+        .await
+        .unwrap();
+    assert!(hits.is_empty());
+    assert_eq!(store.list(&other).await.unwrap(), vec!["a"]);
+
+    store
+        .put(&ns, "c", &serde_json::json!({"text": "c"}))
+        .await
+        .unwrap();
+    assert_eq!(store.list(&ns).await.unwrap(), vec!["c"]);
+}
+
+/// **Scenario**: Filtered searches, with and without a query, return only matching values and
+/// page over the matches.
+#[tokio::test]
+async fn sqlite_vec_store_search_with_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store_vec.db");
+    let store = SqliteVecStore::new(&path, Arc::new(MockEmbedder::new(8))).unwrap();
+    let ns = vec!["memories".into()];
+    for (key, tag, day) in [("m1", "work", 1), ("m2", "home", 2), ("m3", "work", 3)] {
+        store
+            .put(
+                &ns,
+                key,
+                &serde_json::json!({"text": format!("note {}", key), "tag": tag, "day": day}),
+            )
+            .await
+            .unwrap();
+    }
+
+    let filter = serde_json::json!({"tag": "work"});
+    let hits = store
+        .search_with_filter(&ns, Some("note"), &filter)
+        .await
+        .unwrap();
+    let mut keys: Vec<&str> = hits.iter().map(|h| h.item.key.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["m1", "m3"]);
+
+    let filter = serde_json::json!({"tag": "work", "day": {"$gt": 1}});
+    let hits = store.search_with_filter(&ns, None, &filter).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].item.key, "m3");
+
+    let options = SearchOptions::new()
+        .with_filter("tag", FilterOp::Eq(serde_json::json!("work")))
+        .with_limit(1)
+        .with_offset(1);
+    let hits = store.search(&ns, options).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].item.key, "m3");
+}
+
 #[tokio::test]
 async fn sqlite_vec_store_search_no_query() {
     let dir = tempfile::tempdir().unwrap();
@@ -202,3 +293,106 @@ async fn sqlite_vec_store_batch() {
         _ => panic!("expected Get result with item"),
     }
 }
+
+/// **Scenario**: put_many stores every item in one transaction; the items can be read back and
+/// searched, and an existing key is overwritten.
+#[tokio::test]
+async fn sqlite_vec_store_put_many() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store_vec.db");
+    let store = SqliteVecStore::new(&path, Arc::new(MockEmbedder::new(8))).unwrap();
+    let ns = vec!["docs".into()];
+    store
+        .put(&ns, "a", &serde_json::json!({"text": "old"}))
+        .await
+        .unwrap();
+
+    let items = vec![
+        ("a".to_string(), serde_json::json!({"text": "hello world"})),
+        ("b".to_string(), serde_json::json!({"text": "rust traits"})),
+        ("c".to_string(), serde_json::json!({"text": "vectors"})),
+    ];
+    store.put_many(&ns, &items).await.unwrap();
+
+    let mut keys = store.list(&ns).await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b", "c"]);
+    assert_eq!(
+        store.get(&ns, "a").await.unwrap(),
+        Some(serde_json::json!({"text": "hello world"}))
+    );
+    let results = store
+        .search(&ns, SearchOptions::new().with_query("rust traits"))
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].item.key, "b");
+    store.put_many(&ns, &[]).await.unwrap();
+}
+
+/// **Scenario**: Reopening the file with another embedding model refuses puts and queries in the
+/// pinned namespace; reembed migrates it (same dimension only) and writes work again.
+#[tokio::test]
+async fn sqlite_vec_store_pins_embedder_per_namespace() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("store_vec.db");
+    let ns = vec!["user1".into(), "memories".into()];
+
+    {
+        let store = SqliteVecStore::new(&path, Arc::new(MockEmbedder::new(8))).unwrap();
+        store
+            .put(&ns, "k1", &serde_json::json!({"text": "hello world"}))
+            .await
+            .unwrap();
+        store
+            .put(&ns, "k2", &serde_json::json!({"text": "rust programming"}))
+            .await
+            .unwrap();
+    }
+
+    let v2 = Arc::new(MockEmbedder::new(8).with_model("mock-v2"));
+    let store = SqliteVecStore::new(&path, v2.clone()).unwrap();
+    let err = store
+        .put(&ns, "k3", &serde_json::json!({"text": "new"}))
+        .await
+        .unwrap_err();
+    match &err {
+        StoreError::EmbedderMismatch {
+            namespace,
+            pinned,
+            current,
+        } => {
+            assert_eq!(namespace, &ns);
+            assert_eq!(pinned.model, "mock");
+            assert_eq!(current.model, "mock-v2");
+        }
+        other => panic!("expected EmbedderMismatch, got {}", other),
+    }
+    let err = store
+        .search(&ns, SearchOptions::new().with_query("rust"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, StoreError::EmbedderMismatch { .. }),
+        "{}",
+        err
+    );
+    assert!(store.get(&ns, "k1").await.unwrap().is_some());
+
+    let err = store
+        .reembed(&ns, Arc::new(MockEmbedder::new(4).with_model("mock-small")))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("dimension 8"), "{}", err);
+
+    assert_eq!(store.reembed(&ns, v2).await.unwrap(), 2);
+    store
+        .put(&ns, "k3", &serde_json::json!({"text": "new"}))
+        .await
+        .unwrap();
+    let hits = store
+        .search(&ns, SearchOptions::new().with_query("rust").with_limit(10))
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 3);
+}
@@ -3,6 +3,9 @@
 //! Verifies list_tools returns 5 tools; call_tool dispatches to store/short-term;
 //! set_call_context is forwarded so get_recent_messages sees context.
 
+// Keeps covering the deprecated wrappers until they are removed.
+#![allow(deprecated)]
+
 mod init_logging;
 
 use async_trait::async_trait;
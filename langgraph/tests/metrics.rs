@@ -0,0 +1,66 @@
+//! Integration test: a ReAct run reports node durations, tool calls and checkpoint I/O to the
+//! installed metrics recorder.
+//!
+//! The recorder is process-wide, so this file holds a single test.
+
+mod init_logging;
+
+use std::sync::Arc;
+
+use langgraph::metrics::set_recorder;
+use langgraph::{
+    MemorySaver, MockLlm, MockToolSource, PrometheusRecorder, ReActState, ReactRunner,
+    RunnableConfig,
+};
+
+/// **Scenario**: A checkpointed run that calls `get_time` once records think, act and observe
+/// durations, one successful tool call, and checkpoint reads and writes.
+#[tokio::test]
+async fn react_run_records_metrics() {
+    let recorder = Arc::new(PrometheusRecorder::new());
+    assert!(set_recorder(recorder.clone()).is_ok());
+    assert!(set_recorder(Arc::new(PrometheusRecorder::new())).is_err());
+
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    let config = Some(RunnableConfig {
+        thread_id: Some("metrics".into()),
+        ..Default::default()
+    });
+    runner
+        .invoke_with_config("What time is it?", config)
+        .await
+        .expect("run");
+
+    let nodes = "langgraph_node_duration_seconds";
+    assert_eq!(
+        recorder.observations(nodes, &[("node", "think"), ("status", "ok")]),
+        2
+    );
+    assert_eq!(
+        recorder.observations(nodes, &[("node", "act"), ("status", "ok")]),
+        2
+    );
+    assert_eq!(
+        recorder.observations(nodes, &[("node", "observe"), ("status", "ok")]),
+        2
+    );
+    assert_eq!(
+        recorder.counter(
+            "langgraph_tool_calls_total",
+            &[("tool", "get_time"), ("status", "ok")]
+        ),
+        1
+    );
+    let checkpoints = "langgraph_checkpoint_duration_seconds";
+    assert!(recorder.observations(checkpoints, &[("op", "read"), ("status", "ok")]) >= 1);
+    assert!(recorder.observations(checkpoints, &[("op", "write"), ("status", "ok")]) > 1);
+}
@@ -9,8 +9,8 @@ mod init_logging;
 
 use langgraph::{
     stream::{MessageChunk, StreamMetadata},
-    parse_chat_request, ChatCompletionRequest, ChatMessage, ChunkMeta, ReActState, StreamEvent,
-    StreamToSse,
+    parse_chat_request, ChatCompletion, ChatCompletionRequest, ChatMessage, ChunkMeta,
+    LlmResponseMetadata, LlmUsage, Message, ReActState, StreamEvent, StreamToSse,
 };
 
 fn empty_state() -> ReActState {
@@ -19,6 +19,10 @@ fn empty_state() -> ReActState {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     }
 }
 
@@ -60,17 +64,13 @@ fn adapter_emits_content_delta_per_messages_event() {
         node_id: "think".to_string(),
     });
     adapter.feed(StreamEvent::Messages {
-        chunk: MessageChunk {
-            content: "Hello".to_string(),
-        },
+        chunk: MessageChunk::text("Hello"),
         metadata: StreamMetadata {
             langgraph_node: "think".to_string(),
         },
     });
     adapter.feed(StreamEvent::Messages {
-        chunk: MessageChunk {
-            content: " world".to_string(),
-        },
+        chunk: MessageChunk::text(" world"),
         metadata: StreamMetadata {
             langgraph_node: "think".to_string(),
         },
@@ -82,6 +82,57 @@ fn adapter_emits_content_delta_per_messages_event() {
     assert!(lines[2].contains(r#""content":" world""#));
 }
 
+/// **Scenario**: Content deltas written from the pre-serialized prefix are byte for byte what
+/// serde writes for the same chunk, including escapes in the id, model and text.
+#[test]
+fn adapter_content_delta_matches_serialized_chunk() {
+    use langgraph::openai_sse::{ChunkChoice, Delta};
+
+    let meta = ChunkMeta {
+        id: "chatcmpl-\"quoted\"".to_string(),
+        model: "gpt-4o\\mini".to_string(),
+        created: Some(1694268190),
+    };
+    let mut adapter = StreamToSse::new(meta.clone(), false);
+    let texts = [
+        "Hello",
+        "",
+        "line\nbreak \"quote\" \\ tab\t \u{1}",
+        "\u{4f60}\u{597d} \u{1f600}",
+    ];
+    for text in texts {
+        adapter.feed(StreamEvent::Messages {
+            chunk: MessageChunk::text(text),
+            metadata: StreamMetadata {
+                langgraph_node: "think".to_string(),
+            },
+        });
+    }
+
+    let lines = adapter.take_lines();
+    assert_eq!(lines.len(), texts.len());
+    for (line, text) in lines.iter().zip(texts) {
+        let expected = write_sse_line(&ChatCompletionChunk {
+            id: meta.id.clone(),
+            object: ChatCompletionChunk::OBJECT,
+            created: 1694268190,
+            model: meta.model.clone(),
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta: Delta {
+                    content: Some(text.to_string()),
+                    ..Default::default()
+                },
+                finish_reason: None,
+            }],
+            usage: None,
+            system_fingerprint: None,
+            citations: None,
+        });
+        assert_eq!(line, &expected);
+    }
+}
+
 /// **Scenario**: finish() emits final chunk with finish_reason "stop" and no content delta.
 #[test]
 fn adapter_finish_emits_stop_chunk() {
@@ -96,9 +147,7 @@ fn adapter_finish_emits_stop_chunk() {
         node_id: "think".to_string(),
     });
     adapter.feed(StreamEvent::Messages {
-        chunk: MessageChunk {
-            content: "Hi".to_string(),
-        },
+        chunk: MessageChunk::text("Hi"),
         metadata: StreamMetadata {
             langgraph_node: "think".to_string(),
         },
@@ -139,6 +188,64 @@ fn adapter_finish_includes_usage_when_requested() {
     assert!(last.contains(r#""total_tokens":15"#));
 }
 
+/// **Scenario**: Usage of several LLM calls in one run is summed in the final chunk.
+#[test]
+fn adapter_finish_sums_usage_of_all_calls() {
+    let meta = ChunkMeta {
+        id: "chatcmpl-usage-sum".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let mut adapter = StreamToSse::new(meta, true);
+    for (prompt_tokens, completion_tokens) in [(10, 5), (30, 7)] {
+        adapter.feed(StreamEvent::Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        });
+    }
+    adapter.finish();
+
+    let lines = adapter.take_lines();
+    let last = lines.last().expect("at least one line");
+    assert!(last.contains(r#""prompt_tokens":40"#));
+    assert!(last.contains(r#""completion_tokens":12"#));
+    assert!(last.contains(r#""total_tokens":52"#));
+}
+
+/// **Scenario**: ResponseMetadata sets the final chunk's finish_reason and system_fingerprint;
+/// a trailing tool_calls reason is reported as "stop".
+#[test]
+fn adapter_finish_uses_response_metadata() {
+    let meta = ChunkMeta {
+        id: "chatcmpl-meta".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let mut adapter = StreamToSse::new(meta.clone(), false);
+    adapter.feed(StreamEvent::ResponseMetadata(LlmResponseMetadata {
+        finish_reason: Some("length".to_string()),
+        model: Some("gpt-4o-2024-08-06".to_string()),
+        system_fingerprint: Some("fp_abc".to_string()),
+    }));
+    adapter.finish();
+    let lines = adapter.take_lines();
+    let last = lines.last().expect("at least one line");
+    assert!(last.contains(r#""finish_reason":"length""#));
+    assert!(last.contains(r#""system_fingerprint":"fp_abc""#));
+
+    let mut adapter = StreamToSse::new(meta, false);
+    adapter.feed(StreamEvent::ResponseMetadata(LlmResponseMetadata {
+        finish_reason: Some("tool_calls".to_string()),
+        ..Default::default()
+    }));
+    adapter.finish();
+    let lines = adapter.take_lines();
+    let last = lines.last().expect("at least one line");
+    assert!(last.contains(r#""finish_reason":"stop""#));
+    assert!(!last.contains("system_fingerprint"));
+}
+
 /// **Scenario**: new_with_sink sends each line to the channel as it is produced.
 #[tokio::test]
 async fn adapter_with_sink_sends_lines_to_channel() {
@@ -158,9 +265,7 @@ async fn adapter_with_sink_sends_lines_to_channel() {
     assert!(first.contains(r#""role":"assistant""#));
 
     adapter.feed(StreamEvent::Messages {
-        chunk: MessageChunk {
-            content: "Hi".to_string(),
-        },
+        chunk: MessageChunk::text("Hi"),
         metadata: StreamMetadata {
             langgraph_node: "think".to_string(),
         },
@@ -176,6 +281,42 @@ async fn adapter_with_sink_sends_lines_to_channel() {
     assert!(rx.recv().await.is_none());
 }
 
+/// **Scenario**: new_with_bytes_sink sends each line as bytes, the same as new_with_sink would,
+/// and keeps none for take_lines.
+#[tokio::test]
+async fn adapter_with_bytes_sink_sends_bytes_and_keeps_no_lines() {
+    let meta = ChunkMeta {
+        id: "chatcmpl-bytes".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let mut adapter = StreamToSse::new_with_bytes_sink(meta.clone(), false, tx);
+    let mut reference = StreamToSse::new(meta, false);
+    for adapter in [&mut adapter, &mut reference] {
+        adapter.feed(StreamEvent::TaskStart {
+            node_id: "think".to_string(),
+        });
+        for text in ["Hi", " there"] {
+            adapter.feed(StreamEvent::Messages {
+                chunk: MessageChunk::text(text),
+                metadata: StreamMetadata {
+                    langgraph_node: "think".to_string(),
+                },
+            });
+        }
+        adapter.finish();
+    }
+    assert!(adapter.take_lines().is_empty());
+    drop(adapter);
+
+    let mut sent = Vec::new();
+    while let Some(bytes) = rx.recv().await {
+        sent.push(String::from_utf8(bytes.to_vec()).unwrap());
+    }
+    assert_eq!(sent, reference.take_lines());
+}
+
 /// **Scenario**: Updates with non-empty tool_calls emits a chunk with delta.tool_calls and finish_reason "tool_calls".
 #[test]
 fn adapter_emits_tool_calls_chunk_on_updates_with_tool_calls() {
@@ -206,6 +347,10 @@ fn adapter_emits_tool_calls_chunk_on_updates_with_tool_calls() {
             ],
             tool_results: vec![],
             turn_count: 0,
+            response_metadata: None,
+            usage: Default::default(),
+            final_answer: None,
+            ui_events: vec![],
         },
     });
 
@@ -218,6 +363,83 @@ fn adapter_emits_tool_calls_chunk_on_updates_with_tool_calls() {
     assert!(lines[0].contains("call_1"));
 }
 
+/// **Scenario**: Messages with tool_call_chunks emit delta.tool_calls; the later think Updates only
+/// closes the turn with finish_reason "tool_calls" and does not repeat the calls.
+#[test]
+fn adapter_streams_tool_call_deltas_from_messages() {
+    use langgraph::{ToolCall, ToolCallChunk};
+
+    let meta = ChunkMeta {
+        id: "chatcmpl-tcd".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let mut adapter = StreamToSse::new(meta, false);
+
+    adapter.feed(StreamEvent::TaskStart {
+        node_id: "think".to_string(),
+    });
+    adapter.feed(StreamEvent::Messages {
+        chunk: MessageChunk {
+            content: String::new(),
+            tool_call_chunks: vec![ToolCallChunk {
+                index: 0,
+                id: Some("call_1".to_string()),
+                name: Some("get_time".to_string()),
+                arguments: Some(String::new()),
+            }],
+        },
+        metadata: StreamMetadata {
+            langgraph_node: "think".to_string(),
+        },
+    });
+    adapter.feed(StreamEvent::Messages {
+        chunk: MessageChunk {
+            content: String::new(),
+            tool_call_chunks: vec![ToolCallChunk {
+                index: 0,
+                arguments: Some("{}".to_string()),
+                ..Default::default()
+            }],
+        },
+        metadata: StreamMetadata {
+            langgraph_node: "think".to_string(),
+        },
+    });
+    let state = ReActState {
+        messages: vec![],
+        tool_calls: vec![ToolCall {
+            id: Some("call_1".to_string()),
+            name: "get_time".to_string(),
+            arguments: "{}".to_string(),
+        }],
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    };
+    adapter.feed(StreamEvent::Updates {
+        node_id: "think".to_string(),
+        state: state.clone(),
+    });
+    adapter.feed(StreamEvent::Updates {
+        node_id: "act".to_string(),
+        state,
+    });
+
+    let lines = adapter.take_lines();
+    assert_eq!(lines.len(), 4, "initial + two tool deltas + close; {:?}", lines);
+    assert!(lines[1].contains(r#""name":"get_time""#));
+    assert!(lines[1].contains(r#""id":"call_1""#));
+    assert!(!lines[1].contains(r#""content""#));
+    assert!(lines[2].contains(r#""arguments":"{}""#));
+    assert!(!lines[2].contains("get_time"));
+    assert!(lines[3].contains(r#""finish_reason":"tool_calls""#));
+    assert!(!lines[3].contains("get_time"));
+}
+
 /// **Scenario**: Values event does not emit a chunk; only finish() emits the final chunk.
 #[test]
 fn adapter_values_does_not_emit_finish_chunk() {
@@ -241,6 +463,57 @@ fn adapter_values_does_not_emit_finish_chunk() {
     assert_eq!(lines2.len(), 1, "finish adds one final chunk");
 }
 
+/// **Scenario**: Sources in the last turn's tool results go out with the final chunk as
+/// `url_citation` annotations and `citations`; the non-streamed completion carries the same.
+#[test]
+fn adapter_finish_sends_tool_sources_as_citations() {
+    let meta = ChunkMeta {
+        id: "chatcmpl-cite".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let mut adapter = StreamToSse::new(meta.clone(), false);
+    let state = ReActState {
+        messages: vec![
+            Message::user("What is new in tokio?"),
+            Message::tool(
+                Some("c1".into()),
+                Some("web_search_exa".into()),
+                "Title: Tokio 1.40\nURL: https://tokio.rs/blog/1.40\nText: ...",
+            ),
+            Message::assistant("See https://tokio.rs/blog/1.40"),
+        ],
+        ..empty_state()
+    };
+
+    adapter.feed(StreamEvent::Values(state.clone()));
+    adapter.finish();
+    let lines = adapter.take_lines();
+    let json = lines[0].trim().strip_prefix("data: ").unwrap();
+    let chunk: serde_json::Value = serde_json::from_str(json).unwrap();
+    let annotation = &chunk["choices"][0]["delta"]["annotations"][0];
+    assert_eq!(annotation["type"], "url_citation");
+    assert_eq!(annotation["url_citation"]["url"], "https://tokio.rs/blog/1.40");
+    assert_eq!(annotation["url_citation"]["title"], "Tokio 1.40");
+    assert_eq!(annotation["url_citation"]["start_index"], 4);
+    assert_eq!(
+        chunk["citations"],
+        serde_json::json!(["https://tokio.rs/blog/1.40"])
+    );
+
+    let completion = serde_json::to_value(ChatCompletion::from_state(meta, &state)).unwrap();
+    assert_eq!(
+        completion["choices"][0]["message"]["annotations"][0],
+        *annotation
+    );
+    assert_eq!(completion["citations"], chunk["citations"]);
+
+    adapter.feed(StreamEvent::Values(empty_state()));
+    adapter.finish();
+    let line = adapter.take_lines().remove(0);
+    assert!(!line.contains("annotations") && !line.contains("citations"));
+}
+
 // --- parse_chat_request ---
 
 /// **Scenario**: parse_chat_request returns last user message and system prompt or default.
@@ -261,6 +534,8 @@ fn parse_request_extracts_user_message_and_system_prompt() {
         stream: true,
         stream_options: None,
         thread_id: None,
+        user: None,
+        metadata: None,
     };
     let parsed = parse_chat_request(&req).unwrap();
     assert_eq!(parsed.user_message, "Hello");
@@ -280,6 +555,8 @@ fn parse_request_uses_default_system_prompt_when_no_system_message() {
         stream: true,
         stream_options: None,
         thread_id: None,
+        user: None,
+        metadata: None,
     };
     let parsed = parse_chat_request(&req).unwrap();
     assert_eq!(parsed.user_message, "Hi");
@@ -298,11 +575,41 @@ fn parse_request_passes_thread_id_to_runnable_config() {
         stream: true,
         stream_options: None,
         thread_id: Some("thread-123".to_string()),
+        user: None,
+        metadata: None,
     };
     let parsed = parse_chat_request(&req).unwrap();
     assert_eq!(parsed.runnable_config.thread_id.as_deref(), Some("thread-123"));
 }
 
+/// **Scenario**: `metadata.thread_id` and `user` route the run; the top-level `thread_id` wins
+/// over metadata and blank values count as unset.
+#[test]
+fn parse_request_routes_by_metadata_and_user() {
+    let req: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o",
+        "messages": [{"role": "user", "content": "Hi"}],
+        "user": "alice",
+        "metadata": {"thread_id": "t-meta", "user_id": "bob", "source": "web"}
+    }))
+    .unwrap();
+    let parsed = parse_chat_request(&req).unwrap();
+    assert_eq!(parsed.runnable_config.thread_id.as_deref(), Some("t-meta"));
+    assert_eq!(parsed.runnable_config.user_id.as_deref(), Some("alice"));
+
+    let req: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o",
+        "messages": [{"role": "user", "content": "Hi"}],
+        "thread_id": "t-body",
+        "user": " ",
+        "metadata": {"thread_id": "t-meta", "user_id": "bob"}
+    }))
+    .unwrap();
+    let parsed = parse_chat_request(&req).unwrap();
+    assert_eq!(parsed.runnable_config.thread_id.as_deref(), Some("t-body"));
+    assert_eq!(parsed.runnable_config.user_id.as_deref(), Some("bob"));
+}
+
 /// **Scenario**: No user message returns ParseError::NoUserMessage.
 #[test]
 fn parse_request_errors_when_no_user_message() {
@@ -315,7 +622,52 @@ fn parse_request_errors_when_no_user_message() {
         stream: true,
         stream_options: None,
         thread_id: None,
+        user: None,
+        metadata: None,
     };
     let err = parse_chat_request(&req).unwrap_err();
     assert!(matches!(err, langgraph::ParseError::NoUserMessage));
 }
+
+/// **Scenario**: A finished run becomes a chat.completion with the run's answer, its usage and
+/// the last finish reason (a tool round reports "stop").
+#[test]
+fn completion_from_state_has_answer_usage_and_finish_reason() {
+    let meta = ChunkMeta {
+        id: "chatcmpl-done".to_string(),
+        model: "gpt-4o".to_string(),
+        created: Some(1694268190),
+    };
+    let mut state = empty_state();
+    state.messages = vec![Message::user("Hi"), Message::assistant("Hello!")];
+    state.usage = LlmUsage {
+        prompt_tokens: 12,
+        completion_tokens: 3,
+        total_tokens: 15,
+    };
+    state.response_metadata = Some(LlmResponseMetadata {
+        finish_reason: Some("length".to_string()),
+        ..Default::default()
+    });
+
+    let value = serde_json::to_value(ChatCompletion::from_state(meta.clone(), &state)).unwrap();
+    assert_eq!(value["object"], "chat.completion");
+    assert_eq!(value["id"], "chatcmpl-done");
+    assert_eq!(value["created"], 1694268190);
+    assert_eq!(value["choices"][0]["message"]["role"], "assistant");
+    assert_eq!(value["choices"][0]["message"]["content"], "Hello!");
+    assert_eq!(value["choices"][0]["finish_reason"], "length");
+    assert_eq!(value["usage"]["total_tokens"], 15);
+
+    state.final_answer = Some("Final.".to_string());
+    state.response_metadata = Some(LlmResponseMetadata {
+        finish_reason: Some("tool_calls".to_string()),
+        ..Default::default()
+    });
+    let completion = ChatCompletion::from_state(meta, &state);
+    assert_eq!(
+        completion.choices[0].message.content.as_deref(),
+        Some("Final.")
+    );
+    assert_eq!(completion.choices[0].finish_reason, "stop");
+}
@@ -5,12 +5,17 @@
 
 mod init_logging;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use async_trait::async_trait;
 use langgraph::{
-    ActNode, CompiledStateGraph, Message, MockLlm, MockToolSource, ObserveNode, ReActState,
-    StateGraph, ThinkNode, END, START,
+    ActNode, AgentError, BudgetPolicy, CancellationToken, CompiledStateGraph, InjectError,
+    Injector, LlmClient, LlmResponse, LlmUsage, MemorySaver, Message, MessageChunk, MockLlm,
+    MockToolSource, ObserveNode, ReActState, ReactRunner, RunError, RunnableConfig, SamplingParams,
+    SamplingSchedule, StateGraph, StreamEvent, ThinkNode, END, MAX_REACT_TURNS,
+    NEED_MORE_STEPS_MESSAGE, START,
 };
+use tokio::sync::mpsc;
 
 #[tokio::test]
 async fn react_linear_chain_user_to_tool_result_in_messages() {
@@ -37,18 +42,22 @@ async fn react_linear_chain_user_to_tool_result_in_messages() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     let out = compiled.invoke(state, None).await.unwrap();
 
     // think: 1 user -> 2 (user + assistant)
     // act: filled tool_results
-    // observe: merged tool result as User message, cleared tool_*
+    // observe: merged tool result as Tool message, cleared tool_*
     assert!(out.messages.len() >= 3);
     assert!(matches!(&out.messages[0], Message::User(_)));
-    assert!(matches!(&out.messages[1], Message::Assistant(_)));
+    assert!(matches!(&out.messages[1], Message::Assistant { .. }));
     assert!(
-        matches!(&out.messages[2], Message::User(s) if s.contains("Tool") && s.contains("2025-01-29"))
+        matches!(&out.messages[2], Message::Tool { content, .. } if content.contains("2025-01-29"))
     );
     assert!(out.tool_calls.is_empty());
     assert!(out.tool_results.is_empty());
@@ -81,6 +90,10 @@ async fn react_multi_round_loop_then_end() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     let out = compiled.invoke(state, None).await.unwrap();
@@ -91,3 +104,414 @@ async fn react_multi_round_loop_then_end() {
     assert!(out.tool_calls.is_empty());
     assert!(out.tool_results.is_empty());
 }
+
+/// **Scenario**: ReactRunner loops observe → think after a tool round and ends when the model
+/// answers without tools.
+#[tokio::test]
+async fn react_runner_loops_until_answer_without_tools() {
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    assert_eq!(runner.max_turns(), MAX_REACT_TURNS);
+
+    let out = runner.invoke("What time is it?").await.unwrap();
+
+    assert_eq!(out.turn_count, 2);
+    assert!(out.messages.iter().any(|m| matches!(m, Message::Tool { .. })));
+    assert_eq!(out.last_assistant_reply().as_deref(), Some("The time is as above."));
+    assert_eq!(out.final_answer(), Some("The time is as above."));
+}
+
+/// **Scenario**: The final state of ReactRunner::invoke sums the usage of every think step of
+/// the run; a later run on the same thread starts counting from zero.
+#[tokio::test]
+async fn react_runner_sums_usage_per_run() {
+    let per_call = LlmUsage {
+        prompt_tokens: 100,
+        completion_tokens: 20,
+        total_tokens: 120,
+    };
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end().with_usage(per_call)),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        Some(RunnableConfig {
+            thread_id: Some("usage-thread".into()),
+            ..Default::default()
+        }),
+        None,
+        false,
+    )
+    .expect("compile");
+
+    let first = runner.invoke("What time is it?").await.unwrap();
+    assert_eq!(
+        first.usage,
+        LlmUsage {
+            prompt_tokens: 200,
+            completion_tokens: 40,
+            total_tokens: 240,
+        }
+    );
+
+    let second = runner.invoke("Thanks").await.unwrap();
+    assert_eq!(second.usage.total_tokens, 120);
+}
+
+/// **Scenario**: A model that keeps calling tools is stopped by the runner's budget with
+/// RunError::BudgetExceeded: by tokens after the second think step, and by tool calls before
+/// the second act step.
+#[tokio::test]
+async fn react_runner_budget_stops_run() {
+    let runner = |budget: BudgetPolicy| {
+        let llm = MockLlm::with_get_time_call().with_usage(LlmUsage {
+            prompt_tokens: 100,
+            completion_tokens: 20,
+            total_tokens: 120,
+        });
+        ReactRunner::new(
+            Box::new(llm),
+            Box::new(MockToolSource::get_time_example()),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .expect("compile")
+        .with_budget(budget)
+    };
+
+    let err = runner(BudgetPolicy::new().with_max_tokens(200))
+        .invoke("What time is it?")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, RunError::BudgetExceeded(r) if r == "token budget of 200 exceeded (240 used)"),
+        "{:?}",
+        err
+    );
+
+    let err = runner(BudgetPolicy::new().with_max_tool_calls(1))
+        .invoke("What time is it?")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, RunError::BudgetExceeded(r) if r == "tool call budget of 1 exceeded"),
+        "{:?}",
+        err
+    );
+}
+
+/// **Scenario**: With max_turns 2 and a model that always calls tools, the second think step
+/// is the last step: its tool calls are dropped and the run ends with NEED_MORE_STEPS_MESSAGE.
+#[tokio::test]
+async fn react_runner_max_turns_caps_loop_and_sets_last_step() {
+    let runner = ReactRunner::new_with_max_turns(
+        Box::new(MockLlm::with_get_time_call()),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+        2,
+    )
+    .expect("compile");
+
+    let out = runner.invoke("What time is it?").await.unwrap();
+
+    assert_eq!(out.turn_count, 2);
+    let tool_messages = out
+        .messages
+        .iter()
+        .filter(|m| matches!(m, Message::Tool { .. }))
+        .count();
+    assert_eq!(tool_messages, 1);
+    assert!(matches!(
+        out.messages.last(),
+        Some(Message::Assistant { content, tool_calls }) if content == NEED_MORE_STEPS_MESSAGE && tool_calls.is_empty()
+    ));
+}
+
+/// LLM recording the sampling parameters of every call (default ones for plain calls).
+struct SamplingRecorder {
+    inner: MockLlm,
+    seen: Arc<Mutex<Vec<SamplingParams>>>,
+}
+
+#[async_trait]
+impl LlmClient for SamplingRecorder {
+    async fn invoke(&self, messages: &[Message]) -> Result<LlmResponse, AgentError> {
+        self.seen.lock().unwrap().push(SamplingParams::default());
+        self.inner.invoke(messages).await
+    }
+
+    async fn invoke_stream_with_sampling(
+        &self,
+        messages: &[Message],
+        chunk_tx: Option<mpsc::Sender<MessageChunk>>,
+        sampling: &SamplingParams,
+    ) -> Result<LlmResponse, AgentError> {
+        self.seen.lock().unwrap().push(*sampling);
+        self.inner.invoke_stream(messages, chunk_tx).await
+    }
+}
+
+/// **Scenario**: With a sampling schedule, tool-selection steps use its `tool_selection`
+/// parameters and the last step its `final_answer` ones, for invoke and stream runs alike.
+#[tokio::test]
+async fn react_runner_sampling_schedule_per_phase() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let schedule = SamplingSchedule::new()
+        .with_tool_selection(SamplingParams::temperature(0.0))
+        .with_final_answer(SamplingParams::temperature(0.8).with_top_p(0.9));
+    let runner = ReactRunner::new_with_max_turns(
+        Box::new(SamplingRecorder {
+            inner: MockLlm::with_get_time_call(),
+            seen: Arc::clone(&seen),
+        }),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+        2,
+    )
+    .expect("compile")
+    .with_sampling_schedule(schedule);
+    let expected = vec![
+        SamplingParams::temperature(0.0),
+        SamplingParams::temperature(0.8).with_top_p(0.9),
+    ];
+
+    runner.invoke("What time is it?").await.unwrap();
+    assert_eq!(*seen.lock().unwrap(), expected);
+
+    seen.lock().unwrap().clear();
+    runner
+        .stream_with_config("What time is it?", None, Some(|_| {}))
+        .await
+        .unwrap();
+    assert_eq!(*seen.lock().unwrap(), expected);
+}
+
+/// **Scenario**: A runner paused before `act` waits for approval; resume runs the tools and
+/// continues the loop until the model answers.
+#[tokio::test]
+async fn react_runner_interrupt_before_act_and_resume() {
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile")
+    .with_interrupt_before(&["act".to_string()])
+    .expect("act is a ReAct node");
+    let config = Some(RunnableConfig {
+        thread_id: Some("approve-tools".into()),
+        ..Default::default()
+    });
+
+    let err = runner
+        .invoke_with_config("What time is it?", config.clone())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, RunError::Execution(AgentError::Interrupted(_))),
+        "{:?}",
+        err
+    );
+    assert_eq!(
+        runner.pending_node(config.clone()).await.unwrap().as_deref(),
+        Some("act")
+    );
+
+    // act runs every round, so the answering round pauses once more before finishing.
+    let err = runner.resume(None, config.clone()).await.unwrap_err();
+    assert!(matches!(err, RunError::Execution(AgentError::Interrupted(_))), "{:?}", err);
+    let out = runner.resume(None, config.clone()).await.unwrap();
+    assert!(out.messages.iter().any(|m| matches!(m, Message::Tool { .. })));
+    assert_eq!(out.last_assistant_reply().as_deref(), Some("The time is as above."));
+    assert!(runner.pending_node(config).await.unwrap().is_none());
+}
+
+/// **Scenario**: get_state returns the thread's persisted messages after a run; unknown
+/// threads and configs without thread_id have no state.
+#[tokio::test]
+async fn react_runner_get_state_loads_thread_checkpoint() {
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("Hi there")),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    let config = Some(RunnableConfig {
+        thread_id: Some("stateful".into()),
+        ..Default::default()
+    });
+    runner
+        .invoke_with_config("Hello", config.clone())
+        .await
+        .unwrap();
+
+    let state = runner
+        .get_state(config)
+        .await
+        .unwrap()
+        .expect("checkpointed");
+    assert!(state
+        .messages
+        .iter()
+        .any(|m| matches!(m, Message::User(u) if u == "Hello")));
+    assert_eq!(state.last_assistant_reply().as_deref(), Some("Hi there"));
+
+    let other = Some(RunnableConfig {
+        thread_id: Some("never-run".into()),
+        ..Default::default()
+    });
+    assert!(runner.get_state(other).await.unwrap().is_none());
+    assert!(runner.get_state(None).await.unwrap().is_none());
+}
+
+/// **Scenario**: append_messages imports messages into a new thread after the system prompt
+/// without calling the model, appends to an existing thread in one checkpoint, and a later run
+/// continues from the imported history; a config without thread_id is an error.
+#[tokio::test]
+async fn react_runner_append_messages_imports_history() {
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("Welcome back")),
+        Box::new(MockToolSource::get_time_example()),
+        Some(Arc::new(MemorySaver::<ReActState>::new())),
+        None,
+        None,
+        Some("Be brief.".to_string()),
+        false,
+    )
+    .expect("compile");
+    let config = RunnableConfig {
+        thread_id: Some("imported".into()),
+        ..Default::default()
+    };
+
+    let count = runner
+        .append_messages(
+            config.clone(),
+            vec![Message::user("Hi"), Message::assistant("Hello")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+    let count = runner
+        .append_messages(config.clone(), vec![Message::user("Still there?")])
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+    let state = runner
+        .get_state(Some(config.clone()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(&state.messages[0], Message::System(s) if s == "Be brief."));
+    assert!(matches!(&state.messages[3], Message::User(u) if u == "Still there?"));
+    let checkpoints = runner.list_checkpoints("imported").await.unwrap();
+    assert_eq!(checkpoints.len(), 2);
+
+    let out = runner
+        .invoke_with_config("And now?", Some(config))
+        .await
+        .unwrap();
+    assert!(matches!(&out.messages[1], Message::User(u) if u == "Hi"));
+    assert_eq!(out.last_assistant_reply().as_deref(), Some("Welcome back"));
+
+    let own_prompt = RunnableConfig {
+        thread_id: Some("own-prompt".into()),
+        ..Default::default()
+    };
+    let count = runner
+        .append_messages(
+            own_prompt,
+            vec![Message::system("Old prompt"), Message::user("Hi")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let err = runner
+        .append_messages(RunnableConfig::default(), vec![Message::user("Hi")])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RunError::Execution(_)), "{:?}", err);
+}
+
+/// **Scenario**: A user message injected into a run is merged after `observe`, although the
+/// model had already answered, and `think` answers again; the injector is closed afterwards.
+#[tokio::test]
+async fn react_runner_merges_injected_user_message_and_thinks_again() {
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("ok")),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile");
+    let injector = Injector::new();
+    injector
+        .push(|s: &mut ReActState| s.messages.push(Message::user("actually, in French")))
+        .unwrap();
+
+    let state = runner
+        .stream_with_injector(
+            "Hello",
+            None,
+            CancellationToken::new(),
+            injector.clone(),
+            None::<fn(StreamEvent<ReActState>)>,
+        )
+        .await
+        .unwrap();
+
+    let turns: Vec<(&str, &str)> = state
+        .messages
+        .iter()
+        .filter_map(|m| match m {
+            Message::User(u) => Some(("user", u.as_str())),
+            Message::Assistant { content, .. } => Some(("assistant", content.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        turns,
+        [
+            ("user", "Hello"),
+            ("assistant", "ok"),
+            ("user", "actually, in French"),
+            ("assistant", "ok"),
+        ]
+    );
+    assert!(injector.is_closed());
+    assert_eq!(
+        injector.push(|s: &mut ReActState| s.messages.clear()),
+        Err(InjectError::RunFinished)
+    );
+}
@@ -7,14 +7,21 @@
 mod init_logging;
 
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use langgraph::{
     graph::RunContext,
     memory::RunnableConfig,
     stream::{StreamEvent, StreamMode},
-    ActNode, Message, MockLlm, MockToolSource, Next, Node, ObserveNode, ReActState, ThinkNode,
-    ToolCall, ToolResult,
+    ActNode, HandleToolErrors, InMemoryStore, IsLastStep, Message, MockLlm, MockToolSource, Next, Node,
+    ObserveNode, ReActState, Store, ThinkNode, ToolAttachments, ToolCall, ToolCallContent,
+    ToolCallContext, ToolResult, ToolSource, ToolSourceError, ToolSpec, IS_LAST_STEP,
+    NEED_MORE_STEPS_MESSAGE,
 };
+use serde_json::Value;
 use tokio::sync::mpsc;
 
 // --- ThinkNode ---
@@ -35,10 +42,14 @@ async fn think_node_appends_assistant_message_and_sets_tool_calls() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 2);
-    assert!(matches!(&out.messages[1], Message::Assistant(s) if s == "I'll check the time."));
+    assert!(matches!(&out.messages[1], Message::Assistant { content: s, .. } if s == "I'll check the time."));
     assert_eq!(out.tool_calls.len(), 1);
     assert_eq!(out.tool_calls[0].name, "get_time");
     assert_eq!(out.tool_calls[0].arguments, "{}");
@@ -54,10 +65,14 @@ async fn think_node_with_no_tool_calls_sets_empty_tool_calls() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 2);
-    assert!(matches!(&out.messages[1], Message::Assistant(s) if s == "Hello."));
+    assert!(matches!(&out.messages[1], Message::Assistant { content: s, .. } if s == "Hello."));
     assert!(out.tool_calls.is_empty());
     assert!(out.tool_results.is_empty());
 }
@@ -75,12 +90,46 @@ async fn think_node_preserves_tool_results_from_input_state() {
             content: "12:00".into(),
         }],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.tool_results.len(), 1);
     assert_eq!(out.tool_results[0].content, "12:00");
 }
 
+/// **Scenario**: When the run context carries IsLastStep, tool calls are dropped and the
+/// reply becomes NEED_MORE_STEPS_MESSAGE; without it the same node keeps the tool calls.
+#[tokio::test]
+async fn think_node_last_step_drops_tool_calls() {
+    let node = ThinkNode::new(Box::new(MockLlm::with_get_time_call()));
+    let state = ReActState {
+        messages: vec![Message::user("What time is it?")],
+        tool_calls: vec![],
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    };
+    let ctx = RunContext::<ReActState>::new(RunnableConfig::default())
+        .with_managed_value(IS_LAST_STEP, Arc::new(IsLastStep::new(true)));
+    let (out, _) = node.run_with_context(state.clone(), &ctx).await.unwrap();
+    assert!(out.tool_calls.is_empty());
+    assert_eq!(out.last_assistant_reply().as_deref(), Some(NEED_MORE_STEPS_MESSAGE));
+
+    let (out, _) = node.run(state.clone()).await.unwrap();
+    assert_eq!(out.tool_calls.len(), 1);
+
+    // with_max_turns(1): the first round is already the last one.
+    let node = ThinkNode::new(Box::new(MockLlm::with_get_time_call())).with_max_turns(1);
+    let (out, _) = node.run(state).await.unwrap();
+    assert!(out.tool_calls.is_empty());
+}
+
 // --- ActNode ---
 
 #[tokio::test]
@@ -103,6 +152,10 @@ async fn act_node_executes_tool_calls_and_writes_tool_results() {
         }],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 1);
@@ -122,6 +175,10 @@ async fn act_node_empty_tool_calls_leaves_tool_results_empty() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert!(out.tool_results.is_empty());
@@ -148,6 +205,10 @@ async fn act_node_multiple_tool_calls_produces_multiple_results() {
         ],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.tool_results.len(), 2);
@@ -155,6 +216,381 @@ async fn act_node_multiple_tool_calls_produces_multiple_results() {
     assert_eq!(out.tool_results[1].content, "2025-01-29 12:00:00");
 }
 
+/// Tool source whose `counter` tool increments a value kept in `ToolCallContext::tool_state`.
+struct CounterToolSource;
+
+#[async_trait]
+impl ToolSource for CounterToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(vec![])
+    }
+
+    async fn call_tool(&self, _: &str, _: Value) -> Result<ToolCallContent, ToolSourceError> {
+        Ok(ToolCallContent { text: "no state".into() })
+    }
+
+    async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        let Some(state) = ctx.and_then(|c| c.tool_state.as_ref()) else {
+            return self.call_tool(name, arguments).await;
+        };
+        let n = state
+            .get("n")
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            + 1;
+        state
+            .put("n", &serde_json::json!(n))
+            .await
+            .map_err(|e| ToolSourceError::Transport(e.to_string()))?;
+        Ok(ToolCallContent { text: n.to_string() })
+    }
+}
+
+fn counter_call_state() -> ReActState {
+    ReActState {
+        messages: vec![],
+        tool_calls: vec![ToolCall {
+            name: "counter".into(),
+            arguments: "{}".into(),
+            id: Some("c1".into()),
+        }],
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    }
+}
+
+/// **Scenario**: With a thread_id, tool state persists across ActNode runs per user and thread,
+/// and nothing is written to the run's long-term memory store.
+#[tokio::test]
+async fn act_node_tool_state_persists_per_user_and_thread() {
+    let node = ActNode::new(Box::new(CounterToolSource));
+    let store = Arc::new(InMemoryStore::new());
+    let ctx_for = |user: &str, thread: &str| {
+        RunContext::<ReActState>::new(RunnableConfig {
+            thread_id: Some(thread.into()),
+            user_id: Some(user.into()),
+            ..Default::default()
+        })
+        .with_store(store.clone())
+    };
+
+    for (user, thread, expected) in [
+        ("u1", "t1", "1"),
+        ("u1", "t1", "2"),
+        ("u1", "t2", "1"),
+        ("u2", "t1", "1"),
+    ] {
+        let (out, _) = node
+            .run_with_context(counter_call_state(), &ctx_for(user, thread))
+            .await
+            .unwrap();
+        assert_eq!(out.tool_results[0].content, expected, "{user}/{thread}");
+    }
+    let namespace = vec![
+        "tool_state".to_string(),
+        "u1".into(),
+        "t1".into(),
+        "counter".into(),
+    ];
+    assert!(store.list(&namespace).await.unwrap().is_empty());
+}
+
+/// **Scenario**: Without a thread_id, tools get no tool state.
+#[tokio::test]
+async fn act_node_no_tool_state_without_thread() {
+    let node = ActNode::new(Box::new(CounterToolSource));
+    let (out, _) = node.run(counter_call_state()).await.unwrap();
+    assert_eq!(out.tool_results[0].content, "no state");
+}
+
+/// Tool source recording whether the shared context of `set_call_context` carried per-call
+/// tool state.
+#[derive(Default)]
+struct SharedContextSpy {
+    shared_with_state: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolSource for SharedContextSpy {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(vec![])
+    }
+
+    async fn call_tool(&self, _: &str, _: Value) -> Result<ToolCallContent, ToolSourceError> {
+        Ok(ToolCallContent { text: "ok".into() })
+    }
+
+    fn set_call_context(&self, ctx: Option<ToolCallContext>) {
+        if ctx.is_some_and(|c| c.tool_state.is_some() || c.ui_events.is_some()) {
+            self.shared_with_state.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// **Scenario**: Per-call tool state and UI sinks only go to `call_tool_with_context`; the
+/// shared context set for concurrent calls carries neither.
+#[tokio::test]
+async fn act_node_shared_context_has_no_per_call_state() {
+    let spy = SharedContextSpy::default();
+    let shared_with_state = Arc::clone(&spy.shared_with_state);
+    let node = ActNode::new(Box::new(spy));
+    let mut state = counter_call_state();
+    state.tool_calls.push(state.tool_calls[0].clone());
+    let ctx = RunContext::<ReActState>::new(RunnableConfig {
+        thread_id: Some("t1".into()),
+        ..Default::default()
+    });
+    node.run_with_context(state, &ctx).await.unwrap();
+    assert_eq!(shared_with_state.load(Ordering::SeqCst), 0);
+}
+
+/// Tool source whose `sleep` tool waits `{"ms": n}` and replies `n`, or fails for `"fail": true`;
+/// records how many calls ran at the same time.
+#[derive(Default)]
+struct SleepToolSource {
+    in_flight: AtomicUsize,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolSource for SleepToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(vec![])
+    }
+
+    async fn call_tool(&self, _: &str, args: Value) -> Result<ToolCallContent, ToolSourceError> {
+        let running = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(running, Ordering::SeqCst);
+        let ms = args["ms"].as_u64().unwrap_or(0);
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        if args["fail"].as_bool() == Some(true) {
+            return Err(ToolSourceError::Transport(format!("failed after {}ms", ms)));
+        }
+        Ok(ToolCallContent {
+            text: ms.to_string(),
+        })
+    }
+}
+
+fn sleep_calls_state(calls: &[(&str, &str)]) -> ReActState {
+    ReActState {
+        messages: vec![],
+        tool_calls: calls
+            .iter()
+            .map(|(id, args)| ToolCall {
+                name: "sleep".into(),
+                arguments: args.to_string(),
+                id: Some(id.to_string()),
+            })
+            .collect(),
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    }
+}
+
+/// **Scenario**: Tool calls run concurrently up to the limit and results keep the call order,
+/// even when later calls finish first.
+#[tokio::test]
+async fn act_node_runs_tool_calls_in_parallel_in_call_order() {
+    let calls = [
+        ("c1", r#"{"ms": 60}"#),
+        ("c2", r#"{"ms": 30}"#),
+        ("c3", r#"{"ms": 10}"#),
+    ];
+
+    let tools = SleepToolSource::default();
+    let max_in_flight = tools.max_in_flight.clone();
+    let node = ActNode::new(Box::new(tools));
+    let (out, _) = node.run(sleep_calls_state(&calls)).await.unwrap();
+    let ids: Vec<_> = out
+        .tool_results
+        .iter()
+        .map(|r| r.call_id.as_deref().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["c1", "c2", "c3"]);
+    let contents: Vec<_> = out
+        .tool_results
+        .iter()
+        .map(|r| r.content.as_str())
+        .collect();
+    assert_eq!(contents, vec!["60", "30", "10"]);
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+
+    let tools = SleepToolSource::default();
+    let max_in_flight = tools.max_in_flight.clone();
+    let node = ActNode::new(Box::new(tools)).with_max_parallel_tool_calls(1);
+    let (out, _) = node.run(sleep_calls_state(&calls)).await.unwrap();
+    assert_eq!(out.tool_results.len(), 3);
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+}
+
+/// **Scenario**: With parallel calls, HandleToolErrors applies per call: a caught failure becomes
+/// that call's result; an uncaught one fails the step.
+#[tokio::test]
+async fn act_node_parallel_calls_handle_errors_per_call() {
+    let calls = [
+        ("c1", r#"{"ms": 20}"#),
+        ("c2", r#"{"ms": 0, "fail": true}"#),
+        ("c3", r#"{"ms": 10}"#),
+    ];
+
+    let node = ActNode::new(Box::new(SleepToolSource::default()))
+        .with_handle_tool_errors(HandleToolErrors::Always(None));
+    let (out, _) = node.run(sleep_calls_state(&calls)).await.unwrap();
+    assert_eq!(out.tool_results.len(), 3);
+    assert_eq!(out.tool_results[0].content, "20");
+    assert!(
+        out.tool_results[1].content.contains("failed after 0ms"),
+        "{}",
+        out.tool_results[1].content
+    );
+    assert_eq!(out.tool_results[2].content, "10");
+
+    let node = ActNode::new(Box::new(SleepToolSource::default()));
+    let err = node.run(sleep_calls_state(&calls)).await.unwrap_err();
+    assert!(err.to_string().contains("failed after 0ms"), "{}", err);
+}
+
+/// **Scenario**: A per-tool template is filled once with the call id and the attempt number
+/// (earlier results of the same tool + 1); braces in the arguments are not expanded.
+#[tokio::test]
+async fn act_node_per_tool_error_template_fills_call_vars_once() {
+    let mut state = sleep_calls_state(&[("c7", r#"{"fail": true, "note": "{call_id}"}"#)]);
+    state.messages = vec![
+        Message::tool(Some("c1".into()), Some("sleep".into()), "0"),
+        Message::tool(Some("c2".into()), Some("other".into()), "0"),
+    ];
+    let node = ActNode::new(Box::new(SleepToolSource::default())).with_handle_tool_errors(
+        HandleToolErrors::PerTool {
+            templates: [(
+                "sleep".to_string(),
+                "{call_id} attempt {attempt}: {error} {{{tool_kwargs}}}".to_string(),
+            )]
+            .into(),
+            default: None,
+        },
+    );
+    let (out, _) = node.run(state).await.unwrap();
+    assert_eq!(
+        out.tool_results[0].content,
+        r#"c7 attempt 2: MCP/transport error: failed after 0ms {{"fail":true,"note":"{call_id}"}}"#
+    );
+}
+
+/// Tool source whose `weather` tool requires a string `city`; counts calls that reached it.
+#[derive(Default)]
+struct WeatherToolSource {
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolSource for WeatherToolSource {
+    async fn list_tools(&self) -> Result<Vec<ToolSpec>, ToolSourceError> {
+        Ok(vec![ToolSpec {
+            name: "weather".into(),
+            description: None,
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"]
+            }),
+        }])
+    }
+
+    async fn call_tool(&self, _: &str, args: Value) -> Result<ToolCallContent, ToolSourceError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(ToolCallContent {
+            text: format!("sunny in {}", args["city"]),
+        })
+    }
+}
+
+fn weather_calls_state(args: &[&str]) -> ReActState {
+    ReActState {
+        tool_calls: args
+            .iter()
+            .enumerate()
+            .map(|(i, args)| ToolCall {
+                name: "weather".into(),
+                arguments: args.to_string(),
+                id: Some(format!("c{}", i)),
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// **Scenario**: Malformed JSON and arguments the input schema rejects never reach the tool;
+/// the errors name the problem and go through HandleToolErrors, valid calls still run.
+#[tokio::test]
+async fn act_node_validates_arguments_against_input_schema() {
+    let tools = WeatherToolSource::default();
+    let calls = tools.calls.clone();
+    let node =
+        ActNode::new(Box::new(tools)).with_handle_tool_errors(HandleToolErrors::Always(None));
+    let state = weather_calls_state(&[
+        r#"{"city": "Paris"}"#,
+        r#"{"city": 5"#,
+        r#"{"city": 5}"#,
+        "{}",
+    ]);
+    let (out, _) = node.run(state).await.unwrap();
+
+    assert_eq!(out.tool_results[0].content, "sunny in \"Paris\"");
+    assert!(
+        out.tool_results[1].content.contains("not valid JSON"),
+        "{}",
+        out.tool_results[1].content
+    );
+    assert!(
+        out.tool_results[2].content.contains("/city"),
+        "{}",
+        out.tool_results[2].content
+    );
+    assert!(
+        out.tool_results[2].content.contains("string"),
+        "{}",
+        out.tool_results[2].content
+    );
+    assert!(
+        out.tool_results[3].content.contains("city"),
+        "{}",
+        out.tool_results[3].content
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let node = ActNode::new(Box::new(WeatherToolSource::default()));
+    let err = node
+        .run(weather_calls_state(&[r#"{"city": 5}"#]))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid arguments"), "{}", err);
+
+    let tools = WeatherToolSource::default();
+    let calls = tools.calls.clone();
+    let node = ActNode::new(Box::new(tools)).with_argument_validation(false);
+    node.run(weather_calls_state(&[r#"{"city": 5}"#]))
+        .await
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
 // --- ObserveNode ---
 
 #[tokio::test]
@@ -164,12 +600,12 @@ async fn observe_node_id_is_observe() {
 }
 
 #[tokio::test]
-async fn observe_node_appends_tool_results_as_user_messages_and_clears_tool_fields() {
+async fn observe_node_appends_tool_results_as_tool_messages_and_clears_tool_fields() {
     let node = ObserveNode::new();
     let state = ReActState {
         messages: vec![
             Message::user("What time?"),
-            Message::Assistant("I'll check.".into()),
+            Message::assistant("I'll check."),
         ],
         tool_calls: vec![ToolCall {
             name: "get_time".into(),
@@ -182,11 +618,18 @@ async fn observe_node_appends_tool_results_as_user_messages_and_clears_tool_fiel
             content: "2025-01-29 12:00:00".into(),
         }],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 3);
     assert!(
-        matches!(&out.messages[2], Message::User(s) if s.contains("Tool") && s.contains("2025-01-29 12:00:00"))
+        matches!(&out.messages[2], Message::Tool { call_id, name, content }
+            if call_id.as_deref() == Some("call-1")
+                && name.as_deref() == Some("get_time")
+                && content == "2025-01-29 12:00:00")
     );
     assert!(out.tool_calls.is_empty());
     assert!(out.tool_results.is_empty());
@@ -196,7 +639,7 @@ async fn observe_node_appends_tool_results_as_user_messages_and_clears_tool_fiel
 async fn observe_node_empty_tool_results_clears_tool_fields_only() {
     let node = ObserveNode::new();
     let state = ReActState {
-        messages: vec![Message::user("Hi"), Message::Assistant("Hello.".into())],
+        messages: vec![Message::user("Hi"), Message::assistant("Hello.")],
         tool_calls: vec![ToolCall {
             name: "x".into(),
             arguments: "{}".into(),
@@ -204,6 +647,10 @@ async fn observe_node_empty_tool_results_clears_tool_fields_only() {
         }],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, _) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 2);
@@ -223,7 +670,7 @@ async fn observe_node_with_loop_returns_node_think_when_had_tool_calls() {
     let state = ReActState {
         messages: vec![
             Message::user("Hi"),
-            Message::Assistant("I'll check.".into()),
+            Message::assistant("I'll check."),
         ],
         tool_calls: vec![ToolCall {
             name: "get_time".into(),
@@ -236,24 +683,99 @@ async fn observe_node_with_loop_returns_node_think_when_had_tool_calls() {
             content: "12:00".into(),
         }],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, next) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 3);
     assert!(matches!(next, Next::Node(id) if id == "think"));
+    assert_eq!(out.final_answer(), None);
 }
 
 #[tokio::test]
 async fn observe_node_with_loop_returns_end_when_no_tool_calls() {
     let node = ObserveNode::with_loop();
     let state = ReActState {
-        messages: vec![Message::user("Hi"), Message::Assistant("Hello.".into())],
+        messages: vec![Message::user("Hi"), Message::assistant("Hello.")],
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, next) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 2);
     assert!(matches!(next, Next::End));
+    assert_eq!(out.final_answer(), Some("Hello."));
+}
+
+/// **Scenario**: A round without tool calls whose reply is empty leaves no final answer, even
+/// when an earlier run on the thread answered.
+#[tokio::test]
+async fn observe_node_final_answer_only_from_current_run() {
+    let node = ObserveNode::with_loop();
+    let state = ReActState {
+        messages: vec![
+            Message::user("Hi"),
+            Message::assistant("Hello."),
+            Message::user("And now?"),
+            Message::assistant(""),
+        ],
+        ..Default::default()
+    };
+    let (out, _) = node.run(state).await.unwrap();
+    assert_eq!(out.final_answer(), None);
+}
+
+/// **Scenario**: With attachments, a long tool output is stored in the Store and the Tool
+/// message carries a preview naming it; short outputs stay inline.
+#[tokio::test]
+async fn observe_node_stores_long_outputs_as_attachments() {
+    let store = Arc::new(InMemoryStore::new());
+    let attachments = ToolAttachments::new(store.clone())
+        .with_threshold(20)
+        .with_preview(5);
+    let node = ObserveNode::with_loop().with_attachments(attachments.clone());
+    let long = "x".repeat(50);
+    let state = ReActState {
+        messages: vec![Message::user("Fetch it"), Message::assistant("")],
+        tool_calls: vec![ToolCall {
+            name: "web_fetcher".into(),
+            arguments: "{}".into(),
+            id: Some("c1".into()),
+        }],
+        tool_results: vec![
+            ToolResult {
+                call_id: Some("c1".into()),
+                name: Some("web_fetcher".into()),
+                content: long.clone(),
+            },
+            ToolResult {
+                call_id: Some("c2".into()),
+                name: Some("get_time".into()),
+                content: "12:00".into(),
+            },
+        ],
+        ..Default::default()
+    };
+    let (out, _) = node.run(state).await.unwrap();
+
+    let keys = store.list(attachments.namespace()).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    let preview = out.messages[2].content();
+    assert!(preview.starts_with("xxxxx\n\n[Output of web_fetcher shortened"));
+    assert!(preview.contains(&keys[0]));
+    assert_eq!(out.messages[3].content(), "12:00");
+    let stored = store
+        .get(attachments.namespace(), &keys[0])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored["content"], long.as_str());
 }
 
 /// **Scenario**: When enable_loop and turn_count reaches max (10), observe returns End even if there were tool_calls.
@@ -265,7 +787,7 @@ async fn observe_node_with_loop_returns_end_when_max_turns_reached() {
     let state = ReActState {
         messages: vec![
             Message::user("Hi"),
-            Message::Assistant("I'll check.".into()),
+            Message::assistant("I'll check."),
         ],
         tool_calls: vec![ToolCall {
             name: "get_time".into(),
@@ -278,6 +800,10 @@ async fn observe_node_with_loop_returns_end_when_max_turns_reached() {
             content: "12:00".into(),
         }],
         turn_count: MAX_TURNS - 1,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let (out, next) = node.run(state).await.unwrap();
     assert_eq!(out.messages.len(), 3);
@@ -285,6 +811,29 @@ async fn observe_node_with_loop_returns_end_when_max_turns_reached() {
     assert!(matches!(next, Next::End));
 }
 
+/// **Scenario**: with_max_turns lowers the loop limit; observe ends once it is reached.
+#[tokio::test]
+async fn observe_node_with_max_turns_ends_early() {
+    let node = ObserveNode::with_loop().with_max_turns(2);
+    let state = ReActState {
+        messages: vec![Message::user("Hi"), Message::assistant("I'll check.")],
+        tool_calls: vec![ToolCall {
+            name: "get_time".into(),
+            arguments: "{}".into(),
+            id: Some("c1".into()),
+        }],
+        tool_results: vec![],
+        turn_count: 1,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    };
+    let (out, next) = node.run(state).await.unwrap();
+    assert_eq!(out.turn_count, 2);
+    assert!(matches!(next, Next::End));
+}
+
 // --- ThinkNode Messages Streaming ---
 
 /// **Scenario**: ThinkNode emits Messages when stream_mode contains Messages.
@@ -298,6 +847,10 @@ async fn think_node_run_with_context_emits_messages_when_streaming() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     // Create stream channel
@@ -313,6 +866,7 @@ async fn think_node_run_with_context_emits_messages_when_streaming() {
         store: None,
         previous: None,
         runtime_context: None,
+        resume: None,
     };
 
     // Run node with context
@@ -320,7 +874,7 @@ async fn think_node_run_with_context_emits_messages_when_streaming() {
 
     // Verify output state
     assert_eq!(out.messages.len(), 2);
-    assert!(matches!(&out.messages[1], Message::Assistant(s) if s == content));
+    assert!(matches!(&out.messages[1], Message::Assistant { content: s, .. } if s == content));
 
     // Collect stream events
     drop(ctx); // Drop ctx to close channel
@@ -354,6 +908,86 @@ async fn think_node_run_with_context_emits_messages_when_streaming() {
     }
 }
 
+/// **Scenario**: ThinkNode forwards tool-call deltas from the LLM as Messages with tool_call_chunks.
+#[tokio::test]
+async fn think_node_run_with_context_forwards_tool_call_chunks() {
+    let llm = MockLlm::with_get_time_call();
+    let node = ThinkNode::new(Box::new(llm));
+    let state = ReActState {
+        messages: vec![Message::user("What time is it?")],
+        tool_calls: vec![],
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    };
+
+    let (tx, mut rx) = mpsc::channel::<StreamEvent<ReActState>>(128);
+    let ctx = RunContext::<ReActState> {
+        config: RunnableConfig::default(),
+        stream_tx: Some(tx),
+        stream_mode: HashSet::from_iter([StreamMode::Messages]),
+        managed_values: Default::default(),
+        store: None,
+        previous: None,
+        runtime_context: None,
+        resume: None,
+    };
+
+    let (out, _) = node.run_with_context(state, &ctx).await.unwrap();
+    assert_eq!(out.tool_calls.len(), 1);
+
+    drop(ctx);
+    let mut tool_chunks = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let StreamEvent::Messages { chunk, metadata } = event {
+            assert_eq!(metadata.langgraph_node, "think");
+            tool_chunks.extend(chunk.tool_call_chunks);
+        }
+    }
+    assert_eq!(tool_chunks.len(), 1);
+    assert_eq!(tool_chunks[0].index, 0);
+    assert_eq!(tool_chunks[0].name.as_deref(), Some("get_time"));
+    assert_eq!(tool_chunks[0].id.as_deref(), Some("call-1"));
+}
+
+/// **Scenario**: ThinkNode stores the LLM response metadata in state and, with Updates mode,
+/// emits it as ResponseMetadata.
+#[tokio::test]
+async fn think_node_records_and_emits_response_metadata() {
+    let node = ThinkNode::new(Box::new(MockLlm::with_get_time_call()));
+    let state = ReActState {
+        messages: vec![Message::user("What time is it?")],
+        tool_calls: vec![],
+        tool_results: vec![],
+        turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
+    };
+    let (out, _) = node.run(state.clone()).await.unwrap();
+    let metadata = out.response_metadata.expect("metadata set");
+    assert_eq!(metadata.finish_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(metadata.model.as_deref(), Some("mock"));
+
+    let (tx, mut rx) = mpsc::channel::<StreamEvent<ReActState>>(128);
+    let mut ctx = RunContext::<ReActState>::new(RunnableConfig::default());
+    ctx.stream_tx = Some(tx);
+    ctx.stream_mode = HashSet::from_iter([StreamMode::Updates]);
+    node.run_with_context(state, &ctx).await.unwrap();
+    drop(ctx);
+    let mut emitted = None;
+    while let Ok(event) = rx.try_recv() {
+        if let StreamEvent::ResponseMetadata(m) = event {
+            emitted = Some(m);
+        }
+    }
+    assert_eq!(emitted, Some(metadata));
+}
+
 /// **Scenario**: ThinkNode does NOT emit Messages when stream_mode does not contain Messages.
 #[tokio::test]
 async fn think_node_run_with_context_no_messages_when_mode_empty() {
@@ -365,6 +999,10 @@ async fn think_node_run_with_context_no_messages_when_mode_empty() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     // Create stream channel
@@ -380,6 +1018,7 @@ async fn think_node_run_with_context_no_messages_when_mode_empty() {
         store: None,
         previous: None,
         runtime_context: None,
+        resume: None,
     };
 
     // Run node with context
@@ -387,7 +1026,7 @@ async fn think_node_run_with_context_no_messages_when_mode_empty() {
 
     // Verify output state is correct
     assert_eq!(out.messages.len(), 2);
-    assert!(matches!(&out.messages[1], Message::Assistant(s) if s == content));
+    assert!(matches!(&out.messages[1], Message::Assistant { content: s, .. } if s == content));
 
     // Verify NO Messages events were emitted
     drop(ctx);
@@ -412,6 +1051,10 @@ async fn think_node_run_with_context_no_panic_when_no_stream_tx() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     // Create RunContext without stream_tx
@@ -424,6 +1067,7 @@ async fn think_node_run_with_context_no_panic_when_no_stream_tx() {
         store: None,
         previous: None,
         runtime_context: None,
+        resume: None,
     };
 
     // Should complete without panic
@@ -442,6 +1086,10 @@ async fn think_node_stream_chunks_concatenate_to_full_content() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
 
     let (tx, mut rx) = mpsc::channel::<StreamEvent<ReActState>>(128);
@@ -454,6 +1102,7 @@ async fn think_node_stream_chunks_concatenate_to_full_content() {
         store: None,
         previous: None,
         runtime_context: None,
+        resume: None,
     };
 
     let (out, _) = node.run_with_context(state, &ctx).await.unwrap();
@@ -469,5 +1118,5 @@ async fn think_node_stream_chunks_concatenate_to_full_content() {
 
     // Verify concatenated equals original content and assistant message
     assert_eq!(concatenated, content);
-    assert!(matches!(&out.messages[1], Message::Assistant(s) if s == content));
+    assert!(matches!(&out.messages[1], Message::Assistant { content: s, .. } if s == content));
 }
@@ -159,6 +159,10 @@ fn react_state_construction_and_clone() {
             content: "12:00".into(),
         }],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     assert_eq!(state.messages.len(), 2);
     assert_eq!(state.tool_calls.len(), 1);
@@ -204,6 +208,10 @@ fn react_state_clone_field_by_field() {
             },
         ],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let cloned = state.clone();
     assert_eq!(cloned.messages.len(), 3);
@@ -226,6 +234,10 @@ fn react_state_with_all_message_variants() {
         tool_calls: vec![],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     assert_eq!(state.messages.len(), 3);
     match &state.messages[0] {
@@ -237,7 +249,7 @@ fn react_state_with_all_message_variants() {
         _ => panic!("expected User"),
     }
     match &state.messages[2] {
-        Message::Assistant(s) => assert_eq!(s, "Assistant reply"),
+        Message::Assistant { content: s, .. } => assert_eq!(s, "Assistant reply"),
         _ => panic!("expected Assistant"),
     }
 }
@@ -253,6 +265,10 @@ fn react_state_empty_tool_calls_non_empty_results() {
             content: "12:00".into(),
         }],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     assert!(state.tool_calls.is_empty());
     assert_eq!(state.tool_results.len(), 1);
@@ -270,6 +286,10 @@ fn react_state_debug() {
         }],
         tool_results: vec![],
         turn_count: 0,
+        response_metadata: None,
+        usage: Default::default(),
+        final_answer: None,
+        ui_events: vec![],
     };
     let s = format!("{:?}", state);
     assert!(s.contains("messages"));
@@ -0,0 +1,60 @@
+//! Integration test: a runner's retriever puts the documents for the user's message before
+//! think, and the model can search them again with the `retrieve` tool.
+
+mod init_logging;
+
+use std::sync::Arc;
+
+use langgraph::memory::{InMemoryStore, Store};
+use langgraph::tools::{Tool, TOOL_RETRIEVE};
+use langgraph::{Message, MockLlm, MockToolSource, ReactRunner, Retriever};
+use serde_json::json;
+
+/// **Scenario**: The retrieved passage with its source is a System message right before the
+/// user message the run answers; the tool searches the same collection.
+#[tokio::test]
+async fn runner_retrieves_before_think() {
+    let store = Arc::new(InMemoryStore::new());
+    let namespace = vec!["handbook".to_string()];
+    store
+        .put(
+            &namespace,
+            "leave",
+            &json!({"text": "Vacation is 25 days a year.", "source": "handbook/leave.md"}),
+        )
+        .await
+        .unwrap();
+    let retriever = Retriever::new(store, namespace);
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::with_no_tool_calls("25 days.")),
+        Box::new(MockToolSource::get_time_example()),
+        None,
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile")
+    .with_retriever(retriever.clone());
+
+    let state = runner.invoke("vacation").await.expect("run");
+    let Message::System(context) = &state.messages[1] else {
+        panic!("expected retrieved context, got {:?}", state.messages[1]);
+    };
+    assert!(
+        context.contains("[1] handbook/leave.md\nVacation is 25 days a year."),
+        "{}",
+        context
+    );
+    assert!(matches!(&state.messages[2], Message::User(q) if q == "vacation"));
+    assert_eq!(state.last_assistant_reply().as_deref(), Some("25 days."));
+
+    let tool = retriever.tool();
+    assert_eq!(tool.name(), TOOL_RETRIEVE);
+    let out = tool.call(json!({"query": "Vacation"}), None).await.unwrap();
+    assert!(
+        out.text.starts_with("[1] handbook/leave.md"),
+        "{}",
+        out.text
+    );
+}
@@ -0,0 +1,57 @@
+//! Integration test: a runner with an outcome classifier tags each run's last checkpoint.
+
+mod init_logging;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use langgraph::memory::{MemorySaver, RunnableConfig};
+use langgraph::{MockLlm, MockToolSource, ReActState, ReactRunner, RuleOutcomeClassifier};
+
+/// Tags of the latest checkpoint of `thread_id`, once the background classification added some.
+async fn latest_tags(runner: &ReactRunner, thread_id: &str) -> Vec<String> {
+    for _ in 0..200 {
+        let checkpoints = runner.list_checkpoints(thread_id).await.unwrap();
+        let tags = checkpoints.last().map(|c| c.metadata.tags.clone());
+        if let Some(tags) = tags.filter(|t| !t.is_empty()) {
+            return tags;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    Vec::new()
+}
+
+/// **Scenario**: A run that called a tool and answered is tagged answered and needed_tools; only
+/// the run's last checkpoint is tagged.
+#[tokio::test]
+async fn finished_run_is_tagged_with_its_outcome() {
+    let checkpointer = Arc::new(MemorySaver::<ReActState>::new());
+    let runner = ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(MockToolSource::get_time_example()),
+        Some(checkpointer),
+        None,
+        None,
+        None,
+        false,
+    )
+    .expect("compile")
+    .with_outcome_classifier(Arc::new(RuleOutcomeClassifier));
+    let config = RunnableConfig {
+        thread_id: Some("outcome".into()),
+        ..Default::default()
+    };
+
+    runner
+        .invoke_with_config("What time is it?", Some(config))
+        .await
+        .expect("run");
+
+    assert_eq!(
+        latest_tags(&runner, "outcome").await,
+        ["answered", "needed_tools"]
+    );
+    let checkpoints = runner.list_checkpoints("outcome").await.unwrap();
+    assert!(checkpoints.len() > 1);
+    assert!(checkpoints[0].metadata.tags.is_empty());
+}
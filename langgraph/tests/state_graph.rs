@@ -6,6 +6,9 @@
 //! - `invoke`: invoke output
 //! - `store`: with_store / store()
 //! - `middleware`: compile_with_middleware and with_middleware().compile()
+//! - `interrupt`: interrupt_before / interrupt_after and resume via Command
+//! - `history`: per-step checkpoints, get_state_history and time travel
+//! - `update_state`: manual state edits between runs
 
 mod init_logging;
 
@@ -26,3 +29,12 @@ mod store;
 
 #[path = "state_graph/middleware.rs"]
 mod middleware;
+
+#[path = "state_graph/interrupt.rs"]
+mod interrupt;
+
+#[path = "state_graph/history.rs"]
+mod history;
+
+#[path = "state_graph/update_state.rs"]
+mod update_state;
@@ -0,0 +1,164 @@
+//! Checkpoint history: per-step checkpoints, get_state_history and time travel via checkpoint_id.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use langgraph::{
+    AgentError, Checkpointer, MemorySaver, Next, Node, RunnableConfig, StateGraph, END, START,
+};
+
+struct AddNode {
+    id: &'static str,
+    delta: i32,
+}
+
+#[async_trait]
+impl Node<i32> for AddNode {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+        Ok((state + self.delta, Next::Continue))
+    }
+}
+
+fn config(thread_id: &str) -> RunnableConfig {
+    RunnableConfig {
+        thread_id: Some(thread_id.into()),
+        ..Default::default()
+    }
+}
+
+/// a(+1) → b(+10) → c(+100)
+fn chain() -> StateGraph<i32> {
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_node("b", Arc::new(AddNode { id: "b", delta: 10 }))
+        .add_node("c", Arc::new(AddNode { id: "c", delta: 100 }))
+        .add_edge(START, "a")
+        .add_edge("a", "b")
+        .add_edge("b", "c")
+        .add_edge("c", END);
+    graph
+}
+
+/// **Scenario**: invoke saves the input and every node step; history walks the chain newest first.
+#[tokio::test]
+async fn invoke_saves_checkpoint_per_step_linked_by_parent() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver).unwrap();
+    let cfg = config("t-steps");
+
+    assert_eq!(graph.invoke(0, Some(cfg.clone())).await.unwrap(), 111);
+
+    let history = graph.get_state_history(&cfg).await.unwrap();
+    let states: Vec<i32> = history.iter().map(|c| c.channel_values).collect();
+    assert_eq!(states, vec![111, 11, 1, 0]);
+    let pending: Vec<Option<&str>> = history.iter().map(|c| c.pending_node.as_deref()).collect();
+    assert_eq!(pending, vec![None, Some("c"), Some("b"), Some("a")]);
+    let steps: Vec<i64> = history.iter().map(|c| c.metadata.step).collect();
+    assert_eq!(steps, vec![2, 1, 0, -1]);
+    for pair in history.windows(2) {
+        assert_eq!(
+            pair[0].metadata.parent_checkpoint_id.as_deref(),
+            Some(pair[1].id.as_str())
+        );
+    }
+    assert!(history[3].metadata.parent_checkpoint_id.is_none());
+}
+
+/// **Scenario**: A second invoke on the thread continues the chain from the previous final state.
+#[tokio::test]
+async fn second_invoke_links_to_previous_run() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver).unwrap();
+    let cfg = config("t-two-runs");
+
+    graph.invoke(0, Some(cfg.clone())).await.unwrap();
+    let final_id = graph.get_state_history(&cfg).await.unwrap()[0].id.clone();
+    assert_eq!(graph.invoke(1, Some(cfg.clone())).await.unwrap(), 112);
+
+    let history = graph.get_state_history(&cfg).await.unwrap();
+    assert_eq!(history.len(), 8);
+    assert_eq!(history[3].channel_values, 1);
+    assert_eq!(history[3].metadata.step, 3);
+    assert_eq!(
+        history[3].metadata.parent_checkpoint_id.as_deref(),
+        Some(final_id.as_str())
+    );
+}
+
+/// **Scenario**: invoke with checkpoint_id re-runs from that checkpoint and branches off it.
+#[tokio::test]
+async fn invoke_with_checkpoint_id_branches_from_earlier_checkpoint() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver.clone()).unwrap();
+    let cfg = config("t-travel");
+    graph.invoke(0, Some(cfg.clone())).await.unwrap();
+    let original = graph.get_state_history(&cfg).await.unwrap();
+    let after_a = original[2].clone();
+    assert_eq!(after_a.pending_node.as_deref(), Some("b"));
+
+    let travel = RunnableConfig {
+        checkpoint_id: Some(after_a.id.clone()),
+        ..cfg.clone()
+    };
+    // The input state is ignored: the run starts from after_a's state (1) at node b.
+    assert_eq!(graph.invoke(999, Some(travel.clone())).await.unwrap(), 111);
+
+    let branch = graph.get_state_history(&cfg).await.unwrap();
+    let states: Vec<i32> = branch.iter().map(|c| c.channel_values).collect();
+    assert_eq!(states, vec![111, 11, 1, 0]);
+    assert_eq!(branch[2].id, after_a.id);
+    assert_ne!(branch[0].id, original[0].id);
+    assert_ne!(branch[1].id, original[1].id);
+    assert_eq!(saver.list(&cfg, None, None, None).await.unwrap().len(), 6);
+
+    // History from a given checkpoint only walks its ancestors.
+    let from_a = graph.get_state_history(&travel).await.unwrap();
+    assert_eq!(from_a.len(), 2);
+    assert_eq!(from_a[0].id, after_a.id);
+}
+
+/// **Scenario**: Time travel to a finished checkpoint returns its state without running nodes.
+#[tokio::test]
+async fn invoke_with_final_checkpoint_id_returns_its_state() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver.clone()).unwrap();
+    let cfg = config("t-final");
+    graph.invoke(0, Some(cfg.clone())).await.unwrap();
+    let final_id = graph.get_state_history(&cfg).await.unwrap()[0].id.clone();
+
+    let travel = RunnableConfig {
+        checkpoint_id: Some(final_id),
+        ..cfg.clone()
+    };
+    assert_eq!(graph.invoke(5, Some(travel)).await.unwrap(), 111);
+    assert_eq!(saver.list(&cfg, None, None, None).await.unwrap().len(), 4);
+
+    let missing = RunnableConfig {
+        checkpoint_id: Some("no-such-checkpoint".into()),
+        ..cfg
+    };
+    let err = graph.invoke(0, Some(missing)).await.unwrap_err();
+    assert!(err.to_string().contains("checkpoint not found"), "{}", err);
+}
+
+/// **Scenario**: Without a checkpointer or thread_id the history is empty.
+#[tokio::test]
+async fn get_state_history_empty_without_checkpointer_or_thread() {
+    let graph = chain().compile().unwrap();
+    let history = graph.get_state_history(&config("t-none")).await.unwrap();
+    assert!(history.is_empty());
+
+    let graph = chain()
+        .compile_with_checkpointer(Arc::new(MemorySaver::<i32>::new()))
+        .unwrap();
+    let history = graph
+        .get_state_history(&RunnableConfig::default())
+        .await
+        .unwrap();
+    assert!(history.is_empty());
+}
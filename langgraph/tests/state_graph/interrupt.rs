@@ -0,0 +1,196 @@
+//! Human-in-the-loop interrupts: interrupt_before / interrupt_after, node interrupts, resume.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use langgraph::{
+    AgentError, Checkpointer, Command, GraphInterrupt, Interrupt, MemorySaver, Next, Node,
+    ResumeHandler, RunContext, RunnableConfig, StateGraph, END, START,
+};
+use serde_json::json;
+
+struct AddNode {
+    id: &'static str,
+    delta: i32,
+}
+
+#[async_trait]
+impl Node<i32> for AddNode {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+        Ok((state + self.delta, Next::Continue))
+    }
+}
+
+/// Asks for an amount on first run; on resume adds the amount passed via `Command::resume`.
+struct AskAmountNode;
+
+#[async_trait]
+impl Node<i32> for AskAmountNode {
+    fn id(&self) -> &str {
+        "ask"
+    }
+
+    async fn run(&self, _state: i32) -> Result<(i32, Next), AgentError> {
+        Err(GraphInterrupt(Interrupt::new(json!("amount?"))).into())
+    }
+
+    async fn run_with_context(
+        &self,
+        state: i32,
+        ctx: &RunContext<i32>,
+    ) -> Result<(i32, Next), AgentError> {
+        match ctx.resume_value().and_then(|v| v.as_i64()) {
+            Some(amount) => Ok((state + amount as i32, Next::Continue)),
+            None => self.run(state).await,
+        }
+    }
+}
+
+fn config(thread_id: &str) -> RunnableConfig {
+    RunnableConfig {
+        thread_id: Some(thread_id.into()),
+        ..Default::default()
+    }
+}
+
+/// a(+1) → b(+10) → c(+100)
+fn chain() -> StateGraph<i32> {
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_node("b", Arc::new(AddNode { id: "b", delta: 10 }))
+        .add_node("c", Arc::new(AddNode { id: "c", delta: 100 }))
+        .add_edge(START, "a")
+        .add_edge("a", "b")
+        .add_edge("b", "c")
+        .add_edge("c", END);
+    graph
+}
+
+/// **Scenario**: interrupt_before pauses before the node, saves pending_node, and resume runs it.
+#[tokio::test]
+async fn interrupt_before_pauses_and_resume_continues() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain()
+        .with_interrupt_before(["b"])
+        .compile_with_checkpointer(saver.clone())
+        .unwrap();
+    let cfg = config("t-before");
+
+    let err = graph.invoke(0, Some(cfg.clone())).await.unwrap_err();
+    let interrupt = match err {
+        AgentError::Interrupted(GraphInterrupt(i)) => i,
+        other => panic!("expected interrupt, got {:?}", other),
+    };
+    assert_eq!(interrupt.value["when"], "before");
+    assert_eq!(interrupt.value["node"], "b");
+    let (checkpoint, _) = saver.get_tuple(&cfg).await.unwrap().unwrap();
+    assert_eq!(checkpoint.channel_values, 1);
+    assert_eq!(checkpoint.pending_node.as_deref(), Some("b"));
+    assert_eq!(interrupt.id.as_deref(), Some(checkpoint.id.as_str()));
+
+    let out = graph.resume(Command::default(), cfg.clone()).await.unwrap();
+    assert_eq!(out, 111);
+    let (checkpoint, _) = saver.get_tuple(&cfg).await.unwrap().unwrap();
+    assert!(checkpoint.pending_node.is_none());
+}
+
+/// **Scenario**: interrupt_after pauses once the node's update is applied; Command update is merged.
+#[tokio::test]
+async fn interrupt_after_pauses_and_resume_applies_update() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain()
+        .with_interrupt_after(["a"])
+        .with_interrupt_before(["b"])
+        .compile_with_checkpointer(saver.clone())
+        .unwrap();
+    let cfg = config("t-after");
+
+    let err = graph.invoke(0, Some(cfg.clone())).await.unwrap_err();
+    assert!(
+        matches!(&err, AgentError::Interrupted(GraphInterrupt(i)) if i.value["when"] == "after"),
+        "{:?}",
+        err
+    );
+    let (checkpoint, _) = saver.get_tuple(&cfg).await.unwrap().unwrap();
+    assert_eq!(checkpoint.channel_values, 1);
+    assert_eq!(checkpoint.pending_node.as_deref(), Some("b"));
+
+    // Replace state with 5 (default ReplaceUpdater); b's interrupt_before is covered by this pause.
+    let out = graph
+        .resume(Command::default().with_update(5), cfg)
+        .await
+        .unwrap();
+    assert_eq!(out, 115);
+}
+
+/// **Scenario**: A node raising an interrupt is re-run on resume and reads the resume value.
+#[tokio::test]
+async fn node_interrupt_resumes_with_value() {
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("ask", Arc::new(AskAmountNode))
+        .add_node("c", Arc::new(AddNode { id: "c", delta: 100 }))
+        .add_edge(START, "ask")
+        .add_edge("ask", "c")
+        .add_edge("c", END);
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = graph.compile_with_checkpointer(saver.clone()).unwrap();
+    let cfg = config("t-node");
+
+    let err = graph.invoke(2, Some(cfg.clone())).await.unwrap_err();
+    assert!(matches!(err, AgentError::Interrupted(_)), "{:?}", err);
+    let (checkpoint, _) = saver.get_tuple(&cfg).await.unwrap().unwrap();
+    assert_eq!(checkpoint.pending_node.as_deref(), Some("ask"));
+
+    let out = graph.resume(Command::resume(json!(40)), cfg).await.unwrap();
+    assert_eq!(out, 142);
+}
+
+/// **Scenario**: Resume fails without a checkpointer or when the thread finished normally.
+#[tokio::test]
+async fn resume_fails_when_nothing_to_resume() {
+    let graph = chain().compile().unwrap();
+    let err = graph
+        .resume(Command::default(), config("t-none"))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("checkpointer"), "{}", err);
+
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver).unwrap();
+    let cfg = config("t-done");
+    assert_eq!(graph.invoke(0, Some(cfg.clone())).await.unwrap(), 111);
+    let err = graph.resume(Command::default(), cfg).await.unwrap_err();
+    assert!(err.to_string().contains("not interrupted"), "{}", err);
+}
+
+/// **Scenario**: A ResumeHandler injects the human value into state before the pending node runs.
+#[tokio::test]
+async fn resume_handler_injects_value_into_state() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let add_value =
+        |state: &mut i32, node: &str, value: &serde_json::Value| -> Result<(), AgentError> {
+            assert_eq!(node, "c");
+            *state += value.as_i64().unwrap_or(0) as i32;
+            Ok(())
+        };
+    let handler: Arc<dyn ResumeHandler<i32>> = Arc::new(add_value);
+    let graph = chain()
+        .with_interrupt_before(["c"])
+        .with_resume_handler(handler)
+        .compile_with_checkpointer(saver)
+        .unwrap();
+    let cfg = config("t-handler");
+
+    assert!(graph.invoke(0, Some(cfg.clone())).await.is_err());
+    assert_eq!(graph.pending_node(&cfg).await.unwrap().as_deref(), Some("c"));
+
+    let out = graph.resume_with_value(cfg.clone(), json!(1000)).await.unwrap();
+    assert_eq!(out, 1111);
+    assert!(graph.pending_node(&cfg).await.unwrap().is_none());
+}
@@ -0,0 +1,178 @@
+//! update_state: manual state edits between runs, saved as `CheckpointSource::Update`.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use langgraph::{
+    AgentError, CheckpointSource, Command, MemorySaver, Next, Node, RunnableConfig, StateGraph,
+    END, START,
+};
+
+struct AddNode {
+    id: &'static str,
+    delta: i32,
+}
+
+#[async_trait]
+impl Node<i32> for AddNode {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    async fn run(&self, state: i32) -> Result<(i32, Next), AgentError> {
+        Ok((state + self.delta, Next::Continue))
+    }
+}
+
+fn config(thread_id: &str) -> RunnableConfig {
+    RunnableConfig {
+        thread_id: Some(thread_id.into()),
+        ..Default::default()
+    }
+}
+
+/// a(+1) → b(+10) → c(+100)
+fn chain() -> StateGraph<i32> {
+    let mut graph = StateGraph::<i32>::new();
+    graph
+        .add_node("a", Arc::new(AddNode { id: "a", delta: 1 }))
+        .add_node("b", Arc::new(AddNode { id: "b", delta: 10 }))
+        .add_node(
+            "c",
+            Arc::new(AddNode {
+                id: "c",
+                delta: 100,
+            }),
+        )
+        .add_edge(START, "a")
+        .add_edge("a", "b")
+        .add_edge("b", "c")
+        .add_edge("c", END);
+    graph
+}
+
+/// **Scenario**: Patching a paused thread saves an Update checkpoint linked to the paused one;
+/// the thread stays paused at the same node and resume continues from the patched state.
+#[tokio::test]
+async fn update_state_patches_paused_thread_before_resume() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain()
+        .with_interrupt_before(["b"])
+        .compile_with_checkpointer(saver)
+        .unwrap();
+    let cfg = config("t-patch");
+
+    assert!(graph.invoke(0, Some(cfg.clone())).await.is_err());
+    let paused = graph.get_state_history(&cfg).await.unwrap()[0].clone();
+    assert_eq!(paused.channel_values, 1);
+
+    let updated = graph.update_state(&cfg, 50, None).await.unwrap();
+    let history = graph.get_state_history(&cfg).await.unwrap();
+    let latest = &history[0];
+    assert_eq!(updated.checkpoint_id.as_deref(), Some(latest.id.as_str()));
+    assert_eq!(latest.channel_values, 50);
+    assert_eq!(latest.metadata.source, CheckpointSource::Update);
+    assert_eq!(latest.metadata.step, paused.metadata.step + 1);
+    assert_eq!(
+        latest.metadata.parent_checkpoint_id.as_deref(),
+        Some(paused.id.as_str())
+    );
+    assert_eq!(
+        graph.pending_node(&cfg).await.unwrap().as_deref(),
+        Some("b")
+    );
+
+    let out = graph.resume(Command::default(), cfg).await.unwrap();
+    assert_eq!(out, 160);
+}
+
+/// **Scenario**: With as_node the update counts as that node's output, so the thread moves on
+/// to the node after it; as the last node, nothing is left pending.
+#[tokio::test]
+async fn update_state_as_node_advances_pending_node() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain()
+        .with_interrupt_before(["b"])
+        .compile_with_checkpointer(saver)
+        .unwrap();
+    let cfg = config("t-as-node");
+
+    assert!(graph.invoke(0, Some(cfg.clone())).await.is_err());
+    graph.update_state(&cfg, 7, Some("b")).await.unwrap();
+    assert_eq!(
+        graph.pending_node(&cfg).await.unwrap().as_deref(),
+        Some("c")
+    );
+    let out = graph.resume(Command::default(), cfg.clone()).await.unwrap();
+    assert_eq!(out, 107);
+
+    graph.update_state(&cfg, 1000, Some("c")).await.unwrap();
+    assert!(graph.pending_node(&cfg).await.unwrap().is_none());
+    assert_eq!(
+        graph.get_state_history(&cfg).await.unwrap()[0].channel_values,
+        1000
+    );
+}
+
+/// **Scenario**: The returned config re-runs from the patched checkpoint via time travel.
+#[tokio::test]
+async fn update_state_config_supports_time_travel() {
+    let saver = Arc::new(MemorySaver::<i32>::new());
+    let graph = chain().compile_with_checkpointer(saver).unwrap();
+    let cfg = config("t-fork");
+
+    assert_eq!(graph.invoke(0, Some(cfg.clone())).await.unwrap(), 111);
+    let after_a = graph
+        .get_state_history(&cfg)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.pending_node.as_deref() == Some("b"))
+        .unwrap();
+    let at_a = RunnableConfig {
+        checkpoint_id: Some(after_a.id),
+        ..cfg.clone()
+    };
+
+    let forked = graph.update_state(&at_a, 5, None).await.unwrap();
+    assert_eq!(graph.invoke(0, Some(forked)).await.unwrap(), 115);
+}
+
+/// **Scenario**: update_state fails without a checkpointer, for an unknown node or checkpoint,
+/// and starts a new thread from the given values.
+#[tokio::test]
+async fn update_state_validates_input() {
+    let graph = chain().compile().unwrap();
+    let err = graph
+        .update_state(&config("t-none"), 1, None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("checkpointer"), "{}", err);
+
+    let graph = chain()
+        .compile_with_checkpointer(Arc::new(MemorySaver::<i32>::new()))
+        .unwrap();
+    let cfg = config("t-new");
+    let err = graph
+        .update_state(&cfg, 1, Some("missing"))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("missing"), "{}", err);
+
+    graph.update_state(&cfg, 3, Some("a")).await.unwrap();
+    assert_eq!(
+        graph.pending_node(&cfg).await.unwrap().as_deref(),
+        Some("b")
+    );
+    assert_eq!(
+        graph.resume(Command::default(), cfg.clone()).await.unwrap(),
+        113
+    );
+
+    let unknown = RunnableConfig {
+        checkpoint_id: Some("no-such-checkpoint".into()),
+        ..cfg
+    };
+    let err = graph.update_state(&unknown, 1, None).await.unwrap_err();
+    assert!(err.to_string().contains("not found"), "{}", err);
+}
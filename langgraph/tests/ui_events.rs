@@ -0,0 +1,122 @@
+//! Integration test: UI events tools emit reach the stream and the run's state, but not
+//! checkpoints unless the runner keeps them.
+
+mod init_logging;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use langgraph::memory::{Checkpointer, MemorySaver, RunnableConfig};
+use langgraph::stream::{StreamEvent, UiEventsRetention};
+use langgraph::tool_source::{ToolCallContent, ToolCallContext, ToolSourceError, ToolSpec};
+use langgraph::tools::{AggregateToolSource, Tool};
+use langgraph::{MockLlm, ReActState, ReactRunner};
+use serde_json::{json, Value};
+
+/// `get_time` that shows its answer as a table.
+struct TimeTableTool;
+
+#[async_trait]
+impl Tool for TimeTableTool {
+    fn name(&self) -> &str {
+        "get_time"
+    }
+
+    fn spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: "get_time".to_string(),
+            description: Some("Current time".to_string()),
+            input_schema: json!({"type": "object", "properties": {}}),
+        }
+    }
+
+    async fn call(
+        &self,
+        _args: Value,
+        ctx: Option<&ToolCallContext>,
+    ) -> Result<ToolCallContent, ToolSourceError> {
+        if let Some(ctx) = ctx {
+            ctx.emit_ui_event(json!({"type": "progress", "percent": 100}));
+            ctx.emit_ui_event(json!({"type": "table", "rows": [["utc", "12:00"]]}));
+        }
+        Ok(ToolCallContent {
+            text: "12:00 UTC".to_string(),
+        })
+    }
+}
+
+async fn runner(checkpointer: Arc<MemorySaver<ReActState>>, config: RunnableConfig) -> ReactRunner {
+    let tools = AggregateToolSource::new();
+    tools.register_async(Box::new(TimeTableTool)).await;
+    ReactRunner::new(
+        Box::new(MockLlm::first_tools_then_end()),
+        Box::new(tools),
+        Some(checkpointer),
+        None,
+        Some(config),
+        None,
+        false,
+    )
+    .expect("compile")
+}
+
+/// **Scenario**: UI events a tool emits are streamed once, by `act`, and kept in the run's
+/// state; they are not in the LLM conversation nor, by default, in the thread's checkpoint.
+#[tokio::test]
+async fn tool_ui_events_are_streamed_but_not_checkpointed() {
+    let checkpointer = Arc::new(MemorySaver::new());
+    let config = RunnableConfig {
+        thread_id: Some("ui".into()),
+        ..Default::default()
+    };
+    let runner = runner(checkpointer.clone(), config.clone()).await;
+
+    let mut streamed = Vec::new();
+    let state = runner
+        .stream_with_config(
+            "What time is it?",
+            None,
+            Some(|event: StreamEvent<ReActState>| {
+                if let StreamEvent::UiEvents { node_id, events } = event {
+                    streamed.push((node_id, events));
+                }
+            }),
+        )
+        .await
+        .expect("run");
+
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(streamed[0].0, "act");
+    assert_eq!(streamed[0].1[1]["type"], "table");
+    assert_eq!(state.ui_events, streamed[0].1);
+    assert!(!format!("{:?}", state.messages).contains("table"));
+
+    let (checkpoint, _) = checkpointer.get_tuple(&config).await.unwrap().unwrap();
+    assert!(checkpoint.channel_values.ui_events.is_empty());
+    assert_eq!(
+        checkpoint.channel_values.final_answer(),
+        state.final_answer()
+    );
+}
+
+/// **Scenario**: With `UiEventsRetention::Last(1)` the checkpoint keeps the newest UI event.
+#[tokio::test]
+async fn retention_keeps_newest_ui_events_in_checkpoint() {
+    let checkpointer = Arc::new(MemorySaver::new());
+    let config = RunnableConfig {
+        thread_id: Some("ui-kept".into()),
+        ..Default::default()
+    };
+    let runner = runner(checkpointer.clone(), config.clone())
+        .await
+        .with_ui_events_retention(UiEventsRetention::Last(1));
+
+    let state = runner.invoke("What time is it?").await.expect("run");
+    assert_eq!(state.ui_events.len(), 2);
+
+    let (checkpoint, _) = checkpointer.get_tuple(&config).await.unwrap().unwrap();
+    assert_eq!(
+        checkpoint.channel_values.ui_events,
+        [json!({"type": "table", "rows": [["utc", "12:00"]]})]
+    );
+}
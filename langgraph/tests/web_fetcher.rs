@@ -20,6 +20,10 @@ async fn web_fetcher_tool_spec_has_correct_properties() {
     assert_eq!(spec.input_schema["properties"]["url"]["type"], "string");
     assert!(spec.input_schema["required"].as_array().unwrap().contains(&json!("url")));
     assert_eq!(spec.input_schema["properties"]["method"]["enum"], json!(["GET", "POST"]));
+    assert_eq!(
+        spec.input_schema["properties"]["format"]["enum"],
+        json!(["markdown", "text", "raw"])
+    );
 }
 
 #[tokio::test]